@@ -40,6 +40,14 @@ pub struct SessionConfig {
     pub precision: String,
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    /// Set by `SET max_replica_lag = '5s'`, scans may read a follower lagging within it.
+    pub max_replica_lag: Option<String>,
+    /// Set by `SET follower_read = true`, scans may read a follower consistently.
+    pub follower_read: Option<bool>,
+    /// Set by `SET time_display = 'unix_ms'`, the output of the timestamps in the results.
+    pub time_display: Option<String>,
+    /// Set by `SET time_zone = '+08:00'`, the time zone of the queries.
+    pub time_zone: Option<String>,
     pub accept_encoding: Option<Encoding>,
     pub content_encoding: Option<Encoding>,
     pub fmt: PrintFormat,
@@ -64,6 +72,10 @@ impl SessionConfig {
             precision: DEFAULT_PRECISION.to_string(),
             target_partitions: None,
             stream_trigger_interval: None,
+            max_replica_lag: None,
+            follower_read: None,
+            time_display: None,
+            time_zone: None,
             accept_encoding: None,
             content_encoding: None,
             config_options,
@@ -268,6 +280,26 @@ impl SessionContext {
         self.session_config.database = name.to_string();
     }
 
+    pub fn set_max_replica_lag(&mut self, max_replica_lag: Option<String>) {
+        self.session_config.max_replica_lag = max_replica_lag;
+    }
+
+    pub fn set_follower_read(&mut self, follower_read: Option<bool>) {
+        self.session_config.follower_read = follower_read;
+    }
+
+    pub fn set_time_display(&mut self, time_display: Option<String>) {
+        self.session_config.time_display = time_display;
+    }
+
+    pub fn set_time_zone(&mut self, time_zone: Option<String>) {
+        self.session_config.time_zone = time_zone;
+    }
+
+    pub fn set_target_partitions(&mut self, target_partitions: Option<usize>) {
+        self.session_config.target_partitions = target_partitions;
+    }
+
     pub fn set_tenant(&mut self, tenant: String) {
         self.session_config.tenant = tenant
     }
@@ -292,6 +324,10 @@ impl SessionContext {
         let db = self.session_config.database.clone();
         let target_partitions = self.session_config.target_partitions;
         let stream_trigger_interval = self.session_config.stream_trigger_interval.clone();
+        let max_replica_lag = self.session_config.max_replica_lag.clone();
+        let follower_read = self.session_config.follower_read;
+        let time_display = self.session_config.time_display.clone();
+        let time_zone = self.session_config.time_zone.clone();
         let chunked = self.session_config.chunked;
         let param = SqlParam {
             tenant: Some(tenant),
@@ -299,6 +335,14 @@ impl SessionContext {
             chunked: Some(chunked),
             target_partitions,
             stream_trigger_interval,
+            consistent_meta: None,
+            ignore_retention: None,
+            max_replica_lag,
+            follower_read,
+            cleanup_partial_write: None,
+            time_display,
+            time_zone,
+            session_id: None,
         };
 
         // let param = &[("db", &self.session_config.database)];
@@ -415,6 +459,8 @@ impl SessionContext {
             precision: Some(precision),
             tenant: Some(tenant),
             db: Some(db),
+            dry_run: None,
+            consistency: None,
         };
 
         let mut builder = self
@@ -53,7 +53,11 @@ pub async fn exec_from_lines(
                 let line = line.trim_end();
                 query.push_str(line);
                 if line.ends_with(';') {
-                    match exec_and_print(ctx, print_options, query.clone()).await {
+                    let result = match parse_set_variable(&query) {
+                        Some((name, value)) => set_session_variable(ctx, &name, &value),
+                        None => exec_and_print(ctx, print_options, query.clone()).await,
+                    };
+                    match result {
                         Ok(_) => {}
                         Err(err) => {
                             eprintln!("{:?}", err);
@@ -147,6 +151,15 @@ pub async fn exec_from_repl(ctx: &mut SessionContext, print_options: &PrintOptio
                 }
             }
 
+            Ok(line) if parse_set_variable(&line).is_some() => {
+                rl.add_history_entry(line.trim_end()).unwrap();
+                if let Some((name, value)) = parse_set_variable(&line) {
+                    if let Err(e) = set_session_variable(ctx, &name, &value) {
+                        eprintln!("{e}");
+                    }
+                }
+            }
+
             Ok(line) if parse_use_database(&line).is_some() => {
                 if let Some(db) = parse_use_database(&line) {
                     if connect_database(&db, ctx).await.is_err() {
@@ -269,6 +282,96 @@ fn parse_use_database(sql: &str) -> Option<String> {
     }
 }
 
+/// Parse `SET name = value` or `SET name TO value`, the quotes around the value are removed.
+fn parse_set_variable(sql: &str) -> Option<(String, String)> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    if !sql.get(..3)?.eq_ignore_ascii_case("set") || !sql[3..].starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let assignment = sql[3..].trim();
+    let (name, value) = match assignment.split_once('=') {
+        Some(kv) => kv,
+        None => {
+            let (name, value) = assignment.split_once(char::is_whitespace)?;
+            let value = value.trim_start();
+            if !value.get(..2)?.eq_ignore_ascii_case("to")
+                || !value[2..].starts_with(char::is_whitespace)
+            {
+                return None;
+            }
+            (name, &value[2..])
+        }
+    };
+    let name = name.trim();
+    let value = value.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) || value.is_empty() {
+        return None;
+    }
+    let value = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .unwrap_or(value);
+
+    Some((name.to_ascii_lowercase(), value.to_string()))
+}
+
+/// Session variables are kept by the client and sent with each query.
+fn set_session_variable(ctx: &mut SessionContext, name: &str, value: &str) -> Result<()> {
+    match name {
+        "max_replica_lag" => {
+            if value.eq_ignore_ascii_case("default") {
+                ctx.set_max_replica_lag(None);
+            } else {
+                ctx.set_max_replica_lag(Some(value.to_string()));
+            }
+            Ok(())
+        }
+        "follower_read" => {
+            if value.eq_ignore_ascii_case("default") {
+                ctx.set_follower_read(None);
+            } else {
+                match value.to_ascii_lowercase().parse::<bool>() {
+                    Ok(follower_read) => ctx.set_follower_read(Some(follower_read)),
+                    _ => bail!("follower_read must be true or false, got {}", value),
+                }
+            }
+            Ok(())
+        }
+        "time_display" => {
+            if value.eq_ignore_ascii_case("default") {
+                ctx.set_time_display(None);
+            } else {
+                ctx.set_time_display(Some(value.to_ascii_lowercase()));
+            }
+            Ok(())
+        }
+        "time_zone" => {
+            if value.eq_ignore_ascii_case("default") {
+                ctx.set_time_zone(None);
+            } else {
+                ctx.set_time_zone(Some(value.to_string()));
+            }
+            Ok(())
+        }
+        "target_partitions" => {
+            if value.eq_ignore_ascii_case("default") {
+                ctx.set_target_partitions(None);
+            } else {
+                match value.parse::<usize>() {
+                    Ok(n) if n > 0 => ctx.set_target_partitions(Some(n)),
+                    _ => bail!(
+                        "target_partitions must be a positive integer, got {}",
+                        value
+                    ),
+                }
+            }
+            Ok(())
+        }
+        _ => bail!("Unknown session variable '{}'", name),
+    }
+}
+
 pub fn is_system_table_db(db: &str) -> bool {
     let db = db.to_ascii_lowercase();
     db.eq("cluster_schema") || db.eq("information_schema") || db.eq("usage_schema")
@@ -289,3 +392,31 @@ pub async fn connect_database(database: &str, ctx: &mut SessionContext) -> Resul
         })
         .map(|_| ())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_set_variable;
+
+    #[test]
+    fn test_parse_set_variable() {
+        assert_eq!(
+            parse_set_variable("SET max_replica_lag = '5s';"),
+            Some(("max_replica_lag".to_string(), "5s".to_string()))
+        );
+        assert_eq!(
+            parse_set_variable("set MAX_REPLICA_LAG to DEFAULT"),
+            Some(("max_replica_lag".to_string(), "DEFAULT".to_string()))
+        );
+        assert_eq!(
+            parse_set_variable("SET time_display = 'unix_ms'"),
+            Some(("time_display".to_string(), "unix_ms".to_string()))
+        );
+        assert_eq!(
+            parse_set_variable("SET time_zone TO '+08:00';"),
+            Some(("time_zone".to_string(), "+08:00".to_string()))
+        );
+        assert_eq!(parse_set_variable("settings = 1"), None);
+        assert_eq!(parse_set_variable("SELECT 1"), None);
+        assert_eq!(parse_set_variable("SET max_replica_lag"), None);
+    }
+}
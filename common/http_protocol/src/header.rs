@@ -26,6 +26,12 @@ pub const DB: &str = "db";
 pub const TABLE: &str = "table";
 pub const TARGET_PARTITIONS: &str = "target_partitions";
 pub const STREAM_TRIGGER_INTERVAL: &str = "stream_trigger_interval";
+pub const CONSISTENT_META: &str = "consistent_meta";
+pub const IGNORE_RETENTION: &str = "ignore_retention";
+pub const MAX_REPLICA_LAG: &str = "max_replica_lag";
+pub const FOLLOWER_READ: &str = "follower_read";
+pub const TIME_ZONE: &str = "time_zone";
+pub const SESSION_ID: &str = "session_id";
 
 // encoding
 pub const GZIP: &str = "gzip";
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use reqwest::header::HeaderValue;
 use reqwest::{Certificate, RequestBuilder};
 use snafu::{ResultExt, Snafu};
+use utils::net::build_address;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -66,9 +67,9 @@ impl HttpClient {
         let mut client_builder = reqwest::Client::builder();
         let addr = if use_ssl || use_unsafe_ssl {
             client_builder = client_builder.use_rustls_tls();
-            format!("https://{}:{}", host, port)
+            format!("https://{}", build_address(host, port))
         } else {
-            format!("http://{}:{}", host, port)
+            format!("http://{}", build_address(host, port))
         };
 
         if use_unsafe_ssl {
@@ -9,6 +9,23 @@ pub struct SqlParam {
     // Number of partitions for query execution. Increasing partitions can increase concurrency.
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    // Read the catalog from the meta leader consistently for SHOW/DESCRIBE/DDL statements.
+    pub consistent_meta: Option<bool>,
+    // Also return the rows older than the TTL of the database that are not deleted yet, admin only.
+    pub ignore_retention: Option<bool>,
+    // Scans may read a follower lagging behind the leader within this duration, such as "5s".
+    pub max_replica_lag: Option<String>,
+    // Scans may read a follower once it applies the raft logs committed by the leader.
+    pub follower_read: Option<bool>,
+    // Delete the rows written by an INSERT which fails or is cancelled, all the rows within the
+    // time range written are deleted.
+    pub cleanup_partial_write: Option<bool>,
+    // Output of the timestamps in the results: "rfc3339", "unix_ms" or "unix_ns".
+    pub time_display: Option<String>,
+    // Time zone of the session, such as "+08:00".
+    pub time_zone: Option<String>,
+    // Id of the session keeping the variables changed by SET, chosen by the client.
+    pub session_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -17,6 +34,20 @@ pub struct WriteParam {
     pub precision: Option<String>,
     pub tenant: Option<String>,
     pub db: Option<String>,
+    // Validate schema, routing and limits of the lines without persisting them.
+    pub dry_run: Option<bool>,
+    // Replicas to wait for before the write is acknowledged: one, quorum or all.
+    pub consistency: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct InfluxqlParam {
+    pub tenant: Option<String>,
+    pub db: Option<String>,
+    pub q: Option<String>,
+    // Precision of the returned timestamps: h, m, s, ms, u or ns, RFC3339 if absent.
+    pub epoch: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -7,6 +7,24 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize)]
 pub struct EmptyResponse {}
 
+/// Response of `/api/v1/endpoints`, the nodes clients can send requests to.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct EndpointsResponse {
+    pub endpoints: Vec<Endpoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Endpoint {
+    pub node_id: u64,
+    pub http_addr: String,
+    pub grpc_addr: String,
+    /// Engines running on the node, `query` and/or `tskv`.
+    pub roles: Vec<String>,
+    /// Status reported to meta, `unknown` if the node has not reported.
+    pub status: String,
+    pub healthy: bool,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct ErrorResponse {
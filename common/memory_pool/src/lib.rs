@@ -82,6 +82,73 @@ impl MemoryPool for GreedyMemoryPool {
     }
 }
 
+/// A [`MemoryPool`] that caps the memory used by a single query, the memory is
+/// also allocated from the shared pool of the node.
+#[derive(Debug)]
+pub struct QueryMemoryPool {
+    inner: MemoryPoolRef,
+    limit: usize,
+    used: AtomicUsize,
+}
+
+impl QueryMemoryPool {
+    pub fn new(inner: MemoryPoolRef, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            used: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.used.fetch_add(additional, Ordering::Relaxed);
+        self.inner.grow(reservation, additional)
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.used.fetch_sub(shrink, Ordering::Relaxed);
+        self.inner.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let new_used = used + additional;
+                (new_used <= self.limit).then_some(new_used)
+            })
+            .map_err(|used| {
+                DataFusionError::ResourcesExhausted(format!(
+                    "Query memory exceeded: failed to allocate additional {} bytes with {} bytes already allocated and {} bytes used by the query - the limit is {}",
+                    additional,
+                    reservation.size(),
+                    used,
+                    self.limit
+                ))
+            })?;
+
+        if let Err(err) = self.inner.try_grow(reservation, additional) {
+            self.used.fetch_sub(additional, Ordering::Relaxed);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
 fn insufficient_capacity_err(
     reservation: &MemoryReservation,
     additional: usize,
@@ -126,4 +193,30 @@ mod tests {
         a2.try_grow(25).unwrap();
         assert_eq!(pool.reserved(), 25);
     }
+
+    #[test]
+    fn test_query_memory_pool() {
+        let node_pool = Arc::new(GreedyMemoryPool::new(100)) as MemoryPoolRef;
+        let query_pool = Arc::new(QueryMemoryPool::new(node_pool.clone(), 50)) as MemoryPoolRef;
+
+        let mut a1 = MemoryConsumer::new("a1").register(&query_pool);
+        a1.try_grow(40).unwrap();
+        assert_eq!(query_pool.reserved(), 40);
+        assert_eq!(node_pool.reserved(), 40);
+
+        let err = a1.try_grow(20).unwrap_err();
+        assert!(err.to_string().contains("Query memory exceeded"));
+        assert_eq!(query_pool.reserved(), 40);
+        assert_eq!(node_pool.reserved(), 40);
+
+        // The node pool is exhausted by another query.
+        let mut a2 = MemoryConsumer::new("a2").register(&node_pool);
+        a2.try_grow(55).unwrap();
+        a1.try_grow(10).unwrap_err();
+        assert_eq!(query_pool.reserved(), 40);
+
+        drop(a1);
+        assert_eq!(query_pool.reserved(), 0);
+        assert_eq!(node_pool.reserved(), 55);
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::hash::Hash;
 
@@ -120,6 +121,8 @@ pub enum TenantObjectPrivilege {
     // T: database_name
     // None: all databases in this tenant
     Database(DatabasePrivilege, Option<String>),
+    // database_name, table_name
+    Table(TablePrivilege, String, String),
 }
 
 impl Display for TenantObjectPrivilege {
@@ -142,6 +145,9 @@ impl Display for TenantObjectPrivilege {
                     write!(f, "{:?} on all databases", p)
                 }
             },
+            Self::Table(p, db, table) => {
+                write!(f, "{} on table {}.{}", p, db, table)
+            }
         }
     }
 }
@@ -156,6 +162,13 @@ impl PrivilegeChecker for TenantObjectPrivilege {
             (Self::Database(s, Some(s_t)), Self::Database(o, Some(o_t))) => {
                 s_t == o_t && s.check_privilege(o)
             }
+            (Self::Database(s, None), Self::Table(o, _, _)) => s.check_privilege(&o.privilege),
+            (Self::Database(s, Some(s_db)), Self::Table(o, o_db, _)) => {
+                s_db == o_db && s.check_privilege(&o.privilege)
+            }
+            (Self::Table(s, s_db, s_t), Self::Table(o, o_db, o_t)) => {
+                s_db == o_db && s_t == o_t && s.check_privilege(o)
+            }
             (l, r) => l == r,
         }
     }
@@ -166,6 +179,9 @@ pub enum DatabasePrivilege {
     Read,
     Write,
     Full,
+    /// Read the decrypted values of the encrypted columns, only granted
+    /// explicitly and not implied by the other privileges.
+    Decrypt,
 }
 
 impl DatabasePrivilege {
@@ -174,6 +190,7 @@ impl DatabasePrivilege {
             Self::Read => "Read",
             Self::Write => "Write",
             Self::Full => "All",
+            Self::Decrypt => "Decrypt",
         }
     }
 }
@@ -181,6 +198,7 @@ impl DatabasePrivilege {
 impl PrivilegeChecker for DatabasePrivilege {
     fn check_privilege(&self, other: &Self) -> bool {
         match (self, other) {
+            (Self::Decrypt, _) | (_, Self::Decrypt) => self == other,
             (Self::Full, _) => true,
             (Self::Write, Self::Write | Self::Read) => true,
             (Self::Read, Self::Read) => true,
@@ -188,3 +206,86 @@ impl PrivilegeChecker for DatabasePrivilege {
         }
     }
 }
+
+/// Privilege on a table, limited to some columns of the table if `columns` is Some.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TablePrivilege {
+    pub privilege: DatabasePrivilege,
+    pub columns: Option<BTreeSet<String>>,
+}
+
+impl TablePrivilege {
+    pub fn new(privilege: DatabasePrivilege, columns: Option<BTreeSet<String>>) -> Self {
+        Self { privilege, columns }
+    }
+}
+
+impl Display for TablePrivilege {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.columns {
+            Some(columns) => {
+                let columns = columns.iter().cloned().collect::<Vec<_>>().join(", ");
+                write!(f, "{:?} ({})", self.privilege, columns)
+            }
+            None => write!(f, "{:?}", self.privilege),
+        }
+    }
+}
+
+impl PrivilegeChecker for TablePrivilege {
+    fn check_privilege(&self, other: &Self) -> bool {
+        let columns = match (&self.columns, &other.columns) {
+            (None, _) => true,
+            (Some(s), Some(o)) => o.is_subset(s),
+            (Some(_), None) => false,
+        };
+        columns && self.privilege.check_privilege(&other.privilege)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::{DatabasePrivilege, PrivilegeChecker, TablePrivilege, TenantObjectPrivilege};
+
+    fn table(privilege: DatabasePrivilege, columns: Option<&[&str]>) -> TenantObjectPrivilege {
+        let columns = columns.map(|c| {
+            c.iter()
+                .map(|c| c.to_string())
+                .collect::<BTreeSet<String>>()
+        });
+        TenantObjectPrivilege::Table(
+            TablePrivilege::new(privilege, columns),
+            "db".to_string(),
+            "t".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_table_privilege() {
+        let read_time_f1 = table(DatabasePrivilege::Read, Some(&["time", "f1"]));
+        assert!(read_time_f1.check_privilege(&table(DatabasePrivilege::Read, Some(&["f1"]))));
+        assert!(!read_time_f1.check_privilege(&table(DatabasePrivilege::Read, Some(&["f2"]))));
+        assert!(!read_time_f1.check_privilege(&table(DatabasePrivilege::Read, None)));
+        assert!(!read_time_f1.check_privilege(&table(DatabasePrivilege::Write, Some(&["f1"]))));
+
+        let write_all = table(DatabasePrivilege::Write, None);
+        assert!(write_all.check_privilege(&table(DatabasePrivilege::Read, Some(&["f2"]))));
+
+        let read_db = TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some("db".into()));
+        assert!(read_db.check_privilege(&table(DatabasePrivilege::Read, None)));
+        assert!(!read_db.check_privilege(&table(DatabasePrivilege::Write, None)));
+        let read_other_db =
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some("other".into()));
+        assert!(!read_other_db.check_privilege(&table(DatabasePrivilege::Read, None)));
+        assert!(!read_time_f1.check_privilege(&read_db));
+    }
+
+    #[test]
+    fn test_decrypt_privilege() {
+        assert!(DatabasePrivilege::Decrypt.check_privilege(&DatabasePrivilege::Decrypt));
+        assert!(!DatabasePrivilege::Full.check_privilege(&DatabasePrivilege::Decrypt));
+        assert!(!DatabasePrivilege::Decrypt.check_privilege(&DatabasePrivilege::Read));
+    }
+}
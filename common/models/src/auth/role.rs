@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::sync::Arc;
@@ -6,7 +6,9 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
-use super::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege, TenantObjectPrivilege};
+use super::privilege::{
+    DatabasePrivilege, GlobalPrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
+};
 use super::AuthResult;
 use crate::auth::AuthError;
 use crate::oid::{Id, Identifier};
@@ -32,6 +34,10 @@ impl<T: Id> UserRole<T> {
                     TenantObjectPrivilege::Database(DatabasePrivilege::Full, None),
                     None,
                 ),
+                Privilege::TenantObject(
+                    TenantObjectPrivilege::Database(DatabasePrivilege::Decrypt, None),
+                    None,
+                ),
             ]
             .into_iter()
             .collect(),
@@ -131,6 +137,10 @@ impl SystemTenantRole {
                     TenantObjectPrivilege::Database(DatabasePrivilege::Full, None),
                     Some(tenant_id.clone()),
                 ),
+                Privilege::TenantObject(
+                    TenantObjectPrivilege::Database(DatabasePrivilege::Decrypt, None),
+                    Some(tenant_id.clone()),
+                ),
             ]
             .into_iter()
             .collect(),
@@ -161,6 +171,12 @@ pub struct CustomTenantRole<T> {
     // database_name -> privileges
     // only add database privilege
     additional_privileges: HashMap<String, DatabasePrivilege>,
+    // database_name -> table_name -> privileges
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    table_privileges: HashMap<String, HashMap<String, TablePrivilege>>,
+    // database_name, granted decrypt besides the privilege above
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    decrypt_databases: BTreeSet<String>,
 }
 
 impl<T> CustomTenantRole<T> {
@@ -177,6 +193,8 @@ impl<T> CustomTenantRole<T> {
             name,
             system_role,
             additional_privileges,
+            table_privileges: HashMap::new(),
+            decrypt_databases: BTreeSet::new(),
         }
     }
 
@@ -187,6 +205,22 @@ impl<T> CustomTenantRole<T> {
     pub fn additional_privileges(&self) -> &HashMap<String, DatabasePrivilege> {
         &self.additional_privileges
     }
+
+    pub fn table_privileges(&self) -> &HashMap<String, HashMap<String, TablePrivilege>> {
+        &self.table_privileges
+    }
+
+    /// The privileges on the databases, including decrypt.
+    pub fn database_privileges(&self) -> Vec<(&String, &DatabasePrivilege)> {
+        self.additional_privileges
+            .iter()
+            .chain(
+                self.decrypt_databases
+                    .iter()
+                    .map(|db| (db, &DatabasePrivilege::Decrypt)),
+            )
+            .collect()
+    }
 }
 
 impl<T: Id> CustomTenantRole<T> {
@@ -206,8 +240,45 @@ impl<T: Id> CustomTenantRole<T> {
                 )
             })
             .collect::<HashSet<Privilege<T>>>();
+        let additiona_privileges = self
+            .decrypt_databases
+            .iter()
+            .map(|db_name| {
+                Privilege::TenantObject(
+                    TenantObjectPrivilege::Database(
+                        DatabasePrivilege::Decrypt,
+                        Some(db_name.clone()),
+                    ),
+                    Some(tenant_id.clone()),
+                )
+            })
+            .chain(additiona_privileges)
+            .collect::<HashSet<Privilege<T>>>();
 
-        privileges.union(&additiona_privileges).cloned().collect()
+        let table_privileges = self
+            .table_privileges
+            .iter()
+            .flat_map(|(db_name, tables)| {
+                tables.iter().map(|(table_name, privilege)| {
+                    Privilege::TenantObject(
+                        TenantObjectPrivilege::Table(
+                            privilege.clone(),
+                            db_name.clone(),
+                            table_name.clone(),
+                        ),
+                        Some(tenant_id.clone()),
+                    )
+                })
+            })
+            .collect::<HashSet<Privilege<T>>>();
+
+        privileges
+            .union(&additiona_privileges)
+            .cloned()
+            .collect::<HashSet<_>>()
+            .union(&table_privileges)
+            .cloned()
+            .collect()
     }
 
     pub fn grant_privilege(
@@ -215,7 +286,11 @@ impl<T: Id> CustomTenantRole<T> {
         database_name: String,
         privilege: DatabasePrivilege,
     ) -> AuthResult<()> {
-        self.additional_privileges.insert(database_name, privilege);
+        if privilege == DatabasePrivilege::Decrypt {
+            self.decrypt_databases.insert(database_name);
+        } else {
+            self.additional_privileges.insert(database_name, privilege);
+        }
 
         Ok(())
     }
@@ -225,6 +300,10 @@ impl<T: Id> CustomTenantRole<T> {
         database_name: &str,
         privilege: &DatabasePrivilege,
     ) -> AuthResult<bool> {
+        if privilege == &DatabasePrivilege::Decrypt && self.decrypt_databases.remove(database_name)
+        {
+            return Ok(true);
+        }
         if let Some(p) = self.additional_privileges.get(database_name) {
             if p == privilege {
                 Ok(self.additional_privileges.remove(database_name).is_some())
@@ -245,6 +324,52 @@ impl<T: Id> CustomTenantRole<T> {
     }
 }
 
+impl<T> CustomTenantRole<T> {
+    /// Grant the privilege on the table, replaces the privilege granted before.
+    pub fn grant_table_privilege(
+        &mut self,
+        database_name: String,
+        table_name: String,
+        privilege: TablePrivilege,
+    ) {
+        self.table_privileges
+            .entry(database_name)
+            .or_default()
+            .insert(table_name, privilege);
+    }
+
+    /// Revoke the privilege on the table, whichever columns it was granted on.
+    pub fn revoke_table_privilege(
+        &mut self,
+        database_name: &str,
+        table_name: &str,
+        privilege: &DatabasePrivilege,
+    ) -> AuthResult<()> {
+        let tables = match self.table_privileges.get_mut(database_name) {
+            Some(tables)
+                if tables
+                    .get(table_name)
+                    .is_some_and(|p| &p.privilege == privilege) =>
+            {
+                tables
+            }
+            _ => {
+                return Err(AuthError::PrivilegeNotFound {
+                    db: format!("{database_name}.{table_name}"),
+                    privilege: privilege.to_owned(),
+                    role: self.name.to_owned(),
+                })
+            }
+        };
+        tables.remove(table_name);
+        if tables.is_empty() {
+            self.table_privileges.remove(database_name);
+        }
+
+        Ok(())
+    }
+}
+
 impl<T> Identifier<T> for CustomTenantRole<T> {
     fn id(&self) -> &T {
         &self.id
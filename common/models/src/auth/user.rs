@@ -4,6 +4,7 @@ use std::fmt::Display;
 use base64::prelude::{Engine, BASE64_STANDARD};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use utils::duration::CnosDuration;
 
 use super::privilege::{
     DatabasePrivilege, GlobalPrivilege, Privilege, PrivilegeChecker, TenantObjectPrivilege,
@@ -71,6 +72,18 @@ impl User {
         );
         self.check_privilege(&privilege)
     }
+
+    /// Whether the user can read the decrypted values of the encrypted columns.
+    pub fn can_decrypt_database(&self, tenant_id: Oid, database_name: &str) -> bool {
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(
+                DatabasePrivilege::Decrypt,
+                Some(database_name.to_string()),
+            ),
+            Some(tenant_id),
+        );
+        self.check_privilege(&privilege)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +163,10 @@ pub struct UserOptions {
     comment: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     granted_admin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query_timeout: Option<CnosDuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    write_timeout: Option<CnosDuration>,
 }
 
 impl UserOptions {
@@ -168,6 +185,12 @@ impl UserOptions {
     pub fn granted_admin(&self) -> Option<bool> {
         self.granted_admin
     }
+    pub fn query_timeout(&self) -> Option<&CnosDuration> {
+        self.query_timeout.as_ref()
+    }
+    pub fn write_timeout(&self) -> Option<&CnosDuration> {
+        self.write_timeout.as_ref()
+    }
 
     pub fn merge(self, other: Self) -> Self {
         Self {
@@ -176,6 +199,8 @@ impl UserOptions {
             rsa_public_key: self.rsa_public_key.or(other.rsa_public_key),
             comment: self.comment.or(other.comment),
             granted_admin: self.granted_admin.or(other.granted_admin),
+            query_timeout: self.query_timeout.or(other.query_timeout),
+            write_timeout: self.write_timeout.or(other.write_timeout),
         }
     }
     pub fn hidden_password(&mut self) {
@@ -214,6 +239,14 @@ impl Display for UserOptions {
             write!(f, "granted_admin={},", e)?;
         }
 
+        if let Some(ref e) = self.query_timeout {
+            write!(f, "query_timeout={},", e)?;
+        }
+
+        if let Some(ref e) = self.write_timeout {
+            write!(f, "write_timeout={},", e)?;
+        }
+
         Ok(())
     }
 }
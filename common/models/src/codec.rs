@@ -2,26 +2,30 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-pub const BIGINT_CODEC: [Encoding; 5] = [
+pub const BIGINT_CODEC: [Encoding; 6] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Delta,
     Encoding::DeltaTs,
     Encoding::Quantile,
+    Encoding::Auto,
 ];
 // Because timestamp, bigint, and unsigned bigint are all integers,
 // so their compression algorithms are the same
-pub const TIMESTAMP_CODEC: [Encoding; 5] = BIGINT_CODEC;
-pub const UNSIGNED_BIGINT_CODEC: [Encoding; 5] = BIGINT_CODEC;
+pub const TIMESTAMP_CODEC: [Encoding; 6] = BIGINT_CODEC;
+pub const UNSIGNED_BIGINT_CODEC: [Encoding; 6] = BIGINT_CODEC;
 
-pub const DOUBLE_CODEC: [Encoding; 4] = [
+pub const DOUBLE_CODEC: [Encoding; 7] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Gorilla,
     Encoding::Quantile,
+    Encoding::Zstd,
+    Encoding::Lz4,
+    Encoding::Auto,
 ];
 
-pub const STRING_CODEC: [Encoding; 7] = [
+pub const STRING_CODEC: [Encoding; 9] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Gzip,
@@ -29,9 +33,16 @@ pub const STRING_CODEC: [Encoding; 7] = [
     Encoding::Zstd,
     Encoding::Snappy,
     Encoding::Zlib,
+    Encoding::Lz4,
+    Encoding::Auto,
 ];
 
-pub const BOOLEAN_CODEC: [Encoding; 3] = [Encoding::Default, Encoding::Null, Encoding::BitPack];
+pub const BOOLEAN_CODEC: [Encoding; 4] = [
+    Encoding::Default,
+    Encoding::Null,
+    Encoding::BitPack,
+    Encoding::Auto,
+];
 
 #[derive(
     Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Hash, Default, Ord, PartialOrd,
@@ -50,6 +61,10 @@ pub enum Encoding {
     Zlib = 9,
     BitPack = 10,
     DeltaTs = 11,
+    Lz4 = 12,
+    /// Chooses the codec of each block by the values in it, the chosen codec
+    /// is recorded in the header of the block.
+    Auto = 13,
     Unknown = 15,
 }
 
@@ -92,6 +107,8 @@ impl Encoding {
             Encoding::Zstd => "ZSTD",
             Encoding::Zlib => "ZLIB",
             Encoding::BitPack => "BITPACK",
+            Encoding::Lz4 => "LZ4",
+            Encoding::Auto => "AUTO",
             Encoding::Unknown => "UNKNOWN",
         }
     }
@@ -113,6 +130,8 @@ impl FromStr for Encoding {
             "ZSTD" => Ok(Self::Zstd),
             "ZLIB" => Ok(Self::Zlib),
             "BITPACK" => Ok(Self::BitPack),
+            "LZ4" => Ok(Self::Lz4),
+            "AUTO" => Ok(Self::Auto),
             _ => Err(s.to_string()),
         }
     }
@@ -133,6 +152,8 @@ impl From<u8> for Encoding {
             9 => Encoding::Zlib,
             10 => Encoding::BitPack,
             11 => Encoding::DeltaTs,
+            12 => Encoding::Lz4,
+            13 => Encoding::Auto,
             _ => Encoding::Unknown,
         }
     }
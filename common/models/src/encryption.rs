@@ -0,0 +1,160 @@
+//! Deterministic encryption of string field values.
+//!
+//! A value is encrypted by AES-256-CBC with a synthetic IV, which is the first 16
+//! bytes of HMAC-SHA256 of the value, so the same value is always encrypted to the
+//! same ciphertext under the same key, and encrypted values can still be compared
+//! for equality. The IV is checked after decryption to detect a wrong key.
+
+use std::fmt::Debug;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use openssl::symm::{decrypt, encrypt, Cipher};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::EncryptionSnafu;
+use crate::{ModelError, ModelResult};
+
+/// Length in bytes of an encryption key, keys are stored as hex strings.
+pub const ENCRYPTION_KEY_LEN: usize = 32;
+const IV_LEN: usize = 16;
+
+/// An encryption key in hex, hidden in the debug output.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EncryptionKey(String);
+
+impl EncryptionKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionKey(******)")
+    }
+}
+
+#[derive(Clone)]
+pub struct ColumnCipher {
+    enc_key: Vec<u8>,
+    mac_key: Vec<u8>,
+}
+
+impl Debug for ColumnCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ColumnCipher")
+    }
+}
+
+impl ColumnCipher {
+    /// Create a cipher from a key of `ENCRYPTION_KEY_LEN` bytes in hex.
+    pub fn from_hex(key: &str) -> ModelResult<Self> {
+        let key = decode_hex(key)?;
+        if key.len() != ENCRYPTION_KEY_LEN {
+            return Err(encryption_error(format!(
+                "encryption key must be {} bytes, got {}",
+                ENCRYPTION_KEY_LEN,
+                key.len()
+            )));
+        }
+
+        Ok(Self {
+            enc_key: hmac_sha256(&key, b"cnosdb column encryption")?,
+            mac_key: hmac_sha256(&key, b"cnosdb column iv")?,
+        })
+    }
+
+    /// Encrypt the value, returns `base64(iv + ciphertext)`.
+    pub fn encrypt(&self, value: &[u8]) -> ModelResult<String> {
+        let iv = &hmac_sha256(&self.mac_key, value)?[..IV_LEN];
+        let ciphertext = encrypt(Cipher::aes_256_cbc(), &self.enc_key, Some(iv), value)
+            .map_err(|e| encryption_error(e.to_string()))?;
+
+        let mut data = Vec::with_capacity(IV_LEN + ciphertext.len());
+        data.extend_from_slice(iv);
+        data.extend_from_slice(&ciphertext);
+        Ok(BASE64_STANDARD.encode(data))
+    }
+
+    /// Decrypt the value encrypted by `encrypt`.
+    pub fn decrypt(&self, encrypted: &[u8]) -> ModelResult<Vec<u8>> {
+        let data = BASE64_STANDARD
+            .decode(encrypted)
+            .map_err(|e| encryption_error(format!("invalid encrypted value: {e}")))?;
+        if data.len() <= IV_LEN {
+            return Err(encryption_error("invalid encrypted value: too short"));
+        }
+
+        let (iv, ciphertext) = data.split_at(IV_LEN);
+        let value = decrypt(Cipher::aes_256_cbc(), &self.enc_key, Some(iv), ciphertext)
+            .map_err(|e| encryption_error(e.to_string()))?;
+        if hmac_sha256(&self.mac_key, &value)?[..IV_LEN] != *iv {
+            return Err(encryption_error("encrypted value does not match the key"));
+        }
+
+        Ok(value)
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> ModelResult<Vec<u8>> {
+    let openssl_err = |e: openssl::error::ErrorStack| encryption_error(e.to_string());
+    let pkey = PKey::hmac(key).map_err(openssl_err)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &pkey).map_err(openssl_err)?;
+    signer.update(data).map_err(openssl_err)?;
+    signer.sign_to_vec().map_err(openssl_err)
+}
+
+fn decode_hex(s: &str) -> ModelResult<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return Err(encryption_error("encryption key must be a hex string"));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16)
+                .map_err(|_| encryption_error("encryption key must be a hex string"))
+        })
+        .collect()
+}
+
+fn encryption_error(msg: impl Into<String>) -> ModelError {
+    EncryptionSnafu { msg: msg.into() }.build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColumnCipher, EncryptionKey};
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn test_column_cipher() {
+        let cipher = ColumnCipher::from_hex(KEY).unwrap();
+
+        let encrypted = cipher.encrypt(b"secret").unwrap();
+        assert_ne!(encrypted.as_bytes(), b"secret");
+        // Deterministic
+        assert_eq!(encrypted, cipher.encrypt(b"secret").unwrap());
+        assert_ne!(encrypted, cipher.encrypt(b"secret2").unwrap());
+        assert_eq!(cipher.decrypt(encrypted.as_bytes()).unwrap(), b"secret");
+
+        let other = ColumnCipher::from_hex(&KEY.replace("1f", "ff")).unwrap();
+        assert!(other.decrypt(encrypted.as_bytes()).is_err());
+
+        assert_eq!(
+            format!("{:?}", EncryptionKey::new(KEY)),
+            "EncryptionKey(******)"
+        );
+
+        assert!(ColumnCipher::from_hex("0011").is_err());
+        assert!(ColumnCipher::from_hex(&KEY.replace("00", "zz")).is_err());
+    }
+}
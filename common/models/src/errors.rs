@@ -100,6 +100,13 @@ pub enum ModelError {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Column encryption error: {}", msg))]
+    Encryption {
+        msg: String,
+        location: Location,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("{msg}"))]
     Common {
         msg: String,
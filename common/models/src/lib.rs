@@ -26,6 +26,7 @@ pub mod column_data;
 pub mod column_data_ref;
 pub mod datafusion;
 pub mod duration;
+pub mod encryption;
 pub mod field_value;
 pub mod gis;
 pub mod mutable_batch;
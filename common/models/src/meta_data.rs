@@ -7,7 +7,7 @@ use crate::auth::role::{CustomTenantRole, TenantRoleIdentifier};
 use crate::node_info::NodeStatus;
 use crate::oid::Oid;
 use crate::predicate::domain::TimeRange;
-use crate::schema::database_schema::DatabaseSchema;
+use crate::schema::database_schema::{DatabaseSchema, SeriesPlacement};
 use crate::schema::resource_info::ResourceInfo;
 use crate::schema::table_schema::TableSchema;
 
@@ -21,6 +21,22 @@ pub enum MetaModifyType {
     ResourceInfo(Box<ResourceInfo>),
 }
 
+/// A database or a table dropped, watched from the meta service by all nodes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum DroppedResource {
+    Database {
+        tenant: String,
+        database: String,
+        /// Drop time in nanoseconds.
+        drop_time: i64,
+    },
+    Table {
+        tenant: String,
+        database: String,
+        table: String,
+    },
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct SysInfo {
     pub cpu_load: f64,
@@ -53,6 +69,39 @@ pub struct UserInfo {
 pub struct NodeInfo {
     pub id: NodeId,
     pub grpc_addr: String,
+    /// Address of the http service, empty if it is not enabled.
+    #[serde(default)]
+    pub http_addr: String,
+    /// Deployment mode of the node, empty for the nodes registered before
+    /// it was reported, which run in the default mode `query_tskv`.
+    #[serde(default)]
+    pub mode: String,
+}
+
+impl NodeInfo {
+    /// Whether the node runs the query engine and accepts the requests of clients.
+    pub fn runs_query(&self) -> bool {
+        matches!(
+            self.mode.as_str(),
+            "" | "query_tskv" | "query" | "singleton"
+        )
+    }
+
+    /// Whether the node runs the tskv engine and stores vnodes.
+    pub fn runs_tskv(&self) -> bool {
+        matches!(self.mode.as_str(), "" | "query_tskv" | "tskv" | "singleton")
+    }
+
+    pub fn roles(&self) -> Vec<String> {
+        let mut roles = vec![];
+        if self.runs_query() {
+            roles.push("query".to_string());
+        }
+        if self.runs_tskv() {
+            roles.push("tskv".to_string());
+        }
+        roles
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -61,6 +110,13 @@ pub struct NodeMetrics {
     pub disk_free: u64,
     pub time: i64,
     pub status: NodeStatus,
+    /// Clock offset in nanoseconds of meta leader against this node,
+    /// positive value means the clock of this node is behind.
+    #[serde(default)]
+    pub clock_offset: i64,
+    /// Last applied raft entry of the vnodes on this node.
+    #[serde(default)]
+    pub vnode_applied: HashMap<VnodeId, VnodeApplied>,
 }
 
 impl NodeMetrics {
@@ -69,17 +125,55 @@ impl NodeMetrics {
     }
 }
 
+/// The last raft entry applied by a vnode and when it was applied.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VnodeApplied {
+    pub index: u64,
+    /// Nanoseconds timestamp of the node when the entry was applied.
+    pub time: i64,
+    /// Nanoseconds timestamp of the node when the entry was reported to meta.
+    #[serde(default)]
+    pub reported: i64,
+}
+
+impl VnodeApplied {
+    /// Nanoseconds the follower lags behind the leader, 0 if the follower has
+    /// applied the entry last applied by the leader. Otherwise the follower has
+    /// nothing newer than when it last applied an entry.
+    pub fn lag_behind(&self, leader: &VnodeApplied) -> i64 {
+        if self.index >= leader.index {
+            0
+        } else {
+            (leader.time - self.time).max(0)
+        }
+    }
+
+    /// Nanoseconds the data of the follower may be stale at `now`, the entries
+    /// applied by the leader since it was reported are unknown, so the lag
+    /// grows with the age of the report of the leader.
+    pub fn staleness(&self, leader: &VnodeApplied, now: i64) -> i64 {
+        self.lag_behind(leader)
+            .saturating_add((now - leader.reported).max(0))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct BucketInfo {
     pub id: u32,
     pub start_time: i64,
     pub end_time: i64,
     pub shard_group: Vec<ReplicationSet>,
+    /// Placement of the database when the bucket was created, buckets created
+    /// by older versions place series by modulo.
+    #[serde(default)]
+    pub series_placement: SeriesPlacement,
 }
 
 impl BucketInfo {
     pub fn vnode_for(&self, id: u64) -> ReplicationSet {
-        let index = id as usize % self.shard_group.len();
+        let index = self
+            .series_placement
+            .shard_index(id, self.shard_group.len());
 
         self.shard_group[index].clone()
     }
@@ -127,6 +221,11 @@ impl ReplicationSet {
 
         None
     }
+
+    /// The vnodes voting in the raft group, i.e. all the vnodes except the learners.
+    pub fn voters(&self) -> impl Iterator<Item = &VnodeInfo> {
+        self.vnodes.iter().filter(|vnode| !vnode.learner)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
@@ -135,6 +234,10 @@ pub struct VnodeInfo {
     pub node_id: NodeId,
     #[serde(default = "Default::default")]
     pub status: VnodeStatus,
+    /// A read-only replica that receives the raft logs but never votes or
+    /// becomes the leader, used for the analytical scans and the backups.
+    #[serde(default = "Default::default")]
+    pub learner: bool,
 }
 
 impl VnodeInfo {
@@ -164,6 +267,8 @@ pub struct VnodeAllInfo {
     pub db_name: String,
     pub tenant: String,
     pub status: VnodeStatus,
+    #[serde(default = "Default::default")]
+    pub learner: bool,
     pub start_time: i64,
     pub end_time: i64,
 }
@@ -190,6 +295,7 @@ impl From<VnodeAllInfo> for VnodeInfo {
             id: value.vnode_id,
             node_id: value.node_id,
             status: value.status,
+            learner: value.learner,
         }
     }
 }
@@ -243,6 +349,9 @@ pub struct TenantMetaData {
     pub dbs: HashMap<String, DatabaseInfo>,
     pub roles: HashMap<String, CustomTenantRole<Oid>>,
     pub members: HashMap<String, TenantRoleIdentifier>,
+    // db_name -> drop time in nanoseconds, until the database is created again
+    #[serde(default)]
+    pub dropped_dbs: HashMap<String, i64>,
 }
 
 impl TenantMetaData {
@@ -252,6 +361,7 @@ impl TenantMetaData {
             dbs: HashMap::new(),
             roles: HashMap::new(),
             members: HashMap::new(),
+            dropped_dbs: HashMap::new(),
         }
     }
 
@@ -423,7 +533,27 @@ pub fn get_disk_info(path: &str) -> std::io::Result<u64> {
 
 #[cfg(test)]
 mod test {
-    use super::get_disk_info;
+    use super::{get_disk_info, NodeInfo};
+
+    #[test]
+    fn test_node_roles() {
+        let node: NodeInfo = serde_json::from_str(r#"{"id":1,"grpc_addr":"a:8903"}"#).unwrap();
+        assert!(node.http_addr.is_empty());
+        assert_eq!(node.roles(), vec!["query", "tskv"]);
+
+        for (mode, roles) in [
+            ("query_tskv", vec!["query", "tskv"]),
+            ("singleton", vec!["query", "tskv"]),
+            ("query", vec!["query"]),
+            ("tskv", vec!["tskv"]),
+        ] {
+            let node = NodeInfo {
+                mode: mode.to_string(),
+                ..Default::default()
+            };
+            assert_eq!(node.roles(), roles, "mode: {mode}");
+        }
+    }
 
     #[test]
     fn test_get_disk_info() {
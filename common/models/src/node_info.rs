@@ -30,6 +30,18 @@ pub enum NodeStatus {
     Cordon,
 }
 
+impl NodeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Broken => "broken",
+            Self::Unreachable => "unreachable",
+            Self::NoDiskSpace => "no_disk_space",
+            Self::Cordon => "cordon",
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub struct Location {
     ///  aws / huawei / google / local
@@ -8,8 +8,9 @@ use std::sync::Arc;
 use arrow_schema::Schema;
 use datafusion::arrow::datatypes::DataType;
 use datafusion::common::DFSchema;
-use datafusion::logical_expr::Expr;
+use datafusion::logical_expr::{Expr, Operator};
 use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_expr::expressions::BinaryExpr;
 use datafusion::physical_expr::{create_physical_expr, PhysicalExpr};
 use datafusion::prelude::Column;
 use datafusion::scalar::ScalarValue;
@@ -27,7 +28,7 @@ use super::utils::filter_to_time_ranges;
 use super::PlacedSplit;
 use crate::errors::{InternalSnafu, InvalidQueryExprMsgSnafu, InvalidSerdeMessageSnafu};
 use crate::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
-use crate::{ModelResult, Timestamp};
+use crate::{ColumnId, ModelResult, Timestamp};
 
 pub type PredicateRef = Arc<Predicate>;
 pub type ResolvedPredicateRef = Arc<ResolvedPredicate>;
@@ -1131,6 +1132,30 @@ pub enum Domain {
 }
 
 impl Domain {
+    /// The values of the domain if it consists of finitely many single values,
+    /// such as the domain of `col = 'a' OR col = 'b'`.
+    pub fn exact_values(&self) -> Option<Vec<&ScalarValue>> {
+        match self {
+            Domain::Range(set) => set
+                .low_indexed_ranges()
+                .into_iter()
+                .map(
+                    |(_, range)| match (range.start_bound(), range.end_bound()) {
+                        (StdBound::Included(low), StdBound::Included(high)) if low == high => {
+                            Some(low)
+                        }
+                        _ => None,
+                    },
+                )
+                .collect(),
+            Domain::Equtable(set) if set.is_white_list() => {
+                Some(set.entries().into_iter().map(|e| e.value()).collect())
+            }
+            Domain::None => Some(vec![]),
+            _ => None,
+        }
+    }
+
     /// Ranges are coalesced into the most compact representation of non-overlapping Ranges.
     ///
     /// Return new ValueSet(RangeValueSet)
@@ -1668,6 +1693,9 @@ pub struct ResolvedPredicate {
     time_ranges: Arc<TimeRanges>,
     tags_filter: ColumnDomains<String>,
     physical_expr: PhysicalExprNodeWrap,
+    /// Domains of the indexed fields, by the column ids.
+    #[serde(default)]
+    fields_filter: ColumnDomains<ColumnId>,
 }
 
 impl ResolvedPredicate {
@@ -1685,9 +1713,15 @@ impl ResolvedPredicate {
             time_ranges,
             tags_filter,
             physical_expr: PhysicalExprNodeWrap(node),
+            fields_filter: ColumnDomains::all(),
         })
     }
 
+    pub fn with_fields_filter(mut self, fields_filter: ColumnDomains<ColumnId>) -> Self {
+        self.fields_filter = fields_filter;
+        self
+    }
+
     pub fn time_ranges(&self) -> Arc<TimeRanges> {
         self.time_ranges.clone()
     }
@@ -1696,9 +1730,21 @@ impl ResolvedPredicate {
         &self.tags_filter
     }
 
+    pub fn fields_filter(&self) -> &ColumnDomains<ColumnId> {
+        &self.fields_filter
+    }
+
     pub fn filter(&self) -> &PhysicalExprNode {
         &self.physical_expr.0
     }
+
+    pub fn encode(&self) -> ModelResult<Vec<u8>> {
+        bincode::serialize(self).context(InvalidSerdeMessageSnafu)
+    }
+
+    pub fn decode(buf: &[u8]) -> ModelResult<ResolvedPredicate> {
+        bincode::deserialize::<ResolvedPredicate>(buf).context(InvalidSerdeMessageSnafu)
+    }
 }
 
 #[derive(Debug)]
@@ -1726,6 +1772,26 @@ impl Predicate {
         self
     }
 
+    /// Combine with another predicate, the rows must satisfy both of them.
+    pub fn and(&self, other: &Predicate) -> Predicate {
+        let mut pushed_down_domains = self.pushed_down_domains.clone();
+        pushed_down_domains.intersect(&other.pushed_down_domains);
+        let physical_expr = match (&self.physical_expr, &other.physical_expr) {
+            (Some(left), Some(right)) => Some(Arc::new(BinaryExpr::new(
+                left.clone(),
+                Operator::And,
+                right.clone(),
+            )) as Arc<dyn PhysicalExpr>),
+            (Some(expr), None) | (None, Some(expr)) => Some(expr.clone()),
+            (None, None) => None,
+        };
+        Predicate {
+            pushed_down_domains,
+            physical_expr,
+            limit: self.limit,
+        }
+    }
+
     /// resolve and extract supported filter
     /// convert filter to ColumnDomains and set self
     pub fn push_down_filter(
@@ -1837,9 +1903,50 @@ pub fn decode_agg(buf: &[u8]) -> ModelResult<Option<Vec<PushedAggregateFunction>
     Ok(args)
 }
 
+/// Aggregate function computed by the vnodes, the argument is a column name.
+///
+/// `Count`, and `Min`/`Max` of the time column, are computed from the statistics
+/// of the chunks, the column groups partly in the queried time ranges are decoded.
+/// They are output as one value per chunk, merged by the query node. The others
+/// are computed from the values of the column and output as partial states merged
+/// by the query node:
+/// - `BoolAnd`/`BoolOr`: one boolean.
+/// - `CountDistinct`: the distinct values.
+/// - `Mode`: the distinct values and the number of their occurrences.
+/// - `Min`/`Max` of a field column: the min or the max value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PushedAggregateFunction {
     Count(String),
+    BoolAnd(String),
+    BoolOr(String),
+    CountDistinct(String),
+    Mode(String),
+    Min(String),
+    Max(String),
+}
+
+impl PushedAggregateFunction {
+    pub fn column(&self) -> &str {
+        match self {
+            Self::Count(column)
+            | Self::BoolAnd(column)
+            | Self::BoolOr(column)
+            | Self::CountDistinct(column)
+            | Self::Mode(column)
+            | Self::Min(column)
+            | Self::Max(column) => column,
+        }
+    }
+
+    /// Whether the aggregate is computed from the values of the column rather
+    /// than from the statistics.
+    pub fn reads_values(&self, time_column: &str) -> bool {
+        match self {
+            Self::Count(_) => false,
+            Self::Min(column) | Self::Max(column) => column != time_column,
+            _ => true,
+        }
+    }
 }
 
 #[cfg(test)]
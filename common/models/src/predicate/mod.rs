@@ -8,7 +8,7 @@ use self::domain::{ColumnDomains, PredicateRef, TimeRange, TimeRanges};
 use crate::meta_data::{ReplicationSet, ReplicationSetId, VnodeInfo};
 use crate::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
 use crate::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
-use crate::ModelResult;
+use crate::{ColumnId, ModelResult};
 
 pub mod domain;
 pub mod transformation;
@@ -38,14 +38,17 @@ impl Split {
             ColumnType::Tag => Some(e.name.clone()),
             _ => None,
         });
+        let fields_filter = domains_filter.translate_column(|e| match e.column_type {
+            ColumnType::Field(_) if e.indexed => Some(e.id),
+            _ => None,
+        });
 
         let limit = predicate.limit();
 
-        let predicate = Arc::new(ResolvedPredicate::new(
-            Arc::new(TimeRanges::new(time_ranges)),
-            tags_filter,
-            filter,
-        )?);
+        let predicate = Arc::new(
+            ResolvedPredicate::new(Arc::new(TimeRanges::new(time_ranges)), tags_filter, filter)?
+                .with_fields_filter(fields_filter),
+        );
 
         Ok(Self {
             id,
@@ -66,6 +69,10 @@ impl Split {
         self.predicate.tags_filter()
     }
 
+    pub fn fields_filter(&self) -> &ColumnDomains<ColumnId> {
+        self.predicate.fields_filter()
+    }
+
     pub fn filter(&self) -> &PhysicalExprNode {
         self.predicate.filter()
     }
@@ -17,6 +17,8 @@ use tokio::sync::oneshot::Receiver;
 use tokio_util::sync::CancellationToken;
 use trace::{debug, warn};
 
+use crate::runtime::stats::ThreadPoolStats;
+
 /// Task that can be added to the executor-internal queue.
 ///
 /// Every task within the executor is represented by a [`Job`] that can be polled by the API user.
@@ -26,6 +28,8 @@ struct Task {
 
     #[allow(dead_code)]
     task_ref: Arc<()>,
+
+    stats: Arc<ThreadPoolStats>,
 }
 
 impl Task {
@@ -33,10 +37,12 @@ impl Task {
     ///
     /// This runs the payload or cancels if the linked [`Job`] is dropped.
     async fn run(self) {
+        self.stats.job_started();
         tokio::select! {
             _ = self.cancel.cancelled() => (),
             _ = self.fut => (),
         }
+        self.stats.job_finished();
     }
 }
 
@@ -99,6 +105,8 @@ pub struct DedicatedExecutor {
 
     /// Number of threads
     num_threads: usize,
+
+    stats: Arc<ThreadPoolStats>,
 }
 
 /// Runs futures (and any `tasks` that are `tokio::task::spawned` by
@@ -176,11 +184,16 @@ impl DedicatedExecutor {
 
         let (tx_tasks, rx_tasks) = std::sync::mpsc::channel::<Task>();
         let (tx_shutdown, rx_shutdown) = tokio::sync::oneshot::channel();
+        let stats = ThreadPoolStats::register(&thread_name, num_threads);
+        let thread_stats = stats.clone();
 
         let thread = std::thread::Builder::new()
             .name(format!("{thread_name} driver"))
             .spawn(move || {
-                let runtime = tokio::runtime::Builder::new_multi_thread()
+                let mut builder = tokio::runtime::Builder::new_multi_thread();
+                thread_stats.instrument(&mut builder);
+                let runtime_stats = thread_stats.clone();
+                let runtime = builder
                     .enable_all()
                     .thread_name_fn(move || {
                         format!(
@@ -190,9 +203,13 @@ impl DedicatedExecutor {
                         )
                     })
                     .worker_threads(num_threads)
-                    .on_thread_start(move || set_current_thread_priority(WORKER_PRIORITY))
+                    .on_thread_start(move || {
+                        set_current_thread_priority(WORKER_PRIORITY);
+                        thread_stats.thread_started();
+                    })
                     .build()
                     .expect("Creating tokio runtime");
+                runtime_stats.set_runtime(runtime.handle().clone());
 
                 runtime.block_on(async move {
                     // Dropping the tokio runtime only waits for tasks to yield not to complete
@@ -229,6 +246,7 @@ impl DedicatedExecutor {
         Self {
             state: Arc::new(Mutex::new(state)),
             num_threads,
+            stats,
         }
     }
 
@@ -274,11 +292,15 @@ impl DedicatedExecutor {
             fut,
             cancel: cancel.clone(),
             task_ref: Arc::clone(&state.task_refs),
+            stats: self.stats.clone(),
         };
 
         if let Some(requests) = &mut state.requests {
+            self.stats.job_queued();
             // would fail if someone has started shutdown
-            requests.send(task).ok();
+            if requests.send(task).is_err() {
+                self.stats.job_cancelled();
+            }
         } else {
             warn!("tried to schedule task on an executor that was shutdown");
         }
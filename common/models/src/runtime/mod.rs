@@ -1,2 +1,3 @@
 pub mod cross_rt_stream;
 pub mod executor;
+pub mod stats;
@@ -0,0 +1,270 @@
+//! Statistics of the runtime of this node: the thread pools, the memory
+//! allocator and the gRPC connections, shown by `SHOW RUNTIME`, the
+//! `cluster_schema.runtime` table and the `/debug/runtime` endpoint.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+
+use parking_lot::{const_mutex, Mutex};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+
+static THREAD_POOLS: Mutex<Vec<Weak<ThreadPoolStats>>> = const_mutex(Vec::new());
+
+static GRPC_CONNECTIONS: AtomicI64 = AtomicI64::new(0);
+
+/// A statistic of the runtime, e.g. `("thread_pool", "query-dedicated-scheduler",
+/// "queued_jobs", 3)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeStat {
+    pub category: String,
+    pub name: String,
+    pub metric: String,
+    pub value: i64,
+}
+
+impl RuntimeStat {
+    fn new(category: &str, name: &str, metric: &str, value: i64) -> Self {
+        Self {
+            category: category.to_string(),
+            name: name.to_string(),
+            metric: metric.to_string(),
+            value,
+        }
+    }
+}
+
+/// Counters of a tokio runtime or a [`DedicatedExecutor`], registered when
+/// the pool is built and unregistered when the last reference is dropped.
+///
+/// [`DedicatedExecutor`]: crate::runtime::executor::DedicatedExecutor
+#[derive(Debug)]
+pub struct ThreadPoolStats {
+    name: String,
+    workers: usize,
+    /// Threads started, including the blocking threads of the pool.
+    threads: AtomicI64,
+    /// Threads not parked, running tasks or blocking calls.
+    busy_threads: AtomicI64,
+    /// Jobs spawned but not started yet, only counted by dedicated executors.
+    queued_jobs: AtomicI64,
+    /// Jobs started but not finished, only counted by dedicated executors.
+    running_jobs: AtomicI64,
+    /// Jobs finished or cancelled, only counted by dedicated executors.
+    finished_jobs: AtomicU64,
+    /// The runtime of the pool, its queues of the tasks are reported when
+    /// built with `--cfg tokio_unstable`.
+    runtime: OnceLock<Handle>,
+}
+
+impl ThreadPoolStats {
+    pub fn register(name: impl Into<String>, workers: usize) -> Arc<Self> {
+        let stats = Arc::new(Self {
+            name: name.into(),
+            workers,
+            threads: AtomicI64::new(0),
+            busy_threads: AtomicI64::new(0),
+            queued_jobs: AtomicI64::new(0),
+            running_jobs: AtomicI64::new(0),
+            finished_jobs: AtomicU64::new(0),
+            runtime: OnceLock::new(),
+        });
+        let mut pools = THREAD_POOLS.lock();
+        pools.retain(|p| p.strong_count() > 0);
+        pools.push(Arc::downgrade(&stats));
+        stats
+    }
+
+    /// Count the threads of the runtime built by the builder, the hook of
+    /// thread start is kept for the caller to set, which calls
+    /// [`Self::thread_started`].
+    pub fn instrument(self: &Arc<Self>, builder: &mut tokio::runtime::Builder) {
+        let stats = self.clone();
+        builder.on_thread_stop(move || stats.thread_stopped());
+        let stats = self.clone();
+        builder.on_thread_park(move || {
+            stats.busy_threads.fetch_sub(1, Ordering::Relaxed);
+        });
+        let stats = self.clone();
+        builder.on_thread_unpark(move || {
+            stats.busy_threads.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Set the runtime built with [`Self::instrument`].
+    pub fn set_runtime(&self, handle: Handle) {
+        let _ = self.runtime.set(handle);
+    }
+
+    pub fn thread_started(&self) {
+        self.threads.fetch_add(1, Ordering::Relaxed);
+        self.busy_threads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn thread_stopped(&self) {
+        self.threads.fetch_sub(1, Ordering::Relaxed);
+        self.busy_threads.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn job_queued(&self) {
+        self.queued_jobs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn job_started(&self) {
+        self.queued_jobs.fetch_sub(1, Ordering::Relaxed);
+        self.running_jobs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn job_finished(&self) {
+        self.running_jobs.fetch_sub(1, Ordering::Relaxed);
+        self.finished_jobs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The queued job is dropped before started.
+    pub fn job_cancelled(&self) {
+        self.queued_jobs.fetch_sub(1, Ordering::Relaxed);
+        self.finished_jobs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn append_stats(&self, stats: &mut Vec<RuntimeStat>) {
+        let mut append = |metric: &str, value: i64| {
+            stats.push(RuntimeStat::new("thread_pool", &self.name, metric, value))
+        };
+        append("workers", self.workers as i64);
+        append("threads", self.threads.load(Ordering::Relaxed));
+        append("busy_threads", self.busy_threads.load(Ordering::Relaxed));
+        append("queued_jobs", self.queued_jobs.load(Ordering::Relaxed));
+        append("running_jobs", self.running_jobs.load(Ordering::Relaxed));
+        append(
+            "finished_jobs",
+            self.finished_jobs.load(Ordering::Relaxed) as i64,
+        );
+
+        // The depths of the queues of the runtime are unstable metrics of tokio.
+        #[cfg(tokio_unstable)]
+        if let Some(handle) = self.runtime.get() {
+            let metrics = handle.metrics();
+            let local_depth = (0..metrics.num_workers())
+                .map(|worker| metrics.worker_local_queue_depth(worker))
+                .sum::<usize>();
+            append("global_queue_depth", metrics.injection_queue_depth() as i64);
+            append("local_queue_depth", local_depth as i64);
+        }
+    }
+}
+
+/// Counts an open gRPC connection until dropped.
+#[derive(Debug)]
+pub struct GrpcConnectionGuard(());
+
+impl GrpcConnectionGuard {
+    pub fn new() -> Self {
+        GRPC_CONNECTIONS.fetch_add(1, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Default for GrpcConnectionGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for GrpcConnectionGuard {
+    fn drop(&mut self) {
+        GRPC_CONNECTIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of all the statistics of the runtime of this node.
+pub fn runtime_stats() -> Vec<RuntimeStat> {
+    let mut stats = vec![];
+
+    let pools = THREAD_POOLS
+        .lock()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect::<Vec<_>>();
+    for pool in pools {
+        pool.append_stats(&mut stats);
+    }
+
+    #[cfg(unix)]
+    match utils::pprof_tools::jemalloc_stats() {
+        Ok(values) => {
+            for (metric, value) in values {
+                stats.push(RuntimeStat::new(
+                    "allocator",
+                    "jemalloc",
+                    metric,
+                    value as i64,
+                ));
+            }
+        }
+        Err(e) => trace::warn!("Failed to read the stats of jemalloc: {}", e),
+    }
+
+    stats.push(RuntimeStat::new(
+        "network",
+        "grpc",
+        "open_connections",
+        GRPC_CONNECTIONS.load(Ordering::Relaxed),
+    ));
+
+    stats
+}
+
+#[cfg(test)]
+mod test {
+    use super::{runtime_stats, GrpcConnectionGuard, RuntimeStat, ThreadPoolStats};
+
+    fn find(stats: &[RuntimeStat], name: &str, metric: &str) -> Option<i64> {
+        stats
+            .iter()
+            .find(|s| s.name == name && s.metric == metric)
+            .map(|s| s.value)
+    }
+
+    #[test]
+    fn test_runtime_stats() {
+        let pool = ThreadPoolStats::register("test_runtime_stats", 2);
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        pool.instrument(&mut builder);
+        let thread_pool = pool.clone();
+        let runtime = builder
+            .worker_threads(2)
+            .on_thread_start(move || thread_pool.thread_started())
+            .build()
+            .unwrap();
+        pool.set_runtime(runtime.handle().clone());
+        pool.job_queued();
+        pool.job_queued();
+        pool.job_started();
+        runtime.block_on(async {});
+
+        let _guard = GrpcConnectionGuard::new();
+        let stats = runtime_stats();
+        assert_eq!(find(&stats, "test_runtime_stats", "workers"), Some(2));
+        assert_eq!(find(&stats, "test_runtime_stats", "queued_jobs"), Some(1));
+        assert_eq!(find(&stats, "test_runtime_stats", "running_jobs"), Some(1));
+        assert!(find(&stats, "grpc", "open_connections").unwrap() >= 1);
+        #[cfg(tokio_unstable)]
+        assert_eq!(
+            find(&stats, "test_runtime_stats", "global_queue_depth"),
+            Some(0)
+        );
+
+        drop(runtime);
+
+        let pool = ThreadPoolStats::register("test_runtime_stats_dropped", 1);
+        assert_eq!(
+            find(&runtime_stats(), "test_runtime_stats_dropped", "workers"),
+            Some(1)
+        );
+        drop(pool);
+        assert_eq!(
+            find(&runtime_stats(), "test_runtime_stats_dropped", "workers"),
+            None
+        );
+    }
+}
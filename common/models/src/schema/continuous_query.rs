@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::meta_data::NodeId;
+use crate::schema::query_info::QueryId;
+
+/// Definition of a continuous query, which aggregates the recent data of a
+/// database into a target table on a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContinuousQueryInfo {
+    pub name: String,
+    pub tenant: String,
+    pub database: String,
+    /// The query running the continuous query, it is persisted with the query.
+    pub query_id: QueryId,
+    pub node_id: NodeId,
+    pub target_table: String,
+    /// The `SELECT` statement, its output is inserted into the target table.
+    pub query: String,
+    /// Run the query every interval.
+    pub every: Duration,
+    /// Length of the time range aggregated by each run.
+    pub for_range: Duration,
+}
+
+impl ContinuousQueryInfo {
+    /// The time range `[start, end)` in nanoseconds aggregated by the run at `now`,
+    /// it ends at the last multiple of `every`, so that only closed windows are written.
+    pub fn time_range(&self, now: i64) -> (i64, i64) {
+        let every = self.every.as_nanos().clamp(1, i64::MAX as u128) as i64;
+        let for_range = self.for_range.as_nanos().min(i64::MAX as u128) as i64;
+        let end = now - now.rem_euclid(every);
+
+        (end.saturating_sub(for_range), end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::ContinuousQueryInfo;
+    use crate::schema::query_info::QueryId;
+
+    #[test]
+    fn test_time_range() {
+        let cq = ContinuousQueryInfo {
+            name: "cq".to_string(),
+            tenant: "cnosdb".to_string(),
+            database: "public".to_string(),
+            query_id: QueryId::next_id(),
+            node_id: 1,
+            target_table: "cpu_1m".to_string(),
+            query: "SELECT 1".to_string(),
+            every: Duration::from_nanos(60),
+            for_range: Duration::from_nanos(120),
+        };
+        assert_eq!(cq.time_range(150), (0, 120));
+        assert_eq!(cq.time_range(180), (60, 180));
+        assert_eq!(cq.time_range(-30), (-180, -60));
+    }
+}
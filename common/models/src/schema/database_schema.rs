@@ -1,8 +1,11 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use config::tskv::{Config, StorageConfig, WalConfig};
-use serde::{Deserialize, Serialize};
+use humantime::{format_duration, parse_duration};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use utils::duration::{CnosDuration, YEAR_SECOND};
 use utils::precision::Precision;
 
@@ -111,6 +114,11 @@ pub struct DatabaseOptionsBuilder {
     shard_num: Option<u64>,
     vnode_duration: Option<CnosDuration>,
     replica: Option<u64>,
+    series_placement: Option<SeriesPlacement>,
+    compaction_strategy: Option<CompactionStrategyKind>,
+    compaction_window: Option<CnosDuration>,
+    compaction_file_sizes: Option<Vec<u64>>,
+    time_zone: Option<String>,
 }
 
 impl Default for DatabaseOptionsBuilder {
@@ -126,6 +134,11 @@ impl DatabaseOptionsBuilder {
             shard_num: None,
             vnode_duration: None,
             replica: None,
+            series_placement: None,
+            compaction_strategy: None,
+            compaction_window: None,
+            compaction_file_sizes: None,
+            time_zone: None,
         }
     }
 
@@ -149,6 +162,31 @@ impl DatabaseOptionsBuilder {
         self
     }
 
+    pub fn with_series_placement(&mut self, series_placement: SeriesPlacement) -> &mut Self {
+        self.series_placement = Some(series_placement);
+        self
+    }
+
+    pub fn with_compaction_strategy(&mut self, strategy: CompactionStrategyKind) -> &mut Self {
+        self.compaction_strategy = Some(strategy);
+        self
+    }
+
+    pub fn with_compaction_window(&mut self, window: CnosDuration) -> &mut Self {
+        self.compaction_window = Some(window);
+        self
+    }
+
+    pub fn with_compaction_file_sizes(&mut self, file_sizes: Vec<u64>) -> &mut Self {
+        self.compaction_file_sizes = Some(file_sizes);
+        self
+    }
+
+    pub fn with_time_zone(&mut self, time_zone: String) -> &mut Self {
+        self.time_zone = Some(time_zone);
+        self
+    }
+
     pub fn build(self) -> DatabaseOptions {
         let ttl = self.ttl.unwrap_or(DatabaseOptions::DEFAULT_TTL);
         let shard_num = self.shard_num.unwrap_or(DatabaseOptions::DEFAULT_SHARD_NUM);
@@ -156,7 +194,9 @@ impl DatabaseOptionsBuilder {
             .vnode_duration
             .unwrap_or(DatabaseOptions::DEFAULT_VNODE_DURATION);
         let replica = self.replica.unwrap_or(DatabaseOptions::DEFAULT_REPLICA);
-        DatabaseOptions::new(ttl, shard_num, vnode_duration, replica)
+        let mut options = DatabaseOptions::new(ttl, shard_num, vnode_duration, replica);
+        options.apply_builder(&self);
+        options
     }
 }
 
@@ -166,6 +206,17 @@ pub struct DatabaseOptions {
     shard_num: u64,
     vnode_duration: CnosDuration,
     replica: u64,
+    /// How series are placed into the replication sets of a bucket, only
+    /// applied to the buckets created after it is set.
+    #[serde(default)]
+    series_placement: SeriesPlacement,
+    /// How the files of the vnodes are picked to compact.
+    #[serde(default)]
+    compaction: CompactionOptions,
+    /// The time zone of the sessions querying the database, if the session
+    /// does not set one.
+    #[serde(default)]
+    time_zone: Option<String>,
 }
 
 impl DatabaseOptions {
@@ -185,6 +236,9 @@ impl DatabaseOptions {
             shard_num,
             vnode_duration,
             replica,
+            series_placement: SeriesPlacement::default(),
+            compaction: CompactionOptions::default(),
+            time_zone: None,
         }
     }
 
@@ -220,6 +274,30 @@ impl DatabaseOptions {
         self.replica = replica;
     }
 
+    pub fn series_placement(&self) -> SeriesPlacement {
+        self.series_placement
+    }
+
+    pub fn set_series_placement(&mut self, series_placement: SeriesPlacement) {
+        self.series_placement = series_placement;
+    }
+
+    pub fn compaction(&self) -> &CompactionOptions {
+        &self.compaction
+    }
+
+    pub fn set_compaction(&mut self, compaction: CompactionOptions) {
+        self.compaction = compaction;
+    }
+
+    pub fn time_zone(&self) -> Option<&str> {
+        self.time_zone.as_deref()
+    }
+
+    pub fn set_time_zone(&mut self, time_zone: Option<String>) {
+        self.time_zone = time_zone;
+    }
+
     pub fn apply_builder(&mut self, builder: &DatabaseOptionsBuilder) {
         if let Some(ref ttl) = builder.ttl {
             self.ttl = ttl.clone();
@@ -233,6 +311,21 @@ impl DatabaseOptions {
         if let Some(replica) = builder.replica {
             self.replica = replica;
         }
+        if let Some(series_placement) = builder.series_placement {
+            self.series_placement = series_placement;
+        }
+        if let Some(strategy) = builder.compaction_strategy {
+            self.compaction.strategy = strategy;
+        }
+        if let Some(ref window) = builder.compaction_window {
+            self.compaction.time_window = Some(window.clone());
+        }
+        if let Some(ref file_sizes) = builder.compaction_file_sizes {
+            self.compaction.level_file_sizes = file_sizes.clone();
+        }
+        if let Some(ref time_zone) = builder.time_zone {
+            self.time_zone = Some(time_zone.clone());
+        }
     }
 }
 
@@ -243,6 +336,9 @@ impl Default for DatabaseOptions {
             shard_num: DatabaseOptions::DEFAULT_SHARD_NUM,
             vnode_duration: DatabaseOptions::DEFAULT_VNODE_DURATION,
             replica: DatabaseOptions::DEFAULT_REPLICA,
+            series_placement: SeriesPlacement::default(),
+            compaction: CompactionOptions::default(),
+            time_zone: None,
         }
     }
 }
@@ -253,7 +349,7 @@ pub struct DatabaseConfigBuilder {
     max_memcache_size: Option<u64>,
     memcache_partitions: Option<u64>,
     wal_max_file_size: Option<u64>,
-    wal_sync: Option<bool>,
+    wal_sync: Option<WalSyncPolicy>,
     strict_write: Option<bool>,
     max_cache_readers: Option<u64>,
     replica: Option<u64>,
@@ -299,7 +395,7 @@ impl DatabaseConfigBuilder {
         self
     }
 
-    pub fn with_wal_sync(&mut self, wal_sync: bool) -> &mut Self {
+    pub fn with_wal_sync(&mut self, wal_sync: WalSyncPolicy) -> &mut Self {
         self.wal_sync = Some(wal_sync);
         self
     }
@@ -328,7 +424,9 @@ impl DatabaseConfigBuilder {
             .memcache_partitions
             .unwrap_or(config.cache.partition as u64);
         let wal_max_file_size = self.wal_max_file_size.unwrap_or(config.wal.max_file_size);
-        let wal_sync = self.wal_sync.unwrap_or(config.wal.sync);
+        let wal_sync = self
+            .wal_sync
+            .unwrap_or_else(|| WalSyncPolicy::from(config.wal.sync));
         let strict_write = self.strict_write.unwrap_or(config.storage.strict_write);
         let max_cache_readers = self
             .max_cache_readers
@@ -351,7 +449,7 @@ pub struct DatabaseConfig {
     max_memcache_size: u64,
     memcache_partitions: u64,
     wal_max_file_size: u64,
-    wal_sync: bool,
+    wal_sync: WalSyncPolicy,
     strict_write: bool,
     max_cache_readers: u64,
 }
@@ -366,7 +464,7 @@ impl DatabaseConfig {
         max_memcache_size: u64,
         memcache_partitions: u64,
         wal_max_file_size: u64,
-        wal_sync: bool,
+        wal_sync: WalSyncPolicy,
         strict_write: bool,
         max_cache_readers: u64,
     ) -> Self {
@@ -397,7 +495,7 @@ impl DatabaseConfig {
         self.wal_max_file_size
     }
 
-    pub fn wal_sync(&self) -> bool {
+    pub fn wal_sync(&self) -> WalSyncPolicy {
         self.wal_sync
     }
 
@@ -421,9 +519,297 @@ impl Default for DatabaseConfig {
             max_memcache_size: DatabaseConfig::DEFAULT_MAX_MEMCACHE_SIZE,
             memcache_partitions: DatabaseConfig::DEFAULT_MEMCACHE_PARTITIONS,
             wal_max_file_size: WalConfig::default_max_file_size(),
-            wal_sync: WalConfig::default_sync(),
+            wal_sync: WalSyncPolicy::from(WalConfig::default_sync()),
             strict_write: StorageConfig::default_strict_write(),
             max_cache_readers: StorageConfig::default_max_cached_readers() as u64,
         }
     }
 }
+
+/// When the WAL of the vnodes of a database is fsynced, which trades durability for throughput.
+///
+/// `every_write` and `os` are the `true` and `false` of the legacy boolean
+/// `wal_sync`, they are shown and stored as the booleans, so the DDL and the
+/// metadata stay readable by the older versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalSyncPolicy {
+    /// Fsync after every write, an acknowledged write survives a crash of the node.
+    EveryWrite,
+    /// Fsync at most once per interval, writes since the last fsync may be lost on crash.
+    Interval(Duration),
+    /// Leave flushing to the OS, writes not yet flushed by the OS may be lost on crash.
+    Os,
+}
+
+impl WalSyncPolicy {
+    const INVALID_MSG: &'static str = "use 'every_write' to fsync every write (no acknowledged write is lost on crash), \
+        'interval(<duration>)' e.g. 'interval(10ms)' to fsync at most once per interval (writes since the last fsync may be lost on crash), \
+        or 'os' to leave flushing to the OS (writes not yet flushed by the OS may be lost on crash)";
+}
+
+impl From<bool> for WalSyncPolicy {
+    /// The legacy `wal_sync` option is a boolean.
+    fn from(sync: bool) -> Self {
+        if sync {
+            Self::EveryWrite
+        } else {
+            Self::Os
+        }
+    }
+}
+
+impl Display for WalSyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EveryWrite => write!(f, "true"),
+            Self::Interval(interval) => write!(f, "interval({})", format_duration(*interval)),
+            Self::Os => write!(f, "false"),
+        }
+    }
+}
+
+impl FromStr for WalSyncPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_ascii_lowercase();
+        let policy = match value.as_str() {
+            "every_write" | "true" => Some(Self::EveryWrite),
+            "os" | "false" => Some(Self::Os),
+            _ => value
+                .strip_prefix("interval(")
+                .and_then(|v| v.strip_suffix(')'))
+                .and_then(|v| parse_duration(v.trim()).ok())
+                .filter(|interval| !interval.is_zero())
+                .map(Self::Interval),
+        };
+
+        policy.ok_or_else(|| format!("{s} is not a valid wal_sync value, {}", Self::INVALID_MSG))
+    }
+}
+
+impl Serialize for WalSyncPolicy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::EveryWrite => serializer.serialize_bool(true),
+            Self::Os => serializer.serialize_bool(false),
+            Self::Interval(_) => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WalSyncPolicy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Legacy(bool),
+            Policy(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Legacy(sync) => Ok(Self::from(sync)),
+            Repr::Policy(policy) => policy.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// How the series of a database are placed into the replication sets of a bucket.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesPlacement {
+    /// Hash id of the series modulo the number of replication sets.
+    #[default]
+    Modulo,
+    /// Jump consistent hash of the hash id of the series, which spreads the
+    /// series evenly even if the low bits of the hash ids are not.
+    JumpHash,
+}
+
+impl SeriesPlacement {
+    /// Index of the replication set of the series in a bucket of `shard_num`
+    /// replication sets.
+    pub fn shard_index(&self, hash_id: u64, shard_num: usize) -> usize {
+        match self {
+            Self::Modulo => hash_id as usize % shard_num,
+            Self::JumpHash => utils::jump_consistent_hash(hash_id, shard_num as u32) as usize,
+        }
+    }
+}
+
+impl Display for SeriesPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Modulo => write!(f, "modulo"),
+            Self::JumpHash => write!(f, "jump_hash"),
+        }
+    }
+}
+
+impl FromStr for SeriesPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "modulo" => Ok(Self::Modulo),
+            "jump_hash" => Ok(Self::JumpHash),
+            _ => Err(format!(
+                "{s} is not a valid series_placement value, use 'modulo' or 'jump_hash'"
+            )),
+        }
+    }
+}
+
+/// How the files of the vnodes of a database are picked to compact, level-0
+/// files are always compacted into level-1 first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompactionOptions {
+    pub strategy: CompactionStrategyKind,
+    /// Width of the time windows of the `time_window` strategy.
+    pub time_window: Option<CnosDuration>,
+    /// Target file sizes of level-1 to level-4, the sizes of the levels not
+    /// given are derived from the base file size.
+    pub level_file_sizes: Vec<u64>,
+}
+
+impl CompactionOptions {
+    pub const DEFAULT_TIME_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+    pub fn time_window(&self) -> Duration {
+        self.time_window
+            .as_ref()
+            .and_then(|w| w.to_duration())
+            .filter(|w| !w.is_zero())
+            .unwrap_or(Self::DEFAULT_TIME_WINDOW)
+    }
+
+    /// Target file size of the level(1 to 4), the base file size is multiplied
+    /// by 4 for each level if not given.
+    pub fn level_file_size(&self, level: u32, base_file_size: u64) -> u64 {
+        let level = level.clamp(1, 4);
+        self.level_file_sizes
+            .get(level as usize - 1)
+            .copied()
+            .unwrap_or_else(|| base_file_size << (2 * (level - 1)))
+    }
+}
+
+/// Strategy of picking the files of a level to compact.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionStrategyKind {
+    /// Picks the level with the most files, and merges its oldest files into
+    /// the next level.
+    #[default]
+    Level,
+    /// Merges the files of a level in the same time window, for the data
+    /// written in time order and rarely updated.
+    TimeWindow,
+    /// Merges the files of a level of similar sizes, for the data updated or
+    /// written out of order.
+    SizeTiered,
+}
+
+impl Display for CompactionStrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Level => write!(f, "level"),
+            Self::TimeWindow => write!(f, "time_window"),
+            Self::SizeTiered => write!(f, "size_tiered"),
+        }
+    }
+}
+
+impl FromStr for CompactionStrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "level" => Ok(Self::Level),
+            "time_window" => Ok(Self::TimeWindow),
+            "size_tiered" => Ok(Self::SizeTiered),
+            _ => Err(format!(
+                "{s} is not a valid compaction value, use 'level', 'time_window' or 'size_tiered'"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{CompactionOptions, CompactionStrategyKind, SeriesPlacement, WalSyncPolicy};
+
+    #[test]
+    fn test_wal_sync_policy() {
+        for (text, policy, json) in [
+            ("true", WalSyncPolicy::EveryWrite, "true"),
+            ("false", WalSyncPolicy::Os, "false"),
+            (
+                "interval(10ms)",
+                WalSyncPolicy::Interval(Duration::from_millis(10)),
+                r#""interval(10ms)""#,
+            ),
+        ] {
+            assert_eq!(text.parse::<WalSyncPolicy>().unwrap(), policy);
+            assert_eq!(policy.to_string(), text);
+            assert_eq!(serde_json::to_string(&policy).unwrap(), json);
+            assert_eq!(serde_json::from_str::<WalSyncPolicy>(json).unwrap(), policy);
+        }
+
+        assert_eq!(
+            "Every_Write".parse::<WalSyncPolicy>().unwrap(),
+            WalSyncPolicy::EveryWrite
+        );
+        assert_eq!("os".parse::<WalSyncPolicy>().unwrap(), WalSyncPolicy::Os);
+        assert_eq!(
+            serde_json::from_str::<WalSyncPolicy>(r#""os""#).unwrap(),
+            WalSyncPolicy::Os
+        );
+        assert!("interval(0ms)".parse::<WalSyncPolicy>().is_err());
+        assert!("interval".parse::<WalSyncPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_series_placement() {
+        for placement in [SeriesPlacement::Modulo, SeriesPlacement::JumpHash] {
+            assert_eq!(
+                placement.to_string().parse::<SeriesPlacement>().unwrap(),
+                placement
+            );
+        }
+        assert!("hash".parse::<SeriesPlacement>().is_err());
+
+        assert_eq!(SeriesPlacement::Modulo.shard_index(10, 4), 2);
+        for hash_id in 0..100 {
+            assert!(SeriesPlacement::JumpHash.shard_index(hash_id, 3) < 3);
+        }
+    }
+
+    #[test]
+    fn test_compaction_options() {
+        for strategy in [
+            CompactionStrategyKind::Level,
+            CompactionStrategyKind::TimeWindow,
+            CompactionStrategyKind::SizeTiered,
+        ] {
+            assert_eq!(
+                strategy
+                    .to_string()
+                    .parse::<CompactionStrategyKind>()
+                    .unwrap(),
+                strategy
+            );
+        }
+        assert!("tiered".parse::<CompactionStrategyKind>().is_err());
+
+        let mut options = CompactionOptions::default();
+        assert_eq!(options.time_window(), Duration::from_secs(24 * 60 * 60));
+        assert_eq!(options.level_file_size(1, 16), 16);
+        assert_eq!(options.level_file_size(3, 16), 256);
+        options.level_file_sizes = vec![10, 20];
+        assert_eq!(options.level_file_size(2, 16), 20);
+        assert_eq!(options.level_file_size(4, 16), 1024);
+    }
+}
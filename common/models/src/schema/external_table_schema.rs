@@ -50,8 +50,9 @@ impl ExternalTableSchema {
             }
         };
 
-        let options =
-            ListingOptions::new(file_format).with_target_partitions(self.target_partitions);
+        let options = ListingOptions::new(file_format)
+            .with_target_partitions(self.target_partitions)
+            .with_table_partition_cols(self.table_partition_cols.clone());
 
         Ok(options)
     }
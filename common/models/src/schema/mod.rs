@@ -7,10 +7,12 @@
 //!         - Column #3
 //!         - Column #4
 
+pub mod continuous_query;
 pub mod database_schema;
 pub mod external_table_schema;
 pub mod query_info;
 pub mod resource_info;
+pub mod scheduled_delete;
 pub mod stream_table_schema;
 pub mod table_schema;
 pub mod tenant;
@@ -32,3 +34,6 @@ pub const DATABASE_NAME: &str = "database_name";
 pub const TABLE_NAME: &str = "table_name";
 pub const NEXT_COLUMN_ID: &str = "next_column_id";
 pub const COLUMN_ENCODING_META_KEY: &str = "column_encoding";
+pub const COLUMN_DEFAULT_META_KEY: &str = "column_default";
+pub const COLUMN_ENCRYPTION_META_KEY: &str = "column_encryption_key";
+pub const COLUMN_INDEXED_META_KEY: &str = "column_indexed";
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
@@ -67,6 +68,18 @@ pub struct QueryInfo {
     database_name: String,
     user: User,
     pub node_id: NodeId,
+    /// Set for the stream queries, recovered with the query after a restart.
+    #[serde(default)]
+    stream: Option<StreamQueryState>,
+}
+
+/// The state of a stream query kept with the persisted query.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamQueryState {
+    /// The name of `CREATE STREAM`, none for `INSERT ... SELECT` from stream tables.
+    pub name: Option<String>,
+    /// The last processed offset of each stream source.
+    pub offsets: HashMap<String, i64>,
 }
 
 impl QueryInfo {
@@ -87,6 +100,14 @@ impl QueryInfo {
             database_name,
             user,
             node_id,
+            stream: None,
+        }
+    }
+
+    pub fn with_stream(self, stream: StreamQueryState) -> Self {
+        Self {
+            stream: Some(stream),
+            ..self
         }
     }
 
@@ -121,4 +142,8 @@ impl QueryInfo {
     pub fn user_name(&self) -> &str {
         self.user.desc().name()
     }
+
+    pub fn stream(&self) -> Option<&StreamQueryState> {
+        self.stream.as_ref()
+    }
 }
@@ -5,6 +5,8 @@ use utils::duration::CnosDuration;
 
 use crate::meta_data::{NodeId, ReplicationSet};
 use crate::oid::Oid;
+use crate::predicate::domain::TimeRange;
+use crate::schema::scheduled_delete::ScheduledDeleteJob;
 use crate::schema::tskv_table_schema::{TableColumn, TskvTableSchema};
 use crate::utils::now_timestamp_nanos;
 
@@ -36,6 +38,16 @@ pub enum ResourceOperator {
         Vec<Vec<u8>>,
         Vec<ReplicationSet>,
     ),
+
+    // table_schema, tag_name, old_tag_value, new_tag_value
+    MergeTagValue(TskvTableSchema, String, String, String),
+
+    // job, executed on the schedule until it's dropped
+    ScheduledDelete(ScheduledDeleteJob),
+
+    // tenant_name, db_name, table_name, time_range in the precision of the database,
+    // deletes the rows written by a failed or cancelled write
+    DeleteWrittenRows(String, String, String, TimeRange),
 }
 
 impl fmt::Display for ResourceOperator {
@@ -48,6 +60,9 @@ impl fmt::Display for ResourceOperator {
             ResourceOperator::AddColumn(..) => write!(f, "AddColumn"),
             ResourceOperator::AlterColumn(..) => write!(f, "AlterColumn"),
             ResourceOperator::UpdateTagValue(..) => write!(f, "UpdateTagValue"),
+            ResourceOperator::MergeTagValue(..) => write!(f, "MergeTagValue"),
+            ResourceOperator::ScheduledDelete(..) => write!(f, "ScheduledDelete"),
+            ResourceOperator::DeleteWrittenRows(..) => write!(f, "DeleteWrittenRows"),
         }
     }
 }
@@ -136,6 +151,10 @@ impl ResourceInfo {
         &self.operator
     }
 
+    pub fn get_operator_mut(&mut self) -> &mut ResourceOperator {
+        &mut self.operator
+    }
+
     pub fn get_try_count(&self) -> u64 {
         self.try_count
     }
@@ -160,6 +179,10 @@ impl ResourceInfo {
         self.try_count += 1;
     }
 
+    pub fn set_time(&mut self, time: i64) {
+        self.time = time;
+    }
+
     pub fn set_status(&mut self, status: ResourceStatus) {
         self.status = status;
     }
@@ -0,0 +1,290 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::predicate::domain::{ColumnDomains, TimeRange};
+use crate::predicate::utils::filter_to_time_ranges;
+
+/// The count of the latest runs kept in the history of a job.
+pub const MAX_JOB_HISTORY: usize = 10;
+
+/// A job deleting the rows of a table matching the predicate on the cron
+/// schedule, it's executed by the resource manager of the coordinator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledDeleteJob {
+    pub name: String,
+    pub tenant: String,
+    pub database: String,
+    pub table: String,
+    /// Cron expression of the schedule in UTC, see [`CronSchedule`].
+    pub cron: String,
+    /// Only delete the rows older than the duration at each run.
+    pub older_than: Option<Duration>,
+    /// The `WHERE` clause of the `DELETE` statement, only for display.
+    pub selection: Option<String>,
+    pub tags: ColumnDomains<String>,
+    pub time: ColumnDomains<String>,
+    pub history: VecDeque<ScheduledDeleteRun>,
+}
+
+/// The result of a run of a scheduled delete job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledDeleteRun {
+    pub time: i64,
+    pub success: bool,
+    pub comment: String,
+}
+
+impl ScheduledDeleteJob {
+    /// Name of the resource info of the job.
+    pub fn resource_name(tenant: &str, database: &str, name: &str) -> String {
+        format!("scheduled_delete-{}-{}-{}", tenant, database, name)
+    }
+
+    /// The time in nanoseconds of the first run after `now`.
+    pub fn next_run_time(&self, now: i64) -> Option<i64> {
+        CronSchedule::parse(&self.cron).ok()?.next_after(now)
+    }
+
+    /// The time ranges in nanoseconds to delete by the run at `now`.
+    pub fn time_ranges(&self, now: i64) -> Vec<TimeRange> {
+        let ranges = filter_to_time_ranges(&self.time);
+        let older_than = match self.older_than {
+            Some(older_than) => older_than.as_nanos().min(i64::MAX as u128) as i64,
+            None => return ranges,
+        };
+        let before = TimeRange::new(i64::MIN, now.saturating_sub(older_than).saturating_sub(1));
+        ranges
+            .iter()
+            .filter_map(|range| range.intersect(&before))
+            .collect()
+    }
+
+    pub fn record_run(&mut self, run: ScheduledDeleteRun) {
+        if self.history.len() >= MAX_JOB_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(run);
+    }
+}
+
+/// Schedule of the standard cron expression with 5 fields: minute, hour, day of
+/// month, month and day of week (0 or 7 is Sunday). Each field is `*`, a value,
+/// a range `a-b`, a step `*/n` or `a-b/n`, or a list of them separated by `,`.
+///
+/// As the standard cron, a day matches either the day of month or the day of
+/// week if both of them are restricted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+/// The schedules are searched in the days within the years, so that the
+/// expressions never matching, e.g. `0 0 31 2 *`, end.
+const MAX_SEARCH_DAYS: i64 = 366 * 8;
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 5 {
+            return Err(format!(
+                "cron expression '{}' should have 5 fields: minute, hour, day of month, month and day of week",
+                expr
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // Both 0 and 7 are Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: fields[2] != "*",
+            day_of_week_restricted: fields[4] != "*",
+        })
+    }
+
+    /// The first time in nanoseconds matching the schedule after `ts`.
+    pub fn next_after(&self, ts: i64) -> Option<i64> {
+        let time = NaiveDateTime::from_timestamp_opt(
+            ts.div_euclid(1_000_000_000),
+            ts.rem_euclid(1_000_000_000) as u32,
+        )?;
+        // The next whole minute.
+        let start = time.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+
+        for day in 0..MAX_SEARCH_DAYS {
+            let date = start.date() + ChronoDuration::days(day);
+            if !self.matches_date(
+                date.month(),
+                date.day(),
+                date.weekday().num_days_from_sunday(),
+            ) {
+                continue;
+            }
+            let (from_hour, from_minute) = if day == 0 {
+                (start.hour(), start.minute())
+            } else {
+                (0, 0)
+            };
+            for hour in from_hour..24 {
+                if !contains(self.hours, hour) {
+                    continue;
+                }
+                let from_minute = if hour == from_hour { from_minute } else { 0 };
+                if let Some(minute) = (from_minute..60).find(|m| contains(self.minutes, *m)) {
+                    return date.and_hms_opt(hour, minute, 0)?.timestamp_nanos_opt();
+                }
+            }
+        }
+
+        None
+    }
+
+    fn matches_date(&self, month: u32, day: u32, weekday: u32) -> bool {
+        if !contains(self.months, month) {
+            return false;
+        }
+        let day_of_month = contains(self.days_of_month, day);
+        let day_of_week = contains(self.days_of_week, weekday);
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0_u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step =
+                    step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(|| {
+                        format!("invalid step '{}' of cron field '{}'", step, field)
+                    })?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, field)?,
+                parse_value(end, min, max, field)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, field)?;
+            // `a/n` means from a to the max.
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!(
+                "invalid range '{}' of cron field '{}'",
+                range, field
+            ));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, field: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| {
+            format!(
+                "invalid value '{}' of cron field '{}', expected {}-{}",
+                value, field, min, max
+            )
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use super::CronSchedule;
+
+    fn ts(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+            .timestamp_nanos_opt()
+            .unwrap()
+    }
+
+    fn next(expr: &str, from: i64) -> Option<NaiveDateTime> {
+        let next = CronSchedule::parse(expr).unwrap().next_after(from)?;
+        NaiveDateTime::from_timestamp_opt(next / 1_000_000_000, 0)
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let from = ts(2023, 5, 17, 10, 30) + 1;
+        let cases = [
+            ("* * * * *", ts(2023, 5, 17, 10, 31)),
+            ("*/15 * * * *", ts(2023, 5, 17, 10, 45)),
+            ("0 3 * * *", ts(2023, 5, 18, 3, 0)),
+            ("0 0 1 * *", ts(2023, 6, 1, 0, 0)),
+            ("30 10 17 5 *", ts(2024, 5, 17, 10, 30)),
+            // 2023-05-21 is Sunday.
+            ("0 0 * * 0", ts(2023, 5, 21, 0, 0)),
+            ("0 0 * * 7", ts(2023, 5, 21, 0, 0)),
+            ("0 9-17/4 * * 1-5", ts(2023, 5, 17, 13, 0)),
+            ("5,10 0 * * *", ts(2023, 5, 18, 0, 5)),
+            // Either the day of month or the day of week.
+            ("0 0 1 * 6", ts(2023, 5, 20, 0, 0)),
+            ("0 0 29 2 *", ts(2024, 2, 29, 0, 0)),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(
+                next(expr, from),
+                NaiveDateTime::from_timestamp_opt(expected / 1_000_000_000, 0),
+                "{expr}"
+            );
+        }
+
+        assert_eq!(next("0 0 31 2 *", from), None);
+    }
+
+    #[test]
+    fn test_cron_parse_error() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "10-5 * * * *",
+            "a * * * *",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "{expr}");
+        }
+    }
+}
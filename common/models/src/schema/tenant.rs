@@ -1,10 +1,13 @@
+use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::time::Duration;
 
 use config::common::{RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use utils::duration::CnosDuration;
 
+use crate::encryption::EncryptionKey;
 use crate::oid::{Identifier, Oid};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -45,6 +48,25 @@ pub struct TenantOptions {
     pub limiter_config: Option<TenantLimiterConfig>,
     pub drop_after: Option<CnosDuration>,
     pub tenant_is_hidden: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_timeout: Option<CnosDuration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_timeout: Option<CnosDuration>,
+    /// Key store of column encryption, key name in lowercase -> key in hex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_keys: Option<BTreeMap<String, EncryptionKey>>,
+    /// Quotas of the objects of the tenant, enforced by the meta service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_databases: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tables: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_users: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tables_per_database: Option<usize>,
+    /// Checked by the leaders before the writes are proposed, counted per bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_series_per_table: Option<usize>,
 }
 
 impl From<TenantOptions> for TenantOptionsBuilder {
@@ -59,6 +81,30 @@ impl From<TenantOptions> for TenantOptionsBuilder {
         if let Some(drop_after) = value.get_drop_after() {
             builder.drop_after(drop_after);
         }
+        if let Some(query_timeout) = value.query_timeout.clone() {
+            builder.query_timeout(query_timeout);
+        }
+        if let Some(write_timeout) = value.write_timeout.clone() {
+            builder.write_timeout(write_timeout);
+        }
+        if let Some(encryption_keys) = value.encryption_keys.clone() {
+            builder.encryption_keys(encryption_keys);
+        }
+        if let Some(max_databases) = value.max_databases {
+            builder.max_databases(max_databases);
+        }
+        if let Some(max_tables) = value.max_tables {
+            builder.max_tables(max_tables);
+        }
+        if let Some(max_users) = value.max_users {
+            builder.max_users(max_users);
+        }
+        if let Some(max_tables_per_database) = value.max_tables_per_database {
+            builder.max_tables_per_database(max_tables_per_database);
+        }
+        if let Some(max_series_per_table) = value.max_series_per_table {
+            builder.max_series_per_table(max_series_per_table);
+        }
         builder.tenant_is_hidden(false);
         builder
     }
@@ -74,6 +120,39 @@ impl TenantOptionsBuilder {
     pub fn unset_drop_after(&mut self) {
         self.drop_after = None;
     }
+    pub fn unset_query_timeout(&mut self) {
+        self.query_timeout = None;
+    }
+    pub fn unset_write_timeout(&mut self) {
+        self.write_timeout = None;
+    }
+    pub fn unset_max_databases(&mut self) {
+        self.max_databases = None;
+    }
+    pub fn unset_max_tables(&mut self) {
+        self.max_tables = None;
+    }
+    pub fn unset_max_users(&mut self) {
+        self.max_users = None;
+    }
+    pub fn unset_max_tables_per_database(&mut self) {
+        self.max_tables_per_database = None;
+    }
+    pub fn unset_max_series_per_table(&mut self) {
+        self.max_series_per_table = None;
+    }
+    /// Key names are case-insensitive.
+    pub fn set_encryption_key(&mut self, name: &str, key: EncryptionKey) {
+        self.encryption_keys
+            .get_or_insert(None)
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.to_ascii_lowercase(), key);
+    }
+    pub fn unset_encryption_key(&mut self, name: &str) {
+        if let Some(Some(keys)) = self.encryption_keys.as_mut() {
+            keys.remove(&name.to_ascii_lowercase());
+        }
+    }
 }
 
 impl TenantOptions {
@@ -102,6 +181,83 @@ impl TenantOptions {
     pub fn get_drop_after(&self) -> Option<CnosDuration> {
         self.drop_after.clone()
     }
+
+    /// Timeout of the query statements of the tenant, None if not set or infinite.
+    pub fn query_timeout(&self) -> Option<Duration> {
+        self.query_timeout.as_ref().and_then(|d| d.to_duration())
+    }
+
+    /// Timeout of the write statements of the tenant, None if not set or infinite.
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.write_timeout.as_ref().and_then(|d| d.to_duration())
+    }
+
+    /// Get the key in hex of column encryption by name, case-insensitive.
+    pub fn encryption_key(&self, name: &str) -> Option<&str> {
+        self.encryption_keys
+            .as_ref()
+            .and_then(|keys| keys.get(&name.to_ascii_lowercase()))
+            .map(|key| key.as_str())
+    }
+
+    /// Quotas of the tenant, (name, max), None if unlimited.
+    pub fn quotas(&self) -> [(TenantQuota, Option<usize>); 5] {
+        [
+            (TenantQuota::Databases, self.max_databases),
+            (TenantQuota::Tables, self.max_tables),
+            (TenantQuota::Users, self.max_users),
+            (TenantQuota::TablesPerDatabase, self.max_tables_per_database),
+            (TenantQuota::SeriesPerTable, self.max_series_per_table),
+        ]
+    }
+
+    pub fn quota(&self, quota: TenantQuota) -> Option<usize> {
+        match quota {
+            TenantQuota::Databases => self.max_databases,
+            TenantQuota::Tables => self.max_tables,
+            TenantQuota::Users => self.max_users,
+            TenantQuota::TablesPerDatabase => self.max_tables_per_database,
+            TenantQuota::SeriesPerTable => self.max_series_per_table,
+        }
+    }
+
+    pub fn hidden_encryption_keys(&mut self) {
+        if let Some(keys) = self.encryption_keys.as_mut() {
+            keys.values_mut()
+                .for_each(|key| *key = EncryptionKey::new("*****"));
+        }
+    }
+}
+
+/// Objects of a tenant limited by the quotas in [`TenantOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TenantQuota {
+    Databases,
+    Tables,
+    Users,
+    /// Tables of each database.
+    TablesPerDatabase,
+    /// Series of each table in a bucket.
+    SeriesPerTable,
+}
+
+impl TenantQuota {
+    /// Name of the tenant option of the quota.
+    pub fn option_name(&self) -> &'static str {
+        match self {
+            Self::Databases => "max_databases",
+            Self::Tables => "max_tables",
+            Self::Users => "max_users",
+            Self::TablesPerDatabase => "max_tables_per_database",
+            Self::SeriesPerTable => "max_series_per_table",
+        }
+    }
+}
+
+impl Display for TenantQuota {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.option_name())
+    }
 }
 
 impl Display for TenantOptions {
@@ -116,6 +272,34 @@ impl Display for TenantOptions {
             write!(f, "limiter=None,")?;
         }
 
+        if let Some(ref e) = self.query_timeout {
+            write!(f, "query_timeout={e},")?;
+        }
+
+        if let Some(ref e) = self.write_timeout {
+            write!(f, "write_timeout={e},")?;
+        }
+
+        if let Some(e) = self.max_databases {
+            write!(f, "max_databases={e},")?;
+        }
+
+        if let Some(e) = self.max_tables {
+            write!(f, "max_tables={e},")?;
+        }
+
+        if let Some(e) = self.max_users {
+            write!(f, "max_users={e},")?;
+        }
+
+        if let Some(e) = self.max_tables_per_database {
+            write!(f, "max_tables_per_database={e},")?;
+        }
+
+        if let Some(e) = self.max_series_per_table {
+            write!(f, "max_series_per_table={e},")?;
+        }
+
         Ok(())
     }
 }
@@ -13,17 +13,21 @@ use datafusion::arrow::datatypes::{
 use datafusion::common::{DFField, DFSchema, DFSchemaRef};
 use datafusion::error::DataFusionError;
 use datafusion::prelude::Column;
+use minivec::MiniVec;
 use serde::de::{MapAccess, SeqAccess, Visitor};
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize, Serializer};
 use snafu::ResultExt;
-use utils::precision::Precision;
+use utils::duration::CnosDuration;
+use utils::precision::{timestamp_convert, Precision};
 
 use crate::codec::Encoding;
 use crate::errors::{InternalSnafu, InvalidSerdeMessageSnafu};
+use crate::field_value::FieldVal;
 use crate::gis::data_type::{Geometry, GeometryType};
 use crate::schema::{
-    COLUMN_ENCODING_META_KEY, COLUMN_ID_META_KEY, DATABASE_NAME, DEFAULT_CATALOG, DEFAULT_DATABASE,
+    COLUMN_DEFAULT_META_KEY, COLUMN_ENCODING_META_KEY, COLUMN_ENCRYPTION_META_KEY,
+    COLUMN_ID_META_KEY, COLUMN_INDEXED_META_KEY, DATABASE_NAME, DEFAULT_CATALOG, DEFAULT_DATABASE,
     GIS_SRID_META_KEY, GIS_SUB_TYPE_META_KEY, IS_TAG, NEXT_COLUMN_ID, SCHEMA_VERSION, TABLE_NAME,
     TENANT, TIME_FIELD_NAME,
 };
@@ -39,6 +43,12 @@ pub struct TskvTableSchema {
     pub name: String,
     pub schema_version: SchemaVersion,
     next_column_id: ColumnId,
+    /// Series of the table never receive duplicate timestamps, so flush and
+    /// compaction concatenate sorted runs instead of merging them.
+    pub append_only: bool,
+    /// Data of the table older than the TTL is deleted, overrides the TTL of
+    /// the database if it is shorter.
+    pub ttl: Option<CnosDuration>,
 
     columns: Vec<TableColumn>,
     //ColumnName -> ColumnsIndex
@@ -51,7 +61,7 @@ impl Serialize for TskvTableSchema {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("TskvTableSchema", 6)?;
+        let mut state = serializer.serialize_struct("TskvTableSchema", 9)?;
         state.serialize_field("tenant", &self.tenant)?;
         state.serialize_field("db", &self.db)?;
         state.serialize_field("name", &self.name)?;
@@ -59,6 +69,8 @@ impl Serialize for TskvTableSchema {
         state.serialize_field("next_column_id", &self.next_column_id)?;
         state.serialize_field("columns", &self.columns)?;
         state.serialize_field("columns_index", &self.columns_index)?;
+        state.serialize_field("append_only", &self.append_only)?;
+        state.serialize_field("ttl", &self.ttl)?;
         state.end()
     }
 }
@@ -101,6 +113,8 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                 let columns_index = seq
                     .next_element::<HashMap<String, usize>>()?
                     .ok_or_else(|| serde::de::Error::invalid_length(6, &self))?;
+                let append_only = seq.next_element::<bool>()?.unwrap_or_default();
+                let ttl = seq.next_element::<Option<CnosDuration>>()?.flatten();
                 let fields_ids = TskvTableSchema::build_fields_ids(&columns);
                 Ok(TskvTableSchema {
                     tenant,
@@ -108,6 +122,8 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                     name,
                     schema_version,
                     next_column_id,
+                    append_only,
+                    ttl,
                     columns,
                     columns_index,
                     fields_ids,
@@ -125,6 +141,8 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                 let mut next_column_id = None;
                 let mut columns = None;
                 let mut columns_index = None;
+                let mut append_only = None;
+                let mut ttl = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         "tenant" => {
@@ -169,6 +187,18 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                             }
                             columns_index = Some(map.next_value::<HashMap<String, usize>>()?);
                         }
+                        "append_only" => {
+                            if append_only.is_some() {
+                                return Err(serde::de::Error::duplicate_field("append_only"));
+                            }
+                            append_only = Some(map.next_value::<bool>()?);
+                        }
+                        "ttl" => {
+                            if ttl.is_some() {
+                                return Err(serde::de::Error::duplicate_field("ttl"));
+                            }
+                            ttl = Some(map.next_value::<Option<CnosDuration>>()?);
+                        }
                         _ => {
                             return Err(serde::de::Error::unknown_field(
                                 key,
@@ -180,6 +210,8 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                                     "next_column_id",
                                     "columns",
                                     "columns_index",
+                                    "append_only",
+                                    "ttl",
                                 ],
                             ))?;
                         }
@@ -193,6 +225,8 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                 let next_column_id = next_column_id
                     .ok_or_else(|| serde::de::Error::missing_field("next_column_id"))?;
                 let columns = columns.ok_or_else(|| serde::de::Error::missing_field("columns"))?;
+                let append_only = append_only.unwrap_or_default();
+                let ttl = ttl.flatten();
                 let columns_index = columns
                     .iter()
                     .enumerate()
@@ -205,6 +239,8 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                     name,
                     schema_version,
                     next_column_id,
+                    append_only,
+                    ttl,
                     columns,
                     columns_index,
                     fields_ids,
@@ -221,12 +257,60 @@ impl<'de> Deserialize<'de> for TskvTableSchema {
                 "next_column_id",
                 "columns",
                 "columns_index",
+                "append_only",
+                "ttl",
             ],
             TskvTableSchemaVisitor,
         )
     }
 }
 
+/// The layout of the table schemas encoded by bincode before the table options
+/// (`append_only`, `ttl`) and the column options (`default_value`,
+/// `encryption_key`, `indexed`) were added, bincode can't tell the missing
+/// trailing fields from the data following the schema.
+#[derive(Debug, Deserialize)]
+pub struct TskvTableSchemaV0 {
+    tenant: String,
+    db: String,
+    name: String,
+    schema_version: SchemaVersion,
+    next_column_id: ColumnId,
+    columns: Vec<TableColumnV0>,
+    columns_index: HashMap<String, usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TableColumnV0 {
+    id: ColumnId,
+    name: String,
+    column_type: ColumnType,
+    encoding: Encoding,
+}
+
+impl From<TskvTableSchemaV0> for TskvTableSchema {
+    fn from(schema: TskvTableSchemaV0) -> Self {
+        let columns = schema
+            .columns
+            .into_iter()
+            .map(|c| TableColumn::new(c.id, c.name, c.column_type, c.encoding))
+            .collect::<Vec<_>>();
+        let fields_ids = Self::build_fields_ids(&columns);
+        Self {
+            tenant: schema.tenant,
+            db: schema.db,
+            name: schema.name,
+            schema_version: schema.schema_version,
+            next_column_id: schema.next_column_id,
+            append_only: false,
+            ttl: None,
+            columns,
+            columns_index: schema.columns_index,
+            fields_ids,
+        }
+    }
+}
+
 impl PartialOrd for TskvTableSchema {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.schema_version.cmp(&other.schema_version))
@@ -251,6 +335,8 @@ impl Default for TskvTableSchema {
             name: "template".to_string(),
             schema_version: 0,
             next_column_id: 0,
+            append_only: false,
+            ttl: None,
             columns: Default::default(),
             columns_index: Default::default(),
             fields_ids: Default::default(),
@@ -325,6 +411,8 @@ impl TskvTableSchema {
             name,
             schema_version: 0,
             next_column_id: columns.len() as ColumnId,
+            append_only: false,
+            ttl: None,
             columns,
             columns_index,
             fields_ids,
@@ -406,6 +494,17 @@ impl TskvTableSchema {
         self.columns.iter().map(|c| (c.id, c)).collect()
     }
 
+    /// The min timestamp of the time column kept by the TTL of the table at
+    /// `now` (nanoseconds), None if the table has no TTL.
+    pub fn time_to_expired(&self, now: i64) -> Option<i64> {
+        let ttl = self.ttl.as_ref()?.to_duration()?;
+        let precision = self.time_column_precision();
+        let now = timestamp_convert(Precision::NS, precision, now).unwrap_or(now);
+        let ttl = CnosDuration::new_with_duration(ttl).to_precision(precision);
+
+        Some(now.saturating_sub(ttl))
+    }
+
     pub fn time_column_precision(&self) -> Precision {
         self.columns
             .iter()
@@ -534,6 +633,16 @@ pub struct TableColumn {
     pub name: String,
     pub column_type: ColumnType,
     pub encoding: Encoding,
+    /// Value used for a field missing from a written line, stored as its literal text.
+    #[serde(default)]
+    pub default_value: Option<String>,
+    /// Name of the tenant key the string values of the field are encrypted with.
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+    /// Values of the string field are indexed in the tsm files to prune series
+    /// by equality predicates.
+    #[serde(default)]
+    pub indexed: bool,
 }
 
 impl TryFrom<FieldRef> for TableColumn {
@@ -598,7 +707,11 @@ impl TryFrom<FieldRef> for TableColumn {
             Ok(TableColumn::new_tag_column(column_id, name))
         } else {
             let column_type = value.data_type().clone().into();
-            Ok(TableColumn::new(column_id, name, column_type, encoding))
+            let mut column = TableColumn::new(column_id, name, column_type, encoding);
+            column.default_value = value.metadata().get(COLUMN_DEFAULT_META_KEY).cloned();
+            column.encryption_key = value.metadata().get(COLUMN_ENCRYPTION_META_KEY).cloned();
+            column.indexed = value.metadata().contains_key(COLUMN_INDEXED_META_KEY);
+            Ok(column)
         }
     }
 }
@@ -624,6 +737,21 @@ impl From<&TableColumn> for ArrowField {
             map.insert(IS_TAG.to_string(), "".to_string());
         }
 
+        if let Some(default_value) = &column.default_value {
+            map.insert(COLUMN_DEFAULT_META_KEY.to_string(), default_value.clone());
+        }
+
+        if let Some(encryption_key) = &column.encryption_key {
+            map.insert(
+                COLUMN_ENCRYPTION_META_KEY.to_string(),
+                encryption_key.clone(),
+            );
+        }
+
+        if column.indexed {
+            map.insert(COLUMN_INDEXED_META_KEY.to_string(), "".to_string());
+        }
+
         let nullable = column.nullable();
         let mut f = ArrowField::new(&column.name, column.column_type.clone().into(), nullable);
         f.set_metadata(map);
@@ -650,6 +778,9 @@ impl TableColumn {
             name,
             column_type,
             encoding,
+            default_value: None,
+            encryption_key: None,
+            indexed: false,
         }
     }
     pub fn new_with_default(name: String, column_type: ColumnType) -> Self {
@@ -658,6 +789,9 @@ impl TableColumn {
             name,
             column_type,
             encoding: Encoding::Default,
+            default_value: None,
+            encryption_key: None,
+            indexed: false,
         }
     }
 
@@ -667,6 +801,9 @@ impl TableColumn {
             name: TIME_FIELD_NAME.to_string(),
             column_type: ColumnType::Time(time_unit),
             encoding: Encoding::Default,
+            default_value: None,
+            encryption_key: None,
+            indexed: false,
         }
     }
 
@@ -676,6 +813,9 @@ impl TableColumn {
             name,
             column_type: ColumnType::Tag,
             encoding: Encoding::Default,
+            default_value: None,
+            encryption_key: None,
+            indexed: false,
         }
     }
 
@@ -739,6 +879,36 @@ impl TableColumn {
     pub fn encoding(&self) -> Encoding {
         self.encoding
     }
+
+    /// Parse the default value of a field column according to its physical type.
+    pub fn default_field_value(&self) -> ModelResult<Option<FieldVal>> {
+        let value = match &self.default_value {
+            Some(v) if self.column_type.is_field() => v,
+            _ => return Ok(None),
+        };
+        let parse_err = |e: &dyn Display| {
+            InternalSnafu {
+                err: format!(
+                    "Invalid default value '{}' for column {} of type {}: {}",
+                    value, self.name, self.column_type, e
+                ),
+            }
+            .build()
+        };
+        let field_val = match self.column_type.to_physical_data_type() {
+            PhysicalDType::Float => FieldVal::Float(value.parse().map_err(|e| parse_err(&e))?),
+            PhysicalDType::Integer => FieldVal::Integer(value.parse().map_err(|e| parse_err(&e))?),
+            PhysicalDType::Unsigned => {
+                FieldVal::Unsigned(value.parse().map_err(|e| parse_err(&e))?)
+            }
+            PhysicalDType::Boolean => {
+                FieldVal::Boolean(value.to_lowercase().parse().map_err(|e| parse_err(&e))?)
+            }
+            PhysicalDType::String => FieldVal::Bytes(MiniVec::from(value.as_bytes())),
+            PhysicalDType::Unknown => return Err(parse_err(&"unknown type")),
+        };
+        Ok(Some(field_val))
+    }
 }
 
 impl From<ColumnType> for ArrowDataType {
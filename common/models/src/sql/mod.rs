@@ -10,7 +10,7 @@ use crate::codec::Encoding;
 use crate::datafusion::SqlParserValue;
 use crate::errors::DumpSnafu;
 use crate::oid::{Identifier, Oid};
-use crate::schema::database_schema::DatabaseSchema;
+use crate::schema::database_schema::{CompactionStrategyKind, DatabaseSchema, SeriesPlacement};
 use crate::schema::external_table_schema::ExternalTableSchema;
 use crate::schema::stream_table_schema::StreamTable;
 use crate::schema::table_schema::TableSchema;
@@ -174,6 +174,30 @@ impl ToDDLSql for DatabaseSchema {
         res.push_str(format!("shard {} ", self.options.shard_num()).as_str());
         res.push_str(format!("replica {} ", self.options.replica()).as_str());
         res.push_str(format!("vnode_duration '{}' ", self.options.vnode_duration()).as_str());
+        if self.options.series_placement() != SeriesPlacement::default() {
+            res.push_str(
+                format!("series_placement '{}' ", self.options.series_placement()).as_str(),
+            );
+        }
+        if let Some(time_zone) = self.options.time_zone() {
+            res.push_str(format!("time_zone '{}' ", time_zone).as_str());
+        }
+        let compaction = self.options.compaction();
+        if compaction.strategy != CompactionStrategyKind::default() {
+            res.push_str(format!("compaction '{}' ", compaction.strategy).as_str());
+        }
+        if let Some(window) = &compaction.time_window {
+            res.push_str(format!("compaction_window '{}' ", window).as_str());
+        }
+        if !compaction.level_file_sizes.is_empty() {
+            let file_sizes = compaction
+                .level_file_sizes
+                .iter()
+                .map(|size| CnosByteNumber::format_bytes(*size))
+                .collect::<Vec<_>>()
+                .join(",");
+            res.push_str(format!("compaction_file_sizes '{}' ", file_sizes).as_str());
+        }
 
         if res.trim().ends_with("with") {
             res = res.trim().trim_end_matches("with").trim().to_string();
@@ -217,8 +241,8 @@ pub fn role_to_sql(role: &CustomTenantRole<Oid>) -> Result<Vec<String>> {
 
 // GRANT privilege
 pub fn privilege_to_sql(role: &CustomTenantRole<Oid>) -> Vec<String> {
-    let privileges = role.additional_privileges();
-    privileges
+    let privileges = role.database_privileges();
+    let mut sqls = privileges
         .iter()
         .map(|(d, p)| {
             format!(
@@ -228,7 +252,31 @@ pub fn privilege_to_sql(role: &CustomTenantRole<Oid>) -> Vec<String> {
                 role.name()
             )
         })
-        .collect()
+        .collect::<Vec<_>>();
+    for (d, tables) in role.table_privileges() {
+        for (t, p) in tables {
+            let columns = match &p.columns {
+                Some(columns) => format!(
+                    " ({})",
+                    columns
+                        .iter()
+                        .map(|c| format!("\"{c}\""))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => String::new(),
+            };
+            sqls.push(format!(
+                "grant {}{} on table \"{}\".\"{}\" to \"{}\";",
+                p.privilege.as_str(),
+                columns,
+                d,
+                t,
+                role.name()
+            ));
+        }
+    }
+    sqls
 }
 
 // Add member
@@ -504,7 +552,9 @@ mod test {
     use utils::precision::Precision;
 
     use crate::auth::user::{UserDesc, UserOptionsBuilder};
-    use crate::schema::database_schema::{DatabaseConfig, DatabaseOptions, DatabaseSchema};
+    use crate::schema::database_schema::{
+        DatabaseConfig, DatabaseOptions, DatabaseSchema, WalSyncPolicy,
+    };
     use crate::schema::external_table_schema::ExternalTableSchema;
     use crate::schema::stream_table_schema::{StreamTable, Watermark};
     use crate::schema::tenant::{Tenant, TenantOptionsBuilder};
@@ -607,7 +657,7 @@ remote_bucket = {max = 100, initial = 0, refill = 100, interval = 100}
             512 * 1024 * 1024 * 1024,
             12,
             1024,
-            false,
+            WalSyncPolicy::Os,
             true,
             32,
         );
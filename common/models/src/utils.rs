@@ -100,12 +100,12 @@ impl SeqIdGenerator {
 }
 
 pub fn build_address(address: &str, port: u16) -> String {
-    format!("{address}:{port}")
+    utils::net::build_address(address, port)
 }
 
 pub fn build_address_with_optional_addr(address: &str, port: Option<u16>) -> String {
     match port {
-        Some(p) => format!("{address}:{p}"),
-        None => format!("{address}:null"),
+        Some(p) => utils::net::build_address(address, p),
+        None => utils::net::build_address(address, "null"),
     }
 }
@@ -19,6 +19,7 @@ pub mod json_protocol;
 pub mod line_protocol;
 pub mod lines_convert;
 pub mod open_tsdb;
+pub mod otlp_metrics;
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
@@ -124,6 +125,15 @@ impl<'a> Line<'_> {
         self.fields.dedup_by(|a, b| a.0 == b.0);
         self.init_ordered_hash_id();
     }
+
+    /// Append the default values of the fields that are absent from this line.
+    pub fn fill_default_fields(&mut self, defaults: &[(String, FieldValue)]) {
+        for (name, value) in defaults {
+            if !self.fields.iter().any(|(k, _)| k == name) {
+                self.fields.push((Cow::Owned(name.clone()), value.clone()));
+            }
+        }
+    }
 }
 
 fn check_pos_valid(buf: &str, pos: usize) -> Result<()> {
@@ -290,8 +300,24 @@ fn next_value(buf: &str) -> Option<(&str, usize)> {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{
+        Array, DictionaryArray, Float64Array, LargeStringArray, StringArray,
+        TimestampNanosecondArray,
+    };
+    use datafusion::arrow::datatypes::{DataType, Field, Int32Type, Schema, TimeUnit};
+    use datafusion::arrow::ipc::reader::StreamReader;
+    use datafusion::arrow::ipc::writer::StreamWriter;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+
     use crate::line_protocol::parser::Parser;
-    use crate::lines_convert::{line_to_batches, mutable_batches_to_point};
+    use crate::lines_convert::{
+        arrow_array_to_points, line_to_batches, mutable_batches_to_point, unpack_record_batch,
+    };
 
     #[test]
     #[ignore]
@@ -312,4 +338,126 @@ mod test {
         let flatbuf = mutable_batches_to_point("test_db_12345678", batch);
         println!("to flatbuf: {} elapsed: {:?}", flatbuf.len(), t.elapsed());
     }
+
+    /// Batches sent by Flight `DoPut` are IPC encoded, dictionaries and timezones survive.
+    #[test]
+    fn test_unpack_flight_record_batch() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("+08:00".into())),
+                false,
+            ),
+            Field::new(
+                "host",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Field::new("msg", DataType::LargeUtf8, true),
+            Field::new("usage", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![1, 2, 3]).with_timezone("+08:00")),
+                Arc::new(
+                    vec![Some("a"), None, Some("a")]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ),
+                Arc::new(LargeStringArray::from(vec![Some("x"), Some("y"), None])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0])),
+            ],
+        )
+        .unwrap();
+
+        let mut buf = vec![];
+        {
+            let mut writer = StreamWriter::try_new(&mut buf, &schema).unwrap();
+            writer.write(&batch).unwrap();
+            writer.finish().unwrap();
+        }
+        let reader = StreamReader::try_new(buf.as_slice(), None).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert!(matches!(
+            batches[0].column(1).data_type(),
+            DataType::Dictionary(_, _)
+        ));
+
+        let batch = unpack_record_batch(batches[0].clone()).unwrap();
+        let types = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Float64,
+            ]
+        );
+        let time = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(time.values().to_vec(), vec![1, 2, 3]);
+        let host = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(
+            host.iter().collect::<Vec<_>>(),
+            vec![Some("a"), None, Some("a")]
+        );
+        let msg = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(
+            msg.iter().collect::<Vec<_>>(),
+            vec![Some("x"), Some("y"), None]
+        );
+
+        let table_schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "test".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new(1, "host".to_string(), ColumnType::Tag, Encoding::Default),
+                TableColumn::new(
+                    2,
+                    "msg".to_string(),
+                    ColumnType::Field(ValueType::String),
+                    Encoding::Default,
+                ),
+                TableColumn::new(
+                    3,
+                    "usage".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+            ],
+        ));
+        let points = arrow_array_to_points(
+            batch.columns().to_vec(),
+            batch.schema(),
+            table_schema,
+            batch.num_rows(),
+        )
+        .unwrap();
+        assert!(!points.is_empty());
+
+        // Already physical batches are returned as is.
+        let unpacked = unpack_record_batch(batch.clone()).unwrap();
+        assert_eq!(unpacked, batch);
+    }
 }
@@ -1,14 +1,20 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use datafusion::arrow::array::{
-    Array, ArrayRef, BooleanArray, BooleanBufferBuilder, Float64Array, Int64Array, StringArray,
-    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray, UInt64Array,
+    make_array, Array, ArrayRef, BooleanArray, BooleanBufferBuilder, Float64Array, Int64Array,
+    StringArray, TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    UInt64Array,
 };
-use datafusion::arrow::datatypes::{SchemaRef, TimeUnit};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::RecordBatch;
 use flatbuffers::{FlatBufferBuilder, WIPOffset};
 use models::column_data_ref::PrimaryColumnDataRef;
+use models::field_value::FieldVal;
 use models::mutable_batch::MutableBatch;
-use models::schema::tskv_table_schema::{PhysicalCType, TskvTableSchemaRef};
+use models::schema::tskv_table_schema::{PhysicalCType, TskvTableSchema, TskvTableSchemaRef};
 use models::PhysicalDType as ValueType;
 use protos::models::{
     Column as FbColumn, ColumnBuilder, ColumnType as FbColumnType, FieldType, PointsBuilder,
@@ -17,6 +23,26 @@ use protos::models::{
 
 use crate::{Error, FieldValue, Line, Result};
 
+/// Collect the default values of the field columns of the table which have one.
+pub fn table_default_fields(schema: &TskvTableSchema) -> Result<Vec<(String, FieldValue)>> {
+    let mut defaults = vec![];
+    for column in schema.columns() {
+        let field_val = column.default_field_value().map_err(|e| Error::Common {
+            content: e.to_string(),
+        })?;
+        let value = match field_val {
+            Some(FieldVal::Float(v)) => FieldValue::F64(v),
+            Some(FieldVal::Integer(v)) => FieldValue::I64(v),
+            Some(FieldVal::Unsigned(v)) => FieldValue::U64(v),
+            Some(FieldVal::Boolean(v)) => FieldValue::Bool(v),
+            Some(FieldVal::Bytes(v)) => FieldValue::Str(v.to_vec()),
+            None => continue,
+        };
+        defaults.push((column.name.clone(), value));
+    }
+    Ok(defaults)
+}
+
 pub fn line_to_batches<'a>(lines: &'a [Line<'a>]) -> Result<HashMap<String, MutableBatch<'a>>> {
     let mut batches = HashMap::new();
     for line in lines.iter() {
@@ -279,6 +305,63 @@ pub fn mutable_batches_to_point(db: &str, batches: HashMap<String, MutableBatch>
     data
 }
 
+/// Unpack the logical arrow types that clients (e.g. Arrow Flight) may send, such as
+/// dictionary-encoded, large string and timezone-aware timestamp columns, into the
+/// physical types expected by [`arrow_array_to_points`].
+pub fn unpack_record_batch(record_batch: RecordBatch) -> Result<RecordBatch> {
+    let schema = record_batch.schema();
+    let mut unpacked = false;
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for (field, column) in schema.fields().iter().zip(record_batch.columns()) {
+        let column = match unpack_array(column).map_err(|e| Error::Common {
+            content: format!("unpack column {} error: {}", field.name(), e),
+        })? {
+            Some(array) => {
+                unpacked = true;
+                array
+            }
+            None => column.clone(),
+        };
+        fields.push(
+            Field::new(
+                field.name(),
+                column.data_type().clone(),
+                field.is_nullable(),
+            )
+            .with_metadata(field.metadata().clone()),
+        );
+        columns.push(column);
+    }
+    if !unpacked {
+        return Ok(record_batch);
+    }
+
+    let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+    RecordBatch::try_new(schema, columns).map_err(|e| Error::Common {
+        content: e.to_string(),
+    })
+}
+
+fn unpack_array(array: &ArrayRef) -> std::result::Result<Option<ArrayRef>, ArrowError> {
+    let array = match array.data_type() {
+        DataType::Dictionary(_, value_type) => cast(array, value_type)?,
+        DataType::LargeUtf8 => cast(array, &DataType::Utf8)?,
+        DataType::Timestamp(unit, Some(_)) => {
+            // The values of a timestamp with timezone are already relative to UTC.
+            let data = array
+                .to_data()
+                .into_builder()
+                .data_type(DataType::Timestamp(unit.clone(), None))
+                .build()?;
+            make_array(data)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(unpack_array(&array)?.unwrap_or(array)))
+}
+
 pub fn arrow_array_to_points(
     columns: Vec<ArrayRef>,
     schema: SchemaRef,
@@ -0,0 +1,274 @@
+use std::borrow::Cow;
+
+use protos::common::any_value::Value;
+use protos::common::KeyValue;
+use protos::metrics::metric::Data;
+use protos::metrics::number_data_point;
+use protos::metrics_service::ExportMetricsServiceRequest;
+use protos::FieldValue;
+
+use crate::Line;
+
+pub const VALUE_FIELD: &str = "value";
+pub const COUNT_FIELD: &str = "count";
+pub const SUM_FIELD: &str = "sum";
+pub const MIN_FIELD: &str = "min";
+pub const MAX_FIELD: &str = "max";
+
+type Tags = Vec<(Cow<'static, str>, Cow<'static, str>)>;
+
+/// Flatten an OTLP metrics export request into lines, one line per data point.
+///
+/// The metric name is used as the table, resource, scope and data point attributes
+/// are flattened into tags, a data point attribute overrides the resource or scope
+/// attribute of the same key. Data points without a timestamp use `default_time`.
+pub fn otlp_metrics_to_lines(
+    req: ExportMetricsServiceRequest,
+    default_time: i64,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    for resource_metrics in req.resource_metrics {
+        let mut resource_tags = Tags::new();
+        if let Some(resource) = resource_metrics.resource {
+            append_attributes(&mut resource_tags, resource.attributes);
+        }
+
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut scope_tags = resource_tags.clone();
+            if let Some(scope) = scope_metrics.scope {
+                append_attributes(&mut scope_tags, scope.attributes);
+            }
+
+            for metric in scope_metrics.metrics {
+                let table = Cow::Owned(metric.name);
+                match metric.data {
+                    Some(Data::Gauge(gauge)) => {
+                        for point in gauge.data_points {
+                            if let Some(value) = number_value(point.value) {
+                                lines.push(new_line(
+                                    table.clone(),
+                                    &scope_tags,
+                                    point.attributes,
+                                    vec![(Cow::Borrowed(VALUE_FIELD), value)],
+                                    timestamp(point.time_unix_nano, default_time),
+                                ));
+                            }
+                        }
+                    }
+                    Some(Data::Sum(sum)) => {
+                        for point in sum.data_points {
+                            if let Some(value) = number_value(point.value) {
+                                lines.push(new_line(
+                                    table.clone(),
+                                    &scope_tags,
+                                    point.attributes,
+                                    vec![(Cow::Borrowed(VALUE_FIELD), value)],
+                                    timestamp(point.time_unix_nano, default_time),
+                                ));
+                            }
+                        }
+                    }
+                    Some(Data::Histogram(histogram)) => {
+                        for point in histogram.data_points {
+                            let mut fields =
+                                vec![(Cow::Borrowed(COUNT_FIELD), FieldValue::U64(point.count))];
+                            if let Some(sum) = point.sum {
+                                fields.push((Cow::Borrowed(SUM_FIELD), FieldValue::F64(sum)));
+                            }
+                            if let Some(min) = point.min {
+                                fields.push((Cow::Borrowed(MIN_FIELD), FieldValue::F64(min)));
+                            }
+                            if let Some(max) = point.max {
+                                fields.push((Cow::Borrowed(MAX_FIELD), FieldValue::F64(max)));
+                            }
+                            for (i, count) in point.bucket_counts.iter().enumerate() {
+                                let name = match point.explicit_bounds.get(i) {
+                                    Some(bound) => format!("le_{}", bound),
+                                    None => "le_inf".to_string(),
+                                };
+                                fields.push((Cow::Owned(name), FieldValue::U64(*count)));
+                            }
+                            lines.push(new_line(
+                                table.clone(),
+                                &scope_tags,
+                                point.attributes,
+                                fields,
+                                timestamp(point.time_unix_nano, default_time),
+                            ));
+                        }
+                    }
+                    Some(Data::Summary(summary)) => {
+                        for point in summary.data_points {
+                            let mut fields = vec![
+                                (Cow::Borrowed(COUNT_FIELD), FieldValue::U64(point.count)),
+                                (Cow::Borrowed(SUM_FIELD), FieldValue::F64(point.sum)),
+                            ];
+                            for quantile in point.quantile_values.iter() {
+                                fields.push((
+                                    Cow::Owned(format!("quantile_{}", quantile.quantile)),
+                                    FieldValue::F64(quantile.value),
+                                ));
+                            }
+                            lines.push(new_line(
+                                table.clone(),
+                                &scope_tags,
+                                point.attributes,
+                                fields,
+                                timestamp(point.time_unix_nano, default_time),
+                            ));
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+fn new_line(
+    table: Cow<'static, str>,
+    base_tags: &Tags,
+    attributes: Vec<KeyValue>,
+    fields: Vec<(Cow<'static, str>, FieldValue)>,
+    timestamp: i64,
+) -> Line<'static> {
+    let mut tags = base_tags.clone();
+    append_attributes(&mut tags, attributes);
+    Line::new(table, tags, fields, timestamp)
+}
+
+fn append_attributes(tags: &mut Tags, attributes: Vec<KeyValue>) {
+    for attribute in attributes {
+        let value = match attribute.value.and_then(|v| v.value) {
+            Some(Value::StringValue(v)) => v,
+            Some(Value::BoolValue(v)) => v.to_string(),
+            Some(Value::IntValue(v)) => v.to_string(),
+            Some(Value::DoubleValue(v)) => v.to_string(),
+            Some(v) => format!("{:?}", v).to_lowercase(),
+            None => continue,
+        };
+        // Empty tag values are not stored.
+        if value.is_empty() {
+            continue;
+        }
+        match tags.iter_mut().find(|(k, _)| k == &attribute.key) {
+            Some(tag) => tag.1 = Cow::Owned(value),
+            None => tags.push((Cow::Owned(attribute.key), Cow::Owned(value))),
+        }
+    }
+}
+
+fn number_value(value: Option<number_data_point::Value>) -> Option<FieldValue> {
+    match value? {
+        number_data_point::Value::AsDouble(v) => Some(FieldValue::F64(v)),
+        number_data_point::Value::AsInt(v) => Some(FieldValue::I64(v)),
+    }
+}
+
+fn timestamp(time_unix_nano: u64, default_time: i64) -> i64 {
+    if time_unix_nano == 0 {
+        default_time
+    } else {
+        time_unix_nano as i64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use protos::common::{any_value, AnyValue, InstrumentationScope, KeyValue};
+    use protos::metrics::{
+        metric, number_data_point, Gauge, Histogram, HistogramDataPoint, Metric, NumberDataPoint,
+        ResourceMetrics, ScopeMetrics,
+    };
+    use protos::metrics_service::ExportMetricsServiceRequest;
+    use protos::resource::Resource;
+    use protos::FieldValue;
+
+    use super::otlp_metrics_to_lines;
+
+    fn kv(key: &str, value: &str) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_otlp_metrics_to_lines() {
+        let req = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![kv("service.name", "api"), kv("host", "h1")],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Some(InstrumentationScope {
+                        attributes: vec![kv("lib", "otel")],
+                        ..Default::default()
+                    }),
+                    metrics: vec![
+                        Metric {
+                            name: "cpu".to_string(),
+                            data: Some(metric::Data::Gauge(Gauge {
+                                data_points: vec![NumberDataPoint {
+                                    attributes: vec![kv("host", "h2")],
+                                    time_unix_nano: 100,
+                                    value: Some(number_data_point::Value::AsDouble(0.5)),
+                                    ..Default::default()
+                                }],
+                            })),
+                            ..Default::default()
+                        },
+                        Metric {
+                            name: "latency".to_string(),
+                            data: Some(metric::Data::Histogram(Histogram {
+                                data_points: vec![HistogramDataPoint {
+                                    count: 3,
+                                    sum: Some(6.0),
+                                    bucket_counts: vec![1, 2],
+                                    explicit_bounds: vec![1.5],
+                                    ..Default::default()
+                                }],
+                                aggregation_temporality: 0,
+                            })),
+                            ..Default::default()
+                        },
+                    ],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let lines = otlp_metrics_to_lines(req, 7);
+        assert_eq!(lines.len(), 2);
+
+        let cpu = &lines[0];
+        assert_eq!(cpu.table, "cpu");
+        assert_eq!(cpu.timestamp, 100);
+        let tags = cpu
+            .tags
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.as_ref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![("host", "h2"), ("lib", "otel"), ("service.name", "api")]
+        );
+        assert_eq!(cpu.fields[0].1, FieldValue::F64(0.5));
+
+        let latency = &lines[1];
+        assert_eq!(latency.timestamp, 7);
+        let fields = latency
+            .fields
+            .iter()
+            .map(|(k, _)| k.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["count", "sum", "le_1.5", "le_inf"]);
+    }
+}
@@ -53,6 +53,8 @@ fn main() -> Result<(), AnyError> {
             ("otlp_resource.proto", "resource"),
             ("otlp_trace.proto", "trace"),
             ("otlp_trace_service.proto", "trace_service"),
+            ("otlp_metrics.proto", "metrics"),
+            ("otlp_metrics_service.proto", "metrics_service"),
         ],
     )?;
 
@@ -150,6 +152,16 @@ fn compile_protobuf_models<P: AsRef<Path>, S: AsRef<str>>(
                 &mut sub_mod_rs,
                 "#[path = \"opentelemetry.proto.collector.trace.rs\"]"
             )?;
+        } else if mod_name == "metrics" {
+            writeln!(
+                &mut sub_mod_rs,
+                "#[path = \"opentelemetry.proto.metrics.rs\"]"
+            )?;
+        } else if mod_name == "metrics_service" {
+            writeln!(
+                &mut sub_mod_rs,
+                "#[path = \"opentelemetry.proto.collector.metrics.rs\"]"
+            )?;
         }
         writeln!(&mut sub_mod_rs, "pub mod {mod_name};")?;
     }
@@ -38,6 +38,13 @@ pub struct BatchBytesResponse {
     pub code: i32,
     #[prost(bytes = "vec", tag = "2")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// CRC32 of the data, set if it is requested.
+    #[prost(uint32, optional, tag = "3")]
+    pub checksum: ::core::option::Option<u32>,
+    /// Storage scan metrics of the query, set on the last response without data
+    /// if they are requested.
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub scan_metrics: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
 /// --------------------------------------------------------------------
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -109,7 +116,9 @@ pub struct RaftWriteCommand {
     pub db_name: ::prost::alloc::string::String,
     #[prost(uint32, tag = "3")]
     pub replica_id: u32,
-    #[prost(oneof = "raft_write_command::Command", tags = "4, 5, 6, 7, 8")]
+    #[prost(enumeration = "WriteConsistency", tag = "9")]
+    pub consistency: i32,
+    #[prost(oneof = "raft_write_command::Command", tags = "4, 5, 6, 7, 8, 10, 11")]
     pub command: ::core::option::Option<raft_write_command::Command>,
 }
 /// Nested message and enum types in `RaftWriteCommand`.
@@ -127,6 +136,10 @@ pub mod raft_write_command {
         DeleteFromTable(super::DeleteFromTableRequest),
         #[prost(message, tag = "8")]
         UpdateTags(super::UpdateTagsRequest),
+        #[prost(message, tag = "10")]
+        RepairVnode(super::RepairVnodeRequest),
+        #[prost(message, tag = "11")]
+        MergeSeries(super::MergeSeriesRequest),
     }
 }
 /// --------------------------------------------------------------------
@@ -175,6 +188,9 @@ pub struct AddRaftFollowerRequest {
     pub replica_id: u32,
     #[prost(uint64, tag = "3")]
     pub follower_nid: u64,
+    /// Add the vnode as a learner, which never votes.
+    #[prost(bool, tag = "4")]
+    pub learner: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -203,6 +219,10 @@ pub struct PromoteLeaderRequest {
     pub new_leader_id: u32,
     #[prost(uint32, tag = "3")]
     pub replica_id: u32,
+    /// Reject the transfer if the new leader lags behind the log of the leader
+    /// by more entries, not checked if absent.
+    #[prost(uint64, optional, tag = "4")]
+    pub max_log_lag: ::core::option::Option<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -222,10 +242,229 @@ pub struct BuildRaftGroupRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportVnodeRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(int64, tag = "2")]
+    pub min_ts: i64,
+    #[prost(int64, tag = "3")]
+    pub max_ts: i64,
+    #[prost(string, tag = "4")]
+    pub path: ::prost::alloc::string::String,
+    /// connection option name -> option value in SQL literal
+    #[prost(map = "string, string", tag = "5")]
+    pub connection_options: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchCardinalityRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub top_k: u32,
+    /// The leader vnodes of the replication sets on the node.
+    #[prost(uint32, repeated, tag = "3")]
+    pub vnode_ids: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EstimateScanCostRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "2")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub table: ::prost::alloc::string::String,
+    /// ResolvedPredicate encoded by bincode
+    #[prost(bytes = "vec", tag = "4")]
+    pub predicate: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchVnodesDiskStorageRequest {
+    #[prost(uint32, repeated, tag = "1")]
+    pub vnode_ids: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloseVnodesFilesRequest {
+    #[prost(uint32, repeated, tag = "1")]
+    pub vnode_ids: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExplainCompactionRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchRecoveryProgressRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BackupVnodeRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    /// connection option name -> option value in SQL literal
+    #[prost(map = "string, string", tag = "3")]
+    pub connection_options: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "3")]
+    pub path: ::prost::alloc::string::String,
+    /// connection option name -> option value in SQL literal
+    #[prost(map = "string, string", tag = "4")]
+    pub connection_options: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// VnodeBackup encoded by bincode
+    #[prost(bytes = "vec", tag = "5")]
+    pub backup: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchFilesMetaRequest {
+    /// Paths relative to the storage directory.
+    #[prost(string, repeated, tag = "1")]
+    pub filenames: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepairChunk {
+    /// SeriesKey encoded by bincode
+    #[prost(bytes = "vec", tag = "1")]
+    pub series_key: ::prost::alloc::vec::Vec<u8>,
+    #[prost(int64, tag = "2")]
+    pub min_ts: i64,
+    #[prost(int64, tag = "3")]
+    pub max_ts: i64,
+}
+/// Applied only by the vnode being repaired, it's skipped if the chunks are
+/// written by the raft logs after since_index.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepairVnodeRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "2")]
+    pub table: ::prost::alloc::string::String,
+    /// Data of the chunks is deleted before the points are written.
+    #[prost(message, repeated, tag = "3")]
+    pub chunks: ::prost::alloc::vec::Vec<RepairChunk>,
+    /// Points copied from the leader, in flatbuffers, may be empty.
+    #[prost(bytes = "vec", tag = "4")]
+    pub points: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint32, tag = "5")]
+    pub precision: u32,
+    /// The latest raft log writing the chunks when they were copied.
+    #[prost(uint64, tag = "6")]
+    pub since_index: u64,
+}
+/// Merge the series into the series of a new tag value, applied by all the
+/// vnodes of the replica set.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MergeSeriesRequest {
+    #[prost(string, tag = "1")]
+    pub table: ::prost::alloc::string::String,
+    /// SeriesKeys encoded by bincode, data of the series is deleted before the
+    /// points are written.
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub series_keys: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Points of the series with the new tag value, in flatbuffers, may be empty.
+    #[prost(bytes = "vec", tag = "3")]
+    pub points: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint32, tag = "4")]
+    pub precision: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchVnodesRowsRequest {
+    #[prost(uint32, repeated, tag = "1")]
+    pub vnode_ids: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchCompactionsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KillCompactionRequest {
+    #[prost(uint64, tag = "1")]
+    pub job_id: u64,
+}
+/// Confirm the leadership of the leader, respond with the index of the raft
+/// log committed by it, encoded as u64 in big endian.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReadIndexRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub replica_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WaitAppliedRequest {
+    #[prost(uint32, tag = "1")]
+    pub replica_id: u32,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    /// Wait until the raft log of the index is applied by the vnode.
+    #[prost(uint64, tag = "3")]
+    pub index: u64,
+    #[prost(uint64, tag = "4")]
+    pub timeout_ms: u64,
+}
+/// Count the series of the table in the index of the vnode, respond with the
+/// count encoded as u64 in big endian.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TableSeriesCountRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub table: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub vnode_id: u32,
+}
+/// The index of the latest raft log writing the time range of the table in the
+/// vnode, respond with it encoded as u64 in big endian, empty if it's unknown.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TableLastWriteRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "2")]
+    pub table: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub min_ts: i64,
+    #[prost(int64, tag = "4")]
+    pub max_ts: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AdminCommand {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
-    #[prost(oneof = "admin_command::Command", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
+    #[prost(
+        oneof = "admin_command::Command",
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 23, 24, 25, 26, 27, 28, 29"
+    )]
     pub command: ::core::option::Option<admin_command::Command>,
 }
 /// Nested message and enum types in `AdminCommand`.
@@ -253,6 +492,40 @@ pub mod admin_command {
         LearnerToFollower(super::LearnerToFollowerRequest),
         #[prost(message, tag = "11")]
         BuildRaftGroup(super::BuildRaftGroupRequest),
+        #[prost(message, tag = "12")]
+        ExportVnode(super::ExportVnodeRequest),
+        #[prost(message, tag = "13")]
+        FetchCardinality(super::FetchCardinalityRequest),
+        #[prost(message, tag = "14")]
+        EstimateScanCost(super::EstimateScanCostRequest),
+        #[prost(message, tag = "15")]
+        FetchVnodesDiskStorage(super::FetchVnodesDiskStorageRequest),
+        #[prost(message, tag = "16")]
+        CloseVnodesFiles(super::CloseVnodesFilesRequest),
+        #[prost(message, tag = "17")]
+        ExplainCompaction(super::ExplainCompactionRequest),
+        #[prost(message, tag = "18")]
+        FetchRecoveryProgress(super::FetchRecoveryProgressRequest),
+        #[prost(message, tag = "19")]
+        BackupVnode(super::BackupVnodeRequest),
+        #[prost(message, tag = "20")]
+        RestoreVnode(super::RestoreVnodeRequest),
+        #[prost(message, tag = "21")]
+        FetchFilesMeta(super::FetchFilesMetaRequest),
+        #[prost(message, tag = "23")]
+        FetchVnodesRows(super::FetchVnodesRowsRequest),
+        #[prost(message, tag = "24")]
+        FetchCompactions(super::FetchCompactionsRequest),
+        #[prost(message, tag = "25")]
+        KillCompaction(super::KillCompactionRequest),
+        #[prost(message, tag = "26")]
+        ReadIndex(super::ReadIndexRequest),
+        #[prost(message, tag = "27")]
+        WaitApplied(super::WaitAppliedRequest),
+        #[prost(message, tag = "28")]
+        TableSeriesCount(super::TableSeriesCountRequest),
+        #[prost(message, tag = "29")]
+        TableLastWrite(super::TableLastWriteRequest),
     }
 }
 /// --------------------------------------------------------------------
@@ -261,6 +534,12 @@ pub mod admin_command {
 pub struct DownloadFileRequest {
     #[prost(string, tag = "1")]
     pub filename: ::prost::alloc::string::String,
+    /// Download the file from the offset, to resume an interrupted download.
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+    /// Set the checksum of each chunk of the file.
+    #[prost(bool, tag = "3")]
+    pub checksum: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -271,6 +550,95 @@ pub struct QueryRecordBatchRequest {
     pub expr: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "3")]
     pub aggs: ::prost::alloc::vec::Vec<u8>,
+    /// Set the checksum of each record batch.
+    #[prost(bool, tag = "4")]
+    pub checksum: bool,
+    /// Respond with the storage scan metrics at the end of the stream.
+    #[prost(bool, tag = "5")]
+    pub scan_metrics: bool,
+}
+/// --------------------------------------------------------------------
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeRequest {
+    #[prost(string, tag = "1")]
+    pub tenant: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub db: ::prost::alloc::string::String,
+    /// 0 means from the earliest retained write.
+    #[prost(uint64, tag = "3")]
+    pub start_offset: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeResponse {
+    #[prost(uint64, tag = "1")]
+    pub offset: u64,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(uint64, tag = "3")]
+    pub index: u64,
+    #[prost(uint32, tag = "4")]
+    pub precision: u32,
+    /// Points encoded by flatbuffers.
+    #[prost(bytes = "vec", tag = "5")]
+    pub points: ::prost::alloc::vec::Vec<u8>,
+}
+/// --------------------------------------------------------------------
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportVnodeDataRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(int64, tag = "2")]
+    pub min_ts: i64,
+    #[prost(int64, tag = "3")]
+    pub max_ts: i64,
+    #[prost(string, tag = "4")]
+    pub tenant: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportVnodeDataResponse {
+    #[prost(string, tag = "1")]
+    pub table: ::prost::alloc::string::String,
+    /// A record batch of the table encoded as an Arrow IPC stream.
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+/// Number of replicas a raft write waits for before it is acknowledged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WriteConsistency {
+    /// The write is committed by a quorum of the replicas.
+    Quorum = 0,
+    /// Raft can not acknowledge a write before it is committed, so it is
+    /// the same as QUORUM.
+    One = 1,
+    /// The write is committed and replicated to all replicas.
+    All = 2,
+}
+impl WriteConsistency {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WriteConsistency::Quorum => "QUORUM",
+            WriteConsistency::One => "ONE",
+            WriteConsistency::All => "ALL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "QUORUM" => Some(Self::Quorum),
+            "ONE" => Some(Self::One),
+            "ALL" => Some(Self::All),
+            _ => None,
+        }
+    }
 }
 /// Generated client implementations.
 pub mod tskv_service_client {
@@ -505,6 +873,56 @@ pub mod tskv_service_client {
                 .insert(GrpcMethod::new("kv_service.TSKVService", "AdminRequest"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn subscribe(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SubscribeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::SubscribeResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/kv_service.TSKVService/Subscribe",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("kv_service.TSKVService", "Subscribe"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn export_vnode_data(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportVnodeDataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ExportVnodeDataResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/kv_service.TSKVService/ExportVnodeData",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("kv_service.TSKVService", "ExportVnodeData"));
+            self.inner.server_streaming(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -568,6 +986,26 @@ pub mod tskv_service_server {
             tonic::Response<super::BatchBytesResponse>,
             tonic::Status,
         >;
+        /// Server streaming response type for the Subscribe method.
+        type SubscribeStream: futures_core::Stream<
+                Item = std::result::Result<super::SubscribeResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn subscribe(
+            &self,
+            request: tonic::Request<super::SubscribeRequest>,
+        ) -> std::result::Result<tonic::Response<Self::SubscribeStream>, tonic::Status>;
+        /// Server streaming response type for the ExportVnodeData method.
+        type ExportVnodeDataStream: futures_core::Stream<
+                Item = std::result::Result<super::ExportVnodeDataResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn export_vnode_data(
+            &self,
+            request: tonic::Request<super::ExportVnodeDataRequest>,
+        ) -> std::result::Result<tonic::Response<Self::ExportVnodeDataStream>, tonic::Status>;
     }
     /// --------------------------------------------------------------------
     #[derive(Debug)]
@@ -920,6 +1358,96 @@ pub mod tskv_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/kv_service.TSKVService/Subscribe" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeSvc<T: TskvService>(pub Arc<T>);
+                    impl<
+                        T: TskvService,
+                    > tonic::server::ServerStreamingService<super::SubscribeRequest>
+                    for SubscribeSvc<T> {
+                        type Response = super::SubscribeResponse;
+                        type ResponseStream = T::SubscribeStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubscribeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).subscribe(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SubscribeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/kv_service.TSKVService/ExportVnodeData" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportVnodeDataSvc<T: TskvService>(pub Arc<T>);
+                    impl<
+                        T: TskvService,
+                    > tonic::server::ServerStreamingService<super::ExportVnodeDataRequest>
+                    for ExportVnodeDataSvc<T> {
+                        type Response = super::ExportVnodeDataResponse;
+                        type ResponseStream = T::ExportVnodeDataStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExportVnodeDataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).export_vnode_data(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ExportVnodeDataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
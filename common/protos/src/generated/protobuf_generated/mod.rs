@@ -12,3 +12,7 @@ pub mod resource;
 pub mod trace;
 #[path = "opentelemetry.proto.collector.trace.rs"]
 pub mod trace_service;
+#[path = "opentelemetry.proto.metrics.rs"]
+pub mod metrics;
+#[path = "opentelemetry.proto.collector.metrics.rs"]
+pub mod metrics_service;
@@ -31,6 +31,23 @@ pub struct RaftAppendEntriesReq {
     #[prost(bytes = "vec", tag = "3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
 }
+/// The append entries of the raft groups to the same node, sent together
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RaftBatchAppendEntriesReq {
+    #[prost(message, repeated, tag = "1")]
+    pub requests: ::prost::alloc::vec::Vec<RaftAppendEntriesReq>,
+    /// The max milliseconds appending the entries of each group, 0 means no limit.
+    #[prost(uint64, tag = "2")]
+    pub timeout_ms: u64,
+}
+/// The responses in the same order as the requests
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RaftBatchResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub responses: ::prost::alloc::vec::Vec<RaftResponse>,
+}
 /// Generated client implementations.
 pub mod raft_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -185,6 +202,36 @@ pub mod raft_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        pub async fn raft_batch_append_entries(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RaftBatchAppendEntriesReq>,
+        ) -> std::result::Result<
+            tonic::Response<super::RaftBatchResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/raft_service.RaftService/RaftBatchAppendEntries",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "raft_service.RaftService",
+                        "RaftBatchAppendEntries",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -206,6 +253,13 @@ pub mod raft_service_server {
             &self,
             request: tonic::Request<super::RaftAppendEntriesReq>,
         ) -> std::result::Result<tonic::Response<super::RaftResponse>, tonic::Status>;
+        async fn raft_batch_append_entries(
+            &self,
+            request: tonic::Request<super::RaftBatchAppendEntriesReq>,
+        ) -> std::result::Result<
+            tonic::Response<super::RaftBatchResponse>,
+            tonic::Status,
+        >;
     }
     /// --------------------------------------------------------------------
     #[derive(Debug)]
@@ -421,6 +475,52 @@ pub mod raft_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/raft_service.RaftService/RaftBatchAppendEntries" => {
+                    #[allow(non_camel_case_types)]
+                    struct RaftBatchAppendEntriesSvc<T: RaftService>(pub Arc<T>);
+                    impl<
+                        T: RaftService,
+                    > tonic::server::UnaryService<super::RaftBatchAppendEntriesReq>
+                    for RaftBatchAppendEntriesSvc<T> {
+                        type Response = super::RaftBatchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RaftBatchAppendEntriesReq>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).raft_batch_append_entries(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RaftBatchAppendEntriesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
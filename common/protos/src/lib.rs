@@ -4,6 +4,7 @@ use tonic::codec::CompressionEncoding;
 pub mod models_helper;
 pub mod prompb;
 pub mod test_helper;
+pub mod tls;
 
 use core::time;
 use std::fmt::{Display, Formatter};
@@ -11,10 +12,11 @@ use std::time::Duration;
 
 use flatbuffers::{ForwardsUOffset, Vector};
 use snafu::{Backtrace, Location, OptionExt, Snafu};
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::Channel;
 use tower::timeout::Timeout;
 
 use crate::kv_service::tskv_service_client::TskvServiceClient;
+use crate::kv_service::BatchBytesResponse;
 use crate::models::{Column, Points, Table};
 use crate::raft_service::raft_service_client::RaftServiceClient;
 
@@ -269,6 +271,23 @@ impl<'a> Display for Table<'a> {
     }
 }
 
+impl BatchBytesResponse {
+    /// Set the CRC32 of the data, which is verified by the receiver to detect
+    /// the data corrupted on the network.
+    pub fn with_checksum(mut self) -> Self {
+        self.checksum = Some(crc32fast::hash(&self.data));
+        self
+    }
+
+    /// Returns false if the checksum is set and does not match the data.
+    pub fn verify_checksum(&self) -> bool {
+        match self.checksum {
+            Some(checksum) => crc32fast::hash(&self.data) == checksum,
+            None => true,
+        }
+    }
+}
+
 pub fn tskv_service_time_out_client(
     channel: Channel,
     time_out: Duration,
@@ -306,7 +325,7 @@ pub fn raft_service_time_out_client(
 }
 
 pub async fn tskv_service_ping(addr: &str) -> Result<(), String> {
-    let connector = Endpoint::from_shared(format!("http://{}", addr)).map_err(|e| e.to_string())?;
+    let connector = tls::grpc_endpoint(addr).map_err(|e| e.to_string())?;
     let channel = connector
         .connect()
         .await
@@ -343,9 +362,26 @@ pub mod test {
 
     use flatbuffers::FlatBufferBuilder;
 
+    use crate::kv_service::BatchBytesResponse;
     use crate::models::{FieldType, Points};
     use crate::models_helper::create_const_points;
 
+    #[test]
+    fn test_batch_bytes_checksum() {
+        let resp = BatchBytesResponse {
+            data: b"record batch".to_vec(),
+            ..Default::default()
+        };
+        assert!(resp.verify_checksum());
+
+        let mut resp = resp.with_checksum();
+        assert!(resp.checksum.is_some());
+        assert!(resp.verify_checksum());
+
+        resp.data[0] ^= 0x01;
+        assert!(!resp.verify_checksum());
+    }
+
     #[test]
     #[ignore = "Checked by human"]
     fn test_format_fb_model_points() {
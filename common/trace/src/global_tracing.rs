@@ -11,10 +11,14 @@ use opentelemetry::{InstrumentationLibrary, KeyValue};
 use opentelemetry_otlp::{Protocol, WithExportConfig};
 use opentelemetry_sdk::Resource;
 
+use crate::sampler::{set_sampling_config, SamplingReporter};
+
 pub fn init_global_tracing(trace_config: &TraceConfig, service_name: String) {
+    set_sampling_config(trace_config.sampling.clone());
+
     if let Some(trace_log_path) = &trace_config.trace_log_path {
         let path = PathBuf::from(trace_log_path).join("trace.log");
-        let reporter = FileReporter::new(path);
+        let reporter = SamplingReporter::new(FileReporter::new(path));
         minitrace::set_reporter(
             reporter,
             Config::default()
@@ -25,7 +29,8 @@ pub fn init_global_tracing(trace_config: &TraceConfig, service_name: String) {
     }
 
     if let Some(endpoint) = &trace_config.otlp_endpoint {
-        let reporter = opentelemetry_reporter(endpoint.to_owned(), service_name);
+        let reporter =
+            SamplingReporter::new(opentelemetry_reporter(endpoint.to_owned(), service_name));
         minitrace::set_reporter(
             reporter,
             Config::default()
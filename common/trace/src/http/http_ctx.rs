@@ -2,11 +2,14 @@ use http::HeaderMap;
 use minitrace::collector::SpanContext;
 use snafu::Snafu;
 use tonic::metadata::errors::InvalidMetadataValue;
-use tonic::metadata::MetadataMap;
+use tonic::metadata::{MetadataMap, MetadataValue};
 
+use crate::sampler::sampling_decision;
 use crate::span_ctx_ext::{DecodeError, SpanContextExt};
 
 pub const DEFAULT_TRACE_HEADER_NAME: &str = "cnosdb-trace-ctx";
+/// Whether the trace is sampled by the node which started it.
+pub const TRACE_SAMPLED_HEADER_NAME: &str = "cnosdb-trace-sampled";
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
@@ -56,3 +59,25 @@ pub fn grpc_append_trace_context(
     }
     Ok(())
 }
+
+/// Send the sampling decision of a trace started by this node, see
+/// [`sampling_decision`].
+pub fn grpc_append_sampling_decision(
+    span_ctx: Option<&SpanContext>,
+    tenant: &str,
+    statement: &str,
+    headers: &mut MetadataMap,
+) {
+    let sampled = span_ctx.and_then(|ctx| sampling_decision(ctx.trace_id, tenant, statement));
+    if let Some(sampled) = sampled {
+        let value = if sampled { "true" } else { "false" };
+        headers.insert(TRACE_SAMPLED_HEADER_NAME, MetadataValue::from_static(value));
+    }
+}
+
+pub fn trace_sampled_from_http_headers(headers: &HeaderMap) -> Option<bool> {
+    headers
+        .get(TRACE_SAMPLED_HEADER_NAME)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
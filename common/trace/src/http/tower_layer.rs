@@ -4,8 +4,9 @@ use std::task::{Context, Poll};
 use http::Request;
 use tower::{Layer, Service};
 
-use super::http_ctx::span_context_from_http_headers;
-use crate::{warn, SpanContext};
+use super::http_ctx::{span_context_from_http_headers, trace_sampled_from_http_headers};
+use crate::sampler::{new_root_span_context, TraceSampled};
+use crate::warn;
 
 /// `TraceLayer` implements `tower::Layer` and can be used to decorate a
 /// `tower::Service` to collect information about requests flowing through it
@@ -67,6 +68,9 @@ where
         ) {
             (_, Ok(Some(ctx))) => {
                 request.extensions_mut().insert(ctx);
+                if let Some(sampled) = trace_sampled_from_http_headers(request.headers()) {
+                    request.extensions_mut().insert(TraceSampled(sampled));
+                }
             }
             (_, Err(e)) => {
                 warn!(
@@ -75,7 +79,7 @@ where
                 );
             }
             (true, _) => {
-                request.extensions_mut().insert(SpanContext::random());
+                request.extensions_mut().insert(new_root_span_context());
             }
             _ => {}
         }
@@ -0,0 +1,369 @@
+//! Sampling of the traces started by cnosdb itself.
+//!
+//! Root span contexts generated by the server (see [`new_root_span_context`]) have
+//! no parent span, the whole trace is reported or dropped by [`SamplingReporter`]
+//! according to the [`TraceSamplingConfig`], which can be replaced at runtime.
+//! Traces continued from the span context of a client are always reported.
+//!
+//! The decision is made when spans of a trace are reported, so that the duration
+//! of the root span is known for the tail based sampling of slow statements.
+//!
+//! The node which started a trace sends the decision of the rules to the nodes
+//! it calls (see [`sampling_decision`]), the spans continuing the trace there
+//! carry it in the [`SAMPLED_PROPERTY`]. A dropped trace is still reported by a
+//! remote node if its part of the trace is slow itself.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use config::tskv::TraceSamplingConfig;
+use minitrace::collector::{Reporter, SpanContext, SpanId, SpanRecord, TraceId};
+
+/// Property of span which records the tenant of the request.
+pub const TENANT_PROPERTY: &str = "tenant";
+/// Property of the root span which records the type of statement.
+pub const STATEMENT_PROPERTY: &str = "statement";
+pub const STATEMENT_QUERY: &str = "query";
+pub const STATEMENT_WRITE: &str = "write";
+/// Property of the span continuing the trace of another node, whether the trace
+/// is sampled by the node which started it.
+pub const SAMPLED_PROPERTY: &str = "sampled";
+
+static SAMPLING_CONFIG: RwLock<Option<TraceSamplingConfig>> = RwLock::new(None);
+
+/// Whether the spans are reported by a [`SamplingReporter`], so that the root
+/// spans tracked by [`track_root_span`] are released once reported.
+static SAMPLING_ENABLED: AtomicBool = AtomicBool::new(false);
+/// Number of the root spans of the traces started by this node which are not
+/// reported yet.
+static LOCAL_TRACES: Mutex<Option<HashMap<TraceId, usize>>> = Mutex::new(None);
+
+/// The decision sent by the node which started the trace, see [`SAMPLED_PROPERTY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceSampled(pub bool);
+
+pub fn sampling_config() -> TraceSamplingConfig {
+    SAMPLING_CONFIG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+pub fn set_sampling_config(config: TraceSamplingConfig) {
+    *SAMPLING_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+/// Generate span context for a new trace which is subject to sampling.
+pub fn new_root_span_context() -> SpanContext {
+    SpanContext::new(TraceId(rand::random()), SpanId::default())
+}
+
+/// Record a root span created from the context of [`new_root_span_context`]
+/// until it is reported.
+pub fn track_root_span(context: &SpanContext) {
+    if context.span_id != SpanId::default() || !SAMPLING_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut traces = LOCAL_TRACES.lock().unwrap_or_else(|e| e.into_inner());
+    *traces
+        .get_or_insert_with(HashMap::new)
+        .entry(context.trace_id)
+        .or_default() += 1;
+}
+
+fn release_root_spans(spans: &[SpanRecord]) {
+    let mut traces = LOCAL_TRACES.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(traces) = traces.as_mut() else {
+        return;
+    };
+    for span in spans.iter().filter(|s| s.parent_id == SpanId::default()) {
+        if let Some(count) = traces.get_mut(&span.trace_id) {
+            *count -= 1;
+            if *count == 0 {
+                traces.remove(&span.trace_id);
+            }
+        }
+    }
+}
+
+/// The decision of the rules for a trace started by this node, which is sent to
+/// the nodes continuing the trace. None if the trace is continued from a client
+/// or from another node, it's reported by them on their own.
+pub fn sampling_decision(trace_id: TraceId, tenant: &str, statement: &str) -> Option<bool> {
+    let traces = LOCAL_TRACES.lock().unwrap_or_else(|e| e.into_inner());
+    if !traces.as_ref()?.contains_key(&trace_id) {
+        return None;
+    }
+    drop(traces);
+
+    Some(sampled_by_rules(
+        &sampling_config(),
+        trace_id,
+        Some(tenant),
+        Some(statement),
+    ))
+}
+
+/// Wraps a [`Reporter`] and only reports the sampled traces.
+pub struct SamplingReporter<R> {
+    inner: R,
+}
+
+impl<R> SamplingReporter<R> {
+    pub fn new(inner: R) -> Self {
+        SAMPLING_ENABLED.store(true, Ordering::Relaxed);
+        Self { inner }
+    }
+}
+
+impl<R: Reporter> Reporter for SamplingReporter<R> {
+    fn report(&mut self, spans: &[SpanRecord]) {
+        release_root_spans(spans);
+
+        let config = sampling_config();
+        let dropped = dropped_traces(&config, spans);
+        if dropped.is_empty() {
+            self.inner.report(spans);
+            return;
+        }
+
+        let spans = spans
+            .iter()
+            .filter(|s| !dropped.contains(&s.trace_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !spans.is_empty() {
+            self.inner.report(&spans);
+        }
+    }
+}
+
+#[derive(Default)]
+struct TraceInfo<'a> {
+    root_duration_ns: Option<u64>,
+    tenant: Option<&'a str>,
+    statement: Option<&'a str>,
+    /// The decision of the node which started the trace.
+    sampled: Option<bool>,
+}
+
+fn dropped_traces(config: &TraceSamplingConfig, spans: &[SpanRecord]) -> HashSet<TraceId> {
+    let mut traces: HashMap<TraceId, TraceInfo> = HashMap::new();
+    for span in spans {
+        let info = traces.entry(span.trace_id).or_default();
+        if span.parent_id == SpanId::default() {
+            info.root_duration_ns = info.root_duration_ns.max(Some(span.duration_ns));
+        }
+        for (k, v) in span.properties.iter() {
+            if k == TENANT_PROPERTY {
+                info.tenant.get_or_insert(v.as_ref());
+            } else if k == STATEMENT_PROPERTY {
+                info.statement.get_or_insert(v.as_ref());
+            } else if k == SAMPLED_PROPERTY {
+                // The span continuing the trace is the root of the part of this node.
+                info.root_duration_ns = info.root_duration_ns.max(Some(span.duration_ns));
+                info.sampled = Some(info.sampled.unwrap_or(false) || v == "true");
+            }
+        }
+    }
+
+    traces
+        .into_iter()
+        .filter(|(trace_id, info)| !is_sampled(config, *trace_id, info))
+        .map(|(trace_id, _)| trace_id)
+        .collect()
+}
+
+fn is_sampled(config: &TraceSamplingConfig, trace_id: TraceId, info: &TraceInfo) -> bool {
+    let root_duration_ns = match info.root_duration_ns {
+        Some(d) => d,
+        // Root span is from the client.
+        None => return true,
+    };
+
+    let slow_threshold_ns = config.slow_threshold.as_nanos();
+    if slow_threshold_ns > 0 && root_duration_ns as u128 >= slow_threshold_ns {
+        return true;
+    }
+
+    match info.sampled {
+        Some(sampled) => sampled,
+        None => sampled_by_rules(config, trace_id, info.tenant, info.statement),
+    }
+}
+
+fn sampled_by_rules(
+    config: &TraceSamplingConfig,
+    trace_id: TraceId,
+    tenant: Option<&str>,
+    statement: Option<&str>,
+) -> bool {
+    let ratio = config
+        .rules
+        .iter()
+        .find(|rule| {
+            matches_rule(rule.tenant.as_deref(), tenant)
+                && matches_rule(rule.statement.as_deref(), statement)
+        })
+        .map(|rule| rule.ratio)
+        .unwrap_or(config.ratio);
+    if ratio >= 1.0 {
+        return true;
+    }
+
+    // Trace id is random, so the sampling is consistent for all spans of the trace.
+    let TraceId(id) = trace_id;
+    (id as u64 as f64) < ratio * u64::MAX as f64
+}
+
+fn matches_rule(expected: Option<&str>, actual: Option<&str>) -> bool {
+    match expected {
+        Some(expected) => actual == Some(expected),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    use config::tskv::{TraceSamplingConfig, TraceSamplingRule};
+    use minitrace::collector::{Reporter, SpanContext, SpanId, SpanRecord, TraceId};
+
+    use super::{
+        dropped_traces, new_root_span_context, sampling_decision, track_root_span,
+        SamplingReporter, SAMPLED_PROPERTY, STATEMENT_PROPERTY, STATEMENT_QUERY, STATEMENT_WRITE,
+    };
+
+    fn root_span(trace_id: u128, duration: Duration, statement: &'static str) -> SpanRecord {
+        SpanRecord {
+            trace_id: TraceId(trace_id),
+            span_id: SpanId(1),
+            parent_id: SpanId::default(),
+            duration_ns: duration.as_nanos() as u64,
+            properties: vec![(Cow::Borrowed(STATEMENT_PROPERTY), Cow::Borrowed(statement))],
+            ..Default::default()
+        }
+    }
+
+    fn tenant_span(trace_id: u128, tenant: &'static str) -> SpanRecord {
+        SpanRecord {
+            trace_id: TraceId(trace_id),
+            span_id: SpanId(2),
+            parent_id: SpanId(1),
+            properties: vec![(Cow::Borrowed("tenant"), Cow::Borrowed(tenant))],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sampling_rules() {
+        let config = TraceSamplingConfig {
+            ratio: 0.0,
+            slow_threshold: Duration::from_secs(1),
+            rules: vec![TraceSamplingRule {
+                tenant: Some("t1".to_string()),
+                statement: Some(STATEMENT_WRITE.to_string()),
+                ratio: 1.0,
+            }],
+        };
+        let spans = vec![
+            // Matches the rule.
+            root_span(1, Duration::from_millis(1), STATEMENT_WRITE),
+            tenant_span(1, "t1"),
+            // Falls back to the default ratio.
+            root_span(2, Duration::from_millis(1), STATEMENT_QUERY),
+            tenant_span(2, "t1"),
+            root_span(3, Duration::from_millis(1), STATEMENT_WRITE),
+            tenant_span(3, "t2"),
+            // Slow statement.
+            root_span(4, Duration::from_secs(2), STATEMENT_QUERY),
+            // Root span is from the client.
+            tenant_span(5, "t2"),
+        ];
+        let mut dropped = dropped_traces(&config, &spans)
+            .into_iter()
+            .map(|TraceId(id)| id)
+            .collect::<Vec<_>>();
+        dropped.sort();
+        assert_eq!(dropped, vec![2, 3]);
+    }
+
+    fn remote_span(trace_id: u128, duration: Duration, sampled: bool) -> SpanRecord {
+        SpanRecord {
+            trace_id: TraceId(trace_id),
+            span_id: SpanId(3),
+            parent_id: SpanId(2),
+            duration_ns: duration.as_nanos() as u64,
+            properties: vec![(
+                Cow::Borrowed(SAMPLED_PROPERTY),
+                Cow::Owned(sampled.to_string()),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_remote_sampling() {
+        let config = TraceSamplingConfig {
+            ratio: 1.0,
+            slow_threshold: Duration::from_secs(1),
+            rules: vec![],
+        };
+        let spans = vec![
+            // Dropped by the node which started the trace.
+            remote_span(1, Duration::from_millis(1), false),
+            tenant_span(1, "t1"),
+            remote_span(2, Duration::from_millis(1), true),
+            // The part of this node is slow.
+            remote_span(3, Duration::from_secs(2), false),
+        ];
+        let dropped = dropped_traces(&config, &spans)
+            .into_iter()
+            .map(|TraceId(id)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(dropped, vec![1]);
+    }
+
+    struct DiscardReporter;
+
+    impl Reporter for DiscardReporter {
+        fn report(&mut self, _spans: &[SpanRecord]) {}
+    }
+
+    #[test]
+    fn test_sampling_decision() {
+        let mut reporter = SamplingReporter::new(DiscardReporter);
+
+        let context = new_root_span_context();
+        assert_eq!(
+            sampling_decision(context.trace_id, "t1", STATEMENT_QUERY),
+            None
+        );
+        track_root_span(&context);
+        assert!(sampling_decision(context.trace_id, "t1", STATEMENT_QUERY).is_some());
+
+        // Continued from a client.
+        let client = SpanContext::new(TraceId(7), SpanId(1));
+        track_root_span(&client);
+        assert_eq!(
+            sampling_decision(client.trace_id, "t1", STATEMENT_QUERY),
+            None
+        );
+
+        // Released once the root span is reported.
+        reporter.report(&[SpanRecord {
+            trace_id: context.trace_id,
+            span_id: SpanId(1),
+            parent_id: SpanId::default(),
+            ..Default::default()
+        }]);
+        assert_eq!(
+            sampling_decision(context.trace_id, "t1", STATEMENT_QUERY),
+            None
+        );
+    }
+}
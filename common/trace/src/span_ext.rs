@@ -4,6 +4,8 @@ use std::mem;
 use minitrace::collector::SpanContext;
 use minitrace::{Event, Span};
 
+use crate::sampler::track_root_span;
+
 pub trait SpanExt {
     fn context(&self) -> Option<SpanContext>;
     fn from_context(name: impl Into<Cow<'static, str>>, context: Option<&SpanContext>) -> Self;
@@ -29,7 +31,10 @@ impl SpanExt for Span {
     }
     fn from_context(name: impl Into<Cow<'static, str>>, context: Option<&SpanContext>) -> Self {
         match context {
-            Some(ctx) => Span::root(name, *ctx),
+            Some(ctx) => {
+                track_root_span(ctx);
+                Span::root(name, *ctx)
+            }
             None => Span::noop(),
         }
     }
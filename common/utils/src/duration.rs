@@ -96,6 +96,15 @@ impl CnosDuration {
         }
     }
 
+    /// Returns None if the duration is infinite.
+    pub fn to_duration(&self) -> Option<Duration> {
+        if self.is_inf {
+            None
+        } else {
+            Some(self.duration)
+        }
+    }
+
     pub fn to_precision(&self, pre: Precision) -> i64 {
         match pre {
             Precision::MS => self.to_millisecond(),
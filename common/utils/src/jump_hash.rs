@@ -0,0 +1,40 @@
+/// Jump consistent hash from "A Fast, Minimal Memory, Consistent Hash
+/// Algorithm" (Lamping and Veach), maps the key to a bucket in `[0, buckets)`.
+///
+/// Keys are spread evenly over the buckets even if the low bits of the keys
+/// are not, and only `1 / buckets` of the keys move when a bucket is added.
+pub fn jump_consistent_hash(mut key: u64, buckets: u32) -> u32 {
+    let mut b: i64 = -1;
+    let mut j: i64 = 0;
+    while j < buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1_u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    b.max(0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::jump_consistent_hash;
+
+    #[test]
+    fn test_jump_consistent_hash() {
+        assert_eq!(jump_consistent_hash(10, 0), 0);
+        assert_eq!(jump_consistent_hash(10, 1), 0);
+
+        // Keys whose low bits are all the same are still spread over the buckets.
+        let mut counts = [0_usize; 4];
+        for i in 0..4000_u64 {
+            counts[jump_consistent_hash(i << 8, 4) as usize] += 1;
+        }
+        assert!(counts.iter().all(|c| (800..1200).contains(c)), "{counts:?}");
+
+        // A key moves either to the new bucket or stays.
+        for key in 0..1000_u64 {
+            let before = jump_consistent_hash(key, 5);
+            let after = jump_consistent_hash(key, 6);
+            assert!(after == before || after == 5);
+        }
+    }
+}
@@ -1,16 +1,19 @@
 pub use bkdr_hash::BkdrHasher;
 pub use bloom_filter::BloomFilter;
 pub use dedup::{dedup_front_by, dedup_front_by_key};
+pub use jump_hash::jump_consistent_hash;
 
 pub mod backtrace;
 mod bkdr_hash;
 mod bloom_filter;
 mod dedup;
+mod jump_hash;
 
 pub mod byte_utils;
 
 pub mod byte_nums;
 pub mod duration;
+pub mod net;
 pub mod precision;
 
 pub type Timestamp = i64;
@@ -0,0 +1,84 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Build a `host:port` address, IPv6 literals are enclosed in square brackets,
+/// for example `[::1]:8902`.
+pub fn build_address(host: &str, port: impl std::fmt::Display) -> String {
+    let host = trim_brackets(host);
+    if host.parse::<Ipv6Addr>().is_ok() {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+/// The unspecified address to bind listeners on, `::` also accepts IPv4 connections
+/// as IPv4-mapped IPv6 addresses if the operating system allows (`net.ipv6.bindv6only = 0`).
+pub fn unspecified_host(dual_stack: bool) -> IpAddr {
+    if dual_stack {
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    }
+}
+
+/// Check the host is a hostname, an IPv4 literal or an IPv6 literal (with or without brackets).
+pub fn check_host(host: &str) -> Result<(), String> {
+    if host.is_empty() {
+        return Err("host is empty".to_string());
+    }
+    let trimmed = trim_brackets(host);
+    if trimmed.contains(':') || trimmed.len() != host.len() {
+        return trimmed
+            .parse::<Ipv6Addr>()
+            .map(|_| ())
+            .map_err(|e| format!("invalid IPv6 address '{host}': {e}"));
+    }
+    if trimmed.contains(|c: char| c.is_whitespace() || c == '/') {
+        return Err(format!("invalid host '{host}'"));
+    }
+    Ok(())
+}
+
+fn trim_brackets(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{SocketAddr, ToSocketAddrs};
+
+    use super::*;
+
+    #[test]
+    fn test_build_address() {
+        assert_eq!(build_address("127.0.0.1", 8902), "127.0.0.1:8902");
+        assert_eq!(build_address("localhost", 8902), "localhost:8902");
+        assert_eq!(build_address("::1", 8902), "[::1]:8902");
+        assert_eq!(build_address("[::1]", 8902), "[::1]:8902");
+        assert_eq!(build_address("fe80::1", "null"), "[fe80::1]:null");
+
+        let addr: SocketAddr = build_address(&unspecified_host(true).to_string(), 8902)
+            .parse()
+            .unwrap();
+        assert!(addr.is_ipv6());
+        let addr: SocketAddr = build_address(&unspecified_host(false).to_string(), 8902)
+            .parse()
+            .unwrap();
+        assert!(addr.is_ipv4());
+        assert!(build_address("::1", 8902).to_socket_addrs().is_ok());
+    }
+
+    #[test]
+    fn test_check_host() {
+        assert!(check_host("127.0.0.1").is_ok());
+        assert!(check_host("cnosdb-meta-0.cnosdb").is_ok());
+        assert!(check_host("::1").is_ok());
+        assert!(check_host("[2001:db8::1]").is_ok());
+        assert!(check_host("").is_err());
+        assert!(check_host("::1::2").is_err());
+        assert!(check_host("[127.0.0.1]").is_err());
+        assert!(check_host("127.0.0.1:8902").is_err());
+    }
+}
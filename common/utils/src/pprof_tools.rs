@@ -84,3 +84,19 @@ fn is_prof_enabled() -> Result<bool, String> {
     // safety: OPT_PROF variable, if present, is always a boolean value.
     Ok(unsafe { tikv_jemalloc_ctl::raw::read::<bool>(OPT_PROF).map_err(|e| e.to_string())? })
 }
+
+/// Bytes of the memory allocator, refreshed before reading.
+pub fn jemalloc_stats() -> Result<Vec<(&'static str, u64)>, String> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    epoch::advance().map_err(|e| e.to_string())?;
+    let read = |r: tikv_jemalloc_ctl::Result<usize>| r.map(|v| v as u64).map_err(|e| e.to_string());
+    Ok(vec![
+        ("allocated_bytes", read(stats::allocated::read())?),
+        ("active_bytes", read(stats::active::read())?),
+        ("resident_bytes", read(stats::resident::read())?),
+        ("mapped_bytes", read(stats::mapped::read())?),
+        ("retained_bytes", read(stats::retained::read())?),
+        ("metadata_bytes", read(stats::metadata::read())?),
+    ])
+}
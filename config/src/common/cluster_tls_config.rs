@@ -0,0 +1,101 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use macros::EnvKeys;
+use serde::{Deserialize, Serialize};
+
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::duration;
+
+/// Mutual TLS of the gRPC services of the data nodes, connected by the other
+/// data nodes and the meta servers. The raft between the meta servers and the
+/// HTTP API of the meta servers are not covered.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct ClusterTLSConfig {
+    /// Certificate of this node, presented to both the servers and the clients.
+    #[serde(default = "ClusterTLSConfig::default_certificate")]
+    pub certificate: String,
+    #[serde(default = "ClusterTLSConfig::default_private_key")]
+    pub private_key: String,
+    /// Certificate of the CA that signs the certificates of all nodes.
+    #[serde(default = "ClusterTLSConfig::default_ca_certificate")]
+    pub ca_certificate: String,
+    /// Name to verify the certificates of the servers with, the host of the
+    /// address is used if not set.
+    #[serde(default = "ClusterTLSConfig::default_domain_name")]
+    pub domain_name: Option<String>,
+    /// Interval to check if the files are modified and reload them.
+    #[serde(
+        with = "duration",
+        default = "ClusterTLSConfig::default_reload_interval"
+    )]
+    pub reload_interval: Duration,
+}
+
+impl ClusterTLSConfig {
+    fn default_certificate() -> String {
+        "/etc/cnosdb/tls/node.crt".to_string()
+    }
+
+    fn default_private_key() -> String {
+        "/etc/cnosdb/tls/node.key".to_string()
+    }
+
+    fn default_ca_certificate() -> String {
+        "/etc/cnosdb/tls/ca.crt".to_string()
+    }
+
+    fn default_domain_name() -> Option<String> {
+        None
+    }
+
+    fn default_reload_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+}
+
+impl Default for ClusterTLSConfig {
+    fn default() -> Self {
+        Self {
+            certificate: Self::default_certificate(),
+            private_key: Self::default_private_key(),
+            ca_certificate: Self::default_ca_certificate(),
+            domain_name: Self::default_domain_name(),
+            reload_interval: Self::default_reload_interval(),
+        }
+    }
+}
+
+impl CheckConfig for ClusterTLSConfig {
+    fn check(&self, _: &crate::tskv::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("security.cluster_tls_config".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        for (item, path) in [
+            ("certificate", &self.certificate),
+            ("private_key", &self.private_key),
+            ("ca_certificate", &self.ca_certificate),
+        ] {
+            if path.is_empty() {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: item.to_string(),
+                    message: format!("'{item}' is empty"),
+                });
+            }
+        }
+        if self.reload_interval.is_zero() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "reload_interval".to_string(),
+                message: "'reload_interval' must be greater than 0".to_string(),
+            });
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
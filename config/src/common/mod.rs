@@ -1,5 +1,7 @@
+mod cluster_tls_config;
 mod limiter_config;
 mod log_config;
 
+pub use cluster_tls_config::*;
 pub use limiter_config::*;
 pub use log_config::*;
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
 use macros::EnvKeys;
@@ -44,6 +45,12 @@ where
     }
 }
 
+impl<K, V> EnvKeys for HashMap<K, V> {
+    fn env_keys() -> Vec<String> {
+        vec![]
+    }
+}
+
 impl<T> EnvKeys for Option<T>
 where
     T: EnvKeys,
@@ -8,6 +8,15 @@ pub struct MetaClusterConfig {
     pub raft_logs_to_keep: u64,
     pub install_snapshot_timeout: u64,
     pub send_append_entries_timeout: u64,
+    /// The engine storing the raft entries, "lmdb" or "redb".
+    #[serde(default = "MetaClusterConfig::default_entry_storage")]
+    pub entry_storage: String,
+}
+
+impl MetaClusterConfig {
+    fn default_entry_storage() -> String {
+        "lmdb".to_string()
+    }
 }
 
 impl Default for MetaClusterConfig {
@@ -18,6 +27,7 @@ impl Default for MetaClusterConfig {
             raft_logs_to_keep: 10000,
             install_snapshot_timeout: 3600 * 1000,
             send_append_entries_timeout: 5 * 1000,
+            entry_storage: MetaClusterConfig::default_entry_storage(),
         }
     }
 }
@@ -9,6 +9,8 @@ pub struct MetaGlobalConfig {
     pub listen_port: u16,
     pub grpc_enable_gzip: bool,
     pub data_path: String,
+    #[serde(default)]
+    pub listen_dual_stack: bool,
 }
 
 impl Default for MetaGlobalConfig {
@@ -20,6 +22,7 @@ impl Default for MetaGlobalConfig {
             listen_port: 8901,
             grpc_enable_gzip: false,
             data_path: String::from("/var/lib/cnosdb/meta"),
+            listen_dual_stack: false,
         }
     }
 }
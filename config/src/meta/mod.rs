@@ -1,6 +1,7 @@
 mod cluster_config;
 mod global_config;
 mod heart_beat_config;
+mod security_config;
 mod sys_config;
 
 use std::collections::HashMap;
@@ -16,6 +17,7 @@ use serde::{Deserialize, Serialize};
 use crate::common::LogConfig;
 use crate::meta::cluster_config::MetaClusterConfig;
 use crate::meta::global_config::MetaGlobalConfig;
+pub use crate::meta::security_config::MetaSecurityConfig;
 use crate::meta::sys_config::SysConfig;
 use crate::EnvKeys as _;
 
@@ -33,6 +35,8 @@ pub struct Opt {
     pub log: LogConfig,
     #[serde(default)]
     pub heartbeat: HeartBeatConfig,
+    #[serde(default)]
+    pub security: MetaSecurityConfig,
 }
 
 impl Opt {
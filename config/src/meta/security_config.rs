@@ -0,0 +1,11 @@
+use macros::EnvKeys;
+use serde::{Deserialize, Serialize};
+
+use crate::common::ClusterTLSConfig;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct MetaSecurityConfig {
+    /// Mutual TLS of the gRPC connections to the data nodes, the same as the
+    /// `security.cluster_tls_config` of the data nodes.
+    pub cluster_tls_config: Option<ClusterTLSConfig>,
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use macros::EnvKeys;
+use serde::{Deserialize, Serialize};
+
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::bytes_num;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct AuditConfig {
+    #[serde(default = "AuditConfig::default_enable")]
+    pub enable: bool,
+    #[serde(default = "AuditConfig::default_write_to_table")]
+    pub write_to_table: bool,
+    #[serde(default = "AuditConfig::default_write_to_file")]
+    pub write_to_file: bool,
+    #[serde(default = "AuditConfig::default_path")]
+    pub path: String,
+    #[serde(with = "bytes_num", default = "AuditConfig::default_max_file_size")]
+    pub max_file_size: u64,
+    #[serde(default = "AuditConfig::default_max_file_count")]
+    pub max_file_count: usize,
+    #[serde(default = "AuditConfig::default_buffer_size")]
+    pub buffer_size: usize,
+}
+
+impl AuditConfig {
+    fn default_enable() -> bool {
+        false
+    }
+
+    fn default_write_to_table() -> bool {
+        true
+    }
+
+    fn default_write_to_file() -> bool {
+        false
+    }
+
+    fn default_path() -> String {
+        "/var/log/cnosdb/audit".to_string()
+    }
+
+    fn default_max_file_size() -> u64 {
+        128 * 1024 * 1024
+    }
+
+    fn default_max_file_count() -> usize {
+        10
+    }
+
+    fn default_buffer_size() -> usize {
+        10240
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enable: Self::default_enable(),
+            write_to_table: Self::default_write_to_table(),
+            write_to_file: Self::default_write_to_file(),
+            path: Self::default_path(),
+            max_file_size: Self::default_max_file_size(),
+            max_file_count: Self::default_max_file_count(),
+            buffer_size: Self::default_buffer_size(),
+        }
+    }
+}
+
+impl CheckConfig for AuditConfig {
+    fn check(&self, _: &super::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("audit".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.enable && !self.write_to_table && !self.write_to_file {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "enable".to_string(),
+                message: "audit is enabled but neither 'write_to_table' nor 'write_to_file' is set"
+                    .to_string(),
+            })
+        }
+        if self.write_to_file && self.path.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "path".to_string(),
+                message: "'path' is empty".to_string(),
+            })
+        }
+        if self.max_file_count == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "max_file_count".to_string(),
+                message: "'max_file_count' must be greater than 0".to_string(),
+            })
+        }
+        if self.buffer_size == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "buffer_size".to_string(),
+                message: "'buffer_size' must be greater than 0".to_string(),
+            })
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
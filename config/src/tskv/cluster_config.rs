@@ -4,7 +4,7 @@ use std::time::Duration;
 use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
 
-use crate::check::{CheckConfig, CheckConfigResult};
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
 use crate::codec::{bytes_num, duration};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
@@ -12,6 +12,18 @@ pub struct ClusterConfig {
     #[serde(default = "ClusterConfig::default_raft_logs_to_keep")]
     pub raft_logs_to_keep: u64,
 
+    #[serde(
+        with = "bytes_num",
+        default = "ClusterConfig::default_raft_logs_disk_budget"
+    )]
+    pub raft_logs_disk_budget: u64,
+
+    #[serde(default = "ClusterConfig::default_raft_logs_min_to_keep")]
+    pub raft_logs_min_to_keep: u64,
+
+    #[serde(default = "ClusterConfig::default_purge_replicated_logs")]
+    pub purge_replicated_logs: bool,
+
     #[serde(
         with = "duration",
         default = "ClusterConfig::default_snapshot_holding_time"
@@ -24,6 +36,12 @@ pub struct ClusterConfig {
     )]
     pub trigger_snapshot_interval: Duration,
 
+    #[serde(
+        with = "bytes_num",
+        default = "ClusterConfig::default_snapshot_logs_size"
+    )]
+    pub snapshot_logs_size: u64,
+
     #[serde(
         with = "bytes_num",
         default = "ClusterConfig::default_lmdb_max_map_size"
@@ -47,6 +65,30 @@ pub struct ClusterConfig {
         default = "ClusterConfig::default_install_snapshot_timeout"
     )]
     pub install_snapshot_timeout: Duration, //ms
+
+    #[serde(default = "ClusterConfig::default_max_concurrent_snapshot_transfers")]
+    pub max_concurrent_snapshot_transfers: usize,
+
+    #[serde(default = "ClusterConfig::default_snapshot_download_parallelism")]
+    pub snapshot_download_parallelism: usize,
+
+    #[serde(
+        with = "bytes_num",
+        default = "ClusterConfig::default_snapshot_write_bandwidth"
+    )]
+    pub snapshot_write_bandwidth: u64,
+
+    #[serde(
+        with = "bytes_num",
+        default = "ClusterConfig::default_snapshot_send_bandwidth"
+    )]
+    pub snapshot_send_bandwidth: u64,
+
+    #[serde(default = "ClusterConfig::default_leader_transfer_max_log_lag")]
+    pub leader_transfer_max_log_lag: u64,
+
+    #[serde(default = "ClusterConfig::default_append_entries_max_batch")]
+    pub append_entries_max_batch: usize,
 }
 
 impl ClusterConfig {
@@ -54,6 +96,18 @@ impl ClusterConfig {
         5000
     }
 
+    fn default_raft_logs_disk_budget() -> u64 {
+        0
+    }
+
+    fn default_raft_logs_min_to_keep() -> u64 {
+        1000
+    }
+
+    fn default_purge_replicated_logs() -> bool {
+        true
+    }
+
     fn default_snapshot_holding_time() -> Duration {
         Duration::from_secs(3600)
     }
@@ -62,6 +116,10 @@ impl ClusterConfig {
         Duration::from_secs(600)
     }
 
+    fn default_snapshot_logs_size() -> u64 {
+        0
+    }
+
     fn default_lmdb_max_map_size() -> u64 {
         1024 * 1024 * 1024
     }
@@ -77,26 +135,69 @@ impl ClusterConfig {
     fn default_install_snapshot_timeout() -> Duration {
         Duration::from_millis(3_600_000)
     }
+
+    fn default_max_concurrent_snapshot_transfers() -> usize {
+        8
+    }
+
+    fn default_snapshot_download_parallelism() -> usize {
+        4
+    }
+
+    fn default_snapshot_write_bandwidth() -> u64 {
+        0
+    }
+
+    fn default_snapshot_send_bandwidth() -> u64 {
+        0
+    }
+
+    fn default_leader_transfer_max_log_lag() -> u64 {
+        1000
+    }
+
+    fn default_append_entries_max_batch() -> usize {
+        64
+    }
 }
 
 impl Default for ClusterConfig {
     fn default() -> Self {
         Self {
             raft_logs_to_keep: ClusterConfig::default_raft_logs_to_keep(),
+            raft_logs_disk_budget: ClusterConfig::default_raft_logs_disk_budget(),
+            raft_logs_min_to_keep: ClusterConfig::default_raft_logs_min_to_keep(),
+            purge_replicated_logs: ClusterConfig::default_purge_replicated_logs(),
             snapshot_holding_time: ClusterConfig::default_snapshot_holding_time(),
             lmdb_max_map_size: ClusterConfig::default_lmdb_max_map_size(),
             heartbeat_interval: ClusterConfig::default_heartbeat_interval(),
             trigger_snapshot_interval: ClusterConfig::default_trigger_snapshot_interval(),
+            snapshot_logs_size: ClusterConfig::default_snapshot_logs_size(),
             send_append_entries_timeout: ClusterConfig::default_send_append_entries_timeout(),
             install_snapshot_timeout: ClusterConfig::default_install_snapshot_timeout(),
+            max_concurrent_snapshot_transfers:
+                ClusterConfig::default_max_concurrent_snapshot_transfers(),
+            snapshot_download_parallelism: ClusterConfig::default_snapshot_download_parallelism(),
+            snapshot_write_bandwidth: ClusterConfig::default_snapshot_write_bandwidth(),
+            snapshot_send_bandwidth: ClusterConfig::default_snapshot_send_bandwidth(),
+            leader_transfer_max_log_lag: ClusterConfig::default_leader_transfer_max_log_lag(),
+            append_entries_max_batch: ClusterConfig::default_append_entries_max_batch(),
         }
     }
 }
 
 impl CheckConfig for ClusterConfig {
     fn check(&self, _: &super::Config) -> Option<CheckConfigResult> {
-        let _config_name = Arc::new("cluster".to_string());
-        let ret = CheckConfigResult::default();
+        let config_name = Arc::new("cluster".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.append_entries_max_batch == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "append_entries_max_batch".to_string(),
+                message: "'append_entries_max_batch' must be greater than 0".to_string(),
+            });
+        }
 
         if ret.is_empty() {
             None
@@ -1,9 +1,11 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
 
 use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
 pub struct GlobalConfig {
@@ -17,6 +19,23 @@ pub struct GlobalConfig {
     pub store_metrics: bool,
     #[serde(default = "GlobalConfig::default_pre_create_bucket")]
     pub pre_create_bucket: bool,
+    #[serde(default = "GlobalConfig::default_listen_dual_stack")]
+    pub listen_dual_stack: bool,
+    #[serde(
+        with = "duration",
+        default = "GlobalConfig::default_freshness_probe_interval"
+    )]
+    pub freshness_probe_interval: Duration,
+    #[serde(
+        with = "duration",
+        default = "GlobalConfig::default_freshness_probe_timeout"
+    )]
+    pub freshness_probe_timeout: Duration,
+    #[serde(
+        with = "duration",
+        default = "GlobalConfig::default_dropped_db_retention"
+    )]
+    pub dropped_db_retention: Duration,
 }
 
 impl GlobalConfig {
@@ -39,6 +58,22 @@ impl GlobalConfig {
     fn default_pre_create_bucket() -> bool {
         false
     }
+
+    fn default_listen_dual_stack() -> bool {
+        false
+    }
+
+    fn default_freshness_probe_interval() -> Duration {
+        Duration::ZERO
+    }
+
+    fn default_freshness_probe_timeout() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn default_dropped_db_retention() -> Duration {
+        Duration::from_secs(7 * 24 * 60 * 60)
+    }
 }
 
 impl Default for GlobalConfig {
@@ -49,6 +84,10 @@ impl Default for GlobalConfig {
             cluster_name: GlobalConfig::default_cluster_name(),
             store_metrics: GlobalConfig::default_store_metrics(),
             pre_create_bucket: GlobalConfig::default_pre_create_bucket(),
+            listen_dual_stack: GlobalConfig::default_listen_dual_stack(),
+            freshness_probe_interval: GlobalConfig::default_freshness_probe_interval(),
+            freshness_probe_timeout: GlobalConfig::default_freshness_probe_timeout(),
+            dropped_db_retention: GlobalConfig::default_dropped_db_retention(),
         }
     }
 }
@@ -60,12 +99,28 @@ impl CheckConfig for GlobalConfig {
 
         if self.cluster_name.is_empty() {
             ret.add_warn(CheckConfigItemResult {
-                config: config_name,
+                config: config_name.clone(),
                 item: "name".to_string(),
                 message: "'name' is empty".to_string(),
             });
         }
 
+        if let Err(e) = utils::net::check_host(&self.host) {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "host".to_string(),
+                message: e,
+            });
+        }
+
+        if !self.freshness_probe_interval.is_zero() && self.freshness_probe_timeout.is_zero() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "freshness_probe_timeout".to_string(),
+                message: "'freshness_probe_timeout' must be greater than 0".to_string(),
+            });
+        }
+
         if ret.is_empty() {
             None
         } else {
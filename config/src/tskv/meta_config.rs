@@ -29,6 +29,10 @@ pub struct MetaConfig {
     pub cluster_schema_cache_size: u64,
     #[serde(default = "MetaConfig::default_system_database_replica")]
     pub system_database_replica: u64,
+    #[serde(with = "duration", default = "MetaConfig::default_max_clock_skew")]
+    pub max_clock_skew: Duration,
+    #[serde(default = "MetaConfig::default_reject_write_on_clock_skew")]
+    pub reject_write_on_clock_skew: bool,
 }
 
 impl MetaConfig {
@@ -51,6 +55,14 @@ impl MetaConfig {
     pub fn default_system_database_replica() -> u64 {
         3
     }
+
+    fn default_max_clock_skew() -> Duration {
+        Duration::from_millis(500)
+    }
+
+    fn default_reject_write_on_clock_skew() -> bool {
+        false
+    }
 }
 
 impl Default for MetaConfig {
@@ -61,6 +73,8 @@ impl Default for MetaConfig {
             usage_schema_cache_size: MetaConfig::default_usage_schema_cache_size(),
             cluster_schema_cache_size: MetaConfig::default_cluster_schema_cache_size(),
             system_database_replica: MetaConfig::default_system_database_replica(),
+            max_clock_skew: MetaConfig::default_max_clock_skew(),
+            reject_write_on_clock_skew: MetaConfig::default_reject_write_on_clock_skew(),
         }
     }
 }
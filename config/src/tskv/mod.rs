@@ -1,3 +1,4 @@
+mod audit_config;
 mod cache_config;
 mod cluster_config;
 mod deployment_config;
@@ -13,6 +14,7 @@ mod wal_config;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+pub use audit_config::*;
 pub use cache_config::*;
 pub use cluster_config::*;
 pub use deployment_config::*;
@@ -82,6 +84,9 @@ pub struct Config {
 
     #[serde(default = "Default::default")]
     pub trace: TraceConfig,
+
+    #[serde(default = "Default::default")]
+    pub audit: AuditConfig,
 }
 
 impl Config {
@@ -169,6 +174,12 @@ pub fn check_config(path: impl AsRef<Path>, show_warnings: bool) {
             if let Some(c) = cfg.cluster.check(&cfg) {
                 check_results.add_all(c)
             }
+            if let Some(c) = cfg.trace.check(&cfg) {
+                check_results.add_all(c)
+            }
+            if let Some(c) = cfg.audit.check(&cfg) {
+                check_results.add_all(c)
+            }
 
             check_results.introspect();
             check_results.show_warnings = show_warnings;
@@ -27,6 +27,24 @@ pub struct QueryConfig {
     pub stream_executor_cpu: usize,
     #[serde(with = "duration", default = "QueryConfig::default_sql_record_timeout")]
     pub sql_record_timeout: Duration,
+    #[serde(with = "bytes_num", default = "QueryConfig::default_max_query_memory")]
+    pub max_query_memory: u64,
+    #[serde(default = "QueryConfig::default_coalesce_batch_rows")]
+    pub coalesce_batch_rows: usize,
+    #[serde(
+        with = "bytes_num",
+        default = "QueryConfig::default_coalesce_batch_bytes"
+    )]
+    pub coalesce_batch_bytes: u64,
+    #[serde(default = "QueryConfig::default_shuffle_join_partitions")]
+    pub shuffle_join_partitions: usize,
+    /// How long a follower is waited for to catch up with the ReadIndex of the
+    /// leader in a follower read, the leader is read after it.
+    #[serde(
+        with = "duration",
+        default = "QueryConfig::default_follower_read_timeout"
+    )]
+    pub follower_read_timeout: Duration,
 }
 
 impl QueryConfig {
@@ -65,6 +83,26 @@ impl QueryConfig {
     fn default_sql_record_timeout() -> Duration {
         Duration::from_secs(10)
     }
+
+    fn default_max_query_memory() -> u64 {
+        0
+    }
+
+    fn default_coalesce_batch_rows() -> usize {
+        4096
+    }
+
+    fn default_coalesce_batch_bytes() -> u64 {
+        4 * 1024 * 1024
+    }
+
+    fn default_shuffle_join_partitions() -> usize {
+        0
+    }
+
+    fn default_follower_read_timeout() -> Duration {
+        Duration::from_millis(1_000)
+    }
 }
 
 impl Default for QueryConfig {
@@ -79,6 +117,11 @@ impl Default for QueryConfig {
             stream_trigger_cpu: Self::default_stream_trigger_cpu(),
             stream_executor_cpu: Self::default_stream_executor_cpu(),
             sql_record_timeout: Self::default_sql_record_timeout(),
+            max_query_memory: Self::default_max_query_memory(),
+            coalesce_batch_rows: Self::default_coalesce_batch_rows(),
+            coalesce_batch_bytes: Self::default_coalesce_batch_bytes(),
+            shuffle_join_partitions: Self::default_shuffle_join_partitions(),
+            follower_read_timeout: Self::default_follower_read_timeout(),
         }
     }
 }
@@ -4,10 +4,14 @@ use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
 
 use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::common::ClusterTLSConfig;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
 pub struct SecurityConfig {
     pub tls_config: Option<TLSConfig>,
+    /// Mutual TLS of the gRPC services of the data nodes, the connections to
+    /// the meta servers are not covered.
+    pub cluster_tls_config: Option<ClusterTLSConfig>,
 }
 
 impl CheckConfig for SecurityConfig {
@@ -19,11 +23,16 @@ impl CheckConfig for SecurityConfig {
                 ret.add_all(r);
             }
         }
+        if let Some(ref cluster_tls_config) = self.cluster_tls_config {
+            if let Some(r) = cluster_tls_config.check(all_config) {
+                ret.add_all(r);
+            }
+        }
 
         if ret.is_empty() {
-            Some(ret)
-        } else {
             None
+        } else {
+            Some(ret)
         }
     }
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
+use utils::net::build_address;
 
 use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
 
@@ -14,6 +15,8 @@ pub struct ServiceConfig {
     pub grpc_listen_port: Option<u16>,
     #[serde(default = "ServiceConfig::default_grpc_enable_gzip")]
     pub grpc_enable_gzip: bool,
+    #[serde(default = "ServiceConfig::default_grpc_enable_checksum")]
+    pub grpc_enable_checksum: bool,
     #[serde(default = "ServiceConfig::default_flight_rpc_listen_port")]
     pub flight_rpc_listen_port: Option<u16>,
     #[serde(default = "ServiceConfig::default_tcp_listen_port")]
@@ -22,6 +25,8 @@ pub struct ServiceConfig {
     pub enable_report: bool,
     #[serde(default = "ServiceConfig::default_jaeger_rpc_listen_port")]
     pub jaeger_rpc_listen_port: Option<u16>,
+    #[serde(default = "ServiceConfig::default_otlp_listen_port")]
+    pub otlp_listen_port: Option<u16>,
 }
 
 impl ServiceConfig {
@@ -37,6 +42,10 @@ impl ServiceConfig {
         false
     }
 
+    fn default_grpc_enable_checksum() -> bool {
+        false
+    }
+
     fn default_flight_rpc_listen_port() -> Option<u16> {
         None
     }
@@ -52,6 +61,10 @@ impl ServiceConfig {
     fn default_jaeger_rpc_listen_port() -> Option<u16> {
         None
     }
+
+    fn default_otlp_listen_port() -> Option<u16> {
+        None
+    }
 }
 
 impl Default for ServiceConfig {
@@ -60,10 +73,12 @@ impl Default for ServiceConfig {
             http_listen_port: ServiceConfig::default_http_listen_port(),
             grpc_listen_port: ServiceConfig::default_grpc_listen_port(),
             grpc_enable_gzip: ServiceConfig::default_grpc_enable_gzip(),
+            grpc_enable_checksum: ServiceConfig::default_grpc_enable_checksum(),
             flight_rpc_listen_port: ServiceConfig::default_flight_rpc_listen_port(),
             tcp_listen_port: ServiceConfig::default_tcp_listen_port(),
             enable_report: ServiceConfig::default_enable_report(),
             jaeger_rpc_listen_port: ServiceConfig::default_jaeger_rpc_listen_port(),
+            otlp_listen_port: ServiceConfig::default_otlp_listen_port(),
         }
     }
 }
@@ -99,7 +114,7 @@ impl CheckConfig for ServiceConfig {
         let mut ret = CheckConfigResult::default();
 
         if let Some(port) = self.http_listen_port {
-            let default_http_addr = format!("{}:{}", &config.global.host, port);
+            let default_http_addr = build_address(&config.global.host, port);
             if let Err(e) = default_http_addr.to_socket_addrs() {
                 ret.add_error(CheckConfigItemResult {
                     config: config_name.clone(),
@@ -110,7 +125,7 @@ impl CheckConfig for ServiceConfig {
         }
 
         if let Some(port) = self.grpc_listen_port {
-            let default_grpc_addr = format!("{}:{}", &config.global.host, port);
+            let default_grpc_addr = build_address(&config.global.host, port);
             if let Err(e) = default_grpc_addr.to_socket_addrs() {
                 ret.add_error(CheckConfigItemResult {
                     config: config_name.clone(),
@@ -121,7 +136,7 @@ impl CheckConfig for ServiceConfig {
         }
 
         if let Some(port) = self.flight_rpc_listen_port {
-            let default_flight_rpc_addr = format!("{}:{}", &config.global.host, port);
+            let default_flight_rpc_addr = build_address(&config.global.host, port);
             if let Err(e) = default_flight_rpc_addr.to_socket_addrs() {
                 ret.add_error(CheckConfigItemResult {
                     config: config_name.clone(),
@@ -132,7 +147,7 @@ impl CheckConfig for ServiceConfig {
         }
 
         if let Some(port) = self.tcp_listen_port {
-            let default_tcp_addr = format!("{}:{}", &config.global.host, port);
+            let default_tcp_addr = build_address(&config.global.host, port);
             if let Err(e) = default_tcp_addr.to_socket_addrs() {
                 ret.add_error(CheckConfigItemResult {
                     config: config_name.clone(),
@@ -142,6 +157,17 @@ impl CheckConfig for ServiceConfig {
             }
         }
 
+        if let Some(port) = self.otlp_listen_port {
+            let default_otlp_addr = build_address(&config.global.host, port);
+            if let Err(e) = default_otlp_addr.to_socket_addrs() {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: default_otlp_addr,
+                    message: format!("Cannot resolve 'otlp_listen_addr': {}", e),
+                });
+            }
+        }
+
         if ret.is_empty() {
             None
         } else {
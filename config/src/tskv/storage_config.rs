@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -45,9 +46,36 @@ pub struct StorageConfig {
     )]
     pub max_compact_size: u64,
 
+    #[serde(
+        with = "bytes_num",
+        default = "StorageConfig::default_compact_small_file_size"
+    )]
+    pub compact_small_file_size: u64,
+
+    #[serde(default = "StorageConfig::default_compact_small_file_watermarks")]
+    pub compact_small_file_watermarks: Vec<u32>,
+
+    #[serde(
+        with = "duration",
+        default = "StorageConfig::default_compact_small_file_cooldown"
+    )]
+    pub compact_small_file_cooldown: Duration,
+
     #[serde(default = "StorageConfig::default_max_concurrent_compaction")]
     pub max_concurrent_compaction: u16,
 
+    #[serde(
+        with = "bytes_num",
+        default = "StorageConfig::default_compaction_io_rate_limit"
+    )]
+    pub compaction_io_rate_limit: u64,
+
+    #[serde(
+        with = "duration",
+        default = "StorageConfig::default_compaction_throttle_query_latency"
+    )]
+    pub compaction_throttle_query_latency: Duration,
+
     #[serde(default = "StorageConfig::default_collect_compaction_metrics")]
     pub collect_compaction_metrics: bool,
 
@@ -74,6 +102,48 @@ pub struct StorageConfig {
 
     #[serde(default = "StorageConfig::default_tsm_meta_compress")]
     pub tsm_meta_compress: String,
+
+    #[serde(default = "StorageConfig::default_zstd_compress_level")]
+    pub zstd_compress_level: i32,
+
+    #[serde(
+        with = "duration",
+        default = "StorageConfig::default_write_dedup_window"
+    )]
+    pub write_dedup_window: Duration,
+
+    #[serde(default = "StorageConfig::default_write_dedup_bloom_filter_bits")]
+    pub write_dedup_bloom_filter_bits: u64,
+
+    #[serde(
+        with = "duration",
+        default = "StorageConfig::default_warmup_recent_duration"
+    )]
+    pub warmup_recent_duration: Duration,
+
+    #[serde(
+        with = "bytes_num",
+        default = "StorageConfig::default_warmup_memory_budget"
+    )]
+    pub warmup_memory_budget: u64,
+
+    #[serde(default = "StorageConfig::default_series_creation_alert_threshold")]
+    pub series_creation_alert_threshold: u64,
+
+    #[serde(default = "StorageConfig::default_enable_write_subscription")]
+    pub enable_write_subscription: bool,
+
+    #[serde(default = "StorageConfig::default_subscription_retained_entries")]
+    pub subscription_retained_entries: usize,
+
+    #[serde(with = "duration", default = "StorageConfig::default_tiering_age")]
+    pub tiering_age: Duration,
+
+    #[serde(default = "StorageConfig::default_tiering_path")]
+    pub tiering_path: String,
+
+    #[serde(default = "StorageConfig::default_tiering_options")]
+    pub tiering_options: HashMap<String, String>,
 }
 
 impl StorageConfig {
@@ -114,6 +184,18 @@ impl StorageConfig {
         2 * 1024 * 1024 * 1024
     }
 
+    fn default_compact_small_file_size() -> u64 {
+        2 * 1024 * 1024
+    }
+
+    fn default_compact_small_file_watermarks() -> Vec<u32> {
+        vec![32, 16, 16, 16, 16]
+    }
+
+    fn default_compact_small_file_cooldown() -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+
     fn default_reserve_space() -> u64 {
         0
     }
@@ -122,6 +204,14 @@ impl StorageConfig {
         4
     }
 
+    fn default_compaction_io_rate_limit() -> u64 {
+        0
+    }
+
+    fn default_compaction_throttle_query_latency() -> Duration {
+        Duration::from_secs(0)
+    }
+
     fn default_collect_compaction_metrics() -> bool {
         false
     }
@@ -146,6 +236,50 @@ impl StorageConfig {
         "null".to_string()
     }
 
+    fn default_zstd_compress_level() -> i32 {
+        3
+    }
+
+    fn default_write_dedup_window() -> Duration {
+        Duration::from_secs(0)
+    }
+
+    fn default_write_dedup_bloom_filter_bits() -> u64 {
+        8 * 1024 * 1024
+    }
+
+    fn default_warmup_recent_duration() -> Duration {
+        Duration::from_secs(0)
+    }
+
+    fn default_warmup_memory_budget() -> u64 {
+        256 * 1024 * 1024
+    }
+
+    fn default_series_creation_alert_threshold() -> u64 {
+        10000
+    }
+
+    fn default_enable_write_subscription() -> bool {
+        false
+    }
+
+    fn default_subscription_retained_entries() -> usize {
+        1024
+    }
+
+    fn default_tiering_age() -> Duration {
+        Duration::from_secs(0)
+    }
+
+    fn default_tiering_path() -> String {
+        String::new()
+    }
+
+    fn default_tiering_options() -> HashMap<String, String> {
+        HashMap::new()
+    }
+
     pub fn introspect(&mut self) {
         // Unit of storage.compact_trigger_cold_duration is seconds
         self.compact_trigger_cold_duration =
@@ -165,7 +299,12 @@ impl Default for StorageConfig {
             compact_trigger_file_num: Self::default_compact_trigger_file_num(),
             compact_trigger_cold_duration: Self::default_compact_trigger_cold_duration(),
             max_compact_size: Self::default_max_compact_size(),
+            compact_small_file_size: Self::default_compact_small_file_size(),
+            compact_small_file_watermarks: Self::default_compact_small_file_watermarks(),
+            compact_small_file_cooldown: Self::default_compact_small_file_cooldown(),
             max_concurrent_compaction: Self::default_max_concurrent_compaction(),
+            compaction_io_rate_limit: Self::default_compaction_io_rate_limit(),
+            compaction_throttle_query_latency: Self::default_compaction_throttle_query_latency(),
             collect_compaction_metrics: Self::default_collect_compaction_metrics(),
             strict_write: Self::default_strict_write(),
             reserve_space: Self::default_reserve_space(),
@@ -173,6 +312,17 @@ impl Default for StorageConfig {
             max_datablock_size: Self::default_max_datablock_size(),
             index_cache_capacity: Self::default_index_cache_capacity(),
             tsm_meta_compress: Self::default_tsm_meta_compress(),
+            zstd_compress_level: Self::default_zstd_compress_level(),
+            write_dedup_window: Self::default_write_dedup_window(),
+            write_dedup_bloom_filter_bits: Self::default_write_dedup_bloom_filter_bits(),
+            warmup_recent_duration: Self::default_warmup_recent_duration(),
+            warmup_memory_budget: Self::default_warmup_memory_budget(),
+            series_creation_alert_threshold: Self::default_series_creation_alert_threshold(),
+            enable_write_subscription: Self::default_enable_write_subscription(),
+            subscription_retained_entries: Self::default_subscription_retained_entries(),
+            tiering_age: Self::default_tiering_age(),
+            tiering_path: Self::default_tiering_path(),
+            tiering_options: Self::default_tiering_options(),
         }
     }
 }
@@ -232,6 +382,38 @@ impl CheckConfig for StorageConfig {
                 message: "'max_compact_size' maybe too small(less than 1M)".to_string(),
             });
         }
+        if self.compact_small_file_watermarks.len() > self.max_level as usize + 1 {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "compact_small_file_watermarks".to_string(),
+                message: "'compact_small_file_watermarks' has more items than levels".to_string(),
+            });
+        }
+
+        if !self.write_dedup_window.is_zero() && self.write_dedup_bloom_filter_bits < 1024 * 8 {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "write_dedup_bloom_filter_bits".to_string(),
+                message: "'write_dedup_bloom_filter_bits' maybe too small(less than 8K)"
+                    .to_string(),
+            });
+        }
+
+        if !(-5..=22).contains(&self.zstd_compress_level) {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "zstd_compress_level".to_string(),
+                message: "'zstd_compress_level' must be between -5 and 22".to_string(),
+            });
+        }
+
+        if !self.tiering_age.is_zero() && self.tiering_path.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "tiering_path".to_string(),
+                message: "'tiering_path' is required if 'tiering_age' is not 0".to_string(),
+            });
+        }
 
         if self.tsm_meta_compress != "zstd"
             || self.tsm_meta_compress != "snappy"
@@ -1,11 +1,13 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
 
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
 use crate::codec::duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, EnvKeys)]
 pub struct TraceConfig {
     #[serde(default = "TraceConfig::default_auto_generate_span")]
     pub auto_generate_span: bool,
@@ -22,6 +24,8 @@ pub struct TraceConfig {
     pub otlp_endpoint: Option<String>,
     #[serde(default = "TraceConfig::default_trace_log_path")]
     pub trace_log_path: Option<String>,
+    #[serde(default = "TraceConfig::default_sampling")]
+    pub sampling: TraceSamplingConfig,
 }
 
 impl TraceConfig {
@@ -48,6 +52,10 @@ impl TraceConfig {
     fn default_trace_log_path() -> Option<String> {
         None
     }
+
+    fn default_sampling() -> TraceSamplingConfig {
+        TraceSamplingConfig::default()
+    }
 }
 
 impl Default for TraceConfig {
@@ -59,13 +67,122 @@ impl Default for TraceConfig {
             batch_report_max_spans: Self::default_batch_report_max_spans(),
             otlp_endpoint: Self::default_otlp_endpoint(),
             trace_log_path: Self::default_trace_log_path(),
+            sampling: Self::default_sampling(),
         }
     }
 }
 
+impl CheckConfig for TraceConfig {
+    fn check(&self, _: &super::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("trace".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if !(0.0..=1.0).contains(&self.sampling.ratio) {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "sampling.ratio".to_string(),
+                message: "'sampling.ratio' must be in range [0, 1]".to_string(),
+            });
+        }
+        for (i, rule) in self.sampling.rules.iter().enumerate() {
+            if !(0.0..=1.0).contains(&rule.ratio) {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: format!("sampling.rules[{i}].ratio"),
+                    message: "'ratio' of sampling rule must be in range [0, 1]".to_string(),
+                });
+            }
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
+
+/// Sampling of the traces started by cnosdb itself (see `auto_generate_span`),
+/// traces continued from the span context of a client are always reported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, EnvKeys)]
+pub struct TraceSamplingConfig {
+    /// Default sampling ratio in range [0, 1] if no rule matches.
+    #[serde(default = "TraceSamplingConfig::default_ratio")]
+    pub ratio: f64,
+    /// Statements slower than this are always sampled, zero means disabled.
+    #[serde(
+        with = "duration",
+        default = "TraceSamplingConfig::default_slow_threshold"
+    )]
+    pub slow_threshold: Duration,
+    /// The first matched rule decides the sampling ratio of a trace.
+    #[serde(default = "TraceSamplingConfig::default_rules")]
+    pub rules: Vec<TraceSamplingRule>,
+}
+
+impl TraceSamplingConfig {
+    fn default_ratio() -> f64 {
+        1.0
+    }
+
+    fn default_slow_threshold() -> Duration {
+        Duration::ZERO
+    }
+
+    fn default_rules() -> Vec<TraceSamplingRule> {
+        vec![]
+    }
+}
+
+impl Default for TraceSamplingConfig {
+    fn default() -> Self {
+        Self {
+            ratio: Self::default_ratio(),
+            slow_threshold: Self::default_slow_threshold(),
+            rules: Self::default_rules(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, EnvKeys)]
+pub struct TraceSamplingRule {
+    /// Matches all tenants if not set.
+    #[serde(default)]
+    pub tenant: Option<String>,
+    /// Type of statement, 'query' or 'write', matches all types if not set.
+    #[serde(default)]
+    pub statement: Option<String>,
+    pub ratio: f64,
+}
+
 #[test]
 fn test_serialize() {
     let trace_config = TraceConfig::default();
     let res = toml::to_string_pretty(&trace_config).unwrap();
     println!("{res}");
 }
+
+#[test]
+fn test_parse_sampling() {
+    let config_str = r#"
+        auto_generate_span = true
+        [sampling]
+        ratio = 0.01
+        slow_threshold = "1s"
+        [[sampling.rules]]
+        tenant = "cnosdb"
+        statement = "write"
+        ratio = 1.0
+    "#;
+    let trace_config: TraceConfig = toml::from_str(config_str).unwrap();
+    assert_eq!(trace_config.sampling.ratio, 0.01);
+    assert_eq!(trace_config.sampling.slow_threshold, Duration::from_secs(1));
+    assert_eq!(
+        trace_config.sampling.rules,
+        vec![TraceSamplingRule {
+            tenant: Some("cnosdb".to_string()),
+            statement: Some("write".to_string()),
+            ratio: 1.0,
+        }]
+    );
+}
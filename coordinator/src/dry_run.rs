@@ -0,0 +1,167 @@
+use models::meta_data::ReplicationSet;
+use models::schema::tskv_table_schema::{ColumnType, TskvTableSchema};
+use models::ValueType;
+use protocol_parser::Line;
+use protos::FieldValue;
+use serde::{Deserialize, Serialize};
+
+/// Result of a dry run write, the lines are parsed, validated and routed but not persisted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// Number of lines in the request.
+    pub lines: usize,
+    /// Number of lines which pass the validation and would be written.
+    pub valid_lines: usize,
+    pub routings: Vec<ReplicaRouting>,
+    pub errors: Vec<String>,
+}
+
+impl DryRunReport {
+    /// Add the routing of the lines, they are valid if the replication set is
+    /// available to write.
+    pub fn add_routing(&mut self, routing: ReplicaRouting) {
+        match routing.replica.as_ref() {
+            Some(replica) if replica.vnodes.is_empty() => {
+                self.errors.push(format!(
+                    "no available vnode in replication set {}",
+                    replica.id
+                ));
+            }
+            _ => self.valid_lines += routing.lines,
+        }
+        self.routings.push(routing);
+    }
+
+    /// The whole request would be rejected, none of the lines is written.
+    pub fn reject(&mut self, error: String) {
+        self.errors.push(error);
+        self.valid_lines = 0;
+    }
+}
+
+/// Routing decision of the lines written to a replication set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicaRouting {
+    /// `None` if the bucket of the lines does not exist yet, it will be created by the write.
+    pub replica: Option<ReplicationSet>,
+    pub tables: Vec<String>,
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+fn field_value_type(value: &FieldValue) -> ValueType {
+    match value {
+        FieldValue::U64(_) => ValueType::Unsigned,
+        FieldValue::I64(_) => ValueType::Integer,
+        FieldValue::Str(_) => ValueType::String,
+        FieldValue::F64(_) => ValueType::Float,
+        FieldValue::Bool(_) => ValueType::Boolean,
+    }
+}
+
+/// Check the tags and fields of the line against the existing table schema,
+/// columns not in the schema will be added by the write.
+pub fn check_line_schema(schema: &TskvTableSchema, line: &Line) -> Result<(), String> {
+    for (tag_key, _) in &line.tags {
+        if let Some(column) = schema.column(tag_key) {
+            if !column.column_type.is_tag() {
+                return Err(format!(
+                    "column '{}' of table '{}' is {}, but written as TAG",
+                    tag_key,
+                    line.table,
+                    column.column_type.as_str()
+                ));
+            }
+        }
+    }
+
+    for (field_name, value) in &line.fields {
+        if let Some(column) = schema.column(field_name) {
+            let column_type = ColumnType::Field(field_value_type(value));
+            if !column.column_type.matches_type(&column_type) {
+                return Err(format!(
+                    "column '{}' of table '{}' is {}, but written as {}",
+                    field_name,
+                    line.table,
+                    column.column_type.as_str(),
+                    column_type.as_str()
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use models::codec::Encoding;
+    use models::meta_data::{ReplicationSet, VnodeInfo};
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+    use protocol_parser::Line;
+    use protos::FieldValue;
+
+    use super::{check_line_schema, DryRunReport, ReplicaRouting};
+
+    #[test]
+    fn test_check_line_schema() {
+        let schema = TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "cpu".to_string(),
+            vec![
+                TableColumn::new_tag_column(1, "host".to_string()),
+                TableColumn::new(
+                    2,
+                    "usage".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+            ],
+        );
+        let line = |tag: &'static str, field: &'static str, value| Line {
+            hash_id: 0,
+            table: Cow::Borrowed("cpu"),
+            tags: vec![(Cow::Borrowed(tag), Cow::Borrowed("a"))],
+            fields: vec![(Cow::Borrowed(field), value)],
+            timestamp: 0,
+        };
+
+        assert!(check_line_schema(&schema, &line("host", "usage", FieldValue::F64(1.0))).is_ok());
+        assert!(check_line_schema(&schema, &line("region", "idle", FieldValue::I64(1))).is_ok());
+        assert!(check_line_schema(&schema, &line("host", "usage", FieldValue::I64(1))).is_err());
+        assert!(check_line_schema(&schema, &line("usage", "idle", FieldValue::I64(1))).is_err());
+    }
+
+    #[test]
+    fn test_dry_run_report() {
+        let routing = |vnodes: Option<Vec<VnodeInfo>>, lines| ReplicaRouting {
+            replica: vnodes.map(|vnodes| ReplicationSet::new(1, 1, 1, vnodes)),
+            tables: vec!["cpu".to_string()],
+            lines,
+            bytes: 100,
+        };
+        let mut report = DryRunReport {
+            lines: 7,
+            ..Default::default()
+        };
+
+        report.add_routing(routing(Some(vec![VnodeInfo::new(1, 1)]), 3));
+        report.add_routing(routing(None, 2));
+        assert_eq!(report.valid_lines, 5);
+        assert!(report.errors.is_empty());
+
+        // The lines routed to a replication set without vnodes are not written.
+        report.add_routing(routing(Some(vec![]), 2));
+        assert_eq!(report.valid_lines, 5);
+        assert_eq!(report.routings.len(), 3);
+        assert_eq!(report.errors.len(), 1);
+
+        report.reject("rate limited".to_string());
+        assert_eq!(report.valid_lines, 0);
+        assert_eq!(report.errors.len(), 2);
+    }
+}
@@ -6,13 +6,15 @@ use datafusion::error::DataFusionError;
 use flatbuffers::InvalidFlatbuffer;
 use meta::error::MetaError;
 use models::error_code::{ErrorCode, ErrorCoder};
-use models::meta_data::{ReplicationSet, ReplicationSetId, VnodeId};
+use models::meta_data::{NodeId, ReplicationSet, ReplicationSetId, VnodeId};
 use models::Timestamp;
 use protos::PointsError;
 use replication::errors::ReplicationError;
 use snafu::{Backtrace, IntoError, Location, Snafu};
 use utils::precision::Precision;
 
+use crate::RecordBatchWrite;
+
 #[derive(Snafu, Debug, ErrorCoder)]
 #[snafu(visibility(pub))]
 #[error_code(mod_code = "05")]
@@ -261,13 +263,6 @@ pub enum CoordinatorError {
         backtrace: Backtrace,
     },
 
-    #[snafu(display("Memory Exhausted Retry Later"))]
-    #[error_code(code = 33)]
-    MemoryExhausted {
-        location: Location,
-        backtrace: Backtrace,
-    },
-
     #[snafu(display("Fields can't be empty"))]
     #[error_code(code = 34)]
     FieldsIsEmpty {
@@ -295,6 +290,91 @@ pub enum CoordinatorError {
     ReplicaCannotRemove {
         replica_id: ReplicationSetId,
     },
+
+    #[snafu(display(
+        "Clock offset {}ms of node {} against meta leader exceeds max_clock_skew, write rejected",
+        offset_ms,
+        node_id
+    ))]
+    #[error_code(code = 38)]
+    ClockSkewExceeded {
+        node_id: u64,
+        offset_ms: i64,
+    },
+
+    #[snafu(display(
+        "Write memory exhausted, request {} bytes with {} of {} bytes in use, retry later",
+        request_size,
+        used,
+        total
+    ))]
+    #[error_code(code = 39)]
+    WriteMemoryExhausted {
+        request_size: usize,
+        used: usize,
+        total: usize,
+    },
+
+    #[snafu(display("Checksum mismatch of {} received from network", data))]
+    #[error_code(code = 40)]
+    ChecksumMismatch {
+        data: String,
+    },
+
+    #[snafu(display(
+        "Transfer leadership of replica set {} to vnode {} rejected: {}",
+        replica_id,
+        vnode_id,
+        reason
+    ))]
+    #[error_code(code = 41)]
+    LeaderTransferRejected {
+        replica_id: ReplicationSetId,
+        vnode_id: VnodeId,
+        reason: String,
+    },
+
+    #[snafu(display("Compaction job {} is not found", job_id))]
+    #[error_code(code = 42)]
+    CompactionJobNotFound {
+        job_id: u64,
+    },
+
+    #[snafu(display(
+        "Compaction job {} is running on nodes {:?}, specify the node by ON NODE",
+        job_id,
+        node_ids
+    ))]
+    #[error_code(code = 43)]
+    AmbiguousCompactionJob {
+        job_id: u64,
+        node_ids: Vec<NodeId>,
+    },
+
+    #[snafu(display(
+        "Table {} exceeded the quota max_series_per_table of the tenant, the maximum is {}",
+        table,
+        max
+    ))]
+    #[error_code(code = 44)]
+    SeriesQuotaExceeded {
+        table: String,
+        max: usize,
+    },
+
+    /// The rows of the failed replication sets may be written partly, they
+    /// are not in `written`.
+    #[snafu(display(
+        "Write failed after writing {} rows to the replication sets {:?}: {}",
+        written.rows(),
+        written.replica_rows.keys(),
+        source
+    ))]
+    #[error_code(code = 45)]
+    PartialWrite {
+        written: RecordBatchWrite,
+        source: Box<CoordinatorError>,
+    },
 }
 
 impl From<ArrowError> for CoordinatorError {
@@ -345,6 +425,7 @@ impl CoordinatorError {
             CoordinatorError::Meta { source } => source.error_code(),
             CoordinatorError::TskvError { source } => source.error_code(),
             CoordinatorError::ReplicatError { source } => source.error_code(),
+            CoordinatorError::PartialWrite { source, .. } => source.error_code(),
             _ => self,
         }
     }
@@ -361,6 +442,8 @@ pub fn encode_grpc_response(
         Ok(data) => tonic::Response::new(protos::kv_service::BatchBytesResponse {
             data,
             code: SUCCESS_RESPONSE_CODE,
+            checksum: None,
+            scan_metrics: None,
         }),
 
         Err(err) => {
@@ -372,11 +455,15 @@ pub fn encode_grpc_response(
                 tonic::Response::new(protos::kv_service::BatchBytesResponse {
                     data: format!("{}-{}", replica_id, new_leader).into(),
                     code: FORWARD_TO_LEADER_CODE,
+                    checksum: None,
+                    scan_metrics: None,
                 })
             } else {
                 tonic::Response::new(protos::kv_service::BatchBytesResponse {
                     data: err.to_string().into_bytes(),
                     code: FAILED_RESPONSE_CODE,
+                    checksum: None,
+                    scan_metrics: None,
                 })
             }
         }
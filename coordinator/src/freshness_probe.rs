@@ -0,0 +1,230 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use datafusion::arrow::array::{Array, StringArray};
+use futures::TryStreamExt;
+use metrics::count::U64Counter;
+use metrics::duration::{DurationGauge, DurationHistogram, DurationHistogramOptions};
+use metrics::metric_register::MetricsRegister;
+use metrics::DURATION_MAX;
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRanges};
+use models::predicate::PlacedSplit;
+use models::schema::{DEFAULT_CATALOG, USAGE_SCHEMA};
+use models::utils::now_timestamp_nanos;
+use protocol_parser::Line;
+use protos::kv_service::WriteConsistency;
+use protos::FieldValue;
+use snafu::ResultExt;
+use trace::{debug, warn};
+use utils::precision::Precision;
+
+use crate::errors::{CoordinatorError, CoordinatorResult, MetaSnafu, ModelsSnafu};
+use crate::service::CoordService;
+use crate::{Coordinator, QueryOption};
+
+pub const FRESHNESS_PROBE_TABLE: &str = "freshness_probe";
+const PROBE_NODE_TAG: &str = "node_id";
+const PROBE_SEQ_FIELD: &str = "seq";
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub struct FreshnessProbeMetrics {
+    latency: DurationHistogram,
+    last_latency: DurationGauge,
+    timeouts: U64Counter,
+}
+
+impl FreshnessProbeMetrics {
+    pub fn new(register: &MetricsRegister, node_id: u64) -> Self {
+        let node_id = node_id.to_string();
+        let labels = [("node_id", node_id.as_str())];
+
+        let latency = register
+            .register_metric::<DurationHistogram>(
+                "freshness_probe_latency",
+                "time from writing a probe point to it being readable",
+                DurationHistogramOptions::new([
+                    Duration::from_millis(5),
+                    Duration::from_millis(10),
+                    Duration::from_millis(50),
+                    Duration::from_millis(100),
+                    Duration::from_millis(500),
+                    Duration::from_secs(1),
+                    Duration::from_secs(5),
+                    Duration::from_secs(10),
+                    Duration::from_secs(30),
+                    DURATION_MAX,
+                ]),
+            )
+            .recorder(labels);
+        let last_latency = register
+            .metric::<DurationGauge>(
+                "freshness_probe_last_latency",
+                "latency of the last readable probe point",
+            )
+            .recorder(labels);
+        let timeouts = register
+            .metric::<U64Counter>(
+                "freshness_probe_timeouts",
+                "probe points not readable within the timeout",
+            )
+            .recorder(labels);
+
+        Self {
+            latency,
+            last_latency,
+            timeouts,
+        }
+    }
+}
+
+/// Canary that periodically writes a probe point of this node into usage_schema and
+/// measures the time until it is readable, reading from a vnode on another node if
+/// the replication set has one.
+pub struct FreshnessProbe {
+    coord: Arc<CoordService>,
+    interval: Duration,
+    timeout: Duration,
+    metrics: FreshnessProbeMetrics,
+    seq: u64,
+}
+
+impl FreshnessProbe {
+    pub fn new(coord: Arc<CoordService>, register: &MetricsRegister) -> Self {
+        let config = coord.get_config();
+        let metrics = FreshnessProbeMetrics::new(register, coord.node_id());
+        Self {
+            coord,
+            interval: config.global.freshness_probe_interval,
+            timeout: config.global.freshness_probe_timeout,
+            metrics,
+            seq: 0,
+        }
+    }
+
+    pub async fn run(mut self) {
+        let start = tokio::time::Instant::now() + self.interval;
+        let mut intv = tokio::time::interval_at(start, self.interval);
+        loop {
+            intv.tick().await;
+            self.seq += 1;
+            match self.probe().await {
+                Ok(Some(latency)) => {
+                    debug!("freshness probe {} is readable in {:?}", self.seq, latency);
+                    self.metrics.latency.record(latency);
+                    self.metrics.last_latency.set(latency);
+                }
+                Ok(None) => {
+                    warn!(
+                        "freshness probe {} is not readable in {:?}",
+                        self.seq, self.timeout
+                    );
+                    self.metrics.timeouts.inc_one();
+                }
+                Err(e) => warn!("freshness probe {} failed: {}", self.seq, e),
+            }
+        }
+    }
+
+    /// Write a probe point and poll until it is readable, returns None if timed out.
+    async fn probe(&self) -> CoordinatorResult<Option<Duration>> {
+        let node_id = self.coord.node_id().to_string();
+        let timestamp = now_timestamp_nanos();
+        let line = Line::new(
+            Cow::Borrowed(FRESHNESS_PROBE_TABLE),
+            vec![(
+                Cow::Borrowed(PROBE_NODE_TAG),
+                Cow::Borrowed(node_id.as_str()),
+            )],
+            vec![(Cow::Borrowed(PROBE_SEQ_FIELD), FieldValue::U64(self.seq))],
+            timestamp,
+        );
+
+        let start = Instant::now();
+        self.coord
+            .write_lines(
+                DEFAULT_CATALOG,
+                USAGE_SCHEMA,
+                Precision::NS,
+                WriteConsistency::Quorum,
+                vec![line],
+                None,
+            )
+            .await?;
+
+        while start.elapsed() < self.timeout {
+            if self.is_readable(&node_id, timestamp).await? {
+                return Ok(Some(start.elapsed()));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        Ok(None)
+    }
+
+    async fn is_readable(&self, node_id: &str, timestamp: i64) -> CoordinatorResult<bool> {
+        let tenant_meta = self
+            .coord
+            .tenant_meta(DEFAULT_CATALOG)
+            .await
+            .ok_or_else(|| CoordinatorError::TenantNotFound {
+                name: DEFAULT_CATALOG.to_string(),
+            })?;
+        let Some(schema) = tenant_meta
+            .get_tskv_table_schema(USAGE_SCHEMA, FRESHNESS_PROBE_TABLE)
+            .context(MetaSnafu)?
+        else {
+            return Ok(false);
+        };
+
+        let predicate = Arc::new(
+            ResolvedPredicate::new(
+                Arc::new(TimeRanges::with_inclusive_bounds(timestamp, timestamp)),
+                ColumnDomains::all(),
+                None,
+            )
+            .context(ModelsSnafu)?,
+        );
+        let replica_sets = self
+            .coord
+            .prune_shards(
+                DEFAULT_CATALOG,
+                USAGE_SCHEMA,
+                predicate.time_ranges().as_ref(),
+            )
+            .await?;
+
+        for (i, mut replica_set) in replica_sets.into_iter().enumerate() {
+            // Prefer the vnodes on other nodes, so that the replication is measured too.
+            let local_node_id = self.coord.node_id();
+            replica_set
+                .vnodes
+                .sort_by_key(|vnode| vnode.node_id == local_node_id);
+
+            let split = PlacedSplit::new(i, predicate.clone(), None, replica_set);
+            let option = QueryOption::new(
+                1024,
+                split,
+                None,
+                schema.to_arrow_schema(),
+                schema.clone(),
+                schema.meta(),
+            );
+            let mut stream = self.coord.table_scan(option, None)?;
+            while let Some(batch) = stream.try_next().await? {
+                let Some(nodes) = batch
+                    .column_by_name(PROBE_NODE_TAG)
+                    .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+                else {
+                    continue;
+                };
+                if nodes.iter().any(|n| n == Some(node_id)) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
@@ -1,9 +1,11 @@
 #![recursion_limit = "256"]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 use config::tskv::Config;
 use datafusion::arrow::record_batch::RecordBatch;
@@ -11,40 +13,60 @@ use errors::CoordinatorError;
 use futures::Stream;
 use meta::model::{MetaClientRef, MetaRef};
 use models::meta_data::{
-    NodeId, ReplicaAllInfo, ReplicationSet, ReplicationSetId, VnodeAllInfo, VnodeId,
+    NodeId, ReplicaAllInfo, ReplicationSet, ReplicationSetId, VnodeAllInfo, VnodeId, VnodeInfo,
 };
 use models::object_reference::ResolvedTable;
-use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
+use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef, TimeRange};
+use models::schema::database_schema::SeriesPlacement;
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use protocol_parser::Line;
-use protos::kv_service::{RaftWriteCommand, UpdateSetValue};
+use protos::kv_service::{RaftWriteCommand, UpdateSetValue, WriteConsistency};
 use raft::manager::RaftNodesManager;
+use raft::recovery::NodeRecoveryProgress;
 use raft::writer::TskvRaftWriter;
 use snafu::ResultExt;
 use trace::SpanContext;
 use tskv::reader::QueryOption;
-use tskv::EngineRef;
+use tskv::{CompactionJobInfo, CompactionPlan, EngineRef, ScanCost, VnodeBackup};
 use utils::precision::Precision;
 
+use crate::dry_run::DryRunReport;
 use crate::errors::{CoordinatorResult, MetaSnafu};
+use crate::repair::VnodeRepair;
 use crate::service::CoordServiceMetrics;
 
+pub mod dry_run;
 pub mod errors;
+pub mod freshness_probe;
 pub mod metrics;
 pub mod raft;
 pub mod reader;
+pub mod repair;
+pub mod replica_export;
+pub mod replica_lag;
 pub mod resource_manager;
+pub mod series_quota;
 pub mod service;
 pub mod service_mock;
+pub mod table_ttl;
 pub mod tskv_executor;
 
 pub type SendableCoordinatorRecordBatchStream =
     Pin<Box<dyn Stream<Item = CoordinatorResult<RecordBatch>> + Send>>;
 
+/// Record batches of the tables of a replication set, tagged by the table names.
+pub type ReplicaDataStream =
+    Pin<Box<dyn Stream<Item = CoordinatorResult<(String, RecordBatch)>> + Send>>;
+
+pub type LineBatchStream<'a> =
+    Pin<Box<dyn Stream<Item = CoordinatorResult<Vec<Line<'a>>>> + Send + 'a>>;
+
 #[derive(Debug, Clone)]
 pub enum ReplicationCmdType {
     /// replica set id, dst nod id
     AddRaftFollower(u32, u64),
+    /// replica set id, dst nod id. the learner replicates the data without voting
+    AddRaftLearner(u32, u64),
     /// vnode id. just remove the follower, if remove leader temporarily unavailable
     RemoveRaftNode(u32),
     /// replica set id
@@ -53,6 +75,93 @@ pub enum ReplicationCmdType {
     PromoteLeader(u32, u32),
 }
 
+/// Estimated cost of scanning a table in the vnode selected to be read in a replica set.
+#[derive(Debug, Clone)]
+pub struct VnodeScanCost {
+    pub replica_id: ReplicationSetId,
+    pub node_id: NodeId,
+    pub vnode_id: VnodeId,
+    pub cost: ScanCost,
+}
+
+/// A vnode with the replication set it belongs to, and the size of its files on disk.
+#[derive(Debug, Clone)]
+pub struct VnodePlacement {
+    pub database: String,
+    pub replica_id: ReplicationSetId,
+    pub vnode: VnodeInfo,
+    pub is_leader: bool,
+    /// None if the node holding the vnode did not report it.
+    pub disk_storage: Option<u64>,
+}
+
+/// Rows of a replication set in a bucket, counted on its leader vnode.
+#[derive(Debug, Clone)]
+pub struct ShardRows {
+    pub bucket_id: u32,
+    /// Nanoseconds timestamp of the start of the bucket.
+    pub start_time: i64,
+    /// Nanoseconds timestamp of the end of the bucket.
+    pub end_time: i64,
+    pub series_placement: SeriesPlacement,
+    pub replica_id: ReplicationSetId,
+    /// None if the node holding the leader vnode did not report it.
+    pub rows: Option<u64>,
+}
+
+/// Result of transferring the leadership of a replication set.
+#[derive(Debug, Clone, Default)]
+pub struct LeaderTransfer {
+    pub replica_id: ReplicationSetId,
+    pub old_leader: VnodeId,
+    pub new_leader: VnodeId,
+    /// The node holding the new leader.
+    pub node_id: NodeId,
+    /// Raft entries the new leader lagged behind the old leader before the transfer.
+    pub log_lag: u64,
+    pub elapsed: Duration,
+}
+
+/// Result of writing a record batch.
+#[derive(Debug, Clone)]
+pub struct RecordBatchWrite {
+    /// Size of the points written.
+    pub write_bytes: usize,
+    /// Number of rows written to each replication set.
+    pub replica_rows: HashMap<ReplicationSetId, usize>,
+    /// Time range of the rows written, in the precision of the database.
+    pub time_range: TimeRange,
+}
+
+impl Default for RecordBatchWrite {
+    fn default() -> Self {
+        Self {
+            write_bytes: 0,
+            replica_rows: HashMap::new(),
+            time_range: TimeRange::none(),
+        }
+    }
+}
+
+impl RecordBatchWrite {
+    /// Record the rows written to the replication set.
+    pub fn add_replica(
+        &mut self,
+        replica_id: ReplicationSetId,
+        rows: usize,
+        bytes: usize,
+        time_range: &TimeRange,
+    ) {
+        *self.replica_rows.entry(replica_id).or_default() += rows;
+        self.write_bytes += bytes;
+        self.time_range.merge(time_range);
+    }
+
+    pub fn rows(&self) -> usize {
+        self.replica_rows.values().sum()
+    }
+}
+
 #[async_trait::async_trait]
 pub trait Coordinator: Send + Sync {
     fn node_id(&self) -> u64;
@@ -70,6 +179,25 @@ pub trait Coordinator: Send + Sync {
         predicate: ResolvedPredicateRef,
     ) -> CoordinatorResult<Vec<ReplicationSet>>;
 
+    /// Like `table_vnodes`, but a follower whose data is stale within
+    /// `max_replica_lag` is read in preference to the leader, the staleness is
+    /// estimated by the raft logs applied by the vnodes and reported to meta.
+    async fn table_vnodes_within_lag(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+        max_replica_lag: Duration,
+    ) -> CoordinatorResult<Vec<ReplicationSet>>;
+
+    /// Like `table_vnodes`, but a follower is read in preference to the leader
+    /// once it applies the raft logs committed by the leader when the read starts
+    /// (ReadIndex), so that the read is as consistent as reading the leader.
+    async fn table_vnodes_follower_read(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<ReplicationSet>>;
+
     async fn write_replica_by_raft(
         &self,
         replica: ReplicationSet,
@@ -77,22 +205,51 @@ pub trait Coordinator: Send + Sync {
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<()>;
 
+    /// Write the lines, each write waits for the replicas required by `consistency`.
     async fn write_lines<'a>(
         &self,
         tenant: &str,
         db: &str,
         precision: Precision,
+        consistency: WriteConsistency,
         lines: Vec<Line<'a>>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
+    /// Write batches of lines from the stream, lines are routed incrementally and
+    /// the lines of a replication set are written as soon as they are large enough,
+    /// so that very large payloads need not be held in memory at once.
+    async fn write_lines_stream<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        consistency: WriteConsistency,
+        lines: LineBatchStream<'a>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize>;
+
+    /// Parse, validate schema, route and check limits of the lines like `write_lines`,
+    /// but stop before proposing to raft, returns the routing decisions and errors.
+    async fn dry_run_write_lines<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        lines: Vec<Line<'a>>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<DryRunReport>;
+
+    /// Write the record batch to the replication sets, if some of them fail
+    /// after others are written, the written ones are returned by
+    /// `CoordinatorError::PartialWrite`.
     async fn write_record_batch<'a>(
         &self,
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
         span_ctx: Option<&SpanContext>,
-    ) -> CoordinatorResult<usize>;
+    ) -> CoordinatorResult<RecordBatchWrite>;
 
     fn table_scan(
         &self,
@@ -110,10 +267,126 @@ pub trait Coordinator: Send + Sync {
         &self,
         table: &ResolvedTable,
         predicate: &ResolvedPredicate,
+    ) -> CoordinatorResult<()> {
+        self.delete_from_tables(&[(table.clone(), predicate.clone())])
+            .await
+    }
+
+    /// Delete from several tables in one call, the buckets of each database are
+    /// mapped only once and the deletes are sent to all replicas concurrently.
+    async fn delete_from_tables(
+        &self,
+        deletes: &[(ResolvedTable, ResolvedPredicate)],
     ) -> CoordinatorResult<()>;
 
     async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()>;
 
+    /// Close the idle files of vnodes on the nodes holding them, returns the
+    /// number of files closed of each vnode.
+    async fn close_vnodes_files(
+        &self,
+        tenant: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> CoordinatorResult<Vec<(VnodeId, u64)>>;
+
+    /// Get the compactions that would be run on the vnode by the node holding it,
+    /// nothing is compacted.
+    async fn explain_compaction(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+    ) -> CoordinatorResult<Vec<CompactionPlan>>;
+
+    /// Get the compactions running on every data node, the unreachable nodes are skipped.
+    async fn compaction_jobs(
+        &self,
+        tenant: &str,
+    ) -> CoordinatorResult<Vec<(NodeId, CompactionJobInfo)>>;
+
+    /// Cancel the running compaction of the job id on the data node, the node
+    /// running the job is found if not given.
+    async fn kill_compaction(
+        &self,
+        tenant: &str,
+        node_id: Option<NodeId>,
+        job_id: u64,
+    ) -> CoordinatorResult<()>;
+
+    /// Get the progress of restoring the vnodes of the tenant from snapshots on
+    /// every data node, the unreachable nodes are skipped.
+    async fn recovery_progress(&self, tenant: &str)
+        -> CoordinatorResult<Vec<NodeRecoveryProgress>>;
+
+    /// Export data of the vnode in the time range(in nanoseconds) to parquet files
+    /// under the path, returns the number of exported rows.
+    async fn export_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        time_range: TimeRange,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<u64>;
+
+    /// Stream the data of the replication set in the time range(in nanoseconds)
+    /// as record batches, the data of the leader merged with the rows only the
+    /// other replicas have.
+    async fn export_replica_data(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        time_range: TimeRange,
+    ) -> CoordinatorResult<ReplicaDataStream>;
+
+    /// Back up the files of the vnode to the object store at `path`.
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<VnodeBackup>;
+
+    /// Restore the backup from the object store at `path` into the vnode,
+    /// the vnode must not have been opened on its data node.
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        backup: VnodeBackup,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<()>;
+
+    /// Get the tag value prefixes which created the most series in the database
+    /// from the leader vnode of each replication set.
+    async fn series_cardinality_top(
+        &self,
+        tenant: &str,
+        db: &str,
+        top_k: usize,
+    ) -> CoordinatorResult<RecordBatch>;
+
+    /// Resolve the predicate to the vnodes to be read, then estimate the series
+    /// and bytes to be scanned in each vnode, without executing the scan.
+    async fn estimate_scan_cost(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<VnodeScanCost>>;
+
+    /// Get all vnodes of the databases in the tenant, with the disk storage
+    /// fetched from the nodes holding them.
+    async fn vnodes_placement(
+        &self,
+        tenant: &str,
+        databases: &[String],
+    ) -> CoordinatorResult<Vec<VnodePlacement>>;
+
+    /// Get the rows in the files of each replication set of the buckets of the
+    /// database, to find out whether the series are placed evenly.
+    async fn shard_rows(&self, tenant: &str, database: &str) -> CoordinatorResult<Vec<ShardRows>>;
+
     /// A manager to manage vnode.
     async fn replication_manager(
         &self,
@@ -128,6 +401,26 @@ pub trait Coordinator: Send + Sync {
         replica_id: ReplicationSetId,
     ) -> CoordinatorResult<Vec<RecordBatch>>;
 
+    /// Replace the data of the followers of the replication set, which differs
+    /// from the leader, by the data of the leader.
+    async fn repair_replica(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<Vec<VnodeRepair>>;
+
+    /// Transfer the leadership of the replication set to the vnode, which must be
+    /// running on a healthy node, and lag behind the leader by at most
+    /// `max_log_lag` raft entries if it is set. Returns after the new leader is
+    /// elected and recorded in meta.
+    async fn transfer_leader(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        new_leader: VnodeId,
+        max_log_lag: Option<u64>,
+    ) -> CoordinatorResult<LeaderTransfer>;
+
     fn metrics(&self) -> &Arc<CoordServiceMetrics>;
 
     async fn update_tags_value(
@@ -1,6 +1,6 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use meta::model::MetaRef;
@@ -11,17 +11,23 @@ use openraft::SnapshotPolicy;
 use protos::kv_service::*;
 use replication::metrics::ReplicationMetrics;
 use replication::multi_raft::MultiRaft;
+use replication::network_client::NetworkConn;
 use replication::node_store::NodeStorage;
 use replication::raft_node::RaftNode;
 use replication::state_store::{RaftNodeSummary, StateStorage};
-use replication::{ApplyStorageRef, EntryStorageRef, RaftNodeId, RaftNodeInfo, ReplicationConfig};
+use replication::{
+    ApplyStorageRef, EntryStorageEngine, EntryStorageRef, RaftNodeId, RaftNodeInfo,
+    ReplicationConfig,
+};
 use snafu::ResultExt;
 use tokio::runtime::Runtime;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 use tracing::info;
 use tskv::wal::wal_store::RaftEntryStorage;
 use tskv::{wal, EngineRef};
 
+use super::recovery::RecoveryOrchestrator;
+use super::startup::StartupRecoveryMetrics;
 use super::TskvEngineStorage;
 use crate::errors::{
     CommonSnafu, CoordinatorError, CoordinatorResult, LeaderIsWrongSnafu, MetaSnafu,
@@ -36,10 +42,44 @@ pub struct RaftNodesManager {
     kv_inst: Option<EngineRef>,
     raft_state: Arc<StateStorage>,
     raft_nodes: Arc<RwLock<MultiRaft>>,
+    /// Connections to the other nodes, shared by all the raft groups.
+    network: NetworkConn,
+    recovery: Arc<RecoveryOrchestrator>,
+    /// Replication sets whose leadership is being transferred by this node.
+    transferring: Mutex<HashSet<ReplicationSetId>>,
 
     register: Arc<MetricsRegister>,
 }
 
+/// Removes the replication set from the transferring ones when dropped.
+struct TransferGuard<'a> {
+    transferring: &'a Mutex<HashSet<ReplicationSetId>>,
+    replica_id: ReplicationSetId,
+}
+
+impl<'a> TransferGuard<'a> {
+    /// None if the leadership of the replication set is being transferred.
+    fn start(
+        transferring: &'a Mutex<HashSet<ReplicationSetId>>,
+        replica_id: ReplicationSetId,
+    ) -> Option<Self> {
+        if !transferring.lock().unwrap().insert(replica_id) {
+            return None;
+        }
+
+        Some(Self {
+            transferring,
+            replica_id,
+        })
+    }
+}
+
+impl Drop for TransferGuard<'_> {
+    fn drop(&mut self) {
+        self.transferring.lock().unwrap().remove(&self.replica_id);
+    }
+}
+
 impl RaftNodesManager {
     pub fn new(
         config: config::tskv::Config,
@@ -50,6 +90,14 @@ impl RaftNodesManager {
         let path = PathBuf::from(config.storage.path.clone()).join("raft-state");
         let state =
             StateStorage::open(path, config.cluster.lmdb_max_map_size.try_into().unwrap()).unwrap();
+        let recovery = RecoveryOrchestrator::new(
+            config.global.node_id,
+            config.cluster.max_concurrent_snapshot_transfers,
+            config.cluster.snapshot_download_parallelism,
+            config.cluster.snapshot_write_bandwidth,
+            config.cluster.snapshot_send_bandwidth,
+        );
+        let network = NetworkConn::new(replication_config(&config));
 
         Self {
             meta,
@@ -58,6 +106,9 @@ impl RaftNodesManager {
             register,
             raft_state: Arc::new(state),
             raft_nodes: Arc::new(RwLock::new(MultiRaft::new())),
+            network,
+            recovery: Arc::new(recovery),
+            transferring: Mutex::new(HashSet::new()),
         }
     }
 
@@ -69,6 +120,10 @@ impl RaftNodesManager {
         self.raft_nodes.clone()
     }
 
+    pub fn recovery(&self) -> Arc<RecoveryOrchestrator> {
+        self.recovery.clone()
+    }
+
     pub async fn metrics(&self, group_id: u32) -> String {
         if let Ok(Some(node)) = self.raft_nodes.read().await.get_node(group_id) {
             let res = node.metrics().await;
@@ -83,6 +138,8 @@ impl RaftNodesManager {
         }
     }
 
+    /// Open the raft nodes of this node and replay their WAL, at most
+    /// `deployment.cpu` of them at a time.
     pub async fn start_all_raft_node(
         runtime: Arc<Runtime>,
         manager: Arc<RaftNodesManager>,
@@ -91,20 +148,37 @@ impl RaftNodesManager {
             .raft_state
             .all_nodes_summary()
             .context(ReplicatSnafu)?;
+        let parallelism = manager.config.deployment.cpu.max(1);
+        info!(
+            "start {} raft nodes, {} at a time",
+            nodes_summary.len(),
+            parallelism
+        );
         let mut nodes = manager.raft_nodes.write().await;
+        let workers = Arc::new(Semaphore::new(parallelism));
+        let startup_metrics =
+            StartupRecoveryMetrics::new(manager.register.clone(), nodes_summary.len());
         let mut futures = Vec::with_capacity(nodes_summary.len());
         for summary in nodes_summary {
             let manager = manager.clone();
+            let workers = workers.clone();
+            let mut vnode_metrics = startup_metrics.vnode(&summary);
             let future = runtime.spawn(async move {
-                match manager
+                let _permit = workers
+                    .acquire_owned()
+                    .await
+                    .expect("the worker semaphore is never closed");
+                vnode_metrics.start();
+                let result = manager
                     .open_raft_node(
                         &summary.tenant,
                         &summary.db_name,
                         summary.raft_id as VnodeId,
                         summary.group_id,
                     )
-                    .await
-                {
+                    .await;
+                vnode_metrics.finish(result.is_ok());
+                match result {
                     Ok(node) => {
                         info!("start raft node: {:?} Success", summary);
                         Ok((node, summary))
@@ -212,6 +286,45 @@ impl RaftNodesManager {
         Ok(())
     }
 
+    /// The tenants and databases of the raft nodes on this node.
+    pub fn local_databases(&self) -> CoordinatorResult<HashSet<(String, String)>> {
+        let nodes_summary = self.raft_state.all_nodes_summary().context(ReplicatSnafu)?;
+
+        Ok(nodes_summary
+            .into_iter()
+            .map(|summary| (summary.tenant, summary.db_name))
+            .collect())
+    }
+
+    /// Drop the raft nodes of the dropped database on this node, except the
+    /// ones of the replication sets kept by a database recreated with the name.
+    pub async fn drop_db_raft_nodes(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        keep: &HashSet<ReplicationSetId>,
+    ) -> CoordinatorResult<()> {
+        let nodes_summary = self.raft_state.all_nodes_summary().context(ReplicatSnafu)?;
+        for summary in nodes_summary {
+            if summary.tenant != tenant
+                || summary.db_name != db_name
+                || keep.contains(&summary.group_id)
+            {
+                continue;
+            }
+
+            self.exec_drop_raft_node(
+                tenant,
+                db_name,
+                summary.raft_id as VnodeId,
+                summary.group_id,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn build_replica_group(
         &self,
         tenant: &str,
@@ -299,51 +412,126 @@ impl RaftNodesManager {
     }
 
     async fn assert_leader_node(&self, raft_node: Arc<RaftNode>) -> CoordinatorResult<()> {
+        self.ensure_linearizable(raft_node).await.map(|_| ())
+    }
+
+    /// Confirm the leadership with a quorum of the group, returns the index of the
+    /// raft log to be applied before a linearizable read.
+    async fn ensure_linearizable(&self, raft_node: Arc<RaftNode>) -> CoordinatorResult<u64> {
         let result = raft_node.raw_raft().ensure_linearizable().await;
-        if let Err(err) = result {
-            if let Some(openraft::error::ForwardToLeader {
-                leader_id: Some(id),
-                leader_node: Some(node),
-            }) = err.forward_to_leader()
-            {
-                Err(CoordinatorError::RaftForwardToLeader {
-                    replica_id: node.group_id,
-                    leader_vnode_id: *id as u32,
-                })
-            } else {
-                Err(CoordinatorError::RaftGroupError {
-                    msg: format!("group-{}, is_leader failed: {}", raft_node.group_id(), err),
-                })
+        match result {
+            Ok(read_log_id) => Ok(read_log_id.map(|log_id| log_id.index).unwrap_or_default()),
+            Err(err) => {
+                if let Some(openraft::error::ForwardToLeader {
+                    leader_id: Some(id),
+                    leader_node: Some(node),
+                }) = err.forward_to_leader()
+                {
+                    Err(CoordinatorError::RaftForwardToLeader {
+                        replica_id: node.group_id,
+                        leader_vnode_id: *id as u32,
+                    })
+                } else {
+                    Err(CoordinatorError::RaftGroupError {
+                        msg: format!("group-{}, is_leader failed: {}", raft_node.group_id(), err),
+                    })
+                }
             }
-        } else {
-            Ok(())
         }
     }
 
+    /// The ReadIndex of the replica set on this leader, the followers having
+    /// applied the raft log of the index can be read consistently.
+    pub async fn read_index(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<u64> {
+        let all_info = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+        let raft_node = self
+            .get_node_or_build(tenant, db_name, &all_info.replica_set)
+            .await?;
+
+        self.ensure_linearizable(raft_node).await
+    }
+
+    /// Wait until the vnode of the replica set on this node applies the raft log
+    /// of the index.
+    pub async fn wait_applied(
+        &self,
+        replica_id: ReplicationSetId,
+        vnode_id: VnodeId,
+        index: u64,
+        timeout: Duration,
+    ) -> CoordinatorResult<()> {
+        let raft_node = self
+            .raft_nodes
+            .read()
+            .await
+            .get_node(replica_id)
+            .context(ReplicatSnafu)?
+            .filter(|node| node.raft_id() == vnode_id as RaftNodeId)
+            .ok_or_else(|| {
+                RaftNodeNotFoundSnafu {
+                    vnode_id,
+                    replica_id,
+                }
+                .build()
+            })?;
+
+        raft_node
+            .wait_applied(index, timeout)
+            .await
+            .context(ReplicatSnafu)
+    }
+
+    /// Transfer the leadership of the replica set on this leader to the vnode,
+    /// returns the raft entries the vnode lagged behind before the transfer.
+    /// If `max_log_lag` is set, the transfer is rejected if the vnode lags behind
+    /// by more entries, or the membership of the group is being changed.
     pub async fn promote_follower_to_leader(
         &self,
         tenant: &str,
         db_name: &str,
         new_leader_id: VnodeId,
         replica_id: ReplicationSetId,
-    ) -> CoordinatorResult<()> {
+        max_log_lag: Option<u64>,
+    ) -> CoordinatorResult<u64> {
         let all_info = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
         let replica = &all_info.replica_set;
         if replica.leader_vnode_id == new_leader_id {
-            return Ok(());
+            return Ok(0);
         }
 
-        if replica.vnode(new_leader_id).is_none() {
-            return Err(RaftNodeNotFoundSnafu {
-                vnode_id: new_leader_id,
-                replica_id: replica.id,
-            }
-            .build());
-        }
+        check_new_leader(replica, new_leader_id)?;
 
         let raft_node = self.get_node_or_build(tenant, db_name, replica).await?;
         self.assert_leader_node(raft_node.clone()).await?;
 
+        let rejected = |reason: String| CoordinatorError::LeaderTransferRejected {
+            replica_id: replica.id,
+            vnode_id: new_leader_id,
+            reason,
+        };
+        let _guard = TransferGuard::start(&self.transferring, replica.id)
+            .ok_or_else(|| rejected("the leadership is being transferred".to_string()))?;
+        let log_lag = raft_node.replication_lag(new_leader_id as RaftNodeId);
+        if let Some(max_log_lag) = max_log_lag {
+            let learners = replica
+                .vnodes
+                .iter()
+                .filter(|vnode| vnode.learner)
+                .map(|vnode| vnode.id as RaftNodeId)
+                .collect::<BTreeSet<_>>();
+            check_transfer_lag(
+                log_lag,
+                max_log_lag,
+                raft_node.membership_changing(&learners),
+            )
+            .map_err(rejected)?;
+        }
+
         let mut members = BTreeSet::new();
         members.insert(new_leader_id as RaftNodeId);
         raft_node
@@ -351,10 +539,12 @@ impl RaftNodesManager {
             .await
             .context(ReplicatSnafu)?;
 
+        let mut transferred = false;
         for _ in 0..100 {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             if let Some(id) = raft_node.raw_raft().current_leader().await {
                 if id == new_leader_id as RaftNodeId {
+                    transferred = true;
                     break;
                 }
             }
@@ -380,7 +570,18 @@ impl RaftNodesManager {
             meta: self.meta.clone(),
         };
         executor.do_request(tenant, replica, &caller).await?;
-        Ok(())
+
+        // The other vnodes are restored as followers even if the transfer timed out.
+        if !transferred {
+            return Err(CoordinatorError::RaftGroupError {
+                msg: format!(
+                    "group-{}, promote vnode {} to leader timed out",
+                    replica.id, new_leader_id
+                ),
+            });
+        }
+
+        Ok(log_lag.unwrap_or_default())
     }
 
     pub async fn learner_to_follower(
@@ -395,12 +596,8 @@ impl RaftNodesManager {
         let raft_node = self.get_node_or_build(tenant, db_name, replica).await?;
         self.assert_leader_node(raft_node.clone()).await?;
 
-        let mut members = BTreeSet::new();
-        for vnode in replica.vnodes.iter() {
-            members.insert(vnode.id as RaftNodeId);
-        }
         raft_node
-            .raft_change_membership(members, true)
+            .raft_change_membership(voter_ids(replica), true)
             .await
             .context(ReplicatSnafu)?;
 
@@ -456,12 +653,15 @@ impl RaftNodesManager {
         Ok(())
     }
 
+    /// Add a vnode on the node to the replica set, as a follower or a learner
+    /// which only replicates the data without voting.
     pub async fn add_follower_to_group(
         &self,
         tenant: &str,
         db_name: &str,
         follower_nid: NodeId,
         replica_id: ReplicationSetId,
+        learner: bool,
     ) -> CoordinatorResult<()> {
         let follower_addr = self
             .meta
@@ -480,6 +680,7 @@ impl RaftNodesManager {
             id: new_vnode_id,
             node_id: follower_nid,
             status: VnodeStatus::Running,
+            learner,
         };
         self.open_remote_raft_node(tenant, db_name, &new_vnode, replica.id)
             .await?;
@@ -493,15 +694,12 @@ impl RaftNodesManager {
             .await
             .context(ReplicatSnafu)?;
 
-        let mut members = BTreeSet::new();
-        members.insert(new_vnode_id as RaftNodeId);
-        for vnode in replica.vnodes.iter() {
-            members.insert(vnode.id as RaftNodeId);
+        if let Some(members) = voters_after_add(&replica, &new_vnode) {
+            raft_node
+                .raft_change_membership(members, false)
+                .await
+                .context(ReplicatSnafu)?;
         }
-        raft_node
-            .raft_change_membership(members, false)
-            .await
-            .context(ReplicatSnafu)?;
 
         update_replication_set(
             self.meta.clone(),
@@ -530,16 +728,16 @@ impl RaftNodesManager {
             let raft_node = self.get_node_or_build(tenant, db_name, &replica).await?;
             self.assert_leader_node(raft_node.clone()).await?;
 
-            let mut members = BTreeSet::new();
-            for vnode in replica.vnodes.iter() {
-                if vnode.id != vnode_id {
-                    members.insert(vnode.id as RaftNodeId);
-                }
+            match membership_change_on_remove(&replica, &vnode) {
+                MembershipChange::RemoveLearner(id) => raft_node
+                    .raft_remove_learner(id)
+                    .await
+                    .context(ReplicatSnafu)?,
+                MembershipChange::Voters(members) => raft_node
+                    .raft_change_membership(members, false)
+                    .await
+                    .context(ReplicatSnafu)?,
             }
-            raft_node
-                .raft_change_membership(members, false)
-                .await
-                .context(ReplicatSnafu)?;
 
             if vnode.node_id == self.node_id() {
                 self.exec_drop_raft_node(tenant, db_name, vnode.id, replica.id)
@@ -596,8 +794,8 @@ impl RaftNodesManager {
         .context(ReplicatSnafu)?;
         let storage = Arc::new(storage);
 
-        let repl_config = self.replication_config();
-        let node = RaftNode::new(raft_id, info, storage, repl_config)
+        let repl_config = replication_config(&self.config);
+        let node = RaftNode::new(raft_id, info, storage, repl_config, self.network.clone())
             .await
             .context(ReplicatSnafu)?;
 
@@ -634,7 +832,13 @@ impl RaftNodesManager {
 
         // 2. open raft logs storage
         let owner = make_owner(tenant, db_name);
-        let wal_option = tskv::kv_option::WalOptions::from(&self.config);
+        let mut wal_option = tskv::kv_option::WalOptions::from(&self.config);
+        if let Some(tenant_meta) = self.meta.tenant_meta(tenant).await {
+            if let Ok(Some(db_schema)) = tenant_meta.get_db_schema(db_name) {
+                wal_option.wal_sync = db_schema.config().wal_sync();
+            }
+        }
+        let wal_sync = wal_option.wal_sync;
         let wal = wal::VnodeWal::new(Arc::new(wal_option), Arc::new(owner), vnode_id)
             .await
             .context(TskvSnafu)?;
@@ -662,32 +866,18 @@ impl RaftNodesManager {
             self.meta.clone(),
             vnode_store.clone(),
             storage,
+            self.recovery.clone(),
             self.config.service.grpc_enable_gzip,
+            self.config.service.grpc_enable_checksum,
         );
 
         let engine = Arc::new(RwLock::new(engine));
         let raft_logs = Arc::new(RwLock::new(raft_logs));
+        RaftEntryStorage::spawn_interval_sync(&raft_logs, wal_sync);
 
         Ok((engine, raft_logs))
     }
 
-    fn replication_config(&self) -> ReplicationConfig {
-        ReplicationConfig {
-            // raft_logs_to_keep: 100,
-            // snapshot_policy: SnapshotPolicy::LogsSinceLast(100),
-            snapshot_policy: SnapshotPolicy::Never,
-            raft_logs_to_keep: self.config.cluster.raft_logs_to_keep,
-            cluster_name: self.config.global.cluster_name.clone(),
-            lmdb_max_map_size: self.config.cluster.lmdb_max_map_size.try_into().unwrap(),
-            grpc_enable_gzip: self.config.service.grpc_enable_gzip,
-            heartbeat_interval: self.config.cluster.heartbeat_interval.as_millis() as u64,
-            send_append_entries_timeout: self.config.cluster.send_append_entries_timeout.as_millis()
-                as u64,
-            install_snapshot_timeout: self.config.cluster.install_snapshot_timeout.as_millis()
-                as u64,
-        }
-    }
-
     async fn drop_remote_raft_node(
         &self,
         tenant: &str,
@@ -756,3 +946,197 @@ impl RaftNodesManager {
         let _ = raft_nodes.sync_wal_writer().await;
     }
 }
+
+fn replication_config(config: &config::tskv::Config) -> ReplicationConfig {
+    ReplicationConfig {
+        // raft_logs_to_keep: 100,
+        // snapshot_policy: SnapshotPolicy::LogsSinceLast(100),
+        snapshot_policy: SnapshotPolicy::Never,
+        snapshot_logs_size: config.cluster.snapshot_logs_size,
+        snapshot_interval: config.cluster.trigger_snapshot_interval.as_millis() as u64,
+        raft_logs_to_keep: config.cluster.raft_logs_to_keep,
+        purge_replicated_logs: config.cluster.purge_replicated_logs,
+        cluster_name: config.global.cluster_name.clone(),
+        lmdb_max_map_size: config.cluster.lmdb_max_map_size.try_into().unwrap(),
+        grpc_enable_gzip: config.service.grpc_enable_gzip,
+        heartbeat_interval: config.cluster.heartbeat_interval.as_millis() as u64,
+        send_append_entries_timeout: config.cluster.send_append_entries_timeout.as_millis() as u64,
+        install_snapshot_timeout: config.cluster.install_snapshot_timeout.as_millis() as u64,
+        cluster_tls: true,
+        append_entries_max_batch: config.cluster.append_entries_max_batch,
+        // The raft entries of vnodes are stored in the WAL and applied to tskv,
+        // the entry storage engines are used by the meta nodes only.
+        entry_storage: EntryStorageEngine::default(),
+    }
+}
+
+/// The raft ids of the voters of the replica set, the learners are left out.
+fn voter_ids(replica: &ReplicationSet) -> BTreeSet<RaftNodeId> {
+    replica
+        .voters()
+        .map(|vnode| vnode.id as RaftNodeId)
+        .collect()
+}
+
+/// The voters after the vnode is added to the replica set, None if it is added
+/// as a learner which does not change the voters.
+fn voters_after_add(replica: &ReplicationSet, vnode: &VnodeInfo) -> Option<BTreeSet<RaftNodeId>> {
+    if vnode.learner {
+        return None;
+    }
+
+    let mut members = voter_ids(replica);
+    members.insert(vnode.id as RaftNodeId);
+    Some(members)
+}
+
+/// How the raft membership changes when a vnode is removed from the group.
+#[derive(Debug, PartialEq, Eq)]
+enum MembershipChange {
+    /// Change the voters to the members.
+    Voters(BTreeSet<RaftNodeId>),
+    /// Remove the learner, the voters are unchanged.
+    RemoveLearner(RaftNodeId),
+}
+
+fn membership_change_on_remove(replica: &ReplicationSet, vnode: &VnodeInfo) -> MembershipChange {
+    if vnode.learner {
+        return MembershipChange::RemoveLearner(vnode.id as RaftNodeId);
+    }
+
+    let mut members = voter_ids(replica);
+    members.remove(&(vnode.id as RaftNodeId));
+    MembershipChange::Voters(members)
+}
+
+/// Check the vnode to take over the leadership is a voter of the replica set.
+fn check_new_leader(replica: &ReplicationSet, new_leader_id: VnodeId) -> CoordinatorResult<()> {
+    match replica.vnode(new_leader_id) {
+        Some(vnode) if vnode.learner => Err(CoordinatorError::LeaderTransferRejected {
+            replica_id: replica.id,
+            vnode_id: new_leader_id,
+            reason: "a learner can not be the leader".to_string(),
+        }),
+        Some(_) => Ok(()),
+        None => Err(RaftNodeNotFoundSnafu {
+            vnode_id: new_leader_id,
+            replica_id: replica.id,
+        }
+        .build()),
+    }
+}
+
+/// Whether the leadership can be transferred to a vnode lagging behind the
+/// leader by `log_lag` raft entries, None if it is not replicated by the leader.
+fn check_transfer_lag(
+    log_lag: Option<u64>,
+    max_log_lag: u64,
+    membership_changing: bool,
+) -> Result<(), String> {
+    if membership_changing {
+        return Err("the membership is being changed".to_string());
+    }
+    match log_lag {
+        Some(log_lag) if log_lag <= max_log_lag => Ok(()),
+        Some(log_lag) => Err(format!(
+            "lags behind the leader by {log_lag} raft entries, more than {max_log_lag}"
+        )),
+        None => Err("not replicated by the leader".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+    use std::sync::Mutex;
+
+    use models::meta_data::{ReplicationSet, VnodeInfo};
+
+    use super::{
+        check_new_leader, check_transfer_lag, membership_change_on_remove, voter_ids,
+        voters_after_add, MembershipChange, TransferGuard,
+    };
+    use crate::errors::CoordinatorError;
+
+    fn learner(id: u32, node_id: u64) -> VnodeInfo {
+        VnodeInfo {
+            learner: true,
+            ..VnodeInfo::new(id, node_id)
+        }
+    }
+
+    /// Voters 1 and 2, the learner 3.
+    fn replica_with_learner() -> ReplicationSet {
+        ReplicationSet::new(
+            1,
+            1,
+            1,
+            vec![VnodeInfo::new(1, 1), VnodeInfo::new(2, 2), learner(3, 3)],
+        )
+    }
+
+    #[test]
+    fn test_learner_out_of_voters() {
+        let replica = replica_with_learner();
+        assert_eq!(voter_ids(&replica), BTreeSet::from([1, 2]));
+
+        // Adding a learner keeps the voters.
+        assert_eq!(voters_after_add(&replica, &learner(4, 4)), None);
+        // Adding a follower makes it a voter, the learner is still left out.
+        assert_eq!(
+            voters_after_add(&replica, &VnodeInfo::new(4, 4)),
+            Some(BTreeSet::from([1, 2, 4]))
+        );
+    }
+
+    #[test]
+    fn test_remove_learner() {
+        let replica = replica_with_learner();
+        assert_eq!(
+            membership_change_on_remove(&replica, &learner(3, 3)),
+            MembershipChange::RemoveLearner(3)
+        );
+        // Removing a voter does not make the learner a voter.
+        assert_eq!(
+            membership_change_on_remove(&replica, &VnodeInfo::new(2, 2)),
+            MembershipChange::Voters(BTreeSet::from([1]))
+        );
+    }
+
+    #[test]
+    fn test_check_new_leader() {
+        let replica = replica_with_learner();
+        assert!(check_new_leader(&replica, 2).is_ok());
+        assert!(matches!(
+            check_new_leader(&replica, 3),
+            Err(CoordinatorError::LeaderTransferRejected { vnode_id: 3, .. })
+        ));
+        assert!(matches!(
+            check_new_leader(&replica, 4),
+            Err(CoordinatorError::RaftNodeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_transfer_lag() {
+        assert!(check_transfer_lag(Some(0), 0, false).is_ok());
+        assert!(check_transfer_lag(Some(10), 10, false).is_ok());
+        assert!(check_transfer_lag(Some(11), 10, false).is_err());
+        assert!(check_transfer_lag(None, 10, false).is_err());
+        assert!(check_transfer_lag(Some(0), 10, true).is_err());
+    }
+
+    #[test]
+    fn test_transfer_guard() {
+        let transferring = Mutex::new(HashSet::new());
+
+        let guard = TransferGuard::start(&transferring, 1).unwrap();
+        // Another transfer of the same replication set is rejected.
+        assert!(TransferGuard::start(&transferring, 1).is_none());
+        assert!(TransferGuard::start(&transferring, 2).is_some());
+
+        drop(guard);
+        assert!(TransferGuard::start(&transferring, 1).is_some());
+        assert!(transferring.lock().unwrap().is_empty());
+    }
+}
@@ -1,10 +1,16 @@
+use std::collections::HashMap;
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
+use futures::{stream, TryStreamExt};
 use meta::model::MetaRef;
 use models::meta_data::VnodeId;
 use protos::kv_service::tskv_service_client::TskvServiceClient;
-use protos::kv_service::{DownloadFileRequest, RaftWriteCommand};
+use protos::kv_service::{
+    admin_command, AdminCommand, DownloadFileRequest, FetchFilesMetaRequest, RaftWriteCommand,
+};
 use protos::models_helper::parse_prost_bytes;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use replication::errors::{
@@ -12,23 +18,34 @@ use replication::errors::{
 };
 use replication::{ApplyContext, ApplyStorage, EngineMetrics};
 use snafu::ResultExt;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tokio_stream::StreamExt;
 use tonic::transport::Channel;
 use tower::timeout::Timeout;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tskv::file_system::async_filesystem::LocalFileSystem;
+use tskv::file_system::file_info::{self, FileInfo};
 use tskv::file_system::FileSystem;
 use tskv::kv_option::DATA_PATH;
 use tskv::vnode_store::VnodeStorage;
 use tskv::VnodeSnapshot;
 
-use crate::errors::{CommonSnafu, CoordinatorResult, IOErrorsSnafu, MetaSnafu};
+use self::recovery::RecoveryOrchestrator;
+use self::snapshot_manifest::SnapshotManifest;
+use crate::errors::{
+    BincodeSerdeSnafu, ChecksumMismatchSnafu, CommonSnafu, CoordinatorError, CoordinatorResult,
+    IOErrorsSnafu, MetaSnafu, TskvSnafu,
+};
 
 pub mod manager;
-
+pub mod recovery;
+pub mod snapshot_manifest;
+pub mod startup;
 pub mod writer;
 
+/// Times to download a chunk of a file again if it is corrupted on the network.
+const CHECKSUM_MISMATCH_RETRIES: usize = 3;
+
 pub struct TskvEngineStorage {
     tenant: String,
     db_name: String,
@@ -36,7 +53,9 @@ pub struct TskvEngineStorage {
     meta: MetaRef,
     vnode: VnodeStorage,
     storage: tskv::EngineRef,
+    recovery: Arc<RecoveryOrchestrator>,
     grpc_enable_gzip: bool,
+    grpc_enable_checksum: bool,
 }
 
 impl TskvEngineStorage {
@@ -47,16 +66,20 @@ impl TskvEngineStorage {
         meta: MetaRef,
         vnode: VnodeStorage,
         storage: tskv::EngineRef,
+        recovery: Arc<RecoveryOrchestrator>,
         grpc_enable_gzip: bool,
+        grpc_enable_checksum: bool,
     ) -> Self {
         Self {
             meta,
             vnode_id,
             storage,
             vnode,
+            recovery,
             tenant: tenant.to_owned(),
             db_name: db_name.to_owned(),
             grpc_enable_gzip,
+            grpc_enable_checksum,
         }
     }
 
@@ -70,7 +93,7 @@ impl TskvEngineStorage {
             .get_node_conn(snapshot.node_id)
             .await
             .context(MetaSnafu)?;
-        let mut client = tskv_service_time_out_client(
+        let client = tskv_service_time_out_client(
             channel,
             Duration::from_secs(60 * 60),
             DEFAULT_GRPC_SERVER_MESSAGE_LEN,
@@ -78,64 +101,260 @@ impl TskvEngineStorage {
         );
 
         info!("download snapshot to path: {:?}", dir);
-        if let Err(err) = self
-            .download_snapshot_files(dir, snapshot, &mut client)
+        tokio::fs::create_dir_all(dir)
             .await
-        {
-            tokio::fs::remove_dir_all(&dir)
-                .await
-                .context(IOErrorsSnafu)?;
-            return Err(err);
-        }
+            .context(IOErrorsSnafu)?;
+        // Keep the downloaded files on failure, the retry resumes by the manifest.
+        self.download_snapshot_files(dir, snapshot, &client).await?;
 
         info!("success download snapshot all files");
 
         Ok(())
     }
 
+    /// Remove the download directories of other snapshots of the vnode, which are
+    /// left by failed downloads.
+    async fn remove_stale_snapshot_dirs(&self, parent: &Path, snapshot_name: &str) {
+        let mut entries = match tokio::fs::read_dir(parent).await {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_vnode_snapshot = name.starts_with("snap_")
+                && name.split('_').nth(2) == Some(self.vnode_id.to_string().as_str());
+            if is_vnode_snapshot && name != snapshot_name {
+                info!("remove stale snapshot directory: {:?}", entry.path());
+                if let Err(err) = tokio::fs::remove_dir_all(entry.path()).await {
+                    error!("remove stale snapshot directory failed: {}", err);
+                }
+            }
+        }
+    }
+
     async fn download_snapshot_files(
         &self,
         dir: &Path,
         snapshot: &VnodeSnapshot,
-        client: &mut TskvServiceClient<Timeout<Channel>>,
+        client: &TskvServiceClient<Timeout<Channel>>,
     ) -> CoordinatorResult<()> {
         let src_dir = PathBuf::from(DATA_PATH)
             .join(&snapshot.version_edit.tsf_name)
             .join(snapshot.vnode_id.to_string());
 
+        let mut manifest = SnapshotManifest::load(dir).await;
+        let mut expected = Vec::with_capacity(snapshot.version_edit.add_files.len());
+        let mut pending = vec![];
         for info in snapshot.version_edit.add_files.iter() {
-            let filename = dir.join(info.relative_path());
-            let src_filename = src_dir
-                .join(info.relative_path())
-                .to_string_lossy()
-                .to_string();
-
-            info!(
-                "begin download file {} -> {:?}, from {}",
-                src_filename, filename, snapshot.node_id
-            );
-
-            Self::download_file(&src_filename, &filename, client).await?;
-            let filename = filename.to_string_lossy().to_string();
-            let length = LocalFileSystem::get_file_length(filename);
-            if info.file_size != length {
-                return Err(CommonSnafu {
-                    msg: format!(
-                        "download file length not match {} -> {}",
-                        info.file_size, length
-                    ),
+            let relative_path = info.relative_path().to_string_lossy().to_string();
+            expected.push((relative_path.clone(), info.file_size));
+            if manifest
+                .is_downloaded(dir, &relative_path, info.file_size)
+                .await
+            {
+                info!("skip downloaded file {:?}", dir.join(&relative_path));
+                self.recovery.add_resumed(self.vnode_id, info.file_size);
+                continue;
+            }
+
+            manifest.remove_file(&relative_path);
+            pending.push((relative_path, info.file_size));
+        }
+
+        let pending = self
+            .link_local_files(dir, snapshot, &src_dir, pending, &mut manifest, client)
+            .await?;
+
+        let downloads = pending.into_iter().map(|(relative_path, file_size)| {
+            let src_filename = src_dir.join(&relative_path).to_string_lossy().to_string();
+            let filename = dir.join(&relative_path);
+            let client = client.clone();
+            Ok::<_, CoordinatorError>(async move {
+                let _permit = self.recovery.acquire_transfer().await;
+                let offset = partial_download_len(&filename, file_size).await;
+                info!(
+                    "begin download file {} -> {:?} from offset {}, from {}",
+                    src_filename, filename, offset, snapshot.node_id
+                );
+                self.download_file(&src_filename, &filename, offset, client)
+                    .await?;
+                check_file_length(&filename, file_size)?;
+                Ok::<_, CoordinatorError>((relative_path, offset > 0))
+            })
+        });
+        let mut downloaded =
+            stream::iter(downloads).try_buffer_unordered(self.recovery.download_parallelism());
+        let mut resumed = vec![];
+        while let Some((relative_path, is_resumed)) = downloaded.try_next().await? {
+            manifest.add_file(dir, &relative_path).await?;
+            manifest.save(dir).await?;
+            if is_resumed {
+                resumed.push(relative_path);
+            }
+        }
+
+        // The head of a resumed file is written by an interrupted download, check
+        // it with the source file.
+        if !resumed.is_empty() {
+            let filenames = resumed
+                .iter()
+                .map(|p| src_dir.join(p).to_string_lossy().to_string())
+                .collect();
+            let src_files = self.fetch_files_meta(client, filenames).await?;
+            for relative_path in resumed {
+                let src_filename = src_dir.join(&relative_path).to_string_lossy().to_string();
+                if manifest.md5(&relative_path) != src_files.get(&src_filename).map(|f| &f.md5) {
+                    error!(
+                        "resumed download file {:?} not match the source",
+                        dir.join(&relative_path)
+                    );
+                    manifest.remove_file(&relative_path);
+                    let _ = tokio::fs::remove_file(dir.join(&relative_path)).await;
                 }
-                .build());
             }
+            manifest.save(dir).await?;
+        }
+
+        let report = manifest.verify(dir, &expected).await?;
+        info!(
+            "verify snapshot files in {:?}: {}",
+            dir,
+            serde_json::to_string(&report).unwrap_or_default()
+        );
+        if !report.is_ok() {
+            for relative_path in report.missing.iter().chain(report.mismatched.iter()) {
+                manifest.remove_file(relative_path);
+            }
+            manifest.save(dir).await?;
+            return Err(CommonSnafu {
+                msg: format!(
+                    "verify snapshot files failed, missing: {:?}, mismatched: {:?}",
+                    report.missing, report.mismatched
+                ),
+            }
+            .build());
         }
 
         Ok(())
     }
 
+    /// Link the files of the snapshot which are in the directory of the vnode
+    /// already into the download directory, files with the same size and md5
+    /// are the same. Returns the files to download.
+    async fn link_local_files(
+        &self,
+        dir: &Path,
+        snapshot: &VnodeSnapshot,
+        src_dir: &Path,
+        pending: Vec<(String, u64)>,
+        manifest: &mut SnapshotManifest,
+        client: &TskvServiceClient<Timeout<Channel>>,
+    ) -> CoordinatorResult<Vec<(String, u64)>> {
+        let opt = self.storage.get_storage_options();
+        let vnode_dir = opt.ts_family_dir(&snapshot.version_edit.tsf_name, self.vnode_id);
+        let mut local_files: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for path in file_info::list_files(&vnode_dir) {
+            if let Ok(meta) = tokio::fs::metadata(&path).await {
+                local_files
+                    .entry(meta.len())
+                    .or_default()
+                    .push(PathBuf::from(path));
+            }
+        }
+
+        let filenames = pending
+            .iter()
+            .filter(|(_, size)| local_files.contains_key(size))
+            .map(|(p, _)| src_dir.join(p).to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        if filenames.is_empty() {
+            return Ok(pending);
+        }
+        let src_files = match self.fetch_files_meta(client, filenames).await {
+            Ok(files) => files,
+            Err(err) => {
+                warn!(
+                    "fetch files meta of snapshot failed, download all files: {}",
+                    err
+                );
+                return Ok(pending);
+            }
+        };
+
+        let mut local_md5 = HashMap::new();
+        let mut remaining = vec![];
+        for (relative_path, size) in pending {
+            let src_filename = src_dir.join(&relative_path).to_string_lossy().to_string();
+            let (src_file, paths) = match (src_files.get(&src_filename), local_files.get(&size)) {
+                (Some(src_file), Some(paths)) => (src_file, paths),
+                _ => {
+                    remaining.push((relative_path, size));
+                    continue;
+                }
+            };
+
+            let mut linked = false;
+            for path in paths {
+                if !local_md5.contains_key(path) {
+                    let info = file_info::get_file_info(&path.to_string_lossy())
+                        .await
+                        .context(TskvSnafu)?;
+                    local_md5.insert(path.clone(), info.md5);
+                }
+                if local_md5.get(path) != Some(&src_file.md5) {
+                    continue;
+                }
+
+                let filename = dir.join(&relative_path);
+                match link_file(path, &filename).await {
+                    Ok(()) => {
+                        info!("reuse local file {:?} -> {:?}", path, filename);
+                        manifest.add_file(dir, &relative_path).await?;
+                        self.recovery.add_resumed(self.vnode_id, size);
+                        linked = true;
+                        break;
+                    }
+                    // The file may be removed by a compaction.
+                    Err(err) => warn!("link local file {:?} failed: {}", path, err),
+                }
+            }
+            if !linked {
+                remaining.push((relative_path, size));
+            }
+        }
+        manifest.save(dir).await?;
+
+        Ok(remaining)
+    }
+
+    /// Fetch the sizes and md5 of the files on the node of the client, returns
+    /// the files by name.
+    async fn fetch_files_meta(
+        &self,
+        client: &TskvServiceClient<Timeout<Channel>>,
+        filenames: Vec<String>,
+    ) -> CoordinatorResult<HashMap<String, FileInfo>> {
+        let request = tonic::Request::new(AdminCommand {
+            tenant: self.tenant.clone(),
+            command: Some(admin_command::Command::FetchFilesMeta(
+                FetchFilesMetaRequest { filenames },
+            )),
+        });
+        let response = client.clone().admin_request(request).await?.into_inner();
+        let data = crate::errors::decode_grpc_response(response)?;
+        let files: Vec<FileInfo> = bincode::deserialize(&data).context(BincodeSerdeSnafu)?;
+
+        Ok(files.into_iter().map(|f| (f.name.clone(), f)).collect())
+    }
+
+    /// Download the file from the offset, the head of the file before the
+    /// offset is kept.
     async fn download_file(
+        &self,
         download: &str,
         filename: &Path,
-        client: &mut TskvServiceClient<Timeout<Channel>>,
+        offset: u64,
+        mut client: TskvServiceClient<Timeout<Channel>>,
     ) -> CoordinatorResult<()> {
         if let Some(dir) = filename.parent() {
             tokio::fs::create_dir_all(dir)
@@ -145,24 +364,54 @@ impl TskvEngineStorage {
 
         let mut file = tokio::fs::OpenOptions::new()
             .create(true)
-            .truncate(true)
             .read(true)
             .write(true)
             .open(filename)
             .await
             .context(IOErrorsSnafu)?;
+        file.set_len(offset).await.context(IOErrorsSnafu)?;
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .context(IOErrorsSnafu)?;
+        self.recovery.add_resumed(self.vnode_id, offset);
+
+        let mut offset = offset;
+        let mut retries = 0;
+        loop {
+            let request = tonic::Request::new(DownloadFileRequest {
+                filename: download.to_string(),
+                offset,
+                checksum: self.grpc_enable_checksum,
+            });
+            let mut resp_stream = client.download_file(request).await?.into_inner();
+            let mut corrupted = false;
+            while let Some(received) = resp_stream.next().await {
+                let received = received?;
+                if !received.verify_checksum() {
+                    corrupted = true;
+                    break;
+                }
+                let data = crate::errors::decode_grpc_response(received)?;
+                self.recovery.throttle_write(data.len()).await;
+                file.write_all(&data).await.context(IOErrorsSnafu)?;
+                offset += data.len() as u64;
+                self.recovery
+                    .add_downloaded(self.vnode_id, data.len() as u64);
+            }
+            if !corrupted {
+                return Ok(());
+            }
 
-        let request = tonic::Request::new(DownloadFileRequest {
-            filename: download.to_string(),
-        });
-        let mut resp_stream = client.download_file(request).await?.into_inner();
-        while let Some(received) = resp_stream.next().await {
-            let received = received?;
-            let data = crate::errors::decode_grpc_response(received)?;
-            file.write_all(&data).await.context(IOErrorsSnafu)?;
+            // The chunks before the corrupted one are kept, resume from it.
+            retries += 1;
+            if retries > CHECKSUM_MISMATCH_RETRIES {
+                return Err(ChecksumMismatchSnafu {
+                    data: format!("file {download}"),
+                }
+                .build());
+            }
+            warn!("Checksum mismatch of file {download} at offset {offset}, download it again");
         }
-
-        Ok(())
     }
 
     async fn exec_apply(
@@ -184,6 +433,39 @@ impl TskvEngineStorage {
     }
 }
 
+/// Length of the file left by an interrupted download, which is resumed from it.
+async fn partial_download_len(filename: &Path, expected: u64) -> u64 {
+    match tokio::fs::metadata(filename).await {
+        Ok(meta) if meta.len() <= expected => meta.len(),
+        _ => 0,
+    }
+}
+
+/// Hard link the file, copy it if the link fails.
+async fn link_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if let Some(dir) = dst.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let _ = tokio::fs::remove_file(dst).await;
+    if tokio::fs::hard_link(src, dst).await.is_err() {
+        tokio::fs::copy(src, dst).await?;
+    }
+
+    Ok(())
+}
+
+fn check_file_length(filename: &Path, expected: u64) -> CoordinatorResult<()> {
+    let length = LocalFileSystem::get_file_length(filename.to_string_lossy().to_string());
+    if expected != length {
+        return Err(CommonSnafu {
+            msg: format!("download file length not match {} -> {}", expected, length),
+        }
+        .build());
+    }
+
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl ApplyStorage for TskvEngineStorage {
     async fn apply(
@@ -195,6 +477,7 @@ impl ApplyStorage for TskvEngineStorage {
         if let Err(err) = &apply_result {
             error!("replication apply failed: {:?}; {:?}", ctx, err);
         }
+        self.meta.update_vnode_applied(self.vnode_id, ctx.index);
         let apply_result = apply_result.map_err(|e| e.error_code().message());
 
         match bincode::serialize(&apply_result) {
@@ -243,20 +526,38 @@ impl ApplyStorage for TskvEngineStorage {
             "snap_{}_{}_{}_{}",
             snapshot.node_id, snapshot.vnode_id, snapshot.last_seq_no, snapshot.create_time
         );
-        let download_dir = opt.path().join(snapshot_name);
-
-        self.download_snapshot(&download_dir, &snapshot)
-            .await
-            .map_err(|err| ReplicationError::RestoreSnapshotErr {
+        let download_dir = opt.path().join(&snapshot_name);
+        self.remove_stale_snapshot_dirs(&opt.path(), &snapshot_name)
+            .await;
+
+        let total_bytes = snapshot
+            .version_edit
+            .add_files
+            .iter()
+            .map(|f| f.file_size)
+            .sum();
+        self.recovery
+            .begin(&self.tenant, &self.db_name, self.vnode_id, total_bytes);
+
+        if let Err(err) = self.download_snapshot(&download_dir, &snapshot).await {
+            self.recovery.finish(self.vnode_id, Some(err.to_string()));
+            return Err(ReplicationError::RestoreSnapshotErr {
                 msg: err.to_string(),
-            })?;
+            });
+        }
 
-        self.vnode
+        self.recovery.set_applying(self.vnode_id);
+        if let Err(err) = self
+            .vnode
             .apply_snapshot(snapshot, download_dir.as_path())
             .await
-            .map_err(|err| ReplicationError::RestoreSnapshotErr {
+        {
+            self.recovery.finish(self.vnode_id, Some(err.to_string()));
+            return Err(ReplicationError::RestoreSnapshotErr {
                 msg: err.to_string(),
-            })?;
+            });
+        }
+        self.recovery.finish(self.vnode_id, None);
 
         tokio::fs::remove_dir_all(download_dir)
             .await
@@ -267,6 +568,7 @@ impl ApplyStorage for TskvEngineStorage {
 
     async fn destory(&mut self) -> ReplicationResult<()> {
         info!("destory vnode id: {}", self.vnode_id);
+        self.meta.remove_vnode_applied(self.vnode_id);
         self.storage
             .remove_tsfamily(&self.tenant, &self.db_name, self.vnode_id)
             .await
@@ -281,3 +583,33 @@ impl ApplyStorage for TskvEngineStorage {
         Ok(self.vnode.metrics().await)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{link_file, partial_download_len};
+
+    #[tokio::test]
+    async fn test_reuse_snapshot_files() {
+        let dir = std::path::PathBuf::from("/tmp/test/coordinator/raft/test_reuse_snapshot_files");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let src = dir.join("vnode/tsm/_000001.tsm");
+        std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+        std::fs::write(&src, b"hello world").unwrap();
+
+        // Interrupted download.
+        let dst = dir.join("snapshot/tsm/_000001.tsm");
+        assert_eq!(partial_download_len(&dst, 11).await, 0);
+        std::fs::create_dir_all(dst.parent().unwrap()).unwrap();
+        std::fs::write(&dst, b"hello").unwrap();
+        assert_eq!(partial_download_len(&dst, 11).await, 5);
+        assert_eq!(partial_download_len(&dst, 3).await, 0);
+
+        // The partial file is replaced by the local file.
+        link_file(&src, &dst).await.unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), b"hello world");
+        std::fs::remove_file(&src).unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), b"hello world");
+    }
+}
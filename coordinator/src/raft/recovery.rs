@@ -0,0 +1,335 @@
+//! Restore of vnodes from the snapshots of their leaders.
+//!
+//! Raft groups install snapshots independently, so the vnodes of a node being
+//! rebuilt are restored in parallel. The files downloaded by all restores share
+//! a limit on concurrent transfers and a limit on the disk write bandwidth, the
+//! files of a snapshot are downloaded in parallel within them. The files sent
+//! to the restores on other nodes share a limit on the network bandwidth. The
+//! progress of each restore is kept to estimate the remaining time of the
+//! recovery of the node from the download throughput.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use models::meta_data::{NodeId, VnodeId};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryStatus {
+    Downloading,
+    Applying,
+    Done,
+    Failed,
+}
+
+impl RecoveryStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VnodeRecovery {
+    tenant: String,
+    database: String,
+    status: RecoveryStatus,
+    total_bytes: u64,
+    downloaded_bytes: u64,
+    /// Bytes of the files downloaded by a previous attempt.
+    resumed_bytes: u64,
+    started: Instant,
+    finished: Option<Instant>,
+    error: Option<String>,
+}
+
+/// Progress of the restore of a vnode, reported to other nodes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VnodeRecoveryProgress {
+    pub tenant: String,
+    pub database: String,
+    pub vnode_id: VnodeId,
+    pub status: RecoveryStatus,
+    pub total_bytes: u64,
+    pub downloaded_bytes: u64,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Progress of the restores on a node, `eta_ms` is None if nothing is being
+/// restored or the throughput is unknown yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeRecoveryProgress {
+    pub node_id: NodeId,
+    pub eta_ms: Option<u64>,
+    pub vnodes: Vec<VnodeRecoveryProgress>,
+}
+
+pub struct RecoveryOrchestrator {
+    node_id: NodeId,
+    transfers: Semaphore,
+    download_parallelism: usize,
+    write_limiter: Option<BandwidthLimiter>,
+    send_limiter: Option<BandwidthLimiter>,
+    recoveries: Mutex<BTreeMap<VnodeId, VnodeRecovery>>,
+}
+
+impl RecoveryOrchestrator {
+    /// `write_bandwidth` and `send_bandwidth` are in bytes per second, 0 means unlimited.
+    pub fn new(
+        node_id: NodeId,
+        max_concurrent_transfers: usize,
+        download_parallelism: usize,
+        write_bandwidth: u64,
+        send_bandwidth: u64,
+    ) -> Self {
+        Self {
+            node_id,
+            transfers: Semaphore::new(max_concurrent_transfers.max(1)),
+            download_parallelism: download_parallelism.max(1),
+            write_limiter: (write_bandwidth > 0).then(|| BandwidthLimiter::new(write_bandwidth)),
+            send_limiter: (send_bandwidth > 0).then(|| BandwidthLimiter::new(send_bandwidth)),
+            recoveries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Files of a snapshot downloaded at the same time.
+    pub fn download_parallelism(&self) -> usize {
+        self.download_parallelism
+    }
+
+    /// Wait for a slot to download a file, the slot is released when the permit drops.
+    pub async fn acquire_transfer(&self) -> SemaphorePermit<'_> {
+        self.transfers
+            .acquire()
+            .await
+            .expect("the transfer semaphore is never closed")
+    }
+
+    /// Wait until `bytes` can be written within the write bandwidth.
+    pub async fn throttle_write(&self, bytes: usize) {
+        if let Some(limiter) = &self.write_limiter {
+            limiter.throttle(bytes).await;
+        }
+    }
+
+    /// Wait until `bytes` of a snapshot file can be sent within the send bandwidth.
+    pub async fn throttle_send(&self, bytes: usize) {
+        if let Some(limiter) = &self.send_limiter {
+            limiter.throttle(bytes).await;
+        }
+    }
+
+    /// Start the restore of a vnode, replaces the record of a previous restore.
+    pub fn begin(&self, tenant: &str, database: &str, vnode_id: VnodeId, total_bytes: u64) {
+        let recovery = VnodeRecovery {
+            tenant: tenant.to_string(),
+            database: database.to_string(),
+            status: RecoveryStatus::Downloading,
+            total_bytes,
+            downloaded_bytes: 0,
+            resumed_bytes: 0,
+            started: Instant::now(),
+            finished: None,
+            error: None,
+        };
+        self.lock().insert(vnode_id, recovery);
+    }
+
+    /// Count the bytes of a file downloaded by a previous attempt.
+    pub fn add_resumed(&self, vnode_id: VnodeId, bytes: u64) {
+        if let Some(recovery) = self.lock().get_mut(&vnode_id) {
+            recovery.downloaded_bytes += bytes;
+            recovery.resumed_bytes += bytes;
+        }
+    }
+
+    pub fn add_downloaded(&self, vnode_id: VnodeId, bytes: u64) {
+        if let Some(recovery) = self.lock().get_mut(&vnode_id) {
+            recovery.downloaded_bytes += bytes;
+        }
+    }
+
+    pub fn set_applying(&self, vnode_id: VnodeId) {
+        if let Some(recovery) = self.lock().get_mut(&vnode_id) {
+            recovery.status = RecoveryStatus::Applying;
+        }
+    }
+
+    pub fn finish(&self, vnode_id: VnodeId, error: Option<String>) {
+        if let Some(recovery) = self.lock().get_mut(&vnode_id) {
+            recovery.status = if error.is_some() {
+                RecoveryStatus::Failed
+            } else {
+                RecoveryStatus::Done
+            };
+            recovery.finished = Some(Instant::now());
+            recovery.error = error;
+        }
+    }
+
+    /// The restores of the vnodes of the tenant, all tenants if None.
+    pub fn progress(&self, tenant: Option<&str>) -> NodeRecoveryProgress {
+        let now = Instant::now();
+        let recoveries = self.lock();
+        let eta = estimate_eta(recoveries.values(), now);
+        let vnodes = recoveries
+            .iter()
+            .filter(|(_, r)| tenant.map_or(true, |t| r.tenant == t))
+            .map(|(vnode_id, r)| VnodeRecoveryProgress {
+                tenant: r.tenant.clone(),
+                database: r.database.clone(),
+                vnode_id: *vnode_id,
+                status: r.status,
+                total_bytes: r.total_bytes,
+                downloaded_bytes: r.downloaded_bytes,
+                elapsed_ms: (r.finished.unwrap_or(now) - r.started).as_millis() as u64,
+                error: r.error.clone(),
+            })
+            .collect();
+
+        NodeRecoveryProgress {
+            node_id: self.node_id,
+            eta_ms: eta.map(|d| d.as_millis() as u64),
+            vnodes,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<VnodeId, VnodeRecovery>> {
+        self.recoveries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Estimate the time to download the rest of the unfinished restores by the
+/// throughput since the earliest of them started.
+fn estimate_eta<'a>(
+    recoveries: impl Iterator<Item = &'a VnodeRecovery>,
+    now: Instant,
+) -> Option<Duration> {
+    let mut active = false;
+    let mut earliest = now;
+    let (mut remaining, mut transferred) = (0_u64, 0_u64);
+    for r in recoveries.filter(|r| !r.status.is_finished()) {
+        active = true;
+        earliest = earliest.min(r.started);
+        remaining += r.total_bytes.saturating_sub(r.downloaded_bytes);
+        transferred += r.downloaded_bytes.saturating_sub(r.resumed_bytes);
+    }
+
+    if !active {
+        return None;
+    }
+    if remaining == 0 {
+        return Some(Duration::ZERO);
+    }
+    let elapsed = (now - earliest).as_secs_f64();
+    if transferred == 0 || elapsed <= 0.0 {
+        return None;
+    }
+
+    Some(Duration::from_secs_f64(
+        remaining as f64 * elapsed / transferred as f64,
+    ))
+}
+
+/// Limits the bytes transferred per second, transfers reserve the bandwidth in turn.
+struct BandwidthLimiter {
+    bytes_per_sec: u64,
+    /// When the reserved bandwidth is used up.
+    next_free: Mutex<Option<Instant>>,
+}
+
+impl BandwidthLimiter {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            next_free: Mutex::new(None),
+        }
+    }
+
+    async fn throttle(&self, bytes: usize) {
+        let wait = self.reserve(bytes as u64, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Reserve the bandwidth to transfer `bytes`, returns how long to wait before it.
+    fn reserve(&self, bytes: u64, now: Instant) -> Duration {
+        let mut next_free = self
+            .next_free
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let start = next_free.map_or(now, |t| t.max(now));
+        let cost = Duration::from_secs_f64(bytes as f64 / self.bytes_per_sec as f64);
+        *next_free = Some(start + cost);
+
+        start - now
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{estimate_eta, BandwidthLimiter, RecoveryStatus, VnodeRecovery};
+
+    fn recovery(started: Instant, total: u64, downloaded: u64, resumed: u64) -> VnodeRecovery {
+        VnodeRecovery {
+            tenant: "cnosdb".to_string(),
+            database: "public".to_string(),
+            status: RecoveryStatus::Downloading,
+            total_bytes: total,
+            downloaded_bytes: downloaded,
+            resumed_bytes: resumed,
+            started,
+            finished: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_estimate_eta() {
+        let start = Instant::now();
+        let now = start + Duration::from_secs(10);
+
+        // 300 bytes in 10s, 600 bytes remaining.
+        let recoveries = vec![
+            recovery(start, 500, 200, 0),
+            recovery(start + Duration::from_secs(5), 600, 300, 200),
+        ];
+        assert_eq!(
+            estimate_eta(recoveries.iter(), now),
+            Some(Duration::from_secs(20))
+        );
+
+        // Only the resumed files so far.
+        let recoveries = vec![recovery(start, 500, 200, 200)];
+        assert_eq!(estimate_eta(recoveries.iter(), now), None);
+
+        let mut done = recovery(start, 500, 500, 0);
+        done.status = RecoveryStatus::Done;
+        assert_eq!(estimate_eta([done.clone()].iter(), now), None);
+
+        let mut applying = done;
+        applying.status = RecoveryStatus::Applying;
+        assert_eq!(estimate_eta([applying].iter(), now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_bandwidth_limiter() {
+        let limiter = BandwidthLimiter::new(100);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(50, now), Duration::ZERO);
+        assert_eq!(limiter.reserve(50, now), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(100, now), Duration::from_secs(1));
+
+        // The unused bandwidth is not accumulated.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(100, later), Duration::ZERO);
+        assert_eq!(limiter.reserve(10, later), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tracing::warn;
+use tskv::file_system::file_info::get_file_info;
+
+use crate::errors::{CommonSnafu, CoordinatorResult, IOErrorsSnafu, TskvSnafu};
+
+/// Name of the manifest file in the snapshot download directory.
+pub const SNAPSHOT_MANIFEST_FILE: &str = "snapshot_manifest.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub size: u64,
+    pub md5: String,
+}
+
+/// Files of a snapshot which are downloaded completely, persisted in the download
+/// directory so that a retry only downloads the missing or mismatched files.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SnapshotManifest {
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+/// Result of verifying the downloaded files of a snapshot.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SnapshotVerifyReport {
+    pub files: usize,
+    pub bytes: u64,
+    pub verified: usize,
+    pub missing: Vec<String>,
+    pub mismatched: Vec<String>,
+}
+
+impl SnapshotVerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+impl SnapshotManifest {
+    /// Load the manifest in the directory, returns an empty manifest if not exists or corrupted.
+    pub async fn load(dir: &Path) -> Self {
+        let path = dir.join(SNAPSHOT_MANIFEST_FILE);
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(_) => return Self::default(),
+        };
+        serde_json::from_slice(&data).unwrap_or_else(|e| {
+            warn!("ignore corrupted snapshot manifest {:?}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub async fn save(&self, dir: &Path) -> CoordinatorResult<()> {
+        let data = serde_json::to_vec(self).map_err(|e| {
+            CommonSnafu {
+                msg: format!("serialize snapshot manifest failed: {}", e),
+            }
+            .build()
+        })?;
+
+        let tmp_path = dir.join(format!("{}.tmp", SNAPSHOT_MANIFEST_FILE));
+        tokio::fs::write(&tmp_path, data)
+            .await
+            .context(IOErrorsSnafu)?;
+        tokio::fs::rename(&tmp_path, dir.join(SNAPSHOT_MANIFEST_FILE))
+            .await
+            .context(IOErrorsSnafu)?;
+
+        Ok(())
+    }
+
+    /// Whether the file is downloaded with the expected size.
+    pub async fn is_downloaded(&self, dir: &Path, relative_path: &str, size: u64) -> bool {
+        match self.files.get(relative_path) {
+            Some(entry) if entry.size == size => tokio::fs::metadata(dir.join(relative_path))
+                .await
+                .map(|m| m.len() == size)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Record the downloaded file with its size and md5.
+    pub async fn add_file(&mut self, dir: &Path, relative_path: &str) -> CoordinatorResult<()> {
+        let path = dir.join(relative_path).to_string_lossy().to_string();
+        let info = get_file_info(&path).await.context(TskvSnafu)?;
+        self.files.insert(
+            relative_path.to_string(),
+            ManifestEntry {
+                size: info.size,
+                md5: info.md5,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// The md5 of the downloaded file.
+    pub fn md5(&self, relative_path: &str) -> Option<&String> {
+        self.files.get(relative_path).map(|e| &e.md5)
+    }
+
+    pub fn remove_file(&mut self, relative_path: &str) {
+        self.files.remove(relative_path);
+    }
+
+    /// Check the size and md5 of all expected files `(relative path, size)`.
+    pub async fn verify(
+        &self,
+        dir: &Path,
+        expected: &[(String, u64)],
+    ) -> CoordinatorResult<SnapshotVerifyReport> {
+        let mut report = SnapshotVerifyReport {
+            files: expected.len(),
+            ..Default::default()
+        };
+        for (relative_path, size) in expected {
+            let entry = match self.files.get(relative_path) {
+                Some(entry) => entry,
+                None => {
+                    report.missing.push(relative_path.clone());
+                    continue;
+                }
+            };
+
+            let path = dir.join(relative_path).to_string_lossy().to_string();
+            match get_file_info(&path).await {
+                Ok(info) if info.size == *size && info.md5 == entry.md5 => {
+                    report.verified += 1;
+                    report.bytes += info.size;
+                }
+                Ok(_) => report.mismatched.push(relative_path.clone()),
+                Err(_) => report.missing.push(relative_path.clone()),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SnapshotManifest;
+
+    #[tokio::test]
+    async fn test_snapshot_manifest() {
+        let dir = std::path::PathBuf::from("/tmp/test/coordinator/snapshot_manifest");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.as_path();
+        tokio::fs::write(dir.join("a.tsm"), b"hello").await.unwrap();
+        tokio::fs::write(dir.join("b.tsm"), b"world").await.unwrap();
+        let expected = vec![("a.tsm".to_string(), 5), ("b.tsm".to_string(), 5)];
+
+        let mut manifest = SnapshotManifest::load(dir).await;
+        assert!(!manifest.is_downloaded(dir, "a.tsm", 5).await);
+        manifest.add_file(dir, "a.tsm").await.unwrap();
+        manifest.save(dir).await.unwrap();
+
+        let mut manifest = SnapshotManifest::load(dir).await;
+        assert!(manifest.is_downloaded(dir, "a.tsm", 5).await);
+        assert!(!manifest.is_downloaded(dir, "a.tsm", 6).await);
+        let report = manifest.verify(dir, &expected).await.unwrap();
+        assert_eq!(report.verified, 1);
+        assert_eq!(report.missing, vec!["b.tsm".to_string()]);
+
+        manifest.add_file(dir, "b.tsm").await.unwrap();
+        tokio::fs::write(dir.join("b.tsm"), b"WORLD").await.unwrap();
+        let report = manifest.verify(dir, &expected).await.unwrap();
+        assert_eq!(report.mismatched, vec!["b.tsm".to_string()]);
+        assert!(!report.is_ok());
+    }
+}
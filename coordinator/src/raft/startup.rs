@@ -0,0 +1,163 @@
+//! Progress of opening the local raft nodes on startup.
+//!
+//! The vnodes replay their WAL concurrently, at most as many at a time as the
+//! CPUs of the deployment. The state and the time taken of the replay of each
+//! vnode, and the number of vnodes replayed, are reported through the metrics
+//! registry, so that a slow restart can be watched while it runs.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use metrics::count::U64Counter;
+use metrics::gauge::U64Gauge;
+use metrics::label::Labels;
+use metrics::metric_register::MetricsRegister;
+use replication::state_store::RaftNodeSummary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VnodeStartupState {
+    Waiting = 0,
+    Replaying = 1,
+    Done = 2,
+    Failed = 3,
+}
+
+pub struct StartupRecoveryMetrics {
+    register: Arc<MetricsRegister>,
+    finished: U64Counter,
+}
+
+impl StartupRecoveryMetrics {
+    pub fn new(register: Arc<MetricsRegister>, total: usize) -> Self {
+        register
+            .metric::<U64Gauge>("startup_recovery_vnodes", "vnodes to be opened on startup")
+            .recorder(Labels::default())
+            .set(total as u64);
+        let finished = register
+            .metric::<U64Counter>(
+                "startup_recovery_vnodes_finished",
+                "vnodes opened or failed on startup",
+            )
+            .recorder(Labels::default());
+        Self { register, finished }
+    }
+
+    pub fn vnode(&self, summary: &RaftNodeSummary) -> VnodeStartupMetrics {
+        let vnode_id = summary.raft_id.to_string();
+        let replica_id = summary.group_id.to_string();
+        let labels = [
+            ("tenant", summary.tenant.as_str()),
+            ("database", summary.db_name.as_str()),
+            ("replica_id", replica_id.as_str()),
+            ("vnode_id", vnode_id.as_str()),
+        ];
+        let state = self
+            .register
+            .metric::<U64Gauge>(
+                "vnode_startup_recovery_state",
+                "0: waiting, 1: replaying, 2: done, 3: failed",
+            )
+            .recorder(labels);
+        let elapsed_ms = self
+            .register
+            .metric::<U64Gauge>(
+                "vnode_startup_recovery_time",
+                "time taken to replay the vnode on startup(ms)",
+            )
+            .recorder(labels);
+        state.set(VnodeStartupState::Waiting as u64);
+
+        VnodeStartupMetrics {
+            state,
+            elapsed_ms,
+            finished: self.finished.clone(),
+            started: None,
+        }
+    }
+}
+
+pub struct VnodeStartupMetrics {
+    state: U64Gauge,
+    elapsed_ms: U64Gauge,
+    finished: U64Counter,
+    started: Option<Instant>,
+}
+
+impl VnodeStartupMetrics {
+    pub fn start(&mut self) {
+        self.started = Some(Instant::now());
+        self.state.set(VnodeStartupState::Replaying as u64);
+    }
+
+    pub fn finish(&self, success: bool) {
+        let state = if success {
+            VnodeStartupState::Done
+        } else {
+            VnodeStartupState::Failed
+        };
+        self.state.set(state as u64);
+        if let Some(started) = self.started {
+            self.elapsed_ms.set(started.elapsed().as_millis() as u64);
+        }
+        self.finished.inc_one();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use metrics::count::U64Counter;
+    use metrics::gauge::U64Gauge;
+    use metrics::label::Labels;
+    use metrics::metric_register::MetricsRegister;
+    use replication::state_store::RaftNodeSummary;
+
+    use super::{StartupRecoveryMetrics, VnodeStartupState};
+
+    #[test]
+    fn test_startup_recovery_metrics() {
+        let register = Arc::new(MetricsRegister::default());
+        let metrics = StartupRecoveryMetrics::new(register.clone(), 2);
+        let summary = |raft_id| RaftNodeSummary {
+            raft_id,
+            group_id: 10,
+            tenant: "cnosdb".to_string(),
+            db_name: "public".to_string(),
+        };
+        let state = |raft_id: u64| {
+            let raft_id = raft_id.to_string();
+            register
+                .metric::<U64Gauge>("vnode_startup_recovery_state", "")
+                .recorder([
+                    ("tenant", "cnosdb"),
+                    ("database", "public"),
+                    ("replica_id", "10"),
+                    ("vnode_id", raft_id.as_str()),
+                ])
+                .fetch()
+        };
+        let finished = || {
+            register
+                .metric::<U64Counter>("startup_recovery_vnodes_finished", "")
+                .recorder(Labels::default())
+                .fetch()
+        };
+
+        let mut vnode_1 = metrics.vnode(&summary(1));
+        let mut vnode_2 = metrics.vnode(&summary(2));
+        assert_eq!(state(1), VnodeStartupState::Waiting as u64);
+
+        vnode_1.start();
+        vnode_2.start();
+        assert_eq!(state(2), VnodeStartupState::Replaying as u64);
+
+        vnode_1.finish(true);
+        assert_eq!(state(1), VnodeStartupState::Done as u64);
+        assert_eq!(finished(), 1);
+
+        vnode_2.finish(false);
+        assert_eq!(state(2), VnodeStartupState::Failed as u64);
+        assert_eq!(finished(), 2);
+    }
+}
@@ -1,11 +1,12 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use memory_pool::MemoryPoolRef;
 use meta::model::MetaRef;
+use metrics::count::U64Counter;
 use models::meta_data::*;
-use protos::kv_service::{raft_write_command, RaftWriteCommand};
+use protos::kv_service::{raft_write_command, RaftWriteCommand, WriteConsistency};
 use protos::models_helper::to_prost_bytes;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use replication::raft_node::RaftNode;
@@ -14,8 +15,13 @@ use trace::debug;
 
 use super::manager::RaftNodesManager;
 use crate::errors::*;
+use crate::series_quota::SeriesQuota;
 use crate::TskvLeaderCaller;
 
+/// How long a write waits for the memory pool to drain before it is rejected.
+const MEMORY_ADMISSION_WAIT: Duration = Duration::from_millis(500);
+const MEMORY_ADMISSION_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
 pub struct TskvRaftWriter {
     pub meta: MetaRef,
     pub node_id: NodeId,
@@ -24,6 +30,8 @@ pub struct TskvRaftWriter {
     pub total_memory: usize,
     pub memory_pool: MemoryPoolRef,
     pub raft_manager: Arc<RaftNodesManager>,
+    pub series_quota: Arc<SeriesQuota>,
+    pub memory_exhausted: U64Counter,
 
     pub request: RaftWriteCommand,
 
@@ -39,6 +47,8 @@ impl TskvRaftWriter {
         total_memory: usize,
         memory_pool: MemoryPoolRef,
         raft_manager: Arc<RaftNodesManager>,
+        series_quota: Arc<SeriesQuota>,
+        memory_exhausted: U64Counter,
         request: RaftWriteCommand,
         counter: Arc<AtomicUsize>,
     ) -> TskvRaftWriter {
@@ -51,6 +61,8 @@ impl TskvRaftWriter {
             total_memory,
             memory_pool,
             raft_manager,
+            series_quota,
+            memory_exhausted,
             request,
             counter,
         }
@@ -64,25 +76,35 @@ impl TskvRaftWriter {
 
                     let _ = fb_points.tables().context(InvalidPointTableSnafu)?;
 
-                    if request.data.len()
-                        > self
-                            .total_memory
-                            .saturating_sub(self.memory_pool.reserved())
-                    {
-                        return Err(MemoryExhaustedSnafu.build());
-                    }
+                    self.admit_write_memory(request.data.len()).await?;
                 }
 
                 raft_write_command::Command::DropTable(_request) => {}
                 raft_write_command::Command::DropColumn(_request) => {}
                 raft_write_command::Command::UpdateTags(_request) => {}
                 raft_write_command::Command::DeleteFromTable(_request) => {}
+                raft_write_command::Command::RepairVnode(request) => {
+                    self.admit_write_memory(request.points.len()).await?;
+                }
+                raft_write_command::Command::MergeSeries(request) => {
+                    self.admit_write_memory(request.points.len()).await?;
+                }
             }
         }
 
         Ok(())
     }
 
+    async fn admit_write_memory(&self, request_size: usize) -> CoordinatorResult<()> {
+        admit_write_memory(
+            &self.memory_pool,
+            self.total_memory,
+            request_size,
+            &self.memory_exhausted,
+        )
+        .await
+    }
+
     async fn write_to_remote(&self, leader_id: u64) -> CoordinatorResult<()> {
         let channel = self.meta.get_node_conn(leader_id).await.map_err(|error| {
             CoordinatorError::PreExecution {
@@ -144,6 +166,22 @@ impl TskvRaftWriter {
 
                 let _data = apply_result.map_err(|e| CommonSnafu { msg: e }.build())?;
 
+                // Committed by a quorum already, wait for the others if required.
+                if self.request.consistency() == WriteConsistency::All {
+                    raft.wait_replicated(resp.log_id.index, self.timeout)
+                        .await
+                        .map_err(|err| {
+                            RaftWriteSnafu {
+                                msg: format!(
+                                    "write to replica: {} committed, but not replicated to all replicas: {}",
+                                    raft.group_id(),
+                                    err
+                                ),
+                            }
+                            .build()
+                        })?;
+                }
+
                 Ok(())
             }
         }
@@ -156,6 +194,17 @@ impl TskvRaftWriter {
             .await?;
 
         self.pre_check_write_to_raft(&self.request).await?;
+        if let Some(raft_write_command::Command::WriteData(request)) = &self.request.command {
+            self.series_quota
+                .check(
+                    &self.request.tenant,
+                    &self.request.db_name,
+                    replica.id,
+                    raft.raft_id() as VnodeId,
+                    &request.data,
+                )
+                .await?;
+        }
         let raft_data = to_prost_bytes(&self.request);
         self.write_to_raft(raft, raft_data).await?;
 
@@ -181,3 +230,81 @@ impl Drop for TskvRaftWriter {
         self.counter.fetch_sub(1, Ordering::SeqCst);
     }
 }
+
+/// Wait briefly for enough free memory to write `request_size` bytes,
+/// reject the write if the memory pool is still exhausted.
+async fn admit_write_memory(
+    memory_pool: &MemoryPoolRef,
+    total_memory: usize,
+    request_size: usize,
+    memory_exhausted: &U64Counter,
+) -> CoordinatorResult<()> {
+    let deadline = Instant::now() + MEMORY_ADMISSION_WAIT;
+    loop {
+        let used = memory_pool.reserved();
+        if request_size <= total_memory.saturating_sub(used) {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            memory_exhausted.inc_one();
+            return Err(WriteMemoryExhaustedSnafu {
+                request_size,
+                used,
+                total: total_memory,
+            }
+            .build());
+        }
+
+        tokio::time::sleep(MEMORY_ADMISSION_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use memory_pool::{GreedyMemoryPool, MemoryConsumer, MemoryPoolRef};
+    use metrics::count::U64Counter;
+
+    use super::admit_write_memory;
+    use crate::errors::CoordinatorError;
+
+    #[tokio::test]
+    async fn test_admit_write_memory() {
+        let pool: MemoryPoolRef = Arc::new(GreedyMemoryPool::new(1024));
+        let memory_exhausted = U64Counter::default();
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        reservation.grow(768);
+
+        admit_write_memory(&pool, 1024, 256, &memory_exhausted)
+            .await
+            .unwrap();
+        assert_eq!(memory_exhausted.fetch(), 0);
+
+        let err = admit_write_memory(&pool, 1024, 512, &memory_exhausted)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CoordinatorError::WriteMemoryExhausted {
+                request_size: 512,
+                used: 768,
+                total: 1024,
+            }
+        ));
+        assert_eq!(memory_exhausted.fetch(), 1);
+
+        // Admitted once the memory is freed in time.
+        let free = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            reservation.free();
+        });
+        admit_write_memory(&pool, 1024, 512, &memory_exhausted)
+            .await
+            .unwrap();
+        assert_eq!(memory_exhausted.fetch(), 1);
+        free.await.unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use datafusion::arrow::compute::concat_batches;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::{ready, Stream, StreamExt};
+use metrics::count::U64Counter;
+
+use crate::errors::CoordinatorResult;
+use crate::SendableCoordinatorRecordBatchStream;
+
+/// Merges the small [`RecordBatch`]es of a vnode stream, a batch is yielded once
+/// `target_rows` rows or `target_bytes` bytes are buffered, or the input is exhausted.
+pub struct CoalesceRecordBatchStream {
+    input: SendableCoordinatorRecordBatchStream,
+    target_rows: usize,
+    target_bytes: usize,
+
+    buffer: Vec<RecordBatch>,
+    buffered_rows: usize,
+    buffered_bytes: usize,
+    finished: bool,
+
+    batches_in: U64Counter,
+    batches_out: U64Counter,
+}
+
+impl CoalesceRecordBatchStream {
+    pub fn new(
+        input: SendableCoordinatorRecordBatchStream,
+        target_rows: usize,
+        target_bytes: usize,
+        batches_in: U64Counter,
+        batches_out: U64Counter,
+    ) -> Self {
+        Self {
+            input,
+            target_rows,
+            target_bytes,
+            buffer: vec![],
+            buffered_rows: 0,
+            buffered_bytes: 0,
+            finished: false,
+            batches_in,
+            batches_out,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.buffered_rows >= self.target_rows || self.buffered_bytes >= self.target_bytes
+    }
+
+    fn flush(&mut self) -> CoordinatorResult<Option<RecordBatch>> {
+        self.buffered_rows = 0;
+        self.buffered_bytes = 0;
+        let batches = std::mem::take(&mut self.buffer);
+        let batch = match batches.len() {
+            0 => return Ok(None),
+            1 => batches.into_iter().next().unwrap(),
+            _ => concat_batches(&batches[0].schema(), &batches)?,
+        };
+        self.batches_out.inc_one();
+        Ok(Some(batch))
+    }
+}
+
+impl Stream for CoalesceRecordBatchStream {
+    type Item = CoordinatorResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    self.batches_in.inc_one();
+                    if batch.num_rows() == 0 {
+                        continue;
+                    }
+                    self.buffered_rows += batch.num_rows();
+                    self.buffered_bytes += batch.get_array_memory_size();
+                    self.buffer.push(batch);
+                    if self.is_full() {
+                        return Poll::Ready(self.flush().transpose());
+                    }
+                }
+                Some(Err(err)) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => {
+                    self.finished = true;
+                    return Poll::Ready(self.flush().transpose());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use futures::TryStreamExt;
+    use metrics::count::U64Counter;
+
+    use super::CoalesceRecordBatchStream;
+    use crate::SendableCoordinatorRecordBatchStream;
+
+    fn input(batch_rows: &[usize]) -> SendableCoordinatorRecordBatchStream {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let batches = batch_rows
+            .iter()
+            .map(|rows| {
+                let array = Int64Array::from_iter_values(0..*rows as i64);
+                Ok(RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap())
+            })
+            .collect::<Vec<_>>();
+        Box::pin(futures::stream::iter(batches))
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_by_rows() {
+        let batches_in = U64Counter::default();
+        let batches_out = U64Counter::default();
+        let stream = CoalesceRecordBatchStream::new(
+            input(&[3, 3, 0, 3, 3, 1]),
+            6,
+            usize::MAX,
+            batches_in.clone(),
+            batches_out.clone(),
+        );
+        let rows = stream
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .iter()
+            .map(|b| b.num_rows())
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows, vec![6, 6, 1]);
+        assert_eq!(batches_in.fetch(), 6);
+        assert_eq!(batches_out.fetch(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_by_bytes() {
+        let stream = CoalesceRecordBatchStream::new(
+            input(&[3, 3, 3]),
+            usize::MAX,
+            1,
+            U64Counter::default(),
+            U64Counter::default(),
+        );
+        let rows = stream
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .iter()
+            .map(|b| b.num_rows())
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows, vec![3, 3, 3]);
+    }
+}
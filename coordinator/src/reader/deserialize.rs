@@ -5,17 +5,37 @@ use datafusion::arrow::record_batch::RecordBatch;
 use futures::{ready, Stream, StreamExt};
 use models::record_batch_decode;
 use protos::kv_service::BatchBytesResponse;
+use snafu::IntoError;
 use tonic::Streaming;
+use tskv::reader::scan_metrics::{ScanMetrics, ScanMetricsValues};
 
-use crate::errors::{CoordinatorError, CoordinatorResult};
+use crate::errors::{ChecksumMismatchSnafu, CoordinatorError, CoordinatorResult, TskvSnafu};
 
 pub struct TonicRecordBatchDecoder {
     stream: Streaming<BatchBytesResponse>,
+    /// Where the scan metrics responded by the remote node are added to.
+    scan_metrics: Option<ScanMetrics>,
 }
 
 impl TonicRecordBatchDecoder {
     pub fn new(stream: Streaming<BatchBytesResponse>) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            scan_metrics: None,
+        }
+    }
+
+    pub fn with_scan_metrics(mut self, scan_metrics: ScanMetrics) -> Self {
+        self.scan_metrics = Some(scan_metrics);
+        self
+    }
+
+    fn add_scan_metrics(&self, buf: &[u8]) -> CoordinatorResult<()> {
+        let values = ScanMetricsValues::decode(buf).map_err(|e| TskvSnafu.into_error(e))?;
+        if let Some(scan_metrics) = &self.scan_metrics {
+            scan_metrics.add_values(&values);
+        }
+        Ok(())
     }
 }
 
@@ -23,15 +43,31 @@ impl Stream for TonicRecordBatchDecoder {
     type Item = CoordinatorResult<RecordBatch>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match ready!(self.stream.poll_next_unpin(cx)) {
-            Some(Ok(received)) => match record_batch_decode(&received.data) {
-                Ok(batch) => Poll::Ready(Some(Ok(batch))),
-                Err(err) => Poll::Ready(Some(Err(err.into()))),
-            },
-            Some(Err(err)) => Poll::Ready(Some(Err(CoordinatorError::TskvError {
-                source: err.into(),
-            }))),
-            None => Poll::Ready(None),
+        loop {
+            return match ready!(self.stream.poll_next_unpin(cx)) {
+                Some(Ok(received)) if !received.verify_checksum() => {
+                    Poll::Ready(Some(Err(ChecksumMismatchSnafu {
+                        data: "record batch",
+                    }
+                    .build())))
+                }
+                Some(Ok(BatchBytesResponse {
+                    scan_metrics: Some(buf),
+                    ..
+                })) => match self.add_scan_metrics(&buf) {
+                    // The scan metrics come without a record batch.
+                    Ok(()) => continue,
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                },
+                Some(Ok(received)) => match record_batch_decode(&received.data) {
+                    Ok(batch) => Poll::Ready(Some(Ok(batch))),
+                    Err(err) => Poll::Ready(Some(Err(err.into()))),
+                },
+                Some(Err(err)) => Poll::Ready(Some(Err(CoordinatorError::TskvError {
+                    source: err.into(),
+                }))),
+                None => Poll::Ready(None),
+            };
         }
     }
 }
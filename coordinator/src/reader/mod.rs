@@ -1,3 +1,4 @@
+pub mod coalesce;
 pub mod deserialize;
 pub mod table_scan;
 pub mod tag_scan;
@@ -6,6 +7,7 @@ use std::mem;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::future::BoxFuture;
@@ -21,12 +23,28 @@ use crate::errors::{CommonSnafu, CoordinatorError, CoordinatorResult, MetaSnafu}
 use crate::service::CoordServiceMetrics;
 use crate::SendableCoordinatorRecordBatchStream;
 
+/// Times to read a vnode again if the record batches are corrupted on the network.
+const CHECKSUM_MISMATCH_RETRIES: usize = 3;
+
 /// A fallible future that reads to a stream of [`RecordBatch`]
 pub type VnodeOpenFuture =
     BoxFuture<'static, CoordinatorResult<SendableCoordinatorRecordBatchStream>>;
 /// A fallible future that checks the vnode query operation is available
 pub type CheckFuture = BoxFuture<'static, CoordinatorResult<()>>;
 
+/// The timeout of reading from a remote vnode, `query_timeout` of the tenant
+/// takes precedence over the `read_timeout` in config.
+pub(crate) async fn tenant_read_timeout(
+    meta: &MetaRef,
+    tenant: &str,
+    default: Duration,
+) -> Duration {
+    match meta.tenant_meta(tenant).await {
+        Some(client) => client.tenant().options().query_timeout().unwrap_or(default),
+        None => default,
+    }
+}
+
 /// Generic API for connect a vnode and reading to a stream of [`RecordBatch`]
 pub trait VnodeOpener: Unpin {
     /// Asynchronously open the specified vnode and return a stream of [`RecordBatch`]
@@ -40,6 +58,9 @@ pub struct CheckedCoordinatorRecordBatchStream<O: VnodeOpener> {
     vnode: VnodeInfo,
     option: QueryOption,
     state: StreamState,
+    /// Whether any batch has been read from the vnode.
+    scanned: bool,
+    checksum_retries: usize,
 
     coord_data_out: U64Counter,
 }
@@ -64,6 +85,8 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
             tenant,
             vnode: VnodeInfo::default(),
             state: StreamState::Check(checker),
+            scanned: false,
+            checksum_retries: 0,
             coord_data_out,
         }
     }
@@ -121,6 +144,7 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
                         None => return Poll::Ready(None),
                         Some(res) => match res {
                             Ok(batch) => {
+                                self.scanned = true;
                                 let batch_memory = batch.get_array_memory_size();
                                 let meta = self.meta.clone();
                                 let tenant_name = self.tenant.clone();
@@ -137,6 +161,19 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
                                     ScanState::CheckLimiter(batch, Box::pin(future)),
                                 );
                             }
+                            // Read the vnode again if the first batch is corrupted on
+                            // the network, there is nothing returned from it yet.
+                            Err(CoordinatorError::ChecksumMismatch { .. })
+                                if !self.scanned
+                                    && self.checksum_retries < CHECKSUM_MISMATCH_RETRIES =>
+                            {
+                                self.checksum_retries += 1;
+                                warn!(
+                                    "checksum mismatch of record batch from vnode {}, read it again",
+                                    self.vnode.id
+                                );
+                                self.state = StreamState::Idle;
+                            }
                             Err(err) => {
                                 if tskv::TskvError::vnode_broken_code(err.error_code().code()) {
                                     let id = self.vnode.id;
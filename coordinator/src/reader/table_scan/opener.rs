@@ -7,7 +7,8 @@ use models::meta_data::VnodeInfo;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use snafu::{IntoError, ResultExt};
 use tokio::runtime::Runtime;
-use trace::http::http_ctx::grpc_append_trace_context;
+use trace::http::http_ctx::{grpc_append_sampling_decision, grpc_append_trace_context};
+use trace::sampler::STATEMENT_QUERY;
 use trace::span_ext::SpanExt;
 use trace::{Span, SpanContext};
 use tskv::reader::table_scan::LocalTskvTableScanStream;
@@ -15,8 +16,10 @@ use tskv::reader::QueryOption;
 use tskv::EngineRef;
 
 use crate::errors::{CommonSnafu, CoordinatorError, CoordinatorResult, ModelsSnafu, TskvSnafu};
+use crate::reader::coalesce::CoalesceRecordBatchStream;
 use crate::reader::deserialize::TonicRecordBatchDecoder;
-use crate::reader::{VnodeOpenFuture, VnodeOpener};
+use crate::reader::{tenant_read_timeout, VnodeOpenFuture, VnodeOpener};
+use crate::service::CoordServiceMetrics;
 use crate::SendableCoordinatorRecordBatchStream;
 
 /// for connect a vnode and reading to a stream of [`RecordBatch`]
@@ -27,6 +30,8 @@ pub struct TemporaryTableScanOpener {
     meta: MetaRef,
     span_ctx: Option<SpanContext>,
     grpc_enable_gzip: bool,
+    grpc_enable_checksum: bool,
+    metrics: Arc<CoordServiceMetrics>,
 }
 
 impl TemporaryTableScanOpener {
@@ -37,6 +42,8 @@ impl TemporaryTableScanOpener {
         meta: MetaRef,
         span_ctx: Option<&SpanContext>,
         grpc_enable_gzip: bool,
+        grpc_enable_checksum: bool,
+        metrics: Arc<CoordServiceMetrics>,
     ) -> Self {
         Self {
             config,
@@ -45,6 +52,8 @@ impl TemporaryTableScanOpener {
             meta,
             span_ctx: span_ctx.cloned(),
             grpc_enable_gzip,
+            grpc_enable_checksum,
+            metrics,
         }
     }
 }
@@ -61,6 +70,8 @@ impl VnodeOpener for TemporaryTableScanOpener {
         let config = self.config.clone();
         let span_ctx = self.span_ctx;
         let grpc_enable_gzip = self.grpc_enable_gzip;
+        let grpc_enable_checksum = self.grpc_enable_checksum;
+        let metrics = self.metrics.clone();
 
         let future = async move {
             // TODO 请求路由的过程应该由通信框架决定，客户端只关心业务逻辑（请求目标和请求内容）
@@ -84,9 +95,11 @@ impl VnodeOpener for TemporaryTableScanOpener {
                 // 路由到远程的引擎
                 let mut request = {
                     let vnode_ids = vec![vnode_id];
-                    let req = option
+                    let mut req = option
                         .to_query_record_batch_request(vnode_ids)
                         .context(ModelsSnafu)?;
+                    req.checksum = grpc_enable_checksum;
+                    req.scan_metrics = true;
                     tonic::Request::new(req)
                 };
 
@@ -98,8 +111,20 @@ impl VnodeOpener for TemporaryTableScanOpener {
                         .build()
                     },
                 )?;
+                grpc_append_sampling_decision(
+                    span_ctx.as_ref(),
+                    &option.table_schema.tenant,
+                    STATEMENT_QUERY,
+                    request.metadata_mut(),
+                );
 
                 let resp_stream = {
+                    let read_timeout = tenant_read_timeout(
+                        &meta,
+                        &option.table_schema.tenant,
+                        config.read_timeout,
+                    )
+                    .await;
                     let channel = meta.get_node_conn(node_id).await.map_err(|error| {
                         CoordinatorError::PreExecution {
                             error: error.to_string(),
@@ -107,15 +132,31 @@ impl VnodeOpener for TemporaryTableScanOpener {
                     })?;
                     let mut client = tskv_service_time_out_client(
                         channel,
-                        config.read_timeout,
+                        read_timeout,
                         DEFAULT_GRPC_SERVER_MESSAGE_LEN,
                         grpc_enable_gzip,
                     );
                     client.query_record_batch(request).await?.into_inner()
                 };
 
-                Ok(Box::pin(TonicRecordBatchDecoder::new(resp_stream))
-                    as SendableCoordinatorRecordBatchStream)
+                let stream = Box::pin(
+                    TonicRecordBatchDecoder::new(resp_stream)
+                        .with_scan_metrics(option.scan_metrics.clone()),
+                ) as SendableCoordinatorRecordBatchStream;
+                if config.coalesce_batch_rows == 0 {
+                    return Ok(stream);
+                }
+
+                // Remote vnodes may respond with many tiny batches, merge them.
+                let tenant = option.table_schema.tenant.as_str();
+                let db = option.table_schema.db.as_str();
+                Ok(Box::pin(CoalesceRecordBatchStream::new(
+                    stream,
+                    config.coalesce_batch_rows,
+                    config.coalesce_batch_bytes as usize,
+                    metrics.coord_scan_batches_in(tenant, db),
+                    metrics.coord_scan_batches_out(tenant, db),
+                )) as SendableCoordinatorRecordBatchStream)
             }
         };
 
@@ -4,7 +4,8 @@ use meta::model::MetaRef;
 use models::meta_data::VnodeInfo;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use snafu::{IntoError, ResultExt};
-use trace::http::http_ctx::grpc_append_trace_context;
+use trace::http::http_ctx::{grpc_append_sampling_decision, grpc_append_trace_context};
+use trace::sampler::STATEMENT_QUERY;
 use trace::span_ext::SpanExt;
 use trace::{Span, SpanContext};
 use tskv::reader::tag_scan::LocalTskvTagScanStream;
@@ -13,7 +14,7 @@ use tskv::EngineRef;
 
 use crate::errors::{CommonSnafu, CoordinatorError, CoordinatorResult, ModelsSnafu, TskvSnafu};
 use crate::reader::deserialize::TonicRecordBatchDecoder;
-use crate::reader::{VnodeOpenFuture, VnodeOpener};
+use crate::reader::{tenant_read_timeout, VnodeOpenFuture, VnodeOpener};
 use crate::SendableCoordinatorRecordBatchStream;
 
 pub struct TemporaryTagScanOpener {
@@ -22,6 +23,7 @@ pub struct TemporaryTagScanOpener {
     meta: MetaRef,
     span_ctx: Option<SpanContext>,
     grpc_enable_gzip: bool,
+    grpc_enable_checksum: bool,
 }
 
 impl TemporaryTagScanOpener {
@@ -31,6 +33,7 @@ impl TemporaryTagScanOpener {
         meta: MetaRef,
         span_ctx: Option<&SpanContext>,
         grpc_enable_gzip: bool,
+        grpc_enable_checksum: bool,
     ) -> Self {
         Self {
             config,
@@ -38,6 +41,7 @@ impl TemporaryTagScanOpener {
             meta,
             span_ctx: span_ctx.cloned(),
             grpc_enable_gzip,
+            grpc_enable_checksum,
         }
     }
 }
@@ -53,6 +57,7 @@ impl VnodeOpener for TemporaryTagScanOpener {
         let config = self.config.clone();
         let span_ctx = self.span_ctx;
         let grpc_enable_gzip = self.grpc_enable_gzip;
+        let grpc_enable_checksum = self.grpc_enable_checksum;
 
         let future = async move {
             // TODO 请求路由的过程应该由通信框架决定，客户端只关心业务逻辑（请求目标和请求内容）
@@ -75,9 +80,10 @@ impl VnodeOpener for TemporaryTagScanOpener {
                 // 路由到远程的引擎
                 let mut request = {
                     let vnode_ids = vec![vnode_id];
-                    let req = option
+                    let mut req = option
                         .to_query_record_batch_request(vnode_ids)
                         .context(ModelsSnafu)?;
+                    req.checksum = grpc_enable_checksum;
                     tonic::Request::new(req)
                 };
 
@@ -89,8 +95,20 @@ impl VnodeOpener for TemporaryTagScanOpener {
                         .build()
                     },
                 )?;
+                grpc_append_sampling_decision(
+                    span_ctx.as_ref(),
+                    &option.table_schema.tenant,
+                    STATEMENT_QUERY,
+                    request.metadata_mut(),
+                );
 
                 let resp_stream = {
+                    let read_timeout = tenant_read_timeout(
+                        &admin_meta,
+                        &option.table_schema.tenant,
+                        config.read_timeout,
+                    )
+                    .await;
                     let channel = admin_meta.get_node_conn(node_id).await.map_err(|error| {
                         CoordinatorError::PreExecution {
                             error: error.to_string(),
@@ -98,7 +116,7 @@ impl VnodeOpener for TemporaryTagScanOpener {
                     })?;
                     let mut client = tskv_service_time_out_client(
                         channel,
-                        config.read_timeout,
+                        read_timeout,
                         DEFAULT_GRPC_SERVER_MESSAGE_LEN,
                         grpc_enable_gzip,
                     );
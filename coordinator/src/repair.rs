@@ -0,0 +1,753 @@
+//! Anti-entropy repair of the replicas of a replication set.
+//!
+//! The data of each table in the leader and follower vnodes is digested by
+//! time chunk, then the differing chunks are digested by series. The rows of
+//! the leader in the differing (series, time chunk) are proposed to raft, the
+//! follower replaces its data of them if none of them is written by the raft
+//! logs after the rows were copied. Otherwise the repair is skipped and done
+//! by the next run, so the writes running during the repair are never lost.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::arrow::array::{Array, BooleanArray, Int64Array, StringArray};
+use datafusion::arrow::compute::{cast, concat_batches, filter_record_batch};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{RowConverter, SortField};
+use futures::TryStreamExt;
+use models::meta_data::{NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo};
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRange, TimeRanges};
+use models::predicate::PlacedSplit;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use models::schema::TIME_FIELD_NAME;
+use models::{tag, SeriesKey, Tag};
+use protocol_parser::lines_convert::arrow_array_to_points;
+use protos::kv_service::admin_command::Command::TableLastWrite;
+use protos::kv_service::{
+    raft_write_command, AdminCommand, RaftWriteCommand, RepairChunk, RepairVnodeRequest,
+    TableLastWriteRequest, WriteConsistency,
+};
+use snafu::ResultExt;
+use trace::info;
+use utils::precision::{timestamp_convert, Precision};
+
+use crate::errors::{CommonSnafu, CoordinatorError, CoordinatorResult, MetaSnafu, ModelsSnafu};
+use crate::service::CoordService;
+use crate::{Coordinator, QueryOption};
+
+/// Duration of the time chunks the data of a series is digested by.
+pub const REPAIR_CHUNK_DURATION: Duration = Duration::from_secs(3600);
+const REPAIR_BATCH_SIZE: usize = 4096;
+/// Timeout of confirming the leader of the replication set.
+const REPAIR_LEADER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Result of repairing a follower vnode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VnodeRepair {
+    pub vnode_id: VnodeId,
+    pub node_id: NodeId,
+    /// Number of (series, time chunk) proposed to be replaced by the data of
+    /// the leader.
+    pub chunks: u64,
+    /// Number of rows copied from the leader.
+    pub rows: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ChunkDigest {
+    rows: u64,
+    /// Sum of the hashes of the rows, so that it does not depend on the order
+    /// of the rows.
+    hash: u64,
+}
+
+type ChunkKey = (SeriesKey, i64);
+
+/// Digests of the time chunks of a table in a vnode. The series are not told
+/// apart, so it's small even for the whole table.
+#[derive(Debug)]
+pub struct ChunkDigests {
+    table_schema: TskvTableSchemaRef,
+    /// In the precision of the time column.
+    chunk_duration: i64,
+    chunks: HashMap<i64, ChunkDigest>,
+}
+
+impl ChunkDigests {
+    pub fn new(table_schema: TskvTableSchemaRef, chunk_duration: i64) -> Self {
+        Self {
+            table_schema,
+            chunk_duration: chunk_duration.max(1),
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn add_batch(&mut self, batch: &RecordBatch) -> CoordinatorResult<()> {
+        let rows = BatchRows::try_new(&self.table_schema, batch)?;
+        for i in 0..batch.num_rows() {
+            let digest = self
+                .chunks
+                .entry(rows.chunk(i, self.chunk_duration))
+                .or_default();
+            let mut hasher = DefaultHasher::new();
+            hasher.write_u64(rows.series_hash(i));
+            hasher.write_u64(rows.hash(i));
+            digest.rows += 1;
+            digest.hash = digest.hash.wrapping_add(hasher.finish());
+        }
+
+        Ok(())
+    }
+
+    /// The chunks that are not the same in `other`, in order.
+    pub fn diff(&self, other: &ChunkDigests) -> Vec<i64> {
+        let mut chunks = self
+            .chunks
+            .iter()
+            .filter(|(chunk, digest)| other.chunks.get(chunk) != Some(digest))
+            .map(|(chunk, _)| *chunk)
+            .chain(
+                other
+                    .chunks
+                    .keys()
+                    .filter(|chunk| !self.chunks.contains_key(chunk))
+                    .copied(),
+            )
+            .collect::<Vec<_>>();
+        chunks.sort_unstable();
+
+        chunks
+    }
+
+    pub fn time_range(&self, chunk: i64) -> TimeRange {
+        chunk_time_range(chunk, self.chunk_duration)
+    }
+}
+
+/// Digests of the (series, time chunk) of a table in a vnode.
+#[derive(Debug)]
+pub struct TableDigests {
+    table_schema: TskvTableSchemaRef,
+    /// In the precision of the time column.
+    chunk_duration: i64,
+    chunks: HashMap<ChunkKey, ChunkDigest>,
+}
+impl TableDigests {
+    pub fn new(table_schema: TskvTableSchemaRef, chunk_duration: i64) -> Self {
+        Self {
+            table_schema,
+            chunk_duration: chunk_duration.max(1),
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn add_batch(&mut self, batch: &RecordBatch) -> CoordinatorResult<()> {
+        let rows = BatchRows::try_new(&self.table_schema, batch)?;
+        for i in 0..batch.num_rows() {
+            let key = (rows.series_key(i), rows.chunk(i, self.chunk_duration));
+            let digest = self.chunks.entry(key).or_default();
+            digest.rows += 1;
+            digest.hash = digest.hash.wrapping_add(rows.hash(i));
+        }
+
+        Ok(())
+    }
+
+    /// The chunks that are not the same in `other`.
+    pub fn diff(&self, other: &TableDigests) -> HashSet<ChunkKey> {
+        let mut chunks = HashSet::new();
+        for (key, digest) in self.chunks.iter() {
+            if other.chunks.get(key) != Some(digest) {
+                chunks.insert(key.clone());
+            }
+        }
+        for key in other.chunks.keys() {
+            if !self.chunks.contains_key(key) {
+                chunks.insert(key.clone());
+            }
+        }
+
+        chunks
+    }
+
+    /// Rows of the batch in the chunks.
+    pub fn filter_batch(
+        &self,
+        batch: &RecordBatch,
+        chunks: &HashSet<ChunkKey>,
+    ) -> CoordinatorResult<RecordBatch> {
+        let rows = BatchRows::try_new(&self.table_schema, batch)?;
+        let predicate = (0..batch.num_rows())
+            .map(|i| {
+                let key = (rows.series_key(i), rows.chunk(i, self.chunk_duration));
+                Some(chunks.contains(&key))
+            })
+            .collect::<BooleanArray>();
+
+        Ok(filter_record_batch(batch, &predicate)?)
+    }
+
+    pub fn time_range(&self, chunk: i64) -> TimeRange {
+        chunk_time_range(chunk, self.chunk_duration)
+    }
+}
+
+fn chunk_time_range(chunk: i64, chunk_duration: i64) -> TimeRange {
+    let min_ts = chunk.saturating_mul(chunk_duration);
+    TimeRange::new(min_ts, min_ts.saturating_add(chunk_duration - 1))
+}
+
+/// Series keys, timestamps and hashes of the rows of a record batch.
+struct BatchRows<'a> {
+    table: &'a str,
+    tags: Vec<(u32, &'a StringArray)>,
+    times: Int64Array,
+    hashes: Vec<u64>,
+}
+
+impl<'a> BatchRows<'a> {
+    fn try_new(
+        table_schema: &'a TskvTableSchemaRef,
+        batch: &'a RecordBatch,
+    ) -> CoordinatorResult<Self> {
+        let mut tags = vec![];
+        let mut times = None;
+        let mut values = vec![];
+        for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+            let column = table_schema.column(field.name()).ok_or_else(|| {
+                CommonSnafu {
+                    msg: format!(
+                        "column {} not found in table {}",
+                        field.name(),
+                        table_schema.name
+                    ),
+                }
+                .build()
+            })?;
+            if column.column_type.is_tag() {
+                let array = array
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .ok_or_else(|| {
+                        CommonSnafu {
+                            msg: format!("column {} is not StringArray", field.name()),
+                        }
+                        .build()
+                    })?;
+                tags.push((column.id, array));
+            } else {
+                if field.name() == TIME_FIELD_NAME {
+                    let array = cast(array, &DataType::Int64)?;
+                    times = array.as_any().downcast_ref::<Int64Array>().cloned();
+                }
+                values.push(array.clone());
+            }
+        }
+        let times = times.ok_or_else(|| {
+            CommonSnafu {
+                msg: format!(
+                    "column {} not found in table {}",
+                    TIME_FIELD_NAME, table_schema.name
+                ),
+            }
+            .build()
+        })?;
+
+        let converter = RowConverter::new(
+            values
+                .iter()
+                .map(|v| SortField::new(v.data_type().clone()))
+                .collect(),
+        )?;
+        let hashes = converter
+            .convert_columns(&values)?
+            .iter()
+            .map(|row| {
+                let mut hasher = DefaultHasher::new();
+                hasher.write(row.as_ref());
+                hasher.finish()
+            })
+            .collect();
+
+        Ok(Self {
+            table: table_schema.name.as_str(),
+            tags,
+            times,
+            hashes,
+        })
+    }
+
+    fn series_key(&self, row: usize) -> SeriesKey {
+        let mut tags = self
+            .tags
+            .iter()
+            .filter(|(_, array)| array.is_valid(row))
+            .map(|(id, array)| Tag::new_with_column_id(*id, array.value(row).as_bytes().to_vec()))
+            .collect::<Vec<_>>();
+        tag::sort_tags(&mut tags);
+
+        SeriesKey {
+            tags,
+            table: self.table.to_string(),
+        }
+    }
+
+    /// Hash of the series key, without building it.
+    fn series_hash(&self, row: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for (id, array) in self.tags.iter().filter(|(_, array)| array.is_valid(row)) {
+            let value = array.value(row).as_bytes();
+            hasher.write_u32(*id);
+            hasher.write_usize(value.len());
+            hasher.write(value);
+        }
+        hasher.finish()
+    }
+
+    fn chunk(&self, row: usize, chunk_duration: i64) -> i64 {
+        self.times.value(row).div_euclid(chunk_duration)
+    }
+
+    fn hash(&self, row: usize) -> u64 {
+        self.hashes[row]
+    }
+}
+
+/// Repair the followers of the replication set from the leader, returns the
+/// result of each follower.
+pub async fn repair_replica(
+    coord: &CoordService,
+    tenant: &str,
+    replica_id: ReplicationSetId,
+) -> CoordinatorResult<Vec<VnodeRepair>> {
+    let info = crate::get_replica_all_info(coord.meta_manager(), tenant, replica_id).await?;
+    // The leader in meta may be stale, it's confirmed by raft and updated
+    // to meta first.
+    coord
+        .leader_read_index(
+            tenant,
+            &info.db_name,
+            &info.replica_set,
+            REPAIR_LEADER_TIMEOUT,
+        )
+        .await?;
+    let info = crate::get_replica_all_info(coord.meta_manager(), tenant, replica_id).await?;
+    let replica = info.replica_set;
+    let leader = replica
+        .vnodes
+        .iter()
+        .find(|v| v.id == replica.leader_vnode_id)
+        .cloned()
+        .ok_or(CoordinatorError::VnodeNotFound {
+            id: replica.leader_vnode_id,
+        })?;
+    let followers = replica
+        .vnodes
+        .iter()
+        .filter(|v| v.id != leader.id)
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut repairs = followers
+        .iter()
+        .map(|v| VnodeRepair {
+            vnode_id: v.id,
+            node_id: v.node_id,
+            chunks: 0,
+            rows: 0,
+        })
+        .collect::<Vec<_>>();
+    if followers.is_empty() {
+        return Ok(repairs);
+    }
+
+    let tenant_meta =
+        coord
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            })?;
+    let tables = tenant_meta.list_tables(&info.db_name).context(MetaSnafu)?;
+    for table in tables {
+        let Some(schema) = tenant_meta
+            .get_tskv_table_schema(&info.db_name, &table)
+            .context(MetaSnafu)?
+        else {
+            continue;
+        };
+        let chunk_duration = timestamp_convert(
+            Precision::NS,
+            schema.time_column_precision(),
+            REPAIR_CHUNK_DURATION.as_nanos() as i64,
+        )
+        .unwrap_or(i64::MAX);
+        let table_repair = TableRepair {
+            coord,
+            tenant,
+            db_name: &info.db_name,
+            replica: &replica,
+            leader: &leader,
+            schema,
+            chunk_duration,
+        };
+
+        let leader_chunks = table_repair.digest_chunks(&leader).await?;
+        for (follower, repair) in followers.iter().zip(repairs.iter_mut()) {
+            let chunks = leader_chunks.diff(&table_repair.digest_chunks(follower).await?);
+            if chunks.is_empty() {
+                continue;
+            }
+            info!(
+                "Repairing {} time chunks of table {table} in vnode {} from leader vnode {}",
+                chunks.len(),
+                follower.id,
+                leader.id
+            );
+
+            for chunk in chunks {
+                let (series, rows) = table_repair.repair_chunk(follower, chunk).await?;
+                repair.chunks += series;
+                repair.rows += rows;
+            }
+        }
+    }
+
+    Ok(repairs)
+}
+
+/// Repair of a table in the followers of a replication set.
+struct TableRepair<'a> {
+    coord: &'a CoordService,
+    tenant: &'a str,
+    db_name: &'a str,
+    replica: &'a ReplicationSet,
+    leader: &'a VnodeInfo,
+    schema: TskvTableSchemaRef,
+    chunk_duration: i64,
+}
+
+impl TableRepair<'_> {
+    async fn digest_chunks(&self, vnode: &VnodeInfo) -> CoordinatorResult<ChunkDigests> {
+        let mut digests = ChunkDigests::new(self.schema.clone(), self.chunk_duration);
+        let mut stream = self.scan_vnode(vnode, TimeRanges::all())?;
+        while let Some(batch) = stream.try_next().await? {
+            digests.add_batch(&batch)?;
+        }
+
+        Ok(digests)
+    }
+
+    async fn digest_series(
+        &self,
+        vnode: &VnodeInfo,
+        time_range: TimeRange,
+    ) -> CoordinatorResult<TableDigests> {
+        let mut digests = TableDigests::new(self.schema.clone(), self.chunk_duration);
+        let mut stream = self.scan_vnode(vnode, TimeRanges::new(vec![time_range]))?;
+        while let Some(batch) = stream.try_next().await? {
+            digests.add_batch(&batch)?;
+        }
+
+        Ok(digests)
+    }
+
+    /// Propose to replace the series of the time chunk in the follower, which
+    /// differs from the leader, by the rows of the leader. Returns the number
+    /// of the replaced series and the copied rows.
+    async fn repair_chunk(
+        &self,
+        follower: &VnodeInfo,
+        chunk: i64,
+    ) -> CoordinatorResult<(u64, u64)> {
+        let time_range = chunk_time_range(chunk, self.chunk_duration);
+        // Got before the rows are copied, so the rows are not older than it.
+        let Some(since_index) = self.leader_last_write(&time_range).await? else {
+            info!(
+                "Skip repairing time range {time_range} of table {} in vnode {}, the latest write of the leader is unknown",
+                self.schema.name, follower.id
+            );
+            return Ok((0, 0));
+        };
+
+        let leader_digests = self.digest_series(self.leader, time_range).await?;
+        let series = leader_digests.diff(&self.digest_series(follower, time_range).await?);
+        if series.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let mut batches = vec![];
+        let mut stream = self.scan_vnode(self.leader, TimeRanges::new(vec![time_range]))?;
+        while let Some(batch) = stream.try_next().await? {
+            let batch = leader_digests.filter_batch(&batch, &series)?;
+            if batch.num_rows() > 0 {
+                batches.push(batch);
+            }
+        }
+        let (points, rows) = match batches.first() {
+            Some(first) => {
+                let batch = concat_batches(&first.schema(), &batches)?;
+                let points = arrow_array_to_points(
+                    batch.columns().to_vec(),
+                    batch.schema(),
+                    self.schema.clone(),
+                    batch.num_rows(),
+                )
+                .map_err(|e| {
+                    CommonSnafu {
+                        msg: format!("arrow array to points error: {}", e),
+                    }
+                    .build()
+                })?;
+                (points, batch.num_rows() as u64)
+            }
+            None => (vec![], 0),
+        };
+
+        let chunks = series
+            .iter()
+            .map(|(series_key, _)| RepairChunk {
+                series_key: series_key.encode(),
+                min_ts: time_range.min_ts,
+                max_ts: time_range.max_ts,
+            })
+            .collect::<Vec<_>>();
+        let command = RaftWriteCommand {
+            replica_id: self.replica.id,
+            tenant: self.tenant.to_string(),
+            db_name: self.db_name.to_string(),
+            consistency: WriteConsistency::Quorum as i32,
+            command: Some(raft_write_command::Command::RepairVnode(
+                RepairVnodeRequest {
+                    vnode_id: follower.id,
+                    table: self.schema.name.clone(),
+                    chunks,
+                    points,
+                    precision: self.schema.time_column_precision() as u32,
+                    since_index,
+                },
+            )),
+        };
+        self.coord
+            .write_replica_by_raft(self.replica.clone(), command, None)
+            .await?;
+
+        Ok((series.len() as u64, rows))
+    }
+
+    /// The index of the latest raft log applied to the leader writing the time
+    /// range of the table, None if it's unknown.
+    async fn leader_last_write(&self, time_range: &TimeRange) -> CoordinatorResult<Option<u64>> {
+        let cmd = AdminCommand {
+            tenant: self.tenant.to_string(),
+            command: Some(TableLastWrite(TableLastWriteRequest {
+                vnode_id: self.leader.id,
+                table: self.schema.name.clone(),
+                min_ts: time_range.min_ts,
+                max_ts: time_range.max_ts,
+            })),
+        };
+        let data = self
+            .coord
+            .admin_command_on_node(self.leader.node_id, cmd)
+            .await?;
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let index = data.try_into().map(u64::from_be_bytes).map_err(|_| {
+            CommonSnafu {
+                msg: "invalid response of table last write".to_string(),
+            }
+            .build()
+        })?;
+
+        Ok(Some(index))
+    }
+
+    fn scan_vnode(
+        &self,
+        vnode: &VnodeInfo,
+        time_ranges: TimeRanges,
+    ) -> CoordinatorResult<crate::SendableCoordinatorRecordBatchStream> {
+        let predicate = Arc::new(
+            ResolvedPredicate::new(Arc::new(time_ranges), ColumnDomains::all(), None)
+                .context(ModelsSnafu)?,
+        );
+        let replica_set = ReplicationSet::new(
+            self.replica.id,
+            vnode.node_id,
+            vnode.id,
+            vec![vnode.clone()],
+        );
+        let split = PlacedSplit::new(0, predicate, None, replica_set);
+        let option = QueryOption::new(
+            REPAIR_BATCH_SIZE,
+            split,
+            None,
+            self.schema.to_arrow_schema(),
+            self.schema.clone(),
+            self.schema.meta(),
+        );
+
+        self.coord.table_scan(option, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::TimeUnit;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::codec::Encoding;
+    use models::predicate::domain::TimeRange;
+    use models::schema::tskv_table_schema::{
+        ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
+    };
+    use models::ValueType;
+
+    use super::{ChunkDigests, TableDigests};
+
+    fn schema() -> TskvTableSchemaRef {
+        Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "cpu".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "host".to_string()),
+                TableColumn::new(
+                    2,
+                    "usage".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+            ],
+        ))
+    }
+
+    fn batch(schema: &TskvTableSchemaRef, rows: &[(i64, &str, Option<f64>)]) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.to_arrow_schema(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(
+                    rows.iter().map(|r| r.0).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+                )),
+                Arc::new(Float64Array::from(
+                    rows.iter().map(|r| r.2).collect::<Vec<_>>(),
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn digests(schema: &TskvTableSchemaRef, rows: &[(i64, &str, Option<f64>)]) -> TableDigests {
+        let mut digests = TableDigests::new(schema.clone(), 10);
+        digests.add_batch(&batch(schema, rows)).unwrap();
+        digests
+    }
+
+    #[test]
+    fn test_diff_table_digests() {
+        let schema = schema();
+        let leader = digests(
+            &schema,
+            &[
+                (1, "a", Some(1.0)),
+                (2, "a", Some(2.0)),
+                (11, "a", Some(3.0)),
+                (1, "b", None),
+            ],
+        );
+
+        // The order of rows does not matter.
+        let same = digests(
+            &schema,
+            &[
+                (1, "b", None),
+                (11, "a", Some(3.0)),
+                (2, "a", Some(2.0)),
+                (1, "a", Some(1.0)),
+            ],
+        );
+        assert!(leader.diff(&same).is_empty());
+
+        let follower = digests(
+            &schema,
+            &[
+                (1, "a", Some(1.0)),
+                (2, "a", Some(2.5)),
+                (1, "b", Some(1.0)),
+                (21, "c", Some(1.0)),
+            ],
+        );
+        let chunks = leader.diff(&follower);
+        let mut diff = chunks
+            .iter()
+            .map(|(key, chunk)| (key.string(), *chunk))
+            .collect::<Vec<_>>();
+        diff.sort();
+        assert_eq!(
+            diff,
+            vec![
+                ("cpu,1=a".to_string(), 0),
+                ("cpu,1=a".to_string(), 1),
+                ("cpu,1=b".to_string(), 0),
+                ("cpu,1=c".to_string(), 2),
+            ]
+        );
+
+        let rows = leader
+            .filter_batch(
+                &batch(
+                    &schema,
+                    &[(1, "a", Some(1.0)), (11, "a", Some(3.0)), (3, "d", None)],
+                ),
+                &chunks,
+            )
+            .unwrap();
+        assert_eq!(rows.num_rows(), 2);
+        assert_eq!(leader.time_range(2).min_ts, 20);
+        assert_eq!(leader.time_range(2).max_ts, 29);
+    }
+
+    #[test]
+    fn test_diff_chunk_digests() {
+        let schema = schema();
+        let chunk_digests = |rows: &[(i64, &str, Option<f64>)]| {
+            let mut digests = ChunkDigests::new(schema.clone(), 10);
+            digests.add_batch(&batch(&schema, rows)).unwrap();
+            digests
+        };
+        let leader = chunk_digests(&[
+            (1, "a", Some(1.0)),
+            (2, "b", Some(2.0)),
+            (11, "a", Some(3.0)),
+        ]);
+
+        // The order of rows does not matter.
+        let same = chunk_digests(&[
+            (11, "a", Some(3.0)),
+            (2, "b", Some(2.0)),
+            (1, "a", Some(1.0)),
+        ]);
+        assert!(leader.diff(&same).is_empty());
+
+        // The same rows in the other series.
+        let follower = chunk_digests(&[
+            (1, "b", Some(1.0)),
+            (2, "a", Some(2.0)),
+            (11, "a", Some(3.0)),
+            (21, "c", None),
+        ]);
+        assert_eq!(leader.diff(&follower), vec![0, 2]);
+        assert_eq!(leader.time_range(2), TimeRange::new(20, 29));
+    }
+}
@@ -0,0 +1,262 @@
+//! Export of the data of a replication set.
+//!
+//! The vnodes of the replication set are exported one after another, the
+//! leader first. The rows already exported from a previous vnode, told apart
+//! by the table, the tags and the time, are skipped, so the rows missing from
+//! some replicas, e.g. not replicated to the leader yet, are still exported
+//! once, and the rows of the leader win over the stale ones of the followers.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use datafusion::arrow::array::{ArrayRef, BooleanArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{RowConverter, SortField};
+use futures::StreamExt;
+use models::meta_data::NodeId;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use protos::kv_service::ExportVnodeDataRequest;
+use trace::warn;
+
+use crate::errors::{CommonSnafu, CoordinatorError, CoordinatorResult};
+use crate::service::CoordService;
+use crate::ReplicaDataStream;
+
+struct ExportedTable {
+    /// The tag and time columns.
+    key_columns: Vec<String>,
+    converter: Option<RowConverter>,
+    rows: HashSet<Box<[u8]>>,
+}
+
+/// The keys of the rows exported of each table.
+pub struct ExportedRows {
+    tables: HashMap<String, ExportedTable>,
+}
+
+impl ExportedRows {
+    pub fn new(schemas: impl IntoIterator<Item = TskvTableSchemaRef>) -> Self {
+        let tables = schemas
+            .into_iter()
+            .map(|schema| {
+                let key_columns = schema
+                    .columns()
+                    .iter()
+                    .filter(|c| c.column_type.is_tag() || c.column_type.is_time())
+                    .map(|c| c.name.clone())
+                    .collect();
+                let table = ExportedTable {
+                    key_columns,
+                    converter: None,
+                    rows: HashSet::new(),
+                };
+                (schema.name.clone(), table)
+            })
+            .collect();
+
+        Self { tables }
+    }
+
+    /// Remove the rows of the record batch of `table` which are already
+    /// exported, the others are recorded as exported.
+    pub fn filter_batch(
+        &mut self,
+        table: &str,
+        batch: RecordBatch,
+    ) -> CoordinatorResult<RecordBatch> {
+        let exported = self.tables.get_mut(table).ok_or_else(|| {
+            CommonSnafu {
+                msg: format!("exported table {table} not found"),
+            }
+            .build()
+        })?;
+        let keys = exported
+            .key_columns
+            .iter()
+            .map(|name| {
+                batch.column_by_name(name).cloned().ok_or_else(|| {
+                    CommonSnafu {
+                        msg: format!("column {name} not found in exported table {table}"),
+                    }
+                    .build()
+                })
+            })
+            .collect::<CoordinatorResult<Vec<ArrayRef>>>()?;
+        let converter = match exported.converter.as_mut() {
+            Some(converter) => converter,
+            None => exported.converter.insert(RowConverter::new(
+                keys.iter()
+                    .map(|k| SortField::new(k.data_type().clone()))
+                    .collect(),
+            )?),
+        };
+
+        let predicate = converter
+            .convert_columns(&keys)?
+            .iter()
+            .map(|row| Some(exported.rows.insert(Box::from(row.as_ref()))))
+            .collect::<BooleanArray>();
+
+        Ok(filter_record_batch(&batch, &predicate)?)
+    }
+}
+
+struct ReplicaExport {
+    coord: CoordService,
+    vnodes: VecDeque<(NodeId, ExportVnodeDataRequest)>,
+    current: Option<ReplicaDataStream>,
+    exported: ExportedRows,
+    /// Number of the vnodes exported to the end.
+    completed: usize,
+    last_err: Option<CoordinatorError>,
+}
+
+/// Stream the data of the vnodes in order, the failed vnodes are skipped. It
+/// fails if none of the vnodes is exported to the end.
+pub fn export_vnodes(
+    coord: CoordService,
+    vnodes: Vec<(NodeId, ExportVnodeDataRequest)>,
+    schemas: Vec<TskvTableSchemaRef>,
+) -> ReplicaDataStream {
+    let state = ReplicaExport {
+        coord,
+        vnodes: vnodes.into(),
+        current: None,
+        exported: ExportedRows::new(schemas),
+        completed: 0,
+        last_err: None,
+    };
+
+    let stream = futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(stream) = state.current.as_mut() {
+                match stream.next().await {
+                    Some(Ok((table, batch))) => match state.exported.filter_batch(&table, batch) {
+                        Ok(batch) if batch.num_rows() == 0 => {}
+                        Ok(batch) => return Some((Ok((table, batch)), state)),
+                        Err(e) => {
+                            state.vnodes.clear();
+                            state.current = None;
+                            state.last_err = None;
+                            return Some((Err(e), state));
+                        }
+                    },
+                    Some(Err(e)) => {
+                        warn!("Export data of vnode failed, try the next replica: {}", e);
+                        state.current = None;
+                        state.last_err = Some(e);
+                    }
+                    None => {
+                        state.current = None;
+                        state.completed += 1;
+                    }
+                }
+                continue;
+            }
+
+            let Some((node_id, request)) = state.vnodes.pop_front() else {
+                if state.completed == 0 {
+                    if let Some(e) = state.last_err.take() {
+                        return Some((Err(e), state));
+                    }
+                }
+                return None;
+            };
+            let vnode_id = request.vnode_id;
+            match state
+                .coord
+                .export_vnode_data_on_node(node_id, request)
+                .await
+            {
+                Ok(stream) => state.current = Some(stream),
+                Err(e) => {
+                    warn!(
+                        "Export data of vnode {} on node {} failed, try the next replica: {}",
+                        vnode_id, node_id, e
+                    );
+                    state.last_err = Some(e);
+                }
+            }
+        }
+    });
+
+    Box::pin(stream)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::TimeUnit;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{
+        ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
+    };
+    use models::ValueType;
+
+    use super::ExportedRows;
+
+    fn schema() -> TskvTableSchemaRef {
+        Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "cpu".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "host".to_string()),
+                TableColumn::new(
+                    2,
+                    "usage".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+            ],
+        ))
+    }
+
+    fn batch(schema: &TskvTableSchemaRef, rows: &[(i64, &str, f64)]) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.to_arrow_schema(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(
+                    rows.iter().map(|r| r.0).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+                )),
+                Arc::new(Float64Array::from(
+                    rows.iter().map(|r| r.2).collect::<Vec<_>>(),
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_exported_rows() {
+        let schema = schema();
+        let mut exported = ExportedRows::new([schema.clone()]);
+
+        // The leader.
+        let leader = batch(&schema, &[(1, "a", 1.0), (2, "a", 2.0), (1, "b", 3.0)]);
+        let filtered = exported.filter_batch("cpu", leader.clone()).unwrap();
+        assert_eq!(filtered, leader);
+
+        // A follower with a stale value and a row missing from the leader.
+        let follower = batch(&schema, &[(1, "a", 0.0), (3, "a", 4.0), (1, "b", 3.0)]);
+        let filtered = exported.filter_batch("cpu", follower).unwrap();
+        assert_eq!(filtered, batch(&schema, &[(3, "a", 4.0)]));
+
+        // Exported by the follower.
+        let filtered = exported
+            .filter_batch("cpu", batch(&schema, &[(3, "a", 5.0)]))
+            .unwrap();
+        assert_eq!(filtered.num_rows(), 0);
+
+        assert!(exported
+            .filter_batch("mem", batch(&schema, &[(1, "a", 1.0)]))
+            .is_err());
+    }
+}
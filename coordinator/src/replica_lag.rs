@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use models::meta_data::{ReplicationSet, VnodeApplied, VnodeId, VnodeInfo, VnodeStatus};
+use rand::seq::SliceRandom;
+
+/// Pick a follower whose data is stale within `max_replica_lag` at `now`, one of
+/// them is picked randomly to spread the scans. The learners are preferred to keep
+/// the scans off the voting followers. Returns the follower and the raft index it
+/// must still have applied when it's read, None if there is no such follower or
+/// the lag of the leader is unknown, so that the leader is read.
+pub fn pick_fresh_follower(
+    replica_set: &ReplicationSet,
+    vnodes_applied: &HashMap<VnodeId, VnodeApplied>,
+    max_replica_lag: Duration,
+    now: i64,
+) -> Option<(VnodeInfo, u64)> {
+    let leader = vnodes_applied.get(&replica_set.leader_vnode_id)?;
+    let max_lag = max_replica_lag.as_nanos().min(i64::MAX as u128) as i64;
+
+    let fresh_followers = replica_set
+        .vnodes
+        .iter()
+        .filter(|vnode| {
+            vnode.id != replica_set.leader_vnode_id && vnode.status == VnodeStatus::Running
+        })
+        .filter_map(|vnode| {
+            let applied = vnodes_applied.get(&vnode.id)?;
+            (applied.staleness(leader, now) <= max_lag)
+                .then_some((vnode, applied.index.min(leader.index)))
+        })
+        .collect::<Vec<_>>();
+    let fresh_learners = fresh_followers
+        .iter()
+        .filter(|(vnode, _)| vnode.learner)
+        .copied()
+        .collect::<Vec<_>>();
+    let candidates = if fresh_learners.is_empty() {
+        fresh_followers
+    } else {
+        fresh_learners
+    };
+
+    candidates
+        .choose(&mut rand::thread_rng())
+        .map(|(vnode, index)| ((*vnode).clone(), *index))
+}
+
+/// A running follower of the replica set picked randomly to read instead of the
+/// leader, the learners are preferred.
+pub fn pick_follower(replica_set: &ReplicationSet) -> Option<&VnodeInfo> {
+    let followers = replica_set
+        .vnodes
+        .iter()
+        .filter(|vnode| {
+            vnode.id != replica_set.leader_vnode_id && vnode.status == VnodeStatus::Running
+        })
+        .collect::<Vec<_>>();
+    let learners = followers
+        .iter()
+        .filter(|vnode| vnode.learner)
+        .copied()
+        .collect::<Vec<_>>();
+    let candidates = if learners.is_empty() {
+        followers
+    } else {
+        learners
+    };
+
+    candidates.choose(&mut rand::thread_rng()).copied()
+}
+
+/// Move the vnode to the front of the replica set to be read.
+pub fn move_to_front(replica_set: &mut ReplicationSet, vnode_id: VnodeId) {
+    if let Some(i) = replica_set
+        .vnodes
+        .iter()
+        .position(|vnode| vnode.id == vnode_id)
+    {
+        let vnode = replica_set.vnodes.remove(i);
+        replica_set.vnodes.insert(0, vnode);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use models::meta_data::{ReplicationSet, VnodeApplied, VnodeInfo, VnodeStatus};
+
+    use super::{move_to_front, pick_follower, pick_fresh_follower};
+
+    fn replica_set() -> ReplicationSet {
+        ReplicationSet::new(
+            1,
+            1,
+            10,
+            vec![
+                VnodeInfo::new(10, 1),
+                VnodeInfo::new(11, 2),
+                VnodeInfo {
+                    id: 12,
+                    node_id: 3,
+                    status: VnodeStatus::Copying,
+                    learner: false,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_pick_fresh_follower() {
+        let second = 1_000_000_000;
+        let applied = |index, time| VnodeApplied {
+            index,
+            time: time * second,
+            reported: 10 * second,
+        };
+        let mut vnodes_applied = HashMap::from([
+            (10, applied(100, 10)),
+            (11, applied(90, 7)),
+            (12, applied(100, 10)),
+        ]);
+        let pick = |set: &ReplicationSet, vnodes_applied, max_lag, now| {
+            pick_fresh_follower(
+                set,
+                vnodes_applied,
+                Duration::from_secs(max_lag),
+                now * second,
+            )
+            .map(|(vnode, index)| (vnode.id, index))
+        };
+
+        // The running follower lags 3s behind the leader, reported 1s ago.
+        let set = replica_set();
+        assert_eq!(pick(&set, &vnodes_applied, 5, 11), Some((11, 90)));
+        // Too stale, the copying follower is never read.
+        assert_eq!(pick(&set, &vnodes_applied, 3, 11), None);
+        // The leader may have applied more entries since the old report.
+        assert_eq!(pick(&set, &vnodes_applied, 5, 20), None);
+        // Unknown lag of the leader.
+        assert_eq!(pick(&set, &HashMap::new(), 5, 11), None);
+
+        // The fresh learner is preferred to the follower, it must have applied
+        // the entries of the leader it has reported.
+        let mut set = replica_set();
+        set.vnodes.push(VnodeInfo {
+            id: 13,
+            node_id: 4,
+            status: VnodeStatus::Running,
+            learner: true,
+        });
+        vnodes_applied.insert(13, applied(101, 10));
+        assert_eq!(pick(&set, &vnodes_applied, 5, 11), Some((13, 100)));
+    }
+
+    #[test]
+    fn test_pick_follower() {
+        // The copying follower is never read.
+        let mut set = replica_set();
+        assert_eq!(pick_follower(&set).map(|v| v.id), Some(11));
+
+        set.vnodes.push(VnodeInfo {
+            id: 13,
+            node_id: 4,
+            status: VnodeStatus::Running,
+            learner: true,
+        });
+        assert_eq!(pick_follower(&set).map(|v| v.id), Some(13));
+
+        move_to_front(&mut set, 13);
+        let ids = set.vnodes.iter().map(|v| v.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![13, 10, 11, 12]);
+
+        let set = ReplicationSet::new(1, 1, 10, vec![VnodeInfo::new(10, 1)]);
+        assert!(pick_follower(&set).is_none());
+    }
+
+    #[test]
+    fn test_lag_behind_and_staleness() {
+        let leader = VnodeApplied {
+            index: 5,
+            time: 100,
+            reported: 150,
+        };
+        assert_eq!(
+            VnodeApplied {
+                index: 5,
+                time: 10,
+                reported: 0
+            }
+            .lag_behind(&leader),
+            0
+        );
+        assert_eq!(
+            VnodeApplied {
+                index: 4,
+                time: 10,
+                reported: 0
+            }
+            .lag_behind(&leader),
+            90
+        );
+        assert_eq!(
+            VnodeApplied {
+                index: 4,
+                time: 200,
+                reported: 0,
+            }
+            .lag_behind(&leader),
+            0
+        );
+
+        let follower = VnodeApplied {
+            index: 4,
+            time: 10,
+            reported: 0,
+        };
+        assert_eq!(follower.staleness(&leader, 200), 140);
+        assert_eq!(follower.staleness(&leader, 100), 90);
+    }
+}
@@ -1,19 +1,42 @@
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
-use models::meta_data::ReplicationSet;
+use datafusion::arrow::array::{Array, StringArray};
+use datafusion::arrow::compute::concat_batches;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+use datafusion::sql::TableReference;
+use futures::TryStreamExt;
+use models::meta_data::{NodeId, ReplicationSet};
+use models::object_reference::{Resolve, ResolvedTable};
+use models::oid::Oid;
+use models::predicate::domain::{ColumnDomains, Domain, ResolvedPredicate, TimeRange, TimeRanges};
+use models::predicate::PlacedSplit;
 use models::schema::resource_info::{ResourceInfo, ResourceOperator, ResourceStatus};
+use models::schema::scheduled_delete::{ScheduledDeleteJob, ScheduledDeleteRun};
 use models::schema::table_schema::TableSchema;
+use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef};
+use models::utils::now_timestamp_nanos;
+use models::{tag, ColumnId, SeriesKey, Tag};
+use protocol_parser::lines_convert::arrow_array_to_points;
 use protos::kv_service::{
-    raft_write_command, DropColumnRequest, DropTableRequest, RaftWriteCommand, UpdateSetValue,
-    UpdateTagsRequest,
+    raft_write_command, DropColumnRequest, DropTableRequest, MergeSeriesRequest, RaftWriteCommand,
+    UpdateSetValue, UpdateTagsRequest, WriteConsistency,
 };
 use snafu::ResultExt;
 use tokio::time::sleep;
 use tracing::{debug, error, info};
+use utils::precision::{timestamp_convert, Precision};
 
 use crate::errors::*;
-use crate::{Coordinator, ReplicationCmdType};
+use crate::{Coordinator, QueryOption, ReplicationCmdType};
+
+const MERGE_TAG_BATCH_SIZE: usize = 4096;
+
+/// The tag values of a series, by the column id of the tag.
+type SeriesTags = BTreeMap<ColumnId, String>;
 
 #[derive(Clone)]
 pub struct ResourceManager {}
@@ -56,6 +79,23 @@ impl ResourceManager {
                 )
                 .await
             }
+            ResourceOperator::MergeTagValue(table_schema, tag_name, from, to) => {
+                ResourceManager::merge_tag_value(coord.clone(), table_schema, tag_name, from, to)
+                    .await
+            }
+            ResourceOperator::ScheduledDelete(job) => {
+                ResourceManager::scheduled_delete(coord.clone(), job).await
+            }
+            ResourceOperator::DeleteWrittenRows(tenant_name, db_name, table_name, time_range) => {
+                ResourceManager::delete_written_rows(
+                    coord.clone(),
+                    tenant_name,
+                    db_name,
+                    table_name,
+                    time_range,
+                )
+                .await
+            }
         };
         resourceinfo.set_is_new_add(false);
         let mut status_comment = (ResourceStatus::Successed, String::default());
@@ -64,6 +104,21 @@ impl ResourceManager {
             status_comment.1 = coord_err.to_string();
             resourceinfo.set_is_new_add(true);
         }
+        if matches!(
+            resourceinfo.get_operator(),
+            ResourceOperator::ScheduledDelete(_)
+        ) {
+            // The job dropped while running is not scheduled again.
+            if ResourceManager::is_cancelled(coord.clone(), resourceinfo.get_name()).await? {
+                return operator_result;
+            }
+            status_comment.0 = schedule_next_run(
+                &mut resourceinfo,
+                now_timestamp_nanos(),
+                operator_result.is_ok(),
+                &status_comment.1,
+            );
+        }
         resourceinfo.increase_try_count();
         resourceinfo.set_status(status_comment.0);
         resourceinfo.set_comment(&status_comment.1);
@@ -76,6 +131,60 @@ impl ResourceManager {
         operator_result
     }
 
+    /// Whether the resource task is cancelled or removed, it's read from the
+    /// meta again as it may be cancelled while waiting or executing.
+    pub async fn is_cancelled(coord: Arc<dyn Coordinator>, name: &str) -> CoordinatorResult<bool> {
+        let resourceinfo = coord
+            .meta_manager()
+            .read_resourceinfo_by_name(name)
+            .await
+            .context(MetaSnafu)?;
+
+        Ok(resourceinfo.map_or(true, |r| *r.get_status() == ResourceStatus::Cancel))
+    }
+
+    /// Cancel the scheduled delete jobs of the dropped database, or of the
+    /// dropped table if `table_name` is given.
+    async fn cancel_scheduled_deletes(
+        coord: Arc<dyn Coordinator>,
+        tenant_name: &str,
+        db_name: &str,
+        table_name: Option<&str>,
+    ) -> CoordinatorResult<()> {
+        let tenant = coord.tenant_meta(tenant_name).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant_name.to_string(),
+            }
+        })?;
+        let resourceinfos = tenant.read_resourceinfos().await.context(MetaSnafu)?;
+        for mut resourceinfo in resourceinfos {
+            let ResourceOperator::ScheduledDelete(job) = resourceinfo.get_operator() else {
+                continue;
+            };
+            if job.tenant != tenant_name
+                || job.database != db_name
+                || table_name.is_some_and(|table| table != job.table)
+                || *resourceinfo.get_status() == ResourceStatus::Cancel
+            {
+                continue;
+            }
+
+            info!(
+                "Cancel scheduled delete {} of the dropped table {}.{}",
+                job.name, job.database, job.table
+            );
+            resourceinfo.set_status(ResourceStatus::Cancel);
+            resourceinfo.set_is_new_add(true);
+            coord
+                .meta_manager()
+                .write_resourceinfo(resourceinfo.get_name(), resourceinfo.clone())
+                .await
+                .context(MetaSnafu)?;
+        }
+
+        Ok(())
+    }
+
     async fn drop_tenant(
         coord: Arc<dyn Coordinator>,
         tenant_name: &str,
@@ -133,6 +242,7 @@ impl ResourceManager {
             .drop_db(db_name)
             .await
             .map_err(|err| CoordinatorError::Meta { source: err })?;
+        ResourceManager::cancel_scheduled_deletes(coord, tenant_name, db_name, None).await?;
 
         Ok(true)
     }
@@ -171,6 +281,7 @@ impl ResourceManager {
                     replica_id: replica.id,
                     tenant: tenant_name.to_string(),
                     db_name: db_name.to_string(),
+                    consistency: WriteConsistency::Quorum as i32,
                     command: Some(raft_write_command::Command::DropTable(request)),
                 };
 
@@ -187,6 +298,8 @@ impl ResourceManager {
             .drop_table(db_name, table_name)
             .await
             .map_err(|err| CoordinatorError::Meta { source: err })?;
+        ResourceManager::cancel_scheduled_deletes(coord, tenant_name, db_name, Some(table_name))
+            .await?;
 
         Ok(true)
     }
@@ -235,6 +348,7 @@ impl ResourceManager {
                             replica_id: replica.id,
                             tenant: tenant_name.to_string(),
                             db_name: table_schema.db.to_string(),
+                            consistency: WriteConsistency::Quorum as i32,
                             command: Some(raft_write_command::Command::DropColumn(request)),
                         };
 
@@ -297,6 +411,7 @@ impl ResourceManager {
                 replica_id: replica.id,
                 tenant: tenant_name.to_string(),
                 db_name: db_name.to_string(),
+                consistency: WriteConsistency::Quorum as i32,
                 command: Some(raft_write_command::Command::UpdateTags(
                     update_tags_request.clone(),
                 )),
@@ -313,6 +428,224 @@ impl ResourceManager {
         Ok(true)
     }
 
+    /// Merge the value `from` of the tag `tag_name` into `to`, so that the
+    /// histories of both values appear under `to`.
+    ///
+    /// A series is renamed in place if the series with the new tag value does not
+    /// exist. Otherwise the two series would collide, the data of the series is
+    /// rewritten into the existing one and deleted by the same raft log. Every
+    /// step can be repeated, so a failed task is retried from the beginning.
+    async fn merge_tag_value(
+        coord: Arc<dyn Coordinator>,
+        table_schema: &TskvTableSchema,
+        tag_name: &str,
+        from: &str,
+        to: &str,
+    ) -> CoordinatorResult<bool> {
+        let tenant_name = table_schema.tenant.as_str();
+        let db_name = table_schema.db.as_str();
+        let schema: TskvTableSchemaRef = Arc::new(table_schema.clone());
+        let tag_id = schema
+            .column(tag_name)
+            .filter(|c| c.column_type.is_tag())
+            .ok_or_else(|| {
+                CommonSnafu {
+                    msg: format!("tag {} not found in table {}", tag_name, schema.name),
+                }
+                .build()
+            })?
+            .id;
+        let table = TableReference::bare(schema.name.as_str())
+            .resolve_object(tenant_name, db_name)
+            .map_err(|e| CommonSnafu { msg: e.to_string() }.build())?;
+
+        let from_series = scan_series(coord.clone(), &table, &schema, tag_name, from).await?;
+        if from_series.is_empty() {
+            return Ok(true);
+        }
+        let to_series = scan_series(coord.clone(), &table, &schema, tag_name, to).await?;
+
+        let mut renamed_series = vec![];
+        let mut collided_series = vec![];
+        for series in from_series.iter() {
+            let mut merged = series.clone();
+            merged.insert(tag_id, to.to_string());
+            if to_series.contains(&merged) {
+                collided_series.push(encode_series_key(&schema.name, series));
+            } else {
+                renamed_series.push(encode_series_key(&schema.name, series));
+            }
+        }
+        info!(
+            "Merge tag {}.{} '{}' into '{}': {} series renamed, {} series collided",
+            schema.name,
+            tag_name,
+            from,
+            to,
+            renamed_series.len(),
+            collided_series.len()
+        );
+
+        if !renamed_series.is_empty() {
+            let tenant = coord.tenant_meta(tenant_name).await.ok_or_else(|| {
+                CoordinatorError::TenantNotFound {
+                    name: tenant_name.to_string(),
+                }
+            })?;
+            let replica_sets = tenant
+                .get_db_info(db_name)
+                .context(MetaSnafu)?
+                .ok_or_else(|| {
+                    CommonSnafu {
+                        msg: format!("database not found: {}", db_name),
+                    }
+                    .build()
+                })?
+                .buckets
+                .into_iter()
+                .flat_map(|bucket| bucket.shard_group)
+                .collect::<Vec<_>>();
+
+            ResourceManager::update_tag_value(
+                coord.clone(),
+                tenant_name,
+                db_name,
+                &[(
+                    tag_id.to_string().into_bytes(),
+                    Some(to.as_bytes().to_vec()),
+                )],
+                &renamed_series,
+                &replica_sets,
+            )
+            .await?;
+        }
+
+        if !collided_series.is_empty() {
+            merge_series(
+                coord.clone(),
+                &table,
+                &schema,
+                tag_name,
+                from,
+                to,
+                &collided_series,
+            )
+            .await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Delete the rows matching the predicate of the job, the rows newer than
+    /// `older_than` of the job are kept.
+    async fn scheduled_delete(
+        coord: Arc<dyn Coordinator>,
+        job: &ScheduledDeleteJob,
+    ) -> CoordinatorResult<bool> {
+        let tenant = coord.tenant_meta(&job.tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: job.tenant.clone(),
+            }
+        })?;
+        let precision = *tenant
+            .get_db_schema(&job.database)
+            .context(MetaSnafu)?
+            .ok_or_else(|| {
+                CommonSnafu {
+                    msg: format!("database not found: {}", job.database),
+                }
+                .build()
+            })?
+            .config()
+            .precision();
+
+        let mut time_ranges = job.time_ranges(now_timestamp_nanos());
+        if time_ranges.is_empty() {
+            return Ok(true);
+        }
+        for range in time_ranges.iter_mut() {
+            let convert = |ts| {
+                timestamp_convert(Precision::NS, precision, ts).ok_or_else(|| {
+                    CommonSnafu {
+                        msg: format!("invalid timestamp: {}", ts),
+                    }
+                    .build()
+                })
+            };
+            range.min_ts = convert(range.min_ts)?;
+            range.max_ts = convert(range.max_ts)?;
+        }
+        let predicate = ResolvedPredicate::new(
+            Arc::new(TimeRanges::new(time_ranges)),
+            job.tags.clone(),
+            None,
+        )
+        .context(ModelsSnafu)?;
+        let table = TableReference::bare(job.table.as_str())
+            .resolve_object(&job.tenant, &job.database)
+            .map_err(|e| CommonSnafu { msg: e.to_string() }.build())?;
+
+        info!(
+            "Scheduled delete {} from table {}: {:?}",
+            job.name, table, predicate
+        );
+        coord.delete_from_table(&table, &predicate).await?;
+
+        Ok(true)
+    }
+
+    /// The task deleting the rows written to the table within the time range,
+    /// in the precision of the database, by a write which failed or was cancelled.
+    pub fn delete_written_rows_task(
+        tenant_id: Oid,
+        table_schema: &TskvTableSchema,
+        time_range: TimeRange,
+        execute_node_id: NodeId,
+    ) -> ResourceInfo {
+        ResourceInfo::new(
+            (tenant_id, table_schema.db.clone()),
+            format!(
+                "{}-{}-{}-{}-{}-DeleteWrittenRows",
+                table_schema.tenant,
+                table_schema.db,
+                table_schema.name,
+                time_range.min_ts,
+                time_range.max_ts
+            ),
+            ResourceOperator::DeleteWrittenRows(
+                table_schema.tenant.clone(),
+                table_schema.db.clone(),
+                table_schema.name.clone(),
+                time_range,
+            ),
+            &None,
+            execute_node_id,
+        )
+    }
+
+    /// Delete all the rows of the table within the time range, including the
+    /// rows not written by the failed write.
+    async fn delete_written_rows(
+        coord: Arc<dyn Coordinator>,
+        tenant_name: &str,
+        db_name: &str,
+        table_name: &str,
+        time_range: &TimeRange,
+    ) -> CoordinatorResult<bool> {
+        let predicate = written_rows_predicate(time_range)?;
+        let table = TableReference::bare(table_name)
+            .resolve_object(tenant_name, db_name)
+            .map_err(|e| CommonSnafu { msg: e.to_string() }.build())?;
+
+        info!(
+            "Delete the rows written to table {} within {:?}",
+            table, time_range
+        );
+        coord.delete_from_table(&table, &predicate).await?;
+
+        Ok(true)
+    }
+
     pub async fn add_resource_task(
         coord: Arc<dyn Coordinator>,
         mut resourceinfo: ResourceInfo,
@@ -392,3 +725,409 @@ impl ResourceManager {
         }
     }
 }
+
+/// Record the run of the scheduled delete job and schedule the next run, the
+/// job is fatal if it never runs again. A failed run is not retried, the job
+/// waits for the next run instead. Returns the status of the job.
+fn schedule_next_run(
+    resourceinfo: &mut ResourceInfo,
+    now: i64,
+    success: bool,
+    comment: &str,
+) -> ResourceStatus {
+    let ResourceOperator::ScheduledDelete(job) = resourceinfo.get_operator_mut() else {
+        return resourceinfo.get_status().clone();
+    };
+    job.record_run(ScheduledDeleteRun {
+        time: now,
+        success,
+        comment: comment.to_string(),
+    });
+    match job.next_run_time(now) {
+        Some(next_time) => {
+            resourceinfo.set_time(next_time);
+            resourceinfo.set_is_new_add(true);
+            ResourceStatus::Schedule
+        }
+        None => {
+            resourceinfo.set_is_new_add(false);
+            ResourceStatus::Fatal
+        }
+    }
+}
+
+fn written_rows_predicate(time_range: &TimeRange) -> CoordinatorResult<ResolvedPredicate> {
+    ResolvedPredicate::new(
+        Arc::new(TimeRanges::new(vec![*time_range])),
+        ColumnDomains::all(),
+        None,
+    )
+    .context(ModelsSnafu)
+}
+
+fn tag_value_predicate(tag_name: &str, value: &str) -> CoordinatorResult<ResolvedPredicate> {
+    let domain = Domain::of_values(
+        &DataType::Utf8,
+        true,
+        &[&ScalarValue::Utf8(Some(value.to_string()))],
+    );
+    ResolvedPredicate::new(
+        Arc::new(TimeRanges::all()),
+        ColumnDomains::of(tag_name.to_string(), &domain),
+        None,
+    )
+    .context(ModelsSnafu)
+}
+
+/// Scan the tags of the series whose tag `tag_name` is `value`.
+async fn scan_series(
+    coord: Arc<dyn Coordinator>,
+    table: &ResolvedTable,
+    schema: &TskvTableSchemaRef,
+    tag_name: &str,
+    value: &str,
+) -> CoordinatorResult<HashSet<SeriesTags>> {
+    let tag_columns = schema
+        .columns()
+        .iter()
+        .filter(|c| c.column_type.is_tag())
+        .cloned()
+        .collect::<Vec<_>>();
+    let proj_schema: TskvTableSchemaRef = Arc::new(TskvTableSchema::new(
+        schema.tenant.clone(),
+        schema.db.clone(),
+        schema.name.clone(),
+        tag_columns.clone(),
+    ));
+    let predicate = Arc::new(tag_value_predicate(tag_name, value)?);
+    let replica_sets = coord.table_vnodes(table, predicate.clone()).await?;
+
+    let mut series = HashSet::new();
+    for (i, replica_set) in replica_sets.into_iter().enumerate() {
+        let split = PlacedSplit::new(i, predicate.clone(), None, replica_set);
+        let option = QueryOption::new(
+            MERGE_TAG_BATCH_SIZE,
+            split,
+            None,
+            proj_schema.to_arrow_schema(),
+            proj_schema.clone(),
+            schema.meta(),
+        );
+        let mut stream = coord.tag_scan(option, None)?;
+        while let Some(batch) = stream.try_next().await? {
+            let mut arrays = vec![];
+            for column in tag_columns.iter() {
+                if let Some(array) = batch
+                    .column_by_name(&column.name)
+                    .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+                {
+                    arrays.push((column.id, array));
+                }
+            }
+            for row in 0..batch.num_rows() {
+                let tags = arrays
+                    .iter()
+                    .filter(|(_, array)| array.is_valid(row))
+                    .map(|(id, array)| (*id, array.value(row).to_string()))
+                    .collect::<SeriesTags>();
+                series.insert(tags);
+            }
+        }
+    }
+
+    Ok(series)
+}
+
+fn encode_series_key(table: &str, series: &SeriesTags) -> Vec<u8> {
+    let mut tags = series
+        .iter()
+        .map(|(id, value)| Tag::new_with_column_id(*id, value.as_bytes().to_vec()))
+        .collect::<Vec<_>>();
+    tag::sort_tags(&mut tags);
+
+    SeriesKey {
+        tags,
+        table: table.to_string(),
+    }
+    .encode()
+}
+
+/// Write the rows matching the predicate again with the tag `tag_name` set to `value`.
+/// Merge the series of the tag value `from` into the existing series of `to`.
+/// The data of each replica set is rewritten with the value `to` and deleted
+/// by a single raft log, so it's either merged or left as is.
+async fn merge_series(
+    coord: Arc<dyn Coordinator>,
+    table: &ResolvedTable,
+    schema: &TskvTableSchemaRef,
+    tag_name: &str,
+    from: &str,
+    to: &str,
+    series_keys: &[Vec<u8>],
+) -> CoordinatorResult<()> {
+    // Encrypted values are scanned as stored, they must not be encrypted twice.
+    let write_schema: TskvTableSchemaRef = Arc::new(TskvTableSchema::new(
+        schema.tenant.clone(),
+        schema.db.clone(),
+        schema.name.clone(),
+        schema
+            .columns()
+            .iter()
+            .cloned()
+            .map(|mut c| {
+                c.encryption_key = None;
+                c
+            })
+            .collect(),
+    ));
+    // Only the collided series keep the old tag value after the renaming.
+    let predicate = Arc::new(tag_value_predicate(tag_name, from)?);
+    let replica_sets = coord.table_vnodes(table, predicate.clone()).await?;
+
+    for (i, replica_set) in replica_sets.into_iter().enumerate() {
+        let split = PlacedSplit::new(i, predicate.clone(), None, replica_set.clone());
+        let option = QueryOption::new(
+            MERGE_TAG_BATCH_SIZE,
+            split,
+            None,
+            schema.to_arrow_schema(),
+            schema.clone(),
+            schema.meta(),
+        );
+        let mut batches = vec![];
+        let mut stream = coord.table_scan(option, None)?;
+        while let Some(batch) = stream.try_next().await? {
+            if batch.num_rows() > 0 {
+                batches.push(replace_tag_value(batch, tag_name, to)?);
+            }
+        }
+        let points = match batches.first() {
+            Some(first) => {
+                let batch = concat_batches(&first.schema(), &batches)?;
+                arrow_array_to_points(
+                    batch.columns().to_vec(),
+                    batch.schema(),
+                    write_schema.clone(),
+                    batch.num_rows(),
+                )
+                .map_err(|e| {
+                    CommonSnafu {
+                        msg: format!("arrow array to points error: {}", e),
+                    }
+                    .build()
+                })?
+            }
+            None => vec![],
+        };
+
+        let command = RaftWriteCommand {
+            replica_id: replica_set.id,
+            tenant: schema.tenant.clone(),
+            db_name: schema.db.clone(),
+            consistency: WriteConsistency::Quorum as i32,
+            command: Some(raft_write_command::Command::MergeSeries(
+                MergeSeriesRequest {
+                    table: schema.name.clone(),
+                    series_keys: series_keys.to_vec(),
+                    points,
+                    precision: schema.time_column_precision() as u32,
+                },
+            )),
+        };
+        coord
+            .write_replica_by_raft(replica_set, command, None)
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn replace_tag_value(
+    batch: RecordBatch,
+    tag_name: &str,
+    value: &str,
+) -> CoordinatorResult<RecordBatch> {
+    let schema = batch.schema();
+    let index = schema.index_of(tag_name)?;
+    let mut columns = batch.columns().to_vec();
+    columns[index] = Arc::new(StringArray::from(vec![value; batch.num_rows()]));
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Array, Float64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::SeriesKey;
+
+    use super::{encode_series_key, replace_tag_value};
+
+    #[test]
+    fn test_encode_series_key() {
+        let series = BTreeMap::from([(2, "a".to_string()), (10, "b".to_string())]);
+        let key = SeriesKey::decode(&encode_series_key("cpu", &series)).unwrap();
+        assert_eq!(key.table, "cpu");
+        let tags = key
+            .tags
+            .iter()
+            .map(|t| (t.key.clone(), t.value.clone()))
+            .collect::<Vec<_>>();
+        // Sorted by the tag key.
+        assert_eq!(
+            tags,
+            vec![
+                (b"10".to_vec(), b"b".to_vec()),
+                (b"2".to_vec(), b"a".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_tag_value() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("usage", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["old", "old"])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0])),
+            ],
+        )
+        .unwrap();
+
+        let batch = replace_tag_value(batch, "host", "new").unwrap();
+        let hosts = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(hosts.iter().collect::<Vec<_>>(), vec![Some("new"); 2]);
+        assert_eq!(batch.column(1).len(), 2);
+        assert!(replace_tag_value(batch, "region", "new").is_err());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use chrono::NaiveDate;
+    use datafusion::arrow::datatypes::TimeUnit;
+    use models::predicate::domain::{ColumnDomains, TimeRange};
+    use models::schema::resource_info::{ResourceInfo, ResourceOperator, ResourceStatus};
+    use models::schema::scheduled_delete::{ScheduledDeleteJob, MAX_JOB_HISTORY};
+    use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema};
+
+    use super::{schedule_next_run, written_rows_predicate, ResourceManager};
+
+    fn job_resourceinfo(cron: &str) -> ResourceInfo {
+        let job = ScheduledDeleteJob {
+            name: "job".to_string(),
+            tenant: "cnosdb".to_string(),
+            database: "public".to_string(),
+            table: "air".to_string(),
+            cron: cron.to_string(),
+            older_than: None,
+            selection: None,
+            tags: ColumnDomains::all(),
+            time: ColumnDomains::all(),
+            history: VecDeque::new(),
+        };
+        let mut resourceinfo = ResourceInfo::new(
+            (0, "public".to_string()),
+            ScheduledDeleteJob::resource_name("cnosdb", "public", "job"),
+            ResourceOperator::ScheduledDelete(job),
+            &None,
+            0,
+        );
+        resourceinfo.set_status(ResourceStatus::Executing);
+        resourceinfo.set_is_new_add(false);
+        resourceinfo
+    }
+
+    fn ts(d: u32, h: u32) -> i64 {
+        NaiveDate::from_ymd_opt(2023, 5, d)
+            .unwrap()
+            .and_hms_opt(h, 0, 0)
+            .unwrap()
+            .timestamp_nanos_opt()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_schedule_next_run() {
+        let mut resourceinfo = job_resourceinfo("0 3 * * *");
+        let status = schedule_next_run(&mut resourceinfo, ts(17, 3), false, "failed");
+        // A failed run waits for the next run.
+        assert_eq!(status, ResourceStatus::Schedule);
+        assert_eq!(resourceinfo.get_time(), ts(18, 3));
+        assert!(resourceinfo.get_is_new_add());
+        let ResourceOperator::ScheduledDelete(job) = resourceinfo.get_operator() else {
+            panic!("not a scheduled delete");
+        };
+        assert_eq!(job.history.len(), 1);
+        assert!(!job.history[0].success);
+        assert_eq!(job.history[0].comment, "failed");
+
+        for day in 18..18 + MAX_JOB_HISTORY as u32 {
+            schedule_next_run(&mut resourceinfo, ts(day, 3), true, "");
+        }
+        let ResourceOperator::ScheduledDelete(job) = resourceinfo.get_operator() else {
+            panic!("not a scheduled delete");
+        };
+        assert_eq!(job.history.len(), MAX_JOB_HISTORY);
+        assert!(job.history.iter().all(|run| run.success));
+    }
+
+    #[test]
+    fn test_delete_written_rows_task() {
+        let schema = TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "air".to_string(),
+            vec![TableColumn::new_time_column(0, TimeUnit::Nanosecond)],
+        );
+        let time_range = TimeRange::new(10, 20);
+        let resourceinfo = ResourceManager::delete_written_rows_task(7, &schema, time_range, 1);
+        assert_eq!(
+            resourceinfo.get_name(),
+            "cnosdb-public-air-10-20-DeleteWrittenRows"
+        );
+        assert_eq!(
+            resourceinfo.get_tenant_id_and_db(),
+            &(7, "public".to_string())
+        );
+        assert_eq!(*resourceinfo.get_status(), ResourceStatus::Executing);
+        let ResourceOperator::DeleteWrittenRows(tenant, db, table, range) =
+            resourceinfo.get_operator()
+        else {
+            panic!("not a delete of the written rows");
+        };
+        assert_eq!(
+            (tenant.as_str(), db.as_str(), table.as_str(), *range),
+            ("cnosdb", "public", "air", time_range)
+        );
+
+        let predicate = written_rows_predicate(&time_range).unwrap();
+        assert_eq!(
+            predicate.time_ranges().time_ranges().collect::<Vec<_>>(),
+            vec![time_range]
+        );
+        assert!(predicate.tags_filter().is_all());
+    }
+
+    #[test]
+    fn test_schedule_never_run_again() {
+        let mut resourceinfo = job_resourceinfo("0 0 31 2 *");
+        let status = schedule_next_run(&mut resourceinfo, ts(17, 3), true, "");
+        assert_eq!(status, ResourceStatus::Fatal);
+        assert!(!resourceinfo.get_is_new_add());
+    }
+}
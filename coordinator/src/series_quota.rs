@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use meta::model::MetaRef;
+use models::meta_data::{BucketInfo, NodeId, ReplicationSet, VnodeId};
+use models::schema::tenant::TenantQuota;
+use parking_lot::Mutex;
+use protos::kv_service::admin_command::Command::TableSeriesCount;
+use protos::kv_service::{AdminCommand, TableSeriesCountRequest};
+use snafu::ResultExt;
+use trace::warn;
+use tskv::EngineRef;
+
+use crate::errors::{
+    CommonSnafu, CoordinatorError, CoordinatorResult, SeriesQuotaExceededSnafu, TskvSnafu,
+};
+use crate::tskv_executor::TskvAdminRequest;
+
+/// How long the series count of a table is cached before it is counted again.
+const SERIES_COUNT_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TableKey {
+    tenant: String,
+    db: String,
+    bucket_id: u32,
+    table: String,
+}
+
+/// The tenant quota max_series_per_table, checked by the leader of a replica set
+/// before the write is proposed to raft, so that the replicas always apply the
+/// same writes. The series of a table are counted over the replica sets of the
+/// bucket being written, cached for a while and the admitted new series are
+/// added to the cache until it's counted again.
+pub struct SeriesQuota {
+    meta: MetaRef,
+    kv_inst: Option<EngineRef>,
+    node_id: NodeId,
+    timeout: Duration,
+    enable_gzip: bool,
+
+    counts: SeriesCounts,
+}
+
+impl SeriesQuota {
+    pub fn new(
+        meta: MetaRef,
+        kv_inst: Option<EngineRef>,
+        node_id: NodeId,
+        timeout: Duration,
+        enable_gzip: bool,
+    ) -> Self {
+        Self {
+            meta,
+            kv_inst,
+            node_id,
+            timeout,
+            enable_gzip,
+            counts: SeriesCounts::default(),
+        }
+    }
+
+    /// Check the new series in the `points` written to the leader `vnode_id` of
+    /// the replica set.
+    pub async fn check(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        replica_id: u32,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> CoordinatorResult<()> {
+        let Some(kv_inst) = &self.kv_inst else {
+            return Ok(());
+        };
+        let tenant_meta = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let Some(max) = tenant_meta
+            .tenant()
+            .options()
+            .quota(TenantQuota::SeriesPerTable)
+        else {
+            return Ok(());
+        };
+
+        let new_series = kv_inst
+            .count_new_series(tenant, db_name, vnode_id, points)
+            .await
+            .context(TskvSnafu)?;
+        if new_series.is_empty() {
+            return Ok(());
+        }
+
+        let bucket = tenant_meta
+            .get_replica_all_info(replica_id)
+            .and_then(|info| {
+                tenant_meta
+                    .get_db_info(db_name)
+                    .ok()
+                    .flatten()?
+                    .buckets
+                    .into_iter()
+                    .find(|bucket| bucket.id == info.bucket_id)
+            })
+            .ok_or(CoordinatorError::ReplicationSetNotFound { id: replica_id })?;
+
+        for (table, new) in new_series {
+            let key = TableKey {
+                tenant: tenant.to_string(),
+                db: db_name.to_string(),
+                bucket_id: bucket.id,
+                table,
+            };
+            if self.counts.cached(&key).is_none() {
+                let count = self.bucket_series_count(&key, &bucket).await;
+                self.counts.refresh(&key, count);
+            }
+            if !self.counts.admit(&key, new, max as u64) {
+                return Err(SeriesQuotaExceededSnafu {
+                    table: key.table,
+                    max,
+                }
+                .build());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget the cached series counts of the dropped database, or of the
+    /// dropped table of it if `table` is set.
+    pub fn forget(&self, tenant: &str, db: &str, table: Option<&str>) {
+        self.counts.forget(tenant, db, table);
+    }
+
+    /// Forget all the cached series counts, they are counted again.
+    pub fn clear(&self) {
+        self.counts.0.lock().clear();
+    }
+
+    /// Count the series of the table over the replica sets of the bucket, the
+    /// replica sets failed to count are skipped.
+    async fn bucket_series_count(&self, key: &TableKey, bucket: &BucketInfo) -> u64 {
+        let mut count = 0;
+        for replica_set in bucket.shard_group.iter() {
+            match self.replica_series_count(key, replica_set).await {
+                Ok(c) => count += c,
+                Err(err) => warn!(
+                    "Failed to count the series of table {} in replica set {}: {}",
+                    key.table, replica_set.id, err
+                ),
+            }
+        }
+
+        count
+    }
+
+    async fn replica_series_count(
+        &self,
+        key: &TableKey,
+        replica_set: &ReplicationSet,
+    ) -> CoordinatorResult<u64> {
+        if let (Some(kv_inst), true) = (&self.kv_inst, replica_set.leader_node_id == self.node_id) {
+            return kv_inst
+                .table_series_count(
+                    &key.tenant,
+                    &key.db,
+                    &key.table,
+                    replica_set.leader_vnode_id,
+                )
+                .await
+                .context(TskvSnafu);
+        }
+
+        let request = AdminCommand {
+            tenant: key.tenant.clone(),
+            command: Some(TableSeriesCount(TableSeriesCountRequest {
+                db_name: key.db.clone(),
+                table: key.table.clone(),
+                vnode_id: replica_set.leader_vnode_id,
+            })),
+        };
+        let caller = TskvAdminRequest {
+            request,
+            meta: self.meta.clone(),
+            timeout: self.timeout,
+            enable_gzip: self.enable_gzip,
+        };
+        let data = caller.do_request(replica_set.leader_node_id).await?;
+        let bytes = data.as_slice().try_into().map_err(|_| {
+            CommonSnafu {
+                msg: format!("invalid series count of {} bytes", data.len()),
+            }
+            .build()
+        })?;
+
+        Ok(u64::from_be_bytes(bytes))
+    }
+}
+
+/// The series count of the tables, and when they were counted.
+#[derive(Default)]
+struct SeriesCounts(Mutex<HashMap<TableKey, (u64, Instant)>>);
+
+impl SeriesCounts {
+    /// The cached series count of the table, None if it's not cached or expired.
+    fn cached(&self, key: &TableKey) -> Option<u64> {
+        self.0
+            .lock()
+            .get(key)
+            .filter(|(_, refreshed)| refreshed.elapsed() < SERIES_COUNT_TTL)
+            .map(|(count, _)| *count)
+    }
+
+    fn refresh(&self, key: &TableKey, count: u64) {
+        self.0.lock().insert(key.clone(), (count, Instant::now()));
+    }
+
+    /// Admit `new` series of the table if the series count stays within `max`,
+    /// the admitted series are counted until the next refresh.
+    fn admit(&self, key: &TableKey, new: u64, max: u64) -> bool {
+        let mut counts = self.0.lock();
+        let (count, _) = counts
+            .entry(key.clone())
+            .or_insert_with(|| (0, Instant::now()));
+        if *count + new > max {
+            return false;
+        }
+        *count += new;
+
+        true
+    }
+
+    fn forget(&self, tenant: &str, db: &str, table: Option<&str>) {
+        self.0.lock().retain(|key, _| {
+            key.tenant != tenant || key.db != db || table.map_or(false, |t| key.table != t)
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{SeriesCounts, TableKey, SERIES_COUNT_TTL};
+
+    fn table_key(table: &str) -> TableKey {
+        TableKey {
+            tenant: "cnosdb".to_string(),
+            db: "public".to_string(),
+            bucket_id: 1,
+            table: table.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_admit_series() {
+        let counts = SeriesCounts::default();
+        let key = table_key("air");
+
+        counts.refresh(&key, 8);
+        assert_eq!(counts.cached(&key), Some(8));
+        assert!(counts.admit(&key, 2, 10));
+        assert_eq!(counts.cached(&key), Some(10));
+
+        // Rejected writes are not counted.
+        assert!(!counts.admit(&key, 1, 10));
+        assert_eq!(counts.cached(&key), Some(10));
+
+        // The other tables are counted separately.
+        let other = table_key("sea");
+        assert!(!counts.admit(&other, 11, 10));
+        assert!(counts.admit(&other, 10, 10));
+    }
+
+    #[test]
+    fn test_forget_series_counts() {
+        let counts = SeriesCounts::default();
+        let mut other_db = table_key("air");
+        other_db.db = "other".to_string();
+        for key in [table_key("air"), table_key("sea"), other_db.clone()] {
+            counts.refresh(&key, 1);
+        }
+
+        counts.forget("cnosdb", "public", Some("air"));
+        assert_eq!(counts.cached(&table_key("air")), None);
+        assert_eq!(counts.cached(&table_key("sea")), Some(1));
+
+        counts.forget("cnosdb", "public", None);
+        assert_eq!(counts.cached(&table_key("sea")), None);
+        assert_eq!(counts.cached(&other_db), Some(1));
+    }
+
+    #[test]
+    fn test_cached_series_count_expires() {
+        let counts = SeriesCounts::default();
+        let key = table_key("air");
+
+        assert_eq!(counts.cached(&key), None);
+        counts.0.lock().insert(
+            key.clone(),
+            (
+                5,
+                Instant::now() - SERIES_COUNT_TTL - Duration::from_millis(1),
+            ),
+        );
+        assert_eq!(counts.cached(&key), None);
+
+        counts.refresh(&key, 5);
+        assert_eq!(counts.cached(&key), Some(5));
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(clippy::type_complexity)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
@@ -9,6 +9,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::{mem, vec};
 
+use chrono::TimeZone;
 use config::tskv::Config;
 use datafusion::arrow::array::{
     Array, ArrayRef, Int64Array, StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
@@ -17,6 +18,7 @@ use datafusion::arrow::array::{
 use datafusion::arrow::compute::take;
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
 use memory_pool::MemoryPoolRef;
 use meta::error::MetaError;
 use meta::model::{MetaClientRef, MetaRef};
@@ -25,51 +27,78 @@ use metrics::count::U64Counter;
 use metrics::label::Labels;
 use metrics::metric::Metric;
 use metrics::metric_register::MetricsRegister;
+use models::encryption::ColumnCipher;
 use models::meta_data::{
-    ExpiredBucketInfo, NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeStatus,
+    BucketInfo, DroppedResource, ExpiredBucketInfo, NodeId, NodeMetrics, ReplicaAllInfo,
+    ReplicationSet, ReplicationSetId, VnodeApplied, VnodeId, VnodeInfo, VnodeStatus,
 };
 use models::object_reference::ResolvedTable;
 use models::oid::Identifier;
-use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef, TimeRange, TimeRanges};
+use models::predicate::domain::{
+    ColumnDomains, ResolvedPredicate, ResolvedPredicateRef, TimeRange, TimeRanges,
+};
 use models::schema::resource_info::{ResourceInfo, ResourceOperator};
-use models::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
+use models::schema::tskv_table_schema::{
+    ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
+};
 use models::schema::{DEFAULT_CATALOG, TIME_FIELD_NAME, USAGE_SCHEMA};
 use models::utils::now_timestamp_nanos;
 use models::{record_batch_decode, SeriesKey, Tag};
 use protocol_parser::lines_convert::{
-    arrow_array_to_points, line_to_batches, mutable_batches_to_point,
+    arrow_array_to_points, line_to_batches, mutable_batches_to_point, table_default_fields,
+    unpack_record_batch,
 };
 use protocol_parser::Line;
 use protos::kv_service::admin_command::Command::*;
 use protos::kv_service::*;
-use replication::multi_raft::MultiRaft;
+use protos::{tskv_service_time_out_client, FieldValue, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
+use replication::multi_raft::{LogPurgePolicy, MultiRaft};
 use snafu::{IntoError, OptionExt, ResultExt};
 use tokio::runtime::Runtime;
+use tokio::sync::broadcast;
 use trace::span_ext::SpanExt;
-use trace::{debug, error, info, Span, SpanContext};
-use tskv::EngineRef;
+use trace::{debug, error, info, warn, Span, SpanContext};
+use tskv::index::cardinality::merge_cardinality_top_record_batches;
+use tskv::{CompactionJobInfo, CompactionPlan, EngineRef, ScanCost, VnodeBackup};
 use utils::precision::{timestamp_convert, Precision};
 use utils::BkdrHasher;
 
+use crate::dry_run::{check_line_schema, DryRunReport, ReplicaRouting};
 use crate::errors::{
-    ArrowSnafu, BincodeSerdeSnafu, ColumnNotFoundSnafu, CommonSnafu, CoordinatorError,
-    CoordinatorResult, FieldsIsEmptySnafu, MetaSnafu,
+    AmbiguousCompactionJobSnafu, ArrowSnafu, BincodeSerdeSnafu, ColumnNotFoundSnafu, CommonSnafu,
+    CompactionJobNotFoundSnafu, CoordinatorError, CoordinatorResult, FieldsIsEmptySnafu, MetaSnafu,
+    ModelsSnafu, RaftNodeNotFoundSnafu, TskvSnafu,
 };
+use crate::freshness_probe::FreshnessProbe;
 use crate::metrics::LPReporter;
 use crate::raft::manager::RaftNodesManager;
+use crate::raft::recovery::NodeRecoveryProgress;
 use crate::raft::writer::TskvRaftWriter;
 use crate::reader::table_scan::opener::TemporaryTableScanOpener;
 use crate::reader::tag_scan::opener::TemporaryTagScanOpener;
 use crate::reader::{CheckFuture, CheckedCoordinatorRecordBatchStream};
+use crate::repair::VnodeRepair;
+use crate::replica_lag::{move_to_front, pick_follower, pick_fresh_follower};
 use crate::resource_manager::ResourceManager;
+use crate::series_quota::SeriesQuota;
+use crate::table_ttl::TableTtlState;
 use crate::tskv_executor::{TskvAdminRequest, TskvLeaderExecutor};
 use crate::{
-    get_replica_all_info, get_vnode_all_info, Coordinator, QueryOption, ReplicationCmdType,
-    SendableCoordinatorRecordBatchStream,
+    get_replica_all_info, get_vnode_all_info, replica_export, Coordinator, LeaderTransfer,
+    LineBatchStream, QueryOption, RecordBatchWrite, ReplicaDataStream, ReplicationCmdType,
+    SendableCoordinatorRecordBatchStream, ShardRows, VnodePlacement, VnodeScanCost,
 };
 
 pub type CoordinatorRef = Arc<dyn Coordinator>;
 
+/// Estimated size in bytes of the lines of a replication set buffered by
+/// `write_lines_stream` before they are written.
+const STREAM_WRITE_FLUSH_SIZE: usize = 4 * 1024 * 1024;
+/// Interval of deleting the data of tables older than their TTL.
+const TABLE_TTL_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Interval of purging the dropped databases whose events are missed.
+const DROPPED_DB_SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Clone)]
 pub struct CoordService {
     node_id: u64,
@@ -82,6 +111,7 @@ pub struct CoordService {
     memory_pool: MemoryPoolRef,
     metrics: Arc<CoordServiceMetrics>,
     raft_manager: Arc<RaftNodesManager>,
+    series_quota: Arc<SeriesQuota>,
 }
 
 #[derive(Debug)]
@@ -90,6 +120,8 @@ pub struct CoordServiceMetrics {
     coord_data_out: Metric<U64Counter>,
     coord_queries: Metric<U64Counter>,
     coord_writes: Metric<U64Counter>,
+    coord_scan_batches_in: Metric<U64Counter>,
+    coord_scan_batches_out: Metric<U64Counter>,
 
     sql_data_in: Metric<U64Counter>,
     sql_write_row: Metric<U64Counter>,
@@ -98,6 +130,7 @@ pub struct CoordServiceMetrics {
     write_lines_prepare: Metric<U64Average>,
     write_batch_prepare: Metric<U64Average>,
     write_replica_duration: Metric<U64Average>,
+    write_memory_exhausted: Metric<U64Counter>,
 }
 
 macro_rules! generate_coord_metrics_gets {
@@ -113,12 +146,15 @@ generate_coord_metrics_gets!(coord_data_in, U64Counter);
 generate_coord_metrics_gets!(coord_data_out, U64Counter);
 generate_coord_metrics_gets!(coord_queries, U64Counter);
 generate_coord_metrics_gets!(coord_writes, U64Counter);
+generate_coord_metrics_gets!(coord_scan_batches_in, U64Counter);
+generate_coord_metrics_gets!(coord_scan_batches_out, U64Counter);
 generate_coord_metrics_gets!(sql_data_in, U64Counter);
 generate_coord_metrics_gets!(sql_write_row, U64Counter);
 generate_coord_metrics_gets!(sql_points_data_in, U64Counter);
 generate_coord_metrics_gets!(write_lines_prepare, U64Average);
 generate_coord_metrics_gets!(write_batch_prepare, U64Average);
 generate_coord_metrics_gets!(write_replica_duration, U64Average);
+generate_coord_metrics_gets!(write_memory_exhausted, U64Counter);
 
 impl CoordServiceMetrics {
     pub fn new(register: &MetricsRegister) -> Self {
@@ -126,6 +162,14 @@ impl CoordServiceMetrics {
         let coord_data_out = register.metric("coord_data_out", "tenant data out");
         let coord_writes = register.metric("coord_writes", "");
         let coord_queries = register.metric("coord_queries", "");
+        let coord_scan_batches_in = register.metric(
+            "coord_scan_batches_in",
+            "record batches received from remote vnode scans",
+        );
+        let coord_scan_batches_out = register.metric(
+            "coord_scan_batches_out",
+            "record batches of remote vnode scans after coalescing",
+        );
 
         let sql_data_in = register.metric("sql_data_in", "Traffic written through sql");
         let sql_write_row = register.metric("sql_write_row", "sql write row");
@@ -135,12 +179,18 @@ impl CoordServiceMetrics {
         let write_batch_prepare = register.metric("write_batch_prepare", "write batch prepare");
         let write_replica_duration =
             register.metric("write_replica_duration", "write replica duration");
+        let write_memory_exhausted = register.metric(
+            "write_memory_exhausted",
+            "writes rejected for exhausted memory pool",
+        );
 
         Self {
             coord_data_in,
             coord_data_out,
             coord_writes,
             coord_queries,
+            coord_scan_batches_in,
+            coord_scan_batches_out,
 
             sql_data_in,
             sql_write_row,
@@ -148,6 +198,7 @@ impl CoordServiceMetrics {
             write_lines_prepare,
             write_batch_prepare,
             write_replica_duration,
+            write_memory_exhausted,
         }
     }
 
@@ -175,14 +226,28 @@ impl CoordService {
             .await
             .unwrap();
 
+        let purge_policy = LogPurgePolicy {
+            disk_budget: config.cluster.raft_logs_disk_budget,
+            min_logs_to_keep: config.cluster.raft_logs_min_to_keep,
+        };
         tokio::spawn(MultiRaft::raft_nodes_manager(
             raft_manager.multi_raft(),
             config.cluster.trigger_snapshot_interval,
+            purge_policy,
+        ));
+
+        let series_quota = Arc::new(SeriesQuota::new(
+            meta.clone(),
+            kv_inst.clone(),
+            config.global.node_id,
+            config.query.write_timeout,
+            config.service.grpc_enable_gzip,
         ));
 
         let coord = Arc::new(Self {
             runtime,
             kv_inst,
+            series_quota,
             memory_pool,
             raft_manager,
             meta: meta.clone(),
@@ -193,11 +258,20 @@ impl CoordService {
         });
 
         tokio::spawn(CoordService::db_ttl_service(coord.clone()));
+        tokio::spawn(CoordService::dropped_resource_service(coord.clone()));
+        if coord.kv_inst.is_some() {
+            tokio::spawn(CoordService::table_ttl_service(coord.clone()));
+        }
 
         if config.global.pre_create_bucket {
             tokio::spawn(CoordService::pre_create_bucket_service(coord.clone()));
         }
 
+        if !config.global.freshness_probe_interval.is_zero() {
+            let probe = FreshnessProbe::new(coord.clone(), metrics_register.as_ref());
+            tokio::spawn(probe.run());
+        }
+
         if config.global.store_metrics {
             tokio::spawn(CoordService::metrics_service(
                 coord.clone(),
@@ -222,6 +296,201 @@ impl CoordService {
         }
     }
 
+    async fn table_ttl_service(coord: Arc<CoordService>) {
+        let mut state = TableTtlState::default();
+        loop {
+            tokio::time::sleep(TABLE_TTL_CHECK_INTERVAL).await;
+
+            if let Err(err) = coord.expire_tables(&mut state).await {
+                warn!("Table TTL: failed to delete the expired data: {}", err);
+            }
+        }
+    }
+
+    /// Delete the data of the tables older than their TTL in the replica sets
+    /// led by this node, the deletes are proposed to raft.
+    async fn expire_tables(&self, state: &mut TableTtlState) -> CoordinatorResult<()> {
+        let now = now_timestamp_nanos();
+        let mut replica_ids = HashSet::new();
+        for tenant in self.meta.tenants().await.context(MetaSnafu)? {
+            let Some(meta) = self.meta.tenant_meta(tenant.name()).await else {
+                continue;
+            };
+            for (db, db_info) in meta.list_databases().context(MetaSnafu)? {
+                replica_ids.extend(
+                    db_info
+                        .buckets
+                        .iter()
+                        .flat_map(|bucket| bucket.shard_group.iter())
+                        .map(|replica| replica.id),
+                );
+
+                for expired in state.expired(&db_info, self.node_id, now) {
+                    let predicate = ResolvedPredicate::new(
+                        Arc::new(TimeRanges::new(vec![expired.time_range])),
+                        ColumnDomains::all(),
+                        None,
+                    )
+                    .context(ModelsSnafu)?;
+                    let request = DeleteFromTableRequest {
+                        tenant: tenant.name().to_string(),
+                        database: db.clone(),
+                        table: expired.table.clone(),
+                        predicate: bincode::serialize(&predicate).context(BincodeSerdeSnafu)?,
+                        vnode_id: 0,
+                    };
+                    let command = RaftWriteCommand {
+                        replica_id: expired.replica.id,
+                        tenant: tenant.name().to_string(),
+                        db_name: db.clone(),
+                        consistency: WriteConsistency::Quorum as i32,
+                        command: Some(raft_write_command::Command::DeleteFromTable(request)),
+                    };
+                    match self
+                        .write_replica_by_raft(expired.replica.clone(), command, None)
+                        .await
+                    {
+                        Ok(()) => {
+                            debug!(
+                                "Table TTL: deleted {}.{} in {:?} of replica set {}",
+                                db, expired.table, expired.time_range, expired.replica.id
+                            );
+                            state.proposed(&expired);
+                        }
+                        Err(err) => warn!(
+                            "Table TTL: failed to delete {}.{} of replica set {}: {}",
+                            db, expired.table, expired.replica.id, err
+                        ),
+                    }
+                }
+            }
+        }
+        state.retain(&replica_ids);
+
+        Ok(())
+    }
+
+    async fn dropped_resource_service(coord: Arc<CoordService>) {
+        let mut receiver = coord.meta.subscribe_dropped();
+        // The first tick sweeps the databases dropped while this node was down.
+        let mut sweep = tokio::time::interval(DROPPED_DB_SWEEP_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = sweep.tick() => coord.sweep_dropped_dbs().await,
+                event = receiver.recv() => match event {
+                    Ok(dropped) => {
+                        let result = coord.purge_dropped(&dropped).await;
+                        info!("purge dropped resource: {:?}, {:?}", dropped, result);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(count)) => {
+                        warn!("dropped resource events lagged, {} events missed", count);
+                        coord.series_quota.clear();
+                        coord.sweep_dropped_dbs().await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+
+    async fn purge_dropped(&self, dropped: &DroppedResource) -> CoordinatorResult<()> {
+        match dropped {
+            DroppedResource::Database {
+                tenant, database, ..
+            } => {
+                self.series_quota.forget(tenant, database, None);
+                self.purge_dropped_db(tenant, database).await
+            }
+            DroppedResource::Table {
+                tenant,
+                database,
+                table,
+            } => {
+                // The data of the table is dropped by the raft groups of the
+                // database, which are caught up by the nodes down.
+                self.series_quota.forget(tenant, database, Some(table));
+                Ok(())
+            }
+        }
+    }
+
+    async fn sweep_dropped_dbs(&self) {
+        if let Err(err) = self.try_sweep_dropped_dbs().await {
+            warn!("sweep dropped databases failed: {}", err);
+        }
+    }
+
+    /// Purge the dropped databases left on this node, whose events are missed
+    /// while this node was down or lagged, and forget the databases dropped
+    /// longer than `dropped_db_retention` ago.
+    async fn try_sweep_dropped_dbs(&self) -> CoordinatorResult<()> {
+        let retention =
+            i64::try_from(self.config.global.dropped_db_retention.as_nanos()).unwrap_or(i64::MAX);
+        let now = models::utils::now_timestamp_nanos();
+        for tenant in self.meta.tenants().await.context(MetaSnafu)? {
+            let Some(meta_client) = self.meta.tenant_meta(tenant.name()).await else {
+                continue;
+            };
+            for (db, drop_time) in meta_client.dropped_dbs() {
+                self.series_quota.forget(tenant.name(), &db, None);
+                if let Err(err) = self.purge_dropped_db(tenant.name(), &db).await {
+                    warn!("purge dropped database {}.{}: {}", tenant.name(), db, err);
+                    continue;
+                }
+                if now.saturating_sub(drop_time) > retention {
+                    meta_client
+                        .remove_dropped_db(&db, drop_time)
+                        .await
+                        .context(MetaSnafu)?;
+                }
+            }
+        }
+
+        // The dropped databases of a dropped tenant are removed with the tenant.
+        for (tenant, db) in self.raft_manager.local_databases()? {
+            let exists = self
+                .meta
+                .tenant_for_special(&tenant)
+                .await
+                .context(MetaSnafu)?
+                .is_some();
+            if !exists {
+                self.series_quota.forget(&tenant, &db, None);
+                self.purge_dropped_db(&tenant, &db).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close the vnodes and purge the caches of the dropped database left on
+    /// this node, the database may have been recreated with the same name.
+    async fn purge_dropped_db(&self, tenant: &str, database: &str) -> CoordinatorResult<()> {
+        let db_info = match self.meta.tenant_meta(tenant).await {
+            Some(meta_client) => meta_client.get_db_info(database).context(MetaSnafu)?,
+            None => None,
+        };
+        let keep = db_info
+            .iter()
+            .flat_map(|info| info.buckets.iter())
+            .flat_map(|bucket| bucket.shard_group.iter())
+            .map(|replica| replica.id)
+            .collect::<HashSet<_>>();
+
+        self.raft_manager
+            .drop_db_raft_nodes(tenant, database, &keep)
+            .await?;
+
+        if let (Some(kv_inst), None) = (&self.kv_inst, &db_info) {
+            kv_inst
+                .remove_database(tenant, database)
+                .await
+                .context(TskvSnafu)?;
+        }
+
+        Ok(())
+    }
+
     async fn pre_create_bucket_service(coord: Arc<CoordService>) {
         loop {
             let interval = 5 * 60;
@@ -272,7 +541,14 @@ impl CoordService {
 
             let lines = lines_buffer.iter().map(|l| l.to_line()).collect::<Vec<_>>();
             if let Err(e) = coord
-                .write_lines(DEFAULT_CATALOG, USAGE_SCHEMA, Precision::NS, lines, None)
+                .write_lines(
+                    DEFAULT_CATALOG,
+                    USAGE_SCHEMA,
+                    Precision::NS,
+                    WriteConsistency::Quorum,
+                    lines,
+                    None,
+                )
                 .await
             {
                 error!("write metrics to {DEFAULT_CATALOG} fail. {e}")
@@ -304,7 +580,7 @@ impl CoordService {
         Ok(())
     }
 
-    async fn prune_shards(
+    pub(crate) async fn prune_shards(
         &self,
         tenant: &str,
         database: &str,
@@ -366,6 +642,7 @@ impl CoordService {
         tenant: &'a str,
         db: &'a str,
         precision: Precision,
+        consistency: WriteConsistency,
         info: ReplicationSet,
         points: Arc<Vec<u8>>,
         span_ctx: Option<&'a SpanContext>,
@@ -373,14 +650,8 @@ impl CoordService {
         {
             let _span = Span::from_context("limit check", span_ctx);
 
-            let limiter = self.meta.limiter(tenant).await.context(MetaSnafu)?;
             let write_size = points.len();
-
-            limiter.check_coord_writes().await.context(MetaSnafu)?;
-            limiter
-                .check_coord_data_in(write_size)
-                .await
-                .context(MetaSnafu)?;
+            self.check_write_limit(tenant, write_size).await?;
 
             self.metrics.coord_writes(tenant, db).inc_one();
             self.metrics
@@ -404,7 +675,7 @@ impl CoordService {
             replica_id: info.id,
             db_name: db.to_string(),
             tenant: tenant.to_string(),
-
+            consistency: consistency as i32,
             command: Some(raft_write_command::Command::WriteData(request)),
         };
 
@@ -414,11 +685,141 @@ impl CoordService {
         Ok(requests)
     }
 
+    async fn check_write_limit(&self, tenant: &str, write_size: usize) -> CoordinatorResult<()> {
+        let limiter = self.meta.limiter(tenant).await.context(MetaSnafu)?;
+        limiter.check_coord_writes().await.context(MetaSnafu)?;
+        limiter
+            .check_coord_data_in(write_size)
+            .await
+            .context(MetaSnafu)?;
+
+        Ok(())
+    }
+
+    /// Check the database to be written, returns the tenant meta and the precision
+    /// of the database.
+    async fn write_db_meta(
+        &self,
+        tenant: &str,
+        db: &str,
+    ) -> CoordinatorResult<(MetaClientRef, Precision)> {
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_schema = meta_client
+            .get_db_schema(db)
+            .context(MetaSnafu)?
+            .ok_or_else(|| db_not_found_error(&meta_client, db))
+            .context(MetaSnafu)?;
+        if db_schema.is_hidden() {
+            return Err(CoordinatorError::Meta {
+                source: MetaError::DatabaseNotFound {
+                    database: db.to_string(),
+                },
+            });
+        }
+
+        let db_precision = *db_schema.config.precision();
+        Ok((meta_client, db_precision))
+    }
+
+    /// Fill the default fields of the line and encrypt the encrypted fields, then put
+    /// it into the lines of the replication set it belongs to, returns the id of the
+    /// replication set.
+    #[allow(clippy::too_many_arguments)]
+    async fn route_line<'a>(
+        &self,
+        meta_client: &MetaClientRef,
+        db: &str,
+        precision: Precision,
+        db_precision: Precision,
+        table_fields: &mut HashMap<String, TableWriteFields>,
+        map_lines: &mut HashMap<ReplicationSetId, VnodeLines<'a>>,
+        mut line: Line<'a>,
+    ) -> CoordinatorResult<ReplicationSetId> {
+        if !table_fields.contains_key(line.table.as_ref()) {
+            let fields = match meta_client
+                .get_tskv_table_schema(db, &line.table)
+                .context(MetaSnafu)?
+            {
+                Some(schema) => TableWriteFields::new(meta_client, &schema)?,
+                None => TableWriteFields::default(),
+            };
+            table_fields.insert(line.table.to_string(), fields);
+        }
+        if let Some(fields) = table_fields.get(line.table.as_ref()) {
+            line.fill_default_fields(&fields.defaults);
+            fields.encrypt_line(&mut line)?;
+        }
+
+        let ts = timestamp_convert(precision, db_precision, line.timestamp).ok_or_else(|| {
+            CommonSnafu {
+                msg: "timestamp overflow".to_string(),
+            }
+            .build()
+        })?;
+        let info = meta_client
+            .locate_replication_set_for_write(db, line.hash_id, ts)
+            .await
+            .context(MetaSnafu)?;
+        let replica_id = info.id;
+        let lines_entry = map_lines
+            .entry(replica_id)
+            .or_insert_with(|| VnodeLines::new(info));
+        lines_entry.add_line(line);
+
+        Ok(replica_id)
+    }
+
+    /// Write the lines of a replication set and wait for the result, returns the
+    /// number of bytes written.
+    async fn flush_vnode_lines(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        consistency: WriteConsistency,
+        lines: VnodeLines<'_>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        let points = vnode_lines_to_points(db, &lines)?;
+        let write_bytes = points.len();
+        let requests = self
+            .push_points_to_requests(
+                tenant,
+                db,
+                precision,
+                consistency,
+                lines.info,
+                points,
+                span_ctx,
+            )
+            .await?;
+        for res in futures::future::join_all(requests).await {
+            res?
+        }
+
+        Ok(write_bytes)
+    }
+
+    fn check_clock_skew(&self) -> CoordinatorResult<()> {
+        if self.config.meta.reject_write_on_clock_skew && self.meta.clock_skew_exceeded() {
+            return Err(CoordinatorError::ClockSkewExceeded {
+                node_id: self.node_id,
+                offset_ms: self.meta.clock_offset() / 1_000_000,
+            });
+        }
+
+        Ok(())
+    }
+
     async fn admin_command_on_leader(
         &self,
-        replica: ReplicationSet,
+        replica: &ReplicationSet,
         request: AdminCommand,
-    ) -> CoordinatorResult<()> {
+    ) -> CoordinatorResult<Vec<u8>> {
         let tenant = request.tenant.clone();
         let caller = TskvAdminRequest {
             request,
@@ -429,8 +830,7 @@ impl CoordService {
         let executor = TskvLeaderExecutor {
             meta: self.meta.clone(),
         };
-        executor.do_request(&tenant, &replica, &caller).await?;
-        Ok(())
+        executor.do_request(&tenant, replica, &caller).await
     }
 
     pub async fn admin_command_on_node(
@@ -448,6 +848,40 @@ impl CoordService {
         caller.do_request(node_id).await
     }
 
+    /// Open the `ExportVnodeData` stream of the vnode on the node, the first
+    /// response is read so that a replica failing right away can be skipped.
+    pub(crate) async fn export_vnode_data_on_node(
+        &self,
+        node_id: NodeId,
+        request: ExportVnodeDataRequest,
+    ) -> CoordinatorResult<ReplicaDataStream> {
+        let channel = self.meta.get_node_conn(node_id).await.map_err(|error| {
+            CoordinatorError::PreExecution {
+                error: error.to_string(),
+            }
+        })?;
+        let mut client = tskv_service_time_out_client(
+            channel,
+            Duration::from_secs(3600),
+            DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+            self.config.service.grpc_enable_gzip,
+        );
+        let mut stream = client
+            .export_vnode_data(tonic::Request::new(request))
+            .await?
+            .into_inner();
+        let first = stream.message().await?;
+
+        let decode = |resp: ExportVnodeDataResponse| {
+            record_batch_decode(&resp.data)
+                .map(|batch| (resp.table, batch))
+                .context(ArrowSnafu)
+        };
+        let first = futures::stream::iter(first.map(decode));
+        let rest = stream.map(move |resp| decode(resp?));
+        Ok(Box::pin(first.chain(rest)))
+    }
+
     async fn check_remove_vnode_and_promote(
         &self,
         tenant: &str,
@@ -458,62 +892,91 @@ impl CoordService {
         let meta = self.meta.clone();
         let replica_set = crate::get_replica_by_meta(meta, tenant, db_name, replica_id).await?;
         if replica_set.leader_vnode_id == vnode_id {
-            let mut tmp_vnodes = replica_set.vnodes.clone();
-            tmp_vnodes.retain(|x| x.id != vnode_id);
-            if tmp_vnodes.is_empty() {
+            let Some(new_leader) = replica_set
+                .voters()
+                .find(|x| x.id != vnode_id)
+                .map(|x| x.id)
+            else {
                 return Err(CoordinatorError::ReplicaCannotRemove { replica_id });
-            }
+            };
 
-            let new_leader = tmp_vnodes[0].id;
             let cmd_type = ReplicationCmdType::PromoteLeader(replica_id, new_leader);
             self.replication_manager(tenant, cmd_type).await?;
         }
 
         Ok(())
     }
-}
-
-//***************************** Coordinator Interface ***************************************** */
-#[async_trait::async_trait]
-impl Coordinator for CoordService {
-    fn node_id(&self) -> u64 {
-        self.node_id
-    }
-
-    fn meta_manager(&self) -> MetaRef {
-        self.meta.clone()
-    }
 
-    fn store_engine(&self) -> Option<EngineRef> {
-        self.kv_inst.clone()
-    }
+    /// Transfer the leadership of the replica to `new_leader` on the current leader,
+    /// then record the new leader in meta so requests are routed to it right away.
+    /// Returns the raft entries `new_leader` lagged behind before the transfer.
+    async fn promote_leader(
+        &self,
+        tenant: &str,
+        replica: &ReplicaAllInfo,
+        new_leader: VnodeId,
+        max_log_lag: Option<u64>,
+    ) -> CoordinatorResult<u64> {
+        let replica_id = replica.replica_set.id;
+        if replica.replica_set.leader_vnode_id == new_leader {
+            return Ok(0);
+        }
+        match replica.replica_set.vnode(new_leader) {
+            Some(vnode) if vnode.learner => {
+                return Err(CoordinatorError::LeaderTransferRejected {
+                    replica_id,
+                    vnode_id: new_leader,
+                    reason: "a learner can not be the leader".to_string(),
+                });
+            }
+            Some(_) => {}
+            None => {
+                return Err(RaftNodeNotFoundSnafu {
+                    vnode_id: new_leader,
+                    replica_id,
+                }
+                .build());
+            }
+        }
 
-    fn raft_manager(&self) -> Arc<RaftNodesManager> {
-        self.raft_manager.clone()
-    }
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(PromoteLeader(PromoteLeaderRequest {
+                replica_id,
+                new_leader_id: new_leader,
+                db_name: replica.db_name.clone(),
+                max_log_lag,
+            })),
+        };
+        let data = self
+            .admin_command_on_leader(&replica.replica_set, request)
+            .await?;
+        let log_lag = data
+            .as_slice()
+            .try_into()
+            .map(u64::from_be_bytes)
+            .unwrap_or_default();
 
-    async fn tenant_meta(&self, tenant: &str) -> Option<MetaClientRef> {
-        self.meta.tenant_meta(tenant).await
-    }
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        meta_client
+            .replica_new_leader(new_leader)
+            .await
+            .context(MetaSnafu)?;
 
-    fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter {
-        TskvRaftWriter::new(
-            self.meta.clone(),
-            self.node_id,
-            self.config.query.write_timeout,
-            self.config.service.grpc_enable_gzip,
-            self.config.deployment.memory * 1024 * 1024 * 1024,
-            self.memory_pool.clone(),
-            self.raft_manager.clone(),
-            request,
-            self.writer_count.clone(),
-        )
+        Ok(log_lag)
     }
 
-    async fn table_vnodes(
+    /// Get the vnodes of a table to read, the leader is preferred unless a
+    /// follower is fresh enough for the `read`.
+    async fn select_table_vnodes(
         &self,
         table: &ResolvedTable,
         predicate: ResolvedPredicateRef,
+        read: ReplicaRead,
     ) -> CoordinatorResult<Vec<ReplicationSet>> {
         // 1. 根据传入的过滤条件获取表的分片信息（包括副本）
         let mut replica_sets = self
@@ -532,13 +995,40 @@ impl Coordinator for CoordService {
                     0
                 } else {
                     match vnode.status {
+                        VnodeStatus::Running if vnode.learner => 2,
                         VnodeStatus::Running => 1,
-                        VnodeStatus::Copying => 2,
+                        VnodeStatus::Copying => 3,
                         VnodeStatus::Broken => i32::MAX,
                     }
                 }
             });
+        }
+
+        match &read {
+            ReplicaRead::Leader => {}
+            ReplicaRead::WithinLag(vnodes_applied, max_replica_lag) => {
+                // The vnodes applied are in the clock of meta leader.
+                let now = now_timestamp_nanos() + self.meta.clock_offset();
+                let futures = replica_sets.iter_mut().map(|replica_set| {
+                    self.prefer_fresh_follower(
+                        table.tenant(),
+                        replica_set,
+                        vnodes_applied,
+                        *max_replica_lag,
+                        now,
+                    )
+                });
+                futures::future::join_all(futures).await;
+            }
+            ReplicaRead::FollowerRead => {
+                let futures = replica_sets.iter_mut().map(|replica_set| {
+                    self.prefer_caught_up_follower(table.tenant(), table.database(), replica_set)
+                });
+                futures::future::join_all(futures).await;
+            }
+        }
 
+        for replica_set in replica_sets.iter_mut() {
             replica_set
                 .vnodes
                 .retain(|e| e.status != VnodeStatus::Broken);
@@ -549,84 +1039,299 @@ impl Coordinator for CoordService {
         Ok(replica_sets)
     }
 
-    async fn write_replica_by_raft(
+    /// The ReadIndex of the leader of the replica set, which confirms the
+    /// leadership by raft. The leader in meta is updated if it's changed.
+    pub(crate) async fn leader_read_index(
         &self,
-        replica: ReplicationSet,
-        request: RaftWriteCommand,
-        _span_ctx: Option<&SpanContext>,
-    ) -> CoordinatorResult<()> {
-        let tenant = request.tenant.clone();
-        let writer = self.tskv_raft_writer(request);
+        tenant: &str,
+        db_name: &str,
+        replica_set: &ReplicationSet,
+        timeout: Duration,
+    ) -> CoordinatorResult<u64> {
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(ReadIndex(ReadIndexRequest {
+                db_name: db_name.to_string(),
+                replica_id: replica_set.id,
+            })),
+        };
+        let caller = TskvAdminRequest {
+            request,
+            meta: self.meta.clone(),
+            timeout,
+            enable_gzip: self.config.service.grpc_enable_gzip,
+        };
         let executor = TskvLeaderExecutor {
             meta: self.meta.clone(),
         };
+        let data = executor.do_request(tenant, replica_set, &caller).await?;
+        let bytes = data.as_slice().try_into().map_err(|_| {
+            CommonSnafu {
+                msg: format!("invalid read index of {} bytes", data.len()),
+            }
+            .build()
+        })?;
 
-        executor.do_request(&tenant, &replica, &writer).await?;
-
-        Ok(())
+        Ok(u64::from_be_bytes(bytes))
     }
 
-    async fn write_lines<'a>(
+    /// Move a follower to the front of the replica set once it applies the
+    /// ReadIndex of the leader, the leader is read if the follower does not
+    /// catch up within `follower_read_timeout` or anything fails.
+    async fn prefer_caught_up_follower(
         &self,
         tenant: &str,
-        db: &str,
-        precision: Precision,
-        lines: Vec<Line<'a>>,
-        span_ctx: Option<&SpanContext>,
-    ) -> CoordinatorResult<usize> {
-        let pre_write_start = std::time::Instant::now();
-        let mut write_bytes: usize = 0;
-        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
-            CoordinatorError::TenantNotFound {
-                name: tenant.to_string(),
-            }
-        })?;
-        let mut map_lines: HashMap<ReplicationSetId, VnodeLines> = HashMap::new();
-        let db_schema = meta_client
-            .get_db_schema(db)
-            .context(MetaSnafu)?
-            .ok_or_else(|| MetaError::DatabaseNotFound {
-                database: db.to_string(),
-            })
-            .context(MetaSnafu)?;
-        if db_schema.is_hidden() {
-            return Err(CoordinatorError::Meta {
-                source: MetaError::DatabaseNotFound {
-                    database: db.to_string(),
-                },
-            });
+        db_name: &str,
+        replica_set: &mut ReplicationSet,
+    ) {
+        let Some(follower) = pick_follower(replica_set).cloned() else {
+            return;
+        };
+
+        let timeout = self.config.query.follower_read_timeout;
+        let index = match self
+            .leader_read_index(tenant, db_name, replica_set, timeout)
+            .await
+        {
+            Ok(index) => index,
+            Err(e) => {
+                debug!(
+                    "Failed to get the read index of replica {}: {}",
+                    replica_set.id, e
+                );
+                return;
+            }
+        };
+
+        if self
+            .follower_applied(tenant, replica_set.id, &follower, index)
+            .await
+        {
+            move_to_front(replica_set, follower.id);
         }
+    }
 
-        let db_precision = db_schema.config.precision();
+    /// Move a follower whose data is stale within `max_replica_lag` to the front
+    /// of the replica set. The lag is estimated by the applied raft logs reported
+    /// to meta, so the follower must confirm that it still has applied the logs.
+    async fn prefer_fresh_follower(
+        &self,
+        tenant: &str,
+        replica_set: &mut ReplicationSet,
+        vnodes_applied: &HashMap<VnodeId, VnodeApplied>,
+        max_replica_lag: Duration,
+        now: i64,
+    ) {
+        let Some((follower, index)) =
+            pick_fresh_follower(replica_set, vnodes_applied, max_replica_lag, now)
+        else {
+            return;
+        };
+
+        if self
+            .follower_applied(tenant, replica_set.id, &follower, index)
+            .await
+        {
+            move_to_front(replica_set, follower.id);
+        }
+    }
+
+    /// Whether the follower has applied the raft log of the index, it waits for
+    /// the log within `follower_read_timeout`.
+    async fn follower_applied(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        follower: &VnodeInfo,
+        index: u64,
+    ) -> bool {
+        let timeout = self.config.query.follower_read_timeout;
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(WaitApplied(WaitAppliedRequest {
+                replica_id,
+                vnode_id: follower.id,
+                index,
+                timeout_ms: timeout.as_millis() as u64,
+            })),
+        };
+        let caller = TskvAdminRequest {
+            request,
+            meta: self.meta.clone(),
+            // The follower responds after the timeout of waiting.
+            timeout: timeout * 2,
+            enable_gzip: self.config.service.grpc_enable_gzip,
+        };
+        if let Err(e) = caller.do_request(follower.node_id).await {
+            debug!(
+                "Vnode {} did not apply the raft log {} of replica {}: {}",
+                follower.id, index, replica_id, e
+            );
+            return false;
+        }
+
+        true
+    }
+}
+
+/// How the vnodes of the replica sets are chosen to be read.
+enum ReplicaRead {
+    /// The leaders are read.
+    Leader,
+    /// The followers lagging behind the leader within the duration, by the
+    /// applied raft logs of the vnodes.
+    WithinLag(HashMap<VnodeId, VnodeApplied>, Duration),
+    /// The followers having applied the ReadIndex of the leader.
+    FollowerRead,
+}
+
+//***************************** Coordinator Interface ***************************************** */
+#[async_trait::async_trait]
+impl Coordinator for CoordService {
+    fn node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    fn meta_manager(&self) -> MetaRef {
+        self.meta.clone()
+    }
+
+    fn store_engine(&self) -> Option<EngineRef> {
+        self.kv_inst.clone()
+    }
+
+    fn raft_manager(&self) -> Arc<RaftNodesManager> {
+        self.raft_manager.clone()
+    }
+
+    async fn tenant_meta(&self, tenant: &str) -> Option<MetaClientRef> {
+        self.meta.tenant_meta(tenant).await
+    }
+
+    fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter {
+        TskvRaftWriter::new(
+            self.meta.clone(),
+            self.node_id,
+            self.config.query.write_timeout,
+            self.config.service.grpc_enable_gzip,
+            self.config.deployment.memory * 1024 * 1024 * 1024,
+            self.memory_pool.clone(),
+            self.raft_manager.clone(),
+            self.series_quota.clone(),
+            self.metrics
+                .write_memory_exhausted(&request.tenant, &request.db_name),
+            request,
+            self.writer_count.clone(),
+        )
+    }
+
+    async fn table_vnodes(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<ReplicationSet>> {
+        self.select_table_vnodes(table, predicate, ReplicaRead::Leader)
+            .await
+    }
+
+    async fn table_vnodes_within_lag(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+        max_replica_lag: Duration,
+    ) -> CoordinatorResult<Vec<ReplicationSet>> {
+        // The lag of the followers is unknown without the node metrics, read the leaders.
+        let vnodes_applied = match self.meta.vnodes_applied().await {
+            Ok(vnodes_applied) => vnodes_applied,
+            Err(e) => {
+                warn!("Failed to get the applied raft index of vnodes: {}", e);
+                HashMap::new()
+            }
+        };
+        self.select_table_vnodes(
+            table,
+            predicate,
+            ReplicaRead::WithinLag(vnodes_applied, max_replica_lag),
+        )
+        .await
+    }
+
+    async fn table_vnodes_follower_read(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<ReplicationSet>> {
+        self.select_table_vnodes(table, predicate, ReplicaRead::FollowerRead)
+            .await
+    }
+
+    async fn write_replica_by_raft(
+        &self,
+        replica: ReplicationSet,
+        request: RaftWriteCommand,
+        _span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<()> {
+        let tenant = request.tenant.clone();
+        let mut writer = self.tskv_raft_writer(request);
+        // The write_timeout of the tenant takes precedence over the config.
+        if let Some(meta_client) = self.meta.tenant_meta(&tenant).await {
+            if let Some(timeout) = meta_client.tenant().options().write_timeout() {
+                writer.timeout = timeout;
+            }
+        }
+        let executor = TskvLeaderExecutor {
+            meta: self.meta.clone(),
+        };
+
+        executor.do_request(&tenant, &replica, &writer).await?;
+
+        Ok(())
+    }
+
+    async fn write_lines<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        consistency: WriteConsistency,
+        lines: Vec<Line<'a>>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        self.check_clock_skew()?;
+
+        let pre_write_start = std::time::Instant::now();
+        let mut write_bytes: usize = 0;
+        let (meta_client, db_precision) = self.write_db_meta(tenant, db).await?;
+        let mut map_lines: HashMap<ReplicationSetId, VnodeLines> = HashMap::new();
+        let mut table_fields: HashMap<String, TableWriteFields> = HashMap::new();
         for line in lines {
-            let ts =
-                timestamp_convert(precision, *db_precision, line.timestamp).ok_or_else(|| {
-                    CommonSnafu {
-                        msg: "timestamp overflow".to_string(),
-                    }
-                    .build()
-                })?;
-            let info = meta_client
-                .locate_replication_set_for_write(db, line.hash_id, ts)
-                .await
-                .context(MetaSnafu)?;
-            let lines_entry = map_lines.entry(info.id).or_insert(VnodeLines::new(info));
-            lines_entry.add_line(line)
+            self.route_line(
+                &meta_client,
+                db,
+                precision,
+                db_precision,
+                &mut table_fields,
+                &mut map_lines,
+                line,
+            )
+            .await?;
         }
 
         let mut requests = Vec::new();
         for lines in map_lines.into_values() {
-            let batches = line_to_batches(&lines.lines).map_err(|e| {
-                CommonSnafu {
-                    msg: format!("line to batch error: {}", e),
-                }
-                .build()
-            })?;
-            let points = Arc::new(mutable_batches_to_point(db, batches));
+            let points = vnode_lines_to_points(db, &lines)?;
             write_bytes += points.len();
             requests.extend(
-                self.push_points_to_requests(tenant, db, precision, lines.info, points, span_ctx)
-                    .await?,
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    consistency,
+                    lines.info,
+                    points,
+                    span_ctx,
+                )
+                .await?,
             );
         }
 
@@ -651,16 +1356,162 @@ impl Coordinator for CoordService {
         Ok(write_bytes)
     }
 
+    async fn write_lines_stream<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        consistency: WriteConsistency,
+        mut lines: LineBatchStream<'a>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        self.check_clock_skew()?;
+
+        let mut write_bytes: usize = 0;
+        let (meta_client, db_precision) = self.write_db_meta(tenant, db).await?;
+        let mut map_lines: HashMap<ReplicationSetId, VnodeLines> = HashMap::new();
+        let mut table_fields: HashMap<String, TableWriteFields> = HashMap::new();
+        while let Some(batch) = lines.next().await {
+            for line in batch? {
+                let replica_id = self
+                    .route_line(
+                        &meta_client,
+                        db,
+                        precision,
+                        db_precision,
+                        &mut table_fields,
+                        &mut map_lines,
+                        line,
+                    )
+                    .await?;
+
+                // Flush lines of the replication set as soon as they are large enough.
+                if let Some(lines) =
+                    take_full_vnode_lines(&mut map_lines, replica_id, STREAM_WRITE_FLUSH_SIZE)
+                {
+                    write_bytes += self
+                        .flush_vnode_lines(tenant, db, precision, consistency, lines, span_ctx)
+                        .await?;
+                }
+            }
+        }
+
+        for lines in map_lines.into_values() {
+            write_bytes += self
+                .flush_vnode_lines(tenant, db, precision, consistency, lines, span_ctx)
+                .await?;
+        }
+
+        Ok(write_bytes)
+    }
+
+    async fn dry_run_write_lines<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        lines: Vec<Line<'a>>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<DryRunReport> {
+        self.check_clock_skew()?;
+
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_schema = meta_client
+            .get_db_schema(db)
+            .context(MetaSnafu)?
+            .filter(|schema| !schema.is_hidden())
+            .ok_or_else(|| db_not_found_error(&meta_client, db))
+            .context(MetaSnafu)?;
+
+        let mut report = DryRunReport {
+            lines: lines.len(),
+            ..Default::default()
+        };
+        let db_precision = db_schema.config.precision();
+        let mut table_schemas: HashMap<String, Option<TskvTableSchemaRef>> = HashMap::new();
+        let mut map_lines: HashMap<Option<ReplicationSetId>, (Option<ReplicationSet>, Vec<Line>)> =
+            HashMap::new();
+        for (idx, mut line) in lines.into_iter().enumerate() {
+            if !table_schemas.contains_key(line.table.as_ref()) {
+                let schema = meta_client
+                    .get_tskv_table_schema(db, &line.table)
+                    .context(MetaSnafu)?;
+                table_schemas.insert(line.table.to_string(), schema);
+            }
+            if let Some(Some(schema)) = table_schemas.get(line.table.as_ref()) {
+                match table_default_fields(schema) {
+                    Ok(defaults) => line.fill_default_fields(&defaults),
+                    Err(e) => {
+                        report
+                            .errors
+                            .push(format!("line {idx}: invalid column default value: {e}"));
+                        continue;
+                    }
+                }
+                if let Err(e) = check_line_schema(schema, &line) {
+                    report.errors.push(format!("line {idx}: {e}"));
+                    continue;
+                }
+            }
+
+            let Some(ts) = timestamp_convert(precision, *db_precision, line.timestamp) else {
+                report
+                    .errors
+                    .push(format!("line {idx}: timestamp overflow"));
+                continue;
+            };
+            let replica = meta_client.locate_existing_replication_set(db, line.hash_id, ts);
+            map_lines
+                .entry(replica.as_ref().map(|r| r.id))
+                .or_insert_with(|| (replica, vec![]))
+                .1
+                .push(line);
+        }
+
+        let _span = Span::from_context("dry run routing", span_ctx);
+        for (replica, lines) in map_lines.into_values() {
+            let batches = match line_to_batches(&lines) {
+                Ok(batches) => batches,
+                Err(e) => {
+                    report.errors.push(format!("line to batch error: {}", e));
+                    continue;
+                }
+            };
+            let mut tables = batches.keys().cloned().collect::<Vec<_>>();
+            tables.sort();
+            let bytes = mutable_batches_to_point(db, batches).len();
+            report.add_routing(ReplicaRouting {
+                replica,
+                tables,
+                lines: lines.len(),
+                bytes,
+            });
+        }
+
+        // The limits are checked once for the whole request.
+        let bytes = report.routings.iter().map(|r| r.bytes).sum();
+        if let Err(e) = self.check_write_limit(tenant, bytes).await {
+            report.reject(e.to_string());
+        }
+
+        Ok(report)
+    }
+
     async fn write_record_batch<'a>(
         &self,
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
         span_ctx: Option<&SpanContext>,
-    ) -> CoordinatorResult<usize> {
+    ) -> CoordinatorResult<RecordBatchWrite> {
+        self.check_clock_skew()?;
+
         let pre_write_start = std::time::Instant::now();
 
-        let mut write_bytes: usize = 0;
         let mut precision = Precision::NS;
         let tenant = table_schema.tenant.as_str();
         let db = table_schema.db.as_str();
@@ -670,7 +1521,16 @@ impl Coordinator for CoordService {
             }
         })?;
 
-        let mut repl_idx: HashMap<ReplicationSet, Vec<u32>> = HashMap::new();
+        let record_batch = unpack_record_batch(record_batch).map_err(|e| {
+            CommonSnafu {
+                msg: format!("unpack record batch error: {}", e),
+            }
+            .build()
+        })?;
+        let record_batch = encrypt_record_batch(&meta_client, &table_schema, record_batch)?;
+
+        // <replication set, (indices of the rows, time range of the rows)>
+        let mut repl_idx: HashMap<ReplicationSet, (Vec<u32>, TimeRange)> = HashMap::new();
         let schema = record_batch.schema().fields.clone();
         let table_name = table_schema.name.as_str();
         let columns = record_batch.columns();
@@ -742,11 +1602,17 @@ impl Coordinator for CoordService {
                 .locate_replication_set_for_write(db, hash, ts)
                 .await
                 .context(MetaSnafu)?;
-            repl_idx.entry(info).or_default().push(idx as u32);
+            let (idxs, time_range) = repl_idx
+                .entry(info)
+                .or_insert_with(|| (vec![], TimeRange::none()));
+            idxs.push(idx as u32);
+            time_range.merge(&TimeRange::new(ts, ts));
         }
 
-        let mut requests = Vec::new();
-        for (repl, idxs) in repl_idx {
+        let mut requests = Vec::with_capacity(repl_idx.len());
+        // <replica id, rows, bytes, time range> of each request.
+        let mut replica_writes = Vec::with_capacity(repl_idx.len());
+        for (repl, (idxs, time_range)) in repl_idx {
             let indices = UInt32Array::from(idxs);
             let columns = record_batch
                 .columns()
@@ -770,31 +1636,55 @@ impl Coordinator for CoordService {
                         .build()
                     })?,
             );
-            write_bytes += points.len();
-            requests.extend(
-                self.push_points_to_requests(tenant, db, precision, repl, points, span_ctx)
-                    .await?,
-            );
+            replica_writes.push((repl.id, indices.len(), points.len(), time_range));
+            let repl_requests = self
+                .push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    WriteConsistency::Quorum,
+                    repl,
+                    points,
+                    span_ctx,
+                )
+                .await?;
+            requests.push(futures::future::try_join_all(repl_requests));
         }
         self.metrics
             .write_lines_prepare(tenant, db)
             .add(pre_write_start.elapsed().as_millis() as u64);
 
         let now = tokio::time::Instant::now();
-        for res in futures::future::join_all(requests).await {
+        let mut written = RecordBatchWrite::default();
+        let mut write_err = None;
+        let results = futures::future::join_all(requests).await;
+        for ((replica_id, rows, bytes, time_range), res) in replica_writes.into_iter().zip(results)
+        {
             debug!(
                 "Parallel write points on vnode over, start at: {:?}, elapsed: {} millis, result: {:?}",
                 now,
                 now.elapsed().as_millis(),
                 res
             );
-            res?
+            match res {
+                Ok(_) => written.add_replica(replica_id, rows, bytes, &time_range),
+                Err(e) => {
+                    write_err.get_or_insert(e);
+                }
+            }
         }
         self.metrics
             .write_replica_duration(tenant, db)
             .add(now.elapsed().as_millis() as u64);
 
-        Ok(write_bytes)
+        match write_err {
+            None => Ok(written),
+            Some(e) if written.replica_rows.is_empty() => Err(e),
+            Some(e) => Err(CoordinatorError::PartialWrite {
+                written,
+                source: Box::new(e),
+            }),
+        }
     }
 
     fn table_scan(
@@ -811,6 +1701,8 @@ impl Coordinator for CoordService {
             self.meta.clone(),
             span_ctx,
             self.config.service.grpc_enable_gzip,
+            self.config.service.grpc_enable_checksum,
+            self.metrics.clone(),
         );
 
         Ok(Box::pin(CheckedCoordinatorRecordBatchStream::new(
@@ -835,6 +1727,7 @@ impl Coordinator for CoordService {
             self.meta.clone(),
             span_ctx,
             self.config.service.grpc_enable_gzip,
+            self.config.service.grpc_enable_checksum,
         );
 
         Ok(Box::pin(CheckedCoordinatorRecordBatchStream::new(
@@ -846,163 +1739,703 @@ impl Coordinator for CoordService {
         )))
     }
 
-    async fn delete_from_table(
+    async fn delete_from_tables(
         &self,
-        table: &ResolvedTable,
-        predicate: &ResolvedPredicate,
+        deletes: &[(ResolvedTable, ResolvedPredicate)],
     ) -> CoordinatorResult<()> {
-        let replicas = self
-            .prune_shards(
-                table.tenant(),
-                table.database(),
-                predicate.time_ranges().as_ref(),
-            )
-            .await?;
+        let mut db_deletes: HashMap<(&str, &str), Vec<&(ResolvedTable, ResolvedPredicate)>> =
+            HashMap::new();
+        for delete in deletes {
+            db_deletes
+                .entry((delete.0.tenant(), delete.0.database()))
+                .or_default()
+                .push(delete);
+        }
 
         let now = tokio::time::Instant::now();
         let mut requests = vec![];
-        let predicate_bytes = bincode::serialize(predicate).context(BincodeSerdeSnafu)?;
-        for replica in replicas.iter() {
-            let request = DeleteFromTableRequest {
-                tenant: table.tenant().to_string(),
-                database: table.database().to_string(),
-                table: table.table().to_string(),
-                predicate: predicate_bytes.clone(),
-                vnode_id: 0,
+        for ((tenant, database), deletes) in db_deletes {
+            let meta = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+                CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                }
+            })?;
+            let min_ts = deletes
+                .iter()
+                .map(|(_, predicate)| predicate.time_ranges().min_ts())
+                .min()
+                .unwrap_or(i64::MIN);
+            let max_ts = deletes
+                .iter()
+                .map(|(_, predicate)| predicate.time_ranges().max_ts())
+                .max()
+                .unwrap_or(i64::MAX);
+            let buckets = meta
+                .mapping_bucket(database, min_ts, max_ts)
+                .context(MetaSnafu)?;
+
+            for (replica, command) in delete_requests(tenant, database, &buckets, &deletes)? {
+                requests.push(self.write_replica_by_raft(replica, command, None));
+            }
+        }
+
+        for result in futures::future::join_all(requests).await {
+            debug!(
+                "exec delete from {} tables, now:{now:?}, elapsed:{}ms, result:{result:?}",
+                deletes.len(),
+                now.elapsed().as_millis()
+            );
+            result?
+        }
+
+        Ok(())
+    }
+
+    async fn replication_manager(
+        &self,
+        tenant: &str,
+        cmd_type: ReplicationCmdType,
+    ) -> CoordinatorResult<()> {
+        let (request, replica) = match cmd_type {
+            ReplicationCmdType::AddRaftFollower(replica_id, node_id)
+            | ReplicationCmdType::AddRaftLearner(replica_id, node_id) => {
+                let learner = matches!(cmd_type, ReplicationCmdType::AddRaftLearner(..));
+                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+                if replica.replica_set.by_node_id(node_id).is_some() {
+                    return Err(CommonSnafu {
+                        msg: format!("A Replication Already in {}", node_id),
+                    }
+                    .build());
+                }
+                (
+                    AdminCommand {
+                        tenant: tenant.to_string(),
+                        command: Some(AddRaftFollower(AddRaftFollowerRequest {
+                            db_name: replica.db_name,
+                            replica_id: replica.replica_set.id,
+                            follower_nid: node_id,
+                            learner,
+                        })),
+                    },
+                    replica.replica_set,
+                )
+            }
+
+            ReplicationCmdType::RemoveRaftNode(vnode_id) => {
+                let all_info = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+                let replica_id = all_info.repl_set_id;
+                self.check_remove_vnode_and_promote(
+                    tenant,
+                    &all_info.db_name,
+                    replica_id,
+                    vnode_id,
+                )
+                .await?;
+
+                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+                (
+                    AdminCommand {
+                        tenant: tenant.to_string(),
+                        command: Some(RemoveRaftNode(RemoveRaftNodeRequest {
+                            vnode_id,
+                            replica_id,
+                            db_name: all_info.db_name,
+                        })),
+                    },
+                    replica.replica_set,
+                )
+            }
+
+            ReplicationCmdType::DestoryRaftGroup(replica_id) => {
+                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+                (
+                    AdminCommand {
+                        tenant: tenant.to_string(),
+                        command: Some(DestoryRaftGroup(DestoryRaftGroupRequest {
+                            replica_id,
+                            db_name: replica.db_name.clone(),
+                        })),
+                    },
+                    replica.replica_set,
+                )
+            }
+
+            ReplicationCmdType::PromoteLeader(replica_id, new_leader) => {
+                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+                return self
+                    .promote_leader(tenant, &replica, new_leader, None)
+                    .await
+                    .map(|_| ());
+            }
+        };
+
+        self.admin_command_on_leader(&replica, request).await?;
+        Ok(())
+    }
+
+    async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()> {
+        // Group vnode ids by node id.
+        let mut node_vnode_ids_map: HashMap<u64, Vec<u32>> = HashMap::new();
+        for vnode_id in vnode_ids.iter() {
+            let vnode = get_vnode_all_info(self.meta.clone(), tenant, *vnode_id).await?;
+            node_vnode_ids_map
+                .entry(vnode.node_id)
+                .or_default()
+                .push(*vnode_id);
+        }
+        let nodes = self.meta.data_nodes().await;
+
+        // Send grouped vnode ids to nodes.
+        let mut req_futures = vec![];
+        for node in nodes {
+            if let Some(vnode_ids) = node_vnode_ids_map.remove(&node.id) {
+                let cmd = AdminCommand {
+                    tenant: tenant.to_string(),
+                    command: Some(CompactVnode(CompactVnodeRequest { vnode_ids })),
+                };
+                req_futures.push(self.admin_command_on_node(node.id, cmd));
+            }
+        }
+
+        for res in futures::future::join_all(req_futures).await {
+            res?;
+        }
+
+        return Ok(());
+    }
+
+    async fn close_vnodes_files(
+        &self,
+        tenant: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> CoordinatorResult<Vec<(VnodeId, u64)>> {
+        // Group vnode ids by node id.
+        let mut node_vnode_ids_map: HashMap<NodeId, Vec<VnodeId>> = HashMap::new();
+        for vnode_id in vnode_ids.iter() {
+            let vnode = get_vnode_all_info(self.meta.clone(), tenant, *vnode_id).await?;
+            node_vnode_ids_map
+                .entry(vnode.node_id)
+                .or_default()
+                .push(*vnode_id);
+        }
+
+        let req_futures = node_vnode_ids_map.into_iter().map(|(node_id, vnode_ids)| {
+            let cmd = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(CloseVnodesFiles(CloseVnodesFilesRequest { vnode_ids })),
+            };
+            self.admin_command_on_node(node_id, cmd)
+        });
+
+        let mut closed = vec![];
+        for res in futures::future::join_all(req_futures).await {
+            let data = res?;
+            closed.extend(
+                bincode::deserialize::<Vec<(VnodeId, u64)>>(&data).context(BincodeSerdeSnafu)?,
+            );
+        }
+
+        Ok(closed)
+    }
+
+    async fn explain_compaction(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+    ) -> CoordinatorResult<Vec<CompactionPlan>> {
+        let vnode = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+        let cmd = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(ExplainCompaction(ExplainCompactionRequest { vnode_id })),
+        };
+        let data = self.admin_command_on_node(vnode.node_id, cmd).await?;
+        bincode::deserialize(&data).context(BincodeSerdeSnafu)
+    }
+
+    async fn compaction_jobs(
+        &self,
+        tenant: &str,
+    ) -> CoordinatorResult<Vec<(NodeId, CompactionJobInfo)>> {
+        let nodes = self.meta.data_nodes().await;
+        let req_futures = nodes.iter().map(|node| {
+            let cmd = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchCompactions(FetchCompactionsRequest {})),
+            };
+            self.admin_command_on_node(node.id, cmd)
+        });
+
+        let mut jobs = vec![];
+        for (node, result) in nodes
+            .iter()
+            .zip(futures::future::join_all(req_futures).await)
+        {
+            let node_jobs = result.and_then(|data| {
+                bincode::deserialize::<Vec<CompactionJobInfo>>(&data).context(BincodeSerdeSnafu)
+            });
+            match node_jobs {
+                Ok(node_jobs) => jobs.extend(node_jobs.into_iter().map(|job| (node.id, job))),
+                Err(e) => warn!("Failed to fetch compactions of node {}: {}", node.id, e),
+            }
+        }
+        jobs.sort_by_key(|(node_id, job)| (*node_id, job.job_id));
+
+        Ok(jobs)
+    }
+
+    async fn kill_compaction(
+        &self,
+        tenant: &str,
+        node_id: Option<NodeId>,
+        job_id: u64,
+    ) -> CoordinatorResult<()> {
+        let node_id = match node_id {
+            Some(node_id) => node_id,
+            None => {
+                let node_ids = self
+                    .compaction_jobs(tenant)
+                    .await?
+                    .into_iter()
+                    .filter(|(_, job)| job.job_id == job_id)
+                    .map(|(node_id, _)| node_id)
+                    .collect::<Vec<_>>();
+                match node_ids.as_slice() {
+                    [] => return Err(CompactionJobNotFoundSnafu { job_id }.build()),
+                    [node_id] => *node_id,
+                    _ => return Err(AmbiguousCompactionJobSnafu { job_id, node_ids }.build()),
+                }
+            }
+        };
+
+        let cmd = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(KillCompaction(KillCompactionRequest { job_id })),
+        };
+        let data = self.admin_command_on_node(node_id, cmd).await?;
+        if data.first() != Some(&1) {
+            return Err(CompactionJobNotFoundSnafu { job_id }.build());
+        }
+        info!("Killed compaction job {job_id} on node {node_id}");
+
+        Ok(())
+    }
+
+    async fn recovery_progress(
+        &self,
+        tenant: &str,
+    ) -> CoordinatorResult<Vec<NodeRecoveryProgress>> {
+        let nodes = self.meta.data_nodes().await;
+        let req_futures = nodes.iter().map(|node| {
+            let cmd = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchRecoveryProgress(FetchRecoveryProgressRequest {})),
+            };
+            self.admin_command_on_node(node.id, cmd)
+        });
+
+        let mut progresses = vec![];
+        for (node, result) in nodes
+            .iter()
+            .zip(futures::future::join_all(req_futures).await)
+        {
+            let progress = result.and_then(|data| {
+                bincode::deserialize::<NodeRecoveryProgress>(&data).context(BincodeSerdeSnafu)
+            });
+            match progress {
+                Ok(progress) => progresses.push(progress),
+                Err(e) => warn!(
+                    "Failed to fetch recovery progress of node {}: {}",
+                    node.id, e
+                ),
+            }
+        }
+        progresses.sort_by_key(|p| p.node_id);
+
+        Ok(progresses)
+    }
+
+    async fn export_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        time_range: TimeRange,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<u64> {
+        let vnode = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_schema = meta_client
+            .get_db_schema(&vnode.db_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| MetaError::DatabaseNotFound {
+                database: vnode.db_name.clone(),
+            })
+            .context(MetaSnafu)?;
+        let precision = *db_schema.config.precision();
+
+        // Timestamps in tsm files are in the precision of database.
+        let convert = |ts| timestamp_convert(Precision::NS, precision, ts).unwrap_or(ts);
+        let cmd = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(ExportVnode(ExportVnodeRequest {
+                vnode_id,
+                min_ts: convert(time_range.min_ts),
+                max_ts: convert(time_range.max_ts),
+                path,
+                connection_options,
+            })),
+        };
+        let data = self.admin_command_on_node(vnode.node_id, cmd).await?;
+        let rows = data.try_into().map(u64::from_be_bytes).map_err(|_| {
+            CommonSnafu {
+                msg: "invalid response of export vnode".to_string(),
+            }
+            .build()
+        })?;
+
+        Ok(rows)
+    }
+
+    async fn export_replica_data(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        time_range: TimeRange,
+    ) -> CoordinatorResult<ReplicaDataStream> {
+        let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_schema = meta_client
+            .get_db_schema(&replica.db_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| MetaError::DatabaseNotFound {
+                database: replica.db_name.clone(),
+            })
+            .context(MetaSnafu)?;
+        let precision = *db_schema.config.precision();
+        let convert = |ts| timestamp_convert(Precision::NS, precision, ts).unwrap_or(ts);
+        let mut schemas = vec![];
+        for table in meta_client
+            .list_tables(&replica.db_name)
+            .context(MetaSnafu)?
+        {
+            if let Some(schema) = meta_client
+                .get_tskv_table_schema(&replica.db_name, &table)
+                .context(MetaSnafu)?
+            {
+                schemas.push(schema);
+            }
+        }
+
+        let mut vnodes = replica.replica_set.vnodes.clone();
+        let leader_vnode_id = replica.replica_set.leader_vnode_id;
+        vnodes.sort_by_key(|v| v.id != leader_vnode_id);
+        let requests = vnodes
+            .into_iter()
+            .map(|vnode| {
+                let request = ExportVnodeDataRequest {
+                    vnode_id: vnode.id,
+                    min_ts: convert(time_range.min_ts),
+                    max_ts: convert(time_range.max_ts),
+                    tenant: tenant.to_string(),
+                };
+                (vnode.node_id, request)
+            })
+            .collect();
+
+        Ok(replica_export::export_vnodes(
+            self.clone(),
+            requests,
+            schemas,
+        ))
+    }
+
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<VnodeBackup> {
+        let vnode = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+        let cmd = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(BackupVnode(BackupVnodeRequest {
+                vnode_id,
+                path,
+                connection_options,
+            })),
+        };
+        let data = self.admin_command_on_node(vnode.node_id, cmd).await?;
+        let backup = bincode::deserialize(&data).context(BincodeSerdeSnafu)?;
+
+        Ok(backup)
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        backup: VnodeBackup,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<()> {
+        let vnode = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+        let backup = bincode::serialize(&backup).context(BincodeSerdeSnafu)?;
+        let cmd = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(RestoreVnode(RestoreVnodeRequest {
+                db_name: vnode.db_name,
+                vnode_id,
+                path,
+                connection_options,
+                backup,
+            })),
+        };
+        self.admin_command_on_node(vnode.node_id, cmd).await?;
+
+        Ok(())
+    }
+
+    async fn series_cardinality_top(
+        &self,
+        tenant: &str,
+        db: &str,
+        top_k: usize,
+    ) -> CoordinatorResult<RecordBatch> {
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_info = meta_client
+            .get_db_info(db)
+            .context(MetaSnafu)?
+            .ok_or_else(|| {
+                MetaSnafu.into_error(MetaError::DatabaseNotFound {
+                    database: db.to_string(),
+                })
+            })?;
+
+        // The replicas of a replication set hold the same series, only the
+        // leader vnode of each replication set is counted.
+        let mut node_vnode_ids_map: HashMap<NodeId, Vec<VnodeId>> = HashMap::new();
+        for replica in db_info.buckets.iter().flat_map(|b| b.shard_group.iter()) {
+            node_vnode_ids_map
+                .entry(replica.leader_node_id)
+                .or_default()
+                .push(replica.leader_vnode_id);
+        }
+
+        let req_futures = node_vnode_ids_map.into_iter().map(|(node_id, vnode_ids)| {
+            let cmd = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchCardinality(FetchCardinalityRequest {
+                    db_name: db.to_string(),
+                    top_k: top_k as u32,
+                    vnode_ids,
+                })),
+            };
+            self.admin_command_on_node(node_id, cmd)
+        });
+
+        let mut record_batches = vec![];
+        for data in futures::future::try_join_all(req_futures).await? {
+            record_batches.push(record_batch_decode(&data).context(ArrowSnafu)?);
+        }
+
+        merge_cardinality_top_record_batches(&record_batches, top_k).context(ArrowSnafu)
+    }
+
+    async fn estimate_scan_cost(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<VnodeScanCost>> {
+        let encoded_predicate = predicate.encode().context(ModelsSnafu)?;
+        let replicas = self.table_vnodes(table, predicate).await?;
+
+        // Only the first vnode of each replica set is read by the query.
+        let mut targets = vec![];
+        let mut req_futures = vec![];
+        for replica in replicas {
+            let Some(vnode) = replica.vnodes.first() else {
+                continue;
             };
-            let command = RaftWriteCommand {
-                replica_id: replica.id,
+            let cmd = AdminCommand {
                 tenant: table.tenant().to_string(),
-                db_name: table.database().to_string(),
-                command: Some(raft_write_command::Command::DeleteFromTable(request)),
+                command: Some(EstimateScanCost(EstimateScanCostRequest {
+                    vnode_id: vnode.id,
+                    db_name: table.database().to_string(),
+                    table: table.table().to_string(),
+                    predicate: encoded_predicate.clone(),
+                })),
             };
-
-            let request = self.write_replica_by_raft(replica.clone(), command, None);
-            requests.push(request);
+            targets.push((replica.id, vnode.node_id, vnode.id));
+            req_futures.push(self.admin_command_on_node(vnode.node_id, cmd));
         }
 
-        for result in futures::future::join_all(requests).await {
-            debug!("exec delete from {table} WHERE {predicate:?}, now:{now:?}, elapsed:{}ms, result:{result:?}", now.elapsed().as_millis());
-            result?
+        let mut costs = Vec::with_capacity(targets.len());
+        let responses = futures::future::try_join_all(req_futures).await?;
+        for ((replica_id, node_id, vnode_id), data) in targets.into_iter().zip(responses) {
+            let cost = ScanCost::decode(&data).ok_or_else(|| {
+                CommonSnafu {
+                    msg: "invalid response of estimate scan cost".to_string(),
+                }
+                .build()
+            })?;
+            costs.push(VnodeScanCost {
+                replica_id,
+                node_id,
+                vnode_id,
+                cost,
+            });
         }
 
-        Ok(())
+        Ok(costs)
     }
 
-    async fn replication_manager(
+    async fn vnodes_placement(
         &self,
         tenant: &str,
-        cmd_type: ReplicationCmdType,
-    ) -> CoordinatorResult<()> {
-        let (request, replica) = match cmd_type {
-            ReplicationCmdType::AddRaftFollower(replica_id, node_id) => {
-                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
-                if replica.replica_set.by_node_id(node_id).is_some() {
-                    return Err(CommonSnafu {
-                        msg: format!("A Replication Already in {}", node_id),
-                    }
-                    .build());
-                }
-                (
-                    AdminCommand {
-                        tenant: tenant.to_string(),
-                        command: Some(AddRaftFollower(AddRaftFollowerRequest {
-                            db_name: replica.db_name,
-                            replica_id: replica.replica_set.id,
-                            follower_nid: node_id,
-                        })),
-                    },
-                    replica.replica_set,
-                )
+        databases: &[String],
+    ) -> CoordinatorResult<Vec<VnodePlacement>> {
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
             }
+        })?;
 
-            ReplicationCmdType::RemoveRaftNode(vnode_id) => {
-                let all_info = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
-                let replica_id = all_info.repl_set_id;
-                self.check_remove_vnode_and_promote(
-                    tenant,
-                    &all_info.db_name,
-                    replica_id,
-                    vnode_id,
-                )
-                .await?;
-
-                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
-                (
-                    AdminCommand {
-                        tenant: tenant.to_string(),
-                        command: Some(RemoveRaftNode(RemoveRaftNodeRequest {
-                            vnode_id,
-                            replica_id,
-                            db_name: all_info.db_name,
-                        })),
-                    },
-                    replica.replica_set,
-                )
+        let mut placements = vec![];
+        for database in databases {
+            let Some(db_info) = meta_client.get_db_info(database).context(MetaSnafu)? else {
+                continue;
+            };
+            for replica in db_info.buckets.into_iter().flat_map(|b| b.shard_group) {
+                for vnode in replica.vnodes {
+                    placements.push(VnodePlacement {
+                        database: database.clone(),
+                        replica_id: replica.id,
+                        is_leader: vnode.id == replica.leader_vnode_id,
+                        vnode,
+                        disk_storage: None,
+                    });
+                }
             }
+        }
 
-            ReplicationCmdType::DestoryRaftGroup(replica_id) => {
-                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
-                (
-                    AdminCommand {
-                        tenant: tenant.to_string(),
-                        command: Some(DestoryRaftGroup(DestoryRaftGroupRequest {
-                            replica_id,
-                            db_name: replica.db_name.clone(),
-                        })),
-                    },
-                    replica.replica_set,
-                )
-            }
+        // Group vnode ids by node id.
+        let mut node_vnode_ids_map: HashMap<NodeId, Vec<VnodeId>> = HashMap::new();
+        for placement in placements.iter() {
+            node_vnode_ids_map
+                .entry(placement.vnode.node_id)
+                .or_default()
+                .push(placement.vnode.id);
+        }
 
-            ReplicationCmdType::PromoteLeader(replica_id, new_leader) => {
-                let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
-                (
-                    AdminCommand {
-                        tenant: tenant.to_string(),
-                        command: Some(PromoteLeader(PromoteLeaderRequest {
-                            replica_id,
-                            new_leader_id: new_leader,
-                            db_name: replica.db_name.clone(),
-                        })),
-                    },
-                    replica.replica_set,
-                )
+        let req_futures = node_vnode_ids_map.into_iter().map(|(node_id, vnode_ids)| {
+            let cmd = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchVnodesDiskStorage(FetchVnodesDiskStorageRequest {
+                    vnode_ids,
+                })),
+            };
+            self.admin_command_on_node(node_id, cmd)
+        });
+
+        // An unreachable node should not fail the whole placement.
+        let mut disk_storages = HashMap::new();
+        for result in futures::future::join_all(req_futures).await {
+            let sizes = result.and_then(|data| {
+                bincode::deserialize::<Vec<(VnodeId, u64)>>(&data).context(BincodeSerdeSnafu)
+            });
+            match sizes {
+                Ok(sizes) => disk_storages.extend(sizes),
+                Err(e) => warn!("Failed to fetch disk storage of vnodes: {}", e),
             }
-        };
+        }
+        for placement in placements.iter_mut() {
+            placement.disk_storage = disk_storages.get(&placement.vnode.id).copied();
+        }
 
-        self.admin_command_on_leader(replica, request).await
+        Ok(placements)
     }
 
-    async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()> {
-        // Group vnode ids by node id.
-        let mut node_vnode_ids_map: HashMap<u64, Vec<u32>> = HashMap::new();
-        for vnode_id in vnode_ids.iter() {
-            let vnode = get_vnode_all_info(self.meta.clone(), tenant, *vnode_id).await?;
-            node_vnode_ids_map
-                .entry(vnode.node_id)
-                .or_default()
-                .push(*vnode_id);
-        }
-        let nodes = self.meta.data_nodes().await;
+    async fn shard_rows(&self, tenant: &str, database: &str) -> CoordinatorResult<Vec<ShardRows>> {
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_info = meta_client
+            .get_db_info(database)
+            .context(MetaSnafu)?
+            .ok_or_else(|| {
+                MetaSnafu.into_error(MetaError::DatabaseNotFound {
+                    database: database.to_string(),
+                })
+            })?;
 
-        // Send grouped vnode ids to nodes.
-        let mut req_futures = vec![];
-        for node in nodes {
-            if let Some(vnode_ids) = node_vnode_ids_map.remove(&node.id) {
-                let cmd = AdminCommand {
-                    tenant: tenant.to_string(),
-                    command: Some(CompactVnode(CompactVnodeRequest { vnode_ids })),
-                };
-                req_futures.push(self.admin_command_on_node(node.id, cmd));
+        let precision = *db_info.schema.config.precision();
+        let to_nanos =
+            |ts: i64| timestamp_convert(precision, Precision::NS, ts).unwrap_or_default();
+
+        let mut shards = vec![];
+        // Group the leader vnode ids by node id.
+        let mut node_vnode_ids_map: HashMap<NodeId, Vec<VnodeId>> = HashMap::new();
+        let mut leader_vnodes = HashMap::new();
+        for bucket in db_info.buckets.iter() {
+            for replica in bucket.shard_group.iter() {
+                node_vnode_ids_map
+                    .entry(replica.leader_node_id)
+                    .or_default()
+                    .push(replica.leader_vnode_id);
+                leader_vnodes.insert(replica.id, replica.leader_vnode_id);
+                shards.push(ShardRows {
+                    bucket_id: bucket.id,
+                    start_time: to_nanos(bucket.start_time),
+                    end_time: to_nanos(bucket.end_time),
+                    series_placement: bucket.series_placement,
+                    replica_id: replica.id,
+                    rows: None,
+                });
             }
         }
 
-        for res in futures::future::join_all(req_futures).await {
-            res?;
+        let req_futures = node_vnode_ids_map.into_iter().map(|(node_id, vnode_ids)| {
+            let cmd = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchVnodesRows(FetchVnodesRowsRequest { vnode_ids })),
+            };
+            self.admin_command_on_node(node_id, cmd)
+        });
+
+        // An unreachable node should not fail the whole report.
+        let mut vnode_rows = HashMap::new();
+        for result in futures::future::join_all(req_futures).await {
+            let rows = result.and_then(|data| {
+                bincode::deserialize::<Vec<(VnodeId, u64)>>(&data).context(BincodeSerdeSnafu)
+            });
+            match rows {
+                Ok(rows) => vnode_rows.extend(rows),
+                Err(e) => warn!("Failed to fetch rows of vnodes: {}", e),
+            }
+        }
+        for shard in shards.iter_mut() {
+            shard.rows = leader_vnodes
+                .get(&shard.replica_id)
+                .and_then(|vnode_id| vnode_rows.get(vnode_id))
+                .copied();
         }
 
-        return Ok(());
+        Ok(shards)
     }
 
     async fn replica_checksum(
@@ -1039,6 +2472,64 @@ impl Coordinator for CoordService {
         Ok(record_batches)
     }
 
+    async fn repair_replica(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<Vec<VnodeRepair>> {
+        crate::repair::repair_replica(self, tenant, replica_id).await
+    }
+
+    async fn transfer_leader(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        new_leader: VnodeId,
+        max_log_lag: Option<u64>,
+    ) -> CoordinatorResult<LeaderTransfer> {
+        let start = std::time::Instant::now();
+        let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+        let old_leader = replica.replica_set.leader_vnode_id;
+        let vnode = replica
+            .replica_set
+            .vnode(new_leader)
+            .context(RaftNodeNotFoundSnafu {
+                vnode_id: new_leader,
+                replica_id,
+            })?;
+
+        if old_leader != new_leader {
+            let node_metrics = self.meta.node_metrics().await.context(MetaSnafu)?;
+            check_transfer_target(replica_id, vnode, &node_metrics)?;
+        }
+
+        let log_lag = self
+            .promote_leader(tenant, &replica, new_leader, max_log_lag)
+            .await?;
+
+        let replica = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+        if replica.replica_set.leader_vnode_id != new_leader {
+            return Err(CoordinatorError::RaftGroupError {
+                msg: format!(
+                    "group-{replica_id}, leader in meta is vnode {} after transferring to vnode {new_leader}",
+                    replica.replica_set.leader_vnode_id
+                ),
+            });
+        }
+        info!(
+            "Transferred leadership of replica set {replica_id} from vnode {old_leader} to vnode {new_leader}, log lag: {log_lag}"
+        );
+
+        Ok(LeaderTransfer {
+            replica_id,
+            old_leader,
+            new_leader,
+            node_id: vnode.node_id,
+            log_lag,
+            elapsed: start.elapsed(),
+        })
+    }
+
     fn metrics(&self) -> &Arc<CoordServiceMetrics> {
         &self.metrics
     }
@@ -1143,6 +2634,7 @@ impl Coordinator for CoordService {
                 replica_id: replica.id,
                 tenant: tenant.to_string(),
                 db_name: db.to_string(),
+                consistency: WriteConsistency::Quorum as i32,
                 command: Some(raft_write_command::Command::UpdateTags(
                     update_tags_request.clone(),
                 )),
@@ -1191,6 +2683,8 @@ impl Coordinator for CoordService {
 struct VnodeLines<'a> {
     pub lines: Vec<Line<'a>>,
     pub info: ReplicationSet,
+    /// Estimated size in bytes of the lines.
+    pub size: usize,
 }
 
 impl<'a> VnodeLines<'a> {
@@ -1198,14 +2692,175 @@ impl<'a> VnodeLines<'a> {
         Self {
             lines: vec![],
             info,
+            size: 0,
         }
     }
 
     pub fn add_line(&mut self, line: Line<'a>) {
+        self.size += estimate_line_size(&line);
         self.lines.push(line);
     }
 }
 
+/// Remove the lines of the replication set if they reach `flush_size`.
+fn take_full_vnode_lines<'a>(
+    map_lines: &mut HashMap<ReplicationSetId, VnodeLines<'a>>,
+    replica_id: ReplicationSetId,
+    flush_size: usize,
+) -> Option<VnodeLines<'a>> {
+    match map_lines.get(&replica_id) {
+        Some(lines) if lines.size >= flush_size => map_lines.remove(&replica_id),
+        _ => None,
+    }
+}
+
+/// Fields of a table to be filled or encrypted before the lines are written.
+#[derive(Default)]
+struct TableWriteFields {
+    defaults: Vec<(String, FieldValue)>,
+    ciphers: Vec<(String, ColumnCipher)>,
+}
+
+impl TableWriteFields {
+    fn new(meta_client: &MetaClientRef, schema: &TskvTableSchema) -> CoordinatorResult<Self> {
+        let defaults = table_default_fields(schema).map_err(|e| {
+            CommonSnafu {
+                msg: format!("invalid column default value: {}", e),
+            }
+            .build()
+        })?;
+        let mut ciphers = vec![];
+        for column in schema.columns() {
+            if let Some(cipher) = column_cipher(meta_client, column)? {
+                ciphers.push((column.name.clone(), cipher));
+            }
+        }
+
+        Ok(Self { defaults, ciphers })
+    }
+
+    fn encrypt_line(&self, line: &mut Line) -> CoordinatorResult<()> {
+        if self.ciphers.is_empty() {
+            return Ok(());
+        }
+        for (name, value) in line.fields.iter_mut() {
+            let cipher = self
+                .ciphers
+                .iter()
+                .find(|(n, _)| n.as_str() == name.as_ref());
+            if let (Some((_, cipher)), FieldValue::Str(value)) = (cipher, value) {
+                *value = cipher.encrypt(value).context(ModelsSnafu)?.into_bytes();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error of writing to a database not found, tells when the database was
+/// dropped if it is, so that the late writes are not confused with a typo.
+fn db_not_found_error(meta_client: &MetaClientRef, db: &str) -> MetaError {
+    match meta_client.db_dropped_at(db) {
+        Some(drop_time) => MetaError::DatabaseDropped {
+            database: db.to_string(),
+            drop_time: chrono::Utc.timestamp_nanos(drop_time).to_rfc3339(),
+        },
+        None => MetaError::DatabaseNotFound {
+            database: db.to_string(),
+        },
+    }
+}
+
+/// Get the cipher of the column if it is encrypted, the key is taken from the
+/// key store of the tenant.
+fn column_cipher(
+    meta_client: &MetaClientRef,
+    column: &TableColumn,
+) -> CoordinatorResult<Option<ColumnCipher>> {
+    let Some(key_name) = &column.encryption_key else {
+        return Ok(None);
+    };
+    let key = meta_client
+        .tenant()
+        .options()
+        .encryption_key(key_name)
+        .ok_or_else(|| {
+            CommonSnafu {
+                msg: format!(
+                    "encryption key {} of column {} not found",
+                    key_name, column.name
+                ),
+            }
+            .build()
+        })?;
+    let cipher = ColumnCipher::from_hex(key).context(ModelsSnafu)?;
+    Ok(Some(cipher))
+}
+
+fn encrypt_record_batch(
+    meta_client: &MetaClientRef,
+    table_schema: &TskvTableSchema,
+    record_batch: RecordBatch,
+) -> CoordinatorResult<RecordBatch> {
+    let schema = record_batch.schema();
+    let mut columns = record_batch.columns().to_vec();
+    let mut encrypted = false;
+    for (field, array) in schema.fields().iter().zip(columns.iter_mut()) {
+        let Some(column) = table_schema.column(field.name()) else {
+            continue;
+        };
+        let Some(cipher) = column_cipher(meta_client, column)? else {
+            continue;
+        };
+        let values = array
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| {
+                CommonSnafu {
+                    msg: format!("column {} is not StringArray", field.name()),
+                }
+                .build()
+            })?;
+        let values = values
+            .iter()
+            .map(|v| v.map(|v| cipher.encrypt(v.as_bytes())).transpose())
+            .collect::<Result<StringArray, _>>()
+            .context(ModelsSnafu)?;
+        *array = Arc::new(values);
+        encrypted = true;
+    }
+    if !encrypted {
+        return Ok(record_batch);
+    }
+
+    RecordBatch::try_new(schema, columns).context(ArrowSnafu)
+}
+
+fn estimate_line_size(line: &Line) -> usize {
+    let tags_size: usize = line.tags.iter().map(|(k, v)| k.len() + v.len()).sum();
+    let fields_size: usize = line
+        .fields
+        .iter()
+        .map(|(k, v)| {
+            k.len()
+                + match v {
+                    FieldValue::Str(s) => s.len(),
+                    _ => 8,
+                }
+        })
+        .sum();
+    line.table.len() + tags_size + fields_size + 8
+}
+
+fn vnode_lines_to_points(db: &str, lines: &VnodeLines) -> CoordinatorResult<Arc<Vec<u8>>> {
+    let batches = line_to_batches(&lines.lines).map_err(|e| {
+        CommonSnafu {
+            msg: format!("line to batch error: {}", e),
+        }
+        .build()
+    })?;
+    Ok(Arc::new(mutable_batches_to_point(db, batches)))
+}
+
 fn get_precision_and_value_from_arrow_column(
     column: &ArrayRef,
     idx: usize,
@@ -1275,3 +2930,201 @@ fn get_precision_and_value_from_arrow_column(
         .build()),
     }
 }
+
+/// Check the vnode taking over the leadership of the replication set, it must
+/// be a running voter on a healthy node.
+fn check_transfer_target(
+    replica_id: ReplicationSetId,
+    vnode: &VnodeInfo,
+    node_metrics: &[NodeMetrics],
+) -> CoordinatorResult<()> {
+    let rejected = |reason: String| CoordinatorError::LeaderTransferRejected {
+        replica_id,
+        vnode_id: vnode.id,
+        reason,
+    };
+    if vnode.learner {
+        return Err(rejected("a learner can not be the leader".to_string()));
+    }
+    if vnode.status != VnodeStatus::Running {
+        return Err(rejected(format!("the vnode is {:?}", vnode.status)));
+    }
+    match node_metrics.iter().find(|m| m.id == vnode.node_id) {
+        Some(m) if m.is_healthy() => Ok(()),
+        Some(m) => Err(rejected(format!(
+            "node {} is {}",
+            vnode.node_id,
+            m.status.as_str()
+        ))),
+        None => Err(rejected(format!(
+            "node {} has not reported its metrics",
+            vnode.node_id
+        ))),
+    }
+}
+
+/// The requests deleting from the tables of the database, sent to the
+/// replication sets of the buckets overlapping the time ranges of each delete.
+fn delete_requests(
+    tenant: &str,
+    database: &str,
+    buckets: &[BucketInfo],
+    deletes: &[&(ResolvedTable, ResolvedPredicate)],
+) -> CoordinatorResult<Vec<(ReplicationSet, RaftWriteCommand)>> {
+    let mut requests = vec![];
+    for (table, predicate) in deletes {
+        let time_ranges = predicate.time_ranges();
+        let predicate_bytes = bincode::serialize(predicate).context(BincodeSerdeSnafu)?;
+        let replicas = buckets
+            .iter()
+            .filter(|bucket| {
+                time_ranges.max_ts() >= bucket.start_time && time_ranges.min_ts() <= bucket.end_time
+            })
+            .flat_map(|bucket| bucket.shard_group.iter());
+        for replica in replicas {
+            let request = DeleteFromTableRequest {
+                tenant: tenant.to_string(),
+                database: database.to_string(),
+                table: table.table().to_string(),
+                predicate: predicate_bytes.clone(),
+                vnode_id: 0,
+            };
+            let command = RaftWriteCommand {
+                replica_id: replica.id,
+                tenant: tenant.to_string(),
+                db_name: database.to_string(),
+                consistency: WriteConsistency::Quorum as i32,
+                command: Some(raft_write_command::Command::DeleteFromTable(request)),
+            };
+            requests.push((replica.clone(), command));
+        }
+    }
+
+    Ok(requests)
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use datafusion::sql::TableReference;
+    use models::meta_data::{BucketInfo, NodeMetrics, ReplicationSet, VnodeInfo, VnodeStatus};
+    use models::node_info::NodeStatus;
+    use models::object_reference::Resolve;
+    use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRange, TimeRanges};
+    use protocol_parser::Line;
+    use protos::kv_service::raft_write_command;
+    use protos::FieldValue;
+
+    use super::{check_transfer_target, delete_requests, take_full_vnode_lines, VnodeLines};
+    use crate::errors::CoordinatorError;
+
+    #[test]
+    fn test_check_transfer_target() {
+        let metrics = |id, status| NodeMetrics {
+            id,
+            status,
+            ..Default::default()
+        };
+        let node_metrics = vec![
+            metrics(1, NodeStatus::Healthy),
+            metrics(2, NodeStatus::Unreachable),
+        ];
+        let rejected = |vnode: &VnodeInfo| {
+            matches!(
+                check_transfer_target(1, vnode, &node_metrics),
+                Err(CoordinatorError::LeaderTransferRejected { .. })
+            )
+        };
+
+        let vnode = VnodeInfo::new(3, 1);
+        assert!(check_transfer_target(1, &vnode, &node_metrics).is_ok());
+        assert!(rejected(&VnodeInfo {
+            learner: true,
+            ..vnode.clone()
+        }));
+        assert!(rejected(&VnodeInfo {
+            status: VnodeStatus::Copying,
+            ..vnode.clone()
+        }));
+        // The node is unreachable or has not reported its metrics.
+        assert!(rejected(&VnodeInfo::new(3, 2)));
+        assert!(rejected(&VnodeInfo::new(3, 3)));
+    }
+
+    #[test]
+    fn test_delete_requests() {
+        let bucket = |id: u32, start_time, end_time| BucketInfo {
+            id,
+            start_time,
+            end_time,
+            shard_group: vec![ReplicationSet::new(id, 1, id, vec![VnodeInfo::new(id, 1)])],
+            series_placement: Default::default(),
+        };
+        let buckets = vec![bucket(1, 0, 99), bucket(2, 100, 199)];
+        let delete = |table: &str, min_ts, max_ts| {
+            let table = TableReference::bare(table)
+                .resolve_object("cnosdb", "public")
+                .unwrap();
+            let time_ranges = TimeRanges::new(vec![TimeRange::new(min_ts, max_ts)]);
+            let predicate =
+                ResolvedPredicate::new(Arc::new(time_ranges), ColumnDomains::all(), None).unwrap();
+            (table, predicate)
+        };
+        let deletes = [delete("cpu", 0, 150), delete("mem", 120, 130)];
+
+        let requests = delete_requests(
+            "cnosdb",
+            "public",
+            &buckets,
+            &deletes.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let requests = requests
+            .into_iter()
+            .map(|(replica, command)| match command.command {
+                Some(raft_write_command::Command::DeleteFromTable(request)) => {
+                    assert_eq!(replica.id, command.replica_id);
+                    (request.table, replica.id)
+                }
+                _ => panic!("unexpected command"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            vec![
+                ("cpu".to_string(), 1),
+                ("cpu".to_string(), 2),
+                ("mem".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_take_full_vnode_lines() {
+        let line = |table: &'static str| Line {
+            hash_id: 0,
+            table: Cow::Borrowed(table),
+            tags: vec![(Cow::Borrowed("host"), Cow::Borrowed("a"))],
+            fields: vec![(Cow::Borrowed("usage"), FieldValue::F64(1.0))],
+            timestamp: 0,
+        };
+        let mut map_lines = HashMap::new();
+        let mut lines = VnodeLines::new(ReplicationSet::new(1, 1, 1, vec![]));
+        lines.add_line(line("cpu"));
+        let size = lines.size;
+        map_lines.insert(1, lines);
+
+        assert!(take_full_vnode_lines(&mut map_lines, 1, size + 1).is_none());
+        assert!(take_full_vnode_lines(&mut map_lines, 2, 0).is_none());
+        assert_eq!(map_lines.len(), 1);
+
+        map_lines.get_mut(&1).unwrap().add_line(line("cpu"));
+        let full = take_full_vnode_lines(&mut map_lines, 1, size + 1).unwrap();
+        assert_eq!(full.lines.len(), 2);
+        assert_eq!(full.info.id, 1);
+        assert!(map_lines.is_empty());
+    }
+}
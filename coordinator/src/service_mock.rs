@@ -1,34 +1,134 @@
 #![allow(dead_code, unused_variables)]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 use std::todo;
 
+use config::common::{Bucket, CountBucketConfing, RateBucketConfig, RequestLimiterConfig};
 use config::tskv::Config;
+use datafusion::arrow::datatypes::TimeUnit;
 use datafusion::arrow::record_batch::RecordBatch;
+use meta::client::MetaHttpClient;
+use meta::error::MetaResult;
+use meta::limiter::limiter_factory::LimiterFactory;
+use meta::limiter::limiter_manager::LimiterKey;
+use meta::limiter::{LocalRequestLimiter, RequestLimiter};
 use meta::model::meta_admin::AdminMeta;
 use meta::model::meta_tenant::TenantMeta;
 use meta::model::{MetaClientRef, MetaRef};
-use models::meta_data::{ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus};
+use metrics::metric_register::MetricsRegister;
+use models::codec::Encoding;
+use models::meta_data::{
+    DatabaseInfo, NodeId, ReplicationSet, ReplicationSetId, TenantMetaData, VnodeId, VnodeInfo,
+    VnodeStatus,
+};
 use models::object_reference::ResolvedTable;
-use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
-use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef, TimeRange};
+use models::schema::database_schema::DatabaseSchema;
+use models::schema::table_schema::TableSchema;
+use models::schema::tskv_table_schema::{
+    ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
+};
+use models::ValueType;
 use protocol_parser::Line;
-use protos::kv_service::{RaftWriteCommand, UpdateSetValue};
+use protos::kv_service::{RaftWriteCommand, UpdateSetValue, WriteConsistency};
 use trace::SpanContext;
 use tskv::engine_mock::MockEngine;
 use tskv::reader::QueryOption;
-use tskv::EngineRef;
+use tskv::{CompactionJobInfo, CompactionPlan, EngineRef, VnodeBackup};
 use utils::precision::Precision;
 
+use crate::dry_run::DryRunReport;
 use crate::errors::CoordinatorResult;
 use crate::raft::manager::RaftNodesManager;
+use crate::raft::recovery::NodeRecoveryProgress;
 use crate::raft::writer::TskvRaftWriter;
+use crate::repair::VnodeRepair;
 use crate::service::CoordServiceMetrics;
-use crate::{Coordinator, ReplicationCmdType, SendableCoordinatorRecordBatchStream};
+use crate::{
+    Coordinator, LeaderTransfer, RecordBatchWrite, ReplicaDataStream, ReplicationCmdType,
+    SendableCoordinatorRecordBatchStream, ShardRows, VnodePlacement, VnodeScanCost,
+};
 
 pub const WITH_NONEMPTY_DATABASE_FOR_TEST: &str = "with_nonempty_database";
+/// Table `(time, host, value)` of [`WITH_NONEMPTY_DATABASE_FOR_TEST`].
+pub const WITH_TABLE_FOR_TEST: &str = "with_table";
+/// Tenant whose limiter rejects the HTTP writes.
+pub const WITH_LIMITED_WRITES_TENANT_FOR_TEST: &str = "with_limited_writes";
+
+#[derive(Debug)]
+struct MockLimiterFactory;
+
+#[async_trait::async_trait]
+impl LimiterFactory for MockLimiterFactory {
+    async fn create_default(&self, key: LimiterKey) -> MetaResult<Arc<dyn RequestLimiter>> {
+        let LimiterKey(_, tenant) = key;
+        let config = (tenant == WITH_LIMITED_WRITES_TENANT_FOR_TEST).then(|| {
+            let no_writes = Bucket {
+                remote_bucket: RateBucketConfig {
+                    max: Some(0),
+                    initial: 0,
+                    refill: 0,
+                    interval: 1000,
+                },
+                local_bucket: CountBucketConfing {
+                    max: Some(0),
+                    initial: 0,
+                },
+            };
+            RequestLimiterConfig {
+                coord_data_in: None,
+                coord_data_out: None,
+                coord_queries: None,
+                coord_writes: None,
+                http_data_in: None,
+                http_data_out: None,
+                http_queries: None,
+                http_writes: Some(no_writes),
+            }
+        });
+        let client = MetaHttpClient::new("", Arc::new(MetricsRegister::default()));
+        Ok(Arc::new(LocalRequestLimiter::new(
+            "",
+            &tenant,
+            config.as_ref(),
+            client,
+        )))
+    }
+}
+
+fn mock_tenant_data() -> TenantMetaData {
+    let db = WITH_NONEMPTY_DATABASE_FOR_TEST;
+    let table = TskvTableSchema::new(
+        "cnosdb".to_string(),
+        db.to_string(),
+        WITH_TABLE_FOR_TEST.to_string(),
+        vec![
+            TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+            TableColumn::new_tag_column(1, "host".to_string()),
+            TableColumn::new(
+                2,
+                "value".to_string(),
+                ColumnType::Field(ValueType::Float),
+                Encoding::Default,
+            ),
+        ],
+    );
+    let info = DatabaseInfo {
+        schema: DatabaseSchema::new("cnosdb", db, Default::default(), Default::default()),
+        buckets: vec![],
+        tables: HashMap::from([(
+            WITH_TABLE_FOR_TEST.to_string(),
+            TableSchema::TsKvTableSchema(Arc::new(table)),
+        )]),
+    };
+    let mut data = TenantMetaData::new();
+    data.dbs.insert(db.to_string(), info);
+    data
+}
 
 #[derive(Debug, Default)]
 pub struct MockCoordinator {}
@@ -40,7 +140,7 @@ impl Coordinator for MockCoordinator {
     }
 
     fn meta_manager(&self) -> MetaRef {
-        Arc::new(AdminMeta::mock())
+        Arc::new(AdminMeta::mock_with_limiters(Arc::new(MockLimiterFactory)))
     }
 
     fn store_engine(&self) -> Option<EngineRef> {
@@ -52,13 +152,122 @@ impl Coordinator for MockCoordinator {
     }
 
     async fn tenant_meta(&self, tenant: &str) -> Option<MetaClientRef> {
-        Some(Arc::new(TenantMeta::mock()))
+        Some(Arc::new(TenantMeta::mock_with_data(mock_tenant_data())))
     }
 
     async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()> {
         todo!()
     }
 
+    async fn close_vnodes_files(
+        &self,
+        tenant: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> CoordinatorResult<Vec<(VnodeId, u64)>> {
+        todo!()
+    }
+
+    async fn explain_compaction(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+    ) -> CoordinatorResult<Vec<CompactionPlan>> {
+        todo!()
+    }
+
+    async fn compaction_jobs(
+        &self,
+        tenant: &str,
+    ) -> CoordinatorResult<Vec<(NodeId, CompactionJobInfo)>> {
+        Ok(vec![])
+    }
+
+    async fn kill_compaction(
+        &self,
+        tenant: &str,
+        node_id: Option<NodeId>,
+        job_id: u64,
+    ) -> CoordinatorResult<()> {
+        Ok(())
+    }
+
+    async fn recovery_progress(
+        &self,
+        tenant: &str,
+    ) -> CoordinatorResult<Vec<NodeRecoveryProgress>> {
+        todo!()
+    }
+
+    async fn export_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        time_range: TimeRange,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<u64> {
+        todo!()
+    }
+
+    async fn export_replica_data(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        time_range: TimeRange,
+    ) -> CoordinatorResult<ReplicaDataStream> {
+        Ok(Box::pin(futures::stream::empty()))
+    }
+
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<VnodeBackup> {
+        todo!()
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        vnode_id: VnodeId,
+        backup: VnodeBackup,
+        path: String,
+        connection_options: HashMap<String, String>,
+    ) -> CoordinatorResult<()> {
+        todo!()
+    }
+
+    async fn series_cardinality_top(
+        &self,
+        tenant: &str,
+        db: &str,
+        top_k: usize,
+    ) -> CoordinatorResult<RecordBatch> {
+        todo!()
+    }
+
+    async fn estimate_scan_cost(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<VnodeScanCost>> {
+        todo!()
+    }
+
+    async fn vnodes_placement(
+        &self,
+        tenant: &str,
+        databases: &[String],
+    ) -> CoordinatorResult<Vec<VnodePlacement>> {
+        todo!()
+    }
+
+    async fn shard_rows(&self, tenant: &str, database: &str) -> CoordinatorResult<Vec<ShardRows>> {
+        todo!()
+    }
+
     fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter {
         todo!()
     }
@@ -87,6 +296,7 @@ impl Coordinator for MockCoordinator {
                         id: 0,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -97,6 +307,7 @@ impl Coordinator for MockCoordinator {
                         id: 1,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -107,6 +318,7 @@ impl Coordinator for MockCoordinator {
                         id: 2,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -117,6 +329,7 @@ impl Coordinator for MockCoordinator {
                         id: 3,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -127,6 +340,7 @@ impl Coordinator for MockCoordinator {
                         id: 4,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -137,6 +351,7 @@ impl Coordinator for MockCoordinator {
                         id: 5,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -147,6 +362,7 @@ impl Coordinator for MockCoordinator {
                         id: 6,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
                 ReplicationSet::new(
@@ -157,6 +373,7 @@ impl Coordinator for MockCoordinator {
                         id: 7,
                         node_id: 0,
                         status: VnodeStatus::Running,
+                        learner: false,
                     }],
                 ),
             ]);
@@ -164,25 +381,70 @@ impl Coordinator for MockCoordinator {
         Ok(vec![])
     }
 
+    async fn table_vnodes_within_lag(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+        _max_replica_lag: Duration,
+    ) -> CoordinatorResult<Vec<ReplicationSet>> {
+        self.table_vnodes(table, predicate).await
+    }
+
+    async fn table_vnodes_follower_read(
+        &self,
+        table: &ResolvedTable,
+        predicate: ResolvedPredicateRef,
+    ) -> CoordinatorResult<Vec<ReplicationSet>> {
+        self.table_vnodes(table, predicate).await
+    }
+
     async fn write_lines<'a>(
         &self,
         tenant: &str,
         db: &str,
         precision: Precision,
+        consistency: WriteConsistency,
         line: Vec<Line<'a>>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
     }
 
+    async fn write_lines_stream<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        consistency: WriteConsistency,
+        lines: LineBatchStream<'a>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        todo!()
+    }
+
+    async fn dry_run_write_lines<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        consistency: WriteConsistency,
+        line: Vec<Line<'a>>,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<DryRunReport> {
+        todo!()
+    }
+
     async fn write_record_batch<'a>(
         &self,
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
         span_ctx: Option<&SpanContext>,
-    ) -> CoordinatorResult<usize> {
-        todo!()
+    ) -> CoordinatorResult<RecordBatchWrite> {
+        Ok(RecordBatchWrite {
+            write_bytes: record_batch.get_array_memory_size(),
+            ..Default::default()
+        })
     }
 
     fn table_scan(
@@ -202,12 +464,11 @@ impl Coordinator for MockCoordinator {
         todo!("tag_scan")
     }
 
-    async fn delete_from_table(
+    async fn delete_from_tables(
         &self,
-        table: &ResolvedTable,
-        predicate: &ResolvedPredicate,
+        deletes: &[(ResolvedTable, ResolvedPredicate)],
     ) -> CoordinatorResult<()> {
-        todo!("delete_from_table")
+        Ok(())
     }
 
     async fn replication_manager(
@@ -226,6 +487,24 @@ impl Coordinator for MockCoordinator {
         Ok(vec![])
     }
 
+    async fn repair_replica(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<Vec<VnodeRepair>> {
+        Ok(vec![])
+    }
+
+    async fn transfer_leader(
+        &self,
+        tenant: &str,
+        replica_id: ReplicationSetId,
+        new_leader: VnodeId,
+        max_log_lag: Option<u64>,
+    ) -> CoordinatorResult<LeaderTransfer> {
+        Ok(LeaderTransfer::default())
+    }
+
     fn metrics(&self) -> &Arc<CoordServiceMetrics> {
         todo!()
     }
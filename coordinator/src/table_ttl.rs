@@ -0,0 +1,180 @@
+//! Retention of the tables with a TTL.
+//!
+//! Data of a table older than its TTL is deleted by the leaders of the replica
+//! sets, which propose the deletes to raft like `DELETE FROM`, so the replicas
+//! delete the same data whatever their clocks are.
+
+use std::collections::{HashMap, HashSet};
+
+use models::meta_data::{DatabaseInfo, NodeId, ReplicationSet, ReplicationSetId};
+use models::predicate::domain::TimeRange;
+use models::schema::table_schema::TableSchema;
+
+/// The data of a table in a replica set to delete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiredTable {
+    pub replica: ReplicationSet,
+    pub table: String,
+    pub time_range: TimeRange,
+}
+
+/// The cutoff of each table in each replica set proposed by this node. It's in
+/// memory only, after a restart or a change of the leaders the data before the
+/// cutoff is deleted again, which deletes nothing more.
+#[derive(Default)]
+pub struct TableTtlState {
+    proposed: HashMap<(ReplicationSetId, String), i64>,
+}
+
+impl TableTtlState {
+    /// The data of the tables of the database expired at `now` (nanoseconds),
+    /// in the replica sets led by `node_id`.
+    pub fn expired(&self, db_info: &DatabaseInfo, node_id: NodeId, now: i64) -> Vec<ExpiredTable> {
+        let mut expired = vec![];
+        for (table, schema) in db_info.tables.iter() {
+            let TableSchema::TsKvTableSchema(schema) = schema else {
+                continue;
+            };
+            let Some(cutoff) = schema.time_to_expired(now) else {
+                continue;
+            };
+            let replicas = db_info
+                .buckets
+                .iter()
+                .filter(|bucket| bucket.start_time < cutoff)
+                .flat_map(|bucket| bucket.shard_group.iter())
+                .filter(|replica| replica.leader_node_id == node_id);
+            for replica in replicas {
+                let applied = self.proposed.get(&(replica.id, table.clone())).copied();
+                let Some(time_range) = tombstone_range(applied, cutoff) else {
+                    continue;
+                };
+                expired.push(ExpiredTable {
+                    replica: replica.clone(),
+                    table: table.clone(),
+                    time_range,
+                });
+            }
+        }
+
+        expired
+    }
+
+    /// Record the delete of `expired` committed by raft.
+    pub fn proposed(&mut self, expired: &ExpiredTable) {
+        self.proposed.insert(
+            (expired.replica.id, expired.table.clone()),
+            expired.time_range.max_ts + 1,
+        );
+    }
+
+    /// Forget the replica sets which no longer exist.
+    pub fn retain(&mut self, replica_ids: &HashSet<ReplicationSetId>) {
+        self.proposed
+            .retain(|(replica_id, _), _| replica_ids.contains(replica_id));
+    }
+}
+
+/// The time range to delete up to `cutoff`, the data before `applied` was
+/// already deleted by the previous run.
+fn tombstone_range(applied: Option<i64>, cutoff: i64) -> Option<TimeRange> {
+    let min_ts = applied.unwrap_or(i64::MIN);
+    if min_ts >= cutoff {
+        return None;
+    }
+
+    Some(TimeRange::new(min_ts, cutoff - 1))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use models::meta_data::{BucketInfo, DatabaseInfo, ReplicationSet};
+    use models::predicate::domain::TimeRange;
+    use models::schema::table_schema::TableSchema;
+    use models::schema::tskv_table_schema::TskvTableSchema;
+    use utils::duration::CnosDuration;
+
+    use super::{tombstone_range, TableTtlState};
+
+    fn bucket(id: u32, start_time: i64, end_time: i64, leader_node_id: u64) -> BucketInfo {
+        BucketInfo {
+            id,
+            start_time,
+            end_time,
+            shard_group: vec![ReplicationSet::new(id, leader_node_id, id, vec![])],
+            ..Default::default()
+        }
+    }
+
+    fn table(name: &str, ttl: Option<Duration>) -> (String, TableSchema) {
+        let mut schema = TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            name.to_string(),
+            vec![],
+        );
+        schema.ttl = ttl.map(CnosDuration::new_with_duration);
+        (
+            name.to_string(),
+            TableSchema::TsKvTableSchema(Arc::new(schema)),
+        )
+    }
+
+    #[test]
+    fn test_tombstone_range() {
+        assert_eq!(
+            tombstone_range(None, 100),
+            Some(TimeRange::new(i64::MIN, 99))
+        );
+        assert_eq!(tombstone_range(Some(60), 100), Some(TimeRange::new(60, 99)));
+        assert_eq!(tombstone_range(Some(100), 100), None);
+    }
+
+    #[test]
+    fn test_expired_tables() {
+        let db_info = DatabaseInfo {
+            buckets: vec![
+                bucket(1, 0, 100, 1),
+                bucket(2, 100, 200, 2),
+                bucket(3, 200, 300, 1),
+            ],
+            tables: HashMap::from([
+                table("air", Some(Duration::from_nanos(100))),
+                table("sea", None),
+            ]),
+            ..Default::default()
+        };
+        let mut state = TableTtlState::default();
+
+        // Only the replica sets led by this node starting before the cutoff.
+        let expired = state.expired(&db_info, 1, 250);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].replica.id, 1);
+        assert_eq!(expired[0].table, "air");
+        assert_eq!(expired[0].time_range, TimeRange::new(i64::MIN, 149));
+
+        // The data deleted by the previous run is not deleted again.
+        state.proposed(&expired[0]);
+        assert!(state.expired(&db_info, 1, 250).is_empty());
+        let expired = state.expired(&db_info, 1, 350);
+        assert_eq!(expired.len(), 2);
+        for e in expired.iter() {
+            match e.replica.id {
+                1 => assert_eq!(e.time_range, TimeRange::new(150, 249)),
+                3 => assert_eq!(e.time_range, TimeRange::new(i64::MIN, 249)),
+                id => panic!("unexpected replica set {id}"),
+            }
+        }
+
+        // The data of the dropped replica sets is deleted from the start.
+        state.retain(&HashSet::from([3]));
+        let expired = state.expired(&db_info, 1, 350);
+        assert!(expired
+            .iter()
+            .all(|e| e.time_range == TimeRange::new(i64::MIN, 249)));
+    }
+}
@@ -6,7 +6,7 @@ use models::meta_data::*;
 use protos::kv_service::AdminCommand;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use snafu::ResultExt;
-use trace::info;
+use trace::{info, warn};
 
 use crate::errors::*;
 use crate::TskvLeaderCaller;
@@ -64,6 +64,10 @@ impl TskvLeaderCaller for TskvAdminRequest {
     }
 }
 
+/// Max times of following the leader hint returned by the replica, the leader
+/// may change again while the request is redirecting.
+const MAX_LEADER_REDIRECTS: usize = 3;
+
 pub struct TskvLeaderExecutor {
     pub meta: MetaRef,
 }
@@ -117,16 +121,28 @@ impl TskvLeaderExecutor {
         &self,
         tenant: &str,
         replica: &ReplicationSet,
-        new_leader: VnodeId,
+        mut new_leader: VnodeId,
         caller: &impl TskvLeaderCaller,
     ) -> CoordinatorResult<Vec<u8>> {
-        let new_leader_node_id = self.update_new_leader_to_meta(tenant, new_leader).await?;
-        info!(
-            "Redirect replica: {} request to new_leader(node: {}, vnode id: {})",
-            replica.id, new_leader_node_id, new_leader
-        );
+        let mut redirects = 0;
+        loop {
+            let new_leader_node_id = self.update_new_leader_to_meta(tenant, new_leader).await?;
+            info!(
+                "Redirect replica: {} request to new_leader(node: {}, vnode id: {})",
+                replica.id, new_leader_node_id, new_leader
+            );
 
-        caller.call(replica, new_leader_node_id).await
+            let result = caller.call(replica, new_leader_node_id).await;
+            redirects += 1;
+            match result {
+                Err(CoordinatorError::RaftForwardToLeader {
+                    leader_vnode_id, ..
+                }) if redirects < MAX_LEADER_REDIRECTS && leader_vnode_id != new_leader => {
+                    new_leader = leader_vnode_id;
+                }
+                _ => return result,
+            }
+        }
     }
 
     async fn update_new_leader_to_meta(
@@ -134,17 +150,25 @@ impl TskvLeaderExecutor {
         tenant: &str,
         new_leader: VnodeId,
     ) -> CoordinatorResult<NodeId> {
-        let new_leader_node_id = self
-            .meta
-            .tenant_meta(tenant)
-            .await
-            .ok_or_else(|| CoordinatorError::TenantNotFound {
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
                 name: tenant.to_string(),
-            })?
-            .replica_new_leader(new_leader)
-            .await
-            .context(MetaSnafu)?;
+            }
+        })?;
 
-        Ok(new_leader_node_id)
+        match meta_client.replica_new_leader(new_leader).await {
+            Ok(node_id) => Ok(node_id),
+            Err(err) => {
+                // The request is still redirected to the node of the new
+                // leader, the local replica cache keeps the old one until
+                // the meta commits the change.
+                warn!("Update new leader(vnode id: {new_leader}) to meta failed: {err}");
+                meta_client
+                    .get_vnode_all_info(new_leader)
+                    .map(|info| info.node_id)
+                    .ok_or(err)
+                    .context(MetaSnafu)
+            }
+        }
     }
 }
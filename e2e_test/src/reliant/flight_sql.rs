@@ -8,7 +8,9 @@ use arrow_flight::sql::{
     CommandGetPrimaryKeys, CommandGetTables, SqlInfo,
 };
 use arrow_flight::utils::flight_data_to_batches;
-use arrow_flight::FlightInfo;
+use arrow_flight::{FlightInfo, IpcMessage};
+use datafusion::arrow::array::{Array, BinaryArray};
+use datafusion::arrow::datatypes::Schema;
 use datafusion::arrow::error::ArrowError;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::arrow::util::pretty;
@@ -103,15 +105,27 @@ async fn test_sql_client_get_db_schemas() {
         .unwrap();
 
     let expected = [
-        "+----------------+--------------+",
-        "| db_schema_name | catalog_name |",
-        "+----------------+--------------+",
-        "| usage_schema   | cnosdb       |",
-        "+----------------+--------------+",
+        "+--------------+----------------+",
+        "| catalog_name | db_schema_name |",
+        "+--------------+----------------+",
+        "| cnosdb       | usage_schema   |",
+        "+--------------+----------------+",
     ];
     let actual = fetch_result_and_print(flight_info, &mut client).await;
 
     assert_batches_eq!(expected, &actual);
+
+    // The catalog that doesn't exist has no schemas.
+    let flight_info = client
+        .get_db_schemas(CommandGetDbSchemas {
+            catalog: Some("".to_string()),
+            db_schema_filter_pattern: None,
+        })
+        .await
+        .unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    assert_eq!(actual.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
     check_close(&mut client).await;
 }
 
@@ -150,6 +164,38 @@ async fn test_sql_client_get_tables() {
     check_close(&mut client).await;
 }
 
+#[tokio::test]
+async fn test_sql_client_get_tables_with_schema() {
+    let mut client = authed_client().await;
+
+    let flight_info = client
+        .get_tables(CommandGetTables {
+            catalog: Some("cnosdb".to_string()),
+            db_schema_filter_pattern: Some("usage_schema".to_string()),
+            table_name_filter_pattern: Some("vnode_disk_storage".to_string()),
+            table_types: vec![],
+            include_schema: true,
+        })
+        .await
+        .unwrap();
+
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    for batch in actual {
+        let table_schemas = batch
+            .column_by_name("table_schema")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        for table_schema in table_schemas.iter().flatten() {
+            let schema = Schema::try_from(IpcMessage(table_schema.to_vec().into())).unwrap();
+            assert!(schema.field_with_name("time").is_ok());
+        }
+    }
+
+    check_close(&mut client).await;
+}
+
 #[tokio::test]
 async fn test_sql_client_get_table_types() {
     let mut client = authed_client().await;
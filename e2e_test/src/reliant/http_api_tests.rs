@@ -136,6 +136,35 @@ fn test_v1_sql_path() {
     assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
 }
 
+#[test]
+fn test_v1_sql_plan_path() {
+    let url = "http://127.0.0.1:8902/api/v1/sql/plan?db=public";
+    let client = Client::with_auth("root".to_string(), None);
+
+    let resp = check_response!(client.post(url, "select 1;"));
+    assert_eq!(
+        resp.headers().get(CONTENT_TYPE).unwrap(),
+        &HeaderValue::from_static("application/json")
+    );
+    let plan = resp.text().unwrap();
+    assert!(plan.contains("\"logical_plan\":\"Projection"), "{plan}");
+    assert!(
+        plan.contains("\"physical_plan\":\"ProjectionExec"),
+        "{plan}"
+    );
+
+    // The statement is not executed.
+    let resp = client
+        .post(url, "create database e2e_test_sql_plan;")
+        .unwrap();
+    assert_ne!(resp.status(), StatusCode::OK);
+    let resp = check_response!(client.post(
+        "http://127.0.0.1:8902/api/v1/sql?db=public",
+        "select database_name from information_schema.databases where database_name = 'e2e_test_sql_plan';",
+    ));
+    assert_eq!(resp.text().unwrap().trim(), "database_name");
+}
+
 #[test]
 fn test_v1_write_path() {
     let url = "http://127.0.0.1:8902/api/v1/write?db=public";
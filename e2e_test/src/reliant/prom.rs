@@ -252,3 +252,79 @@ fn test_prom() {
     let resp: ReadResponse = deserialize(&resp.bytes().unwrap());
     assert_eq!(resp, test_read_upper_resp());
 }
+
+fn remote_write_series() -> TimeSeries {
+    TimeSeries {
+        labels: vec![
+            Label {
+                name: "__name__".to_string(),
+                value: "test_prom_remote_write".to_string(),
+            },
+            Label {
+                name: "tag1".to_string(),
+                value: "a".to_string(),
+            },
+        ],
+        samples: vec![Sample {
+            value: 2.2,
+            timestamp: 1686819776617,
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_prom_remote_write() {
+    let client = Client::with_auth("root".to_string(), None);
+
+    let body = "drop table if exists test_prom_remote_write;";
+    check_response!(client.post("http://127.0.0.1:8902/api/v1/sql?db=public", body));
+
+    // write data by the path of the remote write of prometheus
+    let body = serialize(WriteRequest {
+        timeseries: vec![remote_write_series()],
+        ..Default::default()
+    });
+    check_response!(client
+        .request_with_auth(
+            Method::POST,
+            "http://127.0.0.1:8902/api/v1/prom/remote_write?db=public",
+        )
+        .body(body)
+        .send());
+
+    // read data
+    let query = Query {
+        start_timestamp_ms: 1686819776615,
+        end_timestamp_ms: 1686819777615,
+        matchers: vec![LabelMatcher {
+            r#type: label_matcher::Type::Eq.into(),
+            name: "__name__".to_string(),
+            value: "test_prom_remote_write".to_string(),
+        }],
+        ..Default::default()
+    };
+    let body = serialize(ReadRequest {
+        queries: vec![query],
+        ..Default::default()
+    });
+    let resp = client
+        .request_with_auth(
+            Method::POST,
+            "http://127.0.0.1:8902/api/v1/prom/read?db=public",
+        )
+        .body(body)
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), status_code::OK);
+
+    let resp: ReadResponse = deserialize(&resp.bytes().unwrap());
+    assert_eq!(
+        resp,
+        ReadResponse {
+            results: vec![QueryResult {
+                timeseries: vec![remote_write_series()],
+            }],
+        }
+    );
+}
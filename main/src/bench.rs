@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use clap::{Args, ValueEnum};
+use tskv::tsm::codec::bench::{bench_codecs, CodecSample};
+
+#[derive(Debug, Args)]
+pub struct CodecBenchArgs {
+    /// Path to the file of sample values, one value per line, or a CSV file
+    /// with a header line if `--column` is set (values must not contain commas).
+    #[arg(short, long)]
+    file: String,
+
+    /// Type of the values.
+    #[arg(short = 't', long, value_enum)]
+    data_type: SampleType,
+
+    /// Name of the column to read from the CSV file, for example a file
+    /// exported by `COPY INTO` from a table.
+    #[arg(short, long)]
+    column: Option<String>,
+
+    /// Number of times to encode and decode the values by each codec.
+    #[arg(short, long, default_value_t = 10)]
+    iterations: usize,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+enum SampleType {
+    /// Timestamps in nanoseconds.
+    Timestamp,
+    Bigint,
+    BigintUnsigned,
+    Double,
+    Boolean,
+    String,
+}
+
+/// Benchmark the codecs of tskv over the sample values, and print the
+/// compression ratio and throughput of each codec.
+pub fn bench_codec(args: CodecBenchArgs) -> Result<(), String> {
+    let values = read_values(&args.file, args.column.as_deref())?;
+    let sample = parse_sample(args.data_type, values)?;
+    if sample.is_empty() {
+        return Err(format!("No sample values in file '{}'", args.file));
+    }
+
+    println!(
+        "Benchmark codecs of {:?} over {} values ({} bytes), {} iterations",
+        args.data_type,
+        sample.len(),
+        sample.raw_size(),
+        args.iterations
+    );
+    println!(
+        "{:<10} {:>14} {:>10} {:>16} {:>16}",
+        "CODEC", "ENCODED_BYTES", "RATIO", "ENCODE_MB/S", "DECODE_MB/S"
+    );
+    for (encoding, result) in bench_codecs(&sample, args.iterations) {
+        match result {
+            Ok(r) => println!(
+                "{:<10} {:>14} {:>10.2} {:>16.2} {:>16.2}",
+                encoding.as_str(),
+                r.encoded_size,
+                r.compression_ratio(),
+                r.encode_throughput(),
+                r.decode_throughput()
+            ),
+            Err(e) => println!("{:<10} error: {e}", encoding.as_str()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the non-empty values of the file, empty values are nulls.
+fn read_values(path: &str, column: Option<&str>) -> Result<Vec<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open '{path}': {e}"))?;
+    let mut lines = BufReader::new(file).lines();
+    let mut next_line = || {
+        lines
+            .next()
+            .transpose()
+            .map_err(|e| format!("Failed to read '{path}': {e}"))
+    };
+
+    let column_index = match column {
+        Some(column) => {
+            let header = next_line()?.unwrap_or_default();
+            let index = header
+                .split(',')
+                .position(|c| c.trim().trim_matches('"') == column)
+                .ok_or_else(|| format!("Column '{column}' not found in the header of '{path}'"))?;
+            Some(index)
+        }
+        None => None,
+    };
+
+    let mut values = Vec::new();
+    while let Some(line) = next_line()? {
+        let value = match column_index {
+            Some(i) => line.split(',').nth(i).unwrap_or_default(),
+            None => line.as_str(),
+        };
+        let value = value.trim().trim_matches('"');
+        if !value.is_empty() {
+            values.push(value.to_string());
+        }
+    }
+
+    Ok(values)
+}
+
+fn parse_sample(data_type: SampleType, values: Vec<String>) -> Result<CodecSample, String> {
+    fn parse<T: std::str::FromStr>(values: &[String]) -> Result<Vec<T>, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        values
+            .iter()
+            .map(|v| {
+                v.parse::<T>()
+                    .map_err(|e| format!("Invalid value '{v}': {e}"))
+            })
+            .collect()
+    }
+
+    let sample = match data_type {
+        SampleType::Timestamp => CodecSample::Timestamp(parse(&values)?),
+        SampleType::Bigint => CodecSample::Integer(parse(&values)?),
+        SampleType::BigintUnsigned => CodecSample::Unsigned(parse(&values)?),
+        SampleType::Double => CodecSample::Float(parse(&values)?),
+        SampleType::Boolean => CodecSample::Boolean(
+            values
+                .iter()
+                .map(|v| match v.to_ascii_lowercase().as_str() {
+                    "true" | "t" | "1" => Ok(true),
+                    "false" | "f" | "0" => Ok(false),
+                    _ => Err(format!("Invalid value '{v}': not a boolean")),
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        SampleType::String => {
+            CodecSample::String(values.into_iter().map(String::into_bytes).collect())
+        }
+    };
+
+    Ok(sample)
+}
@@ -24,7 +24,11 @@ impl BasicCallHeaderAuthenticator {
 impl CallHeaderAuthenticator for BasicCallHeaderAuthenticator {
     type AuthResult = CommonAuthResult;
 
-    async fn authenticate(&self, req_headers: &MetadataMap) -> Result<Self::AuthResult, Status> {
+    async fn authenticate(
+        &self,
+        req_headers: &MetadataMap,
+        client_addr: Option<&str>,
+    ) -> Result<Self::AuthResult, Status> {
         debug!("authenticate, request headers: {:?}", req_headers);
 
         let authorization = utils::get_value_from_auth_header(req_headers, "")
@@ -48,7 +52,11 @@ impl CallHeaderAuthenticator for BasicCallHeaderAuthenticator {
 
         let user = self
             .instance
-            .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+            .authenticate(
+                &user_info,
+                tenant.as_deref().unwrap_or(DEFAULT_CATALOG),
+                client_addr,
+            )
             .await
             .map_err(|e| Status::unauthenticated(e.to_string()))?;
 
@@ -76,14 +84,17 @@ mod test {
 
         let mut req_headers = MetadataMap::default();
 
-        assert!(authenticator.authenticate(&req_headers).await.is_err());
+        assert!(authenticator
+            .authenticate(&req_headers, None)
+            .await
+            .is_err());
 
         let val = AsciiMetadataValue::from_static("Basic eHg6eHgK");
 
         req_headers.insert(AUTHORIZATION.as_str(), val);
 
         let auth_result = authenticator
-            .authenticate(&req_headers)
+            .authenticate(&req_headers, None)
             .await
             .expect("authenticate");
 
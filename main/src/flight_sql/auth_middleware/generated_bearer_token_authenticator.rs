@@ -50,7 +50,11 @@ where
 {
     type AuthResult = GeneratedBearerTokenAuthResult;
 
-    async fn authenticate(&self, req_headers: &MetadataMap) -> Result<Self::AuthResult, Status> {
+    async fn authenticate(
+        &self,
+        req_headers: &MetadataMap,
+        client_addr: Option<&str>,
+    ) -> Result<Self::AuthResult, Status> {
         debug!("authenticate, request headers: {:?}", req_headers);
 
         // Check if headers contain a bearer token and if so, validate the token.
@@ -72,7 +76,10 @@ where
         debug!("bearer_token not exists, delegate to initial_authenticator");
 
         // Delegate to the basic auth handler to do the validation.
-        let auth_result = self.initial_authenticator.authenticate(req_headers).await?;
+        let auth_result = self
+            .initial_authenticator
+            .authenticate(req_headers, client_addr)
+            .await?;
         self.process_auth_result(auth_result)
     }
 }
@@ -145,6 +152,7 @@ mod test {
         async fn authenticate(
             &self,
             _req_headers: &MetadataMap,
+            _client_addr: Option<&str>,
         ) -> Result<Self::AuthResult, tonic::Status> {
             let options = unsafe {
                 UserOptionsBuilder::default()
@@ -170,7 +178,7 @@ mod test {
         assert_eq!(req_headers.len(), 1);
 
         authenticator
-            .authenticate(&req_headers)
+            .authenticate(&req_headers, None)
             .await
             .expect("authenticate")
             .append_to_outgoing_headers(&mut req_headers)
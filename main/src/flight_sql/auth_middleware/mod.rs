@@ -13,7 +13,12 @@ pub trait CallHeaderAuthenticator {
     /// Implementations of CallHeaderAuthenticator should
     /// take care not to provide leak confidential details
     /// for security reasons when reporting errors back to clients.
-    async fn authenticate(&self, req_headers: &MetadataMap) -> Result<Self::AuthResult, Status>;
+    /// `client_addr` is the address of the client, audited with the login.
+    async fn authenticate(
+        &self,
+        req_headers: &MetadataMap,
+        client_addr: Option<&str>,
+    ) -> Result<Self::AuthResult, Status>;
 }
 
 pub trait AuthResult {
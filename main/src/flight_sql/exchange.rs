@@ -0,0 +1,417 @@
+//! Ingest over the `DoExchange` call of Arrow Flight.
+//!
+//! The client streams the record batches to be written into a table, the
+//! table name is the last element of the path of the [`FlightDescriptor`] in
+//! the first message, the tenant and the database are taken from the headers
+//! as the queries do. Each batch is written by
+//! [`Coordinator::write_record_batch`] and acknowledged by a [`FlightData`]
+//! carrying an [`ExchangeAck`] in the `app_metadata`, the exchange fails at the
+//! first batch failed to write. The exchange is limited by the request limiter
+//! of the tenant as an HTTP write, the size of each message is counted as the
+//! incoming data.
+//!
+//! [`Coordinator::write_record_batch`]: coordinator::Coordinator::write_record_batch
+
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo, HandshakeRequest,
+    SchemaResult, Ticket,
+};
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::{Stream, StreamExt, TryStreamExt};
+use models::auth::privilege::{
+    DatabasePrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
+};
+use models::auth::user::User;
+use models::oid::{Identifier, Oid};
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use serde::{Deserialize, Serialize};
+use spi::service::protocol::Context;
+use tonic::{Request, Response, Status, Streaming};
+use trace::debug;
+use utils::precision::Precision;
+
+use super::auth_middleware::CallHeaderAuthenticator;
+use super::flight_sql_server::FlightSqlServiceImpl;
+use crate::write_check::limiter_status;
+
+pub type DoExchangeStream = Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send>>;
+
+/// Acknowledgement of a written batch, encoded in JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeAck {
+    /// Sequence number of the batch in the exchange, starts from 0.
+    pub batch: u64,
+    /// Rows of the batch written.
+    pub rows: u64,
+}
+
+impl ExchangeAck {
+    fn into_flight_data(self) -> Result<FlightData, Status> {
+        let app_metadata = serde_json::to_vec(&self)
+            .map_err(|e| Status::internal(format!("encode ack failed: {e}")))?;
+        Ok(FlightData {
+            app_metadata: app_metadata.into(),
+            ..Default::default()
+        })
+    }
+}
+
+/// The Flight SQL service with the `DoExchange` ingest, the other calls are
+/// served by [`FlightSqlServiceImpl`].
+pub struct FlightSqlServer<T> {
+    inner: FlightSqlServiceImpl<T>,
+}
+
+impl<T> FlightSqlServer<T> {
+    pub fn new(inner: FlightSqlServiceImpl<T>) -> Self {
+        Self { inner }
+    }
+}
+
+#[tonic::async_trait]
+impl<T> FlightService for FlightSqlServer<T>
+where
+    T: CallHeaderAuthenticator + Send + Sync + 'static,
+{
+    type HandshakeStream = <FlightSqlServiceImpl<T> as FlightService>::HandshakeStream;
+    type ListFlightsStream = <FlightSqlServiceImpl<T> as FlightService>::ListFlightsStream;
+    type DoGetStream = <FlightSqlServiceImpl<T> as FlightService>::DoGetStream;
+    type DoPutStream = <FlightSqlServiceImpl<T> as FlightService>::DoPutStream;
+    type DoExchangeStream = DoExchangeStream;
+    type DoActionStream = <FlightSqlServiceImpl<T> as FlightService>::DoActionStream;
+    type ListActionsStream = <FlightSqlServiceImpl<T> as FlightService>::ListActionsStream;
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        self.inner.handshake(request).await
+    }
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        self.inner.list_flights(request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.inner.get_flight_info(request).await
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        self.inner.get_schema(request).await
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        self.inner.do_get(request).await
+    }
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        self.inner.do_put(request).await
+    }
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        self.inner.do_exchange_ingest(request).await
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        self.inner.do_action(request).await
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        self.inner.list_actions(request).await
+    }
+}
+
+/// Write the record batches of the exchange, the first message has been read
+/// to get the target table.
+pub(super) async fn ingest(
+    coord: CoordinatorRef,
+    ctx: Context,
+    first: FlightData,
+    input: Streaming<FlightData>,
+) -> Result<DoExchangeStream, Status> {
+    let table = first
+        .flight_descriptor
+        .as_ref()
+        .and_then(|d| d.path.last())
+        .filter(|t| !t.is_empty())
+        .cloned()
+        .ok_or_else(|| {
+            Status::invalid_argument("the path of the flight descriptor must be the table name")
+        })?;
+    let tenant = ctx.tenant();
+    let db = ctx.database();
+
+    let meta_client = coord
+        .tenant_meta(tenant)
+        .await
+        .ok_or_else(|| Status::not_found(format!("tenant {} not found", tenant)))?;
+    let tenant_id = *meta_client.tenant().id();
+    // The privilege of some columns of the table at least, the columns of the
+    // batches are checked when they are written.
+    check_write_privilege(ctx.user(), tenant_id, db, &table, BTreeSet::new())?;
+
+    let db_schema = meta_client
+        .get_db_schema(db)
+        .map_err(|e| Status::internal(e.to_string()))?
+        .filter(|s| !s.is_hidden())
+        .ok_or_else(|| Status::not_found(format!("database {} not found", db)))?;
+    let table_schema = meta_client
+        .get_tskv_table_schema(db, &table)
+        .map_err(|e| Status::internal(e.to_string()))?
+        .ok_or_else(|| Status::not_found(format!("table {}.{} not found", db, table)))?;
+    debug!("flight exchange ingest into {}.{}.{}", tenant, db, table);
+
+    let limiter = coord
+        .meta_manager()
+        .limiter(tenant)
+        .await
+        .map_err(limiter_status)?;
+    limiter.check_http_writes().await.map_err(limiter_status)?;
+
+    let writer = Arc::new(BatchWriter {
+        coord,
+        user: ctx.user().clone(),
+        tenant_id,
+        table_schema,
+        precision: *db_schema.config.precision(),
+    });
+    let flight_data = futures::stream::once(async { Ok(first) })
+        .chain(input)
+        .and_then(move |data| {
+            let limiter = limiter.clone();
+            async move {
+                limiter
+                    .check_http_data_in(data.data_header.len() + data.data_body.len())
+                    .await
+                    .map_err(limiter_status)?;
+                Ok(data)
+            }
+        })
+        .map_err(FlightError::Tonic);
+    let output = FlightRecordBatchStream::new_from_flight_data(flight_data)
+        .enumerate()
+        .then(move |(seq, batch)| {
+            let writer = writer.clone();
+            async move {
+                let rows = writer.write(batch?).await?;
+                ExchangeAck {
+                    batch: seq as u64,
+                    rows,
+                }
+                .into_flight_data()
+            }
+        });
+
+    Ok(Box::pin(output))
+}
+
+struct BatchWriter {
+    coord: CoordinatorRef,
+    user: User,
+    tenant_id: Oid,
+    table_schema: TskvTableSchemaRef,
+    precision: Precision,
+}
+
+impl BatchWriter {
+    async fn write(&self, batch: RecordBatch) -> Result<u64, Status> {
+        let rows = batch.num_rows() as u64;
+        if rows == 0 {
+            return Ok(0);
+        }
+        let columns = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect();
+        check_write_privilege(
+            &self.user,
+            self.tenant_id,
+            &self.table_schema.db,
+            &self.table_schema.name,
+            columns,
+        )?;
+        let batch = cast_to_table_schema(batch, &self.table_schema)?;
+        self.coord
+            .write_record_batch(self.table_schema.clone(), batch, self.precision, None)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(rows)
+    }
+}
+
+fn check_write_privilege(
+    user: &User,
+    tenant_id: Oid,
+    db: &str,
+    table: &str,
+    columns: BTreeSet<String>,
+) -> Result<(), Status> {
+    let privilege = Privilege::TenantObject(
+        TenantObjectPrivilege::Table(
+            TablePrivilege::new(DatabasePrivilege::Write, Some(columns)),
+            db.to_string(),
+            table.to_string(),
+        ),
+        Some(tenant_id),
+    );
+    if !user.check_privilege(&privilege) {
+        return Err(Status::permission_denied(format!(
+            "insufficient privileges, need {privilege}"
+        )));
+    }
+    Ok(())
+}
+
+/// Cast the columns of the batch to the types of the same named columns of
+/// the table.
+fn cast_to_table_schema(
+    batch: RecordBatch,
+    table_schema: &TskvTableSchemaRef,
+) -> Result<RecordBatch, Status> {
+    let table_arrow_schema = table_schema.to_arrow_schema();
+    let batch_schema = batch.schema();
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch_schema.fields().iter().zip(batch.columns()) {
+        let target = table_arrow_schema
+            .field_with_name(field.name())
+            .map_err(|_| {
+                Status::invalid_argument(format!(
+                    "column {} not found in table {}",
+                    field.name(),
+                    table_schema.name
+                ))
+            })?;
+        let column = if column.data_type() == target.data_type() {
+            column.clone()
+        } else {
+            cast(column, target.data_type()).map_err(|e| {
+                Status::invalid_argument(format!("cast column {} failed: {e}", field.name()))
+            })?
+        };
+        fields.push(target.clone());
+        columns.push(column);
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, Int32Array, StringArray, TimestampSecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+
+    use super::{cast_to_table_schema, ExchangeAck};
+
+    #[test]
+    fn test_cast_to_table_schema() {
+        let table_schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "air".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "station".to_string()),
+                TableColumn::new(
+                    2,
+                    "visibility".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+            ],
+        ));
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("time", DataType::Timestamp(TimeUnit::Second, None), false),
+                Field::new("station", DataType::Utf8, true),
+                Field::new("visibility", DataType::Int32, true),
+            ])),
+            vec![
+                Arc::new(TimestampSecondArray::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["XiaoMaiDao", "LianYunGang"])),
+                Arc::new(Int32Array::from(vec![50, 60])),
+            ],
+        )
+        .unwrap();
+        let batch = cast_to_table_schema(batch, &table_schema).unwrap();
+        let expected = table_schema.to_arrow_schema();
+        for field in batch.schema().fields() {
+            assert_eq!(
+                field.data_type(),
+                expected.field_with_name(field.name()).unwrap().data_type()
+            );
+        }
+        let visibility = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(visibility.values().as_ref(), &[50.0, 60.0]);
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new(
+                "pressure",
+                DataType::Float64,
+                true,
+            )])),
+            vec![Arc::new(Float64Array::from(vec![1.0]))],
+        )
+        .unwrap();
+        assert!(cast_to_table_schema(batch, &table_schema).is_err());
+    }
+
+    #[test]
+    fn test_exchange_ack() {
+        let ack = ExchangeAck {
+            batch: 3,
+            rows: 100,
+        };
+        let data = ack.into_flight_data().unwrap();
+        let decoded: ExchangeAck = serde_json::from_slice(&data.app_metadata).unwrap();
+        assert_eq!(decoded, ack);
+    }
+}
@@ -2,6 +2,8 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
 use arrow_flight::flight_service_server::FlightService;
 use arrow_flight::sql::server::FlightSqlService;
 use arrow_flight::sql::{
@@ -20,51 +22,116 @@ use arrow_flight::{
     utils as flight_utils, Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
     HandshakeRequest, HandshakeResponse, IpcMessage, Ticket,
 };
-use datafusion::arrow::datatypes::{Schema, SchemaRef, ToByteSlice};
-use futures::Stream;
-use http_protocol::header::{DB, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT};
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::array::{new_empty_array, Array, BinaryArray, StringArray};
+use datafusion::arrow::compute::{cast, concat};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, ToByteSlice};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+use futures::{stream, Stream, TryStreamExt};
+use http_protocol::header::{
+    BEARER_PREFIX, CONSISTENT_META, DB, FOLLOWER_READ, IGNORE_RETENTION, MAX_REPLICA_LAG,
+    SESSION_ID, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT, TIME_ZONE,
+};
+use lazy_static::lazy_static;
 use models::auth::user::User;
 use models::oid::UuidGenerator;
 use moka::sync::Cache;
 use prost::bytes::Bytes;
 use prost::Message;
-use spi::query::config::StreamTriggerInterval;
+use spi::query::config::{parse_max_replica_lag, parse_time_zone, StreamTriggerInterval};
 use spi::query::execution::{Output, QueryStateMachineRef};
-use spi::query::logical_planner::Plan;
+use spi::query::logical_planner::{Plan, QueryPlan};
 use spi::server::dbms::DBMSRef;
 use spi::service::protocol::{Context, ContextBuilder, Query, QueryHandle};
 use tonic::metadata::MetadataMap;
 use tonic::{Extensions, Request, Response, Status, Streaming};
+use trace::sampler::{STATEMENT_PROPERTY, STATEMENT_QUERY, TENANT_PROPERTY};
 use trace::span_ext::SpanExt;
 use trace::{debug, Span, SpanContext};
 
 use super::auth_middleware::CallHeaderAuthenticator;
+use super::exchange::{self, DoExchangeStream};
 use crate::flight_sql::auth_middleware::AuthResult;
 use crate::flight_sql::utils;
 use crate::status;
 
 const UNKNOWN_AFFECTED_ROWS_COUNT: i64 = -1;
+/// Custom action that returns the optimized logical plan and physical plan of the sql
+/// in the action body as json, the sql is not executed.
+pub const SQL_PLAN_ACTION_TYPE: &str = "SqlPlan";
+const PREPARED_STATEMENT_CACHE_CAPACITY: u64 = 10_000;
+
+type DoGetStream = Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send>>;
+
+lazy_static! {
+    /// Schemas of the metadata commands defined by Flight SQL.
+    static ref GET_CATALOGS_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![Field::new(
+        "catalog_name",
+        DataType::Utf8,
+        false
+    )]));
+    static ref GET_DB_SCHEMAS_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, false),
+    ]));
+    static ref GET_TABLES_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("table_type", DataType::Utf8, false),
+    ]));
+    static ref GET_TABLES_SCHEMA_WITH_TABLE_SCHEMA: SchemaRef = {
+        let mut fields = GET_TABLES_SCHEMA.fields().to_vec();
+        fields.push(Arc::new(Field::new("table_schema", DataType::Binary, false)));
+        Arc::new(Schema::new(fields))
+    };
+}
 
 pub struct FlightSqlServiceImpl<T> {
     instance: DBMSRef,
+    coord: CoordinatorRef,
     authenticator: T,
     id_generator: UuidGenerator,
     result_cache: Cache<Vec<u8>, (Option<Plan>, QueryStateMachineRef)>,
+    prepared_statements: Cache<Vec<u8>, PreparedStatement>,
+}
+
+/// A statement created by `CreatePreparedStatement`, it is executed with the
+/// parameters bound by the last `DoPut` of it until it's closed or evicted.
+/// The statement is planned again at each execution to see the changes of the
+/// schemas.
+#[derive(Clone)]
+struct PreparedStatement {
+    sql: String,
+    ctx: Context,
+    /// The parameters `$1`, `$2`, ... of the statement, the type of a
+    /// parameter is `Null` if it can not be inferred from the statement.
+    parameter_schema: SchemaRef,
+    parameters: Vec<ScalarValue>,
 }
 
 impl<T> FlightSqlServiceImpl<T> {
-    pub fn new(instance: DBMSRef, authenticator: T) -> Self {
+    pub fn new(instance: DBMSRef, coord: CoordinatorRef, authenticator: T) -> Self {
         let result_cache = Cache::builder()
             // Time to live (TTL): 2 minutes
             // The query results are only cached for 2 minutes and expire after 2 minutes
             .time_to_live(Duration::from_secs(2 * 60))
             .build();
+        let prepared_statements = Cache::builder()
+            // The prepared statements are kept until closed by the client, the ones
+            // not used for 30 minutes or exceeding the capacity are evicted.
+            .max_capacity(PREPARED_STATEMENT_CACHE_CAPACITY)
+            .time_to_idle(Duration::from_secs(30 * 60))
+            .build();
 
         Self {
             instance,
+            coord,
             authenticator,
             id_generator: Default::default(),
             result_cache,
+            prepared_statements,
         }
     }
 }
@@ -77,20 +144,12 @@ where
         &self,
         sql: impl Into<String>,
         req_headers: &MetadataMap,
+        client_addr: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> Result<(Option<Plan>, QueryStateMachineRef), Status> {
-        // auth request
-        let auth_result = {
-            let _span = Span::from_context("authenticate", span_ctx);
-            self.authenticator.authenticate(req_headers).await?
-        };
-        let user = auth_result.identity();
-
-        // construct context by user_info and headers(parse tenant & default database)
-        let ctx = {
-            let _span = Span::from_context("construct context", span_ctx);
-            self.construct_context(user, req_headers)?
-        };
+        let ctx = self
+            .authenticate_and_construct_context(req_headers, client_addr, span_ctx)
+            .await?;
 
         // build query state machine
         let query_state_machine = {
@@ -105,14 +164,195 @@ where
         Ok((logical_plan, query_state_machine))
     }
 
+    /// Ingest the record batches streamed by `DoExchange`, see [`exchange`].
+    pub(super) async fn do_exchange_ingest(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<DoExchangeStream>, Status> {
+        let span = get_span(request.extensions(), "flight sql do_exchange");
+        let client_addr = utils::client_addr(&request);
+        let ctx = self
+            .authenticate_and_construct_context(
+                request.metadata(),
+                client_addr.as_deref(),
+                span.context().as_ref(),
+            )
+            .await?;
+
+        let mut input = request.into_inner();
+        let first = input
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("no flight data received"))?;
+        let output = exchange::ingest(self.coord.clone(), ctx, first, input).await?;
+
+        Ok(Response::new(output))
+    }
+
+    async fn authenticate_and_construct_context(
+        &self,
+        req_headers: &MetadataMap,
+        client_addr: Option<&str>,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<Context, Status> {
+        // auth request
+        let auth_result = {
+            let _span = Span::from_context("authenticate", span_ctx);
+            self.authenticator
+                .authenticate(req_headers, client_addr)
+                .await?
+        };
+        let user = auth_result.identity();
+
+        // construct context by user_info and headers(parse tenant & default database)
+        let mut span = Span::from_context("construct context", span_ctx);
+        let ctx = self.construct_context(user, req_headers, None, client_addr)?;
+        span.add_property(|| (TENANT_PROPERTY, ctx.tenant().to_owned()));
+        Ok(ctx)
+    }
+
+    /// Construct the context to browse the metadata of the catalog(tenant).
+    ///
+    /// Admin can browse all tenants, other users can only browse the tenant they logged in,
+    /// returns None if the user can't browse the catalog.
+    async fn construct_metadata_context(
+        &self,
+        catalog: Option<&str>,
+        req_headers: &MetadataMap,
+        client_addr: Option<&str>,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<Option<Context>, Status> {
+        let ctx = self
+            .authenticate_and_construct_context(req_headers, client_addr, span_ctx)
+            .await?;
+        match catalog {
+            Some(catalog) if catalog != ctx.tenant() => {
+                if catalog.is_empty() || !ctx.user().desc().is_admin() {
+                    return Ok(None);
+                }
+                let ctx = self.construct_context(
+                    ctx.user().clone(),
+                    req_headers,
+                    Some(catalog.to_string()),
+                    client_addr,
+                )?;
+                Ok(Some(ctx))
+            }
+            _ => Ok(Some(ctx)),
+        }
+    }
+
+    async fn execute_metadata_query(
+        &self,
+        sql: String,
+        ctx: Context,
+        schema: SchemaRef,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<RecordBatch, Status> {
+        let query = Query::new(ctx, sql);
+        let batches = self
+            .instance
+            .execute(&query, span_ctx)
+            .await
+            .map_err(|e| status!("Execute metadata query", e))?
+            .result()
+            .chunk_result()
+            .await
+            .map_err(|e| status!("Could not chunk result", e))?;
+
+        // The columns of the query are in the same order as the schema.
+        let columns = (0..schema.fields().len())
+            .map(|i| {
+                let arrays = batches
+                    .iter()
+                    .map(|b| b.column(i).as_ref())
+                    .collect::<Vec<_>>();
+                if arrays.is_empty() {
+                    Ok(new_empty_array(schema.field(i).data_type()))
+                } else {
+                    concat(&arrays)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| status!("Could not concat result", e))?;
+        RecordBatch::try_new(schema, columns).map_err(|e| status!("Could not build result", e))
+    }
+
+    /// Plans `SELECT *` of each table in the result of `GetTables` to get the arrow
+    /// schemas, which are encoded as IPC messages.
+    async fn table_schemas(
+        &self,
+        tables: &RecordBatch,
+        ctx: Context,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<BinaryArray, Status> {
+        let column = |i: usize| {
+            tables
+                .column(i)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| Status::internal("Unexpected type of the result of GetTables"))
+        };
+        let (databases, table_names) = (column(1)?, column(2)?);
+
+        let mut table_schemas = Vec::with_capacity(tables.num_rows());
+        for (database, table) in databases.iter().zip(table_names.iter()) {
+            let (Some(database), Some(table)) = (database, table) else {
+                return Err(Status::internal(
+                    "Unexpected null in the result of GetTables",
+                ));
+            };
+            let sql = format!(
+                "SELECT * FROM {}.{}",
+                quote_identifier(database),
+                quote_identifier(table)
+            );
+            let query_state_machine = self
+                .build_query_state_machine(sql, ctx.clone(), span_ctx)
+                .await?;
+            let schema = self
+                .build_logical_plan(query_state_machine)
+                .await?
+                .map(|e| e.schema())
+                .unwrap_or(Arc::new(Schema::empty()));
+            let IpcMessage(schema) = utils::schema_to_ipc_message(schema.as_ref())
+                .map_err(|e| status!("Schema to ipc message", e))?;
+            table_schemas.push(schema);
+        }
+
+        Ok(BinaryArray::from_iter_values(table_schemas))
+    }
+
+    /// Returns the flight info of a metadata command, the command itself is the ticket
+    /// and the result set is built by the corresponding `do_get_*` method.
+    async fn metadata_flight_info(
+        &self,
+        command: impl ProstMessageExt,
+        schema: &Schema,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let client_addr = utils::client_addr(&request);
+        self.authenticator
+            .authenticate(request.metadata(), client_addr.as_deref())
+            .await?;
+        let flight_info = self.construct_flight_info(
+            command.as_any().encode_to_vec(),
+            schema,
+            UNKNOWN_AFFECTED_ROWS_COUNT,
+            request.into_inner(),
+        )?;
+        Ok(Response::new(flight_info))
+    }
+
     async fn pre_precess_statement_query_req_and_save(
         &self,
         sql: impl Into<String>,
         req_headers: &MetadataMap,
+        client_addr: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> Result<(Vec<u8>, SchemaRef), Status> {
         let (logical_plan, query_state_machine) = self
-            .pre_precess_statement_query_req(sql, req_headers, span_ctx)
+            .pre_precess_statement_query_req(sql, req_headers, client_addr, span_ctx)
             .await?;
 
         let schema = logical_plan
@@ -136,8 +376,14 @@ where
         request: Request<FlightDescriptor>,
         span_ctx: Option<&SpanContext>,
     ) -> Result<Response<FlightInfo>, Status> {
+        let client_addr = utils::client_addr(&request);
         let (result_ident, schema) = self
-            .pre_precess_statement_query_req_and_save(sql, request.metadata(), span_ctx)
+            .pre_precess_statement_query_req_and_save(
+                sql,
+                request.metadata(),
+                client_addr.as_deref(),
+                span_ctx,
+            )
             .await?;
 
         let ticket = TicketStatementQuery {
@@ -178,9 +424,16 @@ where
         Ok(flight_info)
     }
 
-    fn construct_context(&self, user: User, metadata: &MetadataMap) -> Result<Context, Status> {
+    /// Construct the context by the headers, `tenant` overrides the tenant in headers.
+    fn construct_context(
+        &self,
+        user: User,
+        metadata: &MetadataMap,
+        tenant: Option<String>,
+        client_addr: Option<&str>,
+    ) -> Result<Context, Status> {
         // parse tenant & default database
-        let tenant = utils::get_value_from_header(metadata, TENANT, "");
+        let tenant = tenant.or_else(|| utils::get_value_from_header(metadata, TENANT, ""));
         let db = utils::get_value_from_header(metadata, DB, "");
         let target_partitions = utils::get_value_from_header(metadata, TARGET_PARTITIONS, "")
             .map(|e| e.parse::<usize>())
@@ -201,11 +454,51 @@ where
                         STREAM_TRIGGER_INTERVAL, e
                     ))
                 })?;
+        let consistent_meta = utils::get_value_from_header(metadata, CONSISTENT_META, "")
+            .map(|e| e.parse::<bool>())
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", CONSISTENT_META, e))
+            })?;
+        let ignore_retention = utils::get_value_from_header(metadata, IGNORE_RETENTION, "")
+            .map(|e| e.parse::<bool>())
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", IGNORE_RETENTION, e))
+            })?;
+        let max_replica_lag = utils::get_value_from_header(metadata, MAX_REPLICA_LAG, "")
+            .map(|e| parse_max_replica_lag(&e))
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", MAX_REPLICA_LAG, e))
+            })?;
+        let follower_read = utils::get_value_from_header(metadata, FOLLOWER_READ, "")
+            .map(|e| e.parse::<bool>())
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", FOLLOWER_READ, e))
+            })?;
+        let time_zone = utils::get_value_from_header(metadata, TIME_ZONE, "")
+            .map(|e| parse_time_zone(&e))
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", TIME_ZONE, e))
+            })?;
+        // the session is the one given by the client, or the one of the bearer token
+        let session_id = utils::get_value_from_header(metadata, SESSION_ID, "")
+            .or_else(|| utils::get_value_from_auth_header(metadata, BEARER_PREFIX));
         let ctx = ContextBuilder::new(user)
             .with_tenant(tenant)
             .with_database(db)
             .with_target_partitions(target_partitions)
             .with_stream_trigger_interval(stream_trigger_interval)
+            .with_consistent_meta(consistent_meta)
+            .with_ignore_retention(ignore_retention)
+            .with_max_replica_lag(max_replica_lag)
+            .with_follower_read(follower_read)
+            .with_time_zone(time_zone)
+            .with_client_addr(client_addr.map(|addr| addr.to_string()))
+            .with_session_id(session_id)
             .build();
 
         Ok(ctx)
@@ -288,22 +581,236 @@ where
         let query_result = self
             .execute_logical_plan(logical_plan, query_state_machine)
             .await?;
-        let output = query_result.result();
 
-        let schema = (*output.schema()).clone();
-        let batches = output
-            .chunk_result()
-            .await
-            .map_err(|e| status!("Could not chunk result", e))?;
+        fetch_result_set(query_result).await
+    }
+
+    /// Plan the statement and keep it until closed, returns the handle of the
+    /// statement, the schema of the result set and the schema of the parameters
+    /// at the time of creation.
+    async fn create_prepared_statement(
+        &self,
+        sql: String,
+        req_headers: &MetadataMap,
+        client_addr: Option<&str>,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<(Vec<u8>, SchemaRef, SchemaRef), Status> {
+        let (plan, query_state_machine) = self
+            .pre_precess_statement_query_req(sql.clone(), req_headers, client_addr, span_ctx)
+            .await?;
+
+        let schema = plan
+            .as_ref()
+            .map(|e| e.schema())
+            .unwrap_or(Arc::new(Schema::empty()));
+        let parameter_schema = Arc::new(plan_parameter_schema(plan.as_ref())?);
+
+        let statement_handle = self.id_generator.next_id().to_le_bytes().to_vec();
+        self.prepared_statements.insert(
+            statement_handle.clone(),
+            PreparedStatement {
+                sql,
+                ctx: query_state_machine.query.context().clone(),
+                parameter_schema: parameter_schema.clone(),
+                parameters: vec![],
+            },
+        );
+
+        Ok((statement_handle, schema, parameter_schema))
+    }
+
+    fn get_prepared_statement(&self, statement_handle: &[u8]) -> Result<PreparedStatement, Status> {
+        self.prepared_statements
+            .get(statement_handle)
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "The prepared statement({:?}) does not exist or has been closed",
+                    statement_handle
+                ))
+            })
+    }
+
+    /// Plan the prepared statement with the current schemas, it fails if the
+    /// parameters of the statement are changed by the schemas since created.
+    async fn plan_prepared_statement(
+        &self,
+        statement: &PreparedStatement,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<(Option<Plan>, QueryStateMachineRef), Status> {
+        let query_state_machine = self
+            .build_query_state_machine(statement.sql.clone(), statement.ctx.clone(), span_ctx)
+            .await?;
+        let plan = self.build_logical_plan(query_state_machine.clone()).await?;
+        if plan_parameter_schema(plan.as_ref())? != *statement.parameter_schema {
+            return Err(Status::failed_precondition(
+                "The parameters of the prepared statement are changed by the schemas, prepare it again",
+            ));
+        }
+
+        Ok((plan, query_state_machine))
+    }
+
+    /// Execute the prepared statement with the bound parameters, each execution
+    /// is a new query planned with the current schemas.
+    async fn execute_prepared_statement(
+        &self,
+        statement_handle: &[u8],
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<QueryHandle, Status> {
+        let statement = self.get_prepared_statement(statement_handle)?;
+        let (plan, query_state_machine) =
+            self.plan_prepared_statement(&statement, span_ctx).await?;
+
+        let plan = bind_parameters(plan, &statement.parameter_schema, statement.parameters)?;
+        self.execute_logical_plan(plan, query_state_machine).await
+    }
+}
+
+/// The schema of the parameters `$1`, `$2`, ... of the query, in the order of
+/// the parameters.
+fn plan_parameter_schema(plan: Option<&Plan>) -> Result<Schema, Status> {
+    let df_plan = match plan {
+        Some(Plan::Query(QueryPlan { df_plan, .. })) => df_plan,
+        _ => return Ok(Schema::empty()),
+    };
+
+    let mut parameters = df_plan
+        .get_parameter_types()
+        .map_err(|e| status!("Get parameter types", e))?
+        .into_iter()
+        .map(|(id, data_type)| {
+            let index = id
+                .strip_prefix('$')
+                .and_then(|i| i.parse::<usize>().ok())
+                .ok_or_else(|| {
+                    Status::invalid_argument(format!(
+                        "Invalid parameter {id}, expected $1, $2, ..."
+                    ))
+                })?;
+            Ok((index, id, data_type))
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+    parameters.sort_by_key(|(index, ..)| *index);
+
+    let fields = parameters
+        .into_iter()
+        .enumerate()
+        .map(|(i, (index, id, data_type))| {
+            if index != i + 1 {
+                return Err(Status::invalid_argument(format!(
+                    "Parameter ${} is missing",
+                    i + 1
+                )));
+            }
+            Ok(Field::new(id, data_type.unwrap_or(DataType::Null), true))
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    Ok(Schema::new(fields))
+}
 
-        let flight_data = flight_utils::batches_to_flight_data(schema, batches)
-            .map_err(|e| status!("Could not convert batches", e))?
-            .into_iter()
-            .map(Ok);
-        let stream: Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send>> =
-            Box::pin(futures::stream::iter(flight_data));
-        Ok(stream)
+/// Take the parameters from the only row of the batches put by the client,
+/// each column is casted to the type of the parameter if it's known.
+fn parameters_from_batches(
+    parameter_schema: &Schema,
+    batches: &[RecordBatch],
+) -> Result<Vec<ScalarValue>, Status> {
+    let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+    let batch = match batches.iter().find(|b| b.num_rows() > 0) {
+        Some(batch) if rows == 1 => batch,
+        _ => {
+            return Err(Status::invalid_argument(format!(
+                "Expected one row of parameters, got {rows}"
+            )))
+        }
+    };
+    if batch.num_columns() != parameter_schema.fields().len() {
+        return Err(Status::invalid_argument(format!(
+            "Expected {} parameters, got {}",
+            parameter_schema.fields().len(),
+            batch.num_columns()
+        )));
     }
+
+    batch
+        .columns()
+        .iter()
+        .zip(parameter_schema.fields())
+        .map(|(column, field)| {
+            let column = match field.data_type() {
+                DataType::Null => column.clone(),
+                data_type if data_type == column.data_type() => column.clone(),
+                data_type => cast(column, data_type).map_err(|e| {
+                    Status::invalid_argument(format!("Cast parameter {} failed: {e}", field.name()))
+                })?,
+            };
+            ScalarValue::try_from_array(&column, 0)
+                .map_err(|e| status!("Convert parameter to scalar", e))
+        })
+        .collect()
+}
+
+/// Replace the parameters in the plan by the bound values.
+fn bind_parameters(
+    plan: Option<Plan>,
+    parameter_schema: &Schema,
+    parameters: Vec<ScalarValue>,
+) -> Result<Option<Plan>, Status> {
+    if parameter_schema.fields().is_empty() {
+        return Ok(plan);
+    }
+    if parameters.len() != parameter_schema.fields().len() {
+        return Err(Status::invalid_argument(format!(
+            "Expected {} parameters, got {}, the parameters must be bound before executing",
+            parameter_schema.fields().len(),
+            parameters.len()
+        )));
+    }
+
+    match plan {
+        Some(Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan,
+        })) => {
+            let df_plan = df_plan
+                .with_param_values(parameters)
+                .map_err(|e| Status::invalid_argument(format!("Bind parameters failed: {e}")))?;
+            Ok(Some(Plan::Query(QueryPlan {
+                df_plan,
+                is_tag_scan,
+            })))
+        }
+        plan => Ok(plan),
+    }
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+async fn fetch_result_set(query_result: QueryHandle) -> Result<DoGetStream, Status> {
+    let output = query_result.result();
+
+    let schema = (*output.schema()).clone();
+    let batches = output
+        .chunk_result()
+        .await
+        .map_err(|e| status!("Could not chunk result", e))?;
+
+    batches_to_stream(schema, batches)
+}
+
+fn batches_to_stream(schema: Schema, batches: Vec<RecordBatch>) -> Result<DoGetStream, Status> {
+    let flight_data = flight_utils::batches_to_flight_data(schema, batches)
+        .map_err(|e| status!("Could not convert batches", e))?
+        .into_iter()
+        .map(Ok);
+    let stream: DoGetStream = Box::pin(futures::stream::iter(flight_data));
+    Ok(stream)
 }
 
 /// use jdbc to execute statement query:
@@ -341,10 +848,11 @@ where
 /// .   }
 /// ```
 /// 1. do_handshake: basic auth -> baerar token
-/// 2. do_action_create_prepared_statement: sql(baerar token) -> sql
-/// 3. do_put_prepared_statement_update: not use
-/// 4. get_flight_info_prepared_statement: sql(baerar token) -> address of resut set
-/// 5. do_get_statement: address of resut set(baerar token) -> resut set stream
+/// 2. do_action_create_prepared_statement: sql(baerar token) -> handle of prepared statement, parameter schema
+/// 3. do_put_prepared_statement_query: parameters(baerar token) -> bound to the prepared statement, if any
+/// 4. get_flight_info_prepared_statement: handle of prepared statement(baerar token) -> address of resut set
+/// 5. do_get_prepared_statement: address of resut set(baerar token) -> resut set stream
+/// 6. do_action_close_prepared_statement: handle of prepared statement(baerar token)
 /// ```
 ///
 /// use flight sql to execute statement query:
@@ -375,7 +883,11 @@ where
         let _span_recorder = get_span(request.extensions(), "flight sql do_handshake");
 
         let meta_data = request.metadata();
-        let auth_result = self.authenticator.authenticate(meta_data).await?;
+        let client_addr = utils::client_addr(&request);
+        let auth_result = self
+            .authenticator
+            .authenticate(meta_data, client_addr.as_deref())
+            .await?;
 
         let output: Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>> =
             Box::pin(futures::stream::empty());
@@ -429,7 +941,10 @@ where
         );
 
         let statement_handle = query.prepared_statement_handle.to_byte_slice();
-        let (plan, _) = self.get_plan_and_qsm(statement_handle, span.context())?;
+        let statement = self.get_prepared_statement(statement_handle)?;
+        let (plan, _) = self
+            .plan_prepared_statement(&statement, span.context().as_ref())
+            .await?;
         let schema = plan
             .map(|e| e.schema())
             .unwrap_or(Arc::new(Schema::empty()));
@@ -445,6 +960,7 @@ where
         Ok(Response::new(flight_info))
     }
 
+    /// Fetch the tenants as catalogs, see [`Self::do_get_catalogs`].
     async fn get_flight_info_catalogs(
         &self,
         query: CommandGetCatalogs,
@@ -455,21 +971,11 @@ where
             query, request
         );
 
-        let span = get_span(request.extensions(), "flight sql get_flight_info_catalogs");
-
-        self.precess_flight_info_req(
-            "SELECT 
-                TENANT_NAME AS CATALOG_NAME 
-            FROM 
-                CLUSTER_SCHEMA.TENANTS 
-            ORDER BY 
-                CATALOG_NAME",
-            request,
-            span.context().as_ref(),
-        )
-        .await
+        self.metadata_flight_info(query, &GET_CATALOGS_SCHEMA, request)
+            .await
     }
 
+    /// Fetch the databases as schemas, see [`Self::do_get_schemas`].
     async fn get_flight_info_schemas(
         &self,
         query: CommandGetDbSchemas,
@@ -480,41 +986,11 @@ where
             query, request
         );
 
-        let span = get_span(request.extensions(), "flight sql get_flight_info_catalogs");
-
-        let CommandGetDbSchemas {
-            catalog,
-            db_schema_filter_pattern,
-        } = query;
-
-        let mut filters = vec![];
-        let _ = catalog.map(|e| filters.push(format!("TENANT_NAME = '{}'", e)));
-        let _ =
-            db_schema_filter_pattern.map(|e| filters.push(format!("DATABASE_NAME LIKE '{e}'",)));
-
-        let filter = if filters.is_empty() {
-            "".to_string()
-        } else {
-            format!("WHERE {}", filters.join(" AND "))
-        };
-
-        self.precess_flight_info_req(
-            format!(
-                "SELECT
-                    DATABASE_NAME AS DB_SCHEMA_NAME,
-                    TENANT_NAME AS CATALOG_NAME
-                FROM
-                    INFORMATION_SCHEMA.DATABASES
-                {filter}
-                ORDER BY
-                    CATALOG_NAME, DB_SCHEMA_NAME"
-            ),
-            request,
-            span.context().as_ref(),
-        )
-        .await
+        self.metadata_flight_info(query, &GET_DB_SCHEMAS_SCHEMA, request)
+            .await
     }
 
+    /// Fetch the tables, see [`Self::do_get_tables`].
     async fn get_flight_info_tables(
         &self,
         query: CommandGetTables,
@@ -525,51 +1001,12 @@ where
             query, request
         );
 
-        let span = get_span(request.extensions(), "flight sql get_flight_info_tables");
-
-        let CommandGetTables {
-            catalog,
-            db_schema_filter_pattern,
-            table_name_filter_pattern,
-            table_types,
-            include_schema: _,
-        } = query;
-
-        let mut filters = vec![];
-        let _ = catalog.map(|e| filters.push(format!("TABLE_TENANT = '{}'", e)));
-        let _ =
-            db_schema_filter_pattern.map(|e| filters.push(format!("TABLE_DATABASE LIKE '{e}'")));
-        let _ = table_name_filter_pattern.map(|e| filters.push(format!("TABLE_NAME LIKE '{e}'")));
-        if !table_types.is_empty() {
-            let table_types = table_types
-                .iter()
-                .map(|e| format!("'{}'", e))
-                .collect::<Vec<_>>()
-                .join(",");
-            filters.push(format!("TABLE_TYPE IN ({})", table_types));
-        }
-
-        let filter = if filters.is_empty() {
-            "".to_string()
+        let schema = if query.include_schema {
+            GET_TABLES_SCHEMA_WITH_TABLE_SCHEMA.clone()
         } else {
-            format!("WHERE {}", filters.join(" AND "))
+            GET_TABLES_SCHEMA.clone()
         };
-
-        let sql = format!(
-            "SELECT
-                TABLE_TENANT as CATALOG_NAME,
-                TABLE_DATABASE as DB_SCHEMA_NAME,
-                TABLE_NAME,
-                TABLE_TYPE
-            FROM
-                INFORMATION_SCHEMA.TABLES
-            {filter}
-            ORDER BY
-                TABLE_TYPE, CATALOG_NAME, DB_SCHEMA_NAME, TABLE_NAME"
-        );
-
-        self.precess_flight_info_req(sql, request, span.context().as_ref())
-            .await
+        self.metadata_flight_info(query, &schema, request).await
     }
 
     async fn get_flight_info_table_types(
@@ -722,19 +1159,15 @@ where
 
         let prepared_statement_handle = query.prepared_statement_handle.to_byte_slice();
 
-        let output = self
-            .execute_and_fetch_result_set(prepared_statement_handle, span.context().as_ref())
+        let query_result = self
+            .execute_prepared_statement(prepared_statement_handle, span.context().as_ref())
             .await?;
-
-        // clear cache of this query
-        self.result_cache
-            .invalidate(prepared_statement_handle.to_byte_slice());
+        let output = fetch_result_set(query_result).await?;
 
         Ok(Response::new(output))
     }
 
-    /// TODO support
-    /// wait for <https://github.com/cnosdb/cnosdb/issues/642>
+    /// Admin gets all tenants, other users only get the tenant they logged in.
     async fn do_get_catalogs(
         &self,
         query: CommandGetCatalogs,
@@ -745,11 +1178,39 @@ where
             query, request
         );
 
-        Err(Status::unimplemented("do_get_catalogs not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_catalogs");
+        let span_ctx = span.context();
+
+        let client_addr = utils::client_addr(&request);
+        let ctx = self
+            .authenticate_and_construct_context(
+                request.metadata(),
+                client_addr.as_deref(),
+                span_ctx.as_ref(),
+            )
+            .await?;
+        let batch = if ctx.user().desc().is_admin() {
+            let sql = "SELECT tenant_name FROM cluster_schema.tenants ORDER BY tenant_name";
+            self.execute_metadata_query(
+                sql.to_string(),
+                ctx,
+                GET_CATALOGS_SCHEMA.clone(),
+                span_ctx.as_ref(),
+            )
+            .await?
+        } else {
+            RecordBatch::try_new(
+                GET_CATALOGS_SCHEMA.clone(),
+                vec![Arc::new(StringArray::from(vec![ctx.tenant()]))],
+            )
+            .map_err(|e| status!("Could not build result", e))?
+        };
+
+        let output = batches_to_stream(GET_CATALOGS_SCHEMA.as_ref().clone(), vec![batch])?;
+        Ok(Response::new(output))
     }
 
-    /// TODO support
-    /// wait for <https://github.com/cnosdb/cnosdb/issues/642>
+    /// The databases that the user can read in the catalog(tenant).
     async fn do_get_schemas(
         &self,
         query: CommandGetDbSchemas,
@@ -757,11 +1218,49 @@ where
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         debug!("do_get_schemas: query: {:?}, request: {:?}", query, request);
 
-        Err(Status::unimplemented("do_get_schemas not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_schemas");
+        let span_ctx = span.context();
+
+        let CommandGetDbSchemas {
+            catalog,
+            db_schema_filter_pattern,
+        } = query;
+
+        let schema = GET_DB_SCHEMAS_SCHEMA.clone();
+        let mut batches = vec![];
+        let client_addr = utils::client_addr(&request);
+        if let Some(ctx) = self
+            .construct_metadata_context(
+                catalog.as_deref(),
+                request.metadata(),
+                client_addr.as_deref(),
+                span_ctx.as_ref(),
+            )
+            .await?
+        {
+            let filter = db_schema_filter_pattern
+                .map(|e| format!("WHERE database_name LIKE {}", quote_literal(&e)))
+                .unwrap_or_default();
+            let sql = format!(
+                "SELECT tenant_name, database_name
+                FROM
+                    information_schema.databases
+                {filter}
+                ORDER BY
+                    tenant_name, database_name"
+            );
+            batches.push(
+                self.execute_metadata_query(sql, ctx, schema.clone(), span_ctx.as_ref())
+                    .await?,
+            );
+        }
+
+        let output = batches_to_stream(schema.as_ref().clone(), batches)?;
+        Ok(Response::new(output))
     }
 
-    /// TODO support
-    /// wait for `<https://github.com/cnosdb/cnosdb/issues/642>`
+    /// The tables that the user can read in the catalog(tenant),
+    /// with the arrow schema of each table if `include_schema` is set.
     async fn do_get_tables(
         &self,
         query: CommandGetTables,
@@ -769,7 +1268,88 @@ where
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         debug!("do_get_tables: query: {:?}, request: {:?}", query, request);
 
-        Err(Status::unimplemented("do_get_tables not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_tables");
+        let span_ctx = span.context();
+
+        let CommandGetTables {
+            catalog,
+            db_schema_filter_pattern,
+            table_name_filter_pattern,
+            table_types,
+            include_schema,
+        } = query;
+
+        let schema = if include_schema {
+            GET_TABLES_SCHEMA_WITH_TABLE_SCHEMA.clone()
+        } else {
+            GET_TABLES_SCHEMA.clone()
+        };
+        let mut batches = vec![];
+        let client_addr = utils::client_addr(&request);
+        if let Some(ctx) = self
+            .construct_metadata_context(
+                catalog.as_deref(),
+                request.metadata(),
+                client_addr.as_deref(),
+                span_ctx.as_ref(),
+            )
+            .await?
+        {
+            let mut filters = vec![];
+            let _ = db_schema_filter_pattern
+                .map(|e| filters.push(format!("table_database LIKE {}", quote_literal(&e))));
+            let _ = table_name_filter_pattern
+                .map(|e| filters.push(format!("table_name LIKE {}", quote_literal(&e))));
+            if !table_types.is_empty() {
+                let table_types = table_types
+                    .iter()
+                    .map(|e| quote_literal(e))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                filters.push(format!("table_type IN ({})", table_types));
+            }
+
+            let filter = if filters.is_empty() {
+                "".to_string()
+            } else {
+                format!("WHERE {}", filters.join(" AND "))
+            };
+
+            let sql = format!(
+                "SELECT
+                    table_tenant,
+                    table_database,
+                    table_name,
+                    table_type
+                FROM
+                    information_schema.tables
+                {filter}
+                ORDER BY
+                    table_tenant, table_database, table_name, table_type"
+            );
+            let batch = self
+                .execute_metadata_query(
+                    sql,
+                    ctx.clone(),
+                    GET_TABLES_SCHEMA.clone(),
+                    span_ctx.as_ref(),
+                )
+                .await?;
+
+            let batch = if include_schema {
+                let table_schemas = self.table_schemas(&batch, ctx, span_ctx.as_ref()).await?;
+                let mut columns = batch.columns().to_vec();
+                columns.push(Arc::new(table_schemas));
+                RecordBatch::try_new(schema.clone(), columns)
+                    .map_err(|e| status!("Could not build result", e))?
+            } else {
+                batch
+            };
+            batches.push(batch);
+        }
+
+        let output = batches_to_stream(schema.as_ref().clone(), batches)?;
+        Ok(Response::new(output))
     }
 
     /// TODO support
@@ -869,9 +1449,15 @@ where
         // ignore transaction_id
         let CommandStatementUpdate { query, .. } = ticket;
         let req_headers = request.metadata();
+        let client_addr = utils::client_addr(&request);
 
         let (logical_plan, query_state_machine) = self
-            .pre_precess_statement_query_req(query, req_headers, span_ctx.as_ref())
+            .pre_precess_statement_query_req(
+                query,
+                req_headers,
+                client_addr.as_deref(),
+                span_ctx.as_ref(),
+            )
             .await?;
 
         // execute plan
@@ -884,7 +1470,9 @@ where
         Ok(affected_rows)
     }
 
-    /// not support
+    /// Bind the parameters of the prepared statement, the client puts one row
+    /// whose columns are the values of the parameters `$1`, `$2`, ... in order.
+    /// The parameters are kept for the following executions until bound again.
     async fn do_put_prepared_statement_query(
         &self,
         query: CommandPreparedStatementQuery,
@@ -895,16 +1483,37 @@ where
             query, request
         );
 
-        Err(Status::unimplemented(
-            "do_put_prepared_statement_query not implemented",
-        ))
+        let _span_recorder = get_span(
+            request.extensions(),
+            "flight sql do_put_prepared_statement_query",
+        );
+
+        let statement_handle = query.prepared_statement_handle.to_byte_slice();
+        let statement = self.get_prepared_statement(statement_handle)?;
+
+        let flight_data = request.into_inner().map_err(FlightError::Tonic);
+        let batches = FlightRecordBatchStream::new_from_flight_data(flight_data)
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| Status::invalid_argument(format!("Decode parameters failed: {e}")))?;
+        let parameters = parameters_from_batches(&statement.parameter_schema, &batches)?;
+
+        self.prepared_statements.insert(
+            statement_handle.to_vec(),
+            PreparedStatement {
+                parameters,
+                ..statement
+            },
+        );
+
+        let output: <Self as FlightService>::DoPutStream = Box::pin(futures::stream::empty());
+        Ok(Response::new(output))
     }
 
-    /// Execute the query and return the number of affected rows.
-    /// The prepared statement can be reused afterwards.
+    /// Execute the prepared statement with the bound parameters and return the
+    /// number of affected rows. The prepared statement can be reused afterwards.
     ///
-    /// Prepared statement is not supported,
-    /// because ad-hoc statement of flight jdbc needs to call this interface, so it is simple to implement
+    /// The ad-hoc statement of flight jdbc also calls this interface.
     async fn do_put_prepared_statement_update(
         &self,
         query: CommandPreparedStatementUpdate,
@@ -919,17 +1528,18 @@ where
             request.extensions(),
             "flight sql do_put_prepared_statement_update",
         );
-        let (plan, query_machine) =
-            self.get_plan_and_qsm(prepared_statement_ident, span.context())?;
-        // execute plan
-        let query_result = self.execute_logical_plan(plan, query_machine).await?;
+        let query_result = self
+            .execute_prepared_statement(prepared_statement_ident, span.context().as_ref())
+            .await?;
         let output = query_result.result();
         Ok(output.affected_rows().await)
     }
 
-    /// Prepared statement is not supported,
-    /// because ad-hoc statement of flight jdbc needs to call this interface,
-    /// so directly return the sql as the result.
+    /// Create a prepared statement, the statement is planned and kept until
+    /// closed, the parameters `$1`, `$2`, ... of it are returned as the
+    /// parameter schema, bound by [`Self::do_put_prepared_statement_query`].
+    ///
+    /// The ad-hoc statement of flight jdbc also calls this interface.
     async fn do_action_create_prepared_statement(
         &self,
         query: ActionCreatePreparedStatementRequest,
@@ -947,31 +1557,33 @@ where
         // ignore transaction_id
         let ActionCreatePreparedStatementRequest { query: sql, .. } = query;
 
-        let (result_ident, schema) = self
-            .pre_precess_statement_query_req_and_save(
+        let client_addr = utils::client_addr(&request);
+        let (statement_handle, schema, parameter_schema) = self
+            .create_prepared_statement(
                 sql,
                 request.metadata(),
+                client_addr.as_deref(),
                 span.context().as_ref(),
             )
             .await?;
 
         let IpcMessage(dataset_schema) = utils::schema_to_ipc_message(schema.as_ref())
             .map_err(|e| status!("Schema to ipc message", e))?;
+        let IpcMessage(parameter_schema) = utils::schema_to_ipc_message(parameter_schema.as_ref())
+            .map_err(|e| status!("Schema to ipc message", e))?;
         // JDBC:
         //    - schema.getFields().isEmpty() ? StatementType.UPDATE : StatementType.SELECT;
         //    - long updateCount = statementType.equals(StatementType.UPDATE) ? preparedStatement.executeUpdate() : -1L;
         let result = ActionCreatePreparedStatementResult {
-            prepared_statement_handle: result_ident.into(),
+            prepared_statement_handle: statement_handle.into(),
             dataset_schema,
-            ..Default::default()
+            parameter_schema,
         };
 
         Ok(result)
     }
 
     /// Close a previously created prepared statement.
-    ///
-    /// Empty logic, because we not save created prepared statement.
     async fn do_action_close_prepared_statement(
         &self,
         query: ActionClosePreparedStatementRequest,
@@ -982,6 +1594,9 @@ where
             query, request
         );
 
+        self.prepared_statements
+            .invalidate(query.prepared_statement_handle.to_byte_slice());
+
         Ok(())
     }
 
@@ -1042,6 +1657,45 @@ where
         Err(Status::unimplemented("Implement do_action_cancel_query"))
     }
 
+    async fn do_action_fallback(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<<Self as FlightService>::DoActionStream>, Status> {
+        debug!("do_action_fallback: request: {:?}", request);
+
+        if request.get_ref().r#type != SQL_PLAN_ACTION_TYPE {
+            return Err(Status::invalid_argument(format!(
+                "do_action: The defined request is invalid: {:?}",
+                request.get_ref().r#type
+            )));
+        }
+
+        let span = get_span(request.extensions(), "flight sql do_action_sql_plan");
+        let sql = String::from_utf8(request.get_ref().body.to_vec())
+            .map_err(|e| Status::invalid_argument(format!("sql is not utf8: {}", e)))?;
+
+        let client_addr = utils::client_addr(&request);
+        let auth_result = self
+            .authenticator
+            .authenticate(request.metadata(), client_addr.as_deref())
+            .await?;
+        let ctx = self.construct_context(
+            auth_result.identity(),
+            request.metadata(),
+            None,
+            client_addr.as_deref(),
+        )?;
+        let plan = self
+            .instance
+            .explain(&Query::new(ctx, sql), span.context().as_ref())
+            .await
+            .map_err(|e| status!("Explain sql", e))?;
+        let body = serde_json::to_vec(&plan).map_err(|e| status!("Serialize sql plan", e))?;
+
+        let output = stream::iter(vec![Ok(arrow_flight::Result { body: body.into() })]);
+        Ok(Response::new(Box::pin(output)))
+    }
+
     async fn do_put_substrait_plan(
         &self,
         _ticket: CommandStatementSubstraitPlan,
@@ -1086,6 +1740,7 @@ where
 fn get_span(extensions: &Extensions, child_span_name: &'static str) -> Span {
     let span_context = extensions.get::<SpanContext>();
     Span::from_context(child_span_name, span_context)
+        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_QUERY))
 }
 
 #[cfg(test)]
@@ -1093,27 +1748,40 @@ mod test {
     use std::collections::HashMap;
     use std::sync::Arc;
 
+    use arrow_flight::encode::FlightDataEncoderBuilder;
     use arrow_flight::flight_service_client::FlightServiceClient;
     use arrow_flight::flight_service_server::FlightServiceServer;
     use arrow_flight::sql::client::FlightSqlServiceClient;
     use arrow_flight::sql::{Any, CommandStatementQuery};
     use arrow_flight::utils::flight_data_to_batches;
-    use arrow_flight::{FlightDescriptor, HandshakeRequest, IpcMessage};
+    use arrow_flight::{FlightData, FlightDescriptor, HandshakeRequest, IpcMessage};
+    use coordinator::service_mock::{
+        MockCoordinator, WITH_LIMITED_WRITES_TENANT_FOR_TEST, WITH_NONEMPTY_DATABASE_FOR_TEST,
+        WITH_TABLE_FOR_TEST,
+    };
+    use datafusion::arrow::array::{Float64Array, Int64Array, StringArray};
     use datafusion::arrow::buffer::Buffer;
-    use datafusion::arrow::datatypes::Schema;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
     use datafusion::arrow::{self, ipc};
+    use datafusion::execution::context::SessionContext;
+    use datafusion::scalar::ScalarValue;
     use futures::{StreamExt, TryStreamExt};
-    use http_protocol::header::AUTHORIZATION;
+    use http_protocol::header::{AUTHORIZATION, DB, TENANT};
     use prost::Message;
+    use spi::query::logical_planner::{Plan, QueryPlan};
     use spi::server::dbms::DatabaseManagerSystemMock;
     use tonic::metadata::MetadataValue;
     use tonic::transport::{Channel, Endpoint, Server};
-    use tonic::Request;
+    use tonic::{Code, Request};
     use trace::global_logging::init_default_global_tracing;
 
     use crate::flight_sql::auth_middleware::basic_call_header_authenticator::BasicCallHeaderAuthenticator;
     use crate::flight_sql::auth_middleware::generated_bearer_token_authenticator::GeneratedBearerTokenAuthenticator;
-    use crate::flight_sql::flight_sql_server::FlightSqlServiceImpl;
+    use crate::flight_sql::exchange::{ExchangeAck, FlightSqlServer};
+    use crate::flight_sql::flight_sql_server::{
+        bind_parameters, parameters_from_batches, plan_parameter_schema, FlightSqlServiceImpl,
+    };
     use crate::flight_sql::utils;
 
     async fn run_test_server() {
@@ -1124,7 +1792,11 @@ mod test {
             BasicCallHeaderAuthenticator::new(instance.clone()),
         );
 
-        let svc = FlightServiceServer::new(FlightSqlServiceImpl::new(instance, authenticator));
+        let svc = FlightServiceServer::new(FlightSqlServiceImpl::new(
+            instance,
+            Arc::new(MockCoordinator {}),
+            authenticator,
+        ));
 
         println!("Listening on {:?}", addr);
 
@@ -1219,6 +1891,103 @@ mod test {
         }
     }
 
+    async fn run_exchange_test_server() {
+        let addr = "0.0.0.0:8905".parse().expect("parse address");
+
+        let instance = Arc::new(DatabaseManagerSystemMock {});
+        let authenticator = GeneratedBearerTokenAuthenticator::new(
+            BasicCallHeaderAuthenticator::new(instance.clone()),
+        );
+
+        let svc = FlightServiceServer::new(FlightSqlServer::new(FlightSqlServiceImpl::new(
+            instance,
+            Arc::new(MockCoordinator {}),
+            authenticator,
+        )));
+        let server = Server::builder().add_service(svc).serve(addr);
+
+        let _handle = tokio::spawn(server);
+    }
+
+    async fn exchange_batches(
+        client: &mut FlightServiceClient<Channel>,
+        tenant: &str,
+        batches: Vec<RecordBatch>,
+    ) -> Result<Vec<ExchangeAck>, tonic::Status> {
+        let input = futures::stream::iter(batches.into_iter().map(Ok));
+        let mut flight_data = FlightDataEncoderBuilder::new()
+            .build(input)
+            .try_collect::<Vec<FlightData>>()
+            .await
+            .expect("encode batches");
+        flight_data[0].flight_descriptor = Some(FlightDescriptor::new_path(vec![
+            WITH_TABLE_FOR_TEST.to_string(),
+        ]));
+
+        let mut req = Request::new(futures::stream::iter(flight_data));
+        let metadata = req.metadata_mut();
+        metadata.insert(
+            AUTHORIZATION.as_str(),
+            MetadataValue::from_static("Basic cm9vdDo="),
+        );
+        metadata.insert(TENANT, tenant.parse().unwrap());
+        metadata.insert(DB, WITH_NONEMPTY_DATABASE_FOR_TEST.parse().unwrap());
+
+        client
+            .do_exchange(req)
+            .await?
+            .into_inner()
+            .map_ok(|data| serde_json::from_slice::<ExchangeAck>(&data.app_metadata).unwrap())
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_do_exchange() {
+        run_exchange_test_server().await;
+
+        let endpoint = Endpoint::from_static("http://localhost:8905");
+        let mut client = FlightServiceClient::connect(endpoint)
+            .await
+            .expect("connect");
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let batch = |hosts: Vec<&str>, values: Vec<f64>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(StringArray::from(hosts)),
+                    Arc::new(Float64Array::from(values)),
+                ],
+            )
+            .unwrap()
+        };
+        let batches = vec![
+            batch(vec!["a", "b"], vec![1.0, 2.0]),
+            batch(vec!["c"], vec![3.0]),
+        ];
+
+        let acks = exchange_batches(&mut client, "cnosdb", batches.clone())
+            .await
+            .expect("do_exchange");
+        assert_eq!(
+            acks,
+            vec![
+                ExchangeAck { batch: 0, rows: 2 },
+                ExchangeAck { batch: 1, rows: 1 },
+            ]
+        );
+
+        // The limiter of the tenant rejects the writes.
+        let status = exchange_batches(&mut client, WITH_LIMITED_WRITES_TENANT_FOR_TEST, batches)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+    }
+
     async fn flight_channel(host: &str, port: u16) -> Channel {
         Endpoint::new(format!("http://{}:{}", host, port))
             .unwrap()
@@ -1252,4 +2021,92 @@ mod test {
             };
         }
     }
+
+    #[test]
+    fn test_parameters_from_batches() {
+        let parameter_schema = Schema::new(vec![
+            Field::new("$1", DataType::Float64, true),
+            Field::new("$2", DataType::Null, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new("b", DataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Int64Array::from(vec![56])),
+                Arc::new(StringArray::from(vec!["XiaoMaiDao"])),
+            ],
+        )
+        .unwrap();
+
+        let empty = RecordBatch::new_empty(batch.schema());
+        let parameters =
+            parameters_from_batches(&parameter_schema, &[empty, batch.clone()]).unwrap();
+        assert_eq!(
+            parameters,
+            vec![
+                ScalarValue::Float64(Some(56.0)),
+                ScalarValue::Utf8(Some("XiaoMaiDao".to_string())),
+            ]
+        );
+
+        assert!(parameters_from_batches(&parameter_schema, &[]).is_err());
+        assert!(parameters_from_batches(&parameter_schema, &[batch.clone(), batch]).is_err());
+        assert!(parameters_from_batches(&Schema::empty(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_bind_parameters() {
+        let parameter_schema = Schema::new(vec![Field::new("$1", DataType::Int64, true)]);
+        assert!(bind_parameters(None, &parameter_schema, vec![]).is_err());
+        assert!(bind_parameters(None, &Schema::empty(), vec![])
+            .unwrap()
+            .is_none());
+        assert_eq!(plan_parameter_schema(None).unwrap(), Schema::empty());
+    }
+
+    #[tokio::test]
+    async fn test_plan_parameter_schema() {
+        let ctx = SessionContext::new();
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new("b", DataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Int64Array::from(vec![56])),
+                Arc::new(StringArray::from(vec!["XiaoMaiDao"])),
+            ],
+        )
+        .unwrap();
+        ctx.register_batch("t", batch).unwrap();
+        let query_plan = |sql: &'static str| {
+            let state = ctx.state();
+            async move {
+                let df_plan = state.create_logical_plan(sql).await.unwrap();
+                Plan::Query(QueryPlan {
+                    df_plan,
+                    is_tag_scan: false,
+                })
+            }
+        };
+
+        // Ordered by the index, the types are inferred from the columns.
+        let plan = query_plan("SELECT a FROM t WHERE a > $2 AND b = $1").await;
+        assert_eq!(
+            plan_parameter_schema(Some(&plan)).unwrap(),
+            Schema::new(vec![
+                Field::new("$1", DataType::Utf8, true),
+                Field::new("$2", DataType::Int64, true),
+            ])
+        );
+
+        let plan = query_plan("SELECT a FROM t").await;
+        assert_eq!(plan_parameter_schema(Some(&plan)).unwrap(), Schema::empty());
+
+        // $1 is missing.
+        let plan = query_plan("SELECT a FROM t WHERE a > $2").await;
+        assert!(plan_parameter_schema(Some(&plan)).is_err());
+    }
 }
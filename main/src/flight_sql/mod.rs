@@ -2,24 +2,28 @@ use std::net::SocketAddr;
 
 use arrow_flight::flight_service_server::FlightServiceServer;
 use config::tskv::TLSConfig;
+use coordinator::service::CoordinatorRef;
 use spi::server::dbms::DBMSRef;
 use tokio::sync::oneshot;
 use tonic::transport::{Identity, Server, ServerTlsConfig};
 use trace::http::tower_layer::TraceLayer;
 use trace::info;
 
+use self::exchange::FlightSqlServer;
 use self::flight_sql_server::FlightSqlServiceImpl;
 use crate::flight_sql::auth_middleware::basic_call_header_authenticator::BasicCallHeaderAuthenticator;
 use crate::flight_sql::auth_middleware::generated_bearer_token_authenticator::GeneratedBearerTokenAuthenticator;
 use crate::server::ServiceHandle;
 use crate::spi::service::Service;
 
-mod auth_middleware;
+pub(crate) mod auth_middleware;
+pub mod exchange;
 pub mod flight_sql_server;
-mod utils;
+pub(crate) mod utils;
 
 pub struct FlightSqlServiceAdapter {
     dbms: DBMSRef,
+    coord: CoordinatorRef,
 
     addr: SocketAddr,
     tls_config: Option<TLSConfig>,
@@ -30,12 +34,14 @@ pub struct FlightSqlServiceAdapter {
 impl FlightSqlServiceAdapter {
     pub fn new(
         dbms: DBMSRef,
+        coord: CoordinatorRef,
         addr: SocketAddr,
         tls_config: Option<TLSConfig>,
         auto_generate_span: bool,
     ) -> Self {
         Self {
             dbms,
+            coord,
             addr,
             tls_config,
             auto_generate_span,
@@ -69,8 +75,11 @@ impl Service for FlightSqlServiceAdapter {
         let authenticator = GeneratedBearerTokenAuthenticator::new(
             BasicCallHeaderAuthenticator::new(self.dbms.clone()),
         );
-        let svc =
-            FlightServiceServer::new(FlightSqlServiceImpl::new(self.dbms.clone(), authenticator));
+        let svc = FlightServiceServer::new(FlightSqlServer::new(FlightSqlServiceImpl::new(
+            self.dbms.clone(),
+            self.coord.clone(),
+            authenticator,
+        )));
 
         let server = server
             .layer(trace_layer)
@@ -30,6 +30,11 @@ macro_rules! status {
     };
 }
 
+/// The address of the client sending the request, if it's known.
+pub fn client_addr<T>(request: &Request<T>) -> Option<String> {
+    request.remote_addr().map(|addr| addr.to_string())
+}
+
 /// Helper method for retrieving a value from the Authorization header.
 ///
 /// headers     The headers to inspect.
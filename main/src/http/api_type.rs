@@ -9,8 +9,10 @@ pub enum HttpApiType {
     ApiV1PromWrite,
 
     ApiV1Sql,
+    ApiV1SqlPlan,
     ApiV1PromRead,
     ApiV1ESLogWrite,
+    Query,
 
     ApiV1Ping,
     DebugBacktrace,
@@ -18,8 +20,11 @@ pub enum HttpApiType {
     ApiV1metaleader,
     ApiV1Meta,
     ApiV1Raft,
+    ApiV1Endpoints,
     DebugPprof,
     DebugJeprof,
+    DebugTraceSampling,
+    DebugRuntime,
     Metrics,
     ApiV1DumpSqlDdl,
     ApiV1Traces,
@@ -28,6 +33,8 @@ pub enum HttpApiType {
     ApiServices,
     ApiOperations,
     ApiServicesOperations,
+    GrafanaSearch,
+    GrafanaQuery,
 }
 
 impl Display for HttpApiType {
@@ -48,12 +55,18 @@ impl Display for HttpApiType {
             HttpApiType::ApiV1Sql => {
                 write!(f, "api/v1/sql")
             }
+            HttpApiType::ApiV1SqlPlan => {
+                write!(f, "api/v1/sql/plan")
+            }
             HttpApiType::ApiV1PromRead => {
                 write!(f, "api/v1/prom/read")
             }
             HttpApiType::ApiV1ESLogWrite => {
                 write!(f, "api/v1/es/write")
             }
+            HttpApiType::Query => {
+                write!(f, "query")
+            }
             HttpApiType::ApiV1Ping => {
                 write!(f, "api/v1/ping")
             }
@@ -72,12 +85,21 @@ impl Display for HttpApiType {
             HttpApiType::ApiV1Raft => {
                 write!(f, "api/v1/raft")
             }
+            HttpApiType::ApiV1Endpoints => {
+                write!(f, "api/v1/endpoints")
+            }
             HttpApiType::DebugPprof => {
                 write!(f, "debug/pprof")
             }
             HttpApiType::DebugJeprof => {
                 write!(f, "debug/jeprof")
             }
+            HttpApiType::DebugTraceSampling => {
+                write!(f, "debug/trace_sampling")
+            }
+            HttpApiType::DebugRuntime => {
+                write!(f, "debug/runtime")
+            }
             HttpApiType::Metrics => {
                 write!(f, "metrics")
             }
@@ -102,6 +124,12 @@ impl Display for HttpApiType {
             HttpApiType::ApiServicesOperations => {
                 write!(f, "api/services/name/operations")
             }
+            HttpApiType::GrafanaSearch => {
+                write!(f, "grafana/search")
+            }
+            HttpApiType::GrafanaQuery => {
+                write!(f, "grafana/query")
+            }
         }
     }
 }
@@ -115,21 +143,28 @@ pub fn metrics_record_db(api: &HttpApiType) -> bool {
         | HttpApiType::ApiV1PromWrite
         | HttpApiType::ApiV1ESLogWrite
         | HttpApiType::ApiV1PromRead
+        | HttpApiType::Query
         | HttpApiType::ApiV1Traces
         | HttpApiType::ApiTraces
         | HttpApiType::ApiTracesID
         | HttpApiType::ApiServices
         | HttpApiType::ApiOperations
-        | HttpApiType::ApiServicesOperations => true,
+        | HttpApiType::ApiServicesOperations
+        | HttpApiType::GrafanaSearch
+        | HttpApiType::GrafanaQuery => true,
         HttpApiType::ApiV1Sql
+        | HttpApiType::ApiV1SqlPlan
         | HttpApiType::ApiV1Ping
         | HttpApiType::DebugBacktrace
         | HttpApiType::Write
         | HttpApiType::ApiV1metaleader
         | HttpApiType::ApiV1Meta
         | HttpApiType::ApiV1Raft
+        | HttpApiType::ApiV1Endpoints
         | HttpApiType::DebugPprof
         | HttpApiType::DebugJeprof
+        | HttpApiType::DebugTraceSampling
+        | HttpApiType::DebugRuntime
         | HttpApiType::Metrics
         | HttpApiType::ApiV1DumpSqlDdl => false,
     }
@@ -0,0 +1,421 @@
+//! Handlers of the Grafana SimpleJSON/Infinity compatible API.
+//!
+//! `/grafana/search` lists the numeric fields of a database as `table.field`
+//! metrics, `/grafana/query` scans the requested metrics in a time range
+//! and returns one time series per tag set.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::array::{Array, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::sql::TableReference;
+use futures::TryStreamExt;
+use meta::model::MetaClientRef;
+use models::object_reference::Resolve;
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRanges};
+use models::predicate::PlacedSplit;
+use models::schema::tskv_table_schema::{
+    ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
+};
+use models::schema::TIME_FIELD_NAME;
+use models::ValueType;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tskv::reader::QueryOption;
+use utils::precision::{timestamp_convert, Precision};
+
+use super::Error as HttpError;
+
+const BATCH_SIZE: usize = 4096;
+/// The max number of rows scanned by a query request.
+const MAX_SCAN_ROWS: usize = 1_000_000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchRequest {
+    #[serde(default)]
+    pub target: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryRequest {
+    pub range: Range,
+    pub targets: Vec<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Range {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    pub target: String,
+}
+
+/// A time series in the Grafana response, datapoints are `[value, unix_millis]`.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TimeSeries {
+    pub target: String,
+    pub datapoints: Vec<(f64, i64)>,
+}
+
+pub fn decode_request<T: DeserializeOwned>(body: &[u8]) -> Result<T, HttpError> {
+    serde_json::from_slice(body).map_err(|e| HttpError::InvalidGrafanaRequest {
+        reason: e.to_string(),
+    })
+}
+
+/// List the numeric fields of the database as `table.field`, keeps the metrics
+/// containing the search target only.
+pub fn search(
+    meta: &MetaClientRef,
+    db: &str,
+    req: &SearchRequest,
+) -> Result<Vec<String>, HttpError> {
+    let mut tables = meta
+        .list_tables(db)
+        .map_err(|source| HttpError::Meta { source })?;
+    tables.sort();
+
+    let mut metrics = Vec::new();
+    for table in tables {
+        let Some(schema) = meta
+            .get_tskv_table_schema(db, &table)
+            .map_err(|source| HttpError::Meta { source })?
+        else {
+            continue;
+        };
+        for field in schema.fields() {
+            if !is_numeric(&field) {
+                continue;
+            }
+            let metric = format!("{}.{}", table, field.name);
+            if metric.contains(&req.target) {
+                metrics.push(metric);
+            }
+        }
+    }
+
+    Ok(metrics)
+}
+
+pub async fn query(
+    coord: &CoordinatorRef,
+    meta: &MetaClientRef,
+    tenant: &str,
+    db: &str,
+    req: QueryRequest,
+) -> Result<Vec<TimeSeries>, HttpError> {
+    let from = parse_time(&req.range.from)?;
+    let to = parse_time(&req.range.to)?;
+
+    let mut result = Vec::new();
+    let mut scan_rows = MAX_SCAN_ROWS;
+    for target in req.targets {
+        let (table, field) =
+            target
+                .target
+                .split_once('.')
+                .ok_or_else(|| HttpError::InvalidGrafanaRequest {
+                    reason: format!("target '{}' is not 'table.field'", target.target),
+                })?;
+        let schema = meta
+            .get_tskv_table_schema(db, table)
+            .map_err(|source| HttpError::Meta { source })?
+            .ok_or_else(|| HttpError::InvalidGrafanaRequest {
+                reason: format!("table '{}' not found", table),
+            })?;
+        let field_column = schema
+            .column(field)
+            .filter(|c| is_numeric(c))
+            .ok_or_else(|| HttpError::InvalidGrafanaRequest {
+                reason: format!("numeric field '{}' not found in '{}'", field, table),
+            })?
+            .clone();
+
+        let batches = scan(
+            coord,
+            tenant,
+            db,
+            &schema,
+            field_column,
+            from,
+            to,
+            &mut scan_rows,
+        )
+        .await?;
+        result.extend(batches_to_time_series(
+            table,
+            field,
+            schema.time_column_precision(),
+            &batches,
+        )?);
+    }
+
+    Ok(result)
+}
+
+/// Scan the time, tags and the field column of the table in `[from, to]` (nanoseconds),
+/// fails if the rows exceed `max_rows`, which is decreased by the rows scanned.
+#[allow(clippy::too_many_arguments)]
+async fn scan(
+    coord: &CoordinatorRef,
+    tenant: &str,
+    db: &str,
+    schema: &TskvTableSchemaRef,
+    field_column: TableColumn,
+    from: i64,
+    to: i64,
+    max_rows: &mut usize,
+) -> Result<Vec<RecordBatch>, HttpError> {
+    let precision = schema.time_column_precision();
+    let min_ts = timestamp_convert(Precision::NS, precision, from).unwrap_or(i64::MIN);
+    let max_ts = timestamp_convert(Precision::NS, precision, to).unwrap_or(i64::MAX);
+
+    let mut columns = vec![schema.time_column()];
+    columns.extend(
+        schema
+            .columns()
+            .iter()
+            .filter(|c| c.column_type.is_tag())
+            .cloned(),
+    );
+    columns.push(field_column);
+    let proj_schema: TskvTableSchemaRef = Arc::new(TskvTableSchema::new(
+        schema.tenant.clone(),
+        schema.db.clone(),
+        schema.name.clone(),
+        columns,
+    ));
+
+    let predicate = Arc::new(
+        ResolvedPredicate::new(
+            Arc::new(TimeRanges::with_inclusive_bounds(min_ts, max_ts)),
+            ColumnDomains::all(),
+            None,
+        )
+        .map_err(|e| HttpError::FetchResult {
+            reason: e.to_string(),
+        })?,
+    );
+    let table = TableReference::bare(schema.name.as_str())
+        .resolve_object(tenant, db)
+        .map_err(|e| HttpError::FetchResult {
+            reason: e.to_string(),
+        })?;
+    let replica_sets = coord
+        .table_vnodes(&table, predicate.clone())
+        .await
+        .map_err(|source| HttpError::Coordinator { source })?;
+
+    let mut batches = Vec::new();
+    for (i, replica_set) in replica_sets.into_iter().enumerate() {
+        let split = PlacedSplit::new(i, predicate.clone(), None, replica_set);
+        let option = QueryOption::new(
+            BATCH_SIZE,
+            split,
+            None,
+            proj_schema.to_arrow_schema(),
+            proj_schema.clone(),
+            schema.meta(),
+        );
+        let mut stream = coord
+            .table_scan(option, None)
+            .map_err(|source| HttpError::Coordinator { source })?;
+        while let Some(batch) = stream
+            .try_next()
+            .await
+            .map_err(|source| HttpError::Coordinator { source })?
+        {
+            *max_rows = max_rows.checked_sub(batch.num_rows()).ok_or_else(|| {
+                HttpError::InvalidGrafanaRequest {
+                    reason: format!(
+                        "query scans more than {} rows, narrow the time range",
+                        MAX_SCAN_ROWS
+                    ),
+                }
+            })?;
+            batches.push(batch);
+        }
+    }
+
+    Ok(batches)
+}
+
+fn batches_to_time_series(
+    table: &str,
+    field: &str,
+    precision: Precision,
+    batches: &[RecordBatch],
+) -> Result<Vec<TimeSeries>, HttpError> {
+    let mut series: BTreeMap<String, Vec<(f64, i64)>> = BTreeMap::new();
+    for batch in batches {
+        let times = cast_column(batch, TIME_FIELD_NAME, &DataType::Int64)?;
+        let times = times
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .expect("cast to Int64Array");
+        let values = cast_column(batch, field, &DataType::Float64)?;
+        let values = values
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .expect("cast to Float64Array");
+        let tags = batch
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.name() != TIME_FIELD_NAME && f.name() != field)
+            .filter_map(|(i, f)| {
+                batch
+                    .column(i)
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .map(|a| (f.name().clone(), a))
+            })
+            .collect::<Vec<_>>();
+
+        for row in 0..batch.num_rows() {
+            if values.is_null(row) {
+                continue;
+            }
+            let tag_set = tags
+                .iter()
+                .filter(|(_, a)| !a.is_null(row))
+                .map(|(name, a)| format!("{}={}", name, a.value(row)))
+                .collect::<Vec<_>>();
+            let target = if tag_set.is_empty() {
+                format!("{}.{}", table, field)
+            } else {
+                format!("{}.{}{{{}}}", table, field, tag_set.join(","))
+            };
+            let ts = timestamp_convert(precision, Precision::MS, times.value(row))
+                .unwrap_or_else(|| times.value(row));
+            series
+                .entry(target)
+                .or_default()
+                .push((values.value(row), ts));
+        }
+    }
+
+    Ok(series
+        .into_iter()
+        .map(|(target, mut datapoints)| {
+            datapoints.sort_by_key(|(_, ts)| *ts);
+            TimeSeries { target, datapoints }
+        })
+        .collect())
+}
+
+fn cast_column(
+    batch: &RecordBatch,
+    name: &str,
+    data_type: &DataType,
+) -> Result<Arc<dyn Array>, HttpError> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| HttpError::FetchResult {
+            reason: format!("column {} is not exist", name),
+        })?;
+    cast(column, data_type).map_err(|e| HttpError::FetchResult {
+        reason: e.to_string(),
+    })
+}
+
+fn is_numeric(column: &TableColumn) -> bool {
+    matches!(
+        column.column_type,
+        ColumnType::Field(ValueType::Float | ValueType::Integer | ValueType::Unsigned)
+    )
+}
+
+/// Parse a RFC3339 time or unix milliseconds to nanoseconds.
+fn parse_time(time: &str) -> Result<i64, HttpError> {
+    if let Ok(millis) = time.parse::<i64>() {
+        return Ok(millis.saturating_mul(1_000_000));
+    }
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .and_then(|t| t.timestamp_nanos_opt())
+        .ok_or_else(|| HttpError::InvalidGrafanaRequest {
+            reason: format!("invalid time '{}'", time),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use utils::precision::Precision;
+
+    use super::{batches_to_time_series, decode_request, parse_time, QueryRequest, TimeSeries};
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("1000").unwrap(), 1_000_000_000);
+        assert_eq!(
+            parse_time("1970-01-01T00:00:01.000Z").unwrap(),
+            1_000_000_000
+        );
+        assert!(parse_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_decode_request() {
+        let req: QueryRequest = decode_request(
+            br#"{"range": {"from": "0", "to": "1000"}, "targets": [{"target": "cpu.usage"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(req.range.to, "1000");
+        assert_eq!(req.targets[0].target, "cpu.usage");
+        assert!(decode_request::<QueryRequest>(br#"{"targets": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_batches_to_time_series() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("usage", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    3_000_000, 1_000_000, 2_000_000,
+                ])),
+                Arc::new(StringArray::from(vec![Some("a"), Some("a"), None])),
+                Arc::new(Float64Array::from(vec![Some(3.0), Some(1.0), Some(2.0)])),
+            ],
+        )
+        .unwrap();
+
+        let series = batches_to_time_series("cpu", "usage", Precision::NS, &[batch]).unwrap();
+        assert_eq!(
+            series,
+            vec![
+                TimeSeries {
+                    target: "cpu.usage".to_string(),
+                    datapoints: vec![(2.0, 2)],
+                },
+                TimeSeries {
+                    target: "cpu.usage{host=a}".to_string(),
+                    datapoints: vec![(1.0, 1), (3.0, 3)],
+                },
+            ]
+        );
+    }
+}
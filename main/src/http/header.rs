@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use base64::prelude::{Engine, BASE64_STANDARD};
 use http_protocol::header::{APPLICATION_CSV, BASIC_PREFIX};
 use models::auth::user::UserInfo;
@@ -15,6 +17,7 @@ pub struct Header {
     tenant: Option<String>,
     db: Option<String>,
     table: Option<String>,
+    client_addr: Option<String>,
 }
 
 impl Header {
@@ -33,6 +36,7 @@ impl Header {
             tenant: None,
             db: None,
             table: None,
+            client_addr: None,
         }
     }
 
@@ -55,9 +59,15 @@ impl Header {
             tenant,
             db,
             table,
+            client_addr: None,
         }
     }
 
+    pub fn with_client_addr(mut self, client_addr: Option<SocketAddr>) -> Self {
+        self.client_addr = client_addr.map(|addr| addr.to_string());
+        self
+    }
+
     pub fn get_accept(&self) -> &str {
         self.accept.as_deref().unwrap_or(APPLICATION_CSV)
     }
@@ -82,6 +92,10 @@ impl Header {
         self.table.clone()
     }
 
+    pub fn get_client_addr(&self) -> Option<String> {
+        self.client_addr.clone()
+    }
+
     pub fn try_get_basic_auth(&self) -> Result<UserInfo, HttpError> {
         let private_key = self
             .private_key
@@ -7,10 +7,11 @@ use std::fmt::Display;
 use std::mem::size_of_val;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use config::tskv::TLSConfig;
+use config::tskv::{TLSConfig, TraceSamplingConfig};
 use coordinator::service::CoordinatorRef;
+use coordinator::LineBatchStream;
 use datafusion::arrow::array::{Array, StringArray};
 use futures::TryStreamExt;
 use http_protocol::encoding::Encoding;
@@ -18,19 +19,21 @@ use http_protocol::header::{
     ACCEPT, APPLICATION_JSON, AUTHORIZATION, DB, PRIVATE_KEY, TABLE, TENANT,
 };
 use http_protocol::parameter::{
-    DebugParam, DumpParam, FindTracesParam, GetOperationParam, LogParam, SqlParam, WriteParam,
+    DebugParam, DumpParam, FindTracesParam, GetOperationParam, InfluxqlParam, LogParam, SqlParam,
+    WriteParam,
 };
-use http_protocol::response::ErrorResponse;
+use http_protocol::response::{Endpoint, EndpointsResponse, ErrorResponse};
 use http_protocol::status_code::OK;
 use meta::error::{MetaError, MetaResult};
 use meta::limiter::RequestLimiter;
-use meta::model::MetaRef;
+use meta::model::{MetaClientRef, MetaRef};
 use metrics::count::U64Counter;
 use metrics::metric_register::MetricsRegister;
 use metrics::prom_reporter::PromReporter;
 use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
 use models::error_code::UnknownCodeWithMessage;
 use models::oid::{Identifier, Oid};
+use models::runtime::stats::runtime_stats;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
 use models::utils::now_timestamp_nanos;
 use protocol_parser::json_protocol::parser::{
@@ -41,21 +44,28 @@ use protocol_parser::json_protocol::JsonType;
 use protocol_parser::line_protocol::line_protocol_to_lines;
 use protocol_parser::open_tsdb::open_tsdb_to_lines;
 use protocol_parser::{DataPoint, Line};
+use protos::kv_service::WriteConsistency;
+use query::influxql::server::InfluxqlSqlServer;
 use query::prom::remote_server::PromRemoteSqlServer;
 use reqwest::header::{HeaderName, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
 use snafu::{IntoError, ResultExt};
-use spi::query::config::StreamTriggerInterval;
+use spi::query::config::{parse_max_replica_lag, parse_time_zone, StreamTriggerInterval};
 use spi::server::dbms::DBMSRef;
+use spi::server::influxql::InfluxqlServerRef;
 use spi::server::prom::PromRemoteServerRef;
 use spi::service::protocol::{Context, ContextBuilder, Query};
 use spi::QueryError;
 use tokio::sync::oneshot;
 use trace::http::http_ctx::{HeaderDecodeSnafu, DEFAULT_TRACE_HEADER_NAME};
+use trace::sampler::{
+    new_root_span_context, sampling_config, set_sampling_config, STATEMENT_PROPERTY,
+    STATEMENT_QUERY, STATEMENT_WRITE,
+};
 use trace::span_ctx_ext::SpanContextExt;
 use trace::span_ext::SpanExt;
 use trace::{debug, error, info, Span, SpanContext};
 use utils::backtrace;
-use utils::precision::Precision;
+use utils::precision::{timestamp_convert, Precision};
 use warp::hyper::body::Bytes;
 use warp::hyper::Body;
 use warp::reject::{MethodNotAllowed, MissingHeader, PayloadTooLarge};
@@ -63,12 +73,14 @@ use warp::reply::Response;
 use warp::{header, reject, Filter, Rejection, Reply};
 
 use super::header::Header;
-use super::{ContextSnafu, CoordinatorSnafu, DecodeRequestSnafu, Error as HttpError, MetaSnafu};
+use super::{
+    grafana, ContextSnafu, CoordinatorSnafu, DecodeRequestSnafu, Error as HttpError, MetaSnafu,
+};
 use crate::http::api_type::{metrics_record_db, HttpApiType};
 use crate::http::encoding::{get_accept_encoding_from_header, get_content_encoding_from_header};
 use crate::http::metrics::HttpMetrics;
 use crate::http::response::{HttpResponse, ResponseBuilder};
-use crate::http::result_format::{get_result_format_from_header, ResultFormat};
+use crate::http::result_format::{get_result_format_from_header, ResultFormat, TimeDisplay};
 use crate::http::QuerySnafu;
 use crate::opentelemetry::jaeger_model::{Operation, Process, Trace};
 use crate::opentelemetry::otlp_to_jaeger::{
@@ -78,6 +90,7 @@ use crate::opentelemetry::otlp_to_jaeger::{
 };
 use crate::server::ServiceHandle;
 use crate::spi::service::Service;
+use crate::write_check::{self, check_password_changed, check_write_limiter};
 use crate::{server, VERSION};
 
 pub enum ServerMode {
@@ -108,6 +121,7 @@ pub struct HttpService {
     dbms: DBMSRef,
     coord: CoordinatorRef,
     prs: PromRemoteServerRef,
+    influxql: InfluxqlServerRef,
     handle: Option<ServiceHandle<()>>,
     query_body_limit: u64,
     write_body_limit: u64,
@@ -132,6 +146,7 @@ impl HttpService {
         let http_metrics = Arc::new(HttpMetrics::new(&metrics_register));
 
         let prs = Arc::new(PromRemoteSqlServer::new(dbms.clone(), coord.clone()));
+        let influxql = Arc::new(InfluxqlSqlServer::new(dbms.clone()));
 
         Self {
             tls_config,
@@ -139,6 +154,7 @@ impl HttpService {
             dbms,
             coord,
             prs,
+            influxql,
             handle: None,
             query_body_limit,
             write_body_limit,
@@ -163,6 +179,7 @@ impl HttpService {
             .and(header::optional::<String>(TENANT))
             .and(header::optional::<String>(DB))
             .and(header::optional::<String>(TABLE))
+            .and(warp::addr::remote())
             .and_then(
                 |accept,
                  accept_encoding,
@@ -171,7 +188,8 @@ impl HttpService {
                  private_key,
                  tenant,
                  db,
-                 table| async move {
+                 table,
+                 client_addr| async move {
                     let res: Result<Header, warp::Rejection> = Ok(Header::with_private_key(
                         accept,
                         accept_encoding,
@@ -181,7 +199,8 @@ impl HttpService {
                         tenant,
                         db,
                         table,
-                    ));
+                    )
+                    .with_client_addr(client_addr));
                     res
                 },
             )
@@ -204,7 +223,7 @@ impl HttpService {
                             error!("Failed to decode trace header, err: {:?}", e);
                             reject::custom(e)
                         }),
-                    None if auto_generate_span => Ok(Some(SpanContext::random())),
+                    None if auto_generate_span => Ok(Some(new_root_span_context())),
                     None => Ok(None),
                 };
                 async move { result }
@@ -233,6 +252,13 @@ impl HttpService {
         warp::any().map(move || prs.clone())
     }
 
+    fn with_influxql_server(
+        &self,
+    ) -> impl Filter<Extract = (InfluxqlServerRef,), Error = Infallible> + Clone {
+        let influxql = self.influxql.clone();
+        warp::any().map(move || influxql.clone())
+    }
+
     fn with_metrics_register(
         &self,
     ) -> impl Filter<Extract = (Arc<MetricsRegister>,), Error = Infallible> + Clone {
@@ -257,13 +283,18 @@ impl HttpService {
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         self.ping()
             .or(self.query())
+            .or(self.query_plan())
             .or(self.mock_influxdb_write())
             .or(self.metrics())
             .or(self.print_meta())
             .or(self.meta_leader_addr())
+            .or(self.endpoints())
             .or(self.debug_pprof())
             .or(self.debug_jeprof())
+            .or(self.debug_trace_sampling())
+            .or(self.debug_runtime())
             .or(self.prom_remote_read())
+            .or(self.influxql_query())
             .or(self.backtrace())
             .or(self.print_raft())
             .or(self.dump_ddl_sql())
@@ -285,6 +316,8 @@ impl HttpService {
             .or(self.get_services())
             .or(self.get_operations())
             .or(self.get_operations_by_service())
+            .or(self.grafana_search())
+            .or(self.grafana_query())
     }
 
     fn routes_store(
@@ -294,8 +327,11 @@ impl HttpService {
             .or(self.metrics())
             .or(self.print_meta())
             .or(self.meta_leader_addr())
+            .or(self.endpoints())
             .or(self.debug_pprof())
             .or(self.debug_jeprof())
+            .or(self.debug_trace_sampling())
+            .or(self.debug_runtime())
             .or(self.backtrace())
             .or(self.print_raft())
             .or(self.dump_ddl_sql())
@@ -384,7 +420,8 @@ impl HttpService {
                         header, param
                     );
 
-                    let span = Span::from_context("rest sql request", parent_span_ctx.as_ref());
+                    let span = Span::from_context("rest sql request", parent_span_ctx.as_ref())
+                        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_QUERY));
                     let req_len = req.len();
                     let content_encoding = get_content_encoding_from_header(&header)?;
                     if let Some(encoding) = content_encoding {
@@ -393,6 +430,13 @@ impl HttpService {
                             reject::custom(HttpError::DecodeRequest { source: e })
                         })?;
                     }
+                    let time_display = param
+                        .time_display
+                        .as_deref()
+                        .map(str::parse::<TimeDisplay>)
+                        .transpose()
+                        .map_err(|reason| reject::custom(HttpError::InvalidHeader { reason }))?
+                        .unwrap_or_default();
                     let query = {
                         let mut span = Span::enter_with_parent("authenticate", &span);
 
@@ -437,6 +481,7 @@ impl HttpService {
                             &query,
                             &dbms,
                             result_fmt,
+                            time_display,
                             result_encoding,
                             span.context().as_ref(),
                             limiter,
@@ -478,6 +523,87 @@ impl HttpService {
             )
     }
 
+    /// Returns the optimized logical plan and physical plan of a statement in json,
+    /// the statement is not executed.
+    fn query_plan(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "sql" / "plan")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.query_body_limit))
+            .and(warp::body::bytes())
+            .and(self.handle_header())
+            .and(warp::query::<SqlParam>())
+            .and(self.with_dbms())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |mut req: Bytes,
+                 header: Header,
+                 param: SqlParam,
+                 dbms: DBMSRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    debug!(
+                        "Receive http sql plan request, header: {:?}, param: {:?}",
+                        header, param
+                    );
+
+                    let span =
+                        Span::from_context("rest sql plan request", parent_span_ctx.as_ref())
+                            .with_property(|| (STATEMENT_PROPERTY, STATEMENT_QUERY));
+                    let req_len = req.len();
+                    let content_encoding = get_content_encoding_from_header(&header)?;
+                    if let Some(encoding) = content_encoding {
+                        req = encoding.decode(req).map_err(|e| {
+                            error!("Failed to decode request, err: {:?}", e);
+                            reject::custom(HttpError::DecodeRequest { source: e })
+                        })?;
+                    }
+                    let query = construct_query(req, &header, param, dbms.clone(), coord)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to construct query, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+
+                    let plan = {
+                        let span = Span::enter_with_parent("explain", &span);
+                        dbms.explain(&query, span.context().as_ref())
+                            .await
+                            .map_err(|e| {
+                                span.error(e.to_string());
+                                error!("Failed to explain http sql request, err: {:?}", e);
+                                reject::custom(HttpError::Query { source: e })
+                            })?
+                    };
+
+                    http_record_query_metrics(
+                        &metrics,
+                        query.context(),
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::ApiV1SqlPlan,
+                    );
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        req_len + plan.logical_plan.len() + plan.physical_plan.len(),
+                        start,
+                        HttpApiType::ApiV1SqlPlan,
+                    );
+
+                    Ok::<_, Rejection>(ResponseBuilder::new(OK).json(&plan))
+                },
+            )
+    }
+
     fn write_line_protocol(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
@@ -503,7 +629,8 @@ impl HttpService {
                  parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
                     let span =
-                        Span::from_context("rest line protocol write", parent_span_ctx.as_ref());
+                        Span::from_context("rest line protocol write", parent_span_ctx.as_ref())
+                            .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
                     let span_context = span.context();
 
                     let req_len = req.len();
@@ -514,13 +641,16 @@ impl HttpService {
                             reject::custom(HttpError::DecodeRequest { source: e })
                         })?;
                     }
+                    let dry_run = param.dry_run.unwrap_or_default();
+                    let consistency = parse_write_consistency(param.consistency.as_deref())
+                        .map_err(reject::custom)?;
 
-                    let ctx = {
+                    let (ctx, tenant_id) = {
                         let mut span = Span::enter_with_parent("construct write context", &span);
-                        let ctx = construct_write_context_and_check_privilege(
+                        let (ctx, tenant_id) = construct_lines_write_context(
                             header,
                             param,
-                            dbms,
+                            dbms.clone(),
                             coord.clone(),
                         )
                         .await
@@ -529,7 +659,7 @@ impl HttpService {
                             reject::custom(e)
                         })?;
                         record_context_in_span(&mut span, &ctx);
-                        ctx
+                        (ctx, tenant_id)
                     };
 
                     http_limiter_check_write(&coord.meta_manager(), ctx.tenant(), req_len).await?;
@@ -545,6 +675,8 @@ impl HttpService {
                             reject::custom(e)
                         })?
                     };
+                    check_lines_write_privilege(&ctx, tenant_id, &write_points_lines)
+                        .map_err(reject::custom)?;
 
                     {
                         let api_type = HttpApiType::ApiV1Write;
@@ -561,15 +693,38 @@ impl HttpService {
                             .record(parse_start.elapsed());
                     }
 
+                    if dry_run {
+                        let span = Span::enter_with_parent("dry run write points", &span);
+                        let report = coord
+                            .dry_run_write_lines(
+                                ctx.tenant(),
+                                ctx.database(),
+                                precision,
+                                write_points_lines,
+                                span.context().as_ref(),
+                            )
+                            .await
+                            .map_err(|e| {
+                                error!("Failed to handle http dry run write, err: {:?}", e);
+                                reject::custom(CoordinatorSnafu.into_error(e))
+                            })?;
+                        return Ok(ResponseBuilder::new(OK).json(&report));
+                    }
+
                     let resp = coord_write_points_with_span_recorder(
                         &coord,
-                        ctx.tenant(),
-                        ctx.database(),
+                        &ctx,
                         precision,
+                        consistency,
                         write_points_lines,
                         span_context.as_ref(),
                     )
                     .await;
+                    dbms.audit_write(
+                        &ctx,
+                        "line protocol",
+                        resp.as_ref().err().map(|e| e.to_string()),
+                    );
 
                     http_record_write_metrics(
                         &metrics,
@@ -612,13 +767,15 @@ impl HttpService {
             .and(self.with_coord())
             .and(self.with_http_metrics())
             .and(self.with_hostaddr())
+            .and(warp::addr::remote())
             .and_then(
                 |req: Bytes,
                  mut query: HashMap<String, String>,
                  dbms: DBMSRef,
                  coord: CoordinatorRef,
                  metrics: Arc<HttpMetrics>,
-                 addr: String| async move {
+                 addr: String,
+                 client_addr| async move {
                     let start = Instant::now();
                     let db = query
                         .remove("db")
@@ -628,40 +785,52 @@ impl HttpService {
                         None,
                         None,
                         "Basic cm9vdDo=".to_string(),
-                    );
+                    )
+                    .with_client_addr(client_addr);
+                    let (precision, scale) =
+                        parse_influx_precision(query.remove("precision").as_deref())
+                            .map_err(reject::custom)?;
+                    let consistency =
+                        parse_write_consistency(query.remove("consistency").as_deref())
+                            .map_err(reject::custom)?;
+                    check_influx_retention_policy(query.remove("rp").as_deref())
+                        .map_err(reject::custom)?;
                     let param = WriteParam {
                         db: Some(db),
                         precision: None,
                         tenant: None,
+                        dry_run: None,
+                        consistency: None,
                     };
-                    let precision = Precision::NS;
 
-                    let ctx = construct_write_context_and_check_privilege(
-                        header,
-                        param,
-                        dbms,
-                        coord.clone(),
-                    )
-                    .await
-                    .map_err(|e| {
-                        error!("Failed to construct write context, err: {:?}", e);
-                        reject::custom(e)
-                    })?;
+                    let (ctx, tenant_id) =
+                        construct_lines_write_context(header, param, dbms.clone(), coord.clone())
+                            .await
+                            .map_err(|e| {
+                                error!("Failed to construct write context, err: {:?}", e);
+                                reject::custom(e)
+                            })?;
 
-                    let lines = try_parse_req_to_lines(&req).map_err(|e| {
+                    let lines = parse_influx_lines(&req, precision, scale).map_err(|e| {
                         error!("Failed to parse request to lines, err: {:?}", e);
                         reject::custom(e)
                     })?;
+                    check_lines_write_privilege(&ctx, tenant_id, &lines).map_err(reject::custom)?;
 
                     let resp = coord_write_points_with_span_recorder(
                         &coord,
-                        ctx.tenant(),
-                        ctx.database(),
+                        &ctx,
                         precision,
+                        consistency,
                         lines,
                         None,
                     )
                     .await;
+                    dbms.audit_write(
+                        &ctx,
+                        "line protocol",
+                        resp.as_ref().err().map(|e| e.to_string()),
+                    );
 
                     let result_size = size_of_val(&resp);
                     let value_size = match &resp {
@@ -709,7 +878,8 @@ impl HttpService {
                  addr: String,
                  parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
-                    let span = Span::from_context("rest open tsdb write", parent_span_ctx.as_ref());
+                    let span = Span::from_context("rest open tsdb write", parent_span_ctx.as_ref())
+                        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
                     let span_context = span.context();
 
                     let req_len = req.len();
@@ -721,21 +891,20 @@ impl HttpService {
                         })?;
                     }
 
-                    let ctx = {
+                    let consistency = parse_write_consistency(param.consistency.as_deref())
+                        .map_err(reject::custom)?;
+
+                    let (ctx, tenant_id) = {
                         let mut span = Span::enter_with_parent("construct write context", &span);
-                        let ctx = construct_write_context_and_check_privilege(
-                            header,
-                            param,
-                            dbms,
-                            coord.clone(),
-                        )
-                        .await
-                        .map_err(|e| {
-                            error!("Failed to construct write context, err: {:?}", e);
-                            reject::custom(e)
-                        })?;
+                        let (ctx, tenant_id) =
+                            construct_lines_write_context(header, param, dbms, coord.clone())
+                                .await
+                                .map_err(|e| {
+                                    error!("Failed to construct write context, err: {:?}", e);
+                                    reject::custom(e)
+                                })?;
                         record_context_in_span(&mut span, &ctx);
-                        ctx
+                        (ctx, tenant_id)
                     };
 
                     let precision = Precision::new(ctx.precision()).unwrap_or(Precision::NS);
@@ -759,11 +928,13 @@ impl HttpService {
                             reject::custom(e)
                         })?
                     };
+                    check_lines_write_privilege(&ctx, tenant_id, &write_points_req)
+                        .map_err(reject::custom)?;
                     let resp = coord_write_points_with_span_recorder(
                         &coord,
-                        ctx.tenant(),
-                        ctx.database(),
+                        &ctx,
                         precision,
+                        consistency,
                         write_points_req,
                         span_context.as_ref(),
                     )
@@ -824,7 +995,8 @@ impl HttpService {
                  addr: String,
                  parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
-                    let span = Span::from_context("rest open tsdb put", parent_span_ctx.as_ref());
+                    let span = Span::from_context("rest open tsdb put", parent_span_ctx.as_ref())
+                        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
                     let span_context = span.context();
 
                     let req_len = req.len();
@@ -842,21 +1014,20 @@ impl HttpService {
                             })?;
                     }
 
-                    let ctx = {
+                    let consistency = parse_write_consistency(param.consistency.as_deref())
+                        .map_err(reject::custom)?;
+
+                    let (ctx, tenant_id) = {
                         let mut span = Span::enter_with_parent("construct write context", &span);
-                        let ctx = construct_write_context_and_check_privilege(
-                            header,
-                            param,
-                            dbms,
-                            coord.clone(),
-                        )
-                        .await
-                        .map_err(|e| {
-                            error!("Failed to construct write context, err: {:?}", e);
-                            reject::custom(e)
-                        })?;
+                        let (ctx, tenant_id) =
+                            construct_lines_write_context(header, param, dbms, coord.clone())
+                                .await
+                                .map_err(|e| {
+                                    error!("Failed to construct write context, err: {:?}", e);
+                                    reject::custom(e)
+                                })?;
                         record_context_in_span(&mut span, &ctx);
-                        ctx
+                        (ctx, tenant_id)
                     };
 
                     let precision = Precision::new(ctx.precision()).unwrap_or(Precision::NS);
@@ -882,11 +1053,13 @@ impl HttpService {
                             reject::custom(e)
                         })?
                     };
+                    check_lines_write_privilege(&ctx, tenant_id, &write_points_req)
+                        .map_err(reject::custom)?;
                     let resp = coord_write_points_with_span_recorder(
                         &coord,
-                        ctx.tenant(),
-                        ctx.database(),
+                        &ctx,
                         precision,
+                        consistency,
                         write_points_req,
                         span_context.as_ref(),
                     )
@@ -962,6 +1135,35 @@ impl HttpService {
             )
     }
 
+    /// The nodes running the query engine with their status reported to meta,
+    /// clients may bootstrap from one address and spread the requests to them.
+    fn endpoints(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "endpoints")
+            .and(warp::get())
+            .and(self.with_meta())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and_then(
+                |meta: MetaRef, metrics: Arc<HttpMetrics>, addr: String| async move {
+                    let start = Instant::now();
+                    let resp = list_endpoints(&meta).await.map_err(|err| {
+                        error!("Failed to list endpoints, err: {:?}", err);
+                        reject::custom(MetaSnafu.into_error(err))
+                    })?;
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        size_of_val(&resp),
+                        start,
+                        HttpApiType::ApiV1Endpoints,
+                    );
+                    Ok::<_, warp::Rejection>(warp::reply::json(&resp))
+                },
+            )
+    }
+
     fn print_meta(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
@@ -1118,6 +1320,69 @@ impl HttpService {
             })
     }
 
+    fn debug_trace_sampling(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        let get = warp::get().map(|| None::<TraceSamplingConfig>);
+        let put = warp::put()
+            .and(warp::body::json::<TraceSamplingConfig>())
+            .and(self.handle_header())
+            .and(self.with_dbms())
+            .and_then(
+                |new_config: TraceSamplingConfig, header: Header, dbms: DBMSRef| async move {
+                    authenticate_system_admin(&header, dbms)
+                        .await
+                        .map_err(reject::custom)?;
+                    Ok::<_, warp::Rejection>(Some(new_config))
+                },
+            );
+        warp::path!("debug" / "trace_sampling")
+            .and(get.or(put).unify())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .map(
+                |new_config: Option<TraceSamplingConfig>,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String| {
+                    let start = Instant::now();
+                    if let Some(new_config) = new_config {
+                        info!("Update trace sampling config: {:?}", new_config);
+                        set_sampling_config(new_config);
+                    }
+                    let config = sampling_config();
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        size_of_val(&config),
+                        start,
+                        HttpApiType::DebugTraceSampling,
+                    );
+                    warp::reply::json(&config)
+                },
+            )
+    }
+
+    fn debug_runtime(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("debug" / "runtime")
+            .and(warp::get())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .map(|metrics: Arc<HttpMetrics>, addr: String| {
+                let start = Instant::now();
+                let stats = runtime_stats();
+                http_response_time_and_flow_metrics(
+                    &metrics,
+                    &addr,
+                    size_of_val(stats.as_slice()),
+                    start,
+                    HttpApiType::DebugRuntime,
+                );
+                warp::reply::json(&stats)
+            })
+    }
+
     fn metrics(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
@@ -1176,7 +1441,8 @@ impl HttpService {
                         header, param
                     );
                     let span =
-                        Span::from_context("rest prom remote read", parent_span_ctx.as_ref());
+                        Span::from_context("rest prom remote read", parent_span_ctx.as_ref())
+                            .with_property(|| (STATEMENT_PROPERTY, STATEMENT_QUERY));
 
                     // Parse req、header and param to construct query request
                     let context = {
@@ -1254,86 +1520,224 @@ impl HttpService {
             )
     }
 
-    fn prom_remote_write(
+    /// The `/query` API of InfluxDB 1.x, `q` is read from the query string or
+    /// the form body of POST.
+    fn influxql_query(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        warp::path!("api" / "v1" / "prom" / "write")
-            .and(warp::post())
+        warp::path!("query")
+            .and(warp::get().or(warp::post()).unify())
             .and(warp::body::content_length_limit(self.query_body_limit))
             .and(warp::body::bytes())
             .and(self.handle_header())
-            .and(warp::query::<WriteParam>())
-            .and(self.with_coord())
+            .and(warp::query::<InfluxqlParam>())
             .and(self.with_dbms())
-            .and(self.with_prom_remote_server())
+            .and(self.with_meta())
+            .and(self.with_coord())
             .and(self.with_http_metrics())
+            .and(self.with_influxql_server())
             .and(self.with_hostaddr())
             .and(self.handle_span_header())
             .and_then(
                 |req: Bytes,
                  header: Header,
-                 param: WriteParam,
-                 coord: CoordinatorRef,
+                 mut param: InfluxqlParam,
                  dbms: DBMSRef,
-                 prs: PromRemoteServerRef,
+                 meta: MetaRef,
+                 coord: CoordinatorRef,
                  metrics: Arc<HttpMetrics>,
+                 influxql: InfluxqlServerRef,
                  addr: String,
                  parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
                     debug!(
-                        "Receive rest prom remote write request, header: {:?}, param: {:?}",
+                        "Receive rest influxql query request, header: {:?}, param: {:?}",
                         header, param
                     );
-                    let span =
-                        Span::from_context("rest prom remote write", parent_span_ctx.as_ref());
-                    let span_context = span.context();
+                    let span = Span::from_context("rest influxql query", parent_span_ctx.as_ref())
+                        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_QUERY));
 
-                    // Parse req、header and param to construct query request
-                    let ctx = {
+                    if param.q.is_none() {
+                        param.q = url::form_urlencoded::parse(&req)
+                            .find(|(k, _)| k == "q")
+                            .map(|(_, v)| v.into_owned());
+                    }
+                    let q = param.q.take().ok_or_else(|| {
+                        reject::custom(HttpError::InvalidHeader {
+                            reason: "missing required parameter q".to_string(),
+                        })
+                    })?;
+                    let epoch = param.epoch.take();
+                    let sql_param = SqlParam {
+                        tenant: param.tenant,
+                        db: param.db,
+                        chunked: None,
+                        target_partitions: None,
+                        stream_trigger_interval: None,
+                        consistent_meta: None,
+                        ignore_retention: None,
+                        max_replica_lag: None,
+                        follower_read: None,
+                        cleanup_partial_write: None,
+                        time_display: None,
+                        time_zone: None,
+                        session_id: None,
+                    };
+
+                    let context = {
                         let mut span = Span::enter_with_parent("construct context", &span);
-                        span.add_property(|| ("bytes", req.len().to_string()));
-                        let ctx = construct_write_context_and_check_privilege(
-                            header,
-                            param,
-                            dbms,
-                            coord.clone(),
-                        )
-                        .await
-                        .map_err(|e| {
-                            error!("Failed to construct write context, err: {:?}", e);
-                            reject::custom(e)
-                        })?;
+                        let ctx = construct_read_context(&header, sql_param, dbms, coord, false)
+                            .await
+                            .map_err(|e| {
+                                error!("Failed to construct read context, err: {:?}", e);
+                                reject::custom(e)
+                            })?;
                         record_context_in_span(&mut span, &ctx);
                         ctx
                     };
+                    let req_len = q.len();
 
-                    let req_len = req.len();
-                    http_limiter_check_write(&coord.meta_manager(), ctx.tenant(), req_len)
+                    http_limiter_check_query(&meta, context.tenant(), req_len)
                         .await
                         .map_err(|e| {
-                            error!("Failed to check write limiter, err: {:?}", e);
+                            error!("Failed to check query limiter, err: {:?}", e);
                             reject::custom(e)
                         })?;
 
-                    let span = Span::enter_with_parent("remote write", &span);
-                    let prom_write_request = prs.remote_write(req).map_err(|e| {
-                        span.error(e.to_string());
-                        error!("Failed to handle prom remote write request, err: {:?}", e);
-                        reject::custom(QuerySnafu.into_error(e))
-                    })?;
-                    let write_request = prs
-                        .prom_write_request_to_lines(&prom_write_request)
-                        .map_err(|e| {
+                    let http_query_data_out = metrics.http_data_out(
+                        context.tenant(),
+                        context.user().desc().name(),
+                        Some(context.database()),
+                        addr.as_str(),
+                        HttpApiType::Query,
+                    );
+
+                    let result = {
+                        let span = Span::enter_with_parent("influxql query", &span);
+                        influxql
+                            .query(&context, &q, epoch.as_deref(), span.context().as_ref())
+                            .await
+                            .map_err(|e| {
+                                span.error(e.to_string());
+                                error!("Failed to handle influxql query request, err: {:?}", e);
+                                reject::custom(QuerySnafu.into_error(e))
+                            })
+                            .map(|b| {
+                                http_query_data_out.inc(b.len() as u64);
+                                ResponseBuilder::new(OK)
+                                    .insert_header((
+                                        CONTENT_TYPE,
+                                        HeaderValue::from_static("application/json"),
+                                    ))
+                                    .build(b)
+                            })
+                    };
+
+                    http_record_query_metrics(
+                        &metrics,
+                        &context,
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::Query,
+                    );
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        size_of_val(&result) + req_len,
+                        start,
+                        HttpApiType::Query,
+                    );
+                    result
+                },
+            )
+    }
+
+    fn prom_remote_write(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        // `remote_write` is the path Prometheus documents for remote write receivers.
+        warp::path!("api" / "v1" / "prom" / "write")
+            .or(warp::path!("api" / "v1" / "prom" / "remote_write"))
+            .unify()
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.query_body_limit))
+            .and(warp::body::bytes())
+            .and(self.handle_header())
+            .and(warp::query::<WriteParam>())
+            .and(self.with_coord())
+            .and(self.with_dbms())
+            .and(self.with_prom_remote_server())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |req: Bytes,
+                 header: Header,
+                 param: WriteParam,
+                 coord: CoordinatorRef,
+                 dbms: DBMSRef,
+                 prs: PromRemoteServerRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    debug!(
+                        "Receive rest prom remote write request, header: {:?}, param: {:?}",
+                        header, param
+                    );
+                    let span =
+                        Span::from_context("rest prom remote write", parent_span_ctx.as_ref())
+                            .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
+                    let span_context = span.context();
+
+                    let consistency = parse_write_consistency(param.consistency.as_deref())
+                        .map_err(reject::custom)?;
+
+                    // Parse req、header and param to construct query request
+                    let ctx = {
+                        let mut span = Span::enter_with_parent("construct context", &span);
+                        span.add_property(|| ("bytes", req.len().to_string()));
+                        let (ctx, tenant_id) =
+                            construct_lines_write_context(header, param, dbms, coord.clone())
+                                .await
+                                .map_err(|e| {
+                                    error!("Failed to construct write context, err: {:?}", e);
+                                    reject::custom(e)
+                                })?;
+                        record_context_in_span(&mut span, &ctx);
+                        (ctx, tenant_id)
+                    };
+
+                    let req_len = req.len();
+                    http_limiter_check_write(&coord.meta_manager(), ctx.tenant(), req_len)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to check write limiter, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+
+                    let span = Span::enter_with_parent("remote write", &span);
+                    let prom_write_request = prs.remote_write(req).map_err(|e| {
+                        span.error(e.to_string());
+                        error!("Failed to handle prom remote write request, err: {:?}", e);
+                        reject::custom(QuerySnafu.into_error(e))
+                    })?;
+                    let write_request = prs
+                        .prom_write_request_to_lines(&prom_write_request)
+                        .map_err(|e| {
                             span.error(e.to_string());
                             error!("Failed to handle prom remote write request, err: {:?}", e);
                             reject::custom(QuerySnafu.into_error(e))
                         })?;
+                    check_lines_write_privilege(&ctx, tenant_id, &write_request)
+                        .map_err(reject::custom)?;
 
                     let resp = coord_write_points_with_span_recorder(
                         &coord,
-                        ctx.tenant(),
-                        ctx.database(),
+                        &ctx,
                         Precision::NS,
+                        consistency,
                         write_request,
                         span_context.as_ref(),
                     )
@@ -1545,7 +1949,8 @@ impl HttpService {
                  addr: String,
                  parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
-                    let span = Span::from_context("rest log write", parent_span_ctx.as_ref());
+                    let span = Span::from_context("rest log write", parent_span_ctx.as_ref())
+                        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
                     let span_context = span.context();
 
                     let req_len = req.len();
@@ -1561,6 +1966,8 @@ impl HttpService {
                         precision: None,
                         tenant: param.tenant,
                         db: param.db,
+                        dry_run: None,
+                        consistency: None,
                     };
 
                     if param.table.is_none() {
@@ -1692,7 +2099,8 @@ impl HttpService {
                  addr: String,
                  parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
-                    let span = Span::from_context("rest log write", parent_span_ctx.as_ref());
+                    let span = Span::from_context("rest log write", parent_span_ctx.as_ref())
+                        .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
                     let span_context = span.context();
 
                     let tenant = header
@@ -1710,7 +2118,8 @@ impl HttpService {
                     })?;
 
                     let span =
-                        Span::from_context("rest otlp trace write", parent_span_ctx.as_ref());
+                        Span::from_context("rest otlp trace write", parent_span_ctx.as_ref())
+                            .with_property(|| (STATEMENT_PROPERTY, STATEMENT_WRITE));
 
                     let content_encoding = get_content_encoding_from_header(&header)?;
                     if let Some(encoding) = content_encoding {
@@ -1724,6 +2133,8 @@ impl HttpService {
                         precision: None,
                         tenant: header.get_tenant(),
                         db: header.get_db(),
+                        dry_run: None,
+                        consistency: None,
                     };
                     let ctx = {
                         let mut span = Span::enter_with_parent("construct write context", &span);
@@ -1839,6 +2250,14 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistent_meta: None,
+                        ignore_retention: None,
+                        max_replica_lag: None,
+                        follower_read: None,
+                        cleanup_partial_write: None,
+                        time_display: None,
+                        time_zone: None,
+                        session_id: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2083,6 +2502,14 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistent_meta: None,
+                        ignore_retention: None,
+                        max_replica_lag: None,
+                        follower_read: None,
+                        cleanup_partial_write: None,
+                        time_display: None,
+                        time_zone: None,
+                        session_id: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2157,6 +2584,14 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistent_meta: None,
+                        ignore_retention: None,
+                        max_replica_lag: None,
+                        follower_read: None,
+                        cleanup_partial_write: None,
+                        time_display: None,
+                        time_zone: None,
+                        session_id: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2283,6 +2718,14 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistent_meta: None,
+                        ignore_retention: None,
+                        max_replica_lag: None,
+                        follower_read: None,
+                        cleanup_partial_write: None,
+                        time_display: None,
+                        time_zone: None,
+                        session_id: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2427,6 +2870,14 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistent_meta: None,
+                        ignore_retention: None,
+                        max_replica_lag: None,
+                        follower_read: None,
+                        cleanup_partial_write: None,
+                        time_display: None,
+                        time_zone: None,
+                        session_id: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2525,6 +2976,162 @@ impl HttpService {
                 },
             )
     }
+
+    fn grafana_search(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("grafana" / "search")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.query_body_limit))
+            .and(warp::body::bytes())
+            .and(self.handle_header())
+            .and(self.with_dbms())
+            .and(self.with_meta())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and_then(
+                |req: Bytes,
+                 header: Header,
+                 dbms: DBMSRef,
+                 meta_manager: MetaRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String| async move {
+                    let start = Instant::now();
+                    let (meta, db) = grafana_meta_and_check_privilege(&header, dbms, &coord)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to construct grafana search, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+                    http_limiter_check_query(&meta_manager, meta.tenant().name(), req.len())
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to check query limiter, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+                    let req: grafana::SearchRequest =
+                        grafana::decode_request(&req).map_err(reject::custom)?;
+
+                    let resp = grafana::search(&meta, &db, &req).map_err(reject::custom)?;
+
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        size_of_val(&resp),
+                        start,
+                        HttpApiType::GrafanaSearch,
+                    );
+                    Ok::<_, Rejection>(ResponseBuilder::new(OK).json(&resp))
+                },
+            )
+    }
+
+    fn grafana_query(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("grafana" / "query")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.query_body_limit))
+            .and(warp::body::bytes())
+            .and(self.handle_header())
+            .and(self.with_dbms())
+            .and(self.with_meta())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and_then(
+                |req: Bytes,
+                 header: Header,
+                 dbms: DBMSRef,
+                 meta_manager: MetaRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String| async move {
+                    let start = Instant::now();
+                    let (meta, db) = grafana_meta_and_check_privilege(&header, dbms, &coord)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to construct grafana query, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+                    http_limiter_check_query(&meta_manager, meta.tenant().name(), req.len())
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to check query limiter, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+                    let req: grafana::QueryRequest =
+                        grafana::decode_request(&req).map_err(reject::custom)?;
+
+                    let resp = grafana::query(&coord, &meta, meta.tenant().name(), &db, req)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to handle grafana query, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        size_of_val(&resp),
+                        start,
+                        HttpApiType::GrafanaQuery,
+                    );
+                    Ok::<_, Rejection>(ResponseBuilder::new(OK).json(&resp))
+                },
+            )
+    }
+}
+
+/// Authenticate the user of the grafana request and check the read privilege of
+/// the database, returns the tenant meta and the database.
+async fn grafana_meta_and_check_privilege(
+    header: &Header,
+    dbms: DBMSRef,
+    coord: &CoordinatorRef,
+) -> Result<(MetaClientRef, String), HttpError> {
+    let sql_param = SqlParam {
+        tenant: header.get_tenant(),
+        db: header.get_db(),
+        chunked: None,
+        target_partitions: None,
+        stream_trigger_interval: None,
+        consistent_meta: None,
+        ignore_retention: None,
+        max_replica_lag: None,
+        follower_read: None,
+        cleanup_partial_write: None,
+        time_display: None,
+        time_zone: None,
+        session_id: None,
+    };
+    let context = construct_read_context(header, sql_param, dbms, coord.clone(), false).await?;
+
+    let meta =
+        coord
+            .tenant_meta(context.tenant())
+            .await
+            .ok_or_else(|| HttpError::NotFoundTenant {
+                name: context.tenant().to_string(),
+            })?;
+    let privilege = Privilege::TenantObject(
+        TenantObjectPrivilege::Database(
+            DatabasePrivilege::Read,
+            Some(context.database().to_string()),
+        ),
+        Some(*meta.tenant().id()),
+    );
+    if !context.user().check_privilege(&privilege) {
+        return Err(HttpError::Query {
+            source: QueryError::InsufficientPrivileges {
+                privilege: format!("{privilege}"),
+            },
+        });
+    }
+
+    Ok((meta, context.database().to_string()))
 }
 
 #[async_trait::async_trait]
@@ -2637,8 +3244,13 @@ async fn construct_read_context(
     let user_info = header.try_get_basic_auth()?;
 
     let tenant = param.tenant;
+    let client_addr = header.get_client_addr();
     let user = dbms
-        .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+        .authenticate(
+            &user_info,
+            tenant.as_deref().unwrap_or(DEFAULT_CATALOG),
+            client_addr.as_deref(),
+        )
         .await
         .context(QuerySnafu)?;
 
@@ -2660,8 +3272,22 @@ async fn construct_read_context(
     let context = ContextBuilder::new(user)
         .with_tenant(tenant)
         .with_database(param.db)
+        .with_client_addr(client_addr)
         .with_target_partitions(param.target_partitions)
         .with_chunked(param.chunked)
+        .with_consistent_meta(param.consistent_meta)
+        .with_ignore_retention(param.ignore_retention)
+        .with_session_id(param.session_id)
+        .with_max_replica_lag(
+            param
+                .max_replica_lag
+                .map(|ref e| {
+                    parse_max_replica_lag(e).map_err(|reason| HttpError::InvalidHeader { reason })
+                })
+                .transpose()?,
+        )
+        .with_follower_read(param.follower_read)
+        .with_cleanup_partial_write(param.cleanup_partial_write)
         .with_stream_trigger_interval(
             param
                 .stream_trigger_interval
@@ -2671,11 +3297,39 @@ async fn construct_read_context(
                 })
                 .transpose()?,
         )
+        .with_time_zone(
+            param
+                .time_zone
+                .map(|ref e| {
+                    parse_time_zone(e).map_err(|reason| HttpError::InvalidHeader { reason })
+                })
+                .transpose()?,
+        )
         .build();
 
     Ok(context)
 }
 
+/// Authenticate the user of the request, who must be a system administrator.
+async fn authenticate_system_admin(header: &Header, dbms: DBMSRef) -> Result<(), HttpError> {
+    let user_info = header.try_get_basic_auth()?;
+    let client_addr = header.get_client_addr();
+    let user = dbms
+        .authenticate(&user_info, DEFAULT_CATALOG, client_addr.as_deref())
+        .await
+        .context(QuerySnafu)?;
+
+    if !user.desc().is_admin() {
+        return Err(HttpError::Query {
+            source: QueryError::InsufficientPrivileges {
+                privilege: "system admin".to_string(),
+            },
+        });
+    }
+
+    Ok(())
+}
+
 async fn construct_write_context(
     header: &Header,
     param: WriteParam,
@@ -2685,9 +3339,14 @@ async fn construct_write_context(
     let tenant = param.tenant;
     let db = param.db;
     let precision = param.precision;
+    let client_addr = header.get_client_addr();
 
     let user = dbms
-        .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+        .authenticate(
+            &user_info,
+            tenant.as_deref().unwrap_or(DEFAULT_CATALOG),
+            client_addr.as_deref(),
+        )
         .await
         .context(QuerySnafu)?;
 
@@ -2695,6 +3354,7 @@ async fn construct_write_context(
         .with_tenant(tenant)
         .with_database(db)
         .with_precision(precision)
+        .with_client_addr(client_addr)
         .build();
 
     Ok(context)
@@ -2714,32 +3374,8 @@ async fn construct_write_context_and_check_privilege(
     dbms: DBMSRef,
     coord: CoordinatorRef,
 ) -> Result<Context, HttpError> {
-    let context = construct_write_context(&header, param, dbms).await?;
-
-    let tenant_id = *coord
-        .tenant_meta(context.tenant())
-        .await
-        .ok_or_else(|| MetaError::TenantNotFound {
-            tenant: context.tenant().to_string(),
-        })
-        .context(MetaSnafu)?
-        .tenant()
-        .id();
+    let (context, tenant_id) = construct_lines_write_context(header, param, dbms, coord).await?;
 
-    if coord.get_config().query.auth_enabled
-        && context
-            .user()
-            .desc()
-            .options()
-            .must_change_password()
-            .is_some_and(|x| x)
-    {
-        return Err(HttpError::Query {
-            source: QueryError::InsufficientPrivileges {
-                privilege: "change password".to_string(),
-            },
-        });
-    }
     let privilege = Privilege::TenantObject(
         TenantObjectPrivilege::Database(
             DatabasePrivilege::Write,
@@ -2757,6 +3393,132 @@ async fn construct_write_context_and_check_privilege(
     Ok(context)
 }
 
+/// Construct the context of a write of lines and returns it with the id of the
+/// tenant, the privileges are checked by `check_lines_write_privilege` once
+/// the written tables are known.
+async fn construct_lines_write_context(
+    header: Header,
+    param: WriteParam,
+    dbms: DBMSRef,
+    coord: CoordinatorRef,
+) -> Result<(Context, Oid), HttpError> {
+    let context = construct_write_context(&header, param, dbms).await?;
+
+    let tenant_id = *coord
+        .tenant_meta(context.tenant())
+        .await
+        .ok_or_else(|| MetaError::TenantNotFound {
+            tenant: context.tenant().to_string(),
+        })
+        .context(MetaSnafu)?
+        .tenant()
+        .id();
+
+    check_password_changed(context.user(), coord.get_config().query.auth_enabled)
+        .context(QuerySnafu)?;
+    Ok((context, tenant_id))
+}
+
+fn check_lines_write_privilege(
+    context: &Context,
+    tenant_id: Oid,
+    lines: &[Line],
+) -> Result<(), HttpError> {
+    write_check::check_lines_write_privilege(context.user(), tenant_id, context.database(), lines)
+        .context(QuerySnafu)
+}
+
+/// Parse the `consistency` parameter of a write: `one`, `quorum` or `all`,
+/// `any` of InfluxDB is the same as `one`.
+fn parse_write_consistency(consistency: Option<&str>) -> Result<WriteConsistency, HttpError> {
+    let Some(consistency) = consistency else {
+        return Ok(WriteConsistency::Quorum);
+    };
+    match consistency.to_uppercase().as_str() {
+        "ANY" => Ok(WriteConsistency::One),
+        c => WriteConsistency::from_str_name(c).ok_or_else(|| HttpError::InvalidHeader {
+            reason: format!(
+                "invalid write consistency '{}', expected one of: any, one, quorum, all",
+                consistency
+            ),
+        }),
+    }
+}
+
+/// Parse the `precision` parameter of an InfluxDB write, returns the precision of the
+/// write and the factor to convert the timestamps to it, since `s`, `m` and `h` are
+/// not supported by the storage.
+fn parse_influx_precision(precision: Option<&str>) -> Result<(Precision, i64), HttpError> {
+    match precision.unwrap_or("ns") {
+        "n" | "ns" => Ok((Precision::NS, 1)),
+        "u" | "us" | "µ" => Ok((Precision::US, 1)),
+        "ms" => Ok((Precision::MS, 1)),
+        "s" => Ok((Precision::MS, 1_000)),
+        "m" => Ok((Precision::MS, 60_000)),
+        "h" => Ok((Precision::MS, 3_600_000)),
+        p => Err(HttpError::InvalidHeader {
+            reason: format!(
+                "invalid precision '{}', expected one of: ns, u, ms, s, m, h",
+                p
+            ),
+        }),
+    }
+}
+
+const INFLUX_DEFAULT_RETENTION_POLICY: &str = "autogen";
+/// Writes of more lines are sent to the coordinator as a stream of batches.
+const STREAM_WRITE_BATCH_LINES: usize = 100_000;
+/// The default time of the lines parsed from an InfluxDB write, to tell the
+/// lines without a timestamp apart.
+const NO_TIMESTAMP: i64 = i64::MIN;
+
+/// A database of CnosDB has only one retention policy, the ttl of the database,
+/// which is named `autogen` as the default retention policy of InfluxDB.
+fn check_influx_retention_policy(rp: Option<&str>) -> Result<(), HttpError> {
+    match rp {
+        None | Some("") | Some(INFLUX_DEFAULT_RETENTION_POLICY) => Ok(()),
+        Some(rp) => Err(HttpError::InvalidHeader {
+            reason: format!(
+                "retention policy not found: {}, only '{}' is supported, which is the ttl of the database",
+                rp, INFLUX_DEFAULT_RETENTION_POLICY
+            ),
+        }),
+    }
+}
+
+/// Parse the lines of an InfluxDB write, the timestamps are converted to `precision`
+/// by multiplying `scale`, the lines without a timestamp are written at now.
+fn parse_influx_lines(
+    req: &Bytes,
+    precision: Precision,
+    scale: i64,
+) -> Result<Vec<Line>, HttpError> {
+    let now =
+        timestamp_convert(Precision::NS, precision, now_timestamp_nanos()).ok_or_else(|| {
+            HttpError::InvalidHeader {
+                reason: format!("invalid precision {}", precision),
+            }
+        })?;
+    parse_influx_lines_at(req, scale, now)
+}
+
+fn parse_influx_lines_at(req: &Bytes, scale: i64, now: i64) -> Result<Vec<Line>, HttpError> {
+    let lines = simdutf8::basic::from_utf8(req.as_ref())
+        .map_err(|e| HttpError::InvalidUTF8 { source: e })?;
+    // The lines without a timestamp are told apart by the default time.
+    let mut lines = line_protocol_to_lines(lines, NO_TIMESTAMP)
+        .map_err(|e| HttpError::ParseLineProtocol { source: e })?;
+    for line in lines.iter_mut() {
+        line.timestamp = if line.timestamp == NO_TIMESTAMP {
+            now
+        } else {
+            line.timestamp.saturating_mul(scale)
+        };
+    }
+
+    Ok(lines)
+}
+
 fn try_parse_req_to_lines(req: &Bytes) -> Result<Vec<Line>, HttpError> {
     let lines = simdutf8::basic::from_utf8(req.as_ref())
         .map_err(|e| HttpError::InvalidUTF8 { source: e })?;
@@ -2865,7 +3627,14 @@ async fn coord_write_log(
     }
 
     coord
-        .write_lines(tenant, db, Precision::NS, lines, span.context().as_ref())
+        .write_lines(
+            tenant,
+            db,
+            Precision::NS,
+            WriteConsistency::Quorum,
+            lines,
+            span.context().as_ref(),
+        )
         .await
         .map_err(|e| {
             span.error(e.to_string());
@@ -2876,34 +3645,90 @@ async fn coord_write_log(
     Ok(ResponseBuilder::ok())
 }
 
+/// Write the lines, the write_timeout of the user or of the tenant ends the write.
 async fn coord_write_points_with_span_recorder(
     coord: &CoordinatorRef,
-    tenant: &str,
-    db: &str,
+    ctx: &Context,
     precision: Precision,
+    consistency: WriteConsistency,
     write_points_lines: Vec<Line<'_>>,
     span_context: Option<&SpanContext>,
 ) -> Result<usize, HttpError> {
     let span = Span::from_context("write points", span_context);
+    let span_ctx = span.context();
+    let write = async {
+        if write_points_lines.len() > STREAM_WRITE_BATCH_LINES {
+            // The points of the replication sets are built and written batch by
+            // batch rather than all at once.
+            coord
+                .write_lines_stream(
+                    ctx.tenant(),
+                    ctx.database(),
+                    precision,
+                    consistency,
+                    line_batch_stream(write_points_lines, STREAM_WRITE_BATCH_LINES),
+                    span_ctx.as_ref(),
+                )
+                .await
+        } else {
+            coord
+                .write_lines(
+                    ctx.tenant(),
+                    ctx.database(),
+                    precision,
+                    consistency,
+                    write_points_lines,
+                    span_ctx.as_ref(),
+                )
+                .await
+        }
+    };
+    let result = match write_timeout(coord, ctx).await {
+        Some(timeout) => match tokio::time::timeout(timeout, write).await {
+            Ok(result) => result,
+            Err(_) => {
+                let err = QueryError::StatementTimeout { timeout };
+                span.error(err.to_string());
+                return Err(HttpError::Query { source: err });
+            }
+        },
+        None => write.await,
+    };
+
+    result.map_err(|e| {
+        span.error(e.to_string());
+        CoordinatorSnafu.into_error(e)
+    })
+}
+
+/// Split the lines into batches of `batch_lines` lines.
+fn line_batch_stream(lines: Vec<Line<'_>>, batch_lines: usize) -> LineBatchStream<'_> {
+    let mut lines = lines.into_iter();
+    let batches = std::iter::from_fn(move || {
+        let batch = lines.by_ref().take(batch_lines).collect::<Vec<_>>();
+        (!batch.is_empty()).then_some(Ok(batch))
+    });
+    Box::pin(futures::stream::iter(batches))
+}
+
+/// The write_timeout of the user, or of the tenant if the user has none.
+async fn write_timeout(coord: &CoordinatorRef, ctx: &Context) -> Option<Duration> {
+    if let Some(timeout) = ctx.user().desc().options().write_timeout() {
+        return timeout.to_duration();
+    }
     coord
-        .write_lines(
-            tenant,
-            db,
-            precision,
-            write_points_lines,
-            span.context().as_ref(),
-        )
-        .await
-        .map_err(|e| {
-            span.error(e.to_string());
-            CoordinatorSnafu.into_error(e)
-        })
+        .tenant_meta(ctx.tenant())
+        .await?
+        .tenant()
+        .options()
+        .write_timeout()
 }
 
 async fn sql_handle(
     query: &Query,
     dbms: &DBMSRef,
     fmt: ResultFormat,
+    time_display: TimeDisplay,
     encoding: Option<Encoding>,
     span_ctx: Option<&SpanContext>,
     limiter: Arc<dyn RequestLimiter>,
@@ -2929,7 +3754,9 @@ async fn sql_handle(
         encoding,
         http_query_data_out.clone(),
         limiter.clone(),
-    );
+    )
+    .with_time_display(time_display)
+    .with_time_zone(query.context().time_zone().map(str::to_string));
 
     let span = Span::from_context("build response", span_ctx);
     if !query.context().chunked() {
@@ -2954,7 +3781,9 @@ async fn sql_handle(
                     encoding,
                     http_query_data_out.clone(),
                     limiter.clone(),
-                );
+                )
+                .with_time_display(time_display)
+                .with_time_zone(query.context().time_zone().map(str::to_string));
                 return resp.wrap_batches_to_response().await;
             }
         }
@@ -2965,6 +3794,47 @@ async fn sql_handle(
     }
 }
 
+/// List the nodes running the query engine ordered by id, the data nodes in
+/// query_tskv mode and the query nodes registered in meta.
+async fn list_endpoints(meta: &MetaRef) -> MetaResult<EndpointsResponse> {
+    let metrics = meta
+        .node_metrics()
+        .await?
+        .into_iter()
+        .map(|m| (m.id, m))
+        .collect::<HashMap<_, _>>();
+
+    let this_node = meta.node_info();
+    let mut nodes = meta.data_nodes().await;
+    nodes.extend(meta.query_nodes().await?);
+    nodes.push(this_node.clone());
+    nodes.sort_by_key(|n| n.id);
+    nodes.dedup_by_key(|n| n.id);
+
+    let endpoints = nodes
+        .into_iter()
+        .filter(|n| n.runs_query())
+        .map(|n| {
+            let (status, healthy) = match metrics.get(&n.id) {
+                Some(m) => (m.status.as_str(), m.is_healthy()),
+                // This node is serving the request.
+                None if n.id == this_node.id => ("healthy", true),
+                None => ("unknown", false),
+            };
+            Endpoint {
+                node_id: n.id,
+                roles: n.roles(),
+                http_addr: n.http_addr,
+                grpc_addr: n.grpc_addr,
+                status: status.to_string(),
+                healthy,
+            }
+        })
+        .collect();
+
+    Ok(EndpointsResponse { endpoints })
+}
+
 async fn http_limiter_check_query(
     meta: &MetaRef,
     tenant: &str,
@@ -2984,13 +3854,9 @@ async fn http_limiter_check_write(
     tenant: &str,
     req_len: usize,
 ) -> Result<(), HttpError> {
-    let limiter = meta.limiter(tenant).await.context(MetaSnafu)?;
-    limiter.check_http_writes().await.context(MetaSnafu)?;
-    limiter
-        .check_http_data_in(req_len)
+    check_write_limiter(meta, tenant, req_len)
         .await
-        .context(MetaSnafu)?;
-    Ok(())
+        .context(MetaSnafu)
 }
 
 fn http_record_query_metrics(
@@ -3117,4 +3983,140 @@ mod test {
         dbg!("Server stop");
         let _ = tx.send(());
     }
+    #[tokio::test]
+    async fn test_line_batch_stream() {
+        use futures::StreamExt;
+
+        let lines = super::try_parse_req_to_lines(&bytes::Bytes::from_static(
+            b"cpu,host=a usage=1 1\ncpu,host=b usage=2 2\nmem,host=a used=3 3",
+        ))
+        .unwrap();
+        let batches = super::line_batch_stream(lines, 2)
+            .map(|batch| batch.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), 2);
+        assert_eq!(batches[0][1].timestamp, 2);
+        assert_eq!(batches[1].len(), 1);
+        assert_eq!(batches[1][0].table, "mem");
+
+        let batches = super::line_batch_stream(vec![], 2)
+            .collect::<Vec<_>>()
+            .await;
+        assert!(batches.is_empty());
+    }
+
+    #[test]
+    fn test_parse_write_params() {
+        use protos::kv_service::WriteConsistency;
+        use utils::precision::Precision;
+
+        use super::{parse_influx_precision, parse_write_consistency};
+
+        assert_eq!(
+            parse_write_consistency(None).unwrap(),
+            WriteConsistency::Quorum
+        );
+        assert_eq!(
+            parse_write_consistency(Some("any")).unwrap(),
+            WriteConsistency::One
+        );
+        assert_eq!(
+            parse_write_consistency(Some("All")).unwrap(),
+            WriteConsistency::All
+        );
+        assert!(parse_write_consistency(Some("two")).is_err());
+
+        assert_eq!(parse_influx_precision(None).unwrap(), (Precision::NS, 1));
+        assert_eq!(
+            parse_influx_precision(Some("u")).unwrap(),
+            (Precision::US, 1)
+        );
+        assert_eq!(
+            parse_influx_precision(Some("s")).unwrap(),
+            (Precision::MS, 1_000)
+        );
+        assert!(parse_influx_precision(Some("d")).is_err());
+    }
+
+    #[test]
+    fn test_parse_influx_lines() {
+        use bytes::Bytes;
+
+        use super::parse_influx_lines_at;
+
+        let req = Bytes::from("cpu,host=a usage=1 1700000000\ncpu,host=b usage=2");
+        // In seconds, written in milliseconds.
+        let lines = parse_influx_lines_at(&req, 1_000, 1_700_000_005_123).unwrap();
+        assert_eq!(lines[0].timestamp, 1_700_000_000_000);
+        // Now is not scaled.
+        assert_eq!(lines[1].timestamp, 1_700_000_005_123);
+
+        let lines = parse_influx_lines_at(&req, 1, 1_700_000_005).unwrap();
+        assert_eq!(lines[0].timestamp, 1_700_000_000);
+        assert_eq!(lines[1].timestamp, 1_700_000_005);
+    }
+
+    #[test]
+    fn test_check_influx_retention_policy() {
+        use super::check_influx_retention_policy;
+
+        assert!(check_influx_retention_policy(None).is_ok());
+        assert!(check_influx_retention_policy(Some("")).is_ok());
+        assert!(check_influx_retention_policy(Some("autogen")).is_ok());
+        assert!(check_influx_retention_policy(Some("one_week")).is_err());
+    }
+
+    #[test]
+    fn test_check_lines_write_privilege() {
+        use std::collections::{BTreeSet, HashSet};
+
+        use models::auth::privilege::{
+            DatabasePrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
+        };
+        use models::auth::user::{User, UserDesc, UserOptions};
+        use protocol_parser::line_protocol::line_protocol_to_lines;
+        use spi::service::protocol::ContextBuilder;
+
+        use super::check_lines_write_privilege;
+
+        let context = |privilege: TenantObjectPrivilege| {
+            let desc = UserDesc::new(1, "u".to_string(), UserOptions::default(), false);
+            let user = User::new(
+                desc,
+                HashSet::from([Privilege::TenantObject(privilege, Some(0))]),
+                None,
+            );
+            ContextBuilder::new(user)
+                .with_database(Some("db".to_string()))
+                .build()
+        };
+        let table = |columns: &[&str]| {
+            let columns = columns
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<BTreeSet<_>>();
+            TenantObjectPrivilege::Table(
+                TablePrivilege::new(DatabasePrivilege::Write, Some(columns)),
+                "db".to_string(),
+                "air".to_string(),
+            )
+        };
+        let lines = line_protocol_to_lines("air,station=a f1=1 1\nair f2=2 2", 0).unwrap();
+
+        let ctx = context(table(&["station", "f1", "f2"]));
+        assert!(check_lines_write_privilege(&ctx, 0, &lines).is_ok());
+        let ctx = context(table(&["station", "f1"]));
+        assert!(check_lines_write_privilege(&ctx, 0, &lines).is_err());
+        let ctx = context(TenantObjectPrivilege::Database(
+            DatabasePrivilege::Write,
+            Some("db".to_string()),
+        ));
+        assert!(check_lines_write_privilege(&ctx, 0, &lines).is_ok());
+        // The other tables are not written with the privilege of a table.
+        let lines = line_protocol_to_lines("sea f1=1 1", 0).unwrap();
+        let ctx = context(table(&["station", "f1", "f2"]));
+        assert!(check_lines_write_privilege(&ctx, 0, &lines).is_err());
+    }
 }
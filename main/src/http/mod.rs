@@ -14,6 +14,7 @@ use self::response::ResponseBuilder;
 
 mod api_type;
 mod encoding;
+mod grafana;
 pub mod header;
 pub mod http_service;
 mod metrics;
@@ -135,6 +136,12 @@ pub enum Error {
     ParseOtlpProtocol {
         source: DecodeError,
     },
+
+    #[snafu(display("Invalid grafana request: {}", reason))]
+    #[error_code(code = 20)]
+    InvalidGrafanaRequest {
+        reason: String,
+    },
 }
 
 impl reject::Reject for Error {}
@@ -174,7 +181,8 @@ impl From<&Error> for Response {
             | Error::TraceHttp { .. }
             | Error::DecodeRequest { .. }
             | Error::ParseOpentsdbProtocol { .. }
-            | Error::ParseOpentsdbJsonProtocol { .. } => ResponseBuilder::bad_request(&error_resp),
+            | Error::ParseOpentsdbJsonProtocol { .. }
+            | Error::InvalidGrafanaRequest { .. } => ResponseBuilder::bad_request(&error_resp),
             _ => ResponseBuilder::internal_server_error(),
         }
     }
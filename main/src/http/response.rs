@@ -26,7 +26,7 @@ use warp::reply::Response;
 use warp::{hyper, Reply};
 
 use super::header::IntoHeaderPair;
-use super::result_format::ResultFormat;
+use super::result_format::{ResultFormat, TimeDisplay};
 use super::{Error as HttpError, MetaSnafu, QuerySnafu};
 
 #[derive(Default)]
@@ -126,6 +126,8 @@ pub enum HttpResponseStreamState {
 pub struct HttpResponse {
     result: Output,
     format: ResultFormat,
+    time_display: TimeDisplay,
+    time_zone: Option<String>,
     encoding: Option<Encoding>,
     schema: Option<SchemaRef>,
     http_query_data_out: U64Counter,
@@ -145,6 +147,8 @@ impl HttpResponse {
         Self {
             result,
             format,
+            time_display: TimeDisplay::Default,
+            time_zone: None,
             encoding,
             schema: Some(schema),
             limiter,
@@ -153,8 +157,34 @@ impl HttpResponse {
         }
     }
 
+    pub fn with_time_display(mut self, time_display: TimeDisplay) -> Self {
+        self.time_display = time_display;
+        self
+    }
+
+    /// Show the timestamps in the time zone of the session.
+    pub fn with_time_zone(mut self, time_zone: Option<String>) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    fn display_batch(&self, batch: RecordBatch) -> Result<RecordBatch, HttpError> {
+        self.time_display
+            .display_batch(batch, self.time_zone.as_deref())
+            .map_err(|e| HttpError::FetchResult {
+                reason: format!("{}", e),
+            })
+    }
+
     pub async fn wrap_batches_to_response(self) -> Result<Response, HttpError> {
-        let actual = self.result.chunk_result().await.context(QuerySnafu)?;
+        let actual = self
+            .result
+            .chunk_result()
+            .await
+            .context(QuerySnafu)?
+            .into_iter()
+            .map(|batch| self.display_batch(batch))
+            .collect::<Result<Vec<_>, _>>()?;
         self.format.wrap_batches_to_response(
             &actual,
             true,
@@ -180,7 +210,7 @@ impl HttpResponse {
             None => {
                 if let Some(schema) = self.schema.take() {
                     let has_headers = !schema.fields().is_empty();
-                    let rb = RecordBatch::new_empty(schema);
+                    let rb = self.display_batch(RecordBatch::new_empty(schema))?;
                     let mut buffer =
                         self.format
                             .format_batches(&[rb], has_headers)
@@ -213,6 +243,7 @@ impl HttpResponse {
             }
             Some(Ok(rb)) => {
                 if rb.num_rows() > 0 {
+                    let rb = self.display_batch(rb)?;
                     let mut buffer = self
                         .format
                         .format_batches(&[rb], self.schema.is_some())
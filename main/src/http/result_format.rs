@@ -1,6 +1,14 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
+use chrono::{SecondsFormat, TimeZone, Utc};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::array::{
+    Array, ArrayRef, Int64Array, StringArray, TimestampNanosecondArray,
+};
+use datafusion::arrow::compute::cast;
 use datafusion::arrow::csv::writer::WriterBuilder;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
 use datafusion::arrow::error::Result as ArrowResult;
 use datafusion::arrow::json::{ArrayWriter, LineDelimitedWriter};
 use datafusion::arrow::record_batch::RecordBatch;
@@ -154,6 +162,123 @@ impl FromStr for ResultFormat {
     }
 }
 
+/// Output of the timestamp columns of query results, set by the `time_display`
+/// session setting, so that clients need not guess the precision of them.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum TimeDisplay {
+    /// Formatted by the writer of the result format.
+    #[default]
+    Default,
+    /// RFC3339 strings in UTC, such as `2023-01-01T00:00:00.000000001Z`, or
+    /// with the offset of the time zone of the session.
+    Rfc3339,
+    /// Milliseconds since the unix epoch.
+    UnixMs,
+    /// Nanoseconds since the unix epoch.
+    UnixNs,
+}
+
+impl TimeDisplay {
+    /// Converts the timestamp columns of the batch to the output of the setting,
+    /// the timestamps are shown in the time zone of the session if it's set.
+    pub fn display_batch(
+        &self,
+        batch: RecordBatch,
+        time_zone: Option<&str>,
+    ) -> ArrowResult<RecordBatch> {
+        if (*self == Self::Default && time_zone.is_none())
+            || !batch
+                .schema()
+                .fields()
+                .iter()
+                .any(|f| matches!(f.data_type(), DataType::Timestamp(_, _)))
+        {
+            return Ok(batch);
+        }
+
+        let schema = batch.schema();
+        let mut fields = Vec::with_capacity(schema.fields().len());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            match field.data_type() {
+                DataType::Timestamp(_, _) => {
+                    let column = self.display_column(column, time_zone)?;
+                    fields.push(Arc::new(Field::new(
+                        field.name(),
+                        column.data_type().clone(),
+                        field.is_nullable(),
+                    )));
+                    columns.push(column);
+                }
+                _ => {
+                    fields.push(field.clone());
+                    columns.push(column.clone());
+                }
+            }
+        }
+
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+    }
+
+    fn display_column(&self, column: &ArrayRef, time_zone: Option<&str>) -> ArrowResult<ArrayRef> {
+        let unit = match self {
+            Self::UnixMs => TimeUnit::Millisecond,
+            _ => TimeUnit::Nanosecond,
+        };
+        let timestamps = cast(column, &DataType::Timestamp(unit, None))?;
+        if let (Self::Default, Some(time_zone)) = (self, time_zone) {
+            // the writers of the result formats show the timestamps in the time zone
+            let timestamps = timestamps
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .expect("cast to Timestamp(Nanosecond)")
+                .clone()
+                .with_timezone(time_zone);
+            return Ok(Arc::new(timestamps));
+        }
+        let timestamps = cast(&timestamps, &DataType::Int64)?;
+        if *self != Self::Rfc3339 {
+            return Ok(timestamps);
+        }
+
+        let tz = time_zone.map(Tz::from_str).transpose()?;
+        let nanos = timestamps
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .expect("cast to Int64");
+        let strings = nanos
+            .iter()
+            .map(|n| {
+                n.map(|n| match &tz {
+                    Some(tz) => tz
+                        .timestamp_nanos(n)
+                        .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                    None => Utc
+                        .timestamp_nanos(n)
+                        .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                })
+            })
+            .collect::<StringArray>();
+        Ok(Arc::new(strings))
+    }
+}
+
+impl FromStr for TimeDisplay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "default" => Ok(Self::Default),
+            "rfc3339" => Ok(Self::Rfc3339),
+            "unix_ms" => Ok(Self::UnixMs),
+            "unix_ns" => Ok(Self::UnixNs),
+            _ => Err(format!(
+                "time_display must be one of 'rfc3339', 'unix_ms', 'unix_ns' or 'default', got '{s}'"
+            )),
+        }
+    }
+}
+
 pub fn get_result_format_from_header(header: &Header) -> Result<ResultFormat, Rejection> {
     ResultFormat::try_from(header.get_accept()).map_err(|e| {
         let e = HttpError::InvalidHeader {
@@ -243,4 +368,65 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_time_display() {
+        use datafusion::arrow::array::TimestampNanosecondArray;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("a", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    1_672_531_200_000_000_000,
+                    1_672_531_200_001_000_001,
+                ])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+
+        let format = |display: &str| {
+            let batch = TimeDisplay::from_str(display)
+                .unwrap()
+                .display_batch(batch.clone(), None)
+                .unwrap();
+            String::from_utf8(batches_with_sep(&[batch], b',', true).unwrap()).unwrap()
+        };
+        assert_eq!(
+            format("rfc3339"),
+            "time,a\n2023-01-01T00:00:00Z,1\n2023-01-01T00:00:00.001000001Z,2\n"
+        );
+        assert_eq!(
+            format("UNIX_MS"),
+            "time,a\n1672531200000,1\n1672531200001,2\n"
+        );
+        assert_eq!(
+            format("unix_ns"),
+            "time,a\n1672531200000000000,1\n1672531200001000001,2\n"
+        );
+        assert!(TimeDisplay::from_str("unix_s").is_err());
+
+        let format_in = |display: TimeDisplay| {
+            let batch = display
+                .display_batch(batch.clone(), Some("+08:00"))
+                .unwrap();
+            String::from_utf8(batches_with_sep(&[batch], b',', true).unwrap()).unwrap()
+        };
+        assert_eq!(
+            format_in(TimeDisplay::Rfc3339),
+            "time,a\n2023-01-01T08:00:00+08:00,1\n2023-01-01T08:00:00.001000001+08:00,2\n"
+        );
+        assert_eq!(
+            format_in(TimeDisplay::UnixMs),
+            "time,a\n1672531200000,1\n1672531200001,2\n"
+        );
+    }
 }
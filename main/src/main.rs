@@ -12,6 +12,7 @@ use config::tskv::Config;
 use config::VERSION;
 use memory_pool::GreedyMemoryPool;
 use metrics::metric_register::MetricsRegister;
+use models::runtime::stats::ThreadPoolStats;
 use tokio::runtime::Runtime;
 use tokio::time::sleep;
 use trace::global_logging::init_global_logging;
@@ -20,6 +21,7 @@ use trace::info;
 
 use crate::report::ReportService;
 
+mod bench;
 mod flight_sql;
 mod http;
 mod opentelemetry;
@@ -29,6 +31,7 @@ mod server;
 mod signal;
 mod spi;
 mod tcp;
+mod write_check;
 
 /// cli examples is here
 /// <https://github.com/clap-rs/clap/blob/v3.1.3/examples/git-derive.rs>
@@ -40,7 +43,9 @@ Examples:
     # Run the CnosDB:
     cnosdb run
     # Check configuration file:
-    cnosdb check server-config ./config/config.toml"#)]
+    cnosdb check server-config ./config/config.toml
+    # Benchmark codecs over sample values:
+    cnosdb bench codec --file ./values.txt --data-type double"#)]
 struct Cli {
     #[command(subcommand)]
     subcmd: CliCommand,
@@ -57,6 +62,11 @@ enum CliCommand {
         #[command(subcommand)]
         subcmd: CheckCommand,
     },
+    /// Run benchmarks.
+    Bench {
+        #[command(subcommand)]
+        subcmd: BenchCommand,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -136,6 +146,12 @@ enum CheckCommand {
     // MetaConfig {},
 }
 
+#[derive(Debug, Subcommand)]
+enum BenchCommand {
+    /// Benchmark the compression ratio and throughput of the codecs over sample values.
+    Codec(bench::CodecBenchArgs),
+}
+
 #[cfg(unix)]
 #[global_allocator]
 static A: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
@@ -163,6 +179,12 @@ fn main() -> Result<(), std::io::Error> {
                 return Ok(());
             }
         },
+        CliCommand::Bench { subcmd } => match subcmd {
+            BenchCommand::Codec(args) => {
+                return bench::bench_codec(args)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e));
+            }
+        },
     };
 
     let config = parse_config(&run_args);
@@ -195,6 +217,11 @@ fn main() -> Result<(), std::io::Error> {
         }
 
         let _ = std::fs::create_dir_all(config.storage.path);
+        if !matches!(deployment_mode, DeploymentMode::Query) {
+            if let Err(e) = builder.install_tiered_storage() {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+            }
+        }
         let (storage, coordinator) = match deployment_mode {
             DeploymentMode::QueryTskv => builder.build_query_storage(&mut server).await,
             DeploymentMode::Tskv => builder.build_storage_server(&mut server).await,
@@ -222,8 +249,8 @@ fn main() -> Result<(), std::io::Error> {
         finalize_global_tracing();
 
         println!("CnosDB is stopped.");
-    });
-    Ok(())
+        Ok(())
+    })
 }
 
 fn parse_config(run_args: &RunArgs) -> config::tskv::Config {
@@ -239,20 +266,25 @@ fn parse_config(run_args: &RunArgs) -> config::tskv::Config {
 
 fn init_runtime(cores: Option<usize>) -> Result<Runtime, std::io::Error> {
     use tokio::runtime::Builder;
-    match cores {
-        None => Runtime::new(),
-        Some(cores) => match cores {
-            0 => Builder::new_multi_thread()
-                .enable_all()
-                .thread_stack_size(4 * 1024 * 1024)
-                .build(),
-            _ => Builder::new_multi_thread()
-                .enable_all()
-                .worker_threads(cores)
-                .thread_stack_size(4 * 1024 * 1024)
-                .build(),
-        },
+    let workers = match cores {
+        None | Some(0) => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        Some(cores) => cores,
+    };
+    let stats = ThreadPoolStats::register("main", workers);
+    let mut builder = Builder::new_multi_thread();
+    stats.instrument(&mut builder);
+    let thread_stats = stats.clone();
+    builder
+        .enable_all()
+        .worker_threads(workers)
+        .on_thread_start(move || thread_stats.thread_started());
+    if cores.is_some() {
+        builder.thread_stack_size(4 * 1024 * 1024);
     }
+    let runtime = builder.build()?;
+    stats.set_runtime(runtime.handle().clone());
+
+    Ok(runtime)
 }
 /// Merge the deployment configs(mode) between CLI arguments and config file,
 /// values in the CLI arguments (if any) has higher priority.
@@ -0,0 +1,133 @@
+use std::pin::Pin;
+
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PutResult, Result as ActionResult, SchemaResult, Ticket,
+};
+use futures::{Stream, StreamExt, TryStreamExt};
+use query::exchange::{ExchangeManager, ExchangeTicket};
+use tonic::{Request, Response, Status, Streaming};
+use trace::debug;
+
+type BoxedStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+/// Arrow Flight service exchanging the partitions of queries between nodes,
+/// only `do_get` with an [`ExchangeTicket`] is supported. A ticket reads the
+/// partitions only with the token of the exchange, which is known to the
+/// nodes executing the query only.
+#[derive(Default)]
+pub struct ExchangeServiceImpl {}
+
+#[tonic::async_trait]
+impl FlightService for ExchangeServiceImpl {
+    type HandshakeStream = BoxedStream<HandshakeResponse>;
+    type ListFlightsStream = BoxedStream<FlightInfo>;
+    type DoGetStream = BoxedStream<FlightData>;
+    type DoPutStream = BoxedStream<PutResult>;
+    type DoExchangeStream = BoxedStream<FlightData>;
+    type DoActionStream = BoxedStream<ActionResult>;
+    type ListActionsStream = BoxedStream<ActionType>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented(
+            "handshake is not supported by exchange",
+        ))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented(
+            "list_flights is not supported by exchange",
+        ))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented(
+            "get_flight_info is not supported by exchange",
+        ))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented(
+            "get_schema is not supported by exchange",
+        ))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let manager = ExchangeManager::get()
+            .ok_or_else(|| Status::unavailable("Exchange is not available on this node"))?;
+        debug!("Exchange do_get from {:?}", request.remote_addr());
+        // The partitions are read only with the token of the exchange.
+        let ticket = ExchangeTicket::decode(&request.into_inner().ticket)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        if let ExchangeTicket::Fetch {
+            exchange_id, token, ..
+        } = &ticket
+        {
+            manager
+                .check_token(exchange_id, token)
+                .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        }
+
+        let batches = manager
+            .execute(ticket)
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(|e| FlightError::ExternalError(Box::new(e)));
+        let stream = FlightDataEncoderBuilder::new()
+            .build(batches)
+            .map_err(Status::from);
+
+        Ok(Response::new(stream.boxed()))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put is not supported by exchange"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented(
+            "do_exchange is not supported by exchange",
+        ))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented(
+            "do_action is not supported by exchange",
+        ))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented(
+            "list_actions is not supported by exchange",
+        ))
+    }
+}
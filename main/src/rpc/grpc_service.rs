@@ -1,25 +1,47 @@
+use std::io;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
+use arrow_flight::flight_service_server::FlightServiceServer;
 use config::tskv::TLSConfig;
 use coordinator::service::CoordinatorRef;
+use futures::StreamExt;
 use metrics::metric_register::MetricsRegister;
+use models::runtime::stats::GrpcConnectionGuard;
 use protos::kv_service::tskv_service_server::TskvServiceServer;
 use protos::raft_service::raft_service_server::RaftServiceServer;
+use protos::tls::{ClusterTls, TlsStream};
 use protos::DEFAULT_GRPC_SERVER_MESSAGE_LEN;
+use query::auth::auth_control::{AccessControlImpl, AccessControlNoCheck};
 use replication::network_grpc::RaftCBServer;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::runtime::Runtime;
 use tokio::sync::oneshot;
+use tokio::time;
 use tonic::codec::CompressionEncoding;
+use tonic::transport::server::{Connected, TcpIncoming};
 use tonic::transport::{Identity, Server, ServerTlsConfig};
 use trace::http::tower_layer::TraceLayer;
+use trace::warn;
 use tskv::EngineRef;
 
+use crate::rpc::exchange::ExchangeServiceImpl;
 use crate::rpc::tskv::TskvServiceImpl;
 use crate::server::ServiceHandle;
 use crate::spi::service::Service;
 use crate::{info, server};
 
+const MAX_CONCURRENT_TLS_HANDSHAKES: usize = 128;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Options of the accepted connections, the same as the ones of the clients in
+/// the cluster: Nagle's algorithm is disabled so the small messages of raft are
+/// not delayed, and the dead peers are found by the keepalive.
+const TCP_NODELAY: bool = true;
+const TCP_KEEPALIVE: Option<Duration> = Some(Duration::from_secs(3600));
+
 pub struct GrpcService {
     addr: SocketAddr,
     runtime: Arc<Runtime>,
@@ -88,6 +110,9 @@ impl Service for GrpcService {
             coord: self.coord.clone(),
             metrics_register: self.metrics_register.clone(),
             grpc_enable_gzip: self.enable_gzip,
+            access_control: Arc::new(AccessControlImpl::new(AccessControlNoCheck::new(
+                self.coord.meta_manager(),
+            ))),
         })
         .max_decoding_message_size(DEFAULT_GRPC_SERVER_MESSAGE_LEN);
 
@@ -95,6 +120,9 @@ impl Service for GrpcService {
         let mut raft_grpc_service = RaftServiceServer::new(RaftCBServer::new(multi_raft))
             .max_decoding_message_size(DEFAULT_GRPC_SERVER_MESSAGE_LEN);
 
+        let exchange_grpc_service = FlightServiceServer::new(ExchangeServiceImpl::default())
+            .max_decoding_message_size(DEFAULT_GRPC_SERVER_MESSAGE_LEN);
+
         if self.enable_gzip {
             tskv_grpc_service = tskv_grpc_service
                 .accept_compressed(CompressionEncoding::Gzip)
@@ -109,13 +137,32 @@ impl Service for GrpcService {
             build_grpc_server!(&self.tls_config, self.auto_generate_span, "grpc");
         let grpc_router = grpc_builder
             .add_service(tskv_grpc_service)
-            .add_service(raft_grpc_service);
-        let server = grpc_router.serve_with_shutdown(self.addr, async {
+            .add_service(raft_grpc_service)
+            .add_service(exchange_grpc_service);
+        let incoming = TcpIncoming::new(self.addr, TCP_NODELAY, TCP_KEEPALIVE)
+            .map_err(|e| server::Error::Common {
+                reason: format!("bind grpc server addr {} failed: {}", self.addr, e),
+            })?
+            .map(|conn| conn.map(CountedConnection::new));
+        let shutdown_signal = async {
             rx.await.ok();
             info!("grpc server graceful shutdown!");
-        });
+        };
         info!("grpc server start addr: {}", self.addr);
-        let grpc_handle = tokio::spawn(server);
+        let grpc_handle = match protos::tls::cluster_tls() {
+            // The acceptor is taken for each connection, so the reloaded
+            // certificates are used by the new connections.
+            Some(tls) => {
+                let incoming = incoming
+                    .map(|conn| accept_tls(tls, conn))
+                    .buffer_unordered(MAX_CONCURRENT_TLS_HANDSHAKES)
+                    .filter_map(futures::future::ready);
+                tokio::spawn(grpc_router.serve_with_incoming_shutdown(incoming, shutdown_signal))
+            }
+            None => {
+                tokio::spawn(grpc_router.serve_with_incoming_shutdown(incoming, shutdown_signal))
+            }
+        };
         self.handle = Some(ServiceHandle::new(
             "grpc service".to_string(),
             grpc_handle,
@@ -130,3 +177,89 @@ impl Service for GrpcService {
         };
     }
 }
+
+/// Accept the connection with the mutual TLS of the cluster, returns None if
+/// the handshake failed, which should not stop the server.
+async fn accept_tls<IO: AsyncRead + AsyncWrite + Unpin>(
+    tls: &ClusterTls,
+    conn: io::Result<IO>,
+) -> Option<io::Result<TlsStream<IO>>> {
+    let conn = match conn {
+        Ok(conn) => conn,
+        Err(e) => return Some(Err(e)),
+    };
+    match time::timeout(TLS_HANDSHAKE_TIMEOUT, tls.acceptor().accept(conn)).await {
+        Ok(Ok(conn)) => Some(Ok(conn)),
+        Ok(Err(e)) => {
+            warn!("grpc server TLS handshake failed: {}", e);
+            None
+        }
+        Err(_) => {
+            warn!("grpc server TLS handshake timed out");
+            None
+        }
+    }
+}
+
+/// Connection counted as an open gRPC connection until closed.
+struct CountedConnection<IO> {
+    inner: IO,
+    _guard: GrpcConnectionGuard,
+}
+
+impl<IO> CountedConnection<IO> {
+    fn new(inner: IO) -> Self {
+        Self {
+            inner,
+            _guard: GrpcConnectionGuard::new(),
+        }
+    }
+}
+
+impl<IO: Connected> Connected for CountedConnection<IO> {
+    type ConnectInfo = IO::ConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        self.inner.connect_info()
+    }
+}
+
+impl<IO: AsyncRead + Unpin> AsyncRead for CountedConnection<IO> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for CountedConnection<IO> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
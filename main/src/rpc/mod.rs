@@ -1,2 +1,4 @@
+pub mod exchange;
 pub mod grpc_service;
+pub mod otlp;
 pub mod tskv;
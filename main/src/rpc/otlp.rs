@@ -0,0 +1,308 @@
+use std::net::SocketAddr;
+
+use config::tskv::TLSConfig;
+use coordinator::service::CoordinatorRef;
+use http_protocol::header::{DB, TENANT};
+use models::auth::user::User;
+use models::oid::{Identifier, Oid};
+use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
+use models::utils::now_timestamp_nanos;
+use prost::Message;
+use protocol_parser::otlp_metrics::otlp_metrics_to_lines;
+use protocol_parser::Line;
+use protos::kv_service::WriteConsistency;
+use protos::metrics_service::metrics_service_server::{MetricsService, MetricsServiceServer};
+use protos::metrics_service::{ExportMetricsServiceRequest, ExportMetricsServiceResponse};
+use protos::DEFAULT_GRPC_SERVER_MESSAGE_LEN;
+use spi::server::dbms::DBMSRef;
+use tokio::sync::oneshot;
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status};
+use trace::http::tower_layer::TraceLayer;
+use trace::span_ext::SpanExt;
+use trace::{debug, info, Span};
+use utils::precision::Precision;
+
+use crate::flight_sql::auth_middleware::basic_call_header_authenticator::BasicCallHeaderAuthenticator;
+use crate::flight_sql::auth_middleware::{AuthResult, CallHeaderAuthenticator};
+use crate::server::ServiceHandle;
+use crate::spi::service::Service;
+use crate::write_check::{
+    check_lines_write_privilege, check_password_changed, check_write_limiter, limiter_status,
+};
+use crate::{build_grpc_server, server};
+
+/// Receiver of the OpenTelemetry OTLP/gRPC metrics protocol.
+///
+/// The target tenant and database are taken from the `tenant` and `db` request
+/// headers, the user is authenticated by the `authorization` header.
+pub struct OtlpService {
+    addr: SocketAddr,
+    dbms: DBMSRef,
+    coord: CoordinatorRef,
+    tls_config: Option<TLSConfig>,
+    auto_generate_span: bool,
+    handle: Option<ServiceHandle<Result<(), tonic::transport::Error>>>,
+}
+
+impl OtlpService {
+    pub fn new(
+        dbms: DBMSRef,
+        coord: CoordinatorRef,
+        addr: SocketAddr,
+        tls_config: Option<TLSConfig>,
+        auto_generate_span: bool,
+    ) -> Self {
+        Self {
+            addr,
+            dbms,
+            coord,
+            tls_config,
+            auto_generate_span,
+            handle: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Service for OtlpService {
+    fn start(&mut self) -> server::Result<()> {
+        let (shutdown, rx) = oneshot::channel();
+
+        let metrics_service = MetricsServiceServer::new(OtlpMetricsServiceImpl {
+            coord: self.coord.clone(),
+            authenticator: BasicCallHeaderAuthenticator::new(self.dbms.clone()),
+        })
+        .max_decoding_message_size(DEFAULT_GRPC_SERVER_MESSAGE_LEN);
+
+        let mut grpc_builder =
+            build_grpc_server!(&self.tls_config, self.auto_generate_span, "otlp");
+        let server = grpc_builder
+            .add_service(metrics_service)
+            .serve_with_shutdown(self.addr, async {
+                rx.await.ok();
+                info!("otlp server graceful shutdown!");
+            });
+        info!("otlp server start addr: {}", self.addr);
+        let handle = tokio::spawn(server);
+        self.handle = Some(ServiceHandle::new(
+            "otlp service".to_string(),
+            handle,
+            shutdown,
+        ));
+        Ok(())
+    }
+
+    async fn stop(&mut self, force: bool) {
+        if let Some(stop) = self.handle.take() {
+            stop.shutdown(force).await
+        };
+    }
+}
+
+struct OtlpMetricsServiceImpl {
+    coord: CoordinatorRef,
+    authenticator: BasicCallHeaderAuthenticator,
+}
+
+impl OtlpMetricsServiceImpl {
+    /// Authenticate the user and check the write limits of the tenant, returns
+    /// the target of the export.
+    async fn authorize(
+        &self,
+        metadata: &MetadataMap,
+        client_addr: Option<&str>,
+        req_len: usize,
+    ) -> Result<ExportTarget, Status> {
+        let user = self
+            .authenticator
+            .authenticate(metadata, client_addr)
+            .await?
+            .identity();
+        let tenant = header_value(metadata, TENANT).unwrap_or(DEFAULT_CATALOG);
+        let db = header_value(metadata, DB).unwrap_or(DEFAULT_DATABASE);
+
+        let tenant_id = *self
+            .coord
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| Status::not_found(format!("tenant {} not found", tenant)))?
+            .tenant()
+            .id();
+        check_password_changed(&user, self.coord.get_config().query.auth_enabled)
+            .map_err(|e| Status::permission_denied(e.to_string()))?;
+        check_write_limiter(&self.coord.meta_manager(), tenant, req_len)
+            .await
+            .map_err(limiter_status)?;
+
+        Ok(ExportTarget {
+            user,
+            tenant: tenant.to_string(),
+            tenant_id,
+            db: db.to_string(),
+        })
+    }
+}
+
+/// The authenticated user and the database written by an export.
+struct ExportTarget {
+    user: User,
+    tenant: String,
+    tenant_id: Oid,
+    db: String,
+}
+
+/// Convert the export request to lines and check the write privileges of the
+/// tables and columns written by them.
+fn lines_to_write(
+    target: &ExportTarget,
+    req: ExportMetricsServiceRequest,
+) -> Result<Vec<Line<'static>>, Status> {
+    let lines = otlp_metrics_to_lines(req, now_timestamp_nanos());
+    check_lines_write_privilege(&target.user, target.tenant_id, &target.db, &lines)
+        .map_err(|e| Status::permission_denied(e.to_string()))?;
+    Ok(lines)
+}
+
+#[tonic::async_trait]
+impl MetricsService for OtlpMetricsServiceImpl {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let client_addr = request.remote_addr().map(|addr| addr.to_string());
+        let req_len = request.get_ref().encoded_len();
+        let target = self
+            .authorize(request.metadata(), client_addr.as_deref(), req_len)
+            .await?;
+
+        let lines = lines_to_write(&target, request.into_inner())?;
+        debug!(
+            "otlp export {} data points to {}.{}",
+            lines.len(),
+            target.tenant,
+            target.db
+        );
+        if lines.is_empty() {
+            return Ok(Response::new(ExportMetricsServiceResponse::default()));
+        }
+
+        let span = Span::from_context("otlp metrics export", None);
+        self.coord
+            .write_lines(
+                &target.tenant,
+                &target.db,
+                Precision::NS,
+                WriteConsistency::Quorum,
+                lines,
+                span.context().as_ref(),
+            )
+            .await
+            .map_err(|e| {
+                span.error(e.to_string());
+                Status::internal(e.to_string())
+            })?;
+
+        Ok(Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+fn header_value<'a>(metadata: &'a MetadataMap, key: &str) -> Option<&'a str> {
+    metadata
+        .get(key)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+
+    use models::auth::privilege::{
+        DatabasePrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
+    };
+    use models::auth::user::{User, UserDesc, UserOptions};
+    use protos::common::{any_value, AnyValue, KeyValue};
+    use protos::metrics::{
+        metric, number_data_point, Gauge, Metric, NumberDataPoint, ResourceMetrics, ScopeMetrics,
+    };
+    use protos::metrics_service::ExportMetricsServiceRequest;
+
+    use super::{lines_to_write, ExportTarget};
+
+    fn target(privilege: TenantObjectPrivilege) -> ExportTarget {
+        let desc = UserDesc::new(1, "u".to_string(), UserOptions::default(), false);
+        let user = User::new(
+            desc,
+            HashSet::from([Privilege::TenantObject(privilege, Some(0))]),
+            None,
+        );
+        ExportTarget {
+            user,
+            tenant: "cnosdb".to_string(),
+            tenant_id: 0,
+            db: "db".to_string(),
+        }
+    }
+
+    fn table(table: &str, columns: &[&str]) -> TenantObjectPrivilege {
+        let columns = columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<BTreeSet<_>>();
+        TenantObjectPrivilege::Table(
+            TablePrivilege::new(DatabasePrivilege::Write, Some(columns)),
+            "db".to_string(),
+            table.to_string(),
+        )
+    }
+
+    fn cpu_request() -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: None,
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics: vec![Metric {
+                        name: "cpu".to_string(),
+                        data: Some(metric::Data::Gauge(Gauge {
+                            data_points: vec![NumberDataPoint {
+                                attributes: vec![KeyValue {
+                                    key: "host".to_string(),
+                                    value: Some(AnyValue {
+                                        value: Some(any_value::Value::StringValue(
+                                            "h1".to_string(),
+                                        )),
+                                    }),
+                                }],
+                                time_unix_nano: 1,
+                                value: Some(number_data_point::Value::AsDouble(0.5)),
+                                ..Default::default()
+                            }],
+                        })),
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_lines_to_write() {
+        let db = TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some("db".to_string()));
+        assert_eq!(lines_to_write(&target(db), cpu_request()).unwrap().len(), 1);
+        let cpu = table("cpu", &["host", "value"]);
+        assert!(lines_to_write(&target(cpu), cpu_request()).is_ok());
+
+        // The user restricted to other tables or to some columns of the table is rejected.
+        let mem = table("mem", &["host", "value"]);
+        let status = lines_to_write(&target(mem), cpu_request()).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let cpu_value = table("cpu", &["value"]);
+        let status = lines_to_write(&target(cpu_value), cpu_request()).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+}
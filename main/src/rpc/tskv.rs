@@ -1,32 +1,66 @@
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use coordinator::errors::{
-    encode_grpc_response, ArrowSnafu, CommonSnafu, CoordinatorResult, TskvSnafu,
+    encode_grpc_response, ArrowSnafu, BincodeSerdeSnafu, CommonSnafu, CoordinatorResult,
+    ModelsSnafu, TskvSnafu,
 };
 use coordinator::service::CoordinatorRef;
-use futures::{Stream, TryStreamExt};
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::sql::sqlparser::ast::{Ident, SqlOption};
+use datafusion::sql::sqlparser::dialect::GenericDialect;
+use datafusion::sql::sqlparser::parser::Parser;
+use futures::{Stream, StreamExt, TryStreamExt};
+use http_protocol::header::PRIVATE_KEY;
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
-use models::meta_data::VnodeInfo;
-use models::predicate::domain::{self, PushedAggregateFunction, QueryArgs, QueryExpr};
+use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
+use models::meta_data::{ReplicationSet, VnodeAllInfo, VnodeInfo};
+use models::oid::Identifier;
+use models::predicate::domain::{
+    self, ColumnDomains, PushedAggregateFunction, QueryArgs, QueryExpr, ResolvedPredicate,
+    TimeRanges,
+};
+use models::predicate::PlacedSplit;
 use models::record_batch_encode;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
 use protos::kv_service::tskv_service_server::TskvService;
 use protos::kv_service::*;
 use protos::models::{PingBody, PingBodyBuilder};
 use snafu::ResultExt;
-use tokio::io::AsyncReadExt;
+use spi::query::auth::{AccessControl, AccessControlRef};
+use spi::query::datasource::{build_object_store, UriSchema};
+use spi::query::logical_planner::parse_connection_options;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataMap;
 use tonic::{Extensions, Request, Response, Status};
+use trace::sampler::{TraceSampled, SAMPLED_PROPERTY};
 use trace::span_ext::SpanExt;
 use trace::{debug, error, info, Span, SpanContext};
-use tskv::error::TskvResult;
+use tskv::error::{ModelErrorSnafu, TskvResult};
+use tskv::file_system::file_info::get_file_info;
 use tskv::reader::query_executor::QueryExecutor;
+use tskv::reader::scan_metrics::ScanMetrics;
 use tskv::reader::serialize::TonicRecordBatchEncoder;
 use tskv::reader::{QueryOption, SendableTskvRecordBatchStream};
-use tskv::EngineRef;
+use tskv::{tiering, EngineRef};
+use url::Url;
+
+use crate::flight_sql::utils;
+use crate::http::header::Header;
+
+/// Size of the chunks of a tiered tsm file sent, each chunk is a read of the
+/// object storage.
+const TIERED_FILE_BUFFER_SIZE: usize = 1024 * 1024;
+/// Rows of the record batches of the exported data.
+const EXPORT_BATCH_SIZE: usize = 4096;
 
 type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send>>;
 
@@ -37,6 +71,7 @@ pub struct TskvServiceImpl {
     pub coord: CoordinatorRef,
     pub metrics_register: Arc<MetricsRegister>,
     pub grpc_enable_gzip: bool,
+    pub access_control: AccessControlRef,
 }
 
 impl TskvServiceImpl {
@@ -44,6 +79,55 @@ impl TskvServiceImpl {
         tonic::Status::new(tonic::Code::Internal, msg)
     }
 
+    /// Authenticate the user by the `authorization` header and check the read
+    /// privilege of the database.
+    async fn check_read_privilege(
+        &self,
+        metadata: &MetadataMap,
+        tenant: &str,
+        db: &str,
+    ) -> Result<(), Status> {
+        let authorization = utils::get_value_from_auth_header(metadata, "")
+            .ok_or_else(|| Status::unauthenticated("authorization field not present"))?;
+        let private_key = utils::get_value_from_header(metadata, PRIVATE_KEY, "");
+        let user_info = Header::with_private_key(
+            None,
+            None,
+            None,
+            authorization,
+            private_key,
+            None,
+            None,
+            None,
+        )
+        .try_get_basic_auth()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let user = self
+            .access_control
+            .access_check(&user_info, tenant)
+            .await
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+        let tenant_id = *self
+            .coord
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| Status::not_found(format!("tenant {} not found", tenant)))?
+            .tenant()
+            .id();
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(db.to_string())),
+            Some(tenant_id),
+        );
+        if !user.check_privilege(&privilege) {
+            return Err(Status::permission_denied(format!(
+                "insufficient privileges, need {privilege}"
+            )));
+        }
+
+        Ok(())
+    }
+
     async fn warp_admin_request(
         &self,
         tenant: &str,
@@ -92,6 +176,7 @@ impl TskvServiceImpl {
                         &command.db_name,
                         command.follower_nid,
                         command.replica_id,
+                        command.learner,
                     )
                     .await?;
                 Ok(vec![])
@@ -118,16 +203,18 @@ impl TskvServiceImpl {
                 Ok(vec![])
             }
             admin_command::Command::PromoteLeader(command) => {
-                self.coord
+                let log_lag = self
+                    .coord
                     .raft_manager()
                     .promote_follower_to_leader(
                         tenant,
                         &command.db_name,
                         command.new_leader_id,
                         command.replica_id,
+                        command.max_log_lag,
                     )
                     .await?;
-                Ok(vec![])
+                Ok(log_lag.to_be_bytes().to_vec())
             }
             admin_command::Command::LearnerToFollower(command) => {
                 self.coord
@@ -151,6 +238,172 @@ impl TskvServiceImpl {
                     .await?;
                 Ok(vec![])
             }
+            admin_command::Command::ExportVnode(command) => {
+                let (object_store, prefix) =
+                    build_export_object_store(&command.path, &command.connection_options)?;
+                let rows = self
+                    .kv_inst
+                    .export_vnode(
+                        command.vnode_id,
+                        domain::TimeRange::new(command.min_ts, command.max_ts),
+                        object_store,
+                        prefix,
+                    )
+                    .await
+                    .context(TskvSnafu)?;
+                Ok(rows.to_be_bytes().to_vec())
+            }
+            admin_command::Command::BackupVnode(command) => {
+                let (object_store, prefix) =
+                    build_export_object_store(&command.path, &command.connection_options)?;
+                let backup = self
+                    .kv_inst
+                    .backup_vnode(command.vnode_id, object_store, prefix)
+                    .await
+                    .context(TskvSnafu)?;
+                let data = bincode::serialize(&backup).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::RestoreVnode(command) => {
+                let (object_store, prefix) =
+                    build_export_object_store(&command.path, &command.connection_options)?;
+                let backup = bincode::deserialize(&command.backup).context(BincodeSerdeSnafu)?;
+                self.kv_inst
+                    .restore_vnode(
+                        tenant,
+                        &command.db_name,
+                        command.vnode_id,
+                        backup,
+                        object_store,
+                        prefix,
+                    )
+                    .await
+                    .context(TskvSnafu)?;
+                Ok(vec![])
+            }
+            admin_command::Command::FetchFilesMeta(command) => {
+                let opt = self.kv_inst.get_storage_options();
+                let mut files = Vec::with_capacity(command.filenames.len());
+                for filename in command.filenames.iter() {
+                    let path = opt.vnode_file_path(filename).context(TskvSnafu)?;
+                    // The tiered files are not reused by the other nodes, they are
+                    // downloaded as a whole.
+                    if !path.exists() && tiering::is_tiered(&path) {
+                        continue;
+                    }
+                    let mut info = get_file_info(&path.to_string_lossy())
+                        .await
+                        .context(TskvSnafu)?;
+                    info.name = filename.clone();
+                    files.push(info);
+                }
+                let data = bincode::serialize(&files).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::FetchCardinality(command) => {
+                let record = self
+                    .kv_inst
+                    .series_cardinality_top(
+                        tenant,
+                        &command.db_name,
+                        &command.vnode_ids,
+                        command.top_k as usize,
+                    )
+                    .await
+                    .context(TskvSnafu)?;
+                let data = record_batch_encode(&record).context(ArrowSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::EstimateScanCost(command) => {
+                let predicate =
+                    ResolvedPredicate::decode(&command.predicate).context(ModelsSnafu)?;
+                let cost = self
+                    .kv_inst
+                    .estimate_scan_cost(
+                        tenant,
+                        &command.db_name,
+                        &command.table,
+                        command.vnode_id,
+                        &predicate,
+                    )
+                    .await
+                    .context(TskvSnafu)?;
+                Ok(cost.encode())
+            }
+            admin_command::Command::FetchVnodesDiskStorage(command) => {
+                let sizes = self.kv_inst.vnodes_disk_storage(&command.vnode_ids).await;
+                let data = bincode::serialize(&sizes).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::FetchVnodesRows(command) => {
+                let rows = self.kv_inst.vnodes_rows(&command.vnode_ids).await;
+                let data = bincode::serialize(&rows).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::CloseVnodesFiles(command) => {
+                let closed = self.kv_inst.close_vnodes_files(&command.vnode_ids).await;
+                let data = bincode::serialize(&closed).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::ExplainCompaction(command) => {
+                let plans = self.kv_inst.explain_compaction(command.vnode_id).await;
+                let data = bincode::serialize(&plans).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::FetchCompactions(_) => {
+                let jobs = self.kv_inst.compaction_jobs();
+                let data = bincode::serialize(&jobs).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::KillCompaction(command) => {
+                let killed = self.kv_inst.kill_compaction(command.job_id);
+                Ok(vec![killed as u8])
+            }
+            admin_command::Command::FetchRecoveryProgress(_) => {
+                let progress = self.coord.raft_manager().recovery().progress(Some(tenant));
+                let data = bincode::serialize(&progress).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::ReadIndex(command) => {
+                let index = self
+                    .coord
+                    .raft_manager()
+                    .read_index(tenant, &command.db_name, command.replica_id)
+                    .await?;
+                Ok(index.to_be_bytes().to_vec())
+            }
+            admin_command::Command::WaitApplied(command) => {
+                self.coord
+                    .raft_manager()
+                    .wait_applied(
+                        command.replica_id,
+                        command.vnode_id,
+                        command.index,
+                        Duration::from_millis(command.timeout_ms),
+                    )
+                    .await?;
+                Ok(vec![])
+            }
+            admin_command::Command::TableSeriesCount(command) => {
+                let count = self
+                    .kv_inst
+                    .table_series_count(tenant, &command.db_name, &command.table, command.vnode_id)
+                    .await
+                    .context(TskvSnafu)?;
+                Ok(count.to_be_bytes().to_vec())
+            }
+            admin_command::Command::TableLastWrite(command) => {
+                let index = self
+                    .kv_inst
+                    .table_last_write(
+                        command.vnode_id,
+                        &command.table,
+                        &domain::TimeRange::new(command.min_ts, command.max_ts),
+                    )
+                    .await
+                    .context(TskvSnafu)?;
+                Ok(index.map(|i| i.to_be_bytes().to_vec()).unwrap_or_default())
+            }
         }
     }
 
@@ -159,6 +412,7 @@ impl TskvServiceImpl {
         args: QueryArgs,
         expr: QueryExpr,
         aggs: Option<Vec<PushedAggregateFunction>>,
+        scan_metrics: ScanMetrics,
         span_ctx: Option<&SpanContext>,
     ) -> TskvResult<SendableTskvRecordBatchStream> {
         let option = QueryOption::new(
@@ -168,7 +422,8 @@ impl TskvServiceImpl {
             Arc::new(expr.df_schema),
             expr.table_schema,
             expr.schema_meta,
-        );
+        )
+        .with_scan_metrics(scan_metrics);
 
         let meta = self.coord.meta_manager();
         let node_id = meta.node_id();
@@ -181,6 +436,42 @@ impl TskvServiceImpl {
         executor.local_node_executor(vnodes, span_ctx)
     }
 
+    /// Scan the table in the vnode like a query, so the data deleted or
+    /// overwritten is not exported.
+    fn export_table_exec(
+        &self,
+        vnode: &VnodeAllInfo,
+        schema: TskvTableSchemaRef,
+        time_range: domain::TimeRange,
+    ) -> TskvResult<SendableTskvRecordBatchStream> {
+        let predicate = ResolvedPredicate::new(
+            Arc::new(TimeRanges::new(vec![time_range])),
+            ColumnDomains::all(),
+            None,
+        )
+        .context(ModelErrorSnafu)?;
+        let vnode_info = VnodeInfo::new(vnode.vnode_id, vnode.node_id);
+        let replica_set = ReplicationSet::new(
+            vnode.repl_set_id,
+            vnode.node_id,
+            vnode.vnode_id,
+            vec![vnode_info.clone()],
+        );
+        let split = PlacedSplit::new(0, Arc::new(predicate), None, replica_set);
+        let option = QueryOption::new(
+            EXPORT_BATCH_SIZE,
+            split,
+            None,
+            schema.to_arrow_schema(),
+            schema.clone(),
+            schema.meta(),
+        );
+
+        let meta = self.coord.meta_manager();
+        let executor = QueryExecutor::new(option, self.runtime.clone(), meta, self.kv_inst.clone());
+        executor.local_node_executor(vec![vnode_info], None)
+    }
+
     fn tag_scan_exec(
         args: QueryArgs,
         expr: QueryExpr,
@@ -210,6 +501,49 @@ impl TskvServiceImpl {
     }
 }
 
+/// Build the object store of the export path, local paths are on the data node.
+fn build_export_object_store(
+    path: &str,
+    connection_options: &HashMap<String, String>,
+) -> CoordinatorResult<(Arc<dyn ObjectStore>, ObjectStorePath)> {
+    let common_err = |msg: String| CommonSnafu { msg }.build();
+
+    let mut options = Vec::with_capacity(connection_options.len());
+    for (name, value) in connection_options {
+        // Values are in SQL literal, parse them back to keep the type.
+        let value = Parser::new(&GenericDialect {})
+            .try_with_sql(value)
+            .and_then(|mut p| p.parse_value())
+            .map_err(|e| common_err(e.to_string()))?;
+        options.push(SqlOption {
+            name: Ident::new(name),
+            value,
+        });
+    }
+
+    build_object_store_by_url(path, options)
+}
+
+/// Build the object store of the url `path`, e.g. `s3://bucket/prefix`, returns
+/// the store and the prefix of the path in it.
+pub(crate) fn build_object_store_by_url(
+    path: &str,
+    options: Vec<SqlOption>,
+) -> CoordinatorResult<(Arc<dyn ObjectStore>, ObjectStorePath)> {
+    let common_err = |msg: String| CommonSnafu { msg }.build();
+
+    let table_path = ListingTableUrl::parse(path).map_err(|e| common_err(e.to_string()))?;
+    let url: &Url = table_path.as_ref();
+    let connection_options =
+        parse_connection_options(&UriSchema::from(url.scheme()), url.host_str(), options)
+            .map_err(|e| common_err(e.to_string()))?;
+    let object_store = build_object_store(connection_options)
+        .map_err(|e| common_err(e.to_string()))?
+        .unwrap_or_else(|| Arc::new(LocalFileSystem::new()));
+
+    Ok((object_store, table_path.prefix().clone()))
+}
+
 #[tonic::async_trait]
 impl TskvService for TskvServiceImpl {
     async fn ping(
@@ -290,24 +624,58 @@ impl TskvService for TskvServiceImpl {
     ) -> Result<tonic::Response<Self::DownloadFileStream>, tonic::Status> {
         let inner = request.into_inner();
         let opt = self.kv_inst.get_storage_options();
-        let filename = opt.path().join(inner.filename);
-        info!("request download file name: {:?}", filename);
+        let filename = opt
+            .vnode_file_path(&inner.filename)
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        info!(
+            "request download file name: {:?}, offset: {}",
+            filename, inner.offset
+        );
+
+        // The tiered tsm files are read from the object storage.
+        let tiered = tiering::is_tiered(&filename);
+        let reader = tiering::open_file(&filename)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        let buffer_size = if tiered {
+            TIERED_FILE_BUFFER_SIZE
+        } else {
+            8 * 1024
+        };
 
+        let recovery = self.coord.raft_manager().recovery();
         let (send, recv) = mpsc::channel(1024);
         tokio::spawn(async move {
-            if let Ok(mut file) = tokio::fs::File::open(filename).await {
-                let mut buffer = vec![0; 8 * 1024];
-                while let Ok(len) = file.read(&mut buffer).await {
-                    if len == 0 {
+            let mut buffer = vec![0; buffer_size];
+            let mut pos = inner.offset as usize;
+            loop {
+                let len = match reader.read_at(pos, &mut buffer).await {
+                    Ok(0) => break,
+                    Ok(len) => len,
+                    Err(e) => {
+                        let _ = send
+                            .send(Err(tonic::Status::internal(format!(
+                                "read file {}: {e}",
+                                reader.path().display()
+                            ))))
+                            .await;
                         break;
                     }
-
-                    let _ = send
-                        .send(Ok(BatchBytesResponse {
-                            code: coordinator::errors::SUCCESS_RESPONSE_CODE,
-                            data: (buffer[0..len]).to_vec(),
-                        }))
-                        .await;
+                };
+                pos += len;
+
+                recovery.throttle_send(len).await;
+                let mut resp = BatchBytesResponse {
+                    code: coordinator::errors::SUCCESS_RESPONSE_CODE,
+                    data: (buffer[0..len]).to_vec(),
+                    checksum: None,
+                    scan_metrics: None,
+                };
+                if inner.checksum {
+                    resp = resp.with_checksum();
+                }
+                if send.send(Ok(resp)).await.is_err() {
+                    break;
                 }
             }
         });
@@ -346,14 +714,20 @@ impl TskvService for TskvServiceImpl {
         let encoded_stream = {
             let span = Span::enter_with_parent("RecordBatch encorder stream", &span);
 
+            let scan_metrics = ScanMetrics::default();
             let stream = TskvServiceImpl::query_record_batch_exec(
                 service,
                 args,
                 expr,
                 aggs,
+                scan_metrics.clone(),
                 span.context().as_ref(),
             )?;
-            TonicRecordBatchEncoder::new(stream, span).map_err(Into::into)
+            let mut encoder = TonicRecordBatchEncoder::new(stream, inner.checksum, span);
+            if inner.scan_metrics {
+                encoder = encoder.with_scan_metrics(scan_metrics);
+            }
+            encoder.map_err(Into::into)
         };
 
         Ok(tonic::Response::new(Box::pin(encoded_stream)))
@@ -388,14 +762,114 @@ impl TskvService for TskvServiceImpl {
                 span.context().as_ref(),
             )?;
 
-            TonicRecordBatchEncoder::new(stream, span).map_err(Into::into)
+            TonicRecordBatchEncoder::new(stream, inner.checksum, span).map_err(Into::into)
         };
 
         Ok(tonic::Response::new(Box::pin(stream)))
     }
+
+    type SubscribeStream = ResponseStream<SubscribeResponse>;
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let metadata = request.metadata().clone();
+        let inner = request.into_inner();
+        self.check_read_privilege(&metadata, &inner.tenant, &inner.db)
+            .await?;
+        let mut subscription = self
+            .kv_inst
+            .subscribe_writes(&inner.tenant, &inner.db, inner.start_offset)
+            .map_err(Status::from)?;
+        info!(
+            "subscribe writes of {}.{} from offset {}",
+            inner.tenant, inner.db, inner.start_offset
+        );
+
+        let (send, recv) = mpsc::channel(1024);
+        tokio::spawn(async move {
+            loop {
+                let entry = tokio::select! {
+                    entry = subscription.next() => entry,
+                    // The subscriber is disconnected.
+                    _ = send.closed() => break,
+                };
+                let resp = match entry {
+                    Ok(Some(entry)) => Ok(SubscribeResponse {
+                        offset: entry.offset,
+                        vnode_id: entry.vnode_id,
+                        index: entry.index,
+                        precision: entry.precision,
+                        points: entry.points.clone(),
+                    }),
+                    Ok(None) => break,
+                    Err(err) => Err(Status::from(err)),
+                };
+                let is_err = resp.is_err();
+                if send.send(resp).await.is_err() || is_err {
+                    break;
+                }
+            }
+        });
+
+        Ok(tonic::Response::new(Box::pin(ReceiverStream::new(recv))))
+    }
+
+    type ExportVnodeDataStream = ResponseStream<ExportVnodeDataResponse>;
+    async fn export_vnode_data(
+        &self,
+        request: Request<ExportVnodeDataRequest>,
+    ) -> Result<Response<Self::ExportVnodeDataStream>, Status> {
+        let inner = request.into_inner();
+        info!(
+            "export data of vnode {} in time range [{}, {}]",
+            inner.vnode_id, inner.min_ts, inner.max_ts
+        );
+        let client = self.coord.tenant_meta(&inner.tenant).await.ok_or_else(|| {
+            self.internal_status(format!("Not Found tenant({}) meta", inner.tenant))
+        })?;
+        let vnode = client
+            .get_vnode_all_info(inner.vnode_id)
+            .ok_or_else(|| self.internal_status(format!("Not Found Vnode({})", inner.vnode_id)))?;
+        let mut schemas = vec![];
+        let tables = client
+            .list_tables(&vnode.db_name)
+            .map_err(|err| self.internal_status(err.to_string()))?;
+        for table in tables {
+            if let Some(schema) = client
+                .get_tskv_table_schema(&vnode.db_name, &table)
+                .map_err(|err| self.internal_status(err.to_string()))?
+            {
+                schemas.push(schema);
+            }
+        }
+
+        let time_range = domain::TimeRange::new(inner.min_ts, inner.max_ts);
+        let service = self.clone();
+        let stream = futures::stream::iter(schemas).flat_map(move |schema| {
+            let table = schema.name.clone();
+            match service.export_table_exec(&vnode, schema, time_range) {
+                Ok(stream) => stream.map_ok(move |batch| (table.clone(), batch)).boxed(),
+                Err(err) => futures::stream::once(async move { Err(err) }).boxed(),
+            }
+        });
+        let stream = stream
+            .map_err(Status::from)
+            .and_then(|(table, batch)| async move {
+                let data =
+                    record_batch_encode(&batch).map_err(|e| Status::internal(e.to_string()))?;
+                Ok(ExportVnodeDataResponse { table, data })
+            });
+
+        Ok(tonic::Response::new(Box::pin(stream)))
+    }
 }
 
 fn get_span(extensions: &Extensions, child_span_name: &'static str) -> Span {
     let context = extensions.get::<SpanContext>();
-    Span::from_context(child_span_name, context)
+    let mut span = Span::from_context(child_span_name, context);
+    if let Some(TraceSampled(sampled)) = extensions.get::<TraceSampled>() {
+        span.add_property(|| (SAMPLED_PROPERTY, sampled.to_string()));
+    }
+    span
 }
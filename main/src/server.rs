@@ -3,11 +3,13 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use coordinator::service::{CoordService, CoordinatorRef};
+use datafusion::sql::sqlparser::ast::{Ident, SqlOption, Value};
 use memory_pool::MemoryPoolRef;
 use meta::model::meta_admin::AdminMeta;
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
 use models::utils::build_address;
+use protos::tls::ClusterTls;
 use query::instance::make_cnosdbms;
 use snafu::{Backtrace, Snafu};
 use spi::server::dbms::DBMSRef;
@@ -15,19 +17,21 @@ use tokio::runtime::Runtime;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 use tokio::time;
-use trace::error;
+use trace::{error, info};
+use tskv::tiering::TieredStorage;
 use tskv::{EngineRef, TsKv};
+use utils::net::unspecified_host;
 
 use crate::flight_sql::FlightSqlServiceAdapter;
 use crate::http::http_service::{HttpService, ServerMode};
 use crate::rpc::grpc_service::GrpcService;
+use crate::rpc::otlp::OtlpService;
+use crate::rpc::tskv::build_object_store_by_url;
 use crate::spi::service::ServiceRef;
 use crate::tcp::tcp_service::TcpService;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
-const DEFAULT_NODE_IP: &str = "0.0.0.0";
-
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("Please inject DBMS.\nBacktrace:\n{}", backtrace))]
@@ -124,8 +128,26 @@ async fn regular_report_node_metrics(meta: MetaRef, heartbeat_interval: Duration
     }
 }
 
-fn build_default_address(port: u16) -> String {
-    build_address(DEFAULT_NODE_IP, port)
+async fn regular_reload_cluster_tls(tls: &'static ClusterTls) {
+    let mut interval = time::interval(tls.reload_interval());
+
+    loop {
+        interval.tick().await;
+
+        match tls.reload_if_modified() {
+            Ok(true) => info!("reloaded the certificates of the cluster TLS"),
+            Ok(false) => {}
+            Err(e) => error!(
+                "failed to reload the certificates of the cluster TLS: {}",
+                e
+            ),
+        }
+    }
+}
+
+/// Build the address to listen on all interfaces, listen on `::` if dual stack is enabled.
+fn build_default_address(dual_stack: bool, port: u16) -> String {
+    build_address(&unspecified_host(dual_stack).to_string(), port)
 }
 
 impl ServiceBuilder {
@@ -172,6 +194,11 @@ impl ServiceBuilder {
         server: &mut Server,
     ) -> (Option<EngineRef>, CoordinatorRef) {
         let meta = self.create_meta(self.metrics_register.clone()).await;
+        meta.add_query_node().await.unwrap();
+        tokio::spawn(regular_report_node_metrics(
+            meta.clone(),
+            self.config.meta.report_time_interval,
+        ));
         let coord = self
             .create_coord(meta, None, self.memory_pool.clone())
             .await;
@@ -185,10 +212,16 @@ impl ServiceBuilder {
             server.add_service(Box::new(http_service));
         }
 
-        if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
+        if let Some(flight_sql_service) =
+            self.create_flight_sql_if_enabled(dbms.clone(), coord.clone())
+        {
             server.add_service(Box::new(flight_sql_service));
         }
 
+        if let Some(otlp_service) = self.create_otlp_if_enabled(dbms.clone(), coord.clone()) {
+            server.add_service(Box::new(otlp_service));
+        }
+
         (None, coord)
     }
 
@@ -223,10 +256,16 @@ impl ServiceBuilder {
             server.add_service(Box::new(grpc_service));
         }
 
-        if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
+        if let Some(flight_sql_service) =
+            self.create_flight_sql_if_enabled(dbms.clone(), coord.clone())
+        {
             server.add_service(Box::new(flight_sql_service));
         }
 
+        if let Some(otlp_service) = self.create_otlp_if_enabled(dbms.clone(), coord.clone()) {
+            server.add_service(Box::new(otlp_service));
+        }
+
         if let Some(tcp_service) = self.create_tcp_if_enabled(coord.clone()) {
             server.add_service(Box::new(tcp_service));
         }
@@ -253,6 +292,12 @@ impl ServiceBuilder {
     }
 
     async fn create_meta(&self, metrics_register: Arc<MetricsRegister>) -> MetaRef {
+        if let Some(tls_config) = &self.config.security.cluster_tls_config {
+            let tls = protos::tls::init_cluster_tls(tls_config.clone())
+                .expect("Failed to load the certificates of the cluster TLS");
+            tokio::spawn(regular_reload_cluster_tls(tls));
+        }
+
         let meta: MetaRef = AdminMeta::new(self.config.clone(), metrics_register).await;
 
         meta
@@ -280,6 +325,36 @@ impl ServiceBuilder {
         kv
     }
 
+    /// Set the object storage of the tiered tsm files if `storage.tiering_path` is set,
+    /// it must be installed before the tskv engine is opened.
+    pub fn install_tiered_storage(&self) -> Result<()> {
+        let storage = &self.config.storage;
+        if storage.tiering_path.is_empty() {
+            return Ok(());
+        }
+        let options = storage
+            .tiering_options
+            .iter()
+            .map(|(name, value)| SqlOption {
+                name: Ident::new(name),
+                value: match value.as_str() {
+                    "true" => Value::Boolean(true),
+                    "false" => Value::Boolean(false),
+                    _ => Value::SingleQuotedString(value.clone()),
+                },
+            })
+            .collect();
+        let (store, prefix) =
+            build_object_store_by_url(&storage.tiering_path, options).map_err(|e| {
+                Error::Common {
+                    reason: format!("invalid tiered storage '{}': {e}", storage.tiering_path),
+                }
+            })?;
+        TieredStorage::new(self.config.global.node_id, store, prefix).install();
+
+        Ok(())
+    }
+
     async fn create_dbms(&self, coord: CoordinatorRef, memory_pool: MemoryPoolRef) -> DBMSRef {
         let options = tskv::Options::from(&self.config);
         let dbms = make_cnosdbms(coord, options.clone(), memory_pool)
@@ -319,7 +394,7 @@ impl ServiceBuilder {
         mode: ServerMode,
     ) -> Option<HttpService> {
         let default_http_addr = match self.config.service.http_listen_port {
-            Some(port) => build_default_address(port),
+            Some(port) => build_default_address(self.config.global.listen_dual_stack, port),
             None => return None,
         };
 
@@ -352,7 +427,7 @@ impl ServiceBuilder {
 
     fn create_grpc_if_enabled(&self, kv: EngineRef, coord: CoordinatorRef) -> Option<GrpcService> {
         let default_grpc_addr = match self.config.service.grpc_listen_port {
-            Some(port) => build_default_address(port),
+            Some(port) => build_default_address(self.config.global.listen_dual_stack, port),
             None => return None,
         };
 
@@ -384,16 +459,20 @@ impl ServiceBuilder {
 
     fn create_tcp_if_enabled(&self, coord: CoordinatorRef) -> Option<TcpService> {
         let default_tcp_addr = match self.config.service.tcp_listen_port {
-            Some(port) => build_default_address(port),
+            Some(port) => build_default_address(self.config.global.listen_dual_stack, port),
             None => return None,
         };
 
         Some(TcpService::new(coord, default_tcp_addr))
     }
 
-    fn create_flight_sql_if_enabled(&self, dbms: DBMSRef) -> Option<FlightSqlServiceAdapter> {
+    fn create_flight_sql_if_enabled(
+        &self,
+        dbms: DBMSRef,
+        coord: CoordinatorRef,
+    ) -> Option<FlightSqlServiceAdapter> {
         let default_flight_sql_addr = match self.config.service.flight_rpc_listen_port {
-            Some(port) => build_default_address(port),
+            Some(port) => build_default_address(self.config.global.listen_dual_stack, port),
             None => return None,
         };
         let tls_config = self.config.security.tls_config.clone();
@@ -414,11 +493,41 @@ impl ServiceBuilder {
 
         Some(FlightSqlServiceAdapter::new(
             dbms,
+            coord,
             addr,
             tls_config,
             self.config.trace.auto_generate_span,
         ))
     }
+
+    fn create_otlp_if_enabled(&self, dbms: DBMSRef, coord: CoordinatorRef) -> Option<OtlpService> {
+        let default_otlp_addr = match self.config.service.otlp_listen_port {
+            Some(port) => build_default_address(self.config.global.listen_dual_stack, port),
+            None => return None,
+        };
+
+        let addr = default_otlp_addr
+            .to_socket_addrs()
+            .map_err(|e| {
+                format!(
+                    "Cannot resolve otlp_listen_addr '{}': {}",
+                    default_otlp_addr, e
+                )
+            })
+            .unwrap()
+            .collect::<Vec<SocketAddr>>()
+            .first()
+            .copied()
+            .expect("Config otlp_listen_addr cannot be empty.");
+
+        Some(OtlpService::new(
+            dbms,
+            coord,
+            addr,
+            self.config.security.tls_config.clone(),
+            self.config.trace.auto_generate_span,
+        ))
+    }
 }
 
 #[cfg(test)]
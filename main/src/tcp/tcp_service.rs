@@ -3,6 +3,7 @@ use coordinator::service::CoordinatorRef;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
 use models::utils::now_timestamp_millis;
 use protocol_parser::open_tsdb::parser::Parser;
+use protos::kv_service::WriteConsistency;
 use tokio::io::AsyncReadExt;
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
@@ -79,6 +80,7 @@ impl Service for TcpService {
                                     DEFAULT_CATALOG,
                                     DEFAULT_DATABASE,
                                     Precision::NS,
+                                    WriteConsistency::Quorum,
                                     lines,
                                     None,
                                 )
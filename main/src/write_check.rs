@@ -0,0 +1,84 @@
+//! Checks shared by the services receiving writes: the http line protocol
+//! endpoints, the flight DoExchange and the OTLP receiver.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use meta::error::{MetaError, MetaResult};
+use meta::model::MetaRef;
+use models::auth::privilege::{
+    DatabasePrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
+};
+use models::auth::user::User;
+use models::oid::Oid;
+use protocol_parser::Line;
+use spi::{QueryError, QueryResult};
+use tonic::Status;
+
+/// Check the write limits of the tenant, `req_len` is the size of the request body.
+pub async fn check_write_limiter(meta: &MetaRef, tenant: &str, req_len: usize) -> MetaResult<()> {
+    let limiter = meta.limiter(tenant).await?;
+    limiter.check_http_writes().await?;
+    limiter.check_http_data_in(req_len).await
+}
+
+/// The user who must change the password can not write before changing it,
+/// if the authentication is enabled.
+pub fn check_password_changed(user: &User, auth_enabled: bool) -> QueryResult<()> {
+    if auth_enabled
+        && user
+            .desc()
+            .options()
+            .must_change_password()
+            .is_some_and(|x| x)
+    {
+        return Err(QueryError::InsufficientPrivileges {
+            privilege: "change password".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Check the write privileges of the tags and fields of the tables written by
+/// the lines, the write privilege of the database covers all of them.
+pub fn check_lines_write_privilege(
+    user: &User,
+    tenant_id: Oid,
+    db: &str,
+    lines: &[Line],
+) -> QueryResult<()> {
+    let mut table_columns: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for line in lines {
+        let columns = table_columns.entry(line.table.as_ref()).or_default();
+        for (name, _) in line.tags.iter() {
+            columns.insert(name.to_string());
+        }
+        for (name, _) in line.fields.iter() {
+            columns.insert(name.to_string());
+        }
+    }
+
+    for (table, columns) in table_columns {
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Table(
+                TablePrivilege::new(DatabasePrivilege::Write, Some(columns)),
+                db.to_string(),
+                table.to_string(),
+            ),
+            Some(tenant_id),
+        );
+        if !user.check_privilege(&privilege) {
+            return Err(QueryError::InsufficientPrivileges {
+                privilege: format!("{privilege}"),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The status of the grpc services for the errors of the limiter.
+pub fn limiter_status(e: MetaError) -> Status {
+    match e {
+        MetaError::RequestLimit { .. } => Status::resource_exhausted(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
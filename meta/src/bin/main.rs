@@ -6,6 +6,7 @@ use meta::meta_cluster_command::add_node::add_node;
 use meta::meta_cluster_command::dump::dump;
 use meta::meta_cluster_command::dumpsql::dumpsql;
 use meta::meta_cluster_command::meta_init::meta_init;
+use meta::meta_cluster_command::migrate_entries::migrate_entries;
 use meta::meta_cluster_command::remove_node::remove_node;
 use meta::meta_cluster_command::restore::restore;
 use meta::meta_cluster_command::show_nodes::show_nodes;
@@ -88,6 +89,15 @@ enum Commands {
         #[arg(long)]
         bind: String,
     },
+    /// Convert the raft entries of a stopped node stored by LMDB to redb
+    MigrateEntries {
+        /// Directory of the raft entries, <data_path>/<node_id>_entry
+        #[arg(long)]
+        path: String,
+        /// The lmdb_max_map_size of the node
+        #[arg(long, default_value_t = 1024 * 1024 * 1024)]
+        lmdb_max_map_size: usize,
+    },
 }
 
 #[tokio::main]
@@ -140,6 +150,15 @@ async fn main() {
                 eprintln!("Error showing nodes: {}", e);
             }
         }
+        Some(Commands::MigrateEntries {
+            path,
+            lmdb_max_map_size,
+        }) => {
+            if let Err(e) = migrate_entries(&path, lmdb_max_map_size).await {
+                eprintln!("Error migrating raft entries: {}", e);
+                std::process::exit(1);
+            }
+        }
         None => {
             // use --config to start cnosdb meta
             if let Some(config_path) = cli.config {
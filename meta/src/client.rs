@@ -81,11 +81,27 @@ impl MetaHttpClient {
     }
 
     pub async fn read<T>(&self, req: &ReadCommand) -> MetaResult<T>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        self.do_read("read", req).await
+    }
+
+    /// Read through the meta leader with a read index, the result is never staler
+    /// than the writes acknowledged before.
+    pub async fn read_consistent<T>(&self, req: &ReadCommand) -> MetaResult<T>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        self.do_read("read_consistent", req).await
+    }
+
+    async fn do_read<T>(&self, uri: &str, req: &ReadCommand) -> MetaResult<T>
     where
         T: for<'a> Deserialize<'a>,
     {
         let start = Instant::now();
-        let rsp = self.try_send_to_leader("read", req).await?;
+        let rsp = self.try_send_to_leader(uri, req).await?;
 
         let result = serde_json::from_str::<MetaResult<T>>(&rsp).map_err(|err| {
             MetaError::SerdeMsgInvalid {
@@ -277,17 +293,56 @@ impl MetaHttpClient {
 }
 
 #[cfg(test)]
-mod test {
+pub mod test {
     use std::collections::HashSet;
+    use std::net::SocketAddr;
     use std::sync::Arc;
     use std::{thread, time};
 
     use metrics::metric_register::MetricsRegister;
     use models::meta_data::NodeInfo;
+    use warp::http::StatusCode;
+    use warp::Filter;
 
     use crate::client::MetaHttpClient;
+    use crate::error::MetaResult;
     use crate::store::command;
 
+    /// Serves the `read_consistent` requests of a meta node with the status and
+    /// the body returned by `reply`, returns the address of the node.
+    pub fn serve_read_consistent<F>(reply: F) -> SocketAddr
+    where
+        F: Fn() -> (StatusCode, String) + Clone + Send + Sync + 'static,
+    {
+        let route = warp::path!("read_consistent")
+            .and(warp::post())
+            .map(move || {
+                let (status, body) = reply();
+                warp::reply::with_status(body, status)
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_read_consistent() {
+        let body = serde_json::to_string(&MetaResult::Ok(7_u64)).unwrap();
+        let leader = serve_read_consistent(move || (StatusCode::OK, body.clone()));
+        // The follower redirects the consistent reads to the leader.
+        let follower =
+            serve_read_consistent(move || (StatusCode::PERMANENT_REDIRECT, leader.to_string()));
+
+        let client =
+            MetaHttpClient::new(&follower.to_string(), Arc::new(MetricsRegister::default()));
+        let req = command::ReadCommand::DataNodes("cluster_xxx".to_string());
+        assert_eq!(client.read_consistent::<u64>(&req).await.unwrap(), 7);
+        assert_eq!(*client.leader.read(), leader.to_string());
+
+        // Not served by the plain read.
+        assert!(client.read::<u64>(&req).await.is_err());
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_client() {
@@ -297,6 +352,7 @@ mod test {
         let node = NodeInfo {
             id: 111,
             grpc_addr: "".to_string(),
+            ..Default::default()
         };
 
         let client = reqwest::Client::new();
@@ -324,6 +380,7 @@ mod test {
         let node = NodeInfo {
             id: 111,
             grpc_addr: "".to_string(),
+            ..Default::default()
         };
 
         let req = command::WriteCommand::AddDataNode(cluster.clone(), node);
@@ -182,6 +182,39 @@ pub enum MetaError {
     #[snafu(display("cannot revoke the privilege {privilege} of role"))]
     #[error_code(code = 56)]
     PrivilegeCannotRevoke { privilege: TenantObjectPrivilege },
+
+    #[snafu(display("Continuous query {} already exists", name))]
+    #[error_code(code = 57)]
+    ContinuousQueryAlreadyExists { name: String },
+
+    #[snafu(display("Continuous query {} not found", name))]
+    #[error_code(code = 58)]
+    ContinuousQueryNotFound { name: String },
+
+    #[snafu(display(
+        "Tenant {} exceeded the quota {}, the maximum is {}",
+        tenant,
+        quota,
+        max
+    ))]
+    #[error_code(code = 59)]
+    TenantQuotaExceeded {
+        tenant: String,
+        quota: TenantQuota,
+        max: usize,
+    },
+
+    #[snafu(display("Database {} dropped at {}", database, drop_time))]
+    #[error_code(code = 60)]
+    DatabaseDropped { database: String, drop_time: String },
+
+    #[snafu(display("Scheduled delete {} already exists", name))]
+    #[error_code(code = 61)]
+    ScheduledDeleteAlreadyExists { name: String },
+
+    #[snafu(display("Scheduled delete {} not found", name))]
+    #[error_code(code = 62)]
+    ScheduledDeleteNotFound { name: String },
 }
 
 impl MetaError {
@@ -0,0 +1,13 @@
+use replication::entry_store::migrate_entries_to_redb;
+
+pub async fn migrate_entries(
+    path: &str,
+    lmdb_max_map_size: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = migrate_entries_to_redb(path, lmdb_max_map_size).await?;
+    println!(
+        "Migrated {} raft entries in {} from LMDB to redb",
+        count, path
+    );
+    Ok(())
+}
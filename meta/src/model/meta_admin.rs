@@ -2,25 +2,28 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 
 use config::common::{RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig};
 use config::tskv::Config;
+use dashmap::DashMap;
 use metrics::metric_register::MetricsRegister;
 use models::auth::user::{admin_user, User, UserDesc, UserOptions};
 use models::meta_data::*;
 use models::node_info::NodeStatus;
 use models::oid::{Identifier, Oid, UuidGenerator};
+use models::schema::continuous_query::ContinuousQueryInfo;
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::{ResourceInfo, ResourceStatus};
 use models::schema::table_schema::TableSchema;
 use models::schema::tenant::{Tenant, TenantOptions};
-use models::utils::{build_address_with_optional_addr, now_timestamp_secs};
+use models::utils::{build_address_with_optional_addr, now_timestamp_nanos, now_timestamp_secs};
 use parking_lot::{Mutex, RwLock};
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::{self, Receiver, Sender};
-use tonic::transport::{Channel, Endpoint};
-use trace::error;
+use tonic::transport::Channel;
+use trace::{error, warn};
 use tracing::info;
 
 use super::meta_tenant::TenantMeta;
@@ -59,14 +62,19 @@ pub struct AdminMeta {
     watch_notify: Sender<UseTenantInfo>,
 
     users: RwLock<HashMap<String, UserDesc>>,
-    conn_map: RwLock<HashMap<u64, Channel>>,
+    /// Channels to the data nodes, with the version of the cluster TLS they are made with.
+    conn_map: RwLock<HashMap<u64, (u64, Channel)>>,
     data_nodes: RwLock<HashMap<u64, NodeInfo>>,
 
     tenants: RwLock<HashMap<String, Arc<TenantMeta>>>,
     limiters: Arc<LimiterManager>,
 
     resource_tx_rx: (Sender<MetaModifyType>, ReceiverType),
+    dropped_tx: broadcast::Sender<DroppedResource>,
     metrics_register: Arc<MetricsRegister>,
+
+    clock_offset: AtomicI64,
+    vnode_applied: DashMap<VnodeId, VnodeApplied>,
 }
 
 impl AdminMeta {
@@ -91,10 +99,23 @@ impl AdminMeta {
             watch_version: AtomicU64::new(0),
             watch_tenants: RwLock::new(HashSet::new()),
             resource_tx_rx: (tx, Arc::new(Mutex::new(Some(rx)))),
+            dropped_tx: broadcast::channel(1024).0,
             metrics_register: Arc::new(MetricsRegister::default()),
+            clock_offset: AtomicI64::new(0),
+            vnode_applied: DashMap::new(),
         }
     }
 
+    /// The mock whose limiters of the tenants are created by the factory.
+    pub fn mock_with_limiters(factory: Arc<dyn LimiterFactory>) -> Self {
+        let mut meta = Self::mock();
+        meta.limiters = Arc::new(LimiterManager::new(HashMap::from([(
+            LimiterType::Tenant,
+            factory,
+        )])));
+        meta
+    }
+
     pub async fn new(config: Config, metrics_register: Arc<MetricsRegister>) -> Arc<Self> {
         let meta_service_addr = config.meta.service_addr.clone();
         let meta_url = meta_service_addr.join(";");
@@ -126,7 +147,10 @@ impl AdminMeta {
             watch_version: AtomicU64::new(0),
             watch_tenants: RwLock::new(HashSet::new()),
             resource_tx_rx: (tx, Arc::new(Mutex::new(Some(rx)))),
+            dropped_tx: broadcast::channel(1024).0,
             metrics_register,
+            clock_offset: AtomicI64::new(0),
+            vnode_applied: DashMap::new(),
         });
 
         let base_ver = admin.sync_gobal_info().await.unwrap();
@@ -185,16 +209,20 @@ impl AdminMeta {
     }
 
     pub async fn get_node_conn(&self, node_id: u64) -> MetaResult<Channel> {
-        if let Some(val) = self.conn_map.read().get(&node_id) {
-            return Ok(val.clone());
+        let tls_version = protos::tls::cluster_tls_version();
+        if let Some((version, val)) = self.conn_map.read().get(&node_id) {
+            if *version == tls_version {
+                return Ok(val.clone());
+            }
         }
 
         let info = self.node_info_by_id(node_id).await?;
-        let connector = Endpoint::from_shared(format!("http://{}", info.grpc_addr.clone()))
-            .map_err(|err| MetaError::ConnectServerError {
+        let connector = protos::tls::grpc_endpoint(&info.grpc_addr).map_err(|err| {
+            MetaError::ConnectServerError {
                 addr: info.grpc_addr.clone(),
                 msg: err.to_string(),
-            })?;
+            }
+        })?;
 
         let channel = connector
             .connect()
@@ -204,7 +232,9 @@ impl AdminMeta {
                 msg: err.to_string(),
             })?;
 
-        self.conn_map.write().insert(node_id, channel.clone());
+        self.conn_map
+            .write()
+            .insert(node_id, (tls_version, channel.clone()));
 
         Ok(channel)
     }
@@ -394,6 +424,29 @@ impl AdminMeta {
                 if let Some(client) = opt_client {
                     let _ = client.process_watch_log(entry).await;
                 }
+                // No receivers is not an error.
+                if len == 6
+                    && strs[4] == key_path::DROPPED_DBS
+                    && entry.tye == command::ENTRY_LOG_TYPE_SET
+                {
+                    if let Ok(drop_time) = serde_json::from_str::<i64>(&entry.val) {
+                        let _ = self.dropped_tx.send(DroppedResource::Database {
+                            tenant: tenant_name.to_string(),
+                            database: strs[5].to_string(),
+                            drop_time,
+                        });
+                    }
+                } else if len == 8
+                    && strs[4] == key_path::DBS
+                    && strs[6] == key_path::SCHEMAS
+                    && entry.tye == command::ENTRY_LOG_TYPE_DEL
+                {
+                    let _ = self.dropped_tx.send(DroppedResource::Table {
+                        tenant: tenant_name.to_string(),
+                        database: strs[5].to_string(),
+                        table: strs[7].to_string(),
+                    });
+                }
             } else if len == 3 && strs[2] == key_path::AUTO_INCR_ID {
             } else if len == 4
                 && (strs[2] == key_path::USERS
@@ -464,6 +517,7 @@ impl AdminMeta {
     // **[3]    /cluster_name/auto_incr_id -> id
     // **[4]    /cluster_name/users/name -> [UserDesc]
     // **[4]    /cluster_name/data_nodes/node_id -> [NodeInfo] 集群、数据节点等信息
+    // **[4]    /cluster_name/query_nodes/node_id -> [NodeInfo] 只运行查询引擎的节点
 
     // **[6]    /cluster_name/tenants/tenant/roles/name -> [CustomTenantRole<Oid>]
     // **[6]    /cluster_name/tenants/tenant/members/oid -> [TenantRoleIdentifier]
@@ -475,16 +529,24 @@ impl AdminMeta {
     /******************** Watch Meta Data Change End *********************/
 
     /******************** Data Node Operation Begin *********************/
-    pub async fn add_data_node(&self) -> MetaResult<()> {
-        let grpc_addr = build_address_with_optional_addr(
-            &self.config.global.host,
-            self.config.service.grpc_listen_port,
-        );
-
-        let node = NodeInfo {
+    /// Information of this node, which is registered in meta by data nodes.
+    pub fn node_info(&self) -> NodeInfo {
+        let host = &self.config.global.host;
+        NodeInfo {
             id: self.config.global.node_id,
-            grpc_addr,
-        };
+            grpc_addr: build_address_with_optional_addr(host, self.config.service.grpc_listen_port),
+            http_addr: self
+                .config
+                .service
+                .http_listen_port
+                .map(|port| utils::net::build_address(host, port))
+                .unwrap_or_default(),
+            mode: self.config.deployment.mode.to_ascii_lowercase(),
+        }
+    }
+
+    pub async fn add_data_node(&self) -> MetaResult<()> {
+        let node = self.node_info();
 
         let cluster_name = self.config.global.cluster_name.clone();
         let req = command::WriteCommand::AddDataNode(cluster_name, node.clone());
@@ -496,6 +558,20 @@ impl AdminMeta {
         Ok(())
     }
 
+    /// Register this node, only running the query engine, apart from the data
+    /// nodes so that no vnode is placed on it.
+    pub async fn add_query_node(&self) -> MetaResult<()> {
+        let req = command::WriteCommand::AddQueryNode(self.cluster(), self.node_info());
+        self.client.write::<()>(&req).await?;
+        self.report_node_metrics().await
+    }
+
+    pub async fn query_nodes(&self) -> MetaResult<Vec<NodeInfo>> {
+        let req = command::ReadCommand::QueryNodes(self.cluster());
+
+        self.client.read::<Vec<NodeInfo>>(&req).await
+    }
+
     pub async fn data_nodes(&self) -> Vec<NodeInfo> {
         let mut nodes = vec![];
         for (_, val) in self.data_nodes.read().iter() {
@@ -522,11 +598,32 @@ impl AdminMeta {
             status = NodeStatus::NoDiskSpace;
         }
 
+        let clock_offset = match self.sample_clock_offset().await {
+            Ok(offset) => {
+                self.clock_offset.store(offset, Ordering::Relaxed);
+                offset
+            }
+            Err(e) => {
+                error!("Failed to sample clock offset against meta leader: {}", e);
+                self.clock_offset.load(Ordering::Relaxed)
+            }
+        };
+        if self.clock_skew_exceeded() {
+            warn!(
+                "Clock offset of data node '{}' is {}ms, exceeds 'max_clock_skew' {}ms",
+                self.config.global.node_id,
+                clock_offset / 1_000_000,
+                self.config.meta.max_clock_skew.as_millis()
+            );
+        }
+
         let node_metrics = NodeMetrics {
             id: self.config.global.node_id,
             disk_free,
             time: now_timestamp_secs(),
             status,
+            clock_offset,
+            vnode_applied: self.reported_vnode_applied(),
         };
 
         let req = command::WriteCommand::ReportNodeMetrics(
@@ -536,6 +633,85 @@ impl AdminMeta {
 
         self.client.write::<()>(&req).await
     }
+
+    pub async fn node_metrics(&self) -> MetaResult<Vec<NodeMetrics>> {
+        let req = command::ReadCommand::NodeMetrics(self.cluster());
+
+        self.client.read::<Vec<NodeMetrics>>(&req).await
+    }
+
+    /// Samples the clock offset against meta leader in NTP style.
+    async fn sample_clock_offset(&self) -> MetaResult<i64> {
+        let send_time = now_timestamp_nanos();
+        let server_time = self
+            .client
+            .read::<i64>(&command::ReadCommand::ServerTimestamp)
+            .await?;
+        let recv_time = now_timestamp_nanos();
+
+        Ok(clock_offset_of(send_time, server_time, recv_time))
+    }
+
+    /// Clock offset in nanoseconds of meta leader against this node,
+    /// sampled when node metrics were reported last time.
+    pub fn clock_offset(&self) -> i64 {
+        self.clock_offset.load(Ordering::Relaxed)
+    }
+
+    pub fn clock_skew_exceeded(&self) -> bool {
+        let max_clock_skew = self.config.meta.max_clock_skew.as_nanos() as i64;
+        self.clock_offset().abs() > max_clock_skew
+    }
+
+    /// Record the last raft entry applied by the vnode on this node,
+    /// it is reported to meta with the node metrics.
+    pub fn update_vnode_applied(&self, vnode_id: VnodeId, index: u64) {
+        self.vnode_applied.insert(
+            vnode_id,
+            VnodeApplied {
+                index,
+                time: now_timestamp_nanos(),
+                reported: 0,
+            },
+        );
+    }
+
+    pub fn remove_vnode_applied(&self, vnode_id: VnodeId) {
+        self.vnode_applied.remove(&vnode_id);
+    }
+
+    fn reported_vnode_applied(&self) -> HashMap<VnodeId, VnodeApplied> {
+        let reported = now_timestamp_nanos();
+        self.vnode_applied
+            .iter()
+            .map(|entry| {
+                let applied = VnodeApplied {
+                    reported,
+                    ..*entry.value()
+                };
+                (*entry.key(), applied)
+            })
+            .collect()
+    }
+
+    /// Last applied raft entry of all vnodes in the cluster, taken from
+    /// the node metrics reported by the data nodes. The apply and report
+    /// time are corrected to the clock of meta leader.
+    pub async fn vnodes_applied(&self) -> MetaResult<HashMap<VnodeId, VnodeApplied>> {
+        let mut vnodes_applied = HashMap::new();
+        for node_metrics in self.node_metrics().await? {
+            let clock_offset = node_metrics.clock_offset;
+            vnodes_applied.extend(node_metrics.vnode_applied.into_iter().map(
+                |(vnode_id, mut applied)| {
+                    applied.time += clock_offset;
+                    applied.reported += clock_offset;
+                    (vnode_id, applied)
+                },
+            ));
+        }
+
+        Ok(vnodes_applied)
+    }
     /******************** Data Node Operation End *********************/
 
     /******************** User Operation Begin *********************/
@@ -706,6 +882,14 @@ impl AdminMeta {
                 comment: options.comment,
                 drop_after: options.drop_after,
                 tenant_is_hidden: options.tenant_is_hidden,
+                query_timeout: options.query_timeout,
+                write_timeout: options.write_timeout,
+                encryption_keys: options.encryption_keys,
+                max_databases: options.max_databases,
+                max_tables: options.max_tables,
+                max_users: options.max_users,
+                max_tables_per_database: options.max_tables_per_database,
+                max_series_per_table: options.max_series_per_table,
                 limiter_config: match options.limiter_config {
                     Some(_) => Self::merge_limiter_config(
                         old_options.limiter_config,
@@ -908,6 +1092,12 @@ impl AdminMeta {
         self.resource_tx_rx.1.lock().take()
     }
 
+    /// Subscribe the databases and tables dropped after now, watched from the
+    /// meta service. The events are missed if the receiver lags.
+    pub fn subscribe_dropped(&self) -> broadcast::Receiver<DroppedResource> {
+        self.dropped_tx.subscribe()
+    }
+
     pub async fn write_queryinfo(&self, query_id: u64, query_info: QueryInfo) -> MetaResult<()> {
         let req = command::WriteCommand::WriteQueryInfo(self.cluster(), query_id, query_info);
 
@@ -939,6 +1129,42 @@ impl AdminMeta {
         self.client.write::<()>(&req).await
     }
 
+    pub async fn create_continuous_query(&self, query: ContinuousQueryInfo) -> MetaResult<()> {
+        let req = command::WriteCommand::CreateContinuousQuery(self.cluster(), query);
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn drop_continuous_query(
+        &self,
+        tenant: &str,
+        db: &str,
+        name: &str,
+    ) -> MetaResult<()> {
+        let req = command::WriteCommand::DropContinuousQuery(
+            self.cluster(),
+            tenant.to_string(),
+            db.to_string(),
+            name.to_string(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn read_continuous_queries(
+        &self,
+        tenant: &str,
+        db: &str,
+    ) -> MetaResult<Vec<ContinuousQueryInfo>> {
+        let req = command::ReadCommand::ContinuousQueries(
+            self.cluster(),
+            tenant.to_string(),
+            db.to_string(),
+        );
+
+        self.client.read::<Vec<ContinuousQueryInfo>>(&req).await
+    }
+
     pub async fn read_tableschema(
         &self,
         tenant: &str,
@@ -955,3 +1181,121 @@ impl AdminMeta {
         self.client.read::<TableSchema>(&req).await
     }
 }
+
+/// Clock offset of the server against the client, assumes that the request
+/// and the response take the same time on the network.
+fn clock_offset_of(send_time: i64, server_time: i64, recv_time: i64) -> i64 {
+    server_time - send_time / 2 - recv_time / 2
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use models::meta_data::DroppedResource;
+
+    use super::{clock_offset_of, AdminMeta};
+    use crate::store::command::{self, EntryLog, WatchData};
+    use crate::store::key_path::KeyPath;
+
+    #[tokio::test]
+    async fn test_watch_dropped() {
+        let admin = AdminMeta::mock();
+        let cluster = admin.cluster();
+        let mut receiver = admin.subscribe_dropped();
+
+        let entry = |tye: i32, key: String, val: &str| EntryLog {
+            tye,
+            ver: 1,
+            key,
+            val: val.to_string(),
+        };
+        let watch_data = WatchData {
+            full_sync: false,
+            min_ver: 1,
+            max_ver: 1,
+            entry_logs: vec![
+                entry(
+                    command::ENTRY_LOG_TYPE_SET,
+                    KeyPath::tenant_dropped_db(&cluster, "cnosdb", "db1"),
+                    "100",
+                ),
+                // Forgotten, or created again.
+                entry(
+                    command::ENTRY_LOG_TYPE_DEL,
+                    KeyPath::tenant_dropped_db(&cluster, "cnosdb", "db1"),
+                    "",
+                ),
+                entry(
+                    command::ENTRY_LOG_TYPE_DEL,
+                    KeyPath::tenant_schema_name(&cluster, "cnosdb", "db2", "air"),
+                    "",
+                ),
+                // Altered.
+                entry(
+                    command::ENTRY_LOG_TYPE_SET,
+                    KeyPath::tenant_schema_name(&cluster, "cnosdb", "db2", "sea"),
+                    "{}",
+                ),
+            ],
+        };
+        admin.process_watch_data(&watch_data).await;
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            DroppedResource::Database {
+                tenant: "cnosdb".to_string(),
+                database: "db1".to_string(),
+                drop_time: 100,
+            }
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            DroppedResource::Table {
+                tenant: "cnosdb".to_string(),
+                database: "db2".to_string(),
+                table: "air".to_string(),
+            }
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_clock_offset_of() {
+        // The response takes as long as the request.
+        assert_eq!(clock_offset_of(1_000, 1_500, 1_200), 400);
+        assert_eq!(clock_offset_of(1_000, 600, 1_200), -500);
+        assert_eq!(clock_offset_of(1_000, 1_100, 1_200), 0);
+    }
+
+    #[test]
+    fn test_clock_skew_exceeded() {
+        let mut admin = AdminMeta::mock();
+        admin.config.meta.max_clock_skew = Duration::from_millis(500);
+        assert!(!admin.clock_skew_exceeded());
+
+        admin.clock_offset.store(500_000_000, Ordering::Relaxed);
+        assert!(!admin.clock_skew_exceeded());
+        admin.clock_offset.store(500_000_001, Ordering::Relaxed);
+        assert!(admin.clock_skew_exceeded());
+        admin.clock_offset.store(-500_000_001, Ordering::Relaxed);
+        assert!(admin.clock_skew_exceeded());
+        assert_eq!(admin.clock_offset(), -500_000_001);
+    }
+
+    #[test]
+    fn test_reported_vnode_applied() {
+        let admin = AdminMeta::mock();
+        admin.update_vnode_applied(1, 10);
+        admin.update_vnode_applied(2, 20);
+        admin.update_vnode_applied(1, 11);
+        admin.remove_vnode_applied(2);
+
+        let reported = admin.reported_vnode_applied();
+        assert_eq!(reported.len(), 1);
+        let applied = reported[&1];
+        assert_eq!(applied.index, 11);
+        assert!(applied.reported >= applied.time);
+    }
+}
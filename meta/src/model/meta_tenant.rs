@@ -2,21 +2,22 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use client::MetaHttpClient;
 use config::common::TenantObjectLimiterConfig;
 use metrics::metric_register::MetricsRegister;
-use models::auth::privilege::{DatabasePrivilege, Privilege};
+use models::auth::privilege::{DatabasePrivilege, Privilege, TablePrivilege};
 use models::auth::role::{CustomTenantRole, SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::UserDesc;
 use models::meta_data::*;
 use models::oid::{Identifier, Oid};
-use models::schema::database_schema::DatabaseSchema;
+use models::schema::database_schema::{DatabaseSchema, SeriesPlacement};
 use models::schema::external_table_schema::ExternalTableSchema;
 use models::schema::resource_info::ResourceInfo;
 use models::schema::table_schema::TableSchema;
-use models::schema::tenant::Tenant;
+use models::schema::tenant::{Tenant, TenantQuota};
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use parking_lot::RwLock;
 use store::command;
@@ -36,6 +37,11 @@ pub struct TenantMeta {
     meta_url: String,
 
     data: RwLock<TenantMetaData>,
+    /// Number of the schema changes made by this node, the results of the changes
+    /// may not be synced to the local metadata yet.
+    schema_changes: AtomicU64,
+    /// Number of the schema changes made before the last consistent sync.
+    synced_schema_changes: AtomicU64,
     pub client: MetaHttpClient,
 }
 
@@ -46,10 +52,19 @@ impl TenantMeta {
             tenant: Tenant::default(),
             meta_url: "".to_string(),
             data: RwLock::new(TenantMetaData::new()),
+            schema_changes: AtomicU64::new(0),
+            synced_schema_changes: AtomicU64::new(0),
             client: MetaHttpClient::new("", Arc::new(MetricsRegister::default())),
         }
     }
 
+    /// The mock with the metadata of the tenant.
+    pub fn mock_with_data(data: TenantMetaData) -> Self {
+        let meta = Self::mock();
+        *meta.data.write() = data;
+        meta
+    }
+
     pub async fn new(
         cluster: String,
         tenant: Tenant,
@@ -61,6 +76,8 @@ impl TenantMeta {
             tenant,
             meta_url: meta_url.clone(),
             data: RwLock::new(TenantMetaData::new()),
+            schema_changes: AtomicU64::new(0),
+            synced_schema_changes: AtomicU64::new(0),
             client: MetaHttpClient::new(&meta_url, metrics_register),
         });
 
@@ -81,6 +98,37 @@ impl TenantMeta {
         Ok(())
     }
 
+    /// Sync metadata of the tenant from the meta leader with a consistent read, so
+    /// that the catalog reads after it observe all committed schema changes.
+    pub async fn sync_consistent_tenant_metadata(&self) -> MetaResult<()> {
+        // The changes made during the read may be missed, they are synced next time.
+        let changes = self.schema_changes.load(Ordering::Acquire);
+        let req = command::ReadCommand::TenantMetaData(self.cluster.clone(), self.tenant_name());
+        let resp = self.client.read_consistent::<TenantMetaData>(&req).await?;
+
+        {
+            let mut data = self.data.write();
+            if resp.version > data.version {
+                *data = resp;
+            }
+        }
+        self.synced_schema_changes
+            .fetch_max(changes, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    /// Whether the schema is changed by this node since the last consistent sync,
+    /// the local metadata may be stale.
+    pub fn schema_changed(&self) -> bool {
+        self.schema_changes.load(Ordering::Acquire)
+            > self.synced_schema_changes.load(Ordering::Acquire)
+    }
+
+    fn mark_schema_changed(&self) {
+        self.schema_changes.fetch_add(1, Ordering::AcqRel);
+    }
+
     fn check_create_db(&self, db_schema: &mut DatabaseSchema) -> MetaResult<()> {
         let limiter_config = match self.tenant.options().object_config() {
             Some(config) => config,
@@ -173,6 +221,25 @@ impl TenantMeta {
         self.tenant().name().to_string()
     }
 
+    /// The number of the objects of the tenant limited by the quota, the largest
+    /// one for the quotas of each object, None if not known by the meta service.
+    pub fn quota_usage(&self, quota: TenantQuota) -> Option<usize> {
+        let data = self.data.read();
+        match quota {
+            TenantQuota::Databases => Some(data.dbs.len()),
+            TenantQuota::Tables => Some(data.dbs.values().map(|db| db.tables.len()).sum()),
+            TenantQuota::Users => Some(data.members.len()),
+            TenantQuota::TablesPerDatabase => Some(
+                data.dbs
+                    .values()
+                    .map(|db| db.tables.len())
+                    .max()
+                    .unwrap_or(0),
+            ),
+            TenantQuota::SeriesPerTable => None,
+        }
+    }
+
     pub async fn add_member_with_role(
         &self,
         user_id: Oid,
@@ -353,6 +420,36 @@ impl TenantMeta {
         self.client.write::<()>(&req).await
     }
 
+    pub async fn grant_table_privilege_to_custom_role(
+        &self,
+        table_privileges: Vec<(TablePrivilege, String, String)>,
+        role_name: &str,
+    ) -> MetaResult<()> {
+        let req = command::WriteCommand::GrantTablePrivileges(
+            self.cluster.clone(),
+            table_privileges,
+            role_name.to_string(),
+            self.tenant_name(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn revoke_table_privilege_from_custom_role(
+        &self,
+        table_privileges: Vec<(DatabasePrivilege, String, String)>,
+        role_name: &str,
+    ) -> MetaResult<()> {
+        let req = command::WriteCommand::RevokeTablePrivileges(
+            self.cluster.clone(),
+            table_privileges,
+            role_name.to_string(),
+            self.tenant_name(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
     pub async fn drop_custom_role(&self, role_name: &str) -> MetaResult<bool> {
         let req = command::WriteCommand::DropRole(
             self.cluster.clone(),
@@ -439,6 +536,30 @@ impl TenantMeta {
         Ok(None)
     }
 
+    /// Drop time of the database in nanoseconds, if it was dropped and not
+    /// created again.
+    pub fn db_dropped_at(&self, name: &str) -> Option<i64> {
+        self.data.read().dropped_dbs.get(name).copied()
+    }
+
+    /// The dropped databases not created again, and their drop time in nanoseconds.
+    pub fn dropped_dbs(&self) -> HashMap<String, i64> {
+        self.data.read().dropped_dbs.clone()
+    }
+
+    /// Forget the database dropped at `drop_time`, it is kept if it is dropped
+    /// again since then.
+    pub async fn remove_dropped_db(&self, name: &str, drop_time: i64) -> MetaResult<()> {
+        let req = command::WriteCommand::RemoveDroppedDB(
+            self.cluster.clone(),
+            self.tenant_name(),
+            name.to_string(),
+            drop_time,
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
     pub fn list_databases(&self) -> MetaResult<HashMap<String, DatabaseInfo>> {
         Ok(self.data.read().dbs.clone())
     }
@@ -449,13 +570,15 @@ impl TenantMeta {
             exist = true;
         }
 
-        let req = command::WriteCommand::DropDB(
+        let req = command::WriteCommand::DropDBAt(
             self.cluster.clone(),
             self.tenant_name(),
             name.to_string(),
+            models::utils::now_timestamp_nanos(),
         );
 
         self.client.write::<()>(&req).await?;
+        self.mark_schema_changed();
         Ok(exist)
     }
 
@@ -478,7 +601,9 @@ impl TenantMeta {
             schema.clone(),
         );
 
-        self.client.write::<()>(&req).await
+        self.client.write::<()>(&req).await?;
+        self.mark_schema_changed();
+        Ok(())
     }
 
     pub fn get_table_schema(&self, db: &str, table: &str) -> MetaResult<Option<TableSchema>> {
@@ -556,7 +681,9 @@ impl TenantMeta {
             table.to_string(),
         );
 
-        self.client.write::<()>(&req).await
+        self.client.write::<()>(&req).await?;
+        self.mark_schema_changed();
+        Ok(())
     }
 
     pub async fn create_bucket(&self, db: &str, ts: i64) -> MetaResult<BucketInfo> {
@@ -567,6 +694,34 @@ impl TenantMeta {
             ts,
         );
 
+        self.create_bucket_by(db, ts, req).await
+    }
+
+    /// Create the bucket placing the series by `series_placement` instead of
+    /// the placement of the database, such as the bucket restored from a backup.
+    pub async fn create_bucket_with_placement(
+        &self,
+        db: &str,
+        ts: i64,
+        series_placement: SeriesPlacement,
+    ) -> MetaResult<BucketInfo> {
+        let req = command::WriteCommand::CreateBucketWithPlacement(
+            self.cluster.clone(),
+            self.tenant_name(),
+            db.to_string(),
+            ts,
+            series_placement,
+        );
+
+        self.create_bucket_by(db, ts, req).await
+    }
+
+    async fn create_bucket_by(
+        &self,
+        db: &str,
+        ts: i64,
+        req: command::WriteCommand,
+    ) -> MetaResult<BucketInfo> {
         self.write_with_data(&req).await?;
 
         if let Some(bucket) = self.data.read().bucket_by_timestamp(db, ts) {
@@ -646,6 +801,7 @@ impl TenantMeta {
                                 vnode_id: vnode_info.id,
                                 node_id: vnode_info.node_id,
                                 status: vnode_info.status,
+                                learner: vnode_info.learner,
                                 repl_set_id: repl_set.id,
                                 bucket_id: bucket.id,
                                 db_name: db_name.clone(),
@@ -728,6 +884,20 @@ impl TenantMeta {
         Ok(bucket.vnode_for(hash_id))
     }
 
+    /// Like `locate_replication_set_for_write`, but returns None instead of
+    /// creating the bucket if it does not exist.
+    pub fn locate_existing_replication_set(
+        &self,
+        db: &str,
+        hash_id: u64,
+        ts: i64,
+    ) -> Option<ReplicationSet> {
+        self.data
+            .read()
+            .bucket_by_timestamp(db, ts)
+            .map(|bucket| bucket.vnode_for(hash_id))
+    }
+
     pub async fn get_replication_set(
         &self,
         db_name: &str,
@@ -797,6 +967,26 @@ impl TenantMeta {
         Ok(())
     }
 
+    pub fn change_local_repl_set_leader(
+        &self,
+        repl_id: ReplicationSetId,
+        leader_node_id: NodeId,
+        leader_vnode_id: VnodeId,
+    ) {
+        let mut data = self.data.write();
+        for (_db_name, db_info) in data.dbs.iter_mut() {
+            for bucket in db_info.buckets.iter_mut() {
+                for repl_set in bucket.shard_group.iter_mut() {
+                    if repl_set.id == repl_id {
+                        repl_set.leader_node_id = leader_node_id;
+                        repl_set.leader_vnode_id = leader_vnode_id;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
     pub async fn update_replication_set(
         &self,
         db: &str,
@@ -838,10 +1028,13 @@ impl TenantMeta {
             "change replica set({}) new leader({})",
             info.repl_set_id, new_leader
         );
-
         let req = command::WriteCommand::ChangeReplSetLeader(args);
         self.client.write::<()>(&req).await?;
 
+        // Requests are sent to the new leader once the meta commits it, not
+        // waiting for the watch log.
+        self.change_local_repl_set_leader(info.repl_set_id, info.node_id, info.vnode_id);
+
         Ok(info.node_id)
     }
 
@@ -914,6 +1107,15 @@ impl TenantMeta {
             } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
                 cache.dbs.remove(db_name);
             }
+        } else if len == 6 && strs[4] == key_path::DROPPED_DBS && strs[2] == key_path::TENANTS {
+            let db_name = strs[5];
+            if entry.tye == command::ENTRY_LOG_TYPE_SET {
+                if let Ok(drop_time) = serde_json::from_str::<i64>(&entry.val) {
+                    cache.dropped_dbs.insert(db_name.to_owned(), drop_time);
+                }
+            } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
+                cache.dropped_dbs.remove(db_name);
+            }
         } else if len == 6 && strs[4] == key_path::MEMBERS && strs[2] == key_path::TENANTS {
             let key = strs[5];
             if entry.tye == command::ENTRY_LOG_TYPE_SET {
@@ -962,6 +1164,114 @@ impl TenantMeta {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+    use std::sync::{Arc, OnceLock};
+
+    use metrics::metric_register::MetricsRegister;
+    use models::meta_data::{BucketInfo, DatabaseInfo, ReplicationSet, TenantMetaData, VnodeInfo};
+    use models::schema::database_schema::DatabaseSchema;
+    use warp::http::StatusCode;
+
+    use super::TenantMeta;
+    use crate::client::test::serve_read_consistent;
+    use crate::client::MetaHttpClient;
+    use crate::error::MetaResult;
+
+    fn mock_tenant_meta() -> TenantMeta {
+        let vnodes = vec![
+            VnodeInfo {
+                id: 1,
+                node_id: 1001,
+                ..Default::default()
+            },
+            VnodeInfo {
+                id: 2,
+                node_id: 1002,
+                ..Default::default()
+            },
+        ];
+        let bucket = BucketInfo {
+            id: 1,
+            start_time: 0,
+            end_time: i64::MAX,
+            shard_group: vec![ReplicationSet::new(1, 1001, 1, vnodes)],
+            series_placement: Default::default(),
+        };
+        let info = DatabaseInfo {
+            schema: DatabaseSchema::new("cnosdb", "db", Default::default(), Default::default()),
+            buckets: vec![bucket],
+            tables: HashMap::new(),
+        };
+        let mut data = TenantMetaData::new();
+        data.dbs.insert("db".to_string(), info);
+        TenantMeta::mock_with_data(data)
+    }
+
+    fn leader(meta: &TenantMeta) -> (u64, u32) {
+        let data = meta.data.read();
+        let repl_set = &data.dbs["db"].buckets[0].shard_group[0];
+        (repl_set.leader_node_id, repl_set.leader_vnode_id)
+    }
+
+    #[tokio::test]
+    async fn test_replica_new_leader_not_committed() {
+        let meta = mock_tenant_meta();
+
+        // The mock has no meta service to commit the new leader.
+        assert!(meta.replica_new_leader(2).await.is_err());
+        assert_eq!(leader(&meta), (1001, 1));
+
+        assert!(meta.replica_new_leader(3).await.is_err());
+        assert_eq!(leader(&meta), (1001, 1));
+    }
+
+    #[test]
+    fn test_change_local_repl_set_leader() {
+        let meta = mock_tenant_meta();
+
+        meta.change_local_repl_set_leader(1, 1002, 2);
+        assert_eq!(leader(&meta), (1002, 2));
+
+        // Unknown replication sets are ignored.
+        meta.change_local_repl_set_leader(2, 1001, 1);
+        assert_eq!(leader(&meta), (1002, 2));
+    }
+
+    #[tokio::test]
+    async fn test_sync_consistent_tenant_metadata() {
+        let mut data = TenantMetaData::new();
+        data.version = 5;
+        let body = serde_json::to_string(&MetaResult::Ok(data)).unwrap();
+        // Once set, the schema of the tenant is changed by this node during the reads.
+        let changing_meta = Arc::new(OnceLock::<Arc<TenantMeta>>::new());
+        let leader = serve_read_consistent({
+            let changing_meta = changing_meta.clone();
+            move || {
+                if let Some(meta) = changing_meta.get() {
+                    meta.mark_schema_changed();
+                }
+                (StatusCode::OK, body.clone())
+            }
+        });
+        let mut meta = TenantMeta::mock();
+        meta.client =
+            MetaHttpClient::new(&leader.to_string(), Arc::new(MetricsRegister::default()));
+        let meta = Arc::new(meta);
+
+        assert!(!meta.schema_changed());
+        meta.mark_schema_changed();
+        meta.mark_schema_changed();
+        assert!(meta.schema_changed());
+        meta.sync_consistent_tenant_metadata().await.unwrap();
+        assert!(!meta.schema_changed());
+        assert_eq!(meta.data.read().version, 5);
+
+        // The change made during the read is left to the next sync.
+        changing_meta.set(meta.clone()).unwrap();
+        meta.sync_consistent_tenant_metadata().await.unwrap();
+        assert!(meta.schema_changed());
+    }
+
     #[tokio::test]
     async fn test_sys_info() {
         let info = sys_info::disk_info();
@@ -28,6 +28,7 @@ impl HttpServer {
         self.raft_admin
             .routes()
             .or(self.read())
+            .or(self.read_consistent())
             .or(self.write())
             .or(self.watch())
             .or(self.watch_meta_membership())
@@ -74,6 +75,55 @@ impl HttpServer {
             )
     }
 
+    /// Read after confirming the leadership with a read index, so that the result
+    /// includes all changes committed before the request.
+    fn read_consistent(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("read_consistent")
+            .and(warp::body::bytes())
+            .and(self.with_raft_node())
+            .and(self.with_storage())
+            .and_then(
+                |req: hyper::body::Bytes,
+                 node: Arc<RaftNode>,
+                 storage: Arc<RwLock<StateMachine>>| async move {
+                    let req: ReadCommand = serde_json::from_slice(&req)
+                        .map_err(MetaError::from)
+                        .map_err(|e| {
+                            error!("read command parse error: {:?}", e);
+                            warp::reject::custom(e)
+                        })?;
+
+                    let resp = match node.raw_raft().ensure_linearizable().await {
+                        Ok(_) => {
+                            let rsp = storage.read().await.process_read_command(&req);
+                            warp::reply::with_status(rsp.into_bytes(), http::StatusCode::OK)
+                        }
+                        Err(err) => {
+                            if let Some(openraft::error::ForwardToLeader {
+                                leader_id: Some(_leader_id),
+                                leader_node: Some(leader_node),
+                            }) = err.forward_to_leader()
+                            {
+                                warp::reply::with_status(
+                                    leader_node.address.clone().into_bytes(),
+                                    http::StatusCode::PERMANENT_REDIRECT,
+                                )
+                            } else {
+                                warp::reply::with_status(
+                                    err.to_string().into_bytes(),
+                                    http::StatusCode::INTERNAL_SERVER_ERROR,
+                                )
+                            }
+                        }
+                    };
+                    let res: Result<warp::reply::WithStatus<Vec<u8>>, warp::Rejection> = Ok(resp);
+                    res
+                },
+            )
+    }
+
     fn write(&self) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("write")
             .and(warp::body::bytes())
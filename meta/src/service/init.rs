@@ -43,6 +43,7 @@ impl MetaInit {
             index: 0,
             raft_id: 0,
             apply_type: APPLY_TYPE_WRITE,
+            leader_id: 0,
         };
 
         // init tenant
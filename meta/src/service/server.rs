@@ -11,15 +11,17 @@ use models::schema::database_schema::{DatabaseConfig, DatabaseOptions};
 use models::schema::DEFAULT_DATABASE;
 use openraft::SnapshotPolicy;
 use protos::raft_service::raft_service_server::RaftServiceServer;
-use replication::entry_store::HeedEntryStorage;
+use protos::tls::ClusterTls;
+use replication::entry_store::open_entry_storage;
 use replication::metrics::ReplicationMetrics;
 use replication::multi_raft::MultiRaft;
+use replication::network_client::NetworkConn;
 use replication::network_grpc::RaftCBServer;
 use replication::network_http::{EitherBody, RaftHttpAdmin, SyncSendError};
 use replication::node_store::NodeStorage;
 use replication::raft_node::RaftNode;
 use replication::state_store::StateStorage;
-use replication::{RaftNodeInfo, ReplicationConfig};
+use replication::{EntryStorageEngine, RaftNodeInfo, ReplicationConfig};
 use tokio::sync::RwLock;
 use tower::Service;
 use tracing::{info, warn};
@@ -33,19 +35,53 @@ use crate::store::storage::StateMachine;
 
 pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
     info!("CnosDB meta config: {:?}", opt);
+    if let Some(tls_config) = &opt.security.cluster_tls_config {
+        let tls = protos::tls::init_cluster_tls(tls_config.clone()).map_err(|e| {
+            MetaError::CommonError {
+                msg: format!("Failed to load the certificates of the cluster TLS: {}", e),
+            }
+        })?;
+        tokio::spawn(regular_reload_cluster_tls(tls));
+    }
     let id = opt.global.node_id;
     let path = std::path::Path::new(&opt.global.data_path);
     let http_addr =
         models::utils::build_address(&opt.global.raft_node_host, opt.global.listen_port);
 
+    let entry_storage = opt
+        .cluster
+        .entry_storage
+        .parse::<EntryStorageEngine>()
+        .map_err(|msg| MetaError::CommonError { msg })?;
+    let config = ReplicationConfig {
+        cluster_name: opt.global.cluster_name.clone(),
+        lmdb_max_map_size: opt.cluster.lmdb_max_map_size,
+        grpc_enable_gzip: opt.global.grpc_enable_gzip,
+        heartbeat_interval: opt.cluster.heartbeat_interval,
+        raft_logs_to_keep: opt.cluster.raft_logs_to_keep,
+        send_append_entries_timeout: opt.cluster.send_append_entries_timeout,
+        install_snapshot_timeout: opt.cluster.install_snapshot_timeout,
+        snapshot_policy: SnapshotPolicy::LogsSinceLast(opt.cluster.raft_logs_to_keep),
+        snapshot_logs_size: 0,
+        purge_replicated_logs: false,
+        snapshot_interval: 0,
+        // The cluster TLS only covers the gRPC services of the data nodes.
+        cluster_tls: false,
+        append_entries_max_batch: 1,
+        entry_storage,
+    };
+
     let max_size = opt.cluster.lmdb_max_map_size;
     let state = StateStorage::open(path.join(format!("{}_state", id)), max_size)?;
-    let entry = HeedEntryStorage::open(path.join(format!("{}_entry", id)), max_size)?;
+    let entry = open_entry_storage(
+        config.entry_storage,
+        path.join(format!("{}_entry", id)),
+        max_size,
+    )?;
     let engine = StateMachine::open(path.join(format!("{}_data", id)), max_size)?;
 
     let state = Arc::new(state);
     let engine = Arc::new(RwLock::new(engine));
-    let entry = Arc::new(RwLock::new(entry));
 
     let info = RaftNodeInfo {
         group_id: 2222,
@@ -53,16 +89,6 @@ pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
     };
 
     let storage = NodeStorage::open(id, info.clone(), state, engine.clone(), entry).await?;
-    let config = ReplicationConfig {
-        cluster_name: opt.global.cluster_name.clone(),
-        lmdb_max_map_size: opt.cluster.lmdb_max_map_size,
-        grpc_enable_gzip: opt.global.grpc_enable_gzip,
-        heartbeat_interval: opt.cluster.heartbeat_interval,
-        raft_logs_to_keep: opt.cluster.raft_logs_to_keep,
-        send_append_entries_timeout: opt.cluster.send_append_entries_timeout,
-        install_snapshot_timeout: opt.cluster.install_snapshot_timeout,
-        snapshot_policy: SnapshotPolicy::LogsSinceLast(opt.cluster.raft_logs_to_keep),
-    };
 
     let mut db_opt = DatabaseOptions::default();
     db_opt.set_replica(opt.sys_config.system_database_replica);
@@ -97,7 +123,8 @@ pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
         models::schema::DEFAULT_CATALOG.to_string(),
         default_database,
     );
-    let node = RaftNode::new(id, info, Arc::new(storage), config)
+    let network = NetworkConn::new(config.clone());
+    let node = RaftNode::new(id, info, Arc::new(storage), config, network)
         .await
         .unwrap();
     {
@@ -113,12 +140,29 @@ pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
         opt.heartbeat.clone(),
     ));
 
-    let bind_addr = models::utils::build_address("0.0.0.0", opt.global.listen_port);
+    let bind_host = utils::net::unspecified_host(opt.global.listen_dual_stack);
+    let bind_addr = models::utils::build_address(&bind_host.to_string(), opt.global.listen_port);
     tokio::spawn(start_warp_grpc_server(bind_addr, node, engine));
 
     Ok(())
 }
 
+async fn regular_reload_cluster_tls(tls: &'static ClusterTls) {
+    let mut interval = tokio::time::interval(tls.reload_interval());
+    loop {
+        interval.tick().await;
+
+        match tls.reload_if_modified() {
+            Ok(true) => info!("reloaded the certificates of the cluster TLS"),
+            Ok(false) => {}
+            Err(e) => warn!(
+                "failed to reload the certificates of the cluster TLS: {}",
+                e
+            ),
+        }
+    }
+}
+
 async fn detect_node_heartbeat(
     node: RaftNode,
     storage: Arc<RwLock<StateMachine>>,
@@ -120,7 +120,10 @@ impl SingleServer {
     }
 
     fn read(&self) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        // Reads of the single meta server are always consistent.
         warp::path!("read")
+            .or(warp::path!("read_consistent"))
+            .unify()
             .and(warp::body::bytes())
             .and(self.with_storage())
             .and_then(
@@ -2,12 +2,13 @@
 
 use std::collections::{HashMap, HashSet};
 
-use models::auth::privilege::DatabasePrivilege;
+use models::auth::privilege::{DatabasePrivilege, TablePrivilege};
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::{UserDesc, UserOptions};
 use models::meta_data::*;
 use models::oid::Oid;
-use models::schema::database_schema::DatabaseSchema;
+use models::schema::continuous_query::ContinuousQueryInfo;
+use models::schema::database_schema::{DatabaseSchema, SeriesPlacement};
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::ResourceInfo;
 use models::schema::table_schema::TableSchema;
@@ -60,6 +61,8 @@ pub enum WriteCommand {
     UpdateVnode(UpdateVnodeArgs),
     // cluster, node info
     AddDataNode(String, NodeInfo),
+    // cluster, node info of a node only running the query engine
+    AddQueryNode(String, NodeInfo),
 
     //cluster, node metrics
     ReportNodeMetrics(String, NodeMetrics),
@@ -76,6 +79,12 @@ pub enum WriteCommand {
     // cluster, tenant, db name
     DropDB(String, String, String),
 
+    // cluster, tenant, db name, drop time in nanoseconds
+    DropDBAt(String, String, String, i64),
+
+    // cluster, tenant, db name, drop time in nanoseconds
+    RemoveDroppedDB(String, String, String, i64),
+
     // cluster, tenant, db name, timestamp
     CreateBucket(String, String, String, i64),
 
@@ -129,6 +138,20 @@ pub enum WriteCommand {
     GrantPrivileges(String, Vec<(DatabasePrivilege, String)>, String, String),
     // cluster, privileges, role_name, tenant_name
     RevokePrivileges(String, Vec<(DatabasePrivilege, String)>, String, String),
+    // cluster, (privilege, db name, table name), role_name, tenant_name
+    GrantTablePrivileges(
+        String,
+        Vec<(TablePrivilege, String, String)>,
+        String,
+        String,
+    ),
+    // cluster, (privilege, db name, table name), role_name, tenant_name
+    RevokeTablePrivileges(
+        String,
+        Vec<(DatabasePrivilege, String, String)>,
+        String,
+        String,
+    ),
 
     Set {
         key: String,
@@ -153,12 +176,22 @@ pub enum WriteCommand {
 
     // cluster, source_node_id, dest_node_id
     MoveQueryInfo(String, NodeId, NodeId),
+
+    // cluster, continuous_query
+    CreateContinuousQuery(String, ContinuousQueryInfo),
+
+    // cluster, tenant, db, name
+    DropContinuousQuery(String, String, String, String),
+
+    // cluster, tenant, db name, timestamp, series placement of the bucket
+    CreateBucketWithPlacement(String, String, String, i64, SeriesPlacement),
 }
 
 /******************* read command *************************/
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ReadCommand {
     DataNodes(String),              //cluster
+    QueryNodes(String),             //cluster
     TenantMetaData(String, String), // cluster tenant
 
     NodeMetrics(String), //cluster
@@ -196,8 +229,14 @@ pub enum ReadCommand {
     // cluster, node_id
     ReadQueryInfos(String, NodeId),
 
+    // cluster, tenant, db
+    ContinuousQueries(String, String, String),
+
     // cluster, tenant, db, table
     ReadTableSchema(String, String, String, String),
+
+    // server side timestamp in nanoseconds, used to sample clock offset
+    ServerTimestamp,
 }
 
 pub const ENTRY_LOG_TYPE_SET: i32 = 1;
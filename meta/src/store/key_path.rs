@@ -6,14 +6,17 @@ use models::oid::Oid;
 // **    /cluster_name/tenants/tenant/roles/roles ->
 // **    /cluster_name/tenants/tenant/members/user_id ->
 // **    /cluster_name/tenants/tenant/limiter ->
+// **    /cluster_name/tenants/tenant/dropped_dbs/db_name -> drop time of the database
 // **    /cluster_name/auto_incr_id -> id
 // **    /cluster_name/data_nodes/node_id -> [NodeInfo] 集群、数据节点等信息
+// **    /cluster_name/continuous_queries/tenant/db_name/name -> [ContinuousQueryInfo]
 
 // **    /cluster_name/tenant_name/dbs/db_name -> [DatabaseInfo] db相关信息、保留策略等
 // **    /cluster_name/tenant_name/dbs/db_name/buckets/id -> [BucketInfo] bucket相关信息
 // **    /cluster_name/tenant_name/dbs/db_name/schemas/name -> [TskvTableSchema] schema相关信息
 
 pub const DBS: &str = "dbs";
+pub const DROPPED_DBS: &str = "dropped_dbs";
 pub const USERS: &str = "users";
 pub const ROLES: &str = "roles";
 pub const BUCKETS: &str = "buckets";
@@ -24,6 +27,7 @@ pub const LIMITER: &str = "limiter";
 pub const DATA_NODES: &str = "data_nodes";
 pub const AUTO_INCR_ID: &str = "auto_incr_id";
 pub const DATA_NODES_METRICS: &str = "data_nodes_metrics";
+pub const QUERY_NODES: &str = "query_nodes";
 pub const RESOURCE_INFOS: &str = "resourceinfos";
 pub const RESOURCE_INFOS_MARK: &str = "resourceinfosmark";
 
@@ -65,6 +69,14 @@ impl KeyPath {
         format!("/{}/data_nodes/{}", cluster, id)
     }
 
+    pub fn query_nodes(cluster: &str) -> String {
+        format!("/{}/query_nodes", cluster)
+    }
+
+    pub fn query_node_id(cluster: &str, id: u64) -> String {
+        format!("/{}/query_nodes/{}", cluster, id)
+    }
+
     pub fn data_nodes_metrics(cluster: &str) -> String {
         format!("/{}/data_nodes_metrics", cluster)
     }
@@ -85,6 +97,14 @@ impl KeyPath {
         format!("/{}/tenants/{}/dbs/{}", cluster, tenant, db)
     }
 
+    pub fn tenant_dropped_dbs(cluster: &str, tenant: &str) -> String {
+        format!("/{}/tenants/{}/dropped_dbs", cluster, tenant)
+    }
+
+    pub fn tenant_dropped_db(cluster: &str, tenant: &str, db: &str) -> String {
+        format!("/{}/tenants/{}/dropped_dbs/{}", cluster, tenant, db)
+    }
+
     pub fn tenant_db_buckets(cluster: &str, tenant: &str, db: &str) -> String {
         format!("/{}/tenants/{}/dbs/{}/buckets", cluster, tenant, db)
     }
@@ -147,4 +167,12 @@ impl KeyPath {
     pub fn queries(cluster: &str) -> String {
         format!("/{}/queries", cluster)
     }
+
+    pub fn continuous_query(cluster: &str, tenant: &str, db: &str, name: &str) -> String {
+        format!("/{}/continuous_queries/{}/{}/{}", cluster, tenant, db, name)
+    }
+
+    pub fn continuous_queries(cluster: &str, tenant: &str, db: &str) -> String {
+        format!("/{}/continuous_queries/{}/{}", cluster, tenant, db)
+    }
 }
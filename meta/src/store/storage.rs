@@ -4,16 +4,17 @@ use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 
-use models::auth::privilege::DatabasePrivilege;
+use models::auth::privilege::{DatabasePrivilege, TablePrivilege, TenantObjectPrivilege};
 use models::auth::role::{CustomTenantRole, SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::{UserDesc, UserOptions};
 use models::meta_data::*;
 use models::oid::{Identifier, Oid, UuidGenerator};
-use models::schema::database_schema::DatabaseSchema;
+use models::schema::continuous_query::ContinuousQueryInfo;
+use models::schema::database_schema::{DatabaseSchema, SeriesPlacement};
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::ResourceInfo;
 use models::schema::table_schema::TableSchema;
-use models::schema::tenant::{Tenant, TenantOptions};
+use models::schema::tenant::{Tenant, TenantOptions, TenantQuota};
 use replication::errors::{HeedSnafu, MsgInvalidSnafu, ReplicationResult, SnapshotErrSnafu};
 use replication::{ApplyContext, ApplyStorage, EngineMetrics, Request, Response};
 use serde::{Deserialize, Serialize};
@@ -387,6 +388,8 @@ impl StateMachine {
             self.children_data::<CustomTenantRole<Oid>>(&KeyPath::roles(cluster, tenant))?;
         meta.members =
             self.children_data::<TenantRoleIdentifier>(&KeyPath::members(cluster, tenant))?;
+        meta.dropped_dbs =
+            self.children_data::<i64>(&KeyPath::tenant_dropped_dbs(cluster, tenant))?;
         let db_schemas =
             self.children_data::<DatabaseSchema>(&KeyPath::tenant_dbs(cluster, tenant))?;
 
@@ -414,6 +417,9 @@ impl StateMachine {
             ReadCommand::DataNodes(cluster) => {
                 response_encode(self.process_read_data_nodes(cluster))
             }
+            ReadCommand::QueryNodes(cluster) => {
+                response_encode(self.process_read_query_nodes(cluster))
+            }
             ReadCommand::NodeMetrics(cluster) => {
                 response_encode(self.process_read_node_metrics(cluster))
             }
@@ -465,9 +471,15 @@ impl StateMachine {
             ReadCommand::ReadQueryInfos(cluster, node_id) => {
                 response_encode(self.process_read_queries(cluster, *node_id))
             }
+            ReadCommand::ContinuousQueries(cluster, tenant, db) => {
+                response_encode(self.process_read_continuous_queries(cluster, tenant, db))
+            }
             ReadCommand::ReadTableSchema(cluster, tenant, db_name, table_name) => {
                 response_encode(self.process_read_table(cluster, tenant, db_name, table_name))
             }
+            ReadCommand::ServerTimestamp => {
+                response_encode::<i64>(Ok(models::utils::now_timestamp_nanos()))
+            }
         }
     }
 
@@ -486,6 +498,22 @@ impl StateMachine {
         Ok(queries)
     }
 
+    pub fn process_read_continuous_queries(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db: &str,
+    ) -> MetaResult<Vec<ContinuousQueryInfo>> {
+        let path = KeyPath::continuous_queries(cluster, tenant, db);
+        let mut queries: Vec<ContinuousQueryInfo> = self
+            .children_data::<ContinuousQueryInfo>(&path)?
+            .into_values()
+            .collect();
+        queries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(queries)
+    }
+
     pub fn process_read_replication_set(
         &self,
         cluster: &str,
@@ -516,6 +544,15 @@ impl StateMachine {
         Ok((response, ver))
     }
 
+    pub fn process_read_query_nodes(&self, cluster: &str) -> MetaResult<Vec<NodeInfo>> {
+        let response: Vec<NodeInfo> = self
+            .children_data::<NodeInfo>(&KeyPath::query_nodes(cluster))?
+            .into_values()
+            .collect();
+
+        Ok(response)
+    }
+
     pub fn process_read_node_metrics(&self, cluster: &str) -> MetaResult<Vec<NodeMetrics>> {
         let response: Vec<NodeMetrics> = self
             .children_data::<NodeMetrics>(&KeyPath::data_nodes_metrics(cluster))?
@@ -668,6 +705,9 @@ impl StateMachine {
             WriteCommand::AddDataNode(cluster, node) => {
                 response_encode(self.process_add_date_node(cluster, node))
             }
+            WriteCommand::AddQueryNode(cluster, node) => {
+                response_encode(self.process_add_query_node(cluster, node))
+            }
             WriteCommand::ReportNodeMetrics(cluster, node_metrics) => {
                 response_encode(self.process_add_node_metrics(cluster, node_metrics))
             }
@@ -681,8 +721,14 @@ impl StateMachine {
                 response_encode(self.process_db_is_hidden(cluster, tenant, db, *db_is_hidden))
             }
             WriteCommand::DropDB(cluster, tenant, db_name) => {
-                response_encode(self.process_drop_db(cluster, tenant, db_name))
+                response_encode(self.process_drop_db(cluster, tenant, db_name, None))
+            }
+            WriteCommand::DropDBAt(cluster, tenant, db_name, drop_time) => {
+                response_encode(self.process_drop_db(cluster, tenant, db_name, Some(*drop_time)))
             }
+            WriteCommand::RemoveDroppedDB(cluster, tenant, db_name, drop_time) => response_encode(
+                self.process_remove_dropped_db(cluster, tenant, db_name, *drop_time),
+            ),
             WriteCommand::DropTable(cluster, tenant, db_name, table_name) => {
                 response_encode(self.process_drop_table(cluster, tenant, db_name, table_name))
             }
@@ -692,8 +738,15 @@ impl StateMachine {
             WriteCommand::UpdateTable(cluster, tenant, schema) => {
                 response_encode(self.process_update_table(cluster, tenant, schema))
             }
-            WriteCommand::CreateBucket(cluster, tenant, db, ts) => {
-                response_encode(self.process_create_bucket(cluster, tenant, db, ts).await)
+            WriteCommand::CreateBucket(cluster, tenant, db, ts) => response_encode(
+                self.process_create_bucket(cluster, tenant, db, ts, None)
+                    .await,
+            ),
+            WriteCommand::CreateBucketWithPlacement(cluster, tenant, db, ts, placement) => {
+                response_encode(
+                    self.process_create_bucket(cluster, tenant, db, ts, Some(*placement))
+                        .await,
+                )
             }
             WriteCommand::DeleteBucket(cluster, tenant, db, id) => {
                 response_encode(self.process_delete_bucket(cluster, tenant, db, *id))
@@ -773,6 +826,22 @@ impl StateMachine {
                     tenant_name,
                 ))
             }
+            WriteCommand::GrantTablePrivileges(cluster, privileges, role_name, tenant_name) => {
+                response_encode(self.process_grant_table_privileges(
+                    cluster,
+                    privileges,
+                    role_name,
+                    tenant_name,
+                ))
+            }
+            WriteCommand::RevokeTablePrivileges(cluster, privileges, role_name, tenant_name) => {
+                response_encode(self.process_revoke_table_privileges(
+                    cluster,
+                    privileges,
+                    role_name,
+                    tenant_name,
+                ))
+            }
             WriteCommand::RetainID(cluster, count) => {
                 response_encode(self.process_retain_id(cluster, *count))
             }
@@ -803,6 +872,12 @@ impl StateMachine {
             WriteCommand::MoveQueryInfo(cluster, source_node_id, dest_node_id) => response_encode(
                 self.process_move_queryinfo(cluster, *source_node_id, *dest_node_id),
             ),
+            WriteCommand::CreateContinuousQuery(cluster, query) => {
+                response_encode(self.process_create_continuous_query(cluster, query))
+            }
+            WriteCommand::DropContinuousQuery(cluster, tenant, db, name) => {
+                response_encode(self.process_drop_continuous_query(cluster, tenant, db, name))
+            }
         }
     }
 
@@ -841,6 +916,38 @@ impl StateMachine {
         self.insert(&key, &value_encode(query_info)?)
     }
 
+    fn process_create_continuous_query(
+        &self,
+        cluster: &str,
+        query: &ContinuousQueryInfo,
+    ) -> MetaResult<()> {
+        let key = KeyPath::continuous_query(cluster, &query.tenant, &query.database, &query.name);
+        if self.contains_key(&key)? {
+            return Err(MetaError::ContinuousQueryAlreadyExists {
+                name: query.name.clone(),
+            });
+        }
+
+        self.insert(&key, &value_encode(query)?)
+    }
+
+    fn process_drop_continuous_query(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db: &str,
+        name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::continuous_query(cluster, tenant, db, name);
+        if !self.contains_key(&key)? {
+            return Err(MetaError::ContinuousQueryNotFound {
+                name: name.to_string(),
+            });
+        }
+
+        self.remove(&key)
+    }
+
     fn process_write_set(&self, key: &str, val: &str) -> MetaResult<()> {
         self.insert(key, val)
     }
@@ -905,10 +1012,12 @@ impl StateMachine {
                 set.vnodes.push(info.clone());
             }
 
-            // process if the leader is deleted....
-            if set.vnode(set.leader_vnode_id).is_none() && !set.vnodes.is_empty() {
-                set.leader_vnode_id = set.vnodes[0].id;
-                set.leader_node_id = set.vnodes[0].node_id;
+            // process if the leader is deleted, the learners never become the leader.
+            if set.vnode(set.leader_vnode_id).is_none() {
+                if let Some(vnode) = set.voters().next().cloned() {
+                    set.leader_vnode_id = vnode.id;
+                    set.leader_node_id = vnode.node_id;
+                }
             }
         }
 
@@ -955,6 +1064,11 @@ impl StateMachine {
         Ok(id)
     }
 
+    fn process_add_query_node(&self, cluster: &str, node: &NodeInfo) -> MetaResult<()> {
+        let key = KeyPath::query_node_id(cluster, node.id);
+        self.insert(&key, &value_encode(node)?)
+    }
+
     fn check_node_ip_address(&self, cluster: &str, node: &NodeInfo) -> MetaResult<bool> {
         for value in self
             .children_data::<NodeInfo>(&KeyPath::data_nodes(cluster))?
@@ -996,10 +1110,22 @@ impl StateMachine {
         self.insert(&key, &value)
     }
 
-    fn process_drop_db(&self, cluster: &str, tenant: &str, db_name: &str) -> MetaResult<()> {
+    fn process_drop_db(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        drop_time: Option<i64>,
+    ) -> MetaResult<()> {
         let key = KeyPath::tenant_db_name(cluster, tenant, db_name);
         let _ = self.remove(&key);
 
+        // Watched by the nodes to purge the caches of the database.
+        if let Some(drop_time) = drop_time {
+            let key = KeyPath::tenant_dropped_db(cluster, tenant, db_name);
+            self.insert(&key, &value_encode(&drop_time)?)?;
+        }
+
         let buckets_path = KeyPath::tenant_db_buckets(cluster, tenant, db_name);
         for it in self.children_fullpath(&buckets_path)?.iter() {
             let _ = self.remove(it);
@@ -1010,6 +1136,12 @@ impl StateMachine {
             let _ = self.remove(it);
         }
 
+        // The running continuous queries stop themselves at the next run.
+        let continuous_queries_path = KeyPath::continuous_queries(cluster, tenant, db_name);
+        for it in self.children_fullpath(&continuous_queries_path)?.iter() {
+            let _ = self.remove(it);
+        }
+
         Ok(())
     }
 
@@ -1030,6 +1162,22 @@ impl StateMachine {
         self.remove(&key)
     }
 
+    /// Forget the dropped database, unless it is dropped again after `drop_time`.
+    fn process_remove_dropped_db(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        drop_time: i64,
+    ) -> MetaResult<()> {
+        let key = KeyPath::tenant_dropped_db(cluster, tenant, db_name);
+        if self.get_struct::<i64>(&key)? == Some(drop_time) {
+            self.remove(&key)?;
+        }
+
+        Ok(())
+    }
+
     fn process_create_db(
         &self,
         cluster: &str,
@@ -1044,8 +1192,14 @@ impl StateMachine {
         }
 
         self.check_db_schema_valid(cluster, schema)?;
+        self.check_tenant_quota(cluster, tenant, None, TenantQuota::Databases)?;
         self.insert(&key, &value_encode(schema)?)?;
 
+        let dropped_key = KeyPath::tenant_dropped_db(cluster, tenant, schema.database_name());
+        if self.contains_key(&dropped_key)? {
+            self.remove(&dropped_key)?;
+        }
+
         self.to_tenant_meta_data(cluster, tenant)
     }
 
@@ -1100,6 +1254,71 @@ impl StateMachine {
         Ok(())
     }
 
+    /// The number of the objects of the tenant limited by the quota, the quota of
+    /// the tables of each database counts the tables of `db`.
+    fn tenant_quota_usage(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db: Option<&str>,
+        quota: TenantQuota,
+    ) -> MetaResult<usize> {
+        let usage = match quota {
+            TenantQuota::Databases => self
+                .children_fullpath(&KeyPath::tenant_dbs(cluster, tenant))?
+                .len(),
+            TenantQuota::Tables => {
+                let mut tables = 0;
+                for db_path in self.children_fullpath(&KeyPath::tenant_dbs(cluster, tenant))? {
+                    let db = db_path.rsplit('/').next().unwrap_or_default();
+                    tables += self
+                        .children_fullpath(&KeyPath::tenant_schemas(cluster, tenant, db))?
+                        .len();
+                }
+                tables
+            }
+            TenantQuota::Users => self
+                .children_fullpath(&KeyPath::members(cluster, tenant))?
+                .len(),
+            TenantQuota::TablesPerDatabase => match db {
+                Some(db) => self
+                    .children_fullpath(&KeyPath::tenant_schemas(cluster, tenant, db))?
+                    .len(),
+                None => 0,
+            },
+            // The series are counted by the vnodes.
+            TenantQuota::SeriesPerTable => 0,
+        };
+
+        Ok(usage)
+    }
+
+    /// Returns an error if one more object exceeds the quota of the tenant.
+    fn check_tenant_quota(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db: Option<&str>,
+        quota: TenantQuota,
+    ) -> MetaResult<()> {
+        let max = match self
+            .get_struct::<Tenant>(&KeyPath::tenant(cluster, tenant))?
+            .and_then(|t| t.options().quota(quota))
+        {
+            Some(max) => max,
+            None => return Ok(()),
+        };
+        if self.tenant_quota_usage(cluster, tenant, db, quota)? >= max {
+            return Err(MetaError::TenantQuotaExceeded {
+                tenant: tenant.to_string(),
+                quota,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
     fn process_create_table(
         &self,
         cluster: &str,
@@ -1118,6 +1337,13 @@ impl StateMachine {
                 table_name: schema.name().to_string(),
             });
         }
+        self.check_tenant_quota(cluster, tenant, None, TenantQuota::Tables)?;
+        self.check_tenant_quota(
+            cluster,
+            tenant,
+            Some(schema.db()),
+            TenantQuota::TablesPerDatabase,
+        )?;
 
         self.insert(&key, &value_encode(schema)?)?;
 
@@ -1179,6 +1405,7 @@ impl StateMachine {
         tenant: &str,
         db: &str,
         ts: &i64,
+        series_placement: Option<SeriesPlacement>,
     ) -> MetaResult<TenantMetaData> {
         let db_path = KeyPath::tenant_db_name(cluster, tenant, db);
         let buckets = self.children_data::<BucketInfo>(&(db_path.clone() + "/buckets"))?;
@@ -1215,6 +1442,8 @@ impl StateMachine {
             start_time: 0,
             end_time: 0,
             shard_group: vec![],
+            series_placement: series_placement
+                .unwrap_or_else(|| db_schema.options.series_placement()),
         };
         (bucket.start_time, bucket.end_time) = get_time_range(
             *ts,
@@ -1432,6 +1661,11 @@ impl StateMachine {
             self.process_drop_role(cluster, role.name(), name)?;
         }
 
+        let dropped_dbs_path = KeyPath::tenant_dropped_dbs(cluster, name);
+        for it in self.children_fullpath(&dropped_dbs_path)?.iter() {
+            self.remove(it)?;
+        }
+
         // drop tenant meta
         let key = KeyPath::tenant(cluster, name);
         let limiter_key = KeyPath::limiter(cluster, name);
@@ -1456,7 +1690,10 @@ impl StateMachine {
             self.contains_key(&member_key)?,
             self.contains_key(&role_key)? || SystemTenantRole::try_from(role.name()).is_ok(),
         ) {
-            (false, true) => self.insert(&member_key, &value_encode(&role)?),
+            (false, true) => {
+                self.check_tenant_quota(cluster, tenant_name, None, TenantQuota::Users)?;
+                self.insert(&member_key, &value_encode(&role)?)
+            }
             (true, _) => Err(MetaError::UserAlreadyExists {
                 user: user_id.to_string(),
             }),
@@ -1609,6 +1846,79 @@ impl StateMachine {
         }
     }
 
+    fn process_grant_table_privileges(
+        &self,
+        cluster: &str,
+        privileges: &[(TablePrivilege, String, String)],
+        role_name: &str,
+        tenant_name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::role(cluster, tenant_name, role_name);
+        let mut role = self
+            .get_struct::<CustomTenantRole<Oid>>(&key)?
+            .ok_or_else(|| MetaError::RoleNotFound {
+                role: role_name.to_string(),
+            })?;
+        for (privilege, database_name, table_name) in privileges {
+            let table_key =
+                KeyPath::tenant_schema_name(cluster, tenant_name, database_name, table_name);
+            let schema = self.get_struct::<TableSchema>(&table_key)?.ok_or_else(|| {
+                MetaError::TableNotFound {
+                    table: format!("{database_name}.{table_name}"),
+                }
+            })?;
+            if let (TableSchema::TsKvTableSchema(schema), Some(columns)) =
+                (&schema, &privilege.columns)
+            {
+                if let Some(column) = columns.iter().find(|c| schema.column(c).is_none()) {
+                    return Err(MetaError::CommonError {
+                        msg: format!(
+                            "Column {column} not found in table {database_name}.{table_name}"
+                        ),
+                    });
+                }
+            }
+            role.grant_table_privilege(
+                database_name.clone(),
+                table_name.clone(),
+                privilege.clone(),
+            );
+        }
+
+        Ok(self.insert(&key, &value_encode(&role)?)?)
+    }
+
+    fn process_revoke_table_privileges(
+        &self,
+        cluster: &str,
+        privileges: &[(DatabasePrivilege, String, String)],
+        role_name: &str,
+        tenant_name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::role(cluster, tenant_name, role_name);
+        let mut role = self
+            .get_struct::<CustomTenantRole<Oid>>(&key)?
+            .ok_or_else(|| MetaError::RoleNotFound {
+                role: role_name.to_string(),
+            })?;
+        for (privilege, database_name, table_name) in privileges {
+            if role
+                .revoke_table_privilege(database_name, table_name, privilege)
+                .is_err()
+            {
+                return Err(MetaError::PrivilegeCannotRevoke {
+                    privilege: TenantObjectPrivilege::Table(
+                        TablePrivilege::new(privilege.clone(), None),
+                        database_name.to_string(),
+                        table_name.to_string(),
+                    ),
+                });
+            }
+        }
+
+        Ok(self.insert(&key, &value_encode(&role)?)?)
+    }
+
     fn process_limiter_request(
         &self,
         cluster: &str,
@@ -1706,9 +2016,16 @@ fn check_node_enough(need: u64, node_list: &[NodeInfo]) -> MetaResult<()> {
 mod test {
     use std::collections::BTreeMap;
     use std::println;
+    use std::time::Duration;
 
+    use models::meta_data::NodeInfo;
+    use models::schema::continuous_query::ContinuousQueryInfo;
+    use models::schema::query_info::QueryId;
     use serde::{Deserialize, Serialize};
 
+    use super::StateMachine;
+    use crate::error::MetaError;
+
     #[test]
     fn test_btree_map() {
         let mut map = BTreeMap::new();
@@ -1780,4 +2097,123 @@ mod test {
         let str = serde_json::to_string(&"xxx".to_string()).unwrap();
         print!("\n4 === {}=== \n", str);
     }
+
+    #[test]
+    fn test_continuous_queries_of_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StateMachine::open(dir.path(), 1024 * 1024 * 10).unwrap();
+        let cq = |db: &str| ContinuousQueryInfo {
+            name: "cq".to_string(),
+            tenant: "cnosdb".to_string(),
+            database: db.to_string(),
+            query_id: QueryId::next_id(),
+            node_id: 1,
+            target_table: "cpu_1m".to_string(),
+            query: "SELECT 1".to_string(),
+            every: Duration::from_secs(60),
+            for_range: Duration::from_secs(60),
+        };
+
+        // The same name in different databases.
+        storage
+            .process_create_continuous_query("cluster", &cq("db1"))
+            .unwrap();
+        storage
+            .process_create_continuous_query("cluster", &cq("db2"))
+            .unwrap();
+        assert!(matches!(
+            storage.process_create_continuous_query("cluster", &cq("db1")),
+            Err(MetaError::ContinuousQueryAlreadyExists { .. })
+        ));
+        let queries = storage
+            .process_read_continuous_queries("cluster", "cnosdb", "db1")
+            .unwrap();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].database, "db1");
+
+        // Dropped with the database.
+        storage
+            .process_drop_db("cluster", "cnosdb", "db1", None)
+            .unwrap();
+        assert!(storage
+            .process_read_continuous_queries("cluster", "cnosdb", "db1")
+            .unwrap()
+            .is_empty());
+        storage
+            .process_drop_continuous_query("cluster", "cnosdb", "db2", "cq")
+            .unwrap();
+        assert!(matches!(
+            storage.process_drop_continuous_query("cluster", "cnosdb", "db2", "cq"),
+            Err(MetaError::ContinuousQueryNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_dropped_dbs() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StateMachine::open(dir.path(), 1024 * 1024 * 10).unwrap();
+        let dropped_dbs = || {
+            storage
+                .to_tenant_meta_data("cluster", "cnosdb")
+                .unwrap()
+                .dropped_dbs
+        };
+
+        storage
+            .process_drop_db("cluster", "cnosdb", "db1", Some(100))
+            .unwrap();
+        storage
+            .process_drop_db("cluster", "cnosdb", "db2", None)
+            .unwrap();
+        assert_eq!(dropped_dbs(), [("db1".to_string(), 100)].into());
+
+        // Dropped again since then.
+        storage
+            .process_drop_db("cluster", "cnosdb", "db1", Some(200))
+            .unwrap();
+        storage
+            .process_remove_dropped_db("cluster", "cnosdb", "db1", 100)
+            .unwrap();
+        assert_eq!(dropped_dbs(), [("db1".to_string(), 200)].into());
+
+        storage
+            .process_remove_dropped_db("cluster", "cnosdb", "db1", 200)
+            .unwrap();
+        assert!(dropped_dbs().is_empty());
+    }
+
+    #[test]
+    fn test_query_nodes() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StateMachine::open(dir.path(), 1024 * 1024 * 10).unwrap();
+        let node = |id: u64, mode: &str| NodeInfo {
+            id,
+            grpc_addr: format!("127.0.0.1:{}", 8000 + id),
+            http_addr: format!("127.0.0.1:{}", 9000 + id),
+            mode: mode.to_string(),
+        };
+
+        storage
+            .process_add_date_node("cluster", &node(1, "query_tskv"))
+            .unwrap();
+        storage
+            .process_add_query_node("cluster", &node(2, "query"))
+            .unwrap();
+        // Registered again after a restart.
+        storage
+            .process_add_query_node("cluster", &node(2, "query"))
+            .unwrap();
+
+        let query_nodes = storage.process_read_query_nodes("cluster").unwrap();
+        assert_eq!(
+            query_nodes
+                .iter()
+                .map(|n| (n.id, n.mode.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, "query")]
+        );
+        // The query nodes are not data nodes, no vnode is placed on them.
+        let (data_nodes, _) = storage.process_read_data_nodes("cluster").unwrap();
+        assert_eq!(data_nodes.iter().map(|n| n.id).collect::<Vec<_>>(), vec![1]);
+    }
 }
@@ -0,0 +1,108 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use models::utils::now_timestamp_nanos;
+
+const AUDIT_FILE_NAME: &str = "audit.log";
+
+/// Appends to `audit.log` in the directory, the file is renamed to
+/// `audit.log.<timestamp>` when it exceeds the max size, and the oldest files
+/// are removed to keep at most `max_file_count` files.
+pub struct RotatingFile {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_file_count: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn new(dir: PathBuf, max_file_size: u64, max_file_count: usize) -> Self {
+        Self {
+            dir,
+            max_file_size,
+            max_file_count,
+            file: None,
+            size: 0,
+        }
+    }
+
+    pub fn write(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+        let file = match self.file.take() {
+            Some(file) => file,
+            None => self.open()?,
+        };
+        self.file.insert(file).write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<File> {
+        fs::create_dir_all(&self.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(AUDIT_FILE_NAME))?;
+        self.size = file.metadata()?.len();
+        Ok(file)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        self.size = 0;
+        fs::rename(
+            self.dir.join(AUDIT_FILE_NAME),
+            self.dir
+                .join(format!("{AUDIT_FILE_NAME}.{}", now_timestamp_nanos())),
+        )?;
+
+        let prefix = format!("{AUDIT_FILE_NAME}.");
+        let mut rotated_files = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(&prefix))
+            .collect::<Vec<_>>();
+        rotated_files.sort();
+        // The new file is counted too.
+        let excess = (rotated_files.len() + 1).saturating_sub(self.max_file_count);
+        for name in rotated_files.into_iter().take(excess) {
+            fs::remove_file(self.dir.join(name))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RotatingFile, AUDIT_FILE_NAME};
+
+    #[test]
+    fn test_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::new(dir.path().to_path_buf(), 10, 2);
+        for line in ["0123456\n", "789\n", "abcdefg\n", "hijklmn\n"] {
+            file.write(line.as_bytes()).unwrap();
+        }
+
+        let mut names = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], AUDIT_FILE_NAME);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(&names[0])).unwrap(),
+            "hijklmn\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(&names[1])).unwrap(),
+            "abcdefg\n"
+        );
+    }
+}
@@ -0,0 +1,365 @@
+mod file;
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use coordinator::service::CoordinatorRef;
+use models::schema::{DEFAULT_CATALOG, USAGE_SCHEMA};
+use models::utils::now_timestamp_nanos;
+use protocol_parser::Line;
+use protos::kv_service::WriteConsistency;
+use protos::FieldValue;
+use serde::Serialize;
+use spi::service::protocol::Context;
+use spi::QueryResult;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use trace::warn;
+use utils::precision::Precision;
+
+use self::file::RotatingFile;
+use crate::sql::redact::redact_statement;
+
+pub const AUDIT_TABLE: &str = "audit";
+const MAX_BATCH_SIZE: usize = 1024;
+
+pub type AuditLoggerRef = Arc<AuditLogger>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEventType {
+    Login,
+    Query,
+    Dml,
+    Ddl,
+    Admin,
+}
+
+impl AuditEventType {
+    /// The type of the statement, decided by its first keyword.
+    pub fn of_statement(sql: &str) -> Self {
+        let keyword = sql
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match keyword.as_str() {
+            "select" | "with" | "show" | "describe" | "desc" | "explain" | "preview" => Self::Query,
+            "insert" | "delete" | "update" | "copy" => Self::Dml,
+            "create" | "drop" | "alter" | "grant" | "revoke" | "recover" => Self::Ddl,
+            _ => Self::Admin,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::Query => "query",
+            Self::Dml => "dml",
+            Self::Ddl => "ddl",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// Who did what, from where and with which result.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub time: i64,
+    pub user: String,
+    pub tenant: String,
+    pub client_addr: Option<String>,
+    pub event_type: AuditEventType,
+    /// The objects accessed by the statement, e.g. the tables scanned by a query
+    pub object: Option<String>,
+    pub statement: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn login(user: &str, tenant: &str, client_addr: Option<&str>) -> Self {
+        Self {
+            time: now_timestamp_nanos(),
+            user: user.to_string(),
+            tenant: tenant.to_string(),
+            client_addr: client_addr.map(|a| a.to_string()),
+            event_type: AuditEventType::Login,
+            object: None,
+            statement: None,
+            success: true,
+            error: None,
+        }
+    }
+
+    /// The secrets in the statement are redacted, see [`redact_statement`].
+    pub fn statement(context: &Context, sql: &str) -> Self {
+        Self {
+            time: now_timestamp_nanos(),
+            user: context.user().desc().name().to_string(),
+            tenant: context.tenant().to_string(),
+            client_addr: context.client_addr().map(|a| a.to_string()),
+            event_type: AuditEventType::of_statement(sql),
+            object: None,
+            statement: Some(redact_statement(sql)),
+            success: true,
+            error: None,
+        }
+    }
+
+    /// The write of the `protocol` other than SQL, e.g. line protocol, to the
+    /// database of the context.
+    pub fn write(context: &Context, protocol: &str, error: Option<String>) -> Self {
+        Self {
+            time: now_timestamp_nanos(),
+            user: context.user().desc().name().to_string(),
+            tenant: context.tenant().to_string(),
+            client_addr: context.client_addr().map(|a| a.to_string()),
+            event_type: AuditEventType::Dml,
+            object: Some(context.database().to_string()),
+            statement: Some(format!("{protocol} write")),
+            success: error.is_none(),
+            error,
+        }
+    }
+
+    pub fn with_object(mut self, object: Option<String>) -> Self {
+        self.object = object;
+        self
+    }
+
+    pub fn with_result<T>(mut self, result: &QueryResult<T>) -> Self {
+        if let Err(err) = result {
+            self.success = false;
+            self.error = Some(err.to_string());
+        }
+        self
+    }
+
+    fn to_line(&self, node_id: &str, timestamp: i64) -> Line<'static> {
+        let tags = vec![
+            (Cow::Borrowed("node_id"), Cow::Owned(node_id.to_string())),
+            (Cow::Borrowed("tenant"), Cow::Owned(self.tenant.clone())),
+            (Cow::Borrowed("user"), Cow::Owned(self.user.clone())),
+            (
+                Cow::Borrowed("event_type"),
+                Cow::Borrowed(self.event_type.as_str()),
+            ),
+        ];
+        let mut fields = vec![(Cow::Borrowed("success"), FieldValue::Bool(self.success))];
+        for (name, value) in [
+            ("client_addr", &self.client_addr),
+            ("object", &self.object),
+            ("statement", &self.statement),
+            ("error", &self.error),
+        ] {
+            if let Some(value) = value {
+                fields.push((
+                    Cow::Borrowed(name),
+                    FieldValue::Str(value.as_bytes().to_vec()),
+                ));
+            }
+        }
+
+        Line::new(Cow::Borrowed(AUDIT_TABLE), tags, fields, timestamp)
+    }
+}
+
+/// Records the audit events of this node into `usage_schema.audit` and the
+/// rotating files in the background, enabled by the config or `AUDIT ON`.
+pub struct AuditLogger {
+    enabled: AtomicBool,
+    sender: Sender<AuditEvent>,
+}
+
+impl AuditLogger {
+    pub fn new(coord: CoordinatorRef) -> Self {
+        let config = coord.get_config().audit;
+        let (sender, receiver) = mpsc::channel(config.buffer_size.max(1));
+        let writer = AuditWriter {
+            node_id: coord.node_id().to_string(),
+            coord: config.write_to_table.then(|| coord.clone()),
+            file: config.write_to_file.then(|| {
+                RotatingFile::new(
+                    PathBuf::from(&config.path),
+                    config.max_file_size,
+                    config.max_file_count,
+                )
+            }),
+            last_timestamp: 0,
+        };
+        tokio::spawn(writer.run(receiver));
+
+        Self {
+            enabled: AtomicBool::new(config.enable),
+            sender,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Record the event if enabled, the event is dropped if too many events
+    /// are waiting to be written.
+    pub fn log(&self, event: AuditEvent) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Err(TrySendError::Full(event)) = self.sender.try_send(event) {
+            warn!(
+                "audit buffer is full, drop the audit event of user {}",
+                event.user
+            );
+        }
+    }
+}
+
+struct AuditWriter {
+    node_id: String,
+    coord: Option<CoordinatorRef>,
+    file: Option<RotatingFile>,
+    last_timestamp: i64,
+}
+
+impl AuditWriter {
+    async fn run(mut self, mut receiver: Receiver<AuditEvent>) {
+        let mut events = Vec::with_capacity(MAX_BATCH_SIZE);
+        while let Some(event) = receiver.recv().await {
+            events.push(event);
+            while events.len() < MAX_BATCH_SIZE {
+                match receiver.try_recv() {
+                    Ok(event) => events.push(event),
+                    Err(_) => break,
+                }
+            }
+            self.write(&events).await;
+            events.clear();
+        }
+    }
+
+    async fn write(&mut self, events: &[AuditEvent]) {
+        if let Some(mut file) = self.file.take() {
+            let mut lines = Vec::with_capacity(events.len());
+            for event in events {
+                match serde_json::to_vec(event) {
+                    Ok(mut line) => {
+                        line.push(b'\n');
+                        lines.push(line);
+                    }
+                    Err(e) => warn!("failed to serialize the audit event: {e}"),
+                }
+            }
+            // Don't block the runtime by the file IO.
+            let written = tokio::task::spawn_blocking(move || {
+                for line in lines {
+                    if let Err(e) = file.write(&line) {
+                        warn!("failed to write the audit file: {e}");
+                        break;
+                    }
+                }
+                file
+            })
+            .await;
+            match written {
+                Ok(file) => self.file = Some(file),
+                Err(e) => warn!("failed to write the audit file, stop writing it: {e}"),
+            }
+        }
+
+        if let Some(coord) = self.coord.as_ref() {
+            // The events of the same series with the same timestamp overwrite each other.
+            let lines = events
+                .iter()
+                .map(|event| {
+                    let timestamp = event.time.max(self.last_timestamp + 1);
+                    self.last_timestamp = timestamp;
+                    event.to_line(&self.node_id, timestamp)
+                })
+                .collect::<Vec<_>>();
+            if let Err(e) = coord
+                .write_lines(
+                    DEFAULT_CATALOG,
+                    USAGE_SCHEMA,
+                    Precision::NS,
+                    WriteConsistency::Quorum,
+                    lines,
+                    None,
+                )
+                .await
+            {
+                warn!("failed to write the audit events to {USAGE_SCHEMA}.{AUDIT_TABLE}: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use models::auth::user::{User, UserDesc, UserOptions};
+    use spi::service::protocol::ContextBuilder;
+
+    use super::{AuditEvent, AuditEventType};
+
+    fn context() -> spi::service::protocol::Context {
+        let desc = UserDesc::new(0, "tester".to_string(), UserOptions::default(), false);
+        ContextBuilder::new(User::new(desc, Default::default(), None))
+            .with_client_addr(Some("127.0.0.1:8902".to_string()))
+            .build()
+    }
+
+    #[test]
+    fn test_no_secret_audited() {
+        let context = context();
+        for (sql, secret) in [
+            ("create user u1 with password='abc123'", "abc123"),
+            ("ALTER USER u1 SET PASSWORD = 'abc123'", "abc123"),
+            (
+                "alter tenant cnosdb set encryption key k1 = 'MDEyMzQ1Njc4OWFiY2RlZg=='",
+                "MDEyMzQ1Njc4OWFiY2RlZg==",
+            ),
+            (
+                "COPY INTO t FROM 's3://b/a.csv' CONNECTION = (secret_key = 'sk123') FILE_FORMAT = (TYPE = 'CSV')",
+                "sk123",
+            ),
+        ] {
+            let event = AuditEvent::statement(&context, sql);
+            let line = serde_json::to_string(&event).unwrap();
+            assert!(!line.contains(secret), "{line}");
+            assert!(event.statement.unwrap().contains("'******'"));
+        }
+    }
+
+    #[test]
+    fn test_write_event() {
+        let event = AuditEvent::write(&context(), "line protocol", Some("failed".to_string()));
+        assert_eq!(event.event_type, AuditEventType::Dml);
+        assert_eq!(event.object.as_deref(), Some("public"));
+        assert_eq!(event.client_addr.as_deref(), Some("127.0.0.1:8902"));
+        assert!(!event.success);
+    }
+
+    #[test]
+    fn test_event_type_of_statement() {
+        for (sql, expected) in [
+            ("SELECT * FROM t", AuditEventType::Query),
+            ("  (select 1)", AuditEventType::Query),
+            ("show databases", AuditEventType::Query),
+            ("insert into t(time, f) values (1, 1)", AuditEventType::Dml),
+            ("COPY INTO t FROM 'file:///tmp/a.csv'", AuditEventType::Dml),
+            ("create table t(f bigint)", AuditEventType::Ddl),
+            ("GRANT read on database db to role r", AuditEventType::Ddl),
+            ("kill 100", AuditEventType::Admin),
+            ("", AuditEventType::Admin),
+        ] {
+            assert_eq!(AuditEventType::of_statement(sql), expected, "{sql}");
+        }
+    }
+}
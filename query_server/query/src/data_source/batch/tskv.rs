@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -7,14 +8,16 @@ use coordinator::service::CoordinatorRef;
 use datafusion::arrow::array::Int64Array;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::TreeNode;
 use datafusion::common::DFSchema;
 use datafusion::datasource::{TableProvider, TableType};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::SessionState;
-use datafusion::logical_expr::expr::AggregateFunction;
+use datafusion::logical_expr::expr::{AggregateFunction, InList};
 use datafusion::logical_expr::logical_plan::AggWithGrouping;
 use datafusion::logical_expr::{
-    aggregate_function, Expr, TableProviderAggregationPushDown, TableProviderFilterPushDown,
+    aggregate_function, binary_expr, BinaryExpr, Expr, Operator, TableProviderAggregationPushDown,
+    TableProviderFilterPushDown,
 };
 use datafusion::optimizer::utils::{conjunction, split_conjunction};
 use datafusion::physical_expr::PhysicalExpr;
@@ -22,10 +25,14 @@ use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::memory::MemoryExec;
 use datafusion::physical_plan::{project_schema, ExecutionPlan};
 use datafusion::prelude::Column;
+use datafusion::scalar::ScalarValue;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::arrow::{DataType, Field, Schema};
-use models::predicate::domain::{Predicate, PredicateRef, PushedAggregateFunction};
+use models::encryption::ColumnCipher;
+use models::predicate::domain::{
+    Predicate, PredicateRef, PushedAggregateFunction, ResolvedPredicateRef,
+};
 use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef};
 use models::schema::TIME_FIELD_NAME;
 use trace::debug;
@@ -35,7 +42,7 @@ use crate::data_source::sink::tskv::TskvRecordBatchSinkProvider;
 use crate::data_source::split::tskv::TableLayoutHandle;
 use crate::data_source::split::SplitManagerRef;
 use crate::data_source::{UpdateExecExt, WriteExecExt};
-use crate::extension::expr::expr_utils;
+use crate::extension::expr::{expr_utils, MODE_UDAF_NAME};
 use crate::extension::physical::plan_node::aggregate_filter_scan::AggregateFilterTskvExec;
 use crate::extension::physical::plan_node::table_writer::TableWriterExec;
 use crate::extension::physical::plan_node::tag_scan::TagScanExec;
@@ -46,8 +53,10 @@ use crate::extension::physical::plan_node::update_tag::UpdateTagExec;
 pub struct ClusterTable {
     coord: CoordinatorRef,
     split_manager: SplitManagerRef,
-    _meta: MetaClientRef,
+    meta: MetaClientRef,
     schema: TskvTableSchemaRef,
+    /// Ciphers of the encrypted columns, only set if the values are to be decrypted.
+    decryptors: Arc<HashMap<String, ColumnCipher>>,
 }
 
 impl ClusterTable {
@@ -73,13 +82,16 @@ impl ClusterTable {
             return Ok(Arc::new(EmptyExec::new(false, proj_schema)));
         }
 
-        Ok(Arc::new(TskvExec::new(
-            self.schema.clone(),
-            proj_schema,
-            predicate,
-            self.coord.clone(),
-            splits,
-        )))
+        Ok(Arc::new(
+            TskvExec::new(
+                self.schema.clone(),
+                proj_schema,
+                predicate,
+                self.coord.clone(),
+                splits,
+            )
+            .with_decryptors(self.decryptors.clone()),
+        ))
     }
 
     async fn create_agg_filter_scan(
@@ -106,25 +118,20 @@ impl ClusterTable {
             .await
             .map_err(|err| DataFusionError::External(Box::new(err)))?;
 
-        // Parsing Aggregate Functions
-        let aggs = agg_expr
+        let pushed_aggs = agg_expr
             .iter()
-            .map(|e| match e {
-                Expr::AggregateFunction(agg) => Ok(agg),
-                _ => Err(DataFusionError::Plan(
-                    "Invalid plan, pushed aggregate functions contains unsupported".to_string(),
-                )),
+            .map(|e| {
+                self.pushed_aggregate(e).ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "Invalid plan, pushed aggregate functions contains unsupported: {e}"
+                    ))
+                })
             })
             .collect::<Result<Vec<_>>>()?;
 
-        // Check if all aggregate functions are Count
-        let all_are_count = aggs
-            .iter()
-            .any(|agg| matches!(agg.fun, aggregate_function::AggregateFunction::Count));
-
         // Handling the empty shard
         if splits.is_empty() {
-            if all_are_count {
+            if matches!(pushed_aggs.as_slice(), [PushedAggregateFunction::Count(_)]) {
                 // If there are no shards, return a batch containing 0
                 let field = Field::new("COUNT(Int64(1))", DataType::Int64, true);
                 let schema = Arc::new(Schema::new(vec![field]));
@@ -141,61 +148,6 @@ impl ClusterTable {
             }
         }
 
-        let time_col = unsafe {
-            // use first column(time column)
-            Column::from_name(&self.schema.column_by_index(0).unwrap_unchecked().name)
-        };
-
-        let aggs = agg_expr
-            .iter()
-            .map(|e| match e {
-                Expr::AggregateFunction(agg) => Ok(agg),
-                _ => Err(DataFusionError::Plan(
-                    "Invalid plan, pushed aggregate functions contains unsupported".to_string(),
-                )),
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        let pushed_aggs = aggs
-        .into_iter()
-        .map(|agg| {
-            let AggregateFunction { fun, args, .. } = agg;
-
-            args.iter()
-                .map(|expr| {
-                    // The parameter of the aggregate function pushed down must be a column column
-                    match expr {
-                        Expr::Column(c) => Ok(c),
-                        Expr::Literal(_) => Ok(&time_col),
-                        _ => Err(DataFusionError::Internal(format!(
-                            "Pushed aggregate functions's args contains non-column or non-literal value: {expr:?}."
-                        ))),
-                    }
-                })
-                .collect::<Result<Vec<_>>>()
-                .and_then(|columns| {
-                    // Convert pushdown aggregate functions to intermediate structures
-                    match fun {
-                        aggregate_function::AggregateFunction::Count => {
-                            let column = columns
-                                .first()
-                                .ok_or_else(|| {
-                                    DataFusionError::Internal(
-                                        "Pushed aggregate functions's args is none.".to_string(),
-                                    )
-                                })?;
-                            Ok(PushedAggregateFunction::Count(column.name.to_owned()))
-                        }
-                        // aggregate_function::AggregateFunction::Max => {},
-                        // aggregate_function::AggregateFunction::Min => {},
-                        _ => Err(DataFusionError::Internal(
-                            "Pushed aggregate functions's args is none.".to_string(),
-                        )),
-                    }
-                })
-        })
-        .collect::<Result<Vec<_>>>()?;
-
         Ok(Arc::new(AggregateFilterTskvExec::new(
             self.coord.clone(),
             proj_schema,
@@ -206,6 +158,73 @@ impl ClusterTable {
         )))
     }
 
+    /// The aggregate computed by the vnodes for `expr`, None if it can't be pushed down.
+    ///
+    /// The aggregates other than count read the values of a field column, which
+    /// must not be encrypted, min and max may also read the time column.
+    fn pushed_aggregate(&self, expr: &Expr) -> Option<PushedAggregateFunction> {
+        let field_column = |args: &[Expr]| match args {
+            [Expr::Column(c)] => self
+                .schema
+                .column(&c.name)
+                .filter(|col| col.column_type.is_field() && !self.is_encrypted(c))
+                .map(|col| col.name.clone()),
+            _ => None,
+        };
+        let time_or_field_column = |args: &[Expr]| match args {
+            [Expr::Column(c)] if self.schema.time_column().name == c.name => Some(c.name.clone()),
+            _ => field_column(args),
+        };
+
+        match expr {
+            Expr::AggregateFunction(AggregateFunction {
+                fun,
+                args,
+                distinct,
+                filter: None,
+                order_by: None,
+                can_be_pushed_down: true,
+            }) => match (fun, distinct) {
+                (aggregate_function::AggregateFunction::Count, false) => match args.as_slice() {
+                    [Expr::Column(c)] => self
+                        .schema
+                        .column(&c.name)
+                        .filter(|col| !col.column_type.is_tag())
+                        .map(|col| PushedAggregateFunction::Count(col.name.clone())),
+                    // Count the rows by the time column.
+                    [Expr::Literal(v)] if !v.is_null() => Some(PushedAggregateFunction::Count(
+                        self.schema.time_column().name,
+                    )),
+                    _ => None,
+                },
+                (aggregate_function::AggregateFunction::Count, true) => {
+                    field_column(args).map(PushedAggregateFunction::CountDistinct)
+                }
+                (aggregate_function::AggregateFunction::BoolAnd, false) => {
+                    field_column(args).map(PushedAggregateFunction::BoolAnd)
+                }
+                (aggregate_function::AggregateFunction::BoolOr, false) => {
+                    field_column(args).map(PushedAggregateFunction::BoolOr)
+                }
+                (aggregate_function::AggregateFunction::Min, _) => {
+                    time_or_field_column(args).map(PushedAggregateFunction::Min)
+                }
+                (aggregate_function::AggregateFunction::Max, _) => {
+                    time_or_field_column(args).map(PushedAggregateFunction::Max)
+                }
+                _ => None,
+            },
+            Expr::AggregateUDF(udf)
+                if udf.fun.name == MODE_UDAF_NAME
+                    && udf.filter.is_none()
+                    && udf.order_by.is_none() =>
+            {
+                field_column(&udf.args).map(PushedAggregateFunction::Mode)
+            }
+            _ => None,
+        }
+    }
+
     pub async fn create_tag_scan_physical_plan(
         &self,
         ctx: &SessionState,
@@ -266,15 +285,154 @@ impl ClusterTable {
         ClusterTable {
             coord,
             split_manager,
-            _meta: meta,
+            meta,
             schema,
+            decryptors: Default::default(),
+        }
+    }
+
+    /// Decrypt the values of the encrypted columns when scanning the table, with the
+    /// keys in the key store of the tenant.
+    pub fn with_decryption(mut self) -> Result<Self> {
+        let mut decryptors = HashMap::new();
+        for column in self.schema.columns() {
+            let Some(key_name) = &column.encryption_key else {
+                continue;
+            };
+            let key = self
+                .meta
+                .tenant()
+                .options()
+                .encryption_key(key_name)
+                .ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "Encryption key {} of column {} not found",
+                        key_name, column.name
+                    ))
+                })?;
+            let cipher =
+                ColumnCipher::from_hex(key).map_err(|e| DataFusionError::External(Box::new(e)))?;
+            decryptors.insert(column.name.clone(), cipher);
         }
+        self.decryptors = Arc::new(decryptors);
+        Ok(self)
     }
 
     pub fn table_schema(&self) -> TskvTableSchemaRef {
         self.schema.clone()
     }
 
+    /// Resolve the filters pushed down to the scan of this table, as the predicate
+    /// used to prune vnodes and series.
+    pub fn resolve_predicate(
+        &self,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<ResolvedPredicateRef> {
+        let df_schema = self.schema.to_df_schema()?;
+        let arrow_schema = self.schema.to_arrow_schema();
+        let filters = self.encrypt_filters(filters)?;
+        let filter = rewrite_filters(&filters, df_schema.clone())?;
+        Predicate::push_down_filter(filter, &df_schema, &arrow_schema, limit)
+            .and_then(|predicate| predicate.resolve(&self.schema))
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+
+    /// Filters on encrypted columns are evaluated by tskv on the encrypted values, so
+    /// only equality comparisons with literals are supported, and the literals are
+    /// encrypted if the values are decrypted by the scan.
+    fn encrypt_filters(&self, filters: &[Expr]) -> Result<Vec<Expr>> {
+        if !self
+            .schema
+            .columns()
+            .iter()
+            .any(|c| c.encryption_key.is_some())
+        {
+            return Ok(filters.to_vec());
+        }
+        filters
+            .iter()
+            .map(|e| self.encrypt_filter(e.clone()))
+            .collect()
+    }
+
+    fn encrypt_filter(&self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op, right })
+                if matches!(op, Operator::Eq | Operator::NotEq) =>
+            {
+                match (*left, *right) {
+                    (Expr::Column(c), Expr::Literal(v)) if self.is_encrypted(&c) => {
+                        let v = self.encrypt_literal(&c, v)?;
+                        Ok(binary_expr(Expr::Column(c), op, Expr::Literal(v)))
+                    }
+                    (Expr::Literal(v), Expr::Column(c)) if self.is_encrypted(&c) => {
+                        let v = self.encrypt_literal(&c, v)?;
+                        Ok(binary_expr(Expr::Literal(v), op, Expr::Column(c)))
+                    }
+                    (left, right) => Ok(binary_expr(
+                        self.encrypt_filter(left)?,
+                        op,
+                        self.encrypt_filter(right)?,
+                    )),
+                }
+            }
+            Expr::InList(InList {
+                expr,
+                list,
+                negated,
+            }) => match *expr {
+                Expr::Column(c) if self.is_encrypted(&c) => {
+                    let list = list
+                        .into_iter()
+                        .map(|e| match e {
+                            Expr::Literal(v) => self.encrypt_literal(&c, v).map(Expr::Literal),
+                            _ => Err(self.unsupported_filter(&c)),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(Expr::Column(c).in_list(list, negated))
+                }
+                expr => Ok(Expr::InList(InList {
+                    expr: Box::new(self.encrypt_filter(expr)?),
+                    list: list
+                        .into_iter()
+                        .map(|e| self.encrypt_filter(e))
+                        .collect::<Result<Vec<_>>>()?,
+                    negated,
+                })),
+            },
+            Expr::IsNull(ref e) | Expr::IsNotNull(ref e) if matches!(e.as_ref(), Expr::Column(c) if self.is_encrypted(c)) => {
+                Ok(expr)
+            }
+            Expr::Column(c) if self.is_encrypted(&c) => Err(self.unsupported_filter(&c)),
+            expr => expr.map_children(|e| self.encrypt_filter(e)),
+        }
+    }
+
+    fn is_encrypted(&self, column: &Column) -> bool {
+        self.schema
+            .column(&column.name)
+            .is_some_and(|c| c.encryption_key.is_some())
+    }
+
+    fn encrypt_literal(&self, column: &Column, value: ScalarValue) -> Result<ScalarValue> {
+        match (self.decryptors.get(&column.name), value) {
+            (Some(cipher), ScalarValue::Utf8(Some(v))) => cipher
+                .encrypt(v.as_bytes())
+                .map(|v| ScalarValue::Utf8(Some(v)))
+                .map_err(|e| DataFusionError::External(Box::new(e))),
+            (_, v @ ScalarValue::Utf8(_)) => Ok(v),
+            _ => Err(self.unsupported_filter(column)),
+        }
+    }
+
+    fn unsupported_filter(&self, column: &Column) -> DataFusionError {
+        DataFusionError::Plan(format!(
+            "Only equality comparisons with string literals are supported on encrypted column {}",
+            column.name
+        ))
+    }
+
     // Check and return the projected schema
     fn project_schema(&self, projection: Option<&Vec<usize>>) -> Result<SchemaRef> {
         valid_project(&self.schema, projection)
@@ -329,7 +487,8 @@ impl TableProvider for ClusterTable {
             (df_schema, arrow_schema)
         };
 
-        let filters = rewrite_filters(filters, df_schema.clone())?;
+        let filters = self.encrypt_filters(filters)?;
+        let filters = rewrite_filters(&filters, df_schema.clone())?;
         // Generate physical expressions using projected schema
         let filter = Arc::new(
             Predicate::push_down_filter(filters, &df_schema, &arrow_schema, limit)
@@ -378,45 +537,9 @@ impl TableProvider for ClusterTable {
         if !group_expr.is_empty() {
             return Ok(TableProviderAggregationPushDown::Unsupported);
         }
-        if aggr_expr.len() == 1 {
-            if let Expr::AggregateFunction(AggregateFunction {
-                fun,
-                args,
-                distinct,
-                filter,
-                order_by,
-                can_be_pushed_down,
-            }) = &aggr_expr[0]
-            {
-                if matches!(fun, aggregate_function::AggregateFunction::Count)
-                    && args.len() == 1
-                    && !*distinct
-                    && filter.is_none()
-                    && order_by.is_none()
-                    && *can_be_pushed_down
-                {
-                    match &args[0] {
-                        Expr::Column(expr) => {
-                            if let Some(col) = self.schema.column(&expr.name) {
-                                if col.column_type.is_tag() {
-                                    return Ok(TableProviderAggregationPushDown::Unsupported);
-                                } else {
-                                    return Ok(TableProviderAggregationPushDown::Ungrouped);
-                                }
-                            }
-                        }
-                        Expr::Literal(expr) => {
-                            if expr.is_null() {
-                                return Ok(TableProviderAggregationPushDown::Unsupported);
-                            } else {
-                                return Ok(TableProviderAggregationPushDown::Ungrouped);
-                            }
-                        }
-                        _ => {
-                            return Ok(TableProviderAggregationPushDown::Unsupported);
-                        }
-                    }
-                }
+        if let [expr] = aggr_expr {
+            if self.pushed_aggregate(expr).is_some() {
+                return Ok(TableProviderAggregationPushDown::Ungrouped);
             }
         }
 
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -10,6 +11,8 @@ use datafusion::physical_expr::PhysicalExpr;
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
 use datafusion::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
 use futures::StreamExt;
+use models::meta_data::ReplicationSetId;
+use models::predicate::domain::TimeRange;
 use spi::{QueryError, QueryResult};
 
 use self::table_source::TableSourceAdapter;
@@ -46,17 +49,26 @@ pub trait UpdateExecExt: Send + Sync {
 pub trait RecordBatchSink: Send + Sync {
     async fn append(&self, record_batch: RecordBatch) -> QueryResult<SinkMetadata>;
 
-    async fn stream_write(&self, stream: SendableRecordBatchStream) -> QueryResult<SinkMetadata> {
-        let mut meta = SinkMetadata::default();
+    /// Write the stream, the rows written are recorded into `written` as they
+    /// are written, so they are known if the write fails or is cancelled.
+    async fn stream_write(
+        &self,
+        stream: SendableRecordBatchStream,
+        written: &mut SinkMetadata,
+    ) -> QueryResult<()> {
         let mut stream = DropEmptyRecordBatchStream::new(stream);
 
         while let Some(batch) = stream.next().await {
             let batch: RecordBatch = batch?;
-            meta.merge(self.append(batch).await?);
+            written.merge(self.append(batch).await?);
         }
 
-        Ok(meta)
+        Ok(())
     }
+
+    /// Clean up the rows recorded into `written` by a write which failed or
+    /// was cancelled, nothing is cleaned up by default.
+    fn cleanup(&self, _written: &SinkMetadata) {}
 }
 
 pub trait RecordBatchSinkProvider: Send + Sync {
@@ -73,6 +85,10 @@ pub trait RecordBatchSinkProvider: Send + Sync {
 pub struct SinkMetadata {
     rows_writed: usize,
     bytes_writed: usize,
+    /// Number of rows written to each replication set, only recorded by the tskv sink.
+    replica_rows: BTreeMap<ReplicationSetId, usize>,
+    /// Time range of the rows written, only recorded by the tskv sink.
+    time_range: Option<TimeRange>,
 }
 
 impl SinkMetadata {
@@ -80,12 +96,41 @@ impl SinkMetadata {
         Self {
             rows_writed,
             bytes_writed,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_replica_rows(
+        mut self,
+        replica_rows: impl IntoIterator<Item = (ReplicationSetId, usize)>,
+        time_range: TimeRange,
+    ) -> Self {
+        for (replica_id, rows) in replica_rows {
+            *self.replica_rows.entry(replica_id).or_default() += rows;
         }
+        self.merge_time_range(time_range);
+        self
     }
 
     pub fn merge(&mut self, other: Self) {
         self.rows_writed += other.rows_writed;
         self.bytes_writed += other.bytes_writed;
+        for (replica_id, rows) in other.replica_rows {
+            *self.replica_rows.entry(replica_id).or_default() += rows;
+        }
+        if let Some(time_range) = other.time_range {
+            self.merge_time_range(time_range);
+        }
+    }
+
+    fn merge_time_range(&mut self, time_range: TimeRange) {
+        if time_range.is_none() {
+            return;
+        }
+        match self.time_range.as_mut() {
+            Some(t) => t.merge(&time_range),
+            None => self.time_range = Some(time_range),
+        }
     }
 
     pub fn record_rows_writed(&mut self, rows_writed: usize) {
@@ -103,6 +148,14 @@ impl SinkMetadata {
     pub fn bytes_writed(&self) -> usize {
         self.bytes_writed
     }
+
+    pub fn replica_rows(&self) -> &BTreeMap<ReplicationSetId, usize> {
+        &self.replica_rows
+    }
+
+    pub fn time_range(&self) -> Option<&TimeRange> {
+        self.time_range.as_ref()
+    }
 }
 
 /// Attempt to downcast a TableSource to DefaultTableSource and access the
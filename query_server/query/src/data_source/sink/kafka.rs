@@ -0,0 +1,321 @@
+//! Kafka sink of continuous stream queries.
+//!
+//! With the `json` format each output row is sent as a JSON object message. With
+//! the `avro` format each record batch is sent as an Avro object container, the
+//! schema is embedded so that consumers need no schema registry. Integers and
+//! temporal values are written as `long`, timestamps in the unit of the column.
+//! Messages of a micro-batch are keyed by the batch id.
+
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{Schema as AvroSchema, Writer as AvroWriter};
+use async_trait::async_trait;
+use datafusion::arrow::array::{Array, BooleanArray, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::json::writer::record_batches_to_json_rows;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::future::try_join_all;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde_json::json;
+use snafu::ResultExt;
+use spi::query::datasource::sink::{SinkFormat, StreamSink};
+use spi::{QueryError, QueryResult, SerializeJsonSnafu};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+const AVRO_RECORD_NAME: &str = "stream_output";
+
+pub struct KafkaSink {
+    topic: String,
+    format: SinkFormat,
+    producer: FutureProducer,
+}
+
+impl KafkaSink {
+    pub fn try_new(brokers: &str, topic: String, format: SinkFormat) -> QueryResult<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            // A message is acknowledged only after all in-sync replicas received it.
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .create()
+            .map_err(|e| QueryError::StreamSink {
+                reason: format!("create kafka producer of {}: {}", brokers, e),
+            })?;
+
+        Ok(Self {
+            topic,
+            format,
+            producer,
+        })
+    }
+}
+
+impl Debug for KafkaSink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSink")
+            .field("topic", &self.topic)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl StreamSink for KafkaSink {
+    async fn write_batches(&self, batch_id: i64, batches: &[RecordBatch]) -> QueryResult<()> {
+        let payloads = match self.format {
+            SinkFormat::Json => json_messages(batches)?,
+            SinkFormat::Avro => avro_messages(batches)?,
+        };
+        let key = batch_id.to_string();
+
+        // Wait for the acknowledgements of all messages, the offsets of the
+        // micro-batch are committed only if every message is delivered.
+        let deliveries = payloads.iter().map(|payload| {
+            self.producer.send(
+                FutureRecord::to(&self.topic).key(&key).payload(payload),
+                SEND_TIMEOUT,
+            )
+        });
+        try_join_all(deliveries)
+            .await
+            .map_err(|(e, _)| QueryError::StreamSink {
+                reason: format!("send to kafka topic {}: {}", self.topic, e),
+            })?;
+
+        Ok(())
+    }
+}
+
+fn json_messages(batches: &[RecordBatch]) -> QueryResult<Vec<Vec<u8>>> {
+    let batches = batches.iter().collect::<Vec<_>>();
+    let rows = record_batches_to_json_rows(&batches).context(SerializeJsonSnafu)?;
+
+    rows.iter()
+        .map(|row| {
+            serde_json::to_vec(row).map_err(|e| QueryError::StreamSink {
+                reason: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn avro_messages(batches: &[RecordBatch]) -> QueryResult<Vec<Vec<u8>>> {
+    batches
+        .iter()
+        .filter(|batch| batch.num_rows() > 0)
+        .map(avro_message)
+        .collect()
+}
+
+fn avro_message(batch: &RecordBatch) -> QueryResult<Vec<u8>> {
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|c| AvroColumn::try_new(c.as_ref()))
+        .collect::<QueryResult<Vec<_>>>()?;
+
+    let schema = batch.schema();
+    let fields = schema
+        .fields()
+        .iter()
+        .zip(columns.iter())
+        .map(|(f, c)| json!({"name": f.name(), "type": ["null", c.type_name()]}))
+        .collect::<Vec<_>>();
+    let avro_schema = AvroSchema::parse(&json!({
+        "type": "record",
+        "name": AVRO_RECORD_NAME,
+        "fields": fields,
+    }))
+    .map_err(avro_error)?;
+
+    let mut writer = AvroWriter::new(&avro_schema, Vec::new());
+    for row in 0..batch.num_rows() {
+        let record = schema
+            .fields()
+            .iter()
+            .zip(columns.iter())
+            .map(|(f, c)| (f.name().clone(), c.value(row)))
+            .collect();
+        writer
+            .append(AvroValue::Record(record))
+            .map_err(avro_error)?;
+    }
+
+    writer.into_inner().map_err(avro_error)
+}
+
+fn avro_error(e: apache_avro::Error) -> QueryError {
+    QueryError::StreamSink {
+        reason: format!("serialize avro: {}", e),
+    }
+}
+
+/// A column cast to one of the Avro primitive types.
+enum AvroColumn {
+    Boolean(BooleanArray),
+    Long(Int64Array),
+    Double(Float64Array),
+    String(StringArray),
+}
+
+impl AvroColumn {
+    fn try_new(array: &dyn Array) -> QueryResult<Self> {
+        let column = match array.data_type() {
+            DataType::Boolean => Self::Boolean(downcast_cast(array, &DataType::Boolean)?),
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Timestamp(_, _)
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Time32(_)
+            | DataType::Time64(_)
+            | DataType::Duration(_) => Self::Long(downcast_cast(array, &DataType::Int64)?),
+            DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal128(_, _)
+            | DataType::Decimal256(_, _) => Self::Double(downcast_cast(array, &DataType::Float64)?),
+            _ => Self::String(downcast_cast(array, &DataType::Utf8)?),
+        };
+
+        Ok(column)
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Boolean(_) => "boolean",
+            Self::Long(_) => "long",
+            Self::Double(_) => "double",
+            Self::String(_) => "string",
+        }
+    }
+
+    /// The value of the nullable union `["null", type]`.
+    fn value(&self, row: usize) -> AvroValue {
+        let value = match self {
+            Self::Boolean(a) if a.is_valid(row) => AvroValue::Boolean(a.value(row)),
+            Self::Long(a) if a.is_valid(row) => AvroValue::Long(a.value(row)),
+            Self::Double(a) if a.is_valid(row) => AvroValue::Double(a.value(row)),
+            Self::String(a) if a.is_valid(row) => AvroValue::String(a.value(row).to_string()),
+            _ => return AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+
+        AvroValue::Union(1, Box::new(value))
+    }
+}
+
+fn downcast_cast<T: Array + Clone + 'static>(
+    array: &dyn Array,
+    data_type: &DataType,
+) -> QueryResult<T> {
+    let array = cast(array, data_type).map_err(|e| QueryError::StreamSink {
+        reason: format!("cast {} to {}: {}", array.data_type(), data_type, e),
+    })?;
+
+    Ok(array
+        .as_any()
+        .downcast_ref::<T>()
+        .expect("downcast the cast array")
+        .clone())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use apache_avro::types::Value as AvroValue;
+    use apache_avro::Reader;
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+
+    use super::{avro_messages, json_messages};
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("usage", DataType::Float64, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+                Arc::new(Float64Array::from(vec![Some(0.5), None])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_json_messages() {
+        let messages = json_messages(&[batch()]).unwrap();
+        assert_eq!(messages.len(), 2);
+
+        let first: serde_json::Value = serde_json::from_slice(&messages[0]).unwrap();
+        assert_eq!(first["host"], "a");
+        assert_eq!(first["usage"], 0.5);
+        let second: serde_json::Value = serde_json::from_slice(&messages[1]).unwrap();
+        assert!(second.get("host").is_none());
+    }
+
+    #[test]
+    fn test_avro_messages() {
+        let empty = batch().slice(0, 0);
+        let messages = avro_messages(&[batch(), empty]).unwrap();
+        assert_eq!(messages.len(), 1);
+
+        let records = Reader::new(messages[0].as_slice())
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            vec![
+                AvroValue::Record(vec![
+                    (
+                        "time".to_string(),
+                        AvroValue::Union(1, Box::new(AvroValue::Long(1)))
+                    ),
+                    (
+                        "host".to_string(),
+                        AvroValue::Union(1, Box::new(AvroValue::String("a".to_string())))
+                    ),
+                    (
+                        "usage".to_string(),
+                        AvroValue::Union(1, Box::new(AvroValue::Double(0.5)))
+                    ),
+                ]),
+                AvroValue::Record(vec![
+                    (
+                        "time".to_string(),
+                        AvroValue::Union(1, Box::new(AvroValue::Long(2)))
+                    ),
+                    (
+                        "host".to_string(),
+                        AvroValue::Union(0, Box::new(AvroValue::Null))
+                    ),
+                    (
+                        "usage".to_string(),
+                        AvroValue::Union(0, Box::new(AvroValue::Null))
+                    ),
+                ]),
+            ]
+        );
+    }
+}
@@ -3,9 +3,12 @@ use bytes::Bytes;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::physical_plan::SendableRecordBatchStream;
+use spi::query::datasource::sink::{StreamSinkOptions, StreamSinkRef};
 use spi::query::datasource::WriteContext;
 use spi::QueryResult;
 
+#[cfg(feature = "kafka")]
+pub mod kafka;
 pub mod obj_store;
 pub mod tskv;
 
@@ -31,3 +34,22 @@ pub trait RecordBatchSerializer {
         batches: &[RecordBatch],
     ) -> QueryResult<(usize, Bytes)>;
 }
+
+pub fn create_stream_sink(options: &StreamSinkOptions) -> QueryResult<StreamSinkRef> {
+    match options {
+        #[cfg(feature = "kafka")]
+        StreamSinkOptions::Kafka {
+            brokers,
+            topic,
+            format,
+        } => Ok(std::sync::Arc::new(kafka::KafkaSink::try_new(
+            brokers,
+            topic.clone(),
+            *format,
+        )?)),
+        #[cfg(not(feature = "kafka"))]
+        StreamSinkOptions::Kafka { .. } => Err(spi::QueryError::NotImplemented {
+            err: "Kafka sink, cnosdb is built without the kafka feature".to_string(),
+        }),
+    }
+}
@@ -34,7 +34,11 @@ impl RecordBatchSink for ObjectStoreSink {
         })
     }
 
-    async fn stream_write(&self, stream: SendableRecordBatchStream) -> QueryResult<SinkMetadata> {
+    async fn stream_write(
+        &self,
+        stream: SendableRecordBatchStream,
+        written: &mut SinkMetadata,
+    ) -> QueryResult<()> {
         debug!("Process ObjectStoreRecordBatchSink::stream_write");
 
         pin_mut!(stream);
@@ -46,7 +50,9 @@ impl RecordBatchSink for ObjectStoreSink {
             writer.write(batch).await?;
         }
 
-        writer.close().await
+        written.merge(writer.close().await?);
+
+        Ok(())
     }
 }
 
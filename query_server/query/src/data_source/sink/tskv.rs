@@ -1,31 +1,46 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use coordinator::errors::CoordinatorError;
+use coordinator::resource_manager::ResourceManager;
 use coordinator::service::CoordinatorRef;
+use coordinator::RecordBatchWrite;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::metrics::{self, Count, ExecutionPlanMetricsSet, MetricBuilder};
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::StreamExt;
+use models::predicate::domain::TimeRange;
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use snafu::ResultExt;
-use spi::{CoordinatorSnafu, MetaSnafu, QueryResult};
+use spi::query::session::SqlExecInfo;
+use spi::{CoordinatorSnafu, MetaSnafu, QueryError, QueryResult};
 use trace::span_ext::SpanExt;
-use trace::{Span, SpanContext};
+use trace::{info, warn, Span, SpanContext};
 
 use crate::data_source::{RecordBatchSink, RecordBatchSinkProvider, SinkMetadata};
+use crate::extension::DropEmptyRecordBatchStream;
 
 pub struct TskvRecordBatchSink {
     coord: CoordinatorRef,
     partition: usize,
     schema: TskvTableSchemaRef,
+    /// Delete the written rows if the write fails or is cancelled.
+    cleanup_partial_write: bool,
 
     metrics: TskvSinkMetrics,
     span: Span,
 }
 
-#[async_trait]
-impl RecordBatchSink for TskvRecordBatchSink {
-    async fn append(&self, record_batch: RecordBatch) -> QueryResult<SinkMetadata> {
+impl TskvRecordBatchSink {
+    /// Write the record batch, the rows written are recorded into `written`
+    /// even if the write fails after some replication sets are written.
+    async fn write(
+        &self,
+        record_batch: RecordBatch,
+        written: &mut SinkMetadata,
+    ) -> QueryResult<()> {
         trace::trace!(
             "Partition: {}, \nTskvTableSchema: {:?}, \nTskvRecordBatchSink::append: {:?}",
             self.partition,
@@ -69,7 +84,7 @@ impl RecordBatchSink for TskvRecordBatchSink {
         }
 
         let db_precision = db_schema.config.precision();
-        let write_bytes = self
+        let write = self
             .coord
             .write_record_batch(
                 self.schema.clone(),
@@ -78,15 +93,25 @@ impl RecordBatchSink for TskvRecordBatchSink {
                 span.context().as_ref(),
             )
             .await
-            .map(|write_bytes| {
+            .map(|write| {
                 span.add_property(|| ("output_rows", rows_writed.to_string()));
-                write_bytes
+                write
             })
             .map_err(|err| {
                 span.error(err.to_string());
                 err
-            })
-            .context(CoordinatorSnafu)?;
+            });
+        let write = match write {
+            Ok(write) => write,
+            Err(CoordinatorError::PartialWrite {
+                written: partial,
+                source,
+            }) => {
+                written.merge(write_metadata(partial));
+                return Err(QueryError::Coordinator { source: *source });
+            }
+            Err(err) => return Err(err).context(CoordinatorSnafu),
+        };
         self.coord
             .metrics()
             .sql_data_in(self.schema.tenant.as_str(), self.schema.db.as_str())
@@ -97,7 +122,94 @@ impl RecordBatchSink for TskvRecordBatchSink {
         // Record the number of `RecordBatch` that has been written
         self.metrics.record_output_batches(1);
 
-        Ok(SinkMetadata::new(rows_writed, write_bytes))
+        written.merge(write_metadata(write));
+        Ok(())
+    }
+}
+
+fn write_metadata(write: RecordBatchWrite) -> SinkMetadata {
+    SinkMetadata::new(write.rows(), write.write_bytes)
+        .with_replica_rows(write.replica_rows, write.time_range)
+}
+
+/// Register the task deleting the rows of the table within the time range,
+/// the task is retried by the resource manager until it succeeds.
+async fn register_cleanup(
+    coord: CoordinatorRef,
+    schema: TskvTableSchemaRef,
+    time_range: TimeRange,
+) -> QueryResult<()> {
+    let tenant = coord
+        .tenant_meta(&schema.tenant)
+        .await
+        .ok_or_else(|| CoordinatorError::TenantNotFound {
+            name: schema.tenant.clone(),
+        })
+        .context(CoordinatorSnafu)?;
+    let resourceinfo = ResourceManager::delete_written_rows_task(
+        *tenant.tenant().id(),
+        &schema,
+        time_range,
+        coord.node_id(),
+    );
+    ResourceManager::add_resource_task(coord, resourceinfo)
+        .await
+        .context(CoordinatorSnafu)?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl RecordBatchSink for TskvRecordBatchSink {
+    async fn append(&self, record_batch: RecordBatch) -> QueryResult<SinkMetadata> {
+        let mut written = SinkMetadata::default();
+        self.write(record_batch, &mut written).await?;
+        Ok(written)
+    }
+
+    async fn stream_write(
+        &self,
+        stream: SendableRecordBatchStream,
+        written: &mut SinkMetadata,
+    ) -> QueryResult<()> {
+        let mut stream = DropEmptyRecordBatchStream::new(stream);
+
+        while let Some(batch) = stream.next().await {
+            self.write(batch?, written).await?;
+        }
+
+        Ok(())
+    }
+
+    fn cleanup(&self, written: &SinkMetadata) {
+        if !self.cleanup_partial_write {
+            return;
+        }
+        let Some(time_range) = written.time_range().copied() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!(
+                "No runtime to delete the rows written to table {} within {:?}",
+                self.schema.name, time_range
+            );
+            return;
+        };
+
+        let coord = self.coord.clone();
+        let schema = self.schema.clone();
+        runtime.spawn(async move {
+            info!(
+                "Delete the rows written to table {} within {:?}",
+                schema.name, time_range
+            );
+            if let Err(e) = register_cleanup(coord, schema.clone(), time_range).await {
+                warn!(
+                    "Failed to delete the rows written to table {} within {:?}: {}",
+                    schema.name, time_range, e
+                );
+            }
+        });
     }
 }
 
@@ -128,11 +240,18 @@ impl RecordBatchSinkProvider for TskvRecordBatchSinkProvider {
             format!("TskvRecordBatchSink ({partition})"),
             parent_span_ctx.as_deref(),
         );
+        let cleanup_partial_write = context
+            .session_config()
+            .options()
+            .extensions
+            .get::<SqlExecInfo>()
+            .is_some_and(|e| e.cleanup_partial_write);
 
         Box::new(TskvRecordBatchSink {
             coord: self.coord.clone(),
             partition,
             schema: self.schema.clone(),
+            cleanup_partial_write,
             metrics: TskvSinkMetrics::new(metrics, partition),
             span,
         })
@@ -1,13 +1,22 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use coordinator::errors::CoordinatorError;
 use coordinator::service::CoordinatorRef;
 use datafusion::execution::context::SessionState;
+use datafusion::prelude::{col, lit};
+use datafusion::scalar::ScalarValue;
 use datafusion::sql::TableReference;
 use models::object_reference::Resolve;
+use models::predicate::domain::Predicate;
 use models::predicate::PlacedSplit;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use models::utils::{now_timestamp_micros, now_timestamp_millis, now_timestamp_nanos};
 use snafu::ResultExt;
-use spi::{AnalyzePushedFilterSnafu, CoordinatorSnafu, QueryResult};
+use spi::query::session::SqlExecInfo;
+use spi::{AnalyzePushedFilterSnafu, CoordinatorSnafu, MetaSnafu, QueryResult};
 use trace::debug;
+use utils::precision::Precision;
 
 use self::tskv::TableLayoutHandle;
 
@@ -33,7 +42,7 @@ impl SplitManager {
 
     pub async fn splits(
         &self,
-        _ctx: &SessionState,
+        ctx: &SessionState,
         table_layout: TableLayoutHandle,
     ) -> QueryResult<Vec<PlacedSplit>> {
         let TableLayoutHandle {
@@ -49,15 +58,43 @@ impl SplitManager {
 
         let limit = predicate.limit();
 
+        let predicate = match self.retention_predicate(ctx, &table).await? {
+            Some(retention) => Arc::new(predicate.and(&retention)),
+            None => predicate,
+        };
+
         let resolved_predicate = predicate
             .resolve(&table)
             .context(AnalyzePushedFilterSnafu)?;
 
-        let shards = self
-            .coord
-            .table_vnodes(&table_name, resolved_predicate.clone())
-            .await
-            .context(CoordinatorSnafu)?;
+        let exec_info = ctx.config().options().extensions.get::<SqlExecInfo>();
+        let follower_read = exec_info.is_some_and(|e| e.follower_read);
+        let max_replica_lag = exec_info
+            .and_then(|e| e.max_replica_lag_ms)
+            .map(Duration::from_millis);
+        // The follower read is consistent, it takes precedence over the bounded lag.
+        let shards = match (follower_read, max_replica_lag) {
+            (true, _) => {
+                self.coord
+                    .table_vnodes_follower_read(&table_name, resolved_predicate.clone())
+                    .await
+            }
+            (false, Some(max_replica_lag)) => {
+                self.coord
+                    .table_vnodes_within_lag(
+                        &table_name,
+                        resolved_predicate.clone(),
+                        max_replica_lag,
+                    )
+                    .await
+            }
+            (false, None) => {
+                self.coord
+                    .table_vnodes(&table_name, resolved_predicate.clone())
+                    .await
+            }
+        }
+        .context(CoordinatorSnafu)?;
 
         let splits = shards
             .into_iter()
@@ -74,4 +111,68 @@ impl SplitManager {
 
         Ok(splits)
     }
+
+    /// The predicate that hides the rows older than the TTL of the database, which
+    /// may not be deleted yet. None if the TTL is infinite or the session ignores
+    /// retention.
+    ///
+    /// The aggregates pushed down are computed in the time ranges of the splits
+    /// too, only the column groups across the horizon are decoded, the others
+    /// are still aggregated by their statistics.
+    async fn retention_predicate(
+        &self,
+        ctx: &SessionState,
+        table: &TskvTableSchemaRef,
+    ) -> QueryResult<Option<Predicate>> {
+        let ignore_retention = ctx
+            .config()
+            .options()
+            .extensions
+            .get::<SqlExecInfo>()
+            .is_some_and(|e| e.ignore_retention);
+        if ignore_retention {
+            return Ok(None);
+        }
+
+        let meta = self
+            .coord
+            .tenant_meta(&table.tenant)
+            .await
+            .ok_or_else(|| CoordinatorError::TenantNotFound {
+                name: table.tenant.clone(),
+            })
+            .context(CoordinatorSnafu)?;
+        let Some(db_schema) = meta.get_db_schema(&table.db).context(MetaSnafu)? else {
+            return Ok(None);
+        };
+        let ttl = db_schema.options().ttl();
+        if ttl.to_duration().is_none() {
+            return Ok(None);
+        }
+
+        let horizon = match table.time_column_precision() {
+            Precision::MS => ScalarValue::TimestampMillisecond(
+                Some(now_timestamp_millis().saturating_sub(ttl.to_millisecond())),
+                None,
+            ),
+            Precision::US => ScalarValue::TimestampMicrosecond(
+                Some(now_timestamp_micros().saturating_sub(ttl.to_microseconds())),
+                None,
+            ),
+            Precision::NS => ScalarValue::TimestampNanosecond(
+                Some(now_timestamp_nanos().saturating_sub(ttl.to_nanoseconds())),
+                None,
+            ),
+        };
+        let filter = col(table.time_column().name).gt_eq(lit(horizon));
+        let predicate = Predicate::push_down_filter(
+            Some(filter),
+            &table.to_df_schema()?,
+            &table.to_arrow_schema(),
+            None,
+        )
+        .context(AnalyzePushedFilterSnafu)?;
+
+        Ok(Some(predicate))
+    }
 }
@@ -1,3 +1,4 @@
+use std::cmp;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -79,6 +80,12 @@ impl StreamProvider for TskvStreamProvider {
         Ok(Some(Self::Offset::MAX))
     }
 
+    /// The offsets are the event time and the latest available one is unbounded, so only the
+    /// data before the watermark is processed.
+    fn processed_offset(&self, end: Self::Offset, watermark_ns: i64) -> Self::Offset {
+        cmp::min(end, watermark_ns)
+    }
+
     async fn scan(
         &self,
         state: &SessionState,
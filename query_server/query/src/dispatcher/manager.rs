@@ -5,7 +5,7 @@ use std::time::Duration;
 use async_trait::async_trait;
 use coordinator::resource_manager::ResourceManager;
 use coordinator::service::CoordinatorRef;
-use memory_pool::MemoryPoolRef;
+use memory_pool::{MemoryPoolRef, QueryMemoryPool};
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::auth::auth_cache::{AuthCache, AuthCacheKey};
@@ -24,7 +24,7 @@ use spi::query::function::FuncMetaManagerRef;
 use spi::query::logical_planner::{LogicalPlanner, Plan};
 use spi::query::parser::Parser;
 use spi::query::session::{SessionCtx, SessionCtxFactory};
-use spi::service::protocol::{ContextBuilder, Query};
+use spi::service::protocol::{Context, ContextBuilder, Query};
 use spi::{MetaSnafu, QueryError, QueryResult};
 use tokio::sync::mpsc::Receiver;
 use tokio::task::JoinHandle;
@@ -34,9 +34,11 @@ use tokio_retry::Retry;
 use trace::span_ext::SpanExt;
 use trace::{error, info, Span, SpanContext};
 
-use super::query_tracker::QueryTracker;
+use super::query_tracker::{DeadlineRecordBatchStream, QueryTracker};
+use crate::audit::{AuditEvent, AuditLoggerRef};
 use crate::data_source::split::SplitManagerRef;
-use crate::execution::factory::QueryExecutionFactoryRef;
+use crate::execution::factory::{is_dml, QueryExecutionFactoryRef};
+use crate::extension::logical::utils::extract_table_columns;
 use crate::metadata::{
     BaseTableProvider, ContextProviderExtension, MetadataProvider, TableHandleProviderRef,
 };
@@ -51,6 +53,8 @@ pub struct SimpleQueryDispatcher {
     session_factory: Arc<SessionCtxFactory>,
     // memory pool
     memory_pool: MemoryPoolRef,
+    // memory limit of each query, 0 means no limit
+    max_query_memory: usize,
     // query tracker
     query_tracker: Arc<QueryTracker>,
     // parser
@@ -64,6 +68,7 @@ pub struct SimpleQueryDispatcher {
     async_task_joinhandle: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
     failed_task_joinhandle: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
     auth_cache: Arc<AuthCache<AuthCacheKey, User>>,
+    audit_logger: AuditLoggerRef,
 }
 
 #[async_trait]
@@ -118,35 +123,13 @@ impl QueryDispatcher for SimpleQueryDispatcher {
         &self,
         query_state_machine: Arc<QueryStateMachine>,
     ) -> QueryResult<Option<Plan>> {
-        let session = &query_state_machine.session;
-        let query = &query_state_machine.query;
-
-        let scheme_provider = self.build_scheme_provider(session).await?;
-
-        let logical_planner = DefaultLogicalPlanner::new(&scheme_provider);
-
-        let span_recorder = session.get_child_span("parse sql");
-        let statements = self.parser.parse(query.content())?;
-
-        // not allow multi statement
-        if statements.len() > 1 {
-            return Err(QueryError::MultiStatement {
-                num: statements.len(),
-                sql: query_state_machine.query.content().to_string(),
-            });
+        let result = self.parse_and_plan(query_state_machine.clone()).await;
+        // The statements failed to plan, e.g. without privileges, are audited here,
+        // the others are audited when executed.
+        if result.is_err() {
+            self.audit(&query_state_machine, None, &result);
         }
-
-        let stmt = match statements.front() {
-            Some(stmt) => stmt.clone(),
-            None => return Ok(None),
-        };
-
-        drop(span_recorder);
-
-        let logical_plan = self
-            .statement_to_logical_plan(stmt, &logical_planner, query_state_machine)
-            .await?;
-        Ok(Some(logical_plan))
+        result
     }
 
     async fn execute_logical_plan(
@@ -166,11 +149,23 @@ impl QueryDispatcher for SimpleQueryDispatcher {
         span_ctx: Option<&SpanContext>,
         auth_cache: Arc<AuthCache<AuthCacheKey, User>>,
     ) -> QueryResult<Arc<QueryStateMachine>> {
+        let memory_pool: MemoryPoolRef = if self.max_query_memory > 0 {
+            Arc::new(QueryMemoryPool::new(
+                self.memory_pool.clone(),
+                self.max_query_memory,
+            ))
+        } else {
+            self.memory_pool.clone()
+        };
+        let context = query
+            .context()
+            .clone()
+            .with_default_time_zone(self.database_time_zone(query.context()).await);
         let session = self.session_factory.create_session_ctx(
             query_id.to_string(),
-            query.context(),
+            &context,
             tenant_id,
-            self.memory_pool.clone(),
+            memory_pool,
             span_ctx.cloned(),
             self.coord.clone(),
         )?;
@@ -207,6 +202,19 @@ impl QueryDispatcher for SimpleQueryDispatcher {
 }
 
 impl SimpleQueryDispatcher {
+    /// The time zone of the database of the query, None if the database does
+    /// not set one or is not found.
+    async fn database_time_zone(&self, context: &Context) -> Option<String> {
+        if context.time_zone().is_some() {
+            return None;
+        }
+        let meta = self.coord.tenant_meta(context.tenant()).await?;
+        meta.get_db_schema(context.database())
+            .ok()
+            .flatten()
+            .and_then(|schema| schema.options().time_zone().map(str::to_string))
+    }
+
     async fn execute_persister_query(&self, node_id: NodeId) -> QueryResult<()> {
         // 执行被持久化的任务
         let queries = self.query_tracker.persistent_queries(node_id).await?;
@@ -456,9 +464,15 @@ impl SimpleQueryDispatcher {
     }
 
     async fn exec_async_task(coord: CoordinatorRef, mut resourceinfo: ResourceInfo) {
-        let future_interval = resourceinfo.get_time() - now_timestamp_nanos();
+        let future_interval = (resourceinfo.get_time() - now_timestamp_nanos()).max(0);
         let future_time = Instant::now() + Duration::from_nanos(future_interval as u64);
         tokio::time::sleep_until(future_time).await;
+        // Cancelled while waiting, e.g. the scheduled job is dropped.
+        match ResourceManager::is_cancelled(coord.clone(), resourceinfo.get_name()).await {
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(err) => error!("failed to read the async task: {}", err),
+        }
         resourceinfo.set_status(ResourceStatus::Executing);
         resourceinfo.set_is_new_add(false);
         if let Err(meta_err) = coord
@@ -472,6 +486,46 @@ impl SimpleQueryDispatcher {
         let _ = ResourceManager::do_operator(coord.clone(), resourceinfo.clone()).await;
     }
 
+    async fn parse_and_plan(
+        &self,
+        query_state_machine: Arc<QueryStateMachine>,
+    ) -> QueryResult<Option<Plan>> {
+        let session = &query_state_machine.session;
+        let query = &query_state_machine.query;
+
+        let scheme_provider = self.build_scheme_provider(session).await?;
+
+        let logical_planner = DefaultLogicalPlanner::new(&scheme_provider);
+
+        let span_recorder = session.get_child_span("parse sql");
+        let statements = self.parser.parse(query.content())?;
+
+        // not allow multi statement
+        if statements.len() > 1 {
+            return Err(QueryError::MultiStatement {
+                num: statements.len(),
+                sql: query_state_machine.query.content().to_string(),
+            });
+        }
+
+        let stmt = match statements.front() {
+            Some(stmt) => stmt.clone(),
+            None => return Ok(None),
+        };
+
+        drop(span_recorder);
+
+        if stmt.reads_catalog() {
+            self.sync_catalog(session, query.context().consistent_meta())
+                .await?;
+        }
+
+        let logical_plan = self
+            .statement_to_logical_plan(stmt, &logical_planner, query_state_machine)
+            .await?;
+        Ok(Some(logical_plan))
+    }
+
     async fn statement_to_logical_plan<S: ContextProviderExtension + Send + Sync>(
         &self,
         stmt: ExtStatement,
@@ -497,23 +551,127 @@ impl SimpleQueryDispatcher {
         logical_plan: Plan,
         query_state_machine: Arc<QueryStateMachine>,
     ) -> QueryResult<Output> {
+        let object = self
+            .audit_logger
+            .is_enabled()
+            .then(|| audit_object(&logical_plan))
+            .flatten();
+        let result = self
+            .execute_logical_plan_inner(logical_plan, query_state_machine.clone())
+            .await;
+        self.audit(&query_state_machine, object, &result);
+        result
+    }
+
+    async fn execute_logical_plan_inner(
+        &self,
+        logical_plan: Plan,
+        query_state_machine: Arc<QueryStateMachine>,
+    ) -> QueryResult<Output> {
+        let timeout = self
+            .statement_timeout(&logical_plan, &query_state_machine.session)
+            .await;
         let execution = self
             .query_execution_factory
             .create_query_execution(logical_plan, query_state_machine.clone())
             .await?;
 
         // TrackedQuery.drop() is called implicitly when the value goes out of scope,
-        self.query_tracker
+        let query = self
+            .query_tracker
             .try_track_query(query_state_machine.query_id, execution)
-            .await?
-            .start()
-            .await
+            .await?;
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return query.start().await,
+        };
+
+        let deadline = Instant::now() + timeout;
+        match tokio::time::timeout_at(deadline, query.start()).await {
+            Ok(Ok(Output::StreamData(stream))) => Ok(Output::StreamData(Box::pin(
+                DeadlineRecordBatchStream::new(stream, deadline, timeout),
+            ))),
+            Ok(result) => result,
+            Err(_) => {
+                let _ = query.cancel();
+                let _ = self
+                    .query_tracker
+                    .expire_query(&query_state_machine.query_id);
+                Err(QueryError::StatementTimeout { timeout })
+            }
+        }
+    }
+
+    fn audit<T>(
+        &self,
+        query_state_machine: &QueryStateMachine,
+        object: Option<String>,
+        result: &QueryResult<T>,
+    ) {
+        if !self.audit_logger.is_enabled() {
+            return;
+        }
+        let query = &query_state_machine.query;
+        let event = AuditEvent::statement(query.context(), query.content())
+            .with_object(object)
+            .with_result(result);
+        self.audit_logger.log(event);
+    }
+
+    /// The timeout of the statement, configured by the user or the tenant.
+    /// The options of the user take precedence over the options of the tenant.
+    async fn statement_timeout(&self, plan: &Plan, session: &SessionCtx) -> Option<Duration> {
+        let is_write = match plan {
+            Plan::Query(query_plan) => is_dml(query_plan),
+            Plan::DML(_) => true,
+            Plan::PreviewStream(_) => false,
+            // A continuous stream query never completes.
+            Plan::DDL(_)
+            | Plan::SYSTEM(_)
+            | Plan::CreateStream(_)
+            | Plan::CreateContinuousQuery(_) => return None,
+        };
+
+        let user_options = session.user().desc().options();
+        let user_timeout = if is_write {
+            user_options.write_timeout()
+        } else {
+            user_options.query_timeout()
+        };
+        if let Some(timeout) = user_timeout {
+            return timeout.to_duration();
+        }
+
+        let tenant = self.coord.tenant_meta(session.tenant()).await?;
+        let tenant_options = tenant.tenant().options();
+        if is_write {
+            tenant_options.write_timeout()
+        } else {
+            tenant_options.query_timeout()
+        }
+    }
+
+    /// Sync the catalog from the meta leader if `consistent_meta` is set,
+    /// or by default if the schema is changed by this node since the last sync.
+    async fn sync_catalog(
+        &self,
+        session: &SessionCtx,
+        consistent_meta: Option<bool>,
+    ) -> QueryResult<()> {
+        let meta_client = self.build_current_session_meta_client(session).await?;
+        if consistent_meta.unwrap_or_else(|| meta_client.schema_changed()) {
+            meta_client
+                .sync_consistent_tenant_metadata()
+                .await
+                .context(MetaSnafu)?;
+        }
+        Ok(())
     }
 
     async fn build_scheme_provider(&self, session: &SessionCtx) -> QueryResult<MetadataProvider> {
         let meta_client = self.build_current_session_meta_client(session).await?;
         let current_session_table_provider =
-            self.build_table_handle_provider(meta_client.clone())?;
+            self.build_table_handle_provider(meta_client.clone(), session)?;
         let metadata_provider = MetadataProvider::new(
             self.coord.clone(),
             meta_client,
@@ -546,14 +704,17 @@ impl SimpleQueryDispatcher {
     fn build_table_handle_provider(
         &self,
         meta_client: MetaClientRef,
+        session: &SessionCtx,
     ) -> QueryResult<TableHandleProviderRef> {
-        let current_session_table_provider: Arc<BaseTableProvider> =
-            Arc::new(BaseTableProvider::new(
+        let current_session_table_provider: Arc<BaseTableProvider> = Arc::new(
+            BaseTableProvider::new(
                 self.coord.clone(),
                 self.split_manager.clone(),
                 meta_client,
                 self.stream_provider_manager.clone(),
-            ));
+            )
+            .with_user(session.user().clone()),
+        );
 
         Ok(current_session_table_provider)
     }
@@ -570,11 +731,13 @@ pub struct SimpleQueryDispatcherBuilder {
     query_execution_factory: Option<QueryExecutionFactoryRef>,
     query_tracker: Option<Arc<QueryTracker>>,
     memory_pool: Option<MemoryPoolRef>, // memory
+    max_query_memory: usize,
 
     func_manager: Option<FuncMetaManagerRef>,
     stream_provider_manager: Option<StreamProviderManagerRef>,
     span_ctx: Option<SpanContext>,
     auth_cache: Option<Arc<AuthCache<AuthCacheKey, User>>>,
+    audit_logger: Option<AuditLoggerRef>,
 }
 
 impl SimpleQueryDispatcherBuilder {
@@ -624,6 +787,11 @@ impl SimpleQueryDispatcherBuilder {
         self
     }
 
+    pub fn with_max_query_memory(mut self, max_query_memory: usize) -> Self {
+        self.max_query_memory = max_query_memory;
+        self
+    }
+
     pub fn with_func_manager(mut self, func_manager: FuncMetaManagerRef) -> Self {
         self.func_manager = Some(func_manager);
         self
@@ -647,6 +815,11 @@ impl SimpleQueryDispatcherBuilder {
         self
     }
 
+    pub fn with_audit_logger(mut self, audit_logger: AuditLoggerRef) -> Self {
+        self.audit_logger = Some(audit_logger);
+        self
+    }
+
     pub fn build(self) -> QueryResult<Arc<SimpleQueryDispatcher>> {
         let coord = self.coord.ok_or_else(|| QueryError::BuildQueryDispatcher {
             err: "lost of coord".to_string(),
@@ -714,12 +887,19 @@ impl SimpleQueryDispatcherBuilder {
                 err: "lost of auth_cache".to_string(),
             })?;
 
+        let audit_logger = self
+            .audit_logger
+            .ok_or_else(|| QueryError::BuildQueryDispatcher {
+                err: "lost of audit_logger".to_string(),
+            })?;
+
         let dispatcher = Arc::new(SimpleQueryDispatcher {
             coord,
             default_table_provider,
             split_manager,
             session_factory,
             memory_pool,
+            max_query_memory: self.max_query_memory,
             parser,
             query_execution_factory,
             query_tracker,
@@ -729,6 +909,7 @@ impl SimpleQueryDispatcherBuilder {
             async_task_joinhandle: Arc::new(Mutex::new(HashMap::new())),
             failed_task_joinhandle: Arc::new(Mutex::new(HashMap::new())),
             auth_cache,
+            audit_logger,
         });
 
         let meta_task_receiver = dispatcher
@@ -744,3 +925,16 @@ impl SimpleQueryDispatcherBuilder {
         Ok(dispatcher)
     }
 }
+
+/// The tables scanned by the query, e.g. `db1.t1,db2.t2`.
+fn audit_object(plan: &Plan) -> Option<String> {
+    let Plan::Query(query_plan) = plan else {
+        return None;
+    };
+    let tables = extract_table_columns(&query_plan.df_plan)
+        .ok()?
+        .into_keys()
+        .map(|(db, table)| format!("{db}.{table}"))
+        .collect::<Vec<_>>();
+    (!tables.is_empty()).then(|| tables.join(","))
+}
@@ -4,24 +4,27 @@ use std::ops::Deref;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use coordinator::service::CoordinatorRef;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
-use datafusion::common::Result as DFResult;
+use datafusion::common::{DataFusionError, Result as DFResult};
 use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
-use futures::{Stream, StreamExt};
+use futures::{Future, Stream, StreamExt};
 use models::meta_data::NodeId;
 use models::schema::query_info::{QueryId, QueryInfo};
 use models::schema::{CLUSTER_SCHEMA, DEFAULT_CATALOG};
 use models::utils::now_timestamp_nanos;
 use parking_lot::RwLock;
 use protocol_parser::Line;
+use protos::kv_service::WriteConsistency;
 use protos::FieldValue;
 use spi::query::dispatcher::QueryStatus;
 use spi::query::execution::{Output, QueryExecution, QueryExecutionRef, QueryType};
 use spi::{QueryError, QueryResult};
+use tokio::time::{Instant, Sleep};
 use trace::{debug, warn};
 use utils::precision::Precision;
 
@@ -200,6 +203,7 @@ impl QueryTracker {
                             DEFAULT_CATALOG,
                             CLUSTER_SCHEMA,
                             Precision::NS,
+                            WriteConsistency::Quorum,
                             vec![line],
                             None,
                         )
@@ -340,6 +344,49 @@ impl Drop for TrackedRecordBatchStream {
     }
 }
 
+/// Ends the record batch stream with [`QueryError::StatementTimeout`] after the deadline.
+pub struct DeadlineRecordBatchStream {
+    inner: SendableRecordBatchStream,
+    sleep: Pin<Box<Sleep>>,
+    timeout: Duration,
+    timed_out: bool,
+}
+
+impl DeadlineRecordBatchStream {
+    pub fn new(inner: SendableRecordBatchStream, deadline: Instant, timeout: Duration) -> Self {
+        Self {
+            inner,
+            sleep: Box::pin(tokio::time::sleep_until(deadline)),
+            timeout,
+            timed_out: false,
+        }
+    }
+}
+
+impl RecordBatchStream for DeadlineRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Stream for DeadlineRecordBatchStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.timed_out {
+            return Poll::Ready(None);
+        }
+        if self.sleep.as_mut().poll(cx).is_ready() {
+            self.timed_out = true;
+            let err = QueryError::StatementTimeout {
+                timeout: self.timeout,
+            };
+            return Poll::Ready(Some(Err(DataFusionError::External(Box::new(err)))));
+        }
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -348,8 +395,13 @@ mod tests {
 
     use async_trait::async_trait;
     use coordinator::service_mock::MockCoordinator;
-    use datafusion::arrow::datatypes::Schema;
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::error::DataFusionError;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
     use datafusion::physical_plan::EmptyRecordBatchStream;
+    use futures::{stream, StreamExt};
     use meta::model::meta_admin::AdminMeta;
     use models::auth::user::{User, UserDesc, UserOptions};
     use models::schema::query_info::{QueryId, QueryInfo};
@@ -357,7 +409,7 @@ mod tests {
     use spi::query::execution::{Output, QueryExecution, QueryState, RUNNING};
     use spi::QueryError;
 
-    use super::QueryTracker;
+    use super::{DeadlineRecordBatchStream, QueryTracker};
     use crate::dispatcher::persister::MetaQueryPersister;
 
     struct QueryExecutionMock {}
@@ -500,4 +552,47 @@ mod tests {
 
         assert_eq!(info_actual, info_found);
     }
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int64, false)]);
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int64Array::from(vec![1]))]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_deadline_record_batch_stream() {
+        let timeout = Duration::from_millis(50);
+        let deadline = tokio::time::Instant::now() + timeout;
+        // Yields a batch, then hangs.
+        let inner = RecordBatchStreamAdapter::new(
+            batch().schema(),
+            stream::iter(vec![Ok(batch())]).chain(stream::pending()),
+        );
+        let mut stream = DeadlineRecordBatchStream::new(Box::pin(inner), deadline, timeout);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), batch());
+        match stream.next().await {
+            Some(Err(DataFusionError::External(e))) => assert!(matches!(
+                e.downcast_ref::<QueryError>(),
+                Some(QueryError::StatementTimeout { .. })
+            )),
+            other => panic!("expect the statement timeout, got {other:?}"),
+        }
+        assert!(tokio::time::Instant::now() >= deadline);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_record_batch_stream_finished() {
+        let timeout = Duration::from_secs(60);
+        let deadline = tokio::time::Instant::now() + timeout;
+        let inner = RecordBatchStreamAdapter::new(
+            batch().schema(),
+            stream::iter(vec![Ok(batch()), Ok(batch())]),
+        );
+        let stream = DeadlineRecordBatchStream::new(Box::pin(inner), deadline, timeout);
+
+        let batches = stream.collect::<Vec<_>>().await;
+        assert_eq!(batches.len(), 2);
+        assert!(batches.iter().all(|b| b.is_ok()));
+    }
 }
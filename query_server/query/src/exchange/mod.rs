@@ -0,0 +1,496 @@
+//! Exchange of intermediate partitions of a query between nodes.
+//!
+//! The node executing a query spills the partitions of an exchange into local
+//! files under `{dir}/{exchange_id}`. Nodes of the cluster read them through
+//! the Arrow Flight service of the exchange, the requests of the service are
+//! described by [`ExchangeTicket`].
+//!
+//! Each exchange has a random token, written into `{dir}/{exchange_id}/token`
+//! and sent only within the tickets of the query. A partition is read only by
+//! the requests carrying the token of its exchange.
+
+pub mod spill;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::{IpcMessage, SchemaAsIpc, Ticket};
+use bytes::Bytes;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::execution::context::TaskContext;
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+use datafusion::logical_expr::JoinType;
+use datafusion::physical_plan::expressions::Column;
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion_proto::protobuf;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use memory_pool::{MemoryPoolRef, QueryMemoryPool};
+use models::meta_data::NodeId;
+use once_cell::sync::OnceCell;
+use protos::DEFAULT_GRPC_SERVER_MESSAGE_LEN;
+use serde::{Deserialize, Serialize};
+use tonic::transport::Channel;
+use trace::{debug, warn};
+
+use crate::extension::physical::plan_node::exchange_reader::ExchangeReaderExec;
+
+static EXCHANGE_MANAGER: OnceCell<Arc<ExchangeManager>> = OnceCell::new();
+
+const TOKEN_FILE: &str = "token";
+
+/// The input of a join that a partition belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeSide {
+    Left,
+    Right,
+}
+
+impl ExchangeSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+}
+
+/// Request of the `do_get` of the exchange service, encoded by bincode.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ExchangeTicket {
+    /// Read a partition spilled on the node.
+    Fetch {
+        exchange_id: String,
+        token: String,
+        side: ExchangeSide,
+        partition: usize,
+    },
+    /// Join a partition of both sides, which are fetched from the source node.
+    Join(RemoteJoin),
+}
+
+impl ExchangeTicket {
+    pub fn encode(&self) -> DFResult<Vec<u8>> {
+        bincode::serialize(self).map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+
+    pub fn decode(bytes: &[u8]) -> DFResult<Self> {
+        bincode::deserialize(bytes).map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+/// A partition of a hash join executed by another node.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteJoin {
+    /// The node the partitions are spilled on.
+    pub source: NodeId,
+    pub exchange_id: String,
+    /// Token of the exchange, sent back to the source node to read the inputs.
+    pub token: String,
+    pub partition: usize,
+    /// Schemas of the inputs, encoded in Arrow IPC.
+    pub left_schema: Vec<u8>,
+    pub right_schema: Vec<u8>,
+    /// Names and indices of the columns of the join keys.
+    pub on: Vec<((String, usize), (String, usize))>,
+    /// Value of `datafusion_proto::protobuf::JoinType`.
+    pub join_type: i32,
+    pub null_equals_null: bool,
+}
+
+impl RemoteJoin {
+    pub fn new(
+        source: NodeId,
+        exchange_id: String,
+        token: String,
+        partition: usize,
+        join: &HashJoinExec,
+    ) -> DFResult<Self> {
+        let on = join
+            .on()
+            .iter()
+            .map(|(l, r)| {
+                (
+                    (l.name().to_string(), l.index()),
+                    (r.name().to_string(), r.index()),
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            source,
+            exchange_id,
+            token,
+            partition,
+            left_schema: encode_schema(&join.left().schema())?,
+            right_schema: encode_schema(&join.right().schema())?,
+            on,
+            join_type: protobuf::JoinType::from(*join.join_type()) as i32,
+            null_equals_null: join.null_equals_null(),
+        })
+    }
+
+    /// Build the join of the partition, the inputs are read from the source node.
+    fn to_plan(&self) -> DFResult<HashJoinExec> {
+        let left = self.reader(ExchangeSide::Left, &self.left_schema)?;
+        let right = self.reader(ExchangeSide::Right, &self.right_schema)?;
+        let on = self
+            .on
+            .iter()
+            .map(|((l, li), (r, ri))| (Column::new(l, *li), Column::new(r, *ri)))
+            .collect();
+        let join_type: JoinType = protobuf::JoinType::from_i32(self.join_type)
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!("Unknown join type {}", self.join_type))
+            })?
+            .into();
+
+        HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &join_type,
+            PartitionMode::CollectLeft,
+            self.null_equals_null,
+        )
+    }
+
+    fn reader(&self, side: ExchangeSide, schema: &[u8]) -> DFResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(ExchangeReaderExec::new(
+            decode_schema(schema)?,
+            self.source,
+            self.exchange_id.clone(),
+            self.token.clone(),
+            side,
+            self.partition,
+        )))
+    }
+}
+
+/// Manages the exchanges of this node.
+pub struct ExchangeManager {
+    coord: CoordinatorRef,
+    dir: PathBuf,
+    memory_pool: MemoryPoolRef,
+    /// Limit of the memory of a join requested by another node, 0 if the
+    /// joins are limited by the memory pool of the node only.
+    max_query_memory: usize,
+}
+
+impl ExchangeManager {
+    pub fn new(
+        coord: CoordinatorRef,
+        dir: PathBuf,
+        memory_pool: MemoryPoolRef,
+        max_query_memory: usize,
+    ) -> Self {
+        Self {
+            coord,
+            dir,
+            memory_pool,
+            max_query_memory,
+        }
+    }
+
+    /// Use the manager for the exchanges of this process, returns false if a
+    /// manager is installed already. Files of the exchanges left by the last
+    /// run of the process are removed.
+    pub fn install(self) -> bool {
+        let dir = self.dir.clone();
+        if EXCHANGE_MANAGER.set(Arc::new(self)).is_err() {
+            return false;
+        }
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to clean exchange directory {}: {e}", dir.display());
+            }
+        }
+        true
+    }
+
+    pub fn get() -> Option<Arc<ExchangeManager>> {
+        EXCHANGE_MANAGER.get().cloned()
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.coord.node_id()
+    }
+
+    /// Create the directory of a new exchange, it is removed when the returned
+    /// value is dropped.
+    pub fn create_exchange(&self) -> DFResult<ExchangeDir> {
+        let id = format!("{:016x}", rand::random::<u64>());
+        let token = format!("{:032x}", rand::random::<u128>());
+        let path = self.dir.join(&id);
+        std::fs::create_dir_all(&path)?;
+        let exchange = ExchangeDir { id, token, path };
+        std::fs::write(exchange.path.join(TOKEN_FILE), &exchange.token)?;
+        debug!(
+            "Created exchange {} in {}",
+            exchange.id,
+            exchange.path.display()
+        );
+
+        Ok(exchange)
+    }
+
+    /// Data nodes to join the partitions of an exchange created by this node,
+    /// ordered by id. It is empty if this node is not a data node, since other
+    /// nodes can not read the partitions from it.
+    pub async fn workers(&self) -> Vec<NodeId> {
+        let mut nodes = self
+            .coord
+            .meta_manager()
+            .data_nodes()
+            .await
+            .into_iter()
+            .map(|n| n.id)
+            .collect::<Vec<_>>();
+        if !nodes.contains(&self.node_id()) {
+            return vec![];
+        }
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Check that the token is the one of the exchange created by this node.
+    pub fn check_token(&self, exchange_id: &str, token: &str) -> DFResult<()> {
+        // The id is a part of the path of the files.
+        if exchange_id.is_empty() || !exchange_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(DataFusionError::Execution(format!(
+                "Invalid exchange id '{exchange_id}'"
+            )));
+        }
+        let expected = std::fs::read(self.dir.join(exchange_id).join(TOKEN_FILE))
+            .map_err(|_| DataFusionError::Execution(format!("Exchange {exchange_id} not found")))?;
+        if !token_eq(&expected, token.as_bytes()) {
+            return Err(DataFusionError::Execution(format!(
+                "Invalid token of exchange {exchange_id}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Read a partition of the exchange spilled on this node, the token must
+    /// be the one of the exchange.
+    pub fn read_partition(
+        &self,
+        exchange_id: &str,
+        token: &str,
+        side: ExchangeSide,
+        partition: usize,
+    ) -> DFResult<BoxStream<'static, DFResult<RecordBatch>>> {
+        self.check_token(exchange_id, token)?;
+        let dir = self.dir.join(exchange_id).join(side.as_str());
+        Ok(spill::read_partition(&dir, partition))
+    }
+
+    /// Context of the joins requested by other nodes, the memory is allocated
+    /// from the memory pool of this node.
+    fn task_ctx(&self) -> DFResult<Arc<TaskContext>> {
+        let memory_pool: MemoryPoolRef = if self.max_query_memory > 0 {
+            Arc::new(QueryMemoryPool::new(
+                self.memory_pool.clone(),
+                self.max_query_memory,
+            ))
+        } else {
+            self.memory_pool.clone()
+        };
+        let runtime = RuntimeEnv::new(RuntimeConfig::new().with_memory_pool(memory_pool))?;
+
+        Ok(SessionContext::with_config_rt(SessionConfig::new(), Arc::new(runtime)).task_ctx())
+    }
+
+    /// Send the request to the exchange service of the node.
+    pub async fn do_get(
+        &self,
+        node_id: NodeId,
+        ticket: &ExchangeTicket,
+    ) -> DFResult<BoxStream<'static, DFResult<RecordBatch>>> {
+        let channel = self
+            .coord
+            .meta_manager()
+            .get_node_conn(node_id)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let mut client = FlightServiceClient::<Channel>::new(channel)
+            .max_decoding_message_size(DEFAULT_GRPC_SERVER_MESSAGE_LEN);
+        let ticket = Ticket {
+            ticket: Bytes::from(ticket.encode()?),
+        };
+        let stream = client
+            .do_get(ticket)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?
+            .into_inner()
+            .map_err(FlightError::from);
+
+        Ok(FlightRecordBatchStream::new_from_flight_data(stream)
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+            .boxed())
+    }
+
+    /// Serve a request of the exchange service.
+    pub fn execute(
+        &self,
+        ticket: ExchangeTicket,
+    ) -> DFResult<BoxStream<'static, DFResult<RecordBatch>>> {
+        match ticket {
+            ExchangeTicket::Fetch {
+                exchange_id,
+                token,
+                side,
+                partition,
+            } => self.read_partition(&exchange_id, &token, side, partition),
+            ExchangeTicket::Join(join) => {
+                debug!(
+                    "Join partition {} of exchange {} from node {}",
+                    join.partition, join.exchange_id, join.source
+                );
+                let plan = join.to_plan()?;
+                Ok(plan.execute(0, self.task_ctx()?)?.boxed())
+            }
+        }
+    }
+}
+
+/// Directory of the spilled partitions of an exchange.
+#[derive(Debug)]
+pub struct ExchangeDir {
+    id: String,
+    token: String,
+    path: PathBuf,
+}
+
+impl ExchangeDir {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn side_path(&self, side: ExchangeSide) -> PathBuf {
+        self.path.join(side.as_str())
+    }
+}
+
+impl Drop for ExchangeDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            warn!("Failed to remove exchange {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// Compare the tokens in a time independent of the matched prefix.
+fn token_eq(expected: &[u8], token: &[u8]) -> bool {
+    expected.len() == token.len()
+        && expected
+            .iter()
+            .zip(token)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+pub fn encode_schema(schema: &Schema) -> DFResult<Vec<u8>> {
+    let message: IpcMessage = SchemaAsIpc::new(schema, &IpcWriteOptions::default()).try_into()?;
+    Ok(message.0.to_vec())
+}
+
+pub fn decode_schema(bytes: &[u8]) -> DFResult<SchemaRef> {
+    let schema = Schema::try_from(IpcMessage(Bytes::copy_from_slice(bytes)))?;
+    Ok(Arc::new(schema))
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use coordinator::service_mock::MockCoordinator;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use memory_pool::GreedyMemoryPool;
+
+    use super::{decode_schema, encode_schema, ExchangeManager, ExchangeSide, ExchangeTicket};
+
+    #[tokio::test]
+    async fn test_exchange_token() {
+        let dir = std::path::PathBuf::from("/tmp/test/query/exchange/test_exchange_token");
+        let manager = ExchangeManager::new(
+            Arc::new(MockCoordinator::default()),
+            dir,
+            Arc::new(GreedyMemoryPool::default()),
+            0,
+        );
+        let exchange = manager.create_exchange().unwrap();
+
+        manager
+            .check_token(exchange.id(), exchange.token())
+            .unwrap();
+        assert!(manager
+            .read_partition(exchange.id(), exchange.token(), ExchangeSide::Left, 0)
+            .is_ok());
+        assert!(manager.check_token(exchange.id(), "").is_err());
+        assert!(manager
+            .check_token(exchange.id(), &format!("{:032x}", 0))
+            .is_err());
+        assert!(manager
+            .read_partition(exchange.id(), "invalid", ExchangeSide::Left, 0)
+            .is_err());
+        assert!(manager.check_token("../token", exchange.token()).is_err());
+
+        let id = exchange.id().to_string();
+        let token = exchange.token().to_string();
+        drop(exchange);
+        assert!(manager.check_token(&id, &token).is_err());
+    }
+
+    #[test]
+    fn test_encode_exchange() {
+        let schema = Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("t0", DataType::Utf8, true),
+            Field::new("f0", DataType::Float64, true),
+        ]);
+        let bytes = encode_schema(&schema).unwrap();
+        assert_eq!(decode_schema(&bytes).unwrap().as_ref(), &schema);
+
+        let ticket = ExchangeTicket::Fetch {
+            exchange_id: "00000000000000ff".to_string(),
+            token: "0123456789abcdef".to_string(),
+            side: ExchangeSide::Right,
+            partition: 3,
+        };
+        let bytes = ticket.encode().unwrap();
+        match ExchangeTicket::decode(&bytes).unwrap() {
+            ExchangeTicket::Fetch {
+                exchange_id,
+                token,
+                side,
+                partition,
+            } => {
+                assert_eq!(exchange_id, "00000000000000ff");
+                assert_eq!(token, "0123456789abcdef");
+                assert_eq!(side, ExchangeSide::Right);
+                assert_eq!(partition, 3);
+            }
+            t => panic!("unexpected ticket {t:?}"),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Spill files of the partitions of an exchange.
+//!
+//! The batches of input partition `i` that belong to output partition `p` are
+//! written into `{dir}/{p}/{i}.arrow` in the Arrow IPC file format. A
+//! partition without any rows has no files.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::ipc::reader::FileReader;
+use datafusion::arrow::ipc::writer::FileWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use tokio::sync::mpsc;
+
+const SPILL_FILE_EXTENSION: &str = "arrow";
+
+/// Writes the batches of one input partition into the spill files of the
+/// output partitions.
+pub struct SpillWriter {
+    dir: PathBuf,
+    input: usize,
+    schema: SchemaRef,
+    writers: Vec<Option<FileWriter<BufWriter<File>>>>,
+}
+
+impl SpillWriter {
+    pub fn new(dir: PathBuf, input: usize, schema: SchemaRef, partitions: usize) -> Self {
+        Self {
+            dir,
+            input,
+            schema,
+            writers: (0..partitions).map(|_| None).collect(),
+        }
+    }
+
+    pub fn write(&mut self, partition: usize, batch: &RecordBatch) -> DFResult<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+
+        let writer = match &mut self.writers[partition] {
+            Some(writer) => writer,
+            writer @ None => {
+                let dir = self.dir.join(partition.to_string());
+                std::fs::create_dir_all(&dir)?;
+                let path = dir.join(format!("{}.{SPILL_FILE_EXTENSION}", self.input));
+                let file = BufWriter::new(File::create(path)?);
+                writer.insert(FileWriter::try_new(file, &self.schema)?)
+            }
+        };
+        writer.write(batch)?;
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> DFResult<()> {
+        for mut writer in self.writers.into_iter().flatten() {
+            writer.finish()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the spill files of the partition in `dir`, the files are read by a
+/// blocking thread.
+pub fn read_partition(dir: &Path, partition: usize) -> BoxStream<'static, DFResult<RecordBatch>> {
+    let dir = dir.join(partition.to_string());
+    let (sender, receiver) = mpsc::channel(2);
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = read_spill_files(&dir, &sender) {
+            let _ = sender.blocking_send(Err(e));
+        }
+    });
+
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|batch| (batch, receiver))
+    })
+    .boxed()
+}
+
+fn read_spill_files(dir: &Path, sender: &mpsc::Sender<DFResult<RecordBatch>>) -> DFResult<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.retain(|p| p.extension().map_or(false, |e| e == SPILL_FILE_EXTENSION));
+    paths.sort();
+
+    for path in paths {
+        let reader = FileReader::try_new(File::open(&path)?, None)?;
+        for batch in reader {
+            if sender
+                .blocking_send(batch.map_err(DataFusionError::from))
+                .is_err()
+            {
+                // The receiver is dropped.
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use futures::TryStreamExt;
+
+    use super::{read_partition, SpillWriter};
+
+    #[tokio::test]
+    async fn test_spill_partitions() {
+        let dir = std::path::PathBuf::from("/tmp/test/query/exchange/test_spill_partitions");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int64, false),
+            Field::new("v", DataType::Utf8, true),
+        ]));
+        let batch = |k: Vec<i64>, v: Vec<&str>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from(k)),
+                    Arc::new(StringArray::from(v)),
+                ],
+            )
+            .unwrap()
+        };
+
+        let mut writer = SpillWriter::new(dir.clone(), 0, schema.clone(), 3);
+        writer.write(0, &batch(vec![1, 2], vec!["a", "b"])).unwrap();
+        writer.write(2, &batch(vec![3], vec!["c"])).unwrap();
+        writer.finish().unwrap();
+        let mut writer = SpillWriter::new(dir.clone(), 1, schema.clone(), 3);
+        writer.write(0, &batch(vec![4], vec!["d"])).unwrap();
+        writer.finish().unwrap();
+
+        let batches: Vec<RecordBatch> = read_partition(&dir, 0).try_collect().await.unwrap();
+        assert_eq!(
+            batches,
+            vec![batch(vec![1, 2], vec!["a", "b"]), batch(vec![4], vec!["d"])]
+        );
+        let batches: Vec<RecordBatch> = read_partition(&dir, 1).try_collect().await.unwrap();
+        assert!(batches.is_empty());
+        let batches: Vec<RecordBatch> = read_partition(&dir, 2).try_collect().await.unwrap();
+        assert_eq!(batches, vec![batch(vec![3], vec!["c"])]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -6,13 +6,14 @@ use meta::error::MetaError;
 use models::oid::Identifier;
 use models::schema::resource_info::{ResourceInfo, ResourceOperator};
 use models::schema::table_schema::TableSchema;
-use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema};
+use models::schema::tskv_table_schema::TskvTableSchema;
 use snafu::ResultExt;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::{AlterTable, AlterTableAction};
 use spi::{CoordinatorSnafu, MetaSnafu, QueryError, QueryResult};
 
 // use crate::execution::ddl::query::spi::MetaSnafu;
+use crate::execution::ddl::create_table::check_encryption_keys;
 use crate::execution::ddl::DDLDefinitionTask;
 
 pub struct AlterTableTask {
@@ -50,6 +51,7 @@ impl DDLDefinitionTask for AlterTableTask {
 
         let operator_info = match &self.stmt.alter_action {
             AlterTableAction::AddColumn { table_column } => {
+                check_encryption_keys(client.tenant(), [table_column])?;
                 let table_column = table_column.to_owned();
                 schema.add_column(table_column.clone());
                 schema.schema_version += 1;
@@ -99,12 +101,8 @@ impl DDLDefinitionTask for AlterTableTask {
                 let alter_schema_func =
                     |schema: &mut TskvTableSchema, old_column_name: &str, new_name: &str| {
                         if let Some(old_column) = schema.column(old_column_name) {
-                            let new_column = TableColumn::new(
-                                old_column.id,
-                                new_name.to_string(),
-                                old_column.column_type.clone(),
-                                old_column.encoding,
-                            );
+                            let mut new_column = old_column.clone();
+                            new_column.name = new_name.to_string();
                             schema.change_column(old_column_name, new_column);
                             schema.schema_version += 1;
                         } else {
@@ -119,6 +117,11 @@ impl DDLDefinitionTask for AlterTableTask {
                 alter_schema_func(&mut schema, old_column_name, new_column_name)?;
                 None
             }
+            AlterTableAction::SetTtl { ttl } => {
+                schema.ttl = ttl.clone();
+                schema.schema_version += 1;
+                None
+            }
         };
 
         if let Some(info) = operator_info {
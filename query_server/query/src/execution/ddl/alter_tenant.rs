@@ -1,11 +1,14 @@
 use async_trait::async_trait;
 use meta::error::MetaError;
+use meta::model::MetaClientRef;
+use models::schema::table_schema::TableSchema;
+use models::schema::tenant::TenantOptions;
 use snafu::ResultExt;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::{
     AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser,
 };
-use spi::{MetaSnafu, QueryError};
+use spi::{MetaSnafu, QueryError, QueryResult};
 use trace::debug;
 
 use crate::execution::ddl::DDLDefinitionTask;
@@ -95,6 +98,7 @@ impl DDLDefinitionTask for AlterTenantTask {
                 query_state_machine.remove_user_from_cache_by_user_id(user_id)
             }
             AlterTenantAction::SetOption(tenant_option) => {
+                check_removed_encryption_keys(&meta, tenant_option)?;
                 query_state_machine
                     .meta
                     .alter_tenant(tenant_name, *tenant_option.clone())
@@ -106,3 +110,44 @@ impl DDLDefinitionTask for AlterTenantTask {
         return Ok(Output::Nil(()));
     }
 }
+
+/// The encryption keys removed by the new options of the tenant must not be
+/// used by any encrypted column.
+fn check_removed_encryption_keys(meta: &MetaClientRef, options: &TenantOptions) -> QueryResult<()> {
+    let Some(old_keys) = meta.tenant().options().encryption_keys.as_ref() else {
+        return Ok(());
+    };
+    let removed = old_keys
+        .keys()
+        .filter(|name| options.encryption_key(name).is_none())
+        .collect::<Vec<_>>();
+    if removed.is_empty() {
+        return Ok(());
+    }
+
+    for (db_name, db_info) in meta.list_databases().context(MetaSnafu)? {
+        for table in db_info.tables.values() {
+            let TableSchema::TsKvTableSchema(schema) = table else {
+                continue;
+            };
+            for column in schema.columns() {
+                let Some(key_name) = &column.encryption_key else {
+                    continue;
+                };
+                if removed
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(key_name))
+                {
+                    return Err(QueryError::Semantic {
+                        err: format!(
+                            "Encryption key {} is still used by column {} of table {}.{}",
+                            key_name, column.name, db_name, schema.name
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::UInt64Array;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::sql::sqlparser::ast::SqlOption;
+use meta::error::MetaError;
+use models::schema::database_schema::{DatabaseSchema, SeriesPlacement};
+use models::schema::table_schema::TableSchema;
+use models::schema::tskv_table_schema::TskvTableSchema;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use spi::query::datasource::{build_object_store, UriSchema};
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::{parse_connection_options, BackupDatabase};
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{
+    ArrowSnafu, CoordinatorSnafu, DatafusionSnafu, MetaSnafu, ObjectStoreSnafu, QueryError,
+    QueryResult, SerdeJsonSnafu,
+};
+use trace::info;
+use tskv::VnodeBackup;
+use url::Url;
+
+use super::DDLDefinitionTask;
+
+pub(super) const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Describes a backup of a database, it is saved in the root of the backup.
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) struct BackupManifest {
+    pub database: DatabaseSchema,
+    pub tables: Vec<TskvTableSchema>,
+    pub buckets: Vec<BucketBackup>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) struct BucketBackup {
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Placement of the series of the bucket, the restored bucket places the
+    /// series the same way. Backups of older versions place series by modulo.
+    #[serde(default)]
+    pub series_placement: SeriesPlacement,
+    /// Backups of the leader vnodes of the replication sets, in the order
+    /// of the shard group of the bucket.
+    pub shards: Vec<VnodeBackup>,
+}
+
+pub struct BackupDatabaseTask {
+    schema: SchemaRef,
+    stmt: BackupDatabase,
+}
+
+impl BackupDatabaseTask {
+    #[inline(always)]
+    pub fn new(stmt: BackupDatabase, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for BackupDatabaseTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let BackupDatabase {
+            database,
+            path,
+            connection_options,
+        } = self.stmt.clone();
+        let tenant = query_state_machine.session.tenant();
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+        let db_info = meta
+            .get_db_info(&database)
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::DatabaseNotFound {
+                name: database.clone(),
+            })?;
+        let (object_store, prefix) = build_backup_object_store(&path, connection_options.clone())?;
+
+        let coord = query_state_machine.coord.clone();
+        let options = sql_options_to_literal_map(connection_options);
+        let (mut vnodes, mut files, mut bytes) = (0_u64, 0_u64, 0_u64);
+        let mut buckets = Vec::with_capacity(db_info.buckets.len());
+        for bucket in db_info.buckets.iter() {
+            let mut shards = Vec::with_capacity(bucket.shard_group.len());
+            for replica in bucket.shard_group.iter() {
+                let vnode_id = replica.leader_vnode_id;
+                let backup = coord
+                    .backup_vnode(
+                        tenant,
+                        vnode_id,
+                        vnode_backup_path(&path, vnode_id),
+                        options.clone(),
+                    )
+                    .await
+                    .context(CoordinatorSnafu)?;
+                vnodes += 1;
+                files += backup.files.len() as u64;
+                bytes += backup.files.iter().map(|(_, size)| size).sum::<u64>();
+                shards.push(backup);
+            }
+            buckets.push(BucketBackup {
+                start_time: bucket.start_time,
+                end_time: bucket.end_time,
+                series_placement: bucket.series_placement,
+                shards,
+            });
+        }
+
+        let tables = db_info
+            .tables
+            .into_values()
+            .filter_map(|t| match t {
+                TableSchema::TsKvTableSchema(t) => Some(t.as_ref().clone()),
+                _ => None,
+            })
+            .collect();
+        let manifest = BackupManifest {
+            database: db_info.schema,
+            tables,
+            buckets,
+        };
+        let data = serde_json::to_vec_pretty(&manifest).context(SerdeJsonSnafu)?;
+        object_store
+            .put(&prefix.child(MANIFEST_FILE_NAME), data.into())
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+        info!("Backup database {tenant}.{database} to {path}: {vnodes} vnodes, {files} files");
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt64Array::from(vec![vnodes])),
+                Arc::new(UInt64Array::from(vec![files])),
+                Arc::new(UInt64Array::from(vec![bytes])),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
+
+/// Build the object store of the backup at `path`, returns the store and the
+/// prefix of the backup in it.
+pub(super) fn build_backup_object_store(
+    path: &str,
+    connection_options: Vec<SqlOption>,
+) -> QueryResult<(Arc<dyn ObjectStore>, ObjectStorePath)> {
+    let table_path = ListingTableUrl::parse(path).context(DatafusionSnafu)?;
+    let url: &Url = table_path.as_ref();
+    let connection_options = parse_connection_options(
+        &UriSchema::from(url.scheme()),
+        url.host_str(),
+        connection_options,
+    )?;
+    let object_store = build_object_store(connection_options)
+        .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?
+        .unwrap_or_else(|| Arc::new(LocalFileSystem::new()));
+
+    Ok((object_store, table_path.prefix().clone()))
+}
+
+/// Files of each vnode are saved under `vnodes/<vnode_id>` of the backup.
+pub(super) fn vnode_backup_path(path: &str, vnode_id: u32) -> String {
+    format!("{}/vnodes/{}", path.trim_end_matches('/'), vnode_id)
+}
+
+/// Option values are sent in SQL literal to keep their types.
+pub(super) fn sql_options_to_literal_map(options: Vec<SqlOption>) -> HashMap<String, String> {
+    options
+        .into_iter()
+        .map(|opt| (opt.name.value, opt.value.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use models::schema::database_schema::SeriesPlacement;
+
+    use super::BucketBackup;
+
+    #[test]
+    fn test_bucket_backup_series_placement() {
+        let bucket = BucketBackup {
+            start_time: 0,
+            end_time: 100,
+            series_placement: SeriesPlacement::JumpHash,
+            shards: vec![],
+        };
+        let data = serde_json::to_vec(&bucket).unwrap();
+        let restored: BucketBackup = serde_json::from_slice(&data).unwrap();
+        assert_eq!(restored.series_placement, SeriesPlacement::JumpHash);
+
+        // Backups of older versions.
+        let restored: BucketBackup =
+            serde_json::from_str(r#"{"start_time":0,"end_time":100,"shards":[]}"#).unwrap();
+        assert_eq!(restored.series_placement, SeriesPlacement::Modulo);
+    }
+}
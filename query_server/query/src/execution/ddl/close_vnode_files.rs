@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CloseVnodeFiles;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct CloseVnodeFilesTask {
+    schema: SchemaRef,
+    stmt: CloseVnodeFiles,
+}
+
+impl CloseVnodeFilesTask {
+    #[inline(always)]
+    pub fn new(stmt: CloseVnodeFiles, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CloseVnodeFilesTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let vnode_ids = self.stmt.vnode_ids.clone();
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let mut closed = coord
+            .close_vnodes_files(tenant, vnode_ids)
+            .await
+            .context(CoordinatorSnafu)?;
+        closed.sort_unstable();
+
+        let (vnode_ids, closed_files): (Vec<_>, Vec<_>) = closed.into_iter().unzip();
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vnode_ids)),
+                Arc::new(UInt64Array::from(closed_files)),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
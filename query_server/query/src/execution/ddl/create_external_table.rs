@@ -1,17 +1,25 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::common::cast::as_string_array;
 use datafusion::datasource::file_format::avro::AvroFormat;
 use datafusion::datasource::file_format::csv::CsvFormat;
 use datafusion::datasource::file_format::file_type::{FileCompressionType, FileType};
 use datafusion::datasource::file_format::json::JsonFormat;
 use datafusion::datasource::file_format::parquet::ParquetFormat;
-use datafusion::datasource::file_format::FileFormat;
-use datafusion::datasource::listing::{ListingOptions, ListingTableUrl};
+use datafusion::datasource::file_format::{FileFormat, DEFAULT_SCHEMA_INFER_MAX_RECORD};
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
+use datafusion::datasource::TableProvider;
+use datafusion::error::DataFusionError;
 use datafusion::execution::context::SessionState;
 use datafusion::logical_expr::CreateExternalTable;
+use datafusion::physical_plan::collect;
+use futures::TryStreamExt;
 use lazy_static::__Deref;
 use meta::error::MetaError;
 use models::schema::external_table_schema::ExternalTableSchema;
@@ -22,6 +30,16 @@ use spi::{DatafusionSnafu, MetaSnafu, QueryError, QueryResult};
 
 use super::DDLDefinitionTask;
 
+/// Number of rows sampled to infer the schema of the files.
+const SCHEMA_INFER_MAX_RECORDS: &str = "schema_infer_max_records";
+/// Infer the schema even if columns are declared, the declared columns override
+/// the inferred ones of the same names.
+const INFER_SCHEMA: &str = "infer_schema";
+/// Unit of the inferred timestamp columns, `s`, `ms`, `us` or `ns`. If absent, the
+/// unit of each column of the csv and json files is the finest one needed by the
+/// fractional seconds of its sampled values.
+const TIMESTAMP_PRECISION: &str = "timestamp_precision";
+
 pub struct CreateExternalTableTask {
     stmt: CreateExternalTable,
 }
@@ -111,7 +129,8 @@ async fn build_table_schema(
         reason: "couldn't resolve tenant".to_string(),
     })?;
 
-    let schema = construct_listing_table_schema(stmt, state).await?;
+    let table_partition_cols = table_partition_cols(stmt, state).await?;
+    let schema = construct_listing_table_schema(stmt, state, &table_partition_cols).await?;
 
     let schema = ExternalTableSchema {
         tenant: tenant.to_string(),
@@ -121,7 +140,7 @@ async fn build_table_schema(
         file_type: stmt.file_type.clone(),
         file_compression_type: stmt.file_compression_type.to_string(),
         target_partitions: state.config().target_partitions(),
-        table_partition_cols: Default::default(),
+        table_partition_cols,
         has_header: stmt.has_header,
         delimiter: stmt.delimiter as u8,
         schema: schema.deref().clone(),
@@ -129,9 +148,74 @@ async fn build_table_schema(
     Ok(schema)
 }
 
+/// Partition columns declared by `PARTITIONED BY`, or discovered from the hive-style
+/// directories (`.../date=2024-01-01/...`) of the first file under the location.
+async fn table_partition_cols(
+    stmt: &CreateExternalTable,
+    state: &SessionState,
+) -> QueryResult<Vec<(String, DataType)>> {
+    if !stmt.table_partition_cols.is_empty() {
+        return Ok(stmt
+            .table_partition_cols
+            .iter()
+            .map(|col| (col.clone(), DataType::Utf8))
+            .collect());
+    }
+
+    let table_path = ListingTableUrl::parse(&stmt.location).context(DatafusionSnafu)?;
+    if !table_path.is_collection() {
+        return Ok(vec![]);
+    }
+    let store = state
+        .runtime_env()
+        .object_store(&table_path)
+        .context(DatafusionSnafu)?;
+    let mut objects = store
+        .list(Some(table_path.prefix()))
+        .await
+        .map_err(DataFusionError::from)
+        .context(DatafusionSnafu)?;
+    while let Some(object) = objects
+        .try_next()
+        .await
+        .map_err(DataFusionError::from)
+        .context(DatafusionSnafu)?
+    {
+        let parts = match object.location.prefix_match(table_path.prefix()) {
+            Some(parts) => parts.collect::<Vec<_>>(),
+            None => continue,
+        };
+        let (file_name, dirs) = match parts.split_last() {
+            Some(v) => v,
+            None => continue,
+        };
+        // Skip hidden files and markers such as `_SUCCESS`.
+        if file_name.as_ref().starts_with(['.', '_']) {
+            continue;
+        }
+        let cols = parse_hive_partition_cols(dirs.iter().map(|p| p.as_ref()));
+        return Ok(cols.into_iter().map(|col| (col, DataType::Utf8)).collect());
+    }
+
+    Ok(vec![])
+}
+
+/// Returns names of the partition columns if all directories are `<column>=<value>`.
+fn parse_hive_partition_cols<'a>(dirs: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut cols = vec![];
+    for dir in dirs {
+        match dir.split_once('=') {
+            Some((col, _)) if !col.is_empty() => cols.push(col.to_string()),
+            _ => return vec![],
+        }
+    }
+    cols
+}
+
 async fn construct_listing_table_schema(
     stmt: &CreateExternalTable,
     state: &SessionState,
+    table_partition_cols: &[(String, DataType)],
 ) -> QueryResult<SchemaRef> {
     let CreateExternalTable {
         ref schema,
@@ -140,42 +224,263 @@ async fn construct_listing_table_schema(
     } = stmt;
 
     // TODO make schema in CreateExternalTable optional instead of empty
+    // Values of partition columns come from the paths instead of the files.
     let provided_schema = if schema.fields().is_empty() {
         None
     } else {
-        Some(Arc::new(schema.as_ref().to_owned().into()))
+        let schema: Schema = schema.as_ref().to_owned().into();
+        let fields = schema
+            .fields()
+            .iter()
+            .filter(|f| !table_partition_cols.iter().any(|(col, _)| col == f.name()))
+            .cloned()
+            .collect::<Vec<_>>();
+        Some(Arc::new(Schema::new_with_metadata(
+            fields,
+            schema.metadata().clone(),
+        )))
+    };
+
+    let infer_options = InferOptions::parse(&stmt.name.to_string(), &stmt.options)?;
+    let provided_schema = match provided_schema {
+        Some(s) if !infer_options.infer => return Ok(s),
+        s => s,
     };
 
     let table_path = ListingTableUrl::parse(location).context(DatafusionSnafu)?;
-    let options = build_external_table_config(stmt, state.config().target_partitions())?;
+    let max_records = infer_options
+        .max_records
+        .unwrap_or(DEFAULT_SCHEMA_INFER_MAX_RECORD);
+    let options =
+        build_external_table_config(stmt, state.config().target_partitions(), max_records)?;
+    let inferred = options
+        .infer_schema(state, &table_path)
+        .await
+        .context(spi::DatafusionSnafu)?;
 
-    Ok(match provided_schema {
-        None => options
-            .infer_schema(state, &table_path)
-            .await
-            .context(spi::DatafusionSnafu)?,
-        Some(s) => s,
+    let timestamp_units = match infer_options.timestamp_unit {
+        Some(unit) => inferred
+            .fields()
+            .iter()
+            .filter(|f| matches!(f.data_type(), DataType::Timestamp(..)))
+            .map(|f| (f.name().clone(), unit))
+            .collect(),
+        None => match FileType::from_str(stmt.file_type.as_str())? {
+            FileType::CSV | FileType::JSON => {
+                sample_timestamp_units(state, table_path, options, &inferred, max_records).await?
+            }
+            // The unit is stored in the files.
+            _ => HashMap::new(),
+        },
+    };
+
+    let schema = override_inferred_schema(&inferred, provided_schema.as_deref(), &timestamp_units)
+        .map_err(|reason| QueryError::InvalidTableOption {
+            option_name: INFER_SCHEMA.to_string(),
+            table_name: stmt.name.to_string(),
+            reason,
+        })?;
+    Ok(Arc::new(schema))
+}
+
+/// Options of the schema inference in `OPTIONS (...)` of the statement.
+#[derive(Debug, Default, PartialEq)]
+struct InferOptions {
+    max_records: Option<usize>,
+    infer: bool,
+    timestamp_unit: Option<TimeUnit>,
+}
+
+impl InferOptions {
+    fn parse(table_name: &str, table_options: &HashMap<String, String>) -> QueryResult<Self> {
+        let invalid = |option_name: &str, reason: String| QueryError::InvalidTableOption {
+            option_name: option_name.to_string(),
+            table_name: table_name.to_string(),
+            reason,
+        };
+
+        let mut options = Self::default();
+        for (key, value) in table_options.iter() {
+            match key.to_lowercase().as_str() {
+                SCHEMA_INFER_MAX_RECORDS => {
+                    let max_records =
+                        value
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|n| *n > 0)
+                            .ok_or_else(|| {
+                                invalid(key, format!("expect a positive integer, got '{value}'"))
+                            })?;
+                    options.max_records = Some(max_records);
+                }
+                INFER_SCHEMA => {
+                    options.infer = value.parse::<bool>().map_err(|_| {
+                        invalid(key, format!("expect true or false, got '{value}'"))
+                    })?;
+                }
+                TIMESTAMP_PRECISION => {
+                    let unit = match value.to_lowercase().as_str() {
+                        "s" => TimeUnit::Second,
+                        "ms" => TimeUnit::Millisecond,
+                        "us" => TimeUnit::Microsecond,
+                        "ns" => TimeUnit::Nanosecond,
+                        _ => {
+                            return Err(invalid(
+                                key,
+                                format!("expect one of s, ms, us and ns, got '{value}'"),
+                            ))
+                        }
+                    };
+                    options.timestamp_unit = Some(unit);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Read the sampled values of the inferred timestamp columns as strings, and
+/// returns the finest unit needed by the fractional seconds of each column.
+async fn sample_timestamp_units(
+    state: &SessionState,
+    table_path: ListingTableUrl,
+    options: ListingOptions,
+    inferred: &Schema,
+    max_records: usize,
+) -> QueryResult<HashMap<String, TimeUnit>> {
+    let timestamp_cols = inferred
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| matches!(f.data_type(), DataType::Timestamp(..)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if timestamp_cols.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let fields = inferred
+        .fields()
+        .iter()
+        .map(|f| match f.data_type() {
+            DataType::Timestamp(..) => Field::new(f.name(), DataType::Utf8, f.is_nullable()),
+            _ => f.as_ref().clone(),
+        })
+        .collect::<Vec<_>>();
+    let config = ListingTableConfig::new(table_path)
+        .with_listing_options(options)
+        .with_schema(Arc::new(Schema::new(fields)));
+    let table = ListingTable::try_new(config).context(DatafusionSnafu)?;
+    let plan = table
+        .scan(state, Some(&timestamp_cols), &[], None, Some(max_records))
+        .await
+        .context(DatafusionSnafu)?;
+    let batches = collect(plan, state.task_ctx())
+        .await
+        .context(DatafusionSnafu)?;
+
+    let mut digits = vec![0; timestamp_cols.len()];
+    for batch in batches.iter() {
+        for (i, column) in batch.columns().iter().enumerate() {
+            let values = as_string_array(column).context(DatafusionSnafu)?;
+            for value in values.iter().flatten() {
+                digits[i] = digits[i].max(fractional_digits(value));
+            }
+        }
+    }
+
+    Ok(timestamp_cols
+        .into_iter()
+        .zip(digits)
+        .map(|(i, digits)| (inferred.field(i).name().clone(), time_unit_of(digits)))
+        .collect())
+}
+
+/// Number of the digits of the fractional seconds, e.g. 3 of `2024-01-01T00:00:00.123Z`.
+fn fractional_digits(timestamp: &str) -> usize {
+    timestamp.split_once('.').map_or(0, |(_, fraction)| {
+        fraction.chars().take_while(char::is_ascii_digit).count()
     })
 }
 
+/// The coarsest unit keeping all the digits of the fractional seconds.
+fn time_unit_of(fractional_digits: usize) -> TimeUnit {
+    match fractional_digits {
+        0 => TimeUnit::Second,
+        1..=3 => TimeUnit::Millisecond,
+        4..=6 => TimeUnit::Microsecond,
+        _ => TimeUnit::Nanosecond,
+    }
+}
+
+/// Replace the inferred columns by the declared columns of the same names, and cast
+/// the inferred timestamp columns to their units in `timestamp_units`.
+fn override_inferred_schema(
+    inferred: &Schema,
+    declared: Option<&Schema>,
+    timestamp_units: &HashMap<String, TimeUnit>,
+) -> Result<Schema, String> {
+    if let Some(declared) = declared {
+        if let Some(field) = declared
+            .fields()
+            .iter()
+            .find(|f| inferred.field_with_name(f.name()).is_err())
+        {
+            return Err(format!(
+                "column {} is not found in the inferred schema",
+                field.name()
+            ));
+        }
+    }
+
+    let fields = inferred
+        .fields()
+        .iter()
+        .map(|field| {
+            if let Some(f) = declared.and_then(|d| d.field_with_name(field.name()).ok()) {
+                return f.clone();
+            }
+            match (field.data_type(), timestamp_units.get(field.name())) {
+                (DataType::Timestamp(_, tz), Some(unit)) => Field::new(
+                    field.name(),
+                    DataType::Timestamp(*unit, tz.clone()),
+                    field.is_nullable(),
+                ),
+                _ => field.as_ref().clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Schema::new_with_metadata(
+        fields,
+        inferred.metadata().clone(),
+    ))
+}
+
 fn build_external_table_config(
     stmt: &CreateExternalTable,
     target_partitions: usize,
+    schema_infer_max_records: usize,
 ) -> QueryResult<ListingOptions> {
     let file_compression_type = FileCompressionType::from(stmt.file_compression_type);
     let file_type = FileType::from_str(stmt.file_type.as_str())?;
     let file_format: Arc<dyn FileFormat> = match file_type {
         FileType::CSV => Arc::new(
             CsvFormat::default()
+                .with_schema_infer_max_rec(Some(schema_infer_max_records))
                 .with_has_header(stmt.has_header)
                 .with_delimiter(stmt.delimiter as u8)
                 .with_file_compression_type(file_compression_type),
         ),
         FileType::PARQUET => Arc::new(ParquetFormat::default()),
         FileType::AVRO => Arc::new(AvroFormat),
-        FileType::JSON => {
-            Arc::new(JsonFormat::default().with_file_compression_type(file_compression_type))
-        }
+        FileType::JSON => Arc::new(
+            JsonFormat::default()
+                .with_schema_infer_max_rec(Some(schema_infer_max_records))
+                .with_file_compression_type(file_compression_type),
+        ),
         FileType::ARROW => {
             return Err(QueryError::NotImplemented {
                 err: "Build arrow external table config".to_string(),
@@ -187,3 +492,105 @@ fn build_external_table_config(
 
     Ok(options)
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+
+    use super::{
+        fractional_digits, override_inferred_schema, parse_hive_partition_cols, time_unit_of,
+        InferOptions,
+    };
+
+    #[test]
+    fn test_parse_hive_partition_cols() {
+        let cols = parse_hive_partition_cols(["date=2024-01-01", "region=cn"].into_iter());
+        assert_eq!(cols, vec!["date".to_string(), "region".to_string()]);
+
+        let cols = parse_hive_partition_cols(["date=2024-01-01", "data"].into_iter());
+        assert!(cols.is_empty());
+
+        let cols = parse_hive_partition_cols(["=2024-01-01"].into_iter());
+        assert!(cols.is_empty());
+
+        let cols = parse_hive_partition_cols(std::iter::empty());
+        assert!(cols.is_empty());
+    }
+
+    #[test]
+    fn test_override_inferred_schema() {
+        let inferred = Schema::new(vec![
+            Field::new("time", DataType::Timestamp(TimeUnit::Second, None), true),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("value", DataType::Int64, true),
+        ]);
+
+        let units = HashMap::from([("time".to_string(), TimeUnit::Nanosecond)]);
+        let schema = override_inferred_schema(&inferred, None, &units).unwrap();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None)
+        );
+
+        let declared = Schema::new(vec![Field::new("value", DataType::Float64, true)]);
+        let schema = override_inferred_schema(&inferred, Some(&declared), &HashMap::new()).unwrap();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, None)
+        );
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+
+        let declared = Schema::new(vec![Field::new("region", DataType::Utf8, true)]);
+        assert!(override_inferred_schema(&inferred, Some(&declared), &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_parse_infer_options() {
+        let parse = |options: &[(&str, &str)]| {
+            let options = options
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>();
+            InferOptions::parse("cnosdb.public.t", &options)
+        };
+
+        assert_eq!(parse(&[]).unwrap(), InferOptions::default());
+        assert_eq!(
+            parse(&[
+                ("SCHEMA_INFER_MAX_RECORDS", "100"),
+                ("infer_schema", "true"),
+                ("timestamp_precision", "MS"),
+                ("format.delimiter", ";"),
+            ])
+            .unwrap(),
+            InferOptions {
+                max_records: Some(100),
+                infer: true,
+                timestamp_unit: Some(TimeUnit::Millisecond),
+            }
+        );
+
+        assert!(parse(&[("schema_infer_max_records", "0")]).is_err());
+        assert!(parse(&[("schema_infer_max_records", "-1")]).is_err());
+        assert!(parse(&[("infer_schema", "yes")]).is_err());
+        assert!(parse(&[("timestamp_precision", "m")]).is_err());
+    }
+
+    #[test]
+    fn test_infer_timestamp_unit() {
+        let unit = |timestamp: &str| time_unit_of(fractional_digits(timestamp));
+        assert_eq!(unit("2024-01-01T00:00:00"), TimeUnit::Second);
+        assert_eq!(unit("2024-01-01 00:00:00+08:00"), TimeUnit::Second);
+        assert_eq!(unit("2024-01-01T00:00:00.1"), TimeUnit::Millisecond);
+        assert_eq!(unit("2024-01-01T00:00:00.123Z"), TimeUnit::Millisecond);
+        assert_eq!(unit("2024-01-01T00:00:00.1234"), TimeUnit::Microsecond);
+        assert_eq!(
+            unit("2024-01-01T00:00:00.123456+08:00"),
+            TimeUnit::Microsecond
+        );
+        assert_eq!(unit("2024-01-01T00:00:00.123456789"), TimeUnit::Nanosecond);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+use async_trait::async_trait;
+use coordinator::resource_manager::ResourceManager;
+use meta::error::MetaError;
+use models::oid::Identifier;
+use models::predicate::domain::ColumnDomains;
+use models::predicate::transformation::DeleteSelectionExpressionToDomainsVisitor;
+use models::schema::resource_info::{ResourceInfo, ResourceOperator, ResourceStatus};
+use models::schema::scheduled_delete::ScheduledDeleteJob;
+use models::utils::now_timestamp_nanos;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateScheduledDelete;
+use spi::{CoordinatorSnafu, MetaSnafu, QueryError, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct CreateScheduledDeleteTask {
+    stmt: CreateScheduledDelete,
+}
+
+impl CreateScheduledDeleteTask {
+    #[inline(always)]
+    pub fn new(stmt: CreateScheduledDelete) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateScheduledDeleteTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreateScheduledDelete {
+            if_not_exists,
+            ref name,
+            ref table_name,
+            ref cron,
+            older_than,
+            ref selection,
+            ref selection_sql,
+        } = self.stmt;
+        let tenant_name = table_name.tenant();
+        let database = table_name.database();
+
+        let tenant = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant_name.to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        // The cancelled jobs and the jobs never running again can be replaced.
+        let resource_name = ScheduledDeleteJob::resource_name(tenant_name, database, name);
+        let exists = query_state_machine
+            .meta
+            .read_resourceinfo_by_name(&resource_name)
+            .await
+            .context(MetaSnafu)?
+            .is_some_and(|r| {
+                !matches!(
+                    r.get_status(),
+                    ResourceStatus::Cancel | ResourceStatus::Fatal
+                )
+            });
+        if exists {
+            if if_not_exists {
+                return Ok(Output::Nil(()));
+            }
+            return Err(QueryError::Meta {
+                source: MetaError::ScheduledDeleteAlreadyExists { name: name.clone() },
+            });
+        }
+
+        let (tags, time) = match selection {
+            Some(expr) => {
+                DeleteSelectionExpressionToDomainsVisitor::expr_to_tag_and_time_domains(expr)?
+            }
+            None => (ColumnDomains::all(), ColumnDomains::all()),
+        };
+        let job = ScheduledDeleteJob {
+            name: name.clone(),
+            tenant: tenant_name.to_string(),
+            database: database.to_string(),
+            table: table_name.table().to_string(),
+            cron: cron.clone(),
+            older_than,
+            selection: selection_sql.clone(),
+            tags,
+            time,
+            history: VecDeque::new(),
+        };
+        let first_run_time =
+            job.next_run_time(now_timestamp_nanos())
+                .ok_or_else(|| QueryError::InvalidParam {
+                    reason: format!("cron expression '{}' never matches", cron),
+                })?;
+
+        let mut resourceinfo = ResourceInfo::new(
+            (*tenant.tenant().id(), database.to_string()),
+            resource_name,
+            ResourceOperator::ScheduledDelete(job),
+            &None,
+            query_state_machine.coord.node_id(),
+        );
+        resourceinfo.set_time(first_run_time);
+        resourceinfo.set_status(ResourceStatus::Schedule);
+        ResourceManager::add_resource_task(query_state_machine.coord.clone(), resourceinfo)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -2,8 +2,10 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use meta::error::MetaError;
+use models::oid::Identifier;
 use models::schema::table_schema::TableSchema;
-use models::schema::tskv_table_schema::TskvTableSchema;
+use models::schema::tenant::Tenant;
+use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema};
 use snafu::ResultExt;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::CreateTable;
@@ -51,6 +53,8 @@ async fn create_table(stmt: &CreateTable, machine: QueryStateMachineRef) -> Quer
         })
         .context(MetaSnafu)?;
 
+    check_encryption_keys(client.tenant(), &stmt.schema)?;
+
     let table_schema = build_schema(stmt);
     client
         .create_table(&TableSchema::TsKvTableSchema(Arc::new(table_schema)))
@@ -58,13 +62,42 @@ async fn create_table(stmt: &CreateTable, machine: QueryStateMachineRef) -> Quer
         .context(MetaSnafu)
 }
 
+/// Check the encryption keys of the columns exist in the key store of the tenant.
+pub(super) fn check_encryption_keys<'a>(
+    tenant: &Tenant,
+    columns: impl IntoIterator<Item = &'a TableColumn>,
+) -> QueryResult<()> {
+    for column in columns {
+        if let Some(key_name) = &column.encryption_key {
+            if tenant.options().encryption_key(key_name).is_none() {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "Encryption key {} of column {} not found in tenant {}",
+                        key_name,
+                        column.name,
+                        tenant.name()
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 fn build_schema(stmt: &CreateTable) -> TskvTableSchema {
-    let CreateTable { schema, name, .. } = stmt;
+    let CreateTable {
+        schema,
+        name,
+        append_only,
+        ..
+    } = stmt;
 
-    TskvTableSchema::new(
+    let mut table_schema = TskvTableSchema::new(
         name.tenant().to_string(),
         name.database().to_string(),
         name.table().to_string(),
         schema.to_owned(),
-    )
+    );
+    table_schema.append_only = *append_only;
+    table_schema
 }
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::DropContinuousQuery;
+use spi::{QueryError, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct DropContinuousQueryTask {
+    stmt: DropContinuousQuery,
+}
+
+impl DropContinuousQueryTask {
+    #[inline(always)]
+    pub fn new(stmt: DropContinuousQuery) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for DropContinuousQueryTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let DropContinuousQuery {
+            if_exist,
+            ref name,
+            ref database,
+        } = self.stmt;
+        let tenant = query_state_machine.session.tenant();
+        let result = query_state_machine
+            .meta
+            .drop_continuous_query(tenant, database, name)
+            .await;
+
+        // The running query stops itself at the next run once the definition is dropped.
+        match result {
+            Ok(_) => Ok(Output::Nil(())),
+            Err(MetaError::ContinuousQueryNotFound { .. }) if if_exist => Ok(Output::Nil(())),
+            Err(source) => Err(QueryError::Meta { source }),
+        }
+    }
+}
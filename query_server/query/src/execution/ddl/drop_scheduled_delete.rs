@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use models::schema::resource_info::ResourceStatus;
+use models::schema::scheduled_delete::ScheduledDeleteJob;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::DropScheduledDelete;
+use spi::{MetaSnafu, QueryError, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct DropScheduledDeleteTask {
+    stmt: DropScheduledDelete,
+}
+
+impl DropScheduledDeleteTask {
+    #[inline(always)]
+    pub fn new(stmt: DropScheduledDelete) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for DropScheduledDeleteTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let DropScheduledDelete {
+            if_exist,
+            ref name,
+            ref database,
+        } = self.stmt;
+        let tenant = query_state_machine.session.tenant();
+        let meta = query_state_machine.meta.clone();
+
+        let resource_name = ScheduledDeleteJob::resource_name(tenant, database, name);
+        let resourceinfo = meta
+            .read_resourceinfo_by_name(&resource_name)
+            .await
+            .context(MetaSnafu)?
+            .filter(|r| *r.get_status() != ResourceStatus::Cancel);
+        let Some(mut resourceinfo) = resourceinfo else {
+            if if_exist {
+                return Ok(Output::Nil(()));
+            }
+            return Err(QueryError::Meta {
+                source: MetaError::ScheduledDeleteNotFound { name: name.clone() },
+            });
+        };
+
+        // The waiting run is aborted by the node executing the job.
+        resourceinfo.set_status(ResourceStatus::Cancel);
+        resourceinfo.set_is_new_add(true);
+        meta.write_resourceinfo(resourceinfo.get_name(), resourceinfo.clone())
+            .await
+            .context(MetaSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
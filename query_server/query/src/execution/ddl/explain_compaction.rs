@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{StringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ExplainCompaction;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct ExplainCompactionTask {
+    schema: SchemaRef,
+    stmt: ExplainCompaction,
+}
+
+impl ExplainCompactionTask {
+    #[inline(always)]
+    pub fn new(stmt: ExplainCompaction, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ExplainCompactionTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let plans = coord
+            .explain_compaction(tenant, self.stmt.vnode_id)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        let files = plans
+            .iter()
+            .map(|p| {
+                p.files
+                    .iter()
+                    .map(|(level, file_id, _)| format!("L{level}/{file_id}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        // Rounds to milliseconds, e.g. "1.234s".
+        let durations = plans
+            .iter()
+            .map(|p| {
+                p.duration
+                    .map(|d| format!("{:?}", Duration::from_millis(d.as_millis() as u64)))
+            })
+            .collect::<Vec<_>>();
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(
+                    plans.iter().map(|p| p.vnode_id),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    plans.iter().map(|p| p.compaction.as_str()),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    plans.iter().map(|p| p.in_level),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    plans.iter().map(|p| p.out_level),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    plans.iter().map(|p| p.files.len() as u64),
+                )),
+                Arc::new(StringArray::from(files)),
+                Arc::new(UInt64Array::from_iter_values(
+                    plans.iter().map(|p| p.read_bytes),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    plans.iter().map(|p| p.write_bytes),
+                )),
+                Arc::new(StringArray::from(durations)),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{StringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
+use datafusion::datasource::source_as_provider;
+use datafusion::error::Result as DFResult;
+use datafusion::logical_expr::{Expr, LogicalPlan, TableScan};
+use datafusion::sql::TableReference;
+use models::object_reference::Resolve;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ExplainCost;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+use crate::data_source::batch::tskv::ClusterTable;
+use crate::extension::logical::plan_node::tag_scan::TagScanPlanNode;
+use crate::sql::logical::optimizer::{DefaultLogicalOptimizer, LogicalOptimizer};
+
+pub struct ExplainCostTask {
+    schema: SchemaRef,
+    stmt: ExplainCost,
+}
+
+impl ExplainCostTask {
+    #[inline(always)]
+    pub fn new(stmt: ExplainCost, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ExplainCostTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let session = &query_state_machine.session;
+        // Filters are pushed down to the table scans by the optimizer.
+        let optimized_plan =
+            DefaultLogicalOptimizer::default().optimize(&self.stmt.plan, session)?;
+
+        let mut scans = vec![];
+        optimized_plan.visit(&mut ExtractClusterTableScan { scans: &mut scans })?;
+
+        let mut tables = vec![];
+        let mut replica_ids = vec![];
+        let mut node_ids = vec![];
+        let mut vnode_ids = vec![];
+        let mut series = vec![];
+        let mut files = vec![];
+        let mut bytes = vec![];
+        for (table, filters, limit) in scans {
+            let schema = table.table_schema();
+            let resolved_table =
+                TableReference::bare(&schema.name).resolve_object(&schema.tenant, &schema.db)?;
+            let predicate = table.resolve_predicate(&filters, limit)?;
+
+            let costs = query_state_machine
+                .coord
+                .estimate_scan_cost(&resolved_table, predicate)
+                .await
+                .context(CoordinatorSnafu)?;
+            for vnode_cost in costs {
+                tables.push(schema.name.clone());
+                replica_ids.push(vnode_cost.replica_id);
+                node_ids.push(vnode_cost.node_id);
+                vnode_ids.push(vnode_cost.vnode_id);
+                series.push(vnode_cost.cost.series);
+                files.push(vnode_cost.cost.files);
+                bytes.push(vnode_cost.cost.bytes);
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(StringArray::from(tables)),
+                Arc::new(UInt32Array::from(replica_ids)),
+                Arc::new(UInt64Array::from(node_ids)),
+                Arc::new(UInt32Array::from(vnode_ids)),
+                Arc::new(UInt64Array::from(series)),
+                Arc::new(UInt64Array::from(files)),
+                Arc::new(UInt64Array::from(bytes)),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
+
+/// Collect the tskv tables scanned by the plan, with the filters and limit pushed down.
+struct ExtractClusterTableScan<'a> {
+    scans: &'a mut Vec<(ClusterTable, Vec<Expr>, Option<usize>)>,
+}
+
+impl<'a> TreeNodeVisitor for ExtractClusterTableScan<'a> {
+    type N = LogicalPlan;
+
+    fn pre_visit(&mut self, plan: &LogicalPlan) -> DFResult<VisitRecursion> {
+        match plan {
+            LogicalPlan::TableScan(TableScan {
+                source,
+                filters,
+                fetch,
+                ..
+            }) => {
+                if let Some(table) = source_as_provider(source)?
+                    .as_any()
+                    .downcast_ref::<ClusterTable>()
+                {
+                    self.scans.push((table.clone(), filters.clone(), *fetch));
+                }
+            }
+            LogicalPlan::Extension(extension) => {
+                if let Some(tag_scan) = extension.node.as_any().downcast_ref::<TagScanPlanNode>() {
+                    self.scans.push((
+                        tag_scan.source.as_ref().clone(),
+                        tag_scan.filters.clone(),
+                        tag_scan.fetch,
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(VisitRecursion::Continue)
+    }
+}
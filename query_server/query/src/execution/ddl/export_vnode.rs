@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::UInt64Array;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ExportVnode;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct ExportVnodeTask {
+    schema: SchemaRef,
+    stmt: ExportVnode,
+}
+
+impl ExportVnodeTask {
+    #[inline(always)]
+    pub fn new(stmt: ExportVnode, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ExportVnodeTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let ExportVnode {
+            vnode_id,
+            time_range,
+            path,
+            connection_options,
+        } = self.stmt.clone();
+        let tenant = query_state_machine.session.tenant();
+
+        // Option values are sent in SQL literal to keep their types.
+        let connection_options = connection_options
+            .into_iter()
+            .map(|opt| (opt.name.value, opt.value.to_string()))
+            .collect();
+
+        let coord = query_state_machine.coord.clone();
+        let rows = coord
+            .export_vnode(tenant, vnode_id, time_range, path, connection_options)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![Arc::new(UInt64Array::from(vec![rows]))],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
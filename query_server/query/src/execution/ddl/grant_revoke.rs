@@ -24,6 +24,7 @@ impl DDLDefinitionTask for GrantRevokeTask {
         let GrantRevoke {
             is_grant,
             ref database_privileges,
+            ref table_privileges,
             ref tenant_name,
             ref role_name,
         } = self.stmt;
@@ -55,9 +56,16 @@ impl DDLDefinitionTask for GrantRevokeTask {
                 role_name, tenant_name
             );
 
-            meta.grant_privilege_to_custom_role(database_privileges.clone(), role_name)
-                .await
-                .context(MetaSnafu)?;
+            if !database_privileges.is_empty() {
+                meta.grant_privilege_to_custom_role(database_privileges.clone(), role_name)
+                    .await
+                    .context(MetaSnafu)?;
+            }
+            if !table_privileges.is_empty() {
+                meta.grant_table_privilege_to_custom_role(table_privileges.clone(), role_name)
+                    .await
+                    .context(MetaSnafu)?;
+            }
         } else {
             // 给租户下的自定义角色撤销若干权限
             // fn revoke_privilege_from_custom_role_of_tenant(
@@ -72,9 +80,21 @@ impl DDLDefinitionTask for GrantRevokeTask {
                 role_name, tenant_name
             );
 
-            meta.revoke_privilege_from_custom_role(database_privileges.clone(), role_name)
-                .await
-                .context(MetaSnafu)?;
+            if !database_privileges.is_empty() {
+                meta.revoke_privilege_from_custom_role(database_privileges.clone(), role_name)
+                    .await
+                    .context(MetaSnafu)?;
+            }
+            if !table_privileges.is_empty() {
+                // the privileges are revoked from whole tables, the planner rejects columns
+                let table_privileges = table_privileges
+                    .iter()
+                    .map(|(p, db, table)| (p.privilege.clone(), db.clone(), table.clone()))
+                    .collect();
+                meta.revoke_table_privilege_from_custom_role(table_privileges, role_name)
+                    .await
+                    .context(MetaSnafu)?;
+            }
             query_state_machine.clear_auth_cache();
         }
 
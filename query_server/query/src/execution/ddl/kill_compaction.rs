@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::KillCompaction;
+use spi::{CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+/// Cancel a running compaction job, the files compacted by it are kept.
+pub struct KillCompactionTask {
+    stmt: KillCompaction,
+}
+
+impl KillCompactionTask {
+    #[inline(always)]
+    pub fn new(stmt: KillCompaction) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for KillCompactionTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        query_state_machine
+            .coord
+            .kill_compaction(tenant, self.stmt.node_id, self.stmt.job_id)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
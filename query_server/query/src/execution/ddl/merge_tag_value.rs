@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use coordinator::resource_manager::ResourceManager;
+use meta::error::MetaError;
+use models::oid::Identifier;
+use models::schema::resource_info::{ResourceInfo, ResourceOperator};
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::MergeTagValue;
+use spi::{CoordinatorSnafu, MetaSnafu, QueryResult};
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct MergeTagValueTask {
+    stmt: MergeTagValue,
+}
+
+impl MergeTagValueTask {
+    pub fn new(stmt: MergeTagValue) -> MergeTagValueTask {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for MergeTagValueTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let table_name = &self.stmt.table_name;
+        let tenant = table_name.tenant();
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        let schema = client
+            .get_tskv_table_schema(table_name.database(), table_name.table())
+            .context(MetaSnafu)?
+            .ok_or_else(|| MetaError::TableNotFound {
+                table: table_name.to_string(),
+            })
+            .context(MetaSnafu)?
+            .as_ref()
+            .clone();
+
+        let resourceinfo = ResourceInfo::new(
+            (*client.tenant().id(), table_name.database().to_string()),
+            table_name.tenant().to_string()
+                + "-"
+                + table_name.database()
+                + "-"
+                + table_name.table()
+                + "-"
+                + &self.stmt.tag_name
+                + "-MergeTagValue",
+            ResourceOperator::MergeTagValue(
+                schema,
+                self.stmt.tag_name.clone(),
+                self.stmt.from.clone(),
+                self.stmt.to.clone(),
+            ),
+            &None,
+            query_state_machine.coord.node_id(),
+        );
+        ResourceManager::add_resource_task(query_state_machine.coord.clone(), resourceinfo)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
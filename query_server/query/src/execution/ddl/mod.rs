@@ -27,40 +27,77 @@ use self::replica_remove::ReplicaRemoveTask;
 use self::show_replica::ShowReplicasTask;
 use crate::execution::ddl::alter_database::AlterDatabaseTask;
 use crate::execution::ddl::alter_table::AlterTableTask;
+use crate::execution::ddl::backup_database::BackupDatabaseTask;
 use crate::execution::ddl::checksum_group::ChecksumGroupTask;
+use crate::execution::ddl::close_vnode_files::CloseVnodeFilesTask;
 use crate::execution::ddl::compact_vnode::CompactVnodeTask;
 use crate::execution::ddl::copy_vnode::CopyVnodeTask;
 use crate::execution::ddl::create_database::CreateDatabaseTask;
+use crate::execution::ddl::create_scheduled_delete::CreateScheduledDeleteTask;
+use crate::execution::ddl::drop_continuous_query::DropContinuousQueryTask;
+use crate::execution::ddl::drop_scheduled_delete::DropScheduledDeleteTask;
 use crate::execution::ddl::drop_vnode::DropVnodeTask;
+use crate::execution::ddl::explain_compaction::ExplainCompactionTask;
+use crate::execution::ddl::explain_cost::ExplainCostTask;
+use crate::execution::ddl::export_vnode::ExportVnodeTask;
+use crate::execution::ddl::kill_compaction::KillCompactionTask;
+use crate::execution::ddl::merge_tag_value::MergeTagValueTask;
 use crate::execution::ddl::move_node::MoveVnodeTask;
+use crate::execution::ddl::repair_replica::RepairReplicaTask;
+use crate::execution::ddl::restore_database::RestoreDatabaseTask;
+use crate::execution::ddl::show_cardinality::ShowCardinalityTask;
+use crate::execution::ddl::show_compactions::ShowCompactionsTask;
+use crate::execution::ddl::show_continuous_queries::ShowContinuousQueriesTask;
+use crate::execution::ddl::show_jobs::ShowJobsTask;
+use crate::execution::ddl::show_shard_skew::ShowShardSkewTask;
+use crate::execution::ddl::show_tenant_quotas::ShowTenantQuotasTask;
+use crate::sql::redact::redact_statement;
 
 mod alter_database;
 mod alter_table;
 mod alter_tenant;
 mod alter_user;
+mod backup_database;
 mod checksum_group;
+mod close_vnode_files;
 mod compact_vnode;
 mod copy_vnode;
 mod create_database;
 mod create_external_table;
 mod create_role;
+mod create_scheduled_delete;
 mod create_stream_table;
 mod create_table;
 mod create_tenant;
 mod create_user;
+mod drop_continuous_query;
 mod drop_database_object;
 mod drop_global_object;
+mod drop_scheduled_delete;
 mod drop_tenant_object;
 mod drop_vnode;
+mod explain_compaction;
+mod explain_cost;
+mod export_vnode;
 mod grant_revoke;
+mod kill_compaction;
+mod merge_tag_value;
 mod move_node;
 mod recover_database;
 mod recover_tenant;
+mod repair_replica;
 mod replica_add;
 mod replica_destory;
 mod replica_promote;
 mod replica_remove;
+mod restore_database;
+mod show_cardinality;
+mod show_compactions;
+mod show_continuous_queries;
+mod show_jobs;
 mod show_replica;
+mod show_shard_skew;
+mod show_tenant_quotas;
 
 /// Traits that DDL tasks should implement
 #[async_trait]
@@ -123,7 +160,8 @@ impl QueryExecution for DDLExecution {
         let qsm = &self.query_state_machine;
         QueryInfo::new(
             qsm.query_id,
-            qsm.query.content().to_string(),
+            // Shown by SHOW QUERIES, the secrets of the DDL are redacted.
+            redact_statement(qsm.query.content()),
             *qsm.session.tenant_id(),
             qsm.session.tenant().to_string(),
             qsm.session.default_database().to_string(),
@@ -173,14 +211,68 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::AlterTable(sub_plan) => Box::new(AlterTableTask::new(sub_plan.clone())),
             DDLPlan::AlterTenant(sub_plan) => Box::new(AlterTenantTask::new(sub_plan.clone())),
             DDLPlan::AlterUser(sub_plan) => Box::new(AlterUserTask::new(sub_plan.clone())),
+            DDLPlan::MergeTagValue(sub_plan) => Box::new(MergeTagValueTask::new(sub_plan.clone())),
             DDLPlan::GrantRevoke(sub_plan) => Box::new(GrantRevokeTask::new(sub_plan.clone())),
             DDLPlan::DropVnode(sub_plan) => Box::new(DropVnodeTask::new(sub_plan.clone())),
             DDLPlan::CopyVnode(sub_plan) => Box::new(CopyVnodeTask::new(sub_plan.clone())),
             DDLPlan::MoveVnode(sub_plan) => Box::new(MoveVnodeTask::new(sub_plan.clone())),
             DDLPlan::CompactVnode(sub_plan) => Box::new(CompactVnodeTask::new(sub_plan.clone())),
+            DDLPlan::CloseVnodeFiles(sub_plan) => Box::new(CloseVnodeFilesTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::ExplainCompaction(sub_plan) => Box::new(ExplainCompactionTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::ShowCompactions => Box::new(ShowCompactionsTask::new(self.plan.schema())),
+            DDLPlan::KillCompaction(sub_plan) => {
+                Box::new(KillCompactionTask::new(sub_plan.clone()))
+            }
             DDLPlan::ChecksumGroup(sub_plan) => {
                 Box::new(ChecksumGroupTask::new(sub_plan.clone(), self.plan.schema()))
             }
+            DDLPlan::RepairReplica(sub_plan) => {
+                Box::new(RepairReplicaTask::new(sub_plan.clone(), self.plan.schema()))
+            }
+            DDLPlan::ExportVnode(sub_plan) => {
+                Box::new(ExportVnodeTask::new(sub_plan.clone(), self.plan.schema()))
+            }
+            DDLPlan::BackupDatabase(sub_plan) => Box::new(BackupDatabaseTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::RestoreDatabase(sub_plan) => Box::new(RestoreDatabaseTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::ShowCardinality(sub_plan) => Box::new(ShowCardinalityTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::ShowShardSkew(sub_plan) => {
+                Box::new(ShowShardSkewTask::new(sub_plan.clone(), self.plan.schema()))
+            }
+            DDLPlan::DropContinuousQuery(sub_plan) => {
+                Box::new(DropContinuousQueryTask::new(sub_plan.clone()))
+            }
+            DDLPlan::ShowContinuousQueries(sub_plan) => Box::new(ShowContinuousQueriesTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::ShowTenantQuotas => Box::new(ShowTenantQuotasTask::new(self.plan.schema())),
+            DDLPlan::CreateScheduledDelete(sub_plan) => {
+                Box::new(CreateScheduledDeleteTask::new(sub_plan.clone()))
+            }
+            DDLPlan::DropScheduledDelete(sub_plan) => {
+                Box::new(DropScheduledDeleteTask::new(sub_plan.clone()))
+            }
+            DDLPlan::ShowJobs(sub_plan) => {
+                Box::new(ShowJobsTask::new(sub_plan.clone(), self.plan.schema()))
+            }
+            DDLPlan::ExplainCost(sub_plan) => {
+                Box::new(ExplainCostTask::new(sub_plan.clone(), self.plan.schema()))
+            }
             DDLPlan::CreateStreamTable(sub_plan) => {
                 let checker = self.stream_checker_manager.checker(&sub_plan.stream_type);
 
@@ -196,9 +288,10 @@ impl DDLDefinitionTaskFactory {
             }
             DDLPlan::ReplicaAdd(sub_plan) => Box::new(ReplicaAddTask::new(sub_plan.clone())),
             DDLPlan::ReplicaRemove(sub_plan) => Box::new(ReplicaRemoveTask::new(sub_plan.clone())),
-            DDLPlan::ReplicaPromote(sub_plan) => {
-                Box::new(ReplicaPromoteTask::new(sub_plan.clone()))
-            }
+            DDLPlan::ReplicaPromote(sub_plan) => Box::new(ReplicaPromoteTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
         }
     }
 }
@@ -31,7 +31,12 @@ impl DDLDefinitionTask for MoveVnodeTask {
             .context(CoordinatorSnafu)?;
 
         let replica_id = vnode_all_info.repl_set_id;
-        let cmd_type = coordinator::ReplicationCmdType::AddRaftFollower(replica_id, node_id);
+        // The moved vnode keeps its role in the replica set.
+        let cmd_type = if vnode_all_info.learner {
+            coordinator::ReplicationCmdType::AddRaftLearner(replica_id, node_id)
+        } else {
+            coordinator::ReplicationCmdType::AddRaftFollower(replica_id, node_id)
+        };
         coord
             .replication_manager(tenant, cmd_type)
             .await
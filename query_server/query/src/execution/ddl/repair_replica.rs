@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::RepairReplica;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct RepairReplicaTask {
+    schema: SchemaRef,
+    stmt: RepairReplica,
+}
+
+impl RepairReplicaTask {
+    #[inline(always)]
+    pub fn new(stmt: RepairReplica, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for RepairReplicaTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let replication_set_id = self.stmt.replication_set_id;
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let repairs = coord
+            .repair_replica(tenant, replication_set_id)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(
+                    repairs.iter().map(|r| r.vnode_id),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    repairs.iter().map(|r| r.node_id),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    repairs.iter().map(|r| r.chunks),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    repairs.iter().map(|r| r.rows),
+                )),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -25,7 +25,11 @@ impl DDLDefinitionTask for ReplicaAddTask {
 
         let coord = query_state_machine.coord.clone();
 
-        let cmd_type = coordinator::ReplicationCmdType::AddRaftFollower(replica_id, node_id);
+        let cmd_type = if self.stmt.learner {
+            coordinator::ReplicationCmdType::AddRaftLearner(replica_id, node_id)
+        } else {
+            coordinator::ReplicationCmdType::AddRaftFollower(replica_id, node_id)
+        };
         coord
             .replication_manager(tenant, cmd_type)
             .await
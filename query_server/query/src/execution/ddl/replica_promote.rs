@@ -1,20 +1,26 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use coordinator::ReplicationCmdType;
+use datafusion::arrow::array::{UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
 use snafu::ResultExt;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::ReplicaPromote;
-use spi::{CoordinatorSnafu, QueryError, QueryResult};
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryError, QueryResult};
 
 use super::DDLDefinitionTask;
 
 pub struct ReplicaPromoteTask {
+    schema: SchemaRef,
     stmt: ReplicaPromote,
 }
 
 impl ReplicaPromoteTask {
     #[inline(always)]
-    pub fn new(stmt: ReplicaPromote) -> Self {
-        Self { stmt }
+    pub fn new(stmt: ReplicaPromote, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
     }
 }
 
@@ -30,19 +36,31 @@ impl DDLDefinitionTask for ReplicaPromoteTask {
             .await
             .context(CoordinatorSnafu)?;
 
-        if let Some(info) = all_info.replica_set.by_node_id(node_id) {
-            let cmd_type = ReplicationCmdType::PromoteLeader(replica_id, info.id);
-            coord
-                .replication_manager(tenant, cmd_type)
-                .await
-                .context(CoordinatorSnafu)?;
-
-            Ok(Output::Nil(()))
-        } else {
-            Err(QueryError::ReplicaNotFound {
+        let Some(info) = all_info.replica_set.by_node_id(node_id) else {
+            return Err(QueryError::ReplicaNotFound {
                 replica_id,
                 node_id,
-            })
-        }
+            });
+        };
+        let max_log_lag = coord.get_config().cluster.leader_transfer_max_log_lag;
+        let transfer = coord
+            .transfer_leader(tenant, replica_id, info.id, Some(max_log_lag))
+            .await
+            .context(CoordinatorSnafu)?;
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![transfer.replica_id])),
+                Arc::new(UInt32Array::from(vec![transfer.old_leader])),
+                Arc::new(UInt32Array::from(vec![transfer.new_leader])),
+                Arc::new(UInt64Array::from(vec![transfer.node_id])),
+                Arc::new(UInt64Array::from(vec![transfer.log_lag])),
+                Arc::new(UInt64Array::from(vec![transfer.elapsed.as_millis() as u64])),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
     }
 }
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{StringArray, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use models::schema::database_schema::DatabaseSchema;
+use models::schema::table_schema::TableSchema;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::RestoreDatabase;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{
+    ArrowSnafu, CoordinatorSnafu, MetaSnafu, ObjectStoreSnafu, QueryError, QueryResult,
+    SerdeJsonSnafu,
+};
+use trace::{info, warn};
+
+use super::backup_database::{
+    build_backup_object_store, sql_options_to_literal_map, vnode_backup_path, BackupManifest,
+    MANIFEST_FILE_NAME,
+};
+use super::DDLDefinitionTask;
+
+pub struct RestoreDatabaseTask {
+    schema: SchemaRef,
+    stmt: RestoreDatabase,
+}
+
+impl RestoreDatabaseTask {
+    #[inline(always)]
+    pub fn new(stmt: RestoreDatabase, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for RestoreDatabaseTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let RestoreDatabase {
+            path,
+            connection_options,
+        } = self.stmt.clone();
+        let tenant = query_state_machine.session.tenant();
+
+        let (object_store, prefix) = build_backup_object_store(&path, connection_options.clone())?;
+        let data = object_store
+            .get(&prefix.child(MANIFEST_FILE_NAME))
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?
+            .bytes()
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+        let manifest: BackupManifest = serde_json::from_slice(&data).context(SerdeJsonSnafu)?;
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        // The database is restored into the tenant of the session.
+        let database = manifest.database.database_name().to_string();
+        let db_schema = DatabaseSchema::new(
+            tenant,
+            &database,
+            manifest.database.options.clone(),
+            manifest.database.config.clone(),
+        );
+        let expired_time = db_schema.time_to_expired();
+        meta.create_db(db_schema).await.context(MetaSnafu)?;
+        for mut table in manifest.tables {
+            table.tenant = tenant.to_string();
+            meta.create_table(&TableSchema::TsKvTableSchema(Arc::new(table)))
+                .await
+                .context(MetaSnafu)?;
+        }
+
+        let coord = query_state_machine.coord.clone();
+        let options = sql_options_to_literal_map(connection_options);
+        let mut vnodes = 0_u64;
+        for bucket in manifest.buckets {
+            if bucket.end_time <= expired_time {
+                warn!(
+                    "Restore database {tenant}.{database}: skip expired bucket ({}, {})",
+                    bucket.start_time, bucket.end_time
+                );
+                continue;
+            }
+
+            // Vnodes of the new bucket get new ids, each of them is restored
+            // from the backup of the shard at the same position, so the series
+            // must be placed as the backup even if the database is altered.
+            let new_bucket = meta
+                .create_bucket_with_placement(&database, bucket.start_time, bucket.series_placement)
+                .await
+                .context(MetaSnafu)?;
+            if new_bucket.shard_group.len() != bucket.shards.len() {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "Bucket ({}, {}) has {} shards in the backup, but {} shards are created",
+                        bucket.start_time,
+                        bucket.end_time,
+                        bucket.shards.len(),
+                        new_bucket.shard_group.len()
+                    ),
+                });
+            }
+            for (backup, replica) in bucket.shards.iter().zip(new_bucket.shard_group.iter()) {
+                let backup_path = vnode_backup_path(&path, backup.snapshot.vnode_id);
+                for vnode in replica.vnodes.iter() {
+                    coord
+                        .restore_vnode(
+                            tenant,
+                            vnode.id,
+                            backup.clone(),
+                            backup_path.clone(),
+                            options.clone(),
+                        )
+                        .await
+                        .context(CoordinatorSnafu)?;
+                    vnodes += 1;
+                }
+            }
+        }
+        info!("Restore database {tenant}.{database} from {path}: {vnodes} vnodes");
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![database])),
+                Arc::new(UInt64Array::from(vec![vnodes])),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowCardinality;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{CoordinatorSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct ShowCardinalityTask {
+    schema: SchemaRef,
+    stmt: ShowCardinality,
+}
+
+impl ShowCardinalityTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowCardinality, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowCardinalityTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let batch = coord
+            .series_cardinality_top(tenant, &self.stmt.database, self.stmt.top_k)
+            .await
+            .context(CoordinatorSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
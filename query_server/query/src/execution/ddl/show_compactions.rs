@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{BooleanArray, StringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, CoordinatorSnafu, QueryResult};
+
+use super::show_replica::timestamp_to_string;
+use super::DDLDefinitionTask;
+
+/// List the running compaction jobs of the data nodes.
+pub struct ShowCompactionsTask {
+    schema: SchemaRef,
+}
+
+impl ShowCompactionsTask {
+    #[inline(always)]
+    pub fn new(schema: SchemaRef) -> Self {
+        Self { schema }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowCompactionsTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let jobs = query_state_machine
+            .coord
+            .compaction_jobs(tenant)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt64Array::from_iter_values(
+                    jobs.iter().map(|(node_id, _)| *node_id),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.job_id),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.owner.as_str()),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.vnode_id),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.compaction.as_str()),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.in_level),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.out_level),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.files_in),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.bytes_in),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.files_out),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    jobs.iter().map(|(_, j)| j.bytes_out),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    jobs.iter().map(|(_, j)| timestamp_to_string(j.start_time)),
+                )),
+                Arc::new(BooleanArray::from_iter(
+                    jobs.iter().map(|(_, j)| Some(j.cancelled)),
+                )),
+            ],
+        )
+        .context(ArrowSnafu)?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{StringArray, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowContinuousQueries;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, MetaSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct ShowContinuousQueriesTask {
+    schema: SchemaRef,
+    stmt: ShowContinuousQueries,
+}
+
+impl ShowContinuousQueriesTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowContinuousQueries, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowContinuousQueriesTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+        let queries = query_state_machine
+            .meta
+            .read_continuous_queries(tenant, &self.stmt.database)
+            .await
+            .context(MetaSnafu)?;
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(StringArray::from_iter_values(
+                    queries.iter().map(|q| q.name.as_str()),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    queries.iter().map(|q| q.target_table.as_str()),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    queries.iter().map(|q| format!("{:?}", q.every)),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    queries.iter().map(|q| format!("{:?}", q.for_range)),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    queries.iter().map(|q| q.query.as_str()),
+                )),
+                Arc::new(UInt64Array::from_iter_values(
+                    queries.iter().map(|q| q.node_id),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    queries.iter().map(|q| q.query_id.to_string()),
+                )),
+            ],
+        )
+        .context(ArrowSnafu)?;
+
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
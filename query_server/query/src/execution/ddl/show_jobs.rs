@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use datafusion::arrow::array::{BooleanBuilder, StringBuilder};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use models::schema::resource_info::{ResourceOperator, ResourceStatus};
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowJobs;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, MetaSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct ShowJobsTask {
+    schema: SchemaRef,
+    stmt: ShowJobs,
+}
+
+impl ShowJobsTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowJobs, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowJobsTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant_name = query_state_machine.session.tenant();
+        let resourceinfos = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant_name.to_string(),
+            })
+            .context(MetaSnafu)?
+            .read_resourceinfos()
+            .await
+            .context(MetaSnafu)?;
+
+        let mut name = StringBuilder::new();
+        let mut table = StringBuilder::new();
+        let mut cron = StringBuilder::new();
+        let mut older_than = StringBuilder::new();
+        let mut predicate = StringBuilder::new();
+        let mut status = StringBuilder::new();
+        let mut next_run_time = StringBuilder::new();
+        let mut run_time = StringBuilder::new();
+        let mut success = BooleanBuilder::new();
+        let mut comment = StringBuilder::new();
+
+        for resourceinfo in resourceinfos {
+            let ResourceOperator::ScheduledDelete(job) = resourceinfo.get_operator() else {
+                continue;
+            };
+            if job.tenant != tenant_name
+                || job.database != self.stmt.database
+                || *resourceinfo.get_status() == ResourceStatus::Cancel
+            {
+                continue;
+            }
+            let waiting = matches!(
+                resourceinfo.get_status(),
+                ResourceStatus::Schedule | ResourceStatus::Executing
+            );
+
+            // A row for each run in the history, or a row without run if never run.
+            let runs = job.history.iter().map(Some).collect::<Vec<_>>();
+            let runs = if runs.is_empty() { vec![None] } else { runs };
+            for run in runs {
+                name.append_value(&job.name);
+                table.append_value(&job.table);
+                cron.append_value(&job.cron);
+                older_than.append_option(job.older_than.map(|d| format!("{:?}", d)));
+                predicate.append_option(job.selection.as_deref());
+                status.append_value(resourceinfo.get_status().to_string());
+                next_run_time.append_option(waiting.then(|| format_time(resourceinfo.get_time())));
+                run_time.append_option(run.map(|r| format_time(r.time)));
+                success.append_option(run.map(|r| r.success));
+                comment.append_option(run.map(|r| r.comment.as_str()));
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(name.finish()),
+                Arc::new(table.finish()),
+                Arc::new(cron.finish()),
+                Arc::new(older_than.finish()),
+                Arc::new(predicate.finish()),
+                Arc::new(status.finish()),
+                Arc::new(next_run_time.finish()),
+                Arc::new(run_time.finish()),
+                Arc::new(success.finish()),
+                Arc::new(comment.finish()),
+            ],
+        )
+        .context(ArrowSnafu)?;
+
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
+
+fn format_time(nanos: i64) -> String {
+    Utc.timestamp_nanos(nanos)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
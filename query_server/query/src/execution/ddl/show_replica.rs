@@ -106,7 +106,7 @@ async fn show_replica(machine: QueryStateMachineRef) -> QueryResult<Output> {
     ))))
 }
 
-fn timestamp_to_string(nanos: i64) -> String {
+pub(super) fn timestamp_to_string(nanos: i64) -> String {
     if let Some(datetime) = chrono::NaiveDateTime::from_timestamp_nanos(nanos) {
         let utc_datetime = datetime.and_utc();
 
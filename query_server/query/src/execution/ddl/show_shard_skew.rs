@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use coordinator::ShardRows;
+use datafusion::arrow::array::{Float64Array, StringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowShardSkew;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{CoordinatorSnafu, QueryResult};
+
+use super::show_replica::timestamp_to_string;
+use super::DDLDefinitionTask;
+
+pub struct ShowShardSkewTask {
+    schema: SchemaRef,
+    stmt: ShowShardSkew,
+}
+
+impl ShowShardSkewTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowShardSkew, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowShardSkewTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let shards = query_state_machine
+            .coord
+            .shard_rows(tenant, &self.stmt.database)
+            .await
+            .context(CoordinatorSnafu)?;
+        let skews = shard_skews(&shards);
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(
+                    shards.iter().map(|s| s.bucket_id),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    shards.iter().map(|s| timestamp_to_string(s.start_time)),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    shards.iter().map(|s| timestamp_to_string(s.end_time)),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    shards.iter().map(|s| s.series_placement.to_string()),
+                )),
+                Arc::new(UInt32Array::from_iter_values(
+                    shards.iter().map(|s| s.replica_id),
+                )),
+                Arc::new(UInt64Array::from_iter(shards.iter().map(|s| s.rows))),
+                Arc::new(Float64Array::from(skews)),
+            ],
+        )?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
+
+/// Rows of each replication set divided by the average rows of the replication
+/// sets of its bucket, 1.0 if the rows are placed evenly.
+fn shard_skews(shards: &[ShardRows]) -> Vec<Option<f64>> {
+    let mut buckets: HashMap<u32, (u64, u64)> = HashMap::new();
+    for shard in shards {
+        if let Some(rows) = shard.rows {
+            let (total, count) = buckets.entry(shard.bucket_id).or_default();
+            *total += rows;
+            *count += 1;
+        }
+    }
+
+    shards
+        .iter()
+        .map(|shard| {
+            let rows = shard.rows?;
+            let (total, count) = buckets.get(&shard.bucket_id)?;
+            if *total == 0 {
+                return None;
+            }
+            Some(rows as f64 * *count as f64 / *total as f64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use coordinator::ShardRows;
+    use models::schema::database_schema::SeriesPlacement;
+
+    use super::shard_skews;
+
+    #[test]
+    fn test_shard_skews() {
+        let shard = |bucket_id, replica_id, rows| ShardRows {
+            bucket_id,
+            start_time: 0,
+            end_time: 0,
+            series_placement: SeriesPlacement::Modulo,
+            replica_id,
+            rows,
+        };
+        let shards = vec![
+            shard(1, 1, Some(300)),
+            shard(1, 2, Some(100)),
+            shard(1, 3, None),
+            shard(2, 4, Some(0)),
+        ];
+        assert_eq!(shard_skews(&shards), vec![Some(1.5), Some(0.5), None, None]);
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{StringArray, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ArrowSnafu, MetaSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+/// List the quotas of the tenant of the session and the number of the objects
+/// limited by them.
+pub struct ShowTenantQuotasTask {
+    schema: SchemaRef,
+}
+
+impl ShowTenantQuotasTask {
+    #[inline(always)]
+    pub fn new(schema: SchemaRef) -> Self {
+        Self { schema }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowTenantQuotasTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let tenant = query_state_machine.session.tenant();
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+        let quotas = client.tenant().options().quotas();
+
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(StringArray::from_iter_values(
+                    quotas.iter().map(|(quota, _)| quota.option_name()),
+                )),
+                Arc::new(UInt64Array::from_iter(
+                    quotas.iter().map(|(_, max)| max.map(|max| max as u64)),
+                )),
+                Arc::new(UInt64Array::from_iter(quotas.iter().map(|(quota, _)| {
+                    client.quota_usage(*quota).map(|used| used as u64)
+                }))),
+            ],
+        )
+        .context(ArrowSnafu)?;
+
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
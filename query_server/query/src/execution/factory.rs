@@ -13,9 +13,15 @@ use tskv::kv_option::QueryOptions;
 
 use super::dml::DMLExecution;
 use super::query::SqlQueryExecution;
+use super::stream::continuous_query::ContinuousQueryExecution;
+use super::stream::preview::StreamPreviewExecution;
 use super::stream::trigger::executor::{TriggerExecutorFactory, TriggerExecutorFactoryRef};
-use super::stream::{MicroBatchStreamExecutionBuilder, MicroBatchStreamExecutionDesc};
+use super::stream::{
+    MicroBatchStreamExecutionBuilder, MicroBatchStreamExecutionDesc, StreamOptions,
+};
 use super::sys::SystemExecution;
+use crate::audit::AuditLoggerRef;
+use crate::data_source::sink::create_stream_sink;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::execution::ddl::DDLExecution;
 use crate::extension::logical::plan_node::table_writer_merge::TableWriterMergePlanNode;
@@ -29,6 +35,7 @@ pub struct SqlQueryExecutionFactory {
     trigger_executor_factory: TriggerExecutorFactoryRef,
     runtime: Arc<DedicatedExecutor>,
     stream_checker_manager: StreamCheckerManagerRef,
+    audit_logger: AuditLoggerRef,
 }
 
 impl SqlQueryExecutionFactory {
@@ -39,6 +46,7 @@ impl SqlQueryExecutionFactory {
         query_tracker: Arc<QueryTracker>,
         stream_checker_manager: StreamCheckerManagerRef,
         config: Arc<QueryOptions>,
+        audit_logger: AuditLoggerRef,
     ) -> Self {
         // Only do periodic scheduling, no need for many threads
         let trigger_executor_runtime =
@@ -60,6 +68,7 @@ impl SqlQueryExecutionFactory {
             trigger_executor_factory,
             runtime,
             stream_checker_manager,
+            audit_logger,
         }
     }
 }
@@ -128,12 +137,62 @@ impl QueryExecutionFactory for SqlQueryExecutionFactory {
                 state_machine,
                 sys_plan,
                 self.query_tracker.clone(),
+                self.audit_logger.clone(),
             ))),
+            Plan::PreviewStream(preview) => Ok(Arc::new(StreamPreviewExecution::new(
+                state_machine,
+                preview,
+                self.scheduler.clone(),
+            ))),
+            Plan::CreateContinuousQuery(create) => Ok(Arc::new(ContinuousQueryExecution::new(
+                state_machine,
+                create,
+                self.optimizer.clone(),
+                self.scheduler.clone(),
+                self.trigger_executor_factory.clone(),
+                self.query_tracker.clone(),
+                self.runtime.clone(),
+            ))),
+            Plan::CreateStream(create) => {
+                let stream_providers = extract_stream_providers(&create.plan);
+                if stream_providers.is_empty() {
+                    return Err(QueryError::InvalidParam {
+                        reason: format!(
+                            "stream {} requires a query with a stream table as source",
+                            create.name
+                        ),
+                    });
+                }
+
+                let mut options: StreamOptions = state_machine.session.inner().config().into();
+                if let Some(trigger_interval) = create.trigger_interval {
+                    options.trigger_interval = trigger_interval;
+                }
+                let sink = create_stream_sink(&create.sink)?;
+                trace::info!("Start stream {} into {:?}", create.name, sink);
+
+                let exec = MicroBatchStreamExecutionBuilder::new(MicroBatchStreamExecutionDesc {
+                    plan: Arc::new(create.plan),
+                    options,
+                })
+                .with_name(create.name)
+                .with_stream_providers(stream_providers)
+                .with_sink(sink)
+                .build(
+                    state_machine,
+                    self.scheduler.clone(),
+                    self.trigger_executor_factory.clone(),
+                    self.runtime.clone(),
+                )
+                .await?;
+
+                Ok(Arc::new(exec))
+            }
         }
     }
 }
 
-fn is_dml(query_plan: &QueryPlan) -> bool {
+pub(crate) fn is_dml(query_plan: &QueryPlan) -> bool {
     match &query_plan.df_plan {
         LogicalPlan::Dml(_) => true,
         LogicalPlan::Extension(Extension { node }) => {
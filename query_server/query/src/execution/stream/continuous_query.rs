@@ -0,0 +1,416 @@
+//! Continuous queries, run an `INSERT INTO ... SELECT` periodically.
+//!
+//! Each run at time `now` reads the source tables in the time range
+//! `[end - FOR, end)`, `end` is `now` truncated to the `EVERY` interval.
+//! The definition is kept in meta, the running query is persisted as a stream
+//! query so that it restarts with the node. A run stops the query if its
+//! definition was dropped.
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::StringArray;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::datasource::source_as_provider;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{cast, lit, Expr, Filter, LogicalPlan, TableScan, TableSource};
+use datafusion::scalar::ScalarValue;
+use futures::TryStreamExt;
+use meta::error::MetaError;
+use models::runtime::executor::{DedicatedExecutor, Job};
+use models::schema::continuous_query::ContinuousQueryInfo;
+use models::schema::query_info::QueryInfo;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use models::utils::now_timestamp_nanos;
+use parking_lot::Mutex;
+use spi::query::config::StreamTriggerInterval;
+use spi::query::dispatcher::{QueryStatus, QueryStatusBuilder};
+use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef, QueryType};
+use spi::query::logical_planner::{CreateContinuousQuery, CreateStreamQuery, QueryPlan};
+use spi::query::optimizer::Optimizer;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::query::scheduler::SchedulerRef;
+use spi::{QueryError, QueryResult};
+use trace::{error, info};
+
+use super::trigger::executor::{TriggerExecutorFactoryRef, TriggerExecutorRef};
+use crate::data_source::batch::tskv::ClusterTable;
+use crate::data_source::table_source::{TableHandle, TableSourceAdapter};
+use crate::dispatcher::query_tracker::QueryTracker;
+
+pub struct ContinuousQueryExecution {
+    query_state_machine: QueryStateMachineRef,
+    create: Arc<CreateContinuousQuery>,
+    optimizer: Arc<dyn Optimizer + Send + Sync>,
+    scheduler: SchedulerRef,
+    trigger_executor: TriggerExecutorRef,
+    query_tracker: Arc<QueryTracker>,
+    runtime: Arc<DedicatedExecutor>,
+    abort_handle: Mutex<Option<Job<()>>>,
+}
+
+impl ContinuousQueryExecution {
+    pub fn new(
+        query_state_machine: QueryStateMachineRef,
+        create: CreateContinuousQuery,
+        optimizer: Arc<dyn Optimizer + Send + Sync>,
+        scheduler: SchedulerRef,
+        trigger_executor_factory: TriggerExecutorFactoryRef,
+        query_tracker: Arc<QueryTracker>,
+        runtime: Arc<DedicatedExecutor>,
+    ) -> Self {
+        let trigger_executor =
+            trigger_executor_factory.create(&StreamTriggerInterval::Interval(create.every));
+
+        Self {
+            query_state_machine,
+            create: Arc::new(create),
+            optimizer,
+            scheduler,
+            trigger_executor,
+            query_tracker,
+            runtime,
+            abort_handle: Mutex::new(None),
+        }
+    }
+
+    fn continuous_query_info(&self) -> ContinuousQueryInfo {
+        let qsm = &self.query_state_machine;
+        ContinuousQueryInfo {
+            name: self.create.name.clone(),
+            tenant: qsm.session.tenant().to_string(),
+            database: qsm.session.default_database().to_string(),
+            query_id: qsm.query_id,
+            node_id: qsm.coord.node_id(),
+            target_table: self.create.target_table.clone(),
+            query: self.create.query.clone(),
+            every: self.create.every,
+            for_range: self.create.for_range,
+        }
+    }
+
+    /// Save the definition of a new continuous query, returns false if it
+    /// exists already and `IF NOT EXISTS` is specified.
+    async fn register(&self) -> QueryResult<bool> {
+        let meta = self.query_state_machine.meta.clone();
+        match meta
+            .create_continuous_query(self.continuous_query_info())
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(MetaError::ContinuousQueryAlreadyExists { .. }) if self.create.if_not_exists => {
+                Ok(false)
+            }
+            Err(source) => Err(QueryError::Meta { source }),
+        }
+    }
+
+    fn run_continuous_query(&self) -> Job<()> {
+        self.query_state_machine.begin_schedule();
+
+        let query_state_machine = self.query_state_machine.clone();
+        let create = self.create.clone();
+        let optimizer = self.optimizer.clone();
+        let scheduler = self.scheduler.clone();
+        let query_tracker = self.query_tracker.clone();
+        let info = Arc::new(self.continuous_query_info());
+
+        self.trigger_executor.schedule(
+            move |_| {
+                let run = ContinuousQueryRun {
+                    query_state_machine: query_state_machine.clone(),
+                    create: create.clone(),
+                    optimizer: optimizer.clone(),
+                    scheduler: scheduler.clone(),
+                    query_tracker: query_tracker.clone(),
+                    info: info.clone(),
+                };
+
+                async move {
+                    run.execute().await.map_err(|err| {
+                        error!("Execute continuous query {} error: {err}", run.create.name);
+                        err
+                    })
+                }
+            },
+            self.runtime.clone(),
+        )
+    }
+}
+
+#[async_trait]
+impl QueryExecution for ContinuousQueryExecution {
+    fn query_type(&self) -> QueryType {
+        QueryType::Stream
+    }
+
+    async fn start(&self) -> QueryResult<Output> {
+        let query_id = self.query_state_machine.query_id;
+        if self.query_state_machine.query.context().is_old() {
+            // Restored on startup, the definition may be dropped meanwhile.
+            let exists = is_registered(&self.query_state_machine, &self.create.name).await?;
+            if !exists {
+                self.query_tracker.expire_query(&query_id);
+                return Ok(Output::Nil(()));
+            }
+        } else {
+            // Fails early if the runs can't restrict the time range.
+            restrict_time_range(&self.create.plan.df_plan, 0, 0)?;
+            match self.register().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.query_tracker.expire_query(&query_id);
+                    return Ok(Output::Nil(()));
+                }
+                Err(err) => {
+                    self.query_tracker.expire_query(&query_id);
+                    return Err(err);
+                }
+            }
+        }
+
+        info!(
+            "Start continuous query {} every {:?} for {:?}",
+            self.create.name, self.create.every, self.create.for_range
+        );
+        *self.abort_handle.lock() = Some(self.run_continuous_query());
+
+        let schema = CreateStreamQuery::schema();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec![query_id.to_string()]))],
+        )?;
+        Ok(Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(
+            schema,
+            vec![batch],
+        ))))
+    }
+
+    fn cancel(&self) -> QueryResult<()> {
+        trace::debug!(
+            "Cancel continuous query execution: query_id: {:?}, name: {}",
+            &self.query_state_machine.query_id,
+            self.create.name,
+        );
+
+        self.query_state_machine.cancel();
+        *self.abort_handle.lock() = None;
+
+        Ok(())
+    }
+
+    fn info(&self) -> QueryInfo {
+        let qsm = &self.query_state_machine;
+        QueryInfo::new(
+            qsm.query_id,
+            qsm.query.content().to_string(),
+            *qsm.session.tenant_id(),
+            qsm.session.tenant().to_string(),
+            qsm.session.default_database().to_string(),
+            qsm.session.user().clone(),
+            qsm.coord.node_id(),
+        )
+    }
+
+    fn status(&self) -> QueryStatus {
+        QueryStatusBuilder::new(
+            self.query_state_machine.state().clone(),
+            self.query_state_machine.duration(),
+        )
+        .with_processed_count(self.trigger_executor.processed_count())
+        .with_error_count(self.trigger_executor.error_count())
+        .build()
+    }
+
+    fn need_persist(&self) -> bool {
+        true
+    }
+}
+
+/// Whether the continuous query of `name` is defined and run by this query.
+async fn is_registered(
+    query_state_machine: &QueryStateMachineRef,
+    name: &str,
+) -> QueryResult<bool> {
+    let queries = query_state_machine
+        .meta
+        .read_continuous_queries(
+            query_state_machine.session.tenant(),
+            query_state_machine.session.default_database(),
+        )
+        .await
+        .map_err(|source| QueryError::Meta { source })?;
+
+    Ok(queries
+        .iter()
+        .any(|q| q.name == name && q.query_id == query_state_machine.query_id))
+}
+
+struct ContinuousQueryRun {
+    query_state_machine: QueryStateMachineRef,
+    create: Arc<CreateContinuousQuery>,
+    optimizer: Arc<dyn Optimizer + Send + Sync>,
+    scheduler: SchedulerRef,
+    query_tracker: Arc<QueryTracker>,
+    info: Arc<ContinuousQueryInfo>,
+}
+
+impl ContinuousQueryRun {
+    async fn execute(&self) -> QueryResult<()> {
+        let qsm = &self.query_state_machine;
+        if !is_registered(qsm, &self.create.name).await? {
+            info!("Stop dropped continuous query {}", self.create.name);
+            if let Some(query) = self.query_tracker.expire_query(&qsm.query_id) {
+                query.cancel()?;
+            }
+            return Ok(());
+        }
+
+        let (start, end) = self.info.time_range(now_timestamp_nanos());
+        trace::debug!(
+            "Run continuous query {} in [{}, {})",
+            self.create.name,
+            start,
+            end
+        );
+
+        let plan = QueryPlan {
+            df_plan: restrict_time_range(&self.create.plan.df_plan, start, end)?,
+            is_tag_scan: self.create.plan.is_tag_scan,
+        };
+        let physical_plan = self.optimizer.optimize(&plan, &qsm.session).await?;
+        let mut stream = self
+            .scheduler
+            .schedule(physical_plan, qsm.session.inner().task_ctx())
+            .await?
+            .stream();
+        while stream.try_next().await?.is_some() {}
+
+        Ok(())
+    }
+}
+
+/// The schema of the tskv table scanned from `source`, before or after the
+/// table scans are inlined.
+fn tskv_table_schema(source: &Arc<dyn TableSource>) -> Option<TskvTableSchemaRef> {
+    if let Some(adapter) = source.as_any().downcast_ref::<TableSourceAdapter>() {
+        return match adapter.table_handle() {
+            TableHandle::Tskv(table) => Some(table.table_schema()),
+            _ => None,
+        };
+    }
+
+    source_as_provider(source)
+        .ok()?
+        .as_any()
+        .downcast_ref::<ClusterTable>()
+        .map(|t| t.table_schema())
+}
+
+/// Filter the scans of the tskv tables by the time range `[start, end)` in nanoseconds,
+/// fails if the query doesn't scan a tskv table or a scan doesn't output its time column.
+pub(crate) fn restrict_time_range(
+    plan: &LogicalPlan,
+    start: i64,
+    end: i64,
+) -> QueryResult<LogicalPlan> {
+    let restricted = Cell::new(0_usize);
+    let plan = plan.clone().transform_up(&|plan| {
+        let LogicalPlan::TableScan(TableScan {
+            table_name,
+            source,
+            projected_schema,
+            ..
+        }) = &plan
+        else {
+            return Ok(Transformed::No(plan));
+        };
+        let Some(table) = tskv_table_schema(source) else {
+            return Ok(Transformed::No(plan));
+        };
+        let time_name = table.time_column().name;
+        let Ok(field) = projected_schema.field_with_unqualified_name(&time_name) else {
+            return Err(DataFusionError::Plan(format!(
+                "continuous query can't restrict the time range of {table_name} without its time column {time_name}"
+            )));
+        };
+
+        let time = Expr::Column(field.qualified_column());
+        let bound = |ts: i64| {
+            cast(
+                lit(ScalarValue::TimestampNanosecond(Some(ts), None)),
+                field.data_type().clone(),
+            )
+        };
+        let predicate = time.clone().gt_eq(bound(start)).and(time.lt(bound(end)));
+        let filter = Filter::try_new(predicate, Arc::new(plan))?;
+        restricted.set(restricted.get() + 1);
+
+        Ok(Transformed::Yes(LogicalPlan::Filter(filter)))
+    })?;
+
+    if restricted.get() == 0 {
+        return Err(QueryError::InvalidParam {
+            reason: "continuous query requires a query of tskv tables".to_string(),
+        });
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use coordinator::service_mock::MockCoordinator;
+    use datafusion::arrow::datatypes::TimeUnit;
+    use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder};
+    use meta::model::meta_tenant::TenantMeta;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+
+    use super::restrict_time_range;
+    use crate::data_source::batch::tskv::ClusterTable;
+    use crate::data_source::split;
+    use crate::data_source::table_source::TableSourceAdapter;
+
+    fn air_scan() -> LogicalPlan {
+        let mut schema = TskvTableSchema::default();
+        schema.name = "air".to_string();
+        schema.add_column(TableColumn::new_time_column(0, TimeUnit::Nanosecond));
+        schema.add_column(TableColumn::new_with_default(
+            "value".to_string(),
+            ColumnType::Field(ValueType::Integer),
+        ));
+        let table = Arc::new(ClusterTable::new(
+            Arc::new(MockCoordinator::default()),
+            split::default_split_manager_ref_only_for_test(),
+            Arc::new(TenantMeta::mock()),
+            Arc::new(schema),
+        ));
+        let source = TableSourceAdapter::try_new("air", "public", "air", table).unwrap();
+
+        LogicalPlanBuilder::scan("air", Arc::new(source), None)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_restrict_time_range() {
+        let plan = restrict_time_range(&air_scan(), 60, 120).unwrap();
+        let LogicalPlan::Filter(filter) = &plan else {
+            panic!("expected the filter of the time range, got {plan:?}");
+        };
+        assert!(matches!(filter.input.as_ref(), LogicalPlan::TableScan(_)));
+        let predicate = filter.predicate.to_string();
+        assert!(predicate.contains("air.time >="), "{predicate}");
+        assert!(predicate.contains("air.time <"), "{predicate}");
+
+        // Not silently ignored without a tskv table.
+        let plan = LogicalPlanBuilder::empty(true).build().unwrap();
+        assert!(restrict_time_range(&plan, 60, 120).is_err());
+    }
+}
@@ -1,6 +1,9 @@
+pub mod continuous_query;
+pub mod preview;
 pub mod trigger;
 
 use core::fmt::Debug;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -8,21 +11,26 @@ use datafusion::arrow::array::StringArray;
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::Result as DFResult;
-use datafusion::physical_plan::displayable;
+use datafusion::physical_plan::{displayable, ExecutionPlan};
 use datafusion::prelude::SessionConfig;
 use futures::TryStreamExt;
 use models::runtime::executor::{DedicatedExecutor, Job};
-use models::schema::query_info::QueryInfo;
+use models::schema::query_info::{QueryInfo, StreamQueryState};
 use parking_lot::Mutex;
+use snafu::ResultExt;
 use spi::query::config::StreamTriggerInterval;
-use spi::query::datasource::stream::StreamProviderRef;
+use spi::query::datasource::sink::StreamSinkRef;
+use spi::query::datasource::stream::{Offset, StreamProviderRef};
 use spi::query::dispatcher::{QueryStatus, QueryStatusBuilder};
-use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef, QueryType};
+use spi::query::execution::{
+    Output, QueryExecution, QueryState, QueryStateMachine, QueryStateMachineRef, QueryType,
+};
 use spi::query::logical_planner::QueryPlan;
 use spi::query::physical_planner::PhysicalPlanner;
 use spi::query::recordbatch::RecordBatchStreamWrapper;
 use spi::query::scheduler::SchedulerRef;
-use spi::QueryResult;
+use spi::query::session::SessionCtx;
+use spi::{MetaSnafu, QueryResult};
 use trace::error;
 
 use self::trigger::executor::{TriggerExecutorFactoryRef, TriggerExecutorRef};
@@ -71,21 +79,42 @@ pub struct MicroBatchStreamExecutionDesc {
 
 pub struct MicroBatchStreamExecutionBuilder {
     desc: MicroBatchStreamExecutionDesc,
+    name: Option<String>,
     stream_providers: Option<Vec<StreamProviderRef>>,
+    sink: Option<StreamSinkRef>,
 }
 
 impl MicroBatchStreamExecutionBuilder {
     pub fn new(desc: MicroBatchStreamExecutionDesc) -> Self {
         Self {
             desc,
+            name: None,
             stream_providers: None,
+            sink: None,
+        }
+    }
+
+    /// The name of the stream created by `CREATE STREAM`.
+    pub fn with_name(self, name: String) -> Self {
+        Self {
+            name: Some(name),
+            ..self
         }
     }
 
     pub fn with_stream_providers(self, stream_providers: Vec<StreamProviderRef>) -> Self {
         Self {
-            desc: self.desc,
             stream_providers: Some(stream_providers),
+            ..self
+        }
+    }
+
+    /// Write the output of each micro-batch to the sink,
+    /// otherwise the output is drained and dropped.
+    pub fn with_sink(self, sink: StreamSinkRef) -> Self {
+        Self {
+            sink: Some(sink),
+            ..self
         }
     }
 
@@ -116,15 +145,25 @@ impl MicroBatchStreamExecutionBuilder {
             .await?,
         );
 
+        // Resume from the offsets processed before the restart.
+        let offset_tracker = if query_state_machine.query.context().is_old() {
+            let offsets = recover_processed_offsets(&query_state_machine).await?;
+            OffsetTracker::with_processed_offsets(offsets)
+        } else {
+            OffsetTracker::new()
+        };
+
         Ok(MicroBatchStreamExecution {
             query_state_machine,
+            name: self.name,
             plan,
             stream_providers,
             scheduler,
             trigger_executor,
             watermark_tracker,
-            offset_tracker: Arc::new(OffsetTracker::new()),
+            offset_tracker: Arc::new(offset_tracker),
             state_store_factory: Arc::new(MemoryStateStoreFactory::default()),
+            sink: self.sink,
             runtime,
             abort_handle: Mutex::new(None),
         })
@@ -133,6 +172,7 @@ impl MicroBatchStreamExecutionBuilder {
 
 pub struct MicroBatchStreamExecution {
     query_state_machine: QueryStateMachineRef,
+    name: Option<String>,
     plan: Arc<QueryPlan>,
     stream_providers: Vec<StreamProviderRef>,
     scheduler: SchedulerRef,
@@ -140,6 +180,7 @@ pub struct MicroBatchStreamExecution {
     state_store_factory: Arc<MemoryStateStoreFactory>,
     watermark_tracker: WatermarkTrackerRef,
     offset_tracker: OffsetTrackerRef,
+    sink: Option<StreamSinkRef>,
     runtime: Arc<DedicatedExecutor>,
     abort_handle: Mutex<Option<Job<()>>>,
 }
@@ -152,6 +193,7 @@ impl MicroBatchStreamExecution {
         self.query_state_machine.begin_schedule();
 
         let query_state_machine = self.query_state_machine.clone();
+        let name = self.name.clone();
         let plan = self.plan.clone();
         let scheduler = self.scheduler.clone();
         let stream_providers = self.stream_providers.clone();
@@ -159,11 +201,13 @@ impl MicroBatchStreamExecution {
         let state_store_factory = self.state_store_factory.clone();
         let runtime = self.runtime.clone();
         let offset_tracker = self.offset_tracker.clone();
+        let sink = self.sink.clone();
 
         let result = self.trigger_executor.schedule(
             move |current_batch_id| {
                 let exec = IncrementalExecution {
                     query_state_machine: query_state_machine.clone(),
+                    name: name.clone(),
                     plan: plan.clone(),
                     scheduler: scheduler.clone(),
                     current_batch_id,
@@ -171,6 +215,7 @@ impl MicroBatchStreamExecution {
                     watermark_tracker: watermark_tracker.clone(),
                     state_store_factory: state_store_factory.clone(),
                     offset_tracker: offset_tracker.clone(),
+                    sink: sink.clone(),
                 };
 
                 async move {
@@ -187,6 +232,103 @@ impl MicroBatchStreamExecution {
     }
 }
 
+/// The persisted info of the stream query, with the offsets processed.
+fn stream_query_info(
+    qsm: &QueryStateMachine,
+    name: Option<String>,
+    offsets: HashMap<String, Offset>,
+) -> QueryInfo {
+    QueryInfo::new(
+        qsm.query_id,
+        qsm.query.content().to_string(),
+        *qsm.session.tenant_id(),
+        qsm.session.tenant().to_string(),
+        qsm.session.default_database().to_string(),
+        qsm.session.user().clone(),
+        qsm.coord.node_id(),
+    )
+    .with_stream(StreamQueryState { name, offsets })
+}
+
+/// The offsets processed by the persisted stream query before a restart.
+async fn recover_processed_offsets(
+    qsm: &QueryStateMachine,
+) -> QueryResult<HashMap<String, Offset>> {
+    let offsets = qsm
+        .coord
+        .meta_manager()
+        .read_queryinfos(qsm.coord.node_id())
+        .await
+        .context(MetaSnafu)?
+        .into_iter()
+        .find(|info| info.query_id() == qsm.query_id)
+        .and_then(|info| info.stream().map(|stream| stream.offsets.clone()))
+        .unwrap_or_default();
+
+    Ok(offsets)
+}
+
+/// Plan the query as a micro-batch, which scans the offset ranges of the stream sources.
+async fn create_micro_batch_plan<T>(
+    plan: &QueryPlan,
+    session: &SessionCtx,
+    available_offsets: HashMap<String, (Option<Offset>, Offset)>,
+    watermark_tracker: WatermarkTrackerRef,
+    current_watermark_ns: i64,
+    state_store_factory: Arc<T>,
+) -> QueryResult<Arc<dyn ExecutionPlan>>
+where
+    T: StateStoreFactory + Send + Sync + Debug + 'static,
+    T::SS: Send + Sync + Debug,
+{
+    let logical_optimizer = DefaultLogicalOptimizer::default();
+    let opt_plan = logical_optimizer.optimize(plan, session)?;
+    trace::debug!(
+        "Final stream optimized logical plan:\n{}",
+        opt_plan.display_indent_schema()
+    );
+
+    let mut phy_planner = DefaultPhysicalPlanner::default();
+    // 4. Traverse and replace the TableScan nodes in the execution plan according to the mapping from the data source to the offset range
+    trace::trace!(
+        "Traverse and replace the TableScan nodes in the execution plan according to the mapping from the data source to the offset range"
+    );
+    phy_planner.inject_physical_transform_rule(Arc::new(StreamScanPlanner::new(available_offsets)));
+    phy_planner.inject_physical_transform_rule(Arc::new(WatermarkPlanner::new(watermark_tracker)));
+
+    phy_planner.inject_optimizer_rule(Arc::new(AddStateStore::new(
+        current_watermark_ns,
+        state_store_factory,
+    )));
+
+    let exec_plan = phy_planner.create_physical_plan(&opt_plan, session).await?;
+    trace::debug!(
+        "Final stream physical plan:\nOutput partition count: {}\n{}\n",
+        exec_plan.output_partitioning().partition_count(),
+        displayable(exec_plan.as_ref()).indent(false)
+    );
+
+    Ok(exec_plan)
+}
+
+/// The offsets of the sources processed by the micro-batch of the ranges, after
+/// which the watermark of the stream is `watermark_ns`.
+fn processed_offsets(
+    stream_providers: &[StreamProviderRef],
+    available_offsets: &HashMap<String, (Option<Offset>, Offset)>,
+    watermark_ns: i64,
+) -> HashMap<String, Offset> {
+    stream_providers
+        .iter()
+        .filter_map(|s| {
+            let id = s.id();
+            let (_, end) = available_offsets.get(&id)?;
+            let offset = s.processed_offset(*end, watermark_ns);
+            Some((id, offset))
+        })
+        .collect()
+}
+
 async fn update_available_offsets(
     offset_tracker: OffsetTrackerRef,
     stream_providers: &[StreamProviderRef],
@@ -249,15 +391,10 @@ impl QueryExecution for MicroBatchStreamExecution {
     }
 
     fn info(&self) -> QueryInfo {
-        let qsm = &self.query_state_machine;
-        QueryInfo::new(
-            qsm.query_id,
-            qsm.query.content().to_string(),
-            *qsm.session.tenant_id(),
-            qsm.session.tenant().to_string(),
-            qsm.session.default_database().to_string(),
-            qsm.session.user().clone(),
-            qsm.coord.node_id(),
+        stream_query_info(
+            &self.query_state_machine,
+            self.name.clone(),
+            self.offset_tracker.processed_offsets(),
         )
     }
 
@@ -278,6 +415,7 @@ impl QueryExecution for MicroBatchStreamExecution {
 
 struct IncrementalExecution<T> {
     query_state_machine: QueryStateMachineRef,
+    name: Option<String>,
     plan: Arc<QueryPlan>,
     scheduler: SchedulerRef,
     current_batch_id: i64,
@@ -285,6 +423,7 @@ struct IncrementalExecution<T> {
     watermark_tracker: WatermarkTrackerRef,
     state_store_factory: Arc<T>,
     offset_tracker: OffsetTrackerRef,
+    sink: Option<StreamSinkRef>,
 }
 
 impl<T> IncrementalExecution<T>
@@ -319,35 +458,15 @@ where
             available_offsets,
         );
 
-        let logical_optimizer = DefaultLogicalOptimizer::default();
-        let opt_plan = logical_optimizer.optimize(&self.plan, session)?;
-        trace::debug!(
-            "Final stream optimized logical plan:\n{}",
-            opt_plan.display_indent_schema()
-        );
-
-        let mut phy_planner = DefaultPhysicalPlanner::default();
-        // 4. Traverse and replace the TableScan nodes in the execution plan according to the mapping from the data source to the offset range
-        trace::trace!(
-            "Traverse and replace the TableScan nodes in the execution plan according to the mapping from the data source to the offset range"
-        );
-        phy_planner
-            .inject_physical_transform_rule(Arc::new(StreamScanPlanner::new(available_offsets)));
-        phy_planner.inject_physical_transform_rule(Arc::new(WatermarkPlanner::new(
+        let exec_plan = create_micro_batch_plan(
+            &self.plan,
+            session,
+            available_offsets.clone(),
             self.watermark_tracker.clone(),
-        )));
-
-        phy_planner.inject_optimizer_rule(Arc::new(AddStateStore::new(
             current_watermark_ns,
             self.state_store_factory.clone(),
-        )));
-
-        let exec_plan = phy_planner.create_physical_plan(&opt_plan, session).await?;
-        trace::debug!(
-            "Final stream physical plan:\nOutput partition count: {}\n{}\n",
-            exec_plan.output_partitioning().partition_count(),
-            displayable(exec_plan.as_ref()).indent(false)
-        );
+        )
+        .await?;
 
         let mut stream = self
             .scheduler
@@ -355,18 +474,36 @@ where
             .await?
             .stream();
 
+        let mut outputs = vec![];
         while let Some(batch) = stream.try_next().await? {
             trace::trace!("Receive an item, num rows: {}", batch.num_rows());
+            if self.sink.is_some() && batch.num_rows() > 0 {
+                outputs.push(batch);
+            }
+        }
+
+        // 5. Write the output to the sink before the offsets are committed,
+        // a failed micro-batch is processed again by the next trigger (at-least-once)
+        if let Some(sink) = &self.sink {
+            if !outputs.is_empty() {
+                if let Err(err) = sink.write_batches(self.current_batch_id, &outputs).await {
+                    self.watermark_tracker
+                        .update_watermark(current_watermark_ns, 0);
+                    return Err(err);
+                }
+            }
         }
 
         // 6. Record the commit log after the execution is complete
         trace::trace!("Record the commit log after the execution is complete");
         let after_process_watermark_ns = self.watermark_tracker.current_watermark_ns();
+        let offsets = processed_offsets(
+            &self.stream_providers,
+            &available_offsets,
+            after_process_watermark_ns,
+        );
+        let offsets_advanced = self.offset_tracker.commit(offsets);
         if after_process_watermark_ns > current_watermark_ns {
-            // TODO here is for compatibility with unrealized functions of tskv, which needs to be modified later
-            // After processing a batch, the watermark is updated, then submit to offset_tracker
-            // If not updated, it means that the data has not been processed
-            self.offset_tracker.commit(after_process_watermark_ns);
             // Persist watermark, in order to load the last watermark when restoring
             self.watermark_tracker
                 .commit(
@@ -378,7 +515,30 @@ where
             self.watermark_tracker
                 .update_watermark(current_watermark_ns, 0);
         }
+        if offsets_advanced {
+            // Persist the offsets, in order to resume from them when restoring
+            self.persist_offsets().await?;
+        }
 
         Ok(())
     }
+
+    async fn persist_offsets(&self) -> QueryResult<()> {
+        let qsm = &self.query_state_machine;
+        // The info of a killed query is removed, don't write it back.
+        if matches!(qsm.state(), QueryState::DONE(_)) {
+            return Ok(());
+        }
+
+        let info = stream_query_info(
+            qsm,
+            self.name.clone(),
+            self.offset_tracker.processed_offsets(),
+        );
+        qsm.coord
+            .meta_manager()
+            .write_queryinfo(qsm.query_id.get(), info)
+            .await
+            .context(MetaSnafu)
+    }
 }
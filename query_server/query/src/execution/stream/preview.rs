@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::StringArray;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::logical_expr::{Extension, LogicalPlan};
+use futures::stream::AbortHandle;
+use futures::TryStreamExt;
+use models::schema::query_info::QueryInfo;
+use parking_lot::Mutex;
+use spi::query::datasource::stream::Offset;
+use spi::query::dispatcher::QueryStatus;
+use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef};
+use spi::query::logical_planner::{PreviewStream, QueryPlan};
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::query::scheduler::SchedulerRef;
+use spi::{QueryError, QueryResult};
+use trace::debug;
+
+use super::{create_micro_batch_plan, processed_offsets, update_available_offsets};
+use crate::extension::analyse::stream_checker::UnsupportedOperationChecker;
+use crate::extension::analyse::AnalyzerRule;
+use crate::extension::logical::plan_node::table_writer::TableWriterPlanNode;
+use crate::extension::logical::plan_node::table_writer_merge::TableWriterMergePlanNode;
+use crate::extension::logical::utils::extract_stream_providers;
+use crate::extension::utils::downcast_plan_node;
+use crate::stream::offset_tracker::OffsetTracker;
+use crate::stream::state_store::memory::MemoryStateStoreFactory;
+use crate::stream::watermark_tracker::WatermarkTracker;
+
+/// Executes exactly one micro-batch of a stream query against the current offsets.
+///
+/// The continuous query is not registered, the sink of `INSERT INTO ... SELECT` is not
+/// written, and neither offsets nor watermark are committed.
+pub struct StreamPreviewExecution {
+    query_state_machine: QueryStateMachineRef,
+    plan: QueryPlan,
+    scheduler: SchedulerRef,
+
+    abort_handle: Mutex<Option<AbortHandle>>,
+}
+
+impl StreamPreviewExecution {
+    pub fn new(
+        query_state_machine: QueryStateMachineRef,
+        plan: PreviewStream,
+        scheduler: SchedulerRef,
+    ) -> Self {
+        Self {
+            query_state_machine,
+            plan: plan.plan,
+            scheduler,
+            abort_handle: Mutex::new(None),
+        }
+    }
+
+    async fn start(&self) -> QueryResult<Output> {
+        let plan = QueryPlan {
+            df_plan: strip_table_writer(&self.plan.df_plan).clone(),
+            is_tag_scan: self.plan.is_tag_scan,
+        };
+
+        let stream_providers = extract_stream_providers(&plan);
+        if stream_providers.is_empty() {
+            return Err(QueryError::InvalidParam {
+                reason: "PREVIEW STREAM requires a query with a stream table as source".to_string(),
+            });
+        }
+        // valid plan
+        let _ = UnsupportedOperationChecker::default().analyze(&plan.df_plan)?;
+
+        let qsm = &self.query_state_machine;
+        let session = &qsm.session;
+
+        // 1. Collect the offsets currently available in the stream sources
+        let offset_tracker = Arc::new(OffsetTracker::new());
+        update_available_offsets(offset_tracker.clone(), &stream_providers).await?;
+        let available_offsets = offset_tracker.available_offsets();
+
+        let watermark_tracker = Arc::new(
+            WatermarkTracker::try_new(qsm.query_id, qsm.coord.clone(), session.clone(), false)
+                .await?,
+        );
+        let before_watermark_ns = watermark_tracker.current_watermark_ns();
+
+        // 2. Run one micro-batch, which is skipped if there is no new data
+        let mut batches = vec![];
+        if !available_offsets.is_empty() {
+            qsm.begin_optimize();
+            let exec_plan = create_micro_batch_plan(
+                &plan,
+                session,
+                available_offsets.clone(),
+                watermark_tracker.clone(),
+                before_watermark_ns,
+                Arc::new(MemoryStateStoreFactory::default()),
+            )
+            .await?;
+            qsm.end_optimize();
+
+            qsm.begin_schedule();
+            batches = self
+                .scheduler
+                .schedule(exec_plan, session.inner().task_ctx())
+                .await?
+                .stream()
+                .try_collect::<Vec<_>>()
+                .await?;
+        }
+        let after_watermark_ns = watermark_tracker.current_watermark_ns();
+
+        // 3. Describe the movements the micro-batch would have committed
+        let mut types = vec!["result".to_string()];
+        let mut contents = vec![pretty_format_batches(&batches)?.to_string()];
+
+        let offsets = processed_offsets(&stream_providers, &available_offsets, after_watermark_ns);
+        let mut sources = available_offsets.into_iter().collect::<Vec<_>>();
+        sources.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (id, (start, end)) in sources {
+            let commit = match offsets.get(&id) {
+                Some(&offset) if offset > start.map_or(Offset::MIN, |start| start - 1) => {
+                    offset.to_string()
+                }
+                _ => "none".to_string(),
+            };
+            types.push("offsets".to_string());
+            contents.push(format!(
+                "{id}: range [{}, {end}], commit {commit}",
+                display_offset(start),
+            ));
+        }
+
+        types.push("watermark".to_string());
+        contents.push(format!(
+            "{} -> {}",
+            display_offset(Some(before_watermark_ns)),
+            display_offset(Some(after_watermark_ns)),
+        ));
+
+        let schema = PreviewStream::schema();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(types)),
+                Arc::new(StringArray::from(contents)),
+            ],
+        )?;
+
+        Ok(Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(
+            schema,
+            vec![batch],
+        ))))
+    }
+}
+
+/// Remove the sink of `INSERT INTO ... SELECT`, so that the preview never writes data.
+fn strip_table_writer(plan: &LogicalPlan) -> &LogicalPlan {
+    match plan {
+        LogicalPlan::Dml(dml) => dml.input.as_ref(),
+        LogicalPlan::Extension(Extension { node }) => {
+            if let Some(merge) = downcast_plan_node::<TableWriterMergePlanNode>(node.as_ref()) {
+                return strip_table_writer(merge.input.as_ref());
+            }
+            if let Some(writer) = downcast_plan_node::<TableWriterPlanNode>(node.as_ref()) {
+                return writer.input.as_ref();
+            }
+            plan
+        }
+        _ => plan,
+    }
+}
+
+fn display_offset(offset: Option<Offset>) -> String {
+    match offset {
+        None | Some(Offset::MIN) => "earliest".to_string(),
+        Some(offset) => offset.to_string(),
+    }
+}
+
+#[async_trait]
+impl QueryExecution for StreamPreviewExecution {
+    async fn start(&self) -> QueryResult<Output> {
+        let (task, abort_handle) = futures::future::abortable(self.start());
+
+        {
+            *self.abort_handle.lock() = Some(abort_handle);
+        }
+
+        task.await.map_err(|_| QueryError::Cancel)?
+    }
+
+    fn cancel(&self) -> QueryResult<()> {
+        debug!(
+            "cancel stream preview execution: query_id: {:?}, sql: {}, state: {:?}",
+            &self.query_state_machine.query_id,
+            self.query_state_machine.query.content(),
+            self.query_state_machine.state()
+        );
+
+        // change state
+        self.query_state_machine.cancel();
+        // stop future task
+        if let Some(e) = self.abort_handle.lock().as_ref() {
+            e.abort()
+        };
+
+        Ok(())
+    }
+
+    fn info(&self) -> QueryInfo {
+        let qsm = &self.query_state_machine;
+        QueryInfo::new(
+            qsm.query_id,
+            qsm.query.content().to_string(),
+            *qsm.session.tenant_id(),
+            qsm.session.tenant().to_string(),
+            qsm.session.default_database().to_string(),
+            qsm.session.user().clone(),
+            qsm.coord.node_id(),
+        )
+    }
+
+    fn status(&self) -> QueryStatus {
+        QueryStatus::new(
+            self.query_state_machine.state().clone(),
+            self.query_state_machine.duration(),
+        )
+    }
+}
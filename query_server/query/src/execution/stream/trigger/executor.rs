@@ -1,10 +1,19 @@
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::Future;
 use models::runtime::executor::{DedicatedExecutor, Job};
 use spi::query::config::StreamTriggerInterval;
 use spi::QueryError;
+use tokio_retry::strategy::ExponentialBackoff;
+use tokio_retry::Retry;
+use trace::warn;
+
+/// Retries of a failed `TRIGGER ONCE` micro-batch, after 1s, 2s, 4s and 8s.
+fn once_retry_strategy() -> impl Iterator<Item = Duration> {
+    ExponentialBackoff::from_millis(2).factor(500).take(4)
+}
 
 pub type TriggerExecutorFactoryRef = Arc<TriggerExecutorFactory>;
 
@@ -57,12 +66,12 @@ impl TriggerExecutor {
 
         match self.trigger {
             StreamTriggerInterval::Once => self.runtime.spawn(async move {
-                match task(fetch_add_batch_id()).await {
-                    Ok(_) => {}
-                    Err(_) => {
-                        let _ = err_counter.fetch_add(1, Ordering::Relaxed);
-                    }
-                }
+                let _ = run_with_retry(
+                    || task(fetch_add_batch_id()),
+                    once_retry_strategy(),
+                    &err_counter,
+                )
+                .await;
                 let _ = processed_count.fetch_add(1, Ordering::Relaxed);
             }),
             StreamTriggerInterval::Interval(d) => self.runtime.spawn(async move {
@@ -90,3 +99,69 @@ impl TriggerExecutor {
         self.err_counter.load(Ordering::Relaxed)
     }
 }
+
+/// Run `task` until it succeeds, the failed attempts are counted by
+/// `err_counter` and retried after the delays of `strategy`.
+async fn run_with_retry<F, T>(
+    task: F,
+    strategy: impl Iterator<Item = Duration>,
+    err_counter: &AtomicU64,
+) -> Result<(), QueryError>
+where
+    F: Fn() -> T,
+    T: Future<Output = Result<(), QueryError>>,
+{
+    Retry::spawn(strategy, || async {
+        task().await.map_err(|err| {
+            let _ = err_counter.fetch_add(1, Ordering::Relaxed);
+            warn!("Execute stream micro-batch error: {err}, retry later");
+            err
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    use spi::QueryError;
+
+    use super::run_with_retry;
+
+    #[tokio::test]
+    async fn test_run_with_retry() {
+        let attempts = AtomicU64::new(0);
+        let err_counter = AtomicU64::new(0);
+        let task = || {
+            let attempt = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+            async move {
+                if attempt < 3 {
+                    Err(QueryError::Internal {
+                        reason: format!("attempt {attempt}"),
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+        };
+        let strategy = |retries| std::iter::repeat(Duration::from_millis(1)).take(retries);
+
+        // Succeeds at the third attempt.
+        run_with_retry(task, strategy(4), &err_counter)
+            .await
+            .unwrap();
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+        assert_eq!(err_counter.load(Ordering::Relaxed), 2);
+
+        // Gives up after the last retry.
+        attempts.store(0, Ordering::Relaxed);
+        err_counter.store(0, Ordering::Relaxed);
+        assert!(run_with_retry(task, strategy(1), &err_counter)
+            .await
+            .is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+        assert_eq!(err_counter.load(Ordering::Relaxed), 2);
+    }
+}
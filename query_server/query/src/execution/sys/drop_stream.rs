@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use spi::query::execution::{Output, QueryStateMachineRef, QueryType};
+use spi::{QueryError, QueryResult};
+
+use super::SystemTask;
+use crate::dispatcher::query_tracker::QueryTracker;
+
+pub struct DropStreamTask {
+    query_tracker: Arc<QueryTracker>,
+
+    name: String,
+    if_exist: bool,
+}
+
+impl DropStreamTask {
+    pub fn new(query_tracker: Arc<QueryTracker>, name: String, if_exist: bool) -> Self {
+        Self {
+            query_tracker,
+            name,
+            if_exist,
+        }
+    }
+}
+
+#[async_trait]
+impl SystemTask for DropStreamTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let session = &query_state_machine.session;
+        let streams = self
+            .query_tracker
+            .running_queries()
+            .into_iter()
+            .filter(|q| q.query_type() == QueryType::Stream)
+            .map(|q| q.info())
+            .filter(|info| {
+                info.tenant_id() == *session.tenant_id()
+                    && info.database_name() == session.default_database()
+                    && info
+                        .stream()
+                        .is_some_and(|s| s.name.as_deref() == Some(self.name.as_str()))
+            })
+            .collect::<Vec<_>>();
+
+        if streams.is_empty() && !self.if_exist {
+            return Err(QueryError::StreamNotFound {
+                name: self.name.clone(),
+            });
+        }
+
+        // Expiring the query removes it from the persisted queries,
+        // so it's not restored after a restart.
+        for info in streams {
+            if let Some(q) = self.query_tracker.expire_query(&info.query_id()) {
+                let _ = q.cancel();
+            }
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -1,4 +1,7 @@
+mod drop_stream;
 mod kill_query;
+mod set_audit;
+mod set_variable;
 
 use std::sync::Arc;
 
@@ -9,7 +12,11 @@ use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef};
 use spi::query::logical_planner::SYSPlan;
 use spi::QueryResult;
 
+use self::drop_stream::DropStreamTask;
 use self::kill_query::KillQueryTask;
+use self::set_audit::SetAuditTask;
+use self::set_variable::SetVariableTask;
+use crate::audit::AuditLoggerRef;
 use crate::dispatcher::query_tracker::QueryTracker;
 
 pub struct SystemExecution {
@@ -22,11 +29,13 @@ impl SystemExecution {
         state_machine: QueryStateMachineRef,
         plan: SYSPlan,
         query_tracker: Arc<QueryTracker>,
+        audit_logger: AuditLoggerRef,
     ) -> Self {
         Self {
             task_factory: SystemTaskFactory {
                 plan,
                 query_tracker,
+                audit_logger,
             },
             state_machine,
         }
@@ -86,6 +95,7 @@ trait SystemTask: Send + Sync {
 struct SystemTaskFactory {
     plan: SYSPlan,
     query_tracker: Arc<QueryTracker>,
+    audit_logger: AuditLoggerRef,
 }
 
 impl SystemTaskFactory {
@@ -94,6 +104,15 @@ impl SystemTaskFactory {
             SYSPlan::KillQuery(query_id) => {
                 Box::new(KillQueryTask::new(self.query_tracker.clone(), *query_id))
             }
+            SYSPlan::SetVariable(variable) => Box::new(SetVariableTask::new(variable.clone())),
+            SYSPlan::SetAudit(enabled) => {
+                Box::new(SetAuditTask::new(self.audit_logger.clone(), *enabled))
+            }
+            SYSPlan::DropStream { name, if_exist } => Box::new(DropStreamTask::new(
+                self.query_tracker.clone(),
+                name.clone(),
+                *if_exist,
+            )),
         }
     }
 }
@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::QueryResult;
+use trace::info;
+
+use super::SystemTask;
+use crate::audit::AuditLoggerRef;
+
+/// Enable or disable the audit of this node until it restarts.
+pub struct SetAuditTask {
+    audit_logger: AuditLoggerRef,
+    enabled: bool,
+}
+
+impl SetAuditTask {
+    pub fn new(audit_logger: AuditLoggerRef, enabled: bool) -> Self {
+        Self {
+            audit_logger,
+            enabled,
+        }
+    }
+}
+
+#[async_trait]
+impl SystemTask for SetAuditTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        info!(
+            "audit is {} by user {}",
+            if self.enabled { "enabled" } else { "disabled" },
+            query_state_machine.session.user().desc().name()
+        );
+        self.audit_logger.set_enabled(self.enabled);
+        Ok(Output::Nil(()))
+    }
+}
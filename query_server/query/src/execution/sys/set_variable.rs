@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use spi::query::config::SessionVariable;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::QueryResult;
+
+use super::SystemTask;
+
+/// Keep the variable in the session of the query, it's applied to the
+/// following queries of the session.
+pub struct SetVariableTask {
+    variable: SessionVariable,
+}
+
+impl SetVariableTask {
+    pub fn new(variable: SessionVariable) -> Self {
+        Self { variable }
+    }
+}
+
+#[async_trait]
+impl SystemTask for SetVariableTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        query_state_machine
+            .session
+            .set_variable(self.variable.clone())?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::DFSchemaRef;
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::expr::{self, BinaryExpr};
+use datafusion::logical_expr::utils::split_conjunction;
+use datafusion::logical_expr::{Extension, Join, JoinType, LogicalPlan, Operator};
+use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::prelude::Expr;
+
+use super::transform_time_window::{parse_duration_arg, simplify_expr};
+use crate::extension::expr::ASOF;
+use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
+
+/// Convert a join with [`ASOF`] in the ON clause to an [`AsofJoinNode`].
+///
+/// SELECT * FROM a LEFT JOIN b
+///   ON a.station = b.station AND asof(a.time, b.time, interval '1 minute')
+pub struct TransformAsofJoinRule;
+
+impl AnalyzerRule for TransformAsofJoinRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        plan.transform_up(&analyze_internal)
+    }
+
+    fn name(&self) -> &str {
+        "transform_asof_join"
+    }
+}
+
+fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    if let LogicalPlan::Join(join) = &plan {
+        if let Some(asof_join) = transform_join(join)? {
+            return Ok(Transformed::Yes(asof_join));
+        }
+    }
+
+    Ok(Transformed::No(plan))
+}
+
+fn is_asof(expr: &Expr) -> bool {
+    matches!(expr, Expr::ScalarUDF(expr::ScalarUDF {
+        fun,
+        ..
+    }) if fun.name == ASOF)
+}
+
+fn transform_join(join: &Join) -> Result<Option<LogicalPlan>> {
+    let filter = match &join.filter {
+        Some(filter) => filter,
+        None => return Ok(None),
+    };
+    let (asof, others): (Vec<&Expr>, Vec<&Expr>) = split_conjunction(filter)
+        .into_iter()
+        .partition(|e| is_asof(e));
+    let asof = match asof.as_slice() {
+        [] => return Ok(None),
+        [asof] => *asof,
+        _ => {
+            return Err(DataFusionError::Plan(format!(
+                "Only support a single {ASOF} in the ON clause"
+            )))
+        }
+    };
+    if !matches!(join.join_type, JoinType::Inner | JoinType::Left) {
+        return Err(DataFusionError::Plan(format!(
+            "{ASOF} only supports INNER JOIN and LEFT JOIN, but found {}",
+            join.join_type
+        )));
+    }
+
+    let left_schema = join.left.schema();
+    let right_schema = join.right.schema();
+    let mut on = join.on.clone();
+    for expr in others {
+        let pair = match expr {
+            Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::Eq,
+                right,
+            }) => sides(left, right, left_schema, right_schema),
+            _ => None,
+        };
+        match pair {
+            Some(pair) => on.push(pair),
+            None => {
+                return Err(DataFusionError::Plan(format!(
+                    "{ASOF} only supports the equal conditions between the columns of both sides, but found {expr}"
+                )))
+            }
+        }
+    }
+
+    let args = match asof {
+        Expr::ScalarUDF(expr::ScalarUDF { args, .. }) => args,
+        _ => unreachable!(),
+    };
+    let (left_time, right_time) = sides(&args[0], &args[1], left_schema, right_schema)
+        .ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "The arguments of {ASOF} must be the time columns of both sides, but found {} and {}",
+                args[0], args[1]
+            ))
+        })?;
+    let tolerance = match args.get(2) {
+        Some(tolerance) => {
+            let tolerance = simplify_expr(tolerance.clone(), join.schema.clone())?;
+            let tolerance = parse_duration_arg(&tolerance)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Some(tolerance.as_nanos() as i64)
+        }
+        None => None,
+    };
+
+    let node = AsofJoinNode {
+        left: join.left.clone(),
+        right: join.right.clone(),
+        on,
+        left_time,
+        right_time,
+        tolerance,
+        join_type: join.join_type,
+        schema: join.schema.clone(),
+    };
+
+    Ok(Some(LogicalPlan::Extension(Extension {
+        node: Arc::new(node),
+    })))
+}
+
+/// Returns the pair of the columns ordered by the sides of the join, or None
+/// if they are not the columns of both sides.
+fn sides(
+    a: &Expr,
+    b: &Expr,
+    left_schema: &DFSchemaRef,
+    right_schema: &DFSchemaRef,
+) -> Option<(Expr, Expr)> {
+    let is_column_of = |e: &Expr, schema: &DFSchemaRef| match e {
+        Expr::Column(c) => schema.index_of_column(c).is_ok(),
+        _ => false,
+    };
+    if is_column_of(a, left_schema) && is_column_of(b, right_schema) {
+        Some((a.clone(), b.clone()))
+    } else if is_column_of(b, left_schema) && is_column_of(a, right_schema) {
+        Some((b.clone(), a.clone()))
+    } else {
+        None
+    }
+}
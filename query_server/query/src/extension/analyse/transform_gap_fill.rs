@@ -0,0 +1,541 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{Column, DFSchemaRef};
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::expr::{self, Between, BinaryExpr, Cast, TryCast};
+use datafusion::logical_expr::expr_fn::date_bin;
+use datafusion::logical_expr::utils::split_conjunction;
+use datafusion::logical_expr::{
+    Aggregate, ExprSchemable, Extension, Filter, LogicalPlan, LogicalPlanBuilder, Operator,
+    Projection,
+};
+use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::prelude::{cast, lit, Expr};
+use datafusion::scalar::ScalarValue;
+use spi::QueryError;
+
+use super::transform_time_window::{
+    parse_duration_arg, parse_offset_arg, simplify_expr, valid_duration,
+};
+use crate::extension::expr::expr_fn::is_not_null;
+use crate::extension::expr::expr_rewriter::ExprReplacer;
+use crate::extension::expr::expr_utils::find_exprs_in_exprs_deeply_nested;
+use crate::extension::expr::{
+    bin_in, date_bin_udf, from_local_nanos, session_time_zone, to_local_nanos, FILL_VALUE,
+    INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL,
+};
+use crate::extension::logical::plan_node::gap_fill::{FillStrategy, GapFillNode, GapFillParams};
+use crate::extension::utils::downcast_plan_node;
+
+/// Convert the [`TIME_WINDOW_GAPFILL`] in GROUP BY to a [`GapFillNode`] over the
+/// aggregate, and [`LOCF`], [`INTERPOLATE`] or [`FILL_VALUE`] over the aggregates
+/// in the projection to the fill strategies of the node.
+pub struct TransformGapFillRule;
+
+impl AnalyzerRule for TransformGapFillRule {
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let time_zone = session_time_zone(config)?
+            .map(|tz| (tz, config.execution.time_zone.clone().unwrap_or_default()));
+        plan.transform_up(&|plan| analyze_internal(plan, time_zone.as_ref()))
+    }
+
+    fn name(&self) -> &str {
+        "transform_gap_fill"
+    }
+}
+
+/// `time_zone` is the time zone of the session and its name, None if it's UTC.
+fn analyze_internal(
+    plan: LogicalPlan,
+    time_zone: Option<&(Tz, String)>,
+) -> Result<Transformed<LogicalPlan>> {
+    match &plan {
+        LogicalPlan::Aggregate(aggregate) => {
+            if let Some(gap_fill) = transform_aggregate(aggregate, time_zone)? {
+                return Ok(Transformed::Yes(gap_fill));
+            }
+        }
+        LogicalPlan::Projection(projection) => {
+            if let Some(gap_fill) = find_gap_fill(&projection.input) {
+                return Ok(Transformed::Yes(transform_projection(
+                    projection, gap_fill,
+                )?));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(Transformed::No(plan))
+}
+
+/// The [`GapFillNode`] right below the projection, or below the filters of
+/// HAVING between them.
+fn find_gap_fill(plan: &LogicalPlan) -> Option<&GapFillNode> {
+    match plan {
+        LogicalPlan::Extension(Extension { node }) => {
+            downcast_plan_node::<GapFillNode>(node.as_ref())
+        }
+        LogicalPlan::Filter(filter) => find_gap_fill(&filter.input),
+        _ => None,
+    }
+}
+
+fn is_gap_fill(expr: &Expr) -> bool {
+    matches!(expr, Expr::ScalarUDF(expr::ScalarUDF {
+        fun,
+        ..
+    }) if fun.name == TIME_WINDOW_GAPFILL)
+}
+
+fn query_error(e: QueryError) -> DataFusionError {
+    DataFusionError::External(Box::new(e))
+}
+
+/// Aggregate(group by time_window_gapfill(...), ...)
+///
+/// =>
+///
+/// GapFill
+///   Sort(<group keys>, <window>)
+///     Aggregate(group by date_bin(...), ...)
+///       Filter(<time> IS NOT NULL)
+///
+/// The windows are binned in the local time of the session time zone.
+fn transform_aggregate(
+    aggregate: &Aggregate,
+    time_zone: Option<&(Tz, String)>,
+) -> Result<Option<LogicalPlan>> {
+    let tz = time_zone.map(|(tz, _)| *tz);
+    let positions = aggregate
+        .group_expr
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| is_gap_fill(e))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let window_index = match positions.as_slice() {
+        [] => return Ok(None),
+        [i] => *i,
+        _ => {
+            return Err(DataFusionError::Plan(format!(
+                "Only support a single {TIME_WINDOW_GAPFILL} in GROUP BY"
+            )))
+        }
+    };
+    if aggregate
+        .group_expr
+        .iter()
+        .any(|e| matches!(e, Expr::GroupingSet(_)))
+    {
+        return Err(DataFusionError::Plan(format!(
+            "{TIME_WINDOW_GAPFILL} does not support grouping sets"
+        )));
+    }
+
+    let window_expr = &aggregate.group_expr[window_index];
+    let (time_column, stride, origin) =
+        gap_fill_args(window_expr, aggregate.input.schema().clone(), tz.as_ref())?;
+
+    let ns_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+    let stride_arg = lit(ScalarValue::new_interval_mdn(0, 0, stride));
+    let time_arg = cast(time_column.clone(), ns_type.clone());
+    let window = match &tz {
+        Some(tz) => {
+            let origin = from_local_nanos(tz, origin)?;
+            date_bin_udf(*tz).call(vec![
+                stride_arg,
+                time_arg,
+                lit(ScalarValue::TimestampNanosecond(Some(origin), None)),
+            ])
+        }
+        None => date_bin(
+            stride_arg,
+            time_arg,
+            lit(ScalarValue::TimestampNanosecond(Some(origin), None)),
+        ),
+    };
+    let window = cast(window, ns_type).alias(window_expr.display_name()?);
+    let mut group_expr = aggregate.group_expr.clone();
+    group_expr[window_index] = window;
+
+    let input = LogicalPlanBuilder::from(aggregate.input.as_ref().clone())
+        .filter(is_not_null(time_column.clone()))?
+        .build()?;
+    let new_aggregate = LogicalPlan::Aggregate(Aggregate::try_new(
+        Arc::new(input),
+        group_expr,
+        aggregate.aggr_expr.clone(),
+    )?);
+
+    let columns = new_aggregate
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| Expr::Column(f.qualified_column()))
+        .collect::<Vec<_>>();
+    let (group_columns, aggr_columns) = columns.split_at(aggregate.group_expr.len());
+    let time_expr = group_columns[window_index].clone();
+    let group_expr = group_columns
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != window_index)
+        .map(|(_, e)| e.clone())
+        .collect::<Vec<_>>();
+
+    let sort_expr = group_expr
+        .iter()
+        .chain([&time_expr])
+        .map(|e| e.clone().sort(true, false))
+        .collect::<Vec<_>>();
+    let input = LogicalPlanBuilder::from(new_aggregate)
+        .sort(sort_expr)?
+        .build()?;
+
+    let (first_window, last_window) = time_range(&aggregate.input, &time_column)?;
+    let window_of = |t: i64| match &tz {
+        Some(tz) => bin_in(tz, stride, t, origin),
+        None => Ok(t - (t - origin).rem_euclid(stride)),
+    };
+    let node = GapFillNode {
+        input: Arc::new(input),
+        group_expr,
+        time_expr,
+        aggr_expr: aggr_columns.to_vec(),
+        fill_strategy: vec![FillStrategy::Null; aggr_columns.len()],
+        params: GapFillParams {
+            stride,
+            origin,
+            first_window: first_window.map(window_of).transpose()?,
+            last_window: last_window.map(window_of).transpose()?,
+            time_zone: time_zone.map(|(_, name)| name.clone()),
+        },
+    };
+
+    Ok(Some(LogicalPlan::Extension(Extension {
+        node: Arc::new(node),
+    })))
+}
+
+/// time_window_gapfill(time, window_duration[, slide_duration[, start_time]]),
+/// returns the time column, the stride and the origin in nanoseconds.
+///
+/// With a time zone the origin is in the local time of the zone, a start time
+/// given as an interval is an offset from the local epoch.
+fn gap_fill_args(expr: &Expr, schema: DFSchemaRef, tz: Option<&Tz>) -> Result<(Expr, i64, i64)> {
+    let invalid_signature =
+        || DataFusionError::Internal(format!("Invalid signature of {TIME_WINDOW_GAPFILL}"));
+    let args = match expr {
+        Expr::ScalarUDF(expr::ScalarUDF { args, .. }) => args,
+        _ => return Err(invalid_signature()),
+    };
+    let mut args = args.iter().cloned();
+
+    let time_column = args.next().ok_or_else(invalid_signature)?;
+    let window_duration = args.next().ok_or_else(invalid_signature)?;
+    let window_duration = simplify_expr(window_duration, schema.clone())?;
+    let window_duration =
+        valid_duration(parse_duration_arg(&window_duration).map_err(query_error)?)
+            .map_err(query_error)?;
+
+    if let Some(slide_duration) = args.next() {
+        let slide_duration = simplify_expr(slide_duration, schema.clone())?;
+        let slide_duration = parse_duration_arg(&slide_duration).map_err(query_error)?;
+        if slide_duration != window_duration {
+            return Err(DataFusionError::Plan(format!(
+                "{TIME_WINDOW_GAPFILL} only supports tumbling windows, the slide duration must be equal to the window duration"
+            )));
+        }
+    }
+
+    let origin = match args.next() {
+        Some(start_time) => match simplify_expr(start_time, schema)? {
+            offset @ Expr::Literal(
+                ScalarValue::IntervalYearMonth(_)
+                | ScalarValue::IntervalMonthDayNano(_)
+                | ScalarValue::IntervalDayTime(_),
+            ) => parse_offset_arg(&offset).map_err(query_error)?,
+            Expr::Literal(value) => {
+                let start_time = timestamp_nanos(&value).ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "The start time of {TIME_WINDOW_GAPFILL} must be a timestamp, but found {value}"
+                    ))
+                })?;
+                match tz {
+                    Some(tz) => to_local_nanos(tz, start_time)?,
+                    None => start_time,
+                }
+            }
+            other => {
+                return Err(DataFusionError::Plan(format!(
+                    "The start time of {TIME_WINDOW_GAPFILL} must be a constant, but found {other}"
+                )))
+            }
+        },
+        None => 0,
+    };
+
+    Ok((time_column, window_duration.as_nanos() as i64, origin))
+}
+
+fn timestamp_nanos(value: &ScalarValue) -> Option<i64> {
+    match value {
+        ScalarValue::TimestampSecond(Some(v), _) => v.checked_mul(1_000_000_000),
+        ScalarValue::TimestampMillisecond(Some(v), _) => v.checked_mul(1_000_000),
+        ScalarValue::TimestampMicrosecond(Some(v), _) => v.checked_mul(1_000),
+        ScalarValue::TimestampNanosecond(Some(v), _) => Some(*v),
+        _ => None,
+    }
+}
+
+fn column_of(expr: &Expr) -> Option<&Column> {
+    match expr {
+        Expr::Column(c) => Some(c),
+        Expr::Cast(Cast { expr, .. }) | Expr::TryCast(TryCast { expr, .. }) => column_of(expr),
+        _ => None,
+    }
+}
+
+/// The inclusive bounds of the time in the filters right below the aggregate.
+fn time_range(plan: &LogicalPlan, time_column: &Expr) -> Result<(Option<i64>, Option<i64>)> {
+    let time_column = match column_of(time_column) {
+        Some(c) => c,
+        None => return Ok((None, None)),
+    };
+    let is_time = |e: &Expr| column_of(e).map_or(false, |c| c.name == time_column.name);
+    let constant = |e: &Expr, schema: &DFSchemaRef| match simplify_expr(e.clone(), schema.clone()) {
+        Ok(Expr::Literal(v)) => timestamp_nanos(&v),
+        _ => None,
+    };
+
+    let mut lower: Option<i64> = None;
+    let mut upper: Option<i64> = None;
+    let mut plan = plan;
+    while let LogicalPlan::Filter(filter) = plan {
+        let schema = filter.input.schema();
+        for predicate in split_conjunction(&filter.predicate) {
+            let (op, value) = match predicate {
+                Expr::BinaryExpr(BinaryExpr { left, op, right }) if is_time(left) => {
+                    (*op, constant(right, schema))
+                }
+                Expr::BinaryExpr(BinaryExpr { left, op, right }) if is_time(right) => {
+                    match op.swap() {
+                        Some(op) => (op, constant(left, schema)),
+                        None => continue,
+                    }
+                }
+                Expr::Between(Between {
+                    expr,
+                    negated: false,
+                    low,
+                    high,
+                }) if is_time(expr) => {
+                    if let Some(low) = constant(low, schema) {
+                        lower = Some(lower.map_or(low, |l| l.max(low)));
+                    }
+                    (Operator::LtEq, constant(high, schema))
+                }
+                _ => continue,
+            };
+            match (op, value) {
+                (Operator::GtEq, Some(v)) => lower = Some(lower.map_or(v, |l| l.max(v))),
+                (Operator::Gt, Some(v)) => lower = Some(lower.map_or(v + 1, |l| l.max(v + 1))),
+                (Operator::LtEq, Some(v)) => upper = Some(upper.map_or(v, |u| u.min(v))),
+                (Operator::Lt, Some(v)) => upper = Some(upper.map_or(v - 1, |u| u.min(v - 1))),
+                _ => {}
+            }
+        }
+        plan = filter.input.as_ref();
+    }
+
+    Ok((lower, upper))
+}
+
+/// Projection(locf(<aggr>), interpolate(<aggr>), fill_value(<aggr>, <constant>))
+///   [Filter(<having>)]
+///     GapFill
+///
+/// =>
+///
+/// Projection(<aggr>, <aggr>, <aggr>)
+///   [Filter(<having>)]
+///     GapFill(<aggr>: previous, <aggr>: linear, <aggr>: <constant>)
+///
+/// The fill functions in HAVING are replaced as well, HAVING filters the rows
+/// after they are filled.
+fn transform_projection(projection: &Projection, gap_fill: &GapFillNode) -> Result<LogicalPlan> {
+    let schema = gap_fill.input.schema();
+    let mut exprs = projection.expr.clone();
+    let mut plan = projection.input.as_ref();
+    while let LogicalPlan::Filter(filter) = plan {
+        exprs.push(filter.predicate.clone());
+        plan = filter.input.as_ref();
+    }
+    let fill_exprs = find_exprs_in_exprs_deeply_nested(&exprs, &|e| {
+        matches!(e, Expr::ScalarUDF(expr::ScalarUDF {
+            fun,
+            ..
+        }) if [LOCF, INTERPOLATE, FILL_VALUE].contains(&fun.name.as_str()))
+    });
+    if fill_exprs.is_empty() {
+        return Ok(LogicalPlan::Projection(projection.clone()));
+    }
+
+    let mut node = gap_fill.clone();
+    let mut filled: Vec<(Expr, FillStrategy)> = Vec::with_capacity(fill_exprs.len());
+    for fill_expr in &fill_exprs {
+        let (aggr_expr, fill_strategy) = fill_strategy(fill_expr, schema)?;
+        if !gap_fill.aggr_expr.contains(&aggr_expr) {
+            return Err(DataFusionError::Plan(format!(
+                "{fill_expr} must be applied to an aggregate of the {TIME_WINDOW_GAPFILL} query"
+            )));
+        }
+        if let Some((_, other)) = filled.iter().find(|(e, _)| e == &aggr_expr) {
+            if other != &fill_strategy {
+                return Err(DataFusionError::Plan(format!(
+                    "{aggr_expr} can only be filled in one way, found {other} and {fill_strategy}"
+                )));
+            }
+        }
+        node = node.with_fill_strategy(&aggr_expr, fill_strategy.clone());
+        filled.push((aggr_expr, fill_strategy));
+    }
+
+    let replace_fill_expr = |e: &Expr| {
+        fill_exprs
+            .iter()
+            .position(|f| f == e)
+            .map(|i| filled[i].0.clone())
+    };
+    let replace = |e: &Expr| {
+        let mut replacer = ExprReplacer::new(&replace_fill_expr);
+        e.clone().rewrite(&mut replacer)
+    };
+    let exprs = projection
+        .expr
+        .iter()
+        .map(|e| {
+            let new_expr = replace(e)?;
+            match e {
+                Expr::Alias(_, _) => Ok(new_expr),
+                _ if &new_expr != e => Ok(new_expr.alias(e.display_name()?)),
+                _ => Ok(new_expr),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let input = replace_input(&projection.input, node, &replace)?;
+    Ok(LogicalPlan::Projection(Projection::try_new(
+        exprs,
+        Arc::new(input),
+    )?))
+}
+
+/// Replace the [`GapFillNode`] below the filters of HAVING with `node`, and the
+/// fill functions in the predicates of the filters.
+fn replace_input(
+    plan: &LogicalPlan,
+    node: GapFillNode,
+    replace: &impl Fn(&Expr) -> Result<Expr>,
+) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::Filter(filter) => Ok(LogicalPlan::Filter(Filter::try_new(
+            replace(&filter.predicate)?,
+            Arc::new(replace_input(&filter.input, node, replace)?),
+        )?)),
+        _ => Ok(LogicalPlan::Extension(Extension {
+            node: Arc::new(node),
+        })),
+    }
+}
+
+fn fill_strategy(fill_expr: &Expr, schema: &DFSchemaRef) -> Result<(Expr, FillStrategy)> {
+    let (name, args) = match fill_expr {
+        Expr::ScalarUDF(expr::ScalarUDF { fun, args }) => (fun.name.as_str(), args),
+        _ => {
+            return Err(DataFusionError::Internal(format!(
+                "Expected fill function, but found {fill_expr}"
+            )))
+        }
+    };
+    let aggr_expr = args.first().cloned().ok_or_else(|| {
+        DataFusionError::Plan(format!("Invalid arguments of {name}: {fill_expr}"))
+    })?;
+
+    let fill_strategy = match name {
+        LOCF => FillStrategy::Previous,
+        INTERPOLATE => FillStrategy::Linear,
+        _ => {
+            let value = match args
+                .get(1)
+                .map(|e| simplify_expr(e.clone(), schema.clone()))
+            {
+                Some(Ok(Expr::Literal(value))) => value,
+                _ => {
+                    return Err(DataFusionError::Plan(format!(
+                        "The value of {FILL_VALUE} must be a constant, found {fill_expr}"
+                    )))
+                }
+            };
+            let data_type = aggr_expr.get_type(schema)?;
+            FillStrategy::Constant(value.cast_to(&data_type)?)
+        }
+    };
+
+    Ok((aggr_expr, fill_strategy))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::logical_expr::logical_plan::table_scan;
+    use datafusion::logical_expr::LogicalPlan;
+    use datafusion::prelude::{col, lit, Expr};
+    use datafusion::scalar::ScalarValue;
+
+    use super::time_range;
+
+    #[test]
+    fn test_time_range() {
+        let schema = Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("a", DataType::Float64, true),
+        ]);
+        let ts = |v: i64| lit(ScalarValue::TimestampNanosecond(Some(v), None));
+        let scan = || table_scan(Some("t"), &schema, None).unwrap();
+        let range = |plan: LogicalPlan| time_range(&plan, &col("time")).unwrap();
+
+        let plan = scan()
+            .filter(col("time").gt_eq(ts(10)).and(col("a").gt(lit(1.0))))
+            .unwrap()
+            .filter(ts(100).gt(col("time")))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(range(plan), (Some(10), Some(99)));
+
+        let plan = scan()
+            .filter(col("time").between(ts(10), ts(100)))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(range(plan), (Some(10), Some(100)));
+
+        let plan = scan()
+            .filter(col("a").lt(lit(1.0)))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(range(plan), (None, None));
+
+        let plan = scan().build().unwrap();
+        assert_eq!(time_range(&plan, &Expr::Wildcard).unwrap(), (None, None));
+    }
+}
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use datafusion::arrow::array::timezone::Tz;
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 use datafusion::common::scalar::{dt_to_nano, mdn_to_nano, ym_to_nano};
 use datafusion::common::tree_node::{Transformed, TreeNode};
@@ -21,6 +22,7 @@ use trace::debug;
 use crate::extension::expr::expr_fn::{ge, is_not_null, lt, minus, modulo, multiply, plus};
 use crate::extension::expr::expr_utils::find_exprs_in_exprs_deeply_nested;
 use crate::extension::expr::{
+    from_local_nanos_udf, session_time_zone, to_local_nanos, to_local_nanos_udf,
     DEFAULT_TIME_WINDOW_START, TIME_WINDOW, WINDOW_COL_NAME, WINDOW_END, WINDOW_START,
 };
 use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
@@ -30,8 +32,9 @@ use crate::extension::logical::plan_node::LogicalPlanExt;
 pub struct TransformTimeWindowRule;
 
 impl AnalyzerRule for TransformTimeWindowRule {
-    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
-        plan.transform_up(&analyze_internal)
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let time_zone = session_time_zone(config)?;
+        plan.transform_up(&|plan| analyze_internal(plan, time_zone))
     }
 
     fn name(&self) -> &str {
@@ -39,7 +42,7 @@ impl AnalyzerRule for TransformTimeWindowRule {
     }
 }
 
-fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+fn analyze_internal(plan: LogicalPlan, time_zone: Option<Tz>) -> Result<Transformed<LogicalPlan>> {
     if plan.inputs().len() == 1 {
         let child = plan.inputs()[0];
         let child_project_exprs = expand_wildcard(child.schema().as_ref(), child, None)?;
@@ -52,7 +55,7 @@ fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
 
         if window_expressions.len() == 1 {
             let window_expr = window_expressions.remove(0);
-            let window = make_time_window(window_expr, plan.schema().clone())
+            let window = make_time_window(window_expr, plan.schema().clone(), time_zone)
                 .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
             debug!("Construct time window: {:?}", window);
@@ -101,7 +104,13 @@ fn find_window_exprs(plan: &LogicalPlan) -> Vec<Expr> {
     })
 }
 
-fn make_time_window(expr: Expr, schema: DFSchemaRef) -> Result<TimeWindow, QueryError> {
+/// The windows are computed in the local time of `time_zone` if it's set, so the
+/// start time given as an offset from the unix EPOCH is in the local time too.
+fn make_time_window(
+    expr: Expr,
+    schema: DFSchemaRef,
+    time_zone: Option<Tz>,
+) -> Result<TimeWindow, QueryError> {
     let window_alias = expr.display_name()?;
     match expr {
         Expr::ScalarUDF(expr::ScalarUDF { fun, args }) if fun.name == TIME_WINDOW => {
@@ -120,16 +129,54 @@ fn make_time_window(expr: Expr, schema: DFSchemaRef) -> Result<TimeWindow, Query
 
             let mut time_window_builder =
                 TimeWindowBuilder::new(window_alias, time_column, window_duration);
+            time_window_builder.with_time_zone(time_zone);
 
             // time_window(time, interval '10 seconds', interval '5 milliseconds')
             // third arg: slide_duration
             if let Some(slide_duration) = args.next() {
-                let slide_duration = simplify_expr(slide_duration, schema)?;
+                let slide_duration = simplify_expr(slide_duration, schema.clone())?;
                 let slide_duration = valid_duration(parse_duration_arg(&slide_duration)?)?;
                 time_window_builder.with_slide_duration(slide_duration);
 
-                args.next()
-                    .map(|start_time| time_window_builder.with_start_time(start_time));
+                // fourth arg: start_time, or the offset of start_time from unix EPOCH
+                if let Some(start_time) = args.next() {
+                    let start_time = match (simplify_expr(start_time, schema)?, time_zone) {
+                        (
+                            offset @ Expr::Literal(
+                                ScalarValue::IntervalYearMonth(_)
+                                | ScalarValue::IntervalMonthDayNano(_)
+                                | ScalarValue::IntervalDayTime(_),
+                            ),
+                            _,
+                        ) => Expr::Literal(ScalarValue::TimestampNanosecond(
+                            Some(parse_offset_arg(&offset)?),
+                            Some("+00:00".into()),
+                        )),
+                        (Expr::Literal(value), Some(tz)) => {
+                            let start_time = match value
+                                .cast_to(&DataType::Timestamp(TimeUnit::Nanosecond, None))?
+                            {
+                                ScalarValue::TimestampNanosecond(Some(v), _) => v,
+                                _ => {
+                                    return Err(QueryError::InvalidTimeWindowParam {
+                                        reason: format!(
+                                            "The start time must be a timestamp, but found {value}"
+                                        ),
+                                    })
+                                }
+                            };
+                            Expr::Literal(ScalarValue::TimestampNanosecond(
+                                Some(to_local_nanos(&tz, start_time)?),
+                                Some("+00:00".into()),
+                            ))
+                        }
+                        (start_time, Some(tz)) => {
+                            to_local_nanos_udf(tz).call(vec![cast(start_time, DataType::Int64)])
+                        }
+                        (start_time, None) => start_time,
+                    };
+                    time_window_builder.with_start_time(start_time);
+                }
             }
 
             Ok(time_window_builder.build())
@@ -140,7 +187,7 @@ fn make_time_window(expr: Expr, schema: DFSchemaRef) -> Result<TimeWindow, Query
     }
 }
 
-fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
+pub(super) fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
     if dur.as_millis() > (365 * DAY) as u128 || dur.as_millis() == 0 {
         return Err(QueryError::InvalidTimeWindowParam {
             reason: format!("Max duration is (0s, 365d], but found {}s", dur.as_secs()),
@@ -152,7 +199,7 @@ fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
 
 /// Convert string time duration to [`Duration`] \
 /// Only support [`ScalarValue::IntervalYearMonth`] | [`ScalarValue::IntervalMonthDayNano`] | [`ScalarValue::IntervalDayTime`]
-fn parse_duration_arg(expr: &Expr) -> Result<Duration, QueryError> {
+pub(super) fn parse_duration_arg(expr: &Expr) -> Result<Duration, QueryError> {
     let nano = match expr {
         Expr::Literal(ScalarValue::IntervalYearMonth(val)) => ym_to_nano(val),
         Expr::Literal(ScalarValue::IntervalMonthDayNano(val)) => mdn_to_nano(val),
@@ -171,6 +218,32 @@ fn parse_duration_arg(expr: &Expr) -> Result<Duration, QueryError> {
     Ok(Duration::from_nanos(duration as u64))
 }
 
+/// Convert interval to the signed nanoseconds, the offset may be negative \
+/// Only support [`ScalarValue::IntervalYearMonth`] | [`ScalarValue::IntervalMonthDayNano`] | [`ScalarValue::IntervalDayTime`]
+pub(super) fn parse_offset_arg(expr: &Expr) -> Result<i64, QueryError> {
+    let nano = match expr {
+        Expr::Literal(ScalarValue::IntervalYearMonth(val)) => ym_to_nano(val),
+        Expr::Literal(ScalarValue::IntervalMonthDayNano(val)) => mdn_to_nano(val),
+        Expr::Literal(ScalarValue::IntervalDayTime(val)) => dt_to_nano(val),
+        _ => {
+            return Err(QueryError::InvalidTimeWindowParam {
+                reason: format!("Expected interval, but found {expr}"),
+            })
+        }
+    };
+
+    let offset = nano.ok_or_else(|| QueryError::InvalidTimeWindowParam {
+        reason: format!("{expr}"),
+    })?;
+    if offset.unsigned_abs() > 365 * DAY * 1_000_000 {
+        return Err(QueryError::InvalidTimeWindowParam {
+            reason: format!("Max offset is [-365d, 365d], but found {}ns", offset),
+        });
+    }
+
+    Ok(offset)
+}
+
 #[derive(Debug)]
 pub struct TimeWindow {
     window_alias: String,
@@ -179,7 +252,9 @@ pub struct TimeWindow {
     window_duration: Duration,
     // interval
     slide_duration: Duration,
+    // in the local time of time_zone if it's set
     start_time: Expr,
+    time_zone: Option<Tz>,
 }
 
 impl TimeWindow {
@@ -196,6 +271,7 @@ impl TimeWindow {
             window_duration,
             slide_duration,
             start_time,
+            time_zone: None,
         }
     }
 
@@ -210,6 +286,7 @@ struct TimeWindowBuilder {
     window_duration: Duration,
     slide_duration: Option<Duration>,
     start_time: Expr,
+    time_zone: Option<Tz>,
 }
 
 impl TimeWindowBuilder {
@@ -221,6 +298,7 @@ impl TimeWindowBuilder {
             slide_duration: Default::default(),
             // Default to unix EPOCH
             start_time: Expr::Literal(DEFAULT_TIME_WINDOW_START.clone()),
+            time_zone: None,
         }
     }
 
@@ -234,6 +312,11 @@ impl TimeWindowBuilder {
         self
     }
 
+    pub fn with_time_zone(&mut self, time_zone: Option<Tz>) -> &mut Self {
+        self.time_zone = time_zone;
+        self
+    }
+
     pub fn build(self) -> TimeWindow {
         TimeWindow {
             window_alias: self.window_alias,
@@ -241,6 +324,7 @@ impl TimeWindowBuilder {
             window_duration: self.window_duration,
             slide_duration: self.slide_duration.unwrap_or(self.window_duration),
             start_time: self.start_time,
+            time_zone: self.time_zone,
         }
     }
 }
@@ -248,12 +332,16 @@ impl TimeWindowBuilder {
 /// Generate a window start expression(alias name [`WINDOW_START`])
 /// and a window end expression(alias name [`WINDOW_END`])
 /// based on the given [`TimeWindow`] parameter
+///
+/// With a time zone the windows are computed in the local time, and their start
+/// and end are converted back to UTC.
 pub fn make_window_expr(i: i64, window: &TimeWindow) -> Expr {
     let TimeWindow {
         time_column,
         window_duration,
         slide_duration,
         start_time,
+        time_zone,
         ..
     } = window;
 
@@ -267,6 +355,10 @@ pub fn make_window_expr(i: i64, window: &TimeWindow) -> Expr {
     // TODO may overflow
     // i64::MAX (9223372036854775807) => 2262-04-11 23:47:16.854775807
     let i64_time = cast(ns_time, DataType::Int64);
+    let i64_time = match time_zone {
+        Some(tz) => to_local_nanos_udf(*tz).call(vec![i64_time]),
+        None => i64_time,
+    };
     let i64_start_time = modulo(
         cast(start_time.clone(), DataType::Int64),
         window_duration.clone(),
@@ -282,6 +374,16 @@ pub fn make_window_expr(i: i64, window: &TimeWindow) -> Expr {
     );
     let window_start = minus(last_start, multiply(lit(i), slide_duration));
     let window_end = plus(window_start.clone(), window_duration);
+    let (window_start, window_end) = match time_zone {
+        Some(tz) => {
+            let from_local = from_local_nanos_udf(*tz);
+            (
+                from_local.call(vec![window_start]),
+                from_local.call(vec![window_end]),
+            )
+        }
+        None => (window_start, window_end),
+    };
 
     // Convert bigint to timestamp
     let window_start = cast(window_start, ns_type.clone());
@@ -407,7 +509,7 @@ fn replace_window_expr(new_expr: Expr, plan: &LogicalPlan) -> Result<LogicalPlan
     })
 }
 
-fn simplify_expr(expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
+pub(super) fn simplify_expr(expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
     let mut execution_props = ExecutionProps::new();
     let ctx = OptimizerContext::new();
     execution_props.query_execution_start_time = ctx.query_execution_start_time();
@@ -418,10 +520,31 @@ fn simplify_expr(expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
     use std::time::Duration;
 
+    use datafusion::arrow::array::timezone::Tz;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::common::DFSchema;
+    use datafusion::logical_expr::Expr;
+    use datafusion::prelude::{col, lit};
+    use datafusion::scalar::ScalarValue;
+
+    use super::{make_time_window, parse_offset_arg};
+    use crate::extension::expr::TIME_WINDOW_UDF;
     use crate::utils::duration::parse_duration;
 
+    #[test]
+    fn test_parse_offset_arg() {
+        let minutes =
+            |m: i64| Expr::Literal(ScalarValue::new_interval_mdn(0, 0, m * 60 * 1_000_000_000));
+        assert_eq!(parse_offset_arg(&minutes(30)).unwrap(), 1_800_000_000_000);
+        assert_eq!(parse_offset_arg(&minutes(-30)).unwrap(), -1_800_000_000_000);
+        assert!(parse_offset_arg(&minutes(366 * 24 * 60)).is_err());
+        assert!(parse_offset_arg(&Expr::Literal(ScalarValue::Int64(Some(1)))).is_err());
+    }
+
     #[test]
     fn test_parse_duration() {
         assert!(parse_duration("0.001ms").is_err());
@@ -435,4 +558,47 @@ mod tests {
             Duration::from_millis(6_000)
         );
     }
+
+    #[test]
+    fn test_time_window_start_time_in_time_zone() {
+        let schema = Schema::new(vec![Field::new(
+            "time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        )]);
+        let schema = Arc::new(DFSchema::try_from_qualified_schema("t", &schema).unwrap());
+        let hour = 3_600_000_000_000;
+        let window = |start_time: Expr, tz: Option<Tz>| {
+            let window_duration = lit(ScalarValue::new_interval_mdn(0, 0, 24 * hour));
+            let expr = TIME_WINDOW_UDF.call(vec![
+                col("time"),
+                window_duration.clone(),
+                window_duration,
+                start_time,
+            ]);
+            make_time_window(expr, schema.clone(), tz).unwrap()
+        };
+        let tz = Tz::from_str("+08:00").unwrap();
+
+        // A timestamp is converted to the local time.
+        let start_time = lit(ScalarValue::TimestampNanosecond(Some(0), None));
+        assert_eq!(
+            window(start_time.clone(), Some(tz)).start_time,
+            Expr::Literal(ScalarValue::TimestampNanosecond(
+                Some(8 * hour),
+                Some("+00:00".into())
+            ))
+        );
+        assert_eq!(window(start_time.clone(), None).start_time, start_time);
+
+        // An offset is from the local EPOCH.
+        let offset = lit(ScalarValue::new_interval_mdn(0, 0, hour));
+        assert_eq!(
+            window(offset, Some(tz)).start_time,
+            Expr::Literal(ScalarValue::TimestampNanosecond(
+                Some(hour),
+                Some("+00:00".into())
+            ))
+        );
+    }
 }
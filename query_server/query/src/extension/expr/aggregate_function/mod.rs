@@ -7,7 +7,9 @@ mod gauge;
 mod increase;
 mod last;
 mod mode;
+mod percentile_tdigest;
 mod sample;
+mod selector;
 mod state_agg;
 
 use std::sync::Arc;
@@ -27,6 +29,10 @@ pub const GAUGE_AGG_UDAF_NAME: &str = "gauge_agg";
 pub const FIRST_UDAF_NAME: &str = "first";
 pub const LAST_UDAF_NAME: &str = "last";
 pub const MODE_UDAF_NAME: &str = "mode";
+pub const MODE_MERGE_UDAF_NAME: &str = "mode_merge";
+pub const SELECTOR_FIRST_UDAF_NAME: &str = "selector_first";
+pub const SELECTOR_LAST_UDAF_NAME: &str = "selector_last";
+pub const PERCENTILE_TDIGEST_UDAF_NAME: &str = "percentile_tdigest";
 pub const INCREASE_NAME: &str = "increase";
 pub const COMPLETENESS_UDF_NAME: &str = "completeness";
 pub const CONSISTENCY_UDF_NAME: &str = "consistency";
@@ -34,6 +40,7 @@ pub const TIMELINESS_UDF_NAME: &str = "timeliness";
 pub const VALIDITY_UDF_NAME: &str = "validity";
 pub const EXACT_COUNT_UDAF_NAME: &str = "exact_count";
 pub use gauge::GaugeData;
+pub use mode::new_merge_udaf as new_mode_merge_udaf;
 pub use state_agg::StateAggData;
 
 pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
@@ -46,6 +53,8 @@ pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> QueryRe
     first::register_udaf(func_manager)?;
     last::register_udaf(func_manager)?;
     mode::register_udaf(func_manager)?;
+    selector::register_udafs(func_manager)?;
+    percentile_tdigest::register_udaf(func_manager)?;
     increase::register_udaf(func_manager)?;
     data_quality::register_udafs(func_manager)?;
     exact_count_agg::register_udaf(func_manager)?;
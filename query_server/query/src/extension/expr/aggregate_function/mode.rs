@@ -14,7 +14,7 @@ use datafusion::scalar::ScalarValue;
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 
-use crate::extension::expr::aggregate_function::MODE_UDAF_NAME;
+use crate::extension::expr::aggregate_function::{MODE_MERGE_UDAF_NAME, MODE_UDAF_NAME};
 
 pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<AggregateUDF> {
     let udf = new();
@@ -23,6 +23,18 @@ pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> QueryRes
 }
 
 fn new() -> AggregateUDF {
+    new_with_arity(MODE_UDAF_NAME, 1)
+}
+
+/// `mode_merge(value, count)` returns the value of the largest sum of counts,
+/// it merges the partial results of `mode` computed by the vnodes.
+///
+/// It's only planned by the optimizer, not registered as a function.
+pub fn new_merge_udaf() -> AggregateUDF {
+    new_with_arity(MODE_MERGE_UDAF_NAME, 2)
+}
+
+fn new_with_arity(name: &str, arg_count: usize) -> AggregateUDF {
     let return_type_func: ReturnTypeFunction =
         Arc::new(move |input| Ok(Arc::new(input[0].clone())));
 
@@ -37,8 +49,8 @@ fn new() -> AggregateUDF {
         Arc::new(|input, _| Ok(Box::new(ModeAccumulator::new(input[0].clone()))));
 
     AggregateUDF::new(
-        MODE_UDAF_NAME,
-        &Signature::any(1, Volatility::Immutable),
+        name,
+        &Signature::any(arg_count, Volatility::Immutable),
         &return_type_func,
         &accumulator,
         &state_type_func,
@@ -70,12 +82,17 @@ impl Accumulator for ModeAccumulator {
         }
 
         debug_assert!(
-            values.len() == 1,
-            "mode can only take 1 param, but found {}",
+            values.len() <= 2,
+            "mode can only take 1 param and the counts, but found {}",
             values.len()
         );
 
         let records = values[0].as_ref();
+        // The values are counted by the vnodes if merging the partial results.
+        let counts = match values.get(1) {
+            Some(counts) => Some(downcast_value!(counts.as_ref(), UInt32Array)),
+            None => None,
+        };
 
         let irer: Box<dyn Iterator<Item = usize>> = match records.nulls() {
             Some(null_buffer) => Box::new(null_buffer.valid_indices()),
@@ -84,7 +101,11 @@ impl Accumulator for ModeAccumulator {
 
         for i in irer {
             let scalar = ScalarValue::try_from_array(records, i)?;
-            self.map.entry(scalar).and_modify(|e| *e += 1).or_insert(1);
+            let count = counts.map_or(1, |c| c.value(i));
+            self.map
+                .entry(scalar)
+                .and_modify(|e| *e += count)
+                .or_insert(count);
         }
 
         Ok(())
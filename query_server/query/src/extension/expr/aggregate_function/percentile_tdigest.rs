@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::as_float64_array;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::NUMERICS;
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use datafusion::physical_expr::aggregate::tdigest::{TDigest, DEFAULT_MAX_SIZE};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::PERCENTILE_TDIGEST_UDAF_NAME;
+
+/// Number of the state fields of [`TDigest`]: max_size, sum, count, max, min
+/// and centroids.
+const TDIGEST_STATE_LEN: usize = 6;
+
+pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<AggregateUDF> {
+    let udf = new();
+    func_manager.register_udaf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+
+    // the state of the t-digest and the percentile
+    let state_type_func: StateTypeFunction = Arc::new(|_, _| {
+        Ok(Arc::new(vec![
+            DataType::UInt64,
+            DataType::Float64,
+            DataType::Float64,
+            DataType::Float64,
+            DataType::Float64,
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+            DataType::Float64,
+        ]))
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(|_, _| Ok(Box::<TDigestAccumulator>::default()));
+
+    // percentile_tdigest(
+    //     value NUMERIC,
+    //     percentile DOUBLE, in [0, 1]
+    //   )
+    let type_signatures = NUMERICS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Float64]))
+        .collect();
+
+    AggregateUDF::new(
+        PERCENTILE_TDIGEST_UDAF_NAME,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+/// Approximate percentile with a t-digest, the digests of the partitions are
+/// merged.
+#[derive(Debug)]
+struct TDigestAccumulator {
+    digest: TDigest,
+    /// The percentile, None if no value is aggregated yet
+    percentile: Option<f64>,
+}
+
+impl Default for TDigestAccumulator {
+    fn default() -> Self {
+        Self {
+            digest: TDigest::new(DEFAULT_MAX_SIZE),
+            percentile: None,
+        }
+    }
+}
+
+impl TDigestAccumulator {
+    fn set_percentile(&mut self, percentile: f64) -> DFResult<()> {
+        if !(0.0..=1.0).contains(&percentile) {
+            return Err(DataFusionError::Plan(format!(
+                "Percentile of {PERCENTILE_TDIGEST_UDAF_NAME} must be in [0, 1], but found {percentile}"
+            )));
+        }
+        self.percentile = Some(percentile);
+        Ok(())
+    }
+}
+
+impl Accumulator for TDigestAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        let numbers = cast(&values[0], &DataType::Float64)?;
+        let numbers = as_float64_array(&numbers)?
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if numbers.is_empty() {
+            return Ok(());
+        }
+
+        let percentiles = cast(&values[1], &DataType::Float64)?;
+        let percentile = as_float64_array(&percentiles)?
+            .iter()
+            .flatten()
+            .next()
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "Percentile of {PERCENTILE_TDIGEST_UDAF_NAME} must not be null"
+                ))
+            })?;
+        self.set_percentile(percentile)?;
+        self.digest = self.digest.merge_unsorted_f64(numbers);
+
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        if states.len() != TDIGEST_STATE_LEN + 1 {
+            return Err(DataFusionError::Internal(format!(
+                "{PERCENTILE_TDIGEST_UDAF_NAME} expects {} states, but found {}",
+                TDIGEST_STATE_LEN + 1,
+                states.len()
+            )));
+        }
+
+        let percentiles = as_float64_array(&states[TDIGEST_STATE_LEN])?;
+        let mut digests = vec![self.digest.clone()];
+        for row in 0..percentiles.len() {
+            // the state of an empty partition
+            if percentiles.is_null(row) {
+                continue;
+            }
+            self.set_percentile(percentiles.value(row))?;
+            let state = states[..TDIGEST_STATE_LEN]
+                .iter()
+                .map(|s| ScalarValue::try_from_array(s, row))
+                .collect::<DFResult<Vec<_>>>()?;
+            digests.push(TDigest::from_scalar_state(&state));
+        }
+        self.digest = TDigest::merge_digests(&digests);
+
+        Ok(())
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let mut state = self.digest.to_scalar_state();
+        state.push(ScalarValue::Float64(self.percentile));
+        Ok(state)
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        Ok(ScalarValue::Float64(
+            self.percentile.map(|p| self.digest.estimate_quantile(p)),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.digest.size() - std::mem::size_of_val(&self.digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array};
+    use datafusion::physical_plan::Accumulator;
+    use datafusion::scalar::ScalarValue;
+
+    use super::TDigestAccumulator;
+
+    fn percentile(p: f64, len: usize) -> ArrayRef {
+        Arc::new(Float64Array::from(vec![p; len]))
+    }
+
+    #[test]
+    fn test_percentile_tdigest() {
+        let mut acc_1 = TDigestAccumulator::default();
+        let mut acc_2 = TDigestAccumulator::default();
+        let empty = TDigestAccumulator::default();
+        assert_eq!(empty.evaluate().unwrap(), ScalarValue::Float64(None));
+
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(1..=50));
+        acc_1.update_batch(&[values, percentile(0.5, 50)]).unwrap();
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(51..=100));
+        acc_2.update_batch(&[values, percentile(0.5, 50)]).unwrap();
+
+        let states = [acc_2.state().unwrap(), empty.state().unwrap()];
+        let states = (0..states[0].len())
+            .map(|i| ScalarValue::iter_to_array(states.iter().map(|s| s[i].clone())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        acc_1.merge_batch(&states).unwrap();
+        match acc_1.evaluate().unwrap() {
+            ScalarValue::Float64(Some(median)) => assert!((median - 50.5).abs() <= 1.0),
+            other => panic!("unexpected median {other:?}"),
+        }
+
+        let mut invalid = TDigestAccumulator::default();
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(1..=2));
+        assert!(invalid.update_batch(&[values, percentile(1.5, 2)]).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::compute::{and, filter, is_not_null, sort_to_indices, SortOptions};
+use datafusion::arrow::datatypes::{DataType, Field, Fields};
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::type_coercion::aggregates::{
+    DATES, NUMERICS, STRINGS, TIMES, TIMESTAMPS,
+};
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::{QueryError, QueryResult};
+
+use super::{AggResult, TSPoint, SELECTOR_FIRST_UDAF_NAME, SELECTOR_LAST_UDAF_NAME};
+use crate::extension::expr::BINARYS;
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    func_manager.register_udaf(new(SELECTOR_FIRST_UDAF_NAME, true))?;
+    func_manager.register_udaf(new(SELECTOR_LAST_UDAF_NAME, false))?;
+    Ok(())
+}
+
+/// selector_first(time, value) and selector_last(time, value), return the
+/// earliest or the latest point as `struct(ts, val)`, unlike `first` and `last`
+/// the time of the value is kept.
+fn new(name: &str, first: bool) -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |input| {
+        Ok(Arc::new(DataType::Struct(Fields::from([
+            Arc::new(Field::new("ts", input[0].clone(), true)),
+            Arc::new(Field::new("val", input[1].clone(), true)),
+        ]))))
+    });
+
+    let state_type_func: StateTypeFunction = Arc::new(move |input, _| Ok(Arc::new(input.to_vec())));
+
+    let accumulator: AccumulatorFactoryFunction = Arc::new(move |input, _| {
+        Ok(Box::new(SelectorAccumulator::try_new(
+            input[0].clone(),
+            input[1].clone(),
+            first,
+        )?))
+    });
+
+    let type_signatures = STRINGS
+        .iter()
+        .chain(NUMERICS.iter())
+        .chain(TIMESTAMPS.iter())
+        .chain(DATES.iter())
+        .chain(BINARYS.iter())
+        .chain(TIMES.iter())
+        .flat_map(|t| {
+            TIMESTAMPS
+                .iter()
+                .map(|s_t| TypeSignature::Exact(vec![s_t.clone(), t.clone()]))
+        })
+        .collect();
+
+    AggregateUDF::new(
+        name,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+#[derive(Debug)]
+struct SelectorAccumulator {
+    point: TSPoint,
+    /// Select the earliest point if true, or the latest one
+    first: bool,
+}
+
+impl SelectorAccumulator {
+    fn try_new(time_data_type: DataType, value_data_type: DataType, first: bool) -> DFResult<Self> {
+        Ok(Self {
+            point: TSPoint::try_new_null(time_data_type, value_data_type)?,
+            first,
+        })
+    }
+
+    fn update_inner(&mut self, point: TSPoint) -> DFResult<()> {
+        if self.point.ts().is_null() {
+            self.point = point;
+            return Ok(());
+        }
+
+        let expected = if self.first {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        };
+        match point.ts().partial_cmp(self.point.ts()) {
+            Some(ordering) if ordering == expected => self.point = point,
+            Some(_) => {}
+            None => {
+                return Err(DataFusionError::External(Box::new(QueryError::Internal {
+                    reason: format!("cannot compare {:?} with {:?}", point.ts(), self.point.ts()),
+                })))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Accumulator for SelectorAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        if values.len() != 2 {
+            return Ok(());
+        }
+
+        // the points with null time or value are not selected
+        let valid = and(&is_not_null(&values[0])?, &is_not_null(&values[1])?)?;
+        let times = filter(&values[0], &valid)?;
+        let vals = filter(&values[1], &valid)?;
+
+        let sort_opts = SortOptions {
+            descending: !self.first,
+            nulls_first: false,
+        };
+        let indices = sort_to_indices(&times, Some(sort_opts), Some(1))?;
+        if !indices.is_empty() {
+            let idx = indices.value(0) as usize;
+            let ts = ScalarValue::try_from_array(&times, idx)?;
+            let val = ScalarValue::try_from_array(&vals, idx)?;
+            self.update_inner(TSPoint { ts, val })?;
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        self.point.clone().to_scalar()
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(self.point.ts())
+            + self.point.ts().size()
+            - std::mem::size_of_val(self.point.val())
+            + self.point.val().size()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        Ok(vec![self.point.ts().clone(), self.point.val().clone()])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        self.update_batch(states)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, TimeUnit};
+    use datafusion::physical_plan::Accumulator;
+    use datafusion::scalar::ScalarValue;
+
+    use super::SelectorAccumulator;
+    use crate::extension::expr::aggregate_function::TSPoint;
+
+    fn point(ts: i64, val: f64) -> TSPoint {
+        TSPoint {
+            ts: ScalarValue::TimestampNanosecond(Some(ts), None),
+            val: ScalarValue::Float64(Some(val)),
+        }
+    }
+
+    #[test]
+    fn test_selector() {
+        let time_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let times: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![
+            Some(3),
+            Some(1),
+            None,
+            Some(2),
+            Some(0),
+        ]));
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(3.0),
+            Some(1.0),
+            Some(9.0),
+            Some(2.0),
+            None,
+        ]));
+
+        let mut first =
+            SelectorAccumulator::try_new(time_type.clone(), DataType::Float64, true).unwrap();
+        first
+            .update_batch(&[times.clone(), values.clone()])
+            .unwrap();
+        assert_eq!(first.point, point(1, 1.0));
+
+        let mut last = SelectorAccumulator::try_new(time_type, DataType::Float64, false).unwrap();
+        last.update_batch(&[times, values]).unwrap();
+        assert_eq!(last.point, point(3, 3.0));
+
+        let to_arrays = |acc: &SelectorAccumulator| {
+            acc.state()
+                .unwrap()
+                .into_iter()
+                .map(|s| s.to_array())
+                .collect::<Vec<_>>()
+        };
+        let first_state = to_arrays(&first);
+        first.merge_batch(&to_arrays(&last)).unwrap();
+        last.merge_batch(&first_state).unwrap();
+        assert_eq!(last.point, point(3, 3.0));
+        assert_eq!(first.point, point(1, 1.0));
+    }
+}
@@ -47,6 +47,10 @@ impl<'a> FunctionMetadataManager for DFSessionContextFuncAdapter<'a> {
         Err(QueryError::FunctionExists { name: udwf.name })
     }
 
+    fn register_description(&mut self, _name: &str, _description: &str) -> QueryResult<()> {
+        Ok(())
+    }
+
     fn udf(&self, name: &str) -> QueryResult<Arc<ScalarUDF>> {
         self.ctx.udf(name).context(DatafusionSnafu)
     }
@@ -62,4 +66,19 @@ impl<'a> FunctionMetadataManager for DFSessionContextFuncAdapter<'a> {
     fn udfs(&self) -> HashSet<String> {
         self.ctx.udfs()
     }
+
+    fn functions(&self) -> Vec<FunctionMeta> {
+        let state = self.ctx.state();
+        state
+            .scalar_functions()
+            .values()
+            .map(|f| FunctionMeta::from_udf(f))
+            .chain(
+                state
+                    .aggregate_functions()
+                    .values()
+                    .map(|f| FunctionMeta::from_udaf(f)),
+            )
+            .collect()
+    }
 }
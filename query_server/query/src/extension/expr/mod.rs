@@ -10,10 +10,14 @@ mod session_function;
 mod ts_gen_func;
 mod window;
 
+pub use aggregate_function::{new_mode_merge_udaf, MODE_UDAF_NAME};
 use datafusion::arrow::datatypes::{DataType, IntervalUnit};
-pub use scalar_function::{INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL};
+pub use scalar_function::{ASOF, FILL_VALUE, INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL};
 pub use selector_function::{BOTTOM, TOPK};
-pub use session_function::register_session_udfs;
+pub use session_function::{
+    bin_in, date_bin_udf, from_local_nanos, from_local_nanos_udf, register_session_udfs,
+    session_time_zone, to_local_nanos, to_local_nanos_udf,
+};
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 pub use ts_gen_func::TSGenFunc;
@@ -51,5 +55,72 @@ pub fn load_all_functions(func_manager: &mut dyn FunctionMetadataManager) -> Que
     aggregate_function::register_udafs(func_manager)?;
     selector_function::register_selector_udfs(func_manager)?;
     window::register_window_udfs(func_manager)?;
+    register_descriptions(func_manager)?;
+    Ok(())
+}
+
+/// Descriptions of cnosdb's built-in functions, shown by `SHOW FUNCTIONS`.
+static FUNCTION_DESCRIPTIONS: &[(&str, &str)] = &[
+    // scalar functions
+    (TIME_WINDOW_GAPFILL, "Groups rows into time windows and fills the missing windows, used with locf, interpolate or fill_value"),
+    (LOCF, "Fills the missing value with the last observed value in time_window_gapfill"),
+    (INTERPOLATE, "Fills the missing value by linear interpolation in time_window_gapfill"),
+    (FILL_VALUE, "Fills the missing value with the given constant in time_window_gapfill"),
+    (ASOF, "Matches each row of the left side of a join with the latest row of the right side not after it, within the optional tolerance"),
+    (scalar_function::DURATION_IN, "Returns the duration that the state_agg stays in the given state"),
+    (scalar_function::STATE_AT, "Returns the state of the state_agg at the given time"),
+    ("delta", "Returns the change of the gauge_agg from the first value to the last value"),
+    ("time_delta", "Returns the duration between the first and the last point of the gauge_agg"),
+    ("rate", "Returns the rate of change of the gauge_agg, delta divided by time_delta"),
+    ("first_time", "Returns the time of the first point of the gauge_agg"),
+    ("first_val", "Returns the value of the first point of the gauge_agg"),
+    ("last_time", "Returns the time of the last point of the gauge_agg"),
+    ("last_val", "Returns the value of the last point of the gauge_agg"),
+    ("idelta_left", "Returns the instantaneous change at the left edge of the gauge_agg"),
+    ("idelta_right", "Returns the instantaneous change at the right edge of the gauge_agg"),
+    ("st_distance", "Returns the distance between two geometries in WKT"),
+    ("st_geomfromwkb", "Constructs a geometry in WKT from the well-known binary representation"),
+    ("st_asbinary", "Returns the well-known binary representation of a geometry in WKT"),
+    ("st_area", "Returns the area of a geometry in WKT"),
+    ("st_equals", "Returns true if two geometries are equal"),
+    ("st_contains", "Returns true if the first geometry contains the second geometry"),
+    ("st_intersects", "Returns true if two geometries intersect"),
+    ("st_disjoint", "Returns true if two geometries do not intersect"),
+    ("st_within", "Returns true if the first geometry is within the second geometry"),
+    ("timestamp_repair", "Repairs the timestamps of a series to be evenly spaced"),
+    ("value_fill", "Fills the null values of a series"),
+    ("value_repair", "Repairs the abnormal values of a series"),
+    (TIME_WINDOW, "Groups rows into tumbling or sliding time windows"),
+    // aggregate functions
+    (aggregate_function::SAMPLE_UDAF_NAME, "Returns a random sample of the values"),
+    (aggregate_function::STATE_AGG_UDAF_NAME, "Aggregates the states and their durations"),
+    (aggregate_function::COMPACT_STATE_AGG_UDAF_NAME, "Aggregates the durations of the states, without the time ranges"),
+    (aggregate_function::GAUGE_AGG_UDAF_NAME, "Aggregates the points of a gauge for delta, rate and other accessors"),
+    (aggregate_function::FIRST_UDAF_NAME, "Returns the value with the earliest time"),
+    (aggregate_function::LAST_UDAF_NAME, "Returns the value with the latest time"),
+    (aggregate_function::MODE_UDAF_NAME, "Returns the most frequent value"),
+    (aggregate_function::SELECTOR_FIRST_UDAF_NAME, "Returns the time and the value of the earliest point"),
+    (aggregate_function::SELECTOR_LAST_UDAF_NAME, "Returns the time and the value of the latest point"),
+    (aggregate_function::PERCENTILE_TDIGEST_UDAF_NAME, "Returns the approximate percentile of the values with a t-digest"),
+    (aggregate_function::INCREASE_NAME, "Returns the increase of a counter, handles counter resets"),
+    (aggregate_function::COMPLETENESS_UDF_NAME, "Returns the completeness of a series in data quality analysis"),
+    (aggregate_function::CONSISTENCY_UDF_NAME, "Returns the consistency of a series in data quality analysis"),
+    (aggregate_function::TIMELINESS_UDF_NAME, "Returns the timeliness of a series in data quality analysis"),
+    (aggregate_function::VALIDITY_UDF_NAME, "Returns the validity of a series in data quality analysis"),
+    (aggregate_function::EXACT_COUNT_UDAF_NAME, "Returns the exact number of rows, not using the statistics"),
+    // window functions
+    (window::DIFFERENCE_UDWF_NAME, "Returns the difference between the value and the previous value of the series"),
+    (window::DERIVATIVE_UDWF_NAME, "Returns the rate of change per second between the point and the previous point of the series"),
+    (window::NON_NEGATIVE_DERIVATIVE_UDWF_NAME, "Returns the non-negative rate of change per second between the point and the previous point of the series"),
+    (window::MOVING_AVERAGE_UDWF_NAME, "Returns the average of the last n values of the series"),
+    // selector functions
+    (TOPK, "Returns the rows with the k largest values"),
+    (BOTTOM, "Returns the rows with the k smallest values"),
+];
+
+fn register_descriptions(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    for (name, description) in FUNCTION_DESCRIPTIONS {
+        func_manager.register_description(name, description)?;
+    }
     Ok(())
 }
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{unimplemented_scalar_impl, ASOF};
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    // asof(
+    //     left_time TIMESTAMP,
+    //     right_time TIMESTAMP,
+    //     [tolerance INTERVAL]
+    //   )
+    // Only used in the ON clause of a join, turns the join into an ASOF JOIN.
+    let signature = Signature::one_of(
+        vec![TypeSignature::Any(2), TypeSignature::Any(3)],
+        Volatility::Volatile,
+    );
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
+    ScalarUDF::new(
+        ASOF,
+        &signature,
+        &return_type_fn,
+        &unimplemented_scalar_impl(ASOF),
+    )
+}
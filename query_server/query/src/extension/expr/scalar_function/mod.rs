@@ -1,6 +1,8 @@
+mod asof;
 mod duration_in;
 #[cfg(test)]
 mod example;
+mod fill_value;
 mod gapfill;
 mod gauge;
 mod gis;
@@ -21,8 +23,10 @@ use super::ts_gen_func::TSGenFunc;
 pub const TIME_WINDOW_GAPFILL: &str = "time_window_gapfill";
 pub const LOCF: &str = "locf";
 pub const INTERPOLATE: &str = "interpolate";
+pub const FILL_VALUE: &str = "fill_value";
 pub const DURATION_IN: &str = "duration_in";
 pub const STATE_AT: &str = "state_at";
+pub const ASOF: &str = "asof";
 
 pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
     // extend function...
@@ -31,9 +35,11 @@ pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryRes
     gapfill::register_udf(func_manager)?;
     locf::register_udf(func_manager)?;
     interpolate::register_udf(func_manager)?;
+    fill_value::register_udf(func_manager)?;
     gauge::register_udfs(func_manager)?;
     duration_in::register_udf(func_manager)?;
     state_at::register_udf(func_manager)?;
+    asof::register_udf(func_manager)?;
     gis::register_udfs(func_manager)?;
     TSGenFunc::register_all_udf(func_manager)?;
     Ok(())
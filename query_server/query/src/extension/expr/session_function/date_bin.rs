@@ -0,0 +1,262 @@
+//! `date_bin` in the time zone of the session.
+//!
+//! The builtin `date_bin` bins the timestamps in UTC, so the daily buckets of a
+//! session not in UTC start at the midnight of UTC rather than the local one.
+//! When the session sets a time zone, this function takes the place of the
+//! builtin one, the timestamps are binned in the local time of the zone and
+//! the start of each bucket is converted back to UTC, so that a day is 23 or 25
+//! hours long around the switches of daylight saving time.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{Duration, LocalResult, NaiveDateTime, TimeZone, Utc};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::array::{
+    Array, ArrayRef, IntervalDayTimeArray, IntervalMonthDayNanoArray, TimestampNanosecondArray,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{
+    DataType, IntervalDayTimeType, IntervalMonthDayNanoType, IntervalUnit, TimeUnit,
+};
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::execution::context::SessionContext;
+use datafusion::logical_expr::type_coercion::aggregates::TIMESTAMPS;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_plan::functions::make_scalar_function;
+use spi::service::protocol::Context;
+
+const DATE_BIN: &str = "date_bin";
+const NANOS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+pub fn register_session_udf(df_session_ctx: &SessionContext, context: &Context) {
+    let tz = match context.time_zone().map(Tz::from_str) {
+        Some(Ok(tz)) => tz,
+        _ => return,
+    };
+
+    df_session_ctx.register_udf(date_bin_udf(tz));
+}
+
+/// `date_bin` binning the timestamps in the local time of the zone.
+pub fn date_bin_udf(tz: Tz) -> ScalarUDF {
+    let date_bin = move |args: &[ArrayRef]| -> DFResult<ArrayRef> { date_bin_in(&tz, args) };
+    let return_type_fn: ReturnTypeFunction =
+        Arc::new(|_| Ok(Arc::new(DataType::Timestamp(TimeUnit::Nanosecond, None))));
+    ScalarUDF::new(
+        DATE_BIN,
+        &signature(),
+        &return_type_fn,
+        &make_scalar_function(date_bin),
+    )
+}
+
+/// date_bin(stride, source[, origin]), the same as the builtin `date_bin`.
+fn signature() -> Signature {
+    let strides = [
+        DataType::Interval(IntervalUnit::MonthDayNano),
+        DataType::Interval(IntervalUnit::DayTime),
+    ];
+    let type_signatures = strides
+        .iter()
+        .flat_map(|stride| {
+            TIMESTAMPS.iter().flat_map(move |source| {
+                [
+                    TypeSignature::Exact(vec![stride.clone(), source.clone()]),
+                    TypeSignature::Exact(vec![
+                        stride.clone(),
+                        source.clone(),
+                        DataType::Timestamp(TimeUnit::Nanosecond, None),
+                    ]),
+                ]
+            })
+        })
+        .collect();
+
+    Signature::one_of(type_signatures, Volatility::Immutable)
+}
+
+fn date_bin_in(tz: &Tz, args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let stride = stride_nanos(&args[0])?;
+    let source = cast(&args[1], &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+    let source = source
+        .as_any()
+        .downcast_ref::<TimestampNanosecondArray>()
+        .ok_or_else(|| DataFusionError::Internal("date_bin expects timestamps".to_string()))?;
+    // the origin is the local midnight of 1970-01-01 by default
+    let origin = match args.get(2) {
+        Some(origin) => {
+            let origin = cast(origin, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+            let origin = origin
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .filter(|o| !o.is_empty() && o.is_valid(0))
+                .ok_or_else(|| {
+                    DataFusionError::Execution("date_bin expects a constant origin".to_string())
+                })?;
+            to_local_nanos(tz, origin.value(0))?
+        }
+        None => 0,
+    };
+
+    let stride = match stride {
+        Some(stride) => stride,
+        None => return Ok(Arc::new(TimestampNanosecondArray::new_null(source.len()))),
+    };
+    let binned = source
+        .iter()
+        .map(|ts| ts.map(|ts| bin_in(tz, stride, ts, origin)).transpose())
+        .collect::<DFResult<TimestampNanosecondArray>>()?;
+
+    Ok(Arc::new(binned))
+}
+
+/// The stride in nanoseconds, the months are not supported as the builtin
+/// `date_bin`. Returns None if the stride is null.
+fn stride_nanos(stride: &ArrayRef) -> DFResult<Option<i64>> {
+    if stride.is_empty() || stride.is_null(0) {
+        return Ok(None);
+    }
+    let nanos = match stride.data_type() {
+        DataType::Interval(IntervalUnit::DayTime) => {
+            let value = stride
+                .as_any()
+                .downcast_ref::<IntervalDayTimeArray>()
+                .map(|a| a.value(0))
+                .unwrap_or_default();
+            let (days, millis) = IntervalDayTimeType::to_parts(value);
+            days as i64 * NANOS_PER_DAY + millis as i64 * 1_000_000
+        }
+        DataType::Interval(IntervalUnit::MonthDayNano) => {
+            let value = stride
+                .as_any()
+                .downcast_ref::<IntervalMonthDayNanoArray>()
+                .map(|a| a.value(0))
+                .unwrap_or_default();
+            let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(value);
+            if months != 0 {
+                return Err(DataFusionError::NotImplemented(
+                    "DATE_BIN stride does not support month intervals".to_string(),
+                ));
+            }
+            days as i64 * NANOS_PER_DAY + nanos
+        }
+        other => {
+            return Err(DataFusionError::Execution(format!(
+                "DATE_BIN expects stride argument to be an INTERVAL but got {other}"
+            )))
+        }
+    };
+    if nanos <= 0 {
+        return Err(DataFusionError::Execution(
+            "DATE_BIN stride must be greater than zero".to_string(),
+        ));
+    }
+
+    Ok(Some(nanos))
+}
+
+/// The local time of the timestamp in the zone, as nanoseconds since the
+/// local epoch.
+pub fn to_local_nanos(tz: &Tz, ts: i64) -> DFResult<i64> {
+    Utc.timestamp_nanos(ts)
+        .with_timezone(tz)
+        .naive_local()
+        .timestamp_nanos_opt()
+        .ok_or_else(|| DataFusionError::Execution(format!("timestamp {ts} out of range")))
+}
+
+/// The timestamp in UTC of the local time of the zone, the earlier one if the
+/// local time is ambiguous, and the switch of daylight saving time if the
+/// local time is skipped by it.
+pub fn from_local_nanos(tz: &Tz, local: i64) -> DFResult<i64> {
+    let out_of_range = || DataFusionError::Execution(format!("local time {local} out of range"));
+    let naive = NaiveDateTime::from_timestamp_opt(
+        local.div_euclid(1_000_000_000),
+        local.rem_euclid(1_000_000_000) as u32,
+    )
+    .ok_or_else(out_of_range)?;
+    let utc = match tz.from_local_datetime(&naive) {
+        LocalResult::Single(utc) => utc.naive_utc(),
+        LocalResult::Ambiguous(earliest, _) => earliest.naive_utc(),
+        LocalResult::None => (1..=24 * 60)
+            .find_map(|m| {
+                tz.from_local_datetime(&(naive + Duration::minutes(m)))
+                    .earliest()
+            })
+            .map(|switch| switch.naive_utc())
+            .ok_or_else(|| {
+                DataFusionError::Execution(format!("invalid local time {naive} in {tz:?}"))
+            })?,
+    };
+
+    utc.timestamp_nanos_opt().ok_or_else(out_of_range)
+}
+
+/// Bin the timestamp in the local time of the zone, `origin` is in the local
+/// time, returns the start of the bin in UTC.
+pub fn bin_in(tz: &Tz, stride: i64, ts: i64, origin: i64) -> DFResult<i64> {
+    let local = to_local_nanos(tz, ts)?;
+    from_local_nanos(tz, local - (local - origin).rem_euclid(stride))
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::DateTime;
+    use datafusion::arrow::array::timezone::Tz;
+
+    use super::{bin_in, NANOS_PER_DAY};
+
+    fn nanos(rfc3339: &str) -> i64 {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .timestamp_nanos_opt()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bin_in_fixed_offset() {
+        let tz = Tz::from_str("+08:00").unwrap();
+        let hour = 3_600_000_000_000;
+        assert_eq!(
+            bin_in(&tz, NANOS_PER_DAY, nanos("2023-06-01T20:30:00Z"), 0).unwrap(),
+            nanos("2023-06-02T00:00:00+08:00")
+        );
+        assert_eq!(
+            bin_in(&tz, NANOS_PER_DAY, nanos("2023-06-01T15:59:59Z"), 0).unwrap(),
+            nanos("2023-06-01T00:00:00+08:00")
+        );
+        assert_eq!(
+            bin_in(&tz, hour, nanos("2023-06-01T20:30:00Z"), 0).unwrap(),
+            nanos("2023-06-01T20:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_bin_in_daylight_saving_time() {
+        let tz = Tz::from_str("America/New_York").unwrap();
+        // 2023-03-12 is 23 hours long, 2023-11-05 is 25 hours long
+        assert_eq!(
+            bin_in(&tz, NANOS_PER_DAY, nanos("2023-03-12T23:00:00-04:00"), 0).unwrap(),
+            nanos("2023-03-12T00:00:00-05:00")
+        );
+        assert_eq!(
+            bin_in(&tz, NANOS_PER_DAY, nanos("2023-03-13T00:30:00-04:00"), 0).unwrap(),
+            nanos("2023-03-13T00:00:00-04:00")
+        );
+        assert_eq!(
+            bin_in(&tz, NANOS_PER_DAY, nanos("2023-11-05T23:30:00-05:00"), 0).unwrap(),
+            nanos("2023-11-05T00:00:00-04:00")
+        );
+        // 02:00 is skipped on 2023-03-12
+        let hour = 3_600_000_000_000;
+        assert_eq!(
+            bin_in(&tz, 2 * hour, nanos("2023-03-12T03:30:00-04:00"), 0).unwrap(),
+            nanos("2023-03-12T02:00:00-05:00")
+        );
+    }
+}
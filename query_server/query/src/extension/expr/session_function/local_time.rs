@@ -0,0 +1,132 @@
+//! Conversion of the timestamps between UTC and the local time of the time
+//! zone of the session, used to compute the time windows in the local time.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::array::{ArrayRef, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::as_int64_array;
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::execution::context::SessionContext;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_plan::functions::make_scalar_function;
+use spi::service::protocol::Context;
+
+use super::date_bin::{from_local_nanos, to_local_nanos};
+
+const TO_LOCAL_NANOS: &str = "to_local_nanos";
+const FROM_LOCAL_NANOS: &str = "from_local_nanos";
+
+pub fn register_session_udf(df_session_ctx: &SessionContext, context: &Context) {
+    let tz = match context.time_zone().map(Tz::from_str) {
+        Some(Ok(tz)) => tz,
+        _ => return,
+    };
+
+    df_session_ctx.register_udf(to_local_nanos_udf(tz));
+    df_session_ctx.register_udf(from_local_nanos_udf(tz));
+}
+
+/// The time zone of the session, None if it's UTC.
+pub fn session_time_zone(config: &ConfigOptions) -> DFResult<Option<Tz>> {
+    let tz = match config.execution.time_zone.as_deref() {
+        Some(tz) => Tz::from_str(tz)?,
+        None => return Ok(None),
+    };
+    // a zone without offsets all the year is UTC
+    let is_utc = [(2023, 1), (2023, 7)].iter().all(|&(year, month)| {
+        Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0)
+            .single()
+            .map_or(false, |t| {
+                t.with_timezone(&tz).naive_local() == t.naive_utc()
+            })
+    });
+
+    Ok((!is_utc).then_some(tz))
+}
+
+/// to_local_nanos(<nanoseconds in UTC>), the local time of the zone as
+/// nanoseconds since the local epoch.
+pub fn to_local_nanos_udf(tz: Tz) -> ScalarUDF {
+    convert_udf(TO_LOCAL_NANOS, move |ts| to_local_nanos(&tz, ts))
+}
+
+/// from_local_nanos(<nanoseconds of the local time>), the timestamp in UTC of
+/// the local time of the zone.
+pub fn from_local_nanos_udf(tz: Tz) -> ScalarUDF {
+    convert_udf(FROM_LOCAL_NANOS, move |local| from_local_nanos(&tz, local))
+}
+
+fn convert_udf(
+    name: &str,
+    convert: impl Fn(i64) -> DFResult<i64> + Send + Sync + 'static,
+) -> ScalarUDF {
+    let fun = move |args: &[ArrayRef]| -> DFResult<ArrayRef> {
+        let values = args.first().ok_or_else(|| {
+            DataFusionError::Internal("expects a nanoseconds argument".to_string())
+        })?;
+        let converted = as_int64_array(values)?
+            .iter()
+            .map(|v| v.map(&convert).transpose())
+            .collect::<DFResult<Int64Array>>()?;
+        Ok(Arc::new(converted))
+    };
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+    ScalarUDF::new(
+        name,
+        &Signature::exact(vec![DataType::Int64], Volatility::Immutable),
+        &return_type_fn,
+        &make_scalar_function(fun),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::timezone::Tz;
+    use datafusion::arrow::array::{ArrayRef, Int64Array};
+    use datafusion::common::cast::as_int64_array;
+    use datafusion::config::ConfigOptions;
+    use datafusion::logical_expr::{ColumnarValue, ScalarUDF};
+
+    use super::{from_local_nanos_udf, session_time_zone, to_local_nanos_udf};
+
+    #[test]
+    fn test_session_time_zone() {
+        let mut config = ConfigOptions::new();
+        for utc in [None, Some("+00:00"), Some("UTC")] {
+            config.execution.time_zone = utc.map(str::to_string);
+            assert!(session_time_zone(&config).unwrap().is_none());
+        }
+        for tz in ["+08:00", "America/New_York"] {
+            config.execution.time_zone = Some(tz.to_string());
+            assert!(session_time_zone(&config).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn test_convert_local_nanos() {
+        let tz: Tz = "+08:00".parse().unwrap();
+        let hour = 3_600_000_000_000;
+        let convert = |udf: ScalarUDF, values: Int64Array| {
+            let array: ArrayRef = Arc::new(values);
+            let converted = (udf.fun)(&[ColumnarValue::Array(array)])
+                .unwrap()
+                .into_array(3);
+            as_int64_array(&converted).unwrap().clone()
+        };
+
+        let utc = Int64Array::from(vec![Some(0), None, Some(hour)]);
+        let local = convert(to_local_nanos_udf(tz), utc.clone());
+        assert_eq!(
+            local,
+            Int64Array::from(vec![Some(8 * hour), None, Some(9 * hour)])
+        );
+        assert_eq!(convert(from_local_nanos_udf(tz), local), utc);
+    }
+}
@@ -5,10 +5,17 @@ mod current_database;
 mod current_role;
 mod current_tenant;
 mod current_user;
+mod date_bin;
+mod local_time;
+
+pub use date_bin::{bin_in, date_bin_udf, from_local_nanos, to_local_nanos};
+pub use local_time::{from_local_nanos_udf, session_time_zone, to_local_nanos_udf};
 
 pub fn register_session_udfs(df_session_ctx: &SessionContext, context: &Context) {
     current_user::register_session_udf(df_session_ctx, context);
     current_tenant::register_session_udf(df_session_ctx, context);
     current_database::register_session_udf(df_session_ctx, context);
     current_role::register_session_udf(df_session_ctx, context);
+    date_bin::register_session_udf(df_session_ctx, context);
+    local_time::register_session_udf(df_session_ctx, context);
 }
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Float64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::cast::{as_float64_array, as_timestamp_nanosecond_array};
+use datafusion::error::Result as DFResult;
+use datafusion::logical_expr::type_coercion::aggregates::{NUMERICS, TIMESTAMPS};
+use datafusion::logical_expr::{
+    PartitionEvaluator, PartitionEvaluatorFactory, ReturnTypeFunction, Signature, TypeSignature,
+    Volatility, WindowUDF,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{DERIVATIVE_UDWF_NAME, NON_NEGATIVE_DERIVATIVE_UDWF_NAME};
+
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+
+pub fn register_udwfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    func_manager.register_udwf(new(DERIVATIVE_UDWF_NAME, false))?;
+    func_manager.register_udwf(new(NON_NEGATIVE_DERIVATIVE_UDWF_NAME, true))?;
+    Ok(())
+}
+
+/// derivative(value, time) over (partition by <tags> order by time)
+///
+/// Returns the rate of change per second between the point and the previous
+/// point of the series, non_negative_derivative returns null instead of a
+/// negative rate, e.g. when a counter is reset.
+fn new(name: &str, non_negative: bool) -> WindowUDF {
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+
+    let partition_evaluator_factory: PartitionEvaluatorFactory =
+        Arc::new(move || Ok(Box::new(DerivativeEvaluator { non_negative })));
+
+    let type_signatures = NUMERICS
+        .iter()
+        .flat_map(|v| {
+            TIMESTAMPS
+                .iter()
+                .map(|t| TypeSignature::Exact(vec![v.clone(), t.clone()]))
+        })
+        .collect();
+
+    WindowUDF::new(
+        name,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type,
+        &partition_evaluator_factory,
+    )
+}
+
+#[derive(Debug)]
+struct DerivativeEvaluator {
+    non_negative: bool,
+}
+
+impl PartitionEvaluator for DerivativeEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], _num_rows: usize) -> DFResult<ArrayRef> {
+        let vals = cast(&values[0], &DataType::Float64)?;
+        let times = cast(&values[1], &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+
+        let mut prev: Option<(i64, f64)> = None;
+        let result = as_float64_array(&vals)?
+            .iter()
+            .zip(as_timestamp_nanosecond_array(&times)?.iter())
+            .map(|point| {
+                let (ts, val) = match point {
+                    (Some(val), Some(ts)) => (ts, val),
+                    _ => return None,
+                };
+                let (prev_ts, prev_val) = prev.replace((ts, val))?;
+                if ts == prev_ts {
+                    return None;
+                }
+                let rate = (val - prev_val) / ((ts - prev_ts) as f64 / NANOS_PER_SECOND);
+                if self.non_negative && rate < 0.0 {
+                    return None;
+                }
+                Some(rate)
+            })
+            .collect::<Float64Array>();
+
+        Ok(Arc::new(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array, TimestampSecondArray};
+    use datafusion::logical_expr::PartitionEvaluator;
+
+    use super::DerivativeEvaluator;
+
+    #[test]
+    fn test_derivative() {
+        let values: ArrayRef = Arc::new(Int64Array::from(vec![
+            Some(10),
+            Some(30),
+            None,
+            Some(40),
+            Some(5),
+        ]));
+        let times: ArrayRef = Arc::new(TimestampSecondArray::from(vec![0, 10, 15, 20, 25]));
+
+        let result = DerivativeEvaluator {
+            non_negative: false,
+        }
+        .evaluate_all(&[values.clone(), times.clone()], 5)
+        .unwrap();
+        let expected: ArrayRef = Arc::new(Float64Array::from(vec![
+            None,
+            Some(2.0),
+            None,
+            Some(1.0),
+            Some(-7.0),
+        ]));
+        assert_eq!(&result, &expected);
+
+        let result = DerivativeEvaluator { non_negative: true }
+            .evaluate_all(&[values, times], 5)
+            .unwrap();
+        let expected: ArrayRef = Arc::new(Float64Array::from(vec![
+            None,
+            Some(2.0),
+            None,
+            Some(1.0),
+            None,
+        ]));
+        assert_eq!(&result, &expected);
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{as_float64_array, as_int64_array};
+use datafusion::error::Result as DFResult;
+use datafusion::logical_expr::type_coercion::aggregates::NUMERICS;
+use datafusion::logical_expr::{
+    PartitionEvaluator, PartitionEvaluatorFactory, ReturnTypeFunction, Signature, TypeSignature,
+    Volatility, WindowUDF,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{difference_type, DIFFERENCE_UDWF_NAME};
+
+pub fn register_udwf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<WindowUDF> {
+    let udwf = new();
+    func_manager.register_udwf(udwf.clone())?;
+    Ok(udwf)
+}
+
+/// difference(value) over (partition by <tags> order by time)
+///
+/// Returns the difference between the value and the previous non-null value
+/// of the series, the integers are subtracted as Int64 and the others as
+/// Float64.
+fn new() -> WindowUDF {
+    let return_type: ReturnTypeFunction =
+        Arc::new(|input| Ok(Arc::new(difference_type(&input[0]))));
+
+    let partition_evaluator_factory: PartitionEvaluatorFactory =
+        Arc::new(|| Ok(Box::new(DifferenceEvaluator)));
+
+    let type_signatures = NUMERICS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone()]))
+        .collect();
+
+    WindowUDF::new(
+        DIFFERENCE_UDWF_NAME,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type,
+        &partition_evaluator_factory,
+    )
+}
+
+#[derive(Debug)]
+struct DifferenceEvaluator;
+
+impl PartitionEvaluator for DifferenceEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], _num_rows: usize) -> DFResult<ArrayRef> {
+        let value_type = difference_type(values[0].data_type());
+        let values = cast(&values[0], &value_type)?;
+
+        let result: ArrayRef = match value_type {
+            DataType::Int64 => {
+                let mut prev = None;
+                let result = as_int64_array(&values)?
+                    .iter()
+                    .map(|v| {
+                        let v = v?;
+                        prev.replace(v).map(|p: i64| v.wrapping_sub(p))
+                    })
+                    .collect::<Int64Array>();
+                Arc::new(result)
+            }
+            _ => {
+                let mut prev = None;
+                let result = as_float64_array(&values)?
+                    .iter()
+                    .map(|v| {
+                        let v = v?;
+                        prev.replace(v).map(|p: f64| v - p)
+                    })
+                    .collect::<Float64Array>();
+                Arc::new(result)
+            }
+        };
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array, UInt32Array};
+    use datafusion::logical_expr::PartitionEvaluator;
+
+    use super::DifferenceEvaluator;
+
+    #[test]
+    fn test_difference() {
+        let values: ArrayRef = Arc::new(UInt32Array::from(vec![Some(1), Some(4), None, Some(2)]));
+        let result = DifferenceEvaluator.evaluate_all(&[values], 4).unwrap();
+        let expected: ArrayRef = Arc::new(Int64Array::from(vec![None, Some(3), None, Some(-2)]));
+        assert_eq!(&result, &expected);
+
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![None, Some(1.5), Some(1.0)]));
+        let result = DifferenceEvaluator.evaluate_all(&[values], 3).unwrap();
+        let expected: ArrayRef = Arc::new(Float64Array::from(vec![None, None, Some(-0.5)]));
+        assert_eq!(&result, &expected);
+    }
+}
@@ -1,13 +1,22 @@
+mod derivative;
+mod difference;
+mod moving_average;
 mod time_window;
 
+use datafusion::arrow::datatypes::DataType;
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 
+use super::INTEGERS;
+
 pub fn register_window_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
     // extend function...
     // eg.
     //   example::register_udf(func_manager)?;
     time_window::register_udf(func_manager)?;
+    difference::register_udwf(func_manager)?;
+    derivative::register_udwfs(func_manager)?;
+    moving_average::register_udwf(func_manager)?;
     Ok(())
 }
 
@@ -16,6 +25,20 @@ pub const WINDOW_COL_NAME: &str = "_window";
 pub const WINDOW_START: &str = "start";
 pub const WINDOW_END: &str = "end";
 
+pub const DIFFERENCE_UDWF_NAME: &str = "difference";
+pub const DERIVATIVE_UDWF_NAME: &str = "derivative";
+pub const NON_NEGATIVE_DERIVATIVE_UDWF_NAME: &str = "non_negative_derivative";
+pub const MOVING_AVERAGE_UDWF_NAME: &str = "moving_average";
+
+/// The type of the difference between two values of `value_type`.
+fn difference_type(value_type: &DataType) -> DataType {
+    if INTEGERS.contains(value_type) {
+        DataType::Int64
+    } else {
+        DataType::Float64
+    }
+}
+
 pub use time_window::{
     ceil_sliding_window, floor_sliding_window, signature as time_window_signature,
     DEFAULT_TIME_WINDOW_START, TIME_WINDOW_UDF,
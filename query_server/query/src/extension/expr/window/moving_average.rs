@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Float64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{as_float64_array, as_int64_array};
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::NUMERICS;
+use datafusion::logical_expr::{
+    PartitionEvaluator, PartitionEvaluatorFactory, ReturnTypeFunction, Signature, TypeSignature,
+    Volatility, WindowUDF,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::MOVING_AVERAGE_UDWF_NAME;
+
+pub fn register_udwf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<WindowUDF> {
+    let udwf = new();
+    func_manager.register_udwf(udwf.clone())?;
+    Ok(udwf)
+}
+
+/// moving_average(value, n) over (partition by <tags> order by time)
+///
+/// Returns the average of the value and the previous n - 1 non-null values of
+/// the series, null until n values are observed.
+fn new() -> WindowUDF {
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+
+    let partition_evaluator_factory: PartitionEvaluatorFactory =
+        Arc::new(|| Ok(Box::new(MovingAverageEvaluator)));
+
+    // moving_average(
+    //     value NUMERIC,
+    //     n BIGINT, the number of the values to average
+    //   )
+    let type_signatures = NUMERICS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Int64]))
+        .collect();
+
+    WindowUDF::new(
+        MOVING_AVERAGE_UDWF_NAME,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type,
+        &partition_evaluator_factory,
+    )
+}
+
+#[derive(Debug)]
+struct MovingAverageEvaluator;
+
+impl PartitionEvaluator for MovingAverageEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], _num_rows: usize) -> DFResult<ArrayRef> {
+        let n = as_int64_array(&values[1])?
+            .iter()
+            .flatten()
+            .next()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "The second argument of {MOVING_AVERAGE_UDWF_NAME} must be a positive integer"
+                ))
+            })? as usize;
+
+        let vals = cast(&values[0], &DataType::Float64)?;
+        let mut window = VecDeque::with_capacity(n);
+        let mut sum = 0.0;
+        let result = as_float64_array(&vals)?
+            .iter()
+            .map(|v| {
+                let v = v?;
+                window.push_back(v);
+                sum += v;
+                if window.len() > n {
+                    sum -= window.pop_front().unwrap_or_default();
+                }
+                (window.len() == n).then(|| sum / n as f64)
+            })
+            .collect::<Float64Array>();
+
+        Ok(Arc::new(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array};
+    use datafusion::logical_expr::PartitionEvaluator;
+
+    use super::MovingAverageEvaluator;
+
+    #[test]
+    fn test_moving_average() {
+        let values: ArrayRef = Arc::new(Int64Array::from(vec![
+            Some(1),
+            Some(3),
+            None,
+            Some(5),
+            Some(10),
+        ]));
+        let n: ArrayRef = Arc::new(Int64Array::from(vec![2; 5]));
+        let result = MovingAverageEvaluator
+            .evaluate_all(&[values.clone(), n], 5)
+            .unwrap();
+        let expected: ArrayRef = Arc::new(Float64Array::from(vec![
+            None,
+            Some(2.0),
+            None,
+            Some(4.0),
+            Some(7.5),
+        ]));
+        assert_eq!(&result, &expected);
+
+        let n: ArrayRef = Arc::new(Int64Array::from(vec![0; 5]));
+        assert!(MovingAverageEvaluator
+            .evaluate_all(&[values, n], 5)
+            .is_err());
+    }
+}
@@ -41,6 +41,9 @@ pub fn signature() -> Signature {
     // group by time_window(time, interval '10 second') => group by time_window(time, interval '10 second', interval '5 second', '1970-01-01T00:00:00.000Z')
     // group by time_window(time, interval '10 second', interval '5 second') => group by time_window(time, interval '10 second', interval '5 second', '1970-01-01T00:00:00.000Z')
     // group by time_window(time, interval '10 second', interval '5 second', '1999-12-31T00:00:00.000Z')
+    //
+    // The startTime may be an offset from unix EPOCH, which may be negative
+    // group by time_window(time, interval '1 hour', interval '1 hour', interval '30 minute')
     let type_signatures = TIMESTAMPS
         .iter()
         .flat_map(|first| {
@@ -61,6 +64,15 @@ pub fn signature() -> Signature {
                                 DataType::Timestamp(TimeUnit::Nanosecond, None),
                             ]),
                         ]
+                        .into_iter()
+                        .chain(INTERVALS.iter().map(|offset| {
+                            TypeSignature::Exact(vec![
+                                first.clone(),
+                                second.clone(),
+                                third.clone(),
+                                offset.clone(),
+                            ])
+                        }))
                     })
                     .chain([TypeSignature::Exact(vec![first.clone(), second.clone()])])
             })
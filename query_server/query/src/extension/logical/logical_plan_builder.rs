@@ -9,7 +9,9 @@ use datafusion::prelude::{cast, lit, Expr};
 use datafusion::scalar::ScalarValue;
 use models::schema::stream_table_schema::Watermark;
 use spi::query::datasource::stream::StreamProviderRef;
-use spi::query::logical_planner::{affected_row_expr, merge_affected_row_expr};
+use spi::query::logical_planner::{
+    affected_bytes_expr, affected_row_expr, merge_affected_bytes_expr, merge_affected_row_expr,
+};
 use spi::QueryError;
 use trace::debug;
 
@@ -34,6 +36,13 @@ pub trait LogicalPlanBuilderExt: Sized {
         table_name: &str,
         insert_columns: &[String],
     ) -> DFResult<Self>;
+    /// Same as [`Self::write`] with all the columns, the bytes written are also
+    /// reported besides the rows.
+    fn write_to_location(
+        self,
+        target_table: Arc<dyn TableSource>,
+        table_name: &str,
+    ) -> DFResult<Self>;
 }
 
 impl LogicalPlanBuilderExt for LogicalPlanBuilder {
@@ -94,40 +103,64 @@ impl LogicalPlanBuilderExt for LogicalPlanBuilder {
         table_name: &str,
         insert_columns: &[String],
     ) -> DFResult<Self> {
-        let insert_columns = extract_column_names(insert_columns, target_table.clone());
+        build_write(self, target_table, table_name, insert_columns, false)
+    }
+
+    fn write_to_location(
+        self,
+        target_table: Arc<dyn TableSource>,
+        table_name: &str,
+    ) -> DFResult<Self> {
+        build_write(self, target_table, table_name, &[], true)
+    }
+}
+
+fn build_write(
+    builder: LogicalPlanBuilder,
+    target_table: Arc<dyn TableSource>,
+    table_name: &str,
+    insert_columns: &[String],
+    report_bytes: bool,
+) -> DFResult<LogicalPlanBuilder> {
+    let insert_columns = extract_column_names(insert_columns, target_table.clone());
 
-        let source_plan = self.build()?;
+    let source_plan = builder.build()?;
 
-        debug!("Build writer plan: target table schema: {:?}, insert_columns: {:?}, source schema: {:?}",
-            target_table.schema(),
-            insert_columns,
-            source_plan.schema(),
-        );
+    debug!(
+        "Build writer plan: target table schema: {:?}, insert_columns: {:?}, source schema: {:?}",
+        target_table.schema(),
+        insert_columns,
+        source_plan.schema(),
+    );
 
-        debug!(
-            "Build writer plan: source plan:\n{}",
-            source_plan.display_indent_schema(),
-        );
+    debug!(
+        "Build writer plan: source plan:\n{}",
+        source_plan.display_indent_schema(),
+    );
 
-        // Check if the plan is legal
-        semantic_check(insert_columns.as_ref(), &source_plan, target_table.clone())
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    // Check if the plan is legal
+    semantic_check(insert_columns.as_ref(), &source_plan, target_table.clone())
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
-        let final_source_logical_plan = add_projection_between_source_and_insert_node_if_necessary(
-            target_table.clone(),
-            source_plan,
-            insert_columns.as_ref(),
-        )?;
+    let final_source_logical_plan = add_projection_between_source_and_insert_node_if_necessary(
+        target_table.clone(),
+        source_plan,
+        insert_columns.as_ref(),
+    )?;
 
-        let plan = table_write_plan_node(table_name, target_table, final_source_logical_plan)?;
+    let plan = table_write_plan_node(
+        table_name,
+        target_table,
+        final_source_logical_plan,
+        report_bytes,
+    )?;
 
-        debug!(
-            "Build writer plan: final plan:\n{}",
-            plan.display_indent_schema(),
-        );
+    debug!(
+        "Build writer plan: final plan:\n{}",
+        plan.display_indent_schema(),
+    );
 
-        Ok(Self::from(plan))
-    }
+    Ok(LogicalPlanBuilder::from(plan))
 }
 
 fn extract_column_names(
@@ -250,6 +283,7 @@ fn table_write_plan_node(
     table_name: impl Into<String>,
     target_table: Arc<dyn TableSource>,
     input: LogicalPlan,
+    report_bytes: bool,
 ) -> DFResult<LogicalPlan> {
     let input_exprs = input
         .schema()
@@ -257,18 +291,18 @@ fn table_write_plan_node(
         .iter()
         .map(|f| Expr::Column(f.qualified_column()))
         .collect::<Vec<Expr>>();
-    let affected_row_expr = affected_row_expr(input_exprs);
+    let mut exprs = vec![affected_row_expr(input_exprs.clone())];
+    let mut merge_exprs = vec![merge_affected_row_expr()];
+    if report_bytes {
+        exprs.push(affected_bytes_expr(input_exprs));
+        merge_exprs.push(merge_affected_bytes_expr());
+    }
 
     // construct table writer logical node
-    let plan = TableWriterPlanNode::try_new(
-        table_name.into(),
-        target_table,
-        Arc::new(input),
-        vec![affected_row_expr],
-    )?;
-
     let plan =
-        TableWriterMergePlanNode::try_new(Arc::new(plan.into()), vec![merge_affected_row_expr()])?;
+        TableWriterPlanNode::try_new(table_name.into(), target_table, Arc::new(input), exprs)?;
+
+    let plan = TableWriterMergePlanNode::try_new(Arc::new(plan.into()), merge_exprs)?;
 
     Ok(plan.into())
 
@@ -15,19 +15,23 @@
 //! Push Down Aggregation optimizer rule ensures that aggregations are applied as early as possible in the plan
 
 use std::ops::Deref;
+use std::sync::Arc;
 
-// use std::sync::Arc;
-use datafusion::common::Column;
+use datafusion::arrow::datatypes::{DataType, Schema};
+use datafusion::common::{Column, DFField, DFSchema};
 use datafusion::error::{DataFusionError, Result};
-use datafusion::logical_expr::expr::AggregateFunction;
+use datafusion::logical_expr::expr::{AggregateFunction, AggregateUDF, Between, BinaryExpr};
 use datafusion::logical_expr::utils::{exprlist_to_columns, grouping_set_to_exprlist};
 use datafusion::logical_expr::{
-    AggWithGrouping, Aggregate, AggregateFunction as AggregateFunctionName, LogicalPlan,
-    LogicalPlanBuilder, Projection, TableProviderAggregationPushDown, TableScan,
+    AggWithGrouping, Aggregate, AggregateFunction as AggregateFunctionName, ExprSchemable,
+    LogicalPlan, LogicalPlanBuilder, Operator, Projection, TableProviderAggregationPushDown,
+    TableScan,
 };
 use datafusion::optimizer::{optimize_children, OptimizerConfig, OptimizerRule};
 use datafusion::prelude::Expr;
 
+use crate::extension::expr::{new_mode_merge_udaf, MODE_UDAF_NAME};
+
 /// Push Down Aggregation optimizer rule pushes aggregation clauses down the plan
 /// # Introduction
 /// TODO
@@ -79,7 +83,12 @@ impl OptimizerRule for PushDownAggregation {
                 fetch,
             }) = temp_input.deref()
             {
-                if agg_with_grouping.is_none() && filters.is_empty() {
+                let source_schema = source.schema();
+                if agg_with_grouping.is_none()
+                    && filters
+                        .iter()
+                        .all(|f| is_time_range_filter(f, &source_schema))
+                {
                     let new_plan = match source
                         .supports_aggregate_pushdown(group_expr, aggr_expr)?
                     {
@@ -96,73 +105,86 @@ impl OptimizerRule for PushDownAggregation {
                             // == Optimized Logical Plan ==
                             // Aggregate: groupBy=[[]], aggr=[[min(min(c1)) as min(c1), max(max(c1)) as max(c1)]]
                             //   TableScan: t1 projection=[c1] groupBy=[[]], aggr=[[min(c1), max(c1)]]
+                            //
+                            // The vnodes output the partial states of the aggregates:
+                            // - count: the number of rows, summed up.
+                            // - bool_and/bool_or: a boolean, aggregated again.
+                            // - count(distinct): the distinct values, counted distinctly again.
+                            // - mode: the distinct values and their counts, merged by mode_merge.
+                            let mut partial_fields = Vec::with_capacity(aggr_expr.len());
                             let new_agg_expr_with_alias = aggr_expr
-                            .iter()
-                            .map(|e| {
-                                let col_name = e.display_name()?;
-                                let column = Column::from_name(col_name.clone());
-
-                                let new_expr = match e {
-                                    Expr::AggregateFunction(AggregateFunction {
-                                        fun,
-                                        args: _,
-                                        distinct,
-                                        filter,
-                                        order_by,
-                                        can_be_pushed_down,
-                                    }) => {
-                                        let new_agg_func = match fun {
-                                            /* AggregateFunctionName::Max => {
-                                                AggregateFunction {
-                                                    fun: AggregateFunctionName::Max,
-                                                    args: vec![Expr::Column(column)],
-                                                    distinct: *distinct,
-                                                    filter: filter.clone(),
-                                                    order_by: order_by.clone(),
-                                                }
-                                            },
-                                            AggregateFunctionName::Min => {
-                                                AggregateFunction {
-                                                    fun: AggregateFunctionName::Min,
-                                                    args: vec![Expr::Column(column)],
-                                                    distinct: *distinct,
-                                                    filter: filter.clone(),
-                                                    order_by: order_by.clone(),
+                                .iter()
+                                .enumerate()
+                                .map(|(i, e)| {
+                                    let col_name = e.display_name()?;
+                                    let column = Expr::Column(Column::from_name(col_name.clone()));
+                                    let output_field = schema.field(group_expr.len() + i);
+
+                                    let new_expr = match e {
+                                        Expr::AggregateFunction(AggregateFunction {
+                                            fun,
+                                            args,
+                                            distinct,
+                                            filter,
+                                            order_by,
+                                            can_be_pushed_down,
+                                        }) => {
+                                            let (new_fun, partial_field) = match (fun, distinct) {
+                                                (AggregateFunctionName::Count, false) => {
+                                                    (AggregateFunctionName::Sum, output_field.clone())
                                                 }
-                                            },
-                                            AggregateFunctionName::Sum => {
-                                                AggregateFunction {
-                                                    fun: AggregateFunctionName::Sum,
-                                                    args: vec![Expr::Column(column)],
-                                                    distinct: *distinct,
-                                                    filter: filter.clone(),
-                                                    order_by: order_by.clone(),
+                                                (AggregateFunctionName::Count, true) => {
+                                                    let data_type = args[0].get_type(input.schema())?;
+                                                    (
+                                                        AggregateFunctionName::Count,
+                                                        DFField::new_unqualified(&col_name, data_type, true),
+                                                    )
                                                 }
-                                            }, */
-                                            AggregateFunctionName::Count => {
-                                                AggregateFunction {
-                                                    fun: AggregateFunctionName::Sum,
-                                                    args: vec![Expr::Column(column)],
-                                                    distinct: *distinct,
-                                                    filter: filter.clone(),
-                                                    order_by: order_by.clone(),
-                                                    can_be_pushed_down: *can_be_pushed_down,
+                                                (AggregateFunctionName::BoolAnd, false)
+                                                | (AggregateFunctionName::BoolOr, false)
+                                                | (AggregateFunctionName::Min, _)
+                                                | (AggregateFunctionName::Max, _) => {
+                                                    (fun.clone(), output_field.clone())
                                                 }
-                                            },
-                                            // not support other agg func
-                                            _ => return Err(DataFusionError::Internal(format!("Unreachable, not support {fun:?} push down."))),
-                                        };
+                                                // not support other agg func
+                                                _ => return Err(DataFusionError::Internal(format!("Unreachable, not support {fun:?} push down."))),
+                                            };
+                                            partial_fields.push(partial_field);
 
-                                        Ok(Expr::AggregateFunction(new_agg_func))
-                                    },
-                                    _ => Err(DataFusionError::Internal("Invalid logical plan, Aggregate's aggr_expr contains non-aggregate expr.".to_string())),
-                                }?;
+                                            Expr::AggregateFunction(AggregateFunction {
+                                                fun: new_fun,
+                                                args: vec![column],
+                                                distinct: *distinct,
+                                                filter: filter.clone(),
+                                                order_by: order_by.clone(),
+                                                can_be_pushed_down: *can_be_pushed_down,
+                                            })
+                                        }
+                                        Expr::AggregateUDF(udf) if udf.fun.name == MODE_UDAF_NAME => {
+                                            let data_type = udf.args[0].get_type(input.schema())?;
+                                            let count_name = format!("{col_name}_count");
+                                            partial_fields.push(DFField::new_unqualified(&col_name, data_type, true));
+                                            partial_fields.push(DFField::new_unqualified(&count_name, DataType::UInt32, true));
 
-                                let alias = Expr::Column(Column::from_name(new_expr.display_name()?)).alias(col_name);
+                                            Expr::AggregateUDF(AggregateUDF::new(
+                                                Arc::new(new_mode_merge_udaf()),
+                                                vec![column, Expr::Column(Column::from_name(count_name))],
+                                                udf.filter.clone(),
+                                                udf.order_by.clone(),
+                                            ))
+                                        }
+                                        _ => return Err(DataFusionError::Internal("Invalid logical plan, Aggregate's aggr_expr contains non-aggregate expr.".to_string())),
+                                    };
 
-                                Ok((new_expr, alias))
-                            })
-                            .collect::<Result<Vec<_>>>()?;
+                                    let alias = Expr::Column(Column::from_name(new_expr.display_name()?)).alias(col_name);
+
+                                    Ok((new_expr, alias))
+                                })
+                                .collect::<Result<Vec<_>>>()?;
+                            let partial_schema = Arc::new(DFSchema::new_with_metadata(
+                                partial_fields,
+                                schema.metadata().clone(),
+                            )?);
 
                             let (new_agg_expr, projection_agg_expr): (Vec<_>, Vec<_>) =
                                 new_agg_expr_with_alias.into_iter().unzip();
@@ -182,13 +204,13 @@ impl OptimizerRule for PushDownAggregation {
                                 table_name: table_name.clone(),
                                 source: source.clone(),
                                 projection: None,
-                                projected_schema: schema.clone(),
+                                projected_schema: partial_schema.clone(),
                                 filters: filters.clone(),
                                 fetch: *fetch,
                                 agg_with_grouping: Some(AggWithGrouping {
                                     group_expr: group_expr.clone(),
                                     agg_expr: aggr_expr.clone(),
-                                    schema: schema.clone(),
+                                    schema: partial_schema,
                                 }),
                             });
 
@@ -237,18 +259,52 @@ impl OptimizerRule for PushDownAggregation {
 
 fn determine_whether_support_push_down(aggr_expr: &[Expr]) -> bool {
     aggr_expr.iter().all(|e| match e {
-        Expr::AggregateFunction(AggregateFunction { fun, distinct, .. }) => {
-            let support_agg_func = matches!(
-                fun,
-                /* AggregateFunctionName::Max
-                | AggregateFunctionName::Min
-                | AggregateFunctionName::Sum
-                |  */
-                AggregateFunctionName::Count
-            );
-
-            support_agg_func && !distinct
-        }
+        Expr::AggregateFunction(AggregateFunction { fun, distinct, .. }) => match fun {
+            /* AggregateFunctionName::Sum
+            |  */
+            AggregateFunctionName::Count
+            | AggregateFunctionName::Max
+            | AggregateFunctionName::Min => true,
+            AggregateFunctionName::BoolAnd | AggregateFunctionName::BoolOr => !distinct,
+            _ => false,
+        },
+        Expr::AggregateUDF(udf) => udf.fun.name == MODE_UDAF_NAME,
         _ => false,
     })
 }
+
+/// Whether the filter only compares the time column with constants, the rows
+/// of which are selected by the time ranges of the scan, so the aggregates
+/// can be computed from the statistics of the data in the time ranges.
+fn is_time_range_filter(expr: &Expr, schema: &Schema) -> bool {
+    let is_time_column = |e: &Expr| match e {
+        Expr::Column(c) => schema
+            .field_with_name(&c.name)
+            .map_or(false, |f| matches!(f.data_type(), DataType::Timestamp(..))),
+        _ => false,
+    };
+    let is_constant = |e: &Expr| e.to_columns().map_or(false, |columns| columns.is_empty());
+
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And | Operator::Or,
+            right,
+        }) => is_time_range_filter(left, schema) && is_time_range_filter(right, schema),
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq | Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq,
+            right,
+        }) => {
+            (is_time_column(left) && is_constant(right))
+                || (is_constant(left) && is_time_column(right))
+        }
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) => is_time_column(expr) && is_constant(low) && is_constant(high),
+        _ => false,
+    }
+}
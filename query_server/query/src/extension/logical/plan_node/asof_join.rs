@@ -0,0 +1,95 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use datafusion::common::DFSchemaRef;
+use datafusion::logical_expr::{JoinType, LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion::prelude::Expr;
+
+/// Matches each row of the left input with the row of the right input of the
+/// same join keys and the latest time not after the time of the left row.
+///
+/// The output has the schema of the join, the columns of the left input
+/// followed by the columns of the right input. The join type is either
+/// [`JoinType::Inner`], the unmatched left rows are dropped, or
+/// [`JoinType::Left`], the unmatched left rows are kept with nulls.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AsofJoinNode {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// The equal join keys, the left of a pair is the column of the left input
+    pub on: Vec<(Expr, Expr)>,
+    /// The time column of the left input
+    pub left_time: Expr,
+    /// The time column of the right input
+    pub right_time: Expr,
+    /// The max duration between the left time and the matched right time, in
+    /// nanoseconds, unbounded if None
+    pub tolerance: Option<i64>,
+    pub join_type: JoinType,
+    pub schema: DFSchemaRef,
+}
+
+impl Debug for AsofJoinNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNodeCore for AsofJoinNode {
+    fn name(&self) -> &str {
+        "AsofJoin"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.on
+            .iter()
+            .flat_map(|(l, r)| [l.clone(), r.clone()])
+            .chain([self.left_time.clone(), self.right_time.clone()])
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(l, r)| format!("{l} = {r}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "{}: type={}, on=[{}], time={} >= {}, tolerance={:?}",
+            self.name(),
+            self.join_type,
+            on,
+            self.left_time,
+            self.right_time,
+            self.tolerance,
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 2, "input size inconsistent");
+        let on_len = self.on.len() * 2;
+        Self {
+            left: Arc::new(inputs[0].clone()),
+            right: Arc::new(inputs[1].clone()),
+            on: exprs[..on_len]
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            left_time: exprs[on_len].clone(),
+            right_time: exprs[on_len + 1].clone(),
+            tolerance: self.tolerance,
+            join_type: self.join_type,
+            schema: self.schema.clone(),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use datafusion::common::DFSchemaRef;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion::prelude::Expr;
+use datafusion::scalar::ScalarValue;
+
+/// How the value of an aggregate is filled in a missing time window.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FillStrategy {
+    /// Leave the value null, the default.
+    Null,
+    /// The last non-null value observed before the window, `locf(<expr>)`.
+    Previous,
+    /// Linear interpolation between the observed values around the window,
+    /// `interpolate(<expr>)`.
+    Linear,
+    /// A constant, `fill_value(<expr>, <constant>)`.
+    Constant(ScalarValue),
+}
+
+impl fmt::Display for FillStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Previous => write!(f, "previous"),
+            Self::Linear => write!(f, "linear"),
+            Self::Constant(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// The time windows to be filled, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GapFillParams {
+    pub stride: i64,
+    /// In the local time of `time_zone` if it's set.
+    pub origin: i64,
+    /// The first window of each series, taken from the lower bound of the time
+    /// in the filter if any.
+    pub first_window: Option<i64>,
+    /// The last window of each series, taken from the upper bound of the time
+    /// in the filter if any.
+    pub last_window: Option<i64>,
+    /// The windows are in the local time of the zone of the session, None if
+    /// the session is in UTC.
+    pub time_zone: Option<String>,
+}
+
+/// Fills the missing time windows of the aggregated series.
+///
+/// The input is sorted by `group_expr` and then `time_expr`, the output has
+/// the same schema as the input, each series has a row for every window from
+/// its first to its last.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GapFillNode {
+    pub input: Arc<LogicalPlan>,
+    /// The columns of the group keys other than the time window
+    pub group_expr: Vec<Expr>,
+    /// The column of the start of the time window
+    pub time_expr: Expr,
+    /// The columns of the aggregates
+    pub aggr_expr: Vec<Expr>,
+    /// The fill strategy of each of `aggr_expr`
+    pub fill_strategy: Vec<FillStrategy>,
+    pub params: GapFillParams,
+}
+
+impl GapFillNode {
+    /// Replace the fill strategy of the aggregate column.
+    pub fn with_fill_strategy(mut self, aggr_expr: &Expr, fill_strategy: FillStrategy) -> Self {
+        if let Some(i) = self.aggr_expr.iter().position(|e| e == aggr_expr) {
+            self.fill_strategy[i] = fill_strategy;
+        }
+        self
+    }
+}
+
+impl Debug for GapFillNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNodeCore for GapFillNode {
+    fn name(&self) -> &str {
+        "GapFill"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.input.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.group_expr
+            .iter()
+            .chain([&self.time_expr])
+            .chain(self.aggr_expr.iter())
+            .cloned()
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let aggr_expr = self
+            .aggr_expr
+            .iter()
+            .zip(&self.fill_strategy)
+            .map(|(e, s)| format!("{e}: {s}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "{}: group_expr=[{}], time_expr={}, aggr_expr=[{}], stride={}, origin={}, range={:?}..={:?}, time_zone={}",
+            self.name(),
+            self.group_expr
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.time_expr,
+            aggr_expr,
+            self.params.stride,
+            self.params.origin,
+            self.params.first_window,
+            self.params.last_window,
+            self.params.time_zone.as_deref().unwrap_or("UTC"),
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 1, "input size inconsistent");
+        let group_len = self.group_expr.len();
+        Self {
+            input: Arc::new(inputs[0].clone()),
+            group_expr: exprs[..group_len].to_vec(),
+            time_expr: exprs[group_len].clone(),
+            aggr_expr: exprs[group_len + 1..].to_vec(),
+            fill_strategy: self.fill_strategy.clone(),
+            params: self.params.clone(),
+        }
+    }
+}
@@ -6,7 +6,9 @@ use datafusion::prelude::Expr;
 
 use crate::extension::expr::expr_rewriter::ExprReplacer;
 
+pub mod asof_join;
 pub mod expand;
+pub mod gap_fill;
 pub mod stream_scan;
 pub mod table_writer;
 pub mod table_writer_merge;
@@ -1,7 +1,11 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Arc;
+
 use datafusion::common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
 use datafusion::common::Result;
 use datafusion::error::DataFusionError;
-use datafusion::logical_expr::{LogicalPlan, TableScan};
+use datafusion::logical_expr::expr::{Exists, InSubquery};
+use datafusion::logical_expr::{Expr, LogicalPlan, Subquery, TableScan};
 use spi::query::datasource::stream::StreamProviderRef;
 use spi::query::logical_planner::QueryPlan;
 
@@ -38,3 +42,89 @@ impl<'a> TreeNodeVisitor for ExtractStreamProvider<'a> {
         Ok(VisitRecursion::Continue)
     }
 }
+
+/// Returns the columns of the scanned tables referenced by the plan, keyed by
+/// (database, table).
+///
+/// The columns are matched by name against all expressions of the plan and its
+/// subqueries, the output of the plan and the inputs of the nodes taking whole
+/// rows, like the writers and the unions, so a column with the same name in
+/// another table is also counted.
+pub fn extract_table_columns(
+    plan: &LogicalPlan,
+) -> Result<BTreeMap<(String, String), BTreeSet<String>>> {
+    let mut column_names = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect::<HashSet<_>>();
+    let mut scans = vec![];
+    collect_columns_and_scans(plan, &mut column_names, &mut scans)?;
+
+    let mut table_columns: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    for scan in scans {
+        let adapter = source_downcast_adapter(&scan.source)
+            .map_err(|err| DataFusionError::External(Box::new(err)))?;
+        let columns = table_columns
+            .entry((
+                adapter.database_name().to_string(),
+                adapter.table_name().to_string(),
+            ))
+            .or_default();
+        for field in scan.source.schema().fields() {
+            if column_names.contains(field.name()) {
+                columns.insert(field.name().clone());
+            }
+        }
+    }
+
+    Ok(table_columns)
+}
+
+fn collect_columns_and_scans(
+    plan: &LogicalPlan,
+    column_names: &mut HashSet<String>,
+    scans: &mut Vec<TableScan>,
+) -> Result<()> {
+    match plan {
+        LogicalPlan::TableScan(scan) => scans.push(scan.clone()),
+        LogicalPlan::Extension(_) | LogicalPlan::Dml(_) | LogicalPlan::Union(_) => {
+            for input in plan.inputs() {
+                column_names.extend(input.schema().fields().iter().map(|f| f.name().clone()));
+            }
+        }
+        _ => {}
+    }
+
+    let mut subqueries: Vec<Arc<LogicalPlan>> = vec![];
+    for expr in plan.expressions() {
+        expr.apply(&mut |e| {
+            match e {
+                Expr::Column(c) | Expr::OuterReferenceColumn(_, c) => {
+                    column_names.insert(c.name.clone());
+                }
+                Expr::ScalarSubquery(Subquery { subquery, .. })
+                | Expr::Exists(Exists {
+                    subquery: Subquery { subquery, .. },
+                    ..
+                })
+                | Expr::InSubquery(InSubquery {
+                    subquery: Subquery { subquery, .. },
+                    ..
+                }) => subqueries.push(subquery.clone()),
+                _ => {}
+            }
+            Ok(VisitRecursion::Continue)
+        })?;
+    }
+
+    for subquery in subqueries {
+        collect_columns_and_scans(&subquery, column_names, scans)?;
+    }
+    for input in plan.inputs() {
+        collect_columns_and_scans(input, column_names, scans)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::Result as DFResult;
+use datafusion::config::ConfigOptions;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning};
+
+use crate::exchange::ExchangeManager;
+use crate::extension::physical::plan_node::shuffle_hash_join::ShuffleHashJoinExec;
+use crate::extension::utils::downcast_execution_plan;
+
+/// Replace the partitioned hash joins by [`ShuffleHashJoinExec`], which shuffles
+/// the inputs into `partitions` partitions joined by the data nodes.
+#[non_exhaustive]
+pub struct DistributeHashJoin {
+    partitions: usize,
+}
+
+impl DistributeHashJoin {
+    pub fn new(partitions: usize) -> Self {
+        Self { partitions }
+    }
+}
+
+impl PhysicalOptimizerRule for DistributeHashJoin {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if self.partitions == 0 || ExchangeManager::get().is_none() {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            if let Some(join) = downcast_execution_plan::<HashJoinExec>(plan.as_ref()) {
+                if join.partition_mode() == &PartitionMode::Partitioned {
+                    return Ok(Transformed::Yes(Arc::new(ShuffleHashJoinExec::new(
+                        remove_hash_repartition(join.left().clone()),
+                        remove_hash_repartition(join.right().clone()),
+                        join.on().to_vec(),
+                        join.filter().cloned(),
+                        *join.join_type(),
+                        join.null_equals_null(),
+                        self.partitions,
+                        join.schema(),
+                    ))));
+                }
+            }
+
+            Ok(Transformed::No(plan))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "distribute_hash_join"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// The inputs of a partitioned hash join are repartitioned by the join keys,
+/// which is done by the shuffle instead.
+fn remove_hash_repartition(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+    let mut input = plan.clone();
+    if let Some(coalesce) = downcast_execution_plan::<CoalesceBatchesExec>(input.as_ref()) {
+        input = coalesce.input().clone();
+    }
+    match downcast_execution_plan::<RepartitionExec>(input.as_ref()) {
+        Some(repartition) if matches!(repartition.partitioning(), Partitioning::Hash(_, _)) => {
+            repartition.input().clone()
+        }
+        _ => plan,
+    }
+}
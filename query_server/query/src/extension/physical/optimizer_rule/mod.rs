@@ -3,3 +3,4 @@ pub mod add_assert;
 pub mod add_sort;
 pub mod add_state_store;
 pub mod add_traced_proxy;
+pub mod distribute_hash_join;
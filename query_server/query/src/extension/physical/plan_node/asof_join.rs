@@ -0,0 +1,634 @@
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_null_array, Array, ArrayRef, BooleanArray, UInt32Array};
+use datafusion::arrow::compute::{and, cast, interleave, is_not_null, take};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{OwnedRow, RowConverter, Rows, SortField};
+use datafusion::common::cast::as_timestamp_nanosecond_array;
+use datafusion::common::Statistics;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::JoinType;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{PhysicalSortExpr, PhysicalSortRequirement};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+use futures::StreamExt;
+
+/// Matches each row of the left input with the row of the right input of the
+/// same join keys and the latest time not after the time of the left row.
+///
+/// Both inputs are required to be sorted by the join keys and then the time,
+/// which the time-sorted scans of tskv provide, so the inputs are merged as
+/// they are streamed. Only the current batch of the right input and the latest
+/// right row of the series are kept, they are accounted in the memory pool.
+pub struct AsofJoinExec {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    joiner: Arc<AsofJoiner>,
+    left_sort_expr: Vec<PhysicalSortExpr>,
+    right_sort_expr: Vec<PhysicalSortExpr>,
+    schema: SchemaRef,
+}
+
+impl AsofJoinExec {
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: Vec<(usize, usize)>,
+        left_time: usize,
+        right_time: usize,
+        tolerance: Option<i64>,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        if !matches!(join_type, JoinType::Inner | JoinType::Left) {
+            return Err(DataFusionError::Internal(format!(
+                "AsofJoinExec only supports Inner and Left joins, found {join_type}"
+            )));
+        }
+        if let Some(tolerance) = tolerance {
+            if tolerance < 0 {
+                return Err(DataFusionError::Internal(format!(
+                    "AsofJoinExec expects a non-negative tolerance, found {tolerance}"
+                )));
+            }
+        }
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let right_nullable = join_type == JoinType::Left;
+        let fields = left_schema
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone())
+            .chain(right_schema.fields().iter().map(|f| {
+                let nullable = f.is_nullable() || right_nullable;
+                f.as_ref().clone().with_nullable(nullable)
+            }))
+            .collect::<Vec<Field>>();
+        let schema = Arc::new(Schema::new(fields));
+
+        let key_types = on
+            .iter()
+            .map(|&(_, r)| right_schema.field(r).data_type().clone())
+            .collect();
+        // the same order as the rows of the join keys, nulls first
+        let sort_expr = |schema: &SchemaRef, columns: Vec<usize>| {
+            columns
+                .into_iter()
+                .map(|i| PhysicalSortExpr {
+                    expr: Arc::new(Column::new(schema.field(i).name(), i)),
+                    options: Default::default(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let left_sort_expr = sort_expr(
+            &left_schema,
+            on.iter().map(|&(l, _)| l).chain([left_time]).collect(),
+        );
+        let right_sort_expr = sort_expr(
+            &right_schema,
+            on.iter().map(|&(_, r)| r).chain([right_time]).collect(),
+        );
+
+        Ok(Self {
+            left,
+            right,
+            joiner: Arc::new(AsofJoiner {
+                on,
+                key_types,
+                left_time,
+                right_time,
+                tolerance,
+                join_type,
+            }),
+            left_sort_expr,
+            right_sort_expr,
+            schema,
+        })
+    }
+}
+
+impl Debug for AsofJoinExec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AsofJoinExec")
+    }
+}
+
+impl ExecutionPlan for AsofJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition, Distribution::SinglePartition]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![
+            Some(PhysicalSortRequirement::from_sort_exprs(
+                &self.left_sort_expr,
+            )),
+            Some(PhysicalSortRequirement::from_sort_exprs(
+                &self.right_sort_expr,
+            )),
+        ]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.left.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            left: children[0].clone(),
+            right: children[1].clone(),
+            joiner: self.joiner.clone(),
+            left_sort_expr: self.left_sort_expr.clone(),
+            right_sort_expr: self.right_sort_expr.clone(),
+            schema: self.schema.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "AsofJoinExec invalid partition {partition}, there can be only one partition"
+            )));
+        }
+
+        let schema = self.schema();
+        let reservation = MemoryConsumer::new(format!("AsofJoinExec[{partition}]"))
+            .register(context.memory_pool());
+        let right = self.right.execute(partition, context.clone())?;
+        let left = self.left.execute(partition, context)?;
+        let state = AsofJoinState {
+            joiner: self.joiner.clone(),
+            converter: self.joiner.converter()?,
+            left,
+            right: RightCursor {
+                stream: right,
+                batch: None,
+                position: 0,
+                finished: false,
+            },
+            last: None,
+            last_id: 0,
+            right_nulls: self
+                .right
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| new_null_array(f.data_type(), 1))
+                .collect(),
+            schema: schema.clone(),
+            reservation,
+        };
+        let output = futures::stream::try_unfold(state, |mut state| async move {
+            match state.left.next().await {
+                Some(batch) => {
+                    let output = state.join(&batch?).await?;
+                    Ok(Some((output, state)))
+                }
+                None => Ok(None),
+            }
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, output)))
+    }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let left_schema = self.left.schema();
+        let right_schema = self.right.schema();
+        let AsofJoiner {
+            on,
+            left_time,
+            right_time,
+            tolerance,
+            join_type,
+            ..
+        } = self.joiner.as_ref();
+        write!(
+            f,
+            "AsofJoinExec: type={}, on=[{}], time={} >= {}, tolerance={:?}",
+            join_type,
+            on.iter()
+                .map(|&(l, r)| format!(
+                    "{} = {}",
+                    left_schema.field(l).name(),
+                    right_schema.field(r).name()
+                ))
+                .collect::<Vec<_>>()
+                .join(", "),
+            left_schema.field(*left_time).name(),
+            right_schema.field(*right_time).name(),
+            tolerance,
+        )
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+#[derive(Debug)]
+struct AsofJoiner {
+    on: Vec<(usize, usize)>,
+    /// The types of the join keys, the keys of the left input are cast to
+    /// the types of the right input
+    key_types: Vec<DataType>,
+    left_time: usize,
+    right_time: usize,
+    tolerance: Option<i64>,
+    join_type: JoinType,
+}
+
+impl AsofJoiner {
+    /// The converter of the join keys to rows, None if the join has no keys.
+    fn converter(&self) -> Result<Option<RowConverter>> {
+        if self.on.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(RowConverter::new(
+            self.key_types
+                .iter()
+                .map(|t| SortField::new(t.clone()))
+                .collect(),
+        )?))
+    }
+
+    /// The join keys and the times of the rows of a batch of a side.
+    fn sorted_keys(
+        &self,
+        batch: &RecordBatch,
+        columns: impl Iterator<Item = usize>,
+        time: usize,
+        converter: Option<&mut RowConverter>,
+    ) -> Result<SortedKeys> {
+        let times = cast(
+            batch.column(time),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None),
+        )?;
+        let times = as_timestamp_nanosecond_array(&times)?;
+        let times = (0..batch.num_rows())
+            .map(|i| times.is_valid(i).then(|| times.value(i)))
+            .collect();
+
+        let converter = match converter {
+            Some(converter) => converter,
+            None => {
+                return Ok(SortedKeys {
+                    rows: None,
+                    valid: BooleanArray::from(vec![true; batch.num_rows()]),
+                    times,
+                })
+            }
+        };
+        let columns = columns
+            .zip(&self.key_types)
+            .map(|(i, t)| cast(batch.column(i), t))
+            .collect::<std::result::Result<Vec<ArrayRef>, _>>()?;
+        let mut valid = is_not_null(&columns[0])?;
+        for column in &columns[1..] {
+            valid = and(&valid, &is_not_null(column)?)?;
+        }
+
+        Ok(SortedKeys {
+            rows: Some(converter.convert_columns(&columns)?),
+            valid,
+            times,
+        })
+    }
+}
+
+/// The join keys and the times of the rows of a batch, in the order of the
+/// batch. The rows of null keys or null times are never matched.
+struct SortedKeys {
+    /// None if the join has no keys.
+    rows: Option<Rows>,
+    valid: BooleanArray,
+    times: Vec<Option<i64>>,
+}
+
+impl SortedKeys {
+    fn is_valid(&self, i: usize) -> bool {
+        self.valid.value(i) && self.times[i].is_some()
+    }
+
+    /// Compare the keys of the row `i` with the keys of the row `j` of `other`.
+    fn cmp_keys(&self, i: usize, other: &SortedKeys, j: usize) -> Ordering {
+        match (&self.rows, &other.rows) {
+            (Some(rows), Some(other_rows)) => rows.row(i).cmp(&other_rows.row(j)),
+            _ => Ordering::Equal,
+        }
+    }
+
+    fn owned_key(&self, i: usize) -> Option<OwnedRow> {
+        self.rows.as_ref().map(|rows| rows.row(i).owned())
+    }
+}
+
+struct RightCursor {
+    stream: SendableRecordBatchStream,
+    batch: Option<(RecordBatch, SortedKeys)>,
+    position: usize,
+    finished: bool,
+}
+
+/// The latest right row not after the time of the current left row.
+struct LastRow {
+    key: Option<OwnedRow>,
+    time: i64,
+    /// The row sliced from the right batch.
+    row: RecordBatch,
+    id: u64,
+}
+
+struct AsofJoinState {
+    joiner: Arc<AsofJoiner>,
+    converter: Option<RowConverter>,
+    left: SendableRecordBatchStream,
+    right: RightCursor,
+    last: Option<LastRow>,
+    last_id: u64,
+    right_nulls: Vec<ArrayRef>,
+    schema: SchemaRef,
+    reservation: MemoryReservation,
+}
+
+impl AsofJoinState {
+    async fn join(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let joiner = self.joiner.clone();
+        let left_keys = joiner.sorted_keys(
+            batch,
+            joiner.on.iter().map(|&(l, _)| l),
+            joiner.left_time,
+            self.converter.as_mut(),
+        )?;
+
+        let mut left_indices = Vec::with_capacity(batch.num_rows());
+        // the right rows matched, the first one is the row of nulls
+        let mut right_rows = vec![];
+        let mut right_indices = Vec::with_capacity(batch.num_rows());
+        let mut last_matched: Option<(u64, usize)> = None;
+        for row in 0..batch.num_rows() {
+            let matched = if left_keys.is_valid(row) {
+                self.advance(&left_keys, row).await?;
+                let time = left_keys.times[row].unwrap_or_default();
+                self.last.as_ref().filter(|last| {
+                    last.key == left_keys.owned_key(row)
+                        && joiner
+                            .tolerance
+                            .map_or(true, |tolerance| time - last.time <= tolerance)
+                })
+            } else {
+                None
+            };
+
+            match matched {
+                Some(last) => {
+                    let index = match last_matched {
+                        Some((id, index)) if id == last.id => index,
+                        _ => {
+                            right_rows.push(last.row.clone());
+                            let index = right_rows.len();
+                            last_matched = Some((last.id, index));
+                            index
+                        }
+                    };
+                    left_indices.push(row as u32);
+                    right_indices.push((index, 0));
+                }
+                None if joiner.join_type == JoinType::Left => {
+                    left_indices.push(row as u32);
+                    right_indices.push((0, 0));
+                }
+                None => {}
+            }
+        }
+
+        let left_indices = UInt32Array::from(left_indices);
+        let mut columns = batch
+            .columns()
+            .iter()
+            .map(|c| take(c.as_ref(), &left_indices, None))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        for (i, nulls) in self.right_nulls.iter().enumerate() {
+            let values = [nulls.as_ref()]
+                .into_iter()
+                .chain(right_rows.iter().map(|r| r.column(i).as_ref()))
+                .collect::<Vec<&dyn Array>>();
+            columns.push(interleave(&values, &right_indices)?);
+        }
+
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    /// Consume the right rows not after the keys and the time of the left row
+    /// `i`, the latest of them of the same keys becomes the last row.
+    async fn advance(&mut self, left_keys: &SortedKeys, i: usize) -> Result<()> {
+        let time = left_keys.times[i].unwrap_or_default();
+        loop {
+            let position = self.right.position;
+            let Some((batch, keys)) = self
+                .right
+                .batch
+                .as_ref()
+                .filter(|(batch, _)| position < batch.num_rows())
+            else {
+                if !self.next_right_batch().await? {
+                    return Ok(());
+                }
+                continue;
+            };
+
+            let j = position;
+            // null times are sorted first
+            let ordering = keys
+                .cmp_keys(j, left_keys, i)
+                .then_with(|| keys.times[j].map_or(Ordering::Less, |t| t.cmp(&time)));
+            if ordering == Ordering::Greater {
+                return Ok(());
+            }
+            if keys.is_valid(j) && keys.cmp_keys(j, left_keys, i) == Ordering::Equal {
+                self.last_id += 1;
+                self.last = Some(LastRow {
+                    key: keys.owned_key(j),
+                    time: keys.times[j].unwrap_or_default(),
+                    row: batch.slice(j, 1),
+                    id: self.last_id,
+                });
+            }
+            self.right.position += 1;
+        }
+    }
+
+    /// Read the next non-empty batch of the right input, returns false if the
+    /// right input is finished.
+    async fn next_right_batch(&mut self) -> Result<bool> {
+        if self.right.finished {
+            return Ok(false);
+        }
+        loop {
+            let batch = match self.right.stream.next().await {
+                Some(batch) => batch?,
+                None => {
+                    self.right.finished = true;
+                    self.right.batch = None;
+                    self.reservation.free();
+                    return Ok(false);
+                }
+            };
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            // the slice of the last row shares the buffers of its batch
+            let last_size = self
+                .last
+                .as_ref()
+                .map_or(0, |last| last.row.get_array_memory_size());
+            self.reservation
+                .try_resize(batch.get_array_memory_size() + last_size)?;
+            let keys = self.joiner.sorted_keys(
+                &batch,
+                self.joiner.on.iter().map(|&(_, r)| r),
+                self.joiner.right_time,
+                self.converter.as_mut(),
+            )?;
+            self.right.batch = Some((batch, keys));
+            self.right.position = 0;
+            return Ok(true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::compute::concat_batches;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::error::{DataFusionError, Result};
+    use datafusion::execution::context::SessionContext;
+    use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion::logical_expr::JoinType;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::{collect, ExecutionPlan};
+    use datafusion::prelude::SessionConfig;
+
+    use super::AsofJoinExec;
+
+    fn batch(stations: Vec<&str>, times: Vec<i64>, values: Vec<i64>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("station", DataType::Utf8, true),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("value", DataType::Int64, true),
+        ]));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(stations)),
+            Arc::new(TimestampNanosecondArray::from(times)),
+            Arc::new(Int64Array::from(values)),
+        ];
+        RecordBatch::try_new(schema, columns).unwrap()
+    }
+
+    /// Join the inputs sorted by the station and the time.
+    async fn asof_join(
+        tolerance: Option<i64>,
+        join_type: JoinType,
+        memory_limit: Option<usize>,
+    ) -> Result<RecordBatch> {
+        let left = batch(
+            vec!["a", "a", "b", "c"],
+            vec![10, 25, 10, 10],
+            vec![1, 2, 3, 4],
+        );
+        // the rows of a series are split into batches
+        let right = vec![
+            batch(vec!["a", "a"], vec![0, 20], vec![100, 200]),
+            batch(vec!["a", "b"], vec![30, 1], vec![300, 400]),
+        ];
+        let schema = left.schema();
+        let left = Arc::new(MemoryExec::try_new(&[vec![left]], schema.clone(), None)?);
+        let right = Arc::new(MemoryExec::try_new(&[right], schema, None)?);
+        let exec: Arc<dyn ExecutionPlan> = Arc::new(AsofJoinExec::try_new(
+            left,
+            right,
+            vec![(0, 0)],
+            1,
+            1,
+            tolerance,
+            join_type,
+        )?);
+
+        let mut runtime_config = RuntimeConfig::new();
+        if let Some(memory_limit) = memory_limit {
+            runtime_config = runtime_config.with_memory_limit(memory_limit, 1.0);
+        }
+        let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+        let ctx = SessionContext::with_config_rt(SessionConfig::new(), runtime);
+        let batches = collect(exec.clone(), ctx.task_ctx()).await?;
+        Ok(concat_batches(&exec.schema(), &batches)?)
+    }
+
+    #[tokio::test]
+    async fn test_asof_join() {
+        let output = asof_join(Some(5), JoinType::Left, None).await.unwrap();
+        assert_eq!(output.num_rows(), 4);
+        let right_values: ArrayRef = Arc::new(Int64Array::from(vec![None, Some(200), None, None]));
+        assert_eq!(output.column(5), &right_values);
+
+        let output = asof_join(None, JoinType::Inner, None).await.unwrap();
+        let left_values: ArrayRef = Arc::new(Int64Array::from(vec![1, 2, 3]));
+        let right_values: ArrayRef = Arc::new(Int64Array::from(vec![100, 200, 400]));
+        assert_eq!(output.column(2), &left_values);
+        assert_eq!(output.column(5), &right_values);
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_memory_limit() {
+        let err = asof_join(None, JoinType::Inner, Some(16))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, DataFusionError::ResourcesExhausted(_)),
+            "{err}"
+        );
+    }
+}
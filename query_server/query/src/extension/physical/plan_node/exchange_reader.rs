@@ -0,0 +1,134 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+};
+use futures::{StreamExt, TryStreamExt};
+use models::meta_data::NodeId;
+
+use crate::exchange::{ExchangeManager, ExchangeSide, ExchangeTicket};
+
+/// Reads a partition of an exchange from the node it is spilled on.
+#[derive(Debug)]
+pub struct ExchangeReaderExec {
+    schema: SchemaRef,
+    source: NodeId,
+    exchange_id: String,
+    token: String,
+    side: ExchangeSide,
+    partition: usize,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ExchangeReaderExec {
+    pub fn new(
+        schema: SchemaRef,
+        source: NodeId,
+        exchange_id: String,
+        token: String,
+        side: ExchangeSide,
+        partition: usize,
+    ) -> Self {
+        Self {
+            schema,
+            source,
+            exchange_id,
+            token,
+            side,
+            partition,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for ExchangeReaderExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let manager = ExchangeManager::get().ok_or_else(|| {
+            DataFusionError::Execution("Exchange is not available on this node".to_string())
+        })?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+        let stream = if self.source == manager.node_id() {
+            manager.read_partition(&self.exchange_id, &self.token, self.side, self.partition)?
+        } else {
+            let ticket = ExchangeTicket::Fetch {
+                exchange_id: self.exchange_id.clone(),
+                token: self.token.clone(),
+                side: self.side,
+                partition: self.partition,
+            };
+            let source = self.source;
+            futures::stream::once(async move { manager.do_get(source, &ticket).await })
+                .try_flatten()
+                .boxed()
+        };
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream.inspect_ok(move |batch| {
+                baseline_metrics.record_output(batch.num_rows());
+            }),
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "ExchangeReaderExec: source={}, exchange={}, side={}, partition={}",
+                    self.source,
+                    self.exchange_id,
+                    self.side.as_str(),
+                    self.partition
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
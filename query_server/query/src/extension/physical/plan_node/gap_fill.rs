@@ -0,0 +1,656 @@
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::array::{
+    Array, ArrayRef, BooleanArray, Float64Array, TimestampNanosecondArray, UInt32Array,
+};
+use datafusion::arrow::compute::kernels::zip::zip;
+use datafusion::arrow::compute::{
+    cast, concat_batches, lexicographical_partition_ranges, take, SortColumn,
+};
+use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::{as_float64_array, as_timestamp_nanosecond_array};
+use datafusion::common::Statistics;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::memory_pool::MemoryConsumer;
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{PhysicalSortExpr, PhysicalSortRequirement};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+use futures::TryStreamExt;
+
+use crate::extension::expr::{from_local_nanos, to_local_nanos};
+use crate::extension::logical::plan_node::gap_fill::{FillStrategy, GapFillParams};
+
+/// The rows filled by a query are limited, in case of a tiny window over a
+/// long time range.
+const MAX_GAP_FILL_ROWS: usize = 10_000_000;
+
+/// Fills the missing time windows of the aggregated series, the input is
+/// sorted by the group columns and then the time column.
+pub struct GapFillExec {
+    input: Arc<dyn ExecutionPlan>,
+    filler: Arc<GapFiller>,
+    sort_expr: Vec<PhysicalSortExpr>,
+}
+
+impl GapFillExec {
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        group_columns: Vec<usize>,
+        time_column: usize,
+        aggr_columns: Vec<(usize, FillStrategy)>,
+        params: GapFillParams,
+    ) -> Result<Self> {
+        if params.stride <= 0 {
+            return Err(DataFusionError::Internal(format!(
+                "GapFillExec expects a positive stride, found {}",
+                params.stride
+            )));
+        }
+        let time_zone = params.time_zone.as_deref().map(Tz::from_str).transpose()?;
+        let schema = input.schema();
+        let sort_expr = group_columns
+            .iter()
+            .chain([&time_column])
+            .map(|&i| PhysicalSortExpr {
+                expr: Arc::new(Column::new(schema.field(i).name(), i)),
+                options: Default::default(),
+            })
+            .collect();
+
+        Ok(Self {
+            input,
+            filler: Arc::new(GapFiller {
+                group_columns,
+                time_column,
+                aggr_columns,
+                params,
+                time_zone,
+            }),
+            sort_expr,
+        })
+    }
+}
+
+impl Debug for GapFillExec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GapFillExec")
+    }
+}
+
+impl ExecutionPlan for GapFillExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(
+            &self.sort_expr,
+        ))]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            input: children[0].clone(),
+            filler: self.filler.clone(),
+            sort_expr: self.sort_expr.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "GapFillExec invalid partition {partition}, there can be only one partition"
+            )));
+        }
+
+        let schema = self.schema();
+        let mut input = self.input.execute(partition, context.clone())?;
+        let filler = self.filler.clone();
+        // The input is buffered to fill the series, so it is accounted in the
+        // memory pool of the query along with the concatenated and the filled
+        // batches.
+        let mut reservation = MemoryConsumer::new(format!("GapFillExec[{partition}]"))
+            .register(context.memory_pool());
+        let output = futures::stream::once({
+            let schema = schema.clone();
+            async move {
+                let mut batches = vec![];
+                while let Some(batch) = input.try_next().await? {
+                    reservation.try_grow(batch.get_array_memory_size())?;
+                    batches.push(batch);
+                }
+                let batch = concat_batches(&schema, &batches)?;
+                reservation.try_grow(batch.get_array_memory_size())?;
+                drop(batches);
+                reservation.try_resize(batch.get_array_memory_size())?;
+
+                let filled = filler.fill(&batch)?;
+                reservation.try_grow(filled.get_array_memory_size())?;
+                drop(batch);
+                reservation.try_resize(filled.get_array_memory_size())?;
+                Ok(filled)
+            }
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, output)))
+    }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let schema = self.schema();
+        let GapFiller {
+            group_columns,
+            time_column,
+            aggr_columns,
+            params,
+            ..
+        } = self.filler.as_ref();
+        write!(
+            f,
+            "GapFillExec: group_columns=[{}], time_column={}, aggr_columns=[{}], stride={}, origin={}, time_zone={}",
+            group_columns
+                .iter()
+                .map(|&i| schema.field(i).name().as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            schema.field(*time_column).name(),
+            aggr_columns
+                .iter()
+                .map(|(i, s)| format!("{}: {s}", schema.field(*i).name()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            params.stride,
+            params.origin,
+            params.time_zone.as_deref().unwrap_or("UTC"),
+        )
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// A row of the output, the series is the index of the first input row of the
+/// series, the source is the index of the input row of the window if observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FilledRow {
+    series: usize,
+    time: i64,
+    source: Option<usize>,
+}
+
+#[derive(Debug)]
+struct GapFiller {
+    group_columns: Vec<usize>,
+    time_column: usize,
+    aggr_columns: Vec<(usize, FillStrategy)>,
+    params: GapFillParams,
+    /// The windows are stepped in the local time of the zone if it's set.
+    time_zone: Option<Tz>,
+}
+
+impl GapFiller {
+    fn to_local(&self, ts: i64) -> Result<i64> {
+        match &self.time_zone {
+            Some(tz) => to_local_nanos(tz, ts),
+            None => Ok(ts),
+        }
+    }
+
+    fn from_local(&self, local: i64) -> Result<i64> {
+        match &self.time_zone {
+            Some(tz) => from_local_nanos(tz, local),
+            None => Ok(local),
+        }
+    }
+
+    fn fill(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        if batch.num_rows() == 0 {
+            return Ok(batch.clone());
+        }
+
+        let times = cast(
+            batch.column(self.time_column),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None),
+        )?;
+        let times = as_timestamp_nanosecond_array(&times)?;
+        let ranges = if self.group_columns.is_empty() {
+            vec![0..batch.num_rows()]
+        } else {
+            let sort_columns = self
+                .group_columns
+                .iter()
+                .map(|&i| SortColumn {
+                    values: batch.column(i).clone(),
+                    options: None,
+                })
+                .collect::<Vec<_>>();
+            lexicographical_partition_ranges(&sort_columns)?.collect()
+        };
+
+        let mut rows = Vec::with_capacity(batch.num_rows());
+        for range in ranges {
+            self.fill_series(times, range, &mut rows)?;
+        }
+
+        let series = UInt32Array::from_iter_values(rows.iter().map(|r| r.series as u32));
+        let sources = rows
+            .iter()
+            .map(|r| r.source.map(|i| i as u32))
+            .collect::<UInt32Array>();
+        let columns = batch
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                if i == self.time_column {
+                    let times =
+                        TimestampNanosecondArray::from_iter_values(rows.iter().map(|r| r.time));
+                    Ok(cast(&times, column.data_type())?)
+                } else if self.group_columns.contains(&i) {
+                    Ok(take(column.as_ref(), &series, None)?)
+                } else {
+                    let fill_strategy = self
+                        .aggr_columns
+                        .iter()
+                        .find(|(c, _)| *c == i)
+                        .map(|(_, s)| s)
+                        .unwrap_or(&FillStrategy::Null);
+                    fill_column(column, fill_strategy, &rows, &sources)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RecordBatch::try_new(batch.schema(), columns)?)
+    }
+
+    /// Merge the windows of the series with the observed rows in `range`, the
+    /// windows and the observed times are compared in the local time.
+    fn fill_series(
+        &self,
+        times: &TimestampNanosecondArray,
+        range: Range<usize>,
+        rows: &mut Vec<FilledRow>,
+    ) -> Result<()> {
+        let stride = self.params.stride;
+        let observed_first = times.value(range.start);
+        let observed_last = times.value(range.end - 1);
+        let first = self.to_local(
+            self.params
+                .first_window
+                .map_or(observed_first, |w| w.min(observed_first)),
+        )?;
+        let last = self.to_local(
+            self.params
+                .last_window
+                .map_or(observed_last, |w| w.max(observed_last)),
+        )?;
+
+        let windows = (last as i128 - first as i128) / stride as i128 + 1;
+        if rows.len() as i128 + windows > MAX_GAP_FILL_ROWS as i128 {
+            return Err(DataFusionError::Execution(format!(
+                "Too many rows to be filled by time_window_gapfill, the max is {MAX_GAP_FILL_ROWS}"
+            )));
+        }
+
+        let mut row = range.start;
+        let mut window = first;
+        while window <= last || row < range.end {
+            let observed = if row < range.end {
+                Some(self.to_local(times.value(row))?)
+            } else {
+                None
+            };
+            if let Some(local) = observed.filter(|&local| local <= window) {
+                rows.push(FilledRow {
+                    series: range.start,
+                    time: times.value(row),
+                    source: Some(row),
+                });
+                if local == window {
+                    window += stride;
+                }
+                row += 1;
+            } else {
+                rows.push(FilledRow {
+                    series: range.start,
+                    time: self.from_local(window)?,
+                    source: None,
+                });
+                window += stride;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn fill_column(
+    column: &ArrayRef,
+    fill_strategy: &FillStrategy,
+    rows: &[FilledRow],
+    sources: &UInt32Array,
+) -> Result<ArrayRef> {
+    let observed_mask = || {
+        rows.iter()
+            .map(|r| Some(r.source.is_some()))
+            .collect::<BooleanArray>()
+    };
+
+    match fill_strategy {
+        FillStrategy::Null => Ok(take(column.as_ref(), sources, None)?),
+        FillStrategy::Previous => {
+            let mut series = None;
+            let mut previous = None;
+            let indices = rows
+                .iter()
+                .map(|r| {
+                    if series != Some(r.series) {
+                        series = Some(r.series);
+                        previous = None;
+                    }
+                    match r.source {
+                        Some(i) => {
+                            if column.is_valid(i) {
+                                previous = Some(i as u32);
+                            }
+                            Some(i as u32)
+                        }
+                        None => previous,
+                    }
+                })
+                .collect::<UInt32Array>();
+            Ok(take(column.as_ref(), &indices, None)?)
+        }
+        FillStrategy::Constant(value) => {
+            let observed = take(column.as_ref(), sources, None)?;
+            let filled = value.to_array_of_size(rows.len());
+            Ok(zip(&observed_mask(), &observed, &filled)?)
+        }
+        FillStrategy::Linear => {
+            let observed = take(column.as_ref(), sources, None)?;
+            let values = cast(&observed, &DataType::Float64)?;
+            let filled = interpolate(rows, as_float64_array(&values)?);
+            let filled = cast(&filled, column.data_type())?;
+            Ok(zip(&observed_mask(), &observed, &filled)?)
+        }
+    }
+}
+
+/// Interpolate the values of the missing windows linearly between the observed
+/// non-null values around them in the same series.
+fn interpolate(rows: &[FilledRow], values: &Float64Array) -> Float64Array {
+    let observed = |i: usize| {
+        (rows[i].source.is_some() && values.is_valid(i)).then(|| (rows[i].time, values.value(i)))
+    };
+
+    let mut next = vec![None; rows.len()];
+    let mut following = None;
+    for i in (0..rows.len()).rev() {
+        if i + 1 < rows.len() && rows[i + 1].series != rows[i].series {
+            following = None;
+        }
+        next[i] = following;
+        if let Some(p) = observed(i) {
+            following = Some(p);
+        }
+    }
+
+    let mut previous = None;
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            if i > 0 && rows[i - 1].series != row.series {
+                previous = None;
+            }
+            let value = match (row.source, previous, next[i]) {
+                (Some(_), _, _) => observed(i).map(|(_, v)| v),
+                (None, Some((t0, v0)), Some((t1, v1))) => {
+                    Some(v0 + (v1 - v0) * (row.time - t0) as f64 / (t1 - t0) as f64)
+                }
+                _ => None,
+            };
+            if let Some(p) = observed(i) {
+                previous = Some(p);
+            }
+            value
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use chrono::DateTime;
+    use datafusion::arrow::array::timezone::Tz;
+    use datafusion::arrow::array::{
+        Float64Array, Int64Array, StringArray, TimestampNanosecondArray, TimestampSecondArray,
+    };
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::arrow::util::pretty::pretty_format_batches;
+    use datafusion::common::cast::{as_float64_array, as_timestamp_nanosecond_array};
+    use datafusion::error::DataFusionError;
+    use datafusion::execution::context::SessionContext;
+    use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion::physical_plan::collect;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionConfig;
+    use datafusion::scalar::ScalarValue;
+
+    use super::{GapFillExec, GapFiller};
+    use crate::extension::logical::plan_node::gap_fill::{FillStrategy, GapFillParams};
+
+    #[test]
+    fn test_fill() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("time", DataType::Timestamp(TimeUnit::Second, None), false),
+            Field::new("null", DataType::Float64, true),
+            Field::new("previous", DataType::Float64, true),
+            Field::new("linear", DataType::Int64, true),
+            Field::new("constant", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["a", "a", "a", "b"])),
+                Arc::new(TimestampSecondArray::from(vec![10, 40, 50, 20])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0, 4.0])),
+                Arc::new(Float64Array::from(vec![
+                    Some(1.0),
+                    None,
+                    Some(3.0),
+                    Some(4.0),
+                ])),
+                Arc::new(Int64Array::from(vec![10, 40, 50, 20])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0, 4.0])),
+            ],
+        )
+        .unwrap();
+
+        let filler = GapFiller {
+            group_columns: vec![0],
+            time_column: 1,
+            aggr_columns: vec![
+                (2, FillStrategy::Null),
+                (3, FillStrategy::Previous),
+                (4, FillStrategy::Linear),
+                (5, FillStrategy::Constant(ScalarValue::Float64(Some(0.0)))),
+            ],
+            params: GapFillParams {
+                stride: 10_000_000_000,
+                origin: 0,
+                first_window: Some(0),
+                last_window: Some(30_000_000_000),
+                time_zone: None,
+            },
+            time_zone: None,
+        };
+        let result = filler.fill(&batch).unwrap();
+        let expected = "\
++------+---------------------+------+----------+--------+----------+
+| host | time                | null | previous | linear | constant |
++------+---------------------+------+----------+--------+----------+
+| a    | 1970-01-01T00:00:00 |      |          |        | 0.0      |
+| a    | 1970-01-01T00:00:10 | 1.0  | 1.0      | 10     | 1.0      |
+| a    | 1970-01-01T00:00:20 |      | 1.0      | 20     | 0.0      |
+| a    | 1970-01-01T00:00:30 |      | 1.0      | 30     | 0.0      |
+| a    | 1970-01-01T00:00:40 | 2.0  |          | 40     | 2.0      |
+| a    | 1970-01-01T00:00:50 | 3.0  | 3.0      | 50     | 3.0      |
+| b    | 1970-01-01T00:00:00 |      |          |        | 0.0      |
+| b    | 1970-01-01T00:00:10 |      |          |        | 0.0      |
+| b    | 1970-01-01T00:00:20 | 4.0  | 4.0      | 20     | 4.0      |
+| b    | 1970-01-01T00:00:30 |      | 4.0      |        | 0.0      |
++------+---------------------+------+----------+--------+----------+";
+        assert_eq!(
+            pretty_format_batches(&[result]).unwrap().to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_fill_in_time_zone() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let nanos = |rfc3339: &str| {
+            DateTime::parse_from_rfc3339(rfc3339)
+                .unwrap()
+                .timestamp_nanos_opt()
+                .unwrap()
+        };
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    nanos("2023-03-11T00:00:00-05:00"),
+                    nanos("2023-03-14T00:00:00-04:00"),
+                ])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0])),
+            ],
+        )
+        .unwrap();
+
+        // 2023-03-12 is 23 hours long in New York.
+        let filler = GapFiller {
+            group_columns: vec![],
+            time_column: 0,
+            aggr_columns: vec![(1, FillStrategy::Null)],
+            params: GapFillParams {
+                stride: 24 * 3_600_000_000_000,
+                origin: 0,
+                first_window: None,
+                last_window: None,
+                time_zone: Some("America/New_York".to_string()),
+            },
+            time_zone: Some(Tz::from_str("America/New_York").unwrap()),
+        };
+        let result = filler.fill(&batch).unwrap();
+        let times = as_timestamp_nanosecond_array(result.column(0)).unwrap();
+        assert_eq!(
+            times.values().to_vec(),
+            vec![
+                nanos("2023-03-11T00:00:00-05:00"),
+                nanos("2023-03-12T00:00:00-05:00"),
+                nanos("2023-03-13T00:00:00-04:00"),
+                nanos("2023-03-14T00:00:00-04:00"),
+            ]
+        );
+        let values = as_float64_array(result.column(1)).unwrap();
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            vec![Some(1.0), None, None, Some(2.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fill_memory_limit() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from_iter_values(0..1000)),
+                Arc::new(Float64Array::from_iter_values((0..1000).map(|v| v as f64))),
+            ],
+        )
+        .unwrap();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap());
+        let exec = GapFillExec::try_new(
+            input,
+            vec![],
+            0,
+            vec![(1, FillStrategy::Null)],
+            GapFillParams {
+                stride: 1,
+                origin: 0,
+                first_window: None,
+                last_window: None,
+                time_zone: None,
+            },
+        )
+        .unwrap();
+
+        let runtime = RuntimeEnv::new(RuntimeConfig::new().with_memory_limit(1024, 1.0)).unwrap();
+        let ctx = SessionContext::with_config_rt(SessionConfig::new(), Arc::new(runtime));
+        let err = collect(Arc::new(exec), ctx.task_ctx()).await.unwrap_err();
+        assert!(
+            matches!(err, DataFusionError::ResourcesExhausted(_)),
+            "{err}"
+        );
+    }
+}
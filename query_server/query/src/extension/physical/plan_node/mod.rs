@@ -5,8 +5,12 @@ use datafusion::error::DataFusionError;
 use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, Time};
 
 pub mod aggregate_filter_scan;
+pub mod asof_join;
 pub mod assert;
+pub mod exchange_reader;
 pub mod expand;
+pub mod gap_fill;
+pub mod shuffle_hash_join;
 pub mod state_restore;
 pub mod state_save;
 pub mod table_writer;
@@ -0,0 +1,471 @@
+use std::any::Any;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::logical_expr::JoinType;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::joins::utils::{JoinFilter, JoinOn};
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion::physical_plan::metrics::{
+    ExecutionPlanMetricsSet, MetricBuilder, MetricsSet, Time,
+};
+use datafusion::physical_plan::repartition::BatchPartitioner;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr, SendableRecordBatchStream,
+    Statistics,
+};
+use futures::{StreamExt, TryStreamExt};
+use models::meta_data::NodeId;
+use tokio::sync::OnceCell;
+use trace::debug;
+
+use crate::exchange::spill::SpillWriter;
+use crate::exchange::{ExchangeDir, ExchangeManager, ExchangeSide, ExchangeTicket, RemoteJoin};
+use crate::extension::physical::plan_node::exchange_reader::ExchangeReaderExec;
+
+/// Hash join whose inputs are shuffled into partitions spilled to disk.
+///
+/// Both inputs are hash-partitioned by the join keys once, when the first
+/// partition is executed. Each partition is then joined by one of the data
+/// nodes of the cluster, so only one partition of the build side is kept in
+/// the memory of a node at a time. Joins with a filter are executed on this
+/// node only.
+#[derive(Debug)]
+pub struct ShuffleHashJoinExec {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: JoinOn,
+    filter: Option<JoinFilter>,
+    join_type: JoinType,
+    null_equals_null: bool,
+    partitions: usize,
+    schema: SchemaRef,
+    shuffled: Arc<OnceCell<Arc<ShuffledPartitions>>>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ShuffleHashJoinExec {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        filter: Option<JoinFilter>,
+        join_type: JoinType,
+        null_equals_null: bool,
+        partitions: usize,
+        schema: SchemaRef,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            on,
+            filter,
+            join_type,
+            null_equals_null,
+            partitions,
+            schema,
+            shuffled: Arc::new(OnceCell::new()),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for ShuffleHashJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.partitions)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(ShuffleHashJoinExec::new(
+            children[0].clone(),
+            children[1].clone(),
+            self.on.clone(),
+            self.filter.clone(),
+            self.join_type,
+            self.null_equals_null,
+            self.partitions,
+            self.schema.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let manager = ExchangeManager::get().ok_or_else(|| {
+            DataFusionError::Execution("Exchange is not available on this node".to_string())
+        })?;
+        let output_rows = MetricBuilder::new(&self.metrics).output_rows(partition);
+
+        let shuffled = self.shuffled.clone();
+        let left = self.left.clone();
+        let right = self.right.clone();
+        let partitions = self.partitions;
+        let partition_join = PartitionJoin {
+            left_schema: self.left.schema(),
+            right_schema: self.right.schema(),
+            on: self.on.clone(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            null_equals_null: self.null_equals_null,
+        };
+        let future = async move {
+            let shuffled = shuffled
+                .get_or_try_init(|| {
+                    shuffle(
+                        manager.clone(),
+                        left,
+                        right,
+                        partition_join.on.clone(),
+                        partitions,
+                        context.clone(),
+                    )
+                })
+                .await?
+                .clone();
+
+            let join = partition_join.build(manager.node_id(), &shuffled.dir, partition)?;
+            let stream = match shuffled.worker(partition) {
+                Some(node_id)
+                    if partition_join.filter.is_none() && node_id != manager.node_id() =>
+                {
+                    debug!(
+                        "Join partition {partition} of exchange {} on node {node_id}",
+                        shuffled.dir.id()
+                    );
+                    let ticket = ExchangeTicket::Join(RemoteJoin::new(
+                        manager.node_id(),
+                        shuffled.dir.id().to_string(),
+                        shuffled.dir.token().to_string(),
+                        partition,
+                        &join,
+                    )?);
+                    manager.do_get(node_id, &ticket).await?
+                }
+                _ => join.execute(0, context)?.boxed(),
+            };
+
+            // The spilled partitions are kept until the output is consumed.
+            Ok::<_, DataFusionError>(stream.map(move |batch| {
+                let _shuffled = &shuffled;
+                batch
+            }))
+        };
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::once(future)
+                .try_flatten()
+                .inspect_ok(move |batch| output_rows.add(batch.num_rows())),
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(c1, c2)| format!("({}, {})", c1, c2))
+                    .collect::<Vec<String>>();
+                let display_filter = self.filter.as_ref().map_or_else(
+                    || "".to_string(),
+                    |f| format!(", filter={}", f.expression()),
+                );
+                write!(
+                    f,
+                    "ShuffleHashJoinExec: join_type={:?}, on=[{}]{}, partitions={}",
+                    self.join_type,
+                    on.join(", "),
+                    display_filter,
+                    self.partitions
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Joins a partition of the shuffled inputs.
+struct PartitionJoin {
+    left_schema: SchemaRef,
+    right_schema: SchemaRef,
+    on: JoinOn,
+    filter: Option<JoinFilter>,
+    join_type: JoinType,
+    null_equals_null: bool,
+}
+
+impl PartitionJoin {
+    fn build(
+        &self,
+        source: NodeId,
+        exchange: &ExchangeDir,
+        partition: usize,
+    ) -> Result<HashJoinExec> {
+        let reader = |side, schema| {
+            Arc::new(ExchangeReaderExec::new(
+                schema,
+                source,
+                exchange.id().to_string(),
+                exchange.token().to_string(),
+                side,
+                partition,
+            )) as Arc<dyn ExecutionPlan>
+        };
+
+        HashJoinExec::try_new(
+            reader(ExchangeSide::Left, self.left_schema.clone()),
+            reader(ExchangeSide::Right, self.right_schema.clone()),
+            self.on.clone(),
+            self.filter.clone(),
+            &self.join_type,
+            PartitionMode::CollectLeft,
+            self.null_equals_null,
+        )
+    }
+}
+
+/// The partitions of both inputs spilled on this node.
+#[derive(Debug)]
+struct ShuffledPartitions {
+    dir: ExchangeDir,
+    /// Nodes joining the partitions, partition `p` is joined by
+    /// `workers[p % workers.len()]`.
+    workers: Vec<NodeId>,
+}
+
+impl ShuffledPartitions {
+    fn worker(&self, partition: usize) -> Option<NodeId> {
+        if self.workers.is_empty() {
+            return None;
+        }
+        Some(self.workers[partition % self.workers.len()])
+    }
+}
+
+async fn shuffle(
+    manager: Arc<ExchangeManager>,
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: JoinOn,
+    partitions: usize,
+    context: Arc<TaskContext>,
+) -> Result<Arc<ShuffledPartitions>> {
+    let dir = manager.create_exchange()?;
+    let (left_keys, right_keys): (Vec<_>, Vec<_>) = on
+        .into_iter()
+        .map(|(l, r)| {
+            (
+                Arc::new(l) as Arc<dyn PhysicalExpr>,
+                Arc::new(r) as Arc<dyn PhysicalExpr>,
+            )
+        })
+        .unzip();
+    spill_input(
+        left,
+        left_keys,
+        partitions,
+        dir.side_path(ExchangeSide::Left),
+        context.clone(),
+    )
+    .await?;
+    spill_input(
+        right,
+        right_keys,
+        partitions,
+        dir.side_path(ExchangeSide::Right),
+        context,
+    )
+    .await?;
+
+    let workers = manager.workers().await;
+    debug!(
+        "Shuffled inputs of the join into exchange {} with {partitions} partitions, workers: {workers:?}",
+        dir.id()
+    );
+
+    Ok(Arc::new(ShuffledPartitions { dir, workers }))
+}
+
+/// Hash-partition all partitions of the input by the keys into the spill files
+/// under `dir`.
+async fn spill_input(
+    input: Arc<dyn ExecutionPlan>,
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    partitions: usize,
+    dir: PathBuf,
+    context: Arc<TaskContext>,
+) -> Result<()> {
+    let tasks = (0..input.output_partitioning().partition_count()).map(|i| {
+        let stream = input.execute(i, context.clone());
+        let keys = keys.clone();
+        let dir = dir.clone();
+        async move {
+            let mut stream = stream?;
+            let mut partitioner =
+                BatchPartitioner::try_new(Partitioning::Hash(keys, partitions), Time::new())?;
+            let mut writer = SpillWriter::new(dir, i, stream.schema(), partitions);
+            while let Some(batch) = stream.next().await {
+                partitioner.partition(batch?, |p, batch| writer.write(p, &batch))?;
+            }
+            writer.finish()
+        }
+    });
+    futures::future::try_join_all(tasks).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use coordinator::service_mock::MockCoordinator;
+    use datafusion::arrow::array::{Array, Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::common::cast::{as_int64_array, as_string_array};
+    use datafusion::logical_expr::JoinType;
+    use datafusion::physical_plan::expressions::Column;
+    use datafusion::physical_plan::joins::utils::build_join_schema;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::{collect, ExecutionPlan};
+    use datafusion::prelude::SessionContext;
+    use memory_pool::GreedyMemoryPool;
+
+    use super::ShuffleHashJoinExec;
+    use crate::exchange::ExchangeManager;
+
+    fn batch(schema: &SchemaRef, keys: Vec<i64>, values: Vec<&str>) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(keys)),
+                Arc::new(StringArray::from(values)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_shuffle_hash_join() {
+        let dir = std::path::PathBuf::from("/tmp/test/query/exchange/test_shuffle_hash_join");
+        // Another test may have installed the manager of the process already.
+        ExchangeManager::new(
+            Arc::new(MockCoordinator::default()),
+            dir,
+            Arc::new(GreedyMemoryPool::default()),
+            0,
+        )
+        .install();
+        assert!(ExchangeManager::get().is_some());
+
+        let left_schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int64, false),
+            Field::new("l", DataType::Utf8, true),
+        ]));
+        let right_schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int64, false),
+            Field::new("r", DataType::Utf8, true),
+        ]));
+        // The left input has two partitions.
+        let left = Arc::new(
+            MemoryExec::try_new(
+                &[
+                    vec![batch(&left_schema, vec![1, 2, 3], vec!["a", "b", "c"])],
+                    vec![batch(&left_schema, vec![4, 5], vec!["d", "e"])],
+                ],
+                left_schema.clone(),
+                None,
+            )
+            .unwrap(),
+        );
+        let right = Arc::new(
+            MemoryExec::try_new(
+                &[vec![
+                    batch(&right_schema, vec![2, 4, 4], vec!["x", "y", "z"]),
+                    batch(&right_schema, vec![5, 6], vec!["u", "v"]),
+                ]],
+                right_schema.clone(),
+                None,
+            )
+            .unwrap(),
+        );
+        let (schema, _) = build_join_schema(&left_schema, &right_schema, &JoinType::Inner);
+        let join = Arc::new(ShuffleHashJoinExec::new(
+            left,
+            right,
+            vec![(Column::new("k", 0), Column::new("k", 0))],
+            None,
+            JoinType::Inner,
+            false,
+            3,
+            Arc::new(schema),
+        ));
+        assert_eq!(join.output_partitioning().partition_count(), 3);
+
+        let batches = collect(join, SessionContext::new().task_ctx())
+            .await
+            .unwrap();
+        let mut rows = vec![];
+        for batch in batches {
+            let left_keys = as_int64_array(batch.column(0)).unwrap();
+            let left_values = as_string_array(batch.column(1)).unwrap();
+            let right_keys = as_int64_array(batch.column(2)).unwrap();
+            let right_values = as_string_array(batch.column(3)).unwrap();
+            for i in 0..batch.num_rows() {
+                assert!(!right_values.is_null(i));
+                rows.push((
+                    left_keys.value(i),
+                    left_values.value(i).to_string(),
+                    right_keys.value(i),
+                    right_values.value(i).to_string(),
+                ));
+            }
+        }
+        rows.sort();
+        let expected = [
+            (2, "b", 2, "x"),
+            (4, "d", 4, "y"),
+            (4, "d", 4, "z"),
+            (5, "e", 5, "u"),
+        ]
+        .into_iter()
+        .map(|(lk, l, rk, r)| (lk, l.to_string(), rk, r.to_string()))
+        .collect::<Vec<_>>();
+        assert_eq!(rows, expected);
+    }
+}
@@ -1,9 +1,10 @@
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use datafusion::arrow::array::UInt64Array;
+use datafusion::arrow::array::{ArrayRef, UInt64Array};
 use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result};
@@ -19,8 +20,12 @@ use datafusion::physical_plan::{
     Statistics,
 };
 use futures::TryStreamExt;
-use spi::query::AFFECTED_ROWS;
-use trace::debug;
+use models::meta_data::ReplicationSetId;
+use models::predicate::domain::TimeRange;
+use serde::Serialize;
+use spi::query::{AFFECTED_BYTES, AFFECTED_ROWS};
+use spi::QueryError;
+use trace::{debug, warn};
 
 use crate::data_source::{RecordBatchSink, RecordBatchSinkProvider, SinkMetadata};
 
@@ -59,6 +64,22 @@ impl TableWriterExec {
     pub fn sink_schema(&self) -> SchemaRef {
         self.record_batch_sink_provider.schema()
     }
+
+    /// Output the bytes written besides the rows.
+    pub fn with_bytes_output(&self) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(AFFECTED_ROWS.0, AFFECTED_ROWS.1, false),
+            Field::new(AFFECTED_BYTES.0, AFFECTED_BYTES.1, false),
+        ]));
+
+        Self {
+            input: self.input.clone(),
+            table: self.table.clone(),
+            metrics: self.metrics.clone(),
+            schema,
+            record_batch_sink_provider: self.record_batch_sink_provider.clone(),
+        }
+    }
 }
 
 impl Debug for TableWriterExec {
@@ -134,8 +155,8 @@ impl ExecutionPlan for TableWriterExec {
             self.schema(),
             futures::stream::once(do_write(
                 self.schema.clone(),
+                WriteProgress::new(self.table.clone(), partition, record_batch_sink),
                 input,
-                record_batch_sink,
                 metrics,
             ))
             .try_flatten(),
@@ -175,22 +196,105 @@ impl ExecutionPlan for TableWriterExec {
 
 async fn do_write(
     schema: SchemaRef,
+    mut progress: WriteProgress,
     input: SendableRecordBatchStream,
-    record_batch_sink: Box<dyn RecordBatchSink>,
     metrics: TableWriterMetrics,
 ) -> Result<SendableRecordBatchStream> {
     let timer = metrics.elapsed_write().timer();
-    let sink_metadata = record_batch_sink
-        .stream_write(input)
-        .await
-        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let result = progress
+        .sink
+        .stream_write(input, &mut progress.written)
+        .await;
     timer.done();
 
-    metrics.record_rows_writed(sink_metadata.rows_writed());
-    metrics.record_bytes_writed(sink_metadata.bytes_writed());
+    metrics.record_rows_writed(progress.written.rows_writed());
+    metrics.record_bytes_writed(progress.written.bytes_writed());
     metrics.done();
 
-    aggregate_statistiction(schema, sink_metadata)
+    match result {
+        Ok(()) => aggregate_statistiction(schema, progress.finish()),
+        Err(e) => Err(progress.fail(e)),
+    }
+}
+
+/// Rows written by a partition of the table writer, reported and cleaned up
+/// by the sink if the write fails or is cancelled after some rows are written.
+struct WriteProgress {
+    table: String,
+    partition: usize,
+    sink: Box<dyn RecordBatchSink>,
+    written: SinkMetadata,
+    finished: bool,
+}
+
+#[derive(Serialize)]
+struct PartialWriteReport<'a> {
+    table: &'a str,
+    partition: usize,
+    rows_writed: usize,
+    bytes_writed: usize,
+    /// <replica_id, rows>
+    replica_rows: &'a BTreeMap<ReplicationSetId, usize>,
+    time_range: Option<&'a TimeRange>,
+}
+
+impl WriteProgress {
+    fn new(table: String, partition: usize, sink: Box<dyn RecordBatchSink>) -> Self {
+        Self {
+            table,
+            partition,
+            sink,
+            written: SinkMetadata::default(),
+            finished: false,
+        }
+    }
+
+    /// The rows of the batch being written when the write stopped may be
+    /// written partly, they are not counted.
+    fn report(&self) -> String {
+        let report = PartialWriteReport {
+            table: &self.table,
+            partition: self.partition,
+            rows_writed: self.written.rows_writed(),
+            bytes_writed: self.written.bytes_writed(),
+            replica_rows: self.written.replica_rows(),
+            time_range: self.written.time_range(),
+        };
+        serde_json::to_string(&report).unwrap_or_else(|e| e.to_string())
+    }
+
+    fn finish(mut self) -> SinkMetadata {
+        self.finished = true;
+        std::mem::take(&mut self.written)
+    }
+
+    fn fail(mut self, e: QueryError) -> DataFusionError {
+        self.finished = true;
+        if self.written.rows_writed() == 0 {
+            return DataFusionError::External(Box::new(e));
+        }
+
+        let report = self.report();
+        warn!("Write to table {} failed, {report}: {e}", self.table);
+        self.sink.cleanup(&self.written);
+        DataFusionError::External(Box::new(QueryError::PartialWrite {
+            report,
+            source: Box::new(e),
+        }))
+    }
+}
+
+impl Drop for WriteProgress {
+    fn drop(&mut self) {
+        if !self.finished && self.written.rows_writed() > 0 {
+            warn!(
+                "Write to table {} is cancelled, {}",
+                self.table,
+                self.report()
+            );
+            self.sink.cleanup(&self.written);
+        }
+    }
 }
 
 fn aggregate_statistiction(
@@ -200,8 +304,13 @@ fn aggregate_statistiction(
     let rows_writed = metrics.rows_writed();
 
     let output_rows_col = Arc::new(UInt64Array::from(vec![rows_writed as u64]));
+    let mut columns: Vec<ArrayRef> = vec![output_rows_col];
+    if schema.fields().len() > 1 {
+        let bytes_writed = metrics.bytes_writed();
+        columns.push(Arc::new(UInt64Array::from(vec![bytes_writed as u64])));
+    }
 
-    let batch = RecordBatch::try_new(schema.clone(), vec![output_rows_col])?;
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
 
     Ok(Box::pin(MemoryStream::try_new(vec![batch], schema, None)?))
 }
@@ -270,3 +379,99 @@ impl TableWriterMetrics {
         self.end_time.record()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::predicate::domain::TimeRange;
+    use spi::{QueryError, QueryResult};
+
+    use super::WriteProgress;
+    use crate::data_source::{RecordBatchSink, SinkMetadata};
+
+    /// Records the rows cleaned up.
+    #[derive(Default)]
+    struct CleanupSink {
+        cleaned: Arc<Mutex<Vec<(usize, Option<TimeRange>)>>>,
+    }
+
+    #[async_trait]
+    impl RecordBatchSink for CleanupSink {
+        async fn append(&self, _record_batch: RecordBatch) -> QueryResult<SinkMetadata> {
+            Ok(SinkMetadata::default())
+        }
+
+        fn cleanup(&self, written: &SinkMetadata) {
+            self.cleaned
+                .lock()
+                .unwrap()
+                .push((written.rows_writed(), written.time_range().copied()));
+        }
+    }
+
+    fn progress(cleaned: &Arc<Mutex<Vec<(usize, Option<TimeRange>)>>>) -> WriteProgress {
+        let sink = CleanupSink {
+            cleaned: cleaned.clone(),
+        };
+        WriteProgress::new("t".to_string(), 1, Box::new(sink))
+    }
+
+    #[test]
+    fn test_partial_write_report() {
+        let cleaned = Arc::default();
+        let mut progress = progress(&cleaned);
+        progress.written.merge(
+            SinkMetadata::new(3, 30).with_replica_rows([(1, 2), (2, 1)], TimeRange::new(5, 9)),
+        );
+        progress
+            .written
+            .merge(SinkMetadata::new(2, 20).with_replica_rows([(1, 2)], TimeRange::new(1, 3)));
+        assert_eq!(
+            progress.report(),
+            r#"{"table":"t","partition":1,"rows_writed":5,"bytes_writed":50,"replica_rows":{"1":4,"2":1},"time_range":{"min_ts":1,"max_ts":9}}"#
+        );
+
+        let written = progress.finish();
+        assert_eq!(written.rows_writed(), 5);
+        assert!(cleaned.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_partial_write() {
+        let cleaned = Arc::default();
+
+        // Nothing is written.
+        let progress = progress(&cleaned);
+        progress.fail(QueryError::Internal {
+            reason: "failed".to_string(),
+        });
+        drop(progress(&cleaned));
+        assert!(cleaned.lock().unwrap().is_empty());
+
+        let mut failed = progress(&cleaned);
+        failed
+            .written
+            .merge(SinkMetadata::new(2, 20).with_replica_rows([(1, 2)], TimeRange::new(1, 3)));
+        let err = failed.fail(QueryError::Internal {
+            reason: "failed".to_string(),
+        });
+        assert!(err.to_string().contains("partial write"), "{err}");
+
+        let mut cancelled = progress(&cleaned);
+        cancelled
+            .written
+            .merge(SinkMetadata::new(1, 10).with_replica_rows([(2, 1)], TimeRange::new(7, 7)));
+        drop(cancelled);
+
+        assert_eq!(
+            *cleaned.lock().unwrap(),
+            vec![
+                (2, Some(TimeRange::new(1, 3))),
+                (1, Some(TimeRange::new(7, 7)))
+            ]
+        );
+    }
+}
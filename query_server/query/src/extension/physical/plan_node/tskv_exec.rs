@@ -1,16 +1,20 @@
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 use std::task::Poll;
 
 use coordinator::service::CoordinatorRef;
 use coordinator::SendableCoordinatorRecordBatchStream;
+use datafusion::arrow::array::StringArray;
 use datafusion::arrow::datatypes::{SchemaRef, TimeUnit};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DFResult};
 use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::MemoryConsumer;
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
     Statistics,
@@ -18,6 +22,7 @@ use datafusion::physical_plan::{
 use futures::{Stream, StreamExt};
 use models::codec::Encoding;
 use models::datafusion::limit_record_batch::limit_record_batch;
+use models::encryption::ColumnCipher;
 use models::predicate::domain::PredicateRef;
 use models::predicate::PlacedSplit;
 use models::schema::tskv_table_schema::{
@@ -28,6 +33,7 @@ use snafu::ResultExt;
 use spi::{CommonSnafu, CoordinatorSnafu, QueryResult};
 use trace::span_ext::SpanExt;
 use trace::{debug, Span, SpanContext};
+use tskv::reader::scan_metrics::ScanMetrics;
 use tskv::reader::QueryOption;
 
 use crate::extension::physical::plan_node::TableScanMetrics;
@@ -41,6 +47,8 @@ pub struct TskvExec {
     filter: PredicateRef,
     coord: CoordinatorRef,
     splits: Vec<PlacedSplit>,
+    /// Ciphers of the encrypted columns to be decrypted, by column name.
+    decryptors: Arc<HashMap<String, ColumnCipher>>,
 
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
@@ -62,9 +70,19 @@ impl TskvExec {
             filter,
             coord,
             splits,
+            decryptors: Default::default(),
             metrics,
         }
     }
+
+    pub(crate) fn with_decryptors(
+        mut self,
+        decryptors: Arc<HashMap<String, ColumnCipher>>,
+    ) -> Self {
+        self.decryptors = decryptors;
+        self
+    }
+
     pub fn filter(&self) -> PredicateRef {
         self.filter.clone()
     }
@@ -101,6 +119,7 @@ impl ExecutionPlan for TskvExec {
             filter: self.filter.clone(),
             coord: self.coord.clone(),
             splits: self.splits.clone(),
+            decryptors: self.decryptors.clone(),
             metrics: self.metrics.clone(),
         }))
     }
@@ -127,6 +146,7 @@ impl ExecutionPlan for TskvExec {
         let batch_size = context.session_config().batch_size();
 
         let metrics = TableScanMetrics::new(&self.metrics, partition);
+        let scan_metrics = ScanMetrics::new(&self.metrics, partition);
 
         let span_ctx = context.session_config().get_extension::<SpanContext>();
 
@@ -137,6 +157,7 @@ impl ExecutionPlan for TskvExec {
             split,
             batch_size,
             metrics,
+            scan_metrics,
             Span::from_context(
                 format!("TableScanStream ({partition})"),
                 span_ctx.as_deref(),
@@ -144,7 +165,24 @@ impl ExecutionPlan for TskvExec {
         )
         .map_err(|err| DataFusionError::External(Box::new(err)))?;
 
-        Ok(Box::pin(table_stream))
+        // Account the batch being handed to the downstream operators,
+        // so that a query fails once its memory limit is exceeded.
+        let mut reservation =
+            MemoryConsumer::new(format!("TskvExec[{partition}]")).register(context.memory_pool());
+        let decryptors = self.decryptors.clone();
+        let stream = table_stream.map(move |batch| {
+            let batch = if decryptors.is_empty() {
+                batch?
+            } else {
+                decrypt_record_batch(&decryptors, batch?)?
+            };
+            reservation.try_resize(batch.get_array_memory_size())?;
+            Ok(batch)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
     }
 
     fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
@@ -189,6 +227,39 @@ impl std::fmt::Debug for TskvExec {
     }
 }
 
+fn decrypt_record_batch(
+    decryptors: &HashMap<String, ColumnCipher>,
+    batch: RecordBatch,
+) -> DFResult<RecordBatch> {
+    let schema = batch.schema();
+    let mut columns = batch.columns().to_vec();
+    for (field, array) in schema.fields().iter().zip(columns.iter_mut()) {
+        let Some(cipher) = decryptors.get(field.name()) else {
+            continue;
+        };
+        let values = array
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!("column {} is not StringArray", field.name()))
+            })?
+            .iter()
+            .map(|v| {
+                v.map(|v| {
+                    let value = cipher
+                        .decrypt(v.as_bytes())
+                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                    String::from_utf8(value).map_err(|e| DataFusionError::External(Box::new(e)))
+                })
+                .transpose()
+            })
+            .collect::<DFResult<StringArray>>()?;
+        *array = Arc::new(values);
+    }
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
 /// A wrapper to customize PredicateRef display
 struct PredicateDisplay<'a>(&'a PredicateRef);
 
@@ -227,6 +298,7 @@ impl TableScanStream {
         split: PlacedSplit,
         batch_size: usize,
         metrics: TableScanMetrics,
+        scan_metrics: ScanMetrics,
         span: Span,
     ) -> QueryResult<Self> {
         let mut proj_fileds = Vec::with_capacity(proj_schema.fields().len());
@@ -275,7 +347,8 @@ impl TableScanStream {
             proj_schema.clone(),
             proj_table_schema.into(),
             table_schema.meta(),
-        );
+        )
+        .with_scan_metrics(scan_metrics);
 
         let span_ctx = span.context();
         let iterator = coord
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::common::DFSchema;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+use datafusion::prelude::Expr;
+
+use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
+use crate::extension::physical::plan_node::asof_join::AsofJoinExec;
+use crate::extension::utils::downcast_plan_node;
+
+/// Physical planner for AsofJoin nodes
+pub struct AsofJoinPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for AsofJoinPlanner {
+    /// Create a physical plan for an extension node
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let asof_join = match downcast_plan_node::<AsofJoinNode>(node) {
+            Some(asof_join) => asof_join,
+            None => return Ok(None),
+        };
+        if physical_inputs.len() != 2 || logical_inputs.len() != 2 {
+            return Err(DataFusionError::Internal(format!(
+                "AsofJoin node must have exactly two inputs, got {}",
+                physical_inputs.len()
+            )));
+        }
+
+        let left_schema = logical_inputs[0].schema();
+        let right_schema = logical_inputs[1].schema();
+        let on = asof_join
+            .on
+            .iter()
+            .map(|(l, r)| {
+                Ok((
+                    column_index(l, left_schema)?,
+                    column_index(r, right_schema)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Arc::new(AsofJoinExec::try_new(
+            physical_inputs[0].clone(),
+            physical_inputs[1].clone(),
+            on,
+            column_index(&asof_join.left_time, left_schema)?,
+            column_index(&asof_join.right_time, right_schema)?,
+            asof_join.tolerance,
+            asof_join.join_type,
+        )?)))
+    }
+}
+
+fn column_index(expr: &Expr, schema: &DFSchema) -> Result<usize> {
+    match expr {
+        Expr::Column(c) => schema.index_of_column(c),
+        _ => Err(DataFusionError::NotImplemented(format!(
+            "AsofJoin only supports columns as the join keys, but found {expr}"
+        ))),
+    }
+}
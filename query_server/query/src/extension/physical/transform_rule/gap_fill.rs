@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::common::DFSchema;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+use datafusion::prelude::Expr;
+
+use crate::extension::logical::plan_node::gap_fill::GapFillNode;
+use crate::extension::physical::plan_node::gap_fill::GapFillExec;
+use crate::extension::utils::downcast_plan_node;
+
+/// Physical planner for GapFill nodes
+pub struct GapFillPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for GapFillPlanner {
+    /// Create a physical plan for an extension node
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let gap_fill = match downcast_plan_node::<GapFillNode>(node) {
+            Some(gap_fill) => gap_fill,
+            None => return Ok(None),
+        };
+        if physical_inputs.len() != 1 || logical_inputs.len() != 1 {
+            return Err(DataFusionError::Internal(format!(
+                "GapFill node must have exactly one input, got {}",
+                physical_inputs.len()
+            )));
+        }
+
+        let schema = logical_inputs[0].schema();
+        let group_columns = gap_fill
+            .group_expr
+            .iter()
+            .map(|e| column_index(e, schema))
+            .collect::<Result<Vec<_>>>()?;
+        let time_column = column_index(&gap_fill.time_expr, schema)?;
+        let aggr_columns = gap_fill
+            .aggr_expr
+            .iter()
+            .zip(&gap_fill.fill_strategy)
+            .map(|(e, s)| Ok((column_index(e, schema)?, s.clone())))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Arc::new(GapFillExec::try_new(
+            physical_inputs[0].clone(),
+            group_columns,
+            time_column,
+            aggr_columns,
+            gap_fill.params.clone(),
+        )?)))
+    }
+}
+
+fn column_index(expr: &Expr, schema: &DFSchema) -> Result<usize> {
+    match expr {
+        Expr::Column(c) => schema.index_of_column(c),
+        _ => Err(DataFusionError::Internal(format!(
+            "GapFill expects columns, but found {expr}"
+        ))),
+    }
+}
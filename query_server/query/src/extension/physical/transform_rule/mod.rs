@@ -1,5 +1,7 @@
 //! logical paln to physical plan transform rule
+pub mod asof_join;
 pub mod expand;
+pub mod gap_fill;
 pub mod stream_scan;
 pub mod table_writer;
 pub mod tag_scan;
@@ -9,6 +9,7 @@ use datafusion::physical_plan::{displayable, ExecutionPlan};
 use datafusion::physical_planner::{
     create_aggregate_expr_and_maybe_filter, ExtensionPlanner, PhysicalPlanner,
 };
+use spi::query::AFFECTED_BYTES;
 use trace::{debug, trace};
 
 use crate::data_source::{source_downcast_adapter, WriteExecExt};
@@ -35,6 +36,7 @@ impl ExtensionPlanner for TableWriterPlanner {
             if let Some(TableWriterPlanNode {
                 target_table_name: _,
                 target_table,
+                schema,
                 ..
             }) = downcast_plan_node::<TableWriterPlanNode>(node)
             {
@@ -57,7 +59,10 @@ impl ExtensionPlanner for TableWriterPlanner {
                 let table_provider = source_downcast_adapter(target_table)
                     .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
-                let result = table_provider.write(session_state, physical_input).await?;
+                let mut result = table_provider.write(session_state, physical_input).await?;
+                if schema.has_column_with_unqualified_name(AFFECTED_BYTES.0) {
+                    result = Arc::new(result.with_bytes_output());
+                }
 
                 debug!(
                     "After Apply TableWriterPlanner. Transformed physical plan: {}",
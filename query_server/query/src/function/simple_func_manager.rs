@@ -15,6 +15,8 @@ pub struct SimpleFunctionMetadataManager {
     pub aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
     /// Window functions registered in the context
     pub window_functions: HashMap<String, Arc<WindowUDF>>,
+    /// Descriptions of the functions
+    pub descriptions: HashMap<String, String>,
 }
 
 impl FunctionMetadataManager for SimpleFunctionMetadataManager {
@@ -36,6 +38,12 @@ impl FunctionMetadataManager for SimpleFunctionMetadataManager {
         Ok(())
     }
 
+    fn register_description(&mut self, name: &str, description: &str) -> QueryResult<()> {
+        self.descriptions
+            .insert(name.to_uppercase(), description.to_string());
+        Ok(())
+    }
+
     fn udf(&self, name: &str) -> QueryResult<Arc<ScalarUDF>> {
         let result = self.scalar_functions.get(&name.to_uppercase());
 
@@ -67,4 +75,25 @@ impl FunctionMetadataManager for SimpleFunctionMetadataManager {
     fn udfs(&self) -> HashSet<String> {
         self.scalar_functions.keys().cloned().collect()
     }
+
+    fn functions(&self) -> Vec<FunctionMeta> {
+        let scalar_functions = self
+            .scalar_functions
+            .iter()
+            .map(|(name, f)| (name, FunctionMeta::from_udf(f)));
+        let aggregate_functions = self
+            .aggregate_functions
+            .iter()
+            .map(|(name, f)| (name, FunctionMeta::from_udaf(f)));
+        let window_functions = self
+            .window_functions
+            .iter()
+            .map(|(name, f)| (name, FunctionMeta::from_udwf(f)));
+
+        scalar_functions
+            .chain(aggregate_functions)
+            .chain(window_functions)
+            .map(|(name, meta)| meta.with_description(self.descriptions.get(name).cloned()))
+            .collect()
+    }
 }
@@ -0,0 +1,189 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Select(Box<SelectStatement>),
+    ShowDatabases,
+    ShowMeasurements { limit: Option<u64> },
+    ShowTagKeys { from: Option<Measurement> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStatement {
+    pub fields: Vec<Field>,
+    pub from: Measurement,
+    pub condition: Option<Expr>,
+    /// The interval of `GROUP BY time(<interval>)`.
+    pub group_by_time: Option<Duration>,
+    /// The offset of `GROUP BY time(<interval>, <offset>)`, may be negative.
+    pub group_by_time_offset: Option<Duration>,
+    pub group_by_tags: Vec<String>,
+    pub fill: Fill,
+    pub order_desc: bool,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+/// A measurement in `[<database>.[<retention_policy>].]<measurement>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub database: Option<String>,
+    pub retention_policy: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    Null,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Duration {
+    pub value: i64,
+    pub unit: DurationUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationUnit {
+    Nanosecond,
+    Microsecond,
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+impl DurationUnit {
+    /// Parse a duration unit of InfluxQL, e.g. `ms`, `h`.
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "ns" => Some(Self::Nanosecond),
+            "u" | "µ" => Some(Self::Microsecond),
+            "ms" => Some(Self::Millisecond),
+            "s" => Some(Self::Second),
+            "m" => Some(Self::Minute),
+            "h" => Some(Self::Hour),
+            "d" => Some(Self::Day),
+            "w" => Some(Self::Week),
+            _ => None,
+        }
+    }
+
+    /// The unit name of SQL interval.
+    pub fn sql_unit(&self) -> &'static str {
+        match self {
+            Self::Nanosecond => "nanosecond",
+            Self::Microsecond => "microsecond",
+            Self::Millisecond => "millisecond",
+            Self::Second => "second",
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "INTERVAL '{} {}'", self.value, self.unit.sql_unit())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Identifier(String),
+    Wildcard,
+    String(String),
+    Number(String),
+    Boolean(bool),
+    Duration(Duration),
+    Regex(String),
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Negative(Box<Expr>),
+    Binary {
+        left: Box<Expr>,
+        op: BinaryOp,
+        right: Box<Expr>,
+    },
+    Nested(Box<Expr>),
+}
+
+impl Expr {
+    /// Returns true if the expression contains an aggregate function.
+    pub fn has_call(&self) -> bool {
+        match self {
+            Expr::Call { .. } => true,
+            Expr::Negative(e) | Expr::Nested(e) => e.has_call(),
+            Expr::Binary { left, right, .. } => left.has_call() || right.has_call(),
+            _ => false,
+        }
+    }
+
+    /// The column name of the expression in the result, it's the name of
+    /// the first function or the first field in the expression.
+    pub fn column_name(&self) -> Option<&str> {
+        match self {
+            Expr::Identifier(name) => Some(name),
+            Expr::Call { name, .. } => Some(name),
+            Expr::Negative(e) | Expr::Nested(e) => e.column_name(),
+            Expr::Binary { left, right, .. } => left.column_name().or_else(|| right.column_name()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    RegexMatch,
+    RegexNotMatch,
+    And,
+    Or,
+}
+
+impl Display for BinaryOp {
+    /// Writes the operator in SQL.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+            BinaryOp::Eq => "=",
+            BinaryOp::NotEq => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::RegexMatch => "~",
+            BinaryOp::RegexNotMatch => "!~",
+            BinaryOp::And => "AND",
+            BinaryOp::Or => "OR",
+        };
+        write!(f, "{op}")
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashSet;
+
+use models::schema::TIME_FIELD_NAME;
+use spi::{QueryError, QueryResult};
+
+use super::ast::{BinaryOp, Expr, Measurement, SelectStatement, Statement};
+
+macro_rules! lower_err {
+    ($($arg:tt)*) => {
+        Err(QueryError::InfluxqlParser {
+            reason: format!($($arg)*),
+        })
+    };
+}
+
+/// A statement of InfluxQL lowered to SQL, with the layout to split the
+/// result into series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoweredStatement {
+    pub sql: String,
+    pub layout: SeriesLayout,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesLayout {
+    pub name: SeriesName,
+    /// Columns of the result that are tags of the series, they are not
+    /// included in the values.
+    pub tags: Vec<String>,
+    /// Whether the result has a time column to be formatted.
+    pub has_time: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeriesName {
+    Fixed(String),
+    /// The name of the series is the value of the column, the column
+    /// is not included in the values.
+    Column(String),
+}
+
+/// Lowers the statement to SQL, `database` is used when the statement
+/// doesn't specify one.
+pub fn lower_statement(statement: &Statement, database: &str) -> QueryResult<LoweredStatement> {
+    match statement {
+        Statement::Select(select) => lower_select(select, database),
+        Statement::ShowDatabases => Ok(LoweredStatement {
+            sql: "SELECT database_name AS \"name\" FROM information_schema.databases \
+                ORDER BY database_name"
+                .to_string(),
+            layout: SeriesLayout {
+                name: SeriesName::Fixed("databases".to_string()),
+                tags: vec![],
+                has_time: false,
+            },
+        }),
+        Statement::ShowMeasurements { limit } => {
+            let mut sql = format!(
+                "SELECT table_name AS \"name\" FROM information_schema.tables \
+                WHERE table_database = {} AND table_engine = 'TSKV' ORDER BY table_name",
+                quote_string(database)
+            );
+            if let Some(limit) = limit {
+                sql.push_str(&format!(" LIMIT {limit}"));
+            }
+            Ok(LoweredStatement {
+                sql,
+                layout: SeriesLayout {
+                    name: SeriesName::Fixed("measurements".to_string()),
+                    tags: vec![],
+                    has_time: false,
+                },
+            })
+        }
+        Statement::ShowTagKeys { from } => {
+            let mut database = database;
+            let mut sql = String::new();
+            if let Some(Measurement {
+                database: db, name, ..
+            }) = from
+            {
+                database = db.as_deref().unwrap_or(database);
+                sql.push_str(&format!(" AND table_name = {}", quote_string(name)));
+            }
+            Ok(LoweredStatement {
+                sql: format!(
+                    "SELECT table_name, column_name AS \"tagKey\" FROM information_schema.columns \
+                    WHERE database_name = {} AND column_type = 'TAG'{sql} \
+                    ORDER BY table_name, ordinal_position",
+                    quote_string(database)
+                ),
+                layout: SeriesLayout {
+                    name: SeriesName::Column("table_name".to_string()),
+                    tags: vec![],
+                    has_time: false,
+                },
+            })
+        }
+    }
+}
+
+fn lower_select(select: &SelectStatement, database: &str) -> QueryResult<LoweredStatement> {
+    let is_aggregate = select.fields.iter().any(|f| f.expr.has_call());
+    if select.group_by_time.is_some() && !is_aggregate {
+        return lower_err!("GROUP BY time() requires an aggregate function");
+    }
+    if is_aggregate
+        && select
+            .fields
+            .iter()
+            .any(|f| !f.expr.has_call() && f.expr.column_name().is_some())
+    {
+        return lower_err!("mixing aggregate and non-aggregate queries is not supported");
+    }
+
+    let time = quote_ident(TIME_FIELD_NAME);
+    let mut projection = vec![];
+    let mut names = HashSet::new();
+    let has_wildcard = select.fields.iter().any(|f| f.expr == Expr::Wildcard);
+    if has_wildcard && is_aggregate {
+        return lower_err!("wildcard in aggregate functions is not supported");
+    }
+
+    // The time column is always the first column like InfluxDB. Intervals without
+    // data are not filled, so fill(null) behaves like fill(none).
+    // The offset shifts the origin of the intervals from unix EPOCH, like `time(1h, 30m)`.
+    let time_expr = select
+        .group_by_time
+        .map(|interval| match select.group_by_time_offset {
+            Some(offset) => {
+                format!("date_bin({interval}, {time}, CAST(0 AS TIMESTAMP) + {offset})")
+            }
+            None => format!("date_bin({interval}, {time})"),
+        });
+    match (&time_expr, is_aggregate) {
+        (Some(expr), _) => projection.push(format!("{expr} AS {time}")),
+        (None, true) => projection.push(format!("CAST(0 AS TIMESTAMP) AS {time}")),
+        (None, false) if !has_wildcard => projection.push(time.clone()),
+        (None, false) => {}
+    }
+    names.insert(TIME_FIELD_NAME.to_string());
+
+    for field in &select.fields {
+        if field.expr == Expr::Wildcard {
+            projection.push("*".to_string());
+            continue;
+        }
+        if matches!(&field.expr, Expr::Identifier(name) if name == TIME_FIELD_NAME) {
+            // The time column has been projected.
+            continue;
+        }
+        let base_name = field
+            .alias
+            .clone()
+            .or_else(|| field.expr.column_name().map(|s| s.to_string()))
+            .unwrap_or_else(|| "expr".to_string());
+        let mut name = base_name.clone();
+        let mut i = 1;
+        while !names.insert(name.clone()) {
+            name = format!("{base_name}_{i}");
+            i += 1;
+        }
+        projection.push(format!(
+            "{} AS {}",
+            lower_expr(&field.expr, true)?,
+            quote_ident(&name)
+        ));
+    }
+
+    let tags = select.group_by_tags.iter().map(|t| quote_ident(t));
+    if !has_wildcard {
+        projection.extend(tags.clone());
+    }
+
+    let mut sql = format!(
+        "SELECT {} FROM {}",
+        projection.join(", "),
+        lower_measurement(&select.from, database)
+    );
+    if let Some(condition) = &select.condition {
+        sql.push_str(&format!(" WHERE {}", lower_expr(condition, false)?));
+    }
+    if is_aggregate {
+        let group_by = time_expr
+            .iter()
+            .cloned()
+            .chain(tags.clone())
+            .collect::<Vec<_>>();
+        if !group_by.is_empty() {
+            sql.push_str(&format!(" GROUP BY {}", group_by.join(", ")));
+        }
+    }
+    let mut order_by = tags.collect::<Vec<_>>();
+    order_by.push(format!(
+        "{time}{}",
+        if select.order_desc { " DESC" } else { "" }
+    ));
+    sql.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+    if let Some(limit) = select.limit {
+        sql.push_str(&format!(" LIMIT {limit}"));
+    }
+    if let Some(offset) = select.offset {
+        sql.push_str(&format!(" OFFSET {offset}"));
+    }
+
+    Ok(LoweredStatement {
+        sql,
+        layout: SeriesLayout {
+            name: SeriesName::Fixed(select.from.name.clone()),
+            tags: select.group_by_tags.clone(),
+            has_time: true,
+        },
+    })
+}
+
+fn lower_measurement(measurement: &Measurement, database: &str) -> String {
+    // The retention policy is ignored, the data of a database has only one
+    // retention policy.
+    let database = measurement.database.as_deref().unwrap_or(database);
+    format!(
+        "{}.{}",
+        quote_ident(database),
+        quote_ident(&measurement.name)
+    )
+}
+
+/// Lowers the expression to SQL, functions are only allowed in fields.
+fn lower_expr(expr: &Expr, is_field: bool) -> QueryResult<String> {
+    let sql = match expr {
+        Expr::Identifier(name) => quote_ident(name),
+        Expr::Wildcard => return lower_err!("wildcard is only allowed in fields"),
+        Expr::String(s) => quote_string(s),
+        Expr::Number(n) => n.clone(),
+        Expr::Boolean(b) => b.to_string(),
+        Expr::Duration(d) => d.to_string(),
+        Expr::Regex(r) => quote_string(r),
+        Expr::Call { name, args } if name == "now" && args.is_empty() => "now()".to_string(),
+        Expr::Call { name, args } if is_field => lower_call(name, args)?,
+        Expr::Call { name, .. } => return lower_err!("function {name}() is not allowed here"),
+        // Parenthesized, `--` starts a comment in SQL.
+        Expr::Negative(e) => format!("(-{})", lower_expr(e, is_field)?),
+        Expr::Nested(e) => format!("({})", lower_expr(e, is_field)?),
+        Expr::Binary { left, op, right } => {
+            let (left, right) = match (left.as_ref(), right.as_ref()) {
+                // Integer literals compared with the time are nanoseconds.
+                (Expr::Identifier(t), Expr::Number(n)) if t == TIME_FIELD_NAME => {
+                    (quote_ident(t), format!("CAST({n} AS TIMESTAMP)"))
+                }
+                (Expr::Number(n), Expr::Identifier(t)) if t == TIME_FIELD_NAME => {
+                    (format!("CAST({n} AS TIMESTAMP)"), quote_ident(t))
+                }
+                (l, r) => (lower_expr(l, is_field)?, lower_expr(r, is_field)?),
+            };
+            if matches!(op, BinaryOp::And | BinaryOp::Or) {
+                format!("({left} {op} {right})")
+            } else {
+                format!("{left} {op} {right}")
+            }
+        }
+    };
+    Ok(sql)
+}
+
+fn lower_call(name: &str, args: &[Expr]) -> QueryResult<String> {
+    let [arg] = args else {
+        return lower_err!("function {name}() expects 1 argument, got {}", args.len());
+    };
+    let arg = match arg {
+        Expr::Identifier(_) => lower_expr(arg, false)?,
+        _ => return lower_err!("expected field argument in {name}()"),
+    };
+    let time = quote_ident(TIME_FIELD_NAME);
+    let sql = match name {
+        "mean" => format!("avg({arg})"),
+        "first" | "last" => format!("{name}({time}, {arg})"),
+        "spread" => format!("(max({arg}) - min({arg}))"),
+        "count" | "sum" | "min" | "max" | "median" | "stddev" | "mode" => {
+            format!("{name}({arg})")
+        }
+        _ => return lower_err!("function {name}() is not supported"),
+    };
+    Ok(sql)
+}
+
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lower_statement, SeriesName};
+    use crate::influxql::parser::InfluxqlParser;
+
+    fn lower(query: &str) -> String {
+        let statements = InfluxqlParser::parse(query).unwrap();
+        lower_statement(&statements[0], "public").unwrap().sql
+    }
+
+    #[test]
+    fn test_lower_select() {
+        assert_eq!(
+            lower("SELECT usage, host FROM cpu WHERE time >= 1000 AND host = 'a''b' LIMIT 2"),
+            "SELECT \"time\", \"usage\" AS \"usage\", \"host\" AS \"host\" FROM \"public\".\"cpu\" \
+            WHERE (\"time\" >= CAST(1000 AS TIMESTAMP) AND \"host\" = 'a''b') \
+            ORDER BY \"time\" LIMIT 2"
+        );
+        assert_eq!(
+            lower("SELECT * FROM db0..cpu ORDER BY time DESC"),
+            "SELECT * FROM \"db0\".\"cpu\" ORDER BY \"time\" DESC"
+        );
+    }
+
+    #[test]
+    fn test_lower_aggregate() {
+        assert_eq!(
+            lower(
+                "SELECT mean(usage), max(usage) AS peak, last(usage) FROM cpu \
+                WHERE host =~ /^a/ AND time > now() - 1h GROUP BY time(10m), host"
+            ),
+            "SELECT date_bin(INTERVAL '10 minute', \"time\") AS \"time\", \
+            avg(\"usage\") AS \"mean\", max(\"usage\") AS \"peak\", \
+            last(\"time\", \"usage\") AS \"last\", \"host\" FROM \"public\".\"cpu\" \
+            WHERE (\"host\" ~ '^a' AND \"time\" > now() - INTERVAL '1 hour') \
+            GROUP BY date_bin(INTERVAL '10 minute', \"time\"), \"host\" \
+            ORDER BY \"host\", \"time\""
+        );
+        assert_eq!(
+            lower("SELECT sum(kwh) FROM meter GROUP BY time(1h, -30m)"),
+            "SELECT date_bin(INTERVAL '1 hour', \"time\", CAST(0 AS TIMESTAMP) + INTERVAL '-30 minute') \
+            AS \"time\", sum(\"kwh\") AS \"sum\" FROM \"public\".\"meter\" \
+            GROUP BY date_bin(INTERVAL '1 hour', \"time\", CAST(0 AS TIMESTAMP) + INTERVAL '-30 minute') \
+            ORDER BY \"time\""
+        );
+        assert_eq!(
+            lower("SELECT count(v), count(v) FROM m"),
+            "SELECT CAST(0 AS TIMESTAMP) AS \"time\", count(\"v\") AS \"count\", \
+            count(\"v\") AS \"count_1\" FROM \"public\".\"m\" ORDER BY \"time\""
+        );
+    }
+
+    #[test]
+    fn test_lower_negative() {
+        assert_eq!(
+            lower("SELECT usage FROM cpu WHERE usage - -1 > - -2"),
+            "SELECT \"time\", \"usage\" AS \"usage\" FROM \"public\".\"cpu\" \
+            WHERE \"usage\" - (-1) > (-(-2)) ORDER BY \"time\""
+        );
+    }
+
+    #[test]
+    fn test_lower_show() {
+        let statements = InfluxqlParser::parse("SHOW TAG KEYS FROM db1..cpu").unwrap();
+        let lowered = lower_statement(&statements[0], "public").unwrap();
+        assert_eq!(
+            lowered.sql,
+            "SELECT table_name, column_name AS \"tagKey\" FROM information_schema.columns \
+            WHERE database_name = 'db1' AND column_type = 'TAG' AND table_name = 'cpu' \
+            ORDER BY table_name, ordinal_position"
+        );
+        assert_eq!(
+            lowered.layout.name,
+            SeriesName::Column("table_name".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lower_unsupported() {
+        for query in [
+            "SELECT usage FROM cpu GROUP BY time(1m)",
+            "SELECT mean(usage), host FROM cpu",
+            "SELECT percentile(usage, 90) FROM cpu",
+            "SELECT derivative(usage) FROM cpu",
+            "SELECT usage FROM cpu WHERE mean(usage) > 1",
+        ] {
+            let statements = InfluxqlParser::parse(query).unwrap();
+            assert!(
+                lower_statement(&statements[0], "public").is_err(),
+                "{query}"
+            );
+        }
+    }
+}
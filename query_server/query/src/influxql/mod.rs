@@ -0,0 +1,9 @@
+//! A compatibility layer of InfluxQL for the `/query` API of InfluxDB 1.x.
+//!
+//! Only a subset of InfluxQL is supported, the statements are lowered to SQL
+//! and executed by the DBMS, the results are grouped into series like InfluxDB.
+
+pub mod ast;
+pub mod lower;
+pub mod parser;
+pub mod server;
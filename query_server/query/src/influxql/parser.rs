@@ -0,0 +1,759 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use spi::{QueryError, QueryResult};
+
+use super::ast::{
+    BinaryOp, Duration, DurationUnit, Expr, Field, Fill, Measurement, SelectStatement, Statement,
+};
+
+macro_rules! parser_err {
+    ($($arg:tt)*) => {
+        Err(QueryError::InfluxqlParser {
+            reason: format!($($arg)*),
+        })
+    };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident { value: String, quoted: bool },
+    String(String),
+    Number(String),
+    Duration(Duration),
+    Regex(String),
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    RegexMatch,
+    RegexNotMatch,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+    DoubleColon,
+    SemiColon,
+    Eof,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(query: &'a str) -> Self {
+        Self {
+            chars: query.chars().peekable(),
+        }
+    }
+
+    fn tokenize(mut self) -> QueryResult<Vec<Token>> {
+        let mut tokens = vec![];
+        loop {
+            let token = match tokens.last() {
+                // A slash after a regex operator starts a regex literal.
+                Some(Token::RegexMatch | Token::RegexNotMatch) => {
+                    self.skip_whitespace();
+                    if self.chars.peek() == Some(&'/') {
+                        self.chars.next();
+                        Token::Regex(self.read_until('/')?)
+                    } else {
+                        self.next_token()?
+                    }
+                }
+                _ => self.next_token()?,
+            };
+            if token == Token::Eof {
+                tokens.push(token);
+                return Ok(tokens);
+            }
+            tokens.push(token);
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else if *c == '-' {
+                let mut ahead = self.chars.clone();
+                ahead.next();
+                if ahead.peek() != Some(&'-') {
+                    return;
+                }
+                // Skip the comment until the end of line.
+                for c in self.chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> QueryResult<Token> {
+        self.skip_whitespace();
+        let Some(c) = self.chars.next() else {
+            return Ok(Token::Eof);
+        };
+        let token = match c {
+            '"' => Token::Ident {
+                value: self.read_until('"')?,
+                quoted: true,
+            },
+            '\'' => Token::String(self.read_until('\'')?),
+            '=' if self.consume('~') => Token::RegexMatch,
+            '=' => Token::Eq,
+            '!' if self.consume('=') => Token::NotEq,
+            '!' if self.consume('~') => Token::RegexNotMatch,
+            '<' if self.consume('=') => Token::LtEq,
+            '<' if self.consume('>') => Token::NotEq,
+            '<' => Token::Lt,
+            '>' if self.consume('=') => Token::GtEq,
+            '>' => Token::Gt,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            ';' => Token::SemiColon,
+            ':' if self.consume(':') => Token::DoubleColon,
+            c if c.is_ascii_digit() => self.read_number(c)?,
+            c if c.is_alphabetic() || c == '_' => {
+                let mut value = c.to_string();
+                value.push_str(&self.read_while(|c| c.is_alphanumeric() || c == '_'));
+                Token::Ident {
+                    value,
+                    quoted: false,
+                }
+            }
+            c => return parser_err!("unexpected character '{c}'"),
+        };
+        Ok(token)
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        self.chars.next_if_eq(&expected).is_some()
+    }
+
+    fn read_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.chars.next_if(|c| predicate(*c)) {
+            s.push(c);
+        }
+        s
+    }
+
+    /// Read a quoted literal, the backslash escapes the next character.
+    fn read_until(&mut self, quote: char) -> QueryResult<String> {
+        let mut s = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '\\' => match self.chars.next() {
+                    Some(c) if c == quote || c == '\\' => s.push(c),
+                    Some(c) => {
+                        s.push('\\');
+                        s.push(c);
+                    }
+                    None => break,
+                },
+                c if c == quote => return Ok(s),
+                c => s.push(c),
+            }
+        }
+        parser_err!("unterminated literal, expected {quote}")
+    }
+
+    /// Read a number or a duration literal like `10m`.
+    fn read_number(&mut self, first: char) -> QueryResult<Token> {
+        let mut number = first.to_string();
+        number.push_str(&self.read_while(|c| c.is_ascii_digit()));
+        if self.chars.peek() == Some(&'.') {
+            self.chars.next();
+            number.push('.');
+            number.push_str(&self.read_while(|c| c.is_ascii_digit()));
+            return Ok(Token::Number(number));
+        }
+
+        let unit = self.read_while(|c| c.is_alphanumeric() || c == '_');
+        if unit.is_empty() {
+            return Ok(Token::Number(number));
+        }
+        match (DurationUnit::parse(&unit), number.parse::<i64>()) {
+            (Some(unit), Ok(value)) => Ok(Token::Duration(Duration { value, unit })),
+            _ => parser_err!("invalid duration {number}{unit}"),
+        }
+    }
+}
+
+/// Parses a subset of InfluxQL:
+/// - SELECT <fields> FROM <measurement> [WHERE <condition>]
+///   [GROUP BY time(<interval>)[, <tag>...]] [fill(null|none)]
+///   [ORDER BY time [ASC|DESC]] [LIMIT <n>] [OFFSET <n>]
+/// - SHOW DATABASES
+/// - SHOW MEASUREMENTS [LIMIT <n>]
+/// - SHOW TAG KEYS [FROM <measurement>]
+pub struct InfluxqlParser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl InfluxqlParser {
+    pub fn parse(query: &str) -> QueryResult<Vec<Statement>> {
+        let tokens = Lexer::new(query).tokenize()?;
+        let mut parser = Self { tokens, index: 0 };
+
+        let mut statements = vec![];
+        loop {
+            while parser.consume(&Token::SemiColon) {}
+            if parser.peek() == &Token::Eof {
+                break;
+            }
+            statements.push(parser.parse_statement()?);
+            match parser.peek() {
+                Token::SemiColon | Token::Eof => {}
+                t => return parser_err!("expected ; or end of query, found {t:?}"),
+            }
+        }
+        if statements.is_empty() {
+            return parser_err!("empty query");
+        }
+        Ok(statements)
+    }
+
+    fn peek(&self) -> &Token {
+        self.tokens.get(self.index).unwrap_or(&Token::Eof)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.index < self.tokens.len() {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn consume(&mut self, expected: &Token) -> bool {
+        if self.peek() == expected {
+            self.index += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, expected: &Token) -> QueryResult<()> {
+        if self.consume(expected) {
+            return Ok(());
+        }
+        parser_err!("expected {expected:?}, found {:?}", self.peek())
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident { value, quoted: false } if value.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.index += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> QueryResult<()> {
+        if self.parse_keyword(keyword) {
+            return Ok(());
+        }
+        parser_err!("expected {keyword}, found {:?}", self.peek())
+    }
+
+    fn parse_identifier(&mut self) -> QueryResult<String> {
+        match self.next() {
+            Token::Ident { value, .. } => Ok(value),
+            t => parser_err!("expected identifier, found {t:?}"),
+        }
+    }
+
+    fn parse_unsigned(&mut self) -> QueryResult<u64> {
+        match self.next() {
+            Token::Number(n) => n
+                .parse::<u64>()
+                .or_else(|_| parser_err!("expected unsigned integer, found {n}")),
+            t => parser_err!("expected unsigned integer, found {t:?}"),
+        }
+    }
+
+    fn parse_statement(&mut self) -> QueryResult<Statement> {
+        if self.parse_keyword("SELECT") {
+            Ok(Statement::Select(Box::new(self.parse_select()?)))
+        } else if self.parse_keyword("SHOW") {
+            self.parse_show()
+        } else {
+            parser_err!("expected SELECT or SHOW, found {:?}", self.peek())
+        }
+    }
+
+    fn parse_show(&mut self) -> QueryResult<Statement> {
+        if self.parse_keyword("DATABASES") {
+            Ok(Statement::ShowDatabases)
+        } else if self.parse_keyword("MEASUREMENTS") {
+            let limit = if self.parse_keyword("LIMIT") {
+                Some(self.parse_unsigned()?)
+            } else {
+                None
+            };
+            Ok(Statement::ShowMeasurements { limit })
+        } else if self.parse_keyword("TAG") {
+            self.expect_keyword("KEYS")?;
+            let from = if self.parse_keyword("FROM") {
+                Some(self.parse_measurement()?)
+            } else {
+                None
+            };
+            Ok(Statement::ShowTagKeys { from })
+        } else {
+            parser_err!(
+                "expected DATABASES, MEASUREMENTS or TAG KEYS after SHOW, found {:?}",
+                self.peek()
+            )
+        }
+    }
+
+    fn parse_select(&mut self) -> QueryResult<SelectStatement> {
+        let mut fields = vec![self.parse_field()?];
+        while self.consume(&Token::Comma) {
+            fields.push(self.parse_field()?);
+        }
+
+        self.expect_keyword("FROM")?;
+        let from = self.parse_measurement()?;
+
+        let condition = if self.parse_keyword("WHERE") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut group_by_time = None;
+        let mut group_by_time_offset = None;
+        let mut group_by_tags = vec![];
+        if self.parse_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            loop {
+                if self.peek_keyword("time")
+                    && self.tokens.get(self.index + 1) == Some(&Token::LParen)
+                {
+                    self.index += 2;
+                    match self.next() {
+                        Token::Duration(d) => group_by_time = Some(d),
+                        t => return parser_err!("expected duration in time(), found {t:?}"),
+                    }
+                    if self.consume(&Token::Comma) {
+                        let negative = self.consume(&Token::Minus);
+                        match self.next() {
+                            Token::Duration(mut d) => {
+                                if negative {
+                                    d.value = -d.value;
+                                }
+                                group_by_time_offset = Some(d);
+                            }
+                            t => return parser_err!("expected offset in time(), found {t:?}"),
+                        }
+                    }
+                    self.expect(&Token::RParen)?;
+                } else if self.peek() == &Token::Star {
+                    return parser_err!("GROUP BY * is not supported");
+                } else {
+                    group_by_tags.push(self.parse_identifier()?);
+                }
+                if !self.consume(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+
+        let mut fill = Fill::Null;
+        if self.parse_keyword("fill") {
+            self.expect(&Token::LParen)?;
+            fill = match self.next() {
+                Token::Ident { value, .. } if value.eq_ignore_ascii_case("null") => Fill::Null,
+                Token::Ident { value, .. } if value.eq_ignore_ascii_case("none") => Fill::None,
+                t => return parser_err!("fill option {t:?} is not supported"),
+            };
+            self.expect(&Token::RParen)?;
+        }
+
+        let mut order_desc = false;
+        if self.parse_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            self.expect_keyword("time")?;
+            if self.parse_keyword("DESC") {
+                order_desc = true;
+            } else {
+                self.parse_keyword("ASC");
+            }
+        }
+
+        let limit = if self.parse_keyword("LIMIT") {
+            Some(self.parse_unsigned()?)
+        } else {
+            None
+        };
+        let offset = if self.parse_keyword("OFFSET") {
+            Some(self.parse_unsigned()?)
+        } else {
+            None
+        };
+        for keyword in ["SLIMIT", "SOFFSET", "tz"] {
+            if self.peek_keyword(keyword) {
+                return parser_err!("{keyword} is not supported");
+            }
+        }
+
+        Ok(SelectStatement {
+            fields,
+            from,
+            condition,
+            group_by_time,
+            group_by_time_offset,
+            group_by_tags,
+            fill,
+            order_desc,
+            limit,
+            offset,
+        })
+    }
+
+    fn parse_field(&mut self) -> QueryResult<Field> {
+        let expr = self.parse_expr()?;
+        let alias = if self.parse_keyword("AS") {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        Ok(Field { expr, alias })
+    }
+
+    fn parse_measurement(&mut self) -> QueryResult<Measurement> {
+        if let Token::Regex(_) | Token::Slash = self.peek() {
+            return parser_err!("regular expression of measurement is not supported");
+        }
+        let mut parts = vec![self.parse_identifier()?];
+        while self.consume(&Token::Dot) {
+            // The retention policy may be empty, e.g. `db..measurement`.
+            if self.peek() == &Token::Dot {
+                parts.push(String::new());
+                continue;
+            }
+            parts.push(self.parse_identifier()?);
+        }
+
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+        let mut parts = parts.into_iter();
+        match parts.len() {
+            1 => Ok(Measurement {
+                database: None,
+                retention_policy: None,
+                name: parts.next().unwrap_or_default(),
+            }),
+            2 => Ok(Measurement {
+                database: None,
+                retention_policy: parts.next().and_then(non_empty),
+                name: parts.next().unwrap_or_default(),
+            }),
+            3 => Ok(Measurement {
+                database: parts.next().and_then(non_empty),
+                retention_policy: parts.next().and_then(non_empty),
+                name: parts.next().unwrap_or_default(),
+            }),
+            _ => parser_err!("too many parts in measurement name"),
+        }
+    }
+
+    fn parse_expr(&mut self) -> QueryResult<Expr> {
+        self.parse_binary(0)
+    }
+
+    fn binary_op(token: &Token, keyword: impl Fn(&str) -> bool) -> Option<(BinaryOp, u8)> {
+        let op = match token {
+            Token::Eq => (BinaryOp::Eq, 3),
+            Token::NotEq => (BinaryOp::NotEq, 3),
+            Token::Lt => (BinaryOp::Lt, 3),
+            Token::LtEq => (BinaryOp::LtEq, 3),
+            Token::Gt => (BinaryOp::Gt, 3),
+            Token::GtEq => (BinaryOp::GtEq, 3),
+            Token::RegexMatch => (BinaryOp::RegexMatch, 3),
+            Token::RegexNotMatch => (BinaryOp::RegexNotMatch, 3),
+            Token::Plus => (BinaryOp::Add, 4),
+            Token::Minus => (BinaryOp::Sub, 4),
+            Token::Star => (BinaryOp::Mul, 5),
+            Token::Slash => (BinaryOp::Div, 5),
+            Token::Percent => (BinaryOp::Mod, 5),
+            _ if keyword("AND") => (BinaryOp::And, 2),
+            _ if keyword("OR") => (BinaryOp::Or, 1),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Parse binary expressions whose operators bind tighter than `min_precedence`.
+    fn parse_binary(&mut self, min_precedence: u8) -> QueryResult<Expr> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let Some((op, precedence)) = Self::binary_op(self.peek(), |k| self.peek_keyword(k))
+            else {
+                break;
+            };
+            if precedence <= min_precedence {
+                break;
+            }
+            self.index += 1;
+            let right = self.parse_binary(precedence)?;
+            left = Expr::Binary {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            };
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> QueryResult<Expr> {
+        if self.consume(&Token::Minus) {
+            return Ok(Expr::Negative(Box::new(self.parse_unary()?)));
+        }
+        let expr = self.parse_primary()?;
+        // Type hints like `value::field` are ignored.
+        if self.consume(&Token::DoubleColon) {
+            self.parse_identifier()?;
+        }
+        Ok(expr)
+    }
+
+    fn parse_primary(&mut self) -> QueryResult<Expr> {
+        let expr = match self.next() {
+            Token::Star => Expr::Wildcard,
+            Token::String(s) => Expr::String(s),
+            Token::Number(n) => Expr::Number(n),
+            Token::Duration(d) => Expr::Duration(d),
+            Token::Regex(r) => Expr::Regex(r),
+            Token::LParen => {
+                let expr = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Expr::Nested(Box::new(expr))
+            }
+            Token::Ident {
+                value,
+                quoted: false,
+            } if self.peek() == &Token::LParen => {
+                self.index += 1;
+                let mut args = vec![];
+                if !self.consume(&Token::RParen) {
+                    loop {
+                        args.push(self.parse_expr()?);
+                        if !self.consume(&Token::Comma) {
+                            break;
+                        }
+                    }
+                    self.expect(&Token::RParen)?;
+                }
+                Expr::Call {
+                    name: value.to_ascii_lowercase(),
+                    args,
+                }
+            }
+            Token::Ident {
+                value,
+                quoted: false,
+            } if value.eq_ignore_ascii_case("true") => Expr::Boolean(true),
+            Token::Ident {
+                value,
+                quoted: false,
+            } if value.eq_ignore_ascii_case("false") => Expr::Boolean(false),
+            Token::Ident { value, .. } => Expr::Identifier(value),
+            t => return parser_err!("expected expression, found {t:?}"),
+        };
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::InfluxqlParser;
+    use crate::influxql::ast::{
+        BinaryOp, Duration, DurationUnit, Expr, Field, Fill, Measurement, SelectStatement,
+        Statement,
+    };
+
+    fn ident(name: &str) -> Expr {
+        Expr::Identifier(name.to_string())
+    }
+
+    fn binary(left: Expr, op: BinaryOp, right: Expr) -> Expr {
+        Expr::Binary {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        }
+    }
+
+    #[test]
+    fn test_parse_select() {
+        let statements = InfluxqlParser::parse(
+            "SELECT mean(\"usage\") AS avg_usage FROM telegraf.autogen.cpu \
+            WHERE host =~ /^server\\/0[12]$/ AND time > now() - 1h \
+            GROUP BY time(10m), \"host\" fill(none) ORDER BY time DESC LIMIT 10;",
+        )
+        .unwrap();
+        let expected = Statement::Select(Box::new(SelectStatement {
+            fields: vec![Field {
+                expr: Expr::Call {
+                    name: "mean".to_string(),
+                    args: vec![ident("usage")],
+                },
+                alias: Some("avg_usage".to_string()),
+            }],
+            from: Measurement {
+                database: Some("telegraf".to_string()),
+                retention_policy: Some("autogen".to_string()),
+                name: "cpu".to_string(),
+            },
+            condition: Some(binary(
+                binary(
+                    ident("host"),
+                    BinaryOp::RegexMatch,
+                    Expr::Regex("^server/0[12]$".to_string()),
+                ),
+                BinaryOp::And,
+                binary(
+                    ident("time"),
+                    BinaryOp::Gt,
+                    binary(
+                        Expr::Call {
+                            name: "now".to_string(),
+                            args: vec![],
+                        },
+                        BinaryOp::Sub,
+                        Expr::Duration(Duration {
+                            value: 1,
+                            unit: DurationUnit::Hour,
+                        }),
+                    ),
+                ),
+            )),
+            group_by_time: Some(Duration {
+                value: 10,
+                unit: DurationUnit::Minute,
+            }),
+            group_by_time_offset: None,
+            group_by_tags: vec!["host".to_string()],
+            fill: Fill::None,
+            order_desc: true,
+            limit: Some(10),
+            offset: None,
+        }));
+        assert_eq!(statements, vec![expected]);
+    }
+
+    #[test]
+    fn test_parse_group_by_time_offset() {
+        for (query, offset) in [
+            ("SELECT mean(v) FROM cpu GROUP BY time(1h, 30m)", 30),
+            ("SELECT mean(v) FROM cpu GROUP BY time(1h, -15m), host", -15),
+        ] {
+            let statements = InfluxqlParser::parse(query).unwrap();
+            let Statement::Select(select) = &statements[0] else {
+                panic!("expected select");
+            };
+            assert_eq!(
+                select.group_by_time_offset,
+                Some(Duration {
+                    value: offset,
+                    unit: DurationUnit::Minute,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let statements =
+            InfluxqlParser::parse("select a + b * 2 from m where a = 1 or b = 2 and c = 3")
+                .unwrap();
+        let Statement::Select(select) = &statements[0] else {
+            panic!("expected select");
+        };
+        assert_eq!(
+            select.fields[0].expr,
+            binary(
+                ident("a"),
+                BinaryOp::Add,
+                binary(ident("b"), BinaryOp::Mul, Expr::Number("2".to_string()))
+            )
+        );
+        assert_eq!(
+            select.condition,
+            Some(binary(
+                binary(ident("a"), BinaryOp::Eq, Expr::Number("1".to_string())),
+                BinaryOp::Or,
+                binary(
+                    binary(ident("b"), BinaryOp::Eq, Expr::Number("2".to_string())),
+                    BinaryOp::And,
+                    binary(ident("c"), BinaryOp::Eq, Expr::Number("3".to_string())),
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_show() {
+        let statements = InfluxqlParser::parse(
+            "SHOW DATABASES; show measurements limit 5; SHOW TAG KEYS FROM \"cpu\"",
+        )
+        .unwrap();
+        assert_eq!(
+            statements,
+            vec![
+                Statement::ShowDatabases,
+                Statement::ShowMeasurements { limit: Some(5) },
+                Statement::ShowTagKeys {
+                    from: Some(Measurement {
+                        database: None,
+                        retention_policy: None,
+                        name: "cpu".to_string(),
+                    })
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unsupported() {
+        for query in [
+            "",
+            "DELETE FROM cpu",
+            "SELECT * FROM /cpu.*/",
+            "SELECT mean(v) FROM cpu GROUP BY time(1m, now())",
+            "SELECT mean(v) FROM cpu GROUP BY *",
+            "SELECT mean(v) FROM cpu GROUP BY time(1m) fill(previous)",
+            "SELECT v FROM cpu SLIMIT 1",
+            "SELECT v FROM cpu WHERE host = 'a",
+        ] {
+            assert!(InfluxqlParser::parse(query).is_err(), "{query}");
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{SecondsFormat, TimeZone, Utc};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::arrow::json::writer::record_batches_to_json_rows;
+use datafusion::arrow::record_batch::RecordBatch;
+use models::schema::TIME_FIELD_NAME;
+use serde_json::{json, Map, Value};
+use spi::server::dbms::DBMSRef;
+use spi::server::influxql::InfluxqlServer;
+use spi::service::protocol::{Context, Query};
+use spi::{QueryError, QueryResult};
+use trace::span_ext::SpanExt;
+use trace::{Span, SpanContext};
+
+use super::lower::{lower_statement, LoweredStatement, SeriesName};
+use super::parser::InfluxqlParser;
+
+pub struct InfluxqlSqlServer {
+    db: DBMSRef,
+}
+
+impl InfluxqlSqlServer {
+    pub fn new(db: DBMSRef) -> Self {
+        Self { db }
+    }
+
+    async fn execute_statement(
+        &self,
+        ctx: &Context,
+        lowered: &LoweredStatement,
+        span: &Span,
+    ) -> QueryResult<(Vec<String>, Vec<RecordBatch>)> {
+        let query = Query::new(ctx.clone(), lowered.sql.clone());
+        let handle = self.db.execute(&query, span.context().as_ref()).await?;
+        let result = handle.result();
+        let schema = result.schema();
+        let columns = schema.fields().iter().map(|f| f.name().clone()).collect();
+        let batches = result
+            .chunk_result()
+            .await?
+            .into_iter()
+            .map(|b| time_to_nanos(b, lowered.layout.has_time))
+            .collect::<QueryResult<Vec<_>>>()?;
+        Ok((columns, batches))
+    }
+}
+
+#[async_trait]
+impl InfluxqlServer for InfluxqlSqlServer {
+    async fn query(
+        &self,
+        ctx: &Context,
+        query: &str,
+        epoch: Option<&str>,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<u8>> {
+        let span = Span::from_context("influxql query", span_ctx);
+        let statements = InfluxqlParser::parse(query)?;
+
+        let mut results = vec![];
+        for (statement_id, statement) in statements.iter().enumerate() {
+            let result = match lower_statement(statement, ctx.database()) {
+                Ok(lowered) => self.execute_statement(ctx, &lowered, &span).await.and_then(
+                    |(columns, batches)| build_series(&lowered, &columns, &batches, epoch),
+                ),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(series) if series.is_empty() => {
+                    results.push(json!({ "statement_id": statement_id }))
+                }
+                Ok(series) => {
+                    results.push(json!({ "statement_id": statement_id, "series": series }))
+                }
+                Err(e) => {
+                    // Like InfluxDB, the remaining statements are not executed.
+                    results.push(json!({ "statement_id": statement_id, "error": e.to_string() }));
+                    break;
+                }
+            }
+        }
+
+        serde_json::to_vec(&json!({ "results": results })).map_err(|e| QueryError::Internal {
+            reason: format!("failed to serialize the result of InfluxQL: {e}"),
+        })
+    }
+}
+
+/// Converts the time column to nanoseconds of Int64, so that it can be
+/// formatted by the epoch.
+fn time_to_nanos(batch: RecordBatch, has_time: bool) -> QueryResult<RecordBatch> {
+    let schema = batch.schema();
+    let Some((idx, _)) = schema
+        .column_with_name(TIME_FIELD_NAME)
+        .filter(|_| has_time)
+    else {
+        return Ok(batch);
+    };
+
+    let time = cast(
+        batch.column(idx),
+        &DataType::Timestamp(TimeUnit::Nanosecond, None),
+    )?;
+    let time = cast(&time, &DataType::Int64)?;
+    let mut fields = schema.fields().iter().cloned().collect::<Vec<_>>();
+    fields[idx] = Arc::new(Field::new(TIME_FIELD_NAME, DataType::Int64, true));
+    let mut columns = batch.columns().to_vec();
+    columns[idx] = time;
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+fn format_time(nanos: i64, epoch: Option<&str>) -> Value {
+    let divisor = match epoch {
+        Some("h") => 3_600_000_000_000,
+        Some("m") => 60_000_000_000,
+        Some("s") => 1_000_000_000,
+        Some("ms") => 1_000_000,
+        Some("u") | Some("µ") => 1_000,
+        Some("ns") => 1,
+        _ => {
+            return Value::String(
+                Utc.timestamp_nanos(nanos)
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            )
+        }
+    };
+    Value::from(nanos / divisor)
+}
+
+fn tag_value(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// Groups the rows into series by the name and tags of the series, in the
+/// order they first appear.
+fn build_series(
+    lowered: &LoweredStatement,
+    columns: &[String],
+    batches: &[RecordBatch],
+    epoch: Option<&str>,
+) -> QueryResult<Vec<Value>> {
+    let layout = &lowered.layout;
+    let value_columns = columns
+        .iter()
+        .filter(|c| !layout.tags.contains(c))
+        .filter(|c| !matches!(&layout.name, SeriesName::Column(n) if n == *c))
+        .collect::<Vec<_>>();
+
+    let batches = batches.iter().collect::<Vec<_>>();
+    let rows = record_batches_to_json_rows(&batches)?;
+
+    let mut series: Vec<(String, Vec<String>, Vec<Value>)> = vec![];
+    for row in rows {
+        let name = match &layout.name {
+            SeriesName::Fixed(name) => name.clone(),
+            SeriesName::Column(column) => tag_value(row.get(column)),
+        };
+        let tags = layout
+            .tags
+            .iter()
+            .map(|t| tag_value(row.get(t)))
+            .collect::<Vec<_>>();
+        let values = value_columns
+            .iter()
+            .map(|c| match row.get(c.as_str()) {
+                Some(Value::Number(n)) if layout.has_time && c.as_str() == TIME_FIELD_NAME => {
+                    n.as_i64().map_or(Value::Null, |n| format_time(n, epoch))
+                }
+                Some(v) => v.clone(),
+                None => Value::Null,
+            })
+            .collect::<Vec<_>>();
+
+        match series.iter_mut().find(|(n, t, _)| *n == name && *t == tags) {
+            Some((_, _, rows)) => rows.push(Value::Array(values)),
+            None => series.push((name, tags, vec![Value::Array(values)])),
+        }
+    }
+
+    Ok(series
+        .into_iter()
+        .map(|(name, tags, values)| {
+            let mut s = Map::new();
+            s.insert("name".to_string(), Value::String(name));
+            if !layout.tags.is_empty() {
+                let tags = layout
+                    .tags
+                    .iter()
+                    .cloned()
+                    .zip(tags.into_iter().map(Value::String))
+                    .collect::<Map<_, _>>();
+                s.insert("tags".to_string(), Value::Object(tags));
+            }
+            s.insert("columns".to_string(), json!(value_columns));
+            s.insert("values".to_string(), Value::Array(values));
+            Value::Object(s)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use serde_json::json;
+
+    use super::{build_series, time_to_nanos};
+    use crate::influxql::lower::{LoweredStatement, SeriesLayout, SeriesName};
+
+    #[test]
+    fn test_build_series() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("mean", DataType::Float64, true),
+            Field::new("host", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![0, 60_000_000_000, 0])),
+                Arc::new(Float64Array::from(vec![Some(1.5), None, Some(3.0)])),
+                Arc::new(StringArray::from(vec!["a", "a", "b"])),
+            ],
+        )
+        .unwrap();
+        let batch = time_to_nanos(batch, true).unwrap();
+        let lowered = LoweredStatement {
+            sql: String::new(),
+            layout: SeriesLayout {
+                name: SeriesName::Fixed("cpu".to_string()),
+                tags: vec!["host".to_string()],
+                has_time: true,
+            },
+        };
+        let columns = ["time", "mean", "host"].map(String::from);
+
+        let series = build_series(&lowered, &columns, &[batch.clone()], None).unwrap();
+        assert_eq!(
+            json!(series),
+            json!([
+                {
+                    "name": "cpu",
+                    "tags": {"host": "a"},
+                    "columns": ["time", "mean"],
+                    "values": [["1970-01-01T00:00:00Z", 1.5], ["1970-01-01T00:01:00Z", null]]
+                },
+                {
+                    "name": "cpu",
+                    "tags": {"host": "b"},
+                    "columns": ["time", "mean"],
+                    "values": [["1970-01-01T00:00:00Z", 3.0]]
+                }
+            ])
+        );
+
+        let series = build_series(&lowered, &columns, &[batch], Some("s")).unwrap();
+        assert_eq!(series[0]["values"], json!([[0, 1.5], [60, null]]));
+    }
+}
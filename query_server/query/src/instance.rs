@@ -3,6 +3,8 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use coordinator::service::CoordinatorRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::as_string_array;
 use derive_builder::Builder;
 use memory_pool::MemoryPoolRef;
 use meta::error::MetaError;
@@ -21,29 +23,35 @@ use spi::query::execution::QueryStateMachineRef;
 use spi::query::logical_planner::Plan;
 use spi::query::session::SessionCtxFactory;
 use spi::server::dbms::DatabaseManagerSystem;
-use spi::service::protocol::{Query, QueryHandle};
-use spi::{AuthSnafu, MetaSnafu, QueryResult};
+use spi::service::protocol::{Context, Query, QueryHandle, QueryPlanDescription};
+use spi::{AuthSnafu, MetaSnafu, QueryError, QueryResult};
 use trace::{debug, SpanContext};
 use tskv::kv_option::Options;
 
+use crate::audit::{AuditEvent, AuditLogger, AuditLoggerRef};
 use crate::auth::auth_control::{AccessControlImpl, AccessControlNoCheck};
 use crate::data_source::split::SplitManager;
 use crate::data_source::stream::tskv::factory::{TskvStreamProviderFactory, TSKV_STREAM_PROVIDER};
 use crate::dispatcher::manager::SimpleQueryDispatcherBuilder;
 use crate::dispatcher::persister::MetaQueryPersister;
 use crate::dispatcher::query_tracker::QueryTracker;
+use crate::exchange::ExchangeManager;
 use crate::execution::factory::SqlQueryExecutionFactory;
 use crate::execution::scheduler::local::LocalScheduler;
 use crate::extension::expr::{load_all_functions, register_session_udfs};
+use crate::extension::physical::optimizer_rule::distribute_hash_join::DistributeHashJoin;
 use crate::extension::variable::load_all_system_vars;
 use crate::function::simple_func_manager::SimpleFunctionMetadataManager;
 use crate::metadata::BaseTableProvider;
 use crate::sql::optimizer::CascadeOptimizerBuilder;
 use crate::sql::parser::DefaultParser;
+use crate::sql::physical::optimizer::PhysicalOptimizer;
+use crate::sql::physical::planner::DefaultPhysicalPlanner;
 use crate::variable::simple_sys_var_manager::SimpleSystemVarManager;
 
 pub const DEFAULT_CNOSDB_PATH: &str = ".cnosdb";
 pub const DEFAULT_CNOSDB_QUERY_DIRECTORY_NAME: &str = "query";
+pub const DEFAULT_CNOSDB_EXCHANGE_DIRECTORY_NAME: &str = "exchange";
 
 #[derive(Builder)]
 pub struct Cnosdbms<D: QueryDispatcher> {
@@ -52,6 +60,7 @@ pub struct Cnosdbms<D: QueryDispatcher> {
     // query dispatcher & query execution
     query_dispatcher: Arc<D>,
     auth_cache: Arc<AuthCache<AuthCacheKey, User>>,
+    audit_logger: AuditLoggerRef,
 }
 
 #[async_trait]
@@ -63,18 +72,27 @@ where
         self.query_dispatcher.start().await
     }
 
-    async fn authenticate(&self, user_info: &UserInfo, tenant_name: &str) -> QueryResult<User> {
+    async fn authenticate(
+        &self,
+        user_info: &UserInfo,
+        tenant_name: &str,
+        client_addr: Option<&str>,
+    ) -> QueryResult<User> {
         let auth_cache_key = AuthCacheKey::new(user_info, tenant_name);
         if let Some(user) = self.auth_cache.get(&auth_cache_key) {
             debug!("Hit auth cache for user: {}", user.desc().name());
             return Ok(user);
         }
 
-        let user = self
+        // Only the logins checking the credentials are audited, not the cached ones.
+        let result = self
             .access_control
             .access_check(user_info, tenant_name)
             .await
-            .context(AuthSnafu)?;
+            .context(AuthSnafu);
+        self.audit_logger
+            .log(AuditEvent::login(&user_info.user, tenant_name, client_addr).with_result(&result));
+        let user = result?;
         self.auth_cache.insert(auth_cache_key, user.clone());
         Ok(user)
     }
@@ -150,6 +168,33 @@ where
         Ok(QueryHandle::new(query_id, query.clone(), result))
     }
 
+    async fn explain(
+        &self,
+        query: &Query,
+        span_context: Option<&SpanContext>,
+    ) -> QueryResult<QueryPlanDescription> {
+        let query_state_machine = self
+            .build_query_state_machine(query.clone(), span_context)
+            .await?;
+        let plan = match self.build_logical_plan(query_state_machine.clone()).await? {
+            Some(Plan::Query(plan)) if !plan.is_explain() => plan,
+            _ => {
+                return Err(QueryError::NotImplemented {
+                    err: "explain non-query statement.".to_string(),
+                })
+            }
+        };
+
+        let batches = self
+            .execute_logical_plan(Plan::Query(plan.explain(false)?), query_state_machine)
+            .await?
+            .result()
+            .chunk_result()
+            .await?;
+
+        plan_description(&batches)
+    }
+
     fn metrics(&self) -> String {
         let infos = self.query_dispatcher.running_query_infos();
         let status = self.query_dispatcher.running_query_status();
@@ -172,6 +217,11 @@ where
     fn cancel(&self, query_id: &QueryId) {
         self.query_dispatcher.cancel_query(query_id);
     }
+
+    fn audit_write(&self, context: &Context, protocol: &str, error: Option<String>) {
+        self.audit_logger
+            .log(AuditEvent::write(context, protocol, error));
+    }
 }
 
 impl<D: QueryDispatcher> Cnosdbms<D> {
@@ -182,6 +232,25 @@ impl<D: QueryDispatcher> Cnosdbms<D> {
         self.access_control.tenant_id(tenant_name).await
     }
 }
+/// Collect the plans from the output of EXPLAIN, which has two columns:
+/// plan_type and plan.
+fn plan_description(batches: &[RecordBatch]) -> QueryResult<QueryPlanDescription> {
+    let mut description = QueryPlanDescription::default();
+    for batch in batches {
+        let plan_types = as_string_array(batch.column(0))?;
+        let plans = as_string_array(batch.column(1))?;
+        for (plan_type, plan) in plan_types.iter().zip(plans.iter()) {
+            match (plan_type, plan) {
+                (Some("logical_plan"), Some(plan)) => description.logical_plan = plan.to_string(),
+                (Some("physical_plan"), Some(plan)) => description.physical_plan = plan.to_string(),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(description)
+}
+
 pub async fn make_cnosdbms(
     coord: CoordinatorRef,
     options: Options,
@@ -207,7 +276,31 @@ pub async fn make_cnosdbms(
         Some(register_session_udfs),
     ));
     let parser = Arc::new(DefaultParser::default());
-    let optimizer = Arc::new(CascadeOptimizerBuilder::default().build());
+    // Serve the exchanges of other nodes even if the shuffle is disabled on this node.
+    ExchangeManager::new(
+        coord.clone(),
+        query_dedicated_hidden_dir
+            .join(DEFAULT_CNOSDB_EXCHANGE_DIRECTORY_NAME)
+            .join(coord.node_id().to_string()),
+        memory_pool.clone(),
+        options.query.max_query_memory as usize,
+    )
+    .install();
+    let optimizer = if options.query.shuffle_join_partitions > 0 {
+        let mut physical_planner = DefaultPhysicalPlanner::default();
+        physical_planner.inject_optimizer_rule(Arc::new(DistributeHashJoin::new(
+            options.query.shuffle_join_partitions,
+        )));
+        let physical_planner = Arc::new(physical_planner);
+        Arc::new(
+            CascadeOptimizerBuilder::default()
+                .with_physical_planner(physical_planner.clone())
+                .with_physical_optimizer(physical_planner)
+                .build(),
+        )
+    } else {
+        Arc::new(CascadeOptimizerBuilder::default().build())
+    };
     // TODO wrap, and num_threads configurable
     let scheduler = Arc::new(LocalScheduler {});
 
@@ -236,12 +329,15 @@ pub async fn make_cnosdbms(
         coord.clone(),
     ));
 
+    let audit_logger = Arc::new(AuditLogger::new(coord.clone()));
+
     let query_execution_factory = Arc::new(SqlQueryExecutionFactory::new(
         optimizer,
         scheduler,
         query_tracker.clone(),
         Arc::new(stream_checker_manager),
         options.query.clone(),
+        audit_logger.clone(),
     ));
 
     let meta_manager = coord.meta_manager();
@@ -272,12 +368,14 @@ pub async fn make_cnosdbms(
         .with_split_manager(split_manager)
         .with_session_factory(session_factory)
         .with_memory_pool(memory_pool)
+        .with_max_query_memory(options.query.max_query_memory as usize)
         .with_parser(parser)
         .with_query_execution_factory(query_execution_factory)
         .with_query_tracker(query_tracker)
         .with_func_manager(Arc::new(func_manager))
         .with_stream_provider_manager(stream_provider_manager)
         .with_auth_cache(auth_cache.clone())
+        .with_audit_logger(audit_logger.clone())
         .build()?;
 
     let mut builder = CnosdbmsBuilder::default();
@@ -294,6 +392,7 @@ pub async fn make_cnosdbms(
     let db_server = builder
         .query_dispatcher(query_dispatcher)
         .auth_cache(auth_cache.clone())
+        .audit_logger(audit_logger)
         .build()
         .expect("build db server");
 
@@ -309,6 +408,7 @@ mod tests {
     use chrono::Utc;
     use config::tskv::get_config_for_test;
     use coordinator::service_mock::MockCoordinator;
+    use datafusion::arrow::array::{ArrayRef, StringArray};
     use datafusion::arrow::record_batch::RecordBatch;
     use datafusion::arrow::util::pretty::pretty_format_batches;
     use memory_pool::GreedyMemoryPool;
@@ -344,7 +444,7 @@ mod tests {
         };
 
         let user = db
-            .authenticate(&user, DEFAULT_CATALOG)
+            .authenticate(&user, DEFAULT_CATALOG, None)
             .await
             .expect("authenticate");
 
@@ -386,6 +486,63 @@ mod tests {
         assert_batches_eq!(expected, result.deref_mut());
     }
 
+    #[test]
+    fn test_plan_description() {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "plan_type",
+                Arc::new(StringArray::from(vec!["logical_plan", "physical_plan"])) as ArrayRef,
+            ),
+            (
+                "plan",
+                Arc::new(StringArray::from(vec![
+                    "Projection: Int64(1)",
+                    "ProjectionExec: expr=[1 as Int64(1)]",
+                ])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let description = plan_description(&[batch]).unwrap();
+        assert_eq!(description.logical_plan, "Projection: Int64(1)");
+        assert_eq!(
+            description.physical_plan,
+            "ProjectionExec: expr=[1 as Int64(1)]"
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_explain() {
+        let config = get_config_for_test();
+        let opt = Options::from(&config);
+        let memory = Arc::new(GreedyMemoryPool::new(1024 * 1024 * 1024));
+        let db = make_cnosdbms(Arc::new(MockCoordinator::default()), opt, memory)
+            .await
+            .unwrap();
+        let user = UserInfo {
+            user: DEFAULT_CATALOG.to_string(),
+            password: "todo".to_string(),
+            private_key: None,
+        };
+        let user = db.authenticate(&user, DEFAULT_CATALOG, None).await.unwrap();
+        let explain = |sql: &str| {
+            let query = Query::new(ContextBuilder::new(user.clone()).build(), sql.to_string());
+            let db = &db;
+            async move { db.explain(&query, None).await }
+        };
+
+        let description = explain("SELECT num FROM (VALUES (1), (2)) AS t (num)")
+            .await
+            .unwrap();
+        assert!(description.logical_plan.contains("Projection"));
+        assert!(description.physical_plan.contains("ProjectionExec"));
+
+        // Not executed.
+        assert!(explain("CREATE DATABASE test_explain").await.is_err());
+        assert!(explain("EXPLAIN SELECT 1").await.is_err());
+    }
+
     fn generate_data(n: usize) -> String {
         // let mut random = rand::thread_rng();
 
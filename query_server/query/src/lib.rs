@@ -1,12 +1,15 @@
 #![recursion_limit = "256"]
 extern crate core;
 
+pub mod audit;
 pub mod auth;
 pub mod data_source;
 pub mod dispatcher;
+pub mod exchange;
 mod execution;
 pub mod extension;
 pub mod function;
+pub mod influxql;
 pub mod instance;
 pub mod metadata;
 pub mod prom;
@@ -6,6 +6,8 @@ use datafusion::datasource::listing::{ListingTable, ListingTableConfig, ListingT
 use datafusion::error::DataFusionError;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::Identifier;
 use models::schema::table_schema::TableSchema;
 use spi::query::datasource::stream::StreamProviderManagerRef;
 
@@ -19,6 +21,8 @@ pub struct BaseTableProvider {
     split_manager: SplitManagerRef,
     meta_client: MetaClientRef,
     stream_provider_manager: StreamProviderManagerRef,
+    /// The user of the session, encrypted columns are decrypted for users with the privilege.
+    user: Option<User>,
 }
 
 impl BaseTableProvider {
@@ -33,8 +37,21 @@ impl BaseTableProvider {
             split_manager,
             meta_client,
             stream_provider_manager,
+            user: None,
         }
     }
+
+    pub fn with_user(mut self, user: User) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    fn can_decrypt(&self, database_name: &str) -> bool {
+        let tenant_id = *self.meta_client.tenant().id();
+        self.user
+            .as_ref()
+            .is_some_and(|user| user.can_decrypt_database(tenant_id, database_name))
+    }
 }
 
 impl TableHandleProvider for BaseTableProvider {
@@ -73,13 +90,18 @@ impl TableHandleProvider for BaseTableProvider {
             .map_err(|e| DataFusionError::External(Box::new(e)))?
         {
             Some(table) => match table {
-                TableSchema::TsKvTableSchema(schema) => Arc::new(ClusterTable::new(
-                    self.coord.clone(),
-                    self.split_manager.clone(),
-                    self.meta_client.clone(),
-                    schema,
-                ))
-                .into(),
+                TableSchema::TsKvTableSchema(schema) => {
+                    let mut table = ClusterTable::new(
+                        self.coord.clone(),
+                        self.split_manager.clone(),
+                        self.meta_client.clone(),
+                        schema,
+                    );
+                    if self.can_decrypt(database_name) {
+                        table = table.with_decryption()?;
+                    }
+                    Arc::new(table).into()
+                }
                 TableSchema::ExternalTableSchema(schema) => {
                     let table_path = ListingTableUrl::parse(&schema.location)?;
                     let options = schema.table_options()?;
@@ -1,2 +1,4 @@
+pub mod nodes;
+pub mod runtime;
 pub mod tenants;
 pub mod users;
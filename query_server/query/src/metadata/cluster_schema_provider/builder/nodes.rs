@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Int64Builder, StringBuilder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref NODE_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("node_id", DataType::UInt64, false),
+        Field::new("grpc_addr", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("disk_free", DataType::UInt64, false),
+        Field::new("last_report_time", DataType::Int64, false),
+        Field::new("clock_offset_ms", DataType::Int64, false),
+    ]));
+}
+
+/// Builds the `cluster_schema.NODES` table row by row
+#[derive(Default)]
+pub struct ClusterSchemaNodesBuilder {
+    node_ids: UInt64Builder,
+    grpc_addrs: StringBuilder,
+    statuses: StringBuilder,
+    disk_frees: UInt64Builder,
+    last_report_times: Int64Builder,
+    clock_offsets: Int64Builder,
+}
+
+impl ClusterSchemaNodesBuilder {
+    pub fn append_row(
+        &mut self,
+        node_id: u64,
+        grpc_addr: impl AsRef<str>,
+        status: impl AsRef<str>,
+        disk_free: u64,
+        last_report_time: i64,
+        clock_offset_ms: i64,
+    ) {
+        // Note: append_value is actually infallable.
+        self.node_ids.append_value(node_id);
+        self.grpc_addrs.append_value(grpc_addr.as_ref());
+        self.statuses.append_value(status.as_ref());
+        self.disk_frees.append_value(disk_free);
+        self.last_report_times.append_value(last_report_time);
+        self.clock_offsets.append_value(clock_offset_ms);
+    }
+}
+
+impl TryFrom<ClusterSchemaNodesBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: ClusterSchemaNodesBuilder) -> Result<Self, Self::Error> {
+        let ClusterSchemaNodesBuilder {
+            mut node_ids,
+            mut grpc_addrs,
+            mut statuses,
+            mut disk_frees,
+            mut last_report_times,
+            mut clock_offsets,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            NODE_SCHEMA.clone(),
+            vec![
+                Arc::new(node_ids.finish()),
+                Arc::new(grpc_addrs.finish()),
+                Arc::new(statuses.finish()),
+                Arc::new(disk_frees.finish()),
+                Arc::new(last_report_times.finish()),
+                Arc::new(clock_offsets.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
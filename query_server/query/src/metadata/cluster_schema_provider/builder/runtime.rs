@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Int64Builder, StringBuilder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref RUNTIME_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("node_id", DataType::UInt64, false),
+        Field::new("category", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("metric", DataType::Utf8, false),
+        Field::new("value", DataType::Int64, false),
+    ]));
+}
+
+/// Builds the `cluster_schema.RUNTIME` table row by row
+#[derive(Default)]
+pub struct ClusterSchemaRuntimeBuilder {
+    node_ids: UInt64Builder,
+    categories: StringBuilder,
+    names: StringBuilder,
+    metrics: StringBuilder,
+    values: Int64Builder,
+}
+
+impl ClusterSchemaRuntimeBuilder {
+    pub fn append_row(
+        &mut self,
+        node_id: u64,
+        category: impl AsRef<str>,
+        name: impl AsRef<str>,
+        metric: impl AsRef<str>,
+        value: i64,
+    ) {
+        // Note: append_value is actually infallable.
+        self.node_ids.append_value(node_id);
+        self.categories.append_value(category.as_ref());
+        self.names.append_value(name.as_ref());
+        self.metrics.append_value(metric.as_ref());
+        self.values.append_value(value);
+    }
+}
+
+impl TryFrom<ClusterSchemaRuntimeBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: ClusterSchemaRuntimeBuilder) -> Result<Self, Self::Error> {
+        let ClusterSchemaRuntimeBuilder {
+            mut node_ids,
+            mut categories,
+            mut names,
+            mut metrics,
+            mut values,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            RUNTIME_SCHEMA.clone(),
+            vec![
+                Arc::new(node_ids.finish()),
+                Arc::new(categories.finish()),
+                Arc::new(names.finish()),
+                Arc::new(metrics.finish()),
+                Arc::new(values.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
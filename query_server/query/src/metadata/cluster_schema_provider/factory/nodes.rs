@@ -0,0 +1,99 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaRef;
+use models::auth::user::User;
+
+use crate::metadata::cluster_schema_provider::builder::nodes::{
+    ClusterSchemaNodesBuilder, NODE_SCHEMA,
+};
+use crate::metadata::cluster_schema_provider::{ClusterSchemaTableFactory, CLUSTER_SCHEMA_NODES};
+
+pub struct ClusterSchemaNodesFactory {}
+
+impl ClusterSchemaTableFactory for ClusterSchemaNodesFactory {
+    fn table_name(&self) -> &str {
+        CLUSTER_SCHEMA_NODES
+    }
+
+    fn create(&self, user: &User, metadata: MetaRef) -> Arc<dyn TableProvider> {
+        Arc::new(ClusterSchemaNodesTable::new(metadata, user.clone()))
+    }
+}
+
+pub struct ClusterSchemaNodesTable {
+    user: User,
+    metadata: MetaRef,
+}
+
+impl ClusterSchemaNodesTable {
+    pub fn new(metadata: MetaRef, user: User) -> Self {
+        Self { user, metadata }
+    }
+}
+
+#[async_trait::async_trait]
+impl TableProvider for ClusterSchemaNodesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        NODE_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = ClusterSchemaNodesBuilder::default();
+
+        // Only visible to admin
+        if self.user.desc().is_admin() {
+            let node_metrics_list = self.metadata.node_metrics().await.map_err(|e| {
+                DataFusionError::Internal(format!("Failed to get node metrics: {:?}", e))
+            })?;
+            for node_metrics in node_metrics_list {
+                let grpc_addr = self
+                    .metadata
+                    .node_info_by_id(node_metrics.id)
+                    .await
+                    .map(|info| info.grpc_addr)
+                    .unwrap_or_default();
+
+                builder.append_row(
+                    node_metrics.id,
+                    grpc_addr,
+                    format!("{:?}", node_metrics.status),
+                    node_metrics.disk_free,
+                    node_metrics.time,
+                    node_metrics.clock_offset / 1_000_000,
+                );
+            }
+        }
+
+        let rb: RecordBatch = builder.try_into()?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
@@ -0,0 +1,85 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaRef;
+use models::auth::user::User;
+use models::runtime::stats::runtime_stats;
+
+use crate::metadata::cluster_schema_provider::builder::runtime::{
+    ClusterSchemaRuntimeBuilder, RUNTIME_SCHEMA,
+};
+use crate::metadata::cluster_schema_provider::{ClusterSchemaTableFactory, CLUSTER_SCHEMA_RUNTIME};
+
+pub struct ClusterSchemaRuntimeFactory {}
+
+impl ClusterSchemaTableFactory for ClusterSchemaRuntimeFactory {
+    fn table_name(&self) -> &str {
+        CLUSTER_SCHEMA_RUNTIME
+    }
+
+    fn create(&self, user: &User, metadata: MetaRef) -> Arc<dyn TableProvider> {
+        Arc::new(ClusterSchemaRuntimeTable::new(metadata, user.clone()))
+    }
+}
+
+/// Runtime statistics of the node serving the query.
+pub struct ClusterSchemaRuntimeTable {
+    user: User,
+    metadata: MetaRef,
+}
+
+impl ClusterSchemaRuntimeTable {
+    pub fn new(metadata: MetaRef, user: User) -> Self {
+        Self { user, metadata }
+    }
+}
+
+#[async_trait::async_trait]
+impl TableProvider for ClusterSchemaRuntimeTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        RUNTIME_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = ClusterSchemaRuntimeBuilder::default();
+
+        // Only visible to admin
+        if self.user.desc().is_admin() {
+            let node_id = self.metadata.node_id();
+            for stat in runtime_stats() {
+                builder.append_row(node_id, stat.category, stat.name, stat.metric, stat.value);
+            }
+        }
+
+        let rb: RecordBatch = builder.try_into()?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
@@ -73,7 +73,9 @@ impl TableProvider for ClusterSchemaTenantsTable {
                     DataFusionError::Internal(format!("failed to list tenant {}", e))
                 })?;
             for tenant in tenants.iter() {
-                let options_str = serde_json::to_string(tenant.options()).map_err(|e| {
+                let mut options = tenant.options().clone();
+                options.hidden_encryption_keys();
+                let options_str = serde_json::to_string(&options).map_err(|e| {
                     DataFusionError::Internal(format!("failed to serialize options: {}", e))
                 })?;
 
@@ -7,6 +7,8 @@ use meta::error::MetaError;
 use meta::model::MetaRef;
 use models::auth::user::User;
 
+use self::factory::nodes::ClusterSchemaNodesFactory;
+use self::factory::runtime::ClusterSchemaRuntimeFactory;
 use self::factory::tenants::ClusterSchemaTenantsFactory;
 use self::factory::users::ClusterSchemaUsersFactory;
 use super::CLUSTER_SCHEMA;
@@ -16,6 +18,8 @@ mod factory;
 
 pub const CLUSTER_SCHEMA_TENANTS: &str = "TENANTS";
 pub const CLUSTER_SCHEMA_USERS: &str = "USERS";
+pub const CLUSTER_SCHEMA_NODES: &str = "NODES";
+pub const CLUSTER_SCHEMA_RUNTIME: &str = "RUNTIME";
 
 pub struct ClusterSchemaProvider {
     table_factories: HashMap<String, BoxSystemTableFactory>,
@@ -29,6 +33,8 @@ impl ClusterSchemaProvider {
 
         provider.register_table_factory(Box::new(ClusterSchemaTenantsFactory {}));
         provider.register_table_factory(Box::new(ClusterSchemaUsersFactory {}));
+        provider.register_table_factory(Box::new(ClusterSchemaNodesFactory {}));
+        provider.register_table_factory(Box::new(ClusterSchemaRuntimeFactory {}));
 
         provider
     }
@@ -32,7 +32,7 @@ lazy_static! {
         Field::new(DATABASES_MAX_MEMCACHE_SIZE, DataType::Utf8, false),
         Field::new(DATABASES_MEMCACHE_PARTITIONS, DataType::UInt64, false),
         Field::new(DATABASES_WAL_MAX_FILE_SIZE, DataType::Utf8, false),
-        Field::new(DATABASES_WAL_SYNC, DataType::Boolean, false),
+        Field::new(DATABASES_WAL_SYNC, DataType::Utf8, false),
         Field::new(DATABASES_STRICT_WRITE, DataType::Boolean, false),
         Field::new(DATABASES_MAX_CACHE_READERS, DataType::UInt64, false),
     ]));
@@ -51,7 +51,7 @@ pub struct InformationSchemaDatabasesBuilder {
     config_max_memcache_sizes: StringBuilder,
     config_memcache_partitions: UInt64Builder,
     config_wal_max_file_sizes: StringBuilder,
-    config_wal_syncs: BooleanBuilder,
+    config_wal_syncs: StringBuilder,
     config_strict_writes: BooleanBuilder,
     config_max_cache_readers: UInt64Builder,
 }
@@ -70,7 +70,7 @@ impl InformationSchemaDatabasesBuilder {
         config_max_memcache_size: impl AsRef<str>,
         config_memcache_partition: u64,
         config_wal_max_file_size: impl AsRef<str>,
-        config_wal_sync: bool,
+        config_wal_sync: impl AsRef<str>,
         config_strict_write: bool,
         config_max_cache_reader: u64,
     ) {
@@ -89,7 +89,7 @@ impl InformationSchemaDatabasesBuilder {
             .append_value(config_memcache_partition);
         self.config_wal_max_file_sizes
             .append_value(config_wal_max_file_size.as_ref());
-        self.config_wal_syncs.append_value(config_wal_sync);
+        self.config_wal_syncs.append_value(config_wal_sync.as_ref());
         self.config_strict_writes.append_value(config_strict_write);
         self.config_max_cache_readers
             .append_value(config_max_cache_reader);
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const FUNCTIONS_FUNCTION_NAME: &str = "function_name";
+pub const FUNCTIONS_FUNCTION_TYPE: &str = "function_type";
+pub const FUNCTIONS_ARGUMENT_TYPES: &str = "argument_types";
+pub const FUNCTIONS_RETURN_TYPE: &str = "return_type";
+pub const FUNCTIONS_DESCRIPTION: &str = "description";
+
+lazy_static! {
+    pub static ref FUNCTION_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(FUNCTIONS_FUNCTION_NAME, DataType::Utf8, false),
+        Field::new(FUNCTIONS_FUNCTION_TYPE, DataType::Utf8, false),
+        Field::new(FUNCTIONS_ARGUMENT_TYPES, DataType::Utf8, false),
+        Field::new(FUNCTIONS_RETURN_TYPE, DataType::Utf8, true),
+        Field::new(FUNCTIONS_DESCRIPTION, DataType::Utf8, true),
+    ]));
+}
+
+/// Builds the `information_schema.Functions` table row by row
+#[derive(Default)]
+pub struct InformationSchemaFunctionsBuilder {
+    function_names: StringBuilder,
+    function_types: StringBuilder,
+    argument_types: StringBuilder,
+    return_types: StringBuilder,
+    descriptions: StringBuilder,
+}
+
+impl InformationSchemaFunctionsBuilder {
+    pub fn append_row(
+        &mut self,
+        function_name: impl AsRef<str>,
+        function_type: impl AsRef<str>,
+        argument_types: impl AsRef<str>,
+        return_type: Option<impl AsRef<str>>,
+        description: Option<impl AsRef<str>>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.function_names.append_value(function_name.as_ref());
+        self.function_types.append_value(function_type.as_ref());
+        self.argument_types.append_value(argument_types.as_ref());
+        self.return_types.append_option(return_type);
+        self.descriptions.append_option(description);
+    }
+}
+
+impl TryFrom<InformationSchemaFunctionsBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaFunctionsBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaFunctionsBuilder {
+            mut function_names,
+            mut function_types,
+            mut argument_types,
+            mut return_types,
+            mut descriptions,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            FUNCTION_SCHEMA.clone(),
+            vec![
+                Arc::new(function_names.finish()),
+                Arc::new(function_types.finish()),
+                Arc::new(argument_types.finish()),
+                Arc::new(return_types.finish()),
+                Arc::new(descriptions.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
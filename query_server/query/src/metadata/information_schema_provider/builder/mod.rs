@@ -2,8 +2,11 @@ pub mod columns;
 pub mod database_privileges;
 pub mod databases;
 pub mod enabled_roles;
+pub mod functions;
 pub mod members;
 pub mod queries;
+pub mod recovery;
 pub mod resource_status;
 pub mod roles;
 pub mod tables;
+pub mod vnodes;
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{StringBuilder, UInt32Builder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const RECOVERY_NODE_ID: &str = "node_id";
+pub const RECOVERY_DATABASE_NAME: &str = "database_name";
+pub const RECOVERY_VNODE_ID: &str = "vnode_id";
+pub const RECOVERY_STATUS: &str = "status";
+pub const RECOVERY_TOTAL_BYTES: &str = "total_bytes";
+pub const RECOVERY_DOWNLOADED_BYTES: &str = "downloaded_bytes";
+pub const RECOVERY_ELAPSED_MS: &str = "elapsed_ms";
+pub const RECOVERY_NODE_ETA_MS: &str = "node_eta_ms";
+pub const RECOVERY_ERROR: &str = "error";
+
+lazy_static! {
+    pub static ref RECOVERY_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(RECOVERY_NODE_ID, DataType::UInt64, false),
+        Field::new(RECOVERY_DATABASE_NAME, DataType::Utf8, false),
+        Field::new(RECOVERY_VNODE_ID, DataType::UInt32, false),
+        Field::new(RECOVERY_STATUS, DataType::Utf8, false),
+        Field::new(RECOVERY_TOTAL_BYTES, DataType::UInt64, false),
+        Field::new(RECOVERY_DOWNLOADED_BYTES, DataType::UInt64, false),
+        Field::new(RECOVERY_ELAPSED_MS, DataType::UInt64, false),
+        Field::new(RECOVERY_NODE_ETA_MS, DataType::UInt64, true),
+        Field::new(RECOVERY_ERROR, DataType::Utf8, true),
+    ]));
+}
+
+/// Builds the `information_schema.RECOVERY` table row by row
+#[derive(Default)]
+pub struct InformationSchemaRecoveryBuilder {
+    node_ids: UInt64Builder,
+    database_names: StringBuilder,
+    vnode_ids: UInt32Builder,
+    statuses: StringBuilder,
+    total_bytes: UInt64Builder,
+    downloaded_bytes: UInt64Builder,
+    elapsed_ms: UInt64Builder,
+    node_eta_ms: UInt64Builder,
+    errors: StringBuilder,
+}
+
+impl InformationSchemaRecoveryBuilder {
+    #[allow(clippy::too_many_arguments)]
+    pub fn append_row(
+        &mut self,
+        node_id: u64,
+        database_name: impl AsRef<str>,
+        vnode_id: u32,
+        status: impl AsRef<str>,
+        total_bytes: u64,
+        downloaded_bytes: u64,
+        elapsed_ms: u64,
+        node_eta_ms: Option<u64>,
+        error: Option<&str>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.node_ids.append_value(node_id);
+        self.database_names.append_value(database_name.as_ref());
+        self.vnode_ids.append_value(vnode_id);
+        self.statuses.append_value(status.as_ref());
+        self.total_bytes.append_value(total_bytes);
+        self.downloaded_bytes.append_value(downloaded_bytes);
+        self.elapsed_ms.append_value(elapsed_ms);
+        self.node_eta_ms.append_option(node_eta_ms);
+        self.errors.append_option(error);
+    }
+}
+
+impl TryFrom<InformationSchemaRecoveryBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaRecoveryBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaRecoveryBuilder {
+            mut node_ids,
+            mut database_names,
+            mut vnode_ids,
+            mut statuses,
+            mut total_bytes,
+            mut downloaded_bytes,
+            mut elapsed_ms,
+            mut node_eta_ms,
+            mut errors,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            RECOVERY_SCHEMA.clone(),
+            vec![
+                Arc::new(node_ids.finish()),
+                Arc::new(database_names.finish()),
+                Arc::new(vnode_ids.finish()),
+                Arc::new(statuses.finish()),
+                Arc::new(total_bytes.finish()),
+                Arc::new(downloaded_bytes.finish()),
+                Arc::new(elapsed_ms.finish()),
+                Arc::new(node_eta_ms.finish()),
+                Arc::new(errors.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
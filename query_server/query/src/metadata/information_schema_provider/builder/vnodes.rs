@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{StringBuilder, UInt32Builder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const VNODES_TENANT_NAME: &str = "tenant_name";
+pub const VNODES_DATABASE_NAME: &str = "database_name";
+pub const VNODES_REPLICA_ID: &str = "replica_id";
+pub const VNODES_VNODE_ID: &str = "vnode_id";
+pub const VNODES_NODE_ID: &str = "node_id";
+pub const VNODES_STATUS: &str = "status";
+pub const VNODES_ROLE: &str = "role";
+pub const VNODES_DISK_STORAGE: &str = "disk_storage";
+
+lazy_static! {
+    pub static ref VNODES_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(VNODES_TENANT_NAME, DataType::Utf8, false),
+        Field::new(VNODES_DATABASE_NAME, DataType::Utf8, false),
+        Field::new(VNODES_REPLICA_ID, DataType::UInt32, false),
+        Field::new(VNODES_VNODE_ID, DataType::UInt32, false),
+        Field::new(VNODES_NODE_ID, DataType::UInt64, false),
+        Field::new(VNODES_STATUS, DataType::Utf8, false),
+        Field::new(VNODES_ROLE, DataType::Utf8, false),
+        Field::new(VNODES_DISK_STORAGE, DataType::UInt64, true),
+    ]));
+}
+
+/// Builds the `information_schema.VNODES` table row by row
+#[derive(Default)]
+pub struct InformationSchemaVnodesBuilder {
+    tenant_names: StringBuilder,
+    database_names: StringBuilder,
+    replica_ids: UInt32Builder,
+    vnode_ids: UInt32Builder,
+    node_ids: UInt64Builder,
+    statuses: StringBuilder,
+    roles: StringBuilder,
+    disk_storages: UInt64Builder,
+}
+
+impl InformationSchemaVnodesBuilder {
+    #[allow(clippy::too_many_arguments)]
+    pub fn append_row(
+        &mut self,
+        tenant_name: impl AsRef<str>,
+        database_name: impl AsRef<str>,
+        replica_id: u32,
+        vnode_id: u32,
+        node_id: u64,
+        status: impl AsRef<str>,
+        role: impl AsRef<str>,
+        disk_storage: Option<u64>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.tenant_names.append_value(tenant_name.as_ref());
+        self.database_names.append_value(database_name.as_ref());
+        self.replica_ids.append_value(replica_id);
+        self.vnode_ids.append_value(vnode_id);
+        self.node_ids.append_value(node_id);
+        self.statuses.append_value(status.as_ref());
+        self.roles.append_value(role.as_ref());
+        self.disk_storages.append_option(disk_storage);
+    }
+}
+
+impl TryFrom<InformationSchemaVnodesBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaVnodesBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaVnodesBuilder {
+            mut tenant_names,
+            mut database_names,
+            mut replica_ids,
+            mut vnode_ids,
+            mut node_ids,
+            mut statuses,
+            mut roles,
+            mut disk_storages,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            VNODES_SCHEMA.clone(),
+            vec![
+                Arc::new(tenant_names.finish()),
+                Arc::new(database_names.finish()),
+                Arc::new(replica_ids.finish()),
+                Arc::new(vnode_ids.finish()),
+                Arc::new(node_ids.finish()),
+                Arc::new(statuses.finish()),
+                Arc::new(roles.finish()),
+                Arc::new(disk_storages.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
@@ -108,7 +108,7 @@ impl TableProvider for InformationDatabasesTable {
                 CnosByteNumber::format_bytes(config.max_memcache_size()),
                 config.memcache_partitions(),
                 CnosByteNumber::format_bytes(config.wal_max_file_size()),
-                config.wal_sync(),
+                config.wal_sync().to_string(),
                 config.strict_write(),
                 config.max_cache_readers(),
             );
@@ -0,0 +1,115 @@
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use spi::query::function::{FuncMetaManagerRef, FunctionMeta};
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::functions::{
+    InformationSchemaFunctionsBuilder, FUNCTION_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_FUNCTIONS: &str = "FUNCTIONS";
+
+/// This view shows the functions that could be used in sql,
+/// including the built-in functions of cnosdb and the session functions.
+///
+/// All records of this view are visible to all users.
+pub struct FunctionsFactory {
+    pub func_manager: FuncMetaManagerRef,
+}
+
+impl InformationSchemaTableFactory for FunctionsFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_FUNCTIONS
+    }
+
+    fn create(
+        &self,
+        _user: &User,
+        _metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationFunctionsTable::new(self.func_manager.clone()))
+    }
+}
+
+pub struct InformationFunctionsTable {
+    func_manager: FuncMetaManagerRef,
+}
+
+impl InformationFunctionsTable {
+    pub fn new(func_manager: FuncMetaManagerRef) -> Self {
+        Self { func_manager }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationFunctionsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        FUNCTION_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaFunctionsBuilder::default();
+
+        let mut functions = self.func_manager.functions();
+        // Session functions, such as current_user(), are registered in the session.
+        let registered = functions
+            .iter()
+            .map(|f| f.name.to_uppercase())
+            .collect::<HashSet<_>>();
+        functions.extend(
+            state
+                .scalar_functions()
+                .values()
+                .filter(|f| !registered.contains(&f.name.to_uppercase()))
+                .map(|f| FunctionMeta::from_udf(f)),
+        );
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for function in functions {
+            builder.append_row(
+                &function.name,
+                function.category.to_string(),
+                function.argument_types(),
+                function.return_type.as_ref().map(|t| t.to_string()),
+                function.description.as_ref(),
+            );
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
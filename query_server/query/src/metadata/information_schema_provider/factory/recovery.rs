@@ -0,0 +1,128 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::recovery::{
+    InformationSchemaRecoveryBuilder, RECOVERY_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_RECOVERY: &str = "RECOVERY";
+
+/// This view shows the vnodes of the tenant restored from snapshots on each
+/// data node since it started, with the estimated time for the node to download
+/// the rest of the snapshots being restored.
+///
+/// Only the vnodes of databases readable by the user are visible.
+pub struct RecoveryFactory {
+    pub coord: CoordinatorRef,
+}
+
+impl InformationSchemaTableFactory for RecoveryFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_RECOVERY
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationSchemaRecoveryTable::new(
+            self.coord.clone(),
+            metadata,
+            user.clone(),
+        ))
+    }
+}
+
+pub struct InformationSchemaRecoveryTable {
+    coord: CoordinatorRef,
+    metadata: MetaClientRef,
+    user: User,
+}
+
+impl InformationSchemaRecoveryTable {
+    pub fn new(coord: CoordinatorRef, metadata: MetaClientRef, user: User) -> Self {
+        Self {
+            coord,
+            metadata,
+            user,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationSchemaRecoveryTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        RECOVERY_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaRecoveryBuilder::default();
+
+        let tenant = self.metadata.tenant();
+        let tenant_id = tenant.id();
+        let progresses = self
+            .coord
+            .recovery_progress(tenant.name())
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        for progress in progresses {
+            for vnode in progress
+                .vnodes
+                .iter()
+                .filter(|v| self.user.can_read_database(*tenant_id, &v.database))
+            {
+                builder.append_row(
+                    progress.node_id,
+                    &vnode.database,
+                    vnode.vnode_id,
+                    format!("{:?}", vnode.status),
+                    vnode.total_bytes,
+                    vnode.downloaded_bytes,
+                    vnode.elapsed_ms,
+                    progress.eta_ms,
+                    vnode.error.as_deref(),
+                );
+            }
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
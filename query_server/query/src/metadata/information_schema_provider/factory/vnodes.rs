@@ -0,0 +1,141 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::vnodes::{
+    InformationSchemaVnodesBuilder, VNODES_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_VNODES: &str = "VNODES";
+
+/// This view shows the placement of vnodes of the databases in the tenant,
+/// the role in the replication set and the size of files on disk.
+///
+/// Only the vnodes of databases readable by the user are visible.
+pub struct VnodesFactory {
+    pub coord: CoordinatorRef,
+}
+
+impl InformationSchemaTableFactory for VnodesFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_VNODES
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationSchemaVnodesTable::new(
+            self.coord.clone(),
+            metadata,
+            user.clone(),
+        ))
+    }
+}
+
+pub struct InformationSchemaVnodesTable {
+    coord: CoordinatorRef,
+    metadata: MetaClientRef,
+    user: User,
+}
+
+impl InformationSchemaVnodesTable {
+    pub fn new(coord: CoordinatorRef, metadata: MetaClientRef, user: User) -> Self {
+        Self {
+            coord,
+            metadata,
+            user,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationSchemaVnodesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        VNODES_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaVnodesBuilder::default();
+
+        let dbs = self
+            .metadata
+            .list_databases()
+            .map_err(|e| DataFusionError::Internal(format!("Failed to list databases: {}", e)))?;
+        let tenant = self.metadata.tenant();
+        let tenant_id = tenant.id();
+        let tenant_name = tenant.name();
+
+        // Check if the current user has at least read permission on this db, skip if not
+        let mut databases = dbs
+            .into_iter()
+            .filter(|(db, info)| !info.is_hidden() && self.user.can_read_database(*tenant_id, db))
+            .map(|(db, _)| db)
+            .collect::<Vec<_>>();
+        databases.sort();
+
+        let placements = self
+            .coord
+            .vnodes_placement(tenant_name, &databases)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        for placement in placements {
+            let role = if placement.is_leader {
+                "leader"
+            } else if placement.vnode.learner {
+                "learner"
+            } else {
+                "follower"
+            };
+            builder.append_row(
+                tenant_name,
+                &placement.database,
+                placement.replica_id,
+                placement.vnode.id,
+                placement.vnode.node_id,
+                format!("{:?}", placement.vnode.status),
+                role,
+                placement.disk_storage,
+            );
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
@@ -12,27 +12,42 @@ pub use builder::databases::{
     DATABASES_STRICT_WRITE, DATABASES_TENANT_NAME, DATABASES_TTL, DATABASES_VNODE_DURATION,
     DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC,
 };
+pub use builder::functions::{
+    FUNCTIONS_ARGUMENT_TYPES, FUNCTIONS_DESCRIPTION, FUNCTIONS_FUNCTION_NAME,
+    FUNCTIONS_FUNCTION_TYPE, FUNCTIONS_RETURN_TYPE,
+};
 pub use builder::tables::{
     TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS,
     TABLES_TABLE_TENANT, TABLES_TABLE_TYPE,
 };
+pub use builder::vnodes::{
+    VNODES_DATABASE_NAME, VNODES_DISK_STORAGE, VNODES_NODE_ID, VNODES_REPLICA_ID, VNODES_ROLE,
+    VNODES_STATUS, VNODES_VNODE_ID,
+};
+use coordinator::service::CoordinatorRef;
 use datafusion::datasource::TableProvider;
 pub use factory::columns::INFORMATION_SCHEMA_COLUMNS;
 pub use factory::databases::INFORMATION_SCHEMA_DATABASES;
+pub use factory::functions::INFORMATION_SCHEMA_FUNCTIONS;
 pub use factory::queries::INFORMATION_SCHEMA_QUERIES;
 pub use factory::tables::INFORMATION_SCHEMA_TABLES;
+pub use factory::vnodes::INFORMATION_SCHEMA_VNODES;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::auth::user::User;
+use spi::query::function::FuncMetaManagerRef;
 
 use self::factory::columns::ColumnsFactory;
 use self::factory::database_privileges::DatabasePrivilegesFactory;
 use self::factory::databases::DatabasesFactory;
 use self::factory::enabled_roles::EnabledRolesFactory;
+use self::factory::functions::FunctionsFactory;
 use self::factory::members::MembersFactory;
 use self::factory::queries::QueriesFactory;
+use self::factory::recovery::RecoveryFactory;
 use self::factory::resource_status::InformationSchemaResourceStatusFactory;
 use self::factory::roles::RolesFactory;
+use self::factory::vnodes::VnodesFactory;
 use super::INFORMATION_SCHEMA;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::metadata::information_schema_provider::factory::tables::TablesFactory;
@@ -47,7 +62,11 @@ pub struct InformationSchemaProvider {
 }
 
 impl InformationSchemaProvider {
-    pub fn new(query_tracker: Arc<QueryTracker>) -> Self {
+    pub fn new(
+        query_tracker: Arc<QueryTracker>,
+        func_manager: FuncMetaManagerRef,
+        coord: CoordinatorRef,
+    ) -> Self {
         let mut provider = Self {
             query_tracker,
             table_factories: Default::default(),
@@ -62,6 +81,11 @@ impl InformationSchemaProvider {
         provider.register_table_factory(Box::new(MembersFactory {}));
         provider.register_table_factory(Box::new(QueriesFactory {}));
         provider.register_table_factory(Box::new(InformationSchemaResourceStatusFactory {}));
+        provider.register_table_factory(Box::new(FunctionsFactory { func_manager }));
+        provider.register_table_factory(Box::new(VnodesFactory {
+            coord: coord.clone(),
+        }));
+        provider.register_table_factory(Box::new(RecoveryFactory { coord }));
 
         provider
     }
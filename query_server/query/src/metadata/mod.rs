@@ -2,7 +2,8 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use cluster_schema_provider::{CLUSTER_SCHEMA_TENANTS, CLUSTER_SCHEMA_USERS};
+pub use cluster_schema_provider::CLUSTER_SCHEMA_RUNTIME;
+use cluster_schema_provider::{CLUSTER_SCHEMA_NODES, CLUSTER_SCHEMA_TENANTS, CLUSTER_SCHEMA_USERS};
 use coordinator::service::CoordinatorRef;
 use datafusion::arrow::datatypes::DataType;
 use datafusion::common::Result as DFResult;
@@ -20,9 +21,13 @@ pub use information_schema_provider::{
     DATABASES_MAX_MEMCACHE_SIZE, DATABASES_MEMCACHE_PARTITIONS, DATABASES_PRECISION,
     DATABASES_REPLICA, DATABASES_SHARD, DATABASES_STRICT_WRITE, DATABASES_TENANT_NAME,
     DATABASES_TTL, DATABASES_VNODE_DURATION, DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC,
-    INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_QUERIES,
-    INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME,
-    TABLES_TABLE_OPTIONS, TABLES_TABLE_TENANT, TABLES_TABLE_TYPE,
+    FUNCTIONS_ARGUMENT_TYPES, FUNCTIONS_DESCRIPTION, FUNCTIONS_FUNCTION_NAME,
+    FUNCTIONS_FUNCTION_TYPE, FUNCTIONS_RETURN_TYPE, INFORMATION_SCHEMA_COLUMNS,
+    INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_FUNCTIONS, INFORMATION_SCHEMA_QUERIES,
+    INFORMATION_SCHEMA_TABLES, INFORMATION_SCHEMA_VNODES, TABLES_TABLE_DATABASE,
+    TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS, TABLES_TABLE_TENANT,
+    TABLES_TABLE_TYPE, VNODES_DATABASE_NAME, VNODES_DISK_STORAGE, VNODES_NODE_ID,
+    VNODES_REPLICA_ID, VNODES_ROLE, VNODES_STATUS, VNODES_VNODE_ID,
 };
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
@@ -110,13 +115,17 @@ impl MetadataProvider {
     ) -> Self {
         Self {
             current_session_table_provider,
-            coord,
+            coord: coord.clone(),
             // TODO refactor
             config_options: session.inner().config_options().clone(),
             session,
             meta_client,
+            information_schema_provider: InformationSchemaProvider::new(
+                query_tracker,
+                func_manager.clone(),
+                coord,
+            ),
             func_manager,
-            information_schema_provider: InformationSchemaProvider::new(query_tracker),
             cluster_schema_provider: ClusterSchemaProvider::new(),
             usage_schema_provider: UsageSchemaProvider::new(default_table_provider),
             access_databases: Default::default(),
@@ -160,7 +169,9 @@ impl MetadataProvider {
         if tenant_name.eq_ignore_ascii_case(DEFAULT_CATALOG)
             && database_name.eq_ignore_ascii_case(self.cluster_schema_provider.name())
             && (table_name.eq_ignore_ascii_case(CLUSTER_SCHEMA_TENANTS)
-                || table_name.eq_ignore_ascii_case(CLUSTER_SCHEMA_USERS))
+                || table_name.eq_ignore_ascii_case(CLUSTER_SCHEMA_USERS)
+                || table_name.eq_ignore_ascii_case(CLUSTER_SCHEMA_NODES)
+                || table_name.eq_ignore_ascii_case(CLUSTER_SCHEMA_RUNTIME))
         {
             let mem_table = self
                 .cluster_schema_provider
@@ -386,6 +397,10 @@ impl TableSet {
     pub fn push_table(&mut self, tbl: impl Into<String>) {
         self.tables.insert(tbl.into());
     }
+
+    pub fn tables(&self) -> Vec<&String> {
+        self.tables.iter().collect()
+    }
 }
 
 // "cnosdb" tenant additional check "public" and "CLUSTER_SCHEMA"
@@ -47,6 +47,8 @@ impl UsageSchemaProvider {
         register_table_factory!("sql_points_data_in", SQLPointsDataIn);
         register_table_factory!("vnode_cache_size", VnodeCacheSize);
         register_table_factory!("vnode_disk_storage", VnodeDiskStorage);
+        register_table_factory!("vnode_open_files", VnodeOpenFiles);
+        register_table_factory!("freshness_probe_latency", FreshnessProbeLatency);
         provider
     }
 
@@ -8,9 +8,11 @@ use spi::QueryResult;
 
 use crate::extension::analyse::add_time_for_tsgenfunc::AddTimeForTSGenFunc;
 use crate::extension::analyse::initial_plan_checker::InitialPlanChecker;
+use crate::extension::analyse::transform_asof_join::TransformAsofJoinRule;
 use crate::extension::analyse::transform_bottom_func_to_topk_node::TransformBottomFuncToTopkNodeRule;
 use crate::extension::analyse::transform_count_gen_time_col::TransformCountGenTimeColRule;
 use crate::extension::analyse::transform_exact_count_to_count::TransformExactCountToCountRule;
+use crate::extension::analyse::transform_gap_fill::TransformGapFillRule;
 use crate::extension::analyse::transform_time_window::TransformTimeWindowRule;
 use crate::extension::analyse::transform_topk_func_to_topk_node::TransformTopkFuncToTopkNodeRule;
 use crate::extension::analyse::transform_ts_gen_func::TransformTSGenFunc;
@@ -29,6 +31,8 @@ impl DefaultAnalyzer {
         rules.push(Arc::new(InitialPlanChecker {}));
         rules.push(Arc::new(TransformBottomFuncToTopkNodeRule {}));
         rules.push(Arc::new(TransformTopkFuncToTopkNodeRule {}));
+        rules.push(Arc::new(TransformGapFillRule));
+        rules.push(Arc::new(TransformAsofJoinRule));
         rules.push(Arc::new(TransformTimeWindowRule {}));
         rules.push(Arc::new(TransformTSGenFunc));
         rules.push(Arc::new(AddTimeForTSGenFunc {}));
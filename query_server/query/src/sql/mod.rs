@@ -5,3 +5,4 @@ pub mod optimizer;
 pub mod parser;
 pub mod physical;
 pub mod planner;
+pub mod redact;
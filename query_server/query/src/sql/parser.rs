@@ -6,26 +6,31 @@ use std::str::FromStr;
 use datafusion::common::parsers::CompressionTypeVariant;
 use datafusion::sql::parser::CreateExternalTable;
 use datafusion::sql::sqlparser::ast::{
-    DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, SqlOption, TableFactor, Value,
+    DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, SqlOption, Statement, TableFactor,
+    Value,
 };
 use datafusion::sql::sqlparser::dialect::keywords::Keyword;
 use datafusion::sql::sqlparser::dialect::Dialect;
 use datafusion::sql::sqlparser::parser::{IsOptional, Parser, ParserError};
 use datafusion::sql::sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
 use models::codec::Encoding;
+use models::encryption::EncryptionKey;
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use serde_json::Value as JsonValue;
 use snafu::ResultExt;
 use spi::query::ast::{
     self, parse_string_value, Action, AlterDatabase, AlterTable, AlterTableAction, AlterTenant,
-    AlterTenantOperation, AlterUser, AlterUserOperation, ChecksumGroup, ColumnOption,
-    CompactDatabase, CompactVnode, CopyIntoLocation, CopyIntoTable, CopyTarget, CopyVnode,
-    CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant, CreateUser,
-    DatabaseConfig, DatabaseOptions, DescribeDatabase, DescribeTable, DropDatabaseObject,
-    DropGlobalObject, DropTenantObject, DropVnode, Explain, ExtStatement, GrantRevoke, MoveVnode,
-    OutputMode, Privilege, RecoverDatabase, RecoverTenant, ShowSeries, ShowTagBody, ShowTagValues,
-    Trigger, UriLocation, With,
+    AlterTenantOperation, AlterUser, AlterUserOperation, BackupDatabase, ChecksumGroup,
+    CloseVnodeFiles, ColumnOption, CompactDatabase, CompactVnode, CopyIntoLocation, CopyIntoTable,
+    CopyTarget, CopyVnode, CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant,
+    CreateUser, DatabaseConfig, DatabaseOptions, DescribeDatabase, DescribeTable,
+    DropDatabaseObject, DropGlobalObject, DropTenantObject, DropVnode, Explain, ExplainCompaction,
+    ExportVnode, ExtStatement, GrantRevoke, KillCompaction, MergeTagValue, MoveVnode, OutputMode,
+    PreviewStream, Privilege, PrivilegeObject, RecoverDatabase, RecoverTenant, RepairReplica,
+    RestoreDatabase, ShowCardinality, ShowDatabaseReplicas, ShowSeries, ShowShardSkew, ShowTagBody,
+    ShowTagValues, ShowVnodes, Trigger, UriLocation, With,
 };
+use spi::query::datasource::sink::{SinkFormat, StreamSinkOptions};
 use spi::query::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
 use spi::query::parser::Parser as CnosdbParser;
 use spi::ParserSnafu;
@@ -78,6 +83,8 @@ enum CnosKeyWord {
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     FILES,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACTION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     PATTERN,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     FILE_FORMAT,
@@ -95,6 +102,12 @@ enum CnosKeyWord {
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     CHECKSUM,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EXPORT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    BACKUP,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    RESTORE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     STREAM,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     STREAMS,
@@ -126,6 +139,20 @@ enum CnosKeyWord {
     DESTORY,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     REPLICAS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    VNODES,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CARDINALITY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COST,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ENCRYPTION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    DECRYPT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ENCRYPTED,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    PREVIEW,
 
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     MAX_MEMCACHE_SIZE,
@@ -139,6 +166,48 @@ enum CnosKeyWord {
     STRICT_WRITE,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     MAX_CACHE_READERS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    KAFKA,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CONTINUOUS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    QUERY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EVERY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    REPAIR,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    QUOTAS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    SERIES_PLACEMENT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    SKEW,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    RUNTIME,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACTION_WINDOW,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACTION_FILE_SIZES,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    TIME_ZONE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACTIONS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    AUDIT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    OFF,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    SCHEDULED,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CRON,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    OLDER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    THAN,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    JOBS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    LEARNER,
 }
 
 impl FromStr for CnosKeyWord {
@@ -163,6 +232,7 @@ impl FromStr for CnosKeyWord {
             "REMOVE" => Ok(CnosKeyWord::REMOVE),
             "SERIES" => Ok(CnosKeyWord::SERIES),
             "FILES" => Ok(CnosKeyWord::FILES),
+            "COMPACTION" => Ok(CnosKeyWord::COMPACTION),
             "PATTERN" => Ok(CnosKeyWord::PATTERN),
             "FILE_FORMAT" => Ok(CnosKeyWord::FILE_FORMAT),
             "COPY_OPTIONS" => Ok(CnosKeyWord::COPY_OPTIONS),
@@ -171,6 +241,9 @@ impl FromStr for CnosKeyWord {
             "MOVE" => Ok(CnosKeyWord::MOVE),
             "COMPACT" => Ok(CnosKeyWord::COMPACT),
             "CHECKSUM" => Ok(CnosKeyWord::CHECKSUM),
+            "EXPORT" => Ok(CnosKeyWord::EXPORT),
+            "BACKUP" => Ok(CnosKeyWord::BACKUP),
+            "RESTORE" => Ok(CnosKeyWord::RESTORE),
             "STREAM" => Ok(CnosKeyWord::STREAM),
             "STREAMS" => Ok(CnosKeyWord::STREAMS),
             "TRIGGER" => Ok(CnosKeyWord::TRIGGER),
@@ -187,12 +260,39 @@ impl FromStr for CnosKeyWord {
             "PROMOTE" => Ok(CnosKeyWord::PROMOTE),
             "DESTORY" => Ok(CnosKeyWord::DESTORY),
             "REPLICAS" => Ok(CnosKeyWord::REPLICAS),
+            "VNODES" => Ok(CnosKeyWord::VNODES),
+            "COST" => Ok(CnosKeyWord::COST),
+            "ENCRYPTION" => Ok(CnosKeyWord::ENCRYPTION),
+            "DECRYPT" => Ok(CnosKeyWord::DECRYPT),
+            "ENCRYPTED" => Ok(CnosKeyWord::ENCRYPTED),
+            "PREVIEW" => Ok(CnosKeyWord::PREVIEW),
             "MAX_MEMCACHE_SIZE" => Ok(CnosKeyWord::MAX_MEMCACHE_SIZE),
             "MEMCACHE_PARTITIONS" => Ok(CnosKeyWord::MEMCACHE_PARTITIONS),
             "WAL_MAX_FILE_SIZE" => Ok(CnosKeyWord::WAL_MAX_FILE_SIZE),
             "WAL_SYNC" => Ok(CnosKeyWord::WAL_SYNC),
             "STRICT_WRITE" => Ok(CnosKeyWord::STRICT_WRITE),
             "MAX_CACHE_READERS" => Ok(CnosKeyWord::MAX_CACHE_READERS),
+            "KAFKA" => Ok(CnosKeyWord::KAFKA),
+            "CONTINUOUS" => Ok(CnosKeyWord::CONTINUOUS),
+            "QUERY" => Ok(CnosKeyWord::QUERY),
+            "EVERY" => Ok(CnosKeyWord::EVERY),
+            "REPAIR" => Ok(CnosKeyWord::REPAIR),
+            "QUOTAS" => Ok(CnosKeyWord::QUOTAS),
+            "SERIES_PLACEMENT" => Ok(CnosKeyWord::SERIES_PLACEMENT),
+            "SKEW" => Ok(CnosKeyWord::SKEW),
+            "RUNTIME" => Ok(CnosKeyWord::RUNTIME),
+            "COMPACTION_WINDOW" => Ok(CnosKeyWord::COMPACTION_WINDOW),
+            "COMPACTION_FILE_SIZES" => Ok(CnosKeyWord::COMPACTION_FILE_SIZES),
+            "TIME_ZONE" => Ok(CnosKeyWord::TIME_ZONE),
+            "COMPACTIONS" => Ok(CnosKeyWord::COMPACTIONS),
+            "AUDIT" => Ok(CnosKeyWord::AUDIT),
+            "OFF" => Ok(CnosKeyWord::OFF),
+            "SCHEDULED" => Ok(CnosKeyWord::SCHEDULED),
+            "CRON" => Ok(CnosKeyWord::CRON),
+            "OLDER" => Ok(CnosKeyWord::OLDER),
+            "THAN" => Ok(CnosKeyWord::THAN),
+            "JOBS" => Ok(CnosKeyWord::JOBS),
+            "LEARNER" => Ok(CnosKeyWord::LEARNER),
             _ => Err(ParserError::ParserError(format!(
                 "fail parse {} to CnosKeyWord",
                 s
@@ -316,8 +416,27 @@ impl<'a> ExtParser<'a> {
                     self.parser.next_token();
                     self.parse_explain()
                 }
+                Keyword::CLOSE => {
+                    self.parser.next_token();
+                    self.parse_close()
+                }
+                Keyword::KILL
+                    if self
+                        .parser
+                        .peek_nth_token(1)
+                        .to_string()
+                        .parse::<CnosKeyWord>()
+                        == Ok(CnosKeyWord::COMPACTION) =>
+                {
+                    self.parser.next_token();
+                    self.parser.next_token();
+                    self.parse_kill_compaction()
+                }
                 Keyword::UPDATE => {
                     self.parser.next_token();
+                    if self.parser.parse_keyword(Keyword::TABLE) {
+                        return self.parse_merge_tag_value();
+                    }
                     let update_ast = self.parser.parse_update()?;
                     Ok(ExtStatement::SqlStatement(Box::new(update_ast)))
                 }
@@ -336,6 +455,18 @@ impl<'a> ExtParser<'a> {
                                 self.parser.next_token();
                                 self.parse_checksum()
                             }
+                            CnosKeyWord::EXPORT => {
+                                self.parser.next_token();
+                                self.parse_export()
+                            }
+                            CnosKeyWord::BACKUP => {
+                                self.parser.next_token();
+                                self.parse_backup()
+                            }
+                            CnosKeyWord::RESTORE => {
+                                self.parser.next_token();
+                                self.parse_restore()
+                            }
                             CnosKeyWord::RECOVER => {
                                 self.parser.next_token();
                                 self.parse_recover()
@@ -344,6 +475,18 @@ impl<'a> ExtParser<'a> {
                                 self.parser.next_token();
                                 self.parse_replica()
                             }
+                            CnosKeyWord::REPAIR => {
+                                self.parser.next_token();
+                                self.parse_repair()
+                            }
+                            CnosKeyWord::PREVIEW => {
+                                self.parser.next_token();
+                                self.parse_preview_stream()
+                            }
+                            CnosKeyWord::AUDIT => {
+                                self.parser.next_token();
+                                self.parse_audit()
+                            }
                             _ => Ok(ExtStatement::SqlStatement(Box::new(
                                 self.parser.parse_statement()?,
                             ))),
@@ -384,6 +527,8 @@ impl<'a> ExtParser<'a> {
             }
         } else if self.parse_cnos_keyword(CnosKeyWord::QUERIES) {
             self.parse_show_queries()
+        } else if self.parser.parse_keyword(Keyword::FUNCTIONS) {
+            self.parse_show_functions()
         } else if self.parse_cnos_keyword(CnosKeyWord::STREAMS) {
             let verbose = self
                 .parser
@@ -393,6 +538,24 @@ impl<'a> ExtParser<'a> {
             Ok(ExtStatement::ShowStreams(ast::ShowStreams { verbose }))
         } else if self.parse_cnos_keyword(CnosKeyWord::REPLICAS) {
             self.parse_show_replicas()
+        } else if self.parse_cnos_keyword(CnosKeyWord::VNODES) {
+            self.parse_show_vnodes()
+        } else if self.parse_cnos_keyword(CnosKeyWord::CARDINALITY) {
+            self.parse_show_cardinality()
+        } else if self.parse_cnos_keyword(CnosKeyWord::SHARD) {
+            self.parse_show_shard_skew()
+        } else if self.parse_cnos_keyword(CnosKeyWord::CONTINUOUS) {
+            self.expect_cnos_keyword(CnosKeyWord::QUERIES)?;
+            Ok(ExtStatement::ShowContinuousQueries)
+        } else if self.parse_cnos_keyword(CnosKeyWord::TENANT) {
+            self.expect_cnos_keyword(CnosKeyWord::QUOTAS)?;
+            Ok(ExtStatement::ShowTenantQuotas)
+        } else if self.parse_cnos_keyword(CnosKeyWord::JOBS) {
+            Ok(ExtStatement::ShowJobs)
+        } else if self.parse_cnos_keyword(CnosKeyWord::RUNTIME) {
+            Ok(ExtStatement::ShowRuntime)
+        } else if self.parse_cnos_keyword(CnosKeyWord::COMPACTIONS) {
+            Ok(ExtStatement::ShowCompactions)
         } else {
             parser_err!(format!("nonsupport: {}", self.parser.peek_token()))
         }
@@ -513,6 +676,28 @@ impl<'a> ExtParser<'a> {
     }
 
     fn parse_explain(&mut self) -> Result<ExtStatement> {
+        // parse: COMPACTION ON VNODE <vnode_id>
+        if self.parse_cnos_keyword(CnosKeyWord::COMPACTION) {
+            self.parser.expect_keyword(Keyword::ON)?;
+            self.expect_cnos_keyword(CnosKeyWord::VNODE)?;
+            let vnode_id = self.parse_number::<VnodeId>()?;
+            return Ok(ExtStatement::ExplainCompaction(ExplainCompaction {
+                vnode_id,
+            }));
+        }
+        // parse: (COST)
+        let cost = self.parser.peek_token().token == Token::LParen
+            && self
+                .parser
+                .peek_nth_token(1)
+                .to_string()
+                .parse::<CnosKeyWord>()
+                == Ok(CnosKeyWord::COST);
+        if cost {
+            self.parser.next_token();
+            self.parser.next_token();
+            self.parser.expect_token(&Token::RParen)?;
+        }
         let analyze = self.parser.parse_keyword(Keyword::ANALYZE);
         let verbose = self.parser.parse_keyword(Keyword::VERBOSE);
         let mut format = None;
@@ -524,21 +709,55 @@ impl<'a> ExtParser<'a> {
                 "EXPLAIN can only appear once".to_string(),
             ));
         }
+        if cost && (analyze || format.is_some()) {
+            return Err(ParserError::ParserError(
+                "EXPLAIN (COST) can not be used with ANALYZE or FORMAT".to_string(),
+            ));
+        }
 
         let stmt = self.parse_statement()?;
 
         Ok(ExtStatement::Explain(Explain {
             analyze,
             verbose,
+            cost,
             format,
             ext_statement: Box::new(stmt),
         }))
     }
 
+    fn parse_preview_stream(&mut self) -> Result<ExtStatement> {
+        // parse: STREAM <statement>
+        if !self.parse_cnos_keyword(CnosKeyWord::STREAM) {
+            return self.expected("STREAM", self.parser.peek_token());
+        }
+        let stmt = self.parse_statement()?;
+        if matches!(
+            stmt,
+            ExtStatement::Explain(_) | ExtStatement::PreviewStream(_)
+        ) {
+            return parser_err!("PREVIEW STREAM can not be used with EXPLAIN or PREVIEW");
+        }
+
+        Ok(ExtStatement::PreviewStream(PreviewStream {
+            ext_statement: Box::new(stmt),
+        }))
+    }
+
     fn parse_show_queries(&mut self) -> Result<ExtStatement> {
         Ok(ExtStatement::ShowQueries)
     }
 
+    /// Parses `SHOW FUNCTIONS [LIKE 'pattern']`
+    fn parse_show_functions(&mut self) -> Result<ExtStatement> {
+        let pattern = if self.parser.parse_keyword(Keyword::LIKE) {
+            Some(self.parser.parse_literal_string()?)
+        } else {
+            None
+        };
+        Ok(ExtStatement::ShowFunctions(pattern))
+    }
+
     fn parse_show_databases(&mut self) -> Result<ExtStatement> {
         Ok(ExtStatement::ShowDatabases())
     }
@@ -547,10 +766,51 @@ impl<'a> ExtParser<'a> {
         Ok(ExtStatement::ShowTables(self.parse_on_database()?))
     }
 
+    /// Parse `SHOW REPLICAS [FOR DATABASE <database>]`
     fn parse_show_replicas(&mut self) -> Result<ExtStatement> {
+        if self.parser.parse_keyword(Keyword::FOR) {
+            self.parser.expect_keyword(Keyword::DATABASE)?;
+            let database_name = self.parser.parse_identifier()?;
+            return Ok(ExtStatement::ShowDatabaseReplicas(ShowDatabaseReplicas {
+                database_name,
+            }));
+        }
         Ok(ExtStatement::ShowReplicas)
     }
 
+    /// Parse `SHOW VNODES [ON NODE <node_id>]`
+    fn parse_show_vnodes(&mut self) -> Result<ExtStatement> {
+        let node_id = if self.parser.parse_keyword(Keyword::ON) {
+            if self.parse_cnos_keyword(CnosKeyWord::NODE).not() {
+                return self.expected("NODE", self.parser.peek_token());
+            }
+            Some(self.parser.parse_literal_uint()?)
+        } else {
+            None
+        };
+        Ok(ExtStatement::ShowVnodes(ShowVnodes { node_id }))
+    }
+
+    /// Parse `SHOW CARDINALITY TOP <n> FOR <database>`
+    fn parse_show_cardinality(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::TOP)?;
+        let top_k = self.parser.parse_literal_uint()?;
+        self.parser.expect_keyword(Keyword::FOR)?;
+        let database_name = self.parser.parse_identifier()?;
+        Ok(ExtStatement::ShowCardinality(ShowCardinality {
+            top_k,
+            database_name,
+        }))
+    }
+
+    /// Parse `SHOW SHARD SKEW FOR <database>`
+    fn parse_show_shard_skew(&mut self) -> Result<ExtStatement> {
+        self.expect_cnos_keyword(CnosKeyWord::SKEW)?;
+        self.parser.expect_keyword(Keyword::FOR)?;
+        let database_name = self.parser.parse_identifier()?;
+        Ok(ExtStatement::ShowShardSkew(ShowShardSkew { database_name }))
+    }
+
     /// Parse a SQL DESCRIBE DATABASE statement
     fn parse_describe_database(&mut self) -> Result<ExtStatement> {
         debug!("Parse Describe DATABASE statement");
@@ -590,9 +850,34 @@ impl<'a> ExtParser<'a> {
             self.parse_alter_tenant()
         } else if self.parser.parse_keyword(Keyword::USER) {
             self.parse_alter_user()
+        } else if self.parse_cnos_keyword(CnosKeyWord::REPLICA) {
+            self.parse_alter_replica()
         } else {
-            self.expected("TABLE/DATABASE/TENANT/USER", self.parser.peek_token())
+            self.expected(
+                "TABLE/DATABASE/TENANT/USER/REPLICA",
+                self.parser.peek_token(),
+            )
+        }
+    }
+
+    /// ALTER REPLICA <replica_id> ADD LEARNER ON NODE <node_id>
+    fn parse_alter_replica(&mut self) -> Result<ExtStatement> {
+        let replica_id = self.parse_number::<ReplicationSetId>()?;
+        self.parser.expect_keyword(Keyword::ADD)?;
+        if !self.parse_cnos_keyword(CnosKeyWord::LEARNER) {
+            return self.expected("LEARNER", self.parser.peek_token());
+        }
+        self.parser.expect_keyword(Keyword::ON)?;
+        if !self.parse_cnos_keyword(CnosKeyWord::NODE) {
+            return self.expected("NODE", self.parser.peek_token());
         }
+        let node_id = self.parse_number::<NodeId>()?;
+
+        Ok(ExtStatement::ReplicaAdd(ast::ReplicaAdd {
+            replica_id,
+            node_id,
+            learner: true,
+        }))
     }
 
     fn parse_alter_table(&mut self) -> Result<ExtStatement> {
@@ -607,13 +892,44 @@ impl<'a> ExtParser<'a> {
         } else if self.parser.parse_keyword(Keyword::RENAME) {
             let alter_tbl = self.parse_alter_table_rename(table_name)?;
             Ok(ExtStatement::AlterTable(alter_tbl))
+        } else if self.parser.parse_keyword(Keyword::SET) {
+            self.expect_cnos_keyword(CnosKeyWord::TTL)?;
+            let ttl = self.parse_string_value()?;
+            Ok(ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::SetTtl { ttl },
+            }))
         } else {
-            self.expected("ADD or ALTER or DROP or RENAME", self.parser.peek_token())
+            self.expected(
+                "ADD or ALTER or DROP or RENAME or SET",
+                self.parser.peek_token(),
+            )
         }
     }
 
+    /// Parse `UPDATE TABLE <table_name> MERGE TAG <tag_name> '<from>' INTO '<to>'`,
+    /// the `UPDATE TABLE` keywords are parsed.
+    fn parse_merge_tag_value(&mut self) -> Result<ExtStatement> {
+        let table_name = self.parser.parse_object_name()?;
+        self.parser.expect_keyword(Keyword::MERGE)?;
+        self.expect_cnos_keyword(CnosKeyWord::TAG)?;
+        let tag_name = self.parser.parse_identifier()?;
+        let from = self.parse_string_value()?;
+        self.parser.expect_keyword(Keyword::INTO)?;
+        let to = self.parse_string_value()?;
+
+        Ok(ExtStatement::MergeTagValue(MergeTagValue {
+            table_name,
+            tag_name,
+            from,
+            to,
+        }))
+    }
+
     fn parse_alter_table_add_column(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
-        if self.parse_cnos_keyword(CnosKeyWord::FIELD) {
+        if self.parser.parse_keywords(&[Keyword::INDEX, Keyword::ON]) {
+            self.parse_alter_table_column_index(table_name, true)
+        } else if self.parse_cnos_keyword(CnosKeyWord::FIELD) {
             let column = self.parse_cnos_field()?;
             Ok(ExtStatement::AlterTable(AlterTable {
                 table_name,
@@ -627,11 +943,32 @@ impl<'a> ExtParser<'a> {
                 alter_action: AlterTableAction::AddColumn { column },
             }))
         } else {
-            self.expected("FIELD or TAG", self.parser.peek_token())
+            self.expected("FIELD or TAG or INDEX", self.parser.peek_token())
         }
     }
 
+    /// Parse `(<column_name>)` of `ADD INDEX ON` or `DROP INDEX ON`.
+    fn parse_alter_table_column_index(
+        &mut self,
+        table_name: ObjectName,
+        indexed: bool,
+    ) -> Result<ExtStatement> {
+        self.parser.expect_token(&Token::LParen)?;
+        let column_name = self.parser.parse_identifier()?;
+        self.parser.expect_token(&Token::RParen)?;
+        Ok(ExtStatement::AlterTable(AlterTable {
+            table_name,
+            alter_action: AlterTableAction::AlterColumnIndex {
+                column_name,
+                indexed,
+            },
+        }))
+    }
+
     fn parse_alter_table_drop_column(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
+        if self.parser.parse_keywords(&[Keyword::INDEX, Keyword::ON]) {
+            return self.parse_alter_table_column_index(table_name, false);
+        }
         let column_name = self.parser.parse_identifier()?;
         Ok(ExtStatement::AlterTable(AlterTable {
             table_name,
@@ -657,10 +994,40 @@ impl<'a> ExtParser<'a> {
     }
 
     fn parse_alter_table_alter_column(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
+        // the COLUMN keyword is optional, unless it is the column name itself
+        if !matches!(
+            self.parser.peek_nth_token(1).token,
+            Token::Word(ref w) if w.keyword == Keyword::SET || w.keyword == Keyword::DROP
+        ) {
+            self.parser.parse_keyword(Keyword::COLUMN);
+        }
         let column_name = self.parser.parse_identifier()?;
-        // parse: SET CODEC(encoding_type)
+        // parse: DROP DEFAULT
+        if self.parser.parse_keyword(Keyword::DROP) {
+            self.parser.expect_keyword(Keyword::DEFAULT)?;
+            return Ok(ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::AlterColumnDefault {
+                    column_name,
+                    default: None,
+                },
+            }));
+        }
+
         self.parser.expect_keyword(Keyword::SET)?;
+        // parse: SET DEFAULT value
+        if self.parser.parse_keyword(Keyword::DEFAULT) {
+            let default = self.parse_default_value()?;
+            return Ok(ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::AlterColumnDefault {
+                    column_name,
+                    default: Some(default),
+                },
+            }));
+        }
 
+        // parse: SET CODEC(encoding_type)
         self.expect_cnos_keyword(CnosKeyWord::CODEC)?;
         let encoding = self.parse_codec_type()?;
         Ok(ExtStatement::AlterTable(AlterTable {
@@ -672,6 +1039,17 @@ impl<'a> ExtParser<'a> {
         }))
     }
 
+    /// Parse a literal value, allowing a leading minus sign for numbers.
+    fn parse_default_value(&mut self) -> Result<Value> {
+        if self.parser.consume_token(&Token::Minus) {
+            return match self.parser.parse_number_value()? {
+                Value::Number(n, l) => Ok(Value::Number(format!("-{}", n), l)),
+                v => parser_err!(format!("Expected number, found: {}", v)),
+            };
+        }
+        self.parser.parse_value()
+    }
+
     fn parse_alter_database(&mut self) -> Result<ExtStatement> {
         let database_name = self.parser.parse_identifier()?;
         self.parser.expect_keyword(Keyword::SET)?;
@@ -684,7 +1062,7 @@ impl<'a> ExtParser<'a> {
             ));
         }
         if config.has_some() {
-            return parser_err!("database config is unmodifiable, only can modify database option: TTL, SHARD, VNODE_DURATION, REPLICA".to_string());
+            return parser_err!("database config is unmodifiable, only can modify database option: TTL, SHARD, VNODE_DURATION, REPLICA, SERIES_PLACEMENT, COMPACTION, COMPACTION_WINDOW, COMPACTION_FILE_SIZES, TIME_ZONE".to_string());
         }
         Ok(ExtStatement::AlterDatabase(
             AlterDatabase {
@@ -714,13 +1092,25 @@ impl<'a> ExtParser<'a> {
                 self.parser.expect_keyword(Keyword::AS)?;
                 let role_name = self.parser.parse_identifier()?;
                 AlterTenantOperation::SetUser(user_name, role_name)
+            } else if self.parse_cnos_keyword(CnosKeyWord::ENCRYPTION) {
+                self.parser.expect_keyword(Keyword::KEY)?;
+                let key_name = self.parser.parse_identifier()?;
+                self.parser.expect_token(&Token::Eq)?;
+                let key = self.parser.parse_literal_string()?;
+                AlterTenantOperation::SetEncryptionKey(key_name, EncryptionKey::new(key))
             } else {
                 let sql_option = self.alter_parse_limiter()?;
                 AlterTenantOperation::Set(sql_option)
             }
         } else if self.parse_cnos_keyword(CnosKeyWord::UNSET) {
-            let ident = self.parser.parse_identifier()?;
-            AlterTenantOperation::UnSet(ident)
+            if self.parse_cnos_keyword(CnosKeyWord::ENCRYPTION) {
+                self.parser.expect_keyword(Keyword::KEY)?;
+                let key_name = self.parser.parse_identifier()?;
+                AlterTenantOperation::UnSetEncryptionKey(key_name)
+            } else {
+                let ident = self.parser.parse_identifier()?;
+                AlterTenantOperation::UnSet(ident)
+            }
         } else {
             self.expected("ADD, REMOVE, SET, UNSET", self.parser.peek_token())?
         };
@@ -735,6 +1125,8 @@ impl<'a> ExtParser<'a> {
         let mut has_limiter_option = false;
         let mut has_comment_option = false;
         let mut has_drop_after_option = false;
+        let mut timeout = None;
+        let mut quota = None;
 
         while self.parser.peek_token().token != Token::EOF {
             let name = self.parser.parse_identifier()?;
@@ -756,6 +1148,31 @@ impl<'a> ExtParser<'a> {
                     drop_after = Some(self.parser.parse_literal_string()?);
                     has_drop_after_option = true;
                 }
+                "query_timeout" | "write_timeout" => {
+                    if timeout.is_some() {
+                        return Err(ParserError::ParserError(
+                            "Cannot set multiple options (query_timeout, write_timeout) at the same time"
+                                .to_string(),
+                        ));
+                    }
+                    timeout = Some((
+                        name.value.to_lowercase(),
+                        self.parser.parse_literal_string()?,
+                    ));
+                }
+                "max_databases"
+                | "max_tables"
+                | "max_users"
+                | "max_tables_per_database"
+                | "max_series_per_table" => {
+                    if quota.is_some() {
+                        return Err(ParserError::ParserError(
+                            "Cannot set multiple options (max_databases, max_tables, max_users, max_tables_per_database, max_series_per_table) at the same time"
+                                .to_string(),
+                        ));
+                    }
+                    quota = Some((name.value.to_lowercase(), self.parser.parse_literal_uint()?));
+                }
                 "object_config" => {
                     limiter_options.insert(name.value.to_lowercase(), self.parse_object_config()?);
                     has_limiter_option = true;
@@ -782,6 +1199,8 @@ impl<'a> ExtParser<'a> {
             has_comment_option,
             has_drop_after_option,
             has_limiter_option,
+            timeout.is_some(),
+            quota.is_some(),
         ]
         .iter()
         .filter(|&&x| x)
@@ -789,7 +1208,7 @@ impl<'a> ExtParser<'a> {
 
         if has_options_count > 1 {
             return Err(ParserError::ParserError(
-                "Cannot set multiple options (comment, drop_after, _limiter) at the same time"
+                "Cannot set multiple options (comment, drop_after, _limiter, query_timeout, write_timeout, max_databases, max_tables, max_users, max_tables_per_database, max_series_per_table) at the same time"
                     .to_string(),
             ));
         }
@@ -843,6 +1262,18 @@ impl<'a> ExtParser<'a> {
                 value: Value::SingleQuotedString(drop_after.unwrap().to_string()),
             });
         }
+        if let Some((name, timeout)) = timeout {
+            return Ok(SqlOption {
+                name: Ident::new(name),
+                value: Value::SingleQuotedString(timeout),
+            });
+        }
+        if let Some((name, max)) = quota {
+            return Ok(SqlOption {
+                name: Ident::new(name),
+                value: Value::Number(max.to_string(), false),
+            });
+        }
         if has_limiter_option {
             return Ok(SqlOption {
                 name: Ident::new("_limiter"),
@@ -1047,14 +1478,47 @@ impl<'a> ExtParser<'a> {
         let table_name = self.parser.parse_object_name()?;
         check_name_not_contain_illegal_character(&table_name)?;
         let columns = self.parse_cnos_columns()?;
+        let append_only = self.parse_create_table_options()?;
         let create = CreateTable {
             name: table_name,
             if_not_exists,
             columns,
+            append_only,
         };
         Ok(ExtStatement::CreateTable(create))
     }
 
+    /// [ WITH ( append_only = true | false ) ]
+    fn parse_create_table_options(&mut self) -> Result<bool> {
+        let mut append_only = false;
+        if !self.parser.parse_keyword(Keyword::WITH) {
+            return Ok(append_only);
+        }
+
+        self.parser.expect_token(&Token::LParen)?;
+        let options = self
+            .parser
+            .parse_comma_separated(ExtParser::parse_sql_option)?;
+        self.parser.expect_token(&Token::RParen)?;
+
+        for SqlOption { name, value } in options {
+            match (name.value.to_lowercase().as_str(), value) {
+                ("append_only", Value::Boolean(b)) => append_only = b,
+                ("append_only", value) => {
+                    return parser_err!(format!(
+                        "Expected a boolean value of append_only, found: {}",
+                        value
+                    ));
+                }
+                (name, _) => {
+                    return parser_err!(format!("Unsupported table option: {}", name));
+                }
+            }
+        }
+
+        Ok(append_only)
+    }
+
     fn parse_database_options_and_config(&mut self) -> Result<(DatabaseOptions, DatabaseConfig)> {
         if self.parser.parse_keyword(Keyword::WITH) {
             let mut options = DatabaseOptions::default();
@@ -1093,6 +1557,21 @@ impl<'a> ExtParser<'a> {
                 return parser_err!("replica number should be greater than 0");
             }
             options.replica = Some(replica);
+        } else if self.parse_cnos_keyword(CnosKeyWord::SERIES_PLACEMENT) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.series_placement = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::COMPACTION) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.compaction = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::COMPACTION_WINDOW) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.compaction_window = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::COMPACTION_FILE_SIZES) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.compaction_file_sizes = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::TIME_ZONE) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.time_zone = Some(self.parse_string_value()?);
         } else if self.parse_cnos_keyword(CnosKeyWord::PRECISION) {
             let _ = self.parser.expect_token(&Token::Eq);
             config.precision = Some(self.parse_string_value()?);
@@ -1214,9 +1693,11 @@ impl<'a> ExtParser<'a> {
             Ok(Action::Write)
         } else if self.parser.parse_keyword(Keyword::ALL) {
             Ok(Action::All)
+        } else if self.parse_cnos_keyword(CnosKeyWord::DECRYPT) {
+            Ok(Action::Decrypt)
         } else {
             self.expected(
-                "a privilege keyword [Read, Write, All]",
+                "a privilege keyword [Read, Write, All, Decrypt]",
                 self.parser.peek_token(),
             )?
         }
@@ -1224,16 +1705,41 @@ impl<'a> ExtParser<'a> {
 
     fn parse_privilege(&mut self) -> Result<Privilege, ParserError> {
         let action = self.parse_grant_permission()?;
+        let columns = self
+            .parser
+            .parse_parenthesized_column_list(IsOptional::Optional, false)?;
         self.parser.expect_keyword(Keyword::ON)?;
-        self.parser.expect_keyword(Keyword::DATABASE)?;
-        let database = self.parser.parse_identifier()?;
-        Ok(Privilege { action, database })
+        let object = if self.parser.parse_keyword(Keyword::TABLE) {
+            let name = self.parser.parse_object_name()?;
+            PrivilegeObject::Table { name, columns }
+        } else {
+            self.parser.expect_keyword(Keyword::DATABASE)?;
+            if !columns.is_empty() {
+                return parser_err!("Column privileges are only supported on table");
+            }
+            PrivilegeObject::Database(self.parser.parse_identifier()?)
+        };
+        Ok(Privilege { action, object })
+    }
+
+    fn parse_audit(&mut self) -> Result<ExtStatement> {
+        // audit on;
+        // audit off;
+        if self.parser.parse_keyword(Keyword::ON) {
+            Ok(ExtStatement::SetAudit(true))
+        } else if self.parse_cnos_keyword(CnosKeyWord::OFF) {
+            Ok(ExtStatement::SetAudit(false))
+        } else {
+            self.expected("ON or OFF", self.parser.peek_token())
+        }
     }
 
     fn parse_grant(&mut self) -> Result<ExtStatement> {
         // grant read on database "db1" to [role] rrr;
         // grant write on database "db2" to rrr;
         // grant all on database "db3" to rrr;
+        // grant read on table "db1"."t1" to rrr;
+        // grant read ("time", "f1") on table "t1" to rrr;
         let privileges = self.parse_comma_separated(ExtParser::parse_privilege)?;
 
         self.parser.expect_keyword(Keyword::TO)?;
@@ -1252,6 +1758,7 @@ impl<'a> ExtParser<'a> {
         // revoke read on database "db1" from [role] rrr;
         // revoke write on database "db2" from rrr;
         // revoke all on database "db3" from rrr;
+        // revoke read on table "db1"."t1" from rrr;
         let privileges = self.parse_comma_separated(ExtParser::parse_privilege)?;
 
         self.parser.expect_keyword(Keyword::FROM)?;
@@ -1338,6 +1845,9 @@ impl<'a> ExtParser<'a> {
         let mut limiter_options = serde_json::Map::new(); // 用于存储 _limiter 内部配置
         let mut comment = None;
         let mut drop_after = None;
+        let mut query_timeout = None;
+        let mut write_timeout = None;
+        let mut quotas = vec![];
         let mut has_limiter_option = false; // 标志，用于检查是否有有效的选项
 
         while self.parser.peek_token().token != Token::EOF {
@@ -1358,6 +1868,19 @@ impl<'a> ExtParser<'a> {
                 "drop_after" => {
                     drop_after = Some(self.parser.parse_literal_string()?);
                 }
+                "query_timeout" => {
+                    query_timeout = Some(self.parser.parse_literal_string()?);
+                }
+                "write_timeout" => {
+                    write_timeout = Some(self.parser.parse_literal_string()?);
+                }
+                "max_databases"
+                | "max_tables"
+                | "max_users"
+                | "max_tables_per_database"
+                | "max_series_per_table" => {
+                    quotas.push((name.value.to_lowercase(), self.parser.parse_literal_uint()?));
+                }
                 "object_config" => {
                     limiter_options.insert(name.value.to_lowercase(), self.parse_object_config()?);
                     has_limiter_option = true; // 记录有有效的选项
@@ -1422,6 +1945,24 @@ impl<'a> ExtParser<'a> {
                 value: Value::SingleQuotedString(drop_after),
             });
         }
+        if let Some(query_timeout) = query_timeout {
+            with_options.push(SqlOption {
+                name: Ident::new("query_timeout"),
+                value: Value::SingleQuotedString(query_timeout),
+            });
+        }
+        if let Some(write_timeout) = write_timeout {
+            with_options.push(SqlOption {
+                name: Ident::new("write_timeout"),
+                value: Value::SingleQuotedString(write_timeout),
+            });
+        }
+        for (name, max) in quotas {
+            with_options.push(SqlOption {
+                name: Ident::new(name),
+                value: Value::Number(max.to_string(), false),
+            });
+        }
         // 将 comment 和 drop_after 添加到 with_options 中
         if let Some(comment) = comment {
             with_options.push(SqlOption {
@@ -1642,9 +2183,19 @@ impl<'a> ExtParser<'a> {
             }
         }
 
+        let sink = if self.parser.parse_keyword(Keyword::INTO) {
+            Some(self.parse_stream_sink()?)
+        } else {
+            None
+        };
+
         self.parser.expect_keyword(Keyword::AS)?;
-        self.parser.expect_keyword(Keyword::INSERT)?;
-        let statement = Box::new(self.parser.parse_insert()?);
+        let statement = if sink.is_some() {
+            Box::new(Statement::Query(Box::new(self.parser.parse_query()?)))
+        } else {
+            self.parser.expect_keyword(Keyword::INSERT)?;
+            Box::new(self.parser.parse_insert()?)
+        };
 
         Ok(ExtStatement::CreateStream(CreateStream {
             if_not_exists,
@@ -1652,10 +2203,37 @@ impl<'a> ExtParser<'a> {
             trigger,
             watermark,
             output_mode,
+            sink,
             statement,
         }))
     }
 
+    /// Parse the sink of a stream query following `INTO`
+    /// e.g.
+    /// KAFKA('localhost:9092', 'topic' [, 'json' | 'avro'])
+    fn parse_stream_sink(&mut self) -> Result<StreamSinkOptions> {
+        if !self.parse_cnos_keyword(CnosKeyWord::KAFKA) {
+            return self.expected("KAFKA", self.parser.peek_token());
+        }
+
+        self.parser.expect_token(&Token::LParen)?;
+        let brokers = self.parse_string_value()?;
+        self.parser.expect_token(&Token::Comma)?;
+        let topic = self.parse_string_value()?;
+        let format = if self.parser.consume_token(&Token::Comma) {
+            SinkFormat::from_str(&self.parse_string_value()?).map_err(ParserError::ParserError)?
+        } else {
+            SinkFormat::Json
+        };
+        self.parser.expect_token(&Token::RParen)?;
+
+        Ok(StreamSinkOptions::Kafka {
+            brokers,
+            topic,
+            format,
+        })
+    }
+
     /// Parse a SQL CREATE statement
     fn parse_create(&mut self) -> Result<ExtStatement> {
         // Currently only supports the creation of external tables
@@ -1676,35 +2254,125 @@ impl<'a> ExtParser<'a> {
             self.parse_create_role()
         } else if self.parse_cnos_keyword(CnosKeyWord::STREAM) {
             self.parse_create_stream()
+        } else if self.parse_cnos_keyword(CnosKeyWord::CONTINUOUS) {
+            self.expect_cnos_keyword(CnosKeyWord::QUERY)?;
+            self.parse_create_continuous_query()
+        } else if self.parse_cnos_keyword(CnosKeyWord::SCHEDULED) {
+            self.parser.expect_keyword(Keyword::DELETE)?;
+            self.parse_create_scheduled_delete()
         } else {
             self.expected("an object type after CREATE", self.parser.peek_token())
         }
     }
 
-    /// Parse a copy statement
-    fn parse_copy(&mut self) -> Result<ExtStatement> {
-        if self.parse_cnos_keyword(CnosKeyWord::VNODE) {
-            let vnode_id = self.parse_number::<VnodeId>()?;
-            self.parser.expect_keyword(Keyword::TO)?;
-            if self.parse_cnos_keyword(CnosKeyWord::NODE).not() {
-                return parser_err!("expected NODE, after TO");
-            }
-            let node_id = self.parse_number::<NodeId>()?;
-            Ok(ExtStatement::CopyVnode(CopyVnode { vnode_id, node_id }))
-        } else if self.parser.parse_keyword(Keyword::INTO) {
-            self.parse_copy_into()
-        } else {
-            parser_err!("expected VNODE, after COPY")
-        }
-    }
+    /// e.g.
+    /// CREATE SCHEDULED DELETE [IF NOT EXISTS] clean_debug CRON '0 3 * * *' [OLDER THAN '7d']
+    /// AS DELETE FROM cpu WHERE debug = 'true'
+    fn parse_create_scheduled_delete(&mut self) -> Result<ExtStatement> {
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parser.parse_identifier()?;
 
-    fn parse_copy_into_table(&mut self, table_name: ObjectName) -> Result<CopyTarget> {
-        // COPY INTO <table> [(columns,...)] FROM <location>
-        let columns = self
-            .parser
-            .parse_parenthesized_column_list(IsOptional::Optional, false)?;
+        self.expect_cnos_keyword(CnosKeyWord::CRON)?;
+        let cron = self.parse_string_value()?;
+        let older_than = if self.parse_cnos_keyword(CnosKeyWord::OLDER) {
+            self.expect_cnos_keyword(CnosKeyWord::THAN)?;
+            Some(self.parse_string_value()?)
+        } else {
+            None
+        };
 
-        self.parser.expect_keyword(Keyword::FROM)?;
+        self.parser.expect_keyword(Keyword::AS)?;
+        let (table_name, selection) = match self.parser.parse_statement()? {
+            Statement::Delete {
+                tables,
+                mut from,
+                using: None,
+                selection,
+                returning: None,
+            } if tables.is_empty() && from.len() == 1 && from[0].joins.is_empty() => {
+                match from.remove(0).relation {
+                    TableFactor::Table { name, .. } => (name, selection),
+                    _ => return parser_err!("expected a table after DELETE FROM"),
+                }
+            }
+            _ => return parser_err!("expected DELETE FROM <table> [WHERE <selection>] after AS"),
+        };
+
+        Ok(ExtStatement::CreateScheduledDelete(Box::new(
+            ast::CreateScheduledDelete {
+                if_not_exists,
+                name,
+                cron,
+                older_than,
+                table_name,
+                selection,
+            },
+        )))
+    }
+
+    /// e.g.
+    /// CREATE CONTINUOUS QUERY [IF NOT EXISTS] cq_cpu_1m EVERY '1m' [FOR '10m']
+    /// INTO cpu_1m AS SELECT date_bin(INTERVAL '1 minute', time) AS time, host, avg(usage) AS usage
+    /// FROM cpu GROUP BY date_bin(INTERVAL '1 minute', time), host
+    fn parse_create_continuous_query(&mut self) -> Result<ExtStatement> {
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parser.parse_identifier()?;
+
+        self.expect_cnos_keyword(CnosKeyWord::EVERY)?;
+        let every = self.parse_string_value()?;
+        let for_range = if self.parser.parse_keyword(Keyword::FOR) {
+            Some(self.parse_string_value()?)
+        } else {
+            None
+        };
+
+        self.parser.expect_keyword(Keyword::INTO)?;
+        let target_table = self.parser.parse_object_name()?;
+        self.parser.expect_keyword(Keyword::AS)?;
+        let query = Box::new(self.parser.parse_query()?);
+
+        Ok(ExtStatement::CreateContinuousQuery(Box::new(
+            ast::CreateContinuousQuery {
+                if_not_exists,
+                name,
+                every,
+                for_range,
+                target_table,
+                query,
+            },
+        )))
+    }
+
+    /// Parse a copy statement
+    fn parse_copy(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::VNODE) {
+            let vnode_id = self.parse_number::<VnodeId>()?;
+            self.parser.expect_keyword(Keyword::TO)?;
+            if self.parse_cnos_keyword(CnosKeyWord::NODE).not() {
+                return parser_err!("expected NODE, after TO");
+            }
+            let node_id = self.parse_number::<NodeId>()?;
+            Ok(ExtStatement::CopyVnode(CopyVnode { vnode_id, node_id }))
+        } else if self.parser.parse_keyword(Keyword::INTO) {
+            self.parse_copy_into()
+        } else if self.parser.consume_token(&Token::LParen) {
+            self.parse_copy_to()
+        } else {
+            parser_err!("expected VNODE, INTO or (, after COPY")
+        }
+    }
+
+    fn parse_copy_into_table(&mut self, table_name: ObjectName) -> Result<CopyTarget> {
+        // COPY INTO <table> [(columns,...)] FROM <location>
+        let columns = self
+            .parser
+            .parse_parenthesized_column_list(IsOptional::Optional, false)?;
+
+        self.parser.expect_keyword(Keyword::FROM)?;
 
         // external location's path
         let path = self.parser.parse_literal_string()?;
@@ -1781,6 +2449,67 @@ impl<'a> ExtParser<'a> {
                 path,
                 connection_options,
             },
+            report_bytes: false,
+        }))
+    }
+
+    /// Parse `COPY (<query>) TO '<uri>' [CONNECTION = (...)] [FORMAT PARQUET | CSV | JSON]`,
+    /// the leading `(` is consumed. The files are written in parquet if the format
+    /// is not specified by either `FORMAT` or `FILE_FORMAT`.
+    fn parse_copy_to(&mut self) -> Result<ExtStatement> {
+        let subquery = Box::new(self.parser.parse_query()?);
+        self.parser.expect_token(&Token::RParen)?;
+        self.parser.expect_keyword(Keyword::TO)?;
+        let path = self.parser.parse_literal_string()?;
+
+        let connection_options = if self.parser.parse_keyword(Keyword::CONNECTION) {
+            self.parse_options()?
+        } else {
+            Default::default()
+        };
+
+        let format = if self.parser.parse_keyword(Keyword::FORMAT) {
+            let format = self.parser.parse_identifier()?.value.to_uppercase();
+            if !matches!(format.as_str(), "PARQUET" | "CSV" | "JSON") {
+                return parser_err!(format!(
+                    "expected PARQUET, CSV or JSON after FORMAT, found: {format}"
+                ));
+            }
+            Some(format)
+        } else {
+            None
+        };
+
+        let (mut file_format_options, copy_options) = self.parse_copy_options()?;
+        let has_type = file_format_options
+            .iter()
+            .any(|o| o.name.value.eq_ignore_ascii_case("type"));
+        match format {
+            Some(_) if has_type => {
+                return parser_err!("FORMAT and the TYPE of FILE_FORMAT are both specified");
+            }
+            Some(format) => file_format_options.insert(0, file_type_option(format)),
+            None if !has_type => {
+                file_format_options.insert(0, file_type_option("PARQUET".to_string()))
+            }
+            None => {}
+        }
+
+        Ok(ExtStatement::Copy(ast::Copy {
+            copy_target: CopyTarget::IntoLocation(CopyIntoLocation {
+                from: TableFactor::Derived {
+                    lateral: false,
+                    subquery,
+                    alias: None,
+                },
+                location: UriLocation {
+                    path,
+                    connection_options,
+                },
+                report_bytes: true,
+            }),
+            file_format_options,
+            copy_options,
         }))
     }
 
@@ -1799,6 +2528,17 @@ impl<'a> ExtParser<'a> {
             }
         };
 
+        let (file_format_options, copy_options) = self.parse_copy_options()?;
+
+        Ok(ExtStatement::Copy(ast::Copy {
+            copy_target,
+            file_format_options,
+            copy_options,
+        }))
+    }
+
+    /// Parse the `FILE_FORMAT = (...)` and `COPY_OPTIONS = (...)` of a copy statement
+    fn parse_copy_options(&mut self) -> Result<(Vec<SqlOption>, Vec<SqlOption>)> {
         let mut copy_options = vec![];
         let mut file_format_options = vec![];
         loop {
@@ -1822,11 +2562,7 @@ impl<'a> ExtParser<'a> {
             }
         }
 
-        Ok(ExtStatement::Copy(ast::Copy {
-            copy_target,
-            file_format_options,
-            copy_options,
-        }))
+        Ok((file_format_options, copy_options))
     }
 
     fn _parse_files_of_copy_into(&mut self) -> Result<Vec<String>> {
@@ -1939,9 +2675,19 @@ impl<'a> ExtParser<'a> {
             let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
             let name = self.parser.parse_identifier()?;
             ExtStatement::DropStream(ast::DropStream { if_exist, name })
+        } else if self.parse_cnos_keyword(CnosKeyWord::CONTINUOUS) {
+            self.expect_cnos_keyword(CnosKeyWord::QUERY)?;
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let name = self.parser.parse_identifier()?;
+            ExtStatement::DropContinuousQuery(ast::DropContinuousQuery { if_exist, name })
+        } else if self.parse_cnos_keyword(CnosKeyWord::SCHEDULED) {
+            self.parser.expect_keyword(Keyword::DELETE)?;
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let name = self.parser.parse_identifier()?;
+            ExtStatement::DropScheduledDelete(ast::DropScheduledDelete { if_exist, name })
         } else {
             return self.expected(
-                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM after DROP",
+                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM,CONTINUOUS QUERY,SCHEDULED DELETE after DROP",
                 self.parser.peek_token(),
             );
         };
@@ -1983,6 +2729,43 @@ impl<'a> ExtParser<'a> {
         }
     }
 
+    /// Parse `KILL COMPACTION <job_id> [ON NODE <node_id>]`
+    fn parse_kill_compaction(&mut self) -> Result<ExtStatement> {
+        let job_id = self.parse_number::<u64>()?;
+        let node_id = if self.parser.parse_keyword(Keyword::ON) {
+            if self.parse_cnos_keyword(CnosKeyWord::NODE).not() {
+                return self.expected("NODE", self.parser.peek_token());
+            }
+            Some(self.parse_number::<NodeId>()?)
+        } else {
+            None
+        };
+        Ok(ExtStatement::KillCompaction(KillCompaction {
+            job_id,
+            node_id,
+        }))
+    }
+
+    fn parse_close(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::VNODE).not() {
+            return parser_err!("Expected VNODE, after CLOSE");
+        }
+        if self.parse_cnos_keyword(CnosKeyWord::FILES).not() {
+            return parser_err!("Expected FILES, after CLOSE VNODE");
+        }
+        let mut vnode_ids = Vec::new();
+        loop {
+            vnode_ids.push(self.parse_number::<VnodeId>()?);
+            if matches!(
+                self.parser.peek_token().token,
+                Token::SemiColon | Token::EOF
+            ) {
+                break;
+            }
+        }
+        Ok(ExtStatement::CloseVnodeFiles(CloseVnodeFiles { vnode_ids }))
+    }
+
     fn parse_checksum(&mut self) -> Result<ExtStatement> {
         if self.parser.parse_keyword(Keyword::GROUP) {
             let replication_set_id = self.parse_number::<ReplicationSetId>()?;
@@ -1994,6 +2777,89 @@ impl<'a> ExtParser<'a> {
         }
     }
 
+    /// REPAIR REPLICA <replica_id>
+    fn parse_repair(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::REPLICA) {
+            let replication_set_id = self.parse_number::<ReplicationSetId>()?;
+            Ok(ExtStatement::RepairReplica(RepairReplica {
+                replication_set_id,
+            }))
+        } else {
+            parser_err!("Expected REPLICA, after REPAIR")
+        }
+    }
+
+    /// EXPORT VNODE <vnode_id> TIME RANGE (<start_time>, <end_time>) TO '<path>'
+    /// [CONNECTION = (key1 = value1 [, ...])]
+    fn parse_export(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::VNODE).not() {
+            return parser_err!("Expected VNODE, after EXPORT");
+        }
+        let vnode_id = self.parse_number::<VnodeId>()?;
+
+        self.parser
+            .expect_keywords(&[Keyword::TIME, Keyword::RANGE])?;
+        self.parser.expect_token(&Token::LParen)?;
+        let start_time = self.parser.parse_value()?;
+        self.parser.expect_token(&Token::Comma)?;
+        let end_time = self.parser.parse_value()?;
+        self.parser.expect_token(&Token::RParen)?;
+
+        self.parser.expect_keyword(Keyword::TO)?;
+        let path = self.parser.parse_literal_string()?;
+        let connection_options = if self.parser.parse_keyword(Keyword::CONNECTION) {
+            self.parse_options()?
+        } else {
+            Default::default()
+        };
+
+        Ok(ExtStatement::ExportVnode(ExportVnode {
+            vnode_id,
+            start_time,
+            end_time,
+            location: UriLocation {
+                path,
+                connection_options,
+            },
+        }))
+    }
+
+    /// BACKUP DATABASE <database_name> TO '<path>' [CONNECTION = (key1 = value1 [, ...])]
+    fn parse_backup(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::DATABASE)?;
+        let database_name = self.parser.parse_identifier()?;
+        self.parser.expect_keyword(Keyword::TO)?;
+        let location = self.parse_uri_location()?;
+
+        Ok(ExtStatement::BackupDatabase(BackupDatabase {
+            database_name,
+            location,
+        }))
+    }
+
+    /// RESTORE DATABASE FROM '<path>' [CONNECTION = (key1 = value1 [, ...])]
+    fn parse_restore(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::DATABASE)?;
+        self.parser.expect_keyword(Keyword::FROM)?;
+        let location = self.parse_uri_location()?;
+
+        Ok(ExtStatement::RestoreDatabase(RestoreDatabase { location }))
+    }
+
+    fn parse_uri_location(&mut self) -> Result<UriLocation> {
+        let path = self.parser.parse_literal_string()?;
+        let connection_options = if self.parser.parse_keyword(Keyword::CONNECTION) {
+            self.parse_options()?
+        } else {
+            Default::default()
+        };
+
+        Ok(UriLocation {
+            path,
+            connection_options,
+        })
+    }
+
     fn consume_token(&mut self, expected: &Token) -> bool {
         if self.parser.peek_token().token == *expected {
             self.parser.next_token();
@@ -2016,6 +2882,7 @@ impl<'a> ExtParser<'a> {
             Ok(ExtStatement::ReplicaAdd(ast::ReplicaAdd {
                 replica_id,
                 node_id,
+                learner: false,
             }))
         } else if self.parse_cnos_keyword(CnosKeyWord::REMOVE) {
             if !self.parse_cnos_keyword(CnosKeyWord::REPLICA_ID) {
@@ -2104,7 +2971,13 @@ impl<'a> ExtParser<'a> {
         } else {
             None
         };
-        Ok(ColumnOption::new_field(name, column_type, encoding))
+        let mut column = ColumnOption::new_field(name, column_type, encoding);
+        if self.parse_cnos_keyword(CnosKeyWord::ENCRYPTED) {
+            self.parser.expect_keyword(Keyword::WITH)?;
+            self.parser.expect_keyword(Keyword::KEY)?;
+            column.encryption_key = Some(self.parser.parse_literal_string()?);
+        }
+        Ok(column)
     }
 
     fn parse_cnos_columns(&mut self) -> Result<Vec<ColumnOption>> {
@@ -2129,6 +3002,7 @@ impl<'a> ExtParser<'a> {
                         is_tag: true,
                         data_type: DataType::String,
                         encoding: None,
+                        encryption_key: None,
                     });
                     all_columns.extend(column_options);
                     self.parser.expect_token(&Token::RParen)?;
@@ -2203,6 +3077,14 @@ fn check_name_not_contain_illegal_character(object_name: &ObjectName) -> Result<
     Ok(())
 }
 
+/// `TYPE = '<format>'` of `FILE_FORMAT`
+fn file_type_option(format: String) -> SqlOption {
+    SqlOption {
+        name: Ident::new("type"),
+        value: Value::SingleQuotedString(format),
+    }
+}
+
 /// This is a copy of the equivalent implementation in Datafusion.
 fn parse_file_type(s: &str) -> Result<String, ParserError> {
     Ok(s.to_uppercase())
@@ -2455,8 +3337,10 @@ mod tests {
                     name: "column1".into(),
                     is_tag: false,
                     data_type: DataType::BigInt(None),
-                    encoding: None
-                }]
+                    encoding: None,
+                    encryption_key: None
+                }],
+                append_only: false,
             })
         );
 
@@ -2464,6 +3348,22 @@ mod tests {
         ExtParser::parse_sql(sql).err().unwrap();
     }
 
+    #[test]
+    fn test_create_append_only_table() {
+        let sql = "CREATE TABLE test(column1 BIGINT) WITH (append_only = true);";
+        let statement = ExtParser::parse_sql(sql).unwrap().pop_front().unwrap();
+        match statement {
+            ExtStatement::CreateTable(CreateTable { append_only, .. }) => assert!(append_only),
+            _ => panic!("expected create table statement"),
+        }
+
+        let sql = "CREATE TABLE test(column1 BIGINT) WITH (append_only = 'yes');";
+        ExtParser::parse_sql(sql).err().unwrap();
+
+        let sql = "CREATE TABLE test(column1 BIGINT) WITH (ttl = true);";
+        ExtParser::parse_sql(sql).err().unwrap();
+    }
+
     #[test]
     fn test_create_table_statement() {
         let sql = "CREATE TABLE IF NOT EXISTS test\
@@ -2480,6 +3380,7 @@ mod tests {
                 name,
                 if_not_exists,
                 columns,
+                ..
             }) => {
                 assert_eq!(name.to_string(), "test".to_string());
                 assert_eq!(if_not_exists.to_string(), "true".to_string());
@@ -2491,43 +3392,50 @@ mod tests {
                             name: Ident::from("column6"),
                             is_tag: true,
                             data_type: DataType::String,
-                            encoding: None
+                            encoding: None,
+                            encryption_key: None
                         },
                         ColumnOption {
                             name: Ident::from("column7"),
                             is_tag: true,
                             data_type: DataType::String,
-                            encoding: None
+                            encoding: None,
+                            encryption_key: None
                         },
                         ColumnOption {
                             name: Ident::from("column1"),
                             is_tag: false,
                             data_type: DataType::BigInt(None),
-                            encoding: Some(Encoding::Delta)
+                            encoding: Some(Encoding::Delta),
+                            encryption_key: None
                         },
                         ColumnOption {
                             name: Ident::from("column2"),
                             is_tag: false,
                             data_type: DataType::String,
-                            encoding: Some(Encoding::Gzip)
+                            encoding: Some(Encoding::Gzip),
+                            encryption_key: None
                         },
                         ColumnOption {
                             name: Ident::from("column3"),
                             is_tag: false,
                             data_type: DataType::UnsignedBigInt(None),
-                            encoding: Some(Encoding::Null)
+                            encoding: Some(Encoding::Null),
+                            encryption_key: None
                         },
                         ColumnOption {
                             name: Ident::from("column4"),
                             is_tag: false,
                             data_type: DataType::Boolean,
-                            encoding: None
+                            encoding: None,
+                            encryption_key: None
                         },
                         ColumnOption {
                             name: Ident::from("column5"),
                             is_tag: false,
                             data_type: DataType::Double,
-                            encoding: Some(Encoding::Gorilla)
+                            encoding: Some(Encoding::Gorilla),
+                            encryption_key: None
                         }
                     ]
                 );
@@ -2679,6 +3587,8 @@ mod tests {
                         shard_num: Some(5),
                         vnode_duration: Some("3d".to_string()),
                         replica: Some(10),
+                        series_placement: None,
+                        ..Default::default()
                     },
                     config: DatabaseConfig {
                         precision: Some("us".to_string()),
@@ -2698,7 +3608,7 @@ mod tests {
 
     #[test]
     fn test_create_database0() {
-        let sql = "create database test with ttl 'inf' shard 6 vnode_duration '730.5d' replica 1 precision 'us' max_memcache_size '128MiB' memcache_partitions 10 wal_max_file_size '300M' wal_sync 'true' strict_write 'true' max_cache_readers 100;";
+        let sql = "create database test with ttl 'inf' shard 6 vnode_duration '730.5d' replica 1 precision 'us' max_memcache_size '128MiB' memcache_partitions 10 wal_max_file_size '300M' wal_sync 'true' strict_write 'true' max_cache_readers 100 series_placement 'jump_hash';";
         let statements = ExtParser::parse_sql(sql).unwrap();
         assert_eq!(statements.len(), 1);
         match statements[0] {
@@ -2714,6 +3624,8 @@ mod tests {
                         shard_num: Some(6),
                         vnode_duration: Some("730.5d".to_string()),
                         replica: Some(1),
+                        series_placement: Some("jump_hash".to_string()),
+                        ..Default::default()
                     },
                     config: DatabaseConfig {
                         precision: Some("us".to_string()),
@@ -2746,6 +3658,11 @@ mod tests {
             ALTER TABLE m DROP f;
             ALTER TABLE m ALTER f SET CODEC(DEFAULT);
             ALTER TABLE m ALTER TIME SET CODEC(NULL);
+            ALTER TABLE m ALTER COLUMN f SET DEFAULT -1;
+            ALTER TABLE m ALTER f SET DEFAULT 'ok';
+            ALTER TABLE m ALTER COLUMN f DROP DEFAULT;
+            ALTER TABLE m ADD INDEX ON (f);
+            ALTER TABLE m DROP INDEX ON (f);
         "#;
         let statement = ExtParser::parse_sql(sql).unwrap();
         let statement: Vec<AlterTable> = statement
@@ -2765,7 +3682,8 @@ mod tests {
                             name: Ident::from("t"),
                             is_tag: true,
                             data_type: DataType::String,
-                            encoding: None
+                            encoding: None,
+                            encryption_key: None
                         }
                     }
                 },
@@ -2776,7 +3694,8 @@ mod tests {
                             name: Ident::from("f"),
                             is_tag: false,
                             data_type: DataType::BigInt(None),
-                            encoding: Some(Encoding::Default)
+                            encoding: Some(Encoding::Default),
+                            encryption_key: None
                         }
                     }
                 },
@@ -2805,6 +3724,41 @@ mod tests {
                         column_name: Ident::from("TIME"),
                         encoding: Encoding::Null
                     }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnDefault {
+                        column_name: Ident::from("f"),
+                        default: Some(Value::Number("-1".to_string(), false))
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnDefault {
+                        column_name: Ident::from("f"),
+                        default: Some(Value::SingleQuotedString("ok".to_string()))
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnDefault {
+                        column_name: Ident::from("f"),
+                        default: None
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnIndex {
+                        column_name: Ident::from("f"),
+                        indexed: true
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnIndex {
+                        column_name: Ident::from("f"),
+                        indexed: false
+                    }
                 }
             ]
         );
@@ -2851,6 +3805,18 @@ mod tests {
             ExtStatement::ReplicaAdd(ast::ReplicaAdd {
                 replica_id: 111,
                 node_id: 2001,
+                learner: false,
+            })
+        );
+
+        let sql1 = "alter replica 111 add learner on node 2001;";
+        let statement = ExtParser::parse_sql(sql1).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ReplicaAdd(ast::ReplicaAdd {
+                replica_id: 111,
+                node_id: 2001,
+                learner: true,
             })
         );
 
@@ -2886,6 +3852,85 @@ mod tests {
         assert_eq!(statement[0], ExtStatement::ShowReplicas);
     }
 
+    #[test]
+    fn test_show_cardinality() {
+        let sql = "show cardinality top 10 for db1;";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowCardinality(ShowCardinality {
+                top_k: 10,
+                database_name: Ident::new("db1"),
+            })
+        );
+
+        assert!(ExtParser::parse_sql("show cardinality for db1;").is_err());
+    }
+
+    #[test]
+    fn test_show_shard_skew() {
+        let statement = ExtParser::parse_sql("show shard skew for db1;").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowShardSkew(ShowShardSkew {
+                database_name: Ident::new("db1"),
+            })
+        );
+
+        assert!(ExtParser::parse_sql("show shard for db1;").is_err());
+    }
+
+    #[test]
+    fn test_show_vnodes_and_database_replicas() {
+        let statement = ExtParser::parse_sql("show vnodes;").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowVnodes(ShowVnodes { node_id: None })
+        );
+
+        let statement = ExtParser::parse_sql("show vnodes on node 1001;").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowVnodes(ShowVnodes {
+                node_id: Some(1001)
+            })
+        );
+
+        let statement = ExtParser::parse_sql("show replicas for database db1;").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowDatabaseReplicas(ShowDatabaseReplicas {
+                database_name: Ident::new("db1"),
+            })
+        );
+
+        assert!(ExtParser::parse_sql("show vnodes on 1001;").is_err());
+        assert!(ExtParser::parse_sql("show replicas for db1;").is_err());
+    }
+
+    #[test]
+    fn test_explain_cost() {
+        let sql = "explain (cost) select * from test where time > 1;";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        match &statement[0] {
+            ExtStatement::Explain(explain) => {
+                assert!(explain.cost);
+                assert!(!explain.analyze);
+                assert!(matches!(
+                    explain.ext_statement.as_ref(),
+                    ExtStatement::SqlStatement(_)
+                ));
+            }
+            _ => panic!("expect explain, got {:?}", statement[0]),
+        }
+
+        let sql = "explain (select * from test);";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert!(matches!(&statement[0], ExtStatement::Explain(e) if !e.cost));
+
+        assert!(ExtParser::parse_sql("explain (cost) analyze select * from test;").is_err());
+    }
+
     #[test]
     fn test_vnode_sql() {
         let sql1 = "move vnode 1 to node 2;";
@@ -2920,15 +3965,120 @@ mod tests {
                 vnode_ids: vec![6, 7, 8, 9],
             })
         );
-        let sql5 = "checksum group 10";
-        let statement = ExtParser::parse_sql(sql5).unwrap();
+        let sql = "close vnode files 6 7;";
+        let statement = ExtParser::parse_sql(sql).unwrap();
         assert_eq!(
             statement[0],
-            ExtStatement::ChecksumGroup(ChecksumGroup {
-                replication_set_id: 10
+            ExtStatement::CloseVnodeFiles(CloseVnodeFiles {
+                vnode_ids: vec![6, 7],
+            })
+        );
+        let sql = "explain compaction on vnode 6";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ExplainCompaction(ExplainCompaction { vnode_id: 6 })
+        );
+        assert!(ExtParser::parse_sql("explain compaction vnode 6").is_err());
+        let statement = ExtParser::parse_sql("show compactions").unwrap();
+        assert_eq!(statement[0], ExtStatement::ShowCompactions);
+        let statement = ExtParser::parse_sql("kill compaction 3").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::KillCompaction(KillCompaction {
+                job_id: 3,
+                node_id: None
+            })
+        );
+        let statement = ExtParser::parse_sql("kill compaction 3 on node 1001").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::KillCompaction(KillCompaction {
+                job_id: 3,
+                node_id: Some(1001)
+            })
+        );
+        assert!(ExtParser::parse_sql("kill compaction 3 on 1001").is_err());
+        let sql5 = "checksum group 10";
+        let statement = ExtParser::parse_sql(sql5).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ChecksumGroup(ChecksumGroup {
+                replication_set_id: 10
+            })
+        );
+        let sql6 = "repair replica 10";
+        let statement = ExtParser::parse_sql(sql6).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::RepairReplica(RepairReplica {
+                replication_set_id: 10
+            })
+        );
+        assert!(ExtParser::parse_sql("repair group 10").is_err());
+    }
+
+    #[test]
+    fn test_export_vnode() {
+        let sql = "export vnode 3 time range ('2024-01-01T00:00:00', 1704153600000000000) \
+            to 's3://bucket/path' connection = (region = 'us-east-1');";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ExportVnode(ExportVnode {
+                vnode_id: 3,
+                start_time: Value::SingleQuotedString("2024-01-01T00:00:00".to_string()),
+                end_time: Value::Number("1704153600000000000".to_string(), false),
+                location: UriLocation {
+                    path: "s3://bucket/path".to_string(),
+                    connection_options: vec![SqlOption {
+                        name: Ident::new("region"),
+                        value: Value::SingleQuotedString("us-east-1".to_string()),
+                    }],
+                },
+            })
+        );
+
+        let sql = "export vnode 3 to '/tmp/export';";
+        assert!(ExtParser::parse_sql(sql).is_err());
+    }
+
+    #[test]
+    fn test_backup_restore_database() {
+        let sql =
+            "backup database db1 to 's3://bucket/backup' connection = (region = 'us-east-1');";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::BackupDatabase(BackupDatabase {
+                database_name: Ident::new("db1"),
+                location: UriLocation {
+                    path: "s3://bucket/backup".to_string(),
+                    connection_options: vec![SqlOption {
+                        name: Ident::new("region"),
+                        value: Value::SingleQuotedString("us-east-1".to_string()),
+                    }],
+                },
             })
         );
-    }
+
+        let sql = "restore database from '/tmp/backup';";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::RestoreDatabase(RestoreDatabase {
+                location: UriLocation {
+                    path: "/tmp/backup".to_string(),
+                    connection_options: vec![],
+                },
+            })
+        );
+
+        let sql = "backup database db1;";
+        assert!(ExtParser::parse_sql(sql).is_err());
+        let sql = "restore database db1 from '/tmp/backup';";
+        assert!(ExtParser::parse_sql(sql).is_err());
+    }
 
     #[test]
     fn test_parse_copy_into_table_no_error() {
@@ -2974,6 +4124,7 @@ mod tests {
                     },
                 ],
             },
+            report_bytes: false,
         });
 
         let expected = ExtStatement::Copy(ast::Copy {
@@ -3001,6 +4152,62 @@ mod tests {
         let _ = ExtParser::parse_sql(sql).unwrap();
     }
 
+    #[test]
+    fn test_parse_copy_to() {
+        let sql = r#"
+            copy (select * from mytable) to 's3://bucket/path' CONNECTION = (xx='a') format csv file_format = (delimiter = '|');
+        "#;
+        let statement = parse_sql(sql);
+        let ExtStatement::Copy(ast::Copy {
+            copy_target: CopyTarget::IntoLocation(target),
+            file_format_options,
+            copy_options,
+        }) = statement
+        else {
+            panic!("expect CopyIntoLocation, found: {statement:?}")
+        };
+        assert!(matches!(target.from, TableFactor::Derived { .. }));
+        assert!(target.report_bytes);
+        assert_eq!(target.location.path, "s3://bucket/path");
+        assert_eq!(target.location.connection_options.len(), 1);
+        assert_eq!(
+            file_format_options,
+            vec![
+                SqlOption {
+                    name: "type".into(),
+                    value: Value::SingleQuotedString("CSV".to_string()),
+                },
+                SqlOption {
+                    name: "delimiter".into(),
+                    value: Value::SingleQuotedString("|".to_string()),
+                },
+            ]
+        );
+        assert!(copy_options.is_empty());
+
+        let statement = parse_sql("copy (select 1) to 'file:///tmp/data/out/';");
+        let ExtStatement::Copy(ast::Copy {
+            file_format_options,
+            ..
+        }) = statement
+        else {
+            panic!("expect Copy, found: {statement:?}")
+        };
+        assert_eq!(
+            file_format_options,
+            vec![SqlOption {
+                name: "type".into(),
+                value: Value::SingleQuotedString("PARQUET".to_string()),
+            }]
+        );
+
+        assert!(ExtParser::parse_sql("copy (select 1) to 'file:///tmp/' format orc;").is_err());
+        assert!(ExtParser::parse_sql(
+            "copy (select 1) to 'file:///tmp/' format csv file_format = (type = 'json');"
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_create_stream() {
         let statement = parse_sql("create stream if not exists test_s trigger = once watermark = '10s' output_mode = update as insert into t_tbl select 1;");
@@ -3013,6 +4220,7 @@ mod tests {
                     trigger,
                     watermark,
                     output_mode,
+                    sink,
                     statement,
                 } = s;
 
@@ -3021,12 +4229,54 @@ mod tests {
                 assert_eq!(trigger, Some(Trigger::Once));
                 assert_eq!(watermark, Some("10s".into()));
                 assert_eq!(output_mode, Some(OutputMode::Update));
+                assert_eq!(sink, None);
                 assert!(matches!(statement.deref(), Statement::Insert { .. }));
             }
             _ => panic!("expect CreateStream"),
         }
     }
 
+    #[test]
+    fn test_create_stream_into_kafka() {
+        let statement = parse_sql("create stream test_s trigger = '10s' into kafka('localhost:9092', 'readings', 'avro') as select * from t_stream;");
+
+        match statement {
+            ExtStatement::CreateStream(s) => {
+                assert_eq!(s.trigger, Some(Trigger::Interval("10s".into())));
+                assert_eq!(
+                    s.sink,
+                    Some(StreamSinkOptions::Kafka {
+                        brokers: "localhost:9092".into(),
+                        topic: "readings".into(),
+                        format: SinkFormat::Avro,
+                    })
+                );
+                assert!(matches!(s.statement.deref(), Statement::Query(_)));
+            }
+            _ => panic!("expect CreateStream"),
+        }
+
+        let statement =
+            parse_sql("create stream test_s into kafka('b1:9092,b2:9092', 'readings') as select * from t_stream;");
+        match statement {
+            ExtStatement::CreateStream(s) => {
+                assert!(matches!(
+                    s.sink,
+                    Some(StreamSinkOptions::Kafka {
+                        format: SinkFormat::Json,
+                        ..
+                    })
+                ));
+            }
+            _ => panic!("expect CreateStream"),
+        }
+
+        assert!(ExtParser::parse_sql(
+            "create stream test_s into kafka('localhost:9092', 'readings', 'xml') as select * from t_stream;"
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_drop_stream() {
         let result = parse_sql("drop stream if exists test_s;");
@@ -3131,6 +4381,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_alter_table_set_ttl() {
+        let statement = parse_sql("ALTER TABLE TskvTable SET TTL '30d';");
+        match statement {
+            ExtStatement::AlterTable(AlterTable {
+                alter_action: AlterTableAction::SetTtl { ttl },
+                ..
+            }) => assert_eq!(ttl, "30d"),
+            _ => panic!("expect SetTtl"),
+        }
+
+        ExtParser::parse_sql("ALTER TABLE TskvTable SET TTL 30").unwrap_err();
+    }
+
+    #[test]
+    fn test_continuous_query() {
+        let sql = "CREATE CONTINUOUS QUERY IF NOT EXISTS cq_cpu EVERY '1m' FOR '10m' INTO cpu_1m \
+            AS SELECT date_bin(INTERVAL '1 minute', time) AS time, avg(usage) AS usage \
+            FROM cpu GROUP BY date_bin(INTERVAL '1 minute', time)";
+        match parse_sql(sql) {
+            ExtStatement::CreateContinuousQuery(stmt) => {
+                assert!(stmt.if_not_exists);
+                assert_eq!(stmt.name, Ident::from("cq_cpu"));
+                assert_eq!(stmt.every, "1m");
+                assert_eq!(stmt.for_range.as_deref(), Some("10m"));
+                assert_eq!(stmt.target_table.to_string(), "cpu_1m");
+                assert!(stmt.query.to_string().starts_with("SELECT date_bin"));
+            }
+            _ => panic!("expect CreateContinuousQuery"),
+        }
+
+        match parse_sql(
+            "CREATE CONTINUOUS QUERY cq_cpu EVERY '1m' INTO cpu_1m AS SELECT * FROM cpu",
+        ) {
+            ExtStatement::CreateContinuousQuery(stmt) => assert_eq!(stmt.for_range, None),
+            _ => panic!("expect CreateContinuousQuery"),
+        }
+        assert!(
+            ExtParser::parse_sql("CREATE CONTINUOUS QUERY cq_cpu INTO cpu_1m AS SELECT 1").is_err()
+        );
+
+        assert_eq!(
+            parse_sql("DROP CONTINUOUS QUERY IF EXISTS cq_cpu"),
+            ExtStatement::DropContinuousQuery(ast::DropContinuousQuery {
+                if_exist: true,
+                name: Ident::from("cq_cpu"),
+            })
+        );
+        assert_eq!(
+            parse_sql("SHOW CONTINUOUS QUERIES"),
+            ExtStatement::ShowContinuousQueries
+        );
+    }
+
+    #[test]
+    fn test_scheduled_delete() {
+        let sql = "CREATE SCHEDULED DELETE IF NOT EXISTS clean_debug CRON '0 3 * * *' \
+            OLDER THAN '7d' AS DELETE FROM cpu WHERE debug = 'true'";
+        match parse_sql(sql) {
+            ExtStatement::CreateScheduledDelete(stmt) => {
+                assert!(stmt.if_not_exists);
+                assert_eq!(stmt.name, Ident::from("clean_debug"));
+                assert_eq!(stmt.cron, "0 3 * * *");
+                assert_eq!(stmt.older_than.as_deref(), Some("7d"));
+                assert_eq!(stmt.table_name.to_string(), "cpu");
+                assert_eq!(stmt.selection.unwrap().to_string(), "debug = 'true'");
+            }
+            _ => panic!("expect CreateScheduledDelete"),
+        }
+
+        match parse_sql("CREATE SCHEDULED DELETE clean_all CRON '*/5 * * * *' AS DELETE FROM cpu") {
+            ExtStatement::CreateScheduledDelete(stmt) => {
+                assert!(!stmt.if_not_exists);
+                assert_eq!(stmt.older_than, None);
+                assert_eq!(stmt.selection, None);
+            }
+            _ => panic!("expect CreateScheduledDelete"),
+        }
+        assert!(ExtParser::parse_sql(
+            "CREATE SCHEDULED DELETE clean CRON '* * * * *' AS SELECT * FROM cpu"
+        )
+        .is_err());
+        assert!(ExtParser::parse_sql("CREATE SCHEDULED DELETE clean AS DELETE FROM cpu").is_err());
+
+        assert_eq!(
+            parse_sql("DROP SCHEDULED DELETE IF EXISTS clean_debug"),
+            ExtStatement::DropScheduledDelete(ast::DropScheduledDelete {
+                if_exist: true,
+                name: Ident::from("clean_debug"),
+            })
+        );
+        assert_eq!(parse_sql("SHOW JOBS"), ExtStatement::ShowJobs);
+    }
+
+    #[test]
+    fn test_merge_tag_value() {
+        let statement = parse_sql("UPDATE TABLE cpu MERGE TAG host 'old-name' INTO 'new-name';");
+
+        assert_eq!(
+            statement,
+            ExtStatement::MergeTagValue(MergeTagValue {
+                table_name: ObjectName(vec![Ident::from("cpu")]),
+                tag_name: Ident::from("host"),
+                from: "old-name".to_string(),
+                to: "new-name".to_string(),
+            })
+        );
+
+        let sql = "UPDATE TABLE cpu MERGE TAG host 'old-name' 'new-name';";
+        assert!(ExtParser::parse_sql(sql).is_err());
+    }
+
     #[test]
     fn test_update() {
         let statement = parse_sql("UPDATE TskvTable SET tag1 = '1' WHERE tag2 = '2';");
@@ -3253,4 +4615,327 @@ mod tests {
             _ => panic!("impossible"),
         }
     }
+
+    #[test]
+    fn test_tenant_timeout_options() {
+        let sql = "create tenant test_tenant with query_timeout='30s', write_timeout='5s'";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match statements[0] {
+            ExtStatement::CreateTenant(ref stmt) => {
+                assert_eq!(
+                    stmt.with_options,
+                    vec![
+                        SqlOption {
+                            name: "query_timeout".into(),
+                            value: Value::SingleQuotedString("30s".to_string()),
+                        },
+                        SqlOption {
+                            name: "write_timeout".into(),
+                            value: Value::SingleQuotedString("5s".to_string()),
+                        },
+                    ]
+                );
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "alter tenant test_tenant set query_timeout='1m'";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match statements[0] {
+            ExtStatement::AlterTenant(AlterTenant {
+                operation: AlterTenantOperation::Set(ref option),
+                ..
+            }) => {
+                assert_eq!(
+                    option,
+                    &SqlOption {
+                        name: "query_timeout".into(),
+                        value: Value::SingleQuotedString("1m".to_string()),
+                    }
+                );
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "alter tenant test_tenant set query_timeout='1m', write_timeout='1m'";
+        assert!(ExtParser::parse_sql(sql).is_err());
+    }
+
+    #[test]
+    fn test_tenant_quotas() {
+        let sql = "create tenant test_tenant with max_databases=2, max_tables=100, max_users=10";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match statements[0] {
+            ExtStatement::CreateTenant(ref stmt) => {
+                assert_eq!(
+                    stmt.with_options,
+                    vec![
+                        SqlOption {
+                            name: "max_databases".into(),
+                            value: Value::Number("2".to_string(), false),
+                        },
+                        SqlOption {
+                            name: "max_tables".into(),
+                            value: Value::Number("100".to_string(), false),
+                        },
+                        SqlOption {
+                            name: "max_users".into(),
+                            value: Value::Number("10".to_string(), false),
+                        },
+                    ]
+                );
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "alter tenant test_tenant set max_tables=200";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match statements[0] {
+            ExtStatement::AlterTenant(AlterTenant {
+                operation: AlterTenantOperation::Set(ref option),
+                ..
+            }) => {
+                assert_eq!(
+                    option,
+                    &SqlOption {
+                        name: "max_tables".into(),
+                        value: Value::Number("200".to_string(), false),
+                    }
+                );
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "alter tenant test_tenant set max_series_per_table=100000";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match statements[0] {
+            ExtStatement::AlterTenant(AlterTenant {
+                operation: AlterTenantOperation::Set(ref option),
+                ..
+            }) => {
+                assert_eq!(
+                    option,
+                    &SqlOption {
+                        name: "max_series_per_table".into(),
+                        value: Value::Number("100000".to_string(), false),
+                    }
+                );
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "alter tenant test_tenant set max_tables=200, max_users=1";
+        assert!(ExtParser::parse_sql(sql).is_err());
+
+        assert_eq!(
+            parse_sql("SHOW TENANT QUOTAS"),
+            ExtStatement::ShowTenantQuotas
+        );
+    }
+
+    #[test]
+    fn test_alter_database_compaction() {
+        let sql = "ALTER DATABASE test SET COMPACTION 'time_window'";
+        match parse_sql(sql) {
+            ExtStatement::AlterDatabase(stmt) => {
+                assert_eq!(stmt.name.to_string(), "test");
+                assert_eq!(stmt.options.compaction, Some("time_window".to_string()));
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "ALTER DATABASE test SET COMPACTION_FILE_SIZES '16MiB,64MiB'";
+        match parse_sql(sql) {
+            ExtStatement::AlterDatabase(stmt) => {
+                assert_eq!(
+                    stmt.options.compaction_file_sizes,
+                    Some("16MiB,64MiB".to_string())
+                );
+            }
+            _ => panic!("impossible"),
+        }
+    }
+
+    #[test]
+    fn test_database_time_zone() {
+        let sql = "CREATE DATABASE test WITH TIME_ZONE 'Asia/Shanghai'";
+        match parse_sql(sql) {
+            ExtStatement::CreateDatabase(stmt) => {
+                assert_eq!(stmt.options.time_zone, Some("Asia/Shanghai".to_string()));
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "ALTER DATABASE test SET TIME_ZONE '+08:00'";
+        match parse_sql(sql) {
+            ExtStatement::AlterDatabase(stmt) => {
+                assert_eq!(stmt.options.time_zone, Some("+08:00".to_string()));
+            }
+            _ => panic!("impossible"),
+        }
+    }
+
+    #[test]
+    fn test_show_runtime() {
+        assert_eq!(parse_sql("SHOW RUNTIME"), ExtStatement::ShowRuntime);
+        assert_eq!(parse_sql("show runtime;"), ExtStatement::ShowRuntime);
+    }
+
+    #[test]
+    fn test_column_encryption() {
+        let sql = "CREATE TABLE test(f1 STRING ENCRYPTED WITH KEY 'k1', f2 STRING CODEC(GZIP) ENCRYPTED WITH KEY 'k2', f3 BIGINT, TAGS(t))";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match &statements[0] {
+            ExtStatement::CreateTable(CreateTable { columns, .. }) => {
+                let keys = columns
+                    .iter()
+                    .map(|c| c.encryption_key.as_deref())
+                    .collect::<Vec<_>>();
+                assert_eq!(keys, vec![None, Some("k1"), Some("k2"), None]);
+                assert_eq!(columns[2].encoding, Some(Encoding::Gzip));
+            }
+            _ => panic!("impossible"),
+        }
+
+        let sql = "CREATE TABLE test(f1 STRING ENCRYPTED WITH KEY k1)";
+        assert!(ExtParser::parse_sql(sql).is_err());
+
+        let sql = "alter tenant test_tenant set encryption key k1 = '0011'";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statements[0],
+            ExtStatement::AlterTenant(AlterTenant {
+                name: "test_tenant".into(),
+                operation: AlterTenantOperation::SetEncryptionKey(
+                    "k1".into(),
+                    EncryptionKey::new("0011")
+                ),
+            })
+        );
+
+        let sql = "alter tenant test_tenant unset encryption key k1";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statements[0],
+            ExtStatement::AlterTenant(AlterTenant {
+                name: "test_tenant".into(),
+                operation: AlterTenantOperation::UnSetEncryptionKey("k1".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_show_functions() {
+        let statements = ExtParser::parse_sql("show functions").unwrap();
+        assert_eq!(statements[0], ExtStatement::ShowFunctions(None));
+
+        let statements = ExtParser::parse_sql("SHOW FUNCTIONS LIKE 'st_%'").unwrap();
+        assert_eq!(
+            statements[0],
+            ExtStatement::ShowFunctions(Some("st_%".to_string()))
+        );
+
+        assert!(ExtParser::parse_sql("show functions like st_area").is_err());
+    }
+
+    #[test]
+    fn test_preview_stream() {
+        let sql = "PREVIEW STREAM INSERT INTO t1 SELECT * FROM s1";
+        let statements = ExtParser::parse_sql(sql).unwrap();
+        match &statements[0] {
+            ExtStatement::PreviewStream(PreviewStream { ext_statement }) => {
+                assert!(matches!(
+                    ext_statement.as_ref(),
+                    ExtStatement::SqlStatement(_)
+                ));
+            }
+            _ => panic!("impossible"),
+        }
+
+        assert!(ExtParser::parse_sql("preview select * from s1").is_err());
+        assert!(ExtParser::parse_sql("preview stream explain select * from s1").is_err());
+    }
+
+    #[test]
+    fn test_set_variable() {
+        for sql in ["SET time_zone = '+08:00'", "set TIME_ZONE to '+08:00';"] {
+            let statement = parse_sql(sql);
+            match statement {
+                ExtStatement::SqlStatement(stmt) => match *stmt {
+                    Statement::SetVariable {
+                        variable, value, ..
+                    } => {
+                        assert_eq!(variable.to_string().to_ascii_lowercase(), "time_zone");
+                        assert_eq!(value.len(), 1);
+                        assert_eq!(value[0].to_string(), "'+08:00'");
+                    }
+                    _ => panic!("expect SetVariable, got {}", stmt),
+                },
+                _ => panic!("expect SqlStatement, got {:?}", statement),
+            }
+        }
+    }
+
+    #[test]
+    fn test_grant_table_privilege() {
+        let sql = "grant read (time, f1) on table db1.t1, write on database db2 to role r1";
+        let statement = parse_sql(sql);
+        match statement {
+            ExtStatement::GrantRevoke(GrantRevoke {
+                is_grant,
+                privileges,
+                role_name,
+            }) => {
+                assert!(is_grant);
+                assert_eq!(role_name, Ident::new("r1"));
+                assert_eq!(
+                    privileges,
+                    vec![
+                        Privilege {
+                            action: Action::Read,
+                            object: PrivilegeObject::Table {
+                                name: ObjectName(vec![Ident::new("db1"), Ident::new("t1")]),
+                                columns: vec![Ident::new("time"), Ident::new("f1")],
+                            },
+                        },
+                        Privilege {
+                            action: Action::Write,
+                            object: PrivilegeObject::Database(Ident::new("db2")),
+                        },
+                    ]
+                );
+            }
+            _ => panic!("expect GrantRevoke, got {:?}", statement),
+        }
+
+        let sql = "revoke all on table t1 from r1";
+        match parse_sql(sql) {
+            ExtStatement::GrantRevoke(GrantRevoke { privileges, .. }) => {
+                assert_eq!(
+                    privileges[0].object,
+                    PrivilegeObject::Table {
+                        name: ObjectName(vec![Ident::new("t1")]),
+                        columns: vec![],
+                    }
+                );
+            }
+            statement => panic!("expect GrantRevoke, got {:?}", statement),
+        }
+
+        assert!(ExtParser::parse_sql("grant read (f1) on database db1 to r1").is_err());
+
+        match parse_sql("grant decrypt on database db1 to r1") {
+            ExtStatement::GrantRevoke(GrantRevoke { privileges, .. }) => {
+                assert_eq!(privileges[0].action, Action::Decrypt);
+            }
+            statement => panic!("expect GrantRevoke, got {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_audit() {
+        assert_eq!(parse_sql("AUDIT ON"), ExtStatement::SetAudit(true));
+        assert_eq!(parse_sql("audit off;"), ExtStatement::SetAudit(false));
+        assert!(ExtParser::parse_sql("audit").is_err());
+    }
 }
@@ -6,10 +6,11 @@ use std::{iter, vec};
 
 use async_recursion::async_recursion;
 use async_trait::async_trait;
+use datafusion::arrow::compute::kernels::cast_utils::string_to_timestamp_nanos;
 use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
 use datafusion::arrow::error::ArrowError;
 use datafusion::common::parsers::CompressionTypeVariant;
-use datafusion::common::tree_node::TreeNode;
+use datafusion::common::tree_node::{TreeNode, VisitRecursion};
 use datafusion::common::{
     Column, DFField, DFSchema, OwnedTableReference, Result as DFResult, ToDFSchema,
 };
@@ -31,20 +32,20 @@ use datafusion::logical_expr::logical_plan::Analyze;
 use datafusion::logical_expr::utils::expr_to_columns;
 use datafusion::logical_expr::{
     lit, BinaryExpr, BuiltinScalarFunction, Case, CreateExternalTable as PlanCreateExternalTable,
-    EmptyRelation, Explain, Expr, Extension, LogicalPlan, LogicalPlanBuilder, Operator, PlanType,
-    SubqueryAlias, TableSource, ToStringifiedPlan, Union,
+    EmptyRelation, Expr, Extension, LogicalPlan, LogicalPlanBuilder, Operator, Partitioning,
+    SubqueryAlias, TableSource, Union,
 };
 use datafusion::optimizer::analyzer::type_coercion::TypeCoercionRewriter;
 use datafusion::optimizer::simplify_expressions::ConstEvaluator;
 use datafusion::physical_expr::execution_props::ExecutionProps;
-use datafusion::prelude::col;
+use datafusion::prelude::{col, lower};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::parser::CreateExternalTable as AstCreateExternalTable;
 use datafusion::sql::planner::{object_name_to_table_reference, PlannerContext, SqlToRel};
 use datafusion::sql::sqlparser::ast::{
     Assignment, DataType as SQLDataType, Expr as SQLExpr, Expr as ASTExpr, Ident, ObjectName,
     Offset, OrderByExpr, Query, SqlOption, Statement, TableAlias, TableFactor, TableWithJoins,
-    TimezoneInfo,
+    TimezoneInfo, Value as SQLValue,
 };
 use datafusion::sql::sqlparser::parser::ParserError;
 use datafusion::sql::TableReference;
@@ -52,14 +53,19 @@ use lazy_static::__Deref;
 use meta::error::MetaError;
 use models::auth::bcrypt_verify;
 use models::auth::privilege::{
-    DatabasePrivilege, GlobalPrivilege, Privilege, TenantObjectPrivilege,
+    DatabasePrivilege, GlobalPrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
 };
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::User;
 use models::gis::data_type::{Geometry, GeometryType};
 use models::object_reference::{Resolve, ResolvedTable};
 use models::oid::{Identifier, Oid};
-use models::schema::database_schema::{DatabaseConfigBuilder, DatabaseOptionsBuilder};
+use models::predicate::domain::TimeRange;
+use models::schema::database_schema::{
+    CompactionStrategyKind, DatabaseConfigBuilder, DatabaseOptionsBuilder, SeriesPlacement,
+    WalSyncPolicy,
+};
+use models::schema::scheduled_delete::CronSchedule;
 use models::schema::stream_table_schema::Watermark;
 use models::schema::tenant::Tenant;
 use models::schema::tskv_table_schema::{
@@ -74,29 +80,40 @@ use spi::query::ast;
 use spi::query::ast::{
     AlterDatabase as ASTAlterDatabase, AlterTable as ASTAlterTable,
     AlterTableAction as ASTAlterTableAction, AlterTenantOperation, AlterUserOperation,
-    ChecksumGroup as ASTChecksumGroup, ColumnOption, CompactDatabase as ASTCompactDatabase,
+    BackupDatabase as ASTBackupDatabase, ChecksumGroup as ASTChecksumGroup,
+    CloseVnodeFiles as ASTCloseVnodeFiles, ColumnOption, CompactDatabase as ASTCompactDatabase,
     CompactVnode as ASTCompactVnode, CopyIntoTable, CopyTarget, CopyVnode as ASTCopyVnode,
     CreateDatabase as ASTCreateDatabase, CreateTable as ASTCreateTable,
     DatabaseConfig as ASTDatabaseConfig, DatabaseOptions as ASTDatabaseOptions,
     DescribeDatabase as DescribeDatabaseOptions, DescribeTable as DescribeTableOptions,
-    DropVnode as ASTDropVnode, ExtStatement, MoveVnode as ASTMoveVnode,
-    ReplicaAdd as ASTReplicaAdd, ReplicaDestory as ASTReplicaDestory,
-    ReplicaPromote as ASTReplicaPromote, ReplicaRemove as ASTReplicaRemove,
-    ShowSeries as ASTShowSeries, ShowTagBody, ShowTagValues as ASTShowTagValues, UriLocation, With,
+    DropVnode as ASTDropVnode, ExplainCompaction as ASTExplainCompaction,
+    ExportVnode as ASTExportVnode, ExtStatement, KillCompaction as ASTKillCompaction,
+    MergeTagValue as ASTMergeTagValue, MoveVnode as ASTMoveVnode,
+    RepairReplica as ASTRepairReplica, ReplicaAdd as ASTReplicaAdd,
+    ReplicaDestory as ASTReplicaDestory, ReplicaPromote as ASTReplicaPromote,
+    ReplicaRemove as ASTReplicaRemove, RestoreDatabase as ASTRestoreDatabase,
+    ShowCardinality as ASTShowCardinality, ShowDatabaseReplicas as ASTShowDatabaseReplicas,
+    ShowSeries as ASTShowSeries, ShowShardSkew as ASTShowShardSkew, ShowTagBody,
+    ShowTagValues as ASTShowTagValues, ShowVnodes as ASTShowVnodes, UriLocation, With,
 };
+use spi::query::config::{parse_time_zone, SessionVariable, StreamTriggerInterval};
 use spi::query::datasource::{self, UriSchema};
 use spi::query::logical_planner::{
-    normalize_sql_object_name_to_string, parse_connection_options,
-    sql_option_to_alter_tenant_action, sql_options_to_map, sql_options_to_tenant_options,
-    sql_options_to_user_options, unset_option_to_alter_tenant_action, AlterDatabase, AlterTable,
-    AlterTableAction, AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser,
-    AlterUser, AlterUserAction, ChecksumGroup, CompactVnode, CopyOptions, CopyOptionsBuilder,
-    CopyVnode, CreateDatabase, CreateRole, CreateStreamTable, CreateTable, CreateTenant,
-    CreateUser, DDLPlan, DMLPlan, DatabaseObjectType, DeleteFromTable, DropDatabaseObject,
-    DropGlobalObject, DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder,
-    GlobalObjectType, GrantRevoke, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan,
-    RecoverDatabase, RecoverTenant, ReplicaAdd, ReplicaDestory, ReplicaPromote, ReplicaRemove,
-    SYSPlan, TenantObjectType, TENANT_OPTION_LIMITER,
+    encryption_key_to_alter_tenant_action, normalize_sql_object_name_to_string,
+    parse_connection_options, sql_option_to_alter_tenant_action, sql_options_to_map,
+    sql_options_to_tenant_options, sql_options_to_user_options,
+    unset_option_to_alter_tenant_action, AlterDatabase, AlterTable, AlterTableAction, AlterTenant,
+    AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser, AlterUser, AlterUserAction,
+    BackupDatabase, ChecksumGroup, CloseVnodeFiles, CompactVnode, CopyOptions, CopyOptionsBuilder,
+    CopyVnode, CreateContinuousQuery, CreateDatabase, CreateRole, CreateScheduledDelete,
+    CreateStreamQuery, CreateStreamTable, CreateTable, CreateTenant, CreateUser, DDLPlan, DMLPlan,
+    DatabaseObjectType, DeleteFromTable, DropContinuousQuery, DropDatabaseObject, DropGlobalObject,
+    DropScheduledDelete, DropTenantObject, DropVnode, ExplainCompaction, ExplainCost, ExportVnode,
+    FileFormatOptions, FileFormatOptionsBuilder, GlobalObjectType, GrantRevoke, KillCompaction,
+    LogicalPlanner, MergeTagValue, MoveVnode, Plan, PlanWithPrivileges, PreviewStream, QueryPlan,
+    RecoverDatabase, RecoverTenant, RepairReplica, ReplicaAdd, ReplicaDestory, ReplicaPromote,
+    ReplicaRemove, RestoreDatabase, SYSPlan, ShowCardinality, ShowContinuousQueries, ShowJobs,
+    ShowShardSkew, TenantObjectType, TENANT_OPTION_LIMITER,
 };
 use spi::query::session::SessionCtx;
 use spi::{
@@ -112,17 +129,23 @@ use utils::precision::Precision;
 use crate::data_source::source_downcast_adapter;
 use crate::data_source::stream::{get_event_time_column, get_watermark_delay};
 use crate::data_source::table_source::{TableHandle, TableSourceAdapter, TEMP_LOCATION_TABLE_NAME};
+use crate::execution::factory::is_dml;
 use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
 use crate::extension::logical::plan_node::update::UpdateNode;
+use crate::extension::logical::utils::extract_table_columns;
 use crate::metadata::{
-    is_system_database, ContextProviderExtension, DatabaseSet, COLUMNS_COLUMN_NAME,
-    COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC, COLUMNS_DATABASE_NAME, COLUMNS_DATA_TYPE,
-    COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME, DATABASES_MAX_CACHE_READERS,
-    DATABASES_MAX_MEMCACHE_SIZE, DATABASES_MEMCACHE_PARTITIONS, DATABASES_PRECISION,
-    DATABASES_REPLICA, DATABASES_SHARD, DATABASES_STRICT_WRITE, DATABASES_TTL,
-    DATABASES_VNODE_DURATION, DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC, INFORMATION_SCHEMA,
-    INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_QUERIES,
-    INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE, TABLES_TABLE_NAME,
+    is_system_database, ContextProviderExtension, DatabaseSet, CLUSTER_SCHEMA,
+    CLUSTER_SCHEMA_RUNTIME, COLUMNS_COLUMN_NAME, COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC,
+    COLUMNS_DATABASE_NAME, COLUMNS_DATA_TYPE, COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME,
+    DATABASES_MAX_CACHE_READERS, DATABASES_MAX_MEMCACHE_SIZE, DATABASES_MEMCACHE_PARTITIONS,
+    DATABASES_PRECISION, DATABASES_REPLICA, DATABASES_SHARD, DATABASES_STRICT_WRITE, DATABASES_TTL,
+    DATABASES_VNODE_DURATION, DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC,
+    FUNCTIONS_ARGUMENT_TYPES, FUNCTIONS_DESCRIPTION, FUNCTIONS_FUNCTION_NAME,
+    FUNCTIONS_FUNCTION_TYPE, FUNCTIONS_RETURN_TYPE, INFORMATION_SCHEMA, INFORMATION_SCHEMA_COLUMNS,
+    INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_FUNCTIONS, INFORMATION_SCHEMA_QUERIES,
+    INFORMATION_SCHEMA_TABLES, INFORMATION_SCHEMA_VNODES, TABLES_TABLE_DATABASE, TABLES_TABLE_NAME,
+    VNODES_DATABASE_NAME, VNODES_DISK_STORAGE, VNODES_NODE_ID, VNODES_REPLICA_ID, VNODES_ROLE,
+    VNODES_STATUS, VNODES_VNODE_ID,
 };
 
 /// CnosDB SQL query planner
@@ -206,46 +229,91 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                 self.explain_statement_to_plan(
                     stmt.analyze,
                     stmt.verbose,
+                    stmt.cost,
                     *stmt.ext_statement,
                     session,
                     false,
                 )
                 .await
             }
+            ExtStatement::PreviewStream(stmt) => {
+                self.preview_stream_to_plan(*stmt.ext_statement, session, auth_enable)
+                    .await
+            }
             ExtStatement::ShowTagValues(stmt) => self.show_tag_values(*stmt, session),
             ExtStatement::AlterTable(stmt) => self.alter_table_to_plan(stmt, session),
+            ExtStatement::MergeTagValue(stmt) => self.merge_tag_value_to_plan(stmt, session),
             ExtStatement::AlterTenant(stmt) => self.alter_tenant_to_plan(stmt).await,
             ExtStatement::AlterUser(stmt) => {
                 self.alter_user_to_plan(stmt, session.user(), false).await
             }
             ExtStatement::GrantRevoke(stmt) => self.grant_revoke_to_plan(stmt, session),
+            ExtStatement::ShowFunctions(pattern) => self.show_functions_to_plan(pattern),
             ExtStatement::ShowQueries => self.show_queries_to_plan(session),
+            ExtStatement::ShowRuntime => self.show_runtime_to_plan(),
             ExtStatement::Copy(stmt) => self.copy_to_plan(stmt, session).await,
             ExtStatement::DropVnode(stmt) => self.drop_vnode_to_plan(stmt),
             ExtStatement::CopyVnode(stmt) => self.copy_vnode_to_plan(stmt),
             ExtStatement::MoveVnode(stmt) => self.move_vnode_to_plan(stmt),
             ExtStatement::CompactVnode(stmt) => self.compact_vnode_to_plan(stmt),
             ExtStatement::CompactDatabase(stmt) => self.compact_database_to_plan(stmt),
-            ExtStatement::ChecksumGroup(stmt) => self.checksum_group_to_plan(stmt),
-            ExtStatement::CreateStream(_) => Err(QueryError::NotImplemented {
-                err: "CreateStream Planner.".to_string(),
-            }),
-            ExtStatement::DropStream(_) => Err(QueryError::NotImplemented {
-                err: "DropStream Planner.".to_string(),
+            ExtStatement::CloseVnodeFiles(stmt) => self.close_vnode_files_to_plan(stmt),
+            ExtStatement::ExplainCompaction(stmt) => self.explain_compaction_to_plan(stmt),
+            ExtStatement::ShowCompactions => Ok(PlanWithPrivileges {
+                plan: Plan::DDL(DDLPlan::ShowCompactions),
+                privileges: vec![Privilege::Global(GlobalPrivilege::System)],
             }),
+            ExtStatement::KillCompaction(stmt) => self.kill_compaction_to_plan(stmt),
+            ExtStatement::ChecksumGroup(stmt) => self.checksum_group_to_plan(stmt),
+            ExtStatement::RepairReplica(stmt) => self.repair_replica_to_plan(stmt),
+            ExtStatement::ExportVnode(stmt) => self.export_vnode_to_plan(stmt),
+            ExtStatement::BackupDatabase(stmt) => self.backup_database_to_plan(stmt),
+            ExtStatement::RestoreDatabase(stmt) => self.restore_database_to_plan(stmt),
+            ExtStatement::ShowCardinality(stmt) => self.show_cardinality_to_plan(stmt, session),
+            ExtStatement::ShowShardSkew(stmt) => self.show_shard_skew_to_plan(stmt, session),
+            ExtStatement::CreateStream(stmt) => self.create_stream_to_plan(stmt, session).await,
+            ExtStatement::DropStream(stmt) => self.drop_stream_to_plan(stmt, session),
             ExtStatement::ShowStreams(_) => Err(QueryError::NotImplemented {
                 err: "ShowStreams Planner.".to_string(),
             }),
             ExtStatement::CreateStreamTable(stmt) => {
                 self.create_stream_table_to_plan(stmt, session)
             }
+            ExtStatement::CreateContinuousQuery(stmt) => {
+                self.create_continuous_query_to_plan(*stmt, session).await
+            }
+            ExtStatement::DropContinuousQuery(stmt) => {
+                self.drop_continuous_query_to_plan(stmt, session)
+            }
+            ExtStatement::ShowContinuousQueries => self.show_continuous_queries_to_plan(session),
+            ExtStatement::CreateScheduledDelete(stmt) => {
+                self.create_scheduled_delete_to_plan(*stmt, session)
+            }
+            ExtStatement::DropScheduledDelete(stmt) => {
+                self.drop_scheduled_delete_to_plan(stmt, session)
+            }
+            ExtStatement::ShowJobs => self.show_jobs_to_plan(session),
+            ExtStatement::ShowTenantQuotas => Ok(PlanWithPrivileges {
+                plan: Plan::DDL(DDLPlan::ShowTenantQuotas),
+                privileges: vec![Privilege::Global(GlobalPrivilege::Tenant(Some(
+                    *session.tenant_id(),
+                )))],
+            }),
             ExtStatement::RecoverTenant(stmt) => self.recovertenant_to_plan(stmt),
             ExtStatement::RecoverDatabase(stmt) => self.recoverdatabase_to_plan(stmt, session),
             ExtStatement::ShowReplicas => self.show_replicas_to_plan(),
+            ExtStatement::ShowDatabaseReplicas(stmt) => {
+                self.show_database_replicas_to_plan(stmt, session)
+            }
+            ExtStatement::ShowVnodes(stmt) => self.show_vnodes_to_plan(stmt, session),
             ExtStatement::ReplicaDestory(stmt) => self.replica_destory_to_plan(stmt),
             ExtStatement::ReplicaAdd(stmt) => self.replica_add_to_plan(stmt),
             ExtStatement::ReplicaRemove(stmt) => self.replica_remove_to_plan(stmt),
             ExtStatement::ReplicaPromote(stmt) => self.replica_promote_to_plan(stmt),
+            ExtStatement::SetAudit(enabled) => Ok(PlanWithPrivileges {
+                plan: Plan::SYSTEM(SYSPlan::SetAudit(enabled)),
+                privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+            }),
         }
     }
 
@@ -257,18 +325,15 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         match stmt {
             Statement::Query(_) => {
                 let df_plan = self.df_planner.sql_statement_to_plan(stmt)?;
+
+                // privileges
+                let access_databases = self.schema_provider.reset_access_databases();
+                let privileges =
+                    query_privileges(&df_plan, *session.tenant_id(), access_databases)?;
                 let plan = Plan::Query(QueryPlan {
                     df_plan,
                     is_tag_scan: false,
                 });
-
-                // privileges
-                let access_databases = self.schema_provider.reset_access_databases();
-                let privileges = databases_privileges(
-                    DatabasePrivilege::Read,
-                    *session.tenant_id(),
-                    access_databases,
-                );
                 Ok(PlanWithPrivileges { plan, privileges })
             }
             Statement::Insert {
@@ -316,6 +381,23 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     privileges: vec![],
                 })
             }
+            Statement::SetVariable {
+                variable, value, ..
+            } => {
+                let value = match value.as_slice() {
+                    [value] => set_variable_value(value)?,
+                    _ => {
+                        return Err(QueryError::Semantic {
+                            err: format!("SET {} expects exactly one value", variable),
+                        })
+                    }
+                };
+                self.set_variable_to_plan(&variable.to_string(), &value)
+            }
+            Statement::SetTimeZone { value, .. } => {
+                let value = set_variable_value(&value)?;
+                self.set_variable_to_plan(SessionVariable::TIME_ZONE, &value)
+            }
             Statement::Update {
                 table,
                 assignments,
@@ -423,6 +505,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         &self,
         analyze: bool,
         verbose: bool,
+        cost: bool,
         statement: ExtStatement,
         session: &SessionCtx,
         auth_enable: bool,
@@ -431,8 +514,18 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             .statement_to_plan(statement, session, auth_enable)
             .await?;
 
-        let (input_df_plan, is_tag_scan) = match plan {
-            Plan::Query(query) => (Arc::new(query.df_plan), query.is_tag_scan),
+        if cost {
+            let Plan::Query(query) = plan else {
+                return Err(QueryError::NotImplemented {
+                    err: "explain cost of non-query statement.".to_string(),
+                });
+            };
+            let plan = Plan::DDL(DDLPlan::ExplainCost(ExplainCost { plan: query }));
+            return Ok(PlanWithPrivileges { plan, privileges });
+        }
+
+        let query = match plan {
+            Plan::Query(query) => query,
             _ => {
                 return Err(QueryError::NotImplemented {
                     err: "explain non-query statement.".to_string(),
@@ -440,34 +533,225 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             }
         };
 
-        let schema = LogicalPlan::explain_schema().to_dfschema_ref()?;
-
-        let df_plan = if analyze {
-            LogicalPlan::Analyze(Analyze {
-                verbose,
-                input: input_df_plan,
-                schema,
-            })
+        let query = if analyze {
+            QueryPlan {
+                df_plan: LogicalPlan::Analyze(Analyze {
+                    verbose,
+                    input: Arc::new(query.df_plan),
+                    schema: LogicalPlan::explain_schema().to_dfschema_ref()?,
+                }),
+                is_tag_scan: query.is_tag_scan,
+            }
         } else {
-            let stringified_plans =
-                vec![input_df_plan.to_stringified(PlanType::InitialLogicalPlan)];
-            LogicalPlan::Explain(Explain {
-                verbose,
-                plan: input_df_plan,
-                stringified_plans,
-                schema,
-                logical_optimization_succeeded: false,
-            })
+            query.explain(verbose)?
         };
 
-        let plan = Plan::Query(QueryPlan {
-            df_plan,
-            is_tag_scan,
+        let plan = Plan::Query(query);
+
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    /// Generate a plan for PREVIEW STREAM ... that runs one micro-batch of the query
+    async fn preview_stream_to_plan(
+        &self,
+        statement: ExtStatement,
+        session: &SessionCtx,
+        auth_enable: bool,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let PlanWithPrivileges { plan, privileges } = self
+            .statement_to_plan(statement, session, auth_enable)
+            .await?;
+
+        let Plan::Query(query) = plan else {
+            return Err(QueryError::NotImplemented {
+                err: "preview stream of non-query statement.".to_string(),
+            });
+        };
+        if query.is_explain() {
+            return Err(QueryError::NotImplemented {
+                err: "preview stream of explain statement.".to_string(),
+            });
+        }
+
+        let plan = Plan::PreviewStream(PreviewStream { plan: query });
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    /// Generate a plan for CREATE STREAM ... INTO <sink> AS <query>
+    async fn create_stream_to_plan(
+        &self,
+        stmt: ast::CreateStream,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateStream {
+            name,
+            trigger,
+            watermark,
+            output_mode,
+            sink,
+            statement,
+            ..
+        } = stmt;
+
+        let Some(sink) = sink else {
+            return Err(QueryError::NotImplemented {
+                err: "CREATE STREAM without INTO sink.".to_string(),
+            });
+        };
+        if watermark.is_some() {
+            return Err(QueryError::NotImplemented {
+                err: "WATERMARK of CREATE STREAM, define it on the stream table.".to_string(),
+            });
+        }
+        if output_mode.is_some_and(|mode| mode != ast::OutputMode::Append) {
+            return Err(QueryError::NotImplemented {
+                err: "OUTPUT_MODE of CREATE STREAM other than APPEND.".to_string(),
+            });
+        }
+        let trigger_interval = match trigger {
+            None => None,
+            Some(ast::Trigger::Once) => Some(StreamTriggerInterval::Once),
+            Some(ast::Trigger::Interval(interval)) => Some(
+                StreamTriggerInterval::from_str(&interval)
+                    .map_err(|reason| QueryError::InvalidParam { reason })?,
+            ),
+        };
+
+        let PlanWithPrivileges { plan, privileges } =
+            self.df_sql_to_plan(*statement, session).await?;
+        let Plan::Query(query) = plan else {
+            return Err(QueryError::NotImplemented {
+                err: "CREATE STREAM of non-query statement.".to_string(),
+            });
+        };
+        if query.is_explain() || is_dml(&query) {
+            return Err(QueryError::NotImplemented {
+                err: "CREATE STREAM ... INTO of explain or insert statement.".to_string(),
+            });
+        }
+
+        let plan = Plan::CreateStream(CreateStreamQuery {
+            name: normalize_ident(name),
+            plan: query,
+            trigger_interval,
+            sink,
+        });
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    fn drop_stream_to_plan(
+        &self,
+        stmt: ast::DropStream,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let plan = Plan::SYSTEM(SYSPlan::DropStream {
+            name: normalize_ident(stmt.name),
+            if_exist: stmt.if_exist,
         });
 
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(
+                    DatabasePrivilege::Write,
+                    Some(session.default_database().to_string()),
+                ),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    async fn create_continuous_query_to_plan(
+        &self,
+        stmt: ast::CreateContinuousQuery,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateContinuousQuery {
+            if_not_exists,
+            name,
+            every,
+            for_range,
+            target_table,
+            query,
+        } = stmt;
+
+        let parse_interval = |interval: &str| match StreamTriggerInterval::from_str(interval) {
+            Ok(StreamTriggerInterval::Interval(d)) if !d.is_zero() => Ok(d),
+            _ => Err(QueryError::InvalidParam {
+                reason: format!(
+                    "invalid interval '{}' of continuous query, such as '1m'",
+                    interval
+                ),
+            }),
+        };
+        let every = parse_interval(&every)?;
+        let for_range = match for_range {
+            Some(for_range) => parse_interval(&for_range)?,
+            None => every,
+        };
+
+        let query_sql = query.to_string();
+        let table_ref = normalize_sql_object_name(target_table.clone())?;
+        let PlanWithPrivileges { plan, privileges } = self
+            .insert_to_plan(target_table, vec![], query, session)
+            .await?;
+        let Plan::Query(plan) = plan else {
+            return Err(QueryError::NotImplemented {
+                err: "CREATE CONTINUOUS QUERY of non-query statement.".to_string(),
+            });
+        };
+
+        let plan = Plan::CreateContinuousQuery(CreateContinuousQuery {
+            if_not_exists,
+            name: normalize_ident(name),
+            target_table: table_ref.to_string(),
+            query: query_sql,
+            every,
+            for_range,
+            plan,
+        });
         Ok(PlanWithPrivileges { plan, privileges })
     }
 
+    fn drop_continuous_query_to_plan(
+        &self,
+        stmt: ast::DropContinuousQuery,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let database = session.default_database().to_string();
+        let plan = Plan::DDL(DDLPlan::DropContinuousQuery(DropContinuousQuery {
+            if_exist: stmt.if_exist,
+            name: normalize_ident(stmt.name),
+            database: database.clone(),
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(database)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    fn show_continuous_queries_to_plan(
+        &self,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let database = session.default_database().to_string();
+        let plan = Plan::DDL(DDLPlan::ShowContinuousQueries(ShowContinuousQueries {
+            database: database.clone(),
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(database)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     async fn insert_to_plan(
         &self,
         sql_object_name: ObjectName,
@@ -480,13 +764,13 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             .df_planner
             .sql_statement_to_plan(Statement::Query(source))?;
 
-        // save database read privileges
+        // save read privileges of the source
         // This operation must be done before fetching the target table metadata
-        let mut read_privileges = databases_privileges(
-            DatabasePrivilege::Read,
+        let mut read_privileges = query_privileges(
+            &source_plan,
             *session.tenant_id(),
             self.schema_provider.reset_access_databases(),
-        );
+        )?;
 
         let table_ref = normalize_sql_object_name(sql_object_name)?;
         let columns = sql_column_names
@@ -496,6 +780,8 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
         // Get the metadata of the target table
         let target_table = self.get_table_source(table_ref.clone())?;
+        let write_privilege =
+            table_write_privilege(*session.tenant_id(), target_table.as_ref(), &columns);
 
         let build_plan_func = || {
             LogicalPlanBuilder::from(source_plan)
@@ -513,11 +799,8 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         });
 
         // privileges
-        let mut write_privileges = databases_privileges(
-            DatabasePrivilege::Write,
-            *session.tenant_id(),
-            self.schema_provider.reset_access_databases(),
-        );
+        let _ = self.schema_provider.reset_access_databases();
+        let mut write_privileges = vec![write_privilege];
         write_privileges.append(&mut read_privileges);
         Ok(PlanWithPrivileges {
             plan,
@@ -567,17 +850,50 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                 }
             }
         };
+        // WHERE <selection>
+        let selection = self.delete_selection_to_expr(&table_name, selections, true)?;
+
+        let table_name = object_name_to_resolved_table(session, table_name)?;
+        let database = table_name.database().to_string();
+        let plan = Plan::DML(DMLPlan::DeleteFromTable(DeleteFromTable {
+            table_name,
+            selection,
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(database)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    /// Plan the selection of deleting from the tskv table, `now()` is evaluated
+    /// at planning, so it's rejected if `allow_now` is false.
+    fn delete_selection_to_expr(
+        &self,
+        table_name: &ObjectName,
+        selection: Option<SQLExpr>,
+        allow_now: bool,
+    ) -> QueryResult<Option<Expr>> {
         let table_ref = normalize_sql_object_name(table_name.clone())?;
         // only support delete from tskv table
         let schema = self.get_tskv_schema(table_ref)?;
         let df_schema = schema.to_arrow_schema().to_dfschema()?;
 
-        // WHERE <selection>
-        let selection = match selections {
+        let selection = match selection {
             Some(expr) => {
                 let sel = self
                     .df_planner
                     .sql_to_expr(expr, &df_schema, &mut Default::default())?;
+                if !allow_now && contains_now(&sel) {
+                    return Err(QueryError::InvalidParam {
+                        reason: "now() is not allowed in the selection of a scheduled delete, \
+                            use OLDER THAN instead"
+                            .to_string(),
+                    });
+                }
 
                 let mut rewriter = TypeCoercionRewriter::new(Arc::new(df_schema));
                 let expr = rewrite_preserving_name(sel, &mut rewriter)?;
@@ -591,11 +907,51 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
         valid_delete(schema.as_ref(), &selection)?;
 
+        Ok(selection)
+    }
+
+    fn create_scheduled_delete_to_plan(
+        &self,
+        stmt: ast::CreateScheduledDelete,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateScheduledDelete {
+            if_not_exists,
+            name,
+            cron,
+            older_than,
+            table_name,
+            selection,
+        } = stmt;
+
+        CronSchedule::parse(&cron).map_err(|reason| QueryError::InvalidParam { reason })?;
+        let older_than = match older_than {
+            Some(older_than) => match StreamTriggerInterval::from_str(&older_than) {
+                Ok(StreamTriggerInterval::Interval(d)) => Some(d),
+                _ => {
+                    return Err(QueryError::InvalidParam {
+                        reason: format!(
+                            "invalid duration '{}' of OLDER THAN, such as '7d'",
+                            older_than
+                        ),
+                    })
+                }
+            },
+            None => None,
+        };
+
+        let selection_sql = selection.as_ref().map(|expr| expr.to_string());
+        let selection = self.delete_selection_to_expr(&table_name, selection, false)?;
         let table_name = object_name_to_resolved_table(session, table_name)?;
         let database = table_name.database().to_string();
-        let plan = Plan::DML(DMLPlan::DeleteFromTable(DeleteFromTable {
+        let plan = Plan::DDL(DDLPlan::CreateScheduledDelete(CreateScheduledDelete {
+            if_not_exists,
+            name: normalize_ident(name),
             table_name,
+            cron,
+            older_than,
             selection,
+            selection_sql,
         }));
 
         Ok(PlanWithPrivileges {
@@ -607,6 +963,42 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn drop_scheduled_delete_to_plan(
+        &self,
+        stmt: ast::DropScheduledDelete,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let database = session.default_database().to_string();
+        let plan = Plan::DDL(DDLPlan::DropScheduledDelete(DropScheduledDelete {
+            if_exist: stmt.if_exist,
+            name: normalize_ident(stmt.name),
+            database: database.clone(),
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(database)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    fn show_jobs_to_plan(&self, session: &SessionCtx) -> QueryResult<PlanWithPrivileges> {
+        let database = session.default_database().to_string();
+        let plan = Plan::DDL(DDLPlan::ShowJobs(ShowJobs {
+            database: database.clone(),
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(database)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     fn drop_database_object_to_plan(
         &self,
         stmt: ast::DropDatabaseObject,
@@ -915,6 +1307,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             name,
             if_not_exists,
             columns,
+            append_only,
         } = statement;
         let id_generator = SeqIdGenerator::default();
         // all col: time col, tag col, field col
@@ -956,6 +1349,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             schema,
             name: resolved_table,
             if_not_exists,
+            append_only,
         }));
 
         // privilege
@@ -981,12 +1375,24 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         } else {
             let name = normalize_ident(column_opt.name);
             let column_type = self.make_data_type(&name, &column_opt.data_type, unit)?;
-            TableColumn::new(
+            if column_opt.encryption_key.is_some()
+                && !matches!(column_type, ColumnType::Field(ValueType::String))
+            {
+                return Err(QueryError::Semantic {
+                    err: format!("Only string field column {} can be encrypted", name),
+                });
+            }
+            let mut col = TableColumn::new(
                 id,
                 name,
                 column_type,
                 column_opt.encoding.unwrap_or_default(),
-            )
+            );
+            // Key names are case-insensitive.
+            col.encryption_key = column_opt
+                .encryption_key
+                .map(|key_name| key_name.to_ascii_lowercase());
+            col
         };
         Ok(col)
     }
@@ -1191,36 +1597,101 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     new_column,
                 }
             }
-            ASTAlterTableAction::RenameColumn {
-                old_column_name,
-                new_column_name,
+            ASTAlterTableAction::AlterColumnDefault {
+                column_name,
+                default,
             } => {
-                let old_column_name = normalize_ident(old_column_name);
-                let new_column_name = normalize_ident(new_column_name);
-                let column = table_schema.column(&old_column_name).ok_or_else(|| {
+                let column_name = normalize_ident(column_name);
+                let column = table_schema.column(&column_name).ok_or_else(|| {
                     QueryError::ColumnNotExists {
-                        column: old_column_name.clone(),
+                        column: column_name.to_string(),
                         table: table_schema.name.to_string(),
                     }
                 })?;
-                if table_schema.column(&new_column_name).is_some() {
-                    return Err(QueryError::ColumnAlreadyExists {
-                        column: new_column_name,
-                        table: table_schema.name.to_string(),
+                if !column.column_type.is_field() {
+                    return Err(QueryError::Semantic {
+                        err: format!("Only field column {} can have a default value", column_name),
                     });
                 }
 
-                if let ColumnType::Time(_) = column.column_type {
-                    return Err(QueryError::NotImplemented {
-                        err: "rename time column".to_string(),
-                    });
-                };
+                let mut new_column = column.clone();
+                new_column.default_value = default.map(sql_value_to_default_string).transpose()?;
+                new_column
+                    .default_field_value()
+                    .map_err(|e| QueryError::Semantic { err: e.to_string() })?;
+
+                AlterTableAction::AlterColumn {
+                    column_name,
+                    new_column,
+                }
+            }
+            ASTAlterTableAction::AlterColumnIndex {
+                column_name,
+                indexed,
+            } => {
+                let column_name = normalize_ident(column_name);
+                let column = table_schema.column(&column_name).ok_or_else(|| {
+                    QueryError::ColumnNotExists {
+                        column: column_name.to_string(),
+                        table: table_schema.name.to_string(),
+                    }
+                })?;
+                if column.column_type != ColumnType::Field(ValueType::String) {
+                    return Err(QueryError::Semantic {
+                        err: format!("Only string field column {} can be indexed", column_name),
+                    });
+                }
+
+                let mut new_column = column.clone();
+                new_column.indexed = indexed;
+
+                AlterTableAction::AlterColumn {
+                    column_name,
+                    new_column,
+                }
+            }
+            ASTAlterTableAction::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                let old_column_name = normalize_ident(old_column_name);
+                let new_column_name = normalize_ident(new_column_name);
+                let column = table_schema.column(&old_column_name).ok_or_else(|| {
+                    QueryError::ColumnNotExists {
+                        column: old_column_name.clone(),
+                        table: table_schema.name.to_string(),
+                    }
+                })?;
+                if table_schema.column(&new_column_name).is_some() {
+                    return Err(QueryError::ColumnAlreadyExists {
+                        column: new_column_name,
+                        table: table_schema.name.to_string(),
+                    });
+                }
+
+                if let ColumnType::Time(_) = column.column_type {
+                    return Err(QueryError::NotImplemented {
+                        err: "rename time column".to_string(),
+                    });
+                };
 
                 AlterTableAction::RenameColumn {
                     old_column_name,
                     new_column_name,
                 }
             }
+            ASTAlterTableAction::SetTtl { ttl } => {
+                let ttl = self.str_to_duration(&ttl)?;
+                if ttl.to_duration().is_some_and(|d| d.is_zero()) {
+                    return Err(QueryError::InvalidParam {
+                        reason: "TTL of table must be greater than 0".to_string(),
+                    });
+                }
+                // An infinite TTL falls back to the TTL of the database.
+                AlterTableAction::SetTtl {
+                    ttl: ttl.to_duration().map(|_| ttl),
+                }
+            }
         };
         let plan = Plan::DDL(DDLPlan::AlterTable(AlterTable {
             table_name,
@@ -1240,6 +1711,61 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn merge_tag_value_to_plan(
+        &self,
+        statement: ASTMergeTagValue,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let table_ref = normalize_sql_object_name(statement.table_name)?;
+        let table_name = table_ref
+            .clone()
+            .resolve_object(session.tenant(), session.default_database())?;
+        let table_schema = match self.get_table_handle(table_ref)? {
+            TableHandle::Tskv(t) => t.table_schema(),
+            _ => {
+                return Err(QueryError::NotImplemented {
+                    err: "only tskv table support merge tag".to_string(),
+                })
+            }
+        };
+
+        let tag_name = normalize_ident(statement.tag_name);
+        let column = table_schema
+            .column(&tag_name)
+            .ok_or_else(|| QueryError::ColumnNotExists {
+                column: tag_name.clone(),
+                table: table_schema.name.to_string(),
+            })?;
+        if !column.column_type.is_tag() {
+            return Err(QueryError::Semantic {
+                err: format!("Column {} is not a tag", tag_name),
+            });
+        }
+        if statement.from == statement.to {
+            return Err(QueryError::Semantic {
+                err: format!("Tag value '{}' is merged into itself", statement.from),
+            });
+        }
+
+        let plan = Plan::DDL(DDLPlan::MergeTagValue(MergeTagValue {
+            table_name,
+            tag_name,
+            from: statement.from,
+            to: statement.to,
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(
+                    DatabasePrivilege::Write,
+                    Some(table_schema.db.clone()),
+                ),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     fn show_databases_to_plan(&self, session: &SessionCtx) -> QueryResult<PlanWithPrivileges> {
         let projections = vec![col(DATABASES_DATABASE_NAME)];
         let sorts = vec![col(DATABASES_DATABASE_NAME).sort(true, true)];
@@ -1589,6 +2115,47 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         if let Some(vnode_duration) = options.vnode_duration {
             plan_options.with_vnode_duration(self.str_to_duration(&vnode_duration)?);
         }
+        if let Some(series_placement) = options.series_placement {
+            plan_options.with_series_placement(
+                SeriesPlacement::from_str(&series_placement).map_err(|err| QueryError::Parser {
+                    source: ParserError::ParserError(err),
+                })?,
+            );
+        }
+        if let Some(compaction) = options.compaction {
+            plan_options.with_compaction_strategy(
+                CompactionStrategyKind::from_str(&compaction).map_err(|err| {
+                    QueryError::Parser {
+                        source: ParserError::ParserError(err),
+                    }
+                })?,
+            );
+        }
+        if let Some(window) = options.compaction_window {
+            plan_options.with_compaction_window(self.str_to_duration(&window)?);
+        }
+        if let Some(file_sizes) = options.compaction_file_sizes {
+            let file_sizes = file_sizes
+                .split(',')
+                .map(|size| self.str_to_bytes(size.trim()))
+                .collect::<QueryResult<Vec<_>>>()?;
+            if file_sizes.len() > 4 || file_sizes.contains(&0) {
+                return Err(QueryError::Parser {
+                    source: ParserError::ParserError(
+                        "compaction_file_sizes should be at most 4 non-zero sizes of level-1 to level-4"
+                            .to_string(),
+                    ),
+                });
+            }
+            plan_options.with_compaction_file_sizes(file_sizes);
+        }
+        if let Some(time_zone) = options.time_zone {
+            plan_options.with_time_zone(parse_time_zone(&time_zone).map_err(|err| {
+                QueryError::Parser {
+                    source: ParserError::ParserError(err),
+                }
+            })?);
+        }
         Ok(plan_options)
     }
 
@@ -1617,14 +2184,11 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             plan_config.with_wal_max_file_size(self.str_to_bytes(&wal_max_file_size)?);
         }
         if let Some(wal_sync) = config.wal_sync {
-            plan_config.with_wal_sync(bool::from_str(wal_sync.as_str()).map_err(|_| {
-                QueryError::Parser {
-                    source: ParserError::ParserError(format!(
-                        "{} is not a valid bool value, use like 'true', 'false'",
-                        wal_sync
-                    )),
-                }
-            })?);
+            plan_config.with_wal_sync(WalSyncPolicy::from_str(wal_sync.as_str()).map_err(
+                |err| QueryError::Parser {
+                    source: ParserError::ParserError(err),
+                },
+            )?);
         }
         if let Some(strict_write) = config.strict_write {
             plan_config.with_strict_write(bool::from_str(strict_write.as_str()).map_err(|_| {
@@ -1903,6 +2467,12 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             AlterTenantOperation::UnSet(ident) => {
                 unset_option_to_alter_tenant_action(tenant, ident)?
             }
+            AlterTenantOperation::SetEncryptionKey(key_name, key) => {
+                encryption_key_to_alter_tenant_action(tenant, key_name, Some(key))?
+            }
+            AlterTenantOperation::UnSetEncryptionKey(key_name) => {
+                encryption_key_to_alter_tenant_action(tenant, key_name, None)?
+            }
         };
 
         Ok(alter_tenant_action_with_privileges)
@@ -2024,6 +2594,12 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     // 修改admin参数需要系统管理权限
                     privileges = vec![Privilege::Global(GlobalPrivilege::System)];
                 }
+                if sql_user_option.query_timeout().is_some()
+                    || sql_user_option.write_timeout().is_some()
+                {
+                    // Users can not loosen their own timeouts.
+                    privileges = vec![Privilege::Global(GlobalPrivilege::System)];
+                }
                 AlterUserAction::Set(sql_user_option)
             }
         };
@@ -2061,19 +2637,42 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             return Err(err);
         }
 
-        let database_privileges = privileges
-            .into_iter()
-            .map(|ast::Privilege { action, database }| {
-                let database_privilege = match action {
-                    ast::Action::Read => DatabasePrivilege::Read,
-                    ast::Action::Write => DatabasePrivilege::Write,
-                    ast::Action::All => DatabasePrivilege::Full,
-                };
-                let database_name = normalize_ident(database);
-
-                (database_privilege, database_name)
-            })
-            .collect::<Vec<(DatabasePrivilege, String)>>();
+        let mut database_privileges = vec![];
+        let mut table_privileges = vec![];
+        for ast::Privilege { action, object } in privileges {
+            let privilege = match action {
+                ast::Action::Read => DatabasePrivilege::Read,
+                ast::Action::Write => DatabasePrivilege::Write,
+                ast::Action::All => DatabasePrivilege::Full,
+                ast::Action::Decrypt => DatabasePrivilege::Decrypt,
+            };
+            match object {
+                ast::PrivilegeObject::Database(database) => {
+                    database_privileges.push((privilege, normalize_ident(database)));
+                }
+                ast::PrivilegeObject::Table { .. } if privilege == DatabasePrivilege::Decrypt => {
+                    return Err(QueryError::Semantic {
+                        err: "Decrypt can only be granted on databases".to_string(),
+                    });
+                }
+                ast::PrivilegeObject::Table { columns, .. } if !is_grant && !columns.is_empty() => {
+                    return Err(QueryError::Semantic {
+                        err: "Privileges are revoked from whole tables, remove the columns"
+                            .to_string(),
+                    });
+                }
+                ast::PrivilegeObject::Table { name, columns } => {
+                    let resolved_table = object_name_to_resolved_table(session, name)?;
+                    let columns = (!columns.is_empty())
+                        .then(|| columns.into_iter().map(normalize_ident).collect());
+                    table_privileges.push((
+                        TablePrivilege::new(privilege, columns),
+                        resolved_table.database().to_string(),
+                        resolved_table.table().to_string(),
+                    ));
+                }
+            }
+        }
 
         let privileges = vec![Privilege::TenantObject(
             TenantObjectPrivilege::RoleFull,
@@ -2083,6 +2682,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let plan = Plan::DDL(DDLPlan::GrantRevoke(GrantRevoke {
             is_grant,
             database_privileges,
+            table_privileges,
             tenant_name: tenant_name.to_string(),
             role_name,
         }));
@@ -2090,6 +2690,40 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         Ok(PlanWithPrivileges { plan, privileges })
     }
 
+    fn show_functions_to_plan(&self, pattern: Option<String>) -> QueryResult<PlanWithPrivileges> {
+        let projections = vec![
+            col(FUNCTIONS_FUNCTION_NAME),
+            col(FUNCTIONS_FUNCTION_TYPE),
+            col(FUNCTIONS_ARGUMENT_TYPES),
+            col(FUNCTIONS_RETURN_TYPE),
+            col(FUNCTIONS_DESCRIPTION),
+        ];
+        let sorts = vec![col(FUNCTIONS_FUNCTION_NAME).sort(true, true)];
+
+        let table_ref = TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_FUNCTIONS);
+
+        let table_source = self.get_table_source(table_ref.clone())?;
+
+        let mut builder = LogicalPlanBuilder::scan(table_ref, table_source, None)?;
+        if let Some(pattern) = pattern {
+            // Function names are case insensitive.
+            builder = builder
+                .filter(lower(col(FUNCTIONS_FUNCTION_NAME)).like(lit(pattern.to_lowercase())))?;
+        }
+        let df_plan = builder.project(projections)?.sort(sorts)?.build()?;
+
+        let plan = Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan: false,
+        });
+
+        // All users could see the functions.
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![],
+        })
+    }
+
     fn show_queries_to_plan(&self, session: &SessionCtx) -> QueryResult<PlanWithPrivileges> {
         // QUERY_SCHEMA: query_id, query_type, query_text, user_name, tenant_name,database_name, state, duration
         let projections = vec![0, 1, 2, 4, 6, 7, 8, 9];
@@ -2118,104 +2752,404 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
-    fn drop_vnode_to_plan(&self, stmt: ASTDropVnode) -> QueryResult<PlanWithPrivileges> {
-        let ASTDropVnode { vnode_id } = stmt;
+    fn show_runtime_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
+        let table_ref =
+            TableReference::full(DEFAULT_CATALOG, CLUSTER_SCHEMA, CLUSTER_SCHEMA_RUNTIME);
+
+        let table_source = self.get_table_source(table_ref.clone())?;
+
+        let df_plan = LogicalPlanBuilder::scan(table_ref, table_source, None)?.build()?;
+
+        let plan = Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan: false,
+        });
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn drop_vnode_to_plan(&self, stmt: ASTDropVnode) -> QueryResult<PlanWithPrivileges> {
+        let ASTDropVnode { vnode_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::DropVnode(DropVnode { vnode_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn copy_vnode_to_plan(&self, stmt: ASTCopyVnode) -> QueryResult<PlanWithPrivileges> {
+        let ASTCopyVnode { vnode_id, node_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::CopyVnode(CopyVnode { vnode_id, node_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn move_vnode_to_plan(&self, stmt: ASTMoveVnode) -> QueryResult<PlanWithPrivileges> {
+        let ASTMoveVnode { vnode_id, node_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::MoveVnode(MoveVnode { vnode_id, node_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn compact_vnode_to_plan(&self, stmt: ASTCompactVnode) -> QueryResult<PlanWithPrivileges> {
+        let ASTCompactVnode { vnode_ids } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::CompactVnode(CompactVnode { vnode_ids }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn compact_database_to_plan(
+        &self,
+        stmt: ASTCompactDatabase,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ASTCompactDatabase { database_name } = stmt;
+
+        let database_name = normalize_ident(database_name);
+        let db = self
+            .schema_provider
+            .get_db_info(&database_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::DatabaseNotFound {
+                name: database_name.clone(),
+            })?;
+
+        let vnode_ids = db
+            .buckets
+            .iter()
+            .flat_map(|bucket| {
+                bucket
+                    .shard_group
+                    .iter()
+                    .flat_map(|group| group.vnodes.iter().map(|vnode| vnode.id))
+            })
+            .collect::<Vec<_>>();
+
+        let plan = Plan::DDL(DDLPlan::CompactVnode(CompactVnode { vnode_ids }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn close_vnode_files_to_plan(
+        &self,
+        stmt: ASTCloseVnodeFiles,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ASTCloseVnodeFiles { vnode_ids } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::CloseVnodeFiles(CloseVnodeFiles { vnode_ids }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn explain_compaction_to_plan(
+        &self,
+        stmt: ASTExplainCompaction,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ASTExplainCompaction { vnode_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::ExplainCompaction(ExplainCompaction { vnode_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn kill_compaction_to_plan(&self, stmt: ASTKillCompaction) -> QueryResult<PlanWithPrivileges> {
+        let ASTKillCompaction { job_id, node_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::KillCompaction(KillCompaction { job_id, node_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn checksum_group_to_plan(&self, stmt: ASTChecksumGroup) -> QueryResult<PlanWithPrivileges> {
+        let ASTChecksumGroup { replication_set_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::ChecksumGroup(ChecksumGroup { replication_set_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn repair_replica_to_plan(&self, stmt: ASTRepairReplica) -> QueryResult<PlanWithPrivileges> {
+        let ASTRepairReplica { replication_set_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::RepairReplica(RepairReplica { replication_set_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn show_cardinality_to_plan(
+        &self,
+        stmt: ASTShowCardinality,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ASTShowCardinality {
+            top_k,
+            database_name,
+        } = stmt;
+
+        let database_name = normalize_ident(database_name);
+        self.schema_provider
+            .get_db_info(&database_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::DatabaseNotFound {
+                name: database_name.clone(),
+            })?;
+
+        let plan = Plan::DDL(DDLPlan::ShowCardinality(ShowCardinality {
+            database: database_name.clone(),
+            top_k: top_k as usize,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(database_name)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    fn show_shard_skew_to_plan(
+        &self,
+        stmt: ASTShowShardSkew,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let database_name = normalize_ident(stmt.database_name);
+        self.schema_provider
+            .get_db_info(&database_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::DatabaseNotFound {
+                name: database_name.clone(),
+            })?;
+
+        let plan = Plan::DDL(DDLPlan::ShowShardSkew(ShowShardSkew {
+            database: database_name.clone(),
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(database_name)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    fn export_vnode_to_plan(&self, stmt: ASTExportVnode) -> QueryResult<PlanWithPrivileges> {
+        let ASTExportVnode {
+            vnode_id,
+            start_time,
+            end_time,
+            location:
+                UriLocation {
+                    path,
+                    connection_options,
+                },
+        } = stmt;
+
+        let time_range = TimeRange::new(
+            sql_value_to_timestamp_nanos(start_time)?,
+            sql_value_to_timestamp_nanos(end_time)?,
+        );
+        if time_range.min_ts > time_range.max_ts {
+            return Err(QueryError::Semantic {
+                err: format!("Invalid time range of export vnode: {}", time_range),
+            });
+        }
+
+        // Check the connection options before sending to the data node.
+        check_object_store_url(&path, &connection_options)?;
+
+        let plan = Plan::DDL(DDLPlan::ExportVnode(ExportVnode {
+            vnode_id,
+            time_range,
+            path,
+            connection_options,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn backup_database_to_plan(&self, stmt: ASTBackupDatabase) -> QueryResult<PlanWithPrivileges> {
+        let ASTBackupDatabase {
+            database_name,
+            location:
+                UriLocation {
+                    path,
+                    connection_options,
+                },
+        } = stmt;
 
-        let plan = Plan::DDL(DDLPlan::DropVnode(DropVnode { vnode_id }));
+        let database_name = normalize_ident(database_name);
+        self.schema_provider
+            .get_db_info(&database_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::DatabaseNotFound {
+                name: database_name.clone(),
+            })?;
+        check_object_store_url(&path, &connection_options)?;
+
+        let plan = Plan::DDL(DDLPlan::BackupDatabase(BackupDatabase {
+            database: database_name,
+            path,
+            connection_options,
+        }));
         Ok(PlanWithPrivileges {
             plan,
             privileges: vec![Privilege::Global(GlobalPrivilege::System)],
         })
     }
 
-    fn copy_vnode_to_plan(&self, stmt: ASTCopyVnode) -> QueryResult<PlanWithPrivileges> {
-        let ASTCopyVnode { vnode_id, node_id } = stmt;
+    fn restore_database_to_plan(
+        &self,
+        stmt: ASTRestoreDatabase,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ASTRestoreDatabase {
+            location:
+                UriLocation {
+                    path,
+                    connection_options,
+                },
+        } = stmt;
 
-        let plan = Plan::DDL(DDLPlan::CopyVnode(CopyVnode { vnode_id, node_id }));
+        check_object_store_url(&path, &connection_options)?;
+
+        let plan = Plan::DDL(DDLPlan::RestoreDatabase(RestoreDatabase {
+            path,
+            connection_options,
+        }));
         Ok(PlanWithPrivileges {
             plan,
             privileges: vec![Privilege::Global(GlobalPrivilege::System)],
         })
     }
 
-    fn move_vnode_to_plan(&self, stmt: ASTMoveVnode) -> QueryResult<PlanWithPrivileges> {
-        let ASTMoveVnode { vnode_id, node_id } = stmt;
-
-        let plan = Plan::DDL(DDLPlan::MoveVnode(MoveVnode { vnode_id, node_id }));
+    fn show_replicas_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
+        let plan = Plan::DDL(DDLPlan::ShowReplicas);
         Ok(PlanWithPrivileges {
             plan,
             privileges: vec![Privilege::Global(GlobalPrivilege::System)],
         })
     }
 
-    fn compact_vnode_to_plan(&self, stmt: ASTCompactVnode) -> QueryResult<PlanWithPrivileges> {
-        let ASTCompactVnode { vnode_ids } = stmt;
+    fn vnodes_plan_builder(&self) -> QueryResult<LogicalPlanBuilder> {
+        let table_ref = TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_VNODES);
+        let table_source = self.get_table_source(table_ref.clone())?;
+        Ok(LogicalPlanBuilder::scan(table_ref, table_source, None)?)
+    }
 
-        let plan = Plan::DDL(DDLPlan::CompactVnode(CompactVnode { vnode_ids }));
+    fn vnodes_to_query_plan(builder: LogicalPlanBuilder) -> QueryResult<Plan> {
+        let projections = vec![
+            col(VNODES_DATABASE_NAME),
+            col(VNODES_REPLICA_ID),
+            col(VNODES_VNODE_ID),
+            col(VNODES_NODE_ID),
+            col(VNODES_STATUS),
+            col(VNODES_ROLE),
+            col(VNODES_DISK_STORAGE),
+        ];
+        let sorts = vec![
+            col(VNODES_DATABASE_NAME).sort(true, true),
+            col(VNODES_REPLICA_ID).sort(true, true),
+            col(VNODES_VNODE_ID).sort(true, true),
+        ];
+        let df_plan = builder.project(projections)?.sort(sorts)?.build()?;
+
+        Ok(Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan: false,
+        }))
+    }
+
+    fn show_vnodes_to_plan(
+        &self,
+        stmt: ASTShowVnodes,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let builder = self.vnodes_plan_builder()?;
+        let builder = match stmt.node_id {
+            Some(node_id) => builder.filter(col(VNODES_NODE_ID).eq(lit(node_id)))?,
+            None => builder,
+        };
+        let plan = Self::vnodes_to_query_plan(builder)?;
+
+        // privileges
         Ok(PlanWithPrivileges {
             plan,
-            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, None),
+                Some(*session.tenant_id()),
+            )],
         })
     }
 
-    fn compact_database_to_plan(
+    fn show_database_replicas_to_plan(
         &self,
-        stmt: ASTCompactDatabase,
+        stmt: ASTShowDatabaseReplicas,
+        session: &SessionCtx,
     ) -> QueryResult<PlanWithPrivileges> {
-        let ASTCompactDatabase { database_name } = stmt;
-
-        let database_name = normalize_ident(database_name);
-        let db = self
-            .schema_provider
+        let database_name = normalize_ident(stmt.database_name);
+        self.schema_provider
             .get_db_info(&database_name)
             .context(MetaSnafu)?
             .ok_or_else(|| QueryError::DatabaseNotFound {
                 name: database_name.clone(),
             })?;
 
-        let vnode_ids = db
-            .buckets
-            .iter()
-            .flat_map(|bucket| {
-                bucket
-                    .shard_group
-                    .iter()
-                    .flat_map(|group| group.vnodes.iter().map(|vnode| vnode.id))
-            })
-            .collect::<Vec<_>>();
+        let builder = self
+            .vnodes_plan_builder()?
+            .filter(col(VNODES_DATABASE_NAME).eq(lit(database_name.as_str())))?;
+        let plan = Self::vnodes_to_query_plan(builder)?;
 
-        let plan = Plan::DDL(DDLPlan::CompactVnode(CompactVnode { vnode_ids }));
+        // privileges
         Ok(PlanWithPrivileges {
             plan,
-            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(database_name)),
+                Some(*session.tenant_id()),
+            )],
         })
     }
 
-    fn checksum_group_to_plan(&self, stmt: ASTChecksumGroup) -> QueryResult<PlanWithPrivileges> {
-        let ASTChecksumGroup { replication_set_id } = stmt;
-
-        let plan = Plan::DDL(DDLPlan::ChecksumGroup(ChecksumGroup { replication_set_id }));
-        Ok(PlanWithPrivileges {
-            plan,
-            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
-        })
-    }
+    fn replica_destory_to_plan(&self, stmt: ASTReplicaDestory) -> QueryResult<PlanWithPrivileges> {
+        let ASTReplicaDestory { replica_id } = stmt;
 
-    fn show_replicas_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
-        let plan = Plan::DDL(DDLPlan::ShowReplicas);
+        let plan = Plan::DDL(DDLPlan::ReplicaDestory(ReplicaDestory { replica_id }));
         Ok(PlanWithPrivileges {
             plan,
             privileges: vec![Privilege::Global(GlobalPrivilege::System)],
         })
     }
 
-    fn replica_destory_to_plan(&self, stmt: ASTReplicaDestory) -> QueryResult<PlanWithPrivileges> {
-        let ASTReplicaDestory { replica_id } = stmt;
-
-        let plan = Plan::DDL(DDLPlan::ReplicaDestory(ReplicaDestory { replica_id }));
+    fn set_variable_to_plan(&self, name: &str, value: &str) -> QueryResult<PlanWithPrivileges> {
+        let variable =
+            SessionVariable::parse(name, value).map_err(|err| QueryError::Semantic { err })?;
         Ok(PlanWithPrivileges {
-            plan,
-            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+            plan: Plan::SYSTEM(SYSPlan::SetVariable(variable)),
+            privileges: vec![],
         })
     }
 
@@ -2223,11 +3157,13 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let ASTReplicaAdd {
             replica_id,
             node_id,
+            learner,
         } = stmt;
 
         let plan = Plan::DDL(DDLPlan::ReplicaAdd(ReplicaAdd {
             replica_id,
             node_id,
+            learner,
         }));
         Ok(PlanWithPrivileges {
             plan,
@@ -2364,6 +3300,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             .build();
 
         let tenant_id = *session.tenant_id();
+        let parallelism = copy_options.parallelism;
 
         match copy_target {
             CopyTarget::IntoTable(stmt) => {
@@ -2377,29 +3314,31 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     external_location_table,
                     target_table.clone(),
                     insert_columns.as_ref(),
+                    parallelism,
                 )?;
 
                 Ok(PlanWithPrivileges {
                     plan,
-                    privileges: vec![Privilege::TenantObject(
-                        TenantObjectPrivilege::Database(
-                            DatabasePrivilege::Write,
-                            Some(target_table.database_name().into()),
-                        ),
-                        Some(tenant_id),
+                    privileges: vec![table_write_privilege(
+                        tenant_id,
+                        target_table.as_ref(),
+                        &insert_columns,
                     )],
                 })
             }
             CopyTarget::IntoLocation(stmt) => {
                 // .   TableWriterPlanNode
                 //         Plan.....
-                let plan = self
+                let df_plan = self
                     .copy_into_location(session, stmt, file_format_options)
                     .await?;
 
                 let database_set = self.schema_provider.reset_access_databases();
-                let privileges =
-                    databases_privileges(DatabasePrivilege::Read, tenant_id, database_set);
+                let privileges = query_privileges(&df_plan, tenant_id, database_set)?;
+                let plan = Plan::Query(QueryPlan {
+                    df_plan,
+                    is_tag_scan: false,
+                });
                 Ok(PlanWithPrivileges { plan, privileges })
             }
         }
@@ -2426,7 +3365,10 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             connection_options,
         } = location;
 
-        let CopyOptions { auto_infer_schema } = copy_options;
+        let CopyOptions {
+            auto_infer_schema,
+            parallelism,
+        } = copy_options;
 
         let table_path = ListingTableUrl::parse(path)?;
         let insert_columns = columns.into_iter().map(normalize_ident).collect::<Vec<_>>();
@@ -2465,6 +3407,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             table_path,
             default_schema,
             file_format_options,
+            parallelism,
         )
         .await?;
 
@@ -2480,8 +3423,12 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         session: &SessionCtx,
         stmt: ast::CopyIntoLocation,
         file_format_options: FileFormatOptions,
-    ) -> QueryResult<Plan> {
-        let ast::CopyIntoLocation { from, location } = stmt;
+    ) -> QueryResult<LogicalPlan> {
+        let ast::CopyIntoLocation {
+            from,
+            location,
+            report_bytes,
+        } = stmt;
         let UriLocation {
             path,
             connection_options,
@@ -2506,18 +3453,20 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             table_path,
             Some(source_schem),
             file_format_options,
+            None,
         )
         .await?;
 
         // 4. build final plan
-        let df_plan = LogicalPlanBuilder::from(source_plan)
-            .write(target_table, TEMP_LOCATION_TABLE_NAME, Default::default())?
-            .build()?;
+        let builder = LogicalPlanBuilder::from(source_plan);
+        let df_plan = if report_bytes {
+            builder.write_to_location(target_table, TEMP_LOCATION_TABLE_NAME)?
+        } else {
+            builder.write(target_table, TEMP_LOCATION_TABLE_NAME, Default::default())?
+        }
+        .build()?;
 
-        Ok(Plan::Query(QueryPlan {
-            df_plan,
-            is_tag_scan: false,
-        }))
+        Ok(df_plan)
     }
 
     fn create_table_relation(
@@ -2606,19 +3555,27 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
     }
 }
 
+/// If `parallelism` is specified, the scan is repartitioned into exactly that many
+/// partitions, each of them writes its batches to tskv one after another, so at most
+/// `parallelism` writes are in flight and reading is throttled by the writing.
 fn build_copy_into_table_plan(
     external_location_table: Arc<dyn TableSource>,
     target_table: Arc<TableSourceAdapter>,
     insert_columns: &[String],
+    parallelism: Option<usize>,
 ) -> DFResult<Plan> {
-    let df_plan =
-        LogicalPlanBuilder::scan(TEMP_LOCATION_TABLE_NAME, external_location_table, None)?
-            .write(
-                target_table.clone(),
-                target_table.table_name(),
-                insert_columns,
-            )?
-            .build()?;
+    let mut builder =
+        LogicalPlanBuilder::scan(TEMP_LOCATION_TABLE_NAME, external_location_table, None)?;
+    if let Some(parallelism) = parallelism {
+        builder = builder.repartition(Partitioning::RoundRobinBatch(parallelism))?;
+    }
+    let df_plan = builder
+        .write(
+            target_table.clone(),
+            target_table.table_name(),
+            insert_columns,
+        )?
+        .build()?;
 
     debug!("Copy into table plan:\n{}", df_plan.display_indent_schema());
 
@@ -2657,6 +3614,35 @@ fn build_and_register_object_store(
     Ok(())
 }
 
+/// Numbers are taken as nanoseconds, strings are parsed as timestamps.
+fn sql_value_to_timestamp_nanos(value: SQLValue) -> QueryResult<i64> {
+    match value {
+        SQLValue::Number(n, _) => n.parse::<i64>().map_err(|e| QueryError::Semantic {
+            err: format!("Invalid timestamp {}: {}", n, e),
+        }),
+        SQLValue::SingleQuotedString(s) => {
+            string_to_timestamp_nanos(&s).map_err(|e| QueryError::Semantic {
+                err: format!("Invalid timestamp '{}': {}", s, e),
+            })
+        }
+        v => Err(QueryError::Semantic {
+            err: format!("Expected timestamp, but found: {}", v),
+        }),
+    }
+}
+
+/// Numbers, strings and booleans are kept as their literal text.
+fn sql_value_to_default_string(value: SQLValue) -> QueryResult<String> {
+    match value {
+        SQLValue::Number(n, _) => Ok(n),
+        SQLValue::SingleQuotedString(s) | SQLValue::DoubleQuotedString(s) => Ok(s),
+        SQLValue::Boolean(b) => Ok(b.to_string()),
+        v => Err(QueryError::Semantic {
+            err: format!("Unsupported default value: {}", v),
+        }),
+    }
+}
+
 fn build_object_store(
     schema: &str,
     bucket: Option<&str>,
@@ -2670,15 +3656,36 @@ fn build_object_store(
         .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())
 }
 
+/// Check the path and connection options of an object store, e.g. before
+/// sending them to the data nodes.
+fn check_object_store_url(path: &str, connection_options: &[SqlOption]) -> QueryResult<()> {
+    let table_path = ListingTableUrl::parse(path)?;
+    let url: &Url = table_path.as_ref();
+    build_object_store(
+        table_path.scheme(),
+        url.host_str(),
+        connection_options.to_vec(),
+    )?;
+
+    Ok(())
+}
+
 async fn build_external_location_table_source(
     ctx: &SessionCtx,
     table_path: ListingTableUrl,
     default_schema: Option<SchemaRef>,
     file_format_options: FileFormatOptions,
+    target_partitions: Option<usize>,
 ) -> datafusion::common::Result<Arc<dyn TableSource>> {
     let file_format = build_file_format(file_format_options)?;
-    let external_location_table =
-        build_listing_table(ctx.inner(), table_path, default_schema, file_format).await?;
+    let external_location_table = build_listing_table(
+        ctx.inner(),
+        table_path,
+        default_schema,
+        file_format,
+        target_partitions,
+    )
+    .await?;
 
     let external_location_table_source = Arc::new(TableSourceAdapter::try_new(
         TableReference::bare(TEMP_LOCATION_TABLE_NAME),
@@ -2695,10 +3702,12 @@ async fn build_listing_table(
     table_path: ListingTableUrl,
     default_schema: Option<SchemaRef>,
     file_format: Arc<dyn FileFormat>,
+    target_partitions: Option<usize>,
 ) -> datafusion::common::Result<Arc<ListingTable>> {
+    let target_partitions = target_partitions.unwrap_or_else(|| ctx.config().target_partitions());
     let options = ListingOptions::new(file_format)
         .with_collect_stat(ctx.config().collect_statistics())
-        .with_target_partitions(ctx.config().target_partitions());
+        .with_target_partitions(target_partitions);
 
     let schema = if let Some(schema) = default_schema {
         schema
@@ -2925,6 +3934,21 @@ fn check_privilege(user: &User, privileges: Vec<Privilege<Oid>>) -> QueryResult<
     Ok(())
 }
 
+/// The value of `SET name = value`, it's a literal, `DEFAULT` or an identifier.
+fn set_variable_value(value: &SQLExpr) -> QueryResult<String> {
+    match value {
+        SQLExpr::Value(SQLValue::SingleQuotedString(s) | SQLValue::DoubleQuotedString(s)) => {
+            Ok(s.clone())
+        }
+        SQLExpr::Value(SQLValue::Number(n, _)) => Ok(n.clone()),
+        SQLExpr::Value(SQLValue::Boolean(b)) => Ok(b.to_string()),
+        SQLExpr::Identifier(ident) => Ok(ident.value.clone()),
+        _ => Err(QueryError::Semantic {
+            err: format!("Unsupported value of SET: {}", value),
+        }),
+    }
+}
+
 fn databases_privileges(
     db_priv: DatabasePrivilege,
     tenant_id: Oid,
@@ -2943,6 +3967,78 @@ fn databases_privileges(
         .collect()
 }
 
+/// The write privilege of the columns of the table, all columns if `columns`
+/// is empty.
+fn table_write_privilege(
+    tenant_id: Oid,
+    table: &TableSourceAdapter,
+    columns: &[String],
+) -> Privilege<Oid> {
+    let columns = if columns.is_empty() {
+        table
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect()
+    } else {
+        columns.iter().cloned().collect()
+    };
+    Privilege::TenantObject(
+        TenantObjectPrivilege::Table(
+            TablePrivilege::new(DatabasePrivilege::Write, Some(columns)),
+            table.database_name().to_string(),
+            table.table_name().to_string(),
+        ),
+        Some(tenant_id),
+    )
+}
+
+/// The read privileges of a query, the tables scanned by the plan require
+/// the privileges of the referenced columns, the other accessed databases
+/// require the privileges of the whole database.
+fn query_privileges(
+    plan: &LogicalPlan,
+    tenant_id: Oid,
+    databases: DatabaseSet,
+) -> QueryResult<Vec<Privilege<Oid>>> {
+    let mut table_columns = extract_table_columns(plan)?;
+
+    let mut privileges = vec![];
+    for db in databases.dbs() {
+        let scanned_tables = databases
+            .table_set(db)
+            .map(|t| t.tables())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|t| {
+                table_columns
+                    .remove(&(db.clone(), t.clone()))
+                    .map(|columns| (t.clone(), columns))
+            })
+            .collect::<Vec<_>>();
+        if scanned_tables.is_empty() {
+            privileges.push(Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(db.clone())),
+                Some(tenant_id),
+            ));
+            continue;
+        }
+        for (table, columns) in scanned_tables {
+            privileges.push(Privilege::TenantObject(
+                TenantObjectPrivilege::Table(
+                    TablePrivilege::new(DatabasePrivilege::Read, Some(columns)),
+                    db.clone(),
+                    table,
+                ),
+                Some(tenant_id),
+            ));
+        }
+    }
+
+    Ok(privileges)
+}
+
 fn extract_database_table_name<'a>(
     full_name: &'a str,
     session: &'a SessionCtx,
@@ -3073,6 +4169,23 @@ fn make_geometry_data_type(params: &[String]) -> std::result::Result<ColumnType,
 ///
 /// - 只能对tskv表执行delete操作
 /// - 过滤条件中不能包含field列
+/// Whether the expression calls `now()`.
+fn contains_now(expr: &Expr) -> bool {
+    let mut found = false;
+    let _ = expr.apply(&mut |e| {
+        if let Expr::ScalarFunction(ScalarFunction {
+            fun: BuiltinScalarFunction::Now,
+            ..
+        }) = e
+        {
+            found = true;
+            return Ok(VisitRecursion::Stop);
+        }
+        Ok(VisitRecursion::Continue)
+    });
+    found
+}
+
 fn valid_delete(schema: &TskvTableSchema, selection: &Option<Expr>) -> QueryResult<()> {
     if let Some(expr) = selection {
         let using_columns = expr.to_columns()?;
@@ -3101,7 +4214,7 @@ fn valid_delete(schema: &TskvTableSchema, selection: &Option<Expr>) -> QueryResu
 #[cfg(test)]
 mod tests {
     use std::any::Any;
-    use std::sync::Arc;
+    use std::sync::{Arc, OnceLock};
     use std::time::Duration;
 
     use coordinator::service_mock::MockCoordinator;
@@ -3185,19 +4298,7 @@ mod tests {
 
     impl ContextProvider for MockContext {
         fn get_table_provider(&self, name: TableReference) -> Result<Arc<dyn TableSource>> {
-            let schema = match name.table() {
-                "test_tb" => Ok(Schema::new(vec![
-                    Field::new("field_int", DataType::Int32, false),
-                    Field::new("field_string", DataType::Utf8, false),
-                ])),
-                _ => {
-                    unimplemented!("use test_tb for test")
-                }
-            };
-            match schema {
-                Ok(tb) => Ok(Arc::new(TestTable::new(Arc::new(tb)))),
-                Err(e) => Err(e),
-            }
+            Ok(self.get_table_source(name)? as Arc<dyn TableSource>)
         }
 
         fn get_function_meta(&self, _name: &str) -> Option<Arc<ScalarUDF>> {
@@ -3213,7 +4314,8 @@ mod tests {
         }
 
         fn options(&self) -> &datafusion::config::ConfigOptions {
-            unimplemented!()
+            static OPTIONS: OnceLock<datafusion::config::ConfigOptions> = OnceLock::new();
+            OPTIONS.get_or_init(Default::default)
         }
 
         fn get_window_meta(&self, _name: &str) -> Option<Arc<datafusion::logical_expr::WindowUDF>> {
@@ -3334,54 +4436,79 @@ mod tests {
                             name: "time".to_string(),
                             column_type: ColumnType::Time(Nanosecond),
                             encoding: Encoding::Default,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 1,
                             name: "column6".to_string(),
                             column_type: ColumnType::Tag,
                             encoding: Encoding::Default,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 2,
                             name: "column7".to_string(),
                             column_type: ColumnType::Tag,
                             encoding: Encoding::Default,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 3,
                             name: "column1".to_string(),
                             column_type: ColumnType::Field(ValueType::Integer),
                             encoding: Encoding::Delta,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 4,
                             name: "column2".to_string(),
                             column_type: ColumnType::Field(ValueType::String),
                             encoding: Encoding::Gzip,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 5,
                             name: "column3".to_string(),
                             column_type: ColumnType::Field(ValueType::Unsigned),
                             encoding: Encoding::Null,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 6,
                             name: "column4".to_string(),
                             column_type: ColumnType::Field(ValueType::Boolean),
                             encoding: Encoding::Default,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                         TableColumn {
                             id: 7,
                             name: "column5".to_string(),
                             column_type: ColumnType::Field(ValueType::Float),
                             encoding: Encoding::Gorilla,
+                            default_value: None,
+                            encryption_key: None,
+                            indexed: false,
                         },
                     ],
                     name: TableReference::parse_str("default_schema.test")
                         .resolve_object("cnosdb", "default_schema")
                         .unwrap(),
                     if_not_exists: true,
+                    append_only: false,
                 }
             );
         } else {
@@ -3490,6 +4617,9 @@ mod tests {
                     name: "time".to_string(),
                     column_type: ColumnType::Time(Nanosecond),
                     encoding: Encoding::Default,
+                    default_value: None,
+                    encryption_key: None,
+                    indexed: false,
                 },
                 TableColumn {
                     id: 1,
@@ -3499,6 +4629,9 @@ mod tests {
                         0,
                     ))),
                     encoding: Encoding::Default,
+                    default_value: None,
+                    encryption_key: None,
+                    indexed: false,
                 },
             ];
             let expected = CreateTable {
@@ -3507,6 +4640,7 @@ mod tests {
                     .resolve_object("cnosdb", "public")
                     .unwrap(),
                 if_not_exists: false,
+                append_only: false,
             };
 
             assert_eq!(expected, create)
@@ -3551,4 +4685,159 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    async fn query_plan(sql: &str) -> LogicalPlan {
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap();
+        match plan.plan {
+            Plan::Query(QueryPlan { df_plan, .. }) => df_plan,
+            _ => panic!("expected query plan"),
+        }
+    }
+
+    fn read_columns(columns: &[&str]) -> Privilege<Oid> {
+        Privilege::TenantObject(
+            TenantObjectPrivilege::Table(
+                TablePrivilege::new(
+                    DatabasePrivilege::Read,
+                    Some(columns.iter().map(|c| c.to_string()).collect()),
+                ),
+                "public".to_string(),
+                "test_tb".to_string(),
+            ),
+            Some(0),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_extract_table_columns() {
+        let table = ("public".to_string(), "test_tb".to_string());
+        let cases = [
+            ("select field_int from test_tb", vec!["field_int"]),
+            ("select * from test_tb", vec!["field_int", "field_string"]),
+            (
+                "select field_int from test_tb where field_string = 'a'",
+                vec!["field_int", "field_string"],
+            ),
+            (
+                "select field_int from test_tb where field_int in \
+                    (select field_int from test_tb where field_string = 'a')",
+                vec!["field_int", "field_string"],
+            ),
+        ];
+        for (sql, expected) in cases {
+            let columns = extract_table_columns(&query_plan(sql).await).unwrap();
+            let expected = expected.into_iter().map(|c| c.to_string()).collect();
+            assert_eq!(columns.get(&table), Some(&expected), "{sql}");
+        }
+
+        // The columns passing through the nodes without expressions.
+        let test = MockContext {};
+        let source = test
+            .get_table_provider(TableReference::bare("test_tb"))
+            .unwrap();
+        let plan = LogicalPlanBuilder::scan("test_tb", source, None)
+            .unwrap()
+            .limit(0, Some(1))
+            .unwrap()
+            .build()
+            .unwrap();
+        let columns = extract_table_columns(&plan).unwrap();
+        assert_eq!(columns[&table].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_query_privileges() {
+        let mut databases = DatabaseSet::default();
+        databases.push_table("public", "test_tb");
+        let plan = query_plan("select field_int from test_tb").await;
+        let privileges = query_privileges(&plan, 0, databases.clone()).unwrap();
+        assert_eq!(privileges, vec![read_columns(&["field_int"])]);
+
+        let user_with = |columns: &[&str]| {
+            let user_desc = UserDesc::new(0, "u".to_string(), UserOptions::default(), false);
+            User::new(user_desc, HashSet::from([read_columns(columns)]), None)
+        };
+        let granted = user_with(&["field_int"]);
+        assert!(privileges.iter().all(|p| granted.check_privilege(p)));
+
+        let plan = query_plan("select field_int from test_tb where field_string = 'a'").await;
+        let privileges = query_privileges(&plan, 0, databases).unwrap();
+        assert!(!privileges.iter().all(|p| granted.check_privilege(p)));
+        let granted = user_with(&["field_int", "field_string"]);
+        assert!(privileges.iter().all(|p| granted.check_privilege(p)));
+
+        // The databases accessed without scanning tables need the read privilege
+        // of the whole database.
+        let mut databases = DatabaseSet::default();
+        databases.push_table("db1", "t");
+        let privileges = query_privileges(&plan, 0, databases).unwrap();
+        assert_eq!(
+            privileges,
+            vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some("db1".to_string())),
+                Some(0),
+            )]
+        );
+        assert!(!privileges.iter().all(|p| granted.check_privilege(p)));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_columns() {
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let sql = "revoke read (field_int) on table test_tb from role r";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let err = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, QueryError::Semantic { .. }));
+
+        let sql = "revoke read on table test_tb from role r";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap();
+        match plan.plan {
+            Plan::DDL(DDLPlan::GrantRevoke(GrantRevoke {
+                is_grant: false,
+                table_privileges,
+                ..
+            })) => assert_eq!(table_privileges.len(), 1),
+            _ => panic!("expected revoke plan"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_stream() {
+        let sql = "drop stream if exists \"S1\"";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap();
+        match plan.plan {
+            Plan::SYSTEM(SYSPlan::DropStream { name, if_exist }) => {
+                assert_eq!(name, "S1");
+                assert!(if_exist);
+            }
+            _ => panic!("expected drop stream plan"),
+        }
+        assert!(matches!(
+            plan.privileges.as_slice(),
+            [Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(_)),
+                _
+            )]
+        ));
+    }
 }
@@ -0,0 +1,122 @@
+use datafusion::sql::sqlparser::tokenizer::{Token, Tokenizer};
+
+use super::dialect::CnosDBDialect;
+
+pub const REDACTED: &str = "******";
+
+/// The options whose values are secrets, e.g. `PASSWORD = '...'` of the users
+/// and the credentials of the object stores in `COPY INTO`.
+const SECRET_OPTIONS: [&str; 6] = [
+    "password",
+    "secret_key",
+    "token",
+    "access_key",
+    "private_key",
+    "bearer_token",
+];
+
+/// Replace the secrets in the statement with `******`, including the passwords
+/// of the users, the encryption keys of the tenants and the credentials of
+/// `COPY INTO`. The statement is redacted entirely if it can't be tokenized
+/// and may contain secrets.
+pub fn redact_statement(sql: &str) -> String {
+    let tokens = match Tokenizer::new(&CnosDBDialect, sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => {
+            let lowercase = sql.to_ascii_lowercase();
+            return if lowercase.contains("encryption")
+                || SECRET_OPTIONS
+                    .iter()
+                    .any(|option| lowercase.contains(option))
+            {
+                REDACTED.to_string()
+            } else {
+                sql.to_string()
+            };
+        }
+    };
+
+    let mut redacted = String::with_capacity(sql.len());
+    // The next string is the value of a secret option.
+    let mut secret_follows = false;
+    // In `SET ENCRYPTION KEY <name> = '<key>'`.
+    let mut encryption_key = false;
+    for token in tokens {
+        match &token {
+            Token::Whitespace(_) | Token::Eq => {}
+            Token::Word(word) => {
+                let value = word.value.to_ascii_lowercase();
+                encryption_key |= word.quote_style.is_none() && value == "encryption";
+                secret_follows =
+                    word.quote_style.is_none() && SECRET_OPTIONS.contains(&value.as_str());
+            }
+            Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::DollarQuotedString(_) => {
+                if secret_follows || encryption_key {
+                    redacted.push_str(&format!("'{REDACTED}'"));
+                    secret_follows = false;
+                    encryption_key = false;
+                    continue;
+                }
+            }
+            Token::SemiColon => {
+                secret_follows = false;
+                encryption_key = false;
+            }
+            _ => secret_follows = false,
+        }
+        redacted.push_str(&token.to_string());
+    }
+
+    redacted
+}
+
+#[cfg(test)]
+mod test {
+    use super::redact_statement;
+
+    #[test]
+    fn test_redact_statement() {
+        for (sql, expected) in [
+            (
+                "CREATE USER IF NOT EXISTS tester WITH PASSWORD='abc123', COMMENT='tester'",
+                "CREATE USER IF NOT EXISTS tester WITH PASSWORD='******', COMMENT='tester'",
+            ),
+            (
+                "alter user tester set password = 'abc123'",
+                "alter user tester set password = '******'",
+            ),
+            (
+                "ALTER TENANT cnosdb SET ENCRYPTION KEY k1 = 'MDEyMzQ1Njc4OWFiY2RlZg=='",
+                "ALTER TENANT cnosdb SET ENCRYPTION KEY k1 = '******'",
+            ),
+            (
+                "COPY INTO t FROM 's3://bucket/a.csv' CONNECTION = (region = 'us-east-1', access_key_id = 'id', secret_key = 'sk', token = 'tk') FILE_FORMAT = (TYPE = 'CSV')",
+                "COPY INTO t FROM 's3://bucket/a.csv' CONNECTION = (region = 'us-east-1', access_key_id = 'id', secret_key = '******', token = '******') FILE_FORMAT = (TYPE = 'CSV')",
+            ),
+            (
+                "copy into 'azblob://c/d' from t connection = (account = 'a', access_key = 'ak', bearer_token = 'bt')",
+                "copy into 'azblob://c/d' from t connection = (account = 'a', access_key = '******', bearer_token = '******')",
+            ),
+            (
+                "COPY INTO 'gcs://b/d' FROM t CONNECTION = (gcs_base_url = 'u', private_key = 'pk')",
+                "COPY INTO 'gcs://b/d' FROM t CONNECTION = (gcs_base_url = 'u', private_key = '******')",
+            ),
+            (
+                "select 'password' as token from t where f = 'abc'",
+                "select 'password' as token from t where f = 'abc'",
+            ),
+        ] {
+            assert_eq!(redact_statement(sql), expected, "{sql}");
+        }
+
+        // The statements which can't be tokenized.
+        assert_eq!(
+            redact_statement("alter user tester set password = 'abc"),
+            "******"
+        );
+        assert_eq!(redact_statement("select 'abc"), "select 'abc");
+    }
+}
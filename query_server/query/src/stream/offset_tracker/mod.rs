@@ -1,4 +1,3 @@
-use std::cmp;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -21,6 +20,18 @@ impl OffsetTracker {
         }
     }
 
+    /// Resume from the offsets processed before a restart.
+    pub fn with_processed_offsets(processed_offsets: HashMap<String, Offset>) -> Self {
+        Self {
+            processed_offsets: Arc::new(RwLock::new(processed_offsets)),
+            available_offsets: Default::default(),
+        }
+    }
+
+    pub fn processed_offsets(&self) -> HashMap<String, Offset> {
+        self.processed_offsets.read().clone()
+    }
+
     pub fn has_available_offsets(&self) -> bool {
         !self.available_offsets.read().is_empty()
     }
@@ -58,17 +69,20 @@ impl OffsetTracker {
         source_to_range
     }
 
-    pub fn commit(&self, commit_offset: Offset) {
-        // TODO 因为目前tskv表使用当前时间作为最新的可用offset，所以这里需要使用watermark_ns来保证不会丢失数据
-        self.available_offsets
-            .read()
-            .iter()
-            .for_each(|(id, offset)| {
-                let offset = cmp::min(commit_offset, *offset);
-                self.processed_offsets.write().insert(id.clone(), offset);
-            });
+    /// Commit the offsets processed of the sources, returns whether any of them advanced.
+    pub fn commit(&self, offsets: HashMap<String, Offset>) -> bool {
+        let mut advanced = false;
+        let mut processed_offsets = self.processed_offsets.write();
+        for (id, offset) in offsets {
+            let current_offset = processed_offsets.get(&id).cloned().unwrap_or(Offset::MIN);
+            if offset > current_offset {
+                processed_offsets.insert(id, offset);
+                advanced = true;
+            }
+        }
 
         self.available_offsets.write().clear();
+        advanced
     }
 }
 
@@ -77,3 +91,41 @@ impl Default for OffsetTracker {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::OffsetTracker;
+
+    #[test]
+    fn test_commit() {
+        let tracker = OffsetTracker::new();
+        tracker.update_available_offset("a".to_string(), 10);
+        tracker.update_available_offset("b".to_string(), 20);
+        assert_eq!(
+            tracker.available_offsets(),
+            HashMap::from([("a".to_string(), (None, 10)), ("b".to_string(), (None, 20))])
+        );
+
+        assert!(tracker.commit(HashMap::from([
+            ("a".to_string(), 10),
+            ("b".to_string(), 15)
+        ])));
+        assert!(!tracker.has_available_offsets());
+        assert_eq!(
+            tracker.processed_offsets(),
+            HashMap::from([("a".to_string(), 10), ("b".to_string(), 15)])
+        );
+
+        // Nothing new is processed.
+        tracker.update_available_offset("a".to_string(), 10);
+        tracker.update_available_offset("b".to_string(), 20);
+        assert_eq!(
+            tracker.available_offsets(),
+            HashMap::from([("b".to_string(), (Some(16), 20))])
+        );
+        assert!(!tracker.commit(HashMap::from([("b".to_string(), 15)])));
+        assert_eq!(tracker.processed_offsets()["b"], 15);
+    }
+}
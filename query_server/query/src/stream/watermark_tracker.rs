@@ -14,6 +14,7 @@ use models::schema::{CLUSTER_SCHEMA, DEFAULT_CATALOG};
 use models::utils::now_timestamp_nanos;
 use models::ValueType;
 use protocol_parser::Line;
+use protos::kv_service::WriteConsistency;
 use protos::FieldValue;
 use snafu::ResultExt;
 use spi::query::session::SessionCtx;
@@ -212,6 +213,7 @@ impl WatermarkTracker {
                 DEFAULT_CATALOG,
                 CLUSTER_SCHEMA,
                 Precision::NS,
+                WriteConsistency::Quorum,
                 vec![line],
                 None,
             )
@@ -590,6 +590,37 @@ pub enum QueryError {
     Models {
         source: ModelError,
     },
+
+    #[snafu(display("The statement timed out after {:?}", timeout))]
+    #[error_code(code = 80)]
+    StatementTimeout {
+        timeout: std::time::Duration,
+    },
+
+    #[snafu(display("InfluxQL parse error: {}", reason))]
+    #[error_code(code = 81)]
+    InfluxqlParser {
+        reason: String,
+    },
+
+    #[snafu(display("Write stream sink error: {}", reason))]
+    #[error_code(code = 82)]
+    StreamSink {
+        reason: String,
+    },
+
+    #[snafu(display("Write stopped after a partial write {}: {}", report, source))]
+    #[error_code(code = 83)]
+    PartialWrite {
+        report: String,
+        source: Box<QueryError>,
+    },
+
+    #[snafu(display("Stream not found: {}", name))]
+    #[error_code(code = 84)]
+    StreamNotFound {
+        name: String,
+    },
 }
 
 impl From<DataFusionError> for QueryError {
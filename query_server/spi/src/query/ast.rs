@@ -2,13 +2,16 @@ use std::fmt;
 
 use datafusion::sql::parser::CreateExternalTable;
 use datafusion::sql::sqlparser::ast::{
-    AnalyzeFormat, DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, SqlOption, Statement,
-    TableFactor, Value,
+    AnalyzeFormat, DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, Query, SqlOption,
+    Statement, TableFactor, Value,
 };
 use datafusion::sql::sqlparser::parser::ParserError;
 use models::codec::Encoding;
+use models::encryption::EncryptionKey;
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
+use utils::duration::CnosDuration;
 
+use super::datasource::sink::StreamSinkOptions;
 use super::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
 
 /// Statement representations
@@ -32,6 +35,15 @@ pub enum ExtStatement {
     DropStream(DropStream),
     ShowStreams(ShowStreams),
 
+    CreateContinuousQuery(Box<CreateContinuousQuery>),
+    DropContinuousQuery(DropContinuousQuery),
+    ShowContinuousQueries,
+    ShowTenantQuotas,
+
+    CreateScheduledDelete(Box<CreateScheduledDelete>),
+    DropScheduledDelete(DropScheduledDelete),
+    ShowJobs,
+
     DropDatabaseObject(DropDatabaseObject),
     DropTenantObject(DropTenantObject),
     DropGlobalObject(DropGlobalObject),
@@ -45,13 +57,18 @@ pub enum ExtStatement {
     ShowSeries(Box<ShowSeries>),
     ShowTagValues(Box<ShowTagValues>),
     Explain(Explain),
+    PreviewStream(PreviewStream),
+
+    ShowFunctions(Option<String>),
 
     // system cmd
     ShowQueries,
+    ShowRuntime,
     AlterDatabase(Box<AlterDatabase>),
     AlterTable(AlterTable),
     AlterTenant(AlterTenant),
     AlterUser(AlterUser),
+    MergeTagValue(MergeTagValue),
 
     // vnode cmd
     DropVnode(DropVnode),
@@ -59,18 +76,58 @@ pub enum ExtStatement {
     MoveVnode(MoveVnode),
     CompactVnode(CompactVnode),
     CompactDatabase(CompactDatabase),
+    CloseVnodeFiles(CloseVnodeFiles),
+    ExplainCompaction(ExplainCompaction),
+    ShowCompactions,
+    KillCompaction(KillCompaction),
     ChecksumGroup(ChecksumGroup),
+    RepairReplica(RepairReplica),
+    ExportVnode(ExportVnode),
+    ShowCardinality(ShowCardinality),
+    ShowShardSkew(ShowShardSkew),
 
     // recover cmd
     RecoverTenant(RecoverTenant),
     RecoverDatabase(RecoverDatabase),
 
+    // backup cmd
+    BackupDatabase(BackupDatabase),
+    RestoreDatabase(RestoreDatabase),
+
     // replica cmd
     ShowReplicas,
+    ShowDatabaseReplicas(ShowDatabaseReplicas),
+    ShowVnodes(ShowVnodes),
     ReplicaDestory(ReplicaDestory),
     ReplicaAdd(ReplicaAdd),
     ReplicaRemove(ReplicaRemove),
     ReplicaPromote(ReplicaPromote),
+
+    /// AUDIT ON|OFF
+    SetAudit(bool),
+}
+
+impl ExtStatement {
+    /// Whether the statement shows or changes the schema of databases and tables.
+    pub fn reads_catalog(&self) -> bool {
+        matches!(
+            self,
+            Self::CreateExternalTable(_)
+                | Self::CreateTable(_)
+                | Self::CreateStreamTable(_)
+                | Self::CreateDatabase(_)
+                | Self::DropDatabaseObject(_)
+                | Self::DropTenantObject(_)
+                | Self::DescribeTable(_)
+                | Self::DescribeDatabase(_)
+                | Self::ShowDatabases()
+                | Self::ShowTables(_)
+                | Self::ShowSeries(_)
+                | Self::ShowTagValues(_)
+                | Self::AlterDatabase(_)
+                | Self::AlterTable(_)
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -82,6 +139,7 @@ pub struct ReplicaDestory {
 pub struct ReplicaAdd {
     pub replica_id: ReplicationSetId,
     pub node_id: NodeId,
+    pub learner: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -101,11 +159,33 @@ pub struct ChecksumGroup {
     pub replication_set_id: ReplicationSetId,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairReplica {
+    pub replication_set_id: ReplicationSetId,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseVnodeFiles {
+    pub vnode_ids: Vec<VnodeId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainCompaction {
+    pub vnode_id: VnodeId,
+}
+
+/// `KILL COMPACTION <job_id> [ON NODE <node_id>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillCompaction {
+    pub job_id: u64,
+    pub node_id: Option<NodeId>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompactDatabase {
     pub database_name: Ident,
@@ -128,6 +208,46 @@ pub struct DropVnode {
     pub vnode_id: VnodeId,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportVnode {
+    pub vnode_id: VnodeId,
+    pub start_time: Value,
+    pub end_time: Value,
+    pub location: UriLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupDatabase {
+    pub database_name: Ident,
+    pub location: UriLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreDatabase {
+    pub location: UriLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowCardinality {
+    pub top_k: u64,
+    pub database_name: Ident,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowShardSkew {
+    pub database_name: Ident,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowVnodes {
+    pub node_id: Option<NodeId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowDatabaseReplicas {
+    pub database_name: Ident,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Copy {
     pub copy_target: CopyTarget,
@@ -152,6 +272,9 @@ pub struct CopyIntoTable {
 pub struct CopyIntoLocation {
     pub from: TableFactor,
     pub location: UriLocation,
+    /// `COPY (<query>) TO '<uri>'` reports the bytes of the written files
+    /// besides the rows.
+    pub report_bytes: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -166,14 +289,31 @@ pub struct AlterTable {
     pub alter_action: AlterTableAction,
 }
 
+/// `UPDATE TABLE <table_name> MERGE TAG <tag_name> '<from>' INTO '<to>'`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeTagValue {
+    pub table_name: ObjectName,
+    pub tag_name: Ident,
+    pub from: String,
+    pub to: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Explain {
     pub analyze: bool,
     pub verbose: bool,
+    /// `EXPLAIN (COST)`, estimate the cost of the query instead of printing the plan.
+    pub cost: bool,
     pub ext_statement: Box<ExtStatement>,
     pub format: Option<AnalyzeFormat>,
 }
 
+/// `PREVIEW STREAM <statement>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewStream {
+    pub ext_statement: Box<ExtStatement>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AlterTableAction {
     AddColumn {
@@ -183,6 +323,11 @@ pub enum AlterTableAction {
         column_name: Ident,
         encoding: Encoding,
     },
+    /// `ALTER COLUMN <column_name> SET DEFAULT <value>` or `DROP DEFAULT`
+    AlterColumnDefault {
+        column_name: Ident,
+        default: Option<Value>,
+    },
     DropColumn {
         column_name: Ident,
     },
@@ -191,6 +336,15 @@ pub enum AlterTableAction {
         old_column_name: Ident,
         new_column_name: Ident,
     },
+    /// `SET TTL '<duration>'`, `'INF'` removes the TTL of the table
+    SetTtl {
+        ttl: String,
+    },
+    /// `ADD INDEX ON (<column_name>)` or `DROP INDEX ON (<column_name>)`
+    AlterColumnIndex {
+        column_name: Ident,
+        indexed: bool,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -215,6 +369,10 @@ pub enum AlterTenantOperation {
     RemoveUser(Ident),
     Set(SqlOption),
     UnSet(Ident),
+    /// `SET ENCRYPTION KEY <key_name> = '<key_in_hex>'`
+    SetEncryptionKey(Ident, EncryptionKey),
+    /// `UNSET ENCRYPTION KEY <key_name>`
+    UnSetEncryptionKey(Ident),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -295,7 +453,17 @@ pub struct GrantRevoke {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Privilege {
     pub action: Action,
-    pub database: Ident,
+    pub object: PrivilegeObject,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivilegeObject {
+    Database(Ident),
+    /// The privilege on all columns of the table if the columns are empty
+    Table {
+        name: ObjectName,
+        columns: Vec<Ident>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -303,6 +471,8 @@ pub enum Action {
     Read,
     Write,
     All,
+    /// Read the decrypted values of the encrypted columns.
+    Decrypt,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -332,6 +502,8 @@ pub struct CreateTable {
     pub name: ObjectName,
     pub if_not_exists: bool,
     pub columns: Vec<ColumnOption>,
+    /// `WITH (append_only = true)`
+    pub append_only: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -340,6 +512,8 @@ pub struct ColumnOption {
     pub is_tag: bool,
     pub data_type: DataType,
     pub encoding: Option<Encoding>,
+    /// `ENCRYPTED WITH KEY '<key_name>'`
+    pub encryption_key: Option<String>,
 }
 
 impl ColumnOption {
@@ -349,6 +523,7 @@ impl ColumnOption {
             is_tag: false,
             data_type,
             encoding,
+            encryption_key: None,
         }
     }
 
@@ -358,6 +533,7 @@ impl ColumnOption {
             is_tag: true,
             data_type: DataType::String,
             encoding: None,
+            encryption_key: None,
         }
     }
 }
@@ -370,6 +546,12 @@ pub struct DatabaseOptions {
     // shard coverage time range
     pub vnode_duration: Option<String>,
     pub replica: Option<u64>,
+    pub series_placement: Option<String>,
+    pub compaction: Option<String>,
+    pub compaction_window: Option<String>,
+    // comma separated target file sizes of level-1 to level-4
+    pub compaction_file_sizes: Option<String>,
+    pub time_zone: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -475,10 +657,49 @@ pub struct CreateStream {
     pub trigger: Option<Trigger>,
     pub watermark: Option<String>,
     pub output_mode: Option<OutputMode>,
+    /// The external sink the output is written to, `statement` is a query if it is set,
+    /// otherwise an insert statement.
+    pub sink: Option<StreamSinkOptions>,
 
     pub statement: Box<Statement>,
 }
 
+/// `CREATE CONTINUOUS QUERY [IF NOT EXISTS] <name> EVERY '<interval>' [FOR '<interval>']
+/// INTO <target_table> AS <query>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateContinuousQuery {
+    pub if_not_exists: bool,
+    pub name: Ident,
+    pub every: String,
+    pub for_range: Option<String>,
+    pub target_table: ObjectName,
+    pub query: Box<Query>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropContinuousQuery {
+    pub if_exist: bool,
+    pub name: Ident,
+}
+
+/// `CREATE SCHEDULED DELETE [IF NOT EXISTS] <name> CRON '<expression>' [OLDER THAN '<duration>']
+/// AS DELETE FROM <table> [WHERE <selection>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateScheduledDelete {
+    pub if_not_exists: bool,
+    pub name: Ident,
+    pub cron: String,
+    pub older_than: Option<String>,
+    pub table_name: ObjectName,
+    pub selection: Option<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropScheduledDelete {
+    pub if_exist: bool,
+    pub name: Ident,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DropStream {
     pub if_exist: bool,
@@ -519,6 +740,31 @@ pub fn parse_string_value(value: Value) -> std::result::Result<String, ParserErr
     }
 }
 
+pub fn parse_usize_value(value: Value) -> std::result::Result<usize, ParserError> {
+    match value {
+        Value::Number(ref n, _) => n.parse::<usize>().map_err(|_| {
+            ParserError::ParserError(format!(
+                "expected unsigned integer value, but found : {}",
+                value
+            ))
+        }),
+        _ => Err(ParserError::ParserError(format!(
+            "expected unsigned integer value, but found : {}",
+            value
+        ))),
+    }
+}
+
+pub fn parse_duration_value(value: Value) -> std::result::Result<CnosDuration, ParserError> {
+    let duration = parse_string_value(value)?;
+    CnosDuration::new(&duration).ok_or_else(|| {
+        ParserError::ParserError(format!(
+            "{} is not a valid duration or duration overflow",
+            duration
+        ))
+    })
+}
+
 pub fn parse_char_value(value: Value) -> std::result::Result<char, ParserError> {
     let token = parse_string_value(value)?;
     match token.len() {
@@ -1,6 +1,8 @@
 use std::str::FromStr;
 use std::time::Duration;
 
+use datafusion::arrow::array::timezone::Tz;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum StreamTriggerInterval {
     Once,
@@ -74,11 +76,111 @@ impl FromStr for StreamTriggerInterval {
     }
 }
 
+/// Parse the bound of the `max_replica_lag` session option, such as `5s` or `500ms`.
+pub fn parse_max_replica_lag(s: &str) -> Result<Duration, String> {
+    duration_str::parse_std(s.trim()).map_err(|err| err.to_string())
+}
+
+/// Parse the time zone of the session, such as `+08:00`.
+pub fn parse_time_zone(s: &str) -> Result<String, String> {
+    let s = s.trim();
+    Tz::from_str(s).map_err(|err| err.to_string())?;
+    Ok(s.to_string())
+}
+
+/// A variable of the session changed by `SET name = value`, `None` means the
+/// variable is reset by `SET name = DEFAULT`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionVariable {
+    /// The time zone of the timestamps with time zone, used by the functions
+    /// such as `now()` and `date_trunc`.
+    TimeZone(Option<String>),
+    /// Number of partitions for query execution.
+    TargetPartitions(Option<usize>),
+    /// Delete the rows written by an `INSERT` which fails or is cancelled.
+    CleanupPartialWrite(Option<bool>),
+}
+
+impl SessionVariable {
+    pub const TIME_ZONE: &'static str = "time_zone";
+    pub const TARGET_PARTITIONS: &'static str = "target_partitions";
+    pub const CLEANUP_PARTIAL_WRITE: &'static str = "cleanup_partial_write";
+
+    /// Parse the variable from the name and the value, both are case insensitive.
+    pub fn parse(name: &str, value: &str) -> Result<Self, String> {
+        let value = Some(value.trim()).filter(|v| !v.eq_ignore_ascii_case("default"));
+        match name.to_ascii_lowercase().as_str() {
+            Self::TIME_ZONE => Ok(Self::TimeZone(value.map(parse_time_zone).transpose()?)),
+            Self::TARGET_PARTITIONS => {
+                let target_partitions = value
+                    .map(|v| match v.parse::<usize>() {
+                        Ok(n) if n > 0 => Ok(n),
+                        _ => Err(format!("{} must be a positive integer, got {v}", name)),
+                    })
+                    .transpose()?;
+                Ok(Self::TargetPartitions(target_partitions))
+            }
+            Self::CLEANUP_PARTIAL_WRITE => {
+                let cleanup = value
+                    .map(|v| {
+                        v.to_ascii_lowercase()
+                            .parse::<bool>()
+                            .map_err(|_| format!("{} must be true or false, got {v}", name))
+                    })
+                    .transpose()?;
+                Ok(Self::CleanupPartialWrite(cleanup))
+            }
+            _ => Err(format!("Unknown session variable '{name}'")),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TimeZone(_) => Self::TIME_ZONE,
+            Self::TargetPartitions(_) => Self::TARGET_PARTITIONS,
+            Self::CleanupPartialWrite(_) => Self::CLEANUP_PARTIAL_WRITE,
+        }
+    }
+
+    /// Whether the variable is reset to the default.
+    pub fn is_default(&self) -> bool {
+        match self {
+            Self::TimeZone(v) => v.is_none(),
+            Self::TargetPartitions(v) => v.is_none(),
+            Self::CleanupPartialWrite(v) => v.is_none(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
-    use crate::query::config::StreamTriggerInterval;
+    use crate::query::config::{SessionVariable, StreamTriggerInterval};
+
+    #[test]
+    fn test_session_variable() {
+        assert_eq!(
+            SessionVariable::parse("TIME_ZONE", "+08:00").unwrap(),
+            SessionVariable::TimeZone(Some("+08:00".to_string()))
+        );
+        assert_eq!(
+            SessionVariable::parse("time_zone", "DEFAULT").unwrap(),
+            SessionVariable::TimeZone(None)
+        );
+        assert!(SessionVariable::parse("time_zone", "+8 hours").is_err());
+        assert_eq!(
+            SessionVariable::parse("target_partitions", "4").unwrap(),
+            SessionVariable::TargetPartitions(Some(4))
+        );
+        assert!(SessionVariable::parse("target_partitions", "0").is_err());
+        assert_eq!(
+            SessionVariable::parse("cleanup_partial_write", "TRUE").unwrap(),
+            SessionVariable::CleanupPartialWrite(Some(true))
+        );
+        assert!(SessionVariable::parse("cleanup_partial_write", "1").is_err());
+        assert!(SessionVariable::parse("unknown", "1").is_err());
+    }
 
     #[test]
     fn test() {
@@ -13,6 +13,7 @@ use super::session::SqlExecInfo;
 pub mod azure;
 pub mod gcs;
 pub mod s3;
+pub mod sink;
 pub mod stream;
 
 pub struct WriteContext {
@@ -0,0 +1,52 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::record_batch::RecordBatch;
+
+use crate::QueryResult;
+
+pub type StreamSinkRef = Arc<dyn StreamSink + Send + Sync>;
+
+/// The destination of the output of a continuous stream query.
+///
+/// The offsets of the stream sources are committed only after the output of a
+/// micro-batch is written successfully, a micro-batch that fails to be written is
+/// processed again by the next trigger, so the output is delivered at least once.
+#[async_trait]
+pub trait StreamSink: Debug {
+    /// Write the output of the micro-batch `batch_id`.
+    async fn write_batches(&self, batch_id: i64, batches: &[RecordBatch]) -> QueryResult<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SinkFormat {
+    Json,
+    Avro,
+}
+
+impl FromStr for SinkFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "avro" => Ok(Self::Avro),
+            other => Err(format!(
+                "unsupported sink format '{}', expected one of json, avro",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StreamSinkOptions {
+    /// `INTO KAFKA('brokers', 'topic' [, 'format'])`
+    Kafka {
+        brokers: String,
+        topic: String,
+        format: SinkFormat,
+    },
+}
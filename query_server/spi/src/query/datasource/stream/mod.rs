@@ -97,6 +97,12 @@ pub trait StreamProvider {
         range: Option<&(Option<Self::Offset>, Self::Offset)>,
     ) -> Result<Arc<dyn ExecutionPlan>>;
 
+    /// Returns the offset processed of the range ending at `end`, once the micro-batch of the
+    /// range moved the watermark of the stream to `watermark_ns`.
+    fn processed_offset(&self, end: Self::Offset, _watermark_ns: i64) -> Self::Offset {
+        end
+    }
+
     /// Informs the source that stream has completed processing all data for offsets less than or
     /// equal to `end` and will only request offsets greater than `end` in the future.
     async fn commit(&self, end: Self::Offset) -> Result<()>;
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
+use std::fmt::Display;
 use std::sync::Arc;
 
-use datafusion::logical_expr::{AggregateUDF, ScalarUDF, WindowUDF};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::logical_expr::{AggregateUDF, ScalarUDF, TypeSignature, WindowUDF};
 
 use crate::QueryResult;
 
@@ -13,6 +15,9 @@ pub trait FunctionMetadataManager {
 
     fn register_udwf(&mut self, udwf: WindowUDF) -> QueryResult<()>;
 
+    /// Register the description of a function, which is shown by `SHOW FUNCTIONS`.
+    fn register_description(&mut self, name: &str, description: &str) -> QueryResult<()>;
+
     fn udf(&self, name: &str) -> QueryResult<Arc<ScalarUDF>>;
 
     fn udaf(&self, name: &str) -> QueryResult<Arc<AggregateUDF>>;
@@ -20,4 +25,150 @@ pub trait FunctionMetadataManager {
     fn udwf(&self, name: &str) -> QueryResult<Arc<WindowUDF>>;
 
     fn udfs(&self) -> HashSet<String>;
+
+    /// Metadata of all registered functions.
+    fn functions(&self) -> Vec<FunctionMeta>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionCategory {
+    Scalar,
+    Aggregate,
+    Window,
+}
+
+impl Display for FunctionCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FunctionCategory::Scalar => write!(f, "SCALAR"),
+            FunctionCategory::Aggregate => write!(f, "AGGREGATE"),
+            FunctionCategory::Window => write!(f, "WINDOW"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionMeta {
+    pub name: String,
+    pub category: FunctionCategory,
+    pub signature: TypeSignature,
+    /// Only known when the signature has exact argument types.
+    pub return_type: Option<DataType>,
+    pub description: Option<String>,
+}
+
+impl FunctionMeta {
+    pub fn new(
+        name: impl Into<String>,
+        category: FunctionCategory,
+        signature: TypeSignature,
+        return_type: impl Fn(&[DataType]) -> Option<DataType>,
+    ) -> Self {
+        let return_type = match &signature {
+            TypeSignature::Exact(arg_types) => return_type(arg_types),
+            _ => None,
+        };
+        Self {
+            name: name.into(),
+            category,
+            signature,
+            return_type,
+            description: None,
+        }
+    }
+
+    pub fn from_udf(udf: &ScalarUDF) -> Self {
+        Self::new(
+            &udf.name,
+            FunctionCategory::Scalar,
+            udf.signature.type_signature.clone(),
+            |args| (udf.return_type)(args).ok().map(|t| t.as_ref().clone()),
+        )
+    }
+
+    pub fn from_udaf(udaf: &AggregateUDF) -> Self {
+        Self::new(
+            &udaf.name,
+            FunctionCategory::Aggregate,
+            udaf.signature.type_signature.clone(),
+            |args| (udaf.return_type)(args).ok().map(|t| t.as_ref().clone()),
+        )
+    }
+
+    pub fn from_udwf(udwf: &WindowUDF) -> Self {
+        Self::new(
+            &udwf.name,
+            FunctionCategory::Window,
+            udwf.signature.type_signature.clone(),
+            |args| (udwf.return_type)(args).ok().map(|t| t.as_ref().clone()),
+        )
+    }
+
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Human readable argument types, e.g. `(Int64, Utf8)`.
+    pub fn argument_types(&self) -> String {
+        format_type_signature(&self.signature)
+    }
+}
+
+fn format_type_signature(signature: &TypeSignature) -> String {
+    let join = |types: &[DataType], sep: &str| {
+        types
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(sep)
+    };
+    match signature {
+        TypeSignature::Exact(types) => format!("({})", join(types, ", ")),
+        TypeSignature::Any(num) => format!("({})", vec!["Any"; *num].join(", ")),
+        TypeSignature::Uniform(num, types) => {
+            let arg = join(types, " | ");
+            format!("({})", vec![arg.as_str(); *num].join(", "))
+        }
+        TypeSignature::Variadic(types) => format!("({}, ...)", join(types, " | ")),
+        TypeSignature::VariadicAny => "(Any, ...)".to_string(),
+        TypeSignature::OneOf(signatures) => signatures
+            .iter()
+            .map(format_type_signature)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        other => format!("{other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::datatypes::DataType;
+    use datafusion::logical_expr::TypeSignature;
+
+    use super::{FunctionCategory, FunctionMeta};
+
+    #[test]
+    fn test_function_meta_argument_types() {
+        let meta = FunctionMeta::new(
+            "f",
+            FunctionCategory::Scalar,
+            TypeSignature::OneOf(vec![
+                TypeSignature::Exact(vec![DataType::Int64, DataType::Utf8]),
+                TypeSignature::Any(1),
+            ]),
+            |_| Some(DataType::Int64),
+        );
+        assert_eq!(meta.argument_types(), "(Int64, Utf8) | (Any)");
+        assert_eq!(meta.return_type, None);
+
+        let meta = FunctionMeta::new(
+            "f",
+            FunctionCategory::Scalar,
+            TypeSignature::Exact(vec![DataType::Int64]),
+            |args| Some(args[0].clone()),
+        );
+        assert_eq!(meta.argument_types(), "(Int64)");
+        assert_eq!(meta.return_type, Some(DataType::Int64));
+    }
 }
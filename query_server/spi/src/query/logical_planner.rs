@@ -1,30 +1,34 @@
 use std::collections::HashMap;
 use std::io::Write;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use config::common::TenantLimiterConfig;
 use datafusion::arrow::array::ArrayRef;
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::common::{Result as DFResult, ToDFSchema};
 use datafusion::datasource::file_format::file_type::{FileCompressionType, FileType};
 use datafusion::logical_expr::type_coercion::aggregates::{
     DATES, NUMERICS, STRINGS, TIMES, TIMESTAMPS,
 };
 use datafusion::logical_expr::{
-    expr, expr_fn, CreateExternalTable, LogicalPlan as DFPlan, ReturnTypeFunction, ScalarUDF,
-    Signature, Volatility,
+    expr, expr_fn, CreateExternalTable, Explain, LogicalPlan as DFPlan, PlanType,
+    ReturnTypeFunction, ScalarUDF, Signature, ToStringifiedPlan, Volatility,
 };
 use datafusion::physical_plan::functions::make_scalar_function;
 use datafusion::prelude::{col, Expr};
 use datafusion::sql::sqlparser::ast::{Ident, ObjectName, SqlOption, Value};
 use datafusion::sql::sqlparser::parser::ParserError;
 use lazy_static::lazy_static;
-use models::auth::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege};
+use models::auth::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege, TablePrivilege};
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::{UserOptions, UserOptionsBuilder};
+use models::encryption::{ColumnCipher, EncryptionKey};
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use models::object_reference::ResolvedTable;
 use models::oid::{Identifier, Oid};
+use models::predicate::domain::TimeRange;
 use models::schema::database_schema::{DatabaseConfigBuilder, DatabaseOptionsBuilder};
 use models::schema::query_info::QueryId;
 use models::schema::stream_table_schema::Watermark;
@@ -34,22 +38,35 @@ use snafu::{IntoError, ResultExt};
 use tempfile::NamedTempFile;
 use utils::duration::CnosDuration;
 
-use super::ast::{parse_bool_value, parse_char_value, parse_string_value, ExtStatement};
+use super::ast::{
+    parse_bool_value, parse_char_value, parse_duration_value, parse_string_value,
+    parse_usize_value, ExtStatement,
+};
+use super::config::{SessionVariable, StreamTriggerInterval};
 use super::datasource::azure::{AzblobStorageConfig, AzblobStorageConfigBuilder};
 use super::datasource::gcs::{
     GcsStorageConfig, ServiceAccountCredentials, ServiceAccountCredentialsBuilder,
 };
 use super::datasource::s3::{S3StorageConfig, S3StorageConfigBuilder};
+use super::datasource::sink::StreamSinkOptions;
 use super::datasource::UriSchema;
 use super::session::SessionCtx;
-use super::AFFECTED_ROWS;
+use super::{AFFECTED_BYTES, AFFECTED_ROWS};
 use crate::{
-    ParserSnafu, QueryError, QueryResult, SerdeJsonSnafu, StdIoSnafu, TenantOptionsBuildFailSnafu,
+    ModelsSnafu, ParserSnafu, QueryError, QueryResult, SerdeJsonSnafu, StdIoSnafu,
+    TenantOptionsBuildFailSnafu,
 };
 
 pub const TENANT_OPTION_LIMITER: &str = "_limiter";
 pub const TENANT_OPTION_COMMENT: &str = "comment";
 pub const TENANT_OPTION_DROP_AFTER: &str = "drop_after";
+pub const TENANT_OPTION_QUERY_TIMEOUT: &str = "query_timeout";
+pub const TENANT_OPTION_WRITE_TIMEOUT: &str = "write_timeout";
+pub const TENANT_OPTION_MAX_DATABASES: &str = "max_databases";
+pub const TENANT_OPTION_MAX_TABLES: &str = "max_tables";
+pub const TENANT_OPTION_MAX_USERS: &str = "max_users";
+pub const TENANT_OPTION_MAX_TABLES_PER_DATABASE: &str = "max_tables_per_database";
+pub const TENANT_OPTION_MAX_SERIES_PER_TABLE: &str = "max_series_per_table";
 
 lazy_static! {
     static ref TABLE_WRITE_UDF: Arc<ScalarUDF> = Arc::new(ScalarUDF::new(
@@ -69,6 +86,12 @@ lazy_static! {
         &(Arc::new(move |_: &[DataType]| Ok(Arc::new(DataType::UInt64))) as ReturnTypeFunction),
         &make_scalar_function(|args: &[ArrayRef]| Ok(Arc::clone(&args[0]))),
     ));
+    static ref TABLE_WRITE_BYTES_UDF: Arc<ScalarUDF> = Arc::new(ScalarUDF::new(
+        "bytes",
+        &TABLE_WRITE_UDF.signature,
+        &(Arc::new(move |_: &[DataType]| Ok(Arc::new(DataType::UInt64))) as ReturnTypeFunction),
+        &make_scalar_function(|args: &[ArrayRef]| Ok(Arc::clone(&args[0]))),
+    ));
 }
 
 #[derive(Clone)]
@@ -87,6 +110,12 @@ pub enum Plan {
     DML(DMLPlan),
     /// Query plan
     SYSTEM(SYSPlan),
+    /// Run one micro-batch of a stream query
+    PreviewStream(PreviewStream),
+    /// Run a stream query continuously into an external sink
+    CreateStream(CreateStreamQuery),
+    /// Run a continuous query on a schedule into a table
+    CreateContinuousQuery(CreateContinuousQuery),
 }
 
 impl Plan {
@@ -96,6 +125,9 @@ impl Plan {
             Self::DDL(p) => p.schema(),
             Self::DML(p) => p.schema(),
             Self::SYSTEM(p) => p.schema(),
+            Self::PreviewStream(_) => PreviewStream::schema(),
+            Self::CreateStream(_) => CreateStreamQuery::schema(),
+            Self::CreateContinuousQuery(_) => CreateStreamQuery::schema(),
         }
     }
 }
@@ -110,6 +142,25 @@ impl QueryPlan {
     pub fn is_explain(&self) -> bool {
         matches!(self.df_plan, DFPlan::Explain(_) | DFPlan::Analyze(_))
     }
+
+    /// The EXPLAIN of the query, which prints out the plans of the query instead
+    /// of executing it.
+    pub fn explain(self, verbose: bool) -> DFResult<QueryPlan> {
+        let plan = Arc::new(self.df_plan);
+        let stringified_plans = vec![plan.to_stringified(PlanType::InitialLogicalPlan)];
+        let df_plan = DFPlan::Explain(Explain {
+            verbose,
+            plan,
+            stringified_plans,
+            schema: DFPlan::explain_schema().to_dfschema_ref()?,
+            logical_optimization_succeeded: false,
+        });
+
+        Ok(QueryPlan {
+            df_plan,
+            is_tag_scan: self.is_tag_scan,
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -144,6 +195,8 @@ pub enum DDLPlan {
 
     AlterUser(AlterUser),
 
+    MergeTagValue(MergeTagValue),
+
     GrantRevoke(GrantRevoke),
 
     DropVnode(DropVnode),
@@ -154,8 +207,30 @@ pub enum DDLPlan {
 
     CompactVnode(CompactVnode),
 
+    CloseVnodeFiles(CloseVnodeFiles),
+
+    ExplainCompaction(ExplainCompaction),
+
+    ShowCompactions,
+
+    KillCompaction(KillCompaction),
+
     ChecksumGroup(ChecksumGroup),
 
+    RepairReplica(RepairReplica),
+
+    ExportVnode(ExportVnode),
+
+    BackupDatabase(BackupDatabase),
+
+    RestoreDatabase(RestoreDatabase),
+
+    ShowCardinality(ShowCardinality),
+
+    ShowShardSkew(ShowShardSkew),
+
+    ExplainCost(ExplainCost),
+
     RecoverDatabase(RecoverDatabase),
 
     RecoverTenant(RecoverTenant),
@@ -169,6 +244,18 @@ pub enum DDLPlan {
     ReplicaRemove(ReplicaRemove),
 
     ReplicaPromote(ReplicaPromote),
+
+    DropContinuousQuery(DropContinuousQuery),
+
+    ShowContinuousQueries(ShowContinuousQueries),
+
+    ShowTenantQuotas,
+
+    CreateScheduledDelete(CreateScheduledDelete),
+
+    DropScheduledDelete(DropScheduledDelete),
+
+    ShowJobs(ShowJobs),
 }
 
 impl DDLPlan {
@@ -178,21 +265,191 @@ impl DDLPlan {
                 Field::new("vnode_id", DataType::UInt32, false),
                 Field::new("check_sum", DataType::Utf8, false),
             ])),
+            DDLPlan::RepairReplica(_) => Arc::new(Schema::new(vec![
+                Field::new("vnode_id", DataType::UInt32, false),
+                Field::new("node_id", DataType::UInt64, false),
+                Field::new("repaired_chunks", DataType::UInt64, false),
+                Field::new("repaired_rows", DataType::UInt64, false),
+            ])),
+            DDLPlan::ReplicaPromote(_) => Arc::new(Schema::new(vec![
+                Field::new("replica_id", DataType::UInt32, false),
+                Field::new("old_leader_vnode", DataType::UInt32, false),
+                Field::new("new_leader_vnode", DataType::UInt32, false),
+                Field::new("node_id", DataType::UInt64, false),
+                Field::new("log_lag", DataType::UInt64, false),
+                Field::new("elapsed_ms", DataType::UInt64, false),
+            ])),
+            DDLPlan::CloseVnodeFiles(_) => Arc::new(Schema::new(vec![
+                Field::new("vnode_id", DataType::UInt32, false),
+                Field::new("closed_files", DataType::UInt64, false),
+            ])),
+            DDLPlan::ExplainCompaction(_) => Arc::new(Schema::new(vec![
+                Field::new("vnode_id", DataType::UInt32, false),
+                Field::new("compaction", DataType::Utf8, false),
+                Field::new("in_level", DataType::UInt32, false),
+                Field::new("out_level", DataType::UInt32, false),
+                Field::new("file_count", DataType::UInt64, false),
+                Field::new("files", DataType::Utf8, false),
+                Field::new("read_bytes", DataType::UInt64, false),
+                Field::new("write_bytes", DataType::UInt64, false),
+                Field::new("estimated_duration", DataType::Utf8, true),
+            ])),
+            DDLPlan::ShowCompactions => Arc::new(Schema::new(vec![
+                Field::new("node_id", DataType::UInt64, false),
+                Field::new("job_id", DataType::UInt64, false),
+                Field::new("database", DataType::Utf8, false),
+                Field::new("vnode_id", DataType::UInt32, false),
+                Field::new("compaction", DataType::Utf8, false),
+                Field::new("in_level", DataType::UInt32, false),
+                Field::new("out_level", DataType::UInt32, false),
+                Field::new("files_in", DataType::UInt64, false),
+                Field::new("bytes_in", DataType::UInt64, false),
+                Field::new("files_out", DataType::UInt64, false),
+                Field::new("bytes_out", DataType::UInt64, false),
+                Field::new("start_time", DataType::Utf8, false),
+                Field::new("cancelled", DataType::Boolean, false),
+            ])),
+            DDLPlan::ExportVnode(_) => Arc::new(Schema::new(vec![Field::new(
+                "rows",
+                DataType::UInt64,
+                false,
+            )])),
+            DDLPlan::BackupDatabase(_) => Arc::new(Schema::new(vec![
+                Field::new("vnodes", DataType::UInt64, false),
+                Field::new("files", DataType::UInt64, false),
+                Field::new("bytes", DataType::UInt64, false),
+            ])),
+            DDLPlan::RestoreDatabase(_) => Arc::new(Schema::new(vec![
+                Field::new("database", DataType::Utf8, false),
+                Field::new("vnodes", DataType::UInt64, false),
+            ])),
+            DDLPlan::ShowCardinality(_) => Arc::new(Schema::new(vec![
+                Field::new("table", DataType::Utf8, false),
+                Field::new("tag_key", DataType::Utf8, false),
+                Field::new("value_prefix", DataType::Utf8, false),
+                Field::new("series_count", DataType::UInt64, false),
+            ])),
+            DDLPlan::ShowShardSkew(_) => Arc::new(Schema::new(vec![
+                Field::new("bucket_id", DataType::UInt32, false),
+                Field::new("start_time", DataType::Utf8, false),
+                Field::new("end_time", DataType::Utf8, false),
+                Field::new("series_placement", DataType::Utf8, false),
+                Field::new("replica_id", DataType::UInt32, false),
+                Field::new("rows", DataType::UInt64, true),
+                Field::new("skew", DataType::Float64, true),
+            ])),
+            DDLPlan::ExplainCost(_) => Arc::new(Schema::new(vec![
+                Field::new("table", DataType::Utf8, false),
+                Field::new("replica_id", DataType::UInt32, false),
+                Field::new("node_id", DataType::UInt64, false),
+                Field::new("vnode_id", DataType::UInt32, false),
+                Field::new("series", DataType::UInt64, false),
+                Field::new("files", DataType::UInt64, false),
+                Field::new("bytes", DataType::UInt64, false),
+            ])),
+            DDLPlan::ShowContinuousQueries(_) => Arc::new(Schema::new(vec![
+                Field::new("name", DataType::Utf8, false),
+                Field::new("target_table", DataType::Utf8, false),
+                Field::new("every", DataType::Utf8, false),
+                Field::new("for", DataType::Utf8, false),
+                Field::new("query", DataType::Utf8, false),
+                Field::new("node_id", DataType::UInt64, false),
+                Field::new("query_id", DataType::Utf8, false),
+            ])),
+            DDLPlan::ShowTenantQuotas => Arc::new(Schema::new(vec![
+                Field::new("quota", DataType::Utf8, false),
+                Field::new("max", DataType::UInt64, true),
+                Field::new("used", DataType::UInt64, true),
+            ])),
+            DDLPlan::ShowJobs(_) => Arc::new(Schema::new(vec![
+                Field::new("name", DataType::Utf8, false),
+                Field::new("table", DataType::Utf8, false),
+                Field::new("cron", DataType::Utf8, false),
+                Field::new("older_than", DataType::Utf8, true),
+                Field::new("predicate", DataType::Utf8, true),
+                Field::new("status", DataType::Utf8, false),
+                Field::new("next_run_time", DataType::Utf8, true),
+                Field::new("run_time", DataType::Utf8, true),
+                Field::new("success", DataType::Boolean, true),
+                Field::new("comment", DataType::Utf8, true),
+            ])),
             _ => Arc::new(Schema::empty()),
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct DropContinuousQuery {
+    pub if_exist: bool,
+    pub name: String,
+    pub database: String,
+}
+
+/// List the continuous queries of the database.
+#[derive(Debug, Clone)]
+pub struct ShowContinuousQueries {
+    pub database: String,
+}
+
+/// A job of `CREATE SCHEDULED DELETE`, deleting the rows of the table matching
+/// `selection` on the cron schedule.
+#[derive(Debug, Clone)]
+pub struct CreateScheduledDelete {
+    pub if_not_exists: bool,
+    pub name: String,
+    pub table_name: ResolvedTable,
+    pub cron: String,
+    pub older_than: Option<Duration>,
+    pub selection: Option<Expr>,
+    /// Sql of the selection, shown by `SHOW JOBS`
+    pub selection_sql: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DropScheduledDelete {
+    pub if_exist: bool,
+    pub name: String,
+    pub database: String,
+}
+
+/// List the scheduled jobs of the database with the history of their runs.
+#[derive(Debug, Clone)]
+pub struct ShowJobs {
+    pub database: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ChecksumGroup {
     pub replication_set_id: ReplicationSetId,
 }
 
+#[derive(Debug, Clone)]
+pub struct RepairReplica {
+    pub replication_set_id: ReplicationSetId,
+}
+
 #[derive(Debug, Clone)]
 pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
 }
 
+#[derive(Debug, Clone)]
+pub struct CloseVnodeFiles {
+    pub vnode_ids: Vec<VnodeId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExplainCompaction {
+    pub vnode_id: VnodeId,
+}
+
+/// `KILL COMPACTION <job_id> [ON NODE <node_id>]`
+#[derive(Debug, Clone)]
+pub struct KillCompaction {
+    pub job_id: u64,
+    pub node_id: Option<NodeId>,
+}
+
 #[derive(Debug, Clone)]
 pub struct MoveVnode {
     pub vnode_id: VnodeId,
@@ -210,6 +467,98 @@ pub struct DropVnode {
     pub vnode_id: VnodeId,
 }
 
+#[derive(Debug, Clone)]
+pub struct ExportVnode {
+    pub vnode_id: VnodeId,
+    /// Time range in nanoseconds.
+    pub time_range: TimeRange,
+    pub path: String,
+    pub connection_options: Vec<SqlOption>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupDatabase {
+    pub database: String,
+    pub path: String,
+    pub connection_options: Vec<SqlOption>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestoreDatabase {
+    pub path: String,
+    pub connection_options: Vec<SqlOption>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowCardinality {
+    pub database: String,
+    pub top_k: usize,
+}
+
+/// `SHOW SHARD SKEW FOR <database>`
+#[derive(Debug, Clone)]
+pub struct ShowShardSkew {
+    pub database: String,
+}
+
+/// `EXPLAIN (COST) <query>`, the query is optimized but not executed.
+#[derive(Debug, Clone)]
+pub struct ExplainCost {
+    pub plan: QueryPlan,
+}
+
+/// `PREVIEW STREAM <query>`, one micro-batch of the stream query is executed against
+/// the current offsets, without registering the continuous query.
+#[derive(Debug, Clone)]
+pub struct PreviewStream {
+    pub plan: QueryPlan,
+}
+
+impl PreviewStream {
+    pub fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("type", DataType::Utf8, false),
+            Field::new("content", DataType::Utf8, false),
+        ]))
+    }
+}
+
+/// A stream query of `CREATE STREAM ... INTO`, the output of each micro-batch
+/// is written to the sink.
+#[derive(Debug, Clone)]
+pub struct CreateStreamQuery {
+    pub name: String,
+    pub plan: QueryPlan,
+    /// Use the trigger interval of the session if None
+    pub trigger_interval: Option<StreamTriggerInterval>,
+    pub sink: StreamSinkOptions,
+}
+
+impl CreateStreamQuery {
+    pub fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![Field::new(
+            "query_id",
+            DataType::Utf8,
+            false,
+        )]))
+    }
+}
+
+/// A continuous query of `CREATE CONTINUOUS QUERY`, `plan` inserts the output
+/// of the query into the target table, the source tables are restricted to
+/// the time range of each run.
+#[derive(Debug, Clone)]
+pub struct CreateContinuousQuery {
+    pub if_not_exists: bool,
+    pub name: String,
+    pub target_table: String,
+    /// Sql of the query, shown by `SHOW CONTINUOUS QUERIES`
+    pub query: String,
+    pub every: Duration,
+    pub for_range: Duration,
+    pub plan: QueryPlan,
+}
+
 #[derive(Debug, Clone)]
 pub enum DMLPlan {
     DeleteFromTable(DeleteFromTable),
@@ -231,6 +580,16 @@ pub struct DeleteFromTable {
 #[derive(Debug, Clone)]
 pub enum SYSPlan {
     KillQuery(QueryId),
+    /// `SET name = value`, the variable is kept for the session id of the context.
+    SetVariable(SessionVariable),
+    /// `AUDIT ON|OFF`, enable or disable the audit of the node.
+    SetAudit(bool),
+    /// `DROP STREAM [IF EXISTS] name`, stop the streams of the name in the
+    /// current database.
+    DropStream {
+        name: String,
+        if_exist: bool,
+    },
 }
 
 impl SYSPlan {
@@ -319,6 +678,8 @@ pub struct CreateTable {
     pub name: ResolvedTable,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
+    /// The table never receives duplicate timestamps
+    pub append_only: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -360,6 +721,7 @@ pub struct ReplicaDestory {
 pub struct ReplicaAdd {
     pub replica_id: ReplicationSetId,
     pub node_id: NodeId,
+    pub learner: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -394,9 +756,37 @@ pub fn unset_option_to_alter_tenant_action(
             tenant_options_builder.unset_drop_after();
             Privilege::Global(GlobalPrivilege::Tenant(Some(tenant_id)))
         }
+        TENANT_OPTION_QUERY_TIMEOUT => {
+            tenant_options_builder.unset_query_timeout();
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_WRITE_TIMEOUT => {
+            tenant_options_builder.unset_write_timeout();
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_DATABASES => {
+            tenant_options_builder.unset_max_databases();
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_TABLES => {
+            tenant_options_builder.unset_max_tables();
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_USERS => {
+            tenant_options_builder.unset_max_users();
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_TABLES_PER_DATABASE => {
+            tenant_options_builder.unset_max_tables_per_database();
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_SERIES_PER_TABLE => {
+            tenant_options_builder.unset_max_series_per_table();
+            Privilege::Global(GlobalPrivilege::System)
+        }
         _ => {
             let source = ParserError::ParserError(format!(
-                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}] found [{}]",
+                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}], [{TENANT_OPTION_QUERY_TIMEOUT}], [{TENANT_OPTION_WRITE_TIMEOUT}], [{TENANT_OPTION_MAX_DATABASES}], [{TENANT_OPTION_MAX_TABLES}], [{TENANT_OPTION_MAX_USERS}], [{TENANT_OPTION_MAX_TABLES_PER_DATABASE}], [{TENANT_OPTION_MAX_SERIES_PER_TABLE}] found [{}]",
                 ident
             ));
             return Err(ParserSnafu.into_error(source));
@@ -412,6 +802,33 @@ pub fn unset_option_to_alter_tenant_action(
     ))
 }
 
+/// Set the encryption key of the tenant if `key` is some, otherwise unset it.
+pub fn encryption_key_to_alter_tenant_action(
+    tenant: Tenant,
+    key_name: Ident,
+    key: Option<EncryptionKey>,
+) -> QueryResult<(AlterTenantAction, Privilege<Oid>)> {
+    let tenant_id = *tenant.id();
+    let mut tenant_options_builder = TenantOptionsBuilder::from(tenant.to_own_options());
+
+    let key_name = normalize_ident(&key_name);
+    match key {
+        Some(key) => {
+            ColumnCipher::from_hex(key.as_str()).context(ModelsSnafu)?;
+            tenant_options_builder.set_encryption_key(&key_name, key);
+        }
+        None => tenant_options_builder.unset_encryption_key(&key_name),
+    }
+    let tenant_options = tenant_options_builder
+        .build()
+        .context(TenantOptionsBuildFailSnafu)?;
+
+    Ok((
+        AlterTenantAction::SetOption(Box::new(tenant_options)),
+        Privilege::Global(GlobalPrivilege::Tenant(Some(tenant_id))),
+    ))
+}
+
 pub fn sql_option_to_alter_tenant_action(
     tenant: Tenant,
     option: SqlOption,
@@ -441,10 +858,42 @@ pub fn sql_option_to_alter_tenant_action(
             tenant_options_builder.drop_after(drop_after);
             Privilege::Global(GlobalPrivilege::Tenant(Some(tenant_id)))
         }
+        TENANT_OPTION_QUERY_TIMEOUT => {
+            let timeout = parse_duration_value(value).context(ParserSnafu)?;
+            tenant_options_builder.query_timeout(timeout);
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_WRITE_TIMEOUT => {
+            let timeout = parse_duration_value(value).context(ParserSnafu)?;
+            tenant_options_builder.write_timeout(timeout);
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_DATABASES => {
+            tenant_options_builder.max_databases(parse_usize_value(value).context(ParserSnafu)?);
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_TABLES => {
+            tenant_options_builder.max_tables(parse_usize_value(value).context(ParserSnafu)?);
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_USERS => {
+            tenant_options_builder.max_users(parse_usize_value(value).context(ParserSnafu)?);
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_TABLES_PER_DATABASE => {
+            tenant_options_builder
+                .max_tables_per_database(parse_usize_value(value).context(ParserSnafu)?);
+            Privilege::Global(GlobalPrivilege::System)
+        }
+        TENANT_OPTION_MAX_SERIES_PER_TABLE => {
+            tenant_options_builder
+                .max_series_per_table(parse_usize_value(value).context(ParserSnafu)?);
+            Privilege::Global(GlobalPrivilege::System)
+        }
         _ => {
             return Err(QueryError::Parser {
                 source: ParserError::ParserError(format!(
-                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}] found [{}]",
+                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}], [{TENANT_OPTION_QUERY_TIMEOUT}], [{TENANT_OPTION_WRITE_TIMEOUT}], [{TENANT_OPTION_MAX_DATABASES}], [{TENANT_OPTION_MAX_TABLES}], [{TENANT_OPTION_MAX_USERS}], [{TENANT_OPTION_MAX_TABLES_PER_DATABASE}], [{TENANT_OPTION_MAX_SERIES_PER_TABLE}] found [{}]",
                 name
             )),
             })
@@ -480,10 +929,31 @@ pub fn sql_options_to_tenant_options(options: Vec<SqlOption>) -> QueryResult<Ten
                 })?;
                 builder.drop_after(drop_after);
             }
+            TENANT_OPTION_QUERY_TIMEOUT => {
+                builder.query_timeout(parse_duration_value(value).context(ParserSnafu)?);
+            }
+            TENANT_OPTION_WRITE_TIMEOUT => {
+                builder.write_timeout(parse_duration_value(value).context(ParserSnafu)?);
+            }
+            TENANT_OPTION_MAX_DATABASES => {
+                builder.max_databases(parse_usize_value(value).context(ParserSnafu)?);
+            }
+            TENANT_OPTION_MAX_TABLES => {
+                builder.max_tables(parse_usize_value(value).context(ParserSnafu)?);
+            }
+            TENANT_OPTION_MAX_USERS => {
+                builder.max_users(parse_usize_value(value).context(ParserSnafu)?);
+            }
+            TENANT_OPTION_MAX_TABLES_PER_DATABASE => {
+                builder.max_tables_per_database(parse_usize_value(value).context(ParserSnafu)?);
+            }
+            TENANT_OPTION_MAX_SERIES_PER_TABLE => {
+                builder.max_series_per_table(parse_usize_value(value).context(ParserSnafu)?);
+            }
             _ => {
                 return Err(QueryError::Parser {
                     source: ParserError::ParserError(format!(
-                        "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}] found [{}]",
+                        "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}], [{TENANT_OPTION_QUERY_TIMEOUT}], [{TENANT_OPTION_WRITE_TIMEOUT}], [{TENANT_OPTION_MAX_DATABASES}], [{TENANT_OPTION_MAX_TABLES}], [{TENANT_OPTION_MAX_USERS}], [{TENANT_OPTION_MAX_TABLES_PER_DATABASE}], [{TENANT_OPTION_MAX_SERIES_PER_TABLE}] found [{}]",
                         name
                     )),
                 })
@@ -532,9 +1002,15 @@ pub fn sql_options_to_user_options(
             "hash_password" => {
                 builder.hash_password(parse_string_value(value)?);
             }
+            "query_timeout" => {
+                builder.query_timeout(parse_duration_value(value)?);
+            }
+            "write_timeout" => {
+                builder.write_timeout(parse_duration_value(value)?);
+            }
             _ => {
                 return Err(ParserError::ParserError(format!(
-                "Expected option [password | rsa_public_key | comment | granted_admin], found [{}]",
+                "Expected option [password | rsa_public_key | comment | granted_admin | query_timeout | write_timeout], found [{}]",
                 name
             )))
             }
@@ -561,6 +1037,8 @@ pub struct GrantRevoke {
     pub is_grant: bool,
     // privilege, db name
     pub database_privileges: Vec<(DatabasePrivilege, String)>,
+    // privilege, db name, table name
+    pub table_privileges: Vec<(TablePrivilege, String, String)>,
     pub tenant_name: String,
     pub role_name: String,
 }
@@ -615,6 +1093,15 @@ pub struct AlterTable {
     pub alter_action: AlterTableAction,
 }
 
+/// Merge the value `from` of the tag `tag_name` into `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeTagValue {
+    pub table_name: ResolvedTable,
+    pub tag_name: String,
+    pub from: String,
+    pub to: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AlterTableAction {
     AddColumn {
@@ -631,6 +1118,9 @@ pub enum AlterTableAction {
         old_column_name: String,
         new_column_name: String,
     },
+    SetTtl {
+        ttl: Option<CnosDuration>,
+    },
 }
 
 #[async_trait]
@@ -654,6 +1144,17 @@ pub fn merge_affected_row_expr() -> Expr {
     expr_fn::sum(col(AFFECTED_ROWS.0)).alias(AFFECTED_ROWS.0)
 }
 
+/// Additional output information of the writes to external locations
+pub fn affected_bytes_expr(args: Vec<Expr>) -> Expr {
+    let udf = expr::ScalarUDF::new(TABLE_WRITE_BYTES_UDF.clone(), args);
+
+    Expr::ScalarUDF(udf).alias(AFFECTED_BYTES.0)
+}
+
+pub fn merge_affected_bytes_expr() -> Expr {
+    expr_fn::sum(col(AFFECTED_BYTES.0)).alias(AFFECTED_BYTES.0)
+}
+
 /// Normalize a SQL object name
 pub fn normalize_sql_object_name_to_string(sql_object_name: &ObjectName) -> String {
     sql_object_name
@@ -674,11 +1175,15 @@ pub fn normalize_ident(id: &Ident) -> String {
 
 pub struct CopyOptions {
     pub auto_infer_schema: bool,
+    /// Number of partitions the source files are read and written in,
+    /// defaults to the `target_partitions` of the session.
+    pub parallelism: Option<usize>,
 }
 
 #[derive(Default)]
 pub struct CopyOptionsBuilder {
     auto_infer_schema: Option<bool>,
+    parallelism: Option<usize>,
 }
 
 impl CopyOptionsBuilder {
@@ -693,6 +1198,15 @@ impl CopyOptionsBuilder {
                 "auto_infer_schema" => {
                     self.auto_infer_schema = Some(parse_bool_value(value).context(ParserSnafu)?);
                 }
+                "parallelism" => {
+                    let parallelism = parse_usize_value(value).context(ParserSnafu)?;
+                    if parallelism == 0 {
+                        return Err(QueryError::Semantic {
+                            err: "parallelism must be greater than 0".to_string(),
+                        });
+                    }
+                    self.parallelism = Some(parallelism);
+                }
                 option => {
                     return Err(QueryError::Semantic {
                         err: format!("Unsupported option [{}]", option),
@@ -708,6 +1222,7 @@ impl CopyOptionsBuilder {
     pub fn build(self) -> CopyOptions {
         CopyOptions {
             auto_infer_schema: self.auto_infer_schema.unwrap_or_default(),
+            parallelism: self.parallelism,
         }
     }
 }
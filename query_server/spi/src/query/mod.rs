@@ -18,5 +18,7 @@ pub mod session;
 pub mod variable;
 
 pub const AFFECTED_ROWS: (&str, DataType) = ("rows", DataType::UInt64);
+/// Bytes of the files written by `COPY (<query>) TO '<uri>'`.
+pub const AFFECTED_BYTES: (&str, DataType) = ("bytes", DataType::UInt64);
 
 pub const UNEXPECTED_EXTERNAL_PLAN: &str = "Unexpected plan, maybe it's a df problem";
@@ -10,19 +10,24 @@ use datafusion::execution::memory_pool::MemoryPool;
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::prelude::{SessionConfig, SessionContext};
 use datafusion::variable::VarType;
+use models::auth::auth_cache::AuthCache;
 use models::auth::user::User;
 use models::oid::Oid;
 use trace::span_ext::SpanExt;
 use trace::{Span, SpanContext};
 
-use super::config::StreamTriggerInterval;
+use super::config::{SessionVariable, StreamTriggerInterval};
 use super::variable::VarProviderRef;
 use crate::service::protocol::Context;
-use crate::QueryResult;
+use crate::{QueryError, QueryResult};
 
 extensions_options! {
     pub struct SqlExecInfo {
         pub copyinto_trigger_flush_size: u64, default = 128 * 1024 * 1024 // 128MB
+        pub ignore_retention: bool, default = false
+        pub max_replica_lag_ms: Option<u64>, default = None // read the leader only if None
+        pub follower_read: bool, default = false
+        pub cleanup_partial_write: bool, default = false // delete the rows written by a failed INSERT
     }
 }
 impl ConfigExtension for SqlExecInfo {
@@ -77,6 +82,84 @@ impl SessionCtx {
     pub fn get_child_span(&self, name: &'static str) -> Span {
         Span::from_context(name, self.get_span_ctx())
     }
+
+    /// Keep the variable changed by `SET` for the following queries of the
+    /// session, fails if the query is not in a session.
+    pub fn set_variable(&self, variable: SessionVariable) -> QueryResult<()> {
+        let session_id = self
+            .desc
+            .session_id
+            .as_deref()
+            .ok_or_else(|| QueryError::NotImplemented {
+                err: format!(
+                    "SET {} is only supported in a session, send the session_id with the queries or set the variable by the parameter of the request",
+                    variable.name()
+                ),
+            })?;
+        self.desc
+            .session_variables
+            .set(self.tenant(), self.user(), session_id, variable);
+        Ok(())
+    }
+}
+
+/// Maximum number of the sessions whose variables are kept, the least
+/// recently used sessions are evicted.
+const SESSION_VARIABLES_CAPACITY: usize = 10_000;
+
+/// The variables changed by `SET` in the sessions of the clients, keyed by the
+/// tenant, the user and the session id, so that a session can only be used by
+/// the user who started it.
+pub struct SessionVariables {
+    sessions: AuthCache<(String, String, String), Vec<SessionVariable>>,
+}
+
+impl SessionVariables {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sessions: AuthCache::new(capacity, None),
+        }
+    }
+
+    /// Keep the variable of the session, the variable reset to `DEFAULT` is removed.
+    pub fn set(&self, tenant: &str, user: &User, session_id: &str, variable: SessionVariable) {
+        let key = Self::key(tenant, user, session_id);
+        let mut variables = self.sessions.get(&key).unwrap_or_default();
+        variables.retain(|v| v.name() != variable.name());
+        if !variable.is_default() {
+            variables.push(variable);
+        }
+        self.sessions.insert(key, variables);
+    }
+
+    /// Apply the variables of the session of the context, they override the
+    /// ones of the request.
+    pub fn apply(&self, context: &Context) -> Context {
+        let variables = context
+            .session_id()
+            .and_then(|session_id| {
+                self.sessions
+                    .get(&Self::key(context.tenant(), context.user(), session_id))
+            })
+            .unwrap_or_default();
+        variables.iter().fold(context.clone(), |context, variable| {
+            context.with_session_variable(variable)
+        })
+    }
+
+    fn key(tenant: &str, user: &User, session_id: &str) -> (String, String, String) {
+        (
+            tenant.to_string(),
+            user.desc().name().to_string(),
+            session_id.to_string(),
+        )
+    }
+}
+
+impl Default for SessionVariables {
+    fn default() -> Self {
+        Self::new(SESSION_VARIABLES_CAPACITY)
+    }
 }
 
 #[derive(Clone)]
@@ -90,6 +173,9 @@ pub struct SessionCtxDesc {
     default_database: String,
 
     query_dedicated_hidden_dir: PathBuf,
+
+    session_id: Option<String>,
+    session_variables: Arc<SessionVariables>,
 }
 
 #[derive(Default)]
@@ -97,6 +183,7 @@ pub struct SessionCtxFactory {
     sys_var_provider: Option<VarProviderRef>,
     query_dedicated_hidden_dir: PathBuf,
     session_function_register: Option<fn(df_session_ctx: &SessionContext, context: &Context)>,
+    session_variables: Arc<SessionVariables>,
 }
 
 impl SessionCtxFactory {
@@ -109,6 +196,7 @@ impl SessionCtxFactory {
             sys_var_provider,
             query_dedicated_hidden_dir,
             session_function_register,
+            session_variables: Default::default(),
         }
     }
 
@@ -121,6 +209,7 @@ impl SessionCtxFactory {
         span_ctx: Option<SpanContext>,
         coord: Arc<dyn Coordinator>,
     ) -> QueryResult<SessionCtx> {
+        let context = &self.session_variables.apply(context);
         let df_session_ctx =
             self.build_df_session_context(session_id, context, memory_pool, &span_ctx, coord)?;
 
@@ -131,6 +220,8 @@ impl SessionCtxFactory {
                 tenant: context.tenant().to_owned(),
                 default_database: context.database().to_owned(),
                 query_dedicated_hidden_dir: self.query_dedicated_hidden_dir.clone(),
+                session_id: context.session_id().map(str::to_string),
+                session_variables: self.session_variables.clone(),
             }),
             inner: df_session_ctx.state(),
             span_ctx,
@@ -159,6 +250,31 @@ impl SessionCtxFactory {
             "sql_exec_info.copyinto_trigger_flush_size",
             coord.get_config().storage.copyinto_trigger_flush_size,
         );
+        if context.ignore_retention() {
+            // Only admins may read the expired data.
+            if !context.user().desc().is_admin() {
+                return Err(QueryError::InsufficientPrivileges {
+                    privilege: "ignore retention".to_string(),
+                });
+            }
+            config = config.set_bool("sql_exec_info.ignore_retention", true);
+        }
+        if let Some(max_replica_lag) = context.max_replica_lag() {
+            config = config.set_u64(
+                "sql_exec_info.max_replica_lag_ms",
+                max_replica_lag.as_millis() as u64,
+            );
+        }
+        if context.follower_read() {
+            config = config.set_bool("sql_exec_info.follower_read", true);
+        }
+        if context.cleanup_partial_write() {
+            config = config.set_bool("sql_exec_info.cleanup_partial_write", true);
+        }
+
+        if let Some(time_zone) = context.time_zone() {
+            config.options_mut().execution.time_zone = Some(time_zone.to_string());
+        }
 
         let rt_config = RuntimeConfig::new().with_memory_pool(memory_pool);
         let rt = RuntimeEnv::new(rt_config)?;
@@ -213,3 +329,86 @@ impl CnosSessionConfig {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use coordinator::service_mock::MockCoordinator;
+    use datafusion::execution::memory_pool::UnboundedMemoryPool;
+    use models::auth::user::{User, UserDesc, UserOptions};
+
+    use super::{SessionCtx, SessionCtxFactory};
+    use crate::query::config::SessionVariable;
+    use crate::service::protocol::{Context, ContextBuilder};
+
+    fn context(user: &str, session_id: Option<&str>) -> Context {
+        let desc = UserDesc::new(0, user.to_string(), UserOptions::default(), false);
+        ContextBuilder::new(User::new(desc, Default::default(), None))
+            .with_time_zone(Some("+01:00".to_string()))
+            .with_session_id(session_id.map(str::to_string))
+            .build()
+    }
+
+    fn session_ctx(factory: &SessionCtxFactory, context: &Context) -> SessionCtx {
+        factory
+            .create_session_ctx(
+                "query_id",
+                context,
+                0,
+                Arc::new(UnboundedMemoryPool::default()),
+                None,
+                Arc::new(MockCoordinator {}),
+            )
+            .unwrap()
+    }
+
+    fn time_zone(session: &SessionCtx) -> Option<String> {
+        session
+            .inner()
+            .config()
+            .options()
+            .execution
+            .time_zone
+            .clone()
+    }
+
+    #[test]
+    fn test_set_variable_in_session() {
+        let factory = SessionCtxFactory::new(None, "/tmp".into(), None);
+        let ctx = context("tester", Some("s1"));
+
+        let session = session_ctx(&factory, &ctx);
+        session
+            .set_variable(SessionVariable::TimeZone(Some("+08:00".to_string())))
+            .unwrap();
+        session
+            .set_variable(SessionVariable::TargetPartitions(Some(3)))
+            .unwrap();
+
+        // The following queries of the session.
+        let session = session_ctx(&factory, &ctx);
+        assert_eq!(time_zone(&session).as_deref(), Some("+08:00"));
+        assert_eq!(session.inner().config().target_partitions(), 3);
+
+        // Other sessions, or the same session id of other users.
+        let other = session_ctx(&factory, &context("tester", Some("s2")));
+        assert_eq!(time_zone(&other).as_deref(), Some("+01:00"));
+        let other = session_ctx(&factory, &context("other", Some("s1")));
+        assert_eq!(time_zone(&other).as_deref(), Some("+01:00"));
+
+        // Reset to the value of the request.
+        session
+            .set_variable(SessionVariable::TimeZone(None))
+            .unwrap();
+        let session = session_ctx(&factory, &ctx);
+        assert_eq!(time_zone(&session).as_deref(), Some("+01:00"));
+        assert_eq!(session.inner().config().target_partitions(), 3);
+
+        // Not in a session.
+        let session = session_ctx(&factory, &context("tester", None));
+        assert!(session
+            .set_variable(SessionVariable::TargetPartitions(Some(3)))
+            .is_err());
+    }
+}
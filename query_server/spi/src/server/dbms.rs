@@ -13,7 +13,7 @@ use trace::SpanContext;
 use crate::query::execution::{Output, QueryStateMachine, QueryStateMachineRef};
 use crate::query::logical_planner::Plan;
 use crate::query::recordbatch::RecordBatchStreamWrapper;
-use crate::service::protocol::{Query, QueryHandle};
+use crate::service::protocol::{Context, Query, QueryHandle};
 use crate::QueryResult;
 
 pub type DBMSRef = Arc<dyn DatabaseManagerSystem + Send + Sync>;
@@ -21,7 +21,13 @@ pub type DBMSRef = Arc<dyn DatabaseManagerSystem + Send + Sync>;
 #[async_trait]
 pub trait DatabaseManagerSystem {
     async fn start(&self) -> QueryResult<()>;
-    async fn authenticate(&self, user_info: &UserInfo, tenant_name: &str) -> QueryResult<User>;
+    /// Authenticate the user logging in from the client address, if it's known.
+    async fn authenticate(
+        &self,
+        user_info: &UserInfo,
+        tenant_name: &str,
+        client_addr: Option<&str>,
+    ) -> QueryResult<User>;
     async fn execute(
         &self,
         query: &Query,
@@ -41,8 +47,16 @@ pub trait DatabaseManagerSystem {
         logical_plan: Plan,
         query_state_machine: QueryStateMachineRef,
     ) -> QueryResult<QueryHandle>;
+    /// Returns the optimized logical plan and physical plan of the query without executing it.
+    async fn explain(
+        &self,
+        query: &Query,
+        span_context: Option<&SpanContext>,
+    ) -> QueryResult<QueryPlanDescription>;
     fn metrics(&self) -> String;
     fn cancel(&self, query_id: &QueryId);
+    /// Audit the write of the `protocol` other than SQL, e.g. line protocol.
+    fn audit_write(&self, context: &Context, protocol: &str, error: Option<String>);
 }
 
 pub struct DatabaseManagerSystemMock {}
@@ -52,7 +66,12 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
     async fn start(&self) -> QueryResult<()> {
         Ok(())
     }
-    async fn authenticate(&self, user_info: &UserInfo, _tenant_name: &str) -> QueryResult<User> {
+    async fn authenticate(
+        &self,
+        user_info: &UserInfo,
+        _tenant_name: &str,
+        _client_addr: Option<&str>,
+    ) -> QueryResult<User> {
         let options = unsafe {
             UserOptionsBuilder::default()
                 .password(user_info.password.clone())
@@ -134,6 +153,14 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
         .await
     }
 
+    async fn explain(
+        &self,
+        _query: &Query,
+        _span_context: Option<&SpanContext>,
+    ) -> QueryResult<QueryPlanDescription> {
+        Ok(QueryPlanDescription::default())
+    }
+
     fn metrics(&self) -> String {
         "todo!()".to_string()
     }
@@ -141,4 +168,6 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
     fn cancel(&self, query_id: &QueryId) {
         println!("DatabaseManagerSystemMock::cancel({:?})", query_id);
     }
+
+    fn audit_write(&self, _context: &Context, _protocol: &str, _error: Option<String>) {}
 }
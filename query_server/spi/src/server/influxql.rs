@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use trace::SpanContext;
+
+use crate::service::protocol::Context;
+use crate::QueryResult;
+
+pub type InfluxqlServerRef = Arc<dyn InfluxqlServer + Send + Sync>;
+
+#[async_trait]
+pub trait InfluxqlServer {
+    /// Executes the InfluxQL query and returns the result in the JSON format of InfluxDB 1.x,
+    /// `epoch` is the precision of timestamps, RFC3339 strings are returned if it's None.
+    async fn query(
+        &self,
+        ctx: &Context,
+        query: &str,
+        epoch: Option<&str>,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<u8>>;
+}
@@ -1,2 +1,3 @@
 pub mod dbms;
+pub mod influxql;
 pub mod prom;
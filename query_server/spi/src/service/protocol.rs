@@ -1,8 +1,11 @@
+use std::time::Duration;
+
 use models::auth::user::User;
 use models::schema::query_info::QueryId;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE, DEFAULT_PRECISION};
+use serde::{Deserialize, Serialize};
 
-use crate::query::config::StreamTriggerInterval;
+use crate::query::config::{SessionVariable, StreamTriggerInterval};
 use crate::query::execution::Output;
 use crate::query::session::CnosSessionConfig;
 
@@ -19,6 +22,14 @@ pub struct Context {
     chunked: bool,
     session_config: CnosSessionConfig,
     is_old: bool,
+    consistent_meta: Option<bool>,
+    ignore_retention: bool,
+    max_replica_lag: Option<Duration>,
+    follower_read: bool,
+    cleanup_partial_write: bool,
+    time_zone: Option<String>,
+    client_addr: Option<String>,
+    session_id: Option<String>,
 }
 
 impl Context {
@@ -47,6 +58,70 @@ impl Context {
     pub fn is_old(&self) -> bool {
         self.is_old
     }
+    /// Whether to read the catalog consistently from the meta leader,
+    /// None means only after schema changes.
+    pub fn consistent_meta(&self) -> Option<bool> {
+        self.consistent_meta
+    }
+    /// Whether the rows older than the TTL of the database but not deleted yet are visible.
+    pub fn ignore_retention(&self) -> bool {
+        self.ignore_retention
+    }
+    /// Scans may read a follower lagging behind the leader within this bound,
+    /// None means the leader is always read.
+    pub fn max_replica_lag(&self) -> Option<Duration> {
+        self.max_replica_lag
+    }
+    /// Scans may read a follower after it applies the raft logs committed by
+    /// the leader when the query starts, which is as consistent as the leader.
+    pub fn follower_read(&self) -> bool {
+        self.follower_read
+    }
+    /// Whether to delete the rows written by an `INSERT` which fails or is
+    /// cancelled, all the rows within the time range written are deleted.
+    pub fn cleanup_partial_write(&self) -> bool {
+        self.cleanup_partial_write
+    }
+    /// The time zone of the session, None means the default `+00:00`.
+    pub fn time_zone(&self) -> Option<&str> {
+        self.time_zone.as_deref()
+    }
+    /// The address of the client, None if it's unknown.
+    pub fn client_addr(&self) -> Option<&str> {
+        self.client_addr.as_deref()
+    }
+    /// The session of the client keeping the variables changed by `SET`,
+    /// None if the queries are not in a session.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Use the time zone, e.g. the one of the database, if the context does
+    /// not set one.
+    pub fn with_default_time_zone(mut self, time_zone: Option<String>) -> Self {
+        if self.time_zone.is_none() {
+            self.time_zone = time_zone;
+        }
+        self
+    }
+
+    /// Apply the variable changed by `SET` in the session, the variable reset
+    /// to `DEFAULT` keeps the value of the context.
+    pub fn with_session_variable(mut self, variable: &SessionVariable) -> Self {
+        match variable {
+            SessionVariable::TimeZone(Some(time_zone)) => {
+                self.time_zone = Some(time_zone.clone());
+            }
+            SessionVariable::TargetPartitions(Some(n)) => {
+                self.session_config = self.session_config.with_target_partitions(*n);
+            }
+            SessionVariable::CleanupPartialWrite(Some(cleanup)) => {
+                self.cleanup_partial_write = *cleanup;
+            }
+            _ => {}
+        }
+        self
+    }
 }
 
 pub struct ContextBuilder {
@@ -57,6 +132,14 @@ pub struct ContextBuilder {
     chunked: bool,
     session_config: CnosSessionConfig,
     is_old: bool,
+    consistent_meta: Option<bool>,
+    ignore_retention: bool,
+    max_replica_lag: Option<Duration>,
+    follower_read: bool,
+    cleanup_partial_write: bool,
+    time_zone: Option<String>,
+    client_addr: Option<String>,
+    session_id: Option<String>,
 }
 
 impl ContextBuilder {
@@ -69,6 +152,14 @@ impl ContextBuilder {
             chunked: Default::default(),
             session_config: Default::default(),
             is_old: Default::default(),
+            consistent_meta: Default::default(),
+            ignore_retention: Default::default(),
+            max_replica_lag: Default::default(),
+            follower_read: Default::default(),
+            cleanup_partial_write: Default::default(),
+            time_zone: Default::default(),
+            client_addr: Default::default(),
+            session_id: Default::default(),
         }
     }
 
@@ -123,6 +214,54 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_consistent_meta(mut self, consistent_meta: Option<bool>) -> Self {
+        self.consistent_meta = consistent_meta;
+        self
+    }
+
+    pub fn with_ignore_retention(mut self, ignore_retention: Option<bool>) -> Self {
+        if let Some(ignore_retention) = ignore_retention {
+            self.ignore_retention = ignore_retention;
+        }
+        self
+    }
+
+    pub fn with_max_replica_lag(mut self, max_replica_lag: Option<Duration>) -> Self {
+        self.max_replica_lag = max_replica_lag;
+        self
+    }
+
+    pub fn with_follower_read(mut self, follower_read: Option<bool>) -> Self {
+        if let Some(follower_read) = follower_read {
+            self.follower_read = follower_read;
+        }
+        self
+    }
+
+    pub fn with_cleanup_partial_write(mut self, cleanup_partial_write: Option<bool>) -> Self {
+        if let Some(cleanup_partial_write) = cleanup_partial_write {
+            self.cleanup_partial_write = cleanup_partial_write;
+        }
+        self
+    }
+
+    pub fn with_time_zone(mut self, time_zone: Option<String>) -> Self {
+        if let Some(time_zone) = time_zone {
+            self.time_zone = Some(time_zone);
+        }
+        self
+    }
+
+    pub fn with_client_addr(mut self, client_addr: Option<String>) -> Self {
+        self.client_addr = client_addr;
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: Option<String>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
     pub fn build(self) -> Context {
         Context {
             user: self.user,
@@ -132,6 +271,14 @@ impl ContextBuilder {
             chunked: self.chunked,
             session_config: self.session_config,
             is_old: self.is_old,
+            consistent_meta: self.consistent_meta,
+            ignore_retention: self.ignore_retention,
+            max_replica_lag: self.max_replica_lag,
+            follower_read: self.follower_read,
+            cleanup_partial_write: self.cleanup_partial_write,
+            time_zone: self.time_zone,
+            client_addr: self.client_addr,
+            session_id: self.session_id,
         }
     }
 }
@@ -181,3 +328,10 @@ impl QueryHandle {
         self.result
     }
 }
+
+/// The optimized logical plan and physical plan of a statement, without executing it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryPlanDescription {
+    pub logical_plan: String,
+    pub physical_plan: String,
+}
@@ -5,9 +5,11 @@ use std::path::Path;
 use async_trait::async_trait;
 use heed::types::*;
 use heed::{Database, Env};
+use redb::{ReadableTable, TableDefinition};
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
+use crate::entry_store::redb_error;
 use crate::errors::{HeedSnafu, IOErrSnafu, MsgInvalidSnafu, ReplicationResult};
 use crate::{ApplyContext, ApplyStorage, EngineMetrics, Request, Response};
 
@@ -18,6 +20,12 @@ pub struct HashMapSnapshotData {
     pub map: HashMap<String, String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RequestCommand {
+    key: String,
+    value: String,
+}
+
 pub struct HeedApplyStorage {
     env: Env,
     db: Database<Str, Str>,
@@ -76,12 +84,6 @@ impl HeedApplyStorage {
 #[async_trait]
 impl ApplyStorage for HeedApplyStorage {
     async fn apply(&mut self, _ctx: &ApplyContext, req: &Request) -> ReplicationResult<Response> {
-        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
-        struct RequestCommand {
-            key: String,
-            value: String,
-        }
-
         let req: RequestCommand = serde_json::from_slice(req)
             .map_err(|e| MsgInvalidSnafu { msg: e.to_string() }.build())?;
         let mut writer = self.env.write_txn().context(HeedSnafu)?;
@@ -148,3 +150,140 @@ impl ApplyStorage for HeedApplyStorage {
         }
     }
 }
+
+// --------------------------------------------------------------------------- //
+const REDB_FILE: &str = "data.redb";
+const DATA_TABLE: TableDefinition<&str, &str> = TableDefinition::new("data");
+
+/// The key-value storage of `HeedApplyStorage` stored by redb.
+pub struct RedbApplyStorage {
+    db: redb::Database,
+    snapshot: Option<(Vec<u8>, u64)>,
+}
+
+impl RedbApplyStorage {
+    pub fn open(path: impl AsRef<Path>) -> ReplicationResult<Self> {
+        fs::create_dir_all(&path).context(IOErrSnafu)?;
+
+        let db = redb::Database::create(path.as_ref().join(REDB_FILE)).map_err(redb_error)?;
+        // Create the table, it can't be opened by a read transaction otherwise.
+        let writer = db.begin_write().map_err(redb_error)?;
+        writer.open_table(DATA_TABLE).map_err(redb_error)?;
+        writer.commit().map_err(redb_error)?;
+
+        Ok(Self { db, snapshot: None })
+    }
+
+    pub fn get(&self, key: &str) -> ReplicationResult<Option<String>> {
+        let reader = self.db.begin_read().map_err(redb_error)?;
+        let table = reader.open_table(DATA_TABLE).map_err(redb_error)?;
+        let value = table.get(key).map_err(redb_error)?;
+
+        Ok(value.map(|v| v.value().to_string()))
+    }
+}
+
+#[async_trait]
+impl ApplyStorage for RedbApplyStorage {
+    async fn apply(&mut self, _ctx: &ApplyContext, req: &Request) -> ReplicationResult<Response> {
+        let req: RequestCommand = serde_json::from_slice(req)
+            .map_err(|e| MsgInvalidSnafu { msg: e.to_string() }.build())?;
+        let writer = self.db.begin_write().map_err(redb_error)?;
+        {
+            let mut table = writer.open_table(DATA_TABLE).map_err(redb_error)?;
+            table
+                .insert(req.key.as_str(), req.value.as_str())
+                .map_err(redb_error)?;
+        }
+        writer.commit().map_err(redb_error)?;
+
+        Ok(req.value.into())
+    }
+
+    async fn get_snapshot(&mut self) -> ReplicationResult<Option<(Vec<u8>, u64)>> {
+        Ok(self.snapshot.clone())
+    }
+
+    async fn create_snapshot(&mut self, applied_id: u64) -> ReplicationResult<(Vec<u8>, u64)> {
+        let mut hash_map = HashMap::new();
+        let reader = self.db.begin_read().map_err(redb_error)?;
+        let table = reader.open_table(DATA_TABLE).map_err(redb_error)?;
+        for pair in table.iter().map_err(redb_error)? {
+            let (key, val) = pair.map_err(redb_error)?;
+            hash_map.insert(key.value().to_string(), val.value().to_string());
+        }
+
+        let data = HashMapSnapshotData { map: hash_map };
+        let bytes = serde_json::to_vec(&data)
+            .map_err(|e| MsgInvalidSnafu { msg: e.to_string() }.build())?;
+
+        self.snapshot = Some((bytes.clone(), applied_id));
+
+        Ok((bytes, applied_id))
+    }
+
+    async fn restore(&mut self, snapshot: &[u8]) -> ReplicationResult<()> {
+        let data: HashMapSnapshotData = serde_json::from_slice(snapshot)
+            .map_err(|e| MsgInvalidSnafu { msg: e.to_string() }.build())?;
+        let writer = self.db.begin_write().map_err(redb_error)?;
+        writer.delete_table(DATA_TABLE).map_err(redb_error)?;
+        {
+            let mut table = writer.open_table(DATA_TABLE).map_err(redb_error)?;
+            for (key, val) in data.map.iter() {
+                table
+                    .insert(key.as_str(), val.as_str())
+                    .map_err(redb_error)?;
+            }
+        }
+        writer.commit().map_err(redb_error)?;
+
+        Ok(())
+    }
+
+    async fn destory(&mut self) -> ReplicationResult<()> {
+        Ok(())
+    }
+
+    async fn metrics(&self) -> ReplicationResult<EngineMetrics> {
+        let snapshot_apply_id = self.snapshot.as_ref().map(|s| s.1).unwrap_or_default();
+        Ok(EngineMetrics {
+            snapshot_apply_id,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RedbApplyStorage;
+    use crate::{ApplyContext, ApplyStorage};
+
+    fn request(key: &str, value: &str) -> Vec<u8> {
+        format!(r#"{{"key":"{key}","value":"{value}"}}"#).into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_redb_apply_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ApplyContext::default();
+        let mut storage = RedbApplyStorage::open(dir.path()).unwrap();
+        storage.apply(&ctx, &request("a", "1")).await.unwrap();
+        storage.apply(&ctx, &request("b", "2")).await.unwrap();
+        assert_eq!(storage.get("a").unwrap().as_deref(), Some("1"));
+
+        let (snapshot, applied_id) = storage.create_snapshot(2).await.unwrap();
+        assert_eq!(applied_id, 2);
+        assert_eq!(storage.metrics().await.unwrap().snapshot_apply_id, 2);
+
+        // Restoring the snapshot drops the keys applied after it.
+        storage.apply(&ctx, &request("c", "3")).await.unwrap();
+        storage.restore(&snapshot).await.unwrap();
+        assert_eq!(storage.get("b").unwrap().as_deref(), Some("2"));
+        assert!(storage.get("c").unwrap().is_none());
+
+        // Reopen the storage.
+        drop(storage);
+        let storage = RedbApplyStorage::open(dir.path()).unwrap();
+        assert_eq!(storage.get("a").unwrap().as_deref(), Some("1"));
+    }
+}
@@ -1,17 +1,95 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use heed::byteorder::BigEndian;
 use heed::types::*;
 use heed::{Database, Env};
 use openraft::Entry;
-use snafu::ResultExt;
+use redb::{ReadableTable, TableDefinition};
+use snafu::{IntoError, ResultExt};
+use tokio::sync::RwLock;
 
-use crate::errors::{HeedSnafu, IOErrSnafu, MsgInvalidSnafu, ReplicationResult};
-use crate::{EntriesMetrics, EntryStorage, TypeConfig};
+use crate::errors::{
+    HeedSnafu, IOErrSnafu, MsgInvalidSnafu, RedbSnafu, ReplicationError, ReplicationResult,
+};
+use crate::{EntriesMetrics, EntryStorage, EntryStorageEngine, EntryStorageRef, TypeConfig};
+
+/// Open the raft entries in the directory `path` stored by the `engine`. The
+/// entries stored by LMDB must be migrated before they are opened by redb.
+pub fn open_entry_storage(
+    engine: EntryStorageEngine,
+    path: impl AsRef<Path>,
+    size: usize,
+) -> ReplicationResult<EntryStorageRef> {
+    let storage: EntryStorageRef = match engine {
+        EntryStorageEngine::Lmdb => Arc::new(RwLock::new(HeedEntryStorage::open(path, size)?)),
+        EntryStorageEngine::Redb => {
+            let path = path.as_ref();
+            if path.join(LMDB_FILE).exists() && !path.join(REDB_FILE).exists() {
+                return Err(MsgInvalidSnafu {
+                    msg: format!(
+                        "raft entries in {} are stored by LMDB, migrate them by \"cnosdb-meta migrate-entries\" first",
+                        path.display()
+                    ),
+                }
+                .build());
+            }
+            Arc::new(RwLock::new(RedbEntryStorage::open(path)?))
+        }
+    };
+
+    Ok(storage)
+}
+
+/// Copy the raft entries in the directory `path` stored by LMDB to redb, in
+/// the same directory, returns the number of entries copied. The entries are
+/// copied to a temporary file renamed at the end, an interrupted migration
+/// continues from the last entry copied. The LMDB files are kept, remove them
+/// once the node runs well with redb.
+pub async fn migrate_entries_to_redb(
+    path: impl AsRef<Path>,
+    size: usize,
+) -> ReplicationResult<u64> {
+    const BATCH_SIZE: u64 = 1024;
+
+    let path = path.as_ref();
+    if path.join(REDB_FILE).exists() {
+        return Err(MsgInvalidSnafu {
+            msg: format!(
+                "raft entries in {} are already stored by redb",
+                path.display()
+            ),
+        }
+        .build());
+    }
+
+    let mut from = HeedEntryStorage::open(path, size)?;
+    let mut to = RedbEntryStorage::open_file(path, REDB_MIGRATING_FILE)?;
+    let mut count = 0;
+    if let (Some(first), Some(last)) = (from.first_entry().await?, from.last_entry().await?) {
+        let mut low = match to.last_entry().await? {
+            Some(copied) => copied.log_id.index + 1,
+            None => first.log_id.index,
+        };
+        while low <= last.log_id.index {
+            let high = (low + BATCH_SIZE).min(last.log_id.index + 1);
+            let ents = from.entries(low, high).await?;
+            to.append(&ents).await?;
+            count += ents.len() as u64;
+            low = high;
+        }
+    }
+    drop(to);
+    fs::rename(path.join(REDB_MIGRATING_FILE), path.join(REDB_FILE)).context(IOErrSnafu)?;
+
+    Ok(count)
+}
 
 // --------------------------------------------------------------------------- //
+const LMDB_FILE: &str = "data.mdb";
+
 type BEU64 = U64<BigEndian>;
 pub struct HeedEntryStorage {
     env: Env,
@@ -148,6 +226,173 @@ impl EntryStorage for HeedEntryStorage {
             min_seq: first.log_id.index,
             max_seq: last.log_id.index,
             avg_write_time: 0,
+            avg_sync_time: 0,
+            unsynced_bytes: 0,
+            disk_size: fs::metadata(self.path.join(LMDB_FILE)).map_or(0, |m| m.len()),
+        })
+    }
+
+    async fn sync(&mut self) -> ReplicationResult<()> {
+        Ok(())
+    }
+}
+
+// --------------------------------------------------------------------------- //
+const REDB_FILE: &str = "data.redb";
+/// The file of redb being written by `migrate_entries_to_redb`.
+const REDB_MIGRATING_FILE: &str = "data.redb.migrating";
+const ENTRIES_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("entries");
+
+pub(crate) fn redb_error(err: impl Into<redb::Error>) -> ReplicationError {
+    RedbSnafu.into_error(err.into())
+}
+
+/// Raft entries stored by redb, the size of the file is not limited by a map
+/// size like LMDB.
+pub struct RedbEntryStorage {
+    db: redb::Database,
+    path: PathBuf,
+}
+
+impl RedbEntryStorage {
+    pub fn open(path: impl AsRef<Path>) -> ReplicationResult<Self> {
+        Self::open_file(path, REDB_FILE)
+    }
+
+    fn open_file(path: impl AsRef<Path>, file_name: &str) -> ReplicationResult<Self> {
+        fs::create_dir_all(&path).context(IOErrSnafu)?;
+
+        let path = path.as_ref().to_path_buf();
+        let db = redb::Database::create(path.join(file_name)).map_err(redb_error)?;
+        // Create the table, it can't be opened by a read transaction otherwise.
+        let writer = db.begin_write().map_err(redb_error)?;
+        writer.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+        writer.commit().map_err(redb_error)?;
+
+        Ok(Self { db, path })
+    }
+
+    fn decode(data: &[u8]) -> ReplicationResult<Entry<TypeConfig>> {
+        bincode::deserialize::<Entry<TypeConfig>>(data)
+            .map_err(|e| MsgInvalidSnafu { msg: e.to_string() }.build())
+    }
+
+    fn first_entry(&self) -> ReplicationResult<Option<Entry<TypeConfig>>> {
+        let reader = self.db.begin_read().map_err(redb_error)?;
+        let table = reader.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+        let mut iter = table.range::<u64>(..).map_err(redb_error)?;
+        match iter.next() {
+            Some(pair) => {
+                let (_, data) = pair.map_err(redb_error)?;
+                Ok(Some(Self::decode(data.value())?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Delete the entries of the indexes in the `range`.
+    fn delete_range(&self, range: impl std::ops::RangeBounds<u64>) -> ReplicationResult<()> {
+        let writer = self.db.begin_write().map_err(redb_error)?;
+        {
+            let mut table = writer.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+            let mut indexes = vec![];
+            for pair in table.range::<u64>(range).map_err(redb_error)? {
+                let (index, _) = pair.map_err(redb_error)?;
+                indexes.push(index.value());
+            }
+            for index in indexes {
+                table.remove(index).map_err(redb_error)?;
+            }
+        }
+        writer.commit().map_err(redb_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EntryStorage for RedbEntryStorage {
+    async fn append(&mut self, ents: &[Entry<TypeConfig>]) -> ReplicationResult<()> {
+        if ents.is_empty() {
+            return Ok(());
+        }
+
+        let writer = self.db.begin_write().map_err(redb_error)?;
+        {
+            let mut table = writer.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+            for entry in ents {
+                let data = bincode::serialize(entry)
+                    .map_err(|e| MsgInvalidSnafu { msg: e.to_string() }.build())?;
+                table
+                    .insert(entry.log_id.index, data.as_slice())
+                    .map_err(redb_error)?;
+            }
+        }
+        writer.commit().map_err(redb_error)?;
+
+        Ok(())
+    }
+
+    async fn last_entry(&mut self) -> ReplicationResult<Option<Entry<TypeConfig>>> {
+        let reader = self.db.begin_read().map_err(redb_error)?;
+        let table = reader.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+        let mut iter = table.range::<u64>(..).map_err(redb_error)?;
+        match iter.next_back() {
+            Some(pair) => {
+                let (_, data) = pair.map_err(redb_error)?;
+                Ok(Some(Self::decode(data.value())?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn entry(&mut self, index: u64) -> ReplicationResult<Option<Entry<TypeConfig>>> {
+        let reader = self.db.begin_read().map_err(redb_error)?;
+        let table = reader.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+        match table.get(index).map_err(redb_error)? {
+            Some(data) => Ok(Some(Self::decode(data.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn entries(&mut self, low: u64, high: u64) -> ReplicationResult<Vec<Entry<TypeConfig>>> {
+        let mut ents = vec![];
+
+        let reader = self.db.begin_read().map_err(redb_error)?;
+        let table = reader.open_table(ENTRIES_TABLE).map_err(redb_error)?;
+        for pair in table.range(low..high).map_err(redb_error)? {
+            let (_, data) = pair.map_err(redb_error)?;
+            ents.push(Self::decode(data.value())?);
+        }
+
+        Ok(ents)
+    }
+
+    async fn del_after(&mut self, index: u64) -> ReplicationResult<()> {
+        self.delete_range(index..)
+    }
+
+    async fn del_before(&mut self, index: u64) -> ReplicationResult<()> {
+        self.delete_range(..index)
+    }
+
+    async fn destroy(&mut self) -> ReplicationResult<()> {
+        let _ = fs::remove_file(self.path.join(REDB_FILE));
+
+        Ok(())
+    }
+
+    async fn metrics(&mut self) -> ReplicationResult<EntriesMetrics> {
+        let first = self.first_entry()?.unwrap_or_default();
+        let last = self.last_entry().await?.unwrap_or_default();
+
+        Ok(EntriesMetrics {
+            min_seq: first.log_id.index,
+            max_seq: last.log_id.index,
+            avg_write_time: 0,
+            avg_sync_time: 0,
+            unsynced_bytes: 0,
+            disk_size: fs::metadata(self.path.join(REDB_FILE)).map_or(0, |m| m.len()),
         })
     }
 
@@ -156,7 +401,97 @@ impl EntryStorage for HeedEntryStorage {
     }
 }
 
+#[cfg(test)]
 mod test {
+    use openraft::{Entry, EntryPayload, LogId};
+
+    use super::{
+        migrate_entries_to_redb, open_entry_storage, HeedEntryStorage, RedbEntryStorage,
+        REDB_MIGRATING_FILE,
+    };
+    use crate::{EntryStorage, EntryStorageEngine, TypeConfig};
+
+    fn entries(low: u64, high: u64) -> Vec<Entry<TypeConfig>> {
+        (low..high)
+            .map(|index| Entry {
+                log_id: LogId {
+                    leader_id: Default::default(),
+                    index,
+                },
+                payload: EntryPayload::Normal(index.to_be_bytes().to_vec()),
+            })
+            .collect()
+    }
+
+    fn indexes(ents: &[Entry<TypeConfig>]) -> Vec<u64> {
+        ents.iter().map(|e| e.log_id.index).collect()
+    }
+
+    #[tokio::test]
+    async fn test_redb_entry_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = RedbEntryStorage::open(dir.path()).unwrap();
+        assert!(storage.last_entry().await.unwrap().is_none());
+
+        storage.append(&entries(1, 11)).await.unwrap();
+        assert_eq!(storage.entry(5).await.unwrap().unwrap().log_id.index, 5);
+        assert!(storage.entry(11).await.unwrap().is_none());
+        assert_eq!(
+            indexes(&storage.entries(3, 6).await.unwrap()),
+            vec![3, 4, 5]
+        );
+
+        storage.del_before(4).await.unwrap();
+        storage.del_after(8).await.unwrap();
+        assert_eq!(
+            indexes(&storage.entries(0, 100).await.unwrap()),
+            vec![4, 5, 6, 7]
+        );
+
+        let metrics = storage.metrics().await.unwrap();
+        assert_eq!((metrics.min_seq, metrics.max_seq), (4, 7));
+
+        // Reopen the storage.
+        drop(storage);
+        let mut storage = RedbEntryStorage::open(dir.path()).unwrap();
+        assert_eq!(storage.last_entry().await.unwrap().unwrap().log_id.index, 7);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_entries_to_redb() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut heed = HeedEntryStorage::open(dir.path(), 1024 * 1024 * 1024).unwrap();
+            heed.append(&entries(3, 3000)).await.unwrap();
+        }
+
+        // Redb can't open the entries stored by LMDB before they are migrated.
+        assert!(open_entry_storage(EntryStorageEngine::Redb, dir.path(), 1024).is_err());
+
+        // Continue the interrupted migration.
+        {
+            let mut migrating =
+                RedbEntryStorage::open_file(dir.path(), REDB_MIGRATING_FILE).unwrap();
+            migrating.append(&entries(3, 1000)).await.unwrap();
+        }
+        let count = migrate_entries_to_redb(dir.path(), 1024 * 1024 * 1024)
+            .await
+            .unwrap();
+        assert_eq!(count, 2000);
+        assert!(!dir.path().join(REDB_MIGRATING_FILE).exists());
+
+        let mut storage = RedbEntryStorage::open(dir.path()).unwrap();
+        assert_eq!(
+            indexes(&storage.entries(0, u64::MAX).await.unwrap()),
+            (3..3000).collect::<Vec<_>>()
+        );
+
+        // Do not migrate again over the entries of redb.
+        assert!(migrate_entries_to_redb(dir.path(), 1024 * 1024 * 1024)
+            .await
+            .is_err());
+    }
+
     #[test]
     #[ignore]
     fn test_heed_range() {
@@ -124,6 +124,14 @@ pub enum ReplicationError {
     #[snafu(display("models error: {}", source))]
     #[error_code(code = 18)]
     ModelError { source: models::ModelError },
+
+    #[snafu(display("redb error: {}", source))]
+    #[error_code(code = 19)]
+    RedbError {
+        source: redb::Error,
+        location: Location,
+        backtrace: Backtrace,
+    },
 }
 
 impl ReplicationError {
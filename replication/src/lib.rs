@@ -1,6 +1,7 @@
 use std::any::Any;
 use std::fmt::Debug;
 use std::io::Cursor;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -36,9 +37,46 @@ pub struct ReplicationConfig {
     pub grpc_enable_gzip: bool,
     pub heartbeat_interval: u64,
     pub raft_logs_to_keep: u64,
+    /// Purge the raft logs in the snapshot once all the members have them,
+    /// the last `raft_logs_to_keep` entries are kept.
+    pub purge_replicated_logs: bool,
     pub send_append_entries_timeout: u64, //ms
     pub install_snapshot_timeout: u64,    //ms
     pub snapshot_policy: openraft::SnapshotPolicy,
+    /// Trigger a snapshot once the raft logs of a group grow beyond the bytes,
+    /// 0 means disabled.
+    pub snapshot_logs_size: u64,
+    /// Trigger a snapshot once the time since the last snapshot of a group
+    /// exceeds the interval, 0 means disabled.
+    pub snapshot_interval: u64, //ms
+    /// Connect to the other nodes with the cluster TLS if it's enabled.
+    pub cluster_tls: bool,
+    /// The max append entries of the raft groups sent to a node in a request,
+    /// 1 means the append entries are not batched.
+    pub append_entries_max_batch: usize,
+    /// The engine storing the raft entries of the groups opened by
+    /// `entry_store::open_entry_storage`.
+    pub entry_storage: EntryStorageEngine,
+}
+
+/// The engines implementing `EntryStorage`.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum EntryStorageEngine {
+    #[default]
+    Lmdb,
+    Redb,
+}
+
+impl FromStr for EntryStorageEngine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lmdb" => Ok(Self::Lmdb),
+            "redb" => Ok(Self::Redb),
+            _ => Err(format!("unknown entry storage engine: {}", s)),
+        }
+    }
 }
 
 // #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
@@ -73,6 +111,8 @@ pub type Response = Vec<u8>;
 
 pub const APPLY_TYPE_WAL: u32 = 1;
 pub const APPLY_TYPE_WRITE: u32 = 2;
+/// Data copied from another replica of the group by a repair.
+pub const APPLY_TYPE_REPAIR: u32 = 3;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub struct EngineMetrics {
@@ -90,6 +130,9 @@ pub struct EntriesMetrics {
     pub min_seq: u64,
     pub max_seq: u64,
     pub avg_write_time: u64,
+    pub avg_sync_time: u64,
+    pub unsynced_bytes: u64,
+    pub disk_size: u64,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, Default)]
@@ -97,6 +140,8 @@ pub struct ApplyContext {
     pub index: u64,
     pub apply_type: u32,
     pub raft_id: RaftNodeId,
+    /// The raft node which proposed the entry as the leader.
+    pub leader_id: RaftNodeId,
 }
 
 #[async_trait]
@@ -12,12 +12,13 @@ use replication::entry_store::HeedEntryStorage;
 use replication::errors::{MsgInvalidSnafu, ReplicationResult};
 use replication::metrics::ReplicationMetrics;
 use replication::multi_raft::MultiRaft;
+use replication::network_client::NetworkConn;
 use replication::network_grpc::RaftCBServer;
 use replication::network_http::{EitherBody, RaftHttpAdmin, SyncSendError};
 use replication::node_store::NodeStorage;
 use replication::raft_node::RaftNode;
 use replication::state_store::StateStorage;
-use replication::{RaftNodeId, RaftNodeInfo, ReplicationConfig};
+use replication::{EntryStorageEngine, RaftNodeId, RaftNodeInfo, ReplicationConfig};
 use tokio::sync::RwLock;
 use tower::Service;
 use trace::{debug, info};
@@ -88,8 +89,17 @@ async fn create_raft_node(dir: &str, id_port: RaftNodeId) -> ReplicationResult<R
         install_snapshot_timeout: 300 * 1000,
         //snapshot_policy: SnapshotPolicy::Never,
         snapshot_policy: SnapshotPolicy::LogsSinceLast(200),
+        snapshot_logs_size: 0,
+        purge_replicated_logs: false,
+        snapshot_interval: 0,
+        cluster_tls: false,
+        append_entries_max_batch: 1,
+        entry_storage: EntryStorageEngine::Lmdb,
     };
-    let node = RaftNode::new(id_port, info, storage, config).await.unwrap();
+    let network = NetworkConn::new(config.clone());
+    let node = RaftNode::new(id_port, info, storage, config, network)
+        .await
+        .unwrap();
 
     let node = Arc::new(node);
     let raft_admin = RaftHttpAdmin::new(node.clone());
@@ -400,6 +410,66 @@ mod tests {
         let _ = std::fs::remove_dir_all(crate::TEST_DATA_DIR);
     }
 
+    #[test]
+    fn test_wait_replicated() {
+        println!("----- begin test_wait_replicated -----");
+        let _ = std::fs::remove_dir_all(crate::TEST_DATA_DIR);
+
+        let rt = create_runtime();
+        let dir = format!("{}/test_wait_replicated", crate::TEST_DATA_DIR);
+        let servers = start_servers(rt.clone(), &dir, 8000..=8002);
+
+        // init 3-nodes cluster
+        let members = btreemap! {
+            servers[0].node.raft_id()=>raft_node_info(servers[0].node.raft_id()),
+            servers[1].node.raft_id()=>raft_node_info(servers[1].node.raft_id()),
+            servers[2].node.raft_id()=>raft_node_info(servers[2].node.raft_id()),
+        };
+        rt.block_on(servers[0].node.raft_init(members)).unwrap();
+        std::thread::sleep(Duration::from_secs(1));
+        let metrics = servers[0].node.raft_metrics();
+        assert_eq!(metrics.state, ServerState::Leader);
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+        struct RequestCommand {
+            key: String,
+            value: String,
+        }
+        let mut command = RequestCommand {
+            key: "test_key".to_string(),
+            value: "v_1".to_string(),
+        };
+
+        let data = serde_json::to_string(&command).unwrap();
+        let resp = rt
+            .block_on(servers[0].node.raw_raft().client_write(data.into()))
+            .unwrap();
+        rt.block_on(
+            servers[0]
+                .node
+                .wait_replicated(resp.log_id.index, Duration::from_secs(5)),
+        )
+        .unwrap();
+
+        // shutdown node-2, the writes are still committed by a quorum
+        servers[2].handler.abort();
+        rt.block_on(servers[2].node.shutdown()).unwrap();
+        command.value = "v_2".to_string();
+        let data = serde_json::to_string(&command).unwrap();
+        let resp = rt
+            .block_on(servers[0].node.raw_raft().client_write(data.into()))
+            .unwrap();
+        assert!(rt
+            .block_on(
+                servers[0]
+                    .node
+                    .wait_replicated(resp.log_id.index, Duration::from_secs(1)),
+            )
+            .is_err());
+
+        let _ = std::fs::remove_dir_all(crate::TEST_DATA_DIR);
+    }
+
     #[test]
     fn test_add_follower_node() {
         println!("----- begin test_add_follower_node -----");
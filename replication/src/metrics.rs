@@ -17,6 +17,8 @@ pub struct ReplicationMetrics {
     pub wal_index_min: U64Gauge,
     pub wal_index_max: U64Gauge,
     pub wal_avg_write_time: U64Gauge,
+    pub wal_avg_sync_time: U64Gauge,
+    pub wal_unsynced_bytes: U64Gauge,
     pub repication_delay: HashMap<RaftNodeId, U64Gauge>,
     pub write_apply_duration: U64Gauge,
     pub write_build_group_duration: U64Gauge,
@@ -76,6 +78,14 @@ impl ReplicationMetrics {
             .metric::<U64Gauge>("raft_wal_avg_write_time", "raft wal average write time(ms)");
         let wal_avg_write_time = metric.recorder(lables);
 
+        let metric = register
+            .metric::<U64Gauge>("raft_wal_avg_sync_time", "raft wal average fsync time(us)");
+        let wal_avg_sync_time = metric.recorder(lables);
+
+        let metric = register
+            .metric::<U64Gauge>("raft_wal_unsynced_bytes", "raft wal bytes not yet fsynced");
+        let wal_unsynced_bytes = metric.recorder(lables);
+
         Self {
             applied_id,
             snapshot_id,
@@ -83,6 +93,8 @@ impl ReplicationMetrics {
             wal_index_min,
             wal_index_max,
             wal_avg_write_time,
+            wal_avg_sync_time,
+            wal_unsynced_bytes,
             write_apply_duration,
             write_build_group_duration,
             write_put_points_duration,
@@ -103,6 +115,8 @@ impl ReplicationMetrics {
         self.wal_index_min.set(metrics.entries.min_seq);
         self.wal_index_max.set(metrics.entries.max_seq);
         self.wal_avg_write_time.set(metrics.entries.avg_write_time);
+        self.wal_avg_sync_time.set(metrics.entries.avg_sync_time);
+        self.wal_unsynced_bytes.set(metrics.entries.unsynced_bytes);
         self.write_apply_duration
             .set(metrics.engine.write_apply_duration);
         self.write_build_group_duration
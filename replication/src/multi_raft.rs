@@ -28,6 +28,49 @@ pub struct MultiRaft {
     nodes: HashMap<ReplicationSetId, RaftNodeWrapper>,
 }
 
+/// Purge raft logs harder than `raft_logs_to_keep` when the raft logs
+/// of all groups on this node grow beyond the disk budget.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogPurgePolicy {
+    /// Total bytes of raft logs allowed on this node, 0 means unlimited.
+    pub disk_budget: u64,
+    /// Entries every group keeps behind the slowest follower.
+    pub min_logs_to_keep: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct GroupLogState {
+    id: ReplicationSetId,
+    disk_size: u64,
+    first_index: u64,
+    last_index: u64,
+    /// The index matched by the slowest follower, or the last index if this
+    /// node is not the leader.
+    matched_index: u64,
+    snapshot_index: u64,
+}
+
+impl GroupLogState {
+    /// Up to which index the logs of this group can be purged.
+    fn purge_upto(&self, min_logs_to_keep: u64) -> Option<u64> {
+        let upto = self
+            .snapshot_index
+            .min(self.matched_index.saturating_sub(min_logs_to_keep));
+        if upto > 0 && upto >= self.first_index && self.first_index != u64::MAX {
+            Some(upto)
+        } else {
+            None
+        }
+    }
+
+    /// Estimate the bytes freed by purging logs up to `upto`.
+    fn freed_size(&self, upto: u64) -> u64 {
+        let total = self.last_index.saturating_sub(self.first_index) + 1;
+        let purged = (upto + 1).saturating_sub(self.first_index).min(total);
+        (self.disk_size as u128 * purged as u128 / total as u128) as u64
+    }
+}
+
 impl Default for MultiRaft {
     fn default() -> Self {
         Self::new()
@@ -83,9 +126,13 @@ impl MultiRaft {
     pub async fn raft_nodes_manager(
         nodes: Arc<RwLock<MultiRaft>>,
         trigger_snapshot_interval: Duration,
+        purge_policy: LogPurgePolicy,
     ) {
-        let start = Instant::now() + trigger_snapshot_interval;
-        let mut trigger_snapshot_ticker = interval_at(start.into(), trigger_snapshot_interval);
+        // The raft logs may grow beyond the size of the snapshot policy
+        // before the interval, check the policy more often.
+        let check_snapshot_interval = trigger_snapshot_interval.min(Duration::from_secs(10));
+        let start = Instant::now() + check_snapshot_interval;
+        let mut trigger_snapshot_ticker = interval_at(start.into(), check_snapshot_interval);
 
         let clear_shutdown_interval = Duration::from_secs(2 * 60);
         let start = Instant::now() + clear_shutdown_interval;
@@ -95,11 +142,16 @@ impl MultiRaft {
         let start = Instant::now() + update_metrics_interval;
         let mut update_metrics_ticker = interval_at(start.into(), update_metrics_interval);
 
+        let purge_logs_interval = Duration::from_secs(60);
+        let start = Instant::now() + purge_logs_interval;
+        let mut purge_logs_ticker = interval_at(start.into(), purge_logs_interval);
+
         loop {
             tokio::select! {
                 _= clear_shutdown_ticker.tick() => {MultiRaft::clear_shutdown_nodes(nodes.clone()).await;}
                 _= trigger_snapshot_ticker.tick() => {MultiRaft::trigger_snapshot_purge_logs(nodes.clone()).await;}
                 _=update_metrics_ticker.tick() =>{MultiRaft::update_metrics_values(nodes.clone()).await;}
+                _=purge_logs_ticker.tick() =>{MultiRaft::purge_logs_over_budget(nodes.clone(), purge_policy).await;}
             }
         }
     }
@@ -111,29 +163,101 @@ impl MultiRaft {
                 continue;
             }
 
-            let engine_metrics = match item.raft.engine_metrics().await {
+            match item.raft.trigger_snapshot_by_policy().await {
+                Ok(true) => info!(
+                    "# Trigger snapshot group id: {} raft id: {}",
+                    item.raft.group_id(),
+                    item.raft.raft_id()
+                ),
+                Ok(false) => {}
+                Err(err) => info!("trigger snapshot failed: {:?}", err),
+            }
+
+            match item.raft.purge_logs_replicated().await {
+                Ok(Some(upto)) => info!(
+                    "# Raft logs replicated to all members, purge group {} upto {}",
+                    item.raft.group_id(),
+                    upto
+                ),
+                Ok(None) => {}
+                Err(err) => info!("purge replicated logs failed: {:?}", err),
+            }
+        }
+    }
+
+    async fn purge_logs_over_budget(nodes: Arc<RwLock<MultiRaft>>, policy: LogPurgePolicy) {
+        if policy.disk_budget == 0 {
+            return;
+        }
+
+        let nodes = nodes.read().await;
+        let mut groups = vec![];
+        for (id, item) in nodes.nodes.iter() {
+            if let Status::Shutdown(_) = item.stat {
+                continue;
+            }
+
+            let metrics = match item.raft.metrics().await {
                 Ok(metrics) => metrics,
                 Err(err) => {
-                    info!("get engine metrics failed: {:?}", err);
+                    info!("get raft metrics failed: {:?}", err);
                     continue;
                 }
             };
+            let last_index = metrics.raft.last_log_index.unwrap_or_default();
+            // The followers which never matched a log are sent the snapshot.
+            let matched_index = metrics
+                .raft
+                .replication
+                .iter()
+                .flatten()
+                .filter_map(|(_, matched)| matched.map(|log_id| log_id.index))
+                .fold(last_index, u64::min);
+            groups.push(GroupLogState {
+                id: *id,
+                disk_size: metrics.entries.disk_size,
+                first_index: metrics.entries.min_seq,
+                last_index,
+                matched_index,
+                snapshot_index: metrics.raft.snapshot.map_or(0, |log_id| log_id.index),
+            });
+        }
+
+        for (id, upto) in MultiRaft::plan_purge_logs(groups, policy) {
+            if let Some(item) = nodes.nodes.get(&id) {
+                info!(
+                    "# Raft logs over disk budget, purge group {} upto {}",
+                    id, upto
+                );
+                let _ = item.raft.raw_raft().trigger().purge_log(upto).await;
+            }
+        }
+    }
 
-            info!(
-                "# Engine Metrics group id: {} raft id: {}; {:?}",
-                item.raft.group_id(),
-                item.raft.raft_id(),
-                engine_metrics
-            );
+    /// Pick the groups to purge, the largest first, until the total size fits the budget.
+    fn plan_purge_logs(
+        mut groups: Vec<GroupLogState>,
+        policy: LogPurgePolicy,
+    ) -> Vec<(ReplicationSetId, u64)> {
+        let mut total_size: u64 = groups.iter().map(|g| g.disk_size).sum();
+        if total_size <= policy.disk_budget {
+            return vec![];
+        }
 
-            if engine_metrics.flushed_apply_id <= engine_metrics.snapshot_apply_id {
-                continue;
+        groups.sort_by(|a, b| b.disk_size.cmp(&a.disk_size));
+        let mut plan = vec![];
+        for group in groups {
+            if total_size <= policy.disk_budget {
+                break;
             }
 
-            let raft = item.raft.raw_raft();
-            let trigger = raft.trigger();
-            let _ = trigger.snapshot().await;
+            if let Some(upto) = group.purge_upto(policy.min_logs_to_keep) {
+                total_size = total_size.saturating_sub(group.freed_size(upto));
+                plan.push((group.id, upto));
+            }
         }
+
+        plan
     }
 
     async fn update_metrics_values(nodes: Arc<RwLock<MultiRaft>>) {
@@ -178,6 +302,55 @@ impl MultiRaft {
 pub mod test {
     use std::time::Duration;
 
+    use super::{GroupLogState, LogPurgePolicy, MultiRaft};
+
+    #[test]
+    fn test_plan_purge_logs() {
+        let group = |id, disk_size, first_index, last_index, snapshot_index| GroupLogState {
+            id,
+            disk_size,
+            first_index,
+            last_index,
+            matched_index: last_index,
+            snapshot_index,
+        };
+        let policy = LogPurgePolicy {
+            disk_budget: 1000,
+            min_logs_to_keep: 100,
+        };
+
+        // Under budget, nothing to purge.
+        let groups = vec![group(1, 400, 1, 1000, 900), group(2, 500, 1, 1000, 900)];
+        assert!(MultiRaft::plan_purge_logs(groups, policy).is_empty());
+
+        // The largest group is enough to fit the budget.
+        let groups = vec![group(1, 400, 1, 1000, 900), group(2, 800, 1, 1000, 900)];
+        assert_eq!(MultiRaft::plan_purge_logs(groups, policy), vec![(2, 900)]);
+
+        // Keep logs for slow followers even if the snapshot is newer.
+        let groups = vec![group(1, 1200, 1, 1000, 1000), group(2, 1000, 1, 1000, 500)];
+        assert_eq!(
+            MultiRaft::plan_purge_logs(groups, policy),
+            vec![(1, 900), (2, 500)]
+        );
+
+        // Groups without snapshot can not be purged.
+        let groups = vec![group(1, 2000, 1, 1000, 0)];
+        assert!(MultiRaft::plan_purge_logs(groups, policy).is_empty());
+
+        // Keep logs behind the slowest follower rather than the last log.
+        let groups = vec![GroupLogState {
+            matched_index: 700,
+            ..group(1, 2000, 1, 1000, 1000)
+        }];
+        assert_eq!(MultiRaft::plan_purge_logs(groups, policy), vec![(1, 600)]);
+        let groups = vec![GroupLogState {
+            matched_index: 50,
+            ..group(1, 2000, 1, 1000, 1000)
+        }];
+        assert!(MultiRaft::plan_purge_logs(groups, policy).is_empty());
+    }
+
     #[tokio::test]
     async fn test_select() {
         let mut ticker1 = tokio::time::interval(Duration::from_secs(3));
@@ -1,40 +1,131 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use openraft::error::{InstallSnapshotError, NetworkError, RemoteError};
 use openraft::network::{RPCOption, RaftNetwork, RaftNetworkFactory};
 use openraft::raft::*;
 use openraft::MessageSummary;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use protos::raft_service::*;
 use protos::{raft_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{oneshot, Semaphore};
 use tonic::transport::{Channel, Endpoint};
 use trace::debug;
 
 use crate::errors::{GRPCRequestSnafu, ReplicationResult};
 use crate::{RaftNodeId, RaftNodeInfo, ReplicationConfig, TypeConfig};
 
+/// The max batches of append entries being sent to a node at the same time,
+/// the requests arriving meanwhile are sent in the next batch.
+const MAX_INFLIGHT_APPEND_BATCHES: usize = 4;
+/// Stop adding requests to a batch once its size exceeds this.
+const MAX_APPEND_BATCH_BYTES: usize = 16 * 1024 * 1024;
+/// The max append entries queued to be sent to a node, the raft groups wait
+/// for the queue once it's full.
+const APPEND_QUEUE_CAPACITY: usize = 1024;
+/// The append entries to a node not supporting batches, e.g. during a rolling
+/// upgrade, are sent one by one in the interval before trying a batch again.
+const UNBATCHED_INTERVAL: Duration = Duration::from_secs(60);
+
 // ------------------------------------------------------------------------- //
+/// Connections to the other nodes, shared by all the raft groups on this node.
+/// The append entries of the groups to the same node are sent in batches.
 #[derive(Clone)]
 pub struct NetworkConn {
     config: ReplicationConfig,
-    conn_map: Arc<RwLock<HashMap<String, Channel>>>,
+    channels: Arc<ChannelPool>,
+    /// Senders to the tasks batching the append entries to the nodes.
+    append_batchers: Arc<Mutex<HashMap<String, Sender<AppendRequest>>>>,
 }
 
 impl NetworkConn {
     pub fn new(config: ReplicationConfig) -> Self {
         Self {
+            channels: Arc::new(ChannelPool {
+                cluster_tls: config.cluster_tls,
+                conn_map: RwLock::new(HashMap::new()),
+            }),
             config,
-            conn_map: Arc::new(RwLock::new(HashMap::new())),
+            append_batchers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    async fn get_conn(&self, addr: &str) -> ReplicationResult<Channel> {
+        self.channels.get_conn(addr).await
+    }
+
+    /// The sender to the batcher of the node, the batcher is started, or
+    /// started again if it exited.
+    fn append_batcher(&self, addr: &str) -> Sender<AppendRequest> {
+        let mut batchers = self.append_batchers.lock();
+        if let Some(batcher) = batchers.get(addr) {
+            if !batcher.is_closed() {
+                return batcher.clone();
+            }
+        }
+
+        let (batcher, batch_receiver) = mpsc::channel(APPEND_QUEUE_CAPACITY);
+        tokio::spawn(run_append_batcher(
+            self.channels.clone(),
+            self.config.clone(),
+            addr.to_string(),
+            batch_receiver,
+        ));
+        batchers.insert(addr.to_string(), batcher.clone());
+        batcher
+    }
+
+    async fn append_entries(
+        &self,
+        addr: &str,
+        req: RaftAppendEntriesReq,
+    ) -> ReplicationResult<RaftResponse> {
+        if self.config.append_entries_max_batch <= 1 {
+            return send_append_entries(&self.channels, &self.config, addr, req).await;
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let request = AppendRequest { req, sender };
+        let batcher = self.append_batcher(addr);
+        if batcher.send(request).await.is_err() {
+            return GRPCRequestSnafu {
+                msg: format!("append entries to({}) is dropped", addr),
+            }
+            .fail();
         }
+
+        receiver.await.map_err(|_| {
+            GRPCRequestSnafu {
+                msg: format!("append entries to({}) is dropped", addr),
+            }
+            .build()
+        })?
     }
+}
+
+struct ChannelPool {
+    cluster_tls: bool,
+    /// Channels to the nodes, with the version of the cluster TLS they are made with.
+    conn_map: RwLock<HashMap<String, (u64, Channel)>>,
+}
+
+impl ChannelPool {
     async fn get_conn(&self, addr: &str) -> ReplicationResult<Channel> {
-        if let Some(val) = self.conn_map.read().get(addr) {
-            return Ok(val.clone());
+        let tls_version = protos::tls::cluster_tls_version();
+        if let Some((version, val)) = self.conn_map.read().get(addr) {
+            if *version == tls_version {
+                return Ok(val.clone());
+            }
         }
 
-        let connector = Endpoint::from_shared(format!("http://{}", addr)).map_err(|err| {
+        let connector = if self.cluster_tls {
+            protos::tls::grpc_endpoint(addr)
+        } else {
+            Endpoint::from_shared(format!("http://{}", addr))
+        };
+        let connector = connector.map_err(|err| {
             GRPCRequestSnafu {
                 msg: format!("Connect to({}) error: {}", addr, err),
             }
@@ -50,12 +141,159 @@ impl NetworkConn {
 
         self.conn_map
             .write()
-            .insert(addr.to_string(), channel.clone());
+            .insert(addr.to_string(), (tls_version, channel.clone()));
 
         Ok(channel)
     }
 }
 
+/// An append entries request waiting to be sent in a batch.
+struct AppendRequest {
+    req: RaftAppendEntriesReq,
+    sender: oneshot::Sender<ReplicationResult<RaftResponse>>,
+}
+
+/// Send the append entries to the node in batches, the requests queued while
+/// the previous batches are being sent are sent together in the next batch.
+async fn run_append_batcher(
+    channels: Arc<ChannelPool>,
+    config: ReplicationConfig,
+    addr: String,
+    mut receiver: Receiver<AppendRequest>,
+) {
+    let inflight = Arc::new(Semaphore::new(MAX_INFLIGHT_APPEND_BATCHES));
+    let unbatched_until = Arc::new(Mutex::new(None::<Instant>));
+    while let Some(first) = receiver.recv().await {
+        let Ok(permit) = inflight.clone().acquire_owned().await else {
+            break;
+        };
+
+        let max_batch = match *unbatched_until.lock() {
+            Some(until) if until > Instant::now() => 1,
+            _ => config.append_entries_max_batch,
+        };
+        let mut size = first.req.data.len();
+        let mut batch = vec![first];
+        while batch.len() < max_batch && size < MAX_APPEND_BATCH_BYTES {
+            match receiver.try_recv() {
+                Ok(request) => {
+                    size += request.req.data.len();
+                    batch.push(request);
+                }
+                Err(_) => break,
+            }
+        }
+
+        let channels = channels.clone();
+        let config = config.clone();
+        let addr = addr.clone();
+        let unbatched_until = unbatched_until.clone();
+        tokio::spawn(async move {
+            send_append_batch(&channels, &config, &addr, batch, &unbatched_until).await;
+            drop(permit);
+        });
+    }
+}
+
+/// Send the batch of append entries to the node. If the node doesn't support
+/// batches, the requests of the batch fail and are retried by raft, the
+/// requests are sent one by one until `unbatched_until`.
+async fn send_append_batch(
+    channels: &ChannelPool,
+    config: &ReplicationConfig,
+    addr: &str,
+    batch: Vec<AppendRequest>,
+    unbatched_until: &Mutex<Option<Instant>>,
+) {
+    let (mut requests, senders): (Vec<_>, Vec<_>) = batch
+        .into_iter()
+        .map(|request| (request.req, request.sender))
+        .unzip();
+    if requests.len() == 1 {
+        let result = send_append_entries(channels, config, addr, requests.remove(0)).await;
+        if let Some(sender) = senders.into_iter().next() {
+            let _ = sender.send(result);
+        }
+        return;
+    }
+
+    let channel = match channels.get_conn(addr).await {
+        Ok(channel) => channel,
+        Err(err) => return reply_error(senders, err.to_string()),
+    };
+    let mut client = raft_service_time_out_client(
+        channel,
+        Duration::from_millis(config.send_append_entries_timeout),
+        DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+        config.grpc_enable_gzip,
+    );
+    // A slow group times out alone, before the timeout of the whole batch, so
+    // the responses of the other groups are still returned.
+    let cmd = tonic::Request::new(RaftBatchAppendEntriesReq {
+        requests,
+        timeout_ms: config.send_append_entries_timeout / 2,
+    });
+    let responses = match client.raft_batch_append_entries(cmd).await {
+        Ok(rsp) => rsp.into_inner().responses,
+        Err(err) => {
+            if err.code() == tonic::Code::Unimplemented {
+                *unbatched_until.lock() = Some(Instant::now() + UNBATCHED_INTERVAL);
+            }
+            return reply_error(
+                senders,
+                format!("Batch append entries to({}) error: {}", addr, err),
+            );
+        }
+    };
+    if responses.len() != senders.len() {
+        return reply_error(
+            senders,
+            format!(
+                "Batch append entries to({}) got {} responses of {} requests",
+                addr,
+                responses.len(),
+                senders.len()
+            ),
+        );
+    }
+    for (sender, rsp) in senders.into_iter().zip(responses) {
+        let _ = sender.send(Ok(rsp));
+    }
+}
+
+fn reply_error(senders: Vec<oneshot::Sender<ReplicationResult<RaftResponse>>>, msg: String) {
+    for sender in senders {
+        let _ = sender.send(Err(GRPCRequestSnafu { msg: msg.clone() }.build()));
+    }
+}
+
+async fn send_append_entries(
+    channels: &ChannelPool,
+    config: &ReplicationConfig,
+    addr: &str,
+    req: RaftAppendEntriesReq,
+) -> ReplicationResult<RaftResponse> {
+    let channel = channels.get_conn(addr).await?;
+    let mut client = raft_service_time_out_client(
+        channel,
+        Duration::from_millis(config.send_append_entries_timeout),
+        DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+        config.grpc_enable_gzip,
+    );
+
+    let rsp = client
+        .raft_append_entries(tonic::Request::new(req))
+        .await
+        .map_err(|err| {
+            GRPCRequestSnafu {
+                msg: format!("Append entries to({}) error: {}", addr, err),
+            }
+            .build()
+        })?;
+
+    Ok(rsp.into_inner())
+}
+
 impl RaftNetworkFactory<TypeConfig> for NetworkConn {
     type Network = TargetClient;
 
@@ -137,31 +375,22 @@ impl RaftNetwork<TypeConfig> for TargetClient {
         //     self.target, begin, end
         // );
 
-        let channel = self
-            .conn
-            .get_conn(&self.target_node.address)
-            .await
-            .map_err(|e| openraft::error::RPCError::Network(NetworkError::new(&e)))?;
-
-        let mut client = raft_service_time_out_client(
-            channel,
-            Duration::from_millis(self.config.send_append_entries_timeout),
-            DEFAULT_GRPC_SERVER_MESSAGE_LEN,
-            self.config.grpc_enable_gzip,
-        );
-
         let data = bincode::serialize(&req)
             .map_err(|e| openraft::error::RPCError::Network(NetworkError::new(&e)))?;
-        let cmd = tonic::Request::new(RaftAppendEntriesReq {
+        let cmd = RaftAppendEntriesReq {
             data,
             group_id: self.target_node.group_id,
-        });
+        };
 
-        let rsp = client
-            .raft_append_entries(cmd)
+        let rsp = self
+            .conn
+            .append_entries(&self.target_node.address, cmd)
             .await
-            .map_err(|e| openraft::error::RPCError::Network(NetworkError::new(&e)))?
-            .into_inner();
+            .map_err(|e| openraft::error::RPCError::Network(NetworkError::new(&e)))?;
+        if rsp.code != 0 {
+            let err = GRPCRequestSnafu { msg: rsp.data }.build();
+            return Err(openraft::error::RPCError::Network(NetworkError::new(&err)));
+        }
 
         let res: Result<AppendEntriesResponse<u64>, RaftError> = serde_json::from_str(&rsp.data)
             .map_err(|e| openraft::error::RPCError::Network(NetworkError::new(&e)))?;
@@ -213,3 +442,177 @@ impl RaftNetwork<TypeConfig> for TargetClient {
         res.map_err(|e| openraft::error::RPCError::RemoteError(RemoteError::new(self.target, e)))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use parking_lot::RwLock;
+    use protos::raft_service::raft_service_server::{RaftService, RaftServiceServer};
+    use protos::raft_service::*;
+    use tokio::sync::{mpsc, oneshot};
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::Server;
+
+    use super::{run_append_batcher, AppendRequest, ChannelPool};
+    use crate::errors::ReplicationResult;
+    use crate::{EntryStorageEngine, ReplicationConfig};
+
+    /// Replies the group id of each append entries request.
+    struct MockRaftService {
+        batch_supported: bool,
+        batches: Arc<AtomicUsize>,
+    }
+
+    #[tonic::async_trait]
+    impl RaftService for MockRaftService {
+        async fn raft_vote(
+            &self,
+            _request: tonic::Request<RaftVoteReq>,
+        ) -> Result<tonic::Response<RaftResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("raft_vote"))
+        }
+
+        async fn raft_snapshot(
+            &self,
+            _request: tonic::Request<RaftSnapshotReq>,
+        ) -> Result<tonic::Response<RaftResponse>, tonic::Status> {
+            Err(tonic::Status::unimplemented("raft_snapshot"))
+        }
+
+        async fn raft_append_entries(
+            &self,
+            request: tonic::Request<RaftAppendEntriesReq>,
+        ) -> Result<tonic::Response<RaftResponse>, tonic::Status> {
+            Ok(tonic::Response::new(RaftResponse {
+                code: 0,
+                data: request.into_inner().group_id.to_string(),
+            }))
+        }
+
+        async fn raft_batch_append_entries(
+            &self,
+            request: tonic::Request<RaftBatchAppendEntriesReq>,
+        ) -> Result<tonic::Response<RaftBatchResponse>, tonic::Status> {
+            self.batches.fetch_add(1, Ordering::SeqCst);
+            if !self.batch_supported {
+                return Err(tonic::Status::unimplemented("raft_batch_append_entries"));
+            }
+            let inner = request.into_inner();
+            assert_eq!(inner.timeout_ms, 500);
+            let responses = inner
+                .requests
+                .into_iter()
+                .map(|req| RaftResponse {
+                    code: 0,
+                    data: req.group_id.to_string(),
+                })
+                .collect();
+            Ok(tonic::Response::new(RaftBatchResponse { responses }))
+        }
+    }
+
+    async fn start_server(batch_supported: bool) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let batches = Arc::new(AtomicUsize::new(0));
+        let service = RaftServiceServer::new(MockRaftService {
+            batch_supported,
+            batches: batches.clone(),
+        });
+        tokio::spawn(
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        (addr, batches)
+    }
+
+    fn config() -> ReplicationConfig {
+        ReplicationConfig {
+            cluster_name: "test".to_string(),
+            lmdb_max_map_size: 1024 * 1024 * 1024,
+            grpc_enable_gzip: false,
+            heartbeat_interval: 1000,
+            raft_logs_to_keep: 200,
+            send_append_entries_timeout: 1000,
+            install_snapshot_timeout: 1000,
+            snapshot_policy: openraft::SnapshotPolicy::Never,
+            snapshot_logs_size: 0,
+            purge_replicated_logs: false,
+            snapshot_interval: 0,
+            cluster_tls: false,
+            append_entries_max_batch: 64,
+            entry_storage: EntryStorageEngine::Lmdb,
+        }
+    }
+
+    fn channels() -> Arc<ChannelPool> {
+        Arc::new(ChannelPool {
+            cluster_tls: false,
+            conn_map: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Queue the append entries of the groups, returns the receivers of the responses.
+    async fn queue_requests(
+        sender: &mpsc::Sender<AppendRequest>,
+        group_ids: &[u32],
+    ) -> Vec<oneshot::Receiver<ReplicationResult<RaftResponse>>> {
+        let mut receivers = vec![];
+        for group_id in group_ids {
+            let (rsp_sender, receiver) = oneshot::channel();
+            let req = RaftAppendEntriesReq {
+                group_id: *group_id,
+                data: vec![0; 8],
+            };
+            sender
+                .send(AppendRequest {
+                    req,
+                    sender: rsp_sender,
+                })
+                .await
+                .unwrap();
+            receivers.push(receiver);
+        }
+        receivers
+    }
+
+    #[tokio::test]
+    async fn test_append_batcher() {
+        let (addr, batches) = start_server(true).await;
+        let (sender, receiver) = mpsc::channel(16);
+        // Queued before the batcher starts, sent in one batch.
+        let receivers = queue_requests(&sender, &[1, 2, 3]).await;
+        tokio::spawn(run_append_batcher(channels(), config(), addr, receiver));
+
+        for (group_id, receiver) in [1, 2, 3].into_iter().zip(receivers) {
+            let rsp = receiver.await.unwrap().unwrap();
+            assert_eq!(rsp.data, group_id.to_string());
+        }
+        assert_eq!(batches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_append_batch_unimplemented() {
+        let (addr, batches) = start_server(false).await;
+        let (sender, receiver) = mpsc::channel(16);
+        let receivers = queue_requests(&sender, &[1, 2]).await;
+        tokio::spawn(run_append_batcher(channels(), config(), addr, receiver));
+
+        // The batch fails, raft retries the append entries.
+        for receiver in receivers {
+            assert!(receiver.await.unwrap().is_err());
+        }
+
+        // Sent one by one.
+        let receivers = queue_requests(&sender, &[3, 4]).await;
+        for (group_id, receiver) in [3, 4].into_iter().zip(receivers) {
+            let rsp = receiver.await.unwrap().unwrap();
+            assert_eq!(rsp.data, group_id.to_string());
+        }
+        assert_eq!(batches.load(Ordering::SeqCst), 1);
+    }
+}
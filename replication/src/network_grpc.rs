@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use openraft::raft::*;
 use protos::raft_service::raft_service_server::RaftService;
@@ -36,6 +37,29 @@ impl RaftCBServer {
 
         Ok(node)
     }
+
+    async fn append_entries(
+        &self,
+        inner: RaftAppendEntriesReq,
+    ) -> std::result::Result<RaftResponse, tonic::Status> {
+        let entries = match bincode::deserialize::<AppendEntriesRequest<TypeConfig>>(&inner.data) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::new(tonic::Code::Internal, err.to_string())),
+        };
+
+        // let begin = entries.entries.first().map_or(0, |ent| ent.log_id.index);
+        // let end = entries.entries.last().map_or(0, |ent| ent.log_id.index);
+        // debug!(
+        //     "Network callback recv raft_append_entries  entries: [{}-{}]",
+        //     begin, end
+        // );
+
+        let node = self.get_node(inner.group_id).await?;
+        let res = node.raw_raft().append_entries(entries).await;
+        let data = serde_json::to_string(&res).unwrap_or_else(|_| "encode vote rsp failed".into());
+
+        Ok(RaftResponse { code: 0, data })
+    }
 }
 
 #[tonic::async_trait]
@@ -88,24 +112,45 @@ impl RaftService for RaftCBServer {
         &self,
         request: tonic::Request<RaftAppendEntriesReq>,
     ) -> std::result::Result<tonic::Response<RaftResponse>, tonic::Status> {
-        let inner = request.into_inner();
+        let rsp = self.append_entries(request.into_inner()).await?;
 
-        let entries = match bincode::deserialize::<AppendEntriesRequest<TypeConfig>>(&inner.data) {
-            Ok(val) => val,
-            Err(err) => return Err(tonic::Status::new(tonic::Code::Internal, err.to_string())),
-        };
-
-        // let begin = entries.entries.first().map_or(0, |ent| ent.log_id.index);
-        // let end = entries.entries.last().map_or(0, |ent| ent.log_id.index);
-        // debug!(
-        //     "Network callback recv raft_append_entries  entries: [{}-{}]",
-        //     begin, end
-        // );
-
-        let node = self.get_node(inner.group_id).await?;
-        let res = node.raw_raft().append_entries(entries).await;
-        let data = serde_json::to_string(&res).unwrap_or_else(|_| "encode vote rsp failed".into());
+        Ok(tonic::Response::new(rsp))
+    }
 
-        Ok(tonic::Response::new(RaftResponse { code: 0, data }))
+    async fn raft_batch_append_entries(
+        &self,
+        request: tonic::Request<RaftBatchAppendEntriesReq>,
+    ) -> std::result::Result<tonic::Response<RaftBatchResponse>, tonic::Status> {
+        let inner = request.into_inner();
+        let timeout = Duration::from_millis(inner.timeout_ms);
+
+        // The groups are independent, a failed or slow request does not fail
+        // the others.
+        let responses =
+            futures::future::join_all(inner.requests.into_iter().map(|req| async move {
+                let group_id = req.group_id;
+                if timeout.is_zero() {
+                    return self.append_entries(req).await;
+                }
+                tokio::time::timeout(timeout, self.append_entries(req))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(tonic::Status::deadline_exceeded(format!(
+                            "Append entries of group {} timed out",
+                            group_id
+                        )))
+                    })
+            }))
+            .await
+            .into_iter()
+            .map(|res| {
+                res.unwrap_or_else(|status| RaftResponse {
+                    code: status.code() as i32,
+                    data: status.message().to_string(),
+                })
+            })
+            .collect();
+
+        Ok(tonic::Response::new(RaftBatchResponse { responses }))
     }
 }
@@ -496,6 +496,7 @@ impl RaftStorage<TypeConfig> for Arc<NodeStorage> {
                         apply_type: crate::APPLY_TYPE_WRITE,
                         index: entry.log_id.index,
                         raft_id: self.id,
+                        leader_id: entry.log_id.leader_id.node_id,
                     };
                     let rsp = engine
                         .apply(&ctx, req)
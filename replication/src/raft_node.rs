@@ -1,9 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use openraft::storage::Adaptor;
-use openraft::{OptionalSend, RaftMetrics};
+use openraft::{ChangeMembers, Membership, OptionalSend, RaftMetrics};
+use parking_lot::Mutex;
 use tracing::info;
 
 use crate::errors::{RaftInternalErrSnafu, ReplicationError, ReplicationResult};
@@ -25,6 +26,9 @@ pub struct RaftNode {
     id: RaftNodeId,
     info: RaftNodeInfo,
     storage: Arc<NodeStorage>,
+    config: ReplicationConfig,
+    /// The index of the last snapshot and when it was seen.
+    last_snapshot: Arc<Mutex<(Option<u64>, Instant)>>,
 
     raft: OpenRaftNode,
 }
@@ -35,6 +39,7 @@ impl RaftNode {
         info: RaftNodeInfo,
         storage: Arc<NodeStorage>,
         config: ReplicationConfig,
+        network: NetworkConn,
     ) -> ReplicationResult<Self> {
         let hb: u64 = config.heartbeat_interval;
         let keep_logs = config.raft_logs_to_keep;
@@ -56,7 +61,7 @@ impl RaftNode {
         let raft_config = Arc::new(raft_config.validate().unwrap());
         let (log_store, state_machine) = Adaptor::new(storage.clone());
 
-        let network = NetworkConn::new(config.clone());
+        let last_snapshot = Arc::new(Mutex::new((None, Instant::now())));
         let raft = openraft::Raft::new(id, raft_config, network, log_store, state_machine)
             .await
             .map_err(|err| {
@@ -70,6 +75,8 @@ impl RaftNode {
             id,
             info,
             storage,
+            config,
+            last_snapshot,
             raft,
         })
     }
@@ -157,6 +164,21 @@ impl RaftNode {
         Ok(())
     }
 
+    /// Removes the learner, which is kept by the changes of the members.
+    pub async fn raft_remove_learner(&self, id: RaftNodeId) -> ReplicationResult<()> {
+        self.raft
+            .change_membership(ChangeMembers::RemoveNodes(BTreeSet::from([id])), false)
+            .await
+            .map_err(|err| {
+                RaftInternalErrSnafu {
+                    msg: format!("Remove learner raft execute failed: {}", err),
+                }
+                .build()
+            })?;
+
+        Ok(())
+    }
+
     pub async fn shutdown(&self) -> ReplicationResult<()> {
         self.raft.shutdown().await.map_err(|err| {
             RaftInternalErrSnafu {
@@ -204,6 +226,139 @@ impl RaftNode {
         self.raft.metrics().borrow().clone()
     }
 
+    /// Raft entries the node `id` lags behind the log of this node, None if this
+    /// node is not the leader or does not replicate to `id`.
+    pub fn replication_lag(&self, id: RaftNodeId) -> Option<u64> {
+        let metrics = self.raft_metrics();
+        if metrics.state != openraft::ServerState::Leader {
+            return None;
+        }
+        let matched = metrics.replication.as_ref()?.get(&id)?;
+
+        Some(log_lag(metrics.last_log_index, matched.map(|l| l.index)))
+    }
+
+    /// Whether the membership of the group is being changed, a joint config is
+    /// in effect or some members other than the `learners` are learners, such
+    /// as when the leadership is being transferred.
+    pub fn membership_changing(&self, learners: &BTreeSet<RaftNodeId>) -> bool {
+        let metrics = self.raft_metrics();
+        is_membership_changing(metrics.membership_config.membership(), learners)
+    }
+
+    /// Wait until the log `index` is replicated to all voters of the group,
+    /// this node must be the leader.
+    pub async fn wait_replicated(&self, index: u64, timeout: Duration) -> ReplicationResult<()> {
+        self.wait_condition(
+            move |m| {
+                let Some(replication) = m.replication.as_ref() else {
+                    return false;
+                };
+                m.membership_config
+                    .membership()
+                    .voter_ids()
+                    .filter(|id| *id != m.id)
+                    .all(|id| {
+                        replication
+                            .get(&id)
+                            .and_then(|log_id| log_id.as_ref())
+                            .map_or(false, |log_id| log_id.index >= index)
+                    })
+            },
+            timeout,
+            format!("log {} replicated to all voters", index),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Wait until the raft log `index` is applied to the state machine of this node.
+    pub async fn wait_applied(&self, index: u64, timeout: Duration) -> ReplicationResult<()> {
+        self.wait_condition(
+            move |m| m.last_applied.map_or(false, |log_id| log_id.index >= index),
+            timeout,
+            format!("log {} applied", index),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Trigger a snapshot if some logs are applied since the last snapshot, and
+    /// the raft logs grow beyond `snapshot_logs_size` or the time since the last
+    /// snapshot exceeds `snapshot_interval`.
+    pub async fn trigger_snapshot_by_policy(&self) -> ReplicationResult<bool> {
+        let engine_metrics = self.storage.engine_metrics().await?;
+        let elapsed = {
+            let mut last_snapshot = self.last_snapshot.lock();
+            // The snapshot may be made by openraft or installed from the leader.
+            if last_snapshot.0 != Some(engine_metrics.snapshot_apply_id) {
+                *last_snapshot = (Some(engine_metrics.snapshot_apply_id), Instant::now());
+            }
+            last_snapshot.1.elapsed()
+        };
+        if engine_metrics.flushed_apply_id <= engine_metrics.snapshot_apply_id {
+            return Ok(false);
+        }
+
+        let entries_metrics = self.storage.entries_metrics().await?;
+        if !snapshot_due(&self.config, entries_metrics.disk_size, elapsed) {
+            return Ok(false);
+        }
+
+        self.raft.trigger().snapshot().await.map_err(|err| {
+            RaftInternalErrSnafu {
+                msg: format!("Trigger snapshot of raft({}) failed: {}", self.id, err),
+            }
+            .build()
+        })?;
+        *self.last_snapshot.lock() = (None, Instant::now());
+
+        Ok(true)
+    }
+
+    /// Purge the raft logs up to the snapshot once all the members have the
+    /// logs in it, replicated or by installing the snapshot, so no member needs
+    /// them any more. The last `raft_logs_to_keep` entries are kept. Only the
+    /// leader knows it, returns the purged index.
+    pub async fn purge_logs_replicated(&self) -> ReplicationResult<Option<u64>> {
+        if !self.config.purge_replicated_logs {
+            return Ok(None);
+        }
+        let metrics = self.raft_metrics();
+        if metrics.state != openraft::ServerState::Leader {
+            return Ok(None);
+        }
+        let (Some(snapshot), Some(replication)) = (metrics.snapshot, metrics.replication) else {
+            return Ok(None);
+        };
+        let min_matched = replication
+            .values()
+            .map(|log_id| log_id.map_or(0, |log_id| log_id.index))
+            .min()
+            .unwrap_or(snapshot.index);
+        let purged = metrics.purged.map(|log_id| log_id.index);
+        let Some(upto) = replicated_purge_upto(
+            snapshot.index,
+            min_matched,
+            metrics.last_log_index.unwrap_or_default(),
+            self.config.raft_logs_to_keep,
+            purged,
+        ) else {
+            return Ok(None);
+        };
+
+        self.raft.trigger().purge_log(upto).await.map_err(|err| {
+            RaftInternalErrSnafu {
+                msg: format!("Purge logs of raft({}) failed: {}", self.id, err),
+            }
+            .build()
+        })?;
+
+        Ok(Some(upto))
+    }
+
     pub async fn engine_metrics(&self) -> ReplicationResult<EngineMetrics> {
         self.storage.engine_metrics().await
     }
@@ -212,3 +367,135 @@ impl RaftNode {
         let _ = self.storage.sync_wal_writer().await;
     }
 }
+
+/// Whether a snapshot is due by the size of the raft logs or the time since
+/// the last snapshot, a limit of 0 is disabled.
+fn snapshot_due(config: &ReplicationConfig, logs_size: u64, elapsed: Duration) -> bool {
+    (config.snapshot_logs_size > 0 && logs_size >= config.snapshot_logs_size)
+        || (config.snapshot_interval > 0
+            && elapsed >= Duration::from_millis(config.snapshot_interval))
+}
+
+/// Up to which index the raft logs can be purged when all members matched
+/// `min_matched`, keeping the last `logs_to_keep` logs before `last_log`.
+/// None if nothing more than `purged` can be purged.
+fn replicated_purge_upto(
+    snapshot: u64,
+    min_matched: u64,
+    last_log: u64,
+    logs_to_keep: u64,
+    purged: Option<u64>,
+) -> Option<u64> {
+    let upto = snapshot
+        .min(min_matched)
+        .min(last_log.saturating_sub(logs_to_keep));
+    if upto == 0 || purged.map_or(false, |purged| purged >= upto) {
+        None
+    } else {
+        Some(upto)
+    }
+}
+
+/// Raft entries between the last log and the log matched by a member, the
+/// member matched nothing if `matched_index` is None.
+fn log_lag(last_log_index: Option<u64>, matched_index: Option<u64>) -> u64 {
+    last_log_index
+        .unwrap_or_default()
+        .saturating_sub(matched_index.unwrap_or_default())
+}
+
+fn is_membership_changing(
+    membership: &Membership<RaftNodeId, RaftNodeInfo>,
+    learners: &BTreeSet<RaftNodeId>,
+) -> bool {
+    membership.get_joint_config().len() > 1
+        || membership.learner_ids().any(|id| !learners.contains(&id))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    use openraft::Membership;
+
+    use super::{is_membership_changing, log_lag, replicated_purge_upto, snapshot_due};
+    use crate::{RaftNodeId, RaftNodeInfo, ReplicationConfig};
+
+    #[test]
+    fn test_snapshot_due() {
+        let config = ReplicationConfig {
+            cluster_name: "test".to_string(),
+            lmdb_max_map_size: 1024 * 1024,
+            grpc_enable_gzip: false,
+            heartbeat_interval: 300,
+            raft_logs_to_keep: 100,
+            purge_replicated_logs: true,
+            send_append_entries_timeout: 5000,
+            install_snapshot_timeout: 5000,
+            snapshot_policy: openraft::SnapshotPolicy::Never,
+            snapshot_logs_size: 1024,
+            snapshot_interval: 60_000,
+            cluster_tls: false,
+            append_entries_max_batch: 1,
+            entry_storage: crate::EntryStorageEngine::Lmdb,
+        };
+        assert!(!snapshot_due(&config, 100, Duration::from_secs(10)));
+        assert!(snapshot_due(&config, 1024, Duration::from_secs(10)));
+        assert!(snapshot_due(&config, 100, Duration::from_secs(60)));
+
+        let disabled = ReplicationConfig {
+            snapshot_logs_size: 0,
+            snapshot_interval: 0,
+            ..config
+        };
+        assert!(!snapshot_due(&disabled, u64::MAX, Duration::MAX));
+    }
+
+    #[test]
+    fn test_replicated_purge_upto() {
+        // All members matched beyond the snapshot.
+        assert_eq!(replicated_purge_upto(100, 150, 150, 0, None), Some(100));
+        // A member lags behind the snapshot.
+        assert_eq!(replicated_purge_upto(100, 80, 150, 0, Some(50)), Some(80));
+        // Already purged.
+        assert_eq!(replicated_purge_upto(100, 150, 150, 0, Some(100)), None);
+        assert_eq!(replicated_purge_upto(0, 150, 150, 0, None), None);
+        // Keep the last logs.
+        assert_eq!(replicated_purge_upto(100, 150, 150, 100, None), Some(50));
+        assert_eq!(replicated_purge_upto(100, 150, 150, 150, None), None);
+        assert_eq!(replicated_purge_upto(100, 150, 150, 200, None), None);
+    }
+
+    #[test]
+    fn test_log_lag() {
+        assert_eq!(log_lag(Some(100), Some(90)), 10);
+        assert_eq!(log_lag(Some(100), Some(100)), 0);
+        // Nothing replicated to the member yet.
+        assert_eq!(log_lag(Some(100), None), 100);
+        assert_eq!(log_lag(None, None), 0);
+        // The metrics of the member are newer than the last log.
+        assert_eq!(log_lag(Some(90), Some(100)), 0);
+    }
+
+    #[test]
+    fn test_is_membership_changing() {
+        let ids = |ids: &[RaftNodeId]| ids.iter().copied().collect::<BTreeSet<_>>();
+        let membership = |configs: &[&[RaftNodeId]], nodes: &[RaftNodeId]| {
+            let configs = configs.iter().map(|c| ids(c)).collect::<Vec<_>>();
+            Membership::<RaftNodeId, RaftNodeInfo>::new(configs, ids(nodes))
+        };
+
+        let stable = membership(&[&[1, 2, 3]], &[1, 2, 3]);
+        assert!(!is_membership_changing(&stable, &ids(&[])));
+
+        // A learner recorded in meta.
+        let with_learner = membership(&[&[1, 2, 3]], &[1, 2, 3, 4]);
+        assert!(!is_membership_changing(&with_learner, &ids(&[4])));
+        // A voter being demoted, e.g. by a leadership transfer.
+        assert!(is_membership_changing(&with_learner, &ids(&[])));
+
+        let joint = membership(&[&[1, 2, 3], &[1, 2, 4]], &[1, 2, 3, 4]);
+        assert!(is_membership_changing(&joint, &ids(&[])));
+    }
+}
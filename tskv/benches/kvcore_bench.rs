@@ -53,6 +53,7 @@ fn tskv_write(
         index,
         raft_id: id.into(),
         apply_type: replication::APPLY_TYPE_WRITE,
+        leader_id: id.into(),
     };
     let vnode_store = Arc::new(rt.block_on(tskv.open_tsfamily(tenant, db, id)).unwrap());
 
@@ -0,0 +1,227 @@
+//! Backup and restore of vnodes through an object storage.
+//!
+//! The backup of a vnode is a snapshot of its version. The files of the version
+//! and their tombstones are uploaded under the prefix of the backup, with their
+//! paths relative to the directory of the vnode. Restoring a backup downloads
+//! the files into a new vnode, which renumbers the files and rebuilds its index
+//! from them.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use models::meta_data::NodeId;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use trace::{debug, info};
+
+use crate::error::{CommonSnafu, TskvResult};
+use crate::file_system::async_filesystem::LocalFileSystem;
+use crate::file_system::FileSystem;
+use crate::summary::CompactMeta;
+use crate::tsfamily::tseries_family::TseriesFamily;
+use crate::tsm::TOMBSTONE_FILE_SUFFIX;
+use crate::{tiering, TskvError, VnodeSnapshot};
+
+const UPLOAD_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// The files of a vnode uploaded by a backup.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VnodeBackup {
+    pub snapshot: VnodeSnapshot,
+    /// Paths relative to the directory of the vnode and sizes of the files.
+    pub files: Vec<(String, u64)>,
+}
+
+/// Upload the files of the current version of the vnode under `prefix`.
+///
+/// Caches of the vnode should be flushed before calling this, the files are
+/// kept by the version until the upload finishes.
+pub(crate) async fn backup_vnode(
+    vnode: Arc<RwLock<TseriesFamily>>,
+    node_id: NodeId,
+    object_store: Arc<dyn ObjectStore>,
+    prefix: ObjectStorePath,
+) -> TskvResult<VnodeBackup> {
+    let (vnode_id, version, version_edit) = {
+        let vnode = vnode.write().await;
+        (vnode.tf_id(), vnode.version(), vnode.build_version_edit())
+    };
+
+    let mut files = Vec::new();
+    for file in version.levels_info().iter().flat_map(|l| l.files.iter()) {
+        let relative_path = CompactMeta::from(file.as_ref()).relative_path();
+        let mut uploads = vec![(file.file_path().clone(), relative_path.clone())];
+        let tombstone_path = file.tombstone_path();
+        if LocalFileSystem::try_exists(&tombstone_path) {
+            uploads.push((
+                tombstone_path,
+                relative_path.with_extension(TOMBSTONE_FILE_SUFFIX),
+            ));
+        }
+        for (path, relative_path) in uploads {
+            let relative_path = relative_path.to_string_lossy().to_string();
+            let location = object_path(&prefix, &relative_path);
+            let size = upload_file(&path, object_store.clone(), &location).await?;
+            debug!(
+                "Backup vnode {vnode_id}: uploaded '{}' to {location}",
+                path.display()
+            );
+            files.push((relative_path, size));
+        }
+    }
+
+    let snapshot = VnodeSnapshot {
+        node_id,
+        vnode_id,
+        last_seq_no: version.last_seq(),
+        create_time: chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string(),
+        version_edit,
+        version: None,
+        active_time: 0,
+    };
+    info!(
+        "Backup vnode {vnode_id}: finished, uploaded {} files to {prefix}",
+        files.len()
+    );
+
+    Ok(VnodeBackup { snapshot, files })
+}
+
+/// Download the files of the backup under `prefix` into `dir`.
+pub(crate) async fn download_backup(
+    backup: &VnodeBackup,
+    object_store: Arc<dyn ObjectStore>,
+    prefix: &ObjectStorePath,
+    dir: &Path,
+) -> TskvResult<()> {
+    for (relative_path, size) in backup.files.iter() {
+        let location = object_path(prefix, relative_path);
+        let path = dir.join(relative_path);
+        download_file(object_store.clone(), &location, &path).await?;
+
+        let downloaded = LocalFileSystem::get_file_length(path.to_string_lossy().to_string());
+        if downloaded != *size {
+            return Err(backup_error(
+                &location,
+                format!("size of the file is {downloaded}, expected {size}"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Upload the file at `path`, tiered files are read from the tiered storage.
+async fn upload_file(
+    path: &Path,
+    object_store: Arc<dyn ObjectStore>,
+    location: &ObjectStorePath,
+) -> TskvResult<u64> {
+    let reader = tiering::open_file(path).await?;
+
+    let (multipart_id, mut writer) = object_store
+        .put_multipart(location)
+        .await
+        .map_err(|e| backup_error(location, e))?;
+    let result = async {
+        let mut buf = vec![0_u8; UPLOAD_BUFFER_SIZE];
+        let mut pos = 0;
+        while pos < reader.len() {
+            let len = reader.read_at(pos, &mut buf).await?;
+            if len == 0 {
+                break;
+            }
+            writer.write_all(&buf[..len]).await?;
+            pos += len;
+        }
+        writer.shutdown().await
+    }
+    .await;
+    if let Err(e) = result {
+        let _ = object_store.abort_multipart(location, &multipart_id).await;
+        return Err(backup_error(location, e));
+    }
+
+    Ok(reader.len() as u64)
+}
+
+async fn download_file(
+    object_store: Arc<dyn ObjectStore>,
+    location: &ObjectStorePath,
+    path: &Path,
+) -> TskvResult<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| backup_error(dir.display(), e))?;
+    }
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| backup_error(path.display(), e))?;
+    let mut stream = object_store
+        .get(location)
+        .await
+        .map_err(|e| backup_error(location, e))?
+        .into_stream();
+    while let Some(bytes) = stream.next().await {
+        let bytes = bytes.map_err(|e| backup_error(location, e))?;
+        file.write_all(&bytes)
+            .await
+            .map_err(|e| backup_error(path.display(), e))?;
+    }
+    file.sync_all()
+        .await
+        .map_err(|e| backup_error(path.display(), e))
+}
+
+fn object_path(prefix: &ObjectStorePath, relative_path: &str) -> ObjectStorePath {
+    relative_path
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .fold(prefix.clone(), |path, part| path.child(part))
+}
+
+fn backup_error(target: impl std::fmt::Display, e: impl std::fmt::Display) -> TskvError {
+    CommonSnafu {
+        reason: format!("failed to transfer backup '{target}': {e}"),
+    }
+    .build()
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path as ObjectStorePath;
+    use object_store::ObjectStore;
+
+    use super::{download_file, object_path, upload_file};
+
+    #[tokio::test]
+    async fn test_transfer_file() {
+        let dir = "/tmp/test/tskv/backup/test_transfer_file";
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+
+        let data = (0..255_u8).cycle().take(100_000).collect::<Vec<_>>();
+        let src = std::path::Path::new(dir).join("src/_000001.tsm");
+        std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+        std::fs::write(&src, &data).unwrap();
+
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let location = object_path(&ObjectStorePath::from("backup/vnodes/3"), "tsm/_000001.tsm");
+        assert_eq!(location.as_ref(), "backup/vnodes/3/tsm/_000001.tsm");
+
+        let size = upload_file(&src, store.clone(), &location).await.unwrap();
+        assert_eq!(size, data.len() as u64);
+
+        let dst = std::path::Path::new(dir).join("dst/tsm/_000001.tsm");
+        download_file(store, &location, &dst).await.unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), data);
+    }
+}
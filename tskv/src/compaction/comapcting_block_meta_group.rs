@@ -9,10 +9,11 @@ use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema};
 use models::schema::TIME_FIELD_NAME;
 use models::SeriesId;
 use snafu::{OptionExt, ResultExt};
-use trace::trace;
+use trace::{trace, warn};
 
 use crate::compaction::compacting_block_meta::CompactingBlockMeta;
 use crate::compaction::metrics::VnodeCompactionMetrics;
+use crate::compaction::utils::concat_sorted_runs;
 use crate::compaction::{CompactingBlock, CompactingFile};
 use crate::error::{ArrowSnafu, CommonSnafu, ModelSnafu};
 use crate::reader::sort_merge::sort_merge;
@@ -49,6 +50,15 @@ impl CompactingBlockMetaGroup {
         self.time_range.merge(&other.time_range);
     }
 
+    /// The size of the data blocks to read.
+    pub fn size(&self) -> u64 {
+        self.blk_metas
+            .iter()
+            .filter_map(|m| m.column_group().ok())
+            .map(|cg| cg.size())
+            .sum()
+    }
+
     pub async fn merge_with_previous_block(
         mut self,
         previous_block: Option<CompactingBlock>,
@@ -72,8 +82,13 @@ impl CompactingBlockMetaGroup {
         if self.blk_metas.len() == 1
             && !compacting_files[self.blk_metas[0].compacting_file_index()].has_tombstone()
             && self.blk_metas[0].included_in_time_range(time_range)?
+            && !self.blk_metas[0]
+                .column_group()?
+                .encoding_changed(&table_schema)
         {
             // Only one compacting block and has no tombstone, write as raw block.
+            // Blocks encoded by codecs different from the table schema are decoded
+            // and re-encoded by the current codecs instead.
             trace::trace!("only one compacting block without tombstone and time_range is entirely included by target level, handled as raw block");
             let meta_0 = &self.blk_metas[0].meta();
             let column_group_id = self.blk_metas[0].column_group_id();
@@ -107,11 +122,13 @@ impl CompactingBlockMetaGroup {
                     decode_pages_buf(&buf_0, chunk, column_group_id, table_schema.clone())?;
                 let record_batch = compacting_block.decode_opt(*time_range)?;
                 metrics.merge_begin();
-                let record_batches = Self::merge_record_batches(
-                    vec![decoded_raw_record_batch, record_batch],
-                    max_block_size,
-                )
-                .await?;
+                let record_batches = self
+                    .merge_or_concat_record_batches(
+                        &table_schema,
+                        vec![decoded_raw_record_batch, record_batch],
+                        max_block_size,
+                    )
+                    .await?;
                 metrics.merge_end();
                 let merged_blks = record_batches
                     .into_iter()
@@ -158,8 +175,9 @@ impl CompactingBlockMetaGroup {
             };
             let record_batches = {
                 metrics.merge_begin();
-                let record_batches =
-                    Self::merge_record_batches(record_batches, max_block_size).await?;
+                let record_batches = self
+                    .merge_or_concat_record_batches(&table_schema, record_batches, max_block_size)
+                    .await?;
                 metrics.merge_end();
                 record_batches
             };
@@ -178,6 +196,31 @@ impl CompactingBlockMetaGroup {
         }
     }
 
+    /// Record batches of an append-only table are concatenated without merging,
+    /// unless duplicate timestamps are found in them.
+    async fn merge_or_concat_record_batches(
+        &self,
+        table_schema: &TskvTableSchema,
+        record_batches: Vec<RecordBatch>,
+        block_size: usize,
+    ) -> TskvResult<Vec<RecordBatch>> {
+        if table_schema.append_only {
+            let (adapted_record_batches, target_schema) =
+                Self::schema_adapt_record_batches(record_batches.clone())?;
+            if let Some(record_batches) =
+                concat_sorted_runs(target_schema, adapted_record_batches, block_size)
+                    .context(ArrowSnafu)?
+            {
+                return Ok(record_batches);
+            }
+            warn!(
+                "Compaction: found duplicate timestamps in series {} of append-only table {}.{}, merge the data blocks instead",
+                self.series_id, table_schema.db, table_schema.name
+            );
+        }
+        Self::merge_record_batches(record_batches, block_size).await
+    }
+
     pub async fn merge_record_batches(
         record_batches: Vec<RecordBatch>,
         block_size: usize,
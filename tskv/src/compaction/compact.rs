@@ -22,7 +22,7 @@ use crate::compaction::utils::filter_record_batch_by_time_range;
 use crate::compaction::writer_wrapper::WriterWrapper;
 use crate::compaction::CompactReq;
 use crate::context::GlobalContext;
-use crate::error::{ArrowSnafu, CommonSnafu, TskvResult};
+use crate::error::{ArrowSnafu, CommonSnafu, CompactionCancelledSnafu, TskvResult};
 use crate::summary::{CompactMeta, VersionEdit};
 use crate::tsm::chunk::Chunk;
 use crate::tsm::page::Page;
@@ -618,6 +618,7 @@ async fn compact_files(
     let max_block_size = request.version.storage_opt().max_datablock_size as usize;
     let mut state = CompactState::new(tsm_readers, out_time_range);
     let mut writer_wrapper = WriterWrapper::new(&request, ctx.clone()).await?;
+    let job = ctx.compaction_jobs().register(&request);
 
     let mut previous_merged_block = Option::<CompactingBlock>::None;
     let mut merging_blk_meta_groups = Vec::with_capacity(32);
@@ -625,6 +626,21 @@ async fn compact_files(
 
     metrics.begin();
     loop {
+        if job.is_cancelled() {
+            info!(
+                "Compaction({}): Job {} is cancelled",
+                request.compact_task,
+                job.job_id()
+            );
+            writer_wrapper.abort().await;
+            return Err(CompactionCancelledSnafu {
+                job_id: job.job_id(),
+            }
+            .build());
+        }
+        let (files_out, bytes_out) = writer_wrapper.written();
+        job.set_output(files_out, bytes_out);
+
         let sid = match state.next(&mut merging_blk_meta_groups).await? {
             Some(sid) => sid,
             None => break,
@@ -647,6 +663,9 @@ async fn compact_files(
             }
             curr_sid = Some(sid);
 
+            ctx.compaction_throttle()
+                .acquire(blk_meta_group.size())
+                .await;
             let mut merged_blks = blk_meta_group
                 .merge_with_previous_block(
                     previous_merged_block.take(),
@@ -1,9 +1,11 @@
 use std::collections::{BTreeMap, HashMap};
-use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
 use std::sync::{atomic, Arc};
 use std::time::{Duration, Instant};
 
 use metrics::metric_register::MetricsRegister;
+use models::utils::now_timestamp_nanos;
+use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::Receiver;
@@ -12,11 +14,13 @@ use tokio::sync::{oneshot, Mutex, Notify, RwLock, RwLockWriteGuard, Semaphore};
 use trace::{error, info, warn};
 
 use crate::compaction::metrics::{CompactionType, VnodeCompactionMetrics};
-use crate::compaction::{flush, pick_compaction, CompactTask, FlushReq};
+use crate::compaction::{
+    database_compaction_strategy, flush, pick_compaction, CompactReq, CompactTask, FlushReq,
+};
 use crate::error::{CommonSnafu, IndexErrSnafu};
 use crate::mem_cache::memcache::MemCache;
 use crate::summary::SummaryTask;
-use crate::{TsKvContext, TskvResult, VersionEdit, VnodeId};
+use crate::{LevelId, TsKvContext, TskvResult, VersionEdit, VnodeId};
 
 const COMPACT_BATCH_CHECKING_SECONDS: u64 = 1;
 
@@ -210,7 +214,9 @@ impl CompactJobInner {
                             return;
                         }
                         let version = tsf.read().await.version();
-                        let compact_req = pick_compaction(task, version).await;
+                        let strategy =
+                            database_compaction_strategy(&ctx.version_set, &version.owner()).await;
+                        let compact_req = pick_compaction(task, version, strategy.as_ref()).await;
                         if let Some(req) = compact_req {
                             // Method acquire_owned() will return AcquireError if the semaphore has been closed.
                             let permit = compaction_limit.clone().acquire_owned().await.unwrap();
@@ -304,6 +310,126 @@ impl<F: FnOnce()> Drop for DeferGuard<F> {
     }
 }
 
+/// Progress of a running compaction, shown by `SHOW COMPACTIONS`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionJobInfo {
+    pub job_id: u64,
+    pub owner: String,
+    pub vnode_id: VnodeId,
+    /// The kind of compaction: normal, delta, manual or small_file.
+    pub compaction: String,
+    pub in_level: LevelId,
+    pub out_level: LevelId,
+    pub files_in: u64,
+    pub bytes_in: u64,
+    pub files_out: u64,
+    pub bytes_out: u64,
+    /// Timestamp in nanoseconds when the job started.
+    pub start_time: i64,
+    pub cancelled: bool,
+}
+
+#[derive(Debug)]
+struct CompactionJobState {
+    info: CompactionJobInfo,
+    files_out: AtomicU64,
+    bytes_out: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+/// Running compactions of a node, registered when the compacting starts and
+/// removed when it finishes, fails or is cancelled.
+#[derive(Debug, Default)]
+pub struct CompactionJobRegistry {
+    next_job_id: AtomicU64,
+    jobs: Arc<parking_lot::Mutex<BTreeMap<u64, Arc<CompactionJobState>>>>,
+}
+
+impl CompactionJobRegistry {
+    pub fn register(&self, request: &CompactReq) -> CompactionJobHandle {
+        let job_id = self.next_job_id.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+        let state = Arc::new(CompactionJobState {
+            info: CompactionJobInfo {
+                job_id,
+                owner: request.version.owner().to_string(),
+                vnode_id: request.compact_task.vnode_id(),
+                compaction: request.compact_task.kind().to_string(),
+                in_level: request.in_level,
+                out_level: request.out_level,
+                files_in: request.files.len() as u64,
+                bytes_in: request.files.iter().map(|f| f.size()).sum(),
+                files_out: 0,
+                bytes_out: 0,
+                start_time: now_timestamp_nanos(),
+                cancelled: false,
+            },
+            files_out: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+            cancelled: AtomicBool::new(false),
+        });
+        self.jobs.lock().insert(job_id, state.clone());
+
+        CompactionJobHandle {
+            state,
+            jobs: self.jobs.clone(),
+        }
+    }
+
+    /// Get the running compactions ordered by job id.
+    pub fn jobs(&self) -> Vec<CompactionJobInfo> {
+        self.jobs
+            .lock()
+            .values()
+            .map(|state| CompactionJobInfo {
+                files_out: state.files_out.load(atomic::Ordering::Relaxed),
+                bytes_out: state.bytes_out.load(atomic::Ordering::Relaxed),
+                cancelled: state.cancelled.load(atomic::Ordering::Relaxed),
+                ..state.info.clone()
+            })
+            .collect()
+    }
+
+    /// Cancel the running compaction, it stops before compacting the next
+    /// series and its output file is removed. Returns false if not found.
+    pub fn cancel(&self, job_id: u64) -> bool {
+        match self.jobs.lock().get(&job_id) {
+            Some(state) => {
+                state.cancelled.store(true, atomic::Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A compaction registered in the [`CompactionJobRegistry`], removed from it
+/// when dropped.
+pub struct CompactionJobHandle {
+    state: Arc<CompactionJobState>,
+    jobs: Arc<parking_lot::Mutex<BTreeMap<u64, Arc<CompactionJobState>>>>,
+}
+
+impl CompactionJobHandle {
+    pub fn job_id(&self) -> u64 {
+        self.state.info.job_id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(atomic::Ordering::Relaxed)
+    }
+
+    pub fn set_output(&self, files: u64, bytes: u64) {
+        self.state.files_out.store(files, atomic::Ordering::Relaxed);
+        self.state.bytes_out.store(bytes, atomic::Ordering::Relaxed);
+    }
+}
+
+impl Drop for CompactionJobHandle {
+    fn drop(&mut self) {
+        self.jobs.lock().remove(&self.state.info.job_id);
+    }
+}
+
 pub struct FlushJob {
     ctx: Arc<TsKvContext>,
 
@@ -500,9 +626,12 @@ mod test {
     use std::sync::atomic::{self, AtomicI32};
     use std::sync::Arc;
 
-    use super::DeferGuard;
+    use models::predicate::domain::TimeRange;
+
+    use super::{CompactionJobRegistry, DeferGuard};
     use crate::compaction::job::CompactProcessor;
-    use crate::compaction::CompactTask;
+    use crate::compaction::test::{FileSketch, VersionSketch};
+    use crate::compaction::{create_options, CompactReq, CompactTask};
     use crate::VnodeId;
 
     #[test]
@@ -531,6 +660,52 @@ mod test {
         assert_eq!(vnode_ids, vec![1, 2, 3]);
     }
 
+    #[tokio::test]
+    async fn test_compaction_job_registry() {
+        let dir = "/tmp/test/compaction/job_registry";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 1);
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(1, FileSketch(1, (1, 1000), 100, false))
+            .add(1, FileSketch(2, (1001, 2000), 200, false))
+            .to_version(opt.storage.clone())
+            .await;
+        let version = Arc::new(version);
+        let request = CompactReq {
+            compact_task: CompactTask::Manual(1),
+            files: version.levels_info()[1].files.clone(),
+            version,
+            in_level: 1,
+            out_level: 2,
+            out_time_range: TimeRange::all(),
+        };
+
+        let registry = CompactionJobRegistry::default();
+        let job_1 = registry.register(&request);
+        let job_2 = registry.register(&request);
+        assert_ne!(job_1.job_id(), job_2.job_id());
+        job_1.set_output(1, 50);
+
+        let jobs = registry.jobs();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].job_id, job_1.job_id());
+        assert_eq!(jobs[0].owner, "dba");
+        assert_eq!(jobs[0].compaction, "manual");
+        assert_eq!((jobs[0].in_level, jobs[0].out_level), (1, 2));
+        assert_eq!((jobs[0].files_in, jobs[0].bytes_in), (2, 300));
+        assert_eq!((jobs[0].files_out, jobs[0].bytes_out), (1, 50));
+
+        assert!(registry.cancel(job_2.job_id()));
+        assert!(job_2.is_cancelled());
+        assert!(!job_1.is_cancelled());
+        assert!(!registry.cancel(100));
+
+        drop(job_2);
+        let jobs = registry.jobs();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].job_id, job_1.job_id());
+    }
+
     #[test]
     fn test_defer_guard() {
         let a = Arc::new(AtomicI32::new(0));
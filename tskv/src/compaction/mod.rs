@@ -6,11 +6,13 @@ mod flush;
 pub mod job;
 pub mod metrics;
 mod picker;
+pub mod throttle;
 mod utils;
 mod writer_wrapper;
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use ::utils::BloomFilter;
 #[cfg(test)]
@@ -18,8 +20,11 @@ pub use compact::test::create_options;
 pub use compact::*;
 use metrics::FlushMetrics;
 use models::predicate::domain::TimeRange;
+use models::schema::database_schema::split_owner;
 pub use picker::*;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use trace::warn;
 
 use crate::compaction::metrics::VnodeCompactionMetrics;
 use crate::context::GlobalContext;
@@ -27,7 +32,8 @@ use crate::index::ts_index::TSIndex;
 use crate::tsfamily::column_file::ColumnFile;
 use crate::tsfamily::tseries_family::TseriesFamily;
 use crate::tsfamily::version::Version;
-use crate::{ColumnFileId, LevelId, TskvResult, VersionEdit, VnodeId};
+use crate::version_set::VersionSet;
+use crate::{ColumnFileId, LevelId, TskvError, TskvResult, VersionEdit, VnodeId};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CompactTask {
@@ -37,6 +43,8 @@ pub enum CompactTask {
     Delta(VnodeId),
     /// Triggers compaction manually.
     Manual(VnodeId),
+    /// Merge small files of a level when the number of them reaches the watermark.
+    SmallFile(VnodeId),
 }
 
 impl CompactTask {
@@ -45,6 +53,17 @@ impl CompactTask {
             CompactTask::Normal(vnode_id) => *vnode_id,
             CompactTask::Delta(vnode_id) => *vnode_id,
             CompactTask::Manual(vnode_id) => *vnode_id,
+            CompactTask::SmallFile(vnode_id) => *vnode_id,
+        }
+    }
+
+    /// The kind of the compaction: normal, delta, manual or small_file.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CompactTask::Normal(_) => "normal",
+            CompactTask::Delta(_) => "delta",
+            CompactTask::Manual(_) => "manual",
+            CompactTask::SmallFile(_) => "small_file",
         }
     }
 
@@ -52,7 +71,8 @@ impl CompactTask {
         match self {
             CompactTask::Manual(_) => 0,
             CompactTask::Delta(_) => 1,
-            CompactTask::Normal(_) => 2,
+            CompactTask::SmallFile(_) => 2,
+            CompactTask::Normal(_) => 3,
         }
     }
 }
@@ -75,6 +95,7 @@ impl std::fmt::Display for CompactTask {
             CompactTask::Normal(vnode_id) => write!(f, "Normal({})", vnode_id),
             CompactTask::Delta(vnode_id) => write!(f, "Delta({})", vnode_id),
             CompactTask::Manual(vnode_id) => write!(f, "Manual({})", vnode_id),
+            CompactTask::SmallFile(vnode_id) => write!(f, "SmallFile({})", vnode_id),
         }
     }
 }
@@ -145,11 +166,110 @@ pub async fn run_compaction_job(
     ctx: Arc<GlobalContext>,
     metrics: VnodeCompactionMetrics,
 ) -> TskvResult<Option<(VersionEdit, HashMap<ColumnFileId, Arc<BloomFilter>>)>> {
-    if request.in_level == 0 {
-        run_delta_compaction_job(request, ctx, metrics).await
+    let read_bytes = request.files.iter().map(|f| f.size()).sum();
+    let files = request.files.clone();
+    let start = Instant::now();
+    let result = if request.in_level == 0 {
+        run_delta_compaction_job(request, ctx.clone(), metrics).await
     } else {
-        run_normal_compaction_job(request, ctx, metrics).await
+        run_normal_compaction_job(request, ctx.clone(), metrics).await
+    };
+    match &result {
+        Ok(Some(_)) => ctx.record_compaction(read_bytes, start.elapsed()),
+        Err(TskvError::CompactionCancelled { .. }) => {
+            // Files of the cancelled compaction can be picked again.
+            for file in files.iter() {
+                *file.write_lock_compacting().await = false;
+            }
+        }
+        _ => {}
+    }
+    result
+}
+
+/// A compaction that would be run on a vnode, see [`plan_compactions`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactionPlan {
+    pub vnode_id: VnodeId,
+    /// The kind of compaction: delta, normal or small_file.
+    pub compaction: String,
+    pub in_level: LevelId,
+    pub out_level: LevelId,
+    /// The (level, file_id, file_size) of files to compact.
+    pub files: Vec<(LevelId, ColumnFileId, u64)>,
+    pub read_bytes: u64,
+    /// The upper bound of bytes to write, data may be deduplicated or deleted by tombstones.
+    pub write_bytes: u64,
+    /// Estimated by the throughput of finished compactions on this node,
+    /// None if no compaction finished yet.
+    pub duration: Option<Duration>,
+}
+
+/// Runs the pickers of all kinds of compaction on the version and returns the
+/// compactions that would be run, nothing is compacted.
+pub async fn plan_compactions(
+    vnode_id: VnodeId,
+    version: Arc<Version>,
+    strategy: &dyn CompactionStrategy,
+    ctx: &GlobalContext,
+) -> Vec<CompactionPlan> {
+    let tasks = [
+        ("delta", CompactTask::Delta(vnode_id)),
+        ("normal", CompactTask::Normal(vnode_id)),
+        ("small_file", CompactTask::SmallFile(vnode_id)),
+    ];
+    let throughput = ctx.compaction_throughput();
+
+    let mut plans = vec![];
+    for (compaction, task) in tasks {
+        let Some(req) = pick_compaction(task, version.clone(), strategy).await else {
+            continue;
+        };
+        // Pickers mark the picked files as compacting, release them.
+        for file in req.files.iter() {
+            *file.write_lock_compacting().await = false;
+        }
+
+        let files = req
+            .files
+            .iter()
+            .map(|f| (f.level(), f.file_id(), f.size()))
+            .collect::<Vec<_>>();
+        let read_bytes = files.iter().map(|(_, _, size)| size).sum();
+        plans.push(CompactionPlan {
+            vnode_id,
+            compaction: compaction.to_string(),
+            in_level: req.in_level,
+            out_level: req.out_level,
+            files,
+            read_bytes,
+            write_bytes: read_bytes,
+            duration: throughput.map(|t| Duration::from_secs_f64(read_bytes as f64 / t)),
+        });
+    }
+    plans
+}
+
+/// Returns the compaction strategy of the database of the owner, the level
+/// strategy is used if the schema of the database is not found.
+pub async fn database_compaction_strategy(
+    version_set: &RwLock<VersionSet>,
+    owner: &str,
+) -> Box<dyn CompactionStrategy> {
+    let (tenant, db_name) = split_owner(owner);
+    let db = version_set.read().await.get_db(tenant, db_name);
+    if let Some(db) = db {
+        match db.read().await.get_schema().await {
+            Ok(schema) => {
+                return compaction_strategy(
+                    schema.options().compaction(),
+                    *schema.config().precision(),
+                );
+            }
+            Err(e) => warn!("Failed to get the schema of database {owner}: {e}"),
+        }
     }
+    Box::new(LevelCompactionPicker)
 }
 
 #[derive(Clone)]
@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use models::predicate::domain::TimeRange;
+use models::schema::database_schema::{CompactionOptions, CompactionStrategyKind};
 use tokio::sync::RwLockWriteGuard;
 use trace::{debug, error, info};
+use utils::duration::CnosDuration;
+use utils::precision::Precision;
 
 use super::CompactTask;
 use crate::compaction::CompactReq;
@@ -13,16 +18,15 @@ use crate::tsfamily::version::Version;
 use crate::tsm::tombstone::TsmTombstoneCache;
 use crate::{LevelId, TskvResult};
 
+/// Picks the files to compact for the task, the files of the normal
+/// compactions are picked by the strategy of the database.
 pub async fn pick_compaction(
     compact_task: CompactTask,
     version: Arc<Version>,
+    strategy: &dyn CompactionStrategy,
 ) -> Option<CompactReq> {
     match &compact_task {
-        CompactTask::Normal(_) => {
-            LevelCompactionPicker
-                .pick_compaction(compact_task, version)
-                .await
-        }
+        CompactTask::Normal(_) => strategy.pick_compaction(compact_task, version).await,
         CompactTask::Delta(_) => {
             DeltaCompactionPicker::new()
                 .pick_compaction(compact_task, version)
@@ -33,15 +37,48 @@ pub async fn pick_compaction(
                 .pick_compaction(compact_task, version)
                 .await
         }
+        CompactTask::SmallFile(_) => {
+            SmallFileCompactionPicker
+                .pick_compaction(compact_task, version)
+                .await
+        }
+    }
+}
+
+/// Strategy of picking the files of level-1 to level-4 for the
+/// [`CompactTask::Normal`] compactions, selected by the `COMPACTION` option
+/// of the database.
+#[async_trait]
+pub trait CompactionStrategy: Debug + Send + Sync {
+    async fn pick_compaction(
+        &self,
+        compact_task: CompactTask,
+        version: Arc<Version>,
+    ) -> Option<CompactReq>;
+}
+
+/// Creates the compaction strategy of the options of a database, the time
+/// window is converted into the precision of the database.
+pub fn compaction_strategy(
+    options: &CompactionOptions,
+    precision: Precision,
+) -> Box<dyn CompactionStrategy> {
+    match options.strategy {
+        CompactionStrategyKind::Level => Box::new(LevelCompactionPicker),
+        CompactionStrategyKind::TimeWindow => {
+            Box::new(TimeWindowCompactionPicker::new(options, precision))
+        }
+        CompactionStrategyKind::SizeTiered => Box::new(SizeTieredCompactionPicker::new(options)),
     }
 }
 
 /// Compaction picker for picking a level from level-1 to level-4, and then
 /// pick inner files of the level.
 #[derive(Debug)]
-struct LevelCompactionPicker;
+pub struct LevelCompactionPicker;
 
-impl LevelCompactionPicker {
+#[async_trait]
+impl CompactionStrategy for LevelCompactionPicker {
     async fn pick_compaction(
         &self,
         compact_task: CompactTask,
@@ -97,7 +134,9 @@ impl LevelCompactionPicker {
             out_time_range: TimeRange::all(),
         })
     }
+}
 
+impl LevelCompactionPicker {
     /// Weight of file number of a level to be picked.
     fn level_weight_file_num(level: LevelId) -> f64 {
         match level {
@@ -212,6 +251,332 @@ impl LevelCompactionPicker {
     }
 }
 
+/// Compaction picker for merging the files of a level in the same time window,
+/// the merged files are moved to the next level when they reach the target
+/// file size of the level.
+#[derive(Debug)]
+pub struct TimeWindowCompactionPicker {
+    options: CompactionOptions,
+    /// Width of the time windows, in the precision of the database.
+    window: i64,
+}
+
+impl TimeWindowCompactionPicker {
+    pub fn new(options: &CompactionOptions, precision: Precision) -> Self {
+        let window = CnosDuration::new_with_duration(options.time_window())
+            .to_precision(precision)
+            .max(1);
+        Self {
+            options: options.clone(),
+            window,
+        }
+    }
+}
+
+#[async_trait]
+impl CompactionStrategy for TimeWindowCompactionPicker {
+    async fn pick_compaction(
+        &self,
+        compact_task: CompactTask,
+        version: Arc<Version>,
+    ) -> Option<CompactReq> {
+        let storage_opt = version.storage_opt();
+        for lvl in version.levels_info().iter().skip(1) {
+            let target_size = self
+                .options
+                .level_file_size(lvl.level, storage_opt.base_file_size);
+            // Files of the level grouped by the time window of min_ts, the
+            // older windows are picked first.
+            let mut windows: BTreeMap<i64, Vec<Arc<ColumnFile>>> = BTreeMap::new();
+            for file in idle_files(lvl).await {
+                windows
+                    .entry(file.time_range().min_ts.div_euclid(self.window))
+                    .or_default()
+                    .push(file);
+            }
+
+            for (window, mut files) in windows {
+                let window_size = files.iter().map(|f| f.size()).sum::<u64>();
+                let out_level = if lvl.level < 4 && window_size >= target_size {
+                    lvl.level + 1
+                } else {
+                    lvl.level
+                };
+                if files.len() < 2 && out_level == lvl.level {
+                    continue;
+                }
+                files.sort_by(LevelCompactionPicker::compare_column_file);
+                let picking_files =
+                    LevelCompactionPicker::pick_files(files, storage_opt.max_compact_size).await;
+                if picking_files.is_empty() || (picking_files.len() < 2 && out_level == lvl.level) {
+                    release_files(&picking_files).await;
+                    continue;
+                }
+                debug!(
+                    "Picker(time_window): picked {} files of window {window} in level: {}",
+                    picking_files.len(),
+                    lvl.level
+                );
+
+                return Some(CompactReq {
+                    compact_task,
+                    version: version.clone(),
+                    files: picking_files,
+                    in_level: lvl.level,
+                    out_level,
+                    out_time_range: TimeRange::all(),
+                });
+            }
+        }
+
+        debug!("Picker(time_window): picked no files");
+        None
+    }
+}
+
+/// Compaction picker for merging the files of a level of similar sizes, the
+/// merged files are moved to the next level when they reach the target file
+/// size of the level.
+#[derive(Debug)]
+pub struct SizeTieredCompactionPicker {
+    options: CompactionOptions,
+}
+
+impl SizeTieredCompactionPicker {
+    /// Files of a tier are at most this times the average size of the
+    /// smaller files of the tier.
+    const TIER_SIZE_RATIO: f64 = 2.0;
+
+    pub fn new(options: &CompactionOptions) -> Self {
+        Self {
+            options: options.clone(),
+        }
+    }
+
+    /// Groups the files sorted by size(ascending) into tiers of similar sizes.
+    fn tiers(files: Vec<Arc<ColumnFile>>) -> Vec<Vec<Arc<ColumnFile>>> {
+        let mut tiers: Vec<Vec<Arc<ColumnFile>>> = vec![];
+        let mut tier_size = 0_u64;
+        for file in files {
+            match tiers.last_mut() {
+                Some(tier)
+                    if file.size() as f64
+                        <= tier_size as f64 / tier.len() as f64 * Self::TIER_SIZE_RATIO =>
+                {
+                    tier_size += file.size();
+                    tier.push(file);
+                }
+                _ => {
+                    tier_size = file.size();
+                    tiers.push(vec![file]);
+                }
+            }
+        }
+        tiers
+    }
+}
+
+#[async_trait]
+impl CompactionStrategy for SizeTieredCompactionPicker {
+    async fn pick_compaction(
+        &self,
+        compact_task: CompactTask,
+        version: Arc<Version>,
+    ) -> Option<CompactReq> {
+        let storage_opt = version.storage_opt();
+        let min_tier_files = (storage_opt.compact_trigger_file_num as usize).max(2);
+        for lvl in version.levels_info().iter().skip(1) {
+            let mut files = idle_files(lvl).await;
+            files.sort_by_key(|f| f.size());
+            // The tier with the most files, the tier of smaller files if tied.
+            let Some(mut tier) = Self::tiers(files)
+                .into_iter()
+                .filter(|tier| tier.len() >= min_tier_files)
+                .rev()
+                .max_by_key(|tier| tier.len())
+            else {
+                continue;
+            };
+
+            tier.sort_by(LevelCompactionPicker::compare_column_file);
+            let picking_files =
+                LevelCompactionPicker::pick_files(tier, storage_opt.max_compact_size).await;
+            if picking_files.len() < 2 {
+                release_files(&picking_files).await;
+                continue;
+            }
+            let picking_size = picking_files.iter().map(|f| f.size()).sum::<u64>();
+            let target_size = self
+                .options
+                .level_file_size(lvl.level, storage_opt.base_file_size);
+            let out_level = if lvl.level < 4 && picking_size >= target_size {
+                lvl.level + 1
+            } else {
+                lvl.level
+            };
+            debug!(
+                "Picker(size_tiered): picked {} files of {picking_size} bytes in level: {}",
+                picking_files.len(),
+                lvl.level
+            );
+
+            return Some(CompactReq {
+                compact_task,
+                version: version.clone(),
+                files: picking_files,
+                in_level: lvl.level,
+                out_level,
+                out_time_range: TimeRange::all(),
+            });
+        }
+
+        debug!("Picker(size_tiered): picked no files");
+        None
+    }
+}
+
+/// Files of the level that are not being compacted.
+async fn idle_files(level: &LevelInfo) -> Vec<Arc<ColumnFile>> {
+    let mut files = Vec::with_capacity(level.files.len());
+    for file in level.files.iter() {
+        if !file.is_compacting().await {
+            files.push(file.clone());
+        }
+    }
+    files
+}
+
+/// Releases the files marked compacting but not to be compacted.
+async fn release_files(files: &[Arc<ColumnFile>]) {
+    for file in files {
+        *file.write_lock_compacting().await = false;
+    }
+}
+
+/// Compaction picker for picking small files of the level whose number of small
+/// files reaches the watermark, and merging them into bigger files of the same level.
+/// Small files of level-0 are merged by the [`DeltaCompactionPicker`].
+#[derive(Debug)]
+pub(crate) struct SmallFileCompactionPicker;
+
+impl SmallFileCompactionPicker {
+    async fn pick_compaction(
+        &self,
+        compact_task: CompactTask,
+        version: Arc<Version>,
+    ) -> Option<CompactReq> {
+        let storage_opt = version.storage_opt();
+        let small_file_size = storage_opt.compact_small_file_size;
+        let level = match Self::pick_level(
+            version.levels_info(),
+            small_file_size,
+            &storage_opt.compact_small_file_watermarks,
+        ) {
+            Some(level) => level,
+            None => {
+                debug!("Picker(small_file): picked no level");
+                return None;
+            }
+        };
+        if level == 0 {
+            return DeltaCompactionPicker::new()
+                .pick_compaction(compact_task, version)
+                .await;
+        }
+
+        // Merges the longest run of adjacent small files, files are small, so
+        // that many files are merged in one pass.
+        let Some(run) =
+            Self::small_file_runs(&version.levels_info()[level as usize], small_file_size)
+                .await
+                .into_iter()
+                .max_by_key(|run| run.len())
+        else {
+            debug!("Picker(small_file): picked no adjacent small files in level: {level}");
+            return None;
+        };
+        let mut picking_files = Vec::with_capacity(run.len());
+        let mut picking_size = 0_u64;
+        for file in run {
+            // Stop at the file being compacted, the picked files stay adjacent.
+            if !file.mark_compacting().await {
+                break;
+            }
+            picking_size += file.size();
+            picking_files.push(file);
+            if picking_size >= storage_opt.max_compact_size {
+                break;
+            }
+        }
+        if picking_files.len() < 2 {
+            release_files(&picking_files).await;
+            return None;
+        }
+        let mut out_time_range = *picking_files[0].time_range();
+        for file in picking_files.iter().skip(1) {
+            out_time_range.merge(file.time_range());
+        }
+        info!(
+            "Picker(small_file): picked {} small files of {out_time_range} in level: {level}",
+            picking_files.len()
+        );
+
+        Some(CompactReq {
+            compact_task,
+            version,
+            files: picking_files,
+            in_level: level,
+            out_level: level,
+            out_time_range,
+        })
+    }
+
+    /// Runs of at least 2 small files that are adjacent in the level sorted by
+    /// time range, a larger file or a file being compacted ends a run, so that
+    /// the file merged from a run does not overlap the other files of the level.
+    async fn small_file_runs(level: &LevelInfo, small_file_size: u64) -> Vec<Vec<Arc<ColumnFile>>> {
+        let mut files = level.files.clone();
+        files.sort_by(LevelCompactionPicker::compare_column_file);
+
+        let mut runs = vec![];
+        let mut run = vec![];
+        for file in files {
+            if file.size() < small_file_size && !file.is_compacting().await {
+                run.push(file);
+            } else if !run.is_empty() {
+                runs.push(std::mem::take(&mut run));
+            }
+        }
+        runs.push(run);
+        runs.retain(|run| run.len() >= 2);
+        runs
+    }
+
+    /// Returns the level with the most small files relative to its watermark,
+    /// only levels that the number of small files reaches the watermark are considered.
+    pub(crate) fn pick_level(
+        levels: &[LevelInfo],
+        small_file_size: u64,
+        watermarks: &[u32],
+    ) -> Option<LevelId> {
+        levels
+            .iter()
+            .zip(watermarks.iter())
+            .filter(|(_, watermark)| **watermark > 0)
+            .filter_map(|(lvl, watermark)| {
+                let small_files = lvl
+                    .files
+                    .iter()
+                    .filter(|f| f.size() < small_file_size)
+                    .count();
+                (small_files >= *watermark as usize)
+                    .then_some((lvl.level, small_files as f64 / *watermark as f64))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(level, _)| level)
+    }
+}
+
 /// For the given time_range of a file, return recommended level to compact in.
 /// If it returns 0, it means that there are no files overlapped with the given
 /// time range in levels.
@@ -496,13 +861,21 @@ impl DeltaCompactionPicker {
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
+    use std::time::Duration;
 
     use models::predicate::domain::TimeRange;
+    use models::schema::database_schema::{CompactionOptions, CompactionStrategyKind};
+    use utils::duration::CnosDuration;
+    use utils::precision::Precision;
 
     use super::advise_out_level;
-    use crate::compaction::picker::{DeltaCompactionPicker, LevelCompactionPicker};
+    use crate::compaction::picker::{
+        compaction_strategy, CompactionStrategy, DeltaCompactionPicker, LevelCompactionPicker,
+        SizeTieredCompactionPicker, SmallFileCompactionPicker, TimeWindowCompactionPicker,
+    };
     use crate::compaction::test::{FileSketch, VersionSketch};
-    use crate::compaction::{create_options, CompactTask};
+    use crate::compaction::{create_options, plan_compactions, CompactTask};
+    use crate::context::GlobalContext;
 
     #[tokio::test]
     async fn test_pick_normal_compaction() {
@@ -536,6 +909,223 @@ mod test {
         assert_eq!(compact_req.out_level, 2);
     }
 
+    #[tokio::test]
+    async fn test_pick_small_file_compaction() {
+        let dir = "/tmp/test/pick/small_file_compaction";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 4);
+        let mut storage_opt = opt.storage.as_ref().clone();
+        storage_opt.compact_small_file_size = 500;
+        storage_opt.compact_small_file_watermarks = vec![0, 4, 2, 0, 0];
+
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(1, FileSketch(7, (601, 650), 100, false))
+            .add(1, FileSketch(8, (651, 700), 100, false))
+            .add(1, FileSketch(9, (701, 750), 100, false))
+            .add(1, FileSketch(10, (751, 800), 1000, false))
+            .add(2, FileSketch(4, (401, 450), 100, false))
+            .add(2, FileSketch(5, (451, 500), 200, false))
+            .add(2, FileSketch(6, (501, 600), 200, false))
+            .to_version(Arc::new(storage_opt))
+            .await;
+
+        // Level-1 has only 3 small files, less than the watermark 4.
+        let compact_req = SmallFileCompactionPicker
+            .pick_compaction(CompactTask::SmallFile(0), Arc::new(version))
+            .await
+            .unwrap();
+        assert_eq!(compact_req.in_level, 2);
+        assert_eq!(compact_req.out_level, 2);
+        assert_eq!(compact_req.files.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pick_adjacent_small_files() {
+        let dir = "/tmp/test/pick/adjacent_small_files";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 4);
+        let mut storage_opt = opt.storage.as_ref().clone();
+        storage_opt.compact_small_file_size = 500;
+        storage_opt.compact_small_file_watermarks = vec![0, 0, 2, 0, 0];
+        let storage_opt = Arc::new(storage_opt);
+
+        // The small files 4 and 6 are separated by the large file 5.
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(2, FileSketch(6, (501, 550), 100, false))
+            .add(2, FileSketch(5, (451, 500), 1000, false))
+            .add(2, FileSketch(4, (401, 450), 100, false))
+            .to_version(storage_opt.clone())
+            .await;
+        assert!(SmallFileCompactionPicker
+            .pick_compaction(CompactTask::SmallFile(0), Arc::new(version))
+            .await
+            .is_none());
+
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(2, FileSketch(4, (401, 450), 100, false))
+            .add(2, FileSketch(5, (451, 500), 1000, false))
+            .add(2, FileSketch(6, (501, 550), 100, false))
+            .add(2, FileSketch(7, (551, 600), 100, false))
+            .to_version(storage_opt)
+            .await;
+        let compact_req = SmallFileCompactionPicker
+            .pick_compaction(CompactTask::SmallFile(0), Arc::new(version))
+            .await
+            .unwrap();
+        assert_eq!((compact_req.in_level, compact_req.out_level), (2, 2));
+        let file_ids = compact_req
+            .files
+            .iter()
+            .map(|f| f.file_id())
+            .collect::<Vec<_>>();
+        assert_eq!(file_ids, vec![6, 7]);
+        assert_eq!(compact_req.out_time_range, TimeRange::new(501, 600));
+    }
+
+    #[tokio::test]
+    async fn test_plan_compactions() {
+        let dir = "/tmp/test/pick/plan_compactions";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 4);
+        let mut storage_opt = opt.storage.as_ref().clone();
+        storage_opt.compact_small_file_size = 500;
+        storage_opt.compact_small_file_watermarks = vec![0, 4, 2, 0, 0];
+
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(2, FileSketch(4, (401, 450), 100, false))
+            .add(2, FileSketch(5, (451, 500), 200, false))
+            .add(2, FileSketch(6, (501, 600), 200, false))
+            .to_version(Arc::new(storage_opt))
+            .await;
+        let version = Arc::new(version);
+
+        let ctx = GlobalContext::new();
+        ctx.record_compaction(1000, Duration::from_secs(1));
+        let plans = plan_compactions(1, version.clone(), &LevelCompactionPicker, &ctx).await;
+        let plan = plans.iter().find(|p| p.compaction == "small_file").unwrap();
+        assert_eq!(plan.vnode_id, 1);
+        assert_eq!((plan.in_level, plan.out_level), (2, 2));
+        assert_eq!(plan.files.len(), 3);
+        assert_eq!(plan.read_bytes, 500);
+        assert_eq!(plan.duration, Some(Duration::from_millis(500)));
+
+        // Files are not left compacting, so they are picked again.
+        assert_eq!(
+            plan_compactions(1, version, &LevelCompactionPicker, &ctx).await,
+            plans
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pick_time_window_compaction() {
+        let dir = "/tmp/test/pick/time_window_compaction";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 4);
+        let options = CompactionOptions {
+            strategy: CompactionStrategyKind::TimeWindow,
+            time_window: Some(CnosDuration::new_with_duration(Duration::from_millis(1000))),
+            level_file_sizes: vec![1000, 4000],
+        };
+
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(1, FileSketch(5, (1, 400), 300, false))
+            .add(1, FileSketch(6, (1001, 1400), 300, false))
+            .add(1, FileSketch(7, (1401, 1900), 300, false))
+            .add(1, FileSketch(8, (1500, 1999), 300, true))
+            .add(1, FileSketch(9, (2001, 2400), 300, false))
+            .add(2, FileSketch(3, (1, 500), 2000, false))
+            .add(2, FileSketch(4, (501, 999), 2000, false))
+            .to_version(opt.storage.clone())
+            .await;
+        let version = Arc::new(version);
+
+        // Files 6 and 7 are in window [1000, 2000) of level-1, but smaller
+        // than the target file size of level-1, so they stay in level-1.
+        let picker = TimeWindowCompactionPicker::new(&options, Precision::MS);
+        let compact_req = picker
+            .pick_compaction(CompactTask::Normal(0), version.clone())
+            .await
+            .unwrap();
+        assert_eq!((compact_req.in_level, compact_req.out_level), (1, 1));
+        let mut file_ids = compact_req
+            .files
+            .iter()
+            .map(|f| f.file_id())
+            .collect::<Vec<_>>();
+        file_ids.sort();
+        assert_eq!(file_ids, vec![6, 7]);
+
+        // Files 3 and 4 are in window [0, 1000) of level-2, and reach the
+        // target file size of level-2.
+        let compact_req = picker
+            .pick_compaction(CompactTask::Normal(0), version.clone())
+            .await
+            .unwrap();
+        assert_eq!((compact_req.in_level, compact_req.out_level), (2, 3));
+        assert_eq!(compact_req.files.len(), 2);
+
+        assert!(picker
+            .pick_compaction(CompactTask::Normal(0), version)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pick_size_tiered_compaction() {
+        let dir = "/tmp/test/pick/size_tiered_compaction";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 3);
+        let options = CompactionOptions {
+            strategy: CompactionStrategyKind::SizeTiered,
+            time_window: None,
+            level_file_sizes: vec![1000, 5000],
+        };
+
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(1, FileSketch(7, (1, 1000), 100, false))
+            .add(1, FileSketch(8, (1001, 2000), 150, false))
+            .add(1, FileSketch(9, (2001, 3000), 5000, false))
+            .add(2, FileSketch(1, (1, 1000), 300, false))
+            .add(2, FileSketch(2, (1001, 2000), 400, false))
+            .add(2, FileSketch(3, (2001, 3000), 500, false))
+            .add(2, FileSketch(4, (3001, 4000), 2000, false))
+            .add(2, FileSketch(5, (4001, 5000), 2500, false))
+            .add(2, FileSketch(6, (5001, 6000), 3000, false))
+            .to_version(opt.storage.clone())
+            .await;
+        let version = Arc::new(version);
+
+        // Level-1 has only 2 small files, less than the compact trigger file num,
+        // level-2 has 2 tiers of 3 files, the tier of smaller files is picked.
+        let picker = compaction_strategy(&options, Precision::NS);
+        let compact_req = picker
+            .pick_compaction(CompactTask::Normal(0), version.clone())
+            .await
+            .unwrap();
+        assert_eq!((compact_req.in_level, compact_req.out_level), (2, 2));
+        let mut file_ids = compact_req
+            .files
+            .iter()
+            .map(|f| f.file_id())
+            .collect::<Vec<_>>();
+        file_ids.sort();
+        assert_eq!(file_ids, vec![1, 2, 3]);
+
+        // The tier of larger files reaches the target file size of level-2.
+        let compact_req = SizeTieredCompactionPicker::new(&options)
+            .pick_compaction(CompactTask::Normal(0), version)
+            .await
+            .unwrap();
+        assert_eq!((compact_req.in_level, compact_req.out_level), (2, 3));
+        let mut file_ids = compact_req
+            .files
+            .iter()
+            .map(|f| f.file_id())
+            .collect::<Vec<_>>();
+        file_ids.sort();
+        assert_eq!(file_ids, vec![4, 5, 6]);
+    }
+
     /// Test picker for delta compaction that all delta files could be merged into level-1.
     #[tokio::test]
     async fn test_pick_delta_compaction_with_tsm_1() {
@@ -0,0 +1,193 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use metrics::metric_value::HistogramValue;
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// The rate is not lowered below `1 / MIN_RATE_DIVISOR` of the configured rate.
+const MIN_RATE_DIVISOR: u64 = 16;
+/// The rate is raised by `1 / RATE_STEP_DIVISOR` of the configured rate each time
+/// the queries are fast again.
+const RATE_STEP_DIVISOR: u64 = 8;
+
+/// Limits the bytes per second read and written by the compactions of this
+/// node, shared by all of them. The rate is lowered while the queries are slow
+/// and raised back while they are fast, see [`Self::adjust`].
+#[derive(Debug, Default)]
+pub struct CompactionThrottle {
+    /// The configured bytes per second, 0 means unlimited.
+    max_rate: AtomicU64,
+    /// The current bytes per second.
+    rate: AtomicU64,
+    /// The time when the bytes acquired so far are all consumed.
+    next_free: Mutex<Option<Instant>>,
+}
+
+impl CompactionThrottle {
+    pub fn new(max_rate: u64) -> Self {
+        Self {
+            max_rate: AtomicU64::new(max_rate),
+            rate: AtomicU64::new(max_rate),
+            next_free: Mutex::new(None),
+        }
+    }
+
+    /// The current bytes per second, 0 means unlimited.
+    pub fn rate(&self) -> u64 {
+        self.rate.load(Ordering::Relaxed)
+    }
+
+    pub fn set_max_rate(&self, max_rate: u64) {
+        self.max_rate.store(max_rate, Ordering::Relaxed);
+        self.rate.store(max_rate, Ordering::Relaxed);
+    }
+
+    /// Wait until `bytes` can be read or written without exceeding the rate.
+    pub async fn acquire(&self, bytes: u64) {
+        let rate = self.rate();
+        if rate == 0 || bytes == 0 {
+            return;
+        }
+        let cost = Duration::from_secs_f64(bytes as f64 / rate as f64);
+        let now = Instant::now();
+        let start = {
+            let mut next_free = self.next_free.lock();
+            let start = match *next_free {
+                Some(t) if t > now => t,
+                _ => now,
+            };
+            *next_free = Some(start + cost);
+            start
+        };
+        if start > now {
+            tokio::time::sleep_until(start).await;
+        }
+    }
+
+    /// Halve the rate if the queries are slow, or raise it by a step towards the
+    /// configured rate if not. Returns the new rate.
+    pub fn adjust(&self, queries_slow: bool) -> u64 {
+        let max_rate = self.max_rate.load(Ordering::Relaxed);
+        if max_rate == 0 {
+            return 0;
+        }
+        let rate = self.rate();
+        let new_rate = if queries_slow {
+            (rate / 2).max(max_rate / MIN_RATE_DIVISOR).max(1)
+        } else {
+            (rate + max_rate / RATE_STEP_DIVISOR).min(max_rate)
+        };
+        self.rate.store(new_rate, Ordering::Relaxed);
+        new_rate
+    }
+}
+
+/// Get the P99 of the latencies recorded between the two snapshots of the
+/// histogram, it's the upper bound of the bucket it falls in. Returns None if
+/// nothing is recorded.
+pub fn latency_p99(
+    previous: &HistogramValue<Duration>,
+    current: &HistogramValue<Duration>,
+) -> Option<Duration> {
+    let counts = current
+        .buckets
+        .iter()
+        .map(|b| {
+            let prev = previous
+                .buckets
+                .iter()
+                .find(|p| p.le == b.le)
+                .map(|p| p.count)
+                .unwrap_or(0);
+            (b.le, b.count.saturating_sub(prev))
+        })
+        .collect::<Vec<_>>();
+    let total = counts.iter().map(|(_, c)| c).sum::<u64>();
+    if total == 0 {
+        return None;
+    }
+    let target = (total * 99).div_ceil(100);
+    let mut seen = 0;
+    for (le, count) in counts {
+        seen += count;
+        if seen >= target {
+            return Some(le);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use metrics::metric_value::{HistogramValue, ValueBucket};
+
+    use super::{latency_p99, CompactionThrottle};
+
+    fn histogram(counts: &[(u64, u64)]) -> HistogramValue<Duration> {
+        HistogramValue {
+            total: Duration::ZERO,
+            buckets: counts
+                .iter()
+                .map(|(le, count)| ValueBucket {
+                    le: Duration::from_millis(*le),
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_adjust_rate() {
+        let throttle = CompactionThrottle::new(1600);
+        assert_eq!(throttle.adjust(true), 800);
+        assert_eq!(throttle.adjust(true), 400);
+        assert_eq!(throttle.adjust(true), 200);
+        assert_eq!(throttle.adjust(true), 100);
+        assert_eq!(throttle.adjust(true), 100);
+        assert_eq!(throttle.adjust(false), 300);
+        for _ in 0..10 {
+            throttle.adjust(false);
+        }
+        assert_eq!(throttle.rate(), 1600);
+
+        let unlimited = CompactionThrottle::default();
+        assert_eq!(unlimited.adjust(true), 0);
+        assert_eq!(unlimited.rate(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_acquire() {
+        let throttle = CompactionThrottle::new(1_000_000);
+        let start = tokio::time::Instant::now();
+        throttle.acquire(50_000).await;
+        throttle.acquire(100_000).await;
+        throttle.acquire(50_000).await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        let unlimited = CompactionThrottle::default();
+        let start = tokio::time::Instant::now();
+        unlimited.acquire(u64::MAX).await;
+        assert!(start.elapsed() < Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_latency_p99() {
+        let previous = histogram(&[(10, 50), (100, 0), (1000, 0)]);
+        assert_eq!(latency_p99(&previous, &previous), None);
+
+        let current = histogram(&[(10, 150), (100, 1), (1000, 0)]);
+        assert_eq!(
+            latency_p99(&previous, &current),
+            Some(Duration::from_millis(10))
+        );
+
+        let current = histogram(&[(10, 148), (100, 1), (1000, 2)]);
+        assert_eq!(
+            latency_p99(&previous, &current),
+            Some(Duration::from_millis(1000))
+        );
+    }
+}
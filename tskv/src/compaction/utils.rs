@@ -1,10 +1,10 @@
-use arrow::compute::filter_record_batch;
+use arrow::compute::{concat_batches, filter_record_batch};
 use arrow_array::{
     BooleanArray, Int64Array, RecordBatch, TimestampMicrosecondArray, TimestampMillisecondArray,
     TimestampNanosecondArray,
 };
 use arrow_buffer::builder::BooleanBufferBuilder;
-use arrow_schema::{ArrowError, DataType, TimeUnit};
+use arrow_schema::{ArrowError, DataType, SchemaRef, TimeUnit};
 use models::predicate::domain::TimeRange;
 
 pub fn filter_record_batch_by_time_range(
@@ -14,6 +14,62 @@ pub fn filter_record_batch_by_time_range(
     let num_rows = record_batch.num_rows();
     let mut bit_set = BooleanBufferBuilder::new(num_rows);
     bit_set.append_n(num_rows, false);
+    let time_column = time_column_values(&record_batch)?;
+    // todo: handle time column
+    for (idx, value) in time_column.iter().enumerate() {
+        let ts = *value;
+        if time_range.contains(ts) {
+            bit_set.set_bit(idx, true);
+        }
+    }
+    let boolean_array = BooleanArray::new(bit_set.finish(), None);
+    filter_record_batch(&record_batch, &boolean_array)
+}
+
+/// Concatenate the record batches of an append-only table into record batches
+/// with at most `block_size` rows, in time order.
+///
+/// Returns `None` if a timestamp is not strictly greater than the previous one
+/// or the time ranges of two record batches overlap, which means the record
+/// batches must be merged and deduplicated.
+pub fn concat_sorted_runs(
+    schema: SchemaRef,
+    record_batches: Vec<RecordBatch>,
+    block_size: usize,
+) -> Result<Option<Vec<RecordBatch>>, ArrowError> {
+    let mut runs = Vec::with_capacity(record_batches.len());
+    for record_batch in record_batches {
+        let (min_ts, max_ts) = {
+            let time_column = time_column_values(&record_batch)?;
+            if time_column.windows(2).any(|w| w[0] >= w[1]) {
+                return Ok(None);
+            }
+            match (time_column.first(), time_column.last()) {
+                (Some(min_ts), Some(max_ts)) => (*min_ts, *max_ts),
+                _ => continue,
+            }
+        };
+        runs.push((min_ts, max_ts, record_batch));
+    }
+    runs.sort_by_key(|(min_ts, _, _)| *min_ts);
+    if runs.windows(2).any(|w| w[0].1 >= w[1].0) {
+        return Ok(None);
+    }
+
+    let record_batch = concat_batches(&schema, runs.iter().map(|(_, _, rb)| rb))?;
+    let num_rows = record_batch.num_rows();
+    let block_size = block_size.max(1);
+    let mut record_batches = Vec::with_capacity(num_rows / block_size + 1);
+    let mut offset = 0;
+    while offset < num_rows {
+        let len = block_size.min(num_rows - offset);
+        record_batches.push(record_batch.slice(offset, len));
+        offset += len;
+    }
+    Ok(Some(record_batches))
+}
+
+fn time_column_values(record_batch: &RecordBatch) -> Result<&[i64], ArrowError> {
     let time_column_type = record_batch.schema().field(0).data_type().clone();
     let time_column = match time_column_type {
         DataType::Timestamp(TimeUnit::Nanosecond, _) => record_batch
@@ -47,13 +103,66 @@ pub fn filter_record_batch_by_time_range(
             )));
         }
     };
-    // todo: handle time column
-    for (idx, value) in time_column.iter().enumerate() {
-        let ts = *value;
-        if time_range.contains(ts) {
-            bit_set.set_bit(idx, true);
-        }
+    Ok(&time_column[..])
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, TimestampNanosecondArray};
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+
+    use super::concat_sorted_runs;
+
+    fn record_batch(ts: Vec<i64>) -> RecordBatch {
+        let values = ts.clone();
+        RecordBatch::try_new(
+            schema(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(ts)) as ArrayRef,
+                Arc::new(Int64Array::from(values)) as ArrayRef,
+            ],
+        )
+        .unwrap()
+    }
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("f1", DataType::Int64, true),
+        ]))
+    }
+
+    #[test]
+    fn test_concat_sorted_runs() {
+        let record_batches = vec![
+            record_batch(vec![4, 5, 6]),
+            record_batch(vec![]),
+            record_batch(vec![1, 2, 3]),
+        ];
+        let result = concat_sorted_runs(schema(), record_batches, 4)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], record_batch(vec![1, 2, 3, 4]));
+        assert_eq!(result[1], record_batch(vec![5, 6]));
+    }
+
+    #[test]
+    fn test_concat_sorted_runs_with_duplicates() {
+        let record_batches = vec![record_batch(vec![1, 2, 3]), record_batch(vec![3, 4])];
+        assert!(concat_sorted_runs(schema(), record_batches, 4)
+            .unwrap()
+            .is_none());
+
+        let record_batches = vec![record_batch(vec![1, 1, 2])];
+        assert!(concat_sorted_runs(schema(), record_batches, 4)
+            .unwrap()
+            .is_none());
     }
-    let boolean_array = BooleanArray::new(bit_set.finish(), None);
-    filter_record_batch(&record_batch, &boolean_array)
 }
@@ -97,8 +97,36 @@ impl WriterWrapper {
         Ok(self.tsm_writer.as_mut().unwrap())
     }
 
+    /// The number and the size of the files written so far.
+    pub fn written(&self) -> (u64, u64) {
+        match &self.tsm_writer {
+            Some(tsm_writer) => (1, tsm_writer.size()),
+            None => (0, 0),
+        }
+    }
+
+    /// Remove the unfinished file of the cancelled compaction.
+    pub async fn abort(self) {
+        if let Some(tsm_writer) = self.tsm_writer {
+            let path = tsm_writer.path().to_path_buf();
+            drop(tsm_writer);
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                trace::warn!(
+                    "Compaction({}): Failed to remove file {}: {e}",
+                    self.compact_task,
+                    path.display()
+                );
+            }
+        }
+    }
+
     /// Write CompactingBlock to TsmWriter, fill file_metas and version_edit.
     pub async fn write(&mut self, blk: CompactingBlock) -> TskvResult<()> {
-        self.writer().await?.write_compacting_block(blk).await
+        let writer = self.writer().await?;
+        let size = writer.size();
+        writer.write_compacting_block(blk).await?;
+        let written = writer.size().saturating_sub(size);
+        self.context.compaction_throttle().acquire(written).await;
+        Ok(())
     }
 }
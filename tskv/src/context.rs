@@ -1,15 +1,31 @@
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::compaction::job::CompactionJobRegistry;
+use crate::compaction::throttle::CompactionThrottle;
 
 #[derive(Default, Debug)]
 pub struct GlobalContext {
     /// Database file id
     file_id: AtomicU64,
+    /// Total bytes read by finished compactions.
+    compacted_bytes: AtomicU64,
+    /// Total time taken by finished compactions in nanoseconds.
+    compaction_nanos: AtomicU64,
+    /// Running compactions of this node.
+    compaction_jobs: CompactionJobRegistry,
+    /// IO rate limit of compactions of this node.
+    compaction_throttle: CompactionThrottle,
 }
 
 impl GlobalContext {
     pub fn new() -> Self {
         Self {
             file_id: AtomicU64::new(0),
+            compacted_bytes: AtomicU64::new(0),
+            compaction_nanos: AtomicU64::new(0),
+            compaction_jobs: CompactionJobRegistry::default(),
+            compaction_throttle: CompactionThrottle::default(),
         }
     }
 }
@@ -30,6 +46,32 @@ impl GlobalContext {
         self.file_id.store(v, Ordering::Release);
     }
 
+    /// Record a finished compaction that read `bytes` in `duration`.
+    pub fn record_compaction(&self, bytes: u64, duration: Duration) {
+        self.compacted_bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.compaction_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Get the average bytes read per second of finished compactions,
+    /// None if no compaction finished yet.
+    pub fn compaction_throughput(&self) -> Option<f64> {
+        let bytes = self.compacted_bytes.load(Ordering::Relaxed);
+        let nanos = self.compaction_nanos.load(Ordering::Relaxed);
+        if bytes == 0 || nanos == 0 {
+            return None;
+        }
+        Some(bytes as f64 / Duration::from_nanos(nanos).as_secs_f64())
+    }
+
+    pub fn compaction_jobs(&self) -> &CompactionJobRegistry {
+        &self.compaction_jobs
+    }
+
+    pub fn compaction_throttle(&self) -> &CompactionThrottle {
+        &self.compaction_throttle
+    }
+
     pub fn mark_file_id_used(&self, v: u64) {
         let mut old = self.file_id.load(Ordering::Acquire);
         while old <= v {
@@ -1,5 +1,5 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::mem::size_of;
 use std::path::Path;
 use std::sync::Arc;
@@ -11,7 +11,7 @@ use metrics::metric_register::MetricsRegister;
 use models::predicate::domain::TimeRange;
 use models::schema::database_schema::{DatabaseConfig, DatabaseSchema};
 use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef};
-use models::{SeriesId, SeriesKey};
+use models::{tag, SeriesId, SeriesKey, Tag};
 use protos::models::{Column, ColumnType, FieldType, Table};
 use snafu::{OptionExt, ResultExt};
 use tokio::sync::mpsc::Sender;
@@ -394,6 +394,99 @@ impl Database {
         Ok(res_sids)
     }
 
+    /// Count the distinct series of each table in the points which are not in
+    /// the index yet, without creating them.
+    pub async fn count_new_series(
+        &self,
+        tables: FlatBufferTable<'_>,
+        ts_index: Arc<RwLock<TSIndex>>,
+    ) -> TskvResult<HashMap<String, u64>> {
+        let ts_index = ts_index.read().await;
+        let mut res = HashMap::new();
+        for table in tables {
+            let table_name = table.tab_ext()?;
+            let columns = table.columns().context(CommonSnafu {
+                reason: "table missing columns".to_string(),
+            })?;
+            let fb_schema = FbSchema::from_fb_column(table_name, columns)?;
+            let schema = self
+                .schemas
+                .get_table_schema(fb_schema.table)
+                .await
+                .context(SchemaSnafu)?;
+
+            let mut new_series = HashSet::new();
+            for row in 0..table.num_rows() as usize {
+                let series_key = schema.as_ref().and_then(|schema| {
+                    SeriesKey::build_series_key(
+                        fb_schema.table,
+                        &columns,
+                        schema,
+                        &fb_schema.tag_indexes,
+                        row,
+                    )
+                    .ok()
+                });
+                match series_key {
+                    Some(series_key) => {
+                        if ts_index
+                            .get_series_id(&series_key)
+                            .await
+                            .context(IndexErrSnafu)?
+                            .is_none()
+                        {
+                            new_series.insert(series_key);
+                        }
+                    }
+                    // The table or some tags are not created, the series is new.
+                    None => {
+                        new_series
+                            .insert(Self::series_key_by_tag_names(&fb_schema, &columns, row)?);
+                    }
+                }
+            }
+            if !new_series.is_empty() {
+                *res.entry(fb_schema.table.to_string()).or_default() += new_series.len() as u64;
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Build a series key of a row by the names of the tags instead of the ids.
+    fn series_key_by_tag_names(
+        fb_schema: &FbSchema<'_>,
+        columns: &Vector<'_, ForwardsUOffset<Column<'_>>>,
+        row: usize,
+    ) -> TskvResult<SeriesKey> {
+        let mut tags = vec![];
+        for (idx, name) in fb_schema.tag_indexes.iter().zip(fb_schema.tag_names.iter()) {
+            let column = columns.get(*idx);
+            let values = column.string_values().context(CommonSnafu {
+                reason: format!("tag column {} missing values", name),
+            })?;
+            let nullbits = column.nullbits().context(CommonSnafu {
+                reason: format!("tag column {} missing null bits", name),
+            })?;
+            if nullbits
+                .bytes()
+                .get(row / 8)
+                .map_or(false, |b| b & (1 << (row % 8)) != 0)
+            {
+                tags.push(Tag::new(
+                    name.as_bytes().to_vec(),
+                    values.get(row).as_bytes().to_vec(),
+                ));
+            }
+        }
+        tag::sort_tags(&mut tags);
+
+        Ok(SeriesKey {
+            tags,
+            table: fb_schema.table.to_string(),
+        })
+    }
+
     pub async fn get_series_key(
         &self,
         vnode_id: u32,
@@ -1,19 +1,28 @@
 #![allow(dead_code, unused_variables)]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use datafusion::arrow::record_batch::RecordBatch;
 use models::meta_data::VnodeId;
-use models::predicate::domain::ColumnDomains;
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRange};
+use models::schema::database_schema::make_owner;
 use models::{SeriesId, SeriesKey};
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
 
+use crate::compaction::job::CompactionJobInfo;
+use crate::compaction::CompactionPlan;
 use crate::error::TskvResult;
 use crate::kv_option::StorageOptions;
+use crate::scan_cost::ScanCost;
+use crate::subscription::{SubscriptionHub, WriteSubscription};
 use crate::tsfamily::super_version::SuperVersion;
 use crate::vnode_store::VnodeStorage;
-use crate::Engine;
+use crate::{Engine, VnodeBackup};
 
 #[derive(Debug, Default)]
 pub struct MockEngine {}
@@ -33,6 +42,10 @@ impl Engine for MockEngine {
         Ok(())
     }
 
+    async fn remove_database(&self, tenant: &str, database: &str) -> TskvResult<bool> {
+        Ok(true)
+    }
+
     async fn flush_tsfamily(
         &self,
         tenant: &str,
@@ -85,6 +98,26 @@ impl Engine for MockEngine {
         Ok(vec![])
     }
 
+    async fn table_series_count(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_id: VnodeId,
+    ) -> TskvResult<u64> {
+        Ok(0)
+    }
+
+    async fn count_new_series(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> TskvResult<HashMap<String, u64>> {
+        Ok(HashMap::new())
+    }
+
     async fn get_db_version(
         &self,
         tenant: &str,
@@ -110,5 +143,101 @@ impl Engine for MockEngine {
         todo!()
     }
 
+    async fn series_cardinality_top(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_ids: &[VnodeId],
+        top_k: usize,
+    ) -> TskvResult<RecordBatch> {
+        todo!()
+    }
+
+    async fn estimate_scan_cost(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_id: VnodeId,
+        predicate: &ResolvedPredicate,
+    ) -> TskvResult<ScanCost> {
+        todo!()
+    }
+
+    async fn export_vnode(
+        &self,
+        vnode_id: VnodeId,
+        time_range: TimeRange,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<u64> {
+        todo!()
+    }
+
+    async fn backup_vnode(
+        &self,
+        vnode_id: VnodeId,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<VnodeBackup> {
+        todo!()
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        backup: VnodeBackup,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<()> {
+        todo!()
+    }
+
+    async fn table_last_write(
+        &self,
+        vnode_id: VnodeId,
+        table: &str,
+        time_range: &TimeRange,
+    ) -> TskvResult<Option<u64>> {
+        Ok(None)
+    }
+
+    async fn vnodes_disk_storage(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)> {
+        vec![]
+    }
+
+    async fn vnodes_rows(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)> {
+        vec![]
+    }
+
+    async fn close_vnodes_files(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)> {
+        vec![]
+    }
+
+    async fn explain_compaction(&self, vnode_id: VnodeId) -> Vec<CompactionPlan> {
+        vec![]
+    }
+
+    fn compaction_jobs(&self) -> Vec<CompactionJobInfo> {
+        vec![]
+    }
+
+    fn kill_compaction(&self, job_id: u64) -> bool {
+        false
+    }
+
+    fn record_query_latency(&self, latency: Duration) {}
+
+    fn subscribe_writes(
+        &self,
+        tenant: &str,
+        database: &str,
+        start_offset: u64,
+    ) -> TskvResult<WriteSubscription> {
+        SubscriptionHub::new(1, 0).subscribe(&make_owner(tenant, database), start_offset)
+    }
+
     async fn close(&self) {}
 }
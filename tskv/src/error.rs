@@ -417,6 +417,38 @@ pub enum TskvError {
         source: FileSystemError,
     },
 
+    #[error_code(code = 59)]
+    #[snafu(display(
+        "Subscription offset {} of {} is out of range, the retained offsets are [{}, {}]",
+        offset,
+        owner,
+        earliest,
+        next
+    ))]
+    SubscriptionOffsetOutOfRange {
+        owner: String,
+        offset: u64,
+        earliest: u64,
+        next: u64,
+    },
+
+    #[error_code(code = 60)]
+    #[snafu(display(
+        "Subscription of {} lagged behind the writes, resume from offset {}",
+        owner,
+        offset
+    ))]
+    SubscriptionLagged {
+        owner: String,
+        offset: u64,
+    },
+
+    #[error_code(code = 61)]
+    #[snafu(display("Compaction job {} is cancelled", job_id))]
+    CompactionCancelled {
+        job_id: u64,
+    },
+
     #[snafu(display("ModelError: {}", source))]
     #[error_code(code = 89)]
     ModelError {
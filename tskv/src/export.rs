@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use arrow::compute::{cast, filter_record_batch};
+use arrow_array::{
+    new_null_array, Array, ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray,
+};
+use arrow_schema::DataType;
+use datafusion::parquet::arrow::AsyncArrowWriter;
+use models::predicate::domain::TimeRange;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use models::SeriesKey;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+use tokio::io::AsyncWrite;
+use tokio::sync::RwLock;
+use trace::{debug, info};
+
+use crate::error::{ArrowSnafu, CommonSnafu, TskvResult};
+use crate::tsfamily::tseries_family::TseriesFamily;
+use crate::tsm::reader::TsmReader;
+
+const PARQUET_WRITER_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// Export data of a vnode in the time range to parquet files in the object store,
+/// returns the number of exported rows.
+///
+/// Caches of the vnode should be flushed before calling this. Each table of each
+/// tsm file is exported to `{prefix}/{table}/{file_id}.parquet`, points overwritten
+/// in different tsm files are not merged.
+pub(crate) async fn export_vnode(
+    vnode: Arc<RwLock<TseriesFamily>>,
+    time_range: TimeRange,
+    object_store: Arc<dyn ObjectStore>,
+    prefix: Path,
+) -> TskvResult<u64> {
+    let (vnode_id, version) = {
+        let vnode = vnode.read().await;
+        (vnode.tf_id(), vnode.version())
+    };
+
+    let mut total_rows = 0_u64;
+    for level in version.levels_info().iter() {
+        for file in level.files.iter() {
+            if file.is_deleted() || !file.overlap(&time_range) {
+                continue;
+            }
+            let reader = version.get_tsm_reader(file.file_path()).await?;
+            for table in reader.chunk_group().keys() {
+                let location = prefix
+                    .child(table.as_str())
+                    .child(format!("{}.parquet", file.file_id()));
+                let rows =
+                    export_table(&reader, table, &time_range, object_store.clone(), &location)
+                        .await?;
+                debug!("Export vnode {vnode_id}: {rows} rows of table '{table}' to {location}");
+                total_rows += rows;
+            }
+        }
+    }
+
+    info!("Export vnode {vnode_id}: finished, exported {total_rows} rows to {prefix}");
+    Ok(total_rows)
+}
+
+async fn export_table(
+    reader: &TsmReader,
+    table: &str,
+    time_range: &TimeRange,
+    object_store: Arc<dyn ObjectStore>,
+    location: &Path,
+) -> TskvResult<u64> {
+    let table_schema = match reader.table_schema(table) {
+        Some(schema) => schema,
+        None => return Ok(0),
+    };
+    if !reader
+        .chunk()
+        .values()
+        .any(|c| c.table_name() == table && c.time_range().overlaps(time_range))
+    {
+        return Ok(0);
+    }
+
+    let (multipart_id, writer) = object_store
+        .put_multipart(location)
+        .await
+        .map_err(|e| export_error(location, e))?;
+    let mut writer = AsyncArrowWriter::try_new(
+        writer,
+        table_schema.to_arrow_schema(),
+        PARQUET_WRITER_BUFFER_SIZE,
+        None,
+    )
+    .map_err(|e| export_error(location, e))?;
+
+    match write_table(reader, table, &table_schema, time_range, &mut writer).await {
+        Ok(()) => {
+            let meta = writer
+                .close()
+                .await
+                .map_err(|e| export_error(location, e))?;
+            Ok(meta.num_rows as u64)
+        }
+        Err(e) => {
+            let _ = object_store.abort_multipart(location, &multipart_id).await;
+            Err(e)
+        }
+    }
+}
+
+async fn write_table(
+    reader: &TsmReader,
+    table: &str,
+    table_schema: &TskvTableSchemaRef,
+    time_range: &TimeRange,
+    writer: &mut AsyncArrowWriter<Box<dyn AsyncWrite + Unpin + Send>>,
+) -> TskvResult<()> {
+    for chunk in reader.chunk().values() {
+        if chunk.table_name() != table || !chunk.time_range().overlaps(time_range) {
+            continue;
+        }
+        for (column_group_id, column_group) in chunk.column_group() {
+            if !column_group.time_range().overlaps(time_range) {
+                continue;
+            }
+            let batch = reader
+                .read_record_batch(chunk.series_id(), *column_group_id)
+                .await?;
+            let batch = to_table_record_batch(table_schema, chunk.series_key(), batch)?;
+            let batch = filter_by_time_range(table_schema, batch, time_range)?;
+            if batch.num_rows() > 0 {
+                writer
+                    .write(&batch)
+                    .await
+                    .map_err(|e| export_error(table, e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert record batch of a column group to the schema of the table,
+/// tag columns are filled by the series key, missing fields are filled by nulls.
+fn to_table_record_batch(
+    table_schema: &TskvTableSchemaRef,
+    series_key: &SeriesKey,
+    batch: RecordBatch,
+) -> TskvResult<RecordBatch> {
+    let schema = table_schema.to_arrow_schema();
+    let num_rows = batch.num_rows();
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (column, field) in table_schema.columns().iter().zip(schema.fields().iter()) {
+        let array = if column.column_type.is_tag() {
+            let value = series_key
+                .tag_string_val(&column.name)
+                .map_err(|e| export_error(&column.name, e))?;
+            Arc::new(StringArray::from(vec![value; num_rows])) as ArrayRef
+        } else {
+            match batch.column_by_name(&column.name) {
+                Some(array) if array.data_type() == field.data_type() => array.clone(),
+                Some(array) => cast(array, field.data_type()).context(ArrowSnafu)?,
+                None => new_null_array(field.data_type(), num_rows),
+            }
+        };
+        columns.push(array);
+    }
+
+    RecordBatch::try_new(schema, columns).context(ArrowSnafu)
+}
+
+fn filter_by_time_range(
+    table_schema: &TskvTableSchemaRef,
+    batch: RecordBatch,
+    time_range: &TimeRange,
+) -> TskvResult<RecordBatch> {
+    if time_range.is_boundless() {
+        return Ok(batch);
+    }
+    let time_column = table_schema.time_column();
+    let time_column_name = &time_column.name;
+    let time_array = batch
+        .column_by_name(time_column_name)
+        .ok_or_else(|| export_error(time_column_name, "time column not found"))?;
+    let time_array = cast(time_array, &DataType::Int64).context(ArrowSnafu)?;
+    let time_array = time_array
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .ok_or_else(|| export_error(time_column_name, "time column is not int64"))?;
+    let predicate = time_array
+        .iter()
+        .map(|ts| ts.map(|ts| time_range.contains(ts)))
+        .collect::<BooleanArray>();
+
+    filter_record_batch(&batch, &predicate).context(ArrowSnafu)
+}
+
+fn export_error(target: impl std::fmt::Display, e: impl std::fmt::Display) -> crate::TskvError {
+    CommonSnafu {
+        reason: format!("failed to export '{target}': {e}"),
+    }
+    .build()
+}
@@ -1,21 +1,35 @@
 use std::io::{Error, ErrorKind, Result, SeekFrom};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::file_system::file::ReadableFile;
+use crate::file_system::open_files::OpenFileHandle;
 
 //todo: add buffer for read
 pub struct FileStreamReader {
     file: Box<dyn ReadableFile>,
     pos: usize,
     path: PathBuf,
+    _handle: Arc<OpenFileHandle>,
 }
 
 impl FileStreamReader {
     pub fn new(file: Box<dyn ReadableFile>, path_buf: PathBuf) -> Self {
+        let handle = Arc::new(OpenFileHandle::register(path_buf.clone()));
+        Self::new_shared(file, path_buf, handle)
+    }
+
+    /// Create a reader on a file which is already opened by `handle`.
+    pub(crate) fn new_shared(
+        file: Box<dyn ReadableFile>,
+        path_buf: PathBuf,
+        handle: Arc<OpenFileHandle>,
+    ) -> Self {
         Self {
             file,
             pos: 0,
             path: path_buf,
+            _handle: handle,
         }
     }
 
@@ -1,9 +1,11 @@
 use std::io::{Error, ErrorKind, IoSlice, Result, SeekFrom};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::file_system::file::async_file::AsyncFile;
 use crate::file_system::file::stream_reader::FileStreamReader;
 use crate::file_system::file::WritableFile;
+use crate::file_system::open_files::OpenFileHandle;
 
 #[derive(Debug)]
 struct Buffer {
@@ -48,16 +50,19 @@ pub struct FileStreamWriter {
     pos: usize,
     buf: Buffer,
     path: PathBuf,
+    handle: Arc<OpenFileHandle>,
 }
 
 impl FileStreamWriter {
     pub fn new(file: Box<dyn WritableFile>, path_buf: PathBuf, buf_size: usize) -> Self {
         let pos = file.file_size();
+        let handle = Arc::new(OpenFileHandle::register(path_buf.clone()));
         Self {
             file,
             pos,
             buf: Buffer::new(buf_size),
             path: path_buf,
+            handle,
         }
     }
 
@@ -145,9 +150,10 @@ impl FileStreamWriter {
 
     pub fn shared_file(&self) -> Option<Box<FileStreamReader>> {
         self.file.as_any().downcast_ref::<AsyncFile>().map(|file| {
-            Box::new(FileStreamReader::new(
+            Box::new(FileStreamReader::new_shared(
                 Box::new(file.clone()),
                 self.path.clone(),
+                self.handle.clone(),
             ))
         })
     }
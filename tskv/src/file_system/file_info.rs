@@ -18,7 +18,7 @@ pub struct FileInfo {
 
 pub async fn get_files_info(dir: &PathBuf) -> TskvResult<Vec<FileInfo>> {
     let mut infos = vec![];
-    for name in recursive_list_files(dir).iter() {
+    for name in list_files(dir).iter() {
         let info = get_file_info(name).await?;
         infos.push(info);
     }
@@ -51,7 +51,8 @@ pub async fn get_file_info(name: &str) -> TskvResult<FileInfo> {
     })
 }
 
-fn recursive_list_files(dir: impl AsRef<std::path::Path>) -> Vec<String> {
+/// List the files in the directory and its sub directories, sorted by name.
+pub fn list_files(dir: impl AsRef<std::path::Path>) -> Vec<String> {
     let mut list = Vec::new();
     for file_name in walkdir::WalkDir::new(dir)
         .min_depth(1)
@@ -78,9 +79,9 @@ fn recursive_list_files(dir: impl AsRef<std::path::Path>) -> Vec<String> {
 mod test {
     #[tokio::test]
     async fn test_list_filenames() {
-        use crate::file_system::file_info::{get_files_info, recursive_list_files};
+        use crate::file_system::file_info::{get_files_info, list_files};
 
-        let list = recursive_list_files(std::path::PathBuf::from("../common/".to_string()));
+        let list = list_files(std::path::PathBuf::from("../common/".to_string()));
         print!("list_all_filenames: {:#?}", list);
 
         let path = std::path::PathBuf::from("../common/");
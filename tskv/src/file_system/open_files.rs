@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use trace::warn;
+
+/// Warn when the open files of the node reach this percentage of the limit.
+const OPEN_FILES_WARNING_PERCENT: usize = 80;
+
+lazy_static! {
+    /// The number of open files of each directory.
+    static ref OPEN_FILES: DashMap<PathBuf, usize> = DashMap::new();
+    static ref OPEN_FILES_LIMIT: Option<usize> = max_open_files();
+}
+static OPEN_FILES_NUM: AtomicUsize = AtomicUsize::new(0);
+static OPEN_FILES_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// A file opened by the file system, it's registered in the node-wide table of
+/// open files until dropped.
+#[derive(Debug)]
+pub struct OpenFileHandle {
+    dir: PathBuf,
+}
+
+impl OpenFileHandle {
+    pub fn register(path: PathBuf) -> Self {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or(path);
+        *OPEN_FILES.entry(dir.clone()).or_default() += 1;
+        let num = OPEN_FILES_NUM.fetch_add(1, Ordering::Relaxed) + 1;
+        check_open_files_limit(num);
+        Self { dir }
+    }
+}
+
+impl Drop for OpenFileHandle {
+    fn drop(&mut self) {
+        OPEN_FILES.remove_if_mut(&self.dir, |_, n| {
+            *n -= 1;
+            *n == 0
+        });
+        let num = OPEN_FILES_NUM.fetch_sub(1, Ordering::Relaxed) - 1;
+        check_open_files_limit(num);
+    }
+}
+
+/// Get the number of files opened by the file system on this node.
+pub fn open_files_num() -> usize {
+    OPEN_FILES_NUM.load(Ordering::Relaxed)
+}
+
+/// Get the number of files opened by the file system under the directory.
+pub fn open_files_num_under(dir: impl AsRef<Path>) -> usize {
+    let dir = dir.as_ref();
+    OPEN_FILES
+        .iter()
+        .filter(|entry| entry.key().starts_with(dir))
+        .map(|entry| *entry.value())
+        .sum()
+}
+
+/// Get the limit of open files of the process, None if it's unknown.
+pub fn open_files_limit() -> Option<usize> {
+    *OPEN_FILES_LIMIT
+}
+
+/// Logs a warning once when the open files reach the warning threshold,
+/// and again after it falls below the threshold and reaches it again.
+fn check_open_files_limit(num: usize) {
+    let Some(limit) = open_files_limit() else {
+        return;
+    };
+    let threshold = limit * OPEN_FILES_WARNING_PERCENT / 100;
+    if num >= threshold {
+        if !OPEN_FILES_WARNED.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Open files of the node is nearing the limit: {num}/{limit}, \
+                idle files of vnodes can be closed by 'CLOSE VNODE FILES'"
+            );
+        }
+    } else {
+        OPEN_FILES_WARNED.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(unix)]
+fn max_open_files() -> Option<usize> {
+    let mut rlimit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut rlimit) } != 0 {
+        return None;
+    }
+    if rlimit.rlim_cur == libc::RLIM_INFINITY {
+        return None;
+    }
+    Some(rlimit.rlim_cur as usize)
+}
+
+#[cfg(not(unix))]
+fn max_open_files() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{open_files_num_under, OpenFileHandle};
+
+    #[test]
+    fn test_open_files_num_under() {
+        let dir = PathBuf::from("/tmp/test/file_system/open_files");
+        let h1 = OpenFileHandle::register(dir.join("1").join("a.tsm"));
+        let h2 = OpenFileHandle::register(dir.join("1").join("a.tsm"));
+        let h3 = OpenFileHandle::register(dir.join("2").join("b.tsm"));
+        assert_eq!(open_files_num_under(&dir), 3);
+        assert_eq!(open_files_num_under(dir.join("1")), 2);
+
+        drop(h1);
+        assert_eq!(open_files_num_under(dir.join("1")), 1);
+        drop(h2);
+        drop(h3);
+        assert_eq!(open_files_num_under(&dir), 0);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, StringArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::RecordBatch;
+use models::SeriesKey;
+use serde::{Deserialize, Serialize};
+use trace::warn;
+
+/// Number of leading characters of a tag value used to group series.
+const VALUE_PREFIX_LEN: usize = 8;
+/// Maximum number of (table, tag key, value prefix) tracked by a watcher,
+/// the prefixes which created the fewest series are evicted beyond it.
+const MAX_TRACKED_PREFIXES: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CardinalityKey {
+    pub table: String,
+    pub tag_key: String,
+    pub value_prefix: String,
+}
+
+/// Tracks the creation of new series of a vnode, the number of new series of
+/// each table in the current window is used to alert the creation rate, and
+/// the number of series created by each tag value prefix is used to find the
+/// tag values generating the most series.
+#[derive(Debug, Default)]
+pub struct CardinalityWatcher {
+    window_series: HashMap<String, u64>,
+    prefix_series: HashMap<CardinalityKey, u64>,
+    /// Number of the prefixes evicted since the watcher was created.
+    evicted_prefixes: u64,
+}
+
+impl CardinalityWatcher {
+    /// Restore the series count of the prefixes encoded by `encode_prefix_series`.
+    pub fn with_prefix_series(data: &[u8]) -> bincode::Result<Self> {
+        let items: Vec<(CardinalityKey, u64)> = bincode::deserialize(data)?;
+        Ok(Self {
+            prefix_series: items.into_iter().collect(),
+            ..Default::default()
+        })
+    }
+
+    pub fn encode_prefix_series(&self) -> bincode::Result<Vec<u8>> {
+        let items: Vec<(&CardinalityKey, &u64)> = self.prefix_series.iter().collect();
+        bincode::serialize(&items)
+    }
+
+    pub fn record_new_series(&mut self, series_key: &SeriesKey) {
+        *self
+            .window_series
+            .entry(series_key.table().clone())
+            .or_default() += 1;
+
+        for tag in series_key.tags() {
+            let key = CardinalityKey {
+                table: series_key.table().clone(),
+                tag_key: String::from_utf8_lossy(&tag.key).to_string(),
+                value_prefix: String::from_utf8_lossy(&tag.value)
+                    .chars()
+                    .take(VALUE_PREFIX_LEN)
+                    .collect(),
+            };
+            if let Some(count) = self.prefix_series.get_mut(&key) {
+                *count += 1;
+            } else {
+                if self.prefix_series.len() >= MAX_TRACKED_PREFIXES {
+                    self.evict_prefixes();
+                }
+                self.prefix_series.insert(key, 1);
+            }
+        }
+    }
+
+    /// Evict the half of the prefixes which created the fewest series, so the
+    /// new prefixes can still be tracked.
+    fn evict_prefixes(&mut self) {
+        let mut counts: Vec<u64> = self.prefix_series.values().copied().collect();
+        let (_, &mut threshold, _) = counts.select_nth_unstable(MAX_TRACKED_PREFIXES / 2);
+        let before = self.prefix_series.len();
+        self.prefix_series.retain(|_, count| *count > threshold);
+        let evicted = before - self.prefix_series.len();
+        self.evicted_prefixes += evicted as u64;
+        warn!(
+            "Series cardinality watcher tracks over {MAX_TRACKED_PREFIXES} tag value prefixes, \
+             evicted {evicted} prefixes which created no more than {threshold} series"
+        );
+    }
+
+    pub fn evicted_prefixes(&self) -> u64 {
+        self.evicted_prefixes
+    }
+
+    /// The number of series created by each tag value prefix.
+    pub fn prefix_series(&self) -> impl Iterator<Item = (&CardinalityKey, u64)> {
+        self.prefix_series.iter().map(|(key, count)| (key, *count))
+    }
+
+    /// Returns the number of new series of each table since the last call.
+    pub fn take_window_series(&mut self) -> HashMap<String, u64> {
+        std::mem::take(&mut self.window_series)
+    }
+
+    /// Returns the `k` tag value prefixes which created the most series.
+    pub fn top_k(&self, k: usize) -> Vec<(CardinalityKey, u64)> {
+        let mut items: Vec<(CardinalityKey, u64)> = self
+            .prefix_series
+            .iter()
+            .map(|(key, count)| (key.clone(), *count))
+            .collect();
+        sort_and_truncate(&mut items, k);
+        items
+    }
+}
+
+/// Sort items by series count in descending order, then keep the first `k`.
+pub fn sort_and_truncate(items: &mut Vec<(CardinalityKey, u64)>, k: usize) {
+    items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    items.truncate(k);
+}
+
+pub fn cardinality_top_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("table", DataType::Utf8, false),
+        Field::new("tag_key", DataType::Utf8, false),
+        Field::new("value_prefix", DataType::Utf8, false),
+        Field::new("series_count", DataType::UInt64, false),
+    ]))
+}
+
+/// Build RecordBatch with columns of table, tag_key, value_prefix and series_count, for example:
+///
+/// | table | tag_key | value_prefix | series_count |
+/// | ----- | ------- | ------------ | ------------ |
+/// | cpu   | host    | server-1     | 1024         |
+pub fn cardinality_top_record_batch(
+    items: &[(CardinalityKey, u64)],
+) -> Result<RecordBatch, ArrowError> {
+    let tables = StringArray::from_iter_values(items.iter().map(|(k, _)| k.table.as_str()));
+    let tag_keys = StringArray::from_iter_values(items.iter().map(|(k, _)| k.tag_key.as_str()));
+    let prefixes =
+        StringArray::from_iter_values(items.iter().map(|(k, _)| k.value_prefix.as_str()));
+    let counts = UInt64Array::from_iter_values(items.iter().map(|(_, c)| *c));
+    RecordBatch::try_new(
+        cardinality_top_schema(),
+        vec![
+            Arc::new(tables),
+            Arc::new(tag_keys),
+            Arc::new(prefixes),
+            Arc::new(counts),
+        ],
+    )
+}
+
+/// Merge RecordBatches built by `cardinality_top_record_batch` by summing the series
+/// count of the same key, then keep the first `k`.
+pub fn merge_cardinality_top_record_batches(
+    batches: &[RecordBatch],
+    k: usize,
+) -> Result<RecordBatch, ArrowError> {
+    fn column<'a, T: 'static>(batch: &'a RecordBatch, i: usize) -> Result<&'a T, ArrowError> {
+        batch
+            .columns()
+            .get(i)
+            .and_then(|c| c.as_any().downcast_ref::<T>())
+            .ok_or_else(|| {
+                ArrowError::SchemaError(format!("invalid column {i} of cardinality record batch"))
+            })
+    }
+
+    let mut series: HashMap<CardinalityKey, u64> = HashMap::new();
+    for batch in batches {
+        let tables = column::<StringArray>(batch, 0)?;
+        let tag_keys = column::<StringArray>(batch, 1)?;
+        let prefixes = column::<StringArray>(batch, 2)?;
+        let counts = column::<UInt64Array>(batch, 3)?;
+        for i in 0..batch.num_rows() {
+            let key = CardinalityKey {
+                table: tables.value(i).to_string(),
+                tag_key: tag_keys.value(i).to_string(),
+                value_prefix: prefixes.value(i).to_string(),
+            };
+            *series.entry(key).or_default() += counts.value(i);
+        }
+    }
+
+    let mut items = series.into_iter().collect();
+    sort_and_truncate(&mut items, k);
+    cardinality_top_record_batch(&items)
+}
+
+#[cfg(test)]
+mod test {
+    use models::{SeriesKey, Tag};
+
+    use super::{
+        cardinality_top_record_batch, merge_cardinality_top_record_batches, CardinalityWatcher,
+        MAX_TRACKED_PREFIXES,
+    };
+
+    fn series_key(table: &str, host: &str) -> SeriesKey {
+        SeriesKey {
+            tags: vec![Tag::new(b"host".to_vec(), host.as_bytes().to_vec())],
+            table: table.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cardinality_watcher() {
+        let mut watcher = CardinalityWatcher::default();
+        for i in 0..10 {
+            watcher.record_new_series(&series_key("cpu", &format!("request-{i}")));
+        }
+        watcher.record_new_series(&series_key("mem", "server-a"));
+
+        let window = watcher.take_window_series();
+        assert_eq!(window.get("cpu"), Some(&10));
+        assert_eq!(window.get("mem"), Some(&1));
+        assert!(watcher.take_window_series().is_empty());
+
+        let top = watcher.top_k(1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].0.table, "cpu");
+        assert_eq!(top[0].0.tag_key, "host");
+        assert_eq!(top[0].0.value_prefix, "request-");
+        assert_eq!(top[0].1, 10);
+
+        let batch = cardinality_top_record_batch(&watcher.top_k(10)).unwrap();
+        let merged = merge_cardinality_top_record_batches(&[batch.clone(), batch], 1).unwrap();
+        assert_eq!(merged.num_rows(), 1);
+        assert_eq!(merged.schema(), super::cardinality_top_schema());
+    }
+
+    #[test]
+    fn test_evict_prefixes() {
+        let mut watcher = CardinalityWatcher::default();
+        for _ in 0..3 {
+            watcher.record_new_series(&series_key("cpu", "runaway"));
+        }
+        for i in 1..MAX_TRACKED_PREFIXES {
+            watcher.record_new_series(&series_key("cpu", &format!("{i:08}")));
+        }
+        assert_eq!(watcher.prefix_series().count(), MAX_TRACKED_PREFIXES);
+        assert_eq!(watcher.evicted_prefixes(), 0);
+
+        // The new prefix is tracked in place of the ones with the fewest series.
+        watcher.record_new_series(&series_key("cpu", "new-host"));
+        assert_eq!(watcher.evicted_prefixes(), MAX_TRACKED_PREFIXES as u64 - 1);
+        let top = watcher.top_k(10);
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].0.value_prefix.as_str(), top[0].1), ("runaway", 3));
+        assert_eq!((top[1].0.value_prefix.as_str(), top[1].1), ("new-host", 1));
+    }
+
+    #[test]
+    fn test_encode_prefix_series() {
+        let mut watcher = CardinalityWatcher::default();
+        watcher.record_new_series(&series_key("cpu", "server-a"));
+        watcher.record_new_series(&series_key("cpu", "server-b"));
+        watcher.record_new_series(&series_key("mem", "server-a"));
+
+        let data = watcher.encode_prefix_series().unwrap();
+        let mut restored = CardinalityWatcher::with_prefix_series(&data).unwrap();
+        assert_eq!(restored.top_k(10), watcher.top_k(10));
+        assert!(restored.take_window_series().is_empty());
+
+        assert!(CardinalityWatcher::with_prefix_series(b"invalid").is_err());
+    }
+}
@@ -11,9 +11,10 @@ use models::schema::tskv_table_schema::TskvTableSchema;
 use models::{tag, SeriesId, SeriesKey, Tag, TagKey, TagValue};
 use snafu::{OptionExt, ResultExt};
 use tokio::sync::RwLock;
-use trace::info;
+use trace::{info, warn};
 
 use super::cache::IndexCache;
+use super::cardinality::CardinalityWatcher;
 use super::engine2::IndexEngine2;
 use super::{DecodeSeriesKeySnafu, IndexResult};
 use crate::error::{ColumnNotFoundSnafu, IndexErrSnafu};
@@ -24,6 +25,7 @@ const SERIES_ID_PREFIX: &str = "_id_";
 const SERIES_KEY_PREFIX: &str = "_key_";
 const TOMBSTONE_PREFIX: &str = "_tomb_";
 const AUTO_INCR_ID_KEY: &str = "_auto_incr_id";
+const CARDINALITY_KEY: &str = "_cardinality";
 
 /// Used to maintain forward and inverted indexes
 ///
@@ -87,6 +89,7 @@ pub struct TSIndex {
 
     cache: IndexCache,
     storage: IndexEngine2,
+    cardinality: CardinalityWatcher,
 }
 
 impl TSIndex {
@@ -111,11 +114,23 @@ impl TSIndex {
             None => 0,
         };
 
+        let cardinality = match storage.get(CARDINALITY_KEY.as_bytes())? {
+            Some(data) => CardinalityWatcher::with_prefix_series(&data).unwrap_or_else(|e| {
+                warn!(
+                    "Decode series cardinality of index '{:?}' failed: {}",
+                    path, e
+                );
+                CardinalityWatcher::default()
+            }),
+            None => CardinalityWatcher::default(),
+        };
+
         let ts_index = Self {
             storage,
             incr_id: AtomicU32::new(incr_id),
             write_count: AtomicU32::new(0),
             cache: IndexCache::new(cap as usize),
+            cardinality,
         };
 
         trace::info!(
@@ -167,6 +182,7 @@ impl TSIndex {
 
             // write index memcache
             trace::debug!("Index add new series id:{}, key: {}", id, series_key);
+            self.cardinality.record_new_series(&series_key);
             self.cache.write(id, series_key);
 
             let _ = self.check_to_flush(false).await;
@@ -175,6 +191,14 @@ impl TSIndex {
         Ok(ids)
     }
 
+    pub fn cardinality(&self) -> &CardinalityWatcher {
+        &self.cardinality
+    }
+
+    pub fn cardinality_mut(&mut self) -> &mut CardinalityWatcher {
+        &mut self.cardinality
+    }
+
     pub async fn get_series_id(&self, series_key: &SeriesKey) -> IndexResult<Option<u32>> {
         if let Some(id) = self.cache.get_series_id_by_key(series_key) {
             return Ok(Some(id));
@@ -300,6 +324,10 @@ impl TSIndex {
         let id_bytes = self.incr_id.load(Ordering::Relaxed).to_be_bytes();
         self.storage.set(AUTO_INCR_ID_KEY.as_bytes(), &id_bytes)?;
         self.cache.write_cache.flush(&self.storage).await?;
+        match self.cardinality.encode_prefix_series() {
+            Ok(data) => self.storage.set(CARDINALITY_KEY.as_bytes(), &data)?,
+            Err(e) => warn!("Encode series cardinality failed: {}", e),
+        }
 
         self.write_count.store(0, Ordering::Relaxed);
 
@@ -313,6 +341,13 @@ impl TSIndex {
         Ok(rb.iter().collect())
     }
 
+    /// The number of the series of the table.
+    pub async fn series_count(&self, tab: &str) -> IndexResult<u64> {
+        let rb = self.get_series_id_bitmap(tab, &[]).await?;
+
+        Ok(rb.len())
+    }
+
     async fn get_series_id_bitmap(
         &self,
         tab: &str,
@@ -841,6 +876,35 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_persist_cardinality() {
+        let dir = "/tmp/test/ts_index/cardinality";
+        let _ = std::fs::remove_dir_all(dir);
+
+        #[rustfmt::skip]
+        let series_keys_desc: Vec<SeriesKeyDesc> = vec![
+            (0, "db_test", "cpu", vec![("host", "server-a")]),
+            (0, "db_test", "cpu", vec![("host", "server-b")]),
+        ];
+        {
+            let ts_index = TSIndex::new(dir, 10000).await.unwrap();
+            let mut ts_index = ts_index.write().await;
+            ts_index
+                .add_series_if_not_exists(build_series_keys(&series_keys_desc))
+                .await
+                .unwrap();
+            ts_index.flush().await.unwrap();
+        }
+
+        let ts_index = TSIndex::new(dir, 10000).await.unwrap();
+        let ts_index = ts_index.read().await;
+        let top = ts_index.cardinality().top_k(10);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].0.table, "cpu");
+        assert_eq!(top[0].0.value_prefix, "server-");
+        assert_eq!(top[0].1, 2);
+    }
+
     #[test]
     fn test_serde() {
         let schema = Schema::new(vec![
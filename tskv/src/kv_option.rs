@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -6,6 +6,10 @@ use std::time::Duration;
 use config::tskv::Config;
 use models::codec::Encoding;
 use models::meta_data::{NodeId, VnodeId};
+use models::schema::database_schema::WalSyncPolicy;
+
+use crate::error::{InvalidFileNameSnafu, TskvError, TskvResult};
+use crate::tiering;
 
 const SUMMARY_PATH: &str = "summary";
 pub const INDEX_PATH: &str = "index";
@@ -41,12 +45,26 @@ pub struct StorageOptions {
     pub compact_trigger_file_num: u32,
     pub compact_trigger_cold_duration: Duration,
     pub max_compact_size: u64,
+    pub compact_small_file_size: u64,
+    pub compact_small_file_watermarks: Vec<u32>,
+    pub compact_small_file_cooldown: Duration,
     pub max_concurrent_compaction: u16,
+    pub compaction_io_rate_limit: u64,
+    pub compaction_throttle_query_latency: Duration,
     pub collect_compaction_metrics: bool,
     pub snapshot_holding_time: i64,
     pub max_datablock_size: u64,
     pub index_cache_capacity: u64,
     pub tsm_meta_compress: Encoding,
+    pub zstd_compress_level: i32,
+    pub write_dedup_window: Duration,
+    pub write_dedup_bloom_filter_bits: u64,
+    pub warmup_recent_duration: Duration,
+    pub warmup_memory_budget: u64,
+    pub series_creation_alert_threshold: u64,
+    pub enable_write_subscription: bool,
+    pub subscription_retained_entries: usize,
+    pub tiering_age: Duration,
 }
 
 // database/data/ts_family_id/tsm
@@ -85,6 +103,57 @@ impl StorageOptions {
     pub fn delta_dir(&self, owner: &str, ts_family_id: VnodeId) -> PathBuf {
         self.ts_family_dir(owner, ts_family_id).join(DELTA_PATH)
     }
+
+    /// The path of a file of a vnode requested by the other nodes, `filename` is
+    /// relative to the storage path, like `data/{owner}/{vnode_id}/tsm/_000001.tsm`.
+    /// The files out of the vnode directories are rejected, including the ones
+    /// reached by `..` or symbolic links.
+    pub fn vnode_file_path(&self, filename: &str) -> TskvResult<PathBuf> {
+        let invalid = |message: &str| -> TskvError {
+            InvalidFileNameSnafu {
+                file_name: filename.to_string(),
+                message: message.to_string(),
+            }
+            .build()
+        };
+
+        let components = Path::new(filename)
+            .components()
+            .map(|c| match c {
+                Component::Normal(c) => c.to_str().ok_or_else(|| invalid("not utf-8")),
+                _ => Err(invalid("not a relative path without '..'")),
+            })
+            .collect::<TskvResult<Vec<_>>>()?;
+        let [DATA_PATH, owner, vnode_id, file @ ..] = components.as_slice() else {
+            return Err(invalid("not a file of a vnode"));
+        };
+        let vnode_id = vnode_id
+            .parse::<VnodeId>()
+            .map_err(|_| invalid("not a file of a vnode"))?;
+        if file.is_empty() {
+            return Err(invalid("not a file of a vnode"));
+        }
+
+        // The symbolic links may point out of the vnode directory.
+        let vnode_dir = self
+            .ts_family_dir(owner, vnode_id)
+            .canonicalize()
+            .map_err(|e| invalid(&e.to_string()))?;
+        let path = vnode_dir.join(file.join("/"));
+        // The tsm files moved to the tiered storage exist by their stubs only.
+        let path = match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) if tiering::is_tiered(&path) && !path.exists() => dir
+                .canonicalize()
+                .map(|dir| dir.join(name))
+                .map_err(|e| invalid(&e.to_string()))?,
+            _ => path.canonicalize().map_err(|e| invalid(&e.to_string()))?,
+        };
+        if !path.starts_with(&vnode_dir) {
+            return Err(invalid("out of the vnode directory"));
+        }
+
+        Ok(path)
+    }
 }
 
 impl From<&Config> for StorageOptions {
@@ -105,12 +174,26 @@ impl From<&Config> for StorageOptions {
             compact_trigger_file_num: config.storage.compact_trigger_file_num,
             compact_trigger_cold_duration: config.storage.compact_trigger_cold_duration,
             max_compact_size: config.storage.max_compact_size,
+            compact_small_file_size: config.storage.compact_small_file_size,
+            compact_small_file_watermarks: config.storage.compact_small_file_watermarks.clone(),
+            compact_small_file_cooldown: config.storage.compact_small_file_cooldown,
             max_concurrent_compaction: config.storage.max_concurrent_compaction,
+            compaction_io_rate_limit: config.storage.compaction_io_rate_limit,
+            compaction_throttle_query_latency: config.storage.compaction_throttle_query_latency,
             collect_compaction_metrics: config.storage.collect_compaction_metrics,
             snapshot_holding_time: config.cluster.snapshot_holding_time.as_secs() as i64,
             max_datablock_size: config.storage.max_datablock_size,
             index_cache_capacity: config.storage.index_cache_capacity,
             tsm_meta_compress,
+            zstd_compress_level: config.storage.zstd_compress_level,
+            write_dedup_window: config.storage.write_dedup_window,
+            write_dedup_bloom_filter_bits: config.storage.write_dedup_bloom_filter_bits,
+            warmup_recent_duration: config.storage.warmup_recent_duration,
+            warmup_memory_budget: config.storage.warmup_memory_budget,
+            series_creation_alert_threshold: config.storage.series_creation_alert_threshold,
+            enable_write_subscription: config.storage.enable_write_subscription,
+            subscription_retained_entries: config.storage.subscription_retained_entries,
+            tiering_age: config.storage.tiering_age,
         }
     }
 }
@@ -125,6 +208,7 @@ pub struct QueryOptions {
     pub write_timeout: Duration,
     pub stream_trigger_cpu: usize,
     pub stream_executor_cpu: usize,
+    pub max_query_memory: u64,
 }
 
 impl From<&Config> for QueryOptions {
@@ -138,6 +222,7 @@ impl From<&Config> for QueryOptions {
             write_timeout: config.query.write_timeout,
             stream_trigger_cpu: config.query.stream_trigger_cpu,
             stream_executor_cpu: config.query.stream_executor_cpu,
+            max_query_memory: config.query.max_query_memory,
         }
     }
 }
@@ -147,7 +232,7 @@ pub struct WalOptions {
     pub path: PathBuf,
     pub wal_max_file_size: u64,
     pub compress: Encoding,
-    pub wal_sync: bool,
+    pub wal_sync: WalSyncPolicy,
 }
 
 impl From<&Config> for WalOptions {
@@ -162,7 +247,7 @@ impl From<&Config> for WalOptions {
             path: PathBuf::from(config.wal.path.clone()),
             wal_max_file_size: config.wal.max_file_size,
             compress,
-            wal_sync: config.wal.sync,
+            wal_sync: WalSyncPolicy::from(config.wal.sync),
         }
     }
 }
@@ -173,3 +258,54 @@ impl WalOptions {
         self.path.join(owner).join(vnode_id.to_string())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::StorageOptions;
+
+    #[test]
+    fn test_vnode_file_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config::tskv::get_config_for_test();
+        config.storage.path = dir.path().to_string_lossy().to_string();
+        let opt = StorageOptions::from(&config);
+
+        let tsm_dir = opt.tsm_dir("cnosdb.public", 3);
+        std::fs::create_dir_all(&tsm_dir).unwrap();
+        std::fs::write(tsm_dir.join("_000001.tsm"), b"tsm").unwrap();
+        std::fs::write(opt.summary_dir().with_extension("secret"), b"secret").unwrap();
+
+        let path = opt
+            .vnode_file_path("data/cnosdb.public/3/tsm/_000001.tsm")
+            .unwrap();
+        assert_eq!(path, tsm_dir.canonicalize().unwrap().join("_000001.tsm"));
+        // A tsm file moved to the tiered storage.
+        std::fs::write(tsm_dir.join("_000004.remote"), b"stub").unwrap();
+        let path = opt
+            .vnode_file_path("data/cnosdb.public/3/tsm/_000004.tsm")
+            .unwrap();
+        assert_eq!(path, tsm_dir.canonicalize().unwrap().join("_000004.tsm"));
+
+        for filename in [
+            "data/cnosdb.public/3/../../../summary.secret",
+            "data/cnosdb.public/3/tsm/../../../../summary.secret",
+            "/etc/passwd",
+            "summary.secret",
+            "data/cnosdb.public/x/tsm/_000001.tsm",
+            "data/cnosdb.public/3",
+            "data/cnosdb.public/3/tsm/_000002.tsm",
+        ] {
+            assert!(opt.vnode_file_path(filename).is_err(), "{filename}");
+        }
+
+        // A symbolic link out of the vnode directory.
+        #[cfg(unix)]
+        {
+            let link = tsm_dir.join("_000003.tsm");
+            std::os::unix::fs::symlink(opt.summary_dir().with_extension("secret"), &link).unwrap();
+            assert!(opt
+                .vnode_file_path("data/cnosdb.public/3/tsm/_000003.tsm")
+                .is_err());
+        }
+    }
+}
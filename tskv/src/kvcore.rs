@@ -1,43 +1,64 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use cache::AsyncCache;
 use datafusion::arrow::record_batch::RecordBatch;
 use memory_pool::{MemoryPool, MemoryPoolRef};
 use meta::error::MetaError;
 use meta::model::MetaRef;
+use metrics::count::U64Counter;
+use metrics::duration::{DurationHistogram, DurationHistogramOptions};
 use metrics::metric_register::MetricsRegister;
 use models::meta_data::VnodeId;
-use models::predicate::domain::ColumnDomains;
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRange};
 use models::schema::database_schema::{make_owner, split_owner};
 use models::{SeriesId, SeriesKey};
-use snafu::ResultExt;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
+use snafu::{OptionExt, ResultExt};
 use tokio::runtime::Runtime;
 use tokio::sync::broadcast::{self, Sender as BroadcastSender};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{oneshot, RwLock};
 use trace::{debug, error, info, warn};
 
-use crate::compaction::job::CompactJob;
+use crate::compaction::job::{CompactJob, CompactionJobInfo};
 use crate::compaction::metrics::{CompactionType, VnodeCompactionMetrics};
-use crate::compaction::{self, check, pick_compaction, CompactTask};
+use crate::compaction::throttle::latency_p99;
+use crate::compaction::{self, check, pick_compaction, CompactTask, SmallFileCompactionPicker};
 use crate::database::Database;
-use crate::error::{IndexErrSnafu, MetaSnafu, TskvResult};
+use crate::error::{
+    ArrowSnafu, CommonSnafu, IndexErrSnafu, MetaSnafu, TskvResult, VnodeNotFoundSnafu,
+};
 use crate::file_system::async_filesystem::LocalFileSystem;
 use crate::file_system::FileSystem;
+use crate::index::cardinality::{self, CardinalityKey};
 use crate::index::IndexResult;
 use crate::kv_option::{Options, StorageOptions};
+use crate::scan_cost::{self, ScanCost};
+use crate::subscription::{self, SubscriptionHub, WriteSubscription};
 use crate::summary::{Summary, SummaryTask};
+use crate::tiering::TieredStorage;
 use crate::tsfamily::super_version::SuperVersion;
 use crate::tsfamily::tseries_family::TseriesFamily;
+use crate::tsm::codec;
 use crate::version_set::VersionSet;
 use crate::vnode_store::VnodeStorage;
-use crate::{file_utils, Engine, TsKvContext};
+use crate::warmup::{self, WarmupBudget};
+use crate::{backup, export, file_utils, Engine, TsKvContext, VnodeBackup};
 
 // TODO: A small summay channel capacity can cause a block
 pub const COMPACT_REQ_CHANNEL_CAP: usize = 1024;
 pub const SUMMARY_REQ_CHANNEL_CAP: usize = 1024;
+/// Interval of checking the number of small files of each vnode.
+const SMALL_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Interval of checking the new series creation rate of each vnode.
+const CARDINALITY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Interval of moving the cold files of each vnode to the tiered storage.
+const TIERING_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Interval of adjusting the IO rate of compactions by the latency of queries.
+const COMPACTION_THROTTLE_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct TsKv {
     ctx: Arc<TsKvContext>,
@@ -46,8 +67,10 @@ pub struct TsKv {
     runtime: Arc<Runtime>,
     vnodes: Arc<RwLock<HashMap<VnodeId, VnodeStorage>>>,
     metrics: Arc<MetricsRegister>,
+    query_latency: DurationHistogram,
     _memory_pool: Arc<dyn MemoryPool>,
     close_sender: BroadcastSender<Sender<()>>,
+    warmup_budget: Arc<WarmupBudget>,
 }
 
 impl TsKv {
@@ -61,6 +84,7 @@ impl TsKv {
         let (compact_task_sender, compact_task_receiver) = mpsc::channel(COMPACT_REQ_CHANNEL_CAP);
         let (summary_task_sender, summary_task_receiver) = mpsc::channel(SUMMARY_REQ_CHANNEL_CAP);
 
+        codec::set_zstd_compress_level(options.storage.zstd_compress_level);
         let shared_options = Arc::new(options);
         let (version_set, summary) = Self::recover_summary(
             runtime.clone(),
@@ -71,6 +95,14 @@ impl TsKv {
         )
         .await;
 
+        let subscriptions = if shared_options.storage.enable_write_subscription {
+            let epoch = subscription::next_epoch(&shared_options.storage.path)?;
+            SubscriptionHub::new(shared_options.storage.subscription_retained_entries, epoch)
+        } else {
+            SubscriptionHub::new(0, 0)
+        };
+        let subscriptions = Arc::new(subscriptions);
+
         let ctx = Arc::new(TsKvContext {
             version_set,
             compact_task_sender,
@@ -78,9 +110,24 @@ impl TsKv {
             runtime: runtime.clone(),
             options: shared_options.clone(),
             global_ctx: summary.global_context(),
+            subscriptions,
         });
 
         let (close_sender, _close_receiver) = broadcast::channel(1);
+        let warmup_budget = Arc::new(WarmupBudget::new(
+            shared_options.storage.warmup_memory_budget,
+        ));
+        ctx.global_ctx
+            .compaction_throttle()
+            .set_max_rate(shared_options.storage.compaction_io_rate_limit);
+        let node_id = shared_options.storage.node_id.to_string();
+        let query_latency = metrics
+            .register_metric::<DurationHistogram>(
+                "tskv_query_latency",
+                "latency of the table scans of vnodes",
+                DurationHistogramOptions::default(),
+            )
+            .recorder([("node_id", node_id.as_str())]);
         let compact_job = CompactJob::new(runtime.clone(), ctx.clone(), metrics.clone());
         let core = Self {
             ctx,
@@ -89,12 +136,18 @@ impl TsKv {
             compact_job,
             close_sender,
             metrics,
+            query_latency,
             runtime,
             vnodes: Default::default(),
+            warmup_budget,
         };
 
         core.run_summary_job(summary, summary_task_receiver);
         core.run_flush_cold_vnode_job();
+        core.run_small_file_compaction_job();
+        core.run_cardinality_check_job();
+        core.run_tiering_job();
+        core.run_compaction_throttle_job();
         core.compact_job
             .start_merge_compact_task_job(compact_task_receiver)
             .await;
@@ -210,6 +263,165 @@ impl TsKv {
         });
     }
 
+    /// Checks the number of small files of each vnode periodically, sends a
+    /// `CompactTask::SmallFile` if any level reaches its watermark. A vnode is
+    /// not triggered again until the cooldown elapsed.
+    fn run_small_file_compaction_job(&self) {
+        let tskv_ctx = self.ctx.clone();
+        let storage_opt = tskv_ctx.options.storage.clone();
+        if storage_opt
+            .compact_small_file_watermarks
+            .iter()
+            .all(|w| *w == 0)
+        {
+            return;
+        }
+        let compact_task_sender = tskv_ctx.compact_task_sender.clone();
+
+        self.runtime.spawn(async move {
+            let mut last_triggered: HashMap<VnodeId, Instant> = HashMap::new();
+            let mut check_interval = tokio::time::interval(SMALL_FILE_CHECK_INTERVAL);
+            loop {
+                check_interval.tick().await;
+
+                let dbs = tskv_ctx.version_set.read().await.get_all_db().clone();
+                for (_, db) in dbs {
+                    let ts_families = db.read().await.ts_families().clone();
+                    for (tf_id, ts_family) in ts_families {
+                        if let Some(instant) = last_triggered.get(&tf_id) {
+                            if instant.elapsed() < storage_opt.compact_small_file_cooldown {
+                                continue;
+                            }
+                        }
+                        let version = ts_family.read().await.super_version().version.clone();
+                        let level = SmallFileCompactionPicker::pick_level(
+                            version.levels_info(),
+                            storage_opt.compact_small_file_size,
+                            &storage_opt.compact_small_file_watermarks,
+                        );
+                        if let Some(level) = level {
+                            let task = CompactTask::SmallFile(tf_id);
+                            debug!("Scheduler(vnode: {tf_id}): small files of level-{level} reached watermark");
+                            if let Err(e) = compact_task_sender.send(task).await {
+                                warn!("Scheduler(vnode: {tf_id}): Failed to send compact task: {task}: {e}");
+                            }
+                            last_triggered.insert(tf_id, Instant::now());
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Checks the number of new series of each table of each vnode created in
+    /// the last interval, warns and records an alert if it exceeds the threshold.
+    fn run_cardinality_check_job(&self) {
+        let tskv_ctx = self.ctx.clone();
+        let threshold = tskv_ctx.options.storage.series_creation_alert_threshold;
+        if threshold == 0 {
+            return;
+        }
+        let alerts = self.metrics.metric::<U64Counter>(
+            "series_cardinality_alerts",
+            "new series creation rate of table over threshold",
+        );
+
+        self.runtime.spawn(async move {
+            let mut check_interval = tokio::time::interval(CARDINALITY_CHECK_INTERVAL);
+            loop {
+                check_interval.tick().await;
+
+                let dbs = tskv_ctx.version_set.read().await.get_all_db().clone();
+                for (owner, db) in dbs {
+                    let (tenant, db_name) = split_owner(&owner);
+                    let ts_indexes = db.read().await.ts_indexes();
+                    for (vnode_id, ts_index) in ts_indexes {
+                        let window_series =
+                            ts_index.write().await.cardinality_mut().take_window_series();
+                        for (table, count) in window_series {
+                            if count <= threshold {
+                                continue;
+                            }
+                            warn!(
+                                "Series cardinality alert: table {owner}.{table} of vnode {vnode_id} created {count} new series in {CARDINALITY_CHECK_INTERVAL:?}, threshold is {threshold}"
+                            );
+                            alerts
+                                .recorder([
+                                    ("tenant", tenant),
+                                    ("database", db_name),
+                                    ("table", table.as_str()),
+                                ])
+                                .inc_one();
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Moves the files older than `storage.tiering_age` to the tiered storage
+    /// periodically.
+    fn run_tiering_job(&self) {
+        let age = self.ctx.options.storage.tiering_age;
+        if age.is_zero() {
+            return;
+        }
+        let Some(storage) = TieredStorage::get() else {
+            warn!("Tiering: 'storage.tiering_age' is set but the tiered storage is not configured");
+            return;
+        };
+        let vnodes = self.vnodes.clone();
+
+        self.runtime.spawn(async move {
+            let mut check_interval = tokio::time::interval(TIERING_CHECK_INTERVAL);
+            loop {
+                check_interval.tick().await;
+
+                let vnodes = vnodes.read().await.values().cloned().collect::<Vec<_>>();
+                for vnode in vnodes {
+                    match vnode.tier_cold_files(&storage, age).await {
+                        Ok(0) => {}
+                        Ok(n) => info!("Tiering: vnode {} moved {n} files", vnode.id()),
+                        Err(e) => warn!("Tiering: vnode {} failed: {e}", vnode.id()),
+                    }
+                }
+            }
+        });
+    }
+
+    /// Lowers the IO rate of compactions while the P99 latency of the table scans
+    /// in the last interval exceeds `storage.compaction_throttle_query_latency`,
+    /// and raises it back to `storage.compaction_io_rate_limit` while not.
+    fn run_compaction_throttle_job(&self) {
+        let storage_opt = self.ctx.options.storage.clone();
+        let threshold = storage_opt.compaction_throttle_query_latency;
+        if storage_opt.compaction_io_rate_limit == 0 || threshold.is_zero() {
+            return;
+        }
+        let global_ctx = self.ctx.global_ctx.clone();
+        let query_latency = self.query_latency.clone();
+
+        self.runtime.spawn(async move {
+            let mut previous = query_latency.fetch();
+            let mut check_interval = tokio::time::interval(COMPACTION_THROTTLE_INTERVAL);
+            loop {
+                check_interval.tick().await;
+
+                let current = query_latency.fetch();
+                let p99 = latency_p99(&previous, &current);
+                previous = current;
+
+                let throttle = global_ctx.compaction_throttle();
+                let rate = throttle.rate();
+                let queries_slow = p99.map_or(false, |p99| p99 > threshold);
+                let new_rate = throttle.adjust(queries_slow);
+                if new_rate != rate {
+                    info!("Compaction throttle: query latency P99 {p99:?}, IO rate {rate} -> {new_rate} B/s");
+                }
+            }
+        });
+    }
+
     async fn sync_indexs(&self) -> IndexResult<()> {
         let vnodes_guard = self.vnodes.read().await;
         for (_, vnode_storage) in vnodes_guard.iter() {
@@ -285,6 +497,23 @@ impl Engine for TsKv {
             .get_tsfamily_or_else_create(vnode_id, database.clone())
             .await?;
 
+        let warmup_recent_duration = self.ctx.options.storage.warmup_recent_duration;
+        if !warmup_recent_duration.is_zero() {
+            let precision = *database.read().await.config().precision();
+            let warmup = warmup::warmup_vnode(
+                ts_family.clone(),
+                ts_index.clone(),
+                precision,
+                warmup_recent_duration,
+                self.warmup_budget.clone(),
+            );
+            self.runtime.spawn(async move {
+                if let Err(e) = warmup.await {
+                    warn!("Warm up vnode {vnode_id} failed: {e}");
+                }
+            });
+        }
+
         let vnode = VnodeStorage::new(vnode_id, database, ts_index, ts_family, self.ctx.clone());
         self.vnodes.write().await.insert(vnode_id, vnode.clone());
 
@@ -328,6 +557,19 @@ impl Engine for TsKv {
         Ok(())
     }
 
+    async fn remove_database(&self, tenant: &str, database: &str) -> TskvResult<bool> {
+        let mut version_set = self.ctx.version_set.write().await;
+        if let Some(db) = version_set.get_db(tenant, database) {
+            if !db.read().await.ts_families().is_empty() {
+                return Ok(false);
+            }
+            version_set.delete_db(tenant, database);
+            info!("Removed caches of database '{}.{}'", tenant, database);
+        }
+
+        Ok(true)
+    }
+
     async fn flush_tsfamily(
         &self,
         _tenant: &str,
@@ -396,6 +638,55 @@ impl Engine for TsKv {
         }
     }
 
+    async fn table_series_count(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_id: VnodeId,
+    ) -> TskvResult<u64> {
+        let ts_index = match self.ctx.version_set.read().await.get_db(tenant, database) {
+            Some(db) => match db.read().await.get_ts_index(vnode_id) {
+                Some(ts_index) => ts_index,
+                None => return Ok(0),
+            },
+            None => return Ok(0),
+        };
+
+        let count = ts_index
+            .read()
+            .await
+            .series_count(table)
+            .await
+            .context(IndexErrSnafu)?;
+
+        Ok(count)
+    }
+
+    async fn count_new_series(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> TskvResult<HashMap<String, u64>> {
+        let fb_points = flatbuffers::root::<protos::models::Points>(points)
+            .context(crate::error::InvalidFlatbufferSnafu)?;
+        let tables = fb_points
+            .tables()
+            .context(crate::error::InvalidPointTableSnafu)?;
+
+        let Some(db) = self.ctx.version_set.read().await.get_db(tenant, database) else {
+            return Ok(HashMap::new());
+        };
+        let db = db.read().await;
+        let Some(ts_index) = db.get_ts_index(vnode_id) else {
+            return Ok(HashMap::new());
+        };
+
+        db.count_new_series(tables, ts_index).await
+    }
+
     async fn get_db_version(
         &self,
         tenant: &str,
@@ -452,7 +743,11 @@ impl Engine for TsKv {
                 }
 
                 let version = ts_family.read().await.version();
-                if let Some(req) = pick_compaction(CompactTask::Manual(vnode_id), version).await {
+                let strategy =
+                    compaction::database_compaction_strategy(&self.ctx.version_set, &owner).await;
+                if let Some(req) =
+                    pick_compaction(CompactTask::Manual(vnode_id), version, strategy.as_ref()).await
+                {
                     let vnode_compaction_metrics = VnodeCompactionMetrics::new(
                         &self.metrics,
                         self.ctx.options.storage.node_id,
@@ -515,6 +810,293 @@ impl Engine for TsKv {
         Ok(RecordBatch::new_empty(check::vnode_table_checksum_schema()))
     }
 
+    async fn series_cardinality_top(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_ids: &[VnodeId],
+        top_k: usize,
+    ) -> TskvResult<RecordBatch> {
+        let mut series: HashMap<CardinalityKey, u64> = HashMap::new();
+        if let Some(db) = self.get_db(tenant, database).await {
+            let ts_indexes = db.read().await.ts_indexes();
+            for vnode_id in vnode_ids {
+                let Some(ts_index) = ts_indexes.get(vnode_id) else {
+                    continue;
+                };
+                for (key, count) in ts_index.read().await.cardinality().prefix_series() {
+                    *series.entry(key.clone()).or_default() += count;
+                }
+            }
+        }
+
+        let mut items = series.into_iter().collect();
+        cardinality::sort_and_truncate(&mut items, top_k);
+        cardinality::cardinality_top_record_batch(&items).context(ArrowSnafu)
+    }
+
+    async fn estimate_scan_cost(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_id: VnodeId,
+        predicate: &ResolvedPredicate,
+    ) -> TskvResult<ScanCost> {
+        let series_ids = self
+            .get_series_id_by_filter(tenant, database, table, vnode_id, predicate.tags_filter())
+            .await?;
+        let mut cost = ScanCost {
+            series: series_ids.len() as u64,
+            ..Default::default()
+        };
+        if series_ids.is_empty() {
+            return Ok(cost);
+        }
+
+        let ts_family = self
+            .ctx
+            .version_set
+            .read()
+            .await
+            .get_tsfamily_by_tf_id(vnode_id)
+            .await
+            .context(VnodeNotFoundSnafu { vnode_id })?;
+        let version = ts_family.read().await.version();
+        scan_cost::estimate_files_cost(&version, &predicate.time_ranges(), &mut cost);
+
+        Ok(cost)
+    }
+
+    async fn export_vnode(
+        &self,
+        vnode_id: VnodeId,
+        time_range: TimeRange,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<u64> {
+        let ts_family = self
+            .ctx
+            .version_set
+            .read()
+            .await
+            .get_tsfamily_by_tf_id(vnode_id)
+            .await
+            .context(VnodeNotFoundSnafu { vnode_id })?;
+
+        let owner = ts_family.read().await.owner();
+        let (tenant, db_name) = split_owner(&owner);
+        self.flush_tsfamily(tenant, db_name, vnode_id, false)
+            .await?;
+
+        export::export_vnode(ts_family, time_range, object_store, prefix).await
+    }
+
+    async fn backup_vnode(
+        &self,
+        vnode_id: VnodeId,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<VnodeBackup> {
+        let ts_family = self
+            .ctx
+            .version_set
+            .read()
+            .await
+            .get_tsfamily_by_tf_id(vnode_id)
+            .await
+            .context(VnodeNotFoundSnafu { vnode_id })?;
+
+        let owner = ts_family.read().await.owner();
+        let (tenant, db_name) = split_owner(&owner);
+        self.flush_tsfamily(tenant, db_name, vnode_id, false)
+            .await?;
+
+        let node_id = self.ctx.options.storage.node_id;
+        backup::backup_vnode(ts_family, node_id, object_store, prefix).await
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        mut backup: VnodeBackup,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<()> {
+        let opened = self
+            .ctx
+            .version_set
+            .read()
+            .await
+            .get_tsfamily_by_tf_id(vnode_id)
+            .await
+            .is_some();
+        if opened {
+            return Err(CommonSnafu {
+                reason: format!("vnode {vnode_id} is already opened, can not restore into it"),
+            }
+            .build());
+        }
+
+        let mut vnode = self.open_tsfamily(tenant, database, vnode_id).await?;
+        let restore_dir = self
+            .ctx
+            .options
+            .storage
+            .path()
+            .join(format!("restore_{vnode_id}"));
+        let _ = std::fs::remove_dir_all(&restore_dir);
+        let result = async {
+            backup::download_backup(&backup, object_store, &prefix, &restore_dir).await?;
+            // The raft logs of the new vnode start from 1, and the database
+            // may be restored into another tenant.
+            backup.snapshot.last_seq_no = 0;
+            backup.snapshot.version_edit.seq_no = 0;
+            backup.snapshot.version_edit.tsf_name = make_owner(tenant, database);
+            vnode.apply_snapshot(backup.snapshot, &restore_dir).await
+        }
+        .await;
+        let _ = std::fs::remove_dir_all(&restore_dir);
+        if let Err(e) = result {
+            let _ = self.remove_tsfamily(tenant, database, vnode_id).await;
+            return Err(e);
+        }
+        self.vnodes.write().await.insert(vnode_id, vnode);
+        info!("Restored vnode {vnode_id} of {tenant}.{database} from {prefix}");
+
+        Ok(())
+    }
+
+    async fn table_last_write(
+        &self,
+        vnode_id: VnodeId,
+        table: &str,
+        time_range: &TimeRange,
+    ) -> TskvResult<Option<u64>> {
+        let vnode = self
+            .vnodes
+            .read()
+            .await
+            .get(&vnode_id)
+            .cloned()
+            .context(VnodeNotFoundSnafu { vnode_id })?;
+        Ok(vnode.last_write(table, time_range))
+    }
+
+    async fn vnodes_disk_storage(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)> {
+        let version_set = self.ctx.version_set.read().await;
+        let mut sizes = Vec::with_capacity(vnode_ids.len());
+        for vnode_id in vnode_ids {
+            if let Some(ts_family) = version_set.get_tsfamily_by_tf_id(*vnode_id).await {
+                sizes.push((*vnode_id, ts_family.read().await.disk_storage()));
+            }
+        }
+
+        sizes
+    }
+
+    async fn vnodes_rows(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)> {
+        let version_set = self.ctx.version_set.read().await;
+        let mut rows = Vec::with_capacity(vnode_ids.len());
+        for vnode_id in vnode_ids {
+            let Some(ts_family) = version_set.get_tsfamily_by_tf_id(*vnode_id).await else {
+                continue;
+            };
+            let version = ts_family.read().await.version();
+            let mut vnode_rows = 0_u64;
+            for file in version.levels_info().iter().flat_map(|l| l.files.iter()) {
+                match version.get_tsm_reader(file.file_path()).await {
+                    Ok(reader) => {
+                        vnode_rows += reader
+                            .chunk()
+                            .values()
+                            .flat_map(|chunk| chunk.column_group().values())
+                            .map(|group| group.row_len() as u64)
+                            .sum::<u64>();
+                    }
+                    Err(e) => warn!(
+                        "Failed to count rows of file {}: {e}",
+                        file.file_path().display()
+                    ),
+                }
+            }
+            rows.push((*vnode_id, vnode_rows));
+        }
+
+        rows
+    }
+
+    async fn close_vnodes_files(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)> {
+        let version_set = self.ctx.version_set.read().await;
+        let mut closed = Vec::with_capacity(vnode_ids.len());
+        for vnode_id in vnode_ids {
+            if let Some(ts_family) = version_set.get_tsfamily_by_tf_id(*vnode_id).await {
+                let num = ts_family.read().await.close_idle_files().await;
+                info!("Closed {num} idle files of vnode {vnode_id}");
+                closed.push((*vnode_id, num as u64));
+            }
+        }
+
+        closed
+    }
+
+    async fn explain_compaction(&self, vnode_id: VnodeId) -> Vec<CompactionPlan> {
+        let ts_family = self
+            .ctx
+            .version_set
+            .read()
+            .await
+            .get_tsfamily_by_tf_id(vnode_id)
+            .await;
+        match ts_family {
+            Some(ts_family) => {
+                let version = ts_family.read().await.version();
+                let strategy = compaction::database_compaction_strategy(
+                    &self.ctx.version_set,
+                    &version.owner(),
+                )
+                .await;
+                compaction::plan_compactions(
+                    vnode_id,
+                    version,
+                    strategy.as_ref(),
+                    &self.ctx.global_ctx,
+                )
+                .await
+            }
+            None => vec![],
+        }
+    }
+
+    fn compaction_jobs(&self) -> Vec<CompactionJobInfo> {
+        self.ctx.global_ctx.compaction_jobs().jobs()
+    }
+
+    fn kill_compaction(&self, job_id: u64) -> bool {
+        let killed = self.ctx.global_ctx.compaction_jobs().cancel(job_id);
+        if killed {
+            info!("Cancelled compaction job {job_id}");
+        }
+        killed
+    }
+
+    fn record_query_latency(&self, latency: Duration) {
+        self.query_latency.record(latency);
+    }
+
+    fn subscribe_writes(
+        &self,
+        tenant: &str,
+        database: &str,
+        start_offset: u64,
+    ) -> TskvResult<WriteSubscription> {
+        self.ctx
+            .subscriptions
+            .subscribe(&make_owner(tenant, database), start_offset)
+    }
+
     async fn close(&self) {
         let (tx, mut rx) = mpsc::channel(1);
         if let Err(e) = self.close_sender.send(tx) {
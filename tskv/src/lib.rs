@@ -1,17 +1,24 @@
 #![recursion_limit = "256"]
 
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 pub use compaction::check::vnode_table_checksum_schema;
+pub use compaction::job::CompactionJobInfo;
 use compaction::CompactTask;
+pub use compaction::CompactionPlan;
 use context::GlobalContext;
 use datafusion::arrow::record_batch::RecordBatch;
 use models::meta_data::{NodeId, VnodeId};
-use models::predicate::domain::ColumnDomains;
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRange};
 use models::{SeriesId, SeriesKey};
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
 use serde::{Deserialize, Serialize};
+use subscription::{SubscriptionHub, WriteSubscription};
 use summary::SummaryTask;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::Sender;
@@ -20,16 +27,19 @@ use tsfamily::version::Version;
 use version_set::VersionSet;
 use vnode_store::VnodeStorage;
 
+pub use crate::backup::VnodeBackup;
 pub use crate::error::{TskvError, TskvResult};
 pub use crate::kv_option::Options;
 use crate::kv_option::StorageOptions;
 pub use crate::kvcore::TsKv;
+pub use crate::scan_cost::ScanCost;
 pub use crate::summary::{print_summary_statistics, Summary, VersionEdit};
 use crate::tsfamily::super_version::SuperVersion;
 // todo: add a method for print tsm statistics
 // pub use crate::tsm::print_tsm_statistics;
 pub use crate::wal::print_wal_statistics;
 
+mod backup;
 pub mod byte_utils;
 mod compaction;
 mod compute;
@@ -37,6 +47,7 @@ mod context;
 pub mod database;
 pub mod engine_mock;
 pub mod error;
+mod export;
 pub mod file_system;
 pub mod file_utils;
 pub mod index;
@@ -46,13 +57,18 @@ mod kvcore;
 mod mem_cache;
 pub mod reader;
 mod record_file;
+mod scan_cost;
 mod schema;
+pub mod subscription;
 mod summary;
+mod table_writes;
+pub mod tiering;
 mod tsfamily;
 pub mod tsm;
 mod version_set;
 pub mod vnode_store;
 pub mod wal;
+mod warmup;
 
 /// The column file ID is unique in a KV instance
 /// and uniquely corresponds to one column file.
@@ -86,6 +102,10 @@ pub trait Engine: Send + Sync + Debug {
         vnode_id: VnodeId,
     ) -> TskvResult<()>;
 
+    /// Remove the caches of the database if it has no storage unit left,
+    /// returns false if there are storage units still opened.
+    async fn remove_database(&self, tenant: &str, database: &str) -> TskvResult<bool>;
+
     /// Flush all caches of the storage unit into a file.
     async fn flush_tsfamily(
         &self,
@@ -115,6 +135,25 @@ pub trait Engine: Send + Sync + Debug {
         series_id: &[SeriesId],
     ) -> TskvResult<Vec<SeriesKey>>;
 
+    /// Read index of a storage unit, count the series of the table.
+    async fn table_series_count(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_id: VnodeId,
+    ) -> TskvResult<u64>;
+
+    /// Read index of a storage unit, count the series of each table in the
+    /// points which are not created yet.
+    async fn count_new_series(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> TskvResult<HashMap<String, u64>>;
+
     /// Get a `SuperVersion` that contains the latest version of caches and files
     /// of the storage unit.
     async fn get_db_version(
@@ -134,6 +173,105 @@ pub trait Engine: Send + Sync + Debug {
     /// Get a compressed hash_tree(ID and checksum of each vnode) of engine.
     async fn get_vnode_hash_tree(&self, vnode_id: VnodeId) -> TskvResult<RecordBatch>;
 
+    /// Get the tag value prefixes which created the most series in the vnodes of
+    /// the database, returns RecordBatch with columns of table, tag_key, value_prefix and series_count.
+    async fn series_cardinality_top(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_ids: &[VnodeId],
+        top_k: usize,
+    ) -> TskvResult<RecordBatch>;
+
+    /// Estimate the cost of scanning the table in a storage unit with the predicate,
+    /// without reading any data.
+    async fn estimate_scan_cost(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_id: VnodeId,
+        predicate: &ResolvedPredicate,
+    ) -> TskvResult<ScanCost>;
+
+    /// Flush all caches of the storage unit into files, then export data in the
+    /// time range into parquet files under `prefix` of the object store,
+    /// returns the number of exported rows.
+    async fn export_vnode(
+        &self,
+        vnode_id: VnodeId,
+        time_range: TimeRange,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<u64>;
+
+    /// Flush all caches of the storage unit into files, then upload the files
+    /// under `prefix` of the object store.
+    async fn backup_vnode(
+        &self,
+        vnode_id: VnodeId,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<VnodeBackup>;
+
+    /// Download the files of the backup under `prefix` of the object store into
+    /// a new storage unit, the storage unit must not be opened in the engine.
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        backup: VnodeBackup,
+        object_store: Arc<dyn ObjectStore>,
+        prefix: ObjectStorePath,
+    ) -> TskvResult<()>;
+
+    /// The index of the latest raft log applied to the storage unit writing the
+    /// time range of the table, None if it's unknown.
+    async fn table_last_write(
+        &self,
+        vnode_id: VnodeId,
+        table: &str,
+        time_range: &TimeRange,
+    ) -> TskvResult<Option<u64>>;
+
+    /// Get the size of files of the storage units on disk, storage units not
+    /// opened in the engine are skipped.
+    async fn vnodes_disk_storage(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)>;
+
+    /// Get the number of rows in the files of the storage units, rows in the
+    /// caches are not counted and rows of overlapped files may be counted more
+    /// than once. Storage units not opened in the engine are skipped.
+    async fn vnodes_rows(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)>;
+
+    /// Close the idle files of the storage units, they are opened again on the next read.
+    /// Returns the number of files closed of each storage unit, storage units not
+    /// opened in the engine are skipped.
+    async fn close_vnodes_files(&self, vnode_ids: &[VnodeId]) -> Vec<(VnodeId, u64)>;
+
+    /// Get the compactions that would be run on the storage unit, nothing is compacted.
+    /// Returns an empty list if the storage unit is not opened in the engine.
+    async fn explain_compaction(&self, vnode_id: VnodeId) -> Vec<CompactionPlan>;
+
+    /// Get the compactions running in the engine.
+    fn compaction_jobs(&self) -> Vec<CompactionJobInfo>;
+
+    /// Cancel the running compaction, returns false if it is not found.
+    fn kill_compaction(&self, job_id: u64) -> bool;
+
+    /// Record the latency of a finished table scan of a storage unit, used to
+    /// throttle the compactions while the queries are slow.
+    fn record_query_latency(&self, latency: Duration);
+
+    /// Subscribe the writes applied to the vnodes of the database in the engine,
+    /// from `start_offset`, 0 means from the earliest retained write.
+    fn subscribe_writes(
+        &self,
+        tenant: &str,
+        database: &str,
+        start_offset: u64,
+    ) -> TskvResult<WriteSubscription>;
+
     /// Close all background jobs of engine.
     async fn close(&self);
 }
@@ -147,6 +285,7 @@ pub struct TsKvContext {
 
     pub compact_task_sender: Sender<CompactTask>,
     pub summary_task_sender: Sender<SummaryTask>,
+    pub subscriptions: Arc<SubscriptionHub>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -0,0 +1,293 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+use models::field_value::FieldVal;
+use models::predicate::domain::TimeRange;
+use models::{SeriesId, Timestamp};
+use utils::BloomFilter;
+
+use super::row_data::RowData;
+use super::series_data::RowGroup;
+
+/// Rolling bloom filters over the recently written rows.
+///
+/// A row is dropped only if the same row, i.e. the same timestamp and fields,
+/// was the last written to the series at the timestamp, so dropping it changes
+/// nothing. The rows are dropped by the local clock and the filters in memory,
+/// which differ between the replicas of a vnode, but the replicas still end up
+/// with the same data. The filters are cleared by deletes, the rows written
+/// again after a delete are not duplicates.
+///
+/// Two generations of filters are kept, the current generation is swapped
+/// to the previous one every `window`, so a row is remembered for at least
+/// `window` and at most `2 * window` after it was written.
+///
+/// Bloom filters may report false positives, so a distinct row may be dropped
+/// as a duplicate, the probability is controlled by the number of bits.
+#[derive(Debug)]
+pub struct WriteDedupWindow {
+    window: Duration,
+    bits: u64,
+    current: Generation,
+    previous: Generation,
+    rotated_at: Instant,
+}
+
+#[derive(Debug)]
+struct Generation {
+    /// `(series_id, timestamp, fields)` of the rows written.
+    rows: BloomFilter,
+    /// `(series_id, timestamp)` of the rows written.
+    points: BloomFilter,
+    /// `(series_id, timestamp)` written with different rows.
+    overwritten: BloomFilter,
+}
+
+impl Generation {
+    fn new(bits: u64) -> Self {
+        Self {
+            rows: BloomFilter::new(bits),
+            points: BloomFilter::new(bits),
+            overwritten: BloomFilter::new(bits),
+        }
+    }
+}
+
+impl WriteDedupWindow {
+    pub fn new(window: Duration, bits: u64) -> Self {
+        Self {
+            window,
+            bits,
+            current: Generation::new(bits),
+            previous: Generation::new(bits),
+            rotated_at: Instant::now(),
+        }
+    }
+
+    /// Drops the rows in `group` that have been written in the window,
+    /// returns the number of dropped rows.
+    pub fn dedup_row_group(&mut self, series_id: SeriesId, group: &mut RowGroup) -> usize {
+        self.dedup_row_group_at(series_id, group, Instant::now())
+    }
+
+    /// Forget all rows, the rows written after a delete are not duplicates.
+    pub fn clear(&mut self) {
+        self.current = Generation::new(self.bits);
+        self.previous = Generation::new(self.bits);
+    }
+
+    fn dedup_row_group_at(
+        &mut self,
+        series_id: SeriesId,
+        group: &mut RowGroup,
+        now: Instant,
+    ) -> usize {
+        self.try_rotate(now);
+
+        let mut dropped = 0;
+        group.rows.retain(|row| {
+            if self.check_and_insert(series_id, row) {
+                dropped += 1;
+                false
+            } else {
+                true
+            }
+        });
+
+        if dropped > 0 {
+            group.range = TimeRange::none();
+            group.size = size_of::<RowGroup>();
+            for row in group.rows.get_ref_rows().iter() {
+                group.range.merge(&TimeRange::new(row.ts, row.ts));
+                group.size += row.size();
+            }
+        }
+
+        dropped
+    }
+
+    /// Returns true if the row may be the last one written to the point in the
+    /// window, otherwise remembers the row and returns false.
+    fn check_and_insert(&mut self, series_id: SeriesId, row: &RowData) -> bool {
+        let point_key = Self::point_key(series_id, row.ts);
+        let row_key = Self::row_key(&point_key, &row.fields);
+        let written = |g: &Generation| g.rows.maybe_contains(&row_key);
+        let overwritten = |g: &Generation| g.overwritten.maybe_contains(&point_key);
+        if (written(&self.current) || written(&self.previous))
+            && !overwritten(&self.current)
+            && !overwritten(&self.previous)
+        {
+            return true;
+        }
+
+        // The point is written again, the rows written to it before are no
+        // longer the last ones.
+        if self.current.points.maybe_contains(&point_key)
+            || self.previous.points.maybe_contains(&point_key)
+        {
+            self.current.overwritten.insert(&point_key);
+        }
+        self.current.points.insert(&point_key);
+        self.current.rows.insert(&row_key);
+        false
+    }
+
+    fn try_rotate(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.rotated_at);
+        if elapsed < self.window {
+            return;
+        }
+
+        if elapsed >= self.window * 2 {
+            // Nothing written in the last window, forget all.
+            self.previous = Generation::new(self.bits);
+        } else {
+            self.previous = std::mem::replace(&mut self.current, Generation::new(self.bits));
+        }
+        self.current = Generation::new(self.bits);
+        self.rotated_at = now;
+    }
+
+    fn point_key(series_id: SeriesId, ts: Timestamp) -> [u8; 12] {
+        let mut key = [0_u8; 12];
+        key[..4].copy_from_slice(&series_id.to_be_bytes());
+        key[4..].copy_from_slice(&ts.to_be_bytes());
+        key
+    }
+
+    fn row_key(point_key: &[u8; 12], fields: &[Option<FieldVal>]) -> [u8; 20] {
+        let mut hasher = DefaultHasher::new();
+        for field in fields {
+            match field {
+                None => 0_u8.hash(&mut hasher),
+                Some(FieldVal::Float(v)) => (1_u8, v.to_bits()).hash(&mut hasher),
+                Some(FieldVal::Integer(v)) => (2_u8, v).hash(&mut hasher),
+                Some(FieldVal::Unsigned(v)) => (3_u8, v).hash(&mut hasher),
+                Some(FieldVal::Boolean(v)) => (4_u8, v).hash(&mut hasher),
+                Some(FieldVal::Bytes(v)) => (5_u8, &v[..]).hash(&mut hasher),
+            }
+        }
+
+        let mut key = [0_u8; 20];
+        key[..12].copy_from_slice(point_key);
+        key[12..].copy_from_slice(&hasher.finish().to_be_bytes());
+        key
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use models::field_value::FieldVal;
+    use models::predicate::domain::TimeRange;
+    use models::schema::tskv_table_schema::TskvTableSchema;
+
+    use super::WriteDedupWindow;
+    use crate::mem_cache::row_data::{OrderedRowsData, RowData};
+    use crate::mem_cache::series_data::RowGroup;
+
+    fn row_group(timestamps: &[i64]) -> RowGroup {
+        let rows = timestamps.iter().map(|ts| (*ts, 0)).collect::<Vec<_>>();
+        row_group_with_values(&rows)
+    }
+
+    /// Row group of the rows `(timestamp, value of the field)`.
+    fn row_group_with_values(rows: &[(i64, i64)]) -> RowGroup {
+        let mut group = RowGroup {
+            schema: Arc::new(TskvTableSchema::default()),
+            range: TimeRange::none(),
+            rows: OrderedRowsData::new(),
+            size: 0,
+        };
+        for (ts, value) in rows {
+            group.range.merge(&TimeRange::new(*ts, *ts));
+            group.rows.insert(RowData {
+                ts: *ts,
+                fields: vec![Some(FieldVal::Integer(*value))],
+            });
+        }
+        group
+    }
+
+    #[test]
+    fn test_dedup_in_window() {
+        let now = Instant::now();
+        let mut window = WriteDedupWindow::new(Duration::from_secs(60), 1024 * 1024);
+
+        let mut group = row_group(&[1, 2, 3]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+        assert_eq!(group.rows.get_ref_rows().len(), 3);
+
+        let mut group = row_group(&[2, 3, 4]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 2);
+        assert_eq!(group.rows.get_ref_rows().len(), 1);
+        assert_eq!(group.range, TimeRange::new(4, 4));
+
+        // Same timestamps of another series are not duplicates.
+        let mut group = row_group(&[2, 3, 4]);
+        assert_eq!(window.dedup_row_group_at(2, &mut group, now), 0);
+    }
+
+    #[test]
+    fn test_dedup_window_rotate() {
+        let now = Instant::now();
+        let mut window = WriteDedupWindow::new(Duration::from_secs(60), 1024 * 1024);
+
+        let mut group = row_group(&[1, 2]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+
+        // Still remembered by the previous generation.
+        let now = now + Duration::from_secs(61);
+        let mut group = row_group(&[1]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 1);
+
+        // Expired after two rotations.
+        let now = now + Duration::from_secs(61);
+        let mut group = row_group(&[1, 2]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+    }
+
+    #[test]
+    fn test_dedup_same_rows_only() {
+        let now = Instant::now();
+        let mut window = WriteDedupWindow::new(Duration::from_secs(60), 1024 * 1024);
+
+        let mut group = row_group_with_values(&[(1, 10), (2, 20)]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+
+        // The rows with other values at the same timestamps overwrite the points.
+        let mut group = row_group_with_values(&[(1, 11), (2, 20)]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 1);
+        assert_eq!(group.rows.get_ref_rows().len(), 1);
+        assert_eq!(group.range, TimeRange::new(1, 1));
+
+        // The first row is not the last one written to the point any more.
+        let mut group = row_group_with_values(&[(1, 10)]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+
+        // Still not dropped after the rotation.
+        let now = now + Duration::from_secs(61);
+        let mut group = row_group_with_values(&[(1, 10)]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+    }
+
+    #[test]
+    fn test_dedup_window_clear() {
+        let now = Instant::now();
+        let mut window = WriteDedupWindow::new(Duration::from_secs(60), 1024 * 1024);
+
+        let mut group = row_group(&[1, 2]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+
+        // The rows written again after a delete are kept.
+        window.clear();
+        let mut group = row_group(&[1, 2]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 0);
+        let mut group = row_group(&[1, 2]);
+        assert_eq!(window.dedup_row_group_at(1, &mut group, now), 2);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod dedup_window;
 pub mod memcache;
 pub mod row_data;
 pub mod series_data;
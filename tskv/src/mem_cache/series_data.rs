@@ -5,6 +5,7 @@ use models::predicate::domain::{TimeRange, TimeRanges};
 use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema, TskvTableSchemaRef};
 use models::{ColumnId, SeriesId, SeriesKey, Timestamp};
 use skiplist::skiplist;
+use trace::warn;
 
 use super::row_data::{OrderedRowsData, RowData, RowDataRef};
 use crate::error::{CommonSnafu, TskvResult};
@@ -214,6 +215,27 @@ impl SeriesData {
         None
     }
 
+    /// Rows of an append-only table are written without the dedup merge if they
+    /// are in only one row group and have no duplicate timestamps.
+    fn append_only_rows(&self, schema: &TskvTableSchema) -> Option<&OrderedRowsData> {
+        if !schema.append_only || self.groups.len() != 1 {
+            return None;
+        }
+        let rows = &self.groups.front()?.rows;
+        let mut prev_ts = None;
+        for row in rows.get_ref_rows().iter() {
+            if prev_ts == Some(row.ts) {
+                warn!(
+                    "Flush: found duplicate timestamp {} in series {} of append-only table {}.{}, dedup the rows instead",
+                    row.ts, self.series_id, schema.db, schema.name
+                );
+                return None;
+            }
+            prev_ts = Some(row.ts);
+        }
+        Some(rows)
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn convert_to_page(&self) -> TskvResult<Option<(Arc<TskvTableSchema>, Vec<page::Page>)>> {
         let latest_schema = match self.get_schema() {
@@ -229,11 +251,25 @@ impl SeriesData {
             .map(|col| MutableColumnRef::empty(col.clone()))
             .collect::<TskvResult<Vec<_>>>()?;
 
-        let iter = SeriesDedupMergeSortIterator::new(self.flat_groups(), latest_schema.clone());
-        for row_data in iter {
-            time_array.push_ts(row_data.ts)?;
-            for (idx, field) in row_data.fields.iter().enumerate() {
-                fields_array[idx].push(*field)?;
+        if let Some(rows) = self.append_only_rows(&latest_schema) {
+            let fields_id = latest_schema.fields_id();
+            for row_data in rows.get_ref_rows().iter() {
+                time_array.push_ts(row_data.ts)?;
+                for (idx, col) in fields_schema.iter().enumerate() {
+                    let field = fields_id
+                        .get(&col.id)
+                        .and_then(|index| row_data.fields.get(*index))
+                        .and_then(|field| field.as_ref());
+                    fields_array[idx].push(field)?;
+                }
+            }
+        } else {
+            let iter = SeriesDedupMergeSortIterator::new(self.flat_groups(), latest_schema.clone());
+            for row_data in iter {
+                time_array.push_ts(row_data.ts)?;
+                for (idx, field) in row_data.fields.iter().enumerate() {
+                    fields_array[idx].push(*field)?;
+                }
             }
         }
 
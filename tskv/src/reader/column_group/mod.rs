@@ -13,6 +13,7 @@ use models::arrow::stream::BoxStream;
 use models::{ColumnId, SeriesId};
 
 use super::metrics::BaselineMetrics;
+use super::scan_metrics::ScanMetrics;
 use super::{
     BatchReader, BatchReaderRef, SchemableTskvRecordBatchStream,
     SendableSchemableTskvRecordBatchStream,
@@ -29,6 +30,7 @@ pub struct ColumnGroupReader {
     pages_meta: Vec<PageWriteSpec>,
     schema: SchemaRef,
     metrics: Arc<ExecutionPlanMetricsSet>,
+    scan_metrics: ScanMetrics,
 }
 impl ColumnGroupReader {
     pub fn try_new(
@@ -39,6 +41,7 @@ impl ColumnGroupReader {
         schema_meta: HashMap<String, String>,
         _batch_size: usize,
         metrics: Arc<ExecutionPlanMetricsSet>,
+        scan_metrics: ScanMetrics,
     ) -> TskvResult<Self> {
         let pages_meta = projection
             .iter()
@@ -66,6 +69,7 @@ impl ColumnGroupReader {
             pages_meta,
             schema,
             metrics,
+            scan_metrics,
         })
     }
 }
@@ -78,6 +82,7 @@ impl BatchReader for ColumnGroupReader {
             self.pages_meta.clone(),
             self.schema.metadata().clone(),
             ColumnGroupReaderMetrics::new(self.metrics.as_ref()),
+            self.scan_metrics.clone(),
         )));
 
         Ok(Box::pin(ColumnGroupRecordBatchStream {
@@ -198,6 +203,7 @@ async fn read(
     pages_meta: Vec<PageWriteSpec>,
     schema_meta: HashMap<String, String>,
     metrics: ColumnGroupReaderMetrics,
+    scan_metrics: ScanMetrics,
 ) -> TskvResult<RecordBatch> {
     let mut sorted_pages = pages_meta.clone();
     sorted_pages.sort_by_key(|p| p.offset());
@@ -214,8 +220,19 @@ async fn read(
         pages.extend(batch_pages);
     }
 
+    scan_metrics.tsm_blocks_read().add(1);
+
     let _timer = metrics.elapsed_pages_to_record_batch_time().timer();
+    let num_rows = pages
+        .iter()
+        .find(|p| p.meta().column.column_type.is_time())
+        .map(|p| p.meta().num_values as usize);
     let record_batch = decode_pages(pages, schema_meta, Some((reader.tombstone(), series_id)))?;
+    if let Some(num_rows) = num_rows {
+        scan_metrics
+            .tombstone_filtered_rows()
+            .add(num_rows.saturating_sub(record_batch.num_rows()));
+    }
     Ok(record_batch)
 }
 
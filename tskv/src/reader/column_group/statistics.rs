@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use arrow::datatypes::DataType;
-use arrow_array::ArrayRef;
+use arrow_array::{ArrayRef, UInt64Array};
 use datafusion::physical_optimizer::pruning::PruningStatistics;
 use datafusion::scalar::ScalarValue;
 
@@ -75,7 +75,18 @@ impl<'a> PruningStatistics for ColumnGroupsStatisticsWrapper<'a> {
         self.0.len()
     }
 
-    fn null_counts(&self, _column: &datafusion::prelude::Column) -> Option<ArrayRef> {
-        None
+    /// The column groups without a page of the column are all null.
+    fn null_counts(&self, column: &datafusion::prelude::Column) -> Option<ArrayRef> {
+        let values = self.0.iter().map(|cg| {
+            let null_count = cg
+                .pages()
+                .iter()
+                .find(|e| e.meta.column.name == column.name)
+                .map(|e| e.meta().statistics.null_count())
+                .unwrap_or(cg.row_len() as u64);
+            Some(null_count)
+        });
+
+        Some(Arc::new(UInt64Array::from_iter(values)))
     }
 }
@@ -11,11 +11,12 @@ use datafusion::arrow::array::{
 };
 use datafusion::arrow::datatypes::TimeUnit;
 use datafusion::physical_plan::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder};
+use datafusion::scalar::ScalarValue;
 use datafusion_proto::physical_plan::from_proto::parse_physical_expr;
 use models::meta_data::VnodeId;
 use models::predicate::domain::{self, PushedAggregateFunction, QueryArgs, QueryExpr, TimeRanges};
 use models::predicate::PlacedSplit;
-use models::schema::tskv_table_schema::{PhysicalCType, TskvTableSchemaRef};
+use models::schema::tskv_table_schema::{PhysicalCType, TskvTableSchema, TskvTableSchemaRef};
 use models::{ColumnId, PhysicalDType, SeriesId, SeriesKey};
 use protos::kv_service::QueryRecordBatchRequest;
 use snafu::ResultExt;
@@ -27,7 +28,10 @@ use trace::{debug, error, Span, SpanContext};
 use super::display::DisplayableBatchReader;
 use super::memcache_reader::MemCacheReader;
 use super::merge::DataMerger;
-use super::pushdown_agg_reader::{PushDownAggregateReader, PushDownAggregateStream};
+use super::pushdown_agg_reader::{
+    aggregated_by_statistics, PartialAggregateReader, PushDownAggregateReader,
+    PushDownAggregateStream,
+};
 use super::series::SeriesReader;
 use super::trace::Recorder;
 use super::{
@@ -40,6 +44,7 @@ use crate::reader::column_group::ColumnGroupReader;
 use crate::reader::filter::DataFilter;
 use crate::reader::function_register::NoRegistry;
 use crate::reader::paralle_merge::ParallelMergeAdapter;
+use crate::reader::scan_metrics::ScanMetrics;
 use crate::reader::schema_alignmenter::SchemaAlignmenter;
 use crate::reader::trace::TraceCollectorBatcherReaderProxy;
 use crate::reader::utils::group_overlapping_segments;
@@ -140,15 +145,39 @@ impl SeriesGroupBatchReaderFactory {
         // 采集读取的 series 数量
         metrics.series_nums().set(series_ids.len());
 
-        let kv_schema = &self.query_option.table_schema;
         let schema = &self.query_option.df_schema;
         let meta = self.query_option.schema_meta.clone();
+        // The aggregates reading values are computed from the merged data of the
+        // series, the data is read as if the time and the column are projected.
+        let time_column = self.query_option.table_schema.time_column();
+        let value_aggregate = self
+            .query_option
+            .aggregates
+            .as_ref()
+            .and_then(|aggs| aggs.first())
+            .filter(|agg| agg.reads_values(&time_column.name));
+        let kv_schema = match value_aggregate {
+            Some(agg) => Arc::new(project_aggregate_columns(
+                &self.query_option.table_schema,
+                agg,
+            )?),
+            None => self.query_option.table_schema.clone(),
+        };
+        let kv_schema = &kv_schema;
         // TODO 投影中一定包含 time 列，后续优化掉
-        let time_fields_schema = if self.query_option.aggregates.is_none() {
+        let time_fields_schema = if value_aggregate.is_some() {
+            project_time_fields(kv_schema, &kv_schema.to_arrow_schema(), meta)
+                .context(SchemaSnafu)?
+        } else if self.query_option.aggregates.is_none() {
             project_time_fields(kv_schema, schema, meta).context(SchemaSnafu)?
         } else {
             schema.clone()
         };
+        // The chunk readers of the aggregates computed from the statistics.
+        let chunk_aggregates = match value_aggregate {
+            Some(_) => &None,
+            None => &self.query_option.aggregates,
+        };
 
         let super_version = &self.super_version;
         let vnode_id = super_version.ts_family_id;
@@ -174,6 +203,8 @@ impl SeriesGroupBatchReaderFactory {
             }
         }
 
+        let field_values = self.field_values();
+
         // 通过sid获取serieskey
         let sid_keys = {
             let _timer = metrics.elapsed_get_series_keys_time().timer();
@@ -184,7 +215,9 @@ impl SeriesGroupBatchReaderFactory {
         let mut series_chunk_readers = Vec::with_capacity(series_ids.len());
         for (sid, series_key) in series_ids.iter().zip(sid_keys) {
             // 选择含有series的所有chunk Vec<DataReference::Chunk(chunk, reader)>
-            let mut chunks = Self::filter_chunks(&column_files_with_reader, *sid).await?;
+            let mut chunks =
+                Self::filter_chunks(&column_files_with_reader, *sid, &field_values, scan_metrics)
+                    .await?;
             // 获取所有符合条件的 memcache rowgroup Vec<DataReference::Memcache(rowgroup)>)
             chunks.append(
                 Self::filter_rowgroups(super_version.caches.clone(), *sid, time_ranges.clone())
@@ -193,7 +226,14 @@ impl SeriesGroupBatchReaderFactory {
             );
             // 按时间范围过滤chunk reader
             chunks.retain(|d| {
-                d.time_range().is_none().not() && time_ranges.overlaps(&d.time_range())
+                let retain =
+                    d.time_range().is_none().not() && time_ranges.overlaps(&d.time_range());
+                if let (false, DataReference::Chunk(chunk, ..)) = (retain, d) {
+                    scan_metrics
+                        .tsm_blocks_pruned_by_time_range()
+                        .add(chunk.column_group().len());
+                }
+                retain
             });
             series_chunk_readers.push((series_key, chunks));
         }
@@ -215,7 +255,7 @@ impl SeriesGroupBatchReaderFactory {
                     schema.clone(),
                     time_fields_schema.clone(),
                     &metrics,
-                    &self.query_option.aggregates,
+                    chunk_aggregates,
                 )
                 .transpose()
             })
@@ -231,7 +271,17 @@ impl SeriesGroupBatchReaderFactory {
         // TODO 可配置
         let readers = series_readers
             .chunks((series_readers.len() + num_cpus::get()) / num_cpus::get())
-            .map(|readers| Arc::new(CombinedBatchReader::new(readers.to_vec())) as BatchReaderRef)
+            .map(|readers| {
+                let reader = Arc::new(CombinedBatchReader::new(readers.to_vec()));
+                match value_aggregate {
+                    Some(agg) => Arc::new(PartialAggregateReader::new(
+                        schema.clone(),
+                        agg.clone(),
+                        reader,
+                    )) as BatchReaderRef,
+                    None => reader as BatchReaderRef,
+                }
+            })
             .collect::<Vec<_>>();
         let reader = Arc::new(ParallelMergeAdapter::try_new(
             schema.clone(),
@@ -284,10 +334,24 @@ impl SeriesGroupBatchReaderFactory {
         Ok(sid_keys)
     }
 
+    /// The values of the equality predicates on the indexed string fields.
+    fn field_values(&self) -> Vec<(ColumnId, Vec<Vec<u8>>)> {
+        let domains = match self.query_option.split.fields_filter().domains() {
+            Some(domains) => domains,
+            None => return vec![],
+        };
+        domains
+            .iter()
+            .filter_map(|(column_id, domain)| Some((*column_id, utf8_exact_values(domain)?)))
+            .collect()
+    }
+
     /// 从给定的文件列表中选择含有指定series的所有chunk及其对应的TsmReader
     async fn filter_chunks(
         column_files: &[(Arc<ColumnFile>, Arc<TsmReader>)],
         sid: SeriesId,
+        field_values: &[(ColumnId, Vec<Vec<u8>>)],
+        scan_metrics: &ScanMetrics,
     ) -> TskvResult<Vec<DataReference>> {
         // 选择含有series的所有文件
         let mut files = Vec::new();
@@ -302,6 +366,22 @@ impl SeriesGroupBatchReaderFactory {
             let chunk = reader.chunk().get(&sid);
             match chunk {
                 None => continue,
+                Some(chunk)
+                    if !field_values.is_empty()
+                        && reader.field_index().prune_series(
+                            chunk.table_name(),
+                            sid,
+                            field_values,
+                        ) =>
+                {
+                    scan_metrics
+                        .tsm_blocks_pruned_by_bloom_filter()
+                        .add(chunk.column_group().len());
+                    debug!(
+                        "Pruned series {sid} of file {} by the index of fields",
+                        reader.file_id()
+                    );
+                }
                 Some(chunk) => {
                     chunks.push(DataReference::Chunk(
                         chunk.clone(),
@@ -352,11 +432,7 @@ impl SeriesGroupBatchReaderFactory {
         aggregates: &Option<Vec<PushedAggregateFunction>>,
     ) -> TskvResult<Option<BatchReaderRef>> {
         if let Some(aggregates) = aggregates {
-            Ok(Some(Arc::new(PushDownAggregateReader::try_new(
-                self.schema(),
-                aggregates[0].clone(),
-                chunk,
-            )?)))
+            self.build_aggregate_chunk_reader(chunk, batch_size, predicate, &aggregates[0])
         } else {
             let chunk_reader: Option<BatchReaderRef> = match chunk {
                 DataReference::Chunk(chunk, reader, _) => {
@@ -369,6 +445,10 @@ impl SeriesGroupBatchReaderFactory {
                     let cgs = filter_column_groups(cgs, predicate, chunk_schema.clone())?;
                     debug!("Filtered column group nums: {}", cgs.len());
                     metrics.filtered_column_group_nums().add(cgs.len());
+                    self.query_option
+                        .scan_metrics
+                        .tsm_blocks_pruned_by_time_range()
+                        .add(chunk.column_group().len() - cgs.len());
 
                     let batch_readers = cgs
                         .into_iter()
@@ -381,6 +461,7 @@ impl SeriesGroupBatchReaderFactory {
                                 chunk_schema.metadata().clone(),
                                 batch_size,
                                 self.column_group_reader_metrics_set.clone(),
+                                self.query_option.scan_metrics.clone(),
                             )?;
                             Ok(Arc::new(column_group_reader) as BatchReaderRef)
                         })
@@ -413,6 +494,60 @@ impl SeriesGroupBatchReaderFactory {
         }
     }
 
+    /// The column groups of the chunk in the time ranges of the query are
+    /// aggregated from the statistics, the ones partly in the time ranges or
+    /// with deleted data are decoded and filtered.
+    fn build_aggregate_chunk_reader(
+        &self,
+        chunk: DataReference,
+        batch_size: usize,
+        predicate: &Option<Arc<Predicate>>,
+        aggregate: &PushedAggregateFunction,
+    ) -> TskvResult<Option<BatchReaderRef>> {
+        let time_ranges = self.query_option.split.time_ranges();
+        let mut readers = Vec::new();
+        if let DataReference::Chunk(chunk, reader, _) = &chunk {
+            let projection = aggregate_projection(&self.query_option.table_schema, aggregate)?;
+            let chunk_schema = chunk.schema_with_metadata(self.query_option.schema_meta.clone());
+            for cg in chunk.column_group().values().filter(|cg| {
+                time_ranges.overlaps(cg.time_range())
+                    && !aggregated_by_statistics(reader, chunk.series_id(), cg, &time_ranges)
+            }) {
+                let column_group_reader = ColumnGroupReader::try_new(
+                    reader.clone(),
+                    chunk.series_id(),
+                    cg.clone(),
+                    &projection,
+                    chunk_schema.metadata().clone(),
+                    batch_size,
+                    self.column_group_reader_metrics_set.clone(),
+                    self.query_option.scan_metrics.clone(),
+                )?;
+                let mut reader: BatchReaderRef = Arc::new(column_group_reader);
+                if let Some(predicate) = predicate {
+                    reader = Arc::new(DataFilter::new(
+                        predicate.clone(),
+                        reader,
+                        self.filter_reader_metrics_set.clone(),
+                    ));
+                }
+                readers.push(Arc::new(PartialAggregateReader::new(
+                    self.schema(),
+                    aggregate.clone(),
+                    reader,
+                )) as BatchReaderRef);
+            }
+        }
+        readers.push(Arc::new(PushDownAggregateReader::try_new(
+            self.schema(),
+            aggregate.clone(),
+            chunk,
+            time_ranges,
+        )?));
+
+        Ok(Some(Arc::new(CombinedBatchReader::new(readers))))
+    }
+
     fn build_chunk_readers(
         &self,
         chunks: Vec<DataReference>,
@@ -573,6 +708,58 @@ impl Drop for SeriesGroupBatchReaderFactory {
     }
 }
 
+/// The values of the domain if it only contains some utf8 values.
+fn utf8_exact_values(domain: &domain::Domain) -> Option<Vec<Vec<u8>>> {
+    domain
+        .exact_values()?
+        .into_iter()
+        .map(|v| match v {
+            ScalarValue::Utf8(Some(v)) => Some(v.as_bytes().to_vec()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The ids of the time column and the column read by the aggregate.
+fn aggregate_projection(
+    table_schema: &TskvTableSchema,
+    aggregate: &PushedAggregateFunction,
+) -> TskvResult<Vec<ColumnId>> {
+    let time_column_id = table_schema.time_column().id;
+    let column = table_schema.column(aggregate.column()).ok_or_else(|| {
+        CommonSnafu {
+            reason: format!("column of {:?} not found", aggregate),
+        }
+        .build()
+    })?;
+    if column.id == time_column_id {
+        Ok(vec![time_column_id])
+    } else {
+        Ok(vec![time_column_id, column.id])
+    }
+}
+
+/// The schema of the time column and the column read by the aggregate.
+fn project_aggregate_columns(
+    table_schema: &TskvTableSchema,
+    aggregate: &PushedAggregateFunction,
+) -> TskvResult<TskvTableSchema> {
+    let column = table_schema.column(aggregate.column()).ok_or_else(|| {
+        CommonSnafu {
+            reason: format!("column of {:?} not found", aggregate),
+        }
+        .build()
+    })?;
+    let columns = vec![table_schema.time_column(), column.clone()];
+
+    Ok(TskvTableSchema::new(
+        table_schema.tenant.clone(),
+        table_schema.db.clone(),
+        table_schema.name.clone(),
+        columns,
+    ))
+}
+
 /// Extracts columns from the provided table schema and schema reference, excluding tag columns.
 /// Returns a new schema reference containing the extracted columns.
 ///
@@ -718,6 +905,8 @@ pub struct QueryOption {
     pub table_schema: TskvTableSchemaRef,
     pub schema_meta: HashMap<String, String>,
     pub aggregates: Option<Vec<PushedAggregateFunction>>, // TODO: Use PushedAggregateFunction
+    /// Storage level counters of the scan, not sent to the remote nodes.
+    pub scan_metrics: ScanMetrics,
 }
 
 impl QueryOption {
@@ -737,9 +926,15 @@ impl QueryOption {
             df_schema,
             table_schema,
             schema_meta,
+            scan_metrics: ScanMetrics::default(),
         }
     }
 
+    pub fn with_scan_metrics(mut self, scan_metrics: ScanMetrics) -> Self {
+        self.scan_metrics = scan_metrics;
+        self
+    }
+
     pub fn tenant_name(&self) -> &str {
         &self.table_schema.tenant
     }
@@ -768,6 +963,8 @@ impl QueryOption {
             args: args_bytes,
             expr: expr_bytes,
             aggs: aggs_bytes,
+            checksum: false,
+            scan_metrics: false,
         })
     }
 }
@@ -894,15 +1091,7 @@ pub async fn execute(
         .await;
     }
 
-    if query_option.aggregates.is_some() {
-        Ok(Box::pin(PushDownAggregateStream {
-            schema,
-            num_count: 0,
-            is_get: false,
-        }))
-    } else {
-        Ok(Box::pin(EmptySchemableTskvRecordBatchStream::new(schema)))
-    }
+    Ok(empty_stream(schema, &query_option.aggregates))
 }
 
 async fn build_stream(
@@ -946,17 +1135,10 @@ async fn build_stream(
     ));
 
     if series_ids.is_empty() {
-        if query_option.aggregates.is_some() {
-            return Ok(Box::pin(PushDownAggregateStream {
-                schema: query_option.df_schema.clone(),
-                num_count: 0,
-                is_get: false,
-            }));
-        } else {
-            return Ok(Box::pin(EmptySchemableTskvRecordBatchStream::new(
-                query_option.df_schema.clone(),
-            )));
-        }
+        return Ok(empty_stream(
+            query_option.df_schema.clone(),
+            &query_option.aggregates,
+        ));
     }
 
     let factory = SeriesGroupBatchReaderFactory::new(
@@ -978,16 +1160,21 @@ async fn build_stream(
         return Ok(Box::pin(reader.process()?));
     }
 
-    if query_option.aggregates.is_some() {
-        Ok(Box::pin(PushDownAggregateStream {
-            schema: factory.schema(),
-            num_count: 0,
+    Ok(empty_stream(factory.schema(), &query_option.aggregates))
+}
+
+/// The stream of a vnode without the data to read, the pushed count is 0.
+fn empty_stream(
+    schema: SchemaRef,
+    aggregates: &Option<Vec<PushedAggregateFunction>>,
+) -> SendableTskvRecordBatchStream {
+    match aggregates.as_deref() {
+        Some([PushedAggregateFunction::Count(_), ..]) => Box::pin(PushDownAggregateStream {
+            schema,
+            value: ScalarValue::Int64(Some(0)),
             is_get: false,
-        }))
-    } else {
-        Ok(Box::pin(EmptySchemableTskvRecordBatchStream::new(
-            factory.schema(),
-        )))
+        }),
+        _ => Box::pin(EmptySchemableTskvRecordBatchStream::new(schema)),
     }
 }
 
@@ -50,6 +50,7 @@ mod visitor;
 
 mod array_builder;
 pub mod query_executor;
+pub mod scan_metrics;
 pub mod serialize;
 pub mod sort_merge;
 pub mod table_scan;
@@ -1,11 +1,17 @@
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use arrow::datatypes::{Schema, SchemaRef};
-use arrow_array::{Int64Array, RecordBatch};
-use futures::Stream;
-use models::predicate::domain::PushedAggregateFunction;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use arrow_array::{new_empty_array, Array, ArrayRef, BooleanArray, RecordBatch, UInt32Array};
+use datafusion::physical_expr::expressions::{MaxAccumulator, MinAccumulator};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use futures::{Stream, StreamExt};
+use models::predicate::domain::{PushedAggregateFunction, TimeRanges};
+use models::SeriesId;
 use parking_lot::RwLock;
 use snafu::ResultExt;
 
@@ -13,32 +19,52 @@ use super::{
     BatchReader, BatchReaderRef, DataReference, SchemableTskvRecordBatchStream,
     SendableSchemableTskvRecordBatchStream,
 };
-use crate::error::ArrowSnafu;
+use crate::error::{ArrowSnafu, CommonSnafu};
 use crate::mem_cache::series_data::SeriesData;
 use crate::tsm::chunk::Chunk;
+use crate::tsm::column_group::ColumnGroup;
+use crate::tsm::reader::TsmReader;
 use crate::TskvResult;
 
+/// Computes `Count`, or `Min` and `Max` of the time column, from the statistics
+/// of the column groups of a chunk in the queried time ranges, or from the rows
+/// of a memcache in its time ranges. The column groups with data deleted by the
+/// tombstone are not counted, see [`aggregated_by_statistics`].
 pub struct PushDownAggregateReader {
     df_schema: Arc<Schema>,
     aggregate: PushedAggregateFunction,
     chunk: DataReference,
+    /// The column groups of the chunk partly in the time ranges, or with data
+    /// deleted, are decoded and aggregated by [`PartialAggregateReader`].
+    time_ranges: Arc<TimeRanges>,
 }
 impl PushDownAggregateReader {
     pub fn try_new(
         df_schema: Arc<Schema>,
         aggregates: PushedAggregateFunction,
         chunk: DataReference,
+        time_ranges: Arc<TimeRanges>,
     ) -> TskvResult<Self> {
         Ok(Self {
             df_schema,
             aggregate: aggregates,
             chunk,
+            time_ranges,
         })
     }
 
-    fn get_rows_number_by_column_name_chunk(&self, chunk: &Arc<Chunk>, col_name: &str) -> i64 {
+    fn get_rows_number_by_column_name_chunk(
+        &self,
+        chunk: &Arc<Chunk>,
+        reader: &TsmReader,
+        col_name: &str,
+    ) -> i64 {
         let mut count: i64 = 0;
-        for cg in chunk.column_group().values().collect::<Vec<_>>() {
+        for cg in chunk
+            .column_group()
+            .values()
+            .filter(|cg| aggregated_by_statistics(reader, chunk.series_id(), cg, &self.time_ranges))
+        {
             cg.pages().iter().for_each(|page| {
                 if page.meta().column.name == col_name {
                     count += page.meta().num_values as i64;
@@ -51,15 +77,21 @@ impl PushDownAggregateReader {
     fn get_rows_number_by_column_name_memcache(
         &self,
         series_data: &Arc<RwLock<SeriesData>>,
+        time_ranges: &TimeRanges,
         col_name: &str,
     ) -> i64 {
         let mut count: i64 = 0;
         series_data.read().groups.iter().for_each(|group| {
             if let Some(index) = group.schema.column_index(col_name) {
+                let rows = group
+                    .rows
+                    .get_ref_rows()
+                    .iter()
+                    .filter(|row| time_ranges.contains(row.ts));
                 if index == 0 {
-                    count += group.rows.get_ref_rows().len() as i64;
+                    count += rows.count() as i64;
                 } else {
-                    for row in group.rows.get_ref_rows() {
+                    for row in rows {
                         if let Some(col_value) = row.fields.get(index - group.schema.tag_num() - 1)
                         {
                             if col_value.is_some() {
@@ -72,28 +104,115 @@ impl PushDownAggregateReader {
         });
         count
     }
+
+    /// The min or the max time of the column groups of the chunk in the time
+    /// ranges.
+    fn get_time_bound_chunk(
+        &self,
+        chunk: &Arc<Chunk>,
+        reader: &TsmReader,
+        min: bool,
+    ) -> Option<i64> {
+        let bounds = chunk
+            .column_group()
+            .values()
+            .filter(|cg| aggregated_by_statistics(reader, chunk.series_id(), cg, &self.time_ranges))
+            .map(|cg| cg.time_range())
+            .map(|tr| if min { tr.min_ts } else { tr.max_ts });
+        if min {
+            bounds.min()
+        } else {
+            bounds.max()
+        }
+    }
+
+    fn get_time_bound_memcache(
+        &self,
+        series_data: &Arc<RwLock<SeriesData>>,
+        time_ranges: &TimeRanges,
+        min: bool,
+    ) -> Option<i64> {
+        let series_data = series_data.read();
+        let times = series_data.groups.iter().flat_map(|group| {
+            group
+                .rows
+                .get_ref_rows()
+                .iter()
+                .map(|row| row.ts)
+                .filter(|ts| time_ranges.contains(*ts))
+        });
+        if min {
+            times.min()
+        } else {
+            times.max()
+        }
+    }
+
+    /// The value of the aggregate of the chunk, of the type of the output.
+    fn aggregate_value(&self) -> TskvResult<ScalarValue> {
+        let value = match (&self.aggregate, &self.chunk) {
+            (PushedAggregateFunction::Count(col_name), DataReference::Chunk(chunk, reader, _)) => {
+                ScalarValue::Int64(Some(
+                    self.get_rows_number_by_column_name_chunk(chunk, reader, col_name),
+                ))
+            }
+            (
+                PushedAggregateFunction::Count(col_name),
+                DataReference::Memcache(series_data, time_ranges, _),
+            ) => ScalarValue::Int64(Some(self.get_rows_number_by_column_name_memcache(
+                series_data,
+                time_ranges,
+                col_name,
+            ))),
+            (
+                PushedAggregateFunction::Min(_) | PushedAggregateFunction::Max(_),
+                DataReference::Chunk(chunk, reader, _),
+            ) => {
+                let min = matches!(self.aggregate, PushedAggregateFunction::Min(_));
+                ScalarValue::Int64(self.get_time_bound_chunk(chunk, reader, min))
+            }
+            (
+                PushedAggregateFunction::Min(_) | PushedAggregateFunction::Max(_),
+                DataReference::Memcache(series_data, time_ranges, _),
+            ) => {
+                let min = matches!(self.aggregate, PushedAggregateFunction::Min(_));
+                ScalarValue::Int64(self.get_time_bound_memcache(series_data, time_ranges, min))
+            }
+            (agg, _) => {
+                return CommonSnafu {
+                    reason: format!("{:?} is not computed from the chunk statistics", agg),
+                }
+                .fail()
+            }
+        };
+
+        cast_scalar(value, &output_type(&self.aggregate, &self.df_schema)?)
+    }
+}
+
+/// Whether the aggregate of the column group of the series is computed from
+/// its statistics, which are not updated when the data is deleted by the
+/// tombstone, so only the column groups in the time ranges without deleted data
+/// are.
+pub fn aggregated_by_statistics(
+    reader: &TsmReader,
+    series_id: SeriesId,
+    column_group: &ColumnGroup,
+    time_ranges: &TimeRanges,
+) -> bool {
+    time_ranges.includes(column_group.time_range())
+        && !reader
+            .tombstone()
+            .overlaps_series_time_range(series_id, column_group.time_range())
 }
 
 impl BatchReader for PushDownAggregateReader {
     fn process(&self) -> TskvResult<SendableSchemableTskvRecordBatchStream> {
-        match &self.aggregate {
-            PushedAggregateFunction::Count(col_name) => {
-                let num_count = match &self.chunk {
-                    DataReference::Chunk(chunk, ..) => {
-                        self.get_rows_number_by_column_name_chunk(chunk, col_name.as_str())
-                    }
-                    DataReference::Memcache(series_data, ..) => {
-                        self.get_rows_number_by_column_name_memcache(series_data, col_name)
-                    }
-                };
-
-                Ok(Box::pin(PushDownAggregateStream {
-                    schema: self.df_schema.clone(),
-                    num_count,
-                    is_get: false,
-                }))
-            }
-        }
+        Ok(Box::pin(PushDownAggregateStream {
+            schema: self.df_schema.clone(),
+            value: self.aggregate_value()?,
+            is_get: false,
+        }))
     }
 
     fn fmt_as(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -107,7 +226,7 @@ impl BatchReader for PushDownAggregateReader {
 
 pub struct PushDownAggregateStream {
     pub schema: SchemaRef,
-    pub num_count: i64,
+    pub value: ScalarValue,
     pub is_get: bool,
 }
 
@@ -123,9 +242,8 @@ impl PushDownAggregateStream {
 
         if !self.is_get {
             self.is_get = true;
-            let num_count = Int64Array::from(vec![self.num_count]);
             Poll::Ready(Some(
-                RecordBatch::try_new(schema, vec![Arc::new(num_count)]).context(ArrowSnafu),
+                RecordBatch::try_new(schema, vec![self.value.to_array()]).context(ArrowSnafu),
             ))
         } else {
             Poll::Ready(None)
@@ -139,3 +257,553 @@ impl Stream for PushDownAggregateStream {
         self.poll_inner(cx)
     }
 }
+
+/// Computes the partial state of an aggregate reading the values of a column
+/// from the merged data of the series, the state is output in one batch of
+/// the schema of the pushed aggregate.
+pub struct PartialAggregateReader {
+    df_schema: SchemaRef,
+    aggregate: PushedAggregateFunction,
+    input: BatchReaderRef,
+}
+
+impl PartialAggregateReader {
+    pub fn new(
+        df_schema: SchemaRef,
+        aggregate: PushedAggregateFunction,
+        input: BatchReaderRef,
+    ) -> Self {
+        Self {
+            df_schema,
+            aggregate,
+            input,
+        }
+    }
+}
+
+impl BatchReader for PartialAggregateReader {
+    fn process(&self) -> TskvResult<SendableSchemableTskvRecordBatchStream> {
+        let state = PartialAggregateState::try_new(&self.aggregate, &self.df_schema)?;
+        Ok(Box::pin(PartialAggregateStream {
+            schema: self.df_schema.clone(),
+            column: self.aggregate.column().to_string(),
+            input: self.input.process()?,
+            state: Some(state),
+        }))
+    }
+
+    fn fmt_as(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "PartialAggregateReader: aggregate={:?}", self.aggregate)
+    }
+
+    fn children(&self) -> Vec<BatchReaderRef> {
+        vec![self.input.clone()]
+    }
+}
+
+enum PartialAggregateState {
+    Count(i64),
+    BoolAnd(Option<bool>),
+    BoolOr(Option<bool>),
+    Distinct(DataType, HashSet<ScalarValue>),
+    Mode(DataType, HashMap<ScalarValue, u32>),
+    Min(DataType, MinAccumulator),
+    Max(DataType, MaxAccumulator),
+}
+
+impl PartialAggregateState {
+    fn try_new(aggregate: &PushedAggregateFunction, schema: &SchemaRef) -> TskvResult<Self> {
+        let value_type = || output_type(aggregate, schema);
+        let accumulator_error = |e| {
+            CommonSnafu {
+                reason: format!("create accumulator of {:?}: {}", aggregate, e),
+            }
+            .build()
+        };
+
+        Ok(match aggregate {
+            PushedAggregateFunction::Count(_) => Self::Count(0),
+            PushedAggregateFunction::BoolAnd(_) => Self::BoolAnd(None),
+            PushedAggregateFunction::BoolOr(_) => Self::BoolOr(None),
+            PushedAggregateFunction::CountDistinct(_) => {
+                Self::Distinct(value_type()?, HashSet::new())
+            }
+            PushedAggregateFunction::Mode(_) => Self::Mode(value_type()?, HashMap::new()),
+            PushedAggregateFunction::Min(_) => {
+                let value_type = value_type()?;
+                let accumulator =
+                    MinAccumulator::try_new(&value_type).map_err(accumulator_error)?;
+                Self::Min(value_type, accumulator)
+            }
+            PushedAggregateFunction::Max(_) => {
+                let value_type = value_type()?;
+                let accumulator =
+                    MaxAccumulator::try_new(&value_type).map_err(accumulator_error)?;
+                Self::Max(value_type, accumulator)
+            }
+        })
+    }
+
+    fn update(&mut self, values: &ArrayRef) -> TskvResult<()> {
+        match self {
+            Self::Count(count) => *count += (values.len() - values.null_count()) as i64,
+            Self::BoolAnd(state) => fold_booleans(state, values.as_ref(), |acc, v| acc && v)?,
+            Self::BoolOr(state) => fold_booleans(state, values.as_ref(), |acc, v| acc || v)?,
+            Self::Distinct(_, set) => {
+                for i in (0..values.len()).filter(|i| values.is_valid(*i)) {
+                    set.insert(scalar_value(values.as_ref(), i)?);
+                }
+            }
+            Self::Mode(_, counts) => {
+                for i in (0..values.len()).filter(|i| values.is_valid(*i)) {
+                    *counts.entry(scalar_value(values.as_ref(), i)?).or_insert(0) += 1;
+                }
+            }
+            Self::Min(value_type, accumulator) => {
+                let values = cast(values, value_type).context(ArrowSnafu)?;
+                accumulator.update_batch(&[values]).map_err(|e| {
+                    CommonSnafu {
+                        reason: format!("update min of partial aggregate: {}", e),
+                    }
+                    .build()
+                })?
+            }
+            Self::Max(value_type, accumulator) => {
+                let values = cast(values, value_type).context(ArrowSnafu)?;
+                accumulator.update_batch(&[values]).map_err(|e| {
+                    CommonSnafu {
+                        reason: format!("update max of partial aggregate: {}", e),
+                    }
+                    .build()
+                })?
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self, schema: SchemaRef) -> TskvResult<RecordBatch> {
+        let columns: Vec<ArrayRef> = match self {
+            Self::Count(count) => vec![ScalarValue::Int64(Some(count)).to_array()],
+            Self::BoolAnd(state) | Self::BoolOr(state) => {
+                vec![Arc::new(BooleanArray::from(vec![state]))]
+            }
+            Self::Distinct(value_type, set) => vec![values_to_array(&value_type, set)?],
+            Self::Mode(value_type, counts) => {
+                let (values, counts): (Vec<_>, Vec<_>) = counts.into_iter().unzip();
+                vec![
+                    values_to_array(&value_type, values)?,
+                    Arc::new(UInt32Array::from(counts)),
+                ]
+            }
+            Self::Min(_, accumulator) => vec![evaluate(&accumulator)?.to_array()],
+            Self::Max(_, accumulator) => vec![evaluate(&accumulator)?.to_array()],
+        };
+
+        RecordBatch::try_new(schema, columns).context(ArrowSnafu)
+    }
+}
+
+fn evaluate(accumulator: &dyn Accumulator) -> TskvResult<ScalarValue> {
+    accumulator.evaluate().map_err(|e| {
+        CommonSnafu {
+            reason: format!("evaluate partial aggregate: {}", e),
+        }
+        .build()
+    })
+}
+
+/// The type of the first output column of the aggregate.
+fn output_type(aggregate: &PushedAggregateFunction, schema: &SchemaRef) -> TskvResult<DataType> {
+    schema
+        .fields()
+        .first()
+        .map(|f| f.data_type().clone())
+        .ok_or_else(|| {
+            CommonSnafu {
+                reason: format!("no output column of {:?}", aggregate),
+            }
+            .build()
+        })
+}
+
+fn cast_scalar(value: ScalarValue, data_type: &DataType) -> TskvResult<ScalarValue> {
+    if &value.get_datatype() == data_type {
+        return Ok(value);
+    }
+    let array = cast(&value.to_array(), data_type).context(ArrowSnafu)?;
+    scalar_value(array.as_ref(), 0)
+}
+
+fn fold_booleans(
+    state: &mut Option<bool>,
+    values: &dyn Array,
+    f: impl Fn(bool, bool) -> bool,
+) -> TskvResult<()> {
+    let values = values
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| {
+            CommonSnafu {
+                reason: format!("expect boolean values, got {}", values.data_type()),
+            }
+            .build()
+        })?;
+    for value in values.iter().flatten() {
+        *state = Some(state.map_or(value, |acc| f(acc, value)));
+    }
+
+    Ok(())
+}
+
+fn scalar_value(values: &dyn Array, index: usize) -> TskvResult<ScalarValue> {
+    ScalarValue::try_from_array(values, index).map_err(|e| {
+        CommonSnafu {
+            reason: format!("read value of partial aggregate: {}", e),
+        }
+        .build()
+    })
+}
+
+fn values_to_array(
+    value_type: &DataType,
+    values: impl IntoIterator<Item = ScalarValue>,
+) -> TskvResult<ArrayRef> {
+    let mut values = values.into_iter().peekable();
+    if values.peek().is_none() {
+        return Ok(new_empty_array(value_type));
+    }
+    ScalarValue::iter_to_array(values).map_err(|e| {
+        CommonSnafu {
+            reason: format!("build values of partial aggregate: {}", e),
+        }
+        .build()
+    })
+}
+
+pub struct PartialAggregateStream {
+    schema: SchemaRef,
+    column: String,
+    input: SendableSchemableTskvRecordBatchStream,
+    /// Taken when the state is output.
+    state: Option<PartialAggregateState>,
+}
+
+impl SchemableTskvRecordBatchStream for PartialAggregateStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for PartialAggregateStream {
+    type Item = TskvResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let Some(state) = this.state.as_mut() else {
+                return Poll::Ready(None);
+            };
+            match this.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => {
+                    // The column is missing in the data written before it's added.
+                    if let Some(values) = batch.column_by_name(&this.column) {
+                        if let Err(e) = state.update(values) {
+                            this.state = None;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    this.state = None;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Ready(None) => {
+                    let state = this.state.take().expect("state is checked");
+                    return Poll::Ready(Some(state.finish(this.schema.clone())));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+    use arrow_array::{
+        Array, BooleanArray, Int64Array, RecordBatch, StringArray, TimestampNanosecondArray,
+        UInt32Array,
+    };
+    use datafusion::assert_batches_eq;
+    use datafusion::scalar::ScalarValue;
+    use futures::TryStreamExt;
+    use models::codec::Encoding;
+    use models::predicate::domain::{PushedAggregateFunction, TimeRange, TimeRanges};
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::{SeriesKey, ValueType};
+
+    use super::{aggregated_by_statistics, PartialAggregateReader, PushDownAggregateReader};
+    use crate::reader::{BatchReader, DataReference, MemoryBatchReader};
+    use crate::tsfamily::column_file::ColumnFile;
+    use crate::tsm::reader::TsmReader;
+    use crate::tsm::writer::TsmWriter;
+
+    fn input_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("time", DataType::Int64, true),
+            Field::new("s", DataType::Utf8, true),
+            Field::new("b", DataType::Boolean, true),
+        ]))
+    }
+
+    fn input_reader() -> Arc<MemoryBatchReader> {
+        let batch = |times: Vec<i64>, strings: Vec<Option<&str>>, booleans: Vec<Option<bool>>| {
+            RecordBatch::try_new(
+                input_schema(),
+                vec![
+                    Arc::new(Int64Array::from(times)),
+                    Arc::new(StringArray::from(strings)),
+                    Arc::new(BooleanArray::from(booleans)),
+                ],
+            )
+            .unwrap()
+        };
+        let batches = vec![
+            batch(
+                vec![1, 2, 3],
+                vec![Some("a"), Some("b"), None],
+                vec![Some(true), None, Some(true)],
+            ),
+            batch(
+                vec![4, 5],
+                vec![Some("b"), Some("b")],
+                vec![Some(false), None],
+            ),
+        ];
+
+        Arc::new(MemoryBatchReader::new(input_schema(), batches))
+    }
+
+    async fn partial_aggregate(
+        aggregate: PushedAggregateFunction,
+        fields: Vec<Field>,
+    ) -> Vec<RecordBatch> {
+        let schema = Arc::new(Schema::new(fields));
+        let reader = PartialAggregateReader::new(schema, aggregate, input_reader());
+        reader.process().unwrap().try_collect().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_partial_bool_aggregate() {
+        let field = Field::new("b", DataType::Boolean, true);
+        let result = partial_aggregate(
+            PushedAggregateFunction::BoolAnd("b".into()),
+            vec![field.clone()],
+        )
+        .await;
+        assert_batches_eq!(
+            [
+                "+-------+",
+                "| b     |",
+                "+-------+",
+                "| false |",
+                "+-------+"
+            ],
+            &result
+        );
+
+        let result =
+            partial_aggregate(PushedAggregateFunction::BoolOr("b".into()), vec![field]).await;
+        assert_batches_eq!(
+            ["+------+", "| b    |", "+------+", "| true |", "+------+"],
+            &result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partial_mode() {
+        let result = partial_aggregate(
+            PushedAggregateFunction::Mode("s".into()),
+            vec![
+                Field::new("s", DataType::Utf8, true),
+                Field::new("s_count", DataType::UInt32, true),
+            ],
+        )
+        .await;
+        assert_eq!(result.len(), 1);
+
+        let values = result[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let counts = result[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        let mut counted = values
+            .iter()
+            .zip(counts.iter())
+            .map(|(v, c)| (v.unwrap().to_string(), c.unwrap()))
+            .collect::<Vec<_>>();
+        counted.sort();
+        assert_eq!(counted, vec![("a".to_string(), 1), ("b".to_string(), 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_partial_count_distinct() {
+        let result = partial_aggregate(
+            PushedAggregateFunction::CountDistinct("s".into()),
+            vec![Field::new("s", DataType::Utf8, true)],
+        )
+        .await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].num_rows(), 2);
+        assert_eq!(result[0].column(0).null_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_partial_min_max() {
+        let field = Field::new("s", DataType::Utf8, true);
+        let result = partial_aggregate(
+            PushedAggregateFunction::Min("s".into()),
+            vec![field.clone()],
+        )
+        .await;
+        assert_batches_eq!(["+---+", "| s |", "+---+", "| a |", "+---+"], &result);
+
+        let result = partial_aggregate(PushedAggregateFunction::Max("s".into()), vec![field]).await;
+        assert_batches_eq!(["+---+", "| s |", "+---+", "| b |", "+---+"], &result);
+    }
+
+    /// A tsm file of the series 1, with the column groups of [1, 3] and [10, 12].
+    async fn write_tsm(dir: &std::path::Path) -> Arc<TsmReader> {
+        let schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "air".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new(
+                    1,
+                    "f1".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::default(),
+                ),
+            ],
+        ));
+        let mut writer = TsmWriter::open(&dir, 1, 0, false, Encoding::Null)
+            .await
+            .unwrap();
+        for times in [vec![1, 2, 3], vec![10, 11, 12]] {
+            let batch = RecordBatch::try_new(
+                schema.to_record_data_schema(),
+                vec![
+                    Arc::new(TimestampNanosecondArray::from(times.clone())),
+                    Arc::new(Int64Array::from(times)),
+                ],
+            )
+            .unwrap();
+            writer
+                .write_record_batch(1, SeriesKey::default(), schema.clone(), batch)
+                .await
+                .unwrap();
+        }
+        writer.finish().await.unwrap();
+
+        Arc::new(TsmReader::open(writer.path()).await.unwrap())
+    }
+
+    async fn pushed_down(
+        reader: &Arc<TsmReader>,
+        aggregate: PushedAggregateFunction,
+        time_ranges: TimeRanges,
+    ) -> ScalarValue {
+        let chunk = reader.chunk()[&1].clone();
+        let file = Arc::new(ColumnFile::new(
+            1,
+            1,
+            *chunk.time_range(),
+            0,
+            std::path::PathBuf::new(),
+        ));
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, true)]));
+        let reader = PushDownAggregateReader::try_new(
+            schema,
+            aggregate,
+            DataReference::Chunk(chunk, reader.clone(), file),
+            Arc::new(time_ranges),
+        )
+        .unwrap();
+        let batches: Vec<RecordBatch> = reader.process().unwrap().try_collect().await.unwrap();
+        ScalarValue::try_from_array(batches[0].column(0), 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pushed_down_time_aggregates() {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = write_tsm(dir.path()).await;
+        let all = || TimeRanges::new(vec![TimeRange::new(0, 100)]);
+        let min = || PushedAggregateFunction::Min("time".into());
+        let max = || PushedAggregateFunction::Max("time".into());
+        let count = || PushedAggregateFunction::Count("time".into());
+
+        assert_eq!(
+            pushed_down(&reader, min(), all()).await,
+            ScalarValue::Int64(Some(1))
+        );
+        assert_eq!(
+            pushed_down(&reader, max(), all()).await,
+            ScalarValue::Int64(Some(12))
+        );
+        assert_eq!(
+            pushed_down(&reader, count(), all()).await,
+            ScalarValue::Int64(Some(6))
+        );
+
+        // The column group partly in the time ranges is decoded instead.
+        let filtered = || TimeRanges::new(vec![TimeRange::new(2, 100)]);
+        assert_eq!(
+            pushed_down(&reader, count(), filtered()).await,
+            ScalarValue::Int64(Some(3))
+        );
+        assert_eq!(
+            pushed_down(&reader, min(), filtered()).await,
+            ScalarValue::Int64(Some(10))
+        );
+        let chunk = reader.chunk()[&1].clone();
+        let decoded = chunk
+            .column_group()
+            .values()
+            .filter(|cg| !aggregated_by_statistics(&reader, 1, cg, &filtered()))
+            .map(|cg| *cg.time_range())
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, vec![TimeRange::new(1, 3)]);
+
+        // The statistics of the column group with deleted data are not used.
+        reader
+            .tombstone()
+            .add_range(&[(1, 1)], TimeRange::new(11, 11), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            pushed_down(&reader, max(), all()).await,
+            ScalarValue::Int64(Some(3))
+        );
+        assert_eq!(
+            pushed_down(&reader, count(), all()).await,
+            ScalarValue::Int64(Some(3))
+        );
+        let decoded = chunk
+            .column_group()
+            .values()
+            .filter(|cg| !aggregated_by_statistics(&reader, 1, cg, &all()))
+            .map(|cg| *cg.time_range())
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, vec![TimeRange::new(10, 12)]);
+    }
+}
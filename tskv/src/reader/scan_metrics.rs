@@ -0,0 +1,132 @@
+use datafusion::physical_plan::metrics::{Count, ExecutionPlanMetricsSet, MetricBuilder};
+use serde::{Deserialize, Serialize};
+use snafu::IntoError;
+
+use crate::error::{DecodeSnafu, EncodeSnafu};
+use crate::TskvResult;
+
+/// Storage level counters of a table scan, shared by the readers of all the
+/// vnodes scanned for a partition of the query. Registered into the metrics of
+/// the scan plan node, so that they are shown by `EXPLAIN ANALYZE`.
+#[derive(Debug, Clone, Default)]
+pub struct ScanMetrics {
+    /// Tsm blocks (column groups) read.
+    tsm_blocks_read: Count,
+    /// Tsm blocks skipped by the time range and the statistics of the filter.
+    tsm_blocks_pruned_by_time_range: Count,
+    /// Tsm blocks skipped by the bloom filter of tags or the index of fields.
+    tsm_blocks_pruned_by_bloom_filter: Count,
+    /// Rows of the read tsm blocks removed by tombstones.
+    tombstone_filtered_rows: Count,
+}
+
+impl ScanMetrics {
+    pub fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        Self {
+            tsm_blocks_read: MetricBuilder::new(metrics).counter("tsm_blocks_read", partition),
+            tsm_blocks_pruned_by_time_range: MetricBuilder::new(metrics)
+                .counter("tsm_blocks_pruned_by_time_range", partition),
+            tsm_blocks_pruned_by_bloom_filter: MetricBuilder::new(metrics)
+                .counter("tsm_blocks_pruned_by_bloom_filter", partition),
+            tombstone_filtered_rows: MetricBuilder::new(metrics)
+                .counter("tombstone_filtered_rows", partition),
+        }
+    }
+
+    pub fn tsm_blocks_read(&self) -> &Count {
+        &self.tsm_blocks_read
+    }
+
+    pub fn tsm_blocks_pruned_by_time_range(&self) -> &Count {
+        &self.tsm_blocks_pruned_by_time_range
+    }
+
+    pub fn tsm_blocks_pruned_by_bloom_filter(&self) -> &Count {
+        &self.tsm_blocks_pruned_by_bloom_filter
+    }
+
+    pub fn tombstone_filtered_rows(&self) -> &Count {
+        &self.tombstone_filtered_rows
+    }
+
+    /// The current values of the counters, to be sent back to the querying node.
+    pub fn values(&self) -> ScanMetricsValues {
+        ScanMetricsValues {
+            tsm_blocks_read: self.tsm_blocks_read.value() as u64,
+            tsm_blocks_pruned_by_time_range: self.tsm_blocks_pruned_by_time_range.value() as u64,
+            tsm_blocks_pruned_by_bloom_filter: self.tsm_blocks_pruned_by_bloom_filter.value()
+                as u64,
+            tombstone_filtered_rows: self.tombstone_filtered_rows.value() as u64,
+        }
+    }
+
+    /// Add the counters received from a remote node.
+    pub fn add_values(&self, values: &ScanMetricsValues) {
+        self.tsm_blocks_read.add(values.tsm_blocks_read as usize);
+        self.tsm_blocks_pruned_by_time_range
+            .add(values.tsm_blocks_pruned_by_time_range as usize);
+        self.tsm_blocks_pruned_by_bloom_filter
+            .add(values.tsm_blocks_pruned_by_bloom_filter as usize);
+        self.tombstone_filtered_rows
+            .add(values.tombstone_filtered_rows as usize);
+    }
+}
+
+/// Values of [`ScanMetrics`], in the `scan_metrics` of the last response of
+/// a `QueryRecordBatch` stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanMetricsValues {
+    pub tsm_blocks_read: u64,
+    pub tsm_blocks_pruned_by_time_range: u64,
+    pub tsm_blocks_pruned_by_bloom_filter: u64,
+    pub tombstone_filtered_rows: u64,
+}
+
+impl ScanMetricsValues {
+    pub fn encode(&self) -> TskvResult<Vec<u8>> {
+        bincode::serialize(self).map_err(|e| EncodeSnafu.into_error(e))
+    }
+
+    pub fn decode(buf: &[u8]) -> TskvResult<Self> {
+        bincode::deserialize(buf).map_err(|e| DecodeSnafu.into_error(e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
+
+    use super::{ScanMetrics, ScanMetricsValues};
+
+    #[test]
+    fn test_scan_metrics() {
+        let metrics_set = ExecutionPlanMetricsSet::new();
+        let metrics = ScanMetrics::new(&metrics_set, 0);
+        metrics.tsm_blocks_read().add(3);
+        metrics.tsm_blocks_pruned_by_time_range().add(2);
+        metrics.tombstone_filtered_rows().add(10);
+
+        let remote = ScanMetricsValues {
+            tsm_blocks_read: 1,
+            tsm_blocks_pruned_by_bloom_filter: 4,
+            ..Default::default()
+        };
+        let buf = remote.encode().unwrap();
+        metrics.add_values(&ScanMetricsValues::decode(&buf).unwrap());
+
+        assert_eq!(
+            metrics.values(),
+            ScanMetricsValues {
+                tsm_blocks_read: 4,
+                tsm_blocks_pruned_by_time_range: 2,
+                tsm_blocks_pruned_by_bloom_filter: 4,
+                tombstone_filtered_rows: 10,
+            }
+        );
+        let set = metrics_set.clone_inner();
+        assert_eq!(
+            set.sum_by_name("tsm_blocks_read").map(|v| v.as_usize()),
+            Some(4)
+        );
+    }
+}
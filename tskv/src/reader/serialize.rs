@@ -8,17 +8,32 @@ use snafu::IntoError;
 use trace::Span;
 
 use crate::error::{ArrowSnafu, TskvResult};
+use crate::reader::scan_metrics::ScanMetrics;
 use crate::reader::SendableTskvRecordBatchStream;
 
 pub struct TonicRecordBatchEncoder {
     input: SendableTskvRecordBatchStream,
+    /// Set the checksum of the encoded batches.
+    checksum: bool,
+    /// Respond with the scan metrics after the last batch, taken when sent.
+    scan_metrics: Option<ScanMetrics>,
     #[allow(unused)]
     span: Span,
 }
 
 impl TonicRecordBatchEncoder {
-    pub fn new(input: SendableTskvRecordBatchStream, span: Span) -> Self {
-        Self { input, span }
+    pub fn new(input: SendableTskvRecordBatchStream, checksum: bool, span: Span) -> Self {
+        Self {
+            input,
+            checksum,
+            scan_metrics: None,
+            span,
+        }
+    }
+
+    pub fn with_scan_metrics(mut self, scan_metrics: ScanMetrics) -> Self {
+        self.scan_metrics = Some(scan_metrics);
+        self
     }
 }
 
@@ -29,16 +44,28 @@ impl Stream for TonicRecordBatchEncoder {
         match ready!(self.input.poll_next_unpin(cx)) {
             Some(Ok(batch)) => match record_batch_encode(&batch) {
                 Ok(body) => {
-                    let resp = BatchBytesResponse {
+                    let mut resp = BatchBytesResponse {
                         data: body,
                         ..Default::default()
                     };
+                    if self.checksum {
+                        resp = resp.with_checksum();
+                    }
                     Poll::Ready(Some(Ok(resp)))
                 }
                 Err(err) => Poll::Ready(Some(Err(ArrowSnafu.into_error(err)))),
             },
             Some(Err(err)) => Poll::Ready(Some(Err(err))),
-            None => Poll::Ready(None),
+            None => match self.scan_metrics.take() {
+                Some(scan_metrics) => match scan_metrics.values().encode() {
+                    Ok(buf) => Poll::Ready(Some(Ok(BatchBytesResponse {
+                        scan_metrics: Some(buf),
+                        ..Default::default()
+                    }))),
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                },
+                None => Poll::Ready(None),
+            },
         }
     }
 }
@@ -1,6 +1,7 @@
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::future::BoxFuture;
@@ -17,6 +18,9 @@ type Result<T, E = TskvError> = std::result::Result<T, E>;
 
 pub struct LocalTskvTableScanStream {
     state: StreamState,
+    kv_inst: EngineRef,
+    /// Taken when the scan is finished, to record the latency of it.
+    start: Option<Instant>,
     #[allow(unused)]
     span: Span,
 }
@@ -31,14 +35,19 @@ impl LocalTskvTableScanStream {
     ) -> Self {
         let iter_future = Box::pin(iterator::execute(
             runtime,
-            kv_inst,
+            kv_inst.clone(),
             option,
             vnode_id,
             Span::enter_with_parent("build vnode stream", &span),
         ));
         let state = StreamState::Open { iter_future };
 
-        Self { state, span }
+        Self {
+            state,
+            kv_inst,
+            start: Some(Instant::now()),
+            span,
+        }
     }
 
     fn poll_inner(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<RecordBatch>>> {
@@ -62,7 +71,13 @@ impl Stream for LocalTskvTableScanStream {
     type Item = Result<RecordBatch>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.poll_inner(cx)
+        let poll = self.poll_inner(cx);
+        if let Poll::Ready(None | Some(Err(_))) = &poll {
+            if let Some(start) = self.start.take() {
+                self.kv_inst.record_query_latency(start.elapsed());
+            }
+        }
+        poll
     }
 }
 
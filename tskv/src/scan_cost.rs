@@ -0,0 +1,70 @@
+use models::predicate::domain::TimeRanges;
+
+use crate::tsfamily::version::Version;
+
+const ENCODED_LEN: usize = 24;
+
+/// Estimated cost of scanning a table in a vnode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanCost {
+    /// Number of series matching the tag filter.
+    pub series: u64,
+    /// Number of column files overlapping the time ranges.
+    pub files: u64,
+    /// Total size in bytes of the column files overlapping the time ranges.
+    pub bytes: u64,
+}
+
+impl ScanCost {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(ENCODED_LEN);
+        buf.extend_from_slice(&self.series.to_be_bytes());
+        buf.extend_from_slice(&self.files.to_be_bytes());
+        buf.extend_from_slice(&self.bytes.to_be_bytes());
+        buf
+    }
+
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() != ENCODED_LEN {
+            return None;
+        }
+        let read_u64 = |i: usize| u64::from_be_bytes(buf[i..i + 8].try_into().unwrap());
+        Some(Self {
+            series: read_u64(0),
+            files: read_u64(8),
+            bytes: read_u64(16),
+        })
+    }
+}
+
+/// Sum up the column files of the version which overlap the time ranges.
+///
+/// A column file contains data of all tables of the vnode, so the bytes are the
+/// upper bound of the data to be read, and data in caches is not counted.
+pub fn estimate_files_cost(version: &Version, time_ranges: &TimeRanges, cost: &mut ScanCost) {
+    for level in version.levels_info().iter() {
+        for file in level.files.iter() {
+            if time_ranges.overlaps(file.time_range()) {
+                cost.files += 1;
+                cost.bytes += file.size();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ScanCost;
+
+    #[test]
+    fn test_scan_cost_codec() {
+        let cost = ScanCost {
+            series: 10,
+            files: 2,
+            bytes: 4096,
+        };
+        let buf = cost.encode();
+        assert_eq!(ScanCost::decode(&buf), Some(cost));
+        assert_eq!(ScanCost::decode(&buf[1..]), None);
+    }
+}
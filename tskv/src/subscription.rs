@@ -0,0 +1,322 @@
+//! Subscription of the writes applied to the vnodes of the engine.
+//!
+//! Each database keeps the most recent write entries in memory, the entries are
+//! published by the replica which proposed them as the leader, so each write
+//! of a replica set is published once. A subscriber starts from an offset still
+//! retained, replays the retained entries and then tails the new ones, it
+//! resumes from the offset following the last received entry.
+//!
+//! Entries are numbered by a per-database offset, the high bits of which are
+//! the epoch of the engine persisted in the storage directory and increased at
+//! each start. Offsets are never reused after a restart, a subscriber resuming
+//! from an offset of a previous epoch gets `SubscriptionOffsetOutOfRange` and
+//! starts over from the earliest retained entry.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+
+use models::meta_data::VnodeId;
+use parking_lot::{Mutex, RwLock};
+use snafu::ResultExt;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::error::{
+    CommonSnafu, ReadFileSnafu, SubscriptionLaggedSnafu, SubscriptionOffsetOutOfRangeSnafu,
+    TskvResult, WriteFileSnafu,
+};
+
+const EPOCH_FILE_NAME: &str = "subscription_epoch";
+/// Bits of the offsets numbering the entries of an epoch.
+const EPOCH_SHIFT: u32 = 40;
+
+/// Increase the epoch persisted in `dir` and return it, the first epoch is 1.
+pub fn next_epoch(dir: impl AsRef<Path>) -> TskvResult<u64> {
+    let path = dir.as_ref().join(EPOCH_FILE_NAME);
+    let epoch = match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            content.trim().parse::<u64>().map_err(|e| {
+                CommonSnafu {
+                    reason: format!("invalid subscription epoch in {}: {e}", path.display()),
+                }
+                .build()
+            })? + 1
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => 1,
+        Err(e) => return Err(e).context(ReadFileSnafu { path }),
+    };
+
+    std::fs::create_dir_all(dir.as_ref()).context(WriteFileSnafu { path: &path })?;
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, epoch.to_string()).context(WriteFileSnafu { path: &tmp_path })?;
+    std::fs::rename(&tmp_path, &path).context(WriteFileSnafu { path: &path })?;
+
+    Ok(epoch)
+}
+
+/// A write request applied to a vnode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteEntry {
+    pub offset: u64,
+    pub vnode_id: VnodeId,
+    /// Index of the raft log entry of the write.
+    pub index: u64,
+    pub precision: u32,
+    /// Points encoded by flatbuffers.
+    pub points: Vec<u8>,
+}
+
+struct DatabaseEntries {
+    next_offset: u64,
+    entries: VecDeque<Arc<WriteEntry>>,
+    sender: broadcast::Sender<Arc<WriteEntry>>,
+}
+
+impl DatabaseEntries {
+    fn new(capacity: usize, epoch: u64) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            next_offset: (epoch << EPOCH_SHIFT) + 1,
+            entries: VecDeque::with_capacity(capacity),
+            sender,
+        }
+    }
+
+    fn earliest_offset(&self) -> u64 {
+        self.entries
+            .front()
+            .map(|e| e.offset)
+            .unwrap_or(self.next_offset)
+    }
+}
+
+pub struct SubscriptionHub {
+    retained_entries: usize,
+    epoch: u64,
+    databases: RwLock<HashMap<String, Arc<Mutex<DatabaseEntries>>>>,
+}
+
+impl SubscriptionHub {
+    /// Keep `retained_entries` entries of each database, 0 means the
+    /// subscription is disabled and nothing is retained.
+    pub fn new(retained_entries: usize, epoch: u64) -> Self {
+        Self {
+            retained_entries,
+            epoch,
+            databases: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.retained_entries > 0
+    }
+
+    fn database(&self, owner: &str) -> Arc<Mutex<DatabaseEntries>> {
+        if let Some(db) = self.databases.read().get(owner) {
+            return db.clone();
+        }
+        self.databases
+            .write()
+            .entry(owner.to_string())
+            .or_insert_with(|| {
+                Arc::new(Mutex::new(DatabaseEntries::new(
+                    self.retained_entries,
+                    self.epoch,
+                )))
+            })
+            .clone()
+    }
+
+    /// Retain the write entry of the database `owner` and send it to subscribers.
+    pub fn publish(
+        &self,
+        owner: &str,
+        vnode_id: VnodeId,
+        index: u64,
+        precision: u32,
+        points: Vec<u8>,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let db = self.database(owner);
+        let mut db = db.lock();
+        let entry = Arc::new(WriteEntry {
+            offset: db.next_offset,
+            vnode_id,
+            index,
+            precision,
+            points,
+        });
+        db.next_offset += 1;
+        if db.entries.len() >= self.retained_entries {
+            db.entries.pop_front();
+        }
+        db.entries.push_back(entry.clone());
+        // No receivers is not an error.
+        let _ = db.sender.send(entry);
+    }
+
+    /// Subscribe the writes of the database `owner` from `start_offset`,
+    /// 0 means from the earliest retained entry.
+    pub fn subscribe(&self, owner: &str, start_offset: u64) -> TskvResult<WriteSubscription> {
+        if !self.is_enabled() {
+            return CommonSnafu {
+                reason: "write subscription is disabled, set storage.enable_write_subscription to enable it".to_string(),
+            }
+            .fail();
+        }
+
+        let db = self.database(owner);
+        let db = db.lock();
+
+        let earliest = db.earliest_offset();
+        let start_offset = if start_offset == 0 {
+            earliest
+        } else {
+            start_offset
+        };
+        // The offsets of other epochs or not yet written.
+        if start_offset < earliest || start_offset > db.next_offset {
+            return SubscriptionOffsetOutOfRangeSnafu {
+                owner,
+                offset: start_offset,
+                earliest,
+                next: db.next_offset,
+            }
+            .fail();
+        }
+
+        let replay = db
+            .entries
+            .iter()
+            .filter(|e| e.offset >= start_offset)
+            .cloned()
+            .collect();
+        Ok(WriteSubscription {
+            owner: owner.to_string(),
+            next_offset: start_offset,
+            replay,
+            receiver: db.sender.subscribe(),
+        })
+    }
+}
+
+pub struct WriteSubscription {
+    owner: String,
+    next_offset: u64,
+    replay: VecDeque<Arc<WriteEntry>>,
+    receiver: broadcast::Receiver<Arc<WriteEntry>>,
+}
+
+impl WriteSubscription {
+    /// Wait for the next write entry, returns `None` if the engine is closed.
+    pub async fn next(&mut self) -> TskvResult<Option<Arc<WriteEntry>>> {
+        if let Some(entry) = self.replay.pop_front() {
+            self.next_offset = entry.offset + 1;
+            return Ok(Some(entry));
+        }
+
+        loop {
+            match self.receiver.recv().await {
+                Ok(entry) => {
+                    if entry.offset < self.next_offset {
+                        continue;
+                    }
+                    self.next_offset = entry.offset + 1;
+                    return Ok(Some(entry));
+                }
+                Err(RecvError::Lagged(_)) => {
+                    return SubscriptionLaggedSnafu {
+                        owner: self.owner.clone(),
+                        offset: self.next_offset,
+                    }
+                    .fail();
+                }
+                Err(RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{next_epoch, SubscriptionHub, EPOCH_SHIFT};
+    use crate::error::TskvError;
+
+    #[tokio::test]
+    async fn test_subscribe_and_resume() {
+        let hub = SubscriptionHub::new(2, 0);
+        hub.publish("cnosdb.db", 1, 10, 0, b"a".to_vec());
+        hub.publish("cnosdb.db", 1, 11, 0, b"b".to_vec());
+        hub.publish("cnosdb.db", 2, 5, 0, b"c".to_vec());
+
+        // Offset 1 is evicted.
+        assert!(matches!(
+            hub.subscribe("cnosdb.db", 1),
+            Err(TskvError::SubscriptionOffsetOutOfRange { earliest: 2, .. })
+        ));
+        // Offset 5 is not written yet.
+        assert!(matches!(
+            hub.subscribe("cnosdb.db", 5),
+            Err(TskvError::SubscriptionOffsetOutOfRange { next: 4, .. })
+        ));
+
+        let mut sub = hub.subscribe("cnosdb.db", 0).unwrap();
+        let entry = sub.next().await.unwrap().unwrap();
+        assert_eq!(
+            (entry.offset, entry.points.as_slice()),
+            (2, b"b".as_slice())
+        );
+        let entry = sub.next().await.unwrap().unwrap();
+        assert_eq!((entry.offset, entry.vnode_id), (3, 2));
+
+        hub.publish("cnosdb.db", 1, 12, 0, b"d".to_vec());
+        let entry = sub.next().await.unwrap().unwrap();
+        assert_eq!((entry.offset, entry.index), (4, 12));
+
+        let mut sub = hub.subscribe("cnosdb.db", 4).unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap().offset, 4);
+        // Waits for the next write.
+        assert!(hub.subscribe("cnosdb.db", 5).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_lagged() {
+        let hub = SubscriptionHub::new(1, 0);
+        let mut sub = hub.subscribe("cnosdb.db", 0).unwrap();
+        hub.publish("cnosdb.db", 1, 1, 0, b"a".to_vec());
+        hub.publish("cnosdb.db", 1, 2, 0, b"b".to_vec());
+        assert!(matches!(
+            sub.next().await,
+            Err(TskvError::SubscriptionLagged { offset: 1, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(next_epoch(dir.path()).unwrap(), 1);
+        let hub = SubscriptionHub::new(2, 1);
+        hub.publish("cnosdb.db", 1, 1, 0, b"a".to_vec());
+        let offset = hub.subscribe("cnosdb.db", 0).unwrap().next_offset;
+        assert_eq!(offset, (1 << EPOCH_SHIFT) + 1);
+
+        // The offsets of the previous epoch are not reused.
+        let epoch = next_epoch(dir.path()).unwrap();
+        assert_eq!(epoch, 2);
+        let hub = SubscriptionHub::new(2, epoch);
+        hub.publish("cnosdb.db", 1, 2, 0, b"b".to_vec());
+        assert!(matches!(
+            hub.subscribe("cnosdb.db", offset),
+            Err(TskvError::SubscriptionOffsetOutOfRange { .. })
+        ));
+        let mut sub = hub.subscribe("cnosdb.db", 0).unwrap();
+        assert_eq!(
+            sub.next().await.unwrap().unwrap().offset,
+            (2 << EPOCH_SHIFT) + 1
+        );
+    }
+}
@@ -24,6 +24,7 @@ use crate::error::{
     TskvResult,
 };
 use crate::file_system::async_filesystem::LocalFileSystem;
+use crate::file_system::FileSystem;
 use crate::kv_option::{Options, StorageOptions, DELTA_PATH, TSM_PATH};
 use crate::mem_cache::memcache::MemCache;
 use crate::record_file::{Reader, RecordDataType, RecordDataVersion, Writer};
@@ -31,6 +32,7 @@ use crate::tsfamily::column_file::ColumnFile;
 use crate::tsfamily::level_info::LevelInfo;
 use crate::tsfamily::tseries_family::TseriesFamily;
 use crate::tsfamily::version::Version;
+use crate::tsm::TOMBSTONE_FILE_SUFFIX;
 use crate::version_set::VersionSet;
 use crate::{byte_utils, file_utils, ColumnFileId, LevelId, VnodeId};
 
@@ -159,6 +161,11 @@ impl CompactMeta {
 
         trace::info!("rename file from {:?} to {:?}", &old_name, &new_name);
         file_utils::rename(&old_name, &new_name).await?;
+        let old_tombstone = old_name.with_extension(TOMBSTONE_FILE_SUFFIX);
+        if LocalFileSystem::try_exists(&old_tombstone) {
+            let new_tombstone = new_name.with_extension(TOMBSTONE_FILE_SUFFIX);
+            file_utils::rename(&old_tombstone, &new_tombstone).await?;
+        }
 
         Ok(new_name)
     }
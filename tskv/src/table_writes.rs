@@ -0,0 +1,134 @@
+//! The raft logs writing the tables of a vnode, to tell whether the data of a
+//! table copied from another replica is still the latest when a repair of it
+//! is applied.
+
+use std::collections::HashMap;
+
+use models::predicate::domain::TimeRange;
+
+/// Max time ranges tracked for a table, the closest ones are merged beyond it.
+const MAX_TRACKED_RANGES: usize = 32;
+
+/// The index of the latest raft log writing each time range of the tables, the
+/// time ranges are merged so the indexes may be later than the real ones.
+#[derive(Debug, Default)]
+pub struct TableWrites {
+    /// The raft logs up to the index are not tracked, they were replayed from
+    /// the wal or installed by a snapshot. None if nothing is applied yet.
+    untracked: Option<u64>,
+    tables: HashMap<String, Vec<(TimeRange, u64)>>,
+}
+
+impl TableWrites {
+    /// The raft logs up to `index` are applied without being tracked.
+    pub fn untrack(&mut self, index: u64) {
+        self.untracked = Some(index);
+        self.tables.clear();
+    }
+
+    /// The raft log of `index` is being applied, it's the first tracked one if
+    /// nothing is applied yet.
+    pub fn apply(&mut self, index: u64) {
+        if self.untracked.is_none() {
+            self.untracked = Some(index.saturating_sub(1));
+        }
+    }
+
+    /// The raft log of `index` writes the time range of the table.
+    pub fn record(&mut self, index: u64, table: &str, time_range: TimeRange) {
+        let ranges = self.tables.entry(table.to_string()).or_default();
+        let mut merged = time_range;
+        ranges.retain(|(range, _)| {
+            if range.overlaps(&merged) {
+                merged.merge(range);
+                false
+            } else {
+                true
+            }
+        });
+        ranges.push((merged, index));
+
+        if ranges.len() > MAX_TRACKED_RANGES {
+            ranges.sort_by_key(|(range, _)| range.min_ts);
+            let closest = (1..ranges.len())
+                .min_by_key(|i| {
+                    (ranges[*i].0.min_ts as i128 - ranges[*i - 1].0.max_ts as i128).max(0)
+                })
+                .unwrap_or(1);
+            let (range, index) = ranges.remove(closest);
+            let prev = &mut ranges[closest - 1];
+            prev.0.merge(&range);
+            prev.1 = prev.1.max(index);
+        }
+    }
+
+    /// The index of the latest raft log writing the time range of the table,
+    /// None if it's unknown.
+    pub fn last_write(&self, table: &str, time_range: &TimeRange) -> Option<u64> {
+        let untracked = self.untracked?;
+        let last = self
+            .tables
+            .get(table)
+            .into_iter()
+            .flatten()
+            .filter(|(range, _)| range.overlaps(time_range))
+            .map(|(_, index)| *index)
+            .fold(untracked, u64::max);
+
+        Some(last)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use models::predicate::domain::TimeRange;
+
+    use super::{TableWrites, MAX_TRACKED_RANGES};
+
+    #[test]
+    fn test_table_writes() {
+        let mut writes = TableWrites::default();
+        assert_eq!(writes.last_write("air", &TimeRange::all()), None);
+
+        writes.apply(10);
+        assert_eq!(writes.last_write("air", &TimeRange::all()), Some(9));
+        writes.record(10, "air", TimeRange::new(0, 99));
+        writes.apply(11);
+        writes.record(11, "air", TimeRange::new(200, 299));
+        writes.apply(12);
+        writes.record(12, "sea", TimeRange::new(0, 99));
+
+        assert_eq!(writes.last_write("air", &TimeRange::new(0, 9)), Some(10));
+        assert_eq!(writes.last_write("air", &TimeRange::new(100, 199)), Some(9));
+        assert_eq!(
+            writes.last_write("air", &TimeRange::new(150, 250)),
+            Some(11)
+        );
+        assert_eq!(writes.last_write("sea", &TimeRange::new(200, 299)), Some(9));
+
+        // The overlapped time ranges are merged.
+        writes.record(13, "air", TimeRange::new(50, 249));
+        assert_eq!(
+            writes.last_write("air", &TimeRange::new(290, 299)),
+            Some(13)
+        );
+
+        writes.untrack(20);
+        assert_eq!(writes.last_write("air", &TimeRange::new(0, 9)), Some(20));
+    }
+
+    #[test]
+    fn test_table_writes_merge_closest() {
+        let mut writes = TableWrites::default();
+        writes.apply(1);
+        for i in 0..MAX_TRACKED_RANGES as i64 {
+            writes.record(i as u64 + 1, "air", TimeRange::new(i * 100, i * 100 + 9));
+        }
+        // Merged with the closest range, [0, 9].
+        writes.record(100, "air", TimeRange::new(11, 12));
+        assert_eq!(writes.tables["air"].len(), MAX_TRACKED_RANGES);
+        assert_eq!(writes.last_write("air", &TimeRange::new(5, 5)), Some(100));
+        assert_eq!(writes.last_write("air", &TimeRange::new(10, 10)), Some(100));
+        assert_eq!(writes.last_write("air", &TimeRange::new(100, 100)), Some(2));
+    }
+}
@@ -0,0 +1,546 @@
+//! Tiering of the cold tsm files to an object storage.
+//!
+//! A tsm file with all data older than `storage.tiering_age` is uploaded to
+//! `{prefix}/{node_id}/{owner}/{vnode_id}/` of the object storage and replaced
+//! by a stub file `_{file_id}.remote` in the same directory. The stub keeps the
+//! location of the object and a copy of the tail of the file, i.e. the index of
+//! the chunks and the footer, so opening a tiered file reads nothing remote and
+//! only the pages scanned are downloaded.
+//!
+//! The path of a tiered file in the version is unchanged. Stubs never leave the
+//! node, vnode snapshots and backups read the whole tiered files from the object
+//! storage, so the object of a file is referenced only by the node uploaded it.
+//! It's deleted with the file, or when the file is restored to the local disk.
+
+use std::io;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use models::meta_data::{NodeId, VnodeId};
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use trace::{error, info};
+
+use crate::error::{CommonSnafu, FileSystemSnafu, TskvResult};
+use crate::file_system::async_filesystem::{LocalFileSystem, LocalFileType};
+use crate::file_system::file::stream_reader::FileStreamReader;
+use crate::file_system::file::ReadableFile;
+use crate::file_system::FileSystem;
+use crate::tsm::footer::Footer;
+use crate::tsm::FOOTER_SIZE;
+use crate::TskvError;
+
+/// Extension of the stub of a tiered tsm file.
+pub const REMOTE_FILE_SUFFIX: &str = "remote";
+
+static TIERED_STORAGE: OnceCell<Arc<TieredStorage>> = OnceCell::new();
+
+/// The object storage of the tiered tsm files of this node.
+pub struct TieredStorage {
+    node_id: NodeId,
+    store: Arc<dyn ObjectStore>,
+    prefix: ObjectStorePath,
+}
+
+impl TieredStorage {
+    pub fn new(node_id: NodeId, store: Arc<dyn ObjectStore>, prefix: ObjectStorePath) -> Self {
+        Self {
+            node_id,
+            store,
+            prefix,
+        }
+    }
+
+    /// Use the storage for the tiered files of this process, returns false if
+    /// a storage is installed already.
+    pub fn install(self) -> bool {
+        TIERED_STORAGE.set(Arc::new(self)).is_ok()
+    }
+
+    pub fn get() -> Option<Arc<TieredStorage>> {
+        TIERED_STORAGE.get().cloned()
+    }
+
+    /// Upload the tsm file at `path` to `{prefix}/{node_id}/{owner}/{vnode_id}/`
+    /// and replace it by a stub, returns the size of the file.
+    pub async fn tier_file(&self, path: &Path, owner: &str, vnode_id: VnodeId) -> TskvResult<u64> {
+        let file_name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return Err(tiering_error(path.display(), "invalid file path")),
+        };
+        let location = self
+            .prefix
+            .child(self.node_id.to_string())
+            .child(owner)
+            .child(vnode_id.to_string())
+            .child(file_name);
+
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| tiering_error(path.display(), e))?;
+        let (file_size, tail_offset, tail) = read_tail(&mut file)
+            .await
+            .map_err(|e| tiering_error(path.display(), e))?;
+        self.upload(&mut file, &location).await?;
+
+        let remote = RemoteTsmFile {
+            node_id: self.node_id,
+            location: location.to_string(),
+            file_size,
+            tail_offset,
+            tail,
+        };
+        remote.save(&remote_file_path(path)).await?;
+        tokio::fs::remove_file(path)
+            .await
+            .map_err(|e| tiering_error(path.display(), e))?;
+
+        info!("Tiered tsm file '{}' to {location}", path.display());
+        Ok(file_size)
+    }
+
+    async fn upload(
+        &self,
+        file: &mut tokio::fs::File,
+        location: &ObjectStorePath,
+    ) -> TskvResult<()> {
+        file.seek(SeekFrom::Start(0))
+            .await
+            .map_err(|e| tiering_error(location, e))?;
+        let (multipart_id, mut writer) = self
+            .store
+            .put_multipart(location)
+            .await
+            .map_err(|e| tiering_error(location, e))?;
+        let result = async {
+            tokio::io::copy(file, &mut writer).await?;
+            writer.shutdown().await
+        }
+        .await;
+        if let Err(e) = result {
+            let _ = self.store.abort_multipart(location, &multipart_id).await;
+            return Err(tiering_error(location, e));
+        }
+
+        Ok(())
+    }
+
+    /// Download the tiered tsm file back to `path` and remove its stub, does
+    /// nothing if the file is not tiered.
+    pub async fn restore_file(&self, path: &Path) -> TskvResult<()> {
+        let stub_path = remote_file_path(path);
+        if !LocalFileSystem::try_exists(&stub_path) {
+            return Ok(());
+        }
+        let remote = RemoteTsmFile::load(&stub_path).await?;
+        let location = ObjectStorePath::from(remote.location.as_str());
+
+        let tmp_path = tmp_file_path(path);
+        let mut file = tokio::fs::File::create(&tmp_path)
+            .await
+            .map_err(|e| tiering_error(tmp_path.display(), e))?;
+        let mut stream = self
+            .store
+            .get(&location)
+            .await
+            .map_err(|e| tiering_error(&location, e))?
+            .into_stream();
+        while let Some(bytes) = stream.next().await {
+            let bytes = bytes.map_err(|e| tiering_error(&location, e))?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|e| tiering_error(tmp_path.display(), e))?;
+        }
+        file.sync_all()
+            .await
+            .map_err(|e| tiering_error(tmp_path.display(), e))?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .map_err(|e| tiering_error(path.display(), e))?;
+        tokio::fs::remove_file(&stub_path)
+            .await
+            .map_err(|e| tiering_error(stub_path.display(), e))?;
+        if remote.node_id == self.node_id {
+            if let Err(e) = self.store.delete(&location).await {
+                error!("Failed to delete tiered tsm file {location}: {e}");
+            }
+        }
+
+        info!(
+            "Restored tiered tsm file '{}' from {location}",
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Open the tiered tsm file at `path` by its stub.
+    async fn open_file(&self, path: &Path) -> TskvResult<Box<FileStreamReader>> {
+        let remote = RemoteTsmFile::load(&remote_file_path(path)).await?;
+        let file = RemoteFile {
+            store: self.store.clone(),
+            location: ObjectStorePath::from(remote.location.as_str()),
+            remote,
+        };
+
+        Ok(Box::new(FileStreamReader::new(
+            Box::new(file),
+            path.to_path_buf(),
+        )))
+    }
+
+    fn remove_object(self: Arc<Self>, remote: RemoteTsmFile) {
+        if remote.node_id != self.node_id {
+            return;
+        }
+        tokio::spawn(async move {
+            let location = ObjectStorePath::from(remote.location.as_str());
+            match self.store.delete(&location).await {
+                Ok(_) => info!("Removed tiered tsm file {location}"),
+                Err(e) => error!("Failed to remove tiered tsm file {location}: {e}"),
+            }
+        });
+    }
+}
+
+/// The stub of a tiered tsm file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct RemoteTsmFile {
+    node_id: NodeId,
+    location: String,
+    file_size: u64,
+    /// Offset of the tail in the file, the tail is the index and the footer.
+    tail_offset: u64,
+    tail: Vec<u8>,
+}
+
+impl RemoteTsmFile {
+    async fn save(&self, path: &Path) -> TskvResult<()> {
+        let buf = bincode::serialize(self).map_err(|e| tiering_error(path.display(), e))?;
+        let tmp_path = tmp_file_path(path);
+        let mut file = tokio::fs::File::create(&tmp_path)
+            .await
+            .map_err(|e| tiering_error(tmp_path.display(), e))?;
+        file.write_all(&buf)
+            .await
+            .map_err(|e| tiering_error(tmp_path.display(), e))?;
+        file.sync_all()
+            .await
+            .map_err(|e| tiering_error(tmp_path.display(), e))?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .map_err(|e| tiering_error(path.display(), e))
+    }
+
+    async fn load(path: &Path) -> TskvResult<Self> {
+        let buf = tokio::fs::read(path)
+            .await
+            .map_err(|e| tiering_error(path.display(), e))?;
+        bincode::deserialize(&buf).map_err(|e| tiering_error(path.display(), e))
+    }
+}
+
+/// A tiered tsm file, reads in the tail are served by the stub.
+struct RemoteFile {
+    store: Arc<dyn ObjectStore>,
+    location: ObjectStorePath,
+    remote: RemoteTsmFile,
+}
+
+#[async_trait]
+impl ReadableFile for RemoteFile {
+    async fn read_at(&self, pos: usize, data: &mut [u8]) -> io::Result<usize> {
+        let file_size = self.remote.file_size as usize;
+        if pos >= file_size {
+            return Ok(0);
+        }
+        let end = file_size.min(pos + data.len());
+        let tail_offset = self.remote.tail_offset as usize;
+        if pos >= tail_offset {
+            data[..end - pos]
+                .copy_from_slice(&self.remote.tail[pos - tail_offset..end - tail_offset]);
+        } else {
+            let bytes = self
+                .store
+                .get_range(&self.location, pos..end)
+                .await
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            data[..bytes.len()].copy_from_slice(&bytes);
+            return Ok(bytes.len());
+        }
+
+        Ok(end - pos)
+    }
+
+    fn file_size(&self) -> usize {
+        self.remote.file_size as usize
+    }
+}
+
+/// Path of the stub of the tsm file at `path`.
+pub fn remote_file_path(path: &Path) -> PathBuf {
+    path.with_extension(REMOTE_FILE_SUFFIX)
+}
+
+pub fn is_tiered(path: &Path) -> bool {
+    LocalFileSystem::try_exists(remote_file_path(path))
+}
+
+/// Open the tsm file at `path` from the object storage if it is tiered.
+pub(crate) async fn open_tiered_file(path: &Path) -> TskvResult<Option<Box<FileStreamReader>>> {
+    if LocalFileSystem::try_exists(path) || !is_tiered(path) {
+        return Ok(None);
+    }
+    let Some(storage) = TieredStorage::get() else {
+        return Err(tiering_error(
+            path.display(),
+            "the file is tiered but 'storage.tiering_path' is not configured",
+        ));
+    };
+
+    storage.open_file(path).await.map(Some)
+}
+
+/// Open the file at `path` to read it as a whole, e.g. to send it to the other
+/// nodes, a tiered tsm file is read from the object storage.
+pub async fn open_file(path: &Path) -> TskvResult<Box<FileStreamReader>> {
+    if let Some(reader) = open_tiered_file(path).await? {
+        return Ok(reader);
+    }
+
+    LocalFileSystem::new(LocalFileType::ThreadPool)
+        .open_file_reader(path)
+        .await
+        .context(FileSystemSnafu)
+}
+
+/// Remove the stub of the tiered tsm file at `path` and the object of the file,
+/// returns false if the file is not tiered.
+pub(crate) fn remove_tiered_file(path: &Path) -> TskvResult<bool> {
+    let stub_path = remote_file_path(path);
+    if !LocalFileSystem::try_exists(&stub_path) {
+        return Ok(false);
+    }
+    let buf = std::fs::read(&stub_path).map_err(|e| tiering_error(stub_path.display(), e))?;
+    let remote: RemoteTsmFile =
+        bincode::deserialize(&buf).map_err(|e| tiering_error(stub_path.display(), e))?;
+    std::fs::remove_file(&stub_path).map_err(|e| tiering_error(stub_path.display(), e))?;
+    if let Some(storage) = TieredStorage::get() {
+        storage.remove_object(remote);
+    }
+
+    Ok(true)
+}
+
+/// Read the size of the tsm file, the offset of its tail and the tail.
+async fn read_tail(file: &mut tokio::fs::File) -> io::Result<(u64, u64, Vec<u8>)> {
+    let file_size = file.metadata().await?.len();
+    if file_size < FOOTER_SIZE as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file is too small",
+        ));
+    }
+    let mut footer = vec![0_u8; FOOTER_SIZE];
+    file.seek(SeekFrom::Start(file_size - FOOTER_SIZE as u64))
+        .await?;
+    file.read_exact(&mut footer).await?;
+    let tail_offset = Footer::deserialize(&footer)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+        .series()
+        .chunk_offset();
+    if tail_offset > file_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid offset of the index",
+        ));
+    }
+
+    let mut tail = vec![0_u8; (file_size - tail_offset) as usize];
+    file.seek(SeekFrom::Start(tail_offset)).await?;
+    file.read_exact(&mut tail).await?;
+    Ok((file_size, tail_offset, tail))
+}
+
+fn tmp_file_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+fn tiering_error(target: impl std::fmt::Display, e: impl std::fmt::Display) -> TskvError {
+    CommonSnafu {
+        reason: format!("failed to tier '{target}': {e}"),
+    }
+    .build()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use arrow::datatypes::TimeUnit;
+    use arrow_array::RecordBatch;
+    use bytes::Bytes;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::{SeriesKey, ValueType};
+    use object_store::memory::InMemory;
+    use object_store::path::Path as ObjectStorePath;
+    use object_store::ObjectStore;
+
+    use super::{remote_file_path, RemoteFile, RemoteTsmFile, TieredStorage};
+    use crate::file_system::file::ReadableFile;
+    use crate::tsm::writer::test::{i64_column, ts_column};
+    use crate::tsm::writer::TsmWriter;
+
+    /// Write a tsm file to `dir`, returns its path.
+    async fn write_tsm_file(dir: &Path) -> PathBuf {
+        let schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "air".to_string(),
+            vec![
+                TableColumn::new(
+                    0,
+                    "time".to_string(),
+                    ColumnType::Time(TimeUnit::Nanosecond),
+                    Encoding::default(),
+                ),
+                TableColumn::new(
+                    1,
+                    "f1".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::default(),
+                ),
+            ],
+        ));
+        let data = RecordBatch::try_new(
+            schema.to_record_data_schema(),
+            vec![
+                ts_column((0..1000).collect()),
+                i64_column((0..1000).collect()),
+            ],
+        )
+        .unwrap();
+        let mut writer = TsmWriter::open(&dir, 1, 0, false, Encoding::Null)
+            .await
+            .unwrap();
+        writer
+            .write_record_batch(1, SeriesKey::default(), schema, data)
+            .await
+            .unwrap();
+        writer.finish().await.unwrap();
+
+        writer.path().to_path_buf()
+    }
+
+    async fn read_all(storage: &TieredStorage, path: &Path) -> Vec<u8> {
+        let reader = storage.open_file(path).await.unwrap();
+        let mut data = vec![0_u8; reader.len()];
+        let mut pos = 0;
+        while pos < data.len() {
+            pos += reader.read_at(pos, &mut data[pos..]).await.unwrap();
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_tier_and_restore_file() {
+        let dir = Path::new("/tmp/test/tskv/tiering/test_tier_and_restore_file");
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+        let path = write_tsm_file(dir).await;
+        let data = std::fs::read(&path).unwrap();
+
+        let store = Arc::new(InMemory::new());
+        let storage = TieredStorage::new(1, store.clone(), ObjectStorePath::from("tiering"));
+        let size = storage.tier_file(&path, "cnosdb.public", 3).await.unwrap();
+        assert_eq!(size, data.len() as u64);
+        assert!(!path.exists());
+        assert!(remote_file_path(&path).exists());
+        let location = ObjectStorePath::from(format!(
+            "tiering/1/cnosdb.public/3/{}",
+            path.file_name().unwrap().to_string_lossy()
+        ));
+        assert!(store.head(&location).await.is_ok());
+
+        // The whole file is read by the stub, e.g. sent by a vnode snapshot.
+        assert_eq!(read_all(&storage, &path).await, data);
+
+        // A node restoring the file of another node keeps the object.
+        let other = TieredStorage::new(2, store.clone(), ObjectStorePath::from("tiering"));
+        let stub = std::fs::read(remote_file_path(&path)).unwrap();
+        other.restore_file(&path).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        assert!(!remote_file_path(&path).exists());
+        assert!(store.head(&location).await.is_ok());
+
+        // The node uploaded the file deletes the object after restoring it.
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(remote_file_path(&path), stub).unwrap();
+        storage.restore_file(&path).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        assert!(store.head(&location).await.is_err());
+
+        // Restoring a file not tiered does nothing.
+        storage.restore_file(&path).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_stub_save_load() {
+        let dir = Path::new("/tmp/test/tskv/tiering/test_stub_save_load");
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+
+        let remote = RemoteTsmFile {
+            node_id: 1,
+            location: "tiering/1/cnosdb.public/3/_000001.tsm".to_string(),
+            file_size: 100,
+            tail_offset: 90,
+            tail: vec![1; 10],
+        };
+        let path = dir.join("_000001.remote");
+        remote.save(&path).await.unwrap();
+        assert_eq!(RemoteTsmFile::load(&path).await.unwrap(), remote);
+    }
+
+    #[tokio::test]
+    async fn test_remote_file_read() {
+        let data = (0..100_u8).collect::<Vec<_>>();
+        let store = Arc::new(InMemory::new());
+        let location = ObjectStorePath::from("tiering/_000001.tsm");
+        // The tail is not in the object storage, so reads of it must not be remote.
+        let file = RemoteFile {
+            store: store.clone(),
+            location: location.clone(),
+            remote: RemoteTsmFile {
+                node_id: 1,
+                location: location.to_string(),
+                file_size: 100,
+                tail_offset: 90,
+                tail: data[90..].to_vec(),
+            },
+        };
+        store
+            .put(&location, Bytes::from(data[..90].to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(file.file_size(), 100);
+
+        let mut buf = vec![0_u8; 10];
+        assert_eq!(file.read_at(10, &mut buf).await.unwrap(), 10);
+        assert_eq!(buf, data[10..20]);
+        assert_eq!(file.read_at(92, &mut buf).await.unwrap(), 8);
+        assert_eq!(buf[..8], data[92..]);
+        assert_eq!(file.read_at(100, &mut buf).await.unwrap(), 0);
+    }
+}
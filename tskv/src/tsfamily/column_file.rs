@@ -5,6 +5,7 @@ use std::sync::{Arc, Weak};
 
 use cache::{AsyncCache, ShardedAsyncCache};
 use models::codec::Encoding;
+use models::meta_data::VnodeId;
 use models::predicate::domain::TimeRange;
 use models::{FieldId, SeriesId, SeriesKey};
 use snafu::ResultExt;
@@ -12,10 +13,11 @@ use tokio::sync::{RwLock as AsyncRwLock, RwLockWriteGuard as AsyncRwLockWriteGua
 use trace::{debug, error, info};
 use utils::BloomFilter;
 
-use crate::error::{FileSystemSnafu, TskvResult};
+use crate::error::{CommonSnafu, FileSystemSnafu, TskvResult};
 use crate::file_system::async_filesystem::{LocalFileSystem, LocalFileType};
 use crate::file_system::FileSystem;
 use crate::summary::CompactMeta;
+use crate::tiering::{self, TieredStorage};
 use crate::tsm::reader::TsmReader;
 use crate::tsm::tombstone::tombstone_compact_tmp_path;
 use crate::tsm::writer::TsmWriter;
@@ -85,6 +87,26 @@ impl ColumnFile {
         path
     }
 
+    /// Whether the file is moved to the tiered storage.
+    pub fn is_tiered(&self) -> bool {
+        tiering::is_tiered(&self.path)
+    }
+
+    /// Move the file to the tiered storage, returns the size of the file.
+    pub async fn tier(
+        &self,
+        storage: &TieredStorage,
+        owner: &str,
+        vnode_id: VnodeId,
+    ) -> TskvResult<u64> {
+        let size = storage.tier_file(&self.path, owner, vnode_id).await?;
+        // Readers opened later read from the tiered storage.
+        if let Some(cache) = self.tsm_reader_cache.upgrade() {
+            cache.remove(&self.path.display().to_string()).await;
+        }
+        Ok(size)
+    }
+
     pub fn overlap(&self, time_range: &TimeRange) -> bool {
         self.time_range.overlaps(time_range)
     }
@@ -165,6 +187,23 @@ impl ColumnFile {
             return Ok(None);
         };
         meta.update_tag_value(series)?;
+        if self.is_tiered() {
+            match TieredStorage::get() {
+                Some(storage) => storage.restore_file(&self.path).await?,
+                None => {
+                    return Err(CommonSnafu {
+                        reason: format!(
+                            "tsm file '{}' is tiered but the tiered storage is not configured",
+                            self.path.display()
+                        ),
+                    }
+                    .build())
+                }
+            }
+            if let Some(cache) = self.tsm_reader_cache.upgrade() {
+                cache.remove(&self.path.display().to_string()).await;
+            }
+        }
         let local_file_system = LocalFileSystem::new(LocalFileType::ThreadPool);
         let writer = local_file_system
             .open_file_writer(&self.path, 1024)
@@ -224,14 +263,28 @@ impl Drop for ColumnFile {
                     cache.remove(&k).await;
                 });
             }
-            if let Err(e) = std::fs::remove_file(path) {
-                error!(
-                    "Failed to remove tsm file {} at '{}': {e}",
+            match tiering::remove_tiered_file(path) {
+                Ok(true) => info!(
+                    "Removed tiered tsm file {} at '{}'",
+                    self.file_id,
+                    path.display()
+                ),
+                Ok(false) => {
+                    if let Err(e) = std::fs::remove_file(path) {
+                        error!(
+                            "Failed to remove tsm file {} at '{}': {e}",
+                            self.file_id,
+                            path.display()
+                        );
+                    } else {
+                        info!("Removed tsm file {} at '{}", self.file_id, path.display());
+                    }
+                }
+                Err(e) => error!(
+                    "Failed to remove tiered tsm file {} at '{}': {e}",
                     self.file_id,
                     path.display()
-                );
-            } else {
-                info!("Removed tsm file {} at '{}", self.file_id, path.display());
+                ),
             }
 
             let tombstone_path = self.tombstone_path();
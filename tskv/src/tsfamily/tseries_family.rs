@@ -12,7 +12,7 @@ use models::meta_data::VnodeStatus;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::schema::database_schema::{split_owner, DatabaseConfig};
 use models::{ColumnId, SeriesId, SeriesKey};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use snafu::ResultExt;
 use tokio::sync::RwLock as TokioRwLock;
 use tokio::time::Instant;
@@ -26,11 +26,14 @@ use super::version::Version;
 use crate::compaction::metrics::FlushMetrics;
 use crate::context::GlobalContext;
 use crate::error::{CommonSnafu, IndexErrSnafu, TskvResult};
+use crate::file_system::open_files;
 use crate::index::ts_index::TSIndex;
 use crate::kv_option::StorageOptions;
+use crate::mem_cache::dedup_window::WriteDedupWindow;
 use crate::mem_cache::memcache::MemCache;
 use crate::mem_cache::series_data::RowGroup;
 use crate::summary::{CompactMeta, VersionEdit};
+use crate::warmup::WarmupPermit;
 use crate::{ColumnFileId, Options, VnodeId};
 
 #[derive(Debug)]
@@ -104,6 +107,8 @@ impl TsfFactory {
             memory_pool: self.memory_pool.clone(),
             tsf_metrics,
             status: VnodeStatus::Running,
+            write_dedup: TseriesFamily::new_write_dedup(&self.options.storage),
+            warmup_permit: None,
         }));
         let weak_tsfamily = Arc::downgrade(&tsfamily);
         tokio::spawn(TseriesFamily::update_vnode_metrics(weak_tsfamily));
@@ -132,6 +137,9 @@ pub struct TseriesFamily {
     memory_pool: MemoryPoolRef,
     tsf_metrics: TsfMetrics,
     status: VnodeStatus,
+    write_dedup: Option<Mutex<WriteDedupWindow>>,
+    /// The warm-up budget held by the data preloaded for the vnode.
+    warmup_permit: Option<WarmupPermit>,
 }
 
 impl TseriesFamily {
@@ -148,6 +156,7 @@ impl TseriesFamily {
         register: &Arc<MetricsRegister>,
     ) -> Self {
         let mm = Arc::new(RwLock::new(cache));
+        let write_dedup = Self::new_write_dedup(&storage_opt);
 
         Self {
             tf_id,
@@ -171,9 +180,21 @@ impl TseriesFamily {
             memory_pool,
             tsf_metrics: TsfMetrics::new(register.clone(), owner.as_str(), tf_id as u64),
             status: VnodeStatus::Running,
+            write_dedup,
+            warmup_permit: None,
         }
     }
 
+    fn new_write_dedup(storage_opt: &StorageOptions) -> Option<Mutex<WriteDedupWindow>> {
+        if storage_opt.write_dedup_window.is_zero() {
+            return None;
+        }
+        Some(Mutex::new(WriteDedupWindow::new(
+            storage_opt.write_dedup_window,
+            storage_opt.write_dedup_bloom_filter_bits,
+        )))
+    }
+
     fn new_super_version(&mut self, version: Arc<Version>) {
         self.super_version_id.fetch_add(1, Ordering::SeqCst);
         self.tsf_metrics.record_disk_storage(self.disk_storage());
@@ -236,10 +257,13 @@ impl TseriesFamily {
         self.new_super_version(self.version());
     }
 
+    /// Write the points into the mutable cache, rows written in the dedup window
+    /// are dropped if `dedup` is set.
     pub fn put_points(
         &self,
         seq: u64,
         points: HashMap<SeriesId, (SeriesKey, RowGroup)>,
+        dedup: bool,
     ) -> TskvResult<u64> {
         if self.status == VnodeStatus::Copying {
             return Err(CommonSnafu {
@@ -247,12 +271,25 @@ impl TseriesFamily {
             }
             .build());
         }
-        let mut res = 0;
-        for (sid, (series_key, group)) in points {
+        let mut write_dedup = match self.write_dedup.as_ref() {
+            Some(d) if dedup => Some(d.lock()),
+            _ => None,
+        };
+        let (mut res, mut dropped) = (0, 0);
+        for (sid, (series_key, mut group)) in points {
+            if let Some(write_dedup) = write_dedup.as_mut() {
+                dropped += write_dedup.dedup_row_group(sid, &mut group);
+                if group.rows.get_ref_rows().is_empty() {
+                    continue;
+                }
+            }
             let mem = self.mut_cache.read();
             res += group.rows.get_ref_rows().len();
             mem.write_group(sid, series_key, seq, group)?;
         }
+        if dropped > 0 {
+            self.tsf_metrics.record_write_dedup_dropped(dropped as u64);
+        }
         Ok(res as u64)
     }
 
@@ -282,7 +319,15 @@ impl TseriesFamily {
         self.status = status;
     }
 
+    /// The rows written again after a delete are not duplicates.
+    fn clear_write_dedup(&self) {
+        if let Some(write_dedup) = self.write_dedup.as_ref() {
+            write_dedup.lock().clear();
+        }
+    }
+
     pub fn drop_columns(&self, series_ids: &[SeriesId], column_ids: &[ColumnId]) {
+        self.clear_write_dedup();
         self.mut_cache.read().drop_columns(series_ids, column_ids);
         for memcache in self.immut_cache.iter() {
             memcache.read().drop_columns(series_ids, column_ids);
@@ -290,6 +335,7 @@ impl TseriesFamily {
     }
 
     pub fn delete_series(&self, sids: &[SeriesId], time_range: &TimeRange) {
+        self.clear_write_dedup();
         self.mut_cache.read().delete_series(sids, time_range);
         for memcache in self.immut_cache.iter() {
             memcache.read().delete_series(sids, time_range);
@@ -297,6 +343,7 @@ impl TseriesFamily {
     }
 
     pub fn delete_series_by_time_ranges(&self, sids: &[SeriesId], time_ranges: &TimeRanges) {
+        self.clear_write_dedup();
         self.mut_cache
             .read()
             .delete_series_by_time_ranges(sids, time_ranges);
@@ -436,6 +483,27 @@ impl TseriesFamily {
         self.storage_opt.clone()
     }
 
+    pub fn record_warmup_loaded_bytes(&self, size: u64) {
+        self.tsf_metrics.record_warmup_loaded(size);
+    }
+
+    /// Hold the budget of the warm-up until the vnode is dropped, the permit of
+    /// a previous warm-up is given back.
+    pub fn set_warmup_permit(&mut self, permit: WarmupPermit) {
+        self.warmup_permit = Some(permit);
+    }
+
+    /// Get the number of files of the vnode opened by the file system.
+    pub fn open_files_num(&self) -> usize {
+        open_files::open_files_num_under(self.storage_opt.ts_family_dir(&self.owner, self.tf_id))
+    }
+
+    /// Close the cached tsm readers of the vnode which are not in use, they are
+    /// opened again on the next read. Returns the number of files closed.
+    pub async fn close_idle_files(&self) -> usize {
+        self.version().remove_idle_tsm_readers().await
+    }
+
     pub fn get_delta_dir(&self) -> PathBuf {
         self.storage_opt.delta_dir(&self.owner, self.tf_id)
     }
@@ -605,15 +673,15 @@ impl TseriesFamily {
             intv.tick().await;
             match tsfamily.upgrade() {
                 Some(tsf_strong_ref) => {
-                    let cache_size = {
+                    let (cache_size, open_files_num) = {
                         let tsfamily = tsf_strong_ref.read().await;
-                        tsfamily.cache_size()
+                        (tsfamily.cache_size(), tsfamily.open_files_num())
                     };
-                    tsf_strong_ref
-                        .write()
-                        .await
+                    let mut tsfamily = tsf_strong_ref.write().await;
+                    tsfamily.tsf_metrics.record_cache_size(cache_size);
+                    tsfamily
                         .tsf_metrics
-                        .record_cache_size(cache_size);
+                        .record_open_files(open_files_num as u64);
                 }
                 None => {
                     break;
@@ -642,6 +710,7 @@ pub mod test_tseries_family {
     use crate::tsfamily::column_file::ColumnFile;
     use crate::tsfamily::level_info::LevelInfo;
     use crate::tsfamily::version::Version;
+    use crate::warmup::WarmupPermit;
 
     #[tokio::test]
     async fn test_version_apply_version_edits_1() {
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use metrics::count::U64Counter;
 use metrics::gauge::U64Gauge;
 use metrics::metric_register::MetricsRegister;
 use models::schema::database_schema::split_owner;
@@ -8,6 +9,9 @@ use models::schema::database_schema::split_owner;
 pub struct TsfMetrics {
     pub vnode_disk_storage: U64Gauge,
     pub vnode_cache_size: U64Gauge,
+    pub vnode_write_dedup_dropped: U64Counter,
+    pub vnode_warmup_loaded: U64Gauge,
+    pub vnode_open_files: U64Gauge,
 
     pub metrics_register: Arc<MetricsRegister>,
 }
@@ -30,10 +34,43 @@ impl TsfMetrics {
             ("vnode_id", vnode_id.to_string().as_str()),
         ]);
 
+        let metric = metrics_register.metric::<U64Counter>(
+            "vnode_write_dedup_dropped",
+            "duplicate points dropped by write dedup window of vnode",
+        );
+        let dedup_dropped_counter = metric.recorder([
+            ("tenant", tenant),
+            ("database", db),
+            ("vnode_id", vnode_id.to_string().as_str()),
+        ]);
+
+        let metric = metrics_register.metric::<U64Gauge>(
+            "vnode_warmup_loaded",
+            "bytes loaded by the warm-up of vnode",
+        );
+        let warmup_loaded_gauge = metric.recorder([
+            ("tenant", tenant),
+            ("database", db),
+            ("vnode_id", vnode_id.to_string().as_str()),
+        ]);
+
+        let metric = metrics_register.metric::<U64Gauge>(
+            "vnode_open_files",
+            "files of vnode opened by the file system",
+        );
+        let open_files_gauge = metric.recorder([
+            ("tenant", tenant),
+            ("database", db),
+            ("vnode_id", vnode_id.to_string().as_str()),
+        ]);
+
         Self {
             metrics_register,
             vnode_disk_storage: disk_storage_gauge,
             vnode_cache_size: cache_gauge,
+            vnode_write_dedup_dropped: dedup_dropped_counter,
+            vnode_warmup_loaded: warmup_loaded_gauge,
+            vnode_open_files: open_files_gauge,
         }
     }
 
@@ -45,6 +82,18 @@ impl TsfMetrics {
         self.vnode_cache_size.set(size)
     }
 
+    pub fn record_write_dedup_dropped(&self, count: u64) {
+        self.vnode_write_dedup_dropped.inc(count)
+    }
+
+    pub fn record_warmup_loaded(&self, size: u64) {
+        self.vnode_warmup_loaded.set(size)
+    }
+
+    pub fn record_open_files(&self, num: u64) {
+        self.vnode_open_files.set(num)
+    }
+
     pub fn drop(register: &MetricsRegister, owner: &str, vnode_id: u64) {
         let (tenant, db) = split_owner(owner);
         let metric = register.metric::<U64Gauge>("vnode_disk_storage", "disk storage of vnode");
@@ -60,5 +109,35 @@ impl TsfMetrics {
             ("database", db),
             ("vnode_id", vnode_id.to_string().as_str()),
         ]);
+
+        let metric = register.metric::<U64Counter>(
+            "vnode_write_dedup_dropped",
+            "duplicate points dropped by write dedup window of vnode",
+        );
+        metric.remove([
+            ("tenant", tenant),
+            ("database", db),
+            ("vnode_id", vnode_id.to_string().as_str()),
+        ]);
+
+        let metric = register.metric::<U64Gauge>(
+            "vnode_warmup_loaded",
+            "bytes loaded by the warm-up of vnode",
+        );
+        metric.remove([
+            ("tenant", tenant),
+            ("database", db),
+            ("vnode_id", vnode_id.to_string().as_str()),
+        ]);
+
+        let metric = register.metric::<U64Gauge>(
+            "vnode_open_files",
+            "files of vnode opened by the file system",
+        );
+        metric.remove([
+            ("tenant", tenant),
+            ("database", db),
+            ("vnode_id", vnode_id.to_string().as_str()),
+        ]);
     }
 }
@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
-use cache::{AsyncCache, ShardedAsyncCache};
+use cache::{AsyncCache, Cache, ShardedAsyncCache};
 use models::predicate::domain::TimeRange;
 use models::{SeriesId, Timestamp};
 use tokio::sync::RwLock as TokioRwLock;
@@ -206,6 +206,17 @@ impl Version {
         self.tsm_reader_cache.remove(&path).await;
     }
 
+    /// Remove the cached tsm readers of the files which are not in use, returns
+    /// the number of readers removed.
+    pub async fn remove_idle_tsm_readers(&self) -> usize {
+        let paths = self
+            .levels_info
+            .iter()
+            .flat_map(|level| level.files.iter())
+            .map(|file| file.file_path().display().to_string());
+        remove_idle_values(&self.tsm_reader_cache, paths).await
+    }
+
     pub async fn unmark_compacting_files(&self, files_ids: &HashSet<ColumnFileId>) {
         if files_ids.is_empty() {
             return;
@@ -238,3 +249,60 @@ impl Version {
         }
     }
 }
+
+/// Remove the values of the keys only referenced by the cache. The shard of a
+/// key is locked while it's checked, so the value can't be taken meanwhile.
+async fn remove_idle_values<V>(
+    cache: &ShardedAsyncCache<String, Arc<V>>,
+    keys: impl Iterator<Item = String>,
+) -> usize
+where
+    V: std::fmt::Debug + Send + Sync + 'static,
+{
+    let mut removed = 0;
+    for key in keys {
+        let Some(mut shard) = cache.lock_shard(&key).await else {
+            continue;
+        };
+        // Referenced by the cache and by the value got here.
+        let idle = shard
+            .get(&key)
+            .map_or(false, |value| Arc::strong_count(&value) == 2);
+        if idle {
+            shard.remove(&key);
+            removed += 1;
+        }
+    }
+
+    removed
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use cache::{AsyncCache, ShardedAsyncCache};
+
+    use super::remove_idle_values;
+
+    #[tokio::test]
+    async fn test_remove_idle_values() {
+        let cache = ShardedAsyncCache::<String, Arc<u64>>::create_lru_sharded_cache(16);
+        cache.insert("a".to_string(), Arc::new(1)).await;
+        cache.insert("b".to_string(), Arc::new(2)).await;
+        cache.insert("c".to_string(), Arc::new(3)).await;
+        let in_use = cache.get(&"b".to_string()).await.unwrap();
+
+        let keys = ["a", "b", "d"].into_iter().map(str::to_string);
+        assert_eq!(remove_idle_values(&cache, keys).await, 1);
+        assert!(cache.get(&"a".to_string()).await.is_none());
+        assert!(cache.get(&"b".to_string()).await.is_some());
+        // Not a key to check.
+        assert!(cache.get(&"c".to_string()).await.is_some());
+
+        drop(in_use);
+        let keys = ["b"].into_iter().map(str::to_string);
+        assert_eq!(remove_idle_values(&cache, keys).await, 1);
+        assert!(cache.get(&"b".to_string()).await.is_none());
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use models::predicate::domain::TimeRange;
-use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef};
+use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef, TskvTableSchemaV0};
 use serde::{Deserialize, Serialize};
 use snafu::IntoError;
 
@@ -55,6 +55,14 @@ impl ChunkGroup {
 
 pub type TableId = u64;
 
+/// Starts the versioned encoding of [`ChunkGroupMeta`], followed by the version
+/// in u32. The legacy encoding starts with the number of tables in u64, which
+/// is never `u64::MAX`.
+const CHUNK_GROUP_META_MAGIC: u64 = u64::MAX;
+/// Version 1: the table schemas with the table options and the column options.
+const CHUNK_GROUP_META_VERSION: u32 = 1;
+const CHUNK_GROUP_META_HEADER_SIZE: usize = 12;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChunkGroupWriteSpec {
     pub(crate) table_schema: Arc<TskvTableSchema>,
@@ -122,11 +130,32 @@ impl ChunkGroupMeta {
     }
 
     pub fn serialize(&self) -> TskvResult<Vec<u8>> {
-        bincode::serialize(&self).map_err(|e| EncodeSnafu.into_error(e))
+        let mut buf = Vec::with_capacity(CHUNK_GROUP_META_HEADER_SIZE);
+        buf.extend_from_slice(&CHUNK_GROUP_META_MAGIC.to_le_bytes());
+        buf.extend_from_slice(&CHUNK_GROUP_META_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut buf, &self).map_err(|e| EncodeSnafu.into_error(e))?;
+        Ok(buf)
     }
 
     pub fn deserialize(bytes: &[u8]) -> TskvResult<Self> {
-        bincode::deserialize(bytes).map_err(|e| DecodeSnafu.into_error(e))
+        if bytes.len() < CHUNK_GROUP_META_HEADER_SIZE
+            || bytes[..8] != CHUNK_GROUP_META_MAGIC.to_le_bytes()
+        {
+            let meta = bincode::deserialize::<ChunkGroupMetaV0>(bytes)
+                .map_err(|e| DecodeSnafu.into_error(e))?;
+            return Ok(meta.into());
+        }
+
+        let version =
+            u32::from_le_bytes(bytes[8..CHUNK_GROUP_META_HEADER_SIZE].try_into().unwrap());
+        match version {
+            CHUNK_GROUP_META_VERSION => {
+                bincode::deserialize(&bytes[CHUNK_GROUP_META_HEADER_SIZE..])
+                    .map_err(|e| DecodeSnafu.into_error(e))
+            }
+            _ => Err(DecodeSnafu
+                .into_error(format!("unsupported version {version} of chunk group meta").into())),
+        }
     }
 
     pub fn push(&mut self, table: ChunkGroupWriteSpec) {
@@ -157,3 +186,187 @@ impl ChunkGroupMeta {
         self.tables.get(table_name).map(|t| t.table_schema.clone())
     }
 }
+
+/// [`ChunkGroupMeta`] written without the version, see [`TskvTableSchemaV0`].
+#[derive(Deserialize)]
+struct ChunkGroupMetaV0 {
+    tables: BTreeMap<String, ChunkGroupWriteSpecV0>,
+}
+
+#[derive(Deserialize)]
+struct ChunkGroupWriteSpecV0 {
+    table_schema: TskvTableSchemaV0,
+    chunk_group_offset: u64,
+    chunk_group_size: u64,
+    time_range: TimeRange,
+    count: usize,
+}
+
+impl From<ChunkGroupMetaV0> for ChunkGroupMeta {
+    fn from(meta: ChunkGroupMetaV0) -> Self {
+        let tables = meta
+            .tables
+            .into_iter()
+            .map(|(name, spec)| {
+                let spec = ChunkGroupWriteSpec::new(
+                    Arc::new(spec.table_schema.into()),
+                    spec.chunk_group_offset,
+                    spec.chunk_group_size,
+                    spec.time_range,
+                    spec.count,
+                );
+                (name, spec)
+            })
+            .collect();
+        Self { tables }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    use arrow_schema::TimeUnit;
+    use models::codec::Encoding;
+    use models::predicate::domain::TimeRange;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+    use serde::Serialize;
+    use utils::duration::CnosDuration;
+
+    use super::{ChunkGroupMeta, ChunkGroupWriteSpec};
+
+    /// The layouts written before the table options and the column options.
+    #[derive(Serialize)]
+    struct ChunkGroupMetaV0 {
+        tables: BTreeMap<String, ChunkGroupWriteSpecV0>,
+    }
+
+    #[derive(Serialize)]
+    struct ChunkGroupWriteSpecV0 {
+        table_schema: TskvTableSchemaV0,
+        chunk_group_offset: u64,
+        chunk_group_size: u64,
+        time_range: TimeRange,
+        count: usize,
+    }
+
+    #[derive(Serialize)]
+    struct TskvTableSchemaV0 {
+        tenant: String,
+        db: String,
+        name: String,
+        schema_version: u64,
+        next_column_id: u32,
+        columns: Vec<TableColumnV0>,
+        columns_index: HashMap<String, usize>,
+    }
+
+    #[derive(Serialize)]
+    struct TableColumnV0 {
+        id: u32,
+        name: String,
+        column_type: ColumnType,
+        encoding: Encoding,
+    }
+
+    fn columns() -> Vec<TableColumn> {
+        vec![
+            TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+            TableColumn::new_tag_column(1, "ta".to_string()),
+            TableColumn::new(
+                2,
+                "f1".to_string(),
+                ColumnType::Field(ValueType::Float),
+                Encoding::Gorilla,
+            ),
+        ]
+    }
+
+    fn table_schema_v0(name: &str) -> TskvTableSchemaV0 {
+        let columns = columns()
+            .into_iter()
+            .map(|c| TableColumnV0 {
+                id: c.id,
+                name: c.name,
+                column_type: c.column_type,
+                encoding: c.encoding,
+            })
+            .collect::<Vec<_>>();
+        let columns_index = columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.name.clone(), i))
+            .collect::<HashMap<_, _>>();
+        TskvTableSchemaV0 {
+            tenant: "cnosdb".to_string(),
+            db: "public".to_string(),
+            name: name.to_string(),
+            schema_version: 1,
+            next_column_id: 3,
+            columns,
+            columns_index,
+        }
+    }
+
+    #[test]
+    fn test_decode_legacy_chunk_group_meta() {
+        let mut tables = BTreeMap::new();
+        for (name, offset) in [("air", 10), ("sea", 100)] {
+            let spec = ChunkGroupWriteSpecV0 {
+                table_schema: table_schema_v0(name),
+                chunk_group_offset: offset,
+                chunk_group_size: 20,
+                time_range: TimeRange::new(1, 2),
+                count: 0,
+            };
+            tables.insert(name.to_string(), spec);
+        }
+        let bytes = bincode::serialize(&ChunkGroupMetaV0 { tables }).unwrap();
+
+        let meta = ChunkGroupMeta::deserialize(&bytes).unwrap();
+        assert_eq!(meta.len(), 2);
+        let sea = meta.tables().get("sea").unwrap();
+        assert_eq!(sea.chunk_group_offset(), 100);
+        assert_eq!(sea.chunk_group_size(), 20);
+        assert_eq!(sea.time_range(), &TimeRange::new(1, 2));
+        let schema = meta.table_schema("air").unwrap();
+        assert_eq!(schema.name, "air");
+        assert_eq!(schema.columns(), columns().as_slice());
+        assert!(!schema.append_only);
+        assert!(schema.ttl.is_none());
+    }
+
+    #[test]
+    fn test_encode_chunk_group_meta() {
+        let mut columns = columns();
+        columns[2].default_value = Some("1.0".to_string());
+        columns[2].indexed = true;
+        let mut schema = TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "air".to_string(),
+            columns,
+        );
+        schema.append_only = true;
+        schema.ttl = Some(CnosDuration::new_with_day(7));
+        let schema = Arc::new(schema);
+
+        let mut meta = ChunkGroupMeta::new();
+        meta.push(ChunkGroupWriteSpec::new(
+            schema.clone(),
+            10,
+            20,
+            TimeRange::new(1, 2),
+            0,
+        ));
+        let bytes = meta.serialize().unwrap();
+        let meta = ChunkGroupMeta::deserialize(&bytes).unwrap();
+        assert_eq!(meta.table_schema("air").unwrap(), schema);
+
+        let mut bytes = bytes;
+        bytes[8] = 2;
+        assert!(ChunkGroupMeta::deserialize(&bytes).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use models::codec::Encoding;
+
+use super::{get_encoding, CodecError};
+
+/// Number of values at the head of a block that the candidate codecs are
+/// tried on.
+const SAMPLE_SIZE: usize = 1024;
+
+/// A candidate codec is only chosen over the previous ones if it encodes the
+/// sample at least this much smaller, since the candidates are ordered from
+/// the fastest to the slowest.
+const MIN_SIZE_GAIN: f64 = 0.1;
+
+pub(crate) const TIMESTAMP_CANDIDATES: [Encoding; 4] = [
+    Encoding::Null,
+    Encoding::DeltaTs,
+    Encoding::Delta,
+    Encoding::Quantile,
+];
+
+pub(crate) const INTEGER_CANDIDATES: [Encoding; 3] =
+    [Encoding::Null, Encoding::Delta, Encoding::Quantile];
+
+pub(crate) const UNSIGNED_CANDIDATES: [Encoding; 3] =
+    [Encoding::Null, Encoding::Delta, Encoding::Quantile];
+
+pub(crate) const FLOAT_CANDIDATES: [Encoding; 5] = [
+    Encoding::Null,
+    Encoding::Gorilla,
+    Encoding::Lz4,
+    Encoding::Zstd,
+    Encoding::Quantile,
+];
+
+pub(crate) const BOOLEAN_CANDIDATES: [Encoding; 2] = [Encoding::Null, Encoding::BitPack];
+
+pub(crate) const STRING_CANDIDATES: [Encoding; 5] = [
+    Encoding::Null,
+    Encoding::Lz4,
+    Encoding::Snappy,
+    Encoding::Zstd,
+    Encoding::Zlib,
+];
+
+/// Encode a sample of `src` by each of the candidate codecs, and return the
+/// one that encodes it smallest, unless an earlier (faster) candidate is not
+/// much larger. Candidates failing to encode the sample are skipped.
+pub(crate) fn select_encoding<T>(
+    src: &[T],
+    candidates: &[Encoding],
+    encode: impl Fn(Encoding, &[T], &mut Vec<u8>) -> Result<(), CodecError>,
+) -> Encoding {
+    let sample = &src[..src.len().min(SAMPLE_SIZE)];
+    let mut buf = Vec::new();
+    let mut selected: Option<(Encoding, usize)> = None;
+    for encoding in candidates {
+        buf.clear();
+        if encode(*encoding, sample, &mut buf).is_err() {
+            continue;
+        }
+        match selected {
+            Some((_, size)) if (buf.len() as f64) > size as f64 * (1.0 - MIN_SIZE_GAIN) => {}
+            _ => selected = Some((*encoding, buf.len())),
+        }
+    }
+
+    selected
+        .map(|(encoding, _)| encoding)
+        .unwrap_or(candidates[0])
+}
+
+/// Encoding recorded in the header of a block encoded by the AUTO codec.
+pub(crate) fn block_encoding(src: &[u8]) -> Result<Encoding, CodecError> {
+    match get_encoding(src) {
+        Encoding::Auto => Err(From::from("block header must not be AUTO encoding")),
+        encoding => Ok(encoding),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::buffer::NullBuffer;
+    use arrow_array::{Array, Float64Array, Int64Array};
+    use models::codec::Encoding;
+
+    use super::{select_encoding, FLOAT_CANDIDATES, TIMESTAMP_CANDIDATES};
+    use crate::tsm::codec::{get_encoding, get_f64_codec, get_ts_codec};
+
+    #[test]
+    fn test_select_encoding() {
+        let ts = (0..5000_i64)
+            .map(|i| 1_700_000_000_000_000_000 + i * 1_000_000_000)
+            .collect::<Vec<_>>();
+        let encoding = select_encoding(&ts, &TIMESTAMP_CANDIDATES, |e, s, d| {
+            get_ts_codec(e).encode(s, d)
+        });
+        assert_eq!(encoding, Encoding::DeltaTs);
+
+        // Falls back to the first candidate if all of them fail.
+        let encoding = select_encoding(&ts, &TIMESTAMP_CANDIDATES[1..], |_, _, _| {
+            Err(From::from("failed"))
+        });
+        assert_eq!(encoding, Encoding::DeltaTs);
+
+        let mut buf = Vec::new();
+        get_ts_codec(Encoding::Auto).encode(&ts, &mut buf).unwrap();
+        assert_eq!(get_encoding(&buf), Encoding::DeltaTs);
+        let array = get_ts_codec(Encoding::Auto)
+            .decode_to_array(&buf, &NullBuffer::new_valid(ts.len()))
+            .unwrap();
+        let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(array.values().as_ref(), ts.as_slice());
+    }
+
+    #[test]
+    fn test_auto_float_codec() {
+        let values = (0..3000).map(|i| (i % 7) as f64 * 0.5).collect::<Vec<_>>();
+        let encoding = select_encoding(&values, &FLOAT_CANDIDATES, |e, s, d| {
+            get_f64_codec(e).encode(s, d)
+        });
+        assert_ne!(encoding, Encoding::Null);
+
+        let mut buf = Vec::new();
+        get_f64_codec(Encoding::Auto)
+            .encode(&values, &mut buf)
+            .unwrap();
+        assert_eq!(get_encoding(&buf), encoding);
+        let array = get_f64_codec(Encoding::Auto)
+            .decode_to_array(&buf, &NullBuffer::new_valid(values.len()))
+            .unwrap();
+        let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(array.values().as_ref(), values.as_slice());
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::{Duration, Instant};
+
+use arrow::buffer::NullBuffer;
+use arrow_array::{Array, ArrayRef};
+use models::codec::Encoding;
+
+use super::{
+    get_bool_codec, get_f64_codec, get_i64_codec, get_str_codec, get_ts_codec, get_u64_codec,
+    CodecError,
+};
+
+/// Sample values of a column to benchmark the codecs with, nulls are not included.
+#[derive(Debug, Clone)]
+pub enum CodecSample {
+    Timestamp(Vec<i64>),
+    Integer(Vec<i64>),
+    Unsigned(Vec<u64>),
+    Float(Vec<f64>),
+    Boolean(Vec<bool>),
+    String(Vec<Vec<u8>>),
+}
+
+impl CodecSample {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Timestamp(v) | Self::Integer(v) => v.len(),
+            Self::Unsigned(v) => v.len(),
+            Self::Float(v) => v.len(),
+            Self::Boolean(v) => v.len(),
+            Self::String(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size of the values without encoding.
+    pub fn raw_size(&self) -> usize {
+        match self {
+            Self::String(v) => v.iter().map(|s| s.len()).sum(),
+            Self::Boolean(v) => v.len(),
+            _ => self.len() * 8,
+        }
+    }
+
+    /// Codecs available for the type of the values, `Encoding::Default` is not
+    /// included since it is one of the others.
+    pub fn encodings(&self) -> &'static [Encoding] {
+        match self {
+            Self::Timestamp(_) | Self::Integer(_) => &[
+                Encoding::Null,
+                Encoding::Delta,
+                Encoding::DeltaTs,
+                Encoding::Quantile,
+                Encoding::Auto,
+            ],
+            Self::Unsigned(_) => &[
+                Encoding::Null,
+                Encoding::Delta,
+                Encoding::Quantile,
+                Encoding::Auto,
+            ],
+            Self::Float(_) => &[
+                Encoding::Null,
+                Encoding::Gorilla,
+                Encoding::Quantile,
+                Encoding::Zstd,
+                Encoding::Lz4,
+                Encoding::Auto,
+            ],
+            Self::Boolean(_) => &[Encoding::Null, Encoding::BitPack, Encoding::Auto],
+            Self::String(_) => &[
+                Encoding::Null,
+                Encoding::Gzip,
+                Encoding::Bzip,
+                Encoding::Zstd,
+                Encoding::Snappy,
+                Encoding::Zlib,
+                Encoding::Lz4,
+                Encoding::Auto,
+            ],
+        }
+    }
+
+    fn encode(&self, encoding: Encoding, dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        match self {
+            Self::Timestamp(v) => get_ts_codec(encoding).encode(v, dst),
+            Self::Integer(v) => get_i64_codec(encoding).encode(v, dst),
+            Self::Unsigned(v) => get_u64_codec(encoding).encode(v, dst),
+            Self::Float(v) => get_f64_codec(encoding).encode(v, dst),
+            Self::Boolean(v) => get_bool_codec(encoding).encode(v, dst),
+            Self::String(v) => {
+                let values = v.iter().map(|s| s.as_slice()).collect::<Vec<_>>();
+                get_str_codec(encoding).encode(&values, dst)
+            }
+        }
+    }
+
+    fn decode(
+        &self,
+        encoding: Encoding,
+        src: &[u8],
+        bit_set: &NullBuffer,
+    ) -> Result<ArrayRef, CodecError> {
+        match self {
+            Self::Timestamp(_) => get_ts_codec(encoding).decode_to_array(src, bit_set),
+            Self::Integer(_) => get_i64_codec(encoding).decode_to_array(src, bit_set),
+            Self::Unsigned(_) => get_u64_codec(encoding).decode_to_array(src, bit_set),
+            Self::Float(_) => get_f64_codec(encoding).decode_to_array(src, bit_set),
+            Self::Boolean(_) => get_bool_codec(encoding).decode_to_array(src, bit_set),
+            Self::String(_) => get_str_codec(encoding).decode_to_array(src, bit_set),
+        }
+    }
+}
+
+/// Result of benchmarking a codec, the time is the average of the iterations.
+#[derive(Debug, Clone)]
+pub struct CodecBenchResult {
+    pub encoding: Encoding,
+    pub raw_size: usize,
+    pub encoded_size: usize,
+    pub encode_time: Duration,
+    pub decode_time: Duration,
+}
+
+impl CodecBenchResult {
+    pub fn compression_ratio(&self) -> f64 {
+        if self.encoded_size == 0 {
+            return 0.0;
+        }
+        self.raw_size as f64 / self.encoded_size as f64
+    }
+
+    /// MB of the raw values encoded per second.
+    pub fn encode_throughput(&self) -> f64 {
+        throughput(self.raw_size, self.encode_time)
+    }
+
+    /// MB of the raw values decoded per second.
+    pub fn decode_throughput(&self) -> f64 {
+        throughput(self.raw_size, self.decode_time)
+    }
+}
+
+fn throughput(size: usize, time: Duration) -> f64 {
+    let secs = time.as_secs_f64();
+    if secs == 0.0 {
+        return f64::INFINITY;
+    }
+    size as f64 / 1024.0 / 1024.0 / secs
+}
+
+/// Encode and decode the sample by each codec available for it `iterations` times.
+pub fn bench_codecs(
+    sample: &CodecSample,
+    iterations: usize,
+) -> Vec<(Encoding, Result<CodecBenchResult, CodecError>)> {
+    sample
+        .encodings()
+        .iter()
+        .map(|encoding| (*encoding, bench_codec(sample, *encoding, iterations)))
+        .collect()
+}
+
+fn bench_codec(
+    sample: &CodecSample,
+    encoding: Encoding,
+    iterations: usize,
+) -> Result<CodecBenchResult, CodecError> {
+    let iterations = iterations.max(1);
+    let bit_set = NullBuffer::new_valid(sample.len());
+
+    let mut encoded = Vec::new();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        encoded.clear();
+        sample.encode(encoding, &mut encoded)?;
+    }
+    let encode_time = start.elapsed() / iterations as u32;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let array = sample.decode(encoding, &encoded, &bit_set)?;
+        if array.len() != sample.len() {
+            return Err(From::from(format!(
+                "decoded {} values by {}, expected {}",
+                array.len(),
+                encoding.as_str(),
+                sample.len()
+            )));
+        }
+    }
+    let decode_time = start.elapsed() / iterations as u32;
+
+    Ok(CodecBenchResult {
+        encoding,
+        raw_size: sample.raw_size(),
+        encoded_size: encoded.len(),
+        encode_time,
+        decode_time,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bench_codecs, CodecSample};
+
+    #[test]
+    fn test_bench_codecs() {
+        let samples = [
+            CodecSample::Timestamp((0..1000).map(|i| i * 1_000_000_000).collect()),
+            CodecSample::Integer((0..1000).map(|i| i % 17).collect()),
+            CodecSample::Unsigned((0..1000).collect()),
+            CodecSample::Float((0..1000).map(|i| i as f64 / 4.0).collect()),
+            CodecSample::Boolean((0..1000).map(|i| i % 3 == 0).collect()),
+            CodecSample::String(
+                (0..1000)
+                    .map(|i| format!("v{}", i % 10).into_bytes())
+                    .collect(),
+            ),
+        ];
+        for sample in samples {
+            let results = bench_codecs(&sample, 2);
+            assert_eq!(results.len(), sample.encodings().len());
+            for (encoding, result) in results {
+                let result = result.unwrap();
+                assert_eq!(result.encoding, encoding);
+                assert_eq!(result.raw_size, sample.raw_size());
+                assert!(result.compression_ratio() > 0.0);
+            }
+        }
+    }
+}
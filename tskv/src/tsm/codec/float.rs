@@ -6,7 +6,7 @@ use arrow_array::{ArrayRef, Float64Array};
 use pco::standalone::{simple_decompress, simpler_compress};
 use pco::DEFAULT_COMPRESSION_LEVEL;
 
-use super::CodecError;
+use super::{zstd_compress_level, CodecError};
 use crate::byte_utils::decode_be_f64;
 use crate::tsm::codec::Encoding;
 
@@ -253,6 +253,37 @@ pub fn f64_pco_encode(src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError>
     Ok(())
 }
 
+/// Compress the big-endian bytes of the floats by zstd.
+pub fn f64_zstd_encode(src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    dst.push(Encoding::Zstd as u8);
+    let data = f64_to_be_bytes(src);
+    zstd::stream::copy_encode(data.as_slice(), dst, zstd_compress_level())?;
+    Ok(())
+}
+
+/// Compress the big-endian bytes of the floats by lz4.
+pub fn f64_lz4_encode(src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    dst.push(Encoding::Lz4 as u8);
+    dst.append(&mut lz4_flex::compress_prepend_size(&f64_to_be_bytes(src)));
+    Ok(())
+}
+
+fn f64_to_be_bytes(src: &[f64]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(src.len() * 8);
+    for i in src.iter() {
+        data.extend_from_slice((*i).to_be_bytes().as_slice());
+    }
+    data
+}
+
 pub fn f64_without_compress_encode(src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
     if src.is_empty() {
         return Ok(());
@@ -394,9 +425,35 @@ pub fn f64_without_compress_decode(
         return Ok(Arc::new(array));
     }
 
+    f64_be_bytes_to_array(&src[1..], bit_set)
+}
+
+pub fn f64_zstd_decode(src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+    if src.is_empty() {
+        let null_value: Vec<Option<f64>> = vec![None; bit_set.len()];
+        let array = Float64Array::from(null_value);
+        return Ok(Arc::new(array));
+    }
+
+    let mut data = vec![];
+    zstd::stream::copy_decode(&src[1..], &mut data)?;
+    f64_be_bytes_to_array(&data, bit_set)
+}
+
+pub fn f64_lz4_decode(src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+    if src.is_empty() {
+        let null_value: Vec<Option<f64>> = vec![None; bit_set.len()];
+        let array = Float64Array::from(null_value);
+        return Ok(Arc::new(array));
+    }
+
+    let data = lz4_flex::decompress_size_prepended(&src[1..])?;
+    f64_be_bytes_to_array(&data, bit_set)
+}
+
+fn f64_be_bytes_to_array(data: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
     let mut builder = Float64Builder::with_capacity(bit_set.len());
-    let src = &src[1..];
-    let mut iter = src.chunks(8);
+    let mut iter = data.chunks(8);
 
     for is_valid in bit_set.iter() {
         if is_valid {
@@ -615,7 +672,8 @@ mod tests {
     use arrow_array::{Array, Float64Array};
 
     use crate::tsm::codec::float::{
-        f64_gorilla_decode, f64_gorilla_encode, f64_pco_decode, f64_pco_encode,
+        f64_gorilla_decode, f64_gorilla_encode, f64_lz4_decode, f64_lz4_encode, f64_pco_decode,
+        f64_pco_encode, f64_zstd_decode, f64_zstd_encode,
     };
 
     #[test]
@@ -694,6 +752,25 @@ mod tests {
         assert_eq!(*array, expected);
     }
 
+    #[test]
+    fn test_encode_decode_block_compression() {
+        let src: Vec<f64> = vec![1.5, 1.5, 2.25, -0.0, f64::MAX, f64::MIN_POSITIVE, 1.5];
+        let null_bitset = NullBuffer::new_valid(src.len());
+        let expected = Float64Array::from_iter(src.iter().cloned());
+
+        let mut dst = vec![];
+        f64_zstd_encode(&src, &mut dst).expect("failed to encode src");
+        let array_ref = f64_zstd_decode(&dst, &null_bitset).expect("failed to decode");
+        let array = array_ref.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(*array, expected);
+
+        let mut dst = vec![];
+        f64_lz4_encode(&src, &mut dst).expect("failed to encode src");
+        let array_ref = f64_lz4_decode(&dst, &null_bitset).expect("failed to decode");
+        let array = array_ref.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(*array, expected);
+    }
+
     #[test]
     fn encode() {
         struct Test {
@@ -3,14 +3,19 @@ use arrow_array::ArrayRef;
 use minivec::MiniVec;
 use models::codec::Encoding;
 
+use super::auto::{
+    block_encoding, select_encoding, BOOLEAN_CANDIDATES, FLOAT_CANDIDATES, INTEGER_CANDIDATES,
+    STRING_CANDIDATES, TIMESTAMP_CANDIDATES, UNSIGNED_CANDIDATES,
+};
 use super::CodecError;
 use crate::tsm::codec::boolean::{
     bool_bitpack_decode, bool_bitpack_encode, bool_without_compress_decode,
     bool_without_compress_encode,
 };
 use crate::tsm::codec::float::{
-    f64_gorilla_decode, f64_gorilla_encode, f64_pco_decode, f64_pco_encode,
-    f64_without_compress_decode, f64_without_compress_encode,
+    f64_gorilla_decode, f64_gorilla_encode, f64_lz4_decode, f64_lz4_encode, f64_pco_decode,
+    f64_pco_encode, f64_without_compress_decode, f64_without_compress_encode, f64_zstd_decode,
+    f64_zstd_encode,
 };
 use crate::tsm::codec::integer::{
     i64_pco_decode_to_array, i64_pco_encode, i64_without_compress_decode_to_array,
@@ -18,10 +23,11 @@ use crate::tsm::codec::integer::{
 };
 use crate::tsm::codec::string::{
     str_bzip_decode, str_bzip_decode_to_array, str_bzip_encode, str_gzip_decode,
-    str_gzip_decode_to_array, str_gzip_encode, str_snappy_decode, str_snappy_decode_to_array,
-    str_snappy_encode, str_without_compress_decode, str_without_compress_decode_to_array,
-    str_without_compress_encode, str_zlib_decode, str_zlib_decode_to_array, str_zlib_encode,
-    str_zstd_decode, str_zstd_decode_to_array, str_zstd_encode,
+    str_gzip_decode_to_array, str_gzip_encode, str_lz4_decode, str_lz4_decode_to_array,
+    str_lz4_encode, str_snappy_decode, str_snappy_decode_to_array, str_snappy_encode,
+    str_without_compress_decode, str_without_compress_decode_to_array, str_without_compress_encode,
+    str_zlib_decode, str_zlib_decode_to_array, str_zlib_encode, str_zstd_decode,
+    str_zstd_decode_to_array, str_zstd_encode,
 };
 use crate::tsm::codec::timestamp::{
     ts_pco_decode_to_array, ts_pco_encode, ts_without_compress_decode_to_array,
@@ -86,6 +92,21 @@ impl TimestampCodec for QuantileTimestampCodec {
     }
 }
 
+struct AutoTimestampCodec();
+
+impl TimestampCodec for AutoTimestampCodec {
+    fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        let encoding = select_encoding(src, &TIMESTAMP_CANDIDATES, |e, s, d| {
+            get_ts_codec(e).encode(s, d)
+        });
+        get_ts_codec(encoding).encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_ts_codec(block_encoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub trait IntegerCodec {
     fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), CodecError>;
 
@@ -140,6 +161,21 @@ impl IntegerCodec for QuantileIntegerCodec {
     }
 }
 
+struct AutoIntegerCodec();
+
+impl IntegerCodec for AutoIntegerCodec {
+    fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        let encoding = select_encoding(src, &INTEGER_CANDIDATES, |e, s, d| {
+            get_i64_codec(e).encode(s, d)
+        });
+        get_i64_codec(encoding).encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_i64_codec(block_encoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub trait FloatCodec {
     fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError>;
     fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError>;
@@ -181,6 +217,45 @@ impl FloatCodec for QuantileFloatCodec {
     }
 }
 
+struct ZstdFloatCodec();
+
+impl FloatCodec for ZstdFloatCodec {
+    fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        f64_zstd_encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        f64_zstd_decode(src, bit_set)
+    }
+}
+
+struct Lz4FloatCodec();
+
+impl FloatCodec for Lz4FloatCodec {
+    fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        f64_lz4_encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        f64_lz4_decode(src, bit_set)
+    }
+}
+
+struct AutoFloatCodec();
+
+impl FloatCodec for AutoFloatCodec {
+    fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        let encoding = select_encoding(src, &FLOAT_CANDIDATES, |e, s, d| {
+            get_f64_codec(e).encode(s, d)
+        });
+        get_f64_codec(encoding).encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_f64_codec(block_encoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub trait UnsignedCodec {
     fn encode(&self, src: &[u64], dst: &mut Vec<u8>) -> Result<(), CodecError>;
 
@@ -223,6 +298,21 @@ impl UnsignedCodec for QuantileUnsignedCodec {
     }
 }
 
+struct AutoUnsignedCodec();
+
+impl UnsignedCodec for AutoUnsignedCodec {
+    fn encode(&self, src: &[u64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        let encoding = select_encoding(src, &UNSIGNED_CANDIDATES, |e, s, d| {
+            get_u64_codec(e).encode(s, d)
+        });
+        get_u64_codec(encoding).encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_u64_codec(block_encoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub trait BooleanCodec {
     fn encode(&self, src: &[bool], dst: &mut Vec<u8>) -> Result<(), CodecError>;
     fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError>;
@@ -252,6 +342,21 @@ impl BooleanCodec for BitPackBooleanCodec {
     }
 }
 
+struct AutoBooleanCodec();
+
+impl BooleanCodec for AutoBooleanCodec {
+    fn encode(&self, src: &[bool], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        let encoding = select_encoding(src, &BOOLEAN_CANDIDATES, |e, s, d| {
+            get_bool_codec(e).encode(s, d)
+        });
+        get_bool_codec(encoding).encode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_bool_codec(block_encoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub trait StringCodec {
     fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError>;
     fn decode(&self, src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError>;
@@ -355,6 +460,41 @@ impl StringCodec for ZlibStringCodec {
     }
 }
 
+struct Lz4StringCodec();
+
+impl StringCodec for Lz4StringCodec {
+    fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        str_lz4_encode(src, dst)
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
+        str_lz4_decode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        str_lz4_decode_to_array(src, bit_set)
+    }
+}
+
+struct AutoStringCodec();
+
+impl StringCodec for AutoStringCodec {
+    fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        let encoding = select_encoding(src, &STRING_CANDIDATES, |e, s, d| {
+            get_str_codec(e).encode(s, d)
+        });
+        get_str_codec(encoding).encode(src, dst)
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
+        get_str_codec(block_encoding(src)?).decode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_str_codec(block_encoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub fn get_encoding(src: &[u8]) -> Encoding {
     if src.is_empty() {
         return Encoding::Unknown;
@@ -368,6 +508,7 @@ pub fn get_ts_codec(algo: Encoding) -> Box<dyn TimestampCodec + Send + Sync> {
         Encoding::Delta => Box::new(DeltaIntegerCodec()),
         Encoding::DeltaTs => Box::new(DeltaTsTimestampCodec()),
         Encoding::Quantile => Box::new(QuantileTimestampCodec()),
+        Encoding::Auto => Box::new(AutoTimestampCodec()),
         _ => Box::new(DeltaTsTimestampCodec()),
     }
 }
@@ -378,6 +519,7 @@ pub fn get_i64_codec(algo: Encoding) -> Box<dyn IntegerCodec + Send + Sync> {
         Encoding::Delta => Box::new(DeltaIntegerCodec()),
         Encoding::DeltaTs => Box::new(DeltaTsTimestampCodec()),
         Encoding::Quantile => Box::new(QuantileIntegerCodec()),
+        Encoding::Auto => Box::new(AutoIntegerCodec()),
         _ => Box::new(DeltaIntegerCodec()),
     }
 }
@@ -387,6 +529,7 @@ pub fn get_u64_codec(algo: Encoding) -> Box<dyn UnsignedCodec + Send + Sync> {
         Encoding::Null => Box::new(NullUnsignedCodec()),
         Encoding::Delta => Box::new(DeltaUnsignedCodec()),
         Encoding::Quantile => Box::new(QuantileUnsignedCodec()),
+        Encoding::Auto => Box::new(AutoUnsignedCodec()),
         _ => Box::new(DeltaUnsignedCodec()),
     }
 }
@@ -396,6 +539,9 @@ pub fn get_f64_codec(algo: Encoding) -> Box<dyn FloatCodec + Send + Sync> {
         Encoding::Null => Box::new(NullFloatCodec()),
         Encoding::Gorilla => Box::new(GorillaFloatCodec()),
         Encoding::Quantile => Box::new(QuantileFloatCodec()),
+        Encoding::Zstd => Box::new(ZstdFloatCodec()),
+        Encoding::Lz4 => Box::new(Lz4FloatCodec()),
+        Encoding::Auto => Box::new(AutoFloatCodec()),
         _ => Box::new(GorillaFloatCodec()),
     }
 }
@@ -408,6 +554,8 @@ pub fn get_str_codec(algo: Encoding) -> Box<dyn StringCodec + Send + Sync> {
         Encoding::Snappy => Box::new(SnappyStringCodec()),
         Encoding::Zstd => Box::new(ZstdStringCodec()),
         Encoding::Zlib => Box::new(ZlibStringCodec()),
+        Encoding::Lz4 => Box::new(Lz4StringCodec()),
+        Encoding::Auto => Box::new(AutoStringCodec()),
         _ => Box::new(SnappyStringCodec()),
     }
 }
@@ -416,6 +564,7 @@ pub fn get_bool_codec(algo: Encoding) -> Box<dyn BooleanCodec + Send + Sync> {
     match algo {
         Encoding::Null => Box::new(NullBooleanCodec()),
         Encoding::BitPack => Box::new(BitPackBooleanCodec()),
+        Encoding::Auto => Box::new(AutoBooleanCodec()),
         _ => Box::new(BitPackBooleanCodec()),
     }
 }
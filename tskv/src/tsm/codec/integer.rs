@@ -5,7 +5,8 @@ use arrow_array::builder::Int64Builder;
 use arrow_array::{ArrayRef, Int64Array};
 use integer_encoding::*;
 
-use super::{simple8b, CodecError};
+use super::{simd, simple8b, CodecError};
+use crate::byte_utils::decode_be_i64;
 use crate::tsm::codec::timestamp::{
     ts_pco_decode_to_array, ts_pco_encode, ts_without_compress_decode_to_array,
     ts_without_compress_encode,
@@ -166,6 +167,16 @@ fn decode_uncompressed_to_array(src: &[u8], bit_set: &NullBuffer) -> Result<Arra
     if src.is_empty() || src.len() & 0x7 != 0 {
         return Err(From::from("invalid uncompressed block length"));
     }
+    if bit_set.null_count() == 0 {
+        let mut values = src
+            .chunks_exact(8)
+            .take(bit_set.len())
+            .map(decode_be_i64)
+            .collect::<Vec<_>>();
+        simd::zigzag_decode(&mut values);
+        simd::prefix_sum(&mut values);
+        return Ok(Arc::new(Int64Array::from(values)));
+    }
     let mut i = 0;
     let mut prev: i64 = 0;
     let mut buf: [u8; 8] = [0; 8];
@@ -222,6 +233,16 @@ fn decode_simple8b_to_array(src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef
     let mut res = vec![];
     let mut buf: [u8; 8] = [0; 8];
     buf.copy_from_slice(&src[0..8]);
+    if bit_set.null_count() == 0 {
+        simple8b::decode(&src[8..], &mut res);
+        let mut values = Vec::with_capacity(res.len() + 1);
+        values.push(i64::from_be_bytes(buf));
+        values.extend(res.iter().map(|v| *v as i64));
+        values.truncate(bit_set.len());
+        simd::zigzag_decode(&mut values);
+        simd::prefix_sum(&mut values);
+        return Ok(Arc::new(Int64Array::from(values)));
+    }
     let mut first_val = true;
     let mut next = 0;
     let mut iter = res.iter();
@@ -1,13 +1,17 @@
+mod auto;
+pub mod bench;
 mod boolean;
 mod float;
 mod instance;
 mod integer;
+mod simd;
 mod simple8b;
 mod string;
 mod timestamp;
 mod unsigned;
 
 use std::error::Error;
+use std::sync::atomic::{AtomicI32, Ordering};
 
 pub use instance::*;
 use models::codec::Encoding;
@@ -19,3 +23,14 @@ const MAX_VAR_INT_32: usize = 5;
 const MAX_VAR_INT_64: usize = 10;
 
 pub type CodecError = Box<dyn Error + Send + Sync>;
+
+/// Compression level of the ZSTD codec, set by `storage.zstd_compress_level`.
+static ZSTD_COMPRESS_LEVEL: AtomicI32 = AtomicI32::new(3);
+
+pub fn set_zstd_compress_level(level: i32) {
+    ZSTD_COMPRESS_LEVEL.store(level, Ordering::Relaxed);
+}
+
+pub(crate) fn zstd_compress_level() -> i32 {
+    ZSTD_COMPRESS_LEVEL.load(Ordering::Relaxed)
+}
@@ -0,0 +1,181 @@
+//! Kernels of decoding the delta encoded timestamp and integer blocks.
+//!
+//! The AVX2 variants are chosen by the CPU features detected at runtime, the
+//! scalar ones are used on the other CPUs.
+
+/// Replace the values by the running sums of them, wrapping on overflow.
+pub(crate) fn prefix_sum(values: &mut [i64]) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // Safety: the CPU supports AVX2.
+        unsafe { x86::prefix_sum_avx2(values) };
+        return;
+    }
+    prefix_sum_scalar(values, 0);
+}
+
+/// Decode the zigzag encoded values in place, the values are the bits of the
+/// encoded `u64`s.
+pub(crate) fn zigzag_decode(values: &mut [i64]) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // Safety: the CPU supports AVX2.
+        unsafe { x86::zigzag_decode_avx2(values) };
+        return;
+    }
+    zigzag_decode_scalar(values);
+}
+
+/// Add `acc` to the running sums of the values, return the last sum.
+fn prefix_sum_scalar(values: &mut [i64], mut acc: i64) -> i64 {
+    for v in values.iter_mut() {
+        acc = acc.wrapping_add(*v);
+        *v = acc;
+    }
+    acc
+}
+
+fn zigzag_decode_scalar(values: &mut [i64]) {
+    for v in values.iter_mut() {
+        let u = *v as u64;
+        *v = ((u >> 1) as i64) ^ -((u & 1) as i64);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    use super::{prefix_sum_scalar, zigzag_decode_scalar};
+
+    const LANES: usize = 4;
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn prefix_sum_avx2(values: &mut [i64]) {
+        let zero = _mm256_setzero_si256();
+        let mut acc = 0_i64;
+        let mut chunks = values.chunks_exact_mut(LANES);
+        for chunk in &mut chunks {
+            let ptr = chunk.as_mut_ptr() as *mut __m256i;
+            // [a, b, c, d]
+            let v = _mm256_loadu_si256(ptr);
+            // [a, a + b, b + c, c + d]
+            let shifted = _mm256_blend_epi32::<0b0000_0011>(
+                _mm256_permute4x64_epi64::<0b10_01_00_00>(v),
+                zero,
+            );
+            let v = _mm256_add_epi64(v, shifted);
+            // [a, a + b, a + b + c, a + b + c + d]
+            let shifted = _mm256_blend_epi32::<0b0000_1111>(
+                _mm256_permute4x64_epi64::<0b01_00_00_00>(v),
+                zero,
+            );
+            let v = _mm256_add_epi64(v, shifted);
+            let v = _mm256_add_epi64(v, _mm256_set1_epi64x(acc));
+            _mm256_storeu_si256(ptr, v);
+            acc = _mm256_extract_epi64::<3>(v);
+        }
+        prefix_sum_scalar(chunks.into_remainder(), acc);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn zigzag_decode_avx2(values: &mut [i64]) {
+        let zero = _mm256_setzero_si256();
+        let one = _mm256_set1_epi64x(1);
+        let mut chunks = values.chunks_exact_mut(LANES);
+        for chunk in &mut chunks {
+            let ptr = chunk.as_mut_ptr() as *mut __m256i;
+            let v = _mm256_loadu_si256(ptr);
+            // (v >> 1) ^ -(v & 1)
+            let sign = _mm256_sub_epi64(zero, _mm256_and_si256(v, one));
+            let v = _mm256_xor_si256(_mm256_srli_epi64::<1>(v), sign);
+            _mm256_storeu_si256(ptr, v);
+        }
+        zigzag_decode_scalar(chunks.into_remainder());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::buffer::NullBuffer;
+    use arrow_array::Int64Array;
+    use models::codec::Encoding;
+    use rand::Rng;
+
+    use super::{prefix_sum, prefix_sum_scalar, zigzag_decode, zigzag_decode_scalar};
+    use crate::tsm::codec::{get_i64_codec, get_ts_codec};
+
+    #[test]
+    fn test_prefix_sum() {
+        let mut rng = rand::thread_rng();
+        for len in [0, 1, 3, 4, 5, 8, 1000, 1003] {
+            let src = (0..len)
+                .map(|_| rng.gen_range(-1_000_000_000..1_000_000_000))
+                .collect::<Vec<i64>>();
+            let mut expected = src.clone();
+            prefix_sum_scalar(&mut expected, 0);
+            let mut values = src.clone();
+            prefix_sum(&mut values);
+            assert_eq!(values, expected);
+        }
+
+        let mut values = vec![i64::MAX, 1, 1, 1, 1];
+        prefix_sum(&mut values);
+        assert_eq!(
+            values,
+            vec![i64::MAX, i64::MIN, i64::MIN + 1, i64::MIN + 2, i64::MIN + 3]
+        );
+    }
+
+    #[test]
+    fn test_zigzag_decode() {
+        let mut values = vec![0, 1, 2, 3, 4, -2, -1];
+        zigzag_decode(&mut values);
+        assert_eq!(values, vec![0, -1, 1, -2, 2, i64::MAX, i64::MIN]);
+
+        let mut rng = rand::thread_rng();
+        let src = (0..1001).map(|_| rng.gen::<i64>()).collect::<Vec<_>>();
+        let mut expected = src.clone();
+        zigzag_decode_scalar(&mut expected);
+        let mut values = src;
+        zigzag_decode(&mut values);
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_decode_delta_blocks() {
+        let mut rng = rand::thread_rng();
+        let timestamps = (0..1000_i64)
+            .map(|i| 1_700_000_000_000_000_000 + i * 1_000_000_000 + rng.gen_range(0..1000))
+            .collect::<Vec<_>>();
+        let integers = (0..1000)
+            .map(|_| rng.gen_range(-1000..1000))
+            .collect::<Vec<_>>();
+        // Deltas too large for simple8b are stored uncompressed.
+        let uncompressed = vec![i64::MIN, 0, i64::MAX, -1, 1];
+
+        for values in [&timestamps, &integers, &uncompressed] {
+            let bit_set = NullBuffer::new_valid(values.len());
+
+            let mut buf = Vec::new();
+            get_ts_codec(Encoding::DeltaTs)
+                .encode(values, &mut buf)
+                .unwrap();
+            let array = get_ts_codec(Encoding::DeltaTs)
+                .decode_to_array(&buf, &bit_set)
+                .unwrap();
+            let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            assert_eq!(array.values().as_ref(), values.as_slice());
+
+            let mut buf = Vec::new();
+            get_i64_codec(Encoding::Delta)
+                .encode(values, &mut buf)
+                .unwrap();
+            let array = get_i64_codec(Encoding::Delta)
+                .decode_to_array(&buf, &bit_set)
+                .unwrap();
+            let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            assert_eq!(array.values().as_ref(), values.as_slice());
+        }
+    }
+}
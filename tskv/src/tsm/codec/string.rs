@@ -12,7 +12,7 @@ use flate2::Compression as CompressionFlate;
 use integer_encoding::VarInt;
 use minivec::MiniVec;
 
-use super::CodecError;
+use super::{zstd_compress_level, CodecError};
 use crate::byte_utils::decode_be_u64;
 use crate::tsm::codec::Encoding;
 
@@ -24,9 +24,6 @@ const HEADER_LEN: usize = 1;
 /// Store `i32::MAX` as a `usize` for comparing with lengths in assertions
 const MAX_I32: usize = i32::MAX as usize;
 
-/// zstd compress level, select from -5 ~ 17
-const ZSTD_COMPRESS_LEVEL: i32 = 3;
-
 /// Encodes a slice of byte slices representing string data into a vector of
 /// bytes. Currently uses Snappy compression.
 pub fn str_snappy_encode(src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
@@ -100,7 +97,24 @@ pub fn str_zstd_encode(src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecErro
     }
 
     dst.push(Encoding::Zstd as u8);
-    zstd::stream::copy_encode(data.as_slice(), dst, ZSTD_COMPRESS_LEVEL)?;
+    zstd::stream::copy_encode(data.as_slice(), dst, zstd_compress_level())?;
+    Ok(())
+}
+
+pub fn str_lz4_encode(src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    let mut data = vec![];
+    for s in src {
+        let len = s.len() as u64;
+        data.extend_from_slice(len.to_be_bytes().as_slice());
+        data.extend_from_slice(s);
+    }
+
+    dst.push(Encoding::Lz4 as u8);
+    dst.append(&mut lz4_flex::compress_prepend_size(&data));
     Ok(())
 }
 
@@ -337,6 +351,27 @@ pub fn str_zstd_decode_to_array(src: &[u8], bit_set: &NullBuffer) -> Result<Arra
     split_stream_to_array(&data, bit_set)
 }
 
+pub fn str_lz4_decode(src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
+    if src.is_empty() {
+        return Ok(());
+    }
+    let data = lz4_flex::decompress_size_prepended(&src[1..])?;
+
+    split_stream(&data, dst)?;
+    Ok(())
+}
+
+pub fn str_lz4_decode_to_array(src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+    if src.is_empty() {
+        let null_value: Vec<Option<String>> = vec![None; bit_set.len()];
+        let array = StringArray::from(null_value);
+        return Ok(Arc::new(array));
+    }
+    let data = lz4_flex::decompress_size_prepended(&src[1..])?;
+
+    split_stream_to_array(&data, bit_set)
+}
+
 pub fn str_bzip_decode(src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
     if src.is_empty() {
         return Ok(());
@@ -493,6 +528,8 @@ mod tests {
         assert_eq!(dst.to_vec().len(), 0);
         str_bzip_encode(&src, &mut dst).unwrap();
         assert_eq!(dst.to_vec().len(), 0);
+        str_lz4_encode(&src, &mut dst).unwrap();
+        assert_eq!(dst.to_vec().len(), 0);
         str_without_compress_encode(&src, &mut dst).unwrap();
         assert_eq!(dst.to_vec().len(), 0);
 
@@ -744,6 +781,15 @@ mod tests {
         dst.clear();
         got.clear();
 
+        str_lz4_encode(&data, &mut dst).unwrap();
+        str_lz4_decode(&dst, &mut got).unwrap();
+        assert_eq!(data_exp, got);
+        let array_ref = str_lz4_decode_to_array(&dst, &null_bitset).expect("failed to encode src");
+        let array = array_ref.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(*array, expected);
+        dst.clear();
+        got.clear();
+
         str_without_compress_encode(&data, &mut dst).unwrap();
         str_without_compress_decode(&dst, &mut got).unwrap();
         assert_eq!(data_exp, got);
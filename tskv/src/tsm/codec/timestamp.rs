@@ -7,7 +7,7 @@ use integer_encoding::*;
 use pco::standalone::{simple_decompress, simpler_compress};
 use pco::DEFAULT_COMPRESSION_LEVEL;
 
-use super::{simple8b, CodecError};
+use super::{simd, simple8b, CodecError};
 use crate::byte_utils::decode_be_i64;
 use crate::tsm::codec::Encoding;
 
@@ -202,6 +202,12 @@ fn decode_uncompressed_to_array(src: &[u8], bit_set: &NullBuffer) -> Result<Arra
     if src.is_empty() || src.len() & 0x7 != 0 {
         return Err(From::from("invalid uncompressed block length"));
     }
+    if bit_set.null_count() == 0 {
+        let mut values = src.chunks_exact(8).map(decode_be_i64).collect::<Vec<_>>();
+        values.truncate(bit_set.len());
+        simd::prefix_sum(&mut values);
+        return Ok(Arc::new(Int64Array::from(values)));
+    }
 
     let mut i = 0;
     let mut prev = 0;
@@ -269,6 +275,15 @@ fn decode_simple8b_to_array(src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef
     let mut buf: [u8; 8] = [0; 8];
     buf.copy_from_slice(&src[1..9]);
     let mut next = i64::from_be_bytes(buf);
+    if bit_set.null_count() == 0 {
+        simple8b::decode(&src[9..], &mut res);
+        let mut values = Vec::with_capacity(res.len() + 1);
+        values.push(next);
+        values.extend(res.iter().map(|v| v.wrapping_mul(scaler) as i64));
+        values.truncate(bit_set.len());
+        simd::prefix_sum(&mut values);
+        return Ok(Arc::new(Int64Array::from(values)));
+    }
     let mut builder = Int64Builder::with_capacity(bit_set.len());
     let mut idx = 0;
     if bit_set.is_valid(idx) {
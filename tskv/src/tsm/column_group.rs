@@ -1,4 +1,5 @@
 use models::predicate::domain::TimeRange;
+use models::schema::tskv_table_schema::TskvTableSchema;
 use serde::{Deserialize, Serialize};
 use snafu::OptionExt;
 
@@ -62,6 +63,17 @@ impl ColumnGroup {
         self.pages.push(page);
     }
 
+    /// Returns true if any page is not encoded by the current encoding of its
+    /// column in the table schema.
+    pub fn encoding_changed(&self, table_schema: &TskvTableSchema) -> bool {
+        self.pages.iter().any(|p| {
+            table_schema
+                .column_name(p.meta.column.id)
+                .and_then(|name| table_schema.column(name))
+                .map_or(false, |c| c.encoding != p.meta.column.encoding)
+        })
+    }
+
     pub fn row_len(&self) -> usize {
         self.pages
             .first()
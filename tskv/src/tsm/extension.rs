@@ -0,0 +1,120 @@
+use crate::error::ReadTsmSnafu;
+use crate::tsm::field_index::FieldIndex;
+use crate::TskvResult;
+
+const EXTENSION_MAGIC: &[u8; 4] = b"TSMX";
+const EXTENSION_VERSION: u8 = 1;
+
+/// Kind of the section of the index of the fields.
+const SECTION_FIELD_INDEX: u8 = 1;
+
+/// Optional data of a tsm file stored after the chunk group meta in the meta
+/// of the file.
+///
+/// magic(4) | version(1) | [ kind(1) | len(4, BE) | payload(len) ]*
+///
+/// Sections of unknown kinds are skipped, so new sections can be added without
+/// moving the others. Files written without the extension, or with an unknown
+/// version of it, have an empty one.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct TsmExtension {
+    pub field_index: FieldIndex,
+}
+
+impl TsmExtension {
+    /// Returns nothing if all the sections are empty.
+    pub fn serialize(&self) -> TskvResult<Vec<u8>> {
+        let mut buffer = vec![];
+        if self.field_index.is_empty() {
+            return Ok(buffer);
+        }
+        buffer.extend_from_slice(EXTENSION_MAGIC);
+        buffer.push(EXTENSION_VERSION);
+        write_section(
+            &mut buffer,
+            SECTION_FIELD_INDEX,
+            &self.field_index.serialize()?,
+        );
+        Ok(buffer)
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> TskvResult<Self> {
+        let mut extension = Self::default();
+        let header_len = EXTENSION_MAGIC.len() + 1;
+        if bytes.len() < header_len
+            || &bytes[..EXTENSION_MAGIC.len()] != EXTENSION_MAGIC
+            || bytes[EXTENSION_MAGIC.len()] != EXTENSION_VERSION
+        {
+            return Ok(extension);
+        }
+
+        let mut pos = header_len;
+        while pos < bytes.len() {
+            if bytes.len() - pos < 5 {
+                return Err(truncated_error());
+            }
+            let kind = bytes[pos];
+            let len = u32::from_be_bytes(bytes[pos + 1..pos + 5].try_into().unwrap()) as usize;
+            pos += 5;
+            if bytes.len() - pos < len {
+                return Err(truncated_error());
+            }
+            let payload = &bytes[pos..pos + len];
+            pos += len;
+            if kind == SECTION_FIELD_INDEX {
+                extension.field_index = FieldIndex::deserialize(payload)?;
+            }
+        }
+
+        Ok(extension)
+    }
+}
+
+fn write_section(buffer: &mut Vec<u8>, kind: u8, payload: &[u8]) {
+    buffer.push(kind);
+    buffer.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(payload);
+}
+
+fn truncated_error() -> crate::TskvError {
+    ReadTsmSnafu {
+        reason: "tsm extension is truncated".to_string(),
+    }
+    .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{write_section, TsmExtension, EXTENSION_MAGIC, EXTENSION_VERSION};
+
+    #[test]
+    fn test_tsm_extension() {
+        assert!(TsmExtension::default().serialize().unwrap().is_empty());
+        assert_eq!(
+            TsmExtension::deserialize(&[]).unwrap(),
+            TsmExtension::default()
+        );
+
+        let mut extension = TsmExtension::default();
+        extension
+            .field_index
+            .insert("t", 1, 1, [b"a".as_slice(), b"b".as_slice()]);
+        let bytes = extension.serialize().unwrap();
+        assert_eq!(TsmExtension::deserialize(&bytes).unwrap(), extension);
+
+        // Sections of unknown kinds are skipped.
+        let mut with_unknown = bytes.clone();
+        write_section(&mut with_unknown, 200, b"unknown");
+        assert_eq!(TsmExtension::deserialize(&with_unknown).unwrap(), extension);
+
+        // Unknown versions are ignored.
+        let mut unknown_version = bytes.clone();
+        unknown_version[EXTENSION_MAGIC.len()] = EXTENSION_VERSION + 1;
+        assert_eq!(
+            TsmExtension::deserialize(&unknown_version).unwrap(),
+            TsmExtension::default()
+        );
+
+        assert!(TsmExtension::deserialize(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use models::{ColumnId, SeriesId};
+use serde::{Deserialize, Serialize};
+use snafu::IntoError;
+
+use crate::error::{DecodeSnafu, EncodeSnafu};
+use crate::TskvResult;
+
+/// Inverted index of the values of the indexed string fields in a tsm file,
+/// stored in the [`TsmExtension`](super::extension::TsmExtension) of the file.
+/// Files written before the index was added are indexed when compacted.
+///
+/// <table, <column_id, ColumnIndex>>
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldIndex {
+    tables: BTreeMap<String, BTreeMap<ColumnId, ColumnIndex>>,
+}
+
+/// Posting lists of the values of a column.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnIndex {
+    /// Series whose all values of the column are indexed.
+    series: BTreeSet<SeriesId>,
+    /// <value, series>
+    values: BTreeMap<Vec<u8>, BTreeSet<SeriesId>>,
+}
+
+impl FieldIndex {
+    pub fn serialize(&self) -> TskvResult<Vec<u8>> {
+        bincode::serialize(&self).map_err(|e| EncodeSnafu.into_error(e))
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> TskvResult<Self> {
+        bincode::deserialize(bytes).map_err(|e| DecodeSnafu.into_error(e))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn insert<'a>(
+        &mut self,
+        table: &str,
+        column_id: ColumnId,
+        series_id: SeriesId,
+        values: impl IntoIterator<Item = &'a [u8]>,
+    ) {
+        let column = self
+            .tables
+            .entry(table.to_string())
+            .or_default()
+            .entry(column_id)
+            .or_default();
+        column.series.insert(series_id);
+        for value in values {
+            column
+                .values
+                .entry(value.to_vec())
+                .or_default()
+                .insert(series_id);
+        }
+    }
+
+    /// Remove the series from the index of the table, it is not covered by the
+    /// index any more.
+    pub fn remove_series(&mut self, table: &str, series_id: SeriesId) {
+        if let Some(columns) = self.tables.get_mut(table) {
+            for column in columns.values_mut() {
+                column.series.remove(&series_id);
+                column.values.retain(|_, series| {
+                    series.remove(&series_id);
+                    !series.is_empty()
+                });
+            }
+            columns.retain(|_, column| !column.series.is_empty());
+            if columns.is_empty() {
+                self.tables.remove(table);
+            }
+        }
+    }
+
+    /// Returns true if the series has none of the values for any one of the
+    /// columns, so no row of the series matches the equality predicates.
+    pub fn prune_series(
+        &self,
+        table: &str,
+        series_id: SeriesId,
+        predicates: &[(ColumnId, Vec<Vec<u8>>)],
+    ) -> bool {
+        let columns = match self.tables.get(table) {
+            Some(columns) => columns,
+            None => return false,
+        };
+        predicates
+            .iter()
+            .any(|(column_id, values)| match columns.get(column_id) {
+                Some(column) if column.series.contains(&series_id) => !values.iter().any(|v| {
+                    column
+                        .values
+                        .get(v)
+                        .map_or(false, |series| series.contains(&series_id))
+                }),
+                _ => false,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FieldIndex;
+
+    #[test]
+    fn test_field_index() {
+        let mut index = FieldIndex::default();
+        index.insert("t", 1, 1, [b"a".as_slice(), b"b".as_slice()]);
+        index.insert("t", 1, 2, [b"b".as_slice()]);
+        index.insert("t", 1, 3, Vec::<&[u8]>::new());
+
+        let index = FieldIndex::deserialize(&index.serialize().unwrap()).unwrap();
+        let pred_a = vec![(1, vec![b"a".to_vec()])];
+        assert!(!index.prune_series("t", 1, &pred_a));
+        assert!(index.prune_series("t", 2, &pred_a));
+        assert!(index.prune_series("t", 3, &pred_a));
+        // Not covered by the index.
+        assert!(!index.prune_series("t", 4, &pred_a));
+        assert!(!index.prune_series("t1", 2, &pred_a));
+        assert!(!index.prune_series("t", 2, &[(2, vec![b"a".to_vec()])]));
+        // IN list
+        let pred_in = vec![(1, vec![b"a".to_vec(), b"b".to_vec()])];
+        assert!(!index.prune_series("t", 2, &pred_in));
+
+        let mut index = index;
+        index.remove_series("t", 2);
+        assert!(!index.prune_series("t", 2, &pred_a));
+        index.remove_series("t", 1);
+        index.remove_series("t", 3);
+        assert!(index.is_empty());
+    }
+}
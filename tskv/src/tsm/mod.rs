@@ -2,6 +2,8 @@ pub mod chunk;
 pub mod chunk_group;
 pub mod codec;
 pub mod column_group;
+pub mod extension;
+pub mod field_index;
 pub mod footer;
 pub mod mutable_column;
 pub mod mutable_column_ref;
@@ -122,8 +122,8 @@ impl Page {
                     .encode(&target_column, &mut buf)
                     .context(EncodeSnafu)?;
                 PageStatistics::Bool(ValueStatistics::new(
-                    None,
-                    None,
+                    target_column.iter().min().copied(),
+                    target_column.iter().max().copied(),
                     None,
                     (array.len() - target_column.len()) as u64,
                 ))
@@ -151,10 +151,10 @@ impl Page {
                 encoder
                     .encode(&target_column, &mut buf)
                     .context(EncodeSnafu)?;
-                PageStatistics::I64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::I64(value_statistics(
+                    target_column.len(),
+                    min,
+                    max,
                     (array.len() - target_column.len()) as u64,
                 ))
             }
@@ -224,10 +224,10 @@ impl Page {
                 encoder
                     .encode(&target_column, &mut buf)
                     .context(EncodeSnafu)?;
-                PageStatistics::I64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::I64(value_statistics(
+                    target_column.len(),
+                    min,
+                    max,
                     (array.len() - target_column.len()) as u64,
                 ))
             }
@@ -257,10 +257,10 @@ impl Page {
                 encoder
                     .encode(&target_column, &mut buf)
                     .context(EncodeSnafu)?;
-                PageStatistics::U64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::U64(value_statistics(
+                    target_column.len(),
+                    min,
+                    max,
                     (array.len() - target_column.len()) as u64,
                 ))
             }
@@ -290,10 +290,10 @@ impl Page {
                 encoder
                     .encode(&target_column, &mut buf)
                     .context(EncodeSnafu)?;
-                PageStatistics::F64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::F64(value_statistics(
+                    target_column.len(),
+                    min,
+                    max,
                     (array.len() - target_column.len()) as u64,
                 ))
             }
@@ -354,7 +354,6 @@ impl Page {
     }
 
     pub fn col_to_page(column: &MutableColumn) -> TskvResult<Page> {
-        let null_count = 1;
         let len_bitset = ((column.valid().len() + 7) >> 3) as u32;
         let data_len = column.valid().len() as u64;
         let mut buf = vec![];
@@ -376,11 +375,11 @@ impl Page {
                     .encode(&target_array, &mut buf)
                     .context(EncodeSnafu)?;
 
-                PageStatistics::F64(ValueStatistics::new(
-                    Some(*min),
-                    Some(*max),
-                    None,
-                    null_count,
+                PageStatistics::F64(value_statistics(
+                    target_array.len(),
+                    *min,
+                    *max,
+                    data_len - target_array.len() as u64,
                 ))
             }
             PrimaryColumnData::I64(array, min, max) => {
@@ -406,11 +405,11 @@ impl Page {
                         .encode(&target_array, &mut buf)
                         .context(EncodeSnafu)?;
                 }
-                PageStatistics::I64(ValueStatistics::new(
-                    Some(*min),
-                    Some(*max),
-                    None,
-                    null_count,
+                PageStatistics::I64(value_statistics(
+                    target_array.len(),
+                    *min,
+                    *max,
+                    data_len - target_array.len() as u64,
                 ))
             }
             PrimaryColumnData::U64(array, min, max) => {
@@ -430,11 +429,11 @@ impl Page {
                     .encode(&target_array, &mut buf)
                     .context(EncodeSnafu)?;
 
-                PageStatistics::U64(ValueStatistics::new(
-                    Some(*min),
-                    Some(*max),
-                    None,
-                    null_count,
+                PageStatistics::U64(value_statistics(
+                    target_array.len(),
+                    *min,
+                    *max,
+                    data_len - target_array.len() as u64,
                 ))
             }
             PrimaryColumnData::String(array, min, max) => {
@@ -454,11 +453,11 @@ impl Page {
                     .encode(&target_array, &mut buf)
                     .context(EncodeSnafu)?;
 
-                PageStatistics::Bytes(ValueStatistics::new(
-                    Some(min.as_bytes().to_vec()),
-                    Some(max.as_bytes().to_vec()),
-                    None,
-                    null_count,
+                PageStatistics::Bytes(value_statistics(
+                    target_array.len(),
+                    min.as_bytes().to_vec(),
+                    max.as_bytes().to_vec(),
+                    data_len - target_array.len() as u64,
                 ))
             }
             PrimaryColumnData::Bool(array, min, max) => {
@@ -478,11 +477,11 @@ impl Page {
                     .encode(&target_array, &mut buf)
                     .context(EncodeSnafu)?;
 
-                PageStatistics::Bool(ValueStatistics::new(
-                    Some(*min),
-                    Some(*max),
-                    None,
-                    null_count,
+                PageStatistics::Bool(value_statistics(
+                    target_array.len(),
+                    *min,
+                    *max,
+                    data_len - target_array.len() as u64,
                 ))
             }
         };
@@ -514,10 +513,10 @@ impl Page {
             PrimaryColumnDataRef::Bool(values, min, max) => {
                 let encoder = get_bool_codec(table_column.encoding());
                 encoder.encode(&values, &mut buffer).context(EncodeSnafu)?;
-                PageStatistics::Bool(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::Bool(value_statistics(
+                    values.len(),
+                    min,
+                    max,
                     column_data_len - values.len() as u64,
                 ))
             }
@@ -525,10 +524,10 @@ impl Page {
             PrimaryColumnDataRef::F64(values, min, max) => {
                 let encoder = get_f64_codec(table_column.encoding());
                 encoder.encode(&values, &mut buffer).context(EncodeSnafu)?;
-                PageStatistics::F64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::F64(value_statistics(
+                    values.len(),
+                    min,
+                    max,
                     column_data_len - values.len() as u64,
                 ))
             }
@@ -544,10 +543,10 @@ impl Page {
                         encoder.encode(&values, &mut buffer).context(EncodeSnafu)?;
                     }
                 };
-                PageStatistics::I64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::I64(value_statistics(
+                    values.len(),
+                    min,
+                    max,
                     column_data_len - values.len() as u64,
                 ))
             }
@@ -555,10 +554,10 @@ impl Page {
             PrimaryColumnDataRef::U64(values, min, max) => {
                 let encoder = get_u64_codec(table_column.encoding());
                 encoder.encode(&values, &mut buffer).context(EncodeSnafu)?;
-                PageStatistics::U64(ValueStatistics::new(
-                    Some(min),
-                    Some(max),
-                    None,
+                PageStatistics::U64(value_statistics(
+                    values.len(),
+                    min,
+                    max,
                     column_data_len - values.len() as u64,
                 ))
             }
@@ -566,10 +565,10 @@ impl Page {
             PrimaryColumnDataRef::String(values, min, max) => {
                 let encoder = get_str_codec(table_column.encoding());
                 encoder.encode(&values, &mut buffer).context(EncodeSnafu)?;
-                PageStatistics::Bytes(ValueStatistics::new(
-                    Some(min.to_vec()),
-                    Some(max.to_vec()),
-                    None,
+                PageStatistics::Bytes(value_statistics(
+                    values.len(),
+                    min.to_vec(),
+                    max.to_vec(),
                     column_data_len - values.len() as u64,
                 ))
             }
@@ -597,6 +596,16 @@ impl Page {
     }
 }
 
+/// The statistics of the non-null values of a page, the min and max are None
+/// if all the values are null.
+fn value_statistics<T>(num_values: usize, min: T, max: T, null_count: u64) -> ValueStatistics<T> {
+    if num_values == 0 {
+        ValueStatistics::new(None, None, None, null_count)
+    } else {
+        ValueStatistics::new(Some(min), Some(max), None, null_count)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PageMeta {
     pub(crate) num_values: u32,
@@ -613,6 +622,18 @@ pub enum PageStatistics {
     Bytes(ValueStatistics<Vec<u8>>),
 }
 
+impl PageStatistics {
+    pub fn null_count(&self) -> u64 {
+        match self {
+            Self::Bool(v) => v.null_count(),
+            Self::F64(v) => v.null_count(),
+            Self::I64(v) => v.null_count(),
+            Self::U64(v) => v.null_count(),
+            Self::Bytes(v) => v.null_count(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PageWriteSpec {
     pub(crate) offset: u64,
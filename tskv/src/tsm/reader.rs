@@ -33,10 +33,12 @@ use crate::tsm::codec::{
     get_bool_codec, get_encoding, get_f64_codec, get_i64_codec, get_str_codec, get_ts_codec,
     get_u64_codec,
 };
+use crate::tsm::extension::TsmExtension;
+use crate::tsm::field_index::FieldIndex;
 use crate::tsm::footer::{Footer, TsmVersion};
 use crate::tsm::page::{Page, PageMeta, PageStatistics, PageWriteSpec};
 use crate::tsm::{ColumnGroupID, TsmTombstone, FOOTER_SIZE};
-use crate::{file_utils, ColumnFileId, TskvError};
+use crate::{file_utils, tiering, ColumnFileId, TskvError};
 
 #[derive(Clone)]
 pub struct TsmMetaData {
@@ -44,6 +46,7 @@ pub struct TsmMetaData {
     chunk_group_meta: Arc<ChunkGroupMeta>,
     chunk_group: BTreeMap<String, Arc<ChunkGroup>>,
     chunk: BTreeMap<SeriesId, Arc<Chunk>>,
+    field_index: Arc<FieldIndex>,
 }
 
 impl TsmMetaData {
@@ -52,12 +55,14 @@ impl TsmMetaData {
         chunk_group_meta: Arc<ChunkGroupMeta>,
         chunk_group: BTreeMap<String, Arc<ChunkGroup>>,
         chunk: BTreeMap<SeriesId, Arc<Chunk>>,
+        field_index: Arc<FieldIndex>,
     ) -> Self {
         Self {
             footer,
             chunk_group_meta,
             chunk_group,
             chunk,
+            field_index,
         }
     }
 
@@ -77,6 +82,10 @@ impl TsmMetaData {
         &self.chunk
     }
 
+    pub fn field_index(&self) -> Arc<FieldIndex> {
+        self.field_index.clone()
+    }
+
     pub fn table_schema(&self, table_name: &str) -> Option<TskvTableSchemaRef> {
         self.chunk_group_meta.table_schema(table_name)
     }
@@ -120,11 +129,16 @@ pub struct TsmReader {
 impl TsmReader {
     pub async fn open(tsm_path: impl AsRef<Path>) -> TskvResult<Self> {
         let path = tsm_path.as_ref().to_path_buf();
-        let file_system = LocalFileSystem::new(LocalFileType::ThreadPool);
-        let reader = file_system
-            .open_file_reader(&path)
-            .await
-            .map_err(|e| TskvError::FileSystemError { source: e })?;
+        let reader = match tiering::open_tiered_file(&path).await? {
+            Some(reader) => reader,
+            None => {
+                let file_system = LocalFileSystem::new(LocalFileType::ThreadPool);
+                file_system
+                    .open_file_reader(&path)
+                    .await
+                    .map_err(|e| TskvError::FileSystemError { source: e })?
+            }
+        };
 
         let file_id = file_utils::get_tsm_file_id_by_path(&path)?;
 
@@ -147,6 +161,7 @@ impl TsmReader {
         let chunk_group_meta = read_chunk_group_meta(tsm_meta_buffer, &footer).await?;
         let chunk_group = read_chunk_groups(tsm_meta_buffer, &chunk_group_meta).await?;
         let chunk = read_chunk(tsm_meta_buffer, &chunk_group).await?;
+        let extension = read_extension(tsm_meta_buffer, &footer).await?;
 
         let tombstone_path = path.parent().unwrap_or_else(|| Path::new("/"));
         let tombstone = Arc::new(TsmTombstone::open(tombstone_path, file_id).await?);
@@ -156,6 +171,7 @@ impl TsmReader {
             chunk_group_meta,
             chunk_group,
             chunk,
+            Arc::new(extension.field_index),
         ));
 
         Ok(Self {
@@ -191,6 +207,10 @@ impl TsmReader {
         &self.tsm_meta.chunk
     }
 
+    pub fn field_index(&self) -> &FieldIndex {
+        &self.tsm_meta.field_index
+    }
+
     pub fn tsm_meta_data(&self) -> Arc<TsmMetaData> {
         self.tsm_meta.clone()
     }
@@ -441,6 +461,16 @@ pub async fn read_chunk_group_meta(
     Ok(Arc::new(specs))
 }
 
+/// Read the extension after the chunk group meta, files written without it
+/// have nothing after the chunk group meta.
+pub async fn read_extension(buffer: &[u8], footer: &Footer) -> TskvResult<TsmExtension> {
+    let pos = (footer.table().chunk_group_offset() + footer.table().chunk_group_size()) as usize;
+    if pos >= buffer.len() {
+        return Ok(TsmExtension::default());
+    }
+    TsmExtension::deserialize(&buffer[pos..])
+}
+
 pub async fn read_chunk_groups(
     buffer: &[u8],
     chunk_group_meta: &ChunkGroupMeta,
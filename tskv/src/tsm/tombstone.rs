@@ -367,6 +367,13 @@ impl TsmTombstone {
             .read()
             .get_all_fields_excluded_time_range(time_range)
     }
+
+    /// Returns true if any data of the series in the `TimeRange` is excluded.
+    pub fn overlaps_series_time_range(&self, series_id: SeriesId, time_range: &TimeRange) -> bool {
+        self.cache
+            .read()
+            .overlaps_series_time_range(series_id, time_range)
+    }
 }
 
 async fn write_tombstone_record(
@@ -539,6 +546,13 @@ impl TsmTombstoneCache {
         self.all_excluded.includes(time_range)
     }
 
+    pub fn overlaps_series_time_range(&self, series_id: SeriesId, time_range: &TimeRange) -> bool {
+        self.all_excluded.overlaps(time_range)
+            || self.column_excluded.iter().any(|((sid, _), time_ranges)| {
+                *sid == series_id && time_ranges.overlaps(time_range)
+            })
+    }
+
     pub fn get_all_fields_excluded_time_range(&self, time_range: &TimeRange) -> Vec<TimeRange> {
         let mut trs = Vec::new();
         for all in self.all_excluded.time_ranges() {
@@ -1,14 +1,15 @@
 use std::cmp::{max, min};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::IoSlice;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use arrow_array::RecordBatch;
+use arrow_array::{Array, RecordBatch, StringArray};
+use bytes::Bytes;
 use models::codec::Encoding;
 use models::predicate::domain::TimeRange;
-use models::schema::tskv_table_schema::{TableColumn, TskvTableSchemaRef};
-use models::{SeriesId, SeriesKey};
+use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchemaRef};
+use models::{ColumnId, SeriesId, SeriesKey, ValueType};
 use snafu::{OptionExt, ResultExt};
 use utils::BloomFilter;
 
@@ -22,6 +23,8 @@ use crate::tsm::chunk::{Chunk, ChunkStatics, ChunkWriteSpec};
 use crate::tsm::chunk_group::{ChunkGroup, ChunkGroupMeta, ChunkGroupWriteSpec};
 use crate::tsm::codec::get_str_codec;
 use crate::tsm::column_group::ColumnGroup;
+use crate::tsm::extension::TsmExtension;
+use crate::tsm::field_index::FieldIndex;
 use crate::tsm::footer::{Footer, SeriesMeta, TableMeta, TsmVersion};
 use crate::tsm::page::{Page, PageStatistics, PageWriteSpec};
 use crate::tsm::reader::TsmMetaData;
@@ -56,6 +59,13 @@ pub struct TsmWriter {
     chunk_specs: BTreeMap<String, ChunkGroup>,
     /// [ChunkGroupWriteSpec]
     chunk_group_specs: ChunkGroupMeta,
+    /// Index of the values of the indexed string fields.
+    field_index: FieldIndex,
+    /// <table, series> written by schemas with different indexed columns,
+    /// which are not covered by the index.
+    unindexed_series: HashSet<(String, SeriesId)>,
+    /// <table, series> and the indexed columns of the first write of it.
+    indexed_columns: HashMap<(String, SeriesId), Vec<ColumnId>>,
     footer: Footer,
     state: State,
 
@@ -107,6 +117,9 @@ impl TsmWriter {
             page_specs: Default::default(),
             chunk_specs: Default::default(),
             chunk_group_specs: Default::default(),
+            field_index: Default::default(),
+            unindexed_series: Default::default(),
+            indexed_columns: Default::default(),
             footer: Footer::empty(tsm_v),
             state: State::Initialised,
             tsm_meta_encode: encoding,
@@ -198,6 +211,17 @@ impl TsmWriter {
         Ok(())
     }
 
+    pub async fn write_extension(&mut self, buffer: &mut Vec<u8>) -> TskvResult<()> {
+        for (table, series_id) in self.unindexed_series.drain() {
+            self.field_index.remove_series(&table, series_id);
+        }
+        let extension = TsmExtension {
+            field_index: self.field_index.clone(),
+        };
+        buffer.extend_from_slice(&extension.serialize()?);
+        Ok(())
+    }
+
     pub async fn write_chunk(&mut self, buffer: &mut Vec<u8>) -> TskvResult<SeriesMeta> {
         let chunk_offset = self.writer.len() as u64;
         for (table, group) in &self.page_specs {
@@ -328,7 +352,11 @@ impl TsmWriter {
         let mut column_group = self.create_column_group(schema.clone(), series_id, &series_key);
 
         let table_name = schema.name.clone();
+        let indexed_columns = self.check_indexed_columns(&schema, series_id);
         for page in pages {
+            if indexed_columns.contains(&page.meta.column.id) {
+                self.index_page(&table_name, series_id, &page)?;
+            }
             let offset = self.writer.len() as u64;
             let size = self.writer.write(&page.bytes).await.context(IOSnafu)?;
             let spec = PageWriteSpec {
@@ -349,6 +377,45 @@ impl TsmWriter {
         Ok(())
     }
 
+    /// Returns the indexed string fields of the schema. The series is not
+    /// covered by the index if the indexed columns differ from the last write.
+    fn check_indexed_columns(
+        &mut self,
+        schema: &TskvTableSchemaRef,
+        series_id: SeriesId,
+    ) -> Vec<ColumnId> {
+        let columns = schema
+            .columns()
+            .iter()
+            .filter(|c| c.indexed && matches!(c.column_type, ColumnType::Field(ValueType::String)))
+            .map(|c| c.id)
+            .collect::<Vec<_>>();
+        let key = (schema.name.clone(), series_id);
+        match self.indexed_columns.get(&key) {
+            Some(last) if last != &columns => {
+                self.unindexed_series.insert(key);
+            }
+            Some(_) => {}
+            None => {
+                self.indexed_columns.insert(key, columns.clone());
+            }
+        }
+        columns
+    }
+
+    fn index_page(&mut self, table: &str, series_id: SeriesId, page: &Page) -> TskvResult<()> {
+        let array = page.to_arrow_array()?;
+        if let Some(values) = array.as_any().downcast_ref::<StringArray>() {
+            self.field_index.insert(
+                table,
+                page.meta.column.id,
+                series_id,
+                values.iter().flatten().map(|v| v.as_bytes()),
+            );
+        }
+        Ok(())
+    }
+
     pub async fn write_raw(
         &mut self,
         schema: TskvTableSchemaRef,
@@ -372,7 +439,23 @@ impl TsmWriter {
             .context(CommonSnafu {
                 reason: format!("column group not found: {}", column_group_id),
             })?;
+        let series_id = meta.series_id();
+        let series_key = meta.series_key().clone();
+        let table_name = schema.name.clone();
+        let indexed_columns = self.check_indexed_columns(&schema, series_id);
+        let mut raw_offset = 0_usize;
         for spec in column_group.pages() {
+            // The raw pages of the indexed columns are decoded to index them.
+            if indexed_columns.contains(&spec.meta.column.id) {
+                let bytes = raw
+                    .get(raw_offset..raw_offset + spec.size as usize)
+                    .context(CommonSnafu {
+                        reason: format!("raw column group {} is truncated", column_group_id),
+                    })?;
+                let page = Page::new(Bytes::copy_from_slice(bytes), spec.meta.clone());
+                self.index_page(&table_name, series_id, &page)?;
+            }
+            raw_offset += spec.size as usize;
             let spec = PageWriteSpec {
                 offset,
                 size: spec.size,
@@ -383,9 +466,6 @@ impl TsmWriter {
         }
         self.insert_schema(schema.clone());
         new_column_group.time_range_merge(column_group.time_range());
-        let series_id = meta.series_id();
-        let series_key = meta.series_key().clone();
-        let table_name = schema.name.clone();
         self.page_specs
             .entry(table_name.clone())
             .or_default()
@@ -480,6 +560,9 @@ impl TsmWriter {
             page_specs: Default::default(),
             chunk_specs: Default::default(),
             chunk_group_specs: Default::default(),
+            field_index: Default::default(),
+            unindexed_series: Default::default(),
+            indexed_columns: Default::default(),
             footer: Footer::empty(TsmVersion::V1),
             state: State::Initialised,
             tsm_meta_encode,
@@ -496,6 +579,7 @@ impl TsmWriter {
                 .insert(chunk.series_id(), chunk.as_ref().clone());
         }
         writer.page_specs = page_specs;
+        writer.field_index = meta.field_index().as_ref().clone();
         writer.finish().await?;
         Ok(writer)
     }
@@ -506,6 +590,7 @@ impl TsmWriter {
         self.write_chunk_group(&mut buffer).await?;
         self.write_chunk_group_specs(series_meta, &mut buffer)
             .await?;
+        self.write_extension(&mut buffer).await?;
         let mut buffer = match self.tsm_meta_encode {
             Encoding::Null => buffer,
             _ => {
@@ -531,7 +616,7 @@ pub mod test {
     use std::sync::Arc;
 
     use arrow::datatypes::TimeUnit;
-    use arrow_array::{ArrayRef, Int64Array, RecordBatch, TimestampNanosecondArray};
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, TimestampNanosecondArray};
     use models::codec::Encoding;
     use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
     use models::{SeriesKey, ValueType};
@@ -743,4 +828,84 @@ pub mod test {
             panic!("meta not found");
         }
     }
+
+    #[tokio::test]
+    async fn test_prune_series_by_field_index() {
+        let mut indexed = TableColumn::new(
+            1,
+            "status".to_string(),
+            ColumnType::Field(ValueType::String),
+            Encoding::default(),
+        );
+        indexed.indexed = true;
+        let schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "test_index".to_string(),
+            vec![
+                TableColumn::new(
+                    0,
+                    "time".to_string(),
+                    ColumnType::Time(TimeUnit::Nanosecond),
+                    Encoding::default(),
+                ),
+                indexed,
+            ],
+        ));
+        let batch = |status: Vec<&str>| {
+            RecordBatch::try_new(
+                schema.to_record_data_schema(),
+                vec![
+                    ts_column((1..=status.len() as i64).collect()),
+                    Arc::new(StringArray::from(status)),
+                ],
+            )
+            .unwrap()
+        };
+        let ok = vec![(1, vec![b"ok".to_vec()])];
+        let failed = vec![(1, vec![b"failed".to_vec()])];
+
+        let path = "/tmp/test/tsm_field_index";
+        let mut tsm_writer = TsmWriter::open(&PathBuf::from(path), 1, 0, false, Encoding::Zstd)
+            .await
+            .unwrap();
+        tsm_writer
+            .write_record_batch(1, SeriesKey::default(), schema.clone(), batch(vec!["ok"]))
+            .await
+            .unwrap();
+        tsm_writer
+            .write_record_batch(
+                2,
+                SeriesKey::default(),
+                schema.clone(),
+                batch(vec!["ok", "failed"]),
+            )
+            .await
+            .unwrap();
+        tsm_writer.finish().await.unwrap();
+
+        let tsm_reader = TsmReader::open(tsm_writer.path).await.unwrap();
+        let index = tsm_reader.field_index();
+        assert!(!index.prune_series("test_index", 1, &ok));
+        assert!(index.prune_series("test_index", 1, &failed));
+        assert!(!index.prune_series("test_index", 2, &failed));
+
+        // The raw column groups copied by the compaction are indexed too.
+        let path2 = "/tmp/test/tsm_field_index_raw";
+        let mut tsm_writer2 = TsmWriter::open(&PathBuf::from(path2), 2, 0, false, Encoding::Zstd)
+            .await
+            .unwrap();
+        for series_id in [1, 2] {
+            let meta = tsm_reader.chunk().get(&series_id).unwrap().clone();
+            let raw = tsm_reader.read_datablock_raw(series_id, 0).await.unwrap();
+            tsm_writer2
+                .write_raw(schema.clone(), meta, 0, raw)
+                .await
+                .unwrap();
+        }
+        tsm_writer2.finish().await.unwrap();
+
+        let tsm_reader2 = TsmReader::open(tsm_writer2.path).await.unwrap();
+        assert_eq!(tsm_reader2.field_index(), tsm_reader.field_index());
+    }
 }
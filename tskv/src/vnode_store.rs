@@ -1,19 +1,21 @@
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use metrics::average::U64Average;
 use models::meta_data::VnodeId;
 use models::predicate::domain::{ResolvedPredicate, TimeRange, TimeRanges};
-use models::utils::now_timestamp_secs;
+use models::utils::{now_timestamp_nanos, now_timestamp_secs};
 use models::{ColumnId, SeriesId, SeriesKey};
+use parking_lot::Mutex;
 use protos::kv_service::{raft_write_command, WritePointsResponse, *};
 use replication::EngineMetrics;
 use snafu::{OptionExt, ResultExt};
 use tokio::sync::RwLock;
 use trace::span_ext::SpanExt;
 use trace::{debug, error, info, Span, SpanContext};
-use utils::precision::Precision;
+use utils::precision::{timestamp_convert, Precision};
 
 use crate::compaction::job::FlushJob;
 use crate::compaction::FlushReq;
@@ -21,6 +23,8 @@ use crate::database::Database;
 use crate::error::{IndexErrSnafu, InvalidParamSnafu, InvalidPointTableSnafu, TskvResult};
 use crate::index::ts_index::TSIndex;
 use crate::schema::error::{FieldNotFoundSnafu, TableNotFoundSnafu};
+use crate::table_writes::TableWrites;
+use crate::tiering::TieredStorage;
 use crate::tsfamily::tseries_family::TseriesFamily;
 use crate::{TsKvContext, VnodeSnapshot};
 
@@ -34,6 +38,7 @@ pub struct VnodeStorage {
     ts_family: Arc<RwLock<TseriesFamily>>,
 
     snapshots: Vec<VnodeSnapshot>,
+    table_writes: Arc<Mutex<TableWrites>>,
 
     write_apply_duration: U64Average,
     write_build_group_duration: U64Average,
@@ -58,6 +63,7 @@ impl VnodeStorage {
             ts_index,
             ts_family,
             snapshots: vec![],
+            table_writes: Arc::new(Mutex::new(TableWrites::default())),
             write_apply_duration: U64Average::default(),
             write_build_group_duration: U64Average::default(),
             write_put_points_duration: U64Average::default(),
@@ -81,9 +87,21 @@ impl VnodeStorage {
         ctx: &replication::ApplyContext,
         command: raft_write_command::Command,
     ) -> TskvResult<Vec<u8>> {
+        if ctx.apply_type == replication::APPLY_TYPE_WAL {
+            self.table_writes.lock().untrack(ctx.index);
+        } else {
+            self.table_writes.lock().apply(ctx.index);
+        }
+
         match command {
             raft_write_command::Command::WriteData(cmd) => {
                 let precision = Precision::from(cmd.precision as u8);
+                // Published once by the replica proposing the entry, entries
+                // replayed from wal were published before restart.
+                let publish = ctx.apply_type == replication::APPLY_TYPE_WRITE
+                    && ctx.leader_id == ctx.raft_id
+                    && self.ctx.subscriptions.is_enabled();
+                let points = if publish { cmd.data.clone() } else { vec![] };
                 if let Err(err) = self.write(ctx, cmd.data, precision, None).await {
                     if ctx.apply_type == replication::APPLY_TYPE_WAL {
                         info!("recover: write points: {}", err);
@@ -92,15 +110,28 @@ impl VnodeStorage {
                     }
                 }
 
+                if publish {
+                    let owner = self.ts_family.read().await.owner();
+                    self.ctx.subscriptions.publish(
+                        &owner,
+                        self.id,
+                        ctx.index,
+                        cmd.precision,
+                        points,
+                    );
+                }
+
                 Ok(vec![])
             }
 
             raft_write_command::Command::DropTable(cmd) => {
+                self.record_write(ctx, &cmd.table, TimeRange::all());
                 self.drop_table(&cmd.table).await?;
                 Ok(vec![])
             }
 
             raft_write_command::Command::DropColumn(cmd) => {
+                self.record_write(ctx, &cmd.table, TimeRange::all());
                 if let Err(err) = self.drop_table_column(&cmd.table, &cmd.column).await {
                     if ctx.apply_type == replication::APPLY_TYPE_WAL {
                         info!("recover: drop column: {}", err);
@@ -117,12 +148,187 @@ impl VnodeStorage {
             }
 
             raft_write_command::Command::DeleteFromTable(cmd) => {
-                self.delete_from_table(&cmd).await?;
+                self.delete_from_table(ctx, &cmd).await?;
+                Ok(vec![])
+            }
+
+            raft_write_command::Command::RepairVnode(cmd) => {
+                let written = self.repair(ctx, &cmd).await?;
+                Ok(written.to_be_bytes().to_vec())
+            }
+
+            raft_write_command::Command::MergeSeries(cmd) => {
+                self.merge_series(ctx, &cmd).await?;
                 Ok(vec![])
             }
         }
     }
 
+    /// The index of the latest raft log applied to the vnode writing the time
+    /// range of the table, None if it's unknown.
+    pub fn last_write(&self, table: &str, time_range: &TimeRange) -> Option<u64> {
+        self.table_writes.lock().last_write(table, time_range)
+    }
+
+    fn record_write(&self, ctx: &replication::ApplyContext, table: &str, time_range: TimeRange) {
+        if ctx.apply_type != replication::APPLY_TYPE_WAL {
+            self.table_writes
+                .lock()
+                .record(ctx.index, table, time_range);
+        }
+    }
+
+    /// Replace the data of the series in the chunks by the points copied from
+    /// another replica, if none of the chunks is written by the raft logs
+    /// after `since_index`, otherwise the repair is skipped and done by the
+    /// next run. Only the vnode being repaired applies it.
+    ///
+    /// The repair is not replayed from wal since whether it was skipped is not
+    /// known, so the caches are flushed to keep it after restart.
+    async fn repair(
+        &self,
+        ctx: &replication::ApplyContext,
+        cmd: &RepairVnodeRequest,
+    ) -> TskvResult<u64> {
+        if cmd.vnode_id != self.id {
+            return Ok(0);
+        }
+        if ctx.apply_type == replication::APPLY_TYPE_WAL {
+            info!(
+                "recover: skip repair of table {} in vnode {}",
+                cmd.table, self.id
+            );
+            return Ok(0);
+        }
+
+        let mut chunks = Vec::with_capacity(cmd.chunks.len());
+        for chunk in cmd.chunks.iter() {
+            let series_key = SeriesKey::decode(&chunk.series_key).map_err(|e| {
+                InvalidParamSnafu {
+                    reason: format!("Deserialize 'series_key' of 'RepairChunk' failed: {e}"),
+                }
+                .build()
+            })?;
+            chunks.push((series_key, TimeRange::new(chunk.min_ts, chunk.max_ts)));
+        }
+
+        {
+            let mut table_writes = self.table_writes.lock();
+            let latest = chunks.iter().try_fold(0, |latest, (_, time_range)| {
+                let index = table_writes.last_write(&cmd.table, time_range)?;
+                Some(latest.max(index))
+            });
+            if latest.map_or(true, |index| index > cmd.since_index) {
+                info!(
+                    "Skip repair of table {} in vnode {}, it's written after raft log {}",
+                    cmd.table, self.id, cmd.since_index
+                );
+                return Ok(0);
+            }
+            for (_, time_range) in chunks.iter() {
+                table_writes.record(ctx.index, &cmd.table, *time_range);
+            }
+        }
+
+        for (series_key, time_range) in chunks.iter() {
+            let series_id = self
+                .ts_index
+                .read()
+                .await
+                .get_series_id(series_key)
+                .await
+                .context(IndexErrSnafu)?;
+            if let Some(series_id) = series_id {
+                self.delete(
+                    &cmd.table,
+                    &[series_id],
+                    &TimeRanges::new(vec![*time_range]),
+                )
+                .await?;
+            }
+        }
+
+        let mut written = 0;
+        if !cmd.points.is_empty() {
+            let repair_ctx = replication::ApplyContext {
+                index: ctx.index,
+                apply_type: replication::APPLY_TYPE_REPAIR,
+                raft_id: ctx.raft_id,
+                leader_id: ctx.leader_id,
+            };
+            written = self
+                .write(
+                    &repair_ctx,
+                    cmd.points.clone(),
+                    Precision::from(cmd.precision as u8),
+                    None,
+                )
+                .await?
+                .points_number;
+        }
+        self.flush(true, true, false).await?;
+        debug!(
+            "Repaired {} chunks of table {} in vnode {} with {written} points",
+            chunks.len(),
+            cmd.table,
+            self.id
+        );
+
+        Ok(written)
+    }
+
+    /// Delete the data of the series and write their points with the new
+    /// series keys, the data is never visible under both keys or lost
+    /// since both are done by the same raft log.
+    async fn merge_series(
+        &self,
+        ctx: &replication::ApplyContext,
+        cmd: &MergeSeriesRequest,
+    ) -> TskvResult<()> {
+        let mut series_ids = Vec::with_capacity(cmd.series_keys.len());
+        for key in cmd.series_keys.iter() {
+            let series_key = SeriesKey::decode(key).map_err(|e| {
+                InvalidParamSnafu {
+                    reason: format!("Deserialize 'series_key' of 'MergeSeries' failed: {e}"),
+                }
+                .build()
+            })?;
+            let series_id = self
+                .ts_index
+                .read()
+                .await
+                .get_series_id(&series_key)
+                .await
+                .context(IndexErrSnafu)?;
+            series_ids.extend(series_id);
+        }
+
+        if !series_ids.is_empty() {
+            self.record_write(ctx, &cmd.table, TimeRange::all());
+            self.delete(&cmd.table, &series_ids, &TimeRanges::all())
+                .await?;
+        }
+
+        if !cmd.points.is_empty() {
+            let precision = Precision::from(cmd.precision as u8);
+            if let Err(err) = self.write(ctx, cmd.points.clone(), precision, None).await {
+                if ctx.apply_type == replication::APPLY_TYPE_WAL {
+                    info!("recover: merge series: {}", err);
+                } else {
+                    return Err(err);
+                }
+            }
+        }
+        debug!(
+            "Merged {} series of table {} in vnode {}",
+            series_ids.len(),
+            cmd.table,
+            self.id
+        );
+
+        Ok(())
+    }
+
     pub async fn get_snapshot(&mut self) -> TskvResult<Option<VnodeSnapshot>> {
         if let Some(snapshot) = self.snapshots.last_mut() {
             snapshot.active_time = now_timestamp_secs();
@@ -181,6 +387,7 @@ impl VnodeStorage {
 
         // clear all snapshot
         self.snapshots = vec![];
+        self.table_writes.lock().untrack(snapshot.last_seq_no);
 
         // delete already exist data
         let mut db_wlock = self.db.write().await;
@@ -291,15 +498,28 @@ impl VnodeStorage {
         self.write_build_group_duration
             .add(write_start.elapsed().as_micros() as u64);
 
+        if ctx.apply_type != replication::APPLY_TYPE_WAL {
+            let mut table_ranges: HashMap<&str, TimeRange> = HashMap::new();
+            for (series_key, group) in write_group.values() {
+                table_ranges
+                    .entry(series_key.table.as_str())
+                    .and_modify(|time_range| time_range.merge(&group.range))
+                    .or_insert(group.range);
+            }
+            let mut table_writes = self.table_writes.lock();
+            for (table, time_range) in table_ranges {
+                table_writes.record(ctx.index, table, time_range);
+            }
+        }
+
         let write_mem_start = std::time::Instant::now();
         let res = {
             let span = Span::enter_with_parent("put points", &span);
-            match self
-                .ts_family
-                .read()
-                .await
-                .put_points(ctx.index, write_group)
-            {
+            match self.ts_family.read().await.put_points(
+                ctx.index,
+                write_group,
+                ctx.apply_type != replication::APPLY_TYPE_REPAIR,
+            ) {
                 Ok(points_number) => Ok(WritePointsResponse { points_number }),
                 Err(err) => {
                     span.error(err.to_string());
@@ -450,6 +670,7 @@ impl VnodeStorage {
             let ss = SeriesKey::decode(key).map_err(|e| InvalidParamSnafu {
             reason: format!("Deserialize 'matched_series' of 'UpdateTagsRequest' failed, expected: SeriesKey, error msg: {e}"),
         }.build())?;
+            self.record_write(ctx, &ss.table, TimeRange::all());
             series.push(ss);
         }
 
@@ -495,7 +716,11 @@ impl VnodeStorage {
         Ok(())
     }
 
-    async fn delete_from_table(&self, cmd: &DeleteFromTableRequest) -> TskvResult<()> {
+    async fn delete_from_table(
+        &self,
+        ctx: &replication::ApplyContext,
+        cmd: &DeleteFromTableRequest,
+    ) -> TskvResult<()> {
         let predicate =
             bincode::deserialize::<ResolvedPredicate>(&cmd.predicate).map_err(|err| {
                 InvalidParamSnafu {
@@ -520,6 +745,9 @@ impl VnodeStorage {
 
         // 执行delete，删除缓存 & 写墓碑文件
         let time_ranges = predicate.time_ranges();
+        for time_range in time_ranges.time_ranges() {
+            self.record_write(ctx, &cmd.table, time_range);
+        }
         self.delete(&cmd.table, &series_ids, &time_ranges).await
     }
 
@@ -600,6 +828,45 @@ impl VnodeStorage {
         Ok(())
     }
 
+    /// Move the files with all data older than `age` to the tiered storage,
+    /// returns the number of the files moved.
+    pub async fn tier_cold_files(
+        &self,
+        storage: &TieredStorage,
+        age: Duration,
+    ) -> TskvResult<usize> {
+        let (owner, schemas) = {
+            let db = self.db.read().await;
+            (db.owner(), db.get_schemas())
+        };
+        let precision = schemas.db_schema().await?.config().precision();
+        let cutoff = now_timestamp_nanos().saturating_sub(age.as_nanos() as i64);
+        let cutoff = timestamp_convert(Precision::NS, precision, cutoff).unwrap_or(cutoff);
+
+        let version = self.ts_family.read().await.version();
+        let mut tiered = 0;
+        for file in version.levels_info().iter().flat_map(|l| l.files.iter()) {
+            if file.is_deleted() || file.time_range().max_ts >= cutoff || file.is_tiered() {
+                continue;
+            }
+            // Compactions and tiering do not run on a file at the same time.
+            if !file.mark_compacting().await {
+                continue;
+            }
+            let result = file.tier(storage, &owner, self.id).await;
+            *file.write_lock_compacting().await = false;
+            let size = result?;
+            debug!(
+                "Tiering: vnode {} moved file {} ({size} bytes)",
+                self.id,
+                file.file_id()
+            );
+            tiered += 1;
+        }
+
+        Ok(tiered)
+    }
+
     pub async fn sync_index(&self) {
         let _ = self.ts_index.write().await.flush().await;
     }
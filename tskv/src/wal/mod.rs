@@ -42,10 +42,13 @@ pub mod writer;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
+use metrics::average::U64Average;
 use minivec::MiniVec;
 use models::codec::Encoding;
 use models::meta_data::VnodeId;
+use models::schema::database_schema::WalSyncPolicy;
 use snafu::{IntoError, OptionExt, ResultExt};
 
 use self::reader::WalReader;
@@ -99,6 +102,10 @@ pub struct VnodeWal {
     owner: Arc<String>,
     vnode_id: VnodeId,
     current_wal: WalWriter,
+
+    last_sync: Instant,
+    unsynced_bytes: u64,
+    sync_duration: U64Average,
 }
 
 impl VnodeWal {
@@ -115,6 +122,9 @@ impl VnodeWal {
             owner,
             vnode_id,
             current_wal: writer_file,
+            last_sync: Instant::now(),
+            unsynced_bytes: 0,
+            sync_duration: U64Average::default(),
         })
     }
 
@@ -143,6 +153,7 @@ impl VnodeWal {
 
             let mut old_file = std::mem::replace(&mut self.current_wal, new_file);
             old_file.close().await?;
+            self.reset_unsynced();
         }
         Ok(())
     }
@@ -187,7 +198,14 @@ impl VnodeWal {
 
         let wal_id = self.current_wal_id();
         let pos = self.current_wal_size();
-        self.current_wal.append_raft_entry(raft_entry).await?;
+        let written_size = self.current_wal.append_raft_entry(raft_entry).await?;
+        self.unsynced_bytes += written_size as u64;
+
+        match self.config.wal_sync {
+            WalSyncPolicy::EveryWrite => self.sync().await?,
+            WalSyncPolicy::Interval(_) => self.sync_if_due().await?,
+            WalSyncPolicy::Os => {}
+        }
 
         Ok((wal_id, pos))
     }
@@ -206,7 +224,29 @@ impl VnodeWal {
     }
 
     pub async fn sync(&mut self) -> TskvResult<()> {
-        self.current_wal.sync().await
+        let start = Instant::now();
+        self.current_wal.sync().await?;
+        self.sync_duration.add(start.elapsed().as_micros() as u64);
+        self.reset_unsynced();
+        Ok(())
+    }
+
+    /// Fsync if the policy is interval, some bytes are not synced yet and the
+    /// interval has passed since the last fsync.
+    pub async fn sync_if_due(&mut self) -> TskvResult<()> {
+        match self.config.wal_sync {
+            WalSyncPolicy::Interval(interval)
+                if self.unsynced_bytes > 0 && self.last_sync.elapsed() >= interval =>
+            {
+                self.sync().await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn reset_unsynced(&mut self) {
+        self.last_sync = Instant::now();
+        self.unsynced_bytes = 0;
     }
 
     /// Close current record file, return count of bytes appended as footer.
@@ -229,6 +269,16 @@ impl VnodeWal {
     pub fn current_wal_size(&self) -> u64 {
         self.current_wal.size()
     }
+
+    /// Average time of fsync on wal files, in microseconds.
+    pub fn avg_sync_time(&self) -> u64 {
+        self.sync_duration.average()
+    }
+
+    /// Count of bytes appended to wal files but not yet fsynced.
+    pub fn unsynced_bytes(&self) -> u64 {
+        self.unsynced_bytes
+    }
 }
 
 pub struct WalEntryCodec {
@@ -1,10 +1,15 @@
+use std::sync::Arc;
+
 use metrics::average::U64Average;
+use models::schema::database_schema::WalSyncPolicy;
 use openraft::{EntryPayload, LogId};
 use protos::kv_service::RaftWriteCommand;
 use protos::models_helper::parse_prost_bytes;
 use replication::errors::{ReplicationError, ReplicationResult};
 use replication::{EntriesMetrics, EntryStorage, RaftNodeId, RaftNodeInfo, TypeConfig};
 use snafu::IntoError;
+use tokio::sync::RwLock;
+use tokio::time::MissedTickBehavior;
 use trace::{error, info};
 
 use super::reader::WalRecordData;
@@ -37,6 +42,34 @@ impl RaftEntryStorage {
         }
     }
 
+    /// Fsync the WAL at each interval of the policy in the background, so the
+    /// writes are synced even if no more writes follow them. Nothing is done if
+    /// the policy is not interval, the task ends when the storage is dropped.
+    pub fn spawn_interval_sync(storage: &Arc<RwLock<Self>>, policy: WalSyncPolicy) {
+        let WalSyncPolicy::Interval(period) = policy else {
+            return;
+        };
+        let storage = Arc::downgrade(storage);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(storage) = storage.upgrade() else {
+                    break;
+                };
+                let mut storage = storage.write().await;
+                if let Err(e) = storage.inner.wal.sync_if_due().await {
+                    error!(
+                        "Failed to sync wal {:?}: {}",
+                        storage.inner.wal.wal_dir(),
+                        e
+                    );
+                }
+            }
+        });
+    }
+
     /// Read WAL files to recover
     pub async fn recover(
         &mut self,
@@ -136,6 +169,9 @@ impl EntryStorage for RaftEntryStorage {
             min_seq: self.inner.min_sequence(),
             max_seq: self.inner.max_sequence(),
             avg_write_time: self.write_duration.average(),
+            avg_sync_time: self.inner.wal.avg_sync_time(),
+            unsynced_bytes: self.inner.wal.unsynced_bytes(),
+            disk_size: self.inner.disk_size(),
         };
 
         Ok(metrics)
@@ -360,6 +396,18 @@ impl RaftEntryStorageInner {
         Some(entries)
     }
 
+    /// Total size of the wal files of this vnode.
+    fn disk_size(&self) -> u64 {
+        match std::fs::read_dir(self.wal.wal_dir()) {
+            Ok(dir) => dir
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum(),
+            Err(_) => 0,
+        }
+    }
+
     fn min_sequence(&self) -> u64 {
         if let Some(item) = self.files_meta.first() {
             return item.min_seq;
@@ -503,6 +551,7 @@ impl RaftEntryStorageInner {
                         index: entry.log_id.index,
                         raft_id: self.wal.vnode_id as u64,
                         apply_type: replication::APPLY_TYPE_WAL,
+                        leader_id: entry.log_id.leader_id.node_id,
                     };
 
                     let request = parse_prost_bytes::<RaftWriteCommand>(req)
@@ -535,8 +584,9 @@ mod test {
     use std::path::{Path, PathBuf};
     use std::sync::atomic::AtomicUsize;
     use std::sync::{atomic, Arc};
+    use std::time::Duration;
 
-    use models::schema::database_schema::make_owner;
+    use models::schema::database_schema::{make_owner, WalSyncPolicy};
     use openraft::EntryPayload;
     use replication::apply_store::HeedApplyStorage;
     use replication::node_store::NodeStorage;
@@ -553,6 +603,13 @@ mod test {
     use crate::{file_utils, TskvResult};
 
     pub async fn get_vnode_wal(dir: impl AsRef<Path>) -> TskvResult<VnodeWal> {
+        get_vnode_wal_with_sync(dir, WalSyncPolicy::Os).await
+    }
+
+    async fn get_vnode_wal_with_sync(
+        dir: impl AsRef<Path>,
+        wal_sync: WalSyncPolicy,
+    ) -> TskvResult<VnodeWal> {
         let dir = dir.as_ref();
         let owner = make_owner("cnosdb", "test_db");
         let owner = Arc::new(owner);
@@ -560,7 +617,7 @@ mod test {
             path: dir.to_path_buf(),
             wal_max_file_size: 1024 * 1024 * 1024,
             compress: 8.into(),
-            wal_sync: false,
+            wal_sync,
         };
 
         VnodeWal::new(Arc::new(wal_option), owner, 1234).await
@@ -640,6 +697,27 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_wal_interval_sync() {
+        let dir = PathBuf::from("/tmp/test/wal/raft_entry_interval_sync");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let policy = WalSyncPolicy::Interval(Duration::from_millis(50));
+        let wal = get_vnode_wal_with_sync(&dir, policy).await.unwrap();
+        let storage = Arc::new(RwLock::new(RaftEntryStorage::new(wal)));
+        RaftEntryStorage::spawn_interval_sync(&storage, policy);
+
+        let mut entry = RaftEntry::default();
+        entry.payload = EntryPayload::Normal(b"not synced by the write".to_vec());
+        storage.write().await.append(&[entry]).await.unwrap();
+        assert!(storage.read().await.inner.wal.unsynced_bytes() > 0);
+
+        // Synced by the timer though no more writes follow.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(storage.read().await.inner.wal.unsynced_bytes(), 0);
+    }
+
     #[tokio::test]
     async fn test_raft_wal_entry_storage() {
         trace::debug!("----------------------------------------");
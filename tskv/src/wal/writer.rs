@@ -58,10 +58,6 @@ impl WalWriter {
             )
             .await?;
 
-        if self.config.wal_sync {
-            self.inner.sync().await?;
-        }
-
         self.size += written_size as u64;
         Ok(written_size)
     }
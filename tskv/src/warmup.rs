@@ -0,0 +1,201 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use models::predicate::domain::TimeRange;
+use models::SeriesId;
+use snafu::ResultExt;
+use tokio::sync::RwLock;
+use trace::info;
+use utils::precision::{timestamp_convert, Precision};
+
+use crate::error::IndexErrSnafu;
+use crate::index::ts_index::TSIndex;
+use crate::tsfamily::tseries_family::TseriesFamily;
+use crate::TskvResult;
+
+/// Memory budget shared by the warm-up of all vnodes.
+#[derive(Debug)]
+pub struct WarmupBudget {
+    remaining: AtomicU64,
+}
+
+impl WarmupBudget {
+    pub fn new(budget: u64) -> Self {
+        Self {
+            remaining: AtomicU64::new(budget),
+        }
+    }
+
+    /// An empty permit to take the budget for the warm-up of a vnode.
+    pub fn permit(self: &Arc<Self>) -> WarmupPermit {
+        WarmupPermit {
+            budget: self.clone(),
+            size: 0,
+        }
+    }
+
+    /// Returns false if the remaining budget is not enough.
+    fn try_acquire(&self, size: u64) -> bool {
+        self.remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |r| r.checked_sub(size))
+            .is_ok()
+    }
+
+    fn release(&self, size: u64) {
+        self.remaining.fetch_add(size, Ordering::SeqCst);
+    }
+}
+
+/// The budget taken by the data preloaded for a vnode, it's given back when the
+/// permit is dropped with the vnode.
+#[derive(Debug)]
+pub struct WarmupPermit {
+    budget: Arc<WarmupBudget>,
+    size: u64,
+}
+
+impl WarmupPermit {
+    /// Returns false if the remaining budget is not enough.
+    pub fn try_grow(&mut self, size: u64) -> bool {
+        if !self.budget.try_acquire(size) {
+            return false;
+        }
+        self.size += size;
+        true
+    }
+}
+
+impl Drop for WarmupPermit {
+    fn drop(&mut self) {
+        self.budget.release(self.size);
+    }
+}
+
+/// Preload tsm readers, data blocks of the most recent duration and the series keys
+/// of them into caches. Data blocks are read to warm the page cache of the system.
+/// The budget taken is held by the vnode until it's closed.
+pub(crate) async fn warmup_vnode(
+    ts_family: Arc<RwLock<TseriesFamily>>,
+    ts_index: Arc<RwLock<TSIndex>>,
+    precision: Precision,
+    recent: Duration,
+    budget: Arc<WarmupBudget>,
+) -> TskvResult<()> {
+    let start = Instant::now();
+    let mut permit = budget.permit();
+    let (vnode_id, version) = {
+        let ts_family = ts_family.read().await;
+        (ts_family.tf_id(), ts_family.version())
+    };
+
+    let mut files = version
+        .levels_info()
+        .iter()
+        .flat_map(|level| level.files.iter())
+        .filter(|f| !f.is_deleted())
+        .collect::<Vec<_>>();
+    let max_ts = match files.iter().map(|f| f.time_range().max_ts).max() {
+        Some(ts) => ts,
+        None => return Ok(()),
+    };
+    let recent =
+        timestamp_convert(Precision::NS, precision, recent.as_nanos() as i64).unwrap_or(i64::MAX);
+    let time_range = TimeRange::new(max_ts.saturating_sub(recent), max_ts);
+    files.retain(|f| f.overlap(&time_range));
+    // Load the latest files first.
+    files.sort_by_key(|f| std::cmp::Reverse(f.time_range().max_ts));
+    info!(
+        "Warm up vnode {vnode_id}: start loading {} files in {time_range}",
+        files.len()
+    );
+
+    let mut loaded_bytes = 0_u64;
+    let mut series_ids = BTreeSet::<SeriesId>::new();
+    let mut exhausted = false;
+    for (i, file) in files.iter().enumerate() {
+        let reader = version.get_tsm_reader(file.file_path()).await?;
+        'chunks: for chunk in reader.chunk().values() {
+            if !chunk.time_range().overlaps(&time_range) {
+                continue;
+            }
+            for (column_group_id, column_group) in chunk.column_group() {
+                if !column_group.time_range().overlaps(&time_range) {
+                    continue;
+                }
+                if !permit.try_grow(column_group.size()) {
+                    exhausted = true;
+                    break 'chunks;
+                }
+                reader
+                    .read_datablock_raw(chunk.series_id(), *column_group_id)
+                    .await?;
+                loaded_bytes += column_group.size();
+            }
+            series_ids.insert(chunk.series_id());
+        }
+        info!(
+            "Warm up vnode {vnode_id}: loaded {}/{} files, {loaded_bytes} bytes",
+            i + 1,
+            files.len()
+        );
+        ts_family
+            .read()
+            .await
+            .record_warmup_loaded_bytes(loaded_bytes);
+        if exhausted {
+            info!("Warm up vnode {vnode_id}: memory budget exhausted");
+            break;
+        }
+    }
+
+    let ts_index = ts_index.read().await;
+    let mut loaded_series = 0;
+    for series_id in series_ids {
+        let key = ts_index
+            .get_series_key(series_id)
+            .await
+            .context(IndexErrSnafu)?;
+        if let Some(key) = key {
+            loaded_series += 1;
+            if !permit.try_grow(key.encode().len() as u64) {
+                break;
+            }
+        }
+    }
+
+    drop(ts_index);
+    ts_family.write().await.set_warmup_permit(permit);
+
+    info!(
+        "Warm up vnode {vnode_id}: finished, loaded {loaded_bytes} bytes and {loaded_series} series keys in {:?}",
+        start.elapsed()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::WarmupBudget;
+
+    #[test]
+    fn test_warmup_budget() {
+        let budget = Arc::new(WarmupBudget::new(100));
+        let mut vnode_1 = budget.permit();
+        let mut vnode_2 = budget.permit();
+        assert!(vnode_1.try_grow(60));
+        assert!(!vnode_2.try_grow(60));
+        assert!(vnode_2.try_grow(40));
+        assert!(!vnode_1.try_grow(1));
+
+        // Given back when the vnode is closed.
+        drop(vnode_1);
+        assert!(vnode_2.try_grow(60));
+        assert!(!budget.permit().try_grow(1));
+        drop(vnode_2);
+        assert!(budget.permit().try_grow(100));
+    }
+}
@@ -9,9 +9,13 @@ mod tests {
     use meta::model::meta_admin::AdminMeta;
     use metrics::metric_register::MetricsRegister;
     use models::meta_data::VnodeId;
+    use models::predicate::domain::TimeRange;
     use models::schema::database_schema::make_owner;
     use models::schema::tenant::TenantOptions;
-    use protos::kv_service::{raft_write_command, WriteDataRequest};
+    use models::SeriesKey;
+    use protos::kv_service::{
+        raft_write_command, RepairChunk, RepairVnodeRequest, WriteDataRequest,
+    };
     use protos::models_helper;
     use serial_test::serial;
     use sysinfo::{ProcessRefreshKind, RefreshKind, System};
@@ -82,6 +86,7 @@ mod tests {
             index,
             raft_id: id.into(),
             apply_type: replication::APPLY_TYPE_WRITE,
+            leader_id: id.into(),
         };
         let vnode_store = Arc::new(rt.block_on(tskv.open_tsfamily(tenant, db, id)).unwrap());
 
@@ -127,6 +132,7 @@ mod tests {
                 test_kvcore_flush_delta();
                 test_kvcore_build_row_data();
                 test_kvcore_snapshot_create_apply_delete();
+                test_kvcore_repair_vnode();
             })
             .await;
         });
@@ -455,6 +461,83 @@ mod tests {
         println!("Leave serial test: test_kvcore_snapshot_create_apply_delete");
     }
 
+    fn test_kvcore_repair_vnode() {
+        println!("Enter serial test: test_kvcore_repair_vnode");
+        let dir = "/tmp/test/kvcore/kvcore_repair_vnode";
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+        let (rt, tskv) = get_tskv(dir, None);
+        let (tenant, database, table, vnode_id) = ("cnosdb", "db_test_repair", "tab_repair", 21);
+
+        let mut fbb = flatbuffers::FlatBufferBuilder::new();
+        let points =
+            models_helper::create_random_points_include_delta(&mut fbb, database, table, 20);
+        fbb.finish(points, None);
+        let points = fbb.finished_data().to_vec();
+        let vnode = rt
+            .block_on(tskv.open_tsfamily(tenant, database, vnode_id))
+            .unwrap();
+        let last_write = || {
+            rt.block_on(tskv.table_last_write(vnode_id, table, &TimeRange::all()))
+                .unwrap()
+        };
+        assert_eq!(last_write(), None);
+
+        let apply_ctx = replication::ApplyContext {
+            index: 1,
+            raft_id: 1,
+            apply_type: replication::APPLY_TYPE_WRITE,
+            leader_id: 1,
+        };
+        let command = raft_write_command::Command::WriteData(WriteDataRequest {
+            data: points.clone(),
+            precision: Precision::NS as u32,
+        });
+        rt.block_on(vnode.apply(&apply_ctx, command)).unwrap();
+        assert_eq!(last_write(), Some(1));
+
+        let repair = |index: u64, vnode_id: VnodeId, since_index: u64| {
+            let apply_ctx = replication::ApplyContext {
+                index,
+                raft_id: 1,
+                apply_type: replication::APPLY_TYPE_WRITE,
+                leader_id: 1,
+            };
+            let command = raft_write_command::Command::RepairVnode(RepairVnodeRequest {
+                vnode_id,
+                table: table.to_string(),
+                chunks: vec![RepairChunk {
+                    series_key: SeriesKey {
+                        tags: vec![],
+                        table: table.to_string(),
+                    }
+                    .encode(),
+                    min_ts: i64::MIN,
+                    max_ts: i64::MAX,
+                }],
+                points: points.clone(),
+                precision: Precision::NS as u32,
+                since_index,
+            });
+            let written = rt.block_on(vnode.apply(&apply_ctx, command)).unwrap();
+            u64::from_be_bytes(written.try_into().unwrap())
+        };
+
+        // The repair of the other vnodes in the replica set.
+        assert_eq!(repair(2, vnode_id + 1, 1), 0);
+        // The chunks are written after the data is copied.
+        assert_eq!(repair(3, vnode_id, 0), 0);
+        assert_eq!(last_write(), Some(1));
+
+        assert!(repair(4, vnode_id, 1) > 0);
+        assert_eq!(last_write(), Some(4));
+        let last_seq = rt.block_on(async { vnode.ts_family().read().await.version().last_seq() });
+        assert_eq!(last_seq, 4);
+
+        rt.block_on(tskv.close());
+        println!("Leave serial test: test_kvcore_repair_vnode");
+    }
+
     fn sleep_in_runtime(runtime: Arc<Runtime>, duration: Duration) {
         let rt = runtime.clone();
         runtime.block_on(async move {